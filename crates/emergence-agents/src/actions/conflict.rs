@@ -16,6 +16,8 @@ use std::collections::BTreeMap;
 use chrono::{DateTime, Utc};
 use emergence_types::{AgentId, RejectionReason, Resource};
 
+use crate::metrics;
+
 /// The strategy used to resolve a conflict over a contested resource.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConflictStrategy {
@@ -66,97 +68,94 @@ pub fn resolve_gather_conflict(
     claims: &[GatherClaim],
     strategy: ConflictStrategy,
 ) -> BTreeMap<AgentId, ClaimOutcome> {
-    if claims.is_empty() {
-        return BTreeMap::new();
-    }
+    claims
+        .iter()
+        .map(|claim| claim.agent_id)
+        .zip(resolve_gather_claims(available, claims, strategy))
+        .collect()
+}
 
-    match strategy {
+/// Resolve a conflict like [`resolve_gather_conflict`], returning outcomes
+/// by position instead of by agent.
+///
+/// The outcome at index `i` belongs to `claims[i]`, so the tick cycle can
+/// pair claims with outcomes without keying a map by agent UUID.
+pub fn resolve_gather_claims(
+    available: u32,
+    claims: &[GatherClaim],
+    strategy: ConflictStrategy,
+) -> Vec<ClaimOutcome> {
+    let mut order: Vec<usize> = (0..claims.len()).collect();
+    order.sort_by_key(|&i| claims.get(i).map(|c| c.submitted_at));
+
+    let requested = order
+        .iter()
+        .map(|&i| claims.get(i).map_or(0, |c| c.requested));
+    let granted: Vec<u32> = match strategy {
         ConflictStrategy::FirstComeFirstServed => {
-            resolve_first_come_first_served(available, claims)
+            grant_first_come_first_served(available, requested).collect()
+        }
+        ConflictStrategy::EqualSplit => grant_equal_split(available, order.len(), requested),
+    };
+
+    let mut outcomes = vec![
+        ClaimOutcome::Rejected {
+            reason: RejectionReason::ConflictLost,
+        };
+        claims.len()
+    ];
+    for (&i, quantity) in order.iter().zip(granted) {
+        if quantity > 0
+            && let Some(outcome) = outcomes.get_mut(i)
+        {
+            *outcome = ClaimOutcome::Granted { quantity };
         }
-        ConflictStrategy::EqualSplit => resolve_equal_split(available, claims),
     }
+    let lost = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, ClaimOutcome::Rejected { .. }))
+        .count();
+    if lost > 0 {
+        metrics::ACTION_REJECTIONS.increment_with(
+            &format!("{:?}", RejectionReason::ConflictLost),
+            u64::try_from(lost).unwrap_or(u64::MAX),
+        );
+    }
+    outcomes
 }
 
-/// First-come-first-served: sort by submission time, grant in order.
-fn resolve_first_come_first_served(
+/// First-come-first-served: grant in submission order until exhausted.
+fn grant_first_come_first_served(
     available: u32,
-    claims: &[GatherClaim],
-) -> BTreeMap<AgentId, ClaimOutcome> {
-    let mut sorted: Vec<&GatherClaim> = claims.iter().collect();
-    sorted.sort_by_key(|c| c.submitted_at);
-
+    requested: impl Iterator<Item = u32>,
+) -> impl Iterator<Item = u32> {
     let mut remaining = available;
-    let mut outcomes = BTreeMap::new();
-
-    for claim in sorted {
-        if remaining == 0 {
-            outcomes.insert(
-                claim.agent_id,
-                ClaimOutcome::Rejected {
-                    reason: RejectionReason::ConflictLost,
-                },
-            );
-            continue;
-        }
-
-        let granted = claim.requested.min(remaining);
+    requested.map(move |want| {
+        let granted = want.min(remaining);
         remaining = remaining.saturating_sub(granted);
-
-        if granted == 0 {
-            outcomes.insert(
-                claim.agent_id,
-                ClaimOutcome::Rejected {
-                    reason: RejectionReason::ConflictLost,
-                },
-            );
-        } else {
-            outcomes.insert(claim.agent_id, ClaimOutcome::Granted { quantity: granted });
-        }
-    }
-
-    outcomes
+        granted
+    })
 }
 
 /// Equal split: divide available evenly, remainder goes to first submitter.
-fn resolve_equal_split(
+fn grant_equal_split(
     available: u32,
-    claims: &[GatherClaim],
-) -> BTreeMap<AgentId, ClaimOutcome> {
-    let mut sorted: Vec<&GatherClaim> = claims.iter().collect();
-    sorted.sort_by_key(|c| c.submitted_at);
-
-    let claim_count = u32::try_from(sorted.len()).unwrap_or(u32::MAX);
-    if claim_count == 0 {
-        return BTreeMap::new();
-    }
-
+    claim_count: usize,
+    requested: impl Iterator<Item = u32>,
+) -> Vec<u32> {
+    let claim_count = u32::try_from(claim_count).unwrap_or(u32::MAX);
     let base_share = available.checked_div(claim_count).unwrap_or(0);
     let leftover = available.checked_rem(claim_count).unwrap_or(0);
 
-    let mut outcomes = BTreeMap::new();
-
-    for (i, claim) in sorted.iter().enumerate() {
-        // First agent gets the remainder
-        let bonus = if i == 0 { leftover } else { 0 };
-        let share = base_share.saturating_add(bonus);
-
-        // Cap to what the agent actually requested
-        let granted = share.min(claim.requested);
-
-        if granted == 0 {
-            outcomes.insert(
-                claim.agent_id,
-                ClaimOutcome::Rejected {
-                    reason: RejectionReason::ConflictLost,
-                },
-            );
-        } else {
-            outcomes.insert(claim.agent_id, ClaimOutcome::Granted { quantity: granted });
-        }
-    }
-
-    outcomes
+    requested
+        .enumerate()
+        .map(|(i, want)| {
+            // First agent gets the remainder
+            let bonus = if i == 0 { leftover } else { 0 };
+            // Cap to what the agent actually requested
+            base_share.saturating_add(bonus).min(want)
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -374,4 +373,31 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn positional_outcomes_follow_claim_order() {
+        // Submitted out of order: the second claim came first.
+        let claims = vec![
+            make_claim(AgentId::new(), 4, 100),
+            make_claim(AgentId::new(), 4, 0),
+        ];
+        let outcomes =
+            resolve_gather_claims(6, &claims, ConflictStrategy::FirstComeFirstServed);
+        assert_eq!(
+            outcomes,
+            vec![
+                ClaimOutcome::Granted { quantity: 2 },
+                ClaimOutcome::Granted { quantity: 4 },
+            ]
+        );
+
+        let split = resolve_gather_claims(5, &claims, ConflictStrategy::EqualSplit);
+        assert_eq!(
+            split,
+            vec![
+                ClaimOutcome::Granted { quantity: 2 },
+                ClaimOutcome::Granted { quantity: 3 },
+            ]
+        );
+    }
 }
@@ -17,23 +17,38 @@ use emergence_types::{ActionType, Resource};
 /// - Move: 15 (per tick of travel)
 /// - Build: 25
 /// - Repair: 15
+/// - Extinguish: 15
 /// - Demolish: 20
+/// - `UpgradeStructure`: 25
 /// - `ImproveRoute`: 30
+/// - `BuildRoute`: 30
 /// - Communicate: 2
 /// - Broadcast: 5
 /// - `TradeOffer`: 2
 /// - `TradeAccept`: 0
 /// - `TradeReject`: 0
+/// - `PostOrder`: 2
 /// - `FormGroup`: 5
 /// - Teach: 10
 /// - `FarmPlant`: 20
 /// - `FarmHarvest`: 10
+/// - Fertilize: 10
+/// - Slaughter: 10
 /// - Craft: 15
 /// - Mine: 20
+/// - `DeepenMine`: 30
+/// - Hunt: 20
+/// - Prospect: 15
+/// - Fish: 15
 /// - Smelt: 20
 /// - Write: 5
 /// - Read: 5
+/// - Chart: 10
+/// - Deposit: 2
+/// - Withdraw: 2
 /// - Claim: 5
+/// - `TransferOwnership`: 2
+/// - `FoundSettlement`: 30
 /// - Legislate: 10
 /// - Enforce: 15
 /// - Reproduce: 30
@@ -48,29 +63,45 @@ pub const fn energy_cost(action: ActionType) -> u32 {
         ActionType::Move => 15,
         ActionType::Build => 25,
         ActionType::Repair => 15,
+        ActionType::Extinguish => 15,
         ActionType::Demolish => 20,
+        ActionType::UpgradeStructure => 25,
         ActionType::ImproveRoute => 30,
+        ActionType::BuildRoute => 30,
         ActionType::Communicate => 2,
         ActionType::Broadcast => 5,
         ActionType::TradeOffer => 2,
         ActionType::TradeAccept => 0,
         ActionType::TradeReject => 0,
+        ActionType::PostOrder => 2,
         ActionType::FormGroup => 5,
         ActionType::Teach => 10,
         ActionType::FarmPlant => 20,
         ActionType::FarmHarvest => 10,
+        ActionType::Fertilize => 10,
+        ActionType::Slaughter => 10,
         ActionType::Craft => 15,
         ActionType::Mine => 20,
+        ActionType::DeepenMine => 30,
+        ActionType::Hunt => 20,
+        ActionType::Prospect => 15,
+        ActionType::Fish => 15,
         ActionType::Smelt => 20,
         ActionType::Write => 5,
         ActionType::Read => 5,
+        ActionType::Chart => 10,
+        ActionType::Deposit => 2,
+        ActionType::Withdraw => 2,
         ActionType::Claim => 5,
+        ActionType::TransferOwnership => 2,
+        ActionType::FoundSettlement => 30,
         ActionType::Legislate => 10,
         ActionType::Enforce => 15,
         ActionType::Reproduce => 30,
         ActionType::Steal => 15,
         ActionType::Attack => 20,
         ActionType::Intimidate => 10,
+        ActionType::Breach => 25,
         ActionType::Propose => 5,
         ActionType::Vote => 2,
         ActionType::Marry => 10,
@@ -129,6 +160,17 @@ pub const BASE_GATHER_YIELD: u32 = 3;
 /// Skill-modified yield is `base + (mining_skill / 2)`.
 pub const BASE_MINE_YIELD: u32 = 2;
 
+/// Base hunting yield (animals taken per hunt action).
+///
+/// Skill-modified yield is `base + (hunting_skill / 4)`.
+pub const BASE_HUNT_YIELD: u32 = 1;
+
+/// Base fishing yield (fish caught per fish action).
+///
+/// Skill-modified yield is `base + (fishing_skill / 3)`, reduced during a
+/// drought.
+pub const BASE_FISH_YIELD: u32 = 2;
+
 /// Ore consumed per smelt action.
 pub const SMELT_ORE_INPUT: u32 = 2;
 
@@ -138,6 +180,15 @@ pub const SMELT_WOOD_INPUT: u32 = 1;
 /// Metal produced per smelt action.
 pub const SMELT_METAL_OUTPUT: u32 = 1;
 
+/// Hide consumed per chart action, as the surface the map is drawn on.
+pub const CHART_HIDE_INPUT: u32 = 1;
+
+/// Maps produced per chart action.
+pub const CHART_MAP_OUTPUT: u32 = 1;
+
+/// Durability knocked off a fortification per breach action.
+pub const BREACH_DAMAGE: u32 = 20;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -19,9 +19,16 @@ use emergence_types::{
     StructureType,
 };
 
+use emergence_world::environment;
 use emergence_world::farming;
+use emergence_world::fauna;
+use emergence_world::fire;
+use emergence_world::mining::{self, MineWork, OreBody};
+use emergence_world::prospecting;
 use emergence_world::route as world_route;
+use emergence_world::route_building::RoutePlan;
 use emergence_world::structure as world_structure;
+use emergence_world::waters;
 
 use crate::config::VitalsConfig;
 use crate::crafting;
@@ -34,7 +41,7 @@ use crate::vitals;
 use super::costs;
 
 /// Maximum allowed length for a message content string (characters).
-const MAX_MESSAGE_LENGTH: usize = 500;
+pub const MAX_MESSAGE_LENGTH: usize = 500;
 
 /// Context provided by the tick cycle for executing an action against world state.
 ///
@@ -68,6 +75,11 @@ pub struct ExecutionContext {
     /// the route connecting the agent's location to the action's destination.
     /// The handler will determine whether to upgrade or repair.
     pub route_to_improve: Option<Route>,
+    /// What building the route of a `BuildRoute` action takes, if any.
+    ///
+    /// Populated by the tick cycle from the world map. The handler deducts
+    /// the plan's materials, which are empty once the work is under way.
+    pub route_to_build: Option<RoutePlan>,
     /// Toll cost for traversing the route in a `Move` action, if any.
     ///
     /// Populated by the tick cycle from the route's ACL. When present, the
@@ -100,6 +112,58 @@ pub struct ExecutionContext {
     /// Key is the structure ID of the library, value is the set of concepts
     /// written to it. Used by `Write` and `Read` actions.
     pub library_knowledge: BTreeMap<StructureId, BTreeSet<String>>,
+    /// Game animals available to hunt at the agent's current location.
+    ///
+    /// Populated by the tick cycle from the fauna registry. The `Hunt`
+    /// handler decrements it by the animals taken.
+    pub game_at_location: u32,
+    /// Resources hidden at the agent's current location, in resource order.
+    ///
+    /// Populated by the tick cycle from the location's undiscovered
+    /// deposits. A successful `Prospect` action reveals the first.
+    pub hidden_resources: Vec<Resource>,
+    /// The roll in `0..100` for a `Prospect` action, from
+    /// [`prospecting::roll`]. The attempt succeeds when it is below the
+    /// agent's success chance.
+    pub prospect_roll: u32,
+    /// The ore body beneath the agent's current location, if one is
+    /// tracked.
+    ///
+    /// Populated by the tick cycle from the mine registry. The `Mine`
+    /// handler takes ore from its face and the `DeepenMine` handler sinks
+    /// its shaft.
+    pub ore_body: Option<OreBody>,
+    /// The roll in `0..100` for a `Mine` action, from
+    /// [`mining::collapse_roll`]. The shaft caves in when it is below the
+    /// ore body's collapse risk.
+    pub collapse_roll: u32,
+    /// Fish in the water at or next to the agent's location.
+    ///
+    /// Populated by the tick cycle from the water registry. The `Fish`
+    /// handler decrements it by the fish caught.
+    pub fish_nearby: u32,
+    /// Intensity of the fire burning at the agent's current location, zero
+    /// if none.
+    ///
+    /// Populated by the tick cycle from the fire system. The `Extinguish`
+    /// handler lowers it by the levels suppressed.
+    pub fire_intensity: u32,
+    /// Percentage of the usual catch landed this tick, from
+    /// [`waters::yield_pct`] for the current weather.
+    pub fishing_yield_pct: u32,
+    /// Percentage of the usual farm harvest the location yields, from
+    /// [`emergence_world::pollution::farm_yield_pct`] for its pollution.
+    pub farm_yield_pct: u32,
+    /// Whether a river or lake at or next to the agent's location waters
+    /// its farm plots.
+    ///
+    /// Populated by the tick cycle from
+    /// [`WaterRegistry::waters_farms_at`](emergence_world::WaterRegistry::waters_farms_at).
+    pub water_nearby: bool,
+    /// Percentage of the usual harvest a watered farm plot yields this
+    /// tick, from [`farming::irrigation_yield_pct`] for the configured
+    /// multiple and the weather.
+    pub irrigated_yield_pct: u32,
 }
 
 /// Result of executing an action handler, containing the changes to apply.
@@ -122,6 +186,9 @@ pub struct HandlerResult {
     /// Structure ID that was repaired this tick, if any.
     /// The caller must call `apply_repair` on the structure in world state.
     pub structure_repaired: Option<StructureId>,
+    /// Structure ID that was upgraded to its next tier this tick, if any.
+    /// The caller must call `apply_upgrade` on the structure in world state.
+    pub structure_upgraded: Option<StructureId>,
     /// Structure ID that was demolished this tick, if any.
     /// The caller must remove this from the world map and location state.
     pub structure_demolished: Option<StructureId>,
@@ -142,6 +209,18 @@ pub struct HandlerResult {
     /// The caller must update the structure's `owner` field in world state
     /// and emit a `StructureClaimed` event.
     pub structure_claimed: Option<StructureId>,
+    /// Structure given away by a `TransferOwnership` action, with its new
+    /// owner.
+    ///
+    /// The caller must set the structure's `owner` field in world state and
+    /// emit a `StructureClaimed` event naming the giver as previous owner.
+    pub structure_transferred: Option<(StructureId, AgentId)>,
+    /// Resources moved into (positive) or out of (negative) a storage
+    /// structure by a `Deposit` or `Withdraw` action.
+    ///
+    /// The caller must apply the change to the structure's `inventory` in
+    /// world state and record a `Deposit` or `Withdrawal` ledger entry.
+    pub storage_change: Option<(StructureId, Resource, i64)>,
     /// A governance rule created by a `Legislate` action.
     ///
     /// The caller must store this rule in the active rules registry and
@@ -173,6 +252,62 @@ pub struct HandlerResult {
     /// the concept to the agent's knowledge base via
     /// [`KnowledgeBase::learn`](crate::knowledge::KnowledgeBase::learn).
     pub library_read: Option<(StructureId, String)>,
+    /// Game animals taken by a `Hunt` action this tick, if any.
+    ///
+    /// The caller must remove them from the location's herd via
+    /// [`FaunaRegistry::hunt`](emergence_world::FaunaRegistry::hunt) so
+    /// overhunting is tracked.
+    pub animals_hunted: Option<u32>,
+    /// Hidden deposit found by a `Prospect` action this tick, if any.
+    ///
+    /// The caller must reveal it via
+    /// [`LocationState::reveal_resource`](emergence_world::LocationState::reveal_resource)
+    /// so it becomes harvestable.
+    pub resource_discovered: Option<Resource>,
+    /// Fish caught by a `Fish` action this tick, if any.
+    ///
+    /// The caller must take them from the fishing spot via
+    /// [`WaterRegistry::fish`](emergence_world::WaterRegistry::fish).
+    pub fish_caught: Option<u32>,
+    /// Name of the settlement founded by a `FoundSettlement` action this
+    /// tick, if any.
+    ///
+    /// The caller must add it to the world map via
+    /// [`settlement::found_settlement`](emergence_world::settlement::found_settlement).
+    pub settlement_founded: Option<String>,
+    /// Destination of the route a `BuildRoute` action worked on this tick,
+    /// if any.
+    ///
+    /// The caller must put the work into the world map via
+    /// [`WorldMap::work_on_route`](emergence_world::WorldMap::work_on_route).
+    pub route_work: Option<LocationId>,
+    /// Whether a `Chart` action drew a map this tick.
+    ///
+    /// The caller must draw the agent's geography onto its maps via
+    /// [`KnownMapRegistry::chart`](emergence_world::KnownMapRegistry::chart).
+    pub map_charted: bool,
+    /// Damage a `Breach` action dealt to the fortifications of an adjacent
+    /// location.
+    ///
+    /// Contains `(destination, damage)`. The caller must apply the damage
+    /// to the fortification [`breach_target`] picks there and record the
+    /// breach against the conflict with its owner's group, if any (see
+    /// [`DiplomacyState::record_breach`](crate::diplomacy::DiplomacyState::record_breach)).
+    ///
+    /// [`breach_target`]: emergence_world::structure::breach_target
+    pub breach: Option<(LocationId, u32)>,
+    /// What a `Mine` or `DeepenMine` action did to the location's ore body
+    /// this tick, if it has one.
+    ///
+    /// The caller must apply it via
+    /// [`MineRegistry::record`](emergence_world::MineRegistry::record).
+    pub mine_work: Option<MineWork>,
+    /// Levels of intensity an `Extinguish` action took off the fire at the
+    /// agent's location this tick, if any.
+    ///
+    /// The caller must lower the fire via
+    /// [`FireSystem::suppress`](emergence_world::FireSystem::suppress).
+    pub fire_suppressed: Option<u32>,
 }
 
 /// Execute a gather action: collect resources from the agent's location.
@@ -265,16 +400,28 @@ pub fn execute_gather(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -326,16 +473,28 @@ pub fn execute_eat(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -399,25 +558,40 @@ pub fn execute_drink(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
 /// Execute a rest action: recover energy.
 ///
 /// Rest recovery is modified by the shelter bonus if the agent is sheltered.
+/// An agent who knows weather lore also earns [`skills::XP_FORECAST`] (5)
+/// forecasting XP.
 ///
 /// Modifies:
 /// - Agent energy (increased by rest recovery, clamped to max)
+/// - Agent forecasting XP, if they know weather lore
 pub fn execute_rest(
     agent: &mut AgentState,
     config: &VitalsConfig,
@@ -436,11 +610,23 @@ pub fn execute_rest(
 
     let energy_recovered = agent.energy.saturating_sub(energy_before);
 
+    let mut skill_xp = BTreeMap::new();
+    if agent.knowledge.contains(environment::FORECAST_KNOWLEDGE) {
+        let xp_gained = skills::XP_FORECAST;
+        let xp_entry = agent.skill_xp.entry(String::from("forecasting")).or_insert(0);
+        *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+            AgentError::ArithmeticOverflow {
+                context: String::from("forecasting XP overflow"),
+            }
+        })?;
+        skill_xp.insert(String::from("forecasting"), xp_gained);
+    }
+
     Ok(HandlerResult {
         outcome: ActionOutcome {
             resource_changes: BTreeMap::new(),
             energy_spent: costs::energy_cost(ActionType::Rest),
-            skill_xp: BTreeMap::new(),
+            skill_xp,
             details: serde_json::json!({
                 "energy_recovered": energy_recovered,
                 "sheltered": ctx.is_sheltered,
@@ -452,16 +638,28 @@ pub fn execute_rest(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -545,16 +743,28 @@ pub fn execute_move(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -635,16 +845,28 @@ pub fn execute_communicate(
         messages: vec![msg],
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -695,16 +917,28 @@ pub fn execute_broadcast(
         messages: vec![msg],
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -768,16 +1002,28 @@ pub fn execute_teach(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -817,16 +1063,28 @@ pub fn execute_reproduce(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -901,6 +1159,7 @@ pub fn execute_build(
         occupants: BTreeSet::new(),
         access_list: None,
         properties: bp.properties,
+        inventory: BTreeMap::new(),
     };
 
     let structure_id = structure.id;
@@ -923,16 +1182,28 @@ pub fn execute_build(
         messages: Vec::new(),
         structure_built: Some(structure),
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1027,16 +1298,205 @@ pub fn execute_repair(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: Some(structure_id),
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute an upgrade action: raise a structure to its next tier.
+///
+/// The handler:
+/// 1. Looks up the structure from the execution context
+/// 2. Computes the upgrade cost (the next tier's blueprint less the current)
+/// 3. Deducts the upgrade materials from the agent's inventory
+/// 4. Deducts the upgrade energy cost (25)
+/// 5. Awards [`skills::XP_BUILD`] (15) building XP
+/// 6. Returns the structure ID in `structure_upgraded`
+///
+/// The tick cycle is responsible for calling [`apply_upgrade`] on the
+/// actual structure in the world map.
+///
+/// [`apply_upgrade`]: emergence_world::structure::apply_upgrade
+///
+/// Modifies:
+/// - Agent inventory (removes upgrade materials)
+/// - Agent energy (deducted for upgrade cost)
+/// - Agent skill XP (adds building XP)
+pub fn execute_upgrade_structure(
+    agent: &mut AgentState,
+    structure_id: StructureId,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    // Look up the structure at the location
+    let structure = ctx
+        .structures_at_location
+        .get(&structure_id)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: format!("structure {structure_id} not found at location for upgrade"),
+        })?;
+
+    let next = world_structure::next_tier(structure.structure_type).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: format!("structure {structure_id} has no tier to upgrade to"),
+        }
+    })?;
+    let upgrade_costs =
+        world_structure::upgrade_cost(structure.structure_type).unwrap_or_default();
+
+    // Deduct upgrade materials from inventory
+    let mut resource_changes: BTreeMap<Resource, i64> = BTreeMap::new();
+    for (&resource, &quantity) in &upgrade_costs {
+        inventory::remove_resource(&mut agent.inventory, resource, quantity)?;
+        let neg = i64::from(quantity).checked_neg().ok_or_else(|| {
+            AgentError::ArithmeticOverflow {
+                context: String::from("upgrade material cost negation overflow"),
+            }
+        })?;
+        resource_changes.insert(resource, neg);
+    }
+
+    // Deduct energy
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::UpgradeStructure));
+
+    // Award building XP
+    let xp_gained = skills::XP_BUILD;
+    let xp_entry = agent
+        .skill_xp
+        .entry(String::from("building"))
+        .or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("building XP overflow in upgrade"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("building"), xp_gained);
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::UpgradeStructure),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "upgrade_structure",
+                "structure_id": structure_id.to_string(),
+                "from": format!("{:?}", structure.structure_type),
+                "to": format!("{next:?}"),
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: Some(structure_id),
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute an extinguish action: pour water on the fire at the location.
+///
+/// Consumes [`fire::WATER_PER_SUPPRESSION`] (2) water and lowers the
+/// fire's intensity by up to [`fire::SUPPRESSION_PER_ACTION`] (2) levels,
+/// putting it out at zero. Deducts 15 energy.
+pub fn execute_extinguish(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    inventory::remove_resource(&mut agent.inventory, Resource::Water, fire::WATER_PER_SUPPRESSION)?;
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Extinguish));
+
+    let suppressed = fire::SUPPRESSION_PER_ACTION.min(ctx.fire_intensity);
+    ctx.fire_intensity = ctx.fire_intensity.saturating_sub(suppressed);
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(
+        Resource::Water,
+        i64::from(fire::WATER_PER_SUPPRESSION).saturating_neg(),
+    );
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Extinguish),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "extinguish",
+                "suppressed": suppressed,
+                "fire_intensity": ctx.fire_intensity,
+                "put_out": ctx.fire_intensity == 0,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: Some(suppressed),
     })
 }
 
@@ -1113,16 +1573,28 @@ pub fn execute_demolish(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: Some(structure_id),
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1250,16 +1722,173 @@ pub fn execute_improve_route(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded,
         route_repaired: route_repaired_val,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a found-settlement action: settle a new location beside the
+/// agent's own.
+///
+/// Validation has already checked that the location is wilderness and
+/// that enough founders are present. The handler deducts the energy cost
+/// (30) and returns the settlement's name in `settlement_founded`; the
+/// tick cycle adds the location and its route back to the world map.
+///
+/// Modifies:
+/// - Agent energy (deducted for founding cost)
+pub fn execute_found_settlement(
+    agent: &mut AgentState,
+    name: &str,
+    ctx: &ExecutionContext,
+) -> HandlerResult {
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::FoundSettlement));
+
+    HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes: BTreeMap::new(),
+            energy_spent: costs::energy_cost(ActionType::FoundSettlement),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "found_settlement",
+                "name": name,
+                "origin": agent.location_id.to_string(),
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: Some(name.to_string()),
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    }
+}
+
+/// Execute a build-route action: put a tick of work into a new route.
+///
+/// The handler:
+/// 1. Deducts the route's materials, if this action starts the work
+/// 2. Deducts energy (30)
+/// 3. Awards [`skills::XP_BUILD`] (15) building XP
+/// 4. Returns the destination in `route_work`
+///
+/// The tick cycle is responsible for recording the work on the world map,
+/// which adds the route once enough work is done.
+///
+/// Modifies:
+/// - Agent inventory (removes route materials, if starting the work)
+/// - Agent energy (deducted for build route cost)
+/// - Agent skill XP (adds building XP)
+pub fn execute_build_route(
+    agent: &mut AgentState,
+    destination: LocationId,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let plan = ctx.route_to_build.as_ref().ok_or_else(|| AgentError::ArithmeticOverflow {
+        context: String::from("route_to_build not set in ExecutionContext for BuildRoute"),
+    })?;
+
+    let mut resource_changes: BTreeMap<Resource, i64> = BTreeMap::new();
+    for (&resource, &required) in &plan.cost {
+        inventory::remove_resource(&mut agent.inventory, resource, required)?;
+        resource_changes.insert(resource, i64::from(required).saturating_neg());
+    }
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::BuildRoute));
+
+    let xp_gained = skills::XP_BUILD;
+    let xp_entry = agent.skill_xp.entry(String::from("building")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("building XP overflow in build_route"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("building"), xp_gained);
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::BuildRoute),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "build_route",
+                "destination": destination.to_string(),
+                "length": plan.length,
+                "started_work": !plan.cost.is_empty(),
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: Some(destination),
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1321,54 +1950,377 @@ pub fn execute_claim(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: Some(structure_id),
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
-/// Execute a legislate action: create a governance rule for a group.
+/// Execute a transfer action: give a structure the agent owns to another
+/// agent at the same location.
 ///
 /// The handler:
-/// 1. Verifies the agent is a member of the specified group
-/// 2. Verifies a `MeetingHall` structure exists at the location
-/// 3. Deducts the legislate energy cost (10)
-/// 4. Creates a new [`Rule`] and returns it in `rule_created`
+/// 1. Looks up the target structure from the execution context
+/// 2. Verifies the agent owns it
+/// 3. Deducts the transfer energy cost (2)
+/// 4. Returns the structure and its new owner in `structure_transferred`
 ///
-/// The tick cycle is responsible for storing the rule in the active rules
-/// registry and emitting the `RuleCreated` event.
+/// The tick cycle is responsible for updating the structure's `owner` field
+/// in world state and emitting the `StructureClaimed` event.
 ///
 /// Modifies:
-/// - Agent energy (deducted for legislate cost)
-pub fn execute_legislate(
+/// - Agent energy (deducted for transfer cost)
+pub fn execute_transfer_ownership(
     agent: &mut AgentState,
-    rule_name: &str,
-    rule_description: &str,
-    group_id: GroupId,
+    structure_id: StructureId,
+    new_owner: AgentId,
     ctx: &ExecutionContext,
 ) -> Result<HandlerResult, AgentError> {
-    // Verify agent is a member of the group
-    if !ctx.agent_groups.contains(&group_id) {
+    // Look up the structure at the location
+    let structure = ctx
+        .structures_at_location
+        .get(&structure_id)
+        .ok_or_else(|| AgentError::GovernanceFailed {
+            reason: format!("structure {structure_id} not found at location for transfer"),
+        })?;
+
+    // Only the owner can give a structure away
+    if structure.owner != Some(agent.agent_id) {
         return Err(AgentError::GovernanceFailed {
-            reason: format!(
-                "agent {} is not a member of group {group_id}",
-                agent.agent_id
-            ),
+            reason: format!("structure {structure_id} is not owned by {}", agent.agent_id),
         });
     }
 
-    // Verify a MeetingHall exists at the location
-    let has_meeting_hall = ctx
-        .structures_at_location
-        .values()
-        .any(|s| s.structure_type == StructureType::MeetingHall);
+    // Deduct energy
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::TransferOwnership));
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes: BTreeMap::new(),
+            energy_spent: costs::energy_cost(ActionType::TransferOwnership),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "transfer_ownership",
+                "structure_id": structure_id.to_string(),
+                "structure_type": format!("{:?}", structure.structure_type),
+                "new_owner": new_owner.to_string(),
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: Some((structure_id, new_owner)),
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a deposit action: put resources from the agent's inventory into
+/// a shared storage structure at the agent's location.
+///
+/// The handler:
+/// 1. Looks up the target structure from the execution context
+/// 2. Removes the deposited resources from the agent's inventory
+/// 3. Deducts the deposit energy cost (2)
+/// 4. Returns the deposit in `storage_change`
+///
+/// The tick cycle is responsible for calling [`deposit`] on the actual
+/// structure in the world map and recording the `Deposit` ledger entry.
+///
+/// [`deposit`]: emergence_world::structure::deposit
+///
+/// Modifies:
+/// - Agent inventory (removes the deposited resources)
+/// - Agent energy (deducted for deposit cost)
+pub fn execute_deposit(
+    agent: &mut AgentState,
+    structure_id: StructureId,
+    resource: Resource,
+    quantity: u32,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    // Look up the structure at the location
+    let structure = ctx
+        .structures_at_location
+        .get(&structure_id)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: format!("structure {structure_id} not found at location for deposit"),
+        })?;
+
+    // Take the resources out of the agent's inventory
+    inventory::remove_resource(&mut agent.inventory, resource, quantity)?;
+
+    // Deduct energy
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Deposit));
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(resource, i64::from(quantity).saturating_neg());
+
+    Ok(storage_result(
+        ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Deposit),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "deposit",
+                "structure_id": structure_id.to_string(),
+                "structure_type": format!("{:?}", structure.structure_type),
+                "resource": format!("{resource:?}"),
+                "quantity": quantity,
+                "tick": ctx.current_tick,
+            }),
+        },
+        (structure_id, resource, i64::from(quantity)),
+    ))
+}
+
+/// Execute a withdraw action: take resources out of a shared storage
+/// structure at the agent's location into the agent's inventory.
+///
+/// The handler:
+/// 1. Looks up the target structure from the execution context
+/// 2. Verifies the structure holds enough of the resource
+/// 3. Adds the withdrawn resources to the agent's inventory
+/// 4. Deducts the withdraw energy cost (2)
+/// 5. Returns the withdrawal in `storage_change`
+///
+/// The tick cycle is responsible for calling [`withdraw`] on the actual
+/// structure in the world map and recording the `Withdrawal` ledger entry.
+///
+/// [`withdraw`]: emergence_world::structure::withdraw
+///
+/// Modifies:
+/// - Agent inventory (adds the withdrawn resources)
+/// - Agent energy (deducted for withdraw cost)
+pub fn execute_withdraw(
+    agent: &mut AgentState,
+    structure_id: StructureId,
+    resource: Resource,
+    quantity: u32,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    // Look up the structure at the location
+    let structure = ctx
+        .structures_at_location
+        .get(&structure_id)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: format!("structure {structure_id} not found at location for withdraw"),
+        })?;
+
+    // The structure must hold what is withdrawn
+    let stored = structure.inventory.get(&resource).copied().unwrap_or(0);
+    if stored < quantity {
+        return Err(AgentError::InsufficientResource {
+            resource,
+            requested: quantity,
+            available: stored,
+        });
+    }
+
+    // Add the resources to the agent's inventory
+    inventory::add_resource(&mut agent.inventory, agent.carry_capacity, resource, quantity)?;
+
+    // Deduct energy
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Withdraw));
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(resource, i64::from(quantity));
+
+    Ok(storage_result(
+        ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Withdraw),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "withdraw",
+                "structure_id": structure_id.to_string(),
+                "structure_type": format!("{:?}", structure.structure_type),
+                "resource": format!("{resource:?}"),
+                "quantity": quantity,
+                "tick": ctx.current_tick,
+            }),
+        },
+        (structure_id, resource, i64::from(quantity).saturating_neg()),
+    ))
+}
+
+/// Execute a breach action: batter the fortifications barring entry to
+/// an adjacent location.
+///
+/// The handler:
+/// 1. Deducts the breach energy cost (25)
+/// 2. Returns [`costs::BREACH_DAMAGE`] against the destination in `breach`
+///
+/// The tick cycle is responsible for applying the damage with
+/// [`apply_breach`] to the fortification in the world state; validation
+/// has already checked that one bars the agent.
+///
+/// [`apply_breach`]: emergence_world::structure::apply_breach
+///
+/// Modifies:
+/// - Agent energy (deducted for breach cost)
+pub fn execute_breach(
+    agent: &mut AgentState,
+    destination: LocationId,
+    ctx: &ExecutionContext,
+) -> HandlerResult {
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Breach));
+
+    HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes: BTreeMap::new(),
+            energy_spent: costs::energy_cost(ActionType::Breach),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "breach",
+                "destination": destination.to_string(),
+                "damage": costs::BREACH_DAMAGE,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: Some((destination, costs::BREACH_DAMAGE)),
+        mine_work: None,
+        fire_suppressed: None,
+    }
+}
+
+/// Build the result of a deposit or withdrawal.
+const fn storage_result(
+    outcome: ActionOutcome,
+    storage_change: (StructureId, Resource, i64),
+) -> HandlerResult {
+    HandlerResult {
+        outcome,
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: Some(storage_change),
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    }
+}
+
+/// Execute a legislate action: create a governance rule for a group.
+///
+/// The handler:
+/// 1. Verifies the agent is a member of the specified group
+/// 2. Verifies a `MeetingHall` structure exists at the location
+/// 3. Deducts the legislate energy cost (10)
+/// 4. Creates a new [`Rule`] and returns it in `rule_created`
+///
+/// The tick cycle is responsible for storing the rule in the active rules
+/// registry and emitting the `RuleCreated` event.
+///
+/// Modifies:
+/// - Agent energy (deducted for legislate cost)
+pub fn execute_legislate(
+    agent: &mut AgentState,
+    rule_name: &str,
+    rule_description: &str,
+    group_id: GroupId,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    // Verify agent is a member of the group
+    if !ctx.agent_groups.contains(&group_id) {
+        return Err(AgentError::GovernanceFailed {
+            reason: format!(
+                "agent {} is not a member of group {group_id}",
+                agent.agent_id
+            ),
+        });
+    }
+
+    // Verify a MeetingHall exists at the location
+    let has_meeting_hall = ctx
+        .structures_at_location
+        .values()
+        .any(|s| s.structure_type == StructureType::MeetingHall);
 
     if !has_meeting_hall {
         return Err(AgentError::GovernanceFailed {
@@ -1409,16 +2361,28 @@ pub fn execute_legislate(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: Some(rule),
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1492,16 +2456,28 @@ pub fn execute_enforce(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: Some(enforcement_details),
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1552,48 +2528,634 @@ pub fn execute_farm_plant(
 
     inventory::remove_resource(&mut agent.inventory, seed_food, 1)?;
 
-    let growth_ticks = farming::DEFAULT_GROWTH_TICKS;
-    let success = ctx
-        .farm_registry
-        .plant(farm_id, ctx.current_tick, growth_ticks);
-    if !success {
-        return Err(AgentError::ArithmeticOverflow {
-            context: String::from("farm_registry.plant failed (already planted or overflow)"),
+    let growth_ticks = farming::DEFAULT_GROWTH_TICKS;
+    let success = ctx
+        .farm_registry
+        .plant(farm_id, ctx.current_tick, growth_ticks);
+    if !success {
+        return Err(AgentError::ArithmeticOverflow {
+            context: String::from("farm_registry.plant failed (already planted or overflow)"),
+        });
+    }
+    ctx.farm_registry.sow(farm_id, seed_food, ctx.current_tick);
+
+    let mature_at_tick = ctx.current_tick.checked_add(growth_ticks).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("mature_at_tick overflow"),
+        }
+    })?;
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::FarmPlant));
+
+    let xp_gained = skills::XP_FARM_PLANT;
+    let xp_entry = agent.skill_xp.entry(String::from("farming")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("farming XP overflow in farm_plant"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("farming"), xp_gained);
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(seed_food, -1);
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::FarmPlant),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "farm_plant",
+                "farm_id": farm_id.to_string(),
+                "seed_food": format!("{seed_food:?}"),
+                "mature_at_tick": mature_at_tick,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: Some((farm_id, mature_at_tick)),
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a farm-harvest action: harvest mature crops from a farm plot.
+///
+/// Yields [`farming::BASE_HARVEST_YIELD`] (5) + farming skill bonus units of
+/// [`Resource::FoodFarmed`], scaled by the context's `irrigated_yield_pct`
+/// if the plot is watered, by its `farm_yield_pct` (reduced on polluted
+/// land), and by the plot's soil fertility (see
+/// [`farming::fertility_yield_pct`]). The harvest drains the soil and
+/// leaves [`farming::HARVEST_WASTE`] (2) [`Resource::FoodWaste`], as much
+/// as the agent can carry. Deducts 10 energy, awards
+/// [`skills::XP_FARM_HARVEST`] (10) farming XP.
+///
+/// A plot is watered if a river or lake is nearby, or if a standing
+/// [`StructureType::Irrigation`] channel at the location can draw
+/// [`farming::IRRIGATION_WATER_PER_HARVEST`] water from it; the water
+/// drawn is returned in `location_resource_deltas`.
+pub fn execute_farm_harvest(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let farm_id = ctx
+        .structures_at_location
+        .iter()
+        .find(|(sid, s)| {
+            s.structure_type == StructureType::FarmPlot
+                && s.durability > 0
+                && s.destroyed_at_tick.is_none()
+                && ctx.farm_registry.is_harvestable(**sid, ctx.current_tick)
+        })
+        .map(|(id, _)| *id)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: String::from("no harvestable farm plot at location"),
+        })?;
+
+    let (irrigation_pct, channel) = harvest_watering(ctx);
+    let skill_level = agent.skills.get("farming").copied().unwrap_or(0);
+    let fertility = ctx.farm_registry.fertility_at(farm_id, ctx.current_tick);
+    let fertility_pct = farming::fertility_yield_pct(fertility);
+    let yield_amount = farming::harvest_yield(skill_level, irrigation_pct)
+        .and_then(|y| y.checked_mul(ctx.farm_yield_pct))
+        .and_then(|y| y.checked_div(100))
+        .and_then(|y| y.checked_mul(fertility_pct))
+        .and_then(|y| y.checked_div(100))
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: String::from("harvest yield overflow"),
+        })?;
+
+    inventory::add_resource(
+        &mut agent.inventory,
+        agent.carry_capacity,
+        Resource::FoodFarmed,
+        yield_amount,
+    )?;
+
+    ctx.farm_registry.harvest(farm_id);
+    let fertility_left = ctx.farm_registry.deplete(farm_id, ctx.current_tick);
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::FarmHarvest));
+
+    let xp_gained = skills::XP_FARM_HARVEST;
+    let xp_entry = agent.skill_xp.entry(String::from("farming")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("farming XP overflow in farm_harvest"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("farming"), xp_gained);
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(Resource::FoodFarmed, i64::from(yield_amount));
+    let chaff = collect_chaff(agent);
+    if chaff > 0 {
+        resource_changes.insert(Resource::FoodWaste, i64::from(chaff));
+    }
+
+    let mut location_resource_deltas = BTreeMap::new();
+    if channel {
+        location_resource_deltas.insert(Resource::Water, farming::IRRIGATION_WATER_PER_HARVEST);
+    }
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::FarmHarvest),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "farm_harvest",
+                "farm_id": farm_id.to_string(),
+                "yield": yield_amount,
+                "yield_pct": ctx.farm_yield_pct,
+                "irrigation_pct": irrigation_pct,
+                "fertility": fertility,
+                "fertility_left": fertility_left,
+                "skill_level": skill_level,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas,
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: Some(farm_id),
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Return the percentage of the usual harvest the plot's watering yields,
+/// and whether an irrigation channel draws on the location's water for it.
+///
+/// The plot is watered from nearby water, or else from a channel.
+fn harvest_watering(ctx: &ExecutionContext) -> (u32, bool) {
+    let channel = !ctx.water_nearby
+        && ctx.structures_at_location.values().any(|s| {
+            s.structure_type == StructureType::Irrigation
+                && s.durability > 0
+                && s.destroyed_at_tick.is_none()
+        })
+        && ctx.location_resources.get(&Resource::Water).copied().unwrap_or(0)
+            >= farming::IRRIGATION_WATER_PER_HARVEST;
+    let irrigation_pct = if ctx.water_nearby || channel {
+        ctx.irrigated_yield_pct
+    } else {
+        100
+    };
+    (irrigation_pct, channel)
+}
+
+/// Add a harvest's [`farming::HARVEST_WASTE`] to the agent's inventory,
+/// returning the amount kept: none if the agent cannot carry it all.
+fn collect_chaff(agent: &mut AgentState) -> u32 {
+    let waste = farming::HARVEST_WASTE;
+    inventory::add_resource(&mut agent.inventory, agent.carry_capacity, Resource::FoodWaste, waste)
+        .map_or(0, |()| waste)
+}
+
+/// Execute a fertilize action: work food waste into a farm plot's soil.
+///
+/// Picks the standing [`StructureType::FarmPlot`] at the location with the
+/// poorest soil, consumes [`farming::FERTILIZER_WASTE`] (3)
+/// [`Resource::FoodWaste`], and adds [`farming::FERTILIZER_GAIN`] (30) to
+/// its fertility. Deducts 10 energy, awards [`skills::XP_FERTILIZE`] (5)
+/// farming XP.
+pub fn execute_fertilize(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let farm_id = ctx
+        .structures_at_location
+        .iter()
+        .filter(|(_, s)| {
+            s.structure_type == StructureType::FarmPlot
+                && s.durability > 0
+                && s.destroyed_at_tick.is_none()
+        })
+        .min_by_key(|(sid, _)| ctx.farm_registry.fertility_at(**sid, ctx.current_tick))
+        .map(|(id, _)| *id)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: String::from("no farm plot at location to fertilize"),
+        })?;
+
+    inventory::remove_resource(
+        &mut agent.inventory,
+        Resource::FoodWaste,
+        farming::FERTILIZER_WASTE,
+    )?;
+    let fertility = ctx.farm_registry.fertilize(farm_id, ctx.current_tick);
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Fertilize));
+
+    let xp_gained = skills::XP_FERTILIZE;
+    let xp_entry = agent.skill_xp.entry(String::from("farming")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("farming XP overflow in fertilize"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("farming"), xp_gained);
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(
+        Resource::FoodWaste,
+        i64::from(farming::FERTILIZER_WASTE).saturating_neg(),
+    );
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Fertilize),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "fertilize",
+                "farm_id": farm_id.to_string(),
+                "fertility": fertility,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a craft action: produce a tool, advanced tool, or medicine at a workshop.
+///
+/// Deducts 15 energy, awards [`skills::XP_CRAFT`] (10) crafting XP.
+pub fn execute_craft(
+    agent: &mut AgentState,
+    output: Resource,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let recipe = crafting::recipe_for(output).ok_or_else(|| AgentError::ArithmeticOverflow {
+        context: format!("no crafting recipe for {output:?}"),
+    })?;
+
+    let mut resource_changes: BTreeMap<Resource, i64> = BTreeMap::new();
+    for (&resource, &quantity) in &recipe.inputs {
+        inventory::remove_resource(&mut agent.inventory, resource, quantity)?;
+        let neg = i64::from(quantity).checked_neg().ok_or_else(|| {
+            AgentError::ArithmeticOverflow {
+                context: String::from("craft input negation overflow"),
+            }
+        })?;
+        resource_changes.insert(resource, neg);
+    }
+
+    inventory::add_resource(
+        &mut agent.inventory,
+        agent.carry_capacity,
+        recipe.output,
+        recipe.output_quantity,
+    )?;
+    resource_changes.insert(recipe.output, i64::from(recipe.output_quantity));
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Craft));
+
+    let xp_gained = skills::XP_CRAFT;
+    let xp_entry = agent.skill_xp.entry(String::from("crafting")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("crafting XP overflow"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("crafting"), xp_gained);
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Craft),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "craft",
+                "output": format!("{:?}", recipe.output),
+                "quantity": recipe.output_quantity,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a mine action: extract ore from the location.
+///
+/// Yield is [`costs::BASE_MINE_YIELD`] (2) + mining skill bonus, capped by
+/// available ore and by what the mine face exposes. Deducts 20 energy,
+/// awards [`skills::XP_MINE`] (10) mining XP.
+///
+/// A deepened shaft caves in when the context's `collapse_roll` is below
+/// its collapse risk: the miner comes away with nothing and loses
+/// [`mining::CAVE_IN_DAMAGE`] health.
+pub fn execute_mine(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    if ctx
+        .ore_body
+        .is_some_and(|body| ctx.collapse_roll < body.collapse_risk_pct())
+    {
+        return Ok(mine_cave_in(agent, ctx));
+    }
+
+    let skill_level = agent.skills.get("mining").copied().unwrap_or(0);
+    let target_yield =
+        effects::mining_yield(costs::BASE_MINE_YIELD, skill_level).ok_or_else(|| {
+            AgentError::ArithmeticOverflow {
+                context: String::from("mine yield overflow"),
+            }
+        })?;
+
+    let available = ctx
+        .location_resources
+        .get(&Resource::Ore)
+        .copied()
+        .unwrap_or(0);
+    let face = ctx.ore_body.map_or(u32::MAX, |body| body.face);
+    let actual = target_yield.min(available).min(face);
+
+    inventory::add_resource(
+        &mut agent.inventory,
+        agent.carry_capacity,
+        Resource::Ore,
+        actual,
+    )?;
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Mine));
+
+    let mine_work = ctx
+        .ore_body
+        .as_mut()
+        .map(|body| MineWork::Mined(body.extract(actual)));
+
+    if let Some(loc_avail) = ctx.location_resources.get_mut(&Resource::Ore) {
+        *loc_avail = loc_avail.saturating_sub(actual);
+    }
+
+    let xp_gained = skills::XP_MINE;
+    let xp_entry = agent.skill_xp.entry(String::from("mining")).or_insert(0);
+    *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("mining XP overflow"),
+        }
+    })?;
+
+    let mut skill_xp = BTreeMap::new();
+    skill_xp.insert(String::from("mining"), xp_gained);
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(Resource::Ore, i64::from(actual));
+
+    let mut location_deltas = BTreeMap::new();
+    location_deltas.insert(Resource::Ore, actual);
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Mine),
+            skill_xp,
+            details: serde_json::json!({
+                "type": "mine",
+                "yield": actual,
+                "skill_level": skill_level,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: location_deltas,
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work,
+        fire_suppressed: None,
+    })
+}
+
+/// Bury the miner's shaft under a cave-in: no ore, no XP, and
+/// [`mining::CAVE_IN_DAMAGE`] health lost on top of the energy spent.
+fn mine_cave_in(agent: &mut AgentState, ctx: &mut ExecutionContext) -> HandlerResult {
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Mine));
+    agent.health = agent.health.saturating_sub(mining::CAVE_IN_DAMAGE);
+    let depth = ctx.ore_body.map_or(0, |body| body.depth);
+    if let Some(body) = ctx.ore_body.as_mut() {
+        body.cave_in();
+    }
+
+    HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes: BTreeMap::new(),
+            energy_spent: costs::energy_cost(ActionType::Mine),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "mine",
+                "yield": 0,
+                "caved_in": true,
+                "depth": depth,
+                "health_lost": mining::CAVE_IN_DAMAGE,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: Some(MineWork::CavedIn),
+        fire_suppressed: None,
+    }
+}
+
+/// Execute a deepen mine action: sink the location's shaft one level.
+///
+/// Consumes the wood and tools [`mining::deepen_cost`] asks for at the
+/// shaft's current depth and opens a fresh face of up to
+/// [`mining::FACE_CAPACITY`] ore. Deducts 30 energy and awards
+/// [`skills::XP_MINE`] (10) mining XP.
+pub fn execute_deepen_mine(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let Some(body) = ctx.ore_body.as_mut().filter(|body| body.can_deepen()) else {
+        return Err(AgentError::InsufficientResource {
+            resource: Resource::Ore,
+            requested: 1,
+            available: 0,
         });
+    };
+    let cost = mining::deepen_cost(body.depth);
+    for (resource, qty) in &cost {
+        inventory::remove_resource(&mut agent.inventory, *resource, *qty)?;
     }
+    let opened = body.deepen().unwrap_or(0);
+    let depth = body.depth;
 
-    let mature_at_tick = ctx.current_tick.checked_add(growth_ticks).ok_or_else(|| {
-        AgentError::ArithmeticOverflow {
-            context: String::from("mature_at_tick overflow"),
-        }
-    })?;
-
-    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::FarmPlant));
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::DeepenMine));
 
-    let xp_gained = skills::XP_FARM_PLANT;
-    let xp_entry = agent.skill_xp.entry(String::from("farming")).or_insert(0);
+    let xp_gained = skills::XP_MINE;
+    let xp_entry = agent.skill_xp.entry(String::from("mining")).or_insert(0);
     *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
         AgentError::ArithmeticOverflow {
-            context: String::from("farming XP overflow in farm_plant"),
+            context: String::from("mining XP overflow"),
         }
     })?;
 
     let mut skill_xp = BTreeMap::new();
-    skill_xp.insert(String::from("farming"), xp_gained);
+    skill_xp.insert(String::from("mining"), xp_gained);
 
-    let mut resource_changes = BTreeMap::new();
-    resource_changes.insert(seed_food, -1);
+    let resource_changes = cost
+        .iter()
+        .map(|(resource, qty)| (*resource, i64::from(*qty).saturating_neg()))
+        .collect();
 
     Ok(HandlerResult {
         outcome: ActionOutcome {
             resource_changes,
-            energy_spent: costs::energy_cost(ActionType::FarmPlant),
+            energy_spent: costs::energy_cost(ActionType::DeepenMine),
             skill_xp,
             details: serde_json::json!({
-                "type": "farm_plant",
-                "farm_id": farm_id.to_string(),
-                "seed_food": format!("{seed_food:?}"),
-                "mature_at_tick": mature_at_tick,
+                "type": "deepen_mine",
+                "depth": depth,
+                "ore_opened": opened,
                 "tick": ctx.current_tick,
             }),
         },
@@ -1602,81 +3164,108 @@ pub fn execute_farm_plant(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
-        farm_planted: Some((farm_id, mature_at_tick)),
+        farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: Some(MineWork::Deepened),
+        fire_suppressed: None,
     })
 }
 
-/// Execute a farm-harvest action: harvest mature crops from a farm plot.
+/// Execute a hunt action: take game at the location for meat and hides.
 ///
-/// Yields [`farming::BASE_HARVEST_YIELD`] (5) + farming skill bonus units of
-/// [`Resource::FoodFarmed`]. Deducts 10 energy, awards
-/// [`skills::XP_FARM_HARVEST`] (10) farming XP.
-pub fn execute_farm_harvest(
+/// Takes [`costs::BASE_HUNT_YIELD`] (1) + hunting skill bonus animals,
+/// capped by the game available. Each animal yields
+/// [`fauna::MEAT_PER_ANIMAL`] `FoodMeat` and [`fauna::HIDE_PER_ANIMAL`]
+/// `Hide`. Where a standing [`StructureType::Pasture`] is at the location,
+/// one animal is corralled alive as [`Resource::Livestock`] instead of
+/// being butchered. Deducts 20 energy, awards [`skills::XP_HUNT`] (10)
+/// hunting XP.
+pub fn execute_hunt(
     agent: &mut AgentState,
     ctx: &mut ExecutionContext,
 ) -> Result<HandlerResult, AgentError> {
-    let farm_id = ctx
-        .structures_at_location
-        .iter()
-        .find(|(sid, s)| {
-            s.structure_type == StructureType::FarmPlot
-                && s.durability > 0
-                && s.destroyed_at_tick.is_none()
-                && ctx.farm_registry.is_harvestable(**sid, ctx.current_tick)
-        })
-        .map(|(id, _)| *id)
-        .ok_or_else(|| AgentError::ArithmeticOverflow {
-            context: String::from("no harvestable farm plot at location"),
+    let skill_level = agent.skills.get("hunting").copied().unwrap_or(0);
+    let target_yield =
+        effects::hunting_yield(costs::BASE_HUNT_YIELD, skill_level).ok_or_else(|| {
+            AgentError::ArithmeticOverflow {
+                context: String::from("hunt yield overflow"),
+            }
         })?;
+    let animals = target_yield.min(ctx.game_at_location);
+    let corralled = u32::from(animals > 0 && has_pasture(ctx));
+    let butchered = animals.saturating_sub(corralled);
 
-    let skill_level = agent.skills.get("farming").copied().unwrap_or(0);
-    let yield_amount =
-        farming::harvest_yield(skill_level).ok_or_else(|| AgentError::ArithmeticOverflow {
-            context: String::from("harvest yield overflow"),
-        })?;
+    let meat = butchered.checked_mul(fauna::MEAT_PER_ANIMAL).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("hunt meat overflow"),
+        }
+    })?;
+    let hide = butchered.checked_mul(fauna::HIDE_PER_ANIMAL).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("hunt hide overflow"),
+        }
+    })?;
 
+    inventory::add_resource(&mut agent.inventory, agent.carry_capacity, Resource::FoodMeat, meat)?;
+    inventory::add_resource(&mut agent.inventory, agent.carry_capacity, Resource::Hide, hide)?;
     inventory::add_resource(
         &mut agent.inventory,
         agent.carry_capacity,
-        Resource::FoodFarmed,
-        yield_amount,
+        Resource::Livestock,
+        corralled,
     )?;
 
-    ctx.farm_registry.harvest(farm_id);
-    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::FarmHarvest));
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Hunt));
 
-    let xp_gained = skills::XP_FARM_HARVEST;
-    let xp_entry = agent.skill_xp.entry(String::from("farming")).or_insert(0);
+    ctx.game_at_location = ctx.game_at_location.saturating_sub(animals);
+
+    let xp_gained = skills::XP_HUNT;
+    let xp_entry = agent.skill_xp.entry(String::from("hunting")).or_insert(0);
     *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
         AgentError::ArithmeticOverflow {
-            context: String::from("farming XP overflow in farm_harvest"),
+            context: String::from("hunting XP overflow"),
         }
     })?;
 
     let mut skill_xp = BTreeMap::new();
-    skill_xp.insert(String::from("farming"), xp_gained);
+    skill_xp.insert(String::from("hunting"), xp_gained);
 
     let mut resource_changes = BTreeMap::new();
-    resource_changes.insert(Resource::FoodFarmed, i64::from(yield_amount));
+    resource_changes.insert(Resource::FoodMeat, i64::from(meat));
+    resource_changes.insert(Resource::Hide, i64::from(hide));
+    if corralled > 0 {
+        resource_changes.insert(Resource::Livestock, i64::from(corralled));
+    }
 
     Ok(HandlerResult {
         outcome: ActionOutcome {
             resource_changes,
-            energy_spent: costs::energy_cost(ActionType::FarmHarvest),
+            energy_spent: costs::energy_cost(ActionType::Hunt),
             skill_xp,
             details: serde_json::json!({
-                "type": "farm_harvest",
-                "farm_id": farm_id.to_string(),
-                "yield": yield_amount,
+                "type": "hunt",
+                "animals": animals,
+                "meat": meat,
+                "hide": hide,
+                "corralled": corralled,
                 "skill_level": skill_level,
                 "tick": ctx.current_tick,
             }),
@@ -1686,72 +3275,154 @@ pub fn execute_farm_harvest(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
-        farm_harvested: Some(farm_id),
+        farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: Some(animals),
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
-/// Execute a craft action: produce a tool, advanced tool, or medicine at a workshop.
+/// Return whether a standing [`StructureType::Pasture`] is at the
+/// location to corral game in.
+fn has_pasture(ctx: &ExecutionContext) -> bool {
+    ctx.structures_at_location.values().any(|s| {
+        s.structure_type == StructureType::Pasture
+            && s.durability > 0
+            && s.destroyed_at_tick.is_none()
+    })
+}
+
+/// Execute a slaughter action: butcher a head of livestock.
 ///
-/// Deducts 15 energy, awards [`skills::XP_CRAFT`] (10) crafting XP.
-pub fn execute_craft(
+/// Removes one [`Resource::Livestock`] and yields
+/// [`fauna::MEAT_PER_ANIMAL`] `FoodMeat` and [`fauna::HIDE_PER_ANIMAL`]
+/// `Hide`, as a hunted animal does. Deducts 10 energy.
+pub fn execute_slaughter(
     agent: &mut AgentState,
-    output: Resource,
     ctx: &ExecutionContext,
 ) -> Result<HandlerResult, AgentError> {
-    let recipe = crafting::recipe_for(output).ok_or_else(|| AgentError::ArithmeticOverflow {
-        context: format!("no crafting recipe for {output:?}"),
-    })?;
-
-    let mut resource_changes: BTreeMap<Resource, i64> = BTreeMap::new();
-    for (&resource, &quantity) in &recipe.inputs {
-        inventory::remove_resource(&mut agent.inventory, resource, quantity)?;
-        let neg = i64::from(quantity).checked_neg().ok_or_else(|| {
-            AgentError::ArithmeticOverflow {
-                context: String::from("craft input negation overflow"),
-            }
-        })?;
-        resource_changes.insert(resource, neg);
-    }
-
+    inventory::remove_resource(&mut agent.inventory, Resource::Livestock, 1)?;
     inventory::add_resource(
         &mut agent.inventory,
         agent.carry_capacity,
-        recipe.output,
-        recipe.output_quantity,
+        Resource::FoodMeat,
+        fauna::MEAT_PER_ANIMAL,
     )?;
-    resource_changes.insert(recipe.output, i64::from(recipe.output_quantity));
+    inventory::add_resource(
+        &mut agent.inventory,
+        agent.carry_capacity,
+        Resource::Hide,
+        fauna::HIDE_PER_ANIMAL,
+    )?;
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Slaughter));
 
-    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Craft));
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(Resource::Livestock, -1);
+    resource_changes.insert(Resource::FoodMeat, i64::from(fauna::MEAT_PER_ANIMAL));
+    resource_changes.insert(Resource::Hide, i64::from(fauna::HIDE_PER_ANIMAL));
 
-    let xp_gained = skills::XP_CRAFT;
-    let xp_entry = agent.skill_xp.entry(String::from("crafting")).or_insert(0);
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Slaughter),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "slaughter",
+                "meat": fauna::MEAT_PER_ANIMAL,
+                "hide": fauna::HIDE_PER_ANIMAL,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a prospect action: search the location for a hidden deposit.
+///
+/// Succeeds when the context's roll is below
+/// [`prospecting::success_chance_pct`] for the agent's prospecting skill and
+/// knowledge, revealing the first hidden deposit if there is one. A
+/// location with nothing hidden always comes up empty. Deducts 15 energy
+/// and awards [`skills::XP_PROSPECT`] (10) prospecting XP either way.
+pub fn execute_prospect(
+    agent: &mut AgentState,
+    ctx: &mut ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    let skill_level = agent.skills.get("prospecting").copied().unwrap_or(0);
+    let chance = prospecting::success_chance_pct(skill_level, &agent.knowledge);
+    let discovered = if ctx.prospect_roll < chance && !ctx.hidden_resources.is_empty() {
+        Some(ctx.hidden_resources.remove(0))
+    } else {
+        None
+    };
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Prospect));
+
+    let xp_gained = skills::XP_PROSPECT;
+    let xp_entry = agent.skill_xp.entry(String::from("prospecting")).or_insert(0);
     *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
         AgentError::ArithmeticOverflow {
-            context: String::from("crafting XP overflow"),
+            context: String::from("prospecting XP overflow"),
         }
     })?;
 
     let mut skill_xp = BTreeMap::new();
-    skill_xp.insert(String::from("crafting"), xp_gained);
+    skill_xp.insert(String::from("prospecting"), xp_gained);
 
     Ok(HandlerResult {
         outcome: ActionOutcome {
-            resource_changes,
-            energy_spent: costs::energy_cost(ActionType::Craft),
+            resource_changes: BTreeMap::new(),
+            energy_spent: costs::energy_cost(ActionType::Prospect),
             skill_xp,
             details: serde_json::json!({
-                "type": "craft",
-                "output": format!("{:?}", recipe.output),
-                "quantity": recipe.output_quantity,
+                "type": "prospect",
+                "discovered": discovered,
+                "chance_pct": chance,
+                "skill_level": skill_level,
                 "tick": ctx.current_tick,
             }),
         },
@@ -1760,99 +3431,122 @@ pub fn execute_craft(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: discovered,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
-/// Execute a mine action: extract ore from the location.
+/// Execute a fish action: catch fish from nearby water.
 ///
-/// Yield is [`costs::BASE_MINE_YIELD`] (2) + mining skill bonus, capped by
-/// available ore. Deducts 20 energy, awards [`skills::XP_MINE`] (10) mining XP.
-pub fn execute_mine(
+/// Catches [`costs::BASE_FISH_YIELD`] (2) + fishing skill bonus fish,
+/// scaled by the context's `fishing_yield_pct` (halved in a drought) and
+/// capped by the fish nearby. Each fish yields [`waters::FOOD_PER_FISH`]
+/// `FoodFish`. Deducts 15 energy, awards [`skills::XP_FISH`] (10) fishing
+/// XP.
+pub fn execute_fish(
     agent: &mut AgentState,
     ctx: &mut ExecutionContext,
 ) -> Result<HandlerResult, AgentError> {
-    let skill_level = agent.skills.get("mining").copied().unwrap_or(0);
+    let skill_level = agent.skills.get("fishing").copied().unwrap_or(0);
     let target_yield =
-        effects::mining_yield(costs::BASE_MINE_YIELD, skill_level).ok_or_else(|| {
+        effects::fishing_yield(costs::BASE_FISH_YIELD, skill_level).ok_or_else(|| {
             AgentError::ArithmeticOverflow {
-                context: String::from("mine yield overflow"),
+                context: String::from("fish yield overflow"),
             }
         })?;
+    let weather_yield = target_yield
+        .checked_mul(ctx.fishing_yield_pct)
+        .and_then(|y| y.checked_div(100))
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: String::from("fish weather yield overflow"),
+        })?;
+    let caught = weather_yield.min(ctx.fish_nearby);
 
-    let available = ctx
-        .location_resources
-        .get(&Resource::Ore)
-        .copied()
-        .unwrap_or(0);
-    let actual = target_yield.min(available);
-
-    inventory::add_resource(
-        &mut agent.inventory,
-        agent.carry_capacity,
-        Resource::Ore,
-        actual,
-    )?;
+    let food = caught.checked_mul(waters::FOOD_PER_FISH).ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("fish food overflow"),
+        }
+    })?;
+    inventory::add_resource(&mut agent.inventory, agent.carry_capacity, Resource::FoodFish, food)?;
 
-    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Mine));
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Fish));
 
-    if let Some(loc_avail) = ctx.location_resources.get_mut(&Resource::Ore) {
-        *loc_avail = loc_avail.saturating_sub(actual);
-    }
+    ctx.fish_nearby = ctx.fish_nearby.saturating_sub(caught);
 
-    let xp_gained = skills::XP_MINE;
-    let xp_entry = agent.skill_xp.entry(String::from("mining")).or_insert(0);
+    let xp_gained = skills::XP_FISH;
+    let xp_entry = agent.skill_xp.entry(String::from("fishing")).or_insert(0);
     *xp_entry = xp_entry.checked_add(xp_gained).ok_or_else(|| {
         AgentError::ArithmeticOverflow {
-            context: String::from("mining XP overflow"),
+            context: String::from("fishing XP overflow"),
         }
     })?;
 
     let mut skill_xp = BTreeMap::new();
-    skill_xp.insert(String::from("mining"), xp_gained);
+    skill_xp.insert(String::from("fishing"), xp_gained);
 
     let mut resource_changes = BTreeMap::new();
-    resource_changes.insert(Resource::Ore, i64::from(actual));
-
-    let mut location_deltas = BTreeMap::new();
-    location_deltas.insert(Resource::Ore, actual);
+    resource_changes.insert(Resource::FoodFish, i64::from(food));
 
     Ok(HandlerResult {
         outcome: ActionOutcome {
             resource_changes,
-            energy_spent: costs::energy_cost(ActionType::Mine),
+            energy_spent: costs::energy_cost(ActionType::Fish),
             skill_xp,
             details: serde_json::json!({
-                "type": "mine",
-                "yield": actual,
+                "type": "fish",
+                "caught": caught,
+                "yield_pct": ctx.fishing_yield_pct,
                 "skill_level": skill_level,
                 "tick": ctx.current_tick,
             }),
         },
-        location_resource_deltas: location_deltas,
+        location_resource_deltas: BTreeMap::new(),
         began_travel: false,
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: Some(caught),
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1920,16 +3614,98 @@ pub fn execute_smelt(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
+        structure_demolished: None,
+        route_upgraded: None,
+        route_repaired: None,
+        structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
+        rule_created: None,
+        enforcement: None,
+        farm_planted: None,
+        farm_harvested: None,
+        library_write: None,
+        library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
+    })
+}
+
+/// Execute a chart action: draw the agent's map onto a hide.
+///
+/// Consumes [`costs::CHART_HIDE_INPUT`] hide and produces
+/// [`costs::CHART_MAP_OUTPUT`] map. Deducts 10 energy. Which places and
+/// routes the map shows is recorded by the caller.
+pub fn execute_chart(
+    agent: &mut AgentState,
+    ctx: &ExecutionContext,
+) -> Result<HandlerResult, AgentError> {
+    inventory::remove_resource(&mut agent.inventory, Resource::Hide, costs::CHART_HIDE_INPUT)?;
+    inventory::add_resource(
+        &mut agent.inventory,
+        agent.carry_capacity,
+        Resource::Map,
+        costs::CHART_MAP_OUTPUT,
+    )?;
+
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::Chart));
+
+    let hide_neg = i64::from(costs::CHART_HIDE_INPUT).checked_neg().ok_or_else(|| {
+        AgentError::ArithmeticOverflow {
+            context: String::from("chart hide negation overflow"),
+        }
+    })?;
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(Resource::Hide, hide_neg);
+    resource_changes.insert(Resource::Map, i64::from(costs::CHART_MAP_OUTPUT));
+
+    Ok(HandlerResult {
+        outcome: ActionOutcome {
+            resource_changes,
+            energy_spent: costs::energy_cost(ActionType::Chart),
+            skill_xp: BTreeMap::new(),
+            details: serde_json::json!({
+                "type": "chart",
+                "maps_drawn": costs::CHART_MAP_OUTPUT,
+                "tick": ctx.current_tick,
+            }),
+        },
+        location_resource_deltas: BTreeMap::new(),
+        began_travel: false,
+        messages: Vec::new(),
+        structure_built: None,
+        structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: true,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -1978,16 +3754,28 @@ pub fn execute_write(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: Some((library_id, String::from(knowledge))),
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -2033,16 +3821,28 @@ pub fn execute_read(
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: Some((library_id, String::from(knowledge))),
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     })
 }
 
@@ -2062,16 +3862,28 @@ pub fn execute_no_action(_agent: &AgentState) -> HandlerResult {
         messages: Vec::new(),
         structure_built: None,
         structure_repaired: None,
+        structure_upgraded: None,
         structure_demolished: None,
         route_upgraded: None,
         route_repaired: None,
         structure_claimed: None,
+        structure_transferred: None,
+        storage_change: None,
         rule_created: None,
         enforcement: None,
         farm_planted: None,
         farm_harvested: None,
         library_write: None,
         library_read: None,
+        animals_hunted: None,
+        resource_discovered: None,
+        fish_caught: None,
+        settlement_founded: None,
+        route_work: None,
+        map_charted: false,
+        breach: None,
+        mine_work: None,
+        fire_suppressed: None,
     }
 }
 
@@ -2080,6 +3892,7 @@ pub fn execute_no_action(_agent: &AgentState) -> HandlerResult {
 /// This is the main entry point for action execution after validation.
 /// Advanced actions (build, trade, craft, etc.) return `NoAction` outcomes
 /// in Phase 2 -- they will be implemented in Phase 3+.
+#[allow(clippy::too_many_lines)]
 pub fn execute_action(
     action_type: ActionType,
     params: &ActionParameters,
@@ -2117,14 +3930,50 @@ pub fn execute_action(
         (ActionType::Repair, ActionParameters::Repair { structure_id }) => {
             execute_repair(agent, *structure_id, ctx)
         }
+        (ActionType::Extinguish, ActionParameters::Extinguish) => execute_extinguish(agent, ctx),
         (ActionType::Demolish, ActionParameters::Demolish { structure_id }) => {
             execute_demolish(agent, *structure_id, ctx)
         }
+        (ActionType::UpgradeStructure, ActionParameters::UpgradeStructure { structure_id }) => {
+            execute_upgrade_structure(agent, *structure_id, ctx)
+        }
         (ActionType::ImproveRoute, ActionParameters::ImproveRoute { .. }) => {
             execute_improve_route(agent, ctx)
         }
-        (ActionType::Claim, ActionParameters::Claim { structure_id }) => {
-            execute_claim(agent, *structure_id, ctx)
+        (ActionType::BuildRoute, ActionParameters::BuildRoute { destination }) => {
+            execute_build_route(agent, *destination, ctx)
+        }
+        (ActionType::Claim, ActionParameters::Claim { structure_id }) => {
+            execute_claim(agent, *structure_id, ctx)
+        }
+        (
+            ActionType::TransferOwnership,
+            ActionParameters::TransferOwnership {
+                structure_id,
+                new_owner,
+            },
+        ) => execute_transfer_ownership(agent, *structure_id, *new_owner, ctx),
+        (
+            ActionType::Deposit,
+            ActionParameters::Deposit {
+                structure_id,
+                resource,
+                quantity,
+            },
+        ) => execute_deposit(agent, *structure_id, *resource, *quantity, ctx),
+        (
+            ActionType::Withdraw,
+            ActionParameters::Withdraw {
+                structure_id,
+                resource,
+                quantity,
+            },
+        ) => execute_withdraw(agent, *structure_id, *resource, *quantity, ctx),
+        (ActionType::FoundSettlement, ActionParameters::FoundSettlement { name }) => {
+            Ok(execute_found_settlement(agent, name, ctx))
+        }
+        (ActionType::Breach, ActionParameters::Breach { destination }) => {
+            Ok(execute_breach(agent, *destination, ctx))
         }
         (
             ActionType::Legislate,
@@ -2148,10 +3997,16 @@ pub fn execute_action(
         (ActionType::FarmHarvest, ActionParameters::FarmHarvest) => {
             execute_farm_harvest(agent, ctx)
         }
+        (ActionType::Fertilize, ActionParameters::Fertilize) => execute_fertilize(agent, ctx),
         (ActionType::Craft, ActionParameters::Craft { output }) => {
             execute_craft(agent, *output, ctx)
         }
         (ActionType::Mine, ActionParameters::Mine) => execute_mine(agent, ctx),
+        (ActionType::DeepenMine, ActionParameters::DeepenMine) => execute_deepen_mine(agent, ctx),
+        (ActionType::Hunt, ActionParameters::Hunt) => execute_hunt(agent, ctx),
+        (ActionType::Slaughter, ActionParameters::Slaughter) => execute_slaughter(agent, ctx),
+        (ActionType::Prospect, ActionParameters::Prospect) => execute_prospect(agent, ctx),
+        (ActionType::Fish, ActionParameters::Fish) => execute_fish(agent, ctx),
         (ActionType::Smelt, ActionParameters::Smelt) => execute_smelt(agent, ctx),
         (ActionType::Write, ActionParameters::Write { knowledge }) => {
             execute_write(agent, knowledge, ctx)
@@ -2159,11 +4014,12 @@ pub fn execute_action(
         (ActionType::Read, ActionParameters::Read { knowledge }) => {
             execute_read(agent, knowledge, ctx)
         }
+        (ActionType::Chart, ActionParameters::Chart) => execute_chart(agent, ctx),
         (ActionType::NoAction, ActionParameters::NoAction) => Ok(execute_no_action(agent)),
         _ => {
-            // Remaining action types (e.g. TradeAccept, TradeReject, FormGroup,
-            // Steal, Attack, Propose, Vote, Marry, Divorce, Conspire, Pray)
-            // are handled externally by the tick cycle or are not yet wired.
+            // Remaining action types (e.g. TradeAccept, TradeReject, PostOrder,
+            // FormGroup, Steal, Attack, Propose, Vote, Marry, Divorce, Conspire,
+            // Pray) are handled externally by the tick cycle or are not yet wired.
             // Freeform actions are routed through the feasibility evaluator
             // in emergence-core before reaching execution.
             Ok(execute_no_action(agent))
@@ -2217,12 +4073,24 @@ mod tests {
             agent_name: String::from("TestAgent"),
             structures_at_location: BTreeMap::new(),
             route_to_improve: None,
+            route_to_build: None,
             move_toll_cost: None,
             dead_agents: BTreeSet::new(),
             agent_groups: BTreeSet::new(),
             active_rules: BTreeMap::new(),
             farm_registry: farming::FarmRegistry::new(),
             library_knowledge: BTreeMap::new(),
+            game_at_location: 0,
+            hidden_resources: Vec::new(),
+            prospect_roll: 0,
+            ore_body: None,
+            collapse_roll: 0,
+            fish_nearby: 0,
+            fire_intensity: 0,
+            fishing_yield_pct: 100,
+            farm_yield_pct: 100,
+            water_nearby: false,
+            irrigated_yield_pct: farming::DEFAULT_IRRIGATION_YIELD_PCT,
         }
     }
 
@@ -2327,6 +4195,21 @@ mod tests {
         assert_eq!(agent.energy, 50);
     }
 
+    #[test]
+    fn resting_weather_watchers_learn_to_forecast() {
+        let mut agent = make_agent(20);
+        let config = VitalsConfig::default();
+        let ctx = make_exec_ctx();
+
+        let result = execute_rest(&mut agent, &config, &ctx).unwrap();
+        assert!(result.outcome.skill_xp.is_empty());
+
+        agent.knowledge.insert(String::from("weather_lore"));
+        let result = execute_rest(&mut agent, &config, &ctx).unwrap();
+        assert_eq!(result.outcome.skill_xp.get("forecasting"), Some(&skills::XP_FORECAST));
+        assert_eq!(agent.skill_xp.get("forecasting"), Some(&skills::XP_FORECAST));
+    }
+
     #[test]
     fn rest_with_shelter_bonus() {
         let mut agent = make_agent(20);
@@ -2855,6 +4738,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         }
     }
 
@@ -3028,6 +4912,41 @@ mod tests {
         assert_eq!(hr.structure_demolished, Some(hut_id));
     }
 
+    #[test]
+    fn upgrade_hut_deducts_the_difference_in_materials() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::Wood, 30);
+        agent.inventory.insert(Resource::Stone, 20);
+        let config = VitalsConfig::default();
+
+        let hut = make_test_structure(
+            StructureType::BasicHut,
+            agent.location_id,
+            Some(agent.agent_id),
+        );
+        let hut_id = hut.id;
+        let mut ctx = make_exec_ctx();
+        ctx.structures_at_location.insert(hut_id, hut);
+
+        let result = execute_action(
+            ActionType::UpgradeStructure,
+            &ActionParameters::UpgradeStructure {
+                structure_id: hut_id,
+            },
+            &mut agent,
+            &config,
+            &mut ctx,
+        );
+        let hr = result.unwrap();
+
+        // A house costs 40 wood and 25 stone; the hut already holds 20 and 10.
+        assert_eq!(agent.inventory.get(&Resource::Wood).copied(), Some(10));
+        assert_eq!(agent.inventory.get(&Resource::Stone).copied(), Some(5));
+        assert_eq!(hr.outcome.energy_spent, 25);
+        assert_eq!(hr.structure_upgraded, Some(hut_id));
+        assert_eq!(hr.outcome.details.get("to"), Some(&serde_json::json!("House")));
+    }
+
     #[test]
     fn dispatch_build_via_execute_action() {
         let mut agent = make_agent(80);
@@ -3303,6 +5222,68 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn transfer_gives_an_owned_structure_away() {
+        let mut agent = make_agent(80);
+        let friend = AgentId::new();
+        let structure = make_test_structure(
+            StructureType::BasicHut,
+            agent.location_id,
+            Some(agent.agent_id),
+        );
+        let sid = structure.id;
+        let mut ctx = make_exec_ctx();
+        ctx.structures_at_location.insert(sid, structure);
+
+        let hr = execute_transfer_ownership(&mut agent, sid, friend, &ctx).unwrap();
+        assert_eq!(hr.structure_transferred, Some((sid, friend)));
+        assert_eq!(hr.outcome.energy_spent, 2);
+
+        // Someone else's structure is not the agent's to give.
+        let other = make_test_structure(
+            StructureType::Campfire,
+            agent.location_id,
+            Some(friend),
+        );
+        let other_id = other.id;
+        ctx.structures_at_location.insert(other_id, other);
+        assert!(execute_transfer_ownership(&mut agent, other_id, friend, &ctx).is_err());
+    }
+
+    #[test]
+    fn deposit_and_withdraw_move_resources_through_a_store() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::FoodFish, 6);
+        let mut granary = make_test_structure(StructureType::Granary, agent.location_id, None);
+        granary.inventory.insert(Resource::FoodFish, 3);
+        let sid = granary.id;
+        let mut ctx = make_exec_ctx();
+        ctx.structures_at_location.insert(sid, granary);
+
+        let hr = execute_deposit(&mut agent, sid, Resource::FoodFish, 4, &ctx).unwrap();
+        assert_eq!(hr.storage_change, Some((sid, Resource::FoodFish, 4)));
+        assert_eq!(agent.inventory.get(&Resource::FoodFish), Some(&2));
+
+        let hr = execute_withdraw(&mut agent, sid, Resource::FoodFish, 3, &ctx).unwrap();
+        assert_eq!(hr.storage_change, Some((sid, Resource::FoodFish, -3)));
+        assert_eq!(agent.inventory.get(&Resource::FoodFish), Some(&5));
+
+        // The context still shows 3 stored, so 4 cannot be taken.
+        assert!(execute_withdraw(&mut agent, sid, Resource::FoodFish, 4, &ctx).is_err());
+    }
+
+    #[test]
+    fn breach_reports_damage_against_the_destination() {
+        let mut agent = make_agent(80);
+        let dest = LocationId::new();
+        let ctx = make_exec_ctx();
+
+        let hr = execute_breach(&mut agent, dest, &ctx);
+        assert_eq!(hr.breach, Some((dest, costs::BREACH_DAMAGE)));
+        assert_eq!(agent.energy, 55);
+        assert_eq!(hr.outcome.energy_spent, costs::energy_cost(ActionType::Breach));
+    }
+
     // -----------------------------------------------------------------------
     // Governance: Legislate (Phase 4.4.2)
     // -----------------------------------------------------------------------
@@ -3464,6 +5445,64 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // BuildRoute
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn build_route_pays_only_to_start_the_work() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::Wood, 30);
+        agent.inventory.insert(Resource::Stone, 10);
+        let destination = LocationId::new();
+        let length = 3;
+        let mut ctx = make_exec_ctx();
+        ctx.route_to_build = Some(RoutePlan {
+            length,
+            cost: emergence_world::route_building::material_cost(length),
+        });
+
+        let hr = execute_build_route(&mut agent, destination, &ctx).unwrap();
+        assert_eq!(hr.route_work, Some(destination));
+        assert_eq!(agent.inventory.get(&Resource::Wood).copied(), Some(15));
+        assert_eq!(agent.inventory.get(&Resource::Stone).copied(), Some(4));
+        assert_eq!(agent.skill_xp.get("building").copied(), Some(skills::XP_BUILD));
+
+        // Carrying on with the work costs only energy.
+        ctx.route_to_build = Some(RoutePlan { length, cost: BTreeMap::new() });
+        let hr = execute_build_route(&mut agent, destination, &ctx).unwrap();
+        assert!(hr.outcome.resource_changes.is_empty());
+        assert_eq!(agent.inventory.get(&Resource::Wood).copied(), Some(15));
+
+        ctx.route_to_build = None;
+        assert!(execute_build_route(&mut agent, destination, &ctx).is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // FoundSettlement
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn found_settlement_names_the_new_location() {
+        let mut agent = make_agent(80);
+        let config = VitalsConfig::default();
+        let mut ctx = make_exec_ctx();
+
+        let result = execute_action(
+            ActionType::FoundSettlement,
+            &ActionParameters::FoundSettlement {
+                name: String::from("Newhome"),
+            },
+            &mut agent,
+            &config,
+            &mut ctx,
+        );
+        let hr = result.unwrap();
+        assert_eq!(hr.settlement_founded.as_deref(), Some("Newhome"));
+        let cost = costs::energy_cost(ActionType::FoundSettlement);
+        assert_eq!(agent.energy, 80u32.saturating_sub(cost));
+    }
+
     // -----------------------------------------------------------------------
     // Governance dispatch via execute_action (Phase 4.4.4)
     // -----------------------------------------------------------------------
@@ -3697,6 +5736,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn farm_harvest_is_boosted_by_water_or_a_channel() {
+        let mut agent = make_agent(80);
+        let location = agent.location_id;
+        let mut ctx = make_exec_ctx();
+        ctx.current_tick = 20;
+        let farm = make_test_structure(StructureType::FarmPlot, location, None);
+        let farm_id = farm.id;
+        ctx.structures_at_location.insert(farm_id, farm);
+
+        // Beside a river: 5 * 150% = 7, drawing no water from the location
+        ctx.water_nearby = true;
+        ctx.farm_registry.plant(farm_id, 5, 10);
+        let hr = execute_farm_harvest(&mut agent, &mut ctx).unwrap();
+        assert_eq!(agent.inventory.get(&Resource::FoodFarmed).copied(), Some(7));
+        assert!(hr.location_resource_deltas.is_empty());
+
+        // A channel with no water to draw on waters nothing. Each harvest
+        // comes after a fallow spell long enough for the soil to recover.
+        ctx.current_tick = 30;
+        ctx.water_nearby = false;
+        ctx.location_resources.remove(&Resource::Water);
+        let channel = make_test_structure(StructureType::Irrigation, location, None);
+        ctx.structures_at_location.insert(channel.id, channel);
+        ctx.farm_registry.plant(farm_id, 5, 10);
+        execute_farm_harvest(&mut agent, &mut ctx).unwrap();
+        assert_eq!(agent.inventory.get(&Resource::FoodFarmed).copied(), Some(12));
+
+        // Fed from the location's water, the channel waters the plot
+        ctx.current_tick = 40;
+        ctx.location_resources.insert(Resource::Water, 10);
+        ctx.farm_registry.plant(farm_id, 5, 10);
+        let hr = execute_farm_harvest(&mut agent, &mut ctx).unwrap();
+        assert_eq!(agent.inventory.get(&Resource::FoodFarmed).copied(), Some(19));
+        assert_eq!(
+            hr.location_resource_deltas.get(&Resource::Water).copied(),
+            Some(farming::IRRIGATION_WATER_PER_HARVEST)
+        );
+    }
+
+    #[test]
+    fn polluted_land_shrinks_the_harvest() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("farming"), 6);
+        let location = agent.location_id;
+        let mut ctx = make_exec_ctx();
+        ctx.current_tick = 20;
+        ctx.farm_yield_pct = emergence_world::pollution::farm_yield_pct(100);
+
+        let farm = make_test_structure(StructureType::FarmPlot, location, Some(agent.agent_id));
+        ctx.farm_registry.plant(farm.id, 5, 10);
+        ctx.structures_at_location.insert(farm.id, farm);
+
+        assert!(execute_farm_harvest(&mut agent, &mut ctx).is_ok());
+        // Half of 8.
+        assert_eq!(agent.inventory.get(&Resource::FoodFarmed).copied(), Some(4));
+    }
+
+    #[test]
+    fn repeated_harvests_wear_out_the_soil_and_leave_waste() {
+        let mut agent = make_agent(80);
+        let location = agent.location_id;
+        let mut ctx = make_exec_ctx();
+        ctx.current_tick = 20;
+        let farm = make_test_structure(StructureType::FarmPlot, location, None);
+        let farm_id = farm.id;
+        ctx.structures_at_location.insert(farm_id, farm);
+
+        // Sown from berries twice in a row: 10 then 20 fertility drained
+        for _ in 0..2 {
+            agent.inventory.insert(Resource::FoodBerry, 1);
+            execute_farm_plant(&mut agent, &mut ctx).unwrap();
+            ctx.current_tick = ctx.current_tick.saturating_add(farming::DEFAULT_GROWTH_TICKS);
+            execute_farm_harvest(&mut agent, &mut ctx).unwrap();
+        }
+        assert_eq!(ctx.farm_registry.fertility_at(farm_id, ctx.current_tick), 70);
+        // 5 at full fertility, then 5 * 95% = 4
+        assert_eq!(agent.inventory.get(&Resource::FoodFarmed).copied(), Some(9));
+        assert_eq!(
+            agent.inventory.get(&Resource::FoodWaste).copied(),
+            Some(farming::HARVEST_WASTE.saturating_mul(2))
+        );
+
+        let hr = execute_fertilize(&mut agent, &mut ctx).unwrap();
+        assert_eq!(ctx.farm_registry.fertility_at(farm_id, ctx.current_tick), 100);
+        assert_eq!(agent.inventory.get(&Resource::FoodWaste).copied(), Some(1));
+        assert_eq!(hr.outcome.energy_spent, 10);
+
+        // Too little waste left for another round
+        assert!(execute_fertilize(&mut agent, &mut ctx).is_err());
+    }
+
     #[test]
     fn farm_harvest_immature_crops_fails() {
         let mut agent = make_agent(80);
@@ -3874,6 +6005,217 @@ mod tests {
         assert_eq!(agent.inventory.get(&Resource::Ore).copied().unwrap_or(0), 0);
     }
 
+    #[test]
+    fn mine_is_capped_by_the_face_and_deep_shafts_cave_in() {
+        let mut agent = make_agent(80);
+        let mut ctx = make_exec_ctx();
+        ctx.location_resources.insert(Resource::Ore, 20);
+        let mut body = OreBody::new(100);
+        body.face = 1;
+        ctx.ore_body = Some(body);
+
+        let hr = execute_mine(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.mine_work, Some(MineWork::Mined(1)));
+        assert_eq!(ctx.ore_body.map(|b| b.face), Some(0));
+
+        // Two levels down, a roll under the 10% risk brings the roof in.
+        body.depth = 2;
+        body.face = 20;
+        ctx.ore_body = Some(body);
+        ctx.collapse_roll = 9;
+        let hr = execute_mine(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.mine_work, Some(MineWork::CavedIn));
+        assert_eq!(agent.health, 100_u32.saturating_sub(mining::CAVE_IN_DAMAGE));
+        assert_eq!(agent.inventory.get(&Resource::Ore).copied(), Some(1));
+        assert_eq!(ctx.ore_body.map(|b| b.face), Some(0));
+    }
+
+    #[test]
+    fn deepen_mine_consumes_wood_and_tools_and_opens_a_face() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::Wood, 20);
+        agent.inventory.insert(Resource::Tool, 2);
+        let mut ctx = make_exec_ctx();
+        let mut body = OreBody::new(100);
+        body.extract(mining::FACE_CAPACITY);
+        ctx.ore_body = Some(body);
+
+        let hr = execute_deepen_mine(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.mine_work, Some(MineWork::Deepened));
+        assert_eq!(ctx.ore_body.map(|b| (b.depth, b.face)), Some((1, mining::FACE_CAPACITY)));
+        assert_eq!(agent.inventory.get(&Resource::Wood).copied(), Some(10));
+        assert_eq!(agent.inventory.get(&Resource::Tool).copied(), Some(1));
+        assert_eq!(hr.outcome.energy_spent, 30);
+
+        // Nothing left behind the face: there is nothing to deepen towards.
+        ctx.ore_body = Some(OreBody::new(10));
+        assert!(execute_deepen_mine(&mut agent, &mut ctx).is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // Extinguish handler
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn extinguish_pours_water_on_the_fire() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::Water, 3);
+        let mut ctx = make_exec_ctx();
+        ctx.fire_intensity = 3;
+
+        let hr = execute_extinguish(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.fire_suppressed, Some(fire::SUPPRESSION_PER_ACTION));
+        assert_eq!(ctx.fire_intensity, 1);
+        assert_eq!(agent.inventory.get(&Resource::Water).copied(), Some(1));
+        assert_eq!(hr.outcome.energy_spent, 15);
+
+        // Without enough water there is nothing to pour.
+        assert!(execute_extinguish(&mut agent, &mut ctx).is_err());
+        assert_eq!(ctx.fire_intensity, 1);
+    }
+
+    // -----------------------------------------------------------------------
+    // Hunt handler
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn hunt_yields_meat_and_hide() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("hunting"), 4);
+        let mut ctx = make_exec_ctx();
+        ctx.game_at_location = 10;
+
+        let hr = execute_hunt(&mut agent, &mut ctx).unwrap();
+
+        // Yield: 1 + 4/4 = 2 animals
+        assert_eq!(hr.animals_hunted, Some(2));
+        assert_eq!(ctx.game_at_location, 8);
+        assert_eq!(agent.inventory.get(&Resource::FoodMeat).copied(), Some(6));
+        assert_eq!(agent.inventory.get(&Resource::Hide).copied(), Some(2));
+        assert_eq!(hr.outcome.energy_spent, 20);
+        assert_eq!(
+            hr.outcome.skill_xp.get("hunting").copied(),
+            Some(skills::XP_HUNT)
+        );
+    }
+
+    #[test]
+    fn hunt_caps_at_game_available() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("hunting"), 8);
+        let mut ctx = make_exec_ctx();
+        ctx.game_at_location = 1;
+
+        let hr = execute_hunt(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.animals_hunted, Some(1));
+        assert_eq!(agent.inventory.get(&Resource::FoodMeat).copied(), Some(3));
+    }
+
+    #[test]
+    fn hunting_by_a_pasture_corrals_one_animal_for_slaughter() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("hunting"), 4);
+        let location = agent.location_id;
+        let mut ctx = make_exec_ctx();
+        ctx.game_at_location = 10;
+        let pasture = make_test_structure(StructureType::Pasture, location, None);
+        ctx.structures_at_location.insert(pasture.id, pasture);
+
+        // 2 animals: one butchered, one kept alive
+        let hr = execute_hunt(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.animals_hunted, Some(2));
+        assert_eq!(agent.inventory.get(&Resource::FoodMeat).copied(), Some(3));
+        assert_eq!(agent.inventory.get(&Resource::Livestock).copied(), Some(1));
+
+        let hr = execute_slaughter(&mut agent, &ctx).unwrap();
+        assert_eq!(hr.outcome.energy_spent, 10);
+        assert_eq!(agent.inventory.get(&Resource::Livestock).copied().unwrap_or(0), 0);
+        assert_eq!(agent.inventory.get(&Resource::FoodMeat).copied(), Some(6));
+        assert_eq!(agent.inventory.get(&Resource::Hide).copied(), Some(2));
+
+        // Nothing left to slaughter
+        assert!(execute_slaughter(&mut agent, &ctx).is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // Prospect handler
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn prospect_reveals_first_hidden_deposit_on_a_good_roll() {
+        let mut agent = make_agent(80);
+        agent.knowledge.insert(String::from("mining"));
+        let mut ctx = make_exec_ctx();
+        ctx.hidden_resources = vec![Resource::Clay, Resource::Ore];
+        // Chance is 15 + 20 (mining) = 35
+        ctx.prospect_roll = 34;
+
+        let hr = execute_prospect(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.resource_discovered, Some(Resource::Clay));
+        assert_eq!(ctx.hidden_resources, vec![Resource::Ore]);
+        assert_eq!(hr.outcome.energy_spent, 15);
+        assert_eq!(
+            hr.outcome.skill_xp.get("prospecting").copied(),
+            Some(skills::XP_PROSPECT)
+        );
+    }
+
+    #[test]
+    fn prospect_finds_nothing_on_a_bad_roll_or_barren_ground() {
+        let mut agent = make_agent(80);
+        let mut ctx = make_exec_ctx();
+        ctx.hidden_resources = vec![Resource::Ore];
+        ctx.prospect_roll = 15;
+
+        let hr = execute_prospect(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.resource_discovered, None);
+
+        ctx.hidden_resources.clear();
+        ctx.prospect_roll = 0;
+        let hr = execute_prospect(&mut agent, &mut ctx).unwrap();
+        assert_eq!(hr.resource_discovered, None);
+        assert_eq!(agent.skill_xp.get("prospecting").copied(), Some(20));
+    }
+
+    // -----------------------------------------------------------------------
+    // Fish handler
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn fish_catches_food_fish() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("fishing"), 3);
+        let mut ctx = make_exec_ctx();
+        ctx.fish_nearby = 20;
+
+        let hr = execute_fish(&mut agent, &mut ctx).unwrap();
+
+        // Yield: 2 + 3/3 = 3 fish
+        assert_eq!(hr.fish_caught, Some(3));
+        assert_eq!(ctx.fish_nearby, 17);
+        assert_eq!(agent.inventory.get(&Resource::FoodFish).copied(), Some(3));
+        assert_eq!(hr.outcome.energy_spent, 15);
+        assert_eq!(
+            hr.outcome.skill_xp.get("fishing").copied(),
+            Some(skills::XP_FISH)
+        );
+    }
+
+    #[test]
+    fn drought_halves_the_catch() {
+        let mut agent = make_agent(80);
+        agent.skills.insert(String::from("fishing"), 6);
+        let mut ctx = make_exec_ctx();
+        ctx.fish_nearby = 20;
+        ctx.fishing_yield_pct = waters::yield_pct(emergence_types::Weather::Drought);
+
+        let hr = execute_fish(&mut agent, &mut ctx).unwrap();
+
+        // Yield: (2 + 6/3) * 50% = 2 fish
+        assert_eq!(hr.fish_caught, Some(2));
+        assert_eq!(agent.inventory.get(&Resource::FoodFish).copied(), Some(2));
+    }
+
     // -----------------------------------------------------------------------
     // Smelt handler (Phase 4.2)
     // -----------------------------------------------------------------------
@@ -3922,6 +6264,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // Chart handler
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn chart_draws_a_map_on_a_hide() {
+        let mut agent = make_agent(80);
+        agent.inventory.insert(Resource::Hide, 2);
+
+        let ctx = make_exec_ctx();
+        let hr = execute_chart(&mut agent, &ctx).unwrap();
+        assert!(hr.map_charted);
+        assert_eq!(agent.inventory.get(&Resource::Hide).copied(), Some(1));
+        assert_eq!(agent.inventory.get(&Resource::Map).copied(), Some(1));
+        assert_eq!(hr.outcome.energy_spent, costs::energy_cost(ActionType::Chart));
+
+        agent.inventory.remove(&Resource::Hide);
+        assert!(execute_chart(&mut agent, &ctx).is_err());
+    }
+
     // -----------------------------------------------------------------------
     // Write handler (Phase 4.2)
     // -----------------------------------------------------------------------
@@ -10,6 +10,8 @@
 //! 7. Conflict -- reserved for the conflict resolution pass.
 //!
 //! Each stage returns `Ok(())` on success or a [`RejectionReason`] on failure.
+//! Every validated action and every rejection is counted in
+//! [`crate::metrics`].
 
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -19,8 +21,13 @@ use emergence_types::{
 };
 
 use emergence_world::farming;
+use emergence_world::fire;
+use emergence_world::mining::{self, OreBody};
+use emergence_world::route_building::RoutePlan;
+use emergence_world::structure as world_structure;
 
 use crate::crafting;
+use crate::metrics;
 use crate::reproduction;
 
 use super::costs;
@@ -62,9 +69,23 @@ pub struct ValidationContext {
     pub route_to_improve: Option<Route>,
     /// The route being used for a `Move` action, if any.
     ///
-    /// Populated by the caller from the world map when the action is `Move`.
-    /// Used for ACL checks (access control and toll costs) during validation.
+    /// Populated by the caller from the world map when the action is `Move`
+    /// or `Breach`. Used for ACL checks (access control and toll costs)
+    /// during validation.
     pub move_route: Option<Route>,
+    /// Fortifications standing at the destination of a `Move` or `Breach`
+    /// action.
+    ///
+    /// Populated by the caller from the world state. Used to refuse entry
+    /// to agents the fortifications' access lists bar, and to find what a
+    /// breach would strike.
+    pub destination_fortifications: Vec<Structure>,
+    /// What building the route of a `BuildRoute` action would take, if any.
+    ///
+    /// Populated by the caller from the world map when the action is
+    /// `BuildRoute` and the agent knows the destination. `None` means no
+    /// route can be built there.
+    pub route_to_build: Option<RoutePlan>,
     /// The agent's group memberships, used for ACL group-based access checks.
     ///
     /// Populated by the caller from the agent's social graph. An empty list
@@ -86,6 +107,33 @@ pub struct ValidationContext {
     /// Populated by the tick cycle from library state in Dragonfly.
     /// Used by `Read` validation to check if the requested concept exists.
     pub library_knowledge: BTreeMap<StructureId, BTreeSet<String>>,
+    /// Game animals available to hunt at the agent's location.
+    ///
+    /// Populated by the tick cycle from the fauna registry. Used by `Hunt`
+    /// validation to check that there is anything left to hunt.
+    pub game_at_location: u32,
+    /// The ore body beneath the agent's location, if one is tracked.
+    ///
+    /// Populated by the tick cycle from the mine registry. Used by `Mine`
+    /// validation to check the face is not worked out, and by `DeepenMine`
+    /// validation to check there is ore left to reach and to price the
+    /// next level.
+    pub ore_body: Option<OreBody>,
+    /// Fish in the water at or next to the agent's location.
+    ///
+    /// Populated by the tick cycle from the water registry. Used by `Fish`
+    /// validation to check that the agent is near water with fish in it.
+    pub fish_nearby: u32,
+    /// Intensity of the fire burning at the agent's location, zero if none.
+    ///
+    /// Populated by the tick cycle from the fire system. Used by
+    /// `Extinguish` validation to check there is a fire to put out.
+    pub fire_intensity: u32,
+    /// The category of the agent's location (natural, settlement, etc.).
+    ///
+    /// Populated by the tick cycle from the world map. Used by
+    /// `FoundSettlement` validation to check the location is wilderness.
+    pub location_type: String,
     /// The current tick number.
     ///
     /// Needed by farm harvest validation to check crop maturity.
@@ -104,6 +152,21 @@ pub fn validate_action(
     params: &ActionParameters,
     agent_state: &AgentState,
     context: &ValidationContext,
+) -> Result<(), RejectionReason> {
+    metrics::ACTIONS.increment_with(&format!("{action_type:?}"), 1);
+    let result = run_pipeline(action_type, params, agent_state, context);
+    if let Err(reason) = result {
+        metrics::ACTION_REJECTIONS.increment_with(&format!("{reason:?}"), 1);
+    }
+    result
+}
+
+/// Stages 1--6 of [`validate_action`], without metrics.
+fn run_pipeline(
+    action_type: ActionType,
+    params: &ActionParameters,
+    agent_state: &AgentState,
+    context: &ValidationContext,
 ) -> Result<(), RejectionReason> {
     // Stage 1: Syntax check
     validate_syntax(action_type, params)?;
@@ -155,29 +218,45 @@ const fn validate_syntax(
             | (ActionType::Move, ActionParameters::Move { .. })
             | (ActionType::Build, ActionParameters::Build { .. })
             | (ActionType::Repair, ActionParameters::Repair { .. })
+            | (ActionType::Extinguish, ActionParameters::Extinguish)
             | (ActionType::Demolish, ActionParameters::Demolish { .. })
+            | (ActionType::UpgradeStructure, ActionParameters::UpgradeStructure { .. })
             | (ActionType::ImproveRoute, ActionParameters::ImproveRoute { .. })
+            | (ActionType::BuildRoute, ActionParameters::BuildRoute { .. })
             | (ActionType::Communicate, ActionParameters::Communicate { .. })
             | (ActionType::Broadcast, ActionParameters::Broadcast { .. })
             | (ActionType::TradeOffer, ActionParameters::TradeOffer { .. })
             | (ActionType::TradeAccept, ActionParameters::TradeAccept { .. })
             | (ActionType::TradeReject, ActionParameters::TradeReject { .. })
+            | (ActionType::PostOrder, ActionParameters::PostOrder { .. })
             | (ActionType::FormGroup, ActionParameters::FormGroup { .. })
             | (ActionType::Teach, ActionParameters::Teach { .. })
             | (ActionType::FarmPlant, ActionParameters::FarmPlant)
             | (ActionType::FarmHarvest, ActionParameters::FarmHarvest)
+            | (ActionType::Fertilize, ActionParameters::Fertilize)
+            | (ActionType::Slaughter, ActionParameters::Slaughter)
             | (ActionType::Craft, ActionParameters::Craft { .. })
             | (ActionType::Mine, ActionParameters::Mine)
+            | (ActionType::DeepenMine, ActionParameters::DeepenMine)
+            | (ActionType::Hunt, ActionParameters::Hunt)
+            | (ActionType::Prospect, ActionParameters::Prospect)
+            | (ActionType::Fish, ActionParameters::Fish)
             | (ActionType::Smelt, ActionParameters::Smelt)
             | (ActionType::Write, ActionParameters::Write { .. })
             | (ActionType::Read, ActionParameters::Read { .. })
+            | (ActionType::Chart, ActionParameters::Chart)
+            | (ActionType::Deposit, ActionParameters::Deposit { .. })
+            | (ActionType::Withdraw, ActionParameters::Withdraw { .. })
             | (ActionType::Claim, ActionParameters::Claim { .. })
+            | (ActionType::TransferOwnership, ActionParameters::TransferOwnership { .. })
+            | (ActionType::FoundSettlement, ActionParameters::FoundSettlement { .. })
             | (ActionType::Legislate, ActionParameters::Legislate { .. })
             | (ActionType::Enforce, ActionParameters::Enforce { .. })
             | (ActionType::Reproduce, ActionParameters::Reproduce { .. })
             | (ActionType::Steal, ActionParameters::Steal { .. })
             | (ActionType::Attack, ActionParameters::Attack { .. })
             | (ActionType::Intimidate, ActionParameters::Intimidate { .. })
+            | (ActionType::Breach, ActionParameters::Breach { .. })
             | (ActionType::Propose, ActionParameters::Propose { .. })
             | (ActionType::Vote, ActionParameters::Vote { .. })
             | (ActionType::Marry, ActionParameters::Marry { .. })
@@ -225,9 +304,12 @@ const fn validate_maturity(
 /// For move: a route must exist (checked later in world state).
 /// For eat/drink: the resource must be in inventory or at location.
 /// For communicate: target agent must be at the same location.
+/// For build route: a new route to the destination must be possible.
 /// For claim: structure must exist at location with no living owner.
 /// For legislate: agent must be in group, `MeetingHall` at location.
 /// For enforce: target agent must be at the same location.
+/// For found settlement: the location must be wilderness, with enough
+/// founders present.
 #[allow(clippy::too_many_lines)]
 fn validate_location(
     action_type: ActionType,
@@ -255,6 +337,27 @@ fn validate_location(
             {
                 return Err(RejectionReason::PermissionDenied);
             }
+            // Fortifications at the destination must admit the agent.
+            if !world_structure::admits(
+                &context.destination_fortifications,
+                context.agent_id,
+                &context.agent_groups,
+            ) {
+                return Err(RejectionReason::PermissionDenied);
+            }
+        }
+        (ActionType::Breach, ActionParameters::Breach { .. }) => {
+            // A route must lead to the destination, and a fortification
+            // there must bar the agent.
+            let barred = world_structure::breach_target(
+                &context.destination_fortifications,
+                context.agent_id,
+                &context.agent_groups,
+            )
+            .is_some();
+            if context.move_route.is_none() || !barred {
+                return Err(RejectionReason::InvalidTarget);
+            }
         }
         (ActionType::Communicate, ActionParameters::Communicate { target_agent, message }) => {
             // Target agent must be present at the same location
@@ -282,6 +385,16 @@ fn validate_location(
                 return Err(RejectionReason::InvalidTarget);
             }
         }
+        (ActionType::PostOrder, ActionParameters::PostOrder { market_id, .. }) => {
+            // Market must be at the agent's location
+            let is_market = context
+                .structures_at_location
+                .get(market_id)
+                .is_some_and(|s| s.structure_type == StructureType::Market);
+            if !is_market {
+                return Err(RejectionReason::InvalidTarget);
+            }
+        }
         (ActionType::Reproduce, ActionParameters::Reproduce { partner_agent }) => {
             // Partner agent must be at the same location
             if !context.agents_at_location.contains(partner_agent) {
@@ -309,6 +422,27 @@ fn validate_location(
                 }
             }
         }
+        (ActionType::Build, ActionParameters::Build { structure_type })
+            if emergence_world::structure::previous_tier(*structure_type).is_some() =>
+        {
+            // Upgraded tiers can only be reached by upgrading the tier below
+            return Err(RejectionReason::UnavailableTarget);
+        }
+        (ActionType::UpgradeStructure, ActionParameters::UpgradeStructure { structure_id }) => {
+            // Structure must exist at the agent's location
+            let Some(s) = context.structures_at_location.get(structure_id) else {
+                return Err(RejectionReason::InvalidTarget);
+            };
+            // Agent must own the structure or structure must be unowned
+            let is_owner = s.owner.is_some_and(|owner| owner == context.agent_id);
+            if !is_owner && s.owner.is_some() {
+                return Err(RejectionReason::PermissionDenied);
+            }
+            // The structure must have a tier above it
+            if emergence_world::next_tier(s.structure_type).is_none() {
+                return Err(RejectionReason::UnavailableTarget);
+            }
+        }
         (ActionType::ImproveRoute, ActionParameters::ImproveRoute { .. }) => {
             // A route must exist and the agent must be at one of its endpoints.
             // The caller provides the resolved route in the context.
@@ -322,6 +456,12 @@ fn validate_location(
                 }
             }
         }
+        (ActionType::BuildRoute, ActionParameters::BuildRoute { .. })
+            if context.route_to_build.is_none() =>
+        {
+            // The destination must be known, and not already joined to here
+            return Err(RejectionReason::InvalidTarget);
+        }
         (ActionType::Claim, ActionParameters::Claim { structure_id }) => {
             // Structure must exist at the agent's location
             let structure = context.structures_at_location.get(structure_id);
@@ -337,6 +477,72 @@ fn validate_location(
                 }
             }
         }
+        (
+            ActionType::TransferOwnership,
+            ActionParameters::TransferOwnership {
+                structure_id,
+                new_owner,
+            },
+        ) => {
+            // Structure must exist at the agent's location
+            let Some(s) = context.structures_at_location.get(structure_id) else {
+                return Err(RejectionReason::InvalidTarget);
+            };
+            // Only the owner can give a structure away
+            if s.owner != Some(context.agent_id) {
+                return Err(RejectionReason::PermissionDenied);
+            }
+            // The new owner must be someone else at the same location
+            if *new_owner == context.agent_id || !context.agents_at_location.contains(new_owner) {
+                return Err(RejectionReason::InvalidTarget);
+            }
+        }
+        (
+            ActionType::Deposit | ActionType::Withdraw,
+            ActionParameters::Deposit {
+                structure_id,
+                resource,
+                ..
+            }
+            | ActionParameters::Withdraw {
+                structure_id,
+                resource,
+                ..
+            },
+        ) => {
+            // Structure must be shared storage at the agent's location
+            let Some(s) = context.structures_at_location.get(structure_id) else {
+                return Err(RejectionReason::InvalidTarget);
+            };
+            if !world_structure::is_shared_storage(s.structure_type)
+                || !world_structure::stores(s.structure_type, *resource)
+            {
+                return Err(RejectionReason::InvalidTarget);
+            }
+            // The structure's access list must admit the agent
+            if !world_structure::can_access(s, context.agent_id, &context.agent_groups) {
+                return Err(RejectionReason::PermissionDenied);
+            }
+        }
+        (ActionType::FoundSettlement, ActionParameters::FoundSettlement { name }) => {
+            // Name must not be empty
+            if name.trim().is_empty() {
+                return Err(RejectionReason::InvalidAction);
+            }
+            // Only wilderness can be settled
+            if !emergence_world::settlement::is_wilderness(&context.location_type) {
+                return Err(RejectionReason::WrongLocation);
+            }
+            // Enough founders, the agent included, must be present
+            let others = context
+                .agents_at_location
+                .iter()
+                .filter(|a| **a != context.agent_id)
+                .count();
+            if others.saturating_add(1) < emergence_world::settlement::MIN_FOUNDERS {
+                return Err(RejectionReason::InvalidTarget);
+            }
+        }
         (ActionType::Legislate, ActionParameters::Legislate { group_id, .. }) => {
             // Agent must be a member of the group
             if !context.agent_groups.contains(group_id) {
@@ -381,6 +587,17 @@ fn validate_location(
                 return Err(RejectionReason::WrongLocation);
             }
         }
+        (ActionType::Fertilize, ActionParameters::Fertilize) => {
+            // A standing FarmPlot must exist at the location
+            let has_plot = context.structures_at_location.values().any(|s| {
+                s.structure_type == StructureType::FarmPlot
+                    && s.durability > 0
+                    && s.destroyed_at_tick.is_none()
+            });
+            if !has_plot {
+                return Err(RejectionReason::WrongLocation);
+            }
+        }
         (ActionType::Craft, ActionParameters::Craft { .. }) => {
             // A Workshop must exist at the location
             let has_workshop = context
@@ -401,6 +618,15 @@ fn validate_location(
                 return Err(RejectionReason::WrongLocation);
             }
         }
+        (ActionType::DeepenMine, ActionParameters::DeepenMine) => {
+            // The location must have an ore body with ore left behind the face
+            let Some(body) = &context.ore_body else {
+                return Err(RejectionReason::WrongLocation);
+            };
+            if !body.can_deepen() {
+                return Err(RejectionReason::UnavailableTarget);
+            }
+        }
         (ActionType::Smelt, ActionParameters::Smelt) => {
             // A Forge must exist at the location
             let has_forge = context
@@ -527,6 +753,17 @@ fn validate_resources(
                 }
             }
         }
+        (ActionType::BuildRoute, ActionParameters::BuildRoute { .. }) => {
+            // Agent must have the materials, unless the work is under way
+            if let Some(plan) = &context.route_to_build {
+                for (resource, &required) in &plan.cost {
+                    let held = agent_state.inventory.get(resource).copied().unwrap_or(0);
+                    if held < required {
+                        return Err(RejectionReason::InsufficientResources);
+                    }
+                }
+            }
+        }
         (ActionType::Build, ActionParameters::Build { structure_type }) => {
             // Agent must have all required materials
             let bp = emergence_world::blueprint(*structure_type);
@@ -559,6 +796,31 @@ fn validate_resources(
                 }
             }
         }
+        (ActionType::Extinguish, ActionParameters::Extinguish) => {
+            // A fire must be burning at the location
+            if context.fire_intensity == 0 {
+                return Err(RejectionReason::UnavailableTarget);
+            }
+            // Agent must have water to pour on it
+            let water = agent_state.inventory.get(&Resource::Water).copied().unwrap_or(0);
+            if water < fire::WATER_PER_SUPPRESSION {
+                return Err(RejectionReason::InsufficientResources);
+            }
+        }
+        (ActionType::UpgradeStructure, ActionParameters::UpgradeStructure { structure_id }) => {
+            // Agent must have the materials the next tier adds
+            if let Some(structure) = context.structures_at_location.get(structure_id)
+                && let Some(costs) =
+                    emergence_world::structure::upgrade_cost(structure.structure_type)
+            {
+                for (resource, &required) in &costs {
+                    let held = agent_state.inventory.get(resource).copied().unwrap_or(0);
+                    if held < required {
+                        return Err(RejectionReason::InsufficientResources);
+                    }
+                }
+            }
+        }
         (ActionType::ImproveRoute, ActionParameters::ImproveRoute { .. }) => {
             // Agent must have the materials needed for the upgrade.
             // If the route is already at max level, reject.
@@ -599,6 +861,38 @@ fn validate_resources(
                 return Err(RejectionReason::InvalidAction);
             }
         }
+        (
+            ActionType::PostOrder,
+            ActionParameters::PostOrder {
+                market_id,
+                side,
+                resource,
+                quantity,
+                price_resource,
+                unit_price,
+            },
+        ) => {
+            // Quantity and price must be positive, in two different resources
+            if *quantity == 0 || *unit_price == 0 || resource == price_resource {
+                return Err(RejectionReason::InvalidAction);
+            }
+            // Agent must hold what the order escrows
+            let terms = crate::market::OrderTerms {
+                market_id: *market_id,
+                side: *side,
+                resource: *resource,
+                quantity: *quantity,
+                price_resource: *price_resource,
+                unit_price: *unit_price,
+            };
+            let Some((escrowed, amount)) = crate::market::order_escrow(&terms) else {
+                return Err(RejectionReason::InvalidAction);
+            };
+            let held = agent_state.inventory.get(&escrowed).copied().unwrap_or(0);
+            if held < amount {
+                return Err(RejectionReason::InsufficientResources);
+            }
+        }
         (ActionType::FarmPlant, ActionParameters::FarmPlant) => {
             // Agent must have at least 1 food item as seed
             let has_seed = [
@@ -615,6 +909,20 @@ fn validate_resources(
                 return Err(RejectionReason::InsufficientResources);
             }
         }
+        (ActionType::Fertilize, ActionParameters::Fertilize) => {
+            // Agent must have the food waste to work into the soil
+            let waste = agent_state.inventory.get(&Resource::FoodWaste).copied().unwrap_or(0);
+            if waste < farming::FERTILIZER_WASTE {
+                return Err(RejectionReason::InsufficientResources);
+            }
+        }
+        (ActionType::Slaughter, ActionParameters::Slaughter) => {
+            // Agent must have a head of livestock to slaughter
+            let head = agent_state.inventory.get(&Resource::Livestock).copied().unwrap_or(0);
+            if head == 0 {
+                return Err(RejectionReason::InsufficientResources);
+            }
+        }
         (ActionType::Craft, ActionParameters::Craft { output }) => {
             // Agent must have all recipe inputs
             if let Some(recipe) = crafting::recipe_for(*output) {
@@ -642,6 +950,10 @@ fn validate_resources(
             } else {
                 return Err(RejectionReason::UnavailableTarget);
             }
+            // The mine face must not be worked out
+            if context.ore_body.is_some_and(|body| body.face == 0) {
+                return Err(RejectionReason::UnavailableTarget);
+            }
             // Agent must have a Tool in inventory
             let has_tool = agent_state
                 .inventory
@@ -653,6 +965,24 @@ fn validate_resources(
                 return Err(RejectionReason::InsufficientResources);
             }
         }
+        (ActionType::DeepenMine, ActionParameters::DeepenMine) => {
+            // Agent must hold the wood and tools for the shaft's next level
+            let depth = context.ore_body.map_or(0, |body| body.depth);
+            for (resource, required) in &mining::deepen_cost(depth) {
+                let held = agent_state.inventory.get(resource).copied().unwrap_or(0);
+                if held < *required {
+                    return Err(RejectionReason::InsufficientResources);
+                }
+            }
+        }
+        (ActionType::Hunt, ActionParameters::Hunt) if context.game_at_location == 0 => {
+            // Game must be left at the location
+            return Err(RejectionReason::UnavailableTarget);
+        }
+        (ActionType::Fish, ActionParameters::Fish) if context.fish_nearby == 0 => {
+            // Agent must be at or next to water with fish in it
+            return Err(RejectionReason::UnavailableTarget);
+        }
         (ActionType::Smelt, ActionParameters::Smelt) => {
             // Agent must have 2 Ore + 1 Wood
             let ore_held = agent_state
@@ -672,6 +1002,67 @@ fn validate_resources(
                 return Err(RejectionReason::InsufficientResources);
             }
         }
+        (ActionType::Chart, ActionParameters::Chart) => {
+            // The map is drawn on a hide
+            let hide_held = agent_state
+                .inventory
+                .get(&Resource::Hide)
+                .copied()
+                .unwrap_or(0);
+            if hide_held < costs::CHART_HIDE_INPUT {
+                return Err(RejectionReason::InsufficientResources);
+            }
+        }
+        (
+            ActionType::Deposit,
+            ActionParameters::Deposit {
+                structure_id,
+                resource,
+                quantity,
+            },
+        ) => {
+            if *quantity == 0 {
+                return Err(RejectionReason::InvalidAction);
+            }
+            // Agent must hold what it deposits
+            let held = agent_state.inventory.get(resource).copied().unwrap_or(0);
+            if held < *quantity {
+                return Err(RejectionReason::InsufficientResources);
+            }
+            // The structure must have room for it
+            if let Some(s) = context.structures_at_location.get(structure_id)
+                && world_structure::stored_total(s).saturating_add(*quantity)
+                    > s.properties.storage_slots
+            {
+                return Err(RejectionReason::CapacityExceeded);
+            }
+        }
+        (
+            ActionType::Withdraw,
+            ActionParameters::Withdraw {
+                structure_id,
+                resource,
+                quantity,
+            },
+        ) => {
+            if *quantity == 0 {
+                return Err(RejectionReason::InvalidAction);
+            }
+            // The structure must hold what is withdrawn
+            let stored = context
+                .structures_at_location
+                .get(structure_id)
+                .and_then(|s| s.inventory.get(resource).copied())
+                .unwrap_or(0);
+            if stored < *quantity {
+                return Err(RejectionReason::InsufficientResources);
+            }
+            // Agent must be able to carry it
+            let current_load: u32 = agent_state.inventory.values().sum();
+            if current_load.saturating_add(*quantity) > agent_state.carry_capacity {
+                return Err(RejectionReason::CapacityExceeded);
+            }
+        }
         _ => {
             // Other actions have resource checks handled in their handlers
         }
@@ -697,6 +1088,7 @@ fn validate_world_state(
 /// Survival actions (gather, eat, drink, rest, move) are always available.
 /// The teach action requires the teacher to know the concept.
 /// Construction actions require specific knowledge per structure type.
+#[allow(clippy::too_many_lines)]
 fn validate_skill(
     action_type: ActionType,
     params: &ActionParameters,
@@ -704,8 +1096,10 @@ fn validate_skill(
 ) -> Result<(), RejectionReason> {
     match (action_type, params) {
         (ActionType::Teach, ActionParameters::Teach { target_agent, knowledge }) => {
-            // Teacher must know the concept
-            if !context.agent_knowledge.contains(knowledge) {
+            // Teacher must know the concept; every agent knows its own map
+            if knowledge != emergence_world::known_map::MAP_CONCEPT
+                && !context.agent_knowledge.contains(knowledge)
+            {
                 return Err(RejectionReason::UnknownAction);
             }
             // Target must be at the same location
@@ -722,6 +1116,18 @@ fn validate_skill(
             }
             Ok(())
         }
+        (ActionType::UpgradeStructure, ActionParameters::UpgradeStructure { structure_id }) => {
+            // Agent must have the knowledge to build the next tier
+            if let Some(structure) = context.structures_at_location.get(structure_id)
+                && let Some(next) = emergence_world::next_tier(structure.structure_type)
+                && !context
+                    .agent_knowledge
+                    .contains(&emergence_world::blueprint(next).required_knowledge)
+            {
+                return Err(RejectionReason::UnknownAction);
+            }
+            Ok(())
+        }
         (ActionType::ImproveRoute, ActionParameters::ImproveRoute { .. }) => {
             // Check knowledge requirements for the target path type
             if let Some(r) = &context.route_to_improve
@@ -745,7 +1151,7 @@ fn validate_skill(
             }
             Ok(())
         }
-        (ActionType::FarmPlant | ActionType::FarmHarvest, _) => {
+        (ActionType::FarmPlant | ActionType::FarmHarvest | ActionType::Fertilize, _) => {
             // Farming requires "agriculture" knowledge
             if !context.agent_knowledge.contains("agriculture") {
                 return Err(RejectionReason::UnknownAction);
@@ -761,13 +1167,32 @@ fn validate_skill(
             }
             Ok(())
         }
-        (ActionType::Mine, ActionParameters::Mine) => {
-            // Mining requires "mining" knowledge
+        (
+            ActionType::Mine | ActionType::DeepenMine,
+            ActionParameters::Mine | ActionParameters::DeepenMine,
+        ) => {
+            // Mining and deepening a mine require "mining" knowledge
             if !context.agent_knowledge.contains("mining") {
                 return Err(RejectionReason::UnknownAction);
             }
             Ok(())
         }
+        (ActionType::Hunt, ActionParameters::Hunt) => {
+            // Hunting requires "animal_tracking" or "hunting" knowledge
+            if !context.agent_knowledge.contains("animal_tracking")
+                && !context.agent_knowledge.contains("hunting")
+            {
+                return Err(RejectionReason::UnknownAction);
+            }
+            Ok(())
+        }
+        (ActionType::Fish, ActionParameters::Fish) => {
+            // Fishing requires "fishing" knowledge
+            if !context.agent_knowledge.contains("fishing") {
+                return Err(RejectionReason::UnknownAction);
+            }
+            Ok(())
+        }
         (ActionType::Smelt, ActionParameters::Smelt) => {
             // Smelting requires "smelting" or "metalworking" knowledge
             if !context.agent_knowledge.contains("smelting")
@@ -777,8 +1202,8 @@ fn validate_skill(
             }
             Ok(())
         }
-        (ActionType::Write | ActionType::Read, _) => {
-            // Reading/writing requires "written_language" knowledge
+        (ActionType::Write | ActionType::Read | ActionType::Chart, _) => {
+            // Reading, writing, and charting require "written_language" knowledge
             if !context.agent_knowledge.contains("written_language") {
                 return Err(RejectionReason::UnknownAction);
             }
@@ -856,10 +1281,17 @@ mod tests {
             structures_at_location: BTreeMap::new(),
             route_to_improve: None,
             move_route: None,
+            destination_fortifications: Vec::new(),
+            route_to_build: None,
             agent_groups: Vec::new(),
             dead_agents: BTreeSet::new(),
             farm_registry: emergence_world::farming::FarmRegistry::new(),
             library_knowledge: BTreeMap::new(),
+            game_at_location: 0,
+            ore_body: None,
+            fish_nearby: 0,
+            fire_intensity: 0,
+            location_type: String::from("settlement"),
             current_tick: 0,
         }
     }
@@ -906,6 +1338,24 @@ mod tests {
         assert_eq!(result, Err(RejectionReason::InsufficientEnergy));
     }
 
+    #[test]
+    fn rejections_are_counted_by_reason() {
+        let before = metrics::ACTION_REJECTIONS.get("InsufficientEnergy");
+        let gathers = metrics::ACTIONS.get("Gather");
+        let result = validate_action(
+            ActionType::Gather,
+            &ActionParameters::Gather {
+                resource: Resource::Wood,
+            },
+            &make_agent_state(5),
+            &make_context(),
+        );
+        assert!(result.is_err());
+        // Other tests run concurrently, so only a lower bound holds.
+        assert!(metrics::ACTION_REJECTIONS.get("InsufficientEnergy") > before);
+        assert!(metrics::ACTIONS.get("Gather") > gathers);
+    }
+
     #[test]
     fn gather_at_location_without_resource_rejected() {
         let state = make_agent_state(80);
@@ -1662,6 +2112,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         };
         ctx.structures_at_location.insert(struct_id, structure);
 
@@ -1701,6 +2152,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         };
         ctx.structures_at_location.insert(struct_id, structure);
 
@@ -1739,6 +2191,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         };
         ctx.structures_at_location.insert(struct_id, structure);
 
@@ -2303,9 +2756,60 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         }
     }
 
+    #[test]
+    fn upgrade_needs_knowledge_materials_and_a_next_tier() {
+        let mut state = make_agent_state(80);
+        let mut ctx = make_context();
+        let hut = make_val_structure(
+            emergence_types::StructureType::BasicHut,
+            ctx.agent_location,
+            None,
+        );
+        let longhouse = make_val_structure(
+            emergence_types::StructureType::Longhouse,
+            ctx.agent_location,
+            None,
+        );
+        let (hut_id, longhouse_id) = (hut.id, longhouse.id);
+        ctx.structures_at_location.insert(hut_id, hut);
+        ctx.structures_at_location.insert(longhouse_id, longhouse);
+        let upgrade = |structure_id| ActionParameters::UpgradeStructure { structure_id };
+
+        let check = |state: &AgentState, ctx: &ValidationContext, id| {
+            validate_action(ActionType::UpgradeStructure, &upgrade(id), state, ctx)
+        };
+        assert_eq!(check(&state, &ctx, hut_id), Err(RejectionReason::InsufficientResources));
+        state.inventory.insert(Resource::Wood, 20);
+        state.inventory.insert(Resource::Stone, 15);
+        assert_eq!(check(&state, &ctx, hut_id), Err(RejectionReason::UnknownAction));
+        ctx.agent_knowledge.insert(String::from("masonry"));
+        assert!(check(&state, &ctx, hut_id).is_ok());
+        assert_eq!(check(&state, &ctx, longhouse_id), Err(RejectionReason::UnavailableTarget));
+    }
+
+    #[test]
+    fn upgraded_tiers_cannot_be_built_directly() {
+        let mut state = make_agent_state(80);
+        state.inventory.insert(Resource::Wood, 50);
+        state.inventory.insert(Resource::Stone, 50);
+        let mut ctx = make_context();
+        ctx.agent_knowledge.insert(String::from("masonry"));
+
+        let result = validate_action(
+            ActionType::Build,
+            &ActionParameters::Build {
+                structure_type: emergence_types::StructureType::House,
+            },
+            &state,
+            &ctx,
+        );
+        assert_eq!(result, Err(RejectionReason::UnavailableTarget));
+    }
+
     #[test]
     fn claim_unowned_structure_passes_validation() {
         let state = make_agent_state(80);
@@ -2414,6 +2918,152 @@ mod tests {
         assert_eq!(result, Err(RejectionReason::InsufficientEnergy));
     }
 
+    #[test]
+    fn transfer_needs_ownership_and_a_recipient_present() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        ctx.agent_id = state.agent_id;
+        let friend = AgentId::new();
+        let structure = make_val_structure(
+            emergence_types::StructureType::BasicHut,
+            ctx.agent_location,
+            Some(state.agent_id),
+        );
+        let sid = structure.id;
+        ctx.structures_at_location.insert(sid, structure);
+        let transfer = ActionParameters::TransferOwnership {
+            structure_id: sid,
+            new_owner: friend,
+        };
+
+        let result = validate_action(ActionType::TransferOwnership, &transfer, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+        ctx.agents_at_location.push(friend);
+        let result = validate_action(ActionType::TransferOwnership, &transfer, &state, &ctx);
+        assert!(result.is_ok());
+
+        if let Some(s) = ctx.structures_at_location.get_mut(&sid) {
+            s.owner = Some(friend);
+        }
+        let result = validate_action(ActionType::TransferOwnership, &transfer, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::PermissionDenied));
+    }
+
+    #[test]
+    fn storage_checks_access_room_and_stock() {
+        let mut state = make_agent_state(80);
+        state.inventory.insert(Resource::FoodBerry, 10);
+        let mut ctx = make_context();
+        ctx.agent_id = state.agent_id;
+        let mut granary = make_val_structure(
+            emergence_types::StructureType::Granary,
+            ctx.agent_location,
+            Some(AgentId::new()),
+        );
+        granary.inventory.insert(Resource::FoodBerry, 95);
+        let sid = granary.id;
+        ctx.structures_at_location.insert(sid, granary);
+        let store = |resource, quantity| ActionParameters::Deposit {
+            structure_id: sid,
+            resource,
+            quantity,
+        };
+
+        let result = validate_action(ActionType::Deposit, &store(Resource::Wood, 1), &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+        let berries = store(Resource::FoodBerry, 10);
+        let result = validate_action(ActionType::Deposit, &berries, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::CapacityExceeded));
+        let berries = store(Resource::FoodBerry, 5);
+        let result = validate_action(ActionType::Deposit, &berries, &state, &ctx);
+        assert!(result.is_ok());
+
+        let take = ActionParameters::Withdraw {
+            structure_id: sid,
+            resource: Resource::FoodBerry,
+            quantity: 96,
+        };
+        let result = validate_action(ActionType::Withdraw, &take, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InsufficientResources));
+
+        if let Some(s) = ctx.structures_at_location.get_mut(&sid) {
+            s.access_list = Some(emergence_types::AccessControlList {
+                allowed_agents: BTreeSet::new(),
+                allowed_groups: BTreeSet::new(),
+                denied_agents: BTreeSet::from([state.agent_id]),
+                public: false,
+                toll_cost: None,
+            });
+        }
+        let result = validate_action(ActionType::Deposit, &berries, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::PermissionDenied));
+    }
+
+    #[test]
+    fn post_order_needs_a_market_and_the_escrow() {
+        let mut state = make_agent_state(80);
+        state.inventory.insert(Resource::CurrencyToken, 10);
+        let mut ctx = make_context();
+        let market = make_val_structure(
+            emergence_types::StructureType::Market,
+            ctx.agent_location,
+            None,
+        );
+        let market_id = market.id;
+        let order = |unit_price| ActionParameters::PostOrder {
+            market_id,
+            side: emergence_types::OrderSide::Buy,
+            resource: Resource::Wood,
+            quantity: 5,
+            price_resource: Resource::CurrencyToken,
+            unit_price,
+        };
+
+        let result = validate_action(ActionType::PostOrder, &order(2), &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+        ctx.structures_at_location.insert(market_id, market);
+        let result = validate_action(ActionType::PostOrder, &order(2), &state, &ctx);
+        assert!(result.is_ok());
+        let result = validate_action(ActionType::PostOrder, &order(3), &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InsufficientResources));
+        let result = validate_action(ActionType::PostOrder, &order(0), &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidAction));
+    }
+
+    #[test]
+    fn fortifications_bar_moves_and_invite_breaches() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        let dest = LocationId::new();
+        let mut palisade =
+            make_val_structure(emergence_types::StructureType::Palisade, dest, None);
+        palisade.access_list = Some(AccessControlList {
+            allowed_agents: BTreeSet::new(),
+            allowed_groups: BTreeSet::new(),
+            denied_agents: BTreeSet::from([ctx.agent_id]),
+            public: false,
+            toll_cost: None,
+        });
+        let enter = ActionParameters::Move { destination: dest };
+        let breach = ActionParameters::Breach { destination: dest };
+        ctx.move_route = Some(make_move_route(ctx.agent_location, dest, None));
+
+        // Nothing bars the way, so there is nothing to breach.
+        assert!(validate_action(ActionType::Move, &enter, &state, &ctx).is_ok());
+        let result = validate_action(ActionType::Breach, &breach, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+
+        ctx.destination_fortifications.push(palisade);
+        let result = validate_action(ActionType::Move, &enter, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::PermissionDenied));
+        assert!(validate_action(ActionType::Breach, &breach, &state, &ctx).is_ok());
+
+        // A breach must be made from along a route.
+        ctx.move_route = None;
+        let result = validate_action(ActionType::Breach, &breach, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+    }
+
     #[test]
     fn immature_agent_cannot_claim() {
         let state = make_agent_state(80);
@@ -2740,6 +3390,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         };
         (id, structure)
     }
@@ -2970,6 +3621,39 @@ mod tests {
         assert_eq!(result, Err(RejectionReason::UnknownAction));
     }
 
+    #[test]
+    fn fertilize_needs_a_plot_and_enough_waste() {
+        let mut state = make_agent_state(80);
+        state.inventory.insert(Resource::FoodWaste, 2);
+        let mut ctx = make_context();
+        ctx.agent_knowledge.insert(String::from("agriculture"));
+        let validate = |state: &AgentState, ctx: &ValidationContext| {
+            validate_action(ActionType::Fertilize, &ActionParameters::Fertilize, state, ctx)
+        };
+        assert_eq!(validate(&state, &ctx), Err(RejectionReason::WrongLocation));
+
+        let (sid, structure) =
+            make_test_structure(StructureType::FarmPlot, ctx.agent_location, None);
+        ctx.structures_at_location.insert(sid, structure);
+        assert_eq!(validate(&state, &ctx), Err(RejectionReason::InsufficientResources));
+
+        state.inventory.insert(Resource::FoodWaste, farming::FERTILIZER_WASTE);
+        assert!(validate(&state, &ctx).is_ok());
+    }
+
+    #[test]
+    fn slaughter_needs_a_head_of_livestock() {
+        let mut state = make_agent_state(80);
+        let ctx = make_context();
+        let validate = |state: &AgentState| {
+            validate_action(ActionType::Slaughter, &ActionParameters::Slaughter, state, &ctx)
+        };
+        assert_eq!(validate(&state), Err(RejectionReason::InsufficientResources));
+
+        state.inventory.insert(Resource::Livestock, 1);
+        assert!(validate(&state).is_ok());
+    }
+
     // -----------------------------------------------------------------------
     // Craft validation (Phase 4.2)
     // -----------------------------------------------------------------------
@@ -3237,6 +3921,200 @@ mod tests {
         assert_eq!(result, Err(RejectionReason::UnknownAction));
     }
 
+    #[test]
+    fn worked_out_face_must_be_deepened_at_a_rising_cost() {
+        let mut state = make_agent_state(80);
+        state.inventory.insert(Resource::Tool, 1);
+        state.inventory.insert(Resource::Wood, 10);
+        let mut ctx = make_context();
+        ctx.agent_knowledge.insert(String::from("mining"));
+        ctx.location_resources.insert(
+            Resource::Ore,
+            ResourceNode {
+                resource: Resource::Ore,
+                available: 20,
+                regen_per_tick: 1,
+                max_capacity: 50,
+            },
+        );
+        let deepen = |state: &AgentState, ctx: &ValidationContext| {
+            validate_action(ActionType::DeepenMine, &ActionParameters::DeepenMine, state, ctx)
+        };
+
+        // No ore body: nowhere to sink a shaft.
+        assert_eq!(deepen(&state, &ctx), Err(RejectionReason::WrongLocation));
+
+        let mut body = OreBody::new(100);
+        body.extract(mining::FACE_CAPACITY);
+        ctx.ore_body = Some(body);
+        let mined = validate_action(ActionType::Mine, &ActionParameters::Mine, &state, &ctx);
+        assert_eq!(mined, Err(RejectionReason::UnavailableTarget));
+        assert!(deepen(&state, &ctx).is_ok());
+
+        // The second level needs more wood than the agent holds.
+        body.depth = 1;
+        ctx.ore_body = Some(body);
+        assert_eq!(deepen(&state, &ctx), Err(RejectionReason::InsufficientResources));
+
+        // Nothing left behind the face.
+        ctx.ore_body = Some(OreBody::new(10));
+        assert_eq!(deepen(&state, &ctx), Err(RejectionReason::UnavailableTarget));
+    }
+
+    // -----------------------------------------------------------------------
+    // Hunt validation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn hunt_valid_with_game_and_tracking() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        ctx.agent_knowledge.insert(String::from("animal_tracking"));
+        ctx.game_at_location = 12;
+
+        let result = validate_action(
+            ActionType::Hunt,
+            &ActionParameters::Hunt,
+            &state,
+            &ctx,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn hunt_without_game_or_knowledge_rejected() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        ctx.game_at_location = 12;
+
+        let result = validate_action(
+            ActionType::Hunt,
+            &ActionParameters::Hunt,
+            &state,
+            &ctx,
+        );
+        assert_eq!(result, Err(RejectionReason::UnknownAction));
+
+        ctx.agent_knowledge.insert(String::from("hunting"));
+        ctx.game_at_location = 0;
+        let result = validate_action(
+            ActionType::Hunt,
+            &ActionParameters::Hunt,
+            &state,
+            &ctx,
+        );
+        assert_eq!(result, Err(RejectionReason::UnavailableTarget));
+    }
+
+    // -----------------------------------------------------------------------
+    // Fish validation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn fish_requires_knowledge_and_nearby_water() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        ctx.fish_nearby = 20;
+
+        let result = validate_action(
+            ActionType::Fish,
+            &ActionParameters::Fish,
+            &state,
+            &ctx,
+        );
+        assert_eq!(result, Err(RejectionReason::UnknownAction));
+
+        ctx.agent_knowledge.insert(String::from("fishing"));
+        let result = validate_action(
+            ActionType::Fish,
+            &ActionParameters::Fish,
+            &state,
+            &ctx,
+        );
+        assert!(result.is_ok());
+
+        ctx.fish_nearby = 0;
+        let result = validate_action(
+            ActionType::Fish,
+            &ActionParameters::Fish,
+            &state,
+            &ctx,
+        );
+        assert_eq!(result, Err(RejectionReason::UnavailableTarget));
+    }
+
+    // -----------------------------------------------------------------------
+    // BuildRoute validation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn build_route_needs_a_plan_and_its_materials() {
+        let mut state = make_agent_state(80);
+        let mut ctx = make_context();
+        let params = ActionParameters::BuildRoute {
+            destination: LocationId::new(),
+        };
+
+        let result = validate_action(ActionType::BuildRoute, &params, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+
+        let cost = emergence_world::route_building::material_cost(2);
+        ctx.route_to_build = Some(RoutePlan { length: 2, cost: cost.clone() });
+        let result = validate_action(ActionType::BuildRoute, &params, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InsufficientResources));
+
+        state.inventory.extend(cost);
+        let result = validate_action(ActionType::BuildRoute, &params, &state, &ctx);
+        assert!(result.is_ok());
+    }
+
+    // -----------------------------------------------------------------------
+    // FoundSettlement validation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn settlements_need_wilderness_and_founders() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        ctx.agents_at_location.push(ctx.agent_id);
+        ctx.agents_at_location.push(AgentId::new());
+        let params = ActionParameters::FoundSettlement {
+            name: String::from("Newhome"),
+        };
+
+        let result = validate_action(ActionType::FoundSettlement, &params, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::WrongLocation));
+
+        ctx.location_type = String::from("natural");
+        let result = validate_action(ActionType::FoundSettlement, &params, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidTarget));
+
+        ctx.agents_at_location.push(AgentId::new());
+        let result = validate_action(ActionType::FoundSettlement, &params, &state, &ctx);
+        assert!(result.is_ok());
+
+        let unnamed = ActionParameters::FoundSettlement { name: String::from(" ") };
+        let result = validate_action(ActionType::FoundSettlement, &unnamed, &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::InvalidAction));
+    }
+
+    #[test]
+    fn every_agent_can_teach_its_map() {
+        let state = make_agent_state(80);
+        let mut ctx = make_context();
+        let student = AgentId::new();
+        ctx.agents_at_location.push(student);
+
+        let teach = |knowledge: &str| ActionParameters::Teach {
+            target_agent: student,
+            knowledge: String::from(knowledge),
+        };
+        let result = validate_action(ActionType::Teach, &teach("smelting"), &state, &ctx);
+        assert_eq!(result, Err(RejectionReason::UnknownAction));
+        let map = teach(emergence_world::known_map::MAP_CONCEPT);
+        assert!(validate_action(ActionType::Teach, &map, &state, &ctx).is_ok());
+    }
+
     // -----------------------------------------------------------------------
     // Smelt validation (Phase 4.2)
     // -----------------------------------------------------------------------
@@ -3374,6 +4252,26 @@ mod tests {
         assert_eq!(result, Err(RejectionReason::UnknownAction));
     }
 
+    // -----------------------------------------------------------------------
+    // Chart validation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn charting_needs_a_hide_and_writing() {
+        let mut state = make_agent_state(80);
+        let mut ctx = make_context();
+        let chart = |state: &AgentState, ctx: &ValidationContext| {
+            validate_action(ActionType::Chart, &ActionParameters::Chart, state, ctx)
+        };
+        assert_eq!(chart(&state, &ctx), Err(RejectionReason::InsufficientResources));
+
+        state.inventory.insert(Resource::Hide, 1);
+        assert_eq!(chart(&state, &ctx), Err(RejectionReason::UnknownAction));
+
+        ctx.agent_knowledge.insert(String::from("written_language"));
+        assert!(chart(&state, &ctx).is_ok());
+    }
+
     // -----------------------------------------------------------------------
     // Write validation (Phase 4.2)
     // -----------------------------------------------------------------------
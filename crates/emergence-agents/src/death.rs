@@ -5,14 +5,16 @@
 //! - Age exceeds lifespan
 //!
 //! On death, inventory drops at the agent's current location, structures
-//! become orphaned (owner set to `None`), and a social notification is
-//! emitted for related agents.
+//! pass to the agent's heir -- a living spouse, then a living child -- or
+//! become orphaned (owner set to `None`) and claimable if there is none,
+//! and a social notification is emitted for related agents.
 
 use std::collections::BTreeMap;
 
 use emergence_types::{AgentId, AgentState, LocationId, Resource, StructureId};
 
 use crate::config::VitalsConfig;
+use crate::family::FamilyTracker;
 use crate::inventory;
 
 /// The cause of an agent's death.
@@ -90,6 +92,14 @@ pub struct DeathConsequences {
     /// orphaned (owner set to `None`).
     pub orphaned_structures: Vec<StructureId>,
 
+    /// The family member who inherits the deceased's structures, set by
+    /// [`settle_inheritance`].
+    pub heir: Option<AgentId>,
+
+    /// Structure IDs that pass to the [`heir`](Self::heir) instead of
+    /// being orphaned.
+    pub inherited_structures: Vec<StructureId>,
+
     /// Agent IDs that had a relationship with the deceased and should
     /// be notified in the next tick's perception.
     pub agents_to_notify: Vec<AgentId>,
@@ -123,10 +133,33 @@ pub fn process_death(
         death_location: state.location_id,
         dropped_inventory,
         orphaned_structures: owned_structures,
+        heir: None,
+        inherited_structures: Vec::new(),
         agents_to_notify,
     }
 }
 
+/// Pass a dead agent's structures to their heir.
+///
+/// Consults `family` for the heir ([`FamilyTracker::heir_of`]). If one is
+/// alive, every orphaned structure moves to `inherited_structures` and
+/// `heir` is set; otherwise the structures stay orphaned and become
+/// claimable. Returns the heir.
+///
+/// The caller is responsible for setting each inherited structure's owner
+/// to the heir and emitting a `StructureInherited` event for it.
+pub fn settle_inheritance(
+    consequences: &mut DeathConsequences,
+    family: &FamilyTracker,
+) -> Option<AgentId> {
+    let heir = family.heir_of(consequences.agent_id)?;
+    consequences.heir = Some(heir);
+    consequences
+        .inherited_structures
+        .append(&mut consequences.orphaned_structures);
+    Some(heir)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -236,6 +269,27 @@ mod tests {
         assert!(result.orphaned_structures.contains(&s2));
     }
 
+    #[test]
+    fn structures_pass_to_the_heir_before_they_are_orphaned() {
+        let mut state = test_state();
+        let s1 = StructureId::new();
+        let mut family = FamilyTracker::new();
+
+        // Without family, the structure becomes claimable.
+        let mut alone = process_death(&mut state, DeathCause::OldAge, vec![s1]);
+        assert_eq!(settle_inheritance(&mut alone, &family), None);
+        assert_eq!(alone.orphaned_structures, vec![s1]);
+
+        let spouse = AgentId::new();
+        family.register_alive(spouse);
+        family.record_marriage(state.agent_id, spouse, 5);
+        let mut result = process_death(&mut state, DeathCause::OldAge, vec![s1]);
+        assert_eq!(settle_inheritance(&mut result, &family), Some(spouse));
+        assert_eq!(result.heir, Some(spouse));
+        assert_eq!(result.inherited_structures, vec![s1]);
+        assert!(result.orphaned_structures.is_empty());
+    }
+
     #[test]
     fn process_death_notifies_related_agents() {
         let mut state = test_state();
@@ -4,6 +4,11 @@
 //! alliances, conflicts, treaties, and tribute. This module implements
 //! task 6.3.6 from the build plan.
 //!
+//! Conflicts are fought over fortified locations: a group at war may lay
+//! siege to a location the enemy holds, and once the besiegers hold every
+//! neighbouring location its supplies are cut. Breaches of the enemy's
+//! walls are counted against the conflict.
+//!
 //! # Architecture
 //!
 //! Diplomacy operates at the **group level** for alliances, conflicts,
@@ -24,6 +29,7 @@
 //! - A group cannot declare conflict against an ally (must break alliance first).
 //! - Treaties require both group leaders to be co-located.
 //! - Tribute transfers go through the ledger for conservation law compliance.
+//! - A siege requires an active conflict and is lifted when it ends.
 
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -33,6 +39,8 @@ use uuid::Uuid;
 
 use emergence_types::{AgentId, GroupId, LocationId, Resource};
 
+use emergence_world::WorldMap;
+
 use crate::error::AgentError;
 
 // ---------------------------------------------------------------------------
@@ -137,6 +145,30 @@ pub struct Conflict {
     pub active: bool,
     /// The tick when the conflict ended, if applicable.
     pub ended_at_tick: Option<u64>,
+    /// Breaches either side has made in the other's fortifications.
+    pub breaches: u32,
+}
+
+// ---------------------------------------------------------------------------
+// Siege types
+// ---------------------------------------------------------------------------
+
+/// A siege laid on a location by one side of a conflict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Siege {
+    /// The besieged location.
+    pub location_id: LocationId,
+    /// The conflict the siege is part of.
+    pub conflict_id: Uuid,
+    /// The group laying the siege.
+    pub besieger: GroupId,
+    /// The group holding the location.
+    pub defender: GroupId,
+    /// The tick when the siege began.
+    pub started_at_tick: u64,
+    /// Whether the besiegers hold every neighbouring location, cutting
+    /// the location's supplies.
+    pub supplies_cut: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -192,6 +224,11 @@ pub enum DiplomacyResult {
         /// The tribute record.
         tribute_id: Uuid,
     },
+    /// A siege was laid on a location.
+    SiegeBegun {
+        /// The besieged location.
+        location_id: LocationId,
+    },
 }
 
 /// Errors specific to diplomacy operations.
@@ -233,6 +270,10 @@ pub enum DiplomacyError {
     #[error("agents must be co-located for tribute")]
     AgentsNotCoLocated,
 
+    /// The location is already under siege.
+    #[error("location {0} is already under siege")]
+    AlreadyBesieged(LocationId),
+
     /// An underlying agent error occurred.
     #[error("agent error: {0}")]
     Agent(#[from] AgentError),
@@ -244,8 +285,8 @@ pub enum DiplomacyError {
 
 /// Tracks all diplomatic relationships in the simulation.
 ///
-/// Maintains active alliances, conflicts, and treaties between groups,
-/// and records tribute offers between agents.
+/// Maintains active alliances, conflicts, treaties, and sieges between
+/// groups, and records tribute offers between agents.
 #[derive(Debug, Clone)]
 pub struct DiplomacyState {
     /// Active alliances between groups.
@@ -256,6 +297,8 @@ pub struct DiplomacyState {
     treaties: BTreeMap<Uuid, Treaty>,
     /// Historical tribute records.
     tributes: Vec<TributeRecord>,
+    /// Sieges under way, keyed by the besieged location.
+    sieges: BTreeMap<LocationId, Siege>,
 }
 
 impl DiplomacyState {
@@ -266,6 +309,7 @@ impl DiplomacyState {
             conflicts: BTreeMap::new(),
             treaties: BTreeMap::new(),
             tributes: Vec::new(),
+            sieges: BTreeMap::new(),
         }
     }
 
@@ -366,6 +410,7 @@ impl DiplomacyState {
             declared_at_tick: current_tick,
             active: true,
             ended_at_tick: None,
+            breaches: 0,
         };
 
         self.conflicts.insert(conflict_id, conflict);
@@ -456,6 +501,116 @@ impl DiplomacyState {
         Ok(DiplomacyResult::TributeOffered { tribute_id })
     }
 
+    /// Lay siege to a location held by an enemy group.
+    ///
+    /// # Validation
+    ///
+    /// - The groups must be in active conflict.
+    /// - The location must not already be under siege.
+    ///
+    /// The siege starts with supplies flowing; [`Self::update_sieges`]
+    /// cuts them once the besiegers surround the location.
+    pub fn begin_siege(
+        &mut self,
+        besieger: GroupId,
+        defender: GroupId,
+        location_id: LocationId,
+        current_tick: u64,
+    ) -> Result<DiplomacyResult, DiplomacyError> {
+        let conflict_id = self
+            .conflict_between(&besieger, &defender)
+            .map(|c| c.id)
+            .ok_or(DiplomacyError::NotInConflict(besieger, defender))?;
+
+        if self.sieges.contains_key(&location_id) {
+            return Err(DiplomacyError::AlreadyBesieged(location_id));
+        }
+
+        self.sieges.insert(location_id, Siege {
+            location_id,
+            conflict_id,
+            besieger,
+            defender,
+            started_at_tick: current_tick,
+            supplies_cut: false,
+        });
+
+        Ok(DiplomacyResult::SiegeBegun { location_id })
+    }
+
+    /// Lift the siege of a location, returning it if there was one.
+    pub fn lift_siege(&mut self, location_id: &LocationId) -> Option<Siege> {
+        self.sieges.remove(location_id)
+    }
+
+    /// Bring every siege up to date with who holds the land around it.
+    ///
+    /// `holders` maps each location to the groups with members present
+    /// there. A siege's supplies are cut while the besiegers hold every
+    /// neighbouring location and the defenders hold none of them. A siege
+    /// is lifted once the besiegers hold no neighbouring location at all.
+    ///
+    /// Returns the sieges that were lifted.
+    pub fn update_sieges(
+        &mut self,
+        map: &WorldMap,
+        holders: &BTreeMap<LocationId, BTreeSet<GroupId>>,
+    ) -> Vec<Siege> {
+        let mut lifted = Vec::new();
+        self.sieges.retain(|location_id, siege| {
+            let neighbors = map.neighbors(*location_id);
+            let held_by = |group: &GroupId| {
+                neighbors
+                    .iter()
+                    .filter(|(n, _)| holders.get(n).is_some_and(|g| g.contains(group)))
+                    .count()
+            };
+            let besieged = held_by(&siege.besieger);
+            if besieged == 0 {
+                lifted.push(siege.clone());
+                return false;
+            }
+            siege.supplies_cut = besieged == neighbors.len() && held_by(&siege.defender) == 0;
+            true
+        });
+        lifted
+    }
+
+    /// Record a breach of `defender`'s fortifications by `attacker`
+    /// against the conflict between them.
+    ///
+    /// Returns `false` if the groups are not in conflict, in which case
+    /// nothing is recorded.
+    pub fn record_breach(&mut self, attacker: &GroupId, defender: &GroupId) -> bool {
+        let Some(conflict_id) = self.conflict_between(attacker, defender).map(|c| c.id) else {
+            return false;
+        };
+        if let Some(conflict) = self.conflicts.get_mut(&conflict_id) {
+            conflict.breaches = conflict.breaches.saturating_add(1);
+        }
+        true
+    }
+
+    /// Get the siege of a location, if it is under one.
+    pub fn siege_at(&self, location_id: &LocationId) -> Option<&Siege> {
+        self.sieges.get(location_id)
+    }
+
+    /// Get all sieges under way.
+    pub fn active_sieges(&self) -> Vec<&Siege> {
+        self.sieges.values().collect()
+    }
+
+    /// Check whether a besieged location's supplies are cut.
+    ///
+    /// The tick cycle withholds resource regeneration from such a
+    /// location.
+    pub fn supplies_cut(&self, location_id: &LocationId) -> bool {
+        self.sieges
+            .get(location_id)
+            .is_some_and(|siege| siege.supplies_cut)
+    }
+
     /// Check whether two groups have an active alliance.
     pub fn are_allied(&self, group_a: &GroupId, group_b: &GroupId) -> bool {
         self.alliances.values().any(|a| {
@@ -612,7 +767,16 @@ impl DiplomacyState {
     // Internal helpers
     // -----------------------------------------------------------------------
 
-    /// End all active conflicts between two groups.
+    /// The active conflict between two groups, if any.
+    fn conflict_between(&self, group_a: &GroupId, group_b: &GroupId) -> Option<&Conflict> {
+        self.conflicts.values().find(|c| {
+            c.active
+                && ((c.aggressor == *group_a && c.target == *group_b)
+                    || (c.aggressor == *group_b && c.target == *group_a))
+        })
+    }
+
+    /// End all active conflicts between two groups, lifting their sieges.
     fn end_conflict_between(
         &mut self,
         group_a: &GroupId,
@@ -630,6 +794,10 @@ impl DiplomacyState {
                 conflict.ended_at_tick = Some(current_tick);
             }
         }
+        let conflicts = &self.conflicts;
+        self.sieges.retain(|_, siege| {
+            conflicts.get(&siege.conflict_id).is_some_and(|c| c.active)
+        });
     }
 }
 
@@ -644,6 +812,7 @@ impl Default for DiplomacyState {
 // ---------------------------------------------------------------------------
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use std::collections::{BTreeMap, BTreeSet};
 
@@ -928,6 +1097,74 @@ mod tests {
         assert!(state.are_in_conflict(&g1, &g2));
     }
 
+    // -----------------------------------------------------------------------
+    // Siege tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn siege_requires_conflict_and_cuts_supplies_when_surrounded() {
+        let (map, ids) = emergence_world::create_starting_world().unwrap();
+        let mut state = DiplomacyState::new();
+        let (g1, g2) = (GroupId::new(), GroupId::new());
+        let cave = ids.mountain_cave;
+
+        assert!(matches!(
+            state.begin_siege(g1, g2, cave, 10),
+            Err(DiplomacyError::NotInConflict(_, _))
+        ));
+        state.declare_conflict(g1, g2, String::from("war"), 10).unwrap();
+        state.begin_siege(g1, g2, cave, 11).unwrap();
+        assert!(matches!(
+            state.begin_siege(g1, g2, cave, 12),
+            Err(DiplomacyError::AlreadyBesieged(_))
+        ));
+        assert!(!state.supplies_cut(&cave));
+
+        // The besiegers hold every neighbouring location.
+        let mut holders: BTreeMap<LocationId, BTreeSet<GroupId>> = map
+            .neighbors(cave)
+            .into_iter()
+            .map(|(neighbor, _)| (neighbor, BTreeSet::from([g1])))
+            .collect();
+        assert!(state.update_sieges(&map, &holders).is_empty());
+        assert!(state.supplies_cut(&cave));
+
+        // A defender slipping into one of them reopens the supply line.
+        if let Some(groups) = holders.values_mut().next() {
+            groups.insert(g2);
+        }
+        state.update_sieges(&map, &holders);
+        assert!(!state.supplies_cut(&cave));
+
+        // Besiegers leaving every neighbour lifts the siege.
+        let lifted = state.update_sieges(&map, &BTreeMap::new());
+        assert_eq!(lifted.len(), 1);
+        assert!(state.siege_at(&cave).is_none());
+    }
+
+    #[test]
+    fn breaches_count_against_the_conflict_and_ceasefire_lifts_sieges() {
+        let mut state = DiplomacyState::new();
+        let (g1, g2, g3) = (GroupId::new(), GroupId::new(), GroupId::new());
+        let loc = LocationId::new();
+
+        state.declare_conflict(g1, g2, String::from("war"), 10).unwrap();
+        assert!(state.record_breach(&g2, &g1));
+        assert!(!state.record_breach(&g1, &g3));
+        assert_eq!(state.conflicts_for_group(&g1).first().unwrap().breaches, 1);
+
+        state.begin_siege(g1, g2, loc, 11).unwrap();
+        assert_eq!(state.active_sieges().len(), 1);
+        let terms = TreatyTerms {
+            ceasefire: true,
+            border_agreement: None,
+            trade_terms: None,
+            duration_ticks: None,
+        };
+        state.negotiate_treaty(g1, g2, terms, true, 20).unwrap();
+        assert!(state.active_sieges().is_empty());
+    }
+
     // -----------------------------------------------------------------------
     // Tribute tests
     // -----------------------------------------------------------------------
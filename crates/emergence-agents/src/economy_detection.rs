@@ -7,6 +7,7 @@
 //! - Taxation detection (regular collection by a leader/group)
 //! - Market detection (high trade volume at a location)
 //! - Economic model classification (Subsistence, Barter, Market, Command, Feudal)
+//! - Credit system classification (from the ledger's outstanding loans)
 //! - Wealth distribution analysis (Gini coefficient)
 //!
 //! # Architecture
@@ -23,6 +24,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use rust_decimal::Decimal;
 
+use emergence_ledger::{DebtRegistry, Ledger};
 use emergence_types::{AgentId, LocationId, Resource};
 
 use crate::error::AgentError;
@@ -43,6 +45,9 @@ const MIN_TRADES_FOR_CURRENCY: usize = 5;
 /// Minimum trades per tick window for a location to be classified as a market.
 const MARKET_TRADE_THRESHOLD: u32 = 3;
 
+/// Minimum distinct debtors a creditor must be owed by to act as a bank.
+const BANK_DEBTOR_THRESHOLD: usize = 3;
+
 // ---------------------------------------------------------------------------
 // EconomicIndicator
 // ---------------------------------------------------------------------------
@@ -104,6 +109,22 @@ pub enum EconomicModel {
     Feudal,
 }
 
+// ---------------------------------------------------------------------------
+// CreditSystem
+// ---------------------------------------------------------------------------
+
+/// How credit is extended between agents, classified from the loans
+/// outstanding in the ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreditSystem {
+    /// No principal is outstanding between any agents.
+    NoCredit,
+    /// Agents lend to one another, but no creditor lends widely.
+    PersonalLending,
+    /// At least one creditor is owed by many debtors, acting as a bank.
+    Banking,
+}
+
 // ---------------------------------------------------------------------------
 // TradeRecord (internal)
 // ---------------------------------------------------------------------------
@@ -284,6 +305,31 @@ impl EconomicDetector {
         Ok(candidates)
     }
 
+    /// Adopt every detected currency in `ledger`, so its supply is minted
+    /// and burned from then on.
+    ///
+    /// Records a [`EconomicIndicator::CurrencyAdoption`] event for each
+    /// newly adopted currency and returns those currencies.
+    pub fn adopt_currencies(
+        &mut self,
+        current_tick: u64,
+        ledger: &mut Ledger,
+    ) -> Result<Vec<Resource>, AgentError> {
+        let mut adopted = Vec::new();
+        for (resource, ratio) in self.detect_currency(current_tick)? {
+            if ledger.adopt_currency(resource) {
+                self.events.push(EconomicEvent {
+                    tick: current_tick,
+                    indicator: EconomicIndicator::CurrencyAdoption,
+                    agents_involved: Vec::new(),
+                    details: format!("{resource:?} adopted as currency ({ratio} of trades)"),
+                });
+                adopted.push(resource);
+            }
+        }
+        Ok(adopted)
+    }
+
     /// Detect employment patterns.
     ///
     /// Employment is detected when one agent repeatedly gives resources
@@ -368,6 +414,41 @@ impl EconomicDetector {
             .collect()
     }
 
+    /// Detect lending from the loans outstanding in the ledger.
+    ///
+    /// Returns the (creditor, debtor) pairs with principal outstanding.
+    pub fn detect_lending(&self, debts: &DebtRegistry) -> Vec<(AgentId, AgentId)> {
+        debts
+            .loans()
+            .map(|((creditor, debtor), _)| (AgentId::from(creditor), AgentId::from(debtor)))
+            .collect()
+    }
+
+    /// Classify the credit system from the loans outstanding in the ledger.
+    ///
+    /// Classification logic:
+    /// - No principal outstanding: `NoCredit`
+    /// - A creditor owed by `BANK_DEBTOR_THRESHOLD` or more debtors: `Banking`
+    /// - Otherwise: `PersonalLending`
+    pub fn classify_credit_system(&self, debts: &DebtRegistry) -> CreditSystem {
+        let lending = self.detect_lending(debts);
+        if lending.is_empty() {
+            return CreditSystem::NoCredit;
+        }
+
+        let mut debtors_per_creditor: BTreeMap<AgentId, usize> = BTreeMap::new();
+        for (creditor, _) in &lending {
+            let count = debtors_per_creditor.entry(*creditor).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+
+        if debtors_per_creditor.values().any(|count| *count >= BANK_DEBTOR_THRESHOLD) {
+            CreditSystem::Banking
+        } else {
+            CreditSystem::PersonalLending
+        }
+    }
+
     /// Classify the overall economic model based on detected patterns.
     ///
     /// Classification logic:
@@ -931,6 +1012,27 @@ mod tests {
         assert_eq!(result.unwrap_or(EconomicModel::Subsistence), EconomicModel::MarketEconomy);
     }
 
+    #[test]
+    fn detected_currency_is_adopted_by_the_ledger_once() {
+        let mut detector = EconomicDetector::new(100);
+        let loc = LocationId::new();
+        let goods = [Resource::Wood, Resource::Stone, Resource::Fiber];
+        for (i, good) in (0_u64..6).zip(goods.into_iter().cycle()) {
+            let (a, b) = (AgentId::new(), AgentId::new());
+            make_trade(&mut detector, i, a, b, Resource::CurrencyToken, 5, good, 3, loc);
+        }
+
+        let mut ledger = Ledger::new();
+        let adopted = detector.adopt_currencies(10, &mut ledger);
+        assert_eq!(adopted.ok(), Some(vec![Resource::CurrencyToken]));
+        assert!(ledger.is_currency(Resource::CurrencyToken));
+        assert_eq!(detector.events().len(), 1);
+
+        let again = detector.adopt_currencies(11, &mut ledger);
+        assert_eq!(again.ok(), Some(Vec::new()));
+        assert_eq!(detector.events().len(), 1);
+    }
+
     // -----------------------------------------------------------------------
     // Gini coefficient
     // -----------------------------------------------------------------------
@@ -1050,4 +1152,32 @@ mod tests {
         let candidates = result.unwrap_or_default();
         assert!(candidates.iter().any(|(r, _)| *r == Resource::CurrencyToken));
     }
+
+    // -----------------------------------------------------------------------
+    // Credit system classification
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn credit_system_from_outstanding_loans() {
+        let detector = EconomicDetector::new(100);
+        let mut ledger = Ledger::new();
+        assert_eq!(detector.classify_credit_system(ledger.debts()), CreditSystem::NoCredit);
+
+        let lender = AgentId::new().into_inner();
+        let borrowers = [AgentId::new(), AgentId::new(), AgentId::new()].map(AgentId::into_inner);
+        let wood = Resource::Wood;
+        for borrower in borrowers.iter().take(2) {
+            let _ = ledger.record_loan(1, wood, Decimal::TWO, lender, *borrower, None);
+        }
+        assert_eq!(detector.detect_lending(ledger.debts()).len(), 2);
+        assert_eq!(
+            detector.classify_credit_system(ledger.debts()),
+            CreditSystem::PersonalLending
+        );
+
+        for borrower in &borrowers {
+            let _ = ledger.record_loan(2, wood, Decimal::ONE, lender, *borrower, None);
+        }
+        assert_eq!(detector.classify_credit_system(ledger.debts()), CreditSystem::Banking);
+    }
 }
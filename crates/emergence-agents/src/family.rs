@@ -369,6 +369,45 @@ impl FamilyTracker {
         siblings
     }
 
+    /// Find the heir of an agent: a living spouse, or failing that a
+    /// living child.
+    ///
+    /// A spouse is a fellow partner in an active (undissolved) family unit;
+    /// with several, the earliest marriage wins. Among children the eldest
+    /// living child inherits. Returns `None` if no such family member is
+    /// alive.
+    pub fn heir_of(&self, agent_id: AgentId) -> Option<AgentId> {
+        let spouse = self
+            .agent_to_units
+            .get(&agent_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|unit_id| self.units.get(unit_id))
+            .filter(|unit| unit.dissolved_at_tick.is_none())
+            .filter(|unit| unit.members.get(&agent_id) == Some(&FamilyRole::Partner))
+            .find_map(|unit| {
+                unit.members
+                    .iter()
+                    .filter(|(member, role)| {
+                        **member != agent_id
+                            && **role == FamilyRole::Partner
+                            && self.alive_agents.contains(member)
+                    })
+                    .map(|(member, _)| *member)
+                    .min()
+            });
+        if spouse.is_some() {
+            return spouse;
+        }
+
+        // Agent IDs are time-ordered, so the smallest is the eldest.
+        self.parent_to_children
+            .get(&agent_id)?
+            .iter()
+            .find(|child| self.alive_agents.contains(child))
+            .copied()
+    }
+
     /// Compute the distribution of family sizes across all active units.
     ///
     /// Returns a map from family size (number of members) to count of
@@ -490,6 +529,42 @@ mod tests {
     // Marriage tests
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn heir_is_a_living_spouse_then_the_eldest_living_child() {
+        let mut tracker = FamilyTracker::new();
+        let parent = AgentId::new();
+        let spouse = AgentId::new();
+        let elder = AgentId::new();
+        let younger = AgentId::new();
+        for agent in [parent, spouse, elder, younger] {
+            tracker.register_alive(agent);
+        }
+        tracker.record_marriage(parent, spouse, 10);
+        tracker.record_birth(elder, parent, spouse, 1, 20);
+        tracker.record_birth(younger, parent, spouse, 1, 30);
+
+        assert_eq!(tracker.heir_of(parent), Some(spouse));
+        tracker.mark_dead(spouse);
+        assert_eq!(tracker.heir_of(parent), Some(elder));
+        tracker.mark_dead(elder);
+        assert_eq!(tracker.heir_of(parent), Some(younger));
+        tracker.mark_dead(younger);
+        assert_eq!(tracker.heir_of(parent), None);
+    }
+
+    #[test]
+    fn divorced_partners_do_not_inherit() {
+        let mut tracker = FamilyTracker::new();
+        let agent_a = AgentId::new();
+        let agent_b = AgentId::new();
+        tracker.register_alive(agent_a);
+        tracker.register_alive(agent_b);
+        let unit_id = tracker.record_marriage(agent_a, agent_b, 10);
+        assert!(tracker.record_divorce(unit_id, 20).is_ok());
+
+        assert_eq!(tracker.heir_of(agent_a), None);
+    }
+
     #[test]
     fn record_marriage_creates_unit() {
         let mut tracker = FamilyTracker::new();
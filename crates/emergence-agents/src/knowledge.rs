@@ -234,6 +234,7 @@ impl TechTree {
         add("pottery", &["gather_stone", "build_campfire"]);
         add("animal_tracking", &["perceive", "move"]);
         add("basic_medicine", &["gather_food", "observe_seasons"]);
+        add("weather_lore", &["observe_seasons", "perceive"]);
         add("build_hut", &["build_lean_to", "gather_stone"]);
         add("build_storage", &["build_lean_to", "gather_stone"]);
         add("oral_tradition", &["basic_communication", "group_formation"]);
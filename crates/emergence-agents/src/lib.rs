@@ -24,7 +24,9 @@
 //! - [`governance`] -- Governance structure tracking and classification
 //! - [`inventory`] -- Inventory (wallet) operations with carry capacity
 //! - [`knowledge`] -- Knowledge base, tech tree, seed knowledge, discovery mechanics
+//! - [`market`] -- Standing buy and sell orders on a market's order book, matched each tick
 //! - [`memory`] -- Tiered memory storage, compression, and perception filtering
+//! - [`metrics`] -- Action and rejection counters for the Observer's `/metrics` endpoint
 //! - [`persuasion`] -- Persuasion mechanics: belief change, recruitment, allegiance shifts
 //! - [`propaganda`] -- Persistent public declarations at locations that influence newcomers
 //! - [`reputation`] -- Observable reputation system: tags, observations, decay, perception summaries
@@ -51,7 +53,9 @@ pub mod family;
 pub mod governance;
 pub mod inventory;
 pub mod knowledge;
+pub mod market;
 pub mod memory;
+pub mod metrics;
 pub mod persuasion;
 pub mod propaganda;
 pub mod reputation;
@@ -75,6 +79,9 @@ pub use trade::{
     trade_accept, trade_offer, trade_reject, validate_trade_offer_location,
     validate_trade_offer_resources,
 };
+pub use market::{
+    DEFAULT_ORDER_EXPIRY_TICKS, OrderBook, OrderFill, OrderTerms, order_escrow, post_order,
+};
 pub use memory::{
     CompressionRecord, MemoryConfig, MemoryStore, find_reflection_triggers, importance_score,
 };
@@ -109,7 +116,7 @@ pub use governance::{
 };
 pub use family::{FamilyBond, FamilyRole, FamilyTracker, FamilyUnit};
 pub use economy_detection::{
-    EconomicDetector, EconomicEvent, EconomicIndicator, EconomicModel,
+    CreditSystem, EconomicDetector, EconomicEvent, EconomicIndicator, EconomicModel,
 };
 pub use crime_justice::{
     CrimeRecord, CrimeTracker, CrimeType, JusticePattern, PunishmentRecord, PunishmentType,
@@ -0,0 +1,648 @@
+//! Standing orders on a market's order book.
+//!
+//! A Market structure keeps an [`OrderBook`] of buy and sell orders, so
+//! agents can trade with counterparties who are not deciding in the same
+//! tick, or not even present:
+//!
+//! 1. [`post_order`] -- Agent posts a buy or sell order (energy cost: 2).
+//!    A sell order escrows the goods; a buy order escrows the most it
+//!    could pay.
+//! 2. [`OrderBook::match_orders`] -- At tick resolution, crossing orders at
+//!    the same market fill against each other.
+//! 3. [`OrderBook::expire_orders`] -- Orders past their `expires_at_tick`
+//!    are withdrawn and their escrow returned.
+//!
+//! # Matching
+//!
+//! For each market and pair of resources, the highest buy meets the lowest
+//! sell while the buy's limit is at least the sell's. Ties go to the
+//! earlier order. A fill trades at the price of whichever order was posted
+//! first, and fills as much as both orders still want; a buyer who bid
+//! above that price gets the difference back. An agent's orders never fill
+//! against each other.
+//!
+//! # Ledger Integration
+//!
+//! Like a direct trade (see [`crate::trade`]), each order's escrow is an
+//! [`Escrow`] entity named by the order ID. A fill releases the goods from
+//! the sell order's escrow to the buyer, and the payment (and any refund)
+//! from the buy order's escrow, as one [`Ledger::record_batch`] tagged with
+//! a fresh [`LedgerTag::Trade`]. Each fill is reported as a
+//! [`TradeCompletedDetails`] with the seller as `agent_a`.
+//!
+//! [`Escrow`]: LedgerEntryType::Escrow
+//! [`Ledger::record_batch`]: emergence_ledger::Ledger::record_batch
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rust_decimal::Decimal;
+
+use emergence_ledger::{Ledger, TransactionBatch, TransactionBuilder};
+use emergence_types::{
+    ActionOutcome, ActionParameters, ActionType, AgentId, AgentState, EntityType,
+    LedgerEntryType, LedgerTag, OrderId, OrderSide, Resource, StandingOrder, StructureId,
+    TradeCompletedDetails, TradeId,
+};
+
+use crate::actions::costs;
+use crate::error::AgentError;
+use crate::inventory;
+use crate::trade::TradeError;
+use crate::vitals;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Default number of ticks a standing order stays on the book.
+pub const DEFAULT_ORDER_EXPIRY_TICKS: u64 = 10;
+
+// ---------------------------------------------------------------------------
+// Posting orders
+// ---------------------------------------------------------------------------
+
+/// The terms of an order an agent wants to post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderTerms {
+    /// The market to post the order at.
+    pub market_id: StructureId,
+    /// Whether the order buys or sells `resource`.
+    pub side: OrderSide,
+    /// The resource bought or sold.
+    pub resource: Resource,
+    /// How much of `resource` to buy or sell.
+    pub quantity: u32,
+    /// The resource paid in.
+    pub price_resource: Resource,
+    /// The limit price per unit of `resource`, in `price_resource`.
+    pub unit_price: u32,
+}
+
+impl OrderTerms {
+    /// Return the terms of a `PostOrder` action, or `None` for any other.
+    pub const fn from_parameters(params: &ActionParameters) -> Option<Self> {
+        let ActionParameters::PostOrder {
+            market_id,
+            side,
+            resource,
+            quantity,
+            price_resource,
+            unit_price,
+        } = *params
+        else {
+            return None;
+        };
+        Some(Self {
+            market_id,
+            side,
+            resource,
+            quantity,
+            price_resource,
+            unit_price,
+        })
+    }
+}
+
+/// Return what posting an order with `terms` puts in escrow: the goods for
+/// a sell order, or the full payment at the limit price for a buy order.
+///
+/// Returns `None` if the payment overflows.
+pub fn order_escrow(terms: &OrderTerms) -> Option<(Resource, u32)> {
+    match terms.side {
+        OrderSide::Sell => Some((terms.resource, terms.quantity)),
+        OrderSide::Buy => terms
+            .quantity
+            .checked_mul(terms.unit_price)
+            .map(|payment| (terms.price_resource, payment)),
+    }
+}
+
+/// Post a standing order for `agent` at a market.
+///
+/// Validates that:
+/// - The quantity and price are non-zero and the two resources differ.
+/// - The agent holds what the order escrows (see [`order_escrow`]).
+///
+/// On success, deducts energy, moves the escrowed resources from the
+/// agent's inventory into the order's escrow, and returns a
+/// [`StandingOrder`] ready to be added to the market's [`OrderBook`] along
+/// with the action outcome.
+///
+/// The caller is responsible for verifying that the market is at the
+/// agent's location (validation pipeline stage 3) before calling this
+/// function.
+///
+/// # Errors
+///
+/// Returns [`TradeError::Agent`] if the order is malformed or the agent
+/// lacks resources, or [`TradeError::Ledger`] if the escrow entry fails
+/// validation.
+pub fn post_order(
+    agent: &mut AgentState,
+    terms: &OrderTerms,
+    ledger: &mut Ledger,
+    current_tick: u64,
+    expiry_ticks: u64,
+) -> Result<(StandingOrder, ActionOutcome), TradeError> {
+    if terms.quantity == 0 || terms.unit_price == 0 || terms.resource == terms.price_resource {
+        return Err(TradeError::Agent(AgentError::ArithmeticOverflow {
+            context: String::from("market order is malformed"),
+        }));
+    }
+    let (escrowed, amount) = order_escrow(terms).ok_or_else(|| AgentError::ArithmeticOverflow {
+        context: String::from("market order payment overflow"),
+    })?;
+    if !inventory::has_resource(&agent.inventory, escrowed, amount) {
+        return Err(TradeError::Agent(AgentError::InsufficientResource {
+            resource: escrowed,
+            requested: amount,
+            available: agent.inventory.get(&escrowed).copied().unwrap_or(0),
+        }));
+    }
+
+    let order_id = OrderId::new();
+    let expires_at_tick = current_tick
+        .checked_add(expiry_ticks)
+        .ok_or_else(|| AgentError::ArithmeticOverflow {
+            context: String::from("market order expiry tick overflow"),
+        })?;
+
+    // Hold what the order gives up in escrow, leaving the agent untouched
+    // if the ledger refuses it
+    let escrow = order_id.into_inner();
+    let mut batch = TransactionBatch::new();
+    batch.push(
+        TransactionBuilder::new(current_tick, LedgerEntryType::Escrow, escrowed)
+            .from(agent.agent_id.into_inner(), EntityType::Agent)
+            .to(escrow, EntityType::Escrow)
+            .quantity(Decimal::from(u64::from(amount)))
+            .reason("MARKET_ESCROW".to_owned())
+            .reference_id(escrow),
+    );
+    ledger.record_batch(batch).map_err(TradeError::Ledger)?;
+    inventory::remove_resource(&mut agent.inventory, escrowed, amount)?;
+
+    // Deduct energy
+    vitals::apply_energy_cost(agent, costs::energy_cost(ActionType::PostOrder));
+
+    let order = StandingOrder {
+        order_id,
+        agent_id: agent.agent_id,
+        market_id: terms.market_id,
+        side: terms.side,
+        resource: terms.resource,
+        quantity: terms.quantity,
+        price_resource: terms.price_resource,
+        unit_price: terms.unit_price,
+        placed_at_tick: current_tick,
+        expires_at_tick,
+    };
+
+    let mut resource_changes = BTreeMap::new();
+    resource_changes.insert(escrowed, i64::from(amount).saturating_neg());
+    let outcome = ActionOutcome {
+        resource_changes,
+        energy_spent: costs::energy_cost(ActionType::PostOrder),
+        skill_xp: BTreeMap::new(),
+        details: serde_json::json!({
+            "order_id": order_id.to_string(),
+            "market_id": terms.market_id.to_string(),
+            "side": format!("{:?}", terms.side),
+            "resource": format!("{:?}", terms.resource),
+            "quantity": terms.quantity,
+            "price_resource": format!("{:?}", terms.price_resource),
+            "unit_price": terms.unit_price,
+            "expires_at_tick": expires_at_tick,
+        }),
+    };
+
+    Ok((order, outcome))
+}
+
+// ---------------------------------------------------------------------------
+// Order book
+// ---------------------------------------------------------------------------
+
+/// One fill between a buy order and a sell order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderFill {
+    /// The market the orders stood at.
+    pub market_id: StructureId,
+    /// The buy order that was filled, wholly or in part.
+    pub buy_order: OrderId,
+    /// The sell order that was filled, wholly or in part.
+    pub sell_order: OrderId,
+    /// The price per unit the fill traded at.
+    pub unit_price: u32,
+    /// Completed trade details for event emission, with the seller as
+    /// `agent_a`.
+    pub completed: TradeCompletedDetails,
+}
+
+/// The standing orders at every market.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderBook {
+    /// Every open order, keyed by ID.
+    orders: BTreeMap<OrderId, StandingOrder>,
+}
+
+impl OrderBook {
+    /// Create an empty order book.
+    pub const fn new() -> Self {
+        Self {
+            orders: BTreeMap::new(),
+        }
+    }
+
+    /// Add a posted order to the book.
+    pub fn insert(&mut self, order: StandingOrder) {
+        self.orders.insert(order.order_id, order);
+    }
+
+    /// Return an open order.
+    pub fn get(&self, order_id: OrderId) -> Option<&StandingOrder> {
+        self.orders.get(&order_id)
+    }
+
+    /// Return the open orders at `market_id`, oldest first.
+    pub fn orders_at(&self, market_id: StructureId) -> Vec<&StandingOrder> {
+        self.orders
+            .values()
+            .filter(|order| order.market_id == market_id)
+            .collect()
+    }
+
+    /// Return the agents with open orders.
+    pub fn traders(&self) -> BTreeSet<AgentId> {
+        self.orders.values().map(|order| order.agent_id).collect()
+    }
+
+    /// Return the number of open orders.
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Return whether there are no open orders.
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Fill every pair of crossing orders, settling each fill through the
+    /// ledger and the agents' inventories.
+    ///
+    /// Orders of agents missing from `agents` (such as the dead) are left
+    /// unmatched. Filled goods and payments are delivered even past the
+    /// recipient's carrying capacity, as they were bought or sold for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TradeError::Agent`] on arithmetic overflow, or
+    /// [`TradeError::Ledger`] if a settlement entry fails validation.
+    pub fn match_orders(
+        &mut self,
+        agents: &mut BTreeMap<AgentId, AgentState>,
+        ledger: &mut Ledger,
+        current_tick: u64,
+    ) -> Result<Vec<OrderFill>, TradeError> {
+        let books: BTreeSet<(StructureId, Resource, Resource)> = self
+            .orders
+            .values()
+            .map(|order| (order.market_id, order.resource, order.price_resource))
+            .collect();
+
+        let mut fills = Vec::new();
+        for book in books {
+            while let Some((buy_id, sell_id)) = self.best_cross(book, agents) {
+                let fill = self.fill(buy_id, sell_id, agents, ledger, current_tick)?;
+                fills.push(fill);
+            }
+        }
+        Ok(fills)
+    }
+
+    /// Withdraw every order past its expiry, returning its escrow to the
+    /// agent who posted it.
+    ///
+    /// Returns the expired orders.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TradeError::Agent`] on arithmetic overflow, or
+    /// [`TradeError::Ledger`] if an escrow release fails validation.
+    pub fn expire_orders(
+        &mut self,
+        agents: &mut BTreeMap<AgentId, AgentState>,
+        ledger: &mut Ledger,
+        current_tick: u64,
+    ) -> Result<Vec<StandingOrder>, TradeError> {
+        let expired: Vec<OrderId> = self
+            .orders
+            .values()
+            .filter(|order| current_tick >= order.expires_at_tick)
+            .map(|order| order.order_id)
+            .collect();
+
+        let mut withdrawn = Vec::new();
+        for order_id in expired {
+            let Some(order) = self.orders.remove(&order_id) else {
+                continue;
+            };
+            let (resource, amount) = remaining_escrow(&order)?;
+            let mut batch = TransactionBatch::new();
+            batch.push(release_entry(current_tick, &order, resource, amount, order.agent_id));
+            ledger.record_batch(batch).map_err(TradeError::Ledger)?;
+            if let Some(agent) = agents.get_mut(&order.agent_id) {
+                credit(agent, resource, amount);
+            }
+            withdrawn.push(order);
+        }
+        Ok(withdrawn)
+    }
+
+    /// Find the best buy and sell orders on one book that cross, if any.
+    ///
+    /// Buys are tried from the highest price down, each against the
+    /// lowest-priced sell by another agent; ties go to the older order.
+    fn best_cross(
+        &self,
+        (market_id, resource, price_resource): (StructureId, Resource, Resource),
+        agents: &BTreeMap<AgentId, AgentState>,
+    ) -> Option<(OrderId, OrderId)> {
+        let open: Vec<&StandingOrder> = self
+            .orders
+            .values()
+            .filter(|order| {
+                order.market_id == market_id
+                    && order.resource == resource
+                    && order.price_resource == price_resource
+                    && order.quantity > 0
+                    && agents.contains_key(&order.agent_id)
+            })
+            .collect();
+
+        let mut buys: Vec<&StandingOrder> =
+            open.iter().copied().filter(|o| o.side == OrderSide::Buy).collect();
+        buys.sort_by_key(|o| (std::cmp::Reverse(o.unit_price), o.placed_at_tick, o.order_id));
+        let mut sells: Vec<&StandingOrder> =
+            open.iter().copied().filter(|o| o.side == OrderSide::Sell).collect();
+        sells.sort_by_key(|o| (o.unit_price, o.placed_at_tick, o.order_id));
+
+        buys.iter().find_map(|buy| {
+            sells
+                .iter()
+                .find(|sell| sell.agent_id != buy.agent_id)
+                .filter(|sell| sell.unit_price <= buy.unit_price)
+                .map(|sell| (buy.order_id, sell.order_id))
+        })
+    }
+
+    /// Fill a crossing bid and ask order as far as both allow.
+    fn fill(
+        &mut self,
+        buy_id: OrderId,
+        sell_id: OrderId,
+        agents: &mut BTreeMap<AgentId, AgentState>,
+        ledger: &mut Ledger,
+        current_tick: u64,
+    ) -> Result<OrderFill, TradeError> {
+        let (Some(bid), Some(ask)) = (self.orders.get(&buy_id), self.orders.get(&sell_id))
+        else {
+            return Err(TradeError::Agent(AgentError::ArithmeticOverflow {
+                context: String::from("crossing market order vanished"),
+            }));
+        };
+        let (bid, ask) = (bid.clone(), ask.clone());
+
+        // The order posted first sets the price.
+        let bid_first = (bid.placed_at_tick, bid.order_id) < (ask.placed_at_tick, ask.order_id);
+        let unit_price = if bid_first { bid.unit_price } else { ask.unit_price };
+        let quantity = bid.quantity.min(ask.quantity);
+        let overflow = || AgentError::ArithmeticOverflow {
+            context: String::from("market fill payment overflow"),
+        };
+        let payment = quantity.checked_mul(unit_price).ok_or_else(overflow)?;
+        let refund = bid
+            .unit_price
+            .checked_sub(unit_price)
+            .and_then(|over| over.checked_mul(quantity))
+            .ok_or_else(overflow)?;
+
+        let trade_id = TradeId::new();
+        let mut batch = TransactionBatch::new();
+        batch.push(
+            release_entry(current_tick, &ask, ask.resource, quantity, bid.agent_id)
+                .tag(LedgerTag::Trade(trade_id)),
+        );
+        batch.push(
+            release_entry(current_tick, &bid, bid.price_resource, payment, ask.agent_id)
+                .tag(LedgerTag::Trade(trade_id)),
+        );
+        if refund > 0 {
+            batch.push(
+                release_entry(current_tick, &bid, bid.price_resource, refund, bid.agent_id)
+                    .tag(LedgerTag::Trade(trade_id)),
+            );
+        }
+        ledger.record_batch(batch).map_err(TradeError::Ledger)?;
+
+        if let Some(buyer) = agents.get_mut(&bid.agent_id) {
+            credit(buyer, bid.resource, quantity);
+            credit(buyer, bid.price_resource, refund);
+        }
+        if let Some(seller) = agents.get_mut(&ask.agent_id) {
+            credit(seller, ask.price_resource, payment);
+        }
+        self.reduce(buy_id, quantity);
+        self.reduce(sell_id, quantity);
+
+        Ok(OrderFill {
+            market_id: bid.market_id,
+            buy_order: buy_id,
+            sell_order: sell_id,
+            unit_price,
+            completed: TradeCompletedDetails {
+                trade_id,
+                agent_a: ask.agent_id,
+                agent_b: bid.agent_id,
+                gave: BTreeMap::from([(ask.resource, quantity)]),
+                received: BTreeMap::from([(ask.price_resource, payment)]),
+            },
+        })
+    }
+
+    /// Take `filled` off an order's quantity, removing it once complete.
+    fn reduce(&mut self, order_id: OrderId, filled: u32) {
+        if let Some(order) = self.orders.get_mut(&order_id) {
+            order.quantity = order.quantity.saturating_sub(filled);
+            if order.quantity == 0 {
+                self.orders.remove(&order_id);
+            }
+        }
+    }
+}
+
+/// Return what an order still holds in escrow.
+fn remaining_escrow(order: &StandingOrder) -> Result<(Resource, u32), TradeError> {
+    let terms = OrderTerms {
+        market_id: order.market_id,
+        side: order.side,
+        resource: order.resource,
+        quantity: order.quantity,
+        price_resource: order.price_resource,
+        unit_price: order.unit_price,
+    };
+    order_escrow(&terms).ok_or_else(|| {
+        TradeError::Agent(AgentError::ArithmeticOverflow {
+            context: String::from("market order escrow overflow"),
+        })
+    })
+}
+
+/// Build the ledger entry releasing `quantity` of `resource` from `order`'s
+/// escrow to `to`.
+fn release_entry(
+    tick: u64,
+    order: &StandingOrder,
+    resource: Resource,
+    quantity: u32,
+    to: AgentId,
+) -> TransactionBuilder {
+    let escrow = order.order_id.into_inner();
+    TransactionBuilder::new(tick, LedgerEntryType::EscrowRelease, resource)
+        .from(escrow, EntityType::Escrow)
+        .to(to.into_inner(), EntityType::Agent)
+        .quantity(Decimal::from(u64::from(quantity)))
+        .reason("MARKET_ESCROW_RELEASE".to_owned())
+        .reference_id(escrow)
+}
+
+/// Add `quantity` of `resource` to an agent's inventory, past its carrying
+/// capacity if need be.
+fn credit(agent: &mut AgentState, resource: Resource, quantity: u32) {
+    if quantity > 0 {
+        let held = agent.inventory.entry(resource).or_insert(0);
+        *held = held.saturating_add(quantity);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::{LocationId, StructureId};
+
+    use super::*;
+
+    fn make_agent(resource: Resource, quantity: u32) -> AgentState {
+        AgentState {
+            agent_id: AgentId::new(),
+            energy: 80,
+            health: 100,
+            hunger: 0,
+            thirst: 0,
+            age: 0,
+            born_at_tick: 0,
+            location_id: LocationId::new(),
+            destination_id: None,
+            travel_progress: 0,
+            inventory: BTreeMap::from([(resource, quantity)]),
+            carry_capacity: 50,
+            knowledge: BTreeSet::new(),
+            skills: BTreeMap::new(),
+            skill_xp: BTreeMap::new(),
+            goals: Vec::new(),
+            relationships: BTreeMap::new(),
+            memory: Vec::new(),
+        }
+    }
+
+    fn terms(market: StructureId, side: OrderSide, quantity: u32, unit_price: u32) -> OrderTerms {
+        OrderTerms {
+            market_id: market,
+            side,
+            resource: Resource::Wood,
+            quantity,
+            price_resource: Resource::CurrencyToken,
+            unit_price,
+        }
+    }
+
+    #[test]
+    fn orders_fill_at_the_resting_price_when_counterparties_are_apart() {
+        let market = StructureId::new();
+        let mut ledger = Ledger::new();
+        let mut seller = make_agent(Resource::Wood, 10);
+        let mut buyer = make_agent(Resource::CurrencyToken, 30);
+        let mut book = OrderBook::new();
+
+        let wood = terms(market, OrderSide::Sell, 10, 2);
+        let (sell, _) = post_order(&mut seller, &wood, &mut ledger, 1, 5).unwrap();
+        book.insert(sell);
+        let mut agents = BTreeMap::from([(seller.agent_id, seller.clone())]);
+        // The seller's order waits alone.
+        assert!(book.match_orders(&mut agents, &mut ledger, 1).unwrap().is_empty());
+
+        let bid = terms(market, OrderSide::Buy, 6, 3);
+        let (buy, _) = post_order(&mut buyer, &bid, &mut ledger, 2, 5).unwrap();
+        assert_eq!(buyer.inventory.get(&Resource::CurrencyToken), Some(&12));
+        book.insert(buy);
+        agents.insert(buyer.agent_id, buyer.clone());
+
+        let fills = book.match_orders(&mut agents, &mut ledger, 2).unwrap();
+        assert_eq!(fills.len(), 1);
+        let fill = fills.first().unwrap();
+        assert_eq!(fill.unit_price, 2);
+        assert_eq!(fill.completed.gave, BTreeMap::from([(Resource::Wood, 6)]));
+        assert_eq!(fill.completed.received, BTreeMap::from([(Resource::CurrencyToken, 12)]));
+
+        let buyer = agents.get(&buyer.agent_id).unwrap();
+        assert_eq!(buyer.inventory.get(&Resource::Wood), Some(&6));
+        assert_eq!(buyer.inventory.get(&Resource::CurrencyToken), Some(&18));
+        let seller_after = agents.get(&seller.agent_id).unwrap();
+        assert_eq!(seller_after.inventory.get(&Resource::CurrencyToken), Some(&12));
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.orders_at(market).first().map(|o| o.quantity), Some(4));
+        assert_eq!(
+            ledger.verify_conservation_strict(2),
+            emergence_ledger::ConservationResult::Balanced
+        );
+    }
+
+    #[test]
+    fn orders_that_do_not_cross_expire_back_to_their_owners() {
+        let market = StructureId::new();
+        let mut ledger = Ledger::new();
+        let mut seller = make_agent(Resource::Wood, 5);
+        let mut buyer = make_agent(Resource::CurrencyToken, 5);
+        let mut book = OrderBook::new();
+        let ask = terms(market, OrderSide::Sell, 5, 3);
+        let (sell, _) = post_order(&mut seller, &ask, &mut ledger, 1, 2).unwrap();
+        let bid = terms(market, OrderSide::Buy, 5, 1);
+        let (buy, _) = post_order(&mut buyer, &bid, &mut ledger, 1, 2).unwrap();
+        book.insert(sell);
+        book.insert(buy);
+        let mut agents =
+            BTreeMap::from([(seller.agent_id, seller.clone()), (buyer.agent_id, buyer.clone())]);
+
+        assert!(book.match_orders(&mut agents, &mut ledger, 2).unwrap().is_empty());
+        assert!(book.expire_orders(&mut agents, &mut ledger, 2).unwrap().is_empty());
+        assert_eq!(book.expire_orders(&mut agents, &mut ledger, 3).unwrap().len(), 2);
+        assert!(book.is_empty());
+        let seller = agents.get(&seller.agent_id).unwrap();
+        assert_eq!(seller.inventory.get(&Resource::Wood), Some(&5));
+    }
+
+    #[test]
+    fn malformed_or_unaffordable_orders_are_refused() {
+        let market = StructureId::new();
+        let mut ledger = Ledger::new();
+        let mut buyer = make_agent(Resource::CurrencyToken, 5);
+        let no_price = terms(market, OrderSide::Buy, 5, 0);
+        assert!(post_order(&mut buyer, &no_price, &mut ledger, 1, 5).is_err());
+        let too_dear = terms(market, OrderSide::Buy, 5, 2);
+        assert!(matches!(
+            post_order(&mut buyer, &too_dear, &mut ledger, 1, 5),
+            Err(TradeError::Agent(AgentError::InsufficientResource { requested: 10, .. }))
+        ));
+        assert_eq!(order_escrow(&terms(market, OrderSide::Buy, u32::MAX, 2)), None);
+    }
+}
@@ -0,0 +1,18 @@
+//! Action pipeline metrics, exported through the Observer's `/metrics`
+//! endpoint (see [`emergence_metrics`]).
+
+use emergence_metrics::Counter;
+
+/// Actions submitted for validation, by action type.
+pub static ACTIONS: Counter = Counter::labeled(
+    "emergence_actions_total",
+    "Actions submitted for validation, by action type.",
+    "action",
+);
+
+/// Actions rejected by validation or lost to a conflict, by reason.
+pub static ACTION_REJECTIONS: Counter = Counter::labeled(
+    "emergence_action_rejections_total",
+    "Actions rejected by validation or conflict resolution, by reason.",
+    "reason",
+);
@@ -175,27 +175,42 @@ pub const fn is_action_restricted_for_immature(action: ActionType) -> bool {
         action,
         ActionType::Build
             | ActionType::Repair
+            | ActionType::Extinguish
             | ActionType::Demolish
+            | ActionType::UpgradeStructure
+            | ActionType::TransferOwnership
             | ActionType::ImproveRoute
+            | ActionType::BuildRoute
             | ActionType::TradeOffer
             | ActionType::TradeAccept
             | ActionType::TradeReject
+            | ActionType::PostOrder
             | ActionType::FormGroup
             | ActionType::Teach
             | ActionType::FarmPlant
             | ActionType::FarmHarvest
+            | ActionType::Fertilize
+            | ActionType::Slaughter
             | ActionType::Craft
             | ActionType::Mine
+            | ActionType::DeepenMine
+            | ActionType::Hunt
+            | ActionType::Fish
             | ActionType::Smelt
             | ActionType::Write
             | ActionType::Read
+            | ActionType::Chart
+            | ActionType::Deposit
+            | ActionType::Withdraw
             | ActionType::Claim
+            | ActionType::FoundSettlement
             | ActionType::Legislate
             | ActionType::Enforce
             | ActionType::Reproduce
             | ActionType::Steal
             | ActionType::Attack
             | ActionType::Intimidate
+            | ActionType::Breach
             | ActionType::Propose
             | ActionType::Vote
             | ActionType::Marry
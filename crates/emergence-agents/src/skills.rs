@@ -77,15 +77,31 @@ pub const XP_FARM_PLANT: u32 = 10;
 /// XP awarded on a successful farm harvest action.
 pub const XP_FARM_HARVEST: u32 = 10;
 
+/// XP awarded on a successful fertilize action.
+pub const XP_FERTILIZE: u32 = 5;
+
 /// XP awarded on a successful craft action.
 pub const XP_CRAFT: u32 = 10;
 
 /// XP awarded on a successful mine action.
 pub const XP_MINE: u32 = 10;
 
+/// XP awarded on a successful hunt action.
+pub const XP_HUNT: u32 = 10;
+
+/// XP awarded on a prospect action, whether or not it finds a deposit.
+pub const XP_PROSPECT: u32 = 10;
+
+/// XP awarded on a successful fish action.
+pub const XP_FISH: u32 = 10;
+
 /// XP awarded on a successful smelt action.
 pub const XP_SMELT: u32 = 10;
 
+/// Forecasting XP awarded when an agent who knows weather lore rests,
+/// watching the sky.
+pub const XP_FORECAST: u32 = 5;
+
 // ---------------------------------------------------------------------------
 // SkillSystem
 // ---------------------------------------------------------------------------
@@ -292,6 +308,29 @@ pub mod effects {
         base_yield.checked_add(bonus)
     }
 
+    /// Compute the modified hunting yield.
+    ///
+    /// Formula: `base_yield + (skill_level * 0.25)`
+    ///
+    /// Each animal is worth several units of meat, so hunting scales half
+    /// as fast as gathering: a level 4 hunter takes one extra animal.
+    ///
+    /// Returns `None` on arithmetic overflow.
+    pub fn hunting_yield(base_yield: u32, skill_level: u32) -> Option<u32> {
+        let bonus = skill_level.checked_div(4)?;
+        base_yield.checked_add(bonus)
+    }
+
+    /// Compute the modified fishing yield.
+    ///
+    /// Formula: `base_yield + (skill_level / 3)`
+    ///
+    /// Returns `None` on arithmetic overflow.
+    pub fn fishing_yield(base_yield: u32, skill_level: u32) -> Option<u32> {
+        let bonus = skill_level.checked_div(3)?;
+        base_yield.checked_add(bonus)
+    }
+
     /// Compute the modified building time.
     ///
     /// Formula: `base_time / (1 + skill_level * 0.1)`
@@ -402,6 +441,26 @@ pub mod effects {
             assert_eq!(mining_yield(2, 10), Some(7));
         }
 
+        // -------------------------------------------------------------------
+        // Hunting yield
+        // -------------------------------------------------------------------
+
+        #[test]
+        fn hunting_yield_level_7() {
+            // 1 + 7/4 = 1 + 1 = 2
+            assert_eq!(hunting_yield(1, 7), Some(2));
+        }
+
+        // -------------------------------------------------------------------
+        // Fishing yield
+        // -------------------------------------------------------------------
+
+        #[test]
+        fn fishing_yield_level_7() {
+            // 2 + 7/3 = 2 + 2 = 4
+            assert_eq!(fishing_yield(2, 7), Some(4));
+        }
+
         // -------------------------------------------------------------------
         // Building time
         // -------------------------------------------------------------------
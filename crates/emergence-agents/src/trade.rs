@@ -10,21 +10,31 @@
 //!
 //! # Ledger Integration
 //!
-//! A successful trade produces one [`LedgerEntry`] per resource per direction
-//! via [`Ledger::record_agent_transfer`]. The conservation law is maintained
-//! because every resource debited from one agent is credited to the other.
+//! Offering a trade reserves the offered resources: they leave the offerer's
+//! inventory and pass into an escrow entity named by the trade ID through
+//! [`Escrow`] entries. Accepting the trade releases them to the target and
+//! transfers the requested resources to the offerer; rejecting or expiring
+//! it releases them back to the offerer. Each step records one
+//! [`LedgerEntry`] per resource per direction, together via
+//! [`Ledger::record_batch`], so that a step is either fully in the ledger or
+//! not at all. The conservation law is maintained because every resource
+//! debited from one entity is credited to another. Every entry is tagged
+//! with [`LedgerTag::Trade`], so [`Ledger::entries_tagged`] returns a
+//! trade's whole history.
 //!
+//! [`Escrow`]: LedgerEntryType::Escrow
 //! [`LedgerEntry`]: emergence_types::LedgerEntry
-//! [`Ledger::record_agent_transfer`]: emergence_ledger::Ledger::record_agent_transfer
+//! [`Ledger::record_batch`]: emergence_ledger::Ledger::record_batch
+//! [`Ledger::entries_tagged`]: emergence_ledger::Ledger::entries_tagged
 
 use std::collections::BTreeMap;
 
 use rust_decimal::Decimal;
 
-use emergence_ledger::{AgentTransferParams, Ledger, LedgerError};
+use emergence_ledger::{Ledger, LedgerError, TransactionBatch, TransactionBuilder};
 use emergence_types::{
-    ActionOutcome, ActionType, AgentId, AgentState, PendingTrade, Resource,
-    TradeCompletedDetails, TradeFailReason, TradeFailedDetails, TradeId,
+    ActionOutcome, ActionType, AgentId, AgentState, EntityType, LedgerEntryType, LedgerTag,
+    PendingTrade, Resource, TradeCompletedDetails, TradeFailReason, TradeFailedDetails, TradeId,
 };
 
 use crate::actions::costs;
@@ -50,47 +60,52 @@ pub const DEFAULT_TRADE_EXPIRY_TICKS: u64 = 3;
 /// - The offerer has the offered resources in inventory.
 /// - The offerer has enough energy (2).
 ///
-/// On success, deducts energy from the offerer and returns a [`PendingTrade`]
-/// ready to be stored in `Dragonfly` along with the action outcome.
+/// On success, deducts energy from the offerer, moves the offered resources
+/// from its inventory into the trade's escrow, and returns a
+/// [`PendingTrade`] ready to be stored in `Dragonfly` along with the action
+/// outcome.
 ///
 /// The caller is responsible for verifying co-location (validation pipeline
 /// stage 3) before calling this function.
+///
+/// # Errors
+///
+/// Returns [`TradeError::Agent`] if the offer is malformed or the offerer
+/// lacks resources, or [`TradeError::Ledger`] if an escrow entry fails
+/// validation.
 pub fn trade_offer(
     offerer: &mut AgentState,
     target_id: AgentId,
     offer: &BTreeMap<Resource, u32>,
     request: &BTreeMap<Resource, u32>,
+    ledger: &mut Ledger,
     current_tick: u64,
     expiry_ticks: u64,
-) -> Result<(PendingTrade, ActionOutcome), AgentError> {
+) -> Result<(PendingTrade, ActionOutcome), TradeError> {
     // Validate non-empty maps
     if offer.is_empty() {
-        return Err(AgentError::ArithmeticOverflow {
+        return Err(TradeError::Agent(AgentError::ArithmeticOverflow {
             context: String::from("trade offer map is empty"),
-        });
+        }));
     }
     if request.is_empty() {
-        return Err(AgentError::ArithmeticOverflow {
+        return Err(TradeError::Agent(AgentError::ArithmeticOverflow {
             context: String::from("trade request map is empty"),
-        });
+        }));
     }
 
     // Validate offerer has the offered resources
     for (resource, &quantity) in offer {
         if !inventory::has_resource(&offerer.inventory, *resource, quantity) {
             let available = offerer.inventory.get(resource).copied().unwrap_or(0);
-            return Err(AgentError::InsufficientResource {
+            return Err(TradeError::Agent(AgentError::InsufficientResource {
                 resource: *resource,
                 requested: quantity,
                 available,
-            });
+            }));
         }
     }
 
-    // Deduct energy
-    vitals::apply_energy_cost(offerer, costs::energy_cost(ActionType::TradeOffer));
-
-    // Build pending trade
     let trade_id = TradeId::new();
     let expires_at_tick = current_tick
         .checked_add(expiry_ticks)
@@ -98,6 +113,31 @@ pub fn trade_offer(
             context: String::from("trade expiry tick overflow"),
         })?;
 
+    // Reserve the offered resources in escrow, leaving the offerer
+    // untouched if the ledger refuses it
+    let escrow = trade_id.into_inner();
+    let mut batch = TransactionBatch::new();
+    let mut resource_changes = BTreeMap::new();
+    for (resource, &quantity) in offer {
+        batch.push(
+            TransactionBuilder::new(current_tick, LedgerEntryType::Escrow, *resource)
+                .from(offerer.agent_id.into_inner(), EntityType::Agent)
+                .to(escrow, EntityType::Escrow)
+                .quantity(Decimal::from(u64::from(quantity)))
+                .reason("TRADE_ESCROW".to_owned())
+                .reference_id(escrow)
+                .tag(LedgerTag::Trade(trade_id)),
+        );
+        resource_changes.insert(*resource, i64::from(quantity).saturating_neg());
+    }
+    ledger.record_batch(batch).map_err(TradeError::Ledger)?;
+    for (resource, &quantity) in offer {
+        inventory::remove_resource(&mut offerer.inventory, *resource, quantity)?;
+    }
+
+    // Deduct energy
+    vitals::apply_energy_cost(offerer, costs::energy_cost(ActionType::TradeOffer));
+
     let pending = PendingTrade {
         trade_id,
         offerer_id: offerer.agent_id,
@@ -110,7 +150,7 @@ pub fn trade_offer(
     };
 
     let outcome = ActionOutcome {
-        resource_changes: BTreeMap::new(),
+        resource_changes,
         energy_spent: costs::energy_cost(ActionType::TradeOffer),
         skill_xp: BTreeMap::new(),
         details: serde_json::json!({
@@ -135,6 +175,12 @@ pub struct TradeAcceptResult {
     pub outcome: ActionOutcome,
     /// Completed trade details for event emission.
     pub completed: TradeCompletedDetails,
+    /// Map items that changed hands, as `(giver, recipient)` pairs.
+    ///
+    /// The caller must pass each pair to
+    /// [`KnownMapRegistry::hand_over_chart`](emergence_world::KnownMapRegistry::hand_over_chart)
+    /// so the recipient learns the geography drawn on the map.
+    pub charts_handed_over: Vec<(AgentId, AgentId)>,
 }
 
 /// Accept a pending trade and execute the resource swap through the ledger.
@@ -143,8 +189,9 @@ pub struct TradeAcceptResult {
 /// - The target agent has the requested resources.
 /// - Both agents are still at the same location as the trade.
 ///
-/// On success, transfers resources between inventories and records ledger
-/// entries for every resource in both directions.
+/// On success, releases the offered resources from escrow to the target,
+/// transfers the requested resources from the target to the offerer, and
+/// records ledger entries for every resource in both directions.
 ///
 /// # Errors
 ///
@@ -162,8 +209,8 @@ pub fn trade_accept(
         return Err(TradeError::NotCoLocated);
     }
 
-    // Verify both sides have the required resources (before any mutations)
-    validate_trade_inventories(offerer, target, trade)?;
+    // Verify the target has the requested resources (before any mutations)
+    validate_target_inventory(target, trade)?;
 
     // Deduct energy (0 for accept, but apply for consistency)
     vitals::apply_energy_cost(target, costs::energy_cost(ActionType::TradeAccept));
@@ -175,25 +222,11 @@ pub fn trade_accept(
     build_accept_outcome(trade)
 }
 
-/// Validate that both agents still hold the resources required for the trade.
+/// Validate that the target still holds the resources requested of it.
 ///
-/// Checks the offerer's inventory against `offered_resources` and the
-/// target's inventory against `requested_resources`. No mutations occur.
-fn validate_trade_inventories(
-    offerer: &AgentState,
-    target: &AgentState,
-    trade: &PendingTrade,
-) -> Result<(), TradeError> {
-    for (resource, &quantity) in &trade.offered_resources {
-        if !inventory::has_resource(&offerer.inventory, *resource, quantity) {
-            return Err(TradeError::OffererInsufficientResources {
-                resource: *resource,
-                needed: quantity,
-                available: offerer.inventory.get(resource).copied().unwrap_or(0),
-            });
-        }
-    }
-
+/// The offered resources are already held in escrow, so only the target's
+/// inventory is checked against `requested_resources`. No mutations occur.
+fn validate_target_inventory(target: &AgentState, trade: &PendingTrade) -> Result<(), TradeError> {
     for (resource, &quantity) in &trade.requested_resources {
         if !inventory::has_resource(&target.inventory, *resource, quantity) {
             return Err(TradeError::TargetInsufficientResources {
@@ -209,9 +242,9 @@ fn validate_trade_inventories(
 
 /// Execute bidirectional inventory transfers and record ledger entries.
 ///
-/// Transfers offered resources from offerer to target, and requested
-/// resources from target to offerer, recording a [`LedgerEntry`] for
-/// each resource direction.
+/// Releases offered resources from escrow to the target, and transfers
+/// requested resources from target to offerer, then records a
+/// [`LedgerEntry`] for each resource direction as one batch.
 ///
 /// [`LedgerEntry`]: emergence_types::LedgerEntry
 fn execute_resource_transfers(
@@ -221,26 +254,13 @@ fn execute_resource_transfers(
     ledger: &mut Ledger,
     current_tick: u64,
 ) -> Result<(), TradeError> {
-    let trade_ref_id = trade.trade_id.into_inner();
+    let mut batch = TransactionBatch::new();
 
-    // Offerer -> target for offered resources
+    // Escrow -> target for offered resources
     for (resource, &quantity) in &trade.offered_resources {
-        transfer_single_resource(
-            &mut offerer.inventory,
-            &mut target.inventory,
-            target.carry_capacity,
-            *resource,
-            quantity,
-        )?;
-        record_trade_ledger_entry(
-            ledger,
-            current_tick,
-            *resource,
-            quantity,
-            offerer.agent_id,
-            target.agent_id,
-            trade_ref_id,
-        )?;
+        inventory::add_resource(&mut target.inventory, target.carry_capacity, *resource, quantity)
+            .map_err(TradeError::Agent)?;
+        batch.push(escrow_release_entry(current_tick, *resource, quantity, trade, target.agent_id));
     }
 
     // Target -> offerer for requested resources
@@ -252,17 +272,17 @@ fn execute_resource_transfers(
             *resource,
             quantity,
         )?;
-        record_trade_ledger_entry(
-            ledger,
+        batch.push(trade_ledger_entry(
             current_tick,
             *resource,
             quantity,
             target.agent_id,
             offerer.agent_id,
-            trade_ref_id,
-        )?;
+            trade.trade_id,
+        ));
     }
 
+    ledger.record_batch(batch).map_err(TradeError::Ledger)?;
     Ok(())
 }
 
@@ -279,29 +299,66 @@ fn transfer_single_resource(
     Ok(())
 }
 
-/// Record a single agent-to-agent transfer in the ledger.
-fn record_trade_ledger_entry(
-    ledger: &mut Ledger,
+/// Build the ledger entry for a single agent-to-agent transfer.
+fn trade_ledger_entry(
     tick: u64,
     resource: Resource,
     quantity: u32,
     from: AgentId,
     to: AgentId,
-    reference_id: uuid::Uuid,
+    trade_id: TradeId,
+) -> TransactionBuilder {
+    TransactionBuilder::new(tick, LedgerEntryType::Transfer, resource)
+        .from(from.into_inner(), EntityType::Agent)
+        .to(to.into_inner(), EntityType::Agent)
+        .quantity(Decimal::from(u64::from(quantity)))
+        .reason("TRADE".to_owned())
+        .reference_id(trade_id.into_inner())
+        .tag(LedgerTag::Trade(trade_id))
+}
+
+/// Build the ledger entry releasing `quantity` of `resource` from `trade`'s
+/// escrow to `to`.
+fn escrow_release_entry(
+    tick: u64,
+    resource: Resource,
+    quantity: u32,
+    trade: &PendingTrade,
+    to: AgentId,
+) -> TransactionBuilder {
+    let escrow = trade.trade_id.into_inner();
+    TransactionBuilder::new(tick, LedgerEntryType::EscrowRelease, resource)
+        .from(escrow, EntityType::Escrow)
+        .to(to.into_inner(), EntityType::Agent)
+        .quantity(Decimal::from(u64::from(quantity)))
+        .reason("TRADE_ESCROW_RELEASE".to_owned())
+        .reference_id(escrow)
+        .tag(LedgerTag::Trade(trade.trade_id))
+}
+
+/// Return `trade`'s escrowed resources to the offerer and record the
+/// release.
+///
+/// The resources were the offerer's own, so they are returned even if the
+/// offerer has since filled its carrying capacity.
+fn return_escrow(
+    offerer: &mut AgentState,
+    trade: &PendingTrade,
+    ledger: &mut Ledger,
+    current_tick: u64,
 ) -> Result<(), TradeError> {
-    let decimal_qty = u64::from(quantity);
-    ledger
-        .record_agent_transfer(AgentTransferParams {
-            tick,
-            resource,
-            quantity: Decimal::from(decimal_qty),
-            from_agent: from.into_inner(),
-            to_agent: to.into_inner(),
-            reason: "TRADE".to_owned(),
-            reference_id: Some(reference_id),
-        })
-        .map(|_entry| ())
-        .map_err(TradeError::Ledger)
+    let mut batch = TransactionBatch::new();
+    for (resource, &quantity) in &trade.offered_resources {
+        let offerer_id = trade.offerer_id;
+        batch.push(escrow_release_entry(current_tick, *resource, quantity, trade, offerer_id));
+    }
+    ledger.record_batch(batch).map_err(TradeError::Ledger)?;
+
+    for (resource, &quantity) in &trade.offered_resources {
+        let held = offerer.inventory.entry(*resource).or_insert(0);
+        *held = held.saturating_add(quantity);
+    }
+    Ok(())
 }
 
 /// Build the [`ActionOutcome`] and [`TradeCompletedDetails`] for a successful
@@ -348,9 +405,18 @@ fn build_accept_outcome(trade: &PendingTrade) -> Result<TradeAcceptResult, Trade
         received: trade.requested_resources.clone(),
     };
 
+    let mut charts_handed_over = Vec::new();
+    if trade.offered_resources.contains_key(&Resource::Map) {
+        charts_handed_over.push((trade.offerer_id, trade.target_id));
+    }
+    if trade.requested_resources.contains_key(&Resource::Map) {
+        charts_handed_over.push((trade.target_id, trade.offerer_id));
+    }
+
     Ok(TradeAcceptResult {
         outcome,
         completed,
+        charts_handed_over,
     })
 }
 
@@ -358,14 +424,23 @@ fn build_accept_outcome(trade: &PendingTrade) -> Result<TradeAcceptResult, Trade
 // Trade reject
 // ---------------------------------------------------------------------------
 
-/// Reject a pending trade.
+/// Reject a pending trade, returning the escrowed resources to the offerer.
 ///
 /// Returns a [`TradeFailedDetails`] for event emission. Energy cost is 0.
 /// The caller is responsible for deleting the trade from `Dragonfly`.
+///
+/// # Errors
+///
+/// Returns [`TradeError::Ledger`] if an escrow release fails validation.
 pub fn trade_reject(
+    offerer: &mut AgentState,
     target: &mut AgentState,
     trade: &PendingTrade,
-) -> (ActionOutcome, TradeFailedDetails) {
+    ledger: &mut Ledger,
+    current_tick: u64,
+) -> Result<(ActionOutcome, TradeFailedDetails), TradeError> {
+    return_escrow(offerer, trade, ledger, current_tick)?;
+
     // Deduct energy (0 for reject)
     vitals::apply_energy_cost(target, costs::energy_cost(ActionType::TradeReject));
 
@@ -387,7 +462,7 @@ pub fn trade_reject(
         target_id: trade.target_id,
     };
 
-    (outcome, failed)
+    Ok((outcome, failed))
 }
 
 // ---------------------------------------------------------------------------
@@ -399,14 +474,26 @@ pub const fn is_trade_expired(trade: &PendingTrade, current_tick: u64) -> bool {
     current_tick >= trade.expires_at_tick
 }
 
-/// Build a [`TradeFailedDetails`] for an expired trade.
-pub const fn expire_trade(trade: &PendingTrade) -> TradeFailedDetails {
-    TradeFailedDetails {
+/// Expire a pending trade, returning the escrowed resources to the offerer.
+///
+/// Returns a [`TradeFailedDetails`] for event emission.
+///
+/// # Errors
+///
+/// Returns [`TradeError::Ledger`] if an escrow release fails validation.
+pub fn expire_trade(
+    offerer: &mut AgentState,
+    trade: &PendingTrade,
+    ledger: &mut Ledger,
+    current_tick: u64,
+) -> Result<TradeFailedDetails, TradeError> {
+    return_escrow(offerer, trade, ledger, current_tick)?;
+    Ok(TradeFailedDetails {
         trade_id: trade.trade_id,
         reason: TradeFailReason::Expired,
         offerer_id: trade.offerer_id,
         target_id: trade.target_id,
-    }
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -447,19 +534,6 @@ pub fn validate_trade_offer_resources(
 /// Errors specific to trade operations.
 #[derive(Debug, thiserror::Error)]
 pub enum TradeError {
-    /// The offerer does not have enough of a resource.
-    #[error(
-        "offerer lacks {resource:?}: needs {needed}, has {available}"
-    )]
-    OffererInsufficientResources {
-        /// The resource in question.
-        resource: Resource,
-        /// Quantity needed.
-        needed: u32,
-        /// Quantity available.
-        available: u32,
-    },
-
     /// The target does not have enough of a resource.
     #[error(
         "target lacks {resource:?}: needs {needed}, has {available}"
@@ -539,11 +613,13 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 3);
 
+        let mut ledger = Ledger::new();
         let result = trade_offer(
             &mut offerer,
             target_id,
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         );
@@ -560,6 +636,13 @@ mod tests {
         assert_eq!(pending.location_id, loc);
         assert_eq!(outcome.energy_spent, 2);
         assert_eq!(offerer.energy, 78); // 80 - 2
+
+        // The offered wood is held in escrow
+        assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(5));
+        assert_eq!(outcome.resource_changes.get(&Resource::Wood).copied(), Some(-5));
+        let escrow = pending.trade_id.into_inner();
+        assert_eq!(ledger.entity_balance(escrow, Resource::Wood), Decimal::new(5, 0));
+        assert_eq!(ledger.verify_conservation(1), ConservationResult::Balanced);
     }
 
     #[test]
@@ -573,16 +656,51 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 3);
 
+        let mut ledger = Ledger::new();
         let result = trade_offer(
             &mut offerer,
             AgentId::new(),
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         );
 
         assert!(result.is_err());
+        assert!(ledger.is_empty());
+        assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(2));
+    }
+
+    #[test]
+    fn trade_offer_leaves_offerer_untouched_when_ledger_refuses_escrow() {
+        let loc = LocationId::new();
+        let mut offerer = make_agent(80, loc);
+        offerer.inventory.insert(Resource::Wood, 5);
+
+        // The zero-quantity stone entry fails the escrow batch after the
+        // wood entry has been built.
+        let mut offer = BTreeMap::new();
+        offer.insert(Resource::Wood, 3);
+        offer.insert(Resource::Stone, 0);
+        let mut request = BTreeMap::new();
+        request.insert(Resource::Water, 2);
+
+        let mut ledger = Ledger::new();
+        let result = trade_offer(
+            &mut offerer,
+            AgentId::new(),
+            &offer,
+            &request,
+            &mut ledger,
+            1,
+            DEFAULT_TRADE_EXPIRY_TICKS,
+        );
+
+        assert!(matches!(result, Err(TradeError::Ledger(_))));
+        assert!(ledger.is_empty());
+        assert_eq!(offerer.energy, 80);
+        assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(5));
     }
 
     #[test]
@@ -593,11 +711,13 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 3);
 
+        let mut ledger = Ledger::new();
         let result = trade_offer(
             &mut offerer,
             AgentId::new(),
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         );
@@ -614,11 +734,13 @@ mod tests {
         offer.insert(Resource::Wood, 5);
         let request = BTreeMap::new(); // empty
 
+        let mut ledger = Ledger::new();
         let result = trade_offer(
             &mut offerer,
             AgentId::new(),
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         );
@@ -630,6 +752,29 @@ mod tests {
     // trade_accept tests
     // -----------------------------------------------------------------------
 
+    /// Offer `offered` for `requested` from `offerer` to `target` at tick 1.
+    fn offer_trade(
+        offerer: &mut AgentState,
+        target: &AgentState,
+        give: &[(Resource, u32)],
+        requested: &[(Resource, u32)],
+        ledger: &mut Ledger,
+    ) -> PendingTrade {
+        let offer: BTreeMap<Resource, u32> = give.iter().copied().collect();
+        let request: BTreeMap<Resource, u32> = requested.iter().copied().collect();
+        let (pending, _) = trade_offer(
+            offerer,
+            target.agent_id,
+            &offer,
+            &request,
+            ledger,
+            1,
+            DEFAULT_TRADE_EXPIRY_TICKS,
+        )
+        .unwrap();
+        pending
+    }
+
     #[test]
     fn trade_accept_swaps_resources() {
         let loc = LocationId::new();
@@ -639,23 +784,14 @@ mod tests {
         let mut target = make_agent(80, loc);
         target.inventory.insert(Resource::Stone, 10);
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc,
-        };
-
         let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
         let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
 
         assert!(result.is_ok());
@@ -668,8 +804,33 @@ mod tests {
         assert_eq!(target.inventory.get(&Resource::Stone).copied(), Some(7));
         assert_eq!(target.inventory.get(&Resource::Wood).copied(), Some(5));
 
-        // Ledger should have 2 entries (wood offerer->target, stone target->offerer)
-        assert_eq!(ledger.len(), 2);
+        // Ledger should have 3 entries (wood offerer->escrow, wood
+        // escrow->target, stone target->offerer)
+        assert_eq!(ledger.len(), 3);
+        let escrow = trade.trade_id.into_inner();
+        assert_eq!(ledger.entity_balance(escrow, Resource::Wood), Decimal::ZERO);
+        assert!(result.unwrap().charts_handed_over.is_empty());
+    }
+
+    #[test]
+    fn trading_a_map_hands_over_its_chart() {
+        let loc = LocationId::new();
+        let mut offerer = make_agent(80, loc);
+        offerer.inventory.insert(Resource::Wood, 10);
+        let mut target = make_agent(80, loc);
+        target.inventory.insert(Resource::Map, 1);
+
+        let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 4)],
+            &[(Resource::Map, 1)],
+            &mut ledger,
+        );
+        let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2).unwrap();
+        assert_eq!(result.charts_handed_over, vec![(target.agent_id, offerer.agent_id)]);
+        assert_eq!(offerer.inventory.get(&Resource::Map).copied(), Some(1));
     }
 
     #[test]
@@ -682,35 +843,28 @@ mod tests {
         let mut target = make_agent(80, loc);
         target.inventory.insert(Resource::Stone, 10);
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        offered.insert(Resource::FoodBerry, 2);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc,
-        };
-
         let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5), (Resource::FoodBerry, 2)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
         let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
         assert!(result.is_ok());
 
-        // 3 entries: wood, food_berry from offerer->target; stone from target->offerer
-        assert_eq!(ledger.len(), 3);
+        // 3 entries at tick 2: wood, food_berry from escrow->target; stone
+        // from target->offerer
+        assert_eq!(ledger.entries_for_tick(2).len(), 3);
 
         // Conservation law must hold
-        assert_eq!(
-            ledger.verify_conservation(2),
-            ConservationResult::Balanced
-        );
+        for tick in 1..=2 {
+            assert_eq!(
+                ledger.verify_conservation(tick),
+                ConservationResult::Balanced
+            );
+        }
     }
 
     #[test]
@@ -724,23 +878,14 @@ mod tests {
         let mut target = make_agent(80, loc_b); // Different location
         target.inventory.insert(Resource::Stone, 10);
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc_a,
-        };
-
         let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
         let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
 
         assert!(result.is_err());
@@ -756,23 +901,14 @@ mod tests {
         let mut target = make_agent(80, loc);
         target.inventory.insert(Resource::Stone, 2); // Only 2, needs 3
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc,
-        };
-
         let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
         let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
 
         assert!(result.is_err());
@@ -783,38 +919,27 @@ mod tests {
     }
 
     #[test]
-    fn trade_accept_rejects_offerer_insufficient_resources() {
+    fn trade_accept_pays_the_target_from_escrow() {
         let loc = LocationId::new();
         let mut offerer = make_agent(80, loc);
-        offerer.inventory.insert(Resource::Wood, 2); // Only 2, promised 5
+        offerer.inventory.insert(Resource::Wood, 5);
 
         let mut target = make_agent(80, loc);
         target.inventory.insert(Resource::Stone, 10);
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc,
-        };
-
         let mut ledger = Ledger::new();
-        let result = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
+        // Everything offered is held in escrow, not by the offerer
+        assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), None);
 
-        assert!(result.is_err());
-        assert!(matches!(
-            result.err().unwrap(),
-            TradeError::OffererInsufficientResources { .. }
-        ));
+        trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2).unwrap();
+        assert_eq!(target.inventory.get(&Resource::Wood).copied(), Some(5));
     }
 
     #[test]
@@ -826,35 +951,26 @@ mod tests {
         let mut target = make_agent(80, loc);
         target.inventory.insert(Resource::Stone, 1); // Insufficient
 
-        let mut offered = BTreeMap::new();
-        offered.insert(Resource::Wood, 5);
-        let mut requested = BTreeMap::new();
-        requested.insert(Resource::Stone, 3);
-
-        let trade = PendingTrade {
-            trade_id: TradeId::new(),
-            offerer_id: offerer.agent_id,
-            target_id: target.agent_id,
-            offered_resources: offered,
-            requested_resources: requested,
-            created_at_tick: 1,
-            expires_at_tick: 4,
-            location_id: loc,
-        };
-
         let mut ledger = Ledger::new();
+        let trade = offer_trade(
+            &mut offerer,
+            &target,
+            &[(Resource::Wood, 5)],
+            &[(Resource::Stone, 3)],
+            &mut ledger,
+        );
         let _ = trade_accept(&mut offerer, &mut target, &trade, &mut ledger, 2);
 
-        // No changes should have been made
+        // No changes beyond the escrow should have been made
         assert_eq!(
             offerer.inventory.get(&Resource::Wood).copied(),
-            Some(10)
+            Some(5)
         );
         assert_eq!(
             target.inventory.get(&Resource::Stone).copied(),
             Some(1)
         );
-        assert!(ledger.is_empty());
+        assert_eq!(ledger.len(), 1);
     }
 
     // -----------------------------------------------------------------------
@@ -864,11 +980,12 @@ mod tests {
     #[test]
     fn trade_reject_produces_failed_details() {
         let loc = LocationId::new();
+        let mut offerer = make_agent(80, loc);
         let mut target = make_agent(80, loc);
 
         let trade = PendingTrade {
             trade_id: TradeId::new(),
-            offerer_id: AgentId::new(),
+            offerer_id: offerer.agent_id,
             target_id: target.agent_id,
             offered_resources: BTreeMap::new(),
             requested_resources: BTreeMap::new(),
@@ -877,7 +994,9 @@ mod tests {
             location_id: loc,
         };
 
-        let (outcome, failed) = trade_reject(&mut target, &trade);
+        let mut ledger = Ledger::new();
+        let (outcome, failed) =
+            trade_reject(&mut offerer, &mut target, &trade, &mut ledger, 2).unwrap();
 
         assert_eq!(outcome.energy_spent, 0);
         assert_eq!(failed.reason, TradeFailReason::Rejected);
@@ -927,9 +1046,10 @@ mod tests {
 
     #[test]
     fn expire_trade_produces_failed_details() {
+        let mut offerer = make_agent(80, LocationId::new());
         let trade = PendingTrade {
             trade_id: TradeId::new(),
-            offerer_id: AgentId::new(),
+            offerer_id: offerer.agent_id,
             target_id: AgentId::new(),
             offered_resources: BTreeMap::new(),
             requested_resources: BTreeMap::new(),
@@ -938,7 +1058,8 @@ mod tests {
             location_id: LocationId::new(),
         };
 
-        let failed = expire_trade(&trade);
+        let mut ledger = Ledger::new();
+        let failed = expire_trade(&mut offerer, &trade, &mut ledger, 4).unwrap();
         assert_eq!(failed.reason, TradeFailReason::Expired);
         assert_eq!(failed.trade_id, trade.trade_id);
     }
@@ -1012,11 +1133,13 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 5);
 
+        let mut ledger = Ledger::new();
         let (pending, _offer_outcome) = trade_offer(
             &mut offerer,
             target_id,
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         )
@@ -1026,7 +1149,6 @@ mod tests {
         assert_eq!(offerer.energy, 78);
 
         // Step 2: Accept
-        let mut ledger = Ledger::new();
         let accept_result =
             trade_accept(&mut offerer, &mut target, &pending, &mut ledger, 2).unwrap();
 
@@ -1041,12 +1163,23 @@ mod tests {
         assert_eq!(accept_result.completed.agent_a, offerer.agent_id);
         assert_eq!(accept_result.completed.agent_b, target_id);
 
-        // Verify ledger (2 entries: wood offerer->target, stone target->offerer)
-        assert_eq!(ledger.len(), 2);
+        // Verify ledger (3 entries: wood offerer->escrow->target, stone
+        // target->offerer)
+        assert_eq!(ledger.len(), 3);
         assert_eq!(
             ledger.verify_conservation(2),
             ConservationResult::Balanced
         );
+        let history = ledger.entries_tagged(LedgerTag::Trade(pending.trade_id));
+        let types: Vec<LedgerEntryType> = history.iter().map(|e| e.entry_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                LedgerEntryType::Escrow,
+                LedgerEntryType::EscrowRelease,
+                LedgerEntryType::Transfer
+            ]
+        );
     }
 
     #[test]
@@ -1066,23 +1199,31 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 5);
 
+        let mut ledger = Ledger::new();
         let (pending, _) = trade_offer(
             &mut offerer,
             target_id,
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         )
         .unwrap();
 
+        // The offered wood is held in escrow until the trade resolves
+        assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(12));
+
         // Step 2: Reject
-        let (_outcome, failed) = trade_reject(&mut target, &pending);
+        let (_outcome, failed) =
+            trade_reject(&mut offerer, &mut target, &pending, &mut ledger, 2).unwrap();
 
         assert_eq!(failed.reason, TradeFailReason::Rejected);
 
-        // No inventory changes
+        // The escrow is returned and no other inventory changes
         assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(20));
+        let escrow = pending.trade_id.into_inner();
+        assert_eq!(ledger.entity_balance(escrow, Resource::Wood), Decimal::ZERO);
         assert_eq!(target.inventory.get(&Resource::Stone).copied(), Some(15));
     }
 
@@ -1097,11 +1238,13 @@ mod tests {
         let mut request = BTreeMap::new();
         request.insert(Resource::Stone, 5);
 
+        let mut ledger = Ledger::new();
         let (pending, _) = trade_offer(
             &mut offerer,
             AgentId::new(),
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         )
@@ -1113,11 +1256,14 @@ mod tests {
         // Expired at tick 4
         assert!(is_trade_expired(&pending, 4));
 
-        let failed = expire_trade(&pending);
+        let failed = expire_trade(&mut offerer, &pending, &mut ledger, 4).unwrap();
         assert_eq!(failed.reason, TradeFailReason::Expired);
 
-        // No inventory changes
+        // The escrow is returned to the offerer
         assert_eq!(offerer.inventory.get(&Resource::Wood).copied(), Some(20));
+        let escrow = pending.trade_id.into_inner();
+        assert_eq!(ledger.entity_balance(escrow, Resource::Wood), Decimal::ZERO);
+        assert_eq!(ledger.verify_conservation(4), ConservationResult::Balanced);
     }
 
     #[test]
@@ -1141,17 +1287,18 @@ mod tests {
         request.insert(Resource::Stone, 4);
         request.insert(Resource::Water, 2);
 
+        let mut ledger = Ledger::new();
         let (pending, _) = trade_offer(
             &mut offerer,
             target_id,
             &offer,
             &request,
+            &mut ledger,
             1,
             DEFAULT_TRADE_EXPIRY_TICKS,
         )
         .unwrap();
 
-        let mut ledger = Ledger::new();
         let result =
             trade_accept(&mut offerer, &mut target, &pending, &mut ledger, 2).unwrap();
 
@@ -1173,8 +1320,9 @@ mod tests {
         assert_eq!(target.inventory.get(&Resource::Stone).copied(), Some(11));
         assert_eq!(target.inventory.get(&Resource::Water).copied(), Some(6));
 
-        // 4 ledger entries: wood, berry (offerer->target), stone, water (target->offerer)
-        assert_eq!(ledger.len(), 4);
+        // 4 ledger entries at tick 2: wood, berry (escrow->target), stone,
+        // water (target->offerer)
+        assert_eq!(ledger.entries_for_tick(2).len(), 4);
         assert_eq!(
             ledger.verify_conservation(2),
             ConservationResult::Balanced
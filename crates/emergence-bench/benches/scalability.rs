@@ -0,0 +1,213 @@
+//! Tick-phase benchmarks against the default synthetic load (10,000
+//! agents, 100 locations, 50 broadcasts per location).
+//!
+//! Benchmark IDs are `group/function` and must match the keys in
+//! `thresholds.json`.
+
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{BatchSize, Criterion};
+use emergence_agents::actions::conflict::{self, ConflictStrategy};
+use emergence_agents::actions::validation;
+use emergence_bench::load::{self, LoadConfig, ScriptedDecisions};
+use emergence_core::decision::DecisionSource;
+use emergence_core::experiment;
+use emergence_core::perception::{self, PerceptionContext, PerceptionRequest};
+use emergence_core::tick::{self, SimulationState};
+use emergence_ledger::{Ledger, TransferParams};
+use emergence_types::{
+    ActionRequest, AgentId, EntityType, LedgerEntryType, LocationId, Perception, Resource, Sex,
+};
+use rust_decimal::Decimal;
+
+fn main() {
+    let config = LoadConfig::default();
+    let Ok(state) = load::build_state(&config) else {
+        eprintln!("failed to build the synthetic load");
+        return;
+    };
+    let contexts = load::perception_contexts(&state, &config);
+    let perceptions = assemble_all(&state, &contexts);
+    let Ok(decisions) = ScriptedDecisions::new(&state).collect_decisions(0, &perceptions) else {
+        eprintln!("failed to script decisions");
+        return;
+    };
+
+    let mut criterion = Criterion::default()
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(5))
+        .configure_from_args();
+    bench_perception(&mut criterion, &state, &contexts);
+    bench_validation(&mut criterion, &state, &decisions);
+    bench_resolution(&mut criterion, &state, &config);
+    bench_ledger(&mut criterion, &state);
+    bench_persist(&mut criterion, &state, &config);
+    criterion.final_summary();
+}
+
+/// Every living agent's perception, as the perception phase builds it.
+fn assemble_all(
+    state: &SimulationState,
+    contexts: &BTreeMap<LocationId, PerceptionContext>,
+) -> BTreeMap<AgentId, Perception> {
+    let requests: Vec<PerceptionRequest<'_>> = state
+        .alive_agents
+        .iter()
+        .filter_map(|id| {
+            let agent_state = state.agent_states.get(id)?;
+            let agent = state.agents.get(id);
+            Some(PerceptionRequest {
+                agent_state,
+                agent_name: state.agent_names.get(id).map_or("Unknown", String::as_str),
+                agent_sex: agent.map_or(Sex::Female, |a| a.sex),
+                personality: agent.map(|a| &a.personality),
+                ctx: contexts.get(&agent_state.location_id)?,
+            })
+        })
+        .collect();
+    perception::assemble_perceptions(&requests)
+}
+
+fn bench_perception(
+    c: &mut Criterion,
+    state: &SimulationState,
+    contexts: &BTreeMap<LocationId, PerceptionContext>,
+) {
+    let mut group = c.benchmark_group("perception");
+    group.bench_function("assemble", |b| b.iter(|| assemble_all(black_box(state), contexts)));
+    group.finish();
+}
+
+fn bench_validation(
+    c: &mut Criterion,
+    state: &SimulationState,
+    decisions: &BTreeMap<AgentId, ActionRequest>,
+) {
+    let contexts = load::validation_contexts(state);
+    let mut group = c.benchmark_group("validation");
+    group.bench_function("validate", |b| {
+        b.iter(|| {
+            decisions
+                .iter()
+                .filter_map(|(id, request)| {
+                    let agent_state = state.agent_states.get(id)?;
+                    let ctx = contexts.get(&agent_state.location_id)?;
+                    let result = validation::validate_action(
+                        request.action_type,
+                        &request.parameters,
+                        agent_state,
+                        ctx,
+                    );
+                    Some(result.is_ok())
+                })
+                .filter(|&ok| ok)
+                .count()
+        });
+    });
+    group.finish();
+}
+
+fn bench_resolution(c: &mut Criterion, state: &SimulationState, config: &LoadConfig) {
+    let claims = load::gather_claims(state, Resource::Wood);
+    let mut group = c.benchmark_group("resolution");
+    group.bench_function("gather_conflicts", |b| {
+        b.iter(|| {
+            claims
+                .iter()
+                .map(|(available, claims)| {
+                    conflict::resolve_gather_claims(
+                        *available,
+                        black_box(claims),
+                        ConflictStrategy::FirstComeFirstServed,
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+    });
+    group.bench_function("run_tick", |b| {
+        b.iter_batched(
+            || {
+                let state = load::build_state(config).ok()?;
+                let source = ScriptedDecisions::new(&state);
+                Some((state, source))
+            },
+            |setup| {
+                let (mut state, mut source) = setup?;
+                tick::run_tick(&mut state, &mut source).ok()
+            },
+            BatchSize::PerIteration,
+        );
+    });
+    group.finish();
+}
+
+fn bench_ledger(c: &mut Criterion, state: &SimulationState) {
+    let gathers: Vec<(uuid::Uuid, uuid::Uuid)> = state
+        .agent_states
+        .locations()
+        .map(|(id, location_id)| (location_id.into_inner(), id.into_inner()))
+        .collect();
+    let record = || {
+        let mut ledger = Ledger::new();
+        for &(location, agent) in &gathers {
+            let _ = ledger.record_gather(1, Resource::Wood, Decimal::ONE, location, agent);
+        }
+        ledger
+    };
+    let transfers: Vec<TransferParams> = gathers
+        .iter()
+        .map(|&(location, agent)| TransferParams {
+            tick: 1,
+            entry_type: LedgerEntryType::Gather,
+            resource: Resource::Wood,
+            quantity: Decimal::ONE,
+            from_entity: location,
+            from_entity_type: EntityType::Location,
+            to_entity: agent,
+            to_entity_type: EntityType::Agent,
+            reason: "GATHER".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("ledger");
+    group.bench_function("record_gathers", |b| b.iter(record));
+    group.bench_function("record_gathers_batched", |b| {
+        b.iter(|| Ledger::new().record_transfers(black_box(&transfers)).map(|e| e.len()));
+    });
+    let ledger = record();
+    group.bench_function("verify_conservation", |b| {
+        b.iter(|| black_box(&ledger).verify_conservation(1));
+    });
+    group.finish();
+}
+
+fn bench_persist(c: &mut Criterion, state: &SimulationState, config: &LoadConfig) {
+    let results = load::build_state(config).ok().and_then(|mut state| {
+        let mut source = ScriptedDecisions::new(&state);
+        tick::run_tick(&mut state, &mut source).ok()
+    });
+    let action_results = results.map(|summary| summary.action_results).unwrap_or_default();
+
+    let mut group = c.benchmark_group("persist");
+    group.bench_function("agent_states_json", |b| {
+        b.iter(|| {
+            state
+                .agent_states
+                .values()
+                .filter_map(|s| serde_json::to_string(s).ok())
+                .map(|json| json.len())
+                .sum::<usize>()
+        });
+    });
+    group.bench_function("action_events", |b| {
+        b.iter(|| emergence_db::tick_persist::action_events(1, black_box(&action_results)));
+    });
+    group.bench_function("snapshot", |b| {
+        b.iter(|| experiment::capture_snapshot(black_box(state)));
+    });
+    group.finish();
+}
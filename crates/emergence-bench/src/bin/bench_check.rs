@@ -0,0 +1,69 @@
+//! `bench-check` -- compare the latest criterion results to the budgets
+//! in `thresholds.json`.
+//!
+//! Run after `cargo bench -p emergence-bench`. Prints one line per
+//! budgeted benchmark and exits non-zero if any is over budget or has no
+//! result.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use emergence_bench::Thresholds;
+use emergence_bench::thresholds;
+
+/// Check benchmark results against their regression thresholds.
+#[derive(Debug, Parser)]
+#[command(name = "bench-check", version)]
+struct Cli {
+    /// Criterion's output directory.
+    #[arg(long, default_value = "target/criterion")]
+    criterion_dir: PathBuf,
+
+    /// The thresholds file.
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/thresholds.json"))]
+    thresholds: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let budgets = match Thresholds::load(&cli.thresholds) {
+        Ok(budgets) => budgets,
+        Err(e) => {
+            eprintln!("bench-check: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let checks = thresholds::check(&cli.criterion_dir, &budgets);
+    for check in &checks {
+        let status = if check.passed() { "ok  " } else { "FAIL" };
+        let mean = check.mean_ns.map_or_else(|| "no result".to_owned(), format_ns);
+        println!(
+            "{status} {:<36} {mean:>12}  (budget {})",
+            check.id,
+            format_ns(check.max_mean_ns)
+        );
+    }
+
+    let failed = checks.iter().filter(|c| !c.passed()).count();
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("bench-check: {failed} of {} benchmarks over budget", checks.len());
+        ExitCode::FAILURE
+    }
+}
+
+/// Format nanoseconds with a readable unit.
+fn format_ns(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} us", ns / 1e3)
+    } else {
+        format!("{ns:.0} ns")
+    }
+}
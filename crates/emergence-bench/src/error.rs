@@ -0,0 +1,36 @@
+//! Error types for load generation and threshold checks.
+
+/// Errors from building a synthetic load or checking results against
+/// the thresholds file.
+#[derive(Debug, thiserror::Error)]
+pub enum BenchError {
+    /// The synthetic world could not be built.
+    #[error("world error: {0}")]
+    World(#[from] emergence_world::WorldError),
+
+    /// The world clock could not be created.
+    #[error("clock error: {0}")]
+    Clock(#[from] emergence_core::clock::ClockError),
+
+    /// A synthetic agent could not be built.
+    #[error("failed to build agent: {0}")]
+    Agent(String),
+
+    /// A thresholds or estimates file could not be read.
+    #[error("failed to read {path}: {source}")]
+    Io {
+        /// The file path.
+        path: String,
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+
+    /// A thresholds or estimates file is not the expected JSON.
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        /// The file path.
+        path: String,
+        /// The underlying JSON error.
+        source: serde_json::Error,
+    },
+}
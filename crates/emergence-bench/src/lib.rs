@@ -0,0 +1,32 @@
+//! Scalability benchmarks for the Emergence tick cycle.
+//!
+//! The [`load`] module builds a synthetic world sized well past a real run
+//! -- 10,000 agents over 100 locations by default, with every location
+//! carrying dense broadcast traffic -- and a scripted decision source that
+//! mixes gathers, broadcasts, meals, rests, and moves every tick. The
+//! criterion benchmarks in `benches/scalability.rs` time the hot paths
+//! against it:
+//!
+//! | Group | Measures |
+//! |-------|----------|
+//! | `perception` | Assembling every agent's perception from shared location contexts |
+//! | `validation` | Running every agent's action through the validation pipeline |
+//! | `resolution` | Gather conflict resolution, and a full `run_tick` |
+//! | `ledger` | Recording one gather per agent, then the conservation check |
+//! | `persist` | Serializing agent states, building action events, capturing a snapshot |
+//!
+//! `thresholds.json` gives each benchmark a mean-time budget, and the
+//! `bench-check` binary compares criterion's latest results against it:
+//!
+//! ```text
+//! cargo bench -p emergence-bench
+//! cargo run -p emergence-bench --bin bench-check
+//! ```
+
+pub mod error;
+pub mod load;
+pub mod thresholds;
+
+pub use error::BenchError;
+pub use load::{LoadConfig, ScriptedDecisions, build_state};
+pub use thresholds::{Check, Thresholds};
@@ -0,0 +1,527 @@
+//! Synthetic load: a large ring world, a dense population, and scripted
+//! decisions that keep every tick phase busy.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use emergence_agents::actions::conflict::{ConflictStrategy, GatherClaim};
+use emergence_agents::actions::validation::ValidationContext;
+use emergence_agents::config::VitalsConfig;
+use emergence_agents::{DiplomacyState, FamilyTracker, OrderBook};
+use emergence_core::agent_store::AgentStore;
+use emergence_core::clock::WorldClock;
+use emergence_core::config::TimeConfig;
+use emergence_core::decision::{DecisionError, DecisionSource};
+use emergence_core::perception::{self, PerceptionContext};
+use emergence_core::scratch::TickScratch;
+use emergence_core::subscribers::EventSubscribers;
+use emergence_core::tick::SimulationState;
+use emergence_ledger::Ledger;
+use emergence_types::{
+    ActionParameters, ActionRequest, ActionType, Agent, AgentId, AgentState, KnownRoute,
+    Location, LocationId, Message, PathType, Perception, Personality, Resource, ResourceNode,
+    Route, RouteId, Season, Sex, Weather,
+};
+use emergence_world::{FarmRegistry, WeatherSystem, WorldMap};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+
+use crate::error::BenchError;
+
+/// Resources stocked at every synthetic location.
+pub const RESOURCES: [Resource; 5] = [
+    Resource::Water,
+    Resource::FoodBerry,
+    Resource::Wood,
+    Resource::Stone,
+    Resource::Fiber,
+];
+
+/// Stock and cap of every synthetic resource node -- enough that the whole
+/// population gathering every tick never empties a location.
+const NODE_STOCK: u32 = 1_000_000;
+
+/// Size and shape of a synthetic load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadConfig {
+    /// Living agents.
+    pub agents: u32,
+    /// Locations; agents are spread evenly across them.
+    pub locations: u32,
+    /// Broadcast messages visible at each location.
+    pub messages_per_location: u32,
+    /// Seed for agent IDs, location IDs, and personalities.
+    pub seed: u64,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            agents: 10_000,
+            locations: 100,
+            messages_per_location: 50,
+            seed: 0,
+        }
+    }
+}
+
+impl LoadConfig {
+    /// Set the number of agents.
+    #[must_use]
+    pub const fn with_agents(mut self, agents: u32) -> Self {
+        self.agents = agents;
+        self
+    }
+
+    /// Set the number of locations.
+    #[must_use]
+    pub const fn with_locations(mut self, locations: u32) -> Self {
+        self.locations = locations;
+        self
+    }
+
+    /// Set the number of broadcasts visible at each location.
+    #[must_use]
+    pub const fn with_messages_per_location(mut self, messages: u32) -> Self {
+        self.messages_per_location = messages;
+        self
+    }
+
+    /// Set the seed.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Build a world of `config.locations` locations joined in a ring, with
+/// `config.agents` agents spread evenly across it.
+///
+/// Location capacities are raised to fit the whole population, so moves
+/// never fail for lack of room.
+pub fn build_state(config: &LoadConfig) -> Result<SimulationState, BenchError> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let location_ids: Vec<LocationId> = (0..config.locations.max(1))
+        .map(|_| LocationId::from(uuid::Uuid::from_u128(rng.random())))
+        .collect();
+
+    let mut world_map = WorldMap::new();
+    for (n, &id) in location_ids.iter().enumerate() {
+        world_map.add_location(location(id, n, config.agents))?;
+    }
+    // Two locations share one route; a ring needs at least three.
+    let ring_routes = match location_ids.len() {
+        0 | 1 => 0,
+        2 => 1,
+        n => n,
+    };
+    let next = location_ids.iter().cycle().skip(1);
+    for (&from, &to) in location_ids.iter().zip(next).take(ring_routes) {
+        world_map.add_route(route(from, to))?;
+    }
+
+    let mut state = SimulationState {
+        clock: WorldClock::new(&TimeConfig::default())?,
+        world_map: WorldMap::new(),
+        weather_system: WeatherSystem::new(config.seed),
+        agents: BTreeMap::new(),
+        agent_names: BTreeMap::new(),
+        agent_states: AgentStore::new(),
+        alive_agents: Vec::new(),
+        vitals_config: VitalsConfig::default(),
+        conflict_strategy: ConflictStrategy::FirstComeFirstServed,
+        injected_events: Vec::new(),
+        active_plagues: Vec::new(),
+        active_resource_booms: Vec::new(),
+        disasters: emergence_world::DisasterSystem::default(),
+        fires: emergence_world::FireSystem::default(),
+        fauna: emergence_world::FaunaRegistry::default(),
+        waters: emergence_world::WaterRegistry::default(),
+        mines: emergence_world::MineRegistry::default(),
+        known_maps: emergence_world::KnownMapRegistry::default(),
+        trade_network: emergence_world::TradeNetwork::default(),
+        structures: BTreeMap::new(),
+        family: FamilyTracker::new(),
+        ledger: Ledger::new(),
+        orders: OrderBook::new(),
+        groups: BTreeMap::new(),
+        diplomacy: DiplomacyState::new(),
+        hooks: None,
+        scratch: TickScratch::new(),
+        subscribers: EventSubscribers::new(),
+    };
+
+    let agents = usize::try_from(config.agents).unwrap_or(usize::MAX);
+    for (n, &location_id) in location_ids.iter().cycle().take(agents).enumerate() {
+        let (agent, agent_state) = make_agent(&mut rng, &format!("Agent-{n}"), location_id)?;
+        if let Some(loc) = world_map.get_location_mut(location_id) {
+            loc.add_occupant(agent.id)?;
+        }
+        let agent_id = agent.id;
+        state.agent_names.insert(agent_id, agent.name.clone());
+        state.agents.insert(agent_id, agent);
+        state.agent_states.insert(agent_state);
+        state.alive_agents.push(agent_id);
+        state.family.register_alive(agent_id);
+    }
+    state.world_map = world_map;
+    Ok(state)
+}
+
+fn location(id: LocationId, n: usize, capacity: u32) -> Location {
+    let resources = RESOURCES
+        .into_iter()
+        .map(|resource| {
+            let node = ResourceNode {
+                resource,
+                available: NODE_STOCK,
+                regen_per_tick: 100,
+                max_capacity: NODE_STOCK,
+            };
+            (resource, node)
+        })
+        .collect();
+    Location {
+        id,
+        name: format!("Location {n}"),
+        region: format!("Region {}", n.checked_div(10).unwrap_or(0)),
+        location_type: "natural".to_owned(),
+        description: "A synthetic location stocked for load testing.".to_owned(),
+        capacity,
+        elevation: 0,
+        base_resources: resources,
+        discovered_by: BTreeSet::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn route(from: LocationId, to: LocationId) -> Route {
+    Route {
+        id: RouteId::new(),
+        from_location: from,
+        to_location: to,
+        cost_ticks: 1,
+        path_type: PathType::DirtTrail,
+        durability: 100,
+        max_durability: 100,
+        decay_per_tick: Decimal::ZERO,
+        acl: None,
+        bidirectional: true,
+        built_by: None,
+        built_at_tick: None,
+    }
+}
+
+fn make_agent(
+    rng: &mut StdRng,
+    name: &str,
+    location_id: LocationId,
+) -> Result<(Agent, AgentState), BenchError> {
+    let agent_id = AgentId::from(uuid::Uuid::from_u128(rng.random()));
+    let agent_state = AgentState::builder(agent_id, location_id)
+        .resource(Resource::FoodBerry, 5)
+        .resource(Resource::Water, 5)
+        .knowledge(BTreeSet::new())
+        .build()
+        .map_err(|e| BenchError::Agent(format!("{e:?}")))?;
+    let mut trait_value = || Decimal::new(rng.random_range(0..=100), 2);
+    let personality = Personality {
+        curiosity: trait_value(),
+        cooperation: trait_value(),
+        aggression: trait_value(),
+        risk_tolerance: trait_value(),
+        industriousness: trait_value(),
+        sociability: trait_value(),
+        honesty: trait_value(),
+        loyalty: trait_value(),
+    };
+    let agent = Agent {
+        id: agent_id,
+        name: name.to_owned(),
+        sex: if rng.random_bool(0.5) { Sex::Male } else { Sex::Female },
+        born_at_tick: 0,
+        died_at_tick: None,
+        cause_of_death: None,
+        parent_a: None,
+        parent_b: None,
+        generation: 0,
+        personality,
+        created_at: Utc::now(),
+    };
+    Ok((agent, agent_state))
+}
+
+/// Build every location's perception context the way the tick cycle's
+/// perception phase does, plus `config.messages_per_location` broadcasts
+/// from each location's occupants.
+pub fn perception_contexts(
+    state: &SimulationState,
+    config: &LoadConfig,
+) -> BTreeMap<LocationId, PerceptionContext> {
+    let tick = state.clock.tick();
+    let messages = usize::try_from(config.messages_per_location).unwrap_or(usize::MAX);
+    state
+        .world_map
+        .locations()
+        .map(|(&location_id, loc)| {
+            let agent_names: BTreeMap<AgentId, String> = loc
+                .occupants
+                .iter()
+                .filter_map(|id| state.agent_names.get(id).map(|name| (*id, name.clone())))
+                .collect();
+            let agent_sexes = loc
+                .occupants
+                .iter()
+                .filter_map(|id| state.agents.get(id).map(|agent| (*id, agent.sex)))
+                .collect();
+            let messages_here = agent_names
+                .iter()
+                .take(messages)
+                .map(|(&sender_id, sender_name)| Message {
+                    sender_id,
+                    sender_name: sender_name.clone(),
+                    recipient_id: None,
+                    content: format!("{sender_name} has wood to trade at {}", loc.location.name),
+                    tick,
+                    is_broadcast: true,
+                    location_id,
+                })
+                .collect();
+            let known_routes = state
+                .world_map
+                .neighbors(location_id)
+                .iter()
+                .filter_map(|(dest_id, _)| {
+                    let dest = state.world_map.get_location(*dest_id)?;
+                    let routes = state.world_map.routes_between(location_id, *dest_id);
+                    let first = routes.first()?;
+                    Some(KnownRoute {
+                        destination_id: dest_id.to_string(),
+                        destination: dest.location.name.clone(),
+                        cost: format!("{} ticks", first.cost_ticks),
+                        path_type: format!("{:?}", first.path_type),
+                        resources_hint: String::new(),
+                    })
+                })
+                .collect();
+            let ctx = PerceptionContext {
+                tick,
+                time_of_day: state.clock.time_of_day(),
+                season: Season::Spring,
+                weather: Weather::Clear,
+                location_name: loc.location.name.clone(),
+                location_description: loc.location.description.clone(),
+                location_resources: loc.available_resources(),
+                structures_here: Vec::new(),
+                messages_here,
+                known_routes,
+                agent_names,
+                agent_sexes,
+                ticks_until_season_change: state.clock.ticks_until_season_change(),
+                message_expiry_ticks: perception::DEFAULT_MESSAGE_EXPIRY_TICKS,
+                overcrowded: emergence_world::crowding::is_overcrowded(loc),
+                pollution: loc.pollution,
+            };
+            (location_id, ctx)
+        })
+        .collect()
+}
+
+/// Build one validation context per location, filled in the way the tick
+/// cycle's resolution phase fills it.
+///
+/// The context's `agent_id` is the location's first occupant; the scripted
+/// actions never target another agent, so it can be shared by everyone at
+/// the location.
+pub fn validation_contexts(state: &SimulationState) -> BTreeMap<LocationId, ValidationContext> {
+    let tick = state.clock.tick();
+    state
+        .world_map
+        .locations()
+        .map(|(&location_id, loc)| {
+            let agents_at_location: Vec<AgentId> = loc.occupants.iter().copied().collect();
+            let ctx = ValidationContext {
+                agent_id: agents_at_location.first().copied().unwrap_or_else(AgentId::new),
+                agent_location: location_id,
+                is_traveling: false,
+                location_resources: loc.resources().clone(),
+                agents_at_location,
+                travel_blocked: false,
+                agent_knowledge: BTreeSet::new(),
+                is_mature: true,
+                structures_at_location: BTreeMap::new(),
+                route_to_improve: None,
+                move_route: None,
+                destination_fortifications: Vec::new(),
+                route_to_build: None,
+                agent_groups: Vec::new(),
+                dead_agents: BTreeSet::new(),
+                farm_registry: FarmRegistry::new(),
+                library_knowledge: BTreeMap::new(),
+                game_at_location: state.fauna.game_at(location_id),
+                ore_body: state.mines.get(location_id).copied(),
+                fish_nearby: state.waters.fish_near(&state.world_map, location_id),
+                fire_intensity: state.fires.intensity_at(location_id),
+                location_type: loc.location.location_type.clone(),
+                current_tick: tick,
+            };
+            (location_id, ctx)
+        })
+        .collect()
+}
+
+/// One claim on `resource` per agent, grouped by location together with
+/// the location's stock -- the input to gather conflict resolution.
+pub fn gather_claims(state: &SimulationState, resource: Resource) -> Vec<(u32, Vec<GatherClaim>)> {
+    let base = DateTime::<Utc>::UNIX_EPOCH;
+    state
+        .world_map
+        .locations()
+        .map(|(_, loc)| {
+            let available = loc.get_resource(&resource).map_or(0, |node| node.available);
+            let claims = loc
+                .occupants
+                .iter()
+                .zip(0_i64..)
+                .map(|(&agent_id, n)| GatherClaim {
+                    agent_id,
+                    resource,
+                    requested: 3,
+                    submitted_at: base
+                        .checked_add_signed(TimeDelta::milliseconds(n))
+                        .unwrap_or(base),
+                })
+                .collect();
+            (available, claims)
+        })
+        .collect()
+}
+
+/// The action in a scripted rotation: gather, broadcast, eat, drink, rest,
+/// move along the ring.
+///
+/// `slot` combines the agent's position in the population with the tick,
+/// so every tick exercises every action type and each agent cycles
+/// through all of them.
+pub fn scripted_action(
+    slot: u64,
+    perception: &Perception,
+    next: Option<LocationId>,
+) -> (ActionType, ActionParameters) {
+    let gather = || {
+        let resource = RESOURCES
+            .into_iter()
+            .find(|r| perception.surroundings.visible_resources.contains_key(r))
+            .unwrap_or(Resource::Wood);
+        (ActionType::Gather, ActionParameters::Gather { resource })
+    };
+    match slot.checked_rem(6).unwrap_or(0) {
+        0 => gather(),
+        1 => {
+            let me = &perception.self_state;
+            let message = format!("{} is at {}", me.name, me.location_name);
+            (ActionType::Broadcast, ActionParameters::Broadcast { message })
+        }
+        2 => (ActionType::Eat, ActionParameters::Eat { food_type: Resource::FoodBerry }),
+        3 => (ActionType::Drink, ActionParameters::Drink),
+        4 => (ActionType::Rest, ActionParameters::Rest),
+        _ => next.map_or_else(gather, |destination| {
+            (ActionType::Move, ActionParameters::Move { destination })
+        }),
+    }
+}
+
+/// A decision source that answers for every agent with
+/// [`scripted_action`], instantly and deterministically.
+pub struct ScriptedDecisions {
+    /// Location name -> the next location along the ring.
+    next: BTreeMap<String, LocationId>,
+}
+
+impl ScriptedDecisions {
+    /// Create a source for the world in `state`.
+    pub fn new(state: &SimulationState) -> Self {
+        let next = state
+            .world_map
+            .locations()
+            .filter_map(|(id, loc)| {
+                let (to, _) = state.world_map.neighbors(*id).into_iter().next()?;
+                Some((loc.location.name.clone(), to))
+            })
+            .collect();
+        Self { next }
+    }
+}
+
+impl DecisionSource for ScriptedDecisions {
+    fn collect_decisions(
+        &mut self,
+        tick: u64,
+        perceptions: &BTreeMap<AgentId, Perception>,
+    ) -> Result<BTreeMap<AgentId, ActionRequest>, DecisionError> {
+        let submitted_at = Utc::now();
+        let decisions = perceptions
+            .iter()
+            .zip(0_u64..)
+            .map(|((&agent_id, perception), n)| {
+                let next = self.next.get(&perception.self_state.location_name).copied();
+                let (action_type, parameters) =
+                    scripted_action(n.wrapping_add(tick), perception, next);
+                let request = ActionRequest {
+                    agent_id,
+                    tick,
+                    action_type,
+                    parameters,
+                    submitted_at,
+                    goal_updates: Vec::new(),
+                };
+                (agent_id, request)
+            })
+            .collect();
+        Ok(decisions)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn small() -> LoadConfig {
+        LoadConfig::default().with_agents(60).with_locations(6).with_messages_per_location(4)
+    }
+
+    #[test]
+    fn agents_are_spread_evenly() {
+        let state = build_state(&small()).unwrap();
+        assert_eq!(state.alive_agents.len(), 60);
+        for (_, loc) in state.world_map.locations() {
+            assert_eq!(loc.occupants.len(), 10);
+            assert_eq!(state.world_map.neighbors(loc.location.id).len(), 2);
+        }
+
+        let contexts = perception_contexts(&state, &small());
+        assert_eq!(contexts.len(), 6);
+        assert!(contexts.values().all(|ctx| ctx.messages_here.len() == 4));
+    }
+
+    #[test]
+    fn scripted_tick_exercises_every_action() {
+        let mut state = build_state(&small()).unwrap();
+        let mut source = ScriptedDecisions::new(&state);
+        let summary = emergence_core::tick::run_tick(&mut state, &mut source).unwrap();
+        assert_eq!(summary.action_results.len(), 60);
+
+        let succeeded: BTreeSet<ActionType> = summary
+            .action_results
+            .values()
+            .filter(|r| r.success)
+            .map(|r| r.action_type)
+            .collect();
+        for action in [ActionType::Gather, ActionType::Broadcast, ActionType::Move] {
+            assert!(succeeded.contains(&action), "{action:?} never succeeded");
+        }
+    }
+}
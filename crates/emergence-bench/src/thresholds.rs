@@ -0,0 +1,126 @@
+//! Per-benchmark time budgets, checked against criterion's saved estimates.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::BenchError;
+
+/// The thresholds file: criterion benchmark ID (`group/function`) to the
+/// largest acceptable mean time per iteration, in nanoseconds.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Thresholds {
+    /// Budgets by benchmark ID.
+    pub max_mean_ns: BTreeMap<String, f64>,
+}
+
+impl Thresholds {
+    /// Load a thresholds file.
+    pub fn load(path: &Path) -> Result<Self, BenchError> {
+        let text = read(path)?;
+        serde_json::from_str(&text).map_err(|source| BenchError::Parse {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+}
+
+/// One benchmark's measured mean against its budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    /// The criterion benchmark ID.
+    pub id: String,
+    /// The budget, in nanoseconds.
+    pub max_mean_ns: f64,
+    /// The latest measured mean, or `None` if criterion has no estimate
+    /// for this benchmark.
+    pub mean_ns: Option<f64>,
+}
+
+impl Check {
+    /// Whether the benchmark was measured and came in within budget.
+    pub fn passed(&self) -> bool {
+        self.mean_ns.is_some_and(|mean| mean <= self.max_mean_ns)
+    }
+}
+
+/// Criterion's `estimates.json`, reduced to the mean.
+#[derive(Deserialize)]
+struct Estimates {
+    mean: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+/// Extract the mean time per iteration, in nanoseconds, from the contents
+/// of a criterion `estimates.json`.
+pub fn mean_ns(estimates_json: &str) -> Result<f64, serde_json::Error> {
+    serde_json::from_str::<Estimates>(estimates_json).map(|e| e.mean.point_estimate)
+}
+
+/// Check every budgeted benchmark against the latest run saved under
+/// `criterion_dir` (normally `target/criterion`).
+pub fn check(criterion_dir: &Path, thresholds: &Thresholds) -> Vec<Check> {
+    thresholds
+        .max_mean_ns
+        .iter()
+        .map(|(id, &max_mean_ns)| {
+            let path = criterion_dir.join(id).join("new").join("estimates.json");
+            let mean_ns = read(&path).ok().and_then(|text| mean_ns(&text).ok());
+            Check {
+                id: id.clone(),
+                max_mean_ns,
+                mean_ns,
+            }
+        })
+        .collect()
+}
+
+fn read(path: &Path) -> Result<String, BenchError> {
+    std::fs::read_to_string(path).map_err(|source| BenchError::Io {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_criterion_mean() {
+        let json = r#"{"mean":{"confidence_interval":{"confidence_level":0.95,
+            "lower_bound":980.0,"upper_bound":1020.0},"point_estimate":1000.5,
+            "standard_error":10.0},"median":{"point_estimate":999.0}}"#;
+        assert!((mean_ns(json).unwrap() - 1000.5).abs() < f64::EPSILON);
+        assert!(mean_ns("{}").is_err());
+    }
+
+    #[test]
+    fn missing_and_slow_benchmarks_fail() {
+        let dir = std::env::temp_dir().join(format!("emergence-bench-{}", std::process::id()));
+        let saved = dir.join("ledger").join("record").join("new");
+        std::fs::create_dir_all(&saved).unwrap();
+        std::fs::write(saved.join("estimates.json"), r#"{"mean":{"point_estimate":500.0}}"#)
+            .unwrap();
+
+        let budget = |ns: f64| Thresholds {
+            max_mean_ns: [("ledger/record".to_owned(), ns), ("ledger/verify".to_owned(), ns)]
+                .into_iter()
+                .collect(),
+        };
+        let within = check(&dir, &budget(600.0));
+        let over = check(&dir, &budget(400.0));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let passed = |checks: &[Check]| checks.iter().map(Check::passed).collect::<Vec<_>>();
+        assert_eq!(passed(&within), [true, false]);
+        assert_eq!(passed(&over), [false, false]);
+        assert_eq!(within.get(1).and_then(|c| c.mean_ns), None);
+    }
+}
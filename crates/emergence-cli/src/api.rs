@@ -0,0 +1,129 @@
+//! Thin client for the Observer and Operator REST API.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::CliError;
+
+/// How long a single API request may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A client bound to one observer base URL (e.g. `http://localhost:8080`).
+pub struct ApiClient {
+    base_url: String,
+    token: Option<String>,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    /// Create a client for the observer at `base_url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::Http`] if the HTTP client cannot be built.
+    pub fn new(base_url: &str) -> Result<Self, CliError> {
+        let base_url = base_url.trim_end_matches('/').to_owned();
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|source| CliError::Http {
+                url: base_url.clone(),
+                source,
+            })?;
+        Ok(Self {
+            base_url,
+            token: None,
+            http,
+        })
+    }
+
+    /// Send `token` as a bearer token with every request (see
+    /// `operator.api_auth_token`). An empty token sends nothing.
+    #[must_use]
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|token| !token.is_empty());
+        self
+    }
+
+    /// The full URL for an API path such as `/api/agents`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// `GET` a path with query parameters and decode the JSON body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::Http`] if the request fails, or
+    /// [`CliError::Api`] on a non-success status.
+    pub async fn get(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<serde_json::Value, CliError> {
+        let url = self.url(path);
+        let response = self.authorize(self.http.get(&url).query(query)).send().await;
+        Self::decode(url, response).await
+    }
+
+    /// `POST` a JSON body to a path and decode the JSON response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::Http`] if the request fails, or
+    /// [`CliError::Api`] on a non-success status.
+    pub async fn post<B: Serialize + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<serde_json::Value, CliError> {
+        let url = self.url(path);
+        let response = self.authorize(self.http.post(&url).json(body)).send().await;
+        Self::decode(url, response).await
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn decode(
+        url: String,
+        response: Result<reqwest::Response, reqwest::Error>,
+    ) -> Result<serde_json::Value, CliError> {
+        let response = match response {
+            Ok(response) => response,
+            Err(source) => return Err(CliError::Http { url, source }),
+        };
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(CliError::Api {
+                url,
+                status: status.as_u16(),
+                body,
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|source| CliError::Http { url, source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_joins_without_double_slash() {
+        let client = ApiClient::new("http://localhost:8080/");
+        assert_eq!(
+            client.map(|c| c.url("/api/world")).ok().as_deref(),
+            Some("http://localhost:8080/api/world")
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Static checks for `emergence-config.yaml`.
+//!
+//! [`SimulationConfig`] fills every missing field with a default and
+//! ignores keys it does not know, so a typo in a section name or an
+//! impossible combination of values only shows up once the engine is
+//! running. [`check`] catches those before a run starts.
+
+use emergence_core::clock::WorldClock;
+use emergence_core::config::SimulationConfig;
+
+/// Top-level sections the engine reads. `agents` is read separately by
+/// the spawner.
+const KNOWN_SECTIONS: &[&str] = &[
+    "world",
+    "map",
+    "time",
+    "population",
+    "economy",
+    "environment",
+    "discovery",
+    "infrastructure",
+    "logging",
+    "llm",
+    "simulation",
+    "operator",
+    "plugins",
+    "agents",
+];
+
+/// End conditions the engine understands.
+const END_CONDITIONS: &[&str] = &["time_limit", "extinction", "era_reached", "manual"];
+
+/// Smallest tick interval the operator API accepts.
+const MIN_TICK_INTERVAL_MS: u64 = 100;
+
+/// Check configuration YAML, returning one message per problem found.
+///
+/// An empty result means the file is usable. YAML that does not parse is
+/// reported as a single problem.
+pub fn check(yaml: &str) -> Vec<String> {
+    let config = match SimulationConfig::parse(yaml) {
+        Ok(config) => config,
+        Err(e) => return vec![e.to_string()],
+    };
+    let mut problems = Vec::new();
+
+    if let Ok(serde_yml::Value::Mapping(root)) = serde_yml::from_str::<serde_yml::Value>(yaml) {
+        for key in root.keys().filter_map(serde_yml::Value::as_str) {
+            if !KNOWN_SECTIONS.contains(&key) {
+                problems.push(format!("unknown section `{key}` is ignored"));
+            }
+        }
+    }
+
+    if let Err(e) = WorldClock::new(&config.time) {
+        problems.push(format!("time: {e}"));
+    }
+    if config.world.tick_interval_ms < MIN_TICK_INTERVAL_MS {
+        problems.push(format!(
+            "world.tick_interval_ms is {} but must be at least {MIN_TICK_INTERVAL_MS}",
+            config.world.tick_interval_ms
+        ));
+    }
+    if config.map.procedural
+        && let Err(e) = config.map.gen_params(config.world.seed).validate()
+    {
+        problems.push(format!("map: {e}"));
+    }
+    if config.population.initial_agents > config.population.max_agents {
+        problems.push(format!(
+            "population.initial_agents ({}) exceeds population.max_agents ({})",
+            config.population.initial_agents, config.population.max_agents
+        ));
+    }
+    if config.simulation.min_population > config.population.max_agents {
+        problems.push(format!(
+            "simulation.min_population ({}) exceeds population.max_agents ({})",
+            config.simulation.min_population, config.population.max_agents
+        ));
+    }
+    if !END_CONDITIONS.contains(&config.simulation.end_condition.as_str()) {
+        problems.push(format!(
+            "simulation.end_condition `{}` is not one of {}",
+            config.simulation.end_condition,
+            END_CONDITIONS.join(", ")
+        ));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_config_is_clean() {
+        let yaml = include_str!("../../../emergence-config.yaml");
+        assert_eq!(check(yaml), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_typos_and_impossible_values() {
+        let yaml = "wrold:\n  name: x\nworld:\n  tick_interval_ms: 10\n\
+                    population:\n  initial_agents: 50\n  max_agents: 10\n\
+                    simulation:\n  end_condition: forever\n";
+        let problems = check(yaml);
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems.iter().any(|p| p.contains("`wrold`")));
+        assert!(problems.iter().any(|p| p.contains("tick_interval_ms")));
+        assert!(problems.iter().any(|p| p.contains("initial_agents")));
+        assert!(problems.iter().any(|p| p.contains("`forever`")));
+    }
+
+    #[test]
+    fn reports_unparseable_yaml() {
+        assert_eq!(check("world: [unclosed").len(), 1);
+    }
+}
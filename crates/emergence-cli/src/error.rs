@@ -0,0 +1,100 @@
+//! Error types for the administration CLI.
+
+use emergence_core::archive::ArchiveError;
+use emergence_core::config::ConfigError;
+use emergence_db::DbError;
+use emergence_events::{BackfillError, CompactError, DeterminismError};
+
+/// Errors that can occur while running a CLI command.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// The HTTP request to the observer API failed.
+    #[error("request to {url} failed: {source}")]
+    Http {
+        /// The URL that was requested.
+        url: String,
+        /// The underlying HTTP error.
+        source: reqwest::Error,
+    },
+
+    /// The observer API answered with a non-success status.
+    #[error("{url} returned {status}: {body}")]
+    Api {
+        /// The URL that was requested.
+        url: String,
+        /// The HTTP status code.
+        status: u16,
+        /// The response body, usually a JSON error object.
+        body: String,
+    },
+
+    /// A database operation failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// Event log compaction failed.
+    #[error("compaction failed: {0}")]
+    Compact(#[from] CompactError),
+
+    /// The event hash chain does not verify.
+    #[error("event hash chain is broken in {breaks} place(s)")]
+    ChainBroken {
+        /// Number of breaks found.
+        breaks: usize,
+    },
+
+    /// Backfilling derived events failed.
+    #[error("backfill failed: {0}")]
+    Backfill(#[from] BackfillError),
+
+    /// No built-in detector has this name.
+    #[error("unknown detector {0:?}")]
+    UnknownDetector(String),
+
+    /// Replay verification failed to run.
+    #[error("replay verification failed: {0}")]
+    Determinism(#[from] DeterminismError),
+
+    /// Replaying the event log did not rebuild the same state.
+    #[error("replay diverged at tick {tick} in {subsystem}")]
+    ReplayDiverged {
+        /// The tick after which the states differed.
+        tick: u64,
+        /// Which state differed.
+        subsystem: String,
+    },
+
+    /// A configuration file could not be loaded.
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    /// A configuration file loaded but failed validation.
+    #[error("{path} has {count} problem(s)")]
+    InvalidConfig {
+        /// The file that was checked.
+        path: String,
+        /// Number of problems reported.
+        count: usize,
+    },
+
+    /// A run archive could not be read or unpacked.
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    /// A scenario file could not be read or parsed.
+    #[error("invalid scenario {path}: {message}")]
+    Scenario {
+        /// The scenario file.
+        path: String,
+        /// What was wrong with it.
+        message: String,
+    },
+
+    /// Reading input or writing output failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON (de)serialization failed.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
@@ -0,0 +1,659 @@
+//! `emergence` -- administration CLI for the Emergence simulation.
+//!
+//! Wraps the Observer/Operator REST API and the `PostgreSQL` stores so day
+//! to day operation does not need `curl` and `psql`:
+//!
+//! | Command | Talks to | Does |
+//! |---------|----------|------|
+//! | `agents list` / `agents show <id>` | API | Inspect agents |
+//! | `events tail` | API | Stream events as JSON lines, tick by tick |
+//! | `operator status\|pause\|resume\|stop\|speed <ms>` | API | Control the tick loop |
+//! | `inject <scenario>` | API | Queue every event in a scenario file |
+//! | `checkpoint --name <name>` | API + DB | Save live state as an experiment snapshot |
+//! | `export <kind> --from <t> --to <t>` | DB | Dump events, ledger, or world snapshots |
+//! | `compact [--retention-ticks <n>]` | DB | Fold snapshotted routine events into summaries |
+//! | `verify-chain --from <t> --to <t>` | DB | Check the event hash chain for tampering |
+//! | `branch create <name> --at <t>` / `branch list` | DB | Fork the event log for what-if runs |
+//! | `backfill --to <t> [--detector <name>]` | DB | Write events derived by newer detectors |
+//! | `validate-config [path]` | -- | Check `emergence-config.yaml` before a run |
+//! | `archive info\|extract <file>` | -- | Inspect or unpack a `.emrun` run archive |
+//!
+//! The API base URL, operator token, and database URL come from
+//! `--api-url` / `EMERGENCE_API_URL`, `--token` / `EMERGENCE_OPERATOR_TOKEN`,
+//! and `--database-url` / `DATABASE_URL`.
+
+mod api;
+mod config_check;
+mod error;
+mod export;
+mod scenario;
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use emergence_core::archive::RunArchive;
+use emergence_db::{BranchStore, EventStore, ExperimentStore, PostgresPool, MAIN_BRANCH};
+use emergence_events::CompactionConfig;
+use uuid::Uuid;
+
+use crate::api::ApiClient;
+use crate::error::CliError;
+use crate::export::ExportKind;
+use crate::scenario::Scenario;
+
+/// Administration CLI for the Emergence simulation.
+#[derive(Debug, Parser)]
+#[command(name = "emergence", version)]
+struct Cli {
+    /// Base URL of the observer API.
+    #[arg(long, env = "EMERGENCE_API_URL", default_value = "http://localhost:8080")]
+    api_url: String,
+
+    /// Bearer token for operator endpoints.
+    #[arg(long, env = "EMERGENCE_OPERATOR_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// `PostgreSQL` URL, for `checkpoint`, `export`, `compact`,
+    /// `verify-chain`, `verify-replay`, `branch`, and `backfill`.
+    #[arg(long, env = "DATABASE_URL", hide_env_values = true)]
+    database_url: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Inspect agents.
+    #[command(subcommand)]
+    Agents(AgentsCommand),
+
+    /// Inspect events.
+    #[command(subcommand)]
+    Events(EventsCommand),
+
+    /// Control the running simulation.
+    #[command(subcommand)]
+    Operator(OperatorCommand),
+
+    /// Queue every event in a scenario file (YAML or JSON) for injection.
+    Inject {
+        /// Path to the scenario file.
+        scenario: PathBuf,
+    },
+
+    /// Save the live world and agent state as an experiment snapshot.
+    Checkpoint {
+        /// Snapshot name.
+        #[arg(long)]
+        name: String,
+        /// Free-text description.
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Experiment the snapshot belongs to.
+        #[arg(long)]
+        experiment: Option<Uuid>,
+        /// Configuration file to store alongside the state.
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+
+    /// Export persisted rows in a tick range as JSON lines.
+    Export {
+        /// What to export.
+        #[arg(value_enum)]
+        kind: ExportKind,
+        /// First tick (inclusive).
+        #[arg(long)]
+        from: u64,
+        /// Last tick (inclusive).
+        #[arg(long)]
+        to: u64,
+        /// Output file (default: stdout).
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Fold routine events that snapshots already cover into summaries.
+    Compact {
+        /// Keep events from this many most recent ticks untouched.
+        #[arg(long, default_value_t = CompactionConfig::new().retention_ticks)]
+        retention_ticks: u64,
+        /// Fold within windows of this many ticks.
+        #[arg(long, default_value_t = CompactionConfig::new().window_ticks)]
+        window_ticks: u64,
+        /// Skip events at or before this tick (a previous run's `through`).
+        #[arg(long, default_value_t = 0)]
+        after: u64,
+    },
+
+    /// Verify the tamper-evident hash chain over the events in a tick range.
+    VerifyChain {
+        /// First tick (inclusive).
+        #[arg(long, default_value_t = 0)]
+        from: u64,
+        /// Last tick (inclusive).
+        #[arg(long)]
+        to: u64,
+    },
+
+    /// Replay a completed run twice and against its snapshots, reporting
+    /// the first tick at which derived state diverges.
+    VerifyReplay {
+        /// Last tick of the run (inclusive).
+        #[arg(long)]
+        to: u64,
+    },
+
+    /// Run event detectors over the recorded history and store what they
+    /// derive, replacing their earlier output.
+    Backfill {
+        /// Last tick to scan (inclusive).
+        #[arg(long)]
+        to: u64,
+        /// Only run these detectors (default: every built-in detector).
+        #[arg(long = "detector")]
+        detectors: Vec<String>,
+    },
+
+    /// Fork the event log into named branches for what-if analysis.
+    #[command(subcommand)]
+    Branch(BranchCommand),
+
+    /// Inspect or unpack `.emrun` run archives.
+    #[command(subcommand)]
+    Archive(ArchiveCommand),
+
+    /// Check a simulation configuration file.
+    ValidateConfig {
+        /// Path to the configuration file.
+        #[arg(default_value = "emergence-config.yaml")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AgentsCommand {
+    /// List agents.
+    List {
+        /// `alive`, `dead`, or `all`.
+        #[arg(long, default_value = "all")]
+        status: String,
+    },
+    /// Show one agent in full.
+    Show {
+        /// Agent ID.
+        id: Uuid,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum EventsCommand {
+    /// Print new events as they happen, one JSON object per line.
+    Tail {
+        /// Only events involving this agent.
+        #[arg(long)]
+        agent: Option<Uuid>,
+        /// Start from this tick instead of the current one.
+        #[arg(long)]
+        from_tick: Option<u64>,
+        /// Polling interval in milliseconds.
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum BranchCommand {
+    /// Fork a branch at a tick; it shares the parent's history before it.
+    Create {
+        /// Name of the new branch.
+        name: String,
+        /// Branch to fork from.
+        #[arg(long, default_value = MAIN_BRANCH)]
+        from: String,
+        /// First tick the new branch records its own events at.
+        #[arg(long)]
+        at: u64,
+    },
+    /// List every branch with its parent and fork tick.
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+enum ArchiveCommand {
+    /// Verify an archive and print its manifest.
+    Info {
+        /// Path to the `.emrun` file.
+        file: PathBuf,
+    },
+    /// Verify an archive and unpack its sections into a directory.
+    Extract {
+        /// Path to the `.emrun` file.
+        file: PathBuf,
+        /// Directory to write the sections to.
+        dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum OperatorCommand {
+    /// Show simulation status.
+    Status,
+    /// Pause the tick loop.
+    Pause,
+    /// Resume the tick loop.
+    Resume,
+    /// Set the tick interval in milliseconds (minimum 100).
+    Speed {
+        /// New tick interval.
+        tick_interval_ms: u64,
+    },
+    /// Shut the simulation down cleanly.
+    Stop,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+async fn run(cli: Cli) -> Result<(), CliError> {
+    let api = ApiClient::new(&cli.api_url)?.with_token(cli.token);
+    match cli.command {
+        Command::Agents(AgentsCommand::List { status }) => {
+            print_json(&api.get("/api/agents", &[("status", status)]).await?)
+        }
+        Command::Agents(AgentsCommand::Show { id }) => {
+            print_json(&api.get(&format!("/api/agents/{id}"), &[]).await?)
+        }
+        Command::Events(EventsCommand::Tail {
+            agent,
+            from_tick,
+            interval_ms,
+        }) => tail_events(&api, agent, from_tick, Duration::from_millis(interval_ms)).await,
+        Command::Operator(command) => {
+            let response = match command {
+                OperatorCommand::Status => api.get("/api/operator/status", &[]).await?,
+                OperatorCommand::Pause => api.post("/api/operator/pause", &()).await?,
+                OperatorCommand::Resume => api.post("/api/operator/resume", &()).await?,
+                OperatorCommand::Stop => api.post("/api/operator/stop", &()).await?,
+                OperatorCommand::Speed { tick_interval_ms } => {
+                    let body = serde_json::json!({ "tick_interval_ms": tick_interval_ms });
+                    api.post("/api/operator/speed", &body).await?
+                }
+            };
+            print_json(&response)
+        }
+        Command::Inject { scenario } => inject(&api, &scenario).await,
+        Command::Checkpoint {
+            name,
+            description,
+            experiment,
+            config,
+        } => {
+            let pool = connect(cli.database_url.as_deref()).await?;
+            let state = live_state(&api).await?;
+            let tick = state
+                .pointer("/world/tick")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0);
+            let config = config.as_deref().map(read_config_json).transpose()?;
+            let id = ExperimentStore::new(pool.pool())
+                .save_snapshot(
+                    experiment,
+                    &name,
+                    &description,
+                    tick,
+                    &config.unwrap_or_else(|| serde_json::json!({})),
+                    &state,
+                )
+                .await?;
+            println!("saved snapshot {id} at tick {tick}");
+            Ok(())
+        }
+        Command::Export {
+            kind,
+            from,
+            to,
+            output,
+        } => {
+            let pool = connect(cli.database_url.as_deref()).await?;
+            let written = if let Some(path) = output {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                export::export(&pool, kind, from, to, &mut file).await?
+            } else {
+                let mut stdout = std::io::BufWriter::new(std::io::stdout());
+                export::export(&pool, kind, from, to, &mut stdout).await?
+            };
+            eprintln!("exported {written} row(s)");
+            Ok(())
+        }
+        Command::Compact {
+            retention_ticks,
+            window_ticks,
+            after,
+        } => {
+            let config = CompactionConfig::new()
+                .with_retention_ticks(retention_ticks)
+                .with_window_ticks(window_ticks);
+            compact(cli.database_url.as_deref(), after, &config).await
+        }
+        Command::VerifyChain { from, to } => {
+            verify_chain(cli.database_url.as_deref(), from, to).await
+        }
+        Command::VerifyReplay { to } => verify_replay(cli.database_url.as_deref(), to).await,
+        Command::Backfill { to, detectors } => {
+            backfill(cli.database_url.as_deref(), to, &detectors).await
+        }
+        Command::Branch(command) => {
+            let pool = connect(cli.database_url.as_deref()).await?;
+            let store = BranchStore::new(pool.pool());
+            match command {
+                BranchCommand::Create { name, from, at } => {
+                    store.create(&name, &from, at).await?;
+                    println!("created branch {name} from {from} at tick {at}");
+                    Ok(())
+                }
+                BranchCommand::List => print_json(&serde_json::to_value(store.list().await?)?),
+            }
+        }
+        Command::Archive(ArchiveCommand::Info { file }) => {
+            print_json(&serde_json::to_value(RunArchive::open(&file)?.manifest())?)
+        }
+        Command::Archive(ArchiveCommand::Extract { file, dir }) => {
+            RunArchive::open(&file)?.extract(&dir)?;
+            println!("extracted {} to {}", file.display(), dir.display());
+            Ok(())
+        }
+        Command::ValidateConfig { path } => {
+            let problems = config_check::check(&std::fs::read_to_string(&path)?);
+            for problem in &problems {
+                eprintln!("{}: {problem}", path.display());
+            }
+            if problems.is_empty() {
+                println!("{} is valid", path.display());
+                Ok(())
+            } else {
+                Err(CliError::InvalidConfig {
+                    path: path.display().to_string(),
+                    count: problems.len(),
+                })
+            }
+        }
+    }
+}
+
+/// Pretty-print a JSON value to stdout.
+fn print_json(value: &serde_json::Value) -> Result<(), CliError> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Connect to `PostgreSQL`, requiring a URL.
+async fn connect(url: Option<&str>) -> Result<PostgresPool, CliError> {
+    let url = url.ok_or_else(|| {
+        emergence_db::DbError::Config("set --database-url or DATABASE_URL".to_owned())
+    })?;
+    Ok(PostgresPool::connect_url(url).await?)
+}
+
+/// Poll the API for each new tick and print its events.
+async fn tail_events(
+    api: &ApiClient,
+    agent: Option<Uuid>,
+    from_tick: Option<u64>,
+    interval: Duration,
+) -> Result<(), CliError> {
+    let current = |world: &serde_json::Value| world.get("tick").and_then(serde_json::Value::as_u64);
+    let mut next = match from_tick {
+        Some(tick) => tick,
+        None => current(&api.get("/api/world", &[]).await?).unwrap_or(0),
+    };
+    loop {
+        let latest = current(&api.get("/api/world", &[]).await?).unwrap_or(0);
+        while next <= latest {
+            let mut query = vec![("tick", next.to_string()), ("limit", "1000".to_owned())];
+            if let Some(agent) = agent {
+                query.push(("agent_id", agent.to_string()));
+            }
+            let page = api.get("/api/events", &query).await?;
+            let events = page.get("events").and_then(serde_json::Value::as_array);
+            for event in events.into_iter().flatten() {
+                println!("{event}");
+            }
+            next = next.saturating_add(1);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Compact the event log and report what was folded.
+async fn compact(
+    database_url: Option<&str>,
+    after: u64,
+    config: &CompactionConfig,
+) -> Result<(), CliError> {
+    let pool = connect(database_url).await?;
+    let report = emergence_events::compact(pool.pool(), after, config).await?;
+    println!(
+        "folded {} event(s) into {} summary event(s) through tick {}",
+        report.folded, report.summaries, report.through_tick
+    );
+    Ok(())
+}
+
+/// Verify the event hash chain and report any breaks.
+async fn verify_chain(database_url: Option<&str>, from: u64, to: u64) -> Result<(), CliError> {
+    let pool = connect(database_url).await?;
+    let report = EventStore::new(pool.pool())
+        .verify_chain(from, to.saturating_add(1))
+        .await?;
+    for broken in &report.breaks {
+        eprintln!("event {} at tick {}: {:?}", broken.id, broken.tick, broken.kind);
+    }
+    let head = report.head.as_ref().map_or_else(
+        || "-".to_owned(),
+        |hash| {
+            hash.iter().fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+        },
+    );
+    println!("checked {} chained event(s), head {head}", report.events);
+    if report.is_intact() {
+        Ok(())
+    } else {
+        Err(CliError::ChainBroken {
+            breaks: report.breaks.len(),
+        })
+    }
+}
+
+/// Verify that replaying the event log rebuilds the same state.
+async fn verify_replay(database_url: Option<&str>, to: u64) -> Result<(), CliError> {
+    let pool = connect(database_url).await?;
+    let report = emergence_events::verify_run(pool.pool(), to).await?;
+    println!(
+        "replayed {} event(s) over {} tick(s), compared {} snapshot(s)",
+        report.events, report.ticks, report.snapshots
+    );
+    let Some(divergence) = report.divergence else {
+        return Ok(());
+    };
+    eprintln!("expected: {}", divergence.expected);
+    eprintln!("actual:   {}", divergence.actual);
+    Err(CliError::ReplayDiverged {
+        tick: divergence.tick,
+        subsystem: divergence.subsystem.to_string(),
+    })
+}
+
+/// Backfill derived events with the named built-in detectors, or all of
+/// them if none are named.
+async fn backfill(database_url: Option<&str>, to: u64, names: &[String]) -> Result<(), CliError> {
+    let mut detectors = emergence_events::builtin_detectors();
+    if let Some(unknown) = names.iter().find(|name| !detectors.iter().any(|d| d.name() == *name)) {
+        return Err(CliError::UnknownDetector(unknown.clone()));
+    }
+    if !names.is_empty() {
+        detectors.retain(|detector| names.iter().any(|name| name == detector.name()));
+    }
+    let pool = connect(database_url).await?;
+    let report = emergence_events::backfill(pool.pool(), &mut detectors, to).await?;
+    for (detector, derived) in &report.derived {
+        println!("{detector}: {derived} event(s)");
+    }
+    println!(
+        "scanned {} event(s) through tick {}, replaced {} earlier derived event(s)",
+        report.scanned, report.through_tick, report.replaced
+    );
+    Ok(())
+}
+
+/// Queue each event of a scenario, stopping at the first rejection.
+async fn inject(api: &ApiClient, path: &Path) -> Result<(), CliError> {
+    let scenario = Scenario::from_file(path)?;
+    for event in &scenario.events {
+        let response = api.post("/api/operator/inject-event", event).await?;
+        let message = response
+            .get("message")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("queued");
+        println!("{message}");
+    }
+    println!(
+        "scenario '{}': {} event(s) queued",
+        scenario.name,
+        scenario.events.len()
+    );
+    Ok(())
+}
+
+/// Gather the world, full agent details, locations, and routes into one
+/// JSON document.
+async fn live_state(api: &ApiClient) -> Result<serde_json::Value, CliError> {
+    let world = api.get("/api/world", &[]).await?;
+    let listing = api.get("/api/agents", &[]).await?;
+    let ids: BTreeSet<String> = listing
+        .get("agents")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|agent| agent.get("id").and_then(serde_json::Value::as_str))
+        .map(str::to_owned)
+        .collect();
+    let mut agents = Vec::with_capacity(ids.len());
+    for id in ids {
+        agents.push(api.get(&format!("/api/agents/{id}"), &[]).await?);
+    }
+    Ok(serde_json::json!({
+        "world": world,
+        "agents": agents,
+        "locations": api.get("/api/locations", &[]).await?,
+        "routes": api.get("/api/routes", &[]).await?,
+    }))
+}
+
+/// Read a YAML configuration file as JSON for storage.
+fn read_config_json(path: &Path) -> Result<serde_json::Value, CliError> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_yml::from_str(&contents).map_err(|source| {
+        CliError::Config(emergence_core::config::ConfigError::Yaml { source })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_compact_arguments() {
+        let cli = Cli::try_parse_from(["emergence", "compact", "--retention-ticks", "500"]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::Compact {
+                retention_ticks: 500,
+                window_ticks: 1_000,
+                after: 0,
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_verify_chain_arguments() {
+        let cli = Cli::try_parse_from(["emergence", "verify-chain", "--to", "500"]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::VerifyChain { from: 0, to: 500 })
+        ));
+    }
+
+    #[test]
+    fn parses_verify_replay_arguments() {
+        let cli = Cli::try_parse_from(["emergence", "verify-replay", "--to", "900"]);
+        assert!(matches!(cli.map(|cli| cli.command), Ok(Command::VerifyReplay { to: 900 })));
+    }
+
+    #[test]
+    fn parses_backfill_arguments() {
+        let cli =
+            Cli::try_parse_from(["emergence", "backfill", "--to", "70", "--detector", "famine"]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::Backfill { to: 70, detectors }) if detectors == ["famine"]
+        ));
+    }
+
+    #[test]
+    fn parses_branch_arguments() {
+        let cli = Cli::try_parse_from(["emergence", "branch", "create", "drought", "--at", "400"]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::Branch(BranchCommand::Create { name, from, at: 400 }))
+                if name == "drought" && from == MAIN_BRANCH
+        ));
+    }
+
+    #[test]
+    fn parses_export_arguments() {
+        let cli = Cli::try_parse_from([
+            "emergence",
+            "export",
+            "world-snapshots",
+            "--from",
+            "10",
+            "--to",
+            "20",
+        ]);
+        assert!(matches!(
+            cli.map(|cli| cli.command),
+            Ok(Command::Export {
+                kind: ExportKind::WorldSnapshots,
+                from: 10,
+                to: 20,
+                output: None,
+            })
+        ));
+    }
+}
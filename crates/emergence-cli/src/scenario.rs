@@ -0,0 +1,140 @@
+//! Scenario files: batches of operator events to inject.
+//!
+//! A scenario is YAML (or JSON, which is valid YAML) holding either a
+//! bare list of events or a document with a `name` and an `events` list:
+//!
+//! ```yaml
+//! name: dry-season
+//! events:
+//!   - event_type: drought
+//!     target_region: Highlands
+//!     severity: high
+//!   - event_type: resource_boom
+//!     description: Fish return to the river
+//! ```
+//!
+//! Each event has the same fields as the body of
+//! `POST /api/operator/inject-event`.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CliError;
+
+/// One event to queue through the operator API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioEvent {
+    /// The type of event to inject (e.g. `plague`, `resource_boom`).
+    pub event_type: String,
+    /// Optional target region.
+    #[serde(default)]
+    pub target_region: Option<String>,
+    /// Optional severity.
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Optional description.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A named batch of events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scenario {
+    /// Scenario name (the file stem when the file does not set one).
+    pub name: String,
+    /// Events in injection order.
+    pub events: Vec<ScenarioEvent>,
+}
+
+/// The two accepted file layouts.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScenarioFile {
+    Document {
+        #[serde(default)]
+        name: Option<String>,
+        events: Vec<ScenarioEvent>,
+    },
+    List(Vec<ScenarioEvent>),
+}
+
+impl Scenario {
+    /// Load a scenario from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::Io`] if the file cannot be read, or
+    /// [`CliError::Scenario`] if it is not a valid scenario.
+    pub fn from_file(path: &Path) -> Result<Self, CliError> {
+        let contents = std::fs::read_to_string(path)?;
+        let fallback = path
+            .file_stem()
+            .map_or_else(|| "scenario".to_owned(), |s| s.to_string_lossy().into_owned());
+        Self::parse(&contents, &fallback).map_err(|message| CliError::Scenario {
+            path: path.display().to_string(),
+            message,
+        })
+    }
+
+    /// Parse scenario text, using `fallback_name` if it does not name
+    /// itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the text does not parse or
+    /// contains no events, or an event has an empty `event_type`.
+    pub fn parse(contents: &str, fallback_name: &str) -> Result<Self, String> {
+        let file: ScenarioFile = serde_yml::from_str(contents).map_err(|e| e.to_string())?;
+        let (name, events) = match file {
+            ScenarioFile::Document { name, events } => (name, events),
+            ScenarioFile::List(events) => (None, events),
+        };
+        if events.is_empty() {
+            return Err("scenario contains no events".to_owned());
+        }
+        if let Some(index) = events.iter().position(|e| e.event_type.trim().is_empty()) {
+            return Err(format!("event {index} has an empty event_type"));
+        }
+        Ok(Self {
+            name: name.unwrap_or_else(|| fallback_name.to_owned()),
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_document() {
+        let yaml = "name: dry-season\nevents:\n  - event_type: drought\n    severity: high\n";
+        let scenario = Scenario::parse(yaml, "file");
+        assert_eq!(scenario.as_ref().map(|s| s.name.as_str()), Ok("dry-season"));
+        assert_eq!(
+            scenario.ok().and_then(|s| s.events.into_iter().next()),
+            Some(ScenarioEvent {
+                event_type: "drought".to_owned(),
+                target_region: None,
+                severity: Some("high".to_owned()),
+                description: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_bare_json_list() {
+        let json = r#"[{"event_type": "plague"}, {"event_type": "resource_boom"}]"#;
+        let scenario = Scenario::parse(json, "outbreak");
+        assert_eq!(scenario.as_ref().map(|s| s.name.as_str()), Ok("outbreak"));
+        assert_eq!(scenario.map(|s| s.events.len()), Ok(2));
+    }
+
+    #[test]
+    fn rejects_empty_and_blank_events() {
+        assert!(Scenario::parse("events: []", "x").is_err());
+        assert!(Scenario::parse("- event_type: ' '", "x").is_err());
+        assert!(Scenario::parse("- severity: high", "x").is_err());
+    }
+}
@@ -0,0 +1,449 @@
+//! Slab storage for agent state, with hot fields mirrored into columns.
+//!
+//! [`AgentStore`] keeps every agent's [`AgentState`] in a slab: one
+//! contiguous vector of rows, where an agent's slot ([`AgentIndex`]) never
+//! changes while it is stored and a removed agent's slot is reused by a
+//! later insert. Alongside the rows, the fields every tick scans for every
+//! agent -- vitals and travel state -- are mirrored into their own dense
+//! columns, so a scan such as "who is at this location" or "the oldest
+//! living agent" walks a few bytes per agent instead of whole rows.
+//!
+//! The row stays the serialization view. [`AgentStore::get`] borrows it,
+//! and [`AgentStore::get_mut`] lends it out behind a guard that refreshes
+//! the columns when dropped, so the two never disagree.
+//!
+//! Every insert and every dropped guard also stamps the row with the
+//! store's next [revision](AgentStore::revision), so a reader that keeps
+//! its own copy -- the observer's published snapshot -- can ask for only
+//! the rows [changed since](AgentStore::changed_since) it last looked.
+
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+
+use emergence_types::{AgentId, AgentState, LocationId};
+
+/// An agent's slot in an [`AgentStore`].
+///
+/// Stable for as long as the agent stays in the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AgentIndex(usize);
+
+impl AgentIndex {
+    /// The slot number.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+/// The vitals column: one agent's energy, health, hunger, thirst, and age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Vitals {
+    /// Current energy (0--100).
+    pub energy: u32,
+    /// Current health (0--100).
+    pub health: u32,
+    /// Current hunger level (0--100).
+    pub hunger: u32,
+    /// Current thirst level (0--100).
+    pub thirst: u32,
+    /// Current age in ticks.
+    pub age: u32,
+}
+
+impl From<&AgentState> for Vitals {
+    fn from(state: &AgentState) -> Self {
+        Self {
+            energy: state.energy,
+            health: state.health,
+            hunger: state.hunger,
+            thirst: state.thirst,
+            age: state.age,
+        }
+    }
+}
+
+/// The travel column: where an agent is and where it is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Travel {
+    /// Current location.
+    pub location_id: LocationId,
+    /// Travel destination, if in transit.
+    pub destination_id: Option<LocationId>,
+    /// Ticks remaining until arrival (0 if not traveling).
+    pub travel_progress: u32,
+}
+
+impl From<&AgentState> for Travel {
+    fn from(state: &AgentState) -> Self {
+        Self {
+            location_id: state.location_id,
+            destination_id: state.destination_id,
+            travel_progress: state.travel_progress,
+        }
+    }
+}
+
+/// Agent state for every agent in the simulation.
+#[derive(Debug, Clone, Default)]
+pub struct AgentStore {
+    /// Agent ID -> slot.
+    slots: BTreeMap<AgentId, AgentIndex>,
+    /// Slot -> agent ID, `None` for a vacant slot.
+    ids: Vec<Option<AgentId>>,
+    /// Slot -> full state, `None` for a vacant slot.
+    rows: Vec<Option<AgentState>>,
+    /// Slot -> vitals, mirrored from the row. Stale for vacant slots.
+    vitals: Vec<Vitals>,
+    /// Slot -> travel state, mirrored from the row. Stale for vacant slots.
+    travel: Vec<Travel>,
+    /// Slot -> revision of the row's last change. Stale for vacant slots.
+    stamps: Vec<u64>,
+    /// Revision of the latest change to any row.
+    revision: u64,
+    /// Vacant slots, reused before the slab grows.
+    free: Vec<AgentIndex>,
+}
+
+impl AgentStore {
+    /// Create an empty store.
+    pub const fn new() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            ids: Vec::new(),
+            rows: Vec::new(),
+            vitals: Vec::new(),
+            travel: Vec::new(),
+            stamps: Vec::new(),
+            revision: 0,
+            free: Vec::new(),
+        }
+    }
+
+    /// Number of agents stored.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Whether `agent_id` is stored.
+    pub fn contains_key(&self, agent_id: &AgentId) -> bool {
+        self.slots.contains_key(agent_id)
+    }
+
+    /// The slot holding `agent_id`.
+    pub fn index_of(&self, agent_id: &AgentId) -> Option<AgentIndex> {
+        self.slots.get(agent_id).copied()
+    }
+
+    /// The agent in slot `index`, if occupied.
+    pub fn id_at(&self, index: AgentIndex) -> Option<AgentId> {
+        self.ids.get(index.0).copied().flatten()
+    }
+
+    /// Store `state`, replacing any state already held for its agent.
+    ///
+    /// A replaced agent keeps its slot; a new agent takes a vacant slot if
+    /// there is one.
+    pub fn insert(&mut self, state: AgentState) -> AgentIndex {
+        let agent_id = state.agent_id;
+        let vitals = Vitals::from(&state);
+        let travel = Travel::from(&state);
+        let index = self
+            .index_of(&agent_id)
+            .or_else(|| self.free.pop())
+            .unwrap_or_else(|| {
+                self.ids.push(None);
+                self.rows.push(None);
+                self.vitals.push(vitals);
+                self.travel.push(travel);
+                self.stamps.push(0);
+                AgentIndex(self.ids.len().saturating_sub(1))
+            });
+        let i = index.0;
+        self.revision = self.revision.saturating_add(1);
+        if let (Some(id), Some(row), Some(v), Some(t), Some(stamp)) = (
+            self.ids.get_mut(i),
+            self.rows.get_mut(i),
+            self.vitals.get_mut(i),
+            self.travel.get_mut(i),
+            self.stamps.get_mut(i),
+        ) {
+            *id = Some(agent_id);
+            *row = Some(state);
+            *v = vitals;
+            *t = travel;
+            *stamp = self.revision;
+        }
+        self.slots.insert(agent_id, index);
+        index
+    }
+
+    /// Remove and return `agent_id`'s state, freeing its slot.
+    pub fn remove(&mut self, agent_id: &AgentId) -> Option<AgentState> {
+        let index = self.slots.remove(agent_id)?;
+        self.revision = self.revision.saturating_add(1);
+        if let Some(id) = self.ids.get_mut(index.0) {
+            *id = None;
+        }
+        self.free.push(index);
+        self.rows.get_mut(index.0)?.take()
+    }
+
+    /// `agent_id`'s full state.
+    pub fn get(&self, agent_id: &AgentId) -> Option<&AgentState> {
+        self.row(self.index_of(agent_id)?)
+    }
+
+    /// The full state in slot `index`.
+    pub fn row(&self, index: AgentIndex) -> Option<&AgentState> {
+        self.rows.get(index.0)?.as_ref()
+    }
+
+    /// Borrow `agent_id`'s full state for modification.
+    ///
+    /// The vitals and travel columns are refreshed from the state, and the
+    /// row stamped with a new revision, when the returned guard is dropped
+    /// -- whether or not anything was changed through it. Changing the guard's `agent_id` has no
+    /// effect on where the state is stored.
+    pub fn get_mut(&mut self, agent_id: &AgentId) -> Option<AgentStateMut<'_>> {
+        let i = self.index_of(agent_id)?.0;
+        Some(AgentStateMut {
+            state: self.rows.get_mut(i)?.as_mut()?,
+            vitals: self.vitals.get_mut(i)?,
+            travel: self.travel.get_mut(i)?,
+            stamp: self.stamps.get_mut(i)?,
+            revision: &mut self.revision,
+        })
+    }
+
+    /// Revision of the latest insert, removal, or guarded change.
+    ///
+    /// Starts at zero for an empty store and only ever grows.
+    pub const fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Every stored agent whose row was inserted or borrowed mutably after
+    /// `revision`, scanned in slot order.
+    ///
+    /// Removed agents are not reported; a caller mirroring the store
+    /// notices them by its copy holding more agents than the store.
+    pub fn changed_since(
+        &self,
+        revision: u64,
+    ) -> impl Iterator<Item = (AgentId, &AgentState)> + '_ {
+        self.ids
+            .iter()
+            .zip(&self.stamps)
+            .zip(&self.rows)
+            .filter(move |((_, stamp), _)| **stamp > revision)
+            .filter_map(|((id, _), row)| Some(((*id)?, row.as_ref()?)))
+    }
+
+    /// `agent_id`'s vitals, from the vitals column.
+    pub fn vitals(&self, agent_id: &AgentId) -> Option<Vitals> {
+        self.vitals.get(self.index_of(agent_id)?.0).copied()
+    }
+
+    /// `agent_id`'s travel state, from the travel column.
+    pub fn travel(&self, agent_id: &AgentId) -> Option<Travel> {
+        self.travel.get(self.index_of(agent_id)?.0).copied()
+    }
+
+    /// `agent_id`'s current location, from the travel column.
+    pub fn location(&self, agent_id: &AgentId) -> Option<LocationId> {
+        self.travel(agent_id).map(|travel| travel.location_id)
+    }
+
+    /// Every stored agent's location, scanned in slot order.
+    pub fn locations(&self) -> impl Iterator<Item = (AgentId, LocationId)> + '_ {
+        self.ids
+            .iter()
+            .zip(&self.travel)
+            .filter_map(|(id, travel)| id.map(|id| (id, travel.location_id)))
+    }
+
+    /// Every stored agent's vitals, scanned in slot order.
+    pub fn all_vitals(&self) -> impl Iterator<Item = (AgentId, Vitals)> + '_ {
+        self.ids
+            .iter()
+            .zip(&self.vitals)
+            .filter_map(|(id, vitals)| id.map(|id| (id, *vitals)))
+    }
+
+    /// Stored agent IDs, in ID order.
+    pub fn ids(&self) -> impl Iterator<Item = AgentId> + '_ {
+        self.slots.keys().copied()
+    }
+
+    /// Every stored agent's state, in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (AgentId, &AgentState)> + '_ {
+        self.slots
+            .iter()
+            .filter_map(|(id, index)| self.row(*index).map(|state| (*id, state)))
+    }
+
+    /// Every stored agent's state, in ID order.
+    pub fn values(&self) -> impl Iterator<Item = &AgentState> + '_ {
+        self.iter().map(|(_, state)| state)
+    }
+
+    /// Copy every agent's state into a map keyed by agent ID.
+    pub fn to_map(&self) -> BTreeMap<AgentId, AgentState> {
+        self.iter().map(|(id, state)| (id, state.clone())).collect()
+    }
+}
+
+impl FromIterator<AgentState> for AgentStore {
+    fn from_iter<I: IntoIterator<Item = AgentState>>(iter: I) -> Self {
+        let mut store = Self::new();
+        for state in iter {
+            store.insert(state);
+        }
+        store
+    }
+}
+
+/// An agent's state borrowed mutably from an [`AgentStore`]. Refreshes
+/// the store's vitals and travel columns, and stamps the row with a new
+/// revision, when dropped.
+#[derive(Debug)]
+pub struct AgentStateMut<'a> {
+    state: &'a mut AgentState,
+    vitals: &'a mut Vitals,
+    travel: &'a mut Travel,
+    stamp: &'a mut u64,
+    revision: &'a mut u64,
+}
+
+impl Deref for AgentStateMut<'_> {
+    type Target = AgentState;
+
+    fn deref(&self) -> &AgentState {
+        self.state
+    }
+}
+
+impl DerefMut for AgentStateMut<'_> {
+    fn deref_mut(&mut self) -> &mut AgentState {
+        self.state
+    }
+}
+
+impl Drop for AgentStateMut<'_> {
+    fn drop(&mut self) {
+        *self.vitals = Vitals::from(&*self.state);
+        *self.travel = Travel::from(&*self.state);
+        *self.revision = self.revision.saturating_add(1);
+        *self.stamp = *self.revision;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::Resource;
+
+    use super::*;
+
+    fn agent(location_id: LocationId) -> AgentState {
+        AgentState::builder(AgentId::new(), location_id)
+            .resource(Resource::Wood, 3)
+            .knowledge(std::iter::once("fire".to_owned()).collect())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips_the_full_state() {
+        let mut state = agent(LocationId::new());
+        state.goals.push("stay warm".to_owned());
+        state.destination_id = Some(LocationId::new());
+        state.travel_progress = 2;
+
+        let mut store = AgentStore::new();
+        store.insert(state.clone());
+        assert_eq!(store.get(&state.agent_id), Some(&state));
+        assert_eq!(store.to_map().get(&state.agent_id), Some(&state));
+        assert_eq!(store.location(&state.agent_id), Some(state.location_id));
+    }
+
+    #[test]
+    fn indices_are_stable_and_slots_reused() {
+        let location = LocationId::new();
+        let (a, b, c) = (agent(location), agent(location), agent(location));
+        let mut store: AgentStore = [a.clone(), b.clone()].into_iter().collect();
+        let index_b = store.index_of(&b.agent_id).unwrap();
+
+        assert_eq!(store.remove(&a.agent_id), Some(a.clone()));
+        assert_eq!(store.index_of(&b.agent_id), Some(index_b));
+        assert_eq!(store.get(&a.agent_id), None);
+
+        let index_c = store.insert(c.clone());
+        assert_eq!(index_c.get(), 0);
+        assert_eq!(store.id_at(index_c), Some(c.agent_id));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.locations().count(), 2);
+        assert_eq!(store.get(&c.agent_id), Some(&c));
+    }
+
+    #[test]
+    fn guard_writes_back_on_drop() {
+        let state = agent(LocationId::new());
+        let id = state.agent_id;
+        let mut store: AgentStore = std::iter::once(state).collect();
+
+        if let Some(mut s) = store.get_mut(&id) {
+            s.energy = 12;
+            s.inventory.insert(Resource::Stone, 4);
+            s.memory.clear();
+        }
+        let after = store.get(&id).unwrap();
+        assert_eq!(after.energy, 12);
+        assert_eq!(after.inventory.get(&Resource::Stone), Some(&4));
+        assert_eq!(after.inventory.get(&Resource::Wood), Some(&3));
+        assert_eq!(store.vitals(&id).map(|v| v.energy), Some(12));
+    }
+
+    #[test]
+    fn columns_follow_the_row() {
+        let state = agent(LocationId::new());
+        let id = state.agent_id;
+        let destination = LocationId::new();
+        let mut store: AgentStore = std::iter::once(state).collect();
+
+        if let Some(mut s) = store.get_mut(&id) {
+            s.location_id = destination;
+            s.age = 40;
+        }
+        assert_eq!(store.location(&id), Some(destination));
+        assert_eq!(store.vitals(&id).map(|v| v.age), Some(40));
+        assert_eq!(store.locations().collect::<Vec<_>>(), vec![(id, destination)]);
+        assert_eq!(store.all_vitals().map(|(_, v)| v.age).sum::<u32>(), 40);
+    }
+
+    #[test]
+    fn reports_rows_changed_since_a_revision() {
+        let location = LocationId::new();
+        let (a, b) = (agent(location), agent(location));
+        let mut store: AgentStore = [a.clone(), b.clone()].into_iter().collect();
+        let seen = store.revision();
+        assert_eq!(store.changed_since(0).count(), 2);
+        assert_eq!(store.changed_since(seen).count(), 0);
+
+        if let Some(mut s) = store.get_mut(&b.agent_id) {
+            s.hunger = 30;
+        }
+        let changed: Vec<AgentId> = store.changed_since(seen).map(|(id, _)| id).collect();
+        assert_eq!(changed, vec![b.agent_id]);
+
+        let seen = store.revision();
+        store.remove(&a.agent_id);
+        assert!(store.revision() > seen);
+        assert_eq!(store.changed_since(seen).count(), 0);
+    }
+}
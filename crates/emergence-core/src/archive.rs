@@ -0,0 +1,524 @@
+//! Single-file run archives (`.emrun`).
+//!
+//! A [`RunArchive`] bundles everything needed to publish a finished run
+//! and replay it elsewhere: the configuration YAML (which carries the world
+//! seed), the final [`SimulationSnapshot`], the full event log, the ledger,
+//! and every agent decision record.
+//!
+//! # Format
+//!
+//! An archive is a UTF-8 text file. The first line is the magic
+//! `EMRUN/<version>`; the rest is a fixed sequence of sections, each a
+//! header line followed by exactly `<bytes>` bytes of body and a newline:
+//!
+//! ```text
+//! EMRUN/1
+//! manifest <bytes> <fnv1a-64 hex>
+//! {"format_version":1,"name":"baseline",...}
+//! config <bytes> <fnv1a-64 hex>
+//! world:
+//!   seed: 42
+//! ...
+//! snapshot <bytes> <fnv1a-64 hex>
+//! events <bytes> <fnv1a-64 hex>      (one JSON event per line)
+//! ledger <bytes> <fnv1a-64 hex>      (one JSON ledger entry per line)
+//! decisions <bytes> <fnv1a-64 hex>   (one JSON decision record per line)
+//! ```
+//!
+//! Each body is checked against its checksum on read. Serialization is
+//! deterministic: the same run always produces byte-identical archives
+//! apart from the manifest's `created_at`.
+
+use std::fmt::Write as _;
+use std::io::{BufRead, Read, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use emergence_types::{DecisionRecord, Event, LedgerEntry};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+
+use crate::config::{ConfigError, SimulationConfig};
+use crate::experiment::SimulationSnapshot;
+
+/// File extension for run archives.
+pub const ARCHIVE_EXTENSION: &str = "emrun";
+
+/// Version of the archive layout written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Magic prefix of the first line.
+const MAGIC: &str = "EMRUN/";
+
+/// Section names, in file order.
+const SECTIONS: [&str; 6] = ["manifest", "config", "snapshot", "events", "ledger", "decisions"];
+
+/// Longest header line accepted when reading.
+const MAX_HEADER_LEN: u64 = 256;
+
+/// Error type for archive operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    /// Reading or writing the archive failed.
+    #[error("archive I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The bundled configuration does not parse.
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    /// The file is not a run archive or its structure is broken.
+    #[error("malformed archive: {0}")]
+    Malformed(String),
+
+    /// The archive was written by a newer, unknown format version.
+    #[error("unsupported archive format version {0} (this build reads {FORMAT_VERSION})")]
+    UnsupportedVersion(u32),
+
+    /// A section's body does not match its recorded checksum.
+    #[error("checksum mismatch in section `{0}`")]
+    Checksum(&'static str),
+
+    /// A section's body is not valid JSON for its type.
+    #[error("invalid JSON in section `{section}`: {message}")]
+    Json {
+        /// The section being decoded.
+        section: &'static str,
+        /// The decoder's message, including the line for JSON-lines sections.
+        message: String,
+    },
+}
+
+/// Summary of an archive, stored as its first section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// Layout version ([`FORMAT_VERSION`] when written by this build).
+    pub format_version: u32,
+    /// Human-readable run name.
+    pub name: String,
+    /// World seed from the bundled configuration.
+    pub seed: u64,
+    /// Tick of the final snapshot.
+    pub final_tick: u64,
+    /// Number of events in the archive.
+    pub event_count: u64,
+    /// Number of ledger entries in the archive.
+    pub ledger_entry_count: u64,
+    /// Number of decision records in the archive.
+    pub decision_count: u64,
+    /// Version of the crate that wrote the archive.
+    pub engine_version: String,
+    /// When the archive was created.
+    pub created_at: DateTime<Utc>,
+}
+
+/// A complete, self-contained record of one simulation run.
+#[derive(Debug, Clone)]
+pub struct RunArchive {
+    /// Human-readable run name.
+    pub name: String,
+    /// The simulation configuration, verbatim.
+    pub config_yaml: String,
+    /// World seed parsed from `config_yaml`.
+    pub seed: u64,
+    /// State at the end of the run.
+    pub final_snapshot: SimulationSnapshot,
+    /// Every event, in the order it was recorded.
+    pub events: Vec<Event>,
+    /// Every ledger entry, in the order it was recorded.
+    pub ledger: Vec<LedgerEntry>,
+    /// Every agent decision, in the order it was recorded.
+    pub decisions: Vec<DecisionRecord>,
+    /// When the archive was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl RunArchive {
+    /// Start an archive for a run that used `config_yaml` and ended in
+    /// `final_snapshot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Config`] if `config_yaml` does not parse, so
+    /// an archive can never carry a configuration it cannot be replayed
+    /// from.
+    pub fn new(
+        name: &str,
+        config_yaml: &str,
+        final_snapshot: SimulationSnapshot,
+    ) -> Result<Self, ArchiveError> {
+        let config = SimulationConfig::parse(config_yaml)?;
+        Ok(Self {
+            name: name.to_owned(),
+            config_yaml: config_yaml.to_owned(),
+            seed: config.world.seed,
+            final_snapshot,
+            events: Vec::new(),
+            ledger: Vec::new(),
+            decisions: Vec::new(),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Set the event log.
+    #[must_use]
+    pub fn with_events(mut self, events: Vec<Event>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Set the ledger entries.
+    #[must_use]
+    pub fn with_ledger(mut self, ledger: Vec<LedgerEntry>) -> Self {
+        self.ledger = ledger;
+        self
+    }
+
+    /// Set the decision records.
+    #[must_use]
+    pub fn with_decisions(mut self, decisions: Vec<DecisionRecord>) -> Self {
+        self.decisions = decisions;
+        self
+    }
+
+    /// The manifest that will be written for this archive.
+    pub fn manifest(&self) -> ArchiveManifest {
+        let count = |len: usize| u64::try_from(len).unwrap_or(u64::MAX);
+        ArchiveManifest {
+            format_version: FORMAT_VERSION,
+            name: self.name.clone(),
+            seed: self.seed,
+            final_tick: self.final_snapshot.tick,
+            event_count: count(self.events.len()),
+            ledger_entry_count: count(self.ledger.len()),
+            decision_count: count(self.decisions.len()),
+            engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+            created_at: self.created_at,
+        }
+    }
+
+    /// The body of every section, in file order.
+    fn section_bodies(&self) -> Result<[String; 6], ArchiveError> {
+        Ok([
+            to_json("manifest", &self.manifest())?,
+            self.config_yaml.clone(),
+            to_json("snapshot", &self.final_snapshot)?,
+            to_json_lines("events", &self.events)?,
+            to_json_lines("ledger", &self.ledger)?,
+            to_json_lines("decisions", &self.decisions)?,
+        ])
+    }
+
+    /// Write the archive to `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Io`] if writing fails, or
+    /// [`ArchiveError::Json`] if a record cannot be serialized.
+    pub fn write_to<W: Write>(&self, out: &mut W) -> Result<(), ArchiveError> {
+        writeln!(out, "{MAGIC}{FORMAT_VERSION}")?;
+        for (name, body) in SECTIONS.iter().zip(self.section_bodies()?) {
+            writeln!(out, "{name} {} {:016x}", body.len(), fnv1a(body.as_bytes()))?;
+            out.write_all(body.as_bytes())?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Read an archive from `input`, verifying every section.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Malformed`], [`ArchiveError::UnsupportedVersion`],
+    /// [`ArchiveError::Checksum`], or [`ArchiveError::Json`] if the input
+    /// is not a valid archive, and [`ArchiveError::Io`] if reading fails.
+    pub fn read_from<R: BufRead>(input: &mut R) -> Result<Self, ArchiveError> {
+        let magic = read_header(input)?;
+        let version = magic
+            .strip_prefix(MAGIC)
+            .and_then(|v| v.parse::<u32>().ok())
+            .ok_or_else(|| ArchiveError::Malformed("missing EMRUN header".to_owned()))?;
+        if version != FORMAT_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version));
+        }
+
+        let mut bodies: Vec<String> = Vec::with_capacity(SECTIONS.len());
+        for name in SECTIONS {
+            bodies.push(read_section(input, name)?);
+        }
+        let [manifest, config_yaml, snapshot, events, ledger, decisions]: [String; 6] = bodies
+            .try_into()
+            .map_err(|found: Vec<String>| {
+                ArchiveError::Malformed(format!("expected 6 sections, found {}", found.len()))
+            })?;
+
+        let manifest: ArchiveManifest = from_json("manifest", &manifest)?;
+        let archive = Self {
+            name: manifest.name.clone(),
+            config_yaml,
+            seed: manifest.seed,
+            final_snapshot: from_json("snapshot", &snapshot)?,
+            events: from_json_lines("events", &events)?,
+            ledger: from_json_lines("ledger", &ledger)?,
+            decisions: from_json_lines("decisions", &decisions)?,
+            created_at: manifest.created_at,
+        };
+        let actual = archive.manifest();
+        if (actual.final_tick, actual.event_count, actual.ledger_entry_count, actual.decision_count)
+            != (
+                manifest.final_tick,
+                manifest.event_count,
+                manifest.ledger_entry_count,
+                manifest.decision_count,
+            )
+        {
+            return Err(ArchiveError::Malformed(
+                "manifest does not match archive contents".to_owned(),
+            ));
+        }
+        Ok(archive)
+    }
+
+    /// Write the archive to a file at `path`, replacing any existing file.
+    ///
+    /// # Errors
+    ///
+    /// See [`RunArchive::write_to`].
+    pub fn create(&self, path: &Path) -> Result<(), ArchiveError> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_to(&mut out)
+    }
+
+    /// Read the archive file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// See [`RunArchive::read_from`].
+    pub fn open(path: &Path) -> Result<Self, ArchiveError> {
+        let mut input = std::io::BufReader::new(std::fs::File::open(path)?);
+        Self::read_from(&mut input)
+    }
+
+    /// Unpack every section into `dir` as ordinary files: `manifest.json`,
+    /// `config.yaml`, `snapshot.json`, `events.jsonl`, `ledger.jsonl`, and
+    /// `decisions.jsonl`. The directory is created if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Io`] if a file cannot be written.
+    pub fn extract(&self, dir: &Path) -> Result<(), ArchiveError> {
+        std::fs::create_dir_all(dir)?;
+        let files = [
+            "manifest.json",
+            "config.yaml",
+            "snapshot.json",
+            "events.jsonl",
+            "ledger.jsonl",
+            "decisions.jsonl",
+        ];
+        for (file, body) in files.iter().zip(self.section_bodies()?) {
+            std::fs::write(dir.join(file), body)?;
+        }
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a hash, used as the section checksum.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes
+        .iter()
+        .fold(OFFSET, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(PRIME))
+}
+
+/// Read one header line, without its newline.
+fn read_header<R: BufRead>(input: &mut R) -> Result<String, ArchiveError> {
+    let mut line = Vec::new();
+    input.by_ref().take(MAX_HEADER_LEN).read_until(b'\n', &mut line)?;
+    if line.pop() != Some(b'\n') {
+        return Err(ArchiveError::Malformed("truncated header line".to_owned()));
+    }
+    String::from_utf8(line)
+        .map_err(|e| ArchiveError::Malformed(format!("header is not UTF-8: {e}")))
+}
+
+/// Read the section `name`, checking its length and checksum.
+fn read_section<R: BufRead>(input: &mut R, name: &'static str) -> Result<String, ArchiveError> {
+    let header = read_header(input)?;
+    let mut fields = header.split(' ');
+    let (Some(found), Some(len), Some(checksum), None) =
+        (fields.next(), fields.next(), fields.next(), fields.next())
+    else {
+        return Err(ArchiveError::Malformed(format!("bad header for section `{name}`")));
+    };
+    if found != name {
+        return Err(ArchiveError::Malformed(format!(
+            "expected section `{name}`, found `{found}`"
+        )));
+    }
+    let len: u64 = len
+        .parse()
+        .map_err(|e| ArchiveError::Malformed(format!("bad length for section `{name}`: {e}")))?;
+    let checksum = u64::from_str_radix(checksum, 16)
+        .map_err(|e| ArchiveError::Malformed(format!("bad checksum for section `{name}`: {e}")))?;
+
+    let mut body = Vec::new();
+    input.by_ref().take(len).read_to_end(&mut body)?;
+    let mut newline = [0_u8; 1];
+    if u64::try_from(body.len()).ok() != Some(len) || input.read(&mut newline)? != 1 {
+        return Err(ArchiveError::Malformed(format!("section `{name}` is truncated")));
+    }
+    if newline != *b"\n" {
+        return Err(ArchiveError::Malformed(format!("section `{name}` is not terminated")));
+    }
+    if fnv1a(&body) != checksum {
+        return Err(ArchiveError::Checksum(name));
+    }
+    String::from_utf8(body)
+        .map_err(|e| ArchiveError::Malformed(format!("section `{name}` is not UTF-8: {e}")))
+}
+
+fn to_json<T: Serialize>(section: &'static str, value: &T) -> Result<String, ArchiveError> {
+    serde_json::to_string(value).map_err(|e| ArchiveError::Json {
+        section,
+        message: e.to_string(),
+    })
+}
+
+fn to_json_lines<T: Serialize>(section: &'static str, items: &[T]) -> Result<String, ArchiveError> {
+    let mut body = String::new();
+    for item in items {
+        let _ = writeln!(body, "{}", to_json(section, item)?);
+    }
+    Ok(body)
+}
+
+fn from_json<T: DeserializeOwned>(section: &'static str, body: &str) -> Result<T, ArchiveError> {
+    serde_json::from_str(body).map_err(|e| ArchiveError::Json {
+        section,
+        message: e.to_string(),
+    })
+}
+
+fn from_json_lines<T: DeserializeOwned>(
+    section: &'static str,
+    body: &str,
+) -> Result<Vec<T>, ArchiveError> {
+    body.lines()
+        .zip(1_usize..)
+        .map(|(line, number)| {
+            serde_json::from_str(line).map_err(|e| ArchiveError::Json {
+                section,
+                message: format!("line {number}: {e}"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::arithmetic_side_effects)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{Era, EventType, Season, Weather, WorldContext};
+
+    use super::*;
+
+    fn archive() -> RunArchive {
+        let snapshot = SimulationSnapshot {
+            tick: 3,
+            clock: serde_json::json!({"tick": 3}),
+            world_map: serde_json::json!({}),
+            agents: BTreeMap::new(),
+            agent_states: BTreeMap::new(),
+            agent_names: BTreeMap::new(),
+            alive_agents: Vec::new(),
+            vitals_config: serde_json::json!({}),
+            weather_seed: 0,
+        };
+        let context = WorldContext {
+            tick: 2,
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 4,
+        };
+        let event = Event::builder(EventType::TickStart, context).build().unwrap();
+        RunArchive::new("baseline", "world:\n  seed: 42\n", snapshot)
+            .unwrap()
+            .with_events(vec![event])
+    }
+
+    fn bytes(archive: &RunArchive) -> Vec<u8> {
+        let mut out = Vec::new();
+        archive.write_to(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let original = archive();
+        let written = bytes(&original);
+        assert!(written.starts_with(b"EMRUN/1\nmanifest "));
+
+        let restored = RunArchive::read_from(&mut written.as_slice()).unwrap();
+        assert_eq!(restored.seed, 42);
+        assert_eq!(restored.config_yaml, original.config_yaml);
+        assert_eq!(restored.events, original.events);
+        assert_eq!(restored.manifest(), original.manifest());
+        assert_eq!(bytes(&restored), written);
+    }
+
+    #[test]
+    fn rejects_corruption() {
+        let written = bytes(&archive());
+        // Flip a bit in the first byte of the events body.
+        let header = written.windows(8).position(|w| w == b"\nevents ").unwrap();
+        let body = header + 1 + written.iter().skip(header + 1).position(|&b| b == b'\n').unwrap();
+        let mut flipped = written.clone();
+        *flipped.get_mut(body + 1).unwrap() ^= 1;
+        assert!(matches!(
+            RunArchive::read_from(&mut flipped.as_slice()),
+            Err(ArchiveError::Checksum("events"))
+        ));
+
+        let mut truncated = written.get(..written.len().saturating_sub(10)).unwrap();
+        assert!(matches!(
+            RunArchive::read_from(&mut truncated),
+            Err(ArchiveError::Malformed(_))
+        ));
+
+        let future = [b"EMRUN/9\n".as_slice(), written.get(8..).unwrap()].concat();
+        assert!(matches!(
+            RunArchive::read_from(&mut future.as_slice()),
+            Err(ArchiveError::UnsupportedVersion(9))
+        ));
+    }
+
+    #[test]
+    fn rejects_unparseable_config() {
+        let snapshot = archive().final_snapshot;
+        assert!(matches!(
+            RunArchive::new("bad", "world: [", snapshot),
+            Err(ArchiveError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn extracts_sections_to_files() {
+        let dir = std::env::temp_dir().join(format!("emrun-test-{}", uuid::Uuid::now_v7()));
+        let original = archive();
+        original.extract(&dir).unwrap();
+        let events = std::fs::read_to_string(dir.join("events.jsonl")).unwrap();
+        assert_eq!(events.lines().count(), 1);
+        let config = std::fs::read_to_string(dir.join("config.yaml")).unwrap();
+        assert_eq!(config, original.config_yaml);
+
+        let file = dir.join(format!("run.{ARCHIVE_EXTENSION}"));
+        original.create(&file).unwrap();
+        assert_eq!(RunArchive::open(&file).unwrap().events, original.events);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
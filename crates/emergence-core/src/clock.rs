@@ -177,6 +177,16 @@ impl WorldClock {
     /// Returns [`ClockError::InvalidConfig`] if the season list is empty
     /// or the computed index is out of bounds.
     pub fn season(&self) -> Result<Season, ClockError> {
+        self.season_at(self.tick)
+    }
+
+    /// Compute the season at `tick`, past or future.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClockError::InvalidConfig`] if the season list is empty
+    /// or the computed index is out of bounds.
+    pub fn season_at(&self, tick: u64) -> Result<Season, ClockError> {
         let season_count = u64::try_from(self.seasons.len()).map_err(|_err| {
             ClockError::InvalidConfig {
                 reason: "season count exceeds u64 range".to_owned(),
@@ -185,7 +195,7 @@ impl WorldClock {
 
         // Division is safe: ticks_per_season >= 1 and season_count >= 1
         // are guaranteed by the constructor.
-        let raw_index = self.tick.checked_div(self.ticks_per_season).ok_or_else(|| {
+        let raw_index = tick.checked_div(self.ticks_per_season).ok_or_else(|| {
             ClockError::InvalidConfig {
                 reason: "ticks_per_season is zero".to_owned(),
             }
@@ -402,6 +412,16 @@ mod tests {
         assert_eq!(clock.ticks_per_year().unwrap(), 360);
     }
 
+    #[test]
+    fn season_at_looks_ahead_without_advancing() {
+        let cfg = default_time_config();
+        let clock = make_clock(&cfg);
+        assert_eq!(clock.season_at(89).unwrap(), Season::Spring);
+        assert_eq!(clock.season_at(90).unwrap(), Season::Summer);
+        assert_eq!(clock.season_at(360).unwrap(), Season::Spring);
+        assert_eq!(clock.tick(), 0);
+    }
+
     #[test]
     fn ticks_until_season_change() {
         let cfg = default_time_config();
@@ -9,6 +9,9 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use emergence_world::{Climate, RegenCurves, WorldGenParams};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
 use serde::Deserialize;
 
 /// Errors that can occur when loading configuration.
@@ -46,6 +49,10 @@ pub struct SimulationConfig {
     #[serde(default)]
     pub world: WorldConfig,
 
+    /// Geography of the world map.
+    #[serde(default)]
+    pub map: MapConfig,
+
     /// Time and season settings.
     #[serde(default)]
     pub time: TimeConfig,
@@ -85,6 +92,10 @@ pub struct SimulationConfig {
     /// Operator control configuration.
     #[serde(default)]
     pub operator: OperatorConfig,
+
+    /// WASM plugins providing custom mechanics.
+    #[serde(default)]
+    pub plugins: PluginsConfig,
 }
 
 impl SimulationConfig {
@@ -160,6 +171,73 @@ impl Default for WorldConfig {
     }
 }
 
+/// World map geography.
+///
+/// By default the engine uses the fixed 12-location starting world. With
+/// `procedural` set, it instead generates a map from the world seed and
+/// these parameters (see [`emergence_world::generate_world`]). With `file`
+/// set, it loads a saved or hand-authored world file instead of either
+/// (see [`emergence_world::world_file`]).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MapConfig {
+    /// Path to a world file to load the map from.
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Whether to generate the map instead of using the starting world.
+    #[serde(default)]
+    pub procedural: bool,
+
+    /// Number of locations to generate.
+    #[serde(default = "default_location_count")]
+    pub location_count: u32,
+
+    /// Number of regions to spread the locations across.
+    #[serde(default = "default_region_count")]
+    pub region_count: u32,
+
+    /// Multiplier on every resource node's quantity, regeneration, and
+    /// capacity; 1.0 matches the starting world.
+    #[serde(default = "default_resource_richness")]
+    pub resource_richness: f64,
+
+    /// Extra routes per location beyond the minimum that keeps the map
+    /// connected.
+    #[serde(default = "default_connectivity")]
+    pub connectivity: f64,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            procedural: false,
+            location_count: default_location_count(),
+            region_count: default_region_count(),
+            resource_richness: default_resource_richness(),
+            connectivity: default_connectivity(),
+        }
+    }
+}
+
+impl MapConfig {
+    /// The world generation parameters for a world with the given seed.
+    ///
+    /// A richness or connectivity that is not a finite number falls back to
+    /// its default.
+    pub fn gen_params(&self, seed: u64) -> WorldGenParams {
+        let defaults = WorldGenParams::default();
+        WorldGenParams {
+            seed,
+            location_count: self.location_count,
+            region_count: self.region_count,
+            resource_richness: Decimal::from_f64(self.resource_richness)
+                .unwrap_or(defaults.resource_richness),
+            connectivity: Decimal::from_f64(self.connectivity).unwrap_or(defaults.connectivity),
+        }
+    }
+}
+
 /// Time and season configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct TimeConfig {
@@ -277,6 +355,26 @@ pub struct EnvironmentConfig {
     /// Whether structures decay over time.
     #[serde(default = "default_true")]
     pub structure_decay_enabled: bool,
+
+    /// Chance of a random flood, wildfire, or earthquake each tick, in
+    /// parts per million. 0 disables random disasters; operator-injected
+    /// ones strike either way.
+    #[serde(default)]
+    pub disaster_chance_per_million: u32,
+
+    /// Percentage of the usual harvest a farm plot watered by a river,
+    /// lake, or irrigation channel yields. A drought halves the gain.
+    #[serde(default = "default_irrigation_yield_pct")]
+    pub irrigation_yield_pct: u32,
+
+    /// Long-term climate drift. Off by default.
+    #[serde(default)]
+    pub climate: ClimateConfig,
+
+    /// How resources regrow through the seasons in each biome. A
+    /// `resources` or `biomes` map given here replaces the default one.
+    #[serde(default)]
+    pub regeneration: RegenCurves,
 }
 
 impl Default for EnvironmentConfig {
@@ -285,10 +383,47 @@ impl Default for EnvironmentConfig {
             weather_enabled: true,
             seasons_enabled: true,
             structure_decay_enabled: true,
+            disaster_chance_per_million: 0,
+            irrigation_yield_pct: default_irrigation_yield_pct(),
+            climate: ClimateConfig::default(),
+            regeneration: RegenCurves::default(),
         }
     }
 }
 
+/// Long-term climate drift: how far the weather warms and dries, and how
+/// much regeneration falls, over how many ticks.
+///
+/// Every field defaults to 0, which leaves the climate steady.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, serde::Serialize)]
+pub struct ClimateConfig {
+    /// Ticks until the drift reaches full strength. 0 disables drift.
+    #[serde(default)]
+    pub drift_ticks: u64,
+
+    /// Percentage of snow that has turned to rain at full strength.
+    #[serde(default)]
+    pub warming_pct: u32,
+
+    /// Percentage of rain that has turned to drought at full strength.
+    #[serde(default)]
+    pub drying_pct: u32,
+
+    /// Percentage of resource regeneration lost at full strength.
+    #[serde(default)]
+    pub regen_loss_pct: u32,
+}
+
+impl ClimateConfig {
+    /// The climate model these settings describe.
+    pub const fn climate(&self) -> Climate {
+        Climate::new(self.drift_ticks)
+            .with_warming_pct(self.warming_pct)
+            .with_drying_pct(self.drying_pct)
+            .with_regen_loss_pct(self.regen_loss_pct)
+    }
+}
+
 /// Discovery and learning parameters.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DiscoveryConfig {
@@ -484,6 +619,37 @@ impl Default for OperatorConfig {
     }
 }
 
+/// WASM plugin configuration.
+///
+/// Each module is loaded by the engine at startup and granted only the
+/// capabilities listed for it (see the `emergence-plugins` crate).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PluginsConfig {
+    /// Plugin modules to load, in call order.
+    #[serde(default)]
+    pub modules: Vec<PluginModuleConfig>,
+}
+
+/// One WASM plugin module.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginModuleConfig {
+    /// Path to the `.wasm` (or `.wat`) file.
+    pub path: String,
+
+    /// Capabilities granted to the plugin, e.g. `read_agents`,
+    /// `resolve_actions`, `inject_events`.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+
+    /// Fuel (roughly, WASM instructions) allowed per plugin call.
+    #[serde(default = "default_plugin_fuel")]
+    pub fuel_per_call: u64,
+
+    /// Upper bound on the plugin's linear memory, in MiB.
+    #[serde(default = "default_plugin_memory_mib")]
+    pub max_memory_mib: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Experiment Framework Configuration (Phase 5.2)
 // ---------------------------------------------------------------------------
@@ -531,6 +697,12 @@ pub struct ExperimentConfig {
     #[serde(default)]
     pub max_ticks: u64,
 
+    /// Climate drift override.
+    ///
+    /// When set, overrides `environment.climate` from the base config.
+    #[serde(default)]
+    pub climate: Option<ClimateConfig>,
+
     /// Arbitrary parameter overrides as key-value pairs.
     ///
     /// Keys follow dot-notation paths into the config tree,
@@ -549,6 +721,7 @@ impl Default for ExperimentConfig {
             personality_distribution: default_personality_distribution(),
             world_seed: None,
             max_ticks: 0,
+            climate: None,
             parameter_overrides: BTreeMap::new(),
         }
     }
@@ -623,6 +796,22 @@ const fn default_knowledge_level() -> u32 {
     1
 }
 
+const fn default_location_count() -> u32 {
+    12
+}
+
+const fn default_region_count() -> u32 {
+    3
+}
+
+const fn default_resource_richness() -> f64 {
+    1.0
+}
+
+const fn default_connectivity() -> f64 {
+    0.5
+}
+
 const fn default_ticks_per_season() -> u64 {
     90
 }
@@ -680,6 +869,10 @@ const fn default_natural_heal_rate() -> u32 {
     2
 }
 
+const fn default_irrigation_yield_pct() -> u32 {
+    emergence_world::DEFAULT_IRRIGATION_YIELD_PCT
+}
+
 const fn default_accidental_discovery_chance() -> f64 {
     0.02
 }
@@ -748,8 +941,18 @@ const fn default_true() -> bool {
     true
 }
 
+const fn default_plugin_fuel() -> u64 {
+    10_000_000
+}
+
+const fn default_plugin_memory_mib() -> u32 {
+    64
+}
+
 #[cfg(test)]
 mod tests {
+    use emergence_types::Resource;
+
     use super::*;
 
     #[test]
@@ -841,6 +1044,47 @@ llm:
         assert_eq!(config.llm.default_backend, "ollama");
     }
 
+    #[test]
+    fn parse_climate_yaml() {
+        let yaml = "environment:\n  climate:\n    drift_ticks: 600\n    drying_pct: 40\n";
+        let config = SimulationConfig::parse(yaml);
+        assert!(config.is_ok());
+        let config = config.ok().unwrap_or_default();
+
+        let climate = config.environment.climate.climate();
+        assert_eq!(climate.drift_ticks, 600);
+        assert_eq!(climate.drying_pct, 40);
+        assert_eq!(climate.warming_pct, 0);
+        assert_eq!(SimulationConfig::default().environment.climate.drift_ticks, 0);
+    }
+
+    #[test]
+    fn parse_irrigation_yaml() {
+        let yaml = "environment:\n  irrigation_yield_pct: 175\n";
+        let config = SimulationConfig::parse(yaml);
+        assert!(config.is_ok());
+        let config = config.ok().unwrap_or_default();
+
+        assert_eq!(config.environment.irrigation_yield_pct, 175);
+        assert_eq!(
+            SimulationConfig::default().environment.irrigation_yield_pct,
+            emergence_world::DEFAULT_IRRIGATION_YIELD_PCT
+        );
+    }
+
+    #[test]
+    fn parse_regeneration_yaml() {
+        let yaml = "environment:\n  regeneration:\n    resources:\n      FoodBerry: { spring: 0, summer: 300, autumn: 0, winter: 0 }\n";
+        let config = SimulationConfig::parse(yaml);
+        assert!(config.is_ok());
+        let config = config.ok().unwrap_or_default();
+
+        let curves = &config.environment.regeneration;
+        assert_eq!(curves.resources.len(), 1);
+        assert_eq!(curves.resources.get(&Resource::FoodBerry).map(|c| c.summer), Some(300));
+        assert_eq!(curves.biomes, RegenCurves::default().biomes);
+    }
+
     #[test]
     fn parse_minimal_yaml() {
         let yaml = "world:\n  seed: 7\n";
@@ -855,6 +1099,27 @@ llm:
         assert_eq!(config.population.initial_agents, 10);
     }
 
+    #[test]
+    fn parse_map_yaml() {
+        let yaml = "world:\n  seed: 9\nmap:\n  procedural: true\n  location_count: 30\n";
+        let config = SimulationConfig::parse(yaml);
+        assert!(config.is_ok());
+        let config = config.ok().unwrap_or_default();
+
+        assert!(config.map.procedural);
+        let params = config.map.gen_params(config.world.seed);
+        assert_eq!(params.seed, 9);
+        assert_eq!(params.location_count, 30);
+        assert_eq!(params.region_count, 3);
+        assert_eq!(params.connectivity, Decimal::new(5, 1));
+        assert!(emergence_world::generate_world(&params).is_ok());
+        assert!(config.map.file.is_none());
+
+        let yaml = "map:\n  file: worlds/archipelago.json\n";
+        let config = SimulationConfig::parse(yaml).ok().unwrap_or_default();
+        assert_eq!(config.map.file.as_deref(), Some("worlds/archipelago.json"));
+    }
+
     #[test]
     fn parse_empty_yaml() {
         let yaml = "";
@@ -6,6 +6,9 @@
 //! decisions are obtained -- it could be an LLM backend, a scripted bot,
 //! a human player, or a test stub.
 //!
+//! A source may also deliver [`ReflectionUpdate`]s -- periodic goal and
+//! memory revisions -- which the engine applies in the Reflection phase.
+//!
 //! For Phase 2, the [`StubDecisionSource`] always returns
 //! [`ActionType::NoAction`], which allows the tick cycle to be exercised
 //! end-to-end before the LLM agent runner is implemented.
@@ -13,7 +16,9 @@
 use std::collections::BTreeMap;
 
 use chrono::Utc;
-use emergence_types::{ActionParameters, ActionRequest, ActionType, AgentId, Perception};
+use emergence_types::{
+    ActionParameters, ActionRequest, ActionType, AgentId, Perception, ReflectionUpdate,
+};
 
 /// Errors that can occur during the decision phase.
 #[derive(Debug, thiserror::Error)]
@@ -59,6 +64,15 @@ pub trait DecisionSource {
         tick: u64,
         perceptions: &BTreeMap<AgentId, Perception>,
     ) -> Result<BTreeMap<AgentId, ActionRequest>, DecisionError>;
+
+    /// Collect reflection updates that have arrived for the given tick.
+    ///
+    /// Called once per tick during the Reflection phase. Reflections are
+    /// optional and may lag their perception by a few ticks; sources
+    /// without a reflection pathway keep the default, which returns none.
+    fn collect_reflections(&mut self, _tick: u64) -> Vec<ReflectionUpdate> {
+        Vec::new()
+    }
 }
 
 /// A stub decision source that always returns [`ActionType::NoAction`].
@@ -137,12 +151,15 @@ mod tests {
                 structures_here: Vec::new(),
                 agents_here: Vec::new(),
                 messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
             },
             known_routes: Vec::new(),
             recent_memory: Vec::new(),
             available_actions: Vec::new(),
             notifications: Vec::new(),
             personality: None,
+            forecast: Vec::new(),
         }
     }
 
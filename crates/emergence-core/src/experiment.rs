@@ -77,7 +77,7 @@ pub fn capture_snapshot(state: &SimulationState) -> Result<SimulationSnapshot, E
     }
 
     let mut agent_states = BTreeMap::new();
-    for (id, agent_state) in &state.agent_states {
+    for (id, agent_state) in state.agent_states.iter() {
         let key = id.to_string();
         let val = serde_json::to_value(agent_state)
             .map_err(|e| ExperimentError::Serialization(format!("agent_state {key}: {e}")))?;
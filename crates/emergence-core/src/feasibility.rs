@@ -99,6 +99,8 @@ const KNOWN_CATEGORIES: &[(&str, ActionType)] = &[
     ("combat", ActionType::Attack),
     ("intimidate", ActionType::Intimidate),
     ("threaten", ActionType::Intimidate),
+    ("breach", ActionType::Breach),
+    ("siege", ActionType::Breach),
     ("propose", ActionType::Propose),
     ("vote", ActionType::Vote),
     ("marry", ActionType::Marry),
@@ -122,25 +124,43 @@ const KNOWN_CATEGORIES: &[(&str, ActionType)] = &[
     ("construct", ActionType::Build),
     ("repair", ActionType::Repair),
     ("fix", ActionType::Repair),
+    ("extinguish", ActionType::Extinguish),
+    ("douse", ActionType::Extinguish),
     ("demolish", ActionType::Demolish),
     ("destroy", ActionType::Demolish),
+    ("upgrade", ActionType::UpgradeStructure),
     ("teach", ActionType::Teach),
     ("trade", ActionType::TradeOffer),
+    ("market", ActionType::PostOrder),
     ("communicate", ActionType::Communicate),
     ("talk", ActionType::Communicate),
     ("broadcast", ActionType::Broadcast),
     ("shout", ActionType::Broadcast),
+    ("deepen", ActionType::DeepenMine),
+    ("shaft", ActionType::DeepenMine),
     ("mine", ActionType::Mine),
+    ("slaughter", ActionType::Slaughter),
+    ("butcher", ActionType::Slaughter),
+    ("hunt", ActionType::Hunt),
+    ("prospect", ActionType::Prospect),
+    ("fish", ActionType::Fish),
     ("craft", ActionType::Craft),
     ("smelt", ActionType::Smelt),
     ("write", ActionType::Write),
     ("read", ActionType::Read),
+    ("chart", ActionType::Chart),
+    ("deposit", ActionType::Deposit),
+    ("withdraw", ActionType::Withdraw),
     ("claim", ActionType::Claim),
+    ("transfer", ActionType::TransferOwnership),
+    ("settle", ActionType::FoundSettlement),
     ("legislate", ActionType::Legislate),
     ("enforce", ActionType::Enforce),
     ("reproduce", ActionType::Reproduce),
     ("farm", ActionType::FarmPlant),
     ("harvest", ActionType::FarmHarvest),
+    ("fertilize", ActionType::Fertilize),
+    ("compost", ActionType::Fertilize),
     ("plant", ActionType::FarmPlant),
 ];
 
@@ -352,7 +372,14 @@ fn check_target_exists(
             )),
         },
         // Structure-targeting actions
-        ActionType::Repair | ActionType::Demolish | ActionType::Claim => match target {
+        ActionType::Repair
+        | ActionType::Demolish
+        | ActionType::UpgradeStructure
+        | ActionType::Claim
+        | ActionType::TransferOwnership
+        | ActionType::Deposit
+        | ActionType::Withdraw
+        | ActionType::PostOrder => match target {
             Some(ActionTarget::Structure(structure_id)) => {
                 if !ctx.structures_at_location.contains(structure_id) {
                     return Some(format!(
@@ -401,6 +428,12 @@ fn resolve_parameters(
             let target_agent = extract_agent_target(action.target.as_ref())?;
             Ok(ActionParameters::Intimidate { target_agent })
         }
+        ActionType::Breach => match action.target {
+            Some(ActionTarget::Location(destination)) => {
+                Ok(ActionParameters::Breach { destination })
+            }
+            _ => Err(String::from("Breach requires the location to breach as its target.")),
+        },
         ActionType::Pray => Ok(ActionParameters::Pray {
             intent: if action.intent.is_empty() {
                 None
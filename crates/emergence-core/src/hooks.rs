@@ -0,0 +1,38 @@
+//! Extension points for custom mechanics.
+//!
+//! A [`MechanicsHooks`] implementation attached to
+//! [`SimulationState::hooks`] is consulted by the tick cycle at two points:
+//!
+//! - **Resolution** -- every freeform action is offered to
+//!   [`MechanicsHooks::resolve_freeform`] before the built-in feasibility
+//!   evaluator. Returning `None` falls through to the evaluator.
+//! - **End of tick** -- [`MechanicsHooks::after_tick`] sees the finished
+//!   tick; any events it returns are queued exactly like operator-injected
+//!   events and applied during the next World Wake.
+//!
+//! Hooks receive the state read-only. The WASM plugin host in
+//! `emergence-plugins` is the production implementation.
+
+use emergence_types::{AgentId, FreeformAction};
+
+use crate::feasibility::FeasibilityResult;
+use crate::operator::InjectedEvent;
+use crate::tick::{SimulationState, TickSummary};
+
+/// Custom mechanics invoked from inside the tick cycle.
+///
+/// Methods take `&self` because they run while the state is borrowed;
+/// implementations that need mutation use interior mutability.
+pub trait MechanicsHooks: Send + Sync + std::fmt::Debug {
+    /// Resolve a freeform action, or return `None` to leave it to the
+    /// built-in feasibility evaluator.
+    fn resolve_freeform(
+        &self,
+        state: &SimulationState,
+        agent_id: AgentId,
+        action: &FreeformAction,
+    ) -> Option<FeasibilityResult>;
+
+    /// Observe a completed tick and return events to inject next tick.
+    fn after_tick(&self, state: &SimulationState, summary: &TickSummary) -> Vec<InjectedEvent>;
+}
@@ -5,6 +5,10 @@
 //!
 //! # Modules
 //!
+//! - [`agent_store`] -- Slab storage for agent state with stable indices,
+//!   mirroring vitals and travel into dense columns for per-tick scans.
+//! - [`archive`] -- Single-file `.emrun` run archives (config, seed, final
+//!   snapshot, events, ledger, decisions) with create/extract.
 //! - [`clock`] -- World clock with tick counter, era tracking, season
 //!   derivation, and time-of-day computation.
 //! - [`config`] -- Configuration loading from `emergence-config.yaml` into
@@ -13,23 +17,38 @@
 //! - [`experiment`] -- Experiment framework for A/B testing, snapshot
 //!   capture, and reproducible simulations.
 //! - [`fuzzy`] -- Fuzzy resource quantity representation for perception.
+//! - [`hooks`] -- [`MechanicsHooks`] extension points for custom freeform
+//!   action handling and end-of-tick event generation.
 //! - [`operator`] -- Shared operator control state for pause, resume,
 //!   speed adjustment, event injection, and clean shutdown.
 //! - [`perception`] -- Per-agent perception assembly from world state.
 //! - [`runner`] -- Top-level simulation loop with operator controls,
 //!   boundary enforcement, and clean shutdown sequencing.
+//! - [`snapshot_diff`] -- Structured differences between two simulation
+//!   snapshots (births, deaths, resources, structures).
+//! - [`scratch`] -- Buffers the tick cycle reuses between ticks instead of
+//!   reallocating its temporaries.
+//! - [`subscribers`] -- The event bus the tick cycle publishes to, and the
+//!   reputation, belief, and economy trackers subscribed to it.
 //! - [`tick`] -- The 6-phase tick cycle engine loop.
 //!
 //! [`DecisionSource`]: decision::DecisionSource
 //! [`StubDecisionSource`]: decision::StubDecisionSource
+//! [`MechanicsHooks`]: hooks::MechanicsHooks
 
+pub mod agent_store;
+pub mod archive;
 pub mod clock;
 pub mod config;
 pub mod decision;
 pub mod experiment;
 pub mod feasibility;
 pub mod fuzzy;
+pub mod hooks;
 pub mod operator;
 pub mod perception;
 pub mod runner;
+pub mod scratch;
+pub mod snapshot_diff;
+pub mod subscribers;
 pub mod tick;
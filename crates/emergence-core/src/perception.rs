@@ -10,10 +10,17 @@
 //! quantities are fuzzified so agents cannot make perfectly optimal
 //! decisions.
 //!
+//! Perceptions depend only on the agent's own state and its location's
+//! shared [`PerceptionContext`], so [`assemble_perceptions`] builds a whole
+//! tick's worth in parallel on the rayon thread pool. The result is keyed
+//! by agent ID, so it is the same whatever order the threads finish in.
+//!
 //! Per `world-engine.md` section 2.2 and `agent-system.md` section 5.
 
 use std::collections::BTreeMap;
 
+use rayon::prelude::*;
+
 use emergence_types::{
     AgentId, AgentState, Message, Perception, Personality, Resource, Season, SelfState, Sex,
     Surroundings, TimeOfDay, VisibleAgent, VisibleMessage, Weather,
@@ -59,6 +66,41 @@ pub struct PerceptionContext {
     pub ticks_until_season_change: u64,
     /// Number of ticks after which messages expire (default 10).
     pub message_expiry_ticks: u64,
+    /// Whether the location is overcrowded.
+    pub overcrowded: bool,
+    /// Pollution at the location, from 0 to 100.
+    pub pollution: u32,
+}
+
+/// One agent's inputs to [`assemble_perception`], for batch assembly with
+/// [`assemble_perceptions`].
+#[derive(Debug, Clone, Copy)]
+pub struct PerceptionRequest<'a> {
+    /// The agent's current state.
+    pub agent_state: &'a AgentState,
+    /// The agent's name.
+    pub agent_name: &'a str,
+    /// The agent's sex.
+    pub agent_sex: Sex,
+    /// The agent's personality, if it has one.
+    pub personality: Option<&'a Personality>,
+    /// The shared context for the agent's location.
+    pub ctx: &'a PerceptionContext,
+}
+
+/// Assemble perceptions for a batch of agents in parallel.
+///
+/// Equivalent to calling [`assemble_perception`] for each request and
+/// collecting the results by agent ID.
+pub fn assemble_perceptions(requests: &[PerceptionRequest<'_>]) -> BTreeMap<AgentId, Perception> {
+    requests
+        .par_iter()
+        .map(|r| {
+            let perception =
+                assemble_perception(r.agent_state, r.agent_name, r.agent_sex, r.personality, r.ctx);
+            (r.agent_state.agent_id, perception)
+        })
+        .collect()
 }
 
 /// Assemble a complete [`Perception`] payload for a single agent.
@@ -105,6 +147,7 @@ pub fn assemble_perception(
         available_actions,
         notifications,
         personality: personality.cloned(),
+        forecast: Vec::new(),
     }
 }
 
@@ -175,6 +218,8 @@ fn build_surroundings(agent_id: AgentId, ctx: &PerceptionContext) -> Surrounding
         structures_here: ctx.structures_here.clone(),
         agents_here,
         messages_here,
+        overcrowded: ctx.overcrowded,
+        pollution: ctx.pollution,
     }
 }
 
@@ -379,6 +424,8 @@ mod tests {
             agent_sexes: BTreeMap::new(),
             ticks_until_season_change: 45,
             message_expiry_ticks: DEFAULT_MESSAGE_EXPIRY_TICKS,
+            overcrowded: false,
+            pollution: 0,
         }
     }
 
@@ -394,6 +441,29 @@ mod tests {
         assert_eq!(p.weather, Weather::Clear);
     }
 
+    #[test]
+    fn batch_matches_one_at_a_time() {
+        let ctx = make_context(3);
+        let states: Vec<AgentState> = (0..64).map(|_| make_agent_state(AgentId::new())).collect();
+        let requests: Vec<PerceptionRequest<'_>> = states
+            .iter()
+            .map(|agent_state| PerceptionRequest {
+                agent_state,
+                agent_name: "Alpha",
+                agent_sex: Sex::Female,
+                personality: None,
+                ctx: &ctx,
+            })
+            .collect();
+
+        let batch = assemble_perceptions(&requests);
+        assert_eq!(batch.len(), states.len());
+        for state in &states {
+            let single = assemble_perception(state, "Alpha", Sex::Female, None, &ctx);
+            assert_eq!(batch.get(&state.agent_id), Some(&single));
+        }
+    }
+
     #[test]
     fn self_state_populated() {
         let agent_id = AgentId::new();
@@ -447,6 +517,27 @@ mod tests {
         assert_eq!(p.surroundings.agents_here.first().map(|a| a.name.as_str()), Some("Beta"));
     }
 
+    #[test]
+    fn overcrowding_is_flagged() {
+        let state = make_agent_state(AgentId::new());
+        let mut ctx = make_context(1);
+        let p = assemble_perception(&state, "Alpha", Sex::Male, None, &ctx);
+        assert!(!p.surroundings.overcrowded);
+
+        ctx.overcrowded = true;
+        let p = assemble_perception(&state, "Alpha", Sex::Male, None, &ctx);
+        assert!(p.surroundings.overcrowded);
+    }
+
+    #[test]
+    fn pollution_is_reported() {
+        let state = make_agent_state(AgentId::new());
+        let mut ctx = make_context(1);
+        ctx.pollution = 35;
+        let p = assemble_perception(&state, "Alpha", Sex::Male, None, &ctx);
+        assert_eq!(p.surroundings.pollution, 35);
+    }
+
     #[test]
     fn traveling_agent_only_wait() {
         let agent_id = AgentId::new();
@@ -391,9 +391,12 @@ mod tests {
     use rust_decimal::Decimal;
 
     use super::*;
+    use crate::agent_store::AgentStore;
     use crate::clock::WorldClock;
     use crate::config::{SimulationBoundsConfig, TimeConfig};
     use crate::decision::StubDecisionSource;
+    use crate::scratch::TickScratch;
+    use crate::subscribers::EventSubscribers;
 
     fn default_time_config() -> TimeConfig {
         TimeConfig {
@@ -449,6 +452,7 @@ mod tests {
             location_type: String::from("natural"),
             description: format!("Test location: {name}"),
             capacity: 20,
+            elevation: 0,
             base_resources: resources,
             discovered_by: BTreeSet::new(),
             created_at: Utc::now(),
@@ -494,8 +498,8 @@ mod tests {
         let mut agent_names = BTreeMap::new();
         agent_names.insert(agent_id, String::from("Alpha"));
 
-        let mut agent_states = BTreeMap::new();
-        agent_states.insert(agent_id, agent_state);
+        let mut agent_states = AgentStore::new();
+        agent_states.insert(agent_state);
 
         SimulationState {
             clock,
@@ -510,6 +514,22 @@ mod tests {
             injected_events: Vec::new(),
             active_plagues: Vec::new(),
             active_resource_booms: Vec::new(),
+            disasters: emergence_world::DisasterSystem::default(),
+            fires: emergence_world::FireSystem::default(),
+            fauna: emergence_world::FaunaRegistry::default(),
+            waters: emergence_world::WaterRegistry::default(),
+            mines: emergence_world::MineRegistry::default(),
+            known_maps: emergence_world::KnownMapRegistry::default(),
+            trade_network: emergence_world::TradeNetwork::default(),
+            structures: BTreeMap::new(),
+            family: emergence_agents::FamilyTracker::new(),
+            ledger: emergence_ledger::Ledger::new(),
+            orders: emergence_agents::OrderBook::new(),
+            groups: BTreeMap::new(),
+            diplomacy: emergence_agents::DiplomacyState::new(),
+            hooks: None,
+            scratch: TickScratch::new(),
+            subscribers: EventSubscribers::new(),
         }
     }
 
@@ -571,7 +591,7 @@ mod tests {
 
         // Kill the agent by setting extreme hunger
         let agent_id = *state.alive_agents.first().unwrap();
-        if let Some(agent_state) = state.agent_states.get_mut(&agent_id) {
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
             agent_state.hunger = 96;
             agent_state.health = 5;
         }
@@ -0,0 +1,129 @@
+//! Reusable buffers for tick-cycle temporaries.
+//!
+//! Every tick builds the same throwaway collections: a copy of the alive
+//! list for the world wake to walk while deaths shrink the original, a
+//! membership set for validation, a list of agents per occupied location
+//! for perception, and a claim list per contested resource for gather
+//! resolution. At high agent counts, allocating and freeing those every
+//! tick dominates the allocator profile.
+//!
+//! [`TickScratch`] keeps them between ticks instead. Each buffer is
+//! cleared before use but keeps its capacity, so after the first few ticks
+//! the cycle stops allocating for them. The scratch lives on
+//! [`SimulationState`](crate::tick::SimulationState) and carries no state
+//! from one tick to the next -- only capacity -- with one exception: the
+//! [`RouteCache`] keeps the paths perception found until the routes or the
+//! weather change.
+
+use emergence_agents::actions::conflict::GatherClaim;
+use emergence_types::AgentId;
+use emergence_world::RouteCache;
+
+/// A free list of cleared buffers.
+///
+/// [`take`](Self::take) hands out an empty buffer, reusing a returned one
+/// when available; [`give`](Self::give) clears a buffer and keeps it for
+/// the next `take`.
+#[derive(Debug, Clone)]
+pub struct Pool<T> {
+    free: Vec<Vec<T>>,
+}
+
+impl<T> Pool<T> {
+    /// Create an empty pool.
+    pub const fn new() -> Self {
+        Self { free: Vec::new() }
+    }
+
+    /// An empty buffer, with whatever capacity it had when returned.
+    pub fn take(&mut self) -> Vec<T> {
+        self.free.pop().unwrap_or_default()
+    }
+
+    /// Return a buffer to the pool.
+    pub fn give(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.free.push(buffer);
+    }
+
+    /// Number of buffers waiting to be reused.
+    pub const fn idle(&self) -> usize {
+        self.free.len()
+    }
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buffers reused across ticks by the tick cycle.
+#[derive(Debug, Clone, Default)]
+pub struct TickScratch {
+    /// The alive list as it stood when the world wake began.
+    pub(crate) wake_order: Vec<AgentId>,
+    /// The alive list, sorted for binary-search membership checks.
+    pub(crate) alive_sorted: Vec<AgentId>,
+    /// Agent lists, one per occupied location during perception.
+    pub(crate) agent_lists: Pool<AgentId>,
+    /// Gather claim lists, one per contested (location, resource) pair.
+    pub(crate) claim_lists: Pool<GatherClaim>,
+    /// Paths to each location's neighbors, for perception.
+    pub(crate) route_cache: RouteCache,
+}
+
+impl TickScratch {
+    /// Create empty scratch buffers.
+    pub const fn new() -> Self {
+        Self {
+            wake_order: Vec::new(),
+            alive_sorted: Vec::new(),
+            agent_lists: Pool::new(),
+            claim_lists: Pool::new(),
+            route_cache: RouteCache::new(),
+        }
+    }
+
+    /// Refill `alive_sorted` from `alive`.
+    pub(crate) fn sort_alive(&mut self, alive: &[AgentId]) {
+        self.alive_sorted.clear();
+        self.alive_sorted.extend_from_slice(alive);
+        self.alive_sorted.sort_unstable();
+    }
+
+    /// Whether `agent_id` was in the list last passed to
+    /// [`sort_alive`](Self::sort_alive).
+    pub(crate) fn is_alive(&self, agent_id: &AgentId) -> bool {
+        self.alive_sorted.binary_search(agent_id).is_ok()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_reuses_capacity() {
+        let mut pool: Pool<u32> = Pool::new();
+        let mut buffer = pool.take();
+        buffer.extend(0..64);
+        pool.give(buffer);
+        assert_eq!(pool.idle(), 1);
+
+        let reused = pool.take();
+        assert!(reused.is_empty());
+        assert!(reused.capacity() >= 64);
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn alive_membership() {
+        let ids: Vec<AgentId> = (0..5).map(|_| AgentId::new()).collect();
+        let mut scratch = TickScratch::new();
+        scratch.sort_alive(ids.get(..3).unwrap());
+        assert!(ids.iter().take(3).all(|id| scratch.is_alive(id)));
+        assert!(ids.iter().skip(3).all(|id| !scratch.is_alive(id)));
+    }
+}
@@ -0,0 +1,287 @@
+//! Structured differences between two simulation snapshots.
+//!
+//! The observer dashboard shows what changed over a span of ticks -- who
+//! was born and who died, how each location's resources moved, which
+//! structures went up or came down -- by diffing the [`SimulationSnapshot`]s
+//! at either end instead of replaying the events in between.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use emergence_types::{AgentId, LocationId, Resource, StructureId};
+use emergence_world::WorldMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::experiment::{ExperimentError, SimulationSnapshot};
+
+/// How one location's resources changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceChange {
+    /// Resources whose available quantity rose, by how much.
+    pub gained: BTreeMap<Resource, u32>,
+    /// Resources whose available quantity fell, by how much.
+    pub lost: BTreeMap<Resource, u32>,
+}
+
+impl ResourceChange {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.gained.is_empty() && self.lost.is_empty()
+    }
+}
+
+/// A structure that appeared or disappeared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StructureChange {
+    /// The structure.
+    pub structure_id: StructureId,
+    /// Where it stands, or stood.
+    pub location_id: LocationId,
+}
+
+/// What changed between two snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Tick of the earlier snapshot.
+    pub from_tick: u64,
+    /// Tick of the later snapshot.
+    pub to_tick: u64,
+    /// Agents present in the later snapshot only.
+    pub agents_born: Vec<AgentId>,
+    /// Agents alive in the earlier snapshot and not in the later one.
+    pub agents_died: Vec<AgentId>,
+    /// Resource changes at each location where any occurred.
+    pub resources: BTreeMap<LocationId, ResourceChange>,
+    /// Structures standing in the later snapshot only.
+    pub structures_built: Vec<StructureChange>,
+    /// Structures standing in the earlier snapshot only.
+    pub structures_destroyed: Vec<StructureChange>,
+}
+
+impl SnapshotDiff {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.agents_born.is_empty()
+            && self.agents_died.is_empty()
+            && self.resources.is_empty()
+            && self.structures_built.is_empty()
+            && self.structures_destroyed.is_empty()
+    }
+}
+
+/// The changes from snapshot `a` to the later snapshot `b`.
+///
+/// # Errors
+///
+/// Returns [`ExperimentError::Serialization`] if either snapshot's world
+/// map or agent IDs do not decode.
+pub fn diff_snapshots(
+    a: &SimulationSnapshot,
+    b: &SimulationSnapshot,
+) -> Result<SnapshotDiff, ExperimentError> {
+    let known_before = agent_ids(a.agents.keys())?;
+    let known_after = agent_ids(b.agents.keys())?;
+    let alive_before = agent_ids(&a.alive_agents)?;
+    let alive_after = agent_ids(&b.alive_agents)?;
+
+    let map_before = world_map(a)?;
+    let map_after = world_map(b)?;
+    let resources_before = location_resources(&map_before);
+    let resources_after = location_resources(&map_after);
+    let structures_before = structures(&map_before);
+    let structures_after = structures(&map_after);
+
+    let locations: BTreeSet<LocationId> =
+        resources_before.keys().chain(resources_after.keys()).copied().collect();
+    let resources = locations
+        .into_iter()
+        .filter_map(|location_id| {
+            let change = resource_change(
+                resources_before.get(&location_id),
+                resources_after.get(&location_id),
+            );
+            (!change.is_empty()).then_some((location_id, change))
+        })
+        .collect();
+
+    Ok(SnapshotDiff {
+        from_tick: a.tick,
+        to_tick: b.tick,
+        agents_born: known_after.difference(&known_before).copied().collect(),
+        agents_died: alive_before.difference(&alive_after).copied().collect(),
+        resources,
+        structures_built: structures_after.difference(&structures_before).copied().collect(),
+        structures_destroyed: structures_before.difference(&structures_after).copied().collect(),
+    })
+}
+
+/// Parse the string agent IDs a snapshot is keyed by.
+fn agent_ids<'a>(
+    ids: impl IntoIterator<Item = &'a String>,
+) -> Result<BTreeSet<AgentId>, ExperimentError> {
+    ids.into_iter()
+        .map(|id| {
+            Uuid::parse_str(id)
+                .map(AgentId::from)
+                .map_err(|e| ExperimentError::Serialization(format!("agent id {id}: {e}")))
+        })
+        .collect()
+}
+
+/// Decode a snapshot's world map.
+fn world_map(snapshot: &SimulationSnapshot) -> Result<WorldMap, ExperimentError> {
+    WorldMap::deserialize(&snapshot.world_map)
+        .map_err(|e| ExperimentError::Serialization(format!("world_map: {e}")))
+}
+
+/// Available quantity of each resource at each location.
+fn location_resources(map: &WorldMap) -> BTreeMap<LocationId, BTreeMap<Resource, u32>> {
+    map.locations()
+        .map(|(id, state)| {
+            let available = state
+                .location
+                .base_resources
+                .iter()
+                .map(|(resource, node)| (*resource, node.available))
+                .collect();
+            (*id, available)
+        })
+        .collect()
+}
+
+/// Every standing structure with its location.
+fn structures(map: &WorldMap) -> BTreeSet<StructureChange> {
+    map.locations()
+        .flat_map(|(location_id, state)| {
+            state.structures.iter().map(|structure_id| StructureChange {
+                structure_id: *structure_id,
+                location_id: *location_id,
+            })
+        })
+        .collect()
+}
+
+/// The change from `before` to `after`; a missing location holds nothing.
+fn resource_change(
+    before: Option<&BTreeMap<Resource, u32>>,
+    after: Option<&BTreeMap<Resource, u32>>,
+) -> ResourceChange {
+    let quantity = |side: Option<&BTreeMap<Resource, u32>>, resource: &Resource| {
+        side.and_then(|side| side.get(resource)).copied().unwrap_or(0)
+    };
+    let resources: BTreeSet<&Resource> =
+        before.into_iter().chain(after).flat_map(BTreeMap::keys).collect();
+
+    let mut change = ResourceChange::default();
+    for resource in resources {
+        let (was, now) = (quantity(before, resource), quantity(after, resource));
+        if now > was {
+            change.gained.insert(*resource, now.saturating_sub(was));
+        } else if was > now {
+            change.lost.insert(*resource, was.saturating_sub(now));
+        }
+    }
+    change
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{Location, ResourceNode};
+
+    use super::*;
+
+    fn location(id: LocationId, wood: u32) -> Location {
+        Location {
+            id,
+            name: String::from("Clearing"),
+            region: String::from("Test"),
+            location_type: String::from("natural"),
+            description: String::from("Test location"),
+            capacity: 20,
+            elevation: 0,
+            base_resources: BTreeMap::from([(
+                Resource::Wood,
+                ResourceNode {
+                    resource: Resource::Wood,
+                    available: wood,
+                    regen_per_tick: 5,
+                    max_capacity: 100,
+                },
+            )]),
+            discovered_by: BTreeSet::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn snapshot(
+        tick: u64,
+        map: &WorldMap,
+        agents: &[AgentId],
+        alive: &[AgentId],
+    ) -> SimulationSnapshot {
+        SimulationSnapshot {
+            tick,
+            clock: serde_json::Value::Null,
+            world_map: serde_json::to_value(map).unwrap(),
+            agents: agents.iter().map(|id| (id.to_string(), serde_json::json!({}))).collect(),
+            agent_states: BTreeMap::new(),
+            agent_names: BTreeMap::new(),
+            alive_agents: alive.iter().map(ToString::to_string).collect(),
+            vitals_config: serde_json::Value::Null,
+            weather_seed: 0,
+        }
+    }
+
+    #[test]
+    fn reports_births_deaths_resources_and_structures() {
+        let clearing = LocationId::new();
+        let (elder, child) = (AgentId::new(), AgentId::new());
+        let (hut, well) = (StructureId::new(), StructureId::new());
+
+        let mut before = WorldMap::new();
+        before.add_location(location(clearing, 50)).unwrap();
+        before.get_location_mut(clearing).unwrap().add_structure(hut);
+        let mut after = WorldMap::new();
+        after.add_location(location(clearing, 35)).unwrap();
+        after.get_location_mut(clearing).unwrap().add_structure(well);
+
+        let a = snapshot(100, &before, &[elder], &[elder]);
+        let b = snapshot(200, &after, &[elder, child], &[child]);
+        let diff = diff_snapshots(&a, &b).unwrap();
+
+        assert_eq!((diff.from_tick, diff.to_tick), (100, 200));
+        assert_eq!(diff.agents_born, [child]);
+        assert_eq!(diff.agents_died, [elder]);
+        let change = diff.resources.get(&clearing).unwrap();
+        assert_eq!(change.lost, BTreeMap::from([(Resource::Wood, 15)]));
+        assert!(change.gained.is_empty());
+        let at = |structure_id| StructureChange {
+            structure_id,
+            location_id: clearing,
+        };
+        assert_eq!(diff.structures_built, [at(well)]);
+        assert_eq!(diff.structures_destroyed, [at(hut)]);
+    }
+
+    #[test]
+    fn identical_snapshots_have_an_empty_diff() {
+        let mut map = WorldMap::new();
+        map.add_location(location(LocationId::new(), 10)).unwrap();
+        let agent = AgentId::new();
+        let a = snapshot(5, &map, &[agent], &[agent]);
+        assert!(diff_snapshots(&a, &a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_ids_are_rejected() {
+        let map = WorldMap::new();
+        let mut a = snapshot(5, &map, &[], &[]);
+        a.alive_agents.push(String::from("not-a-uuid"));
+        assert!(matches!(
+            diff_snapshots(&a, &a),
+            Err(ExperimentError::Serialization(message)) if message.contains("not-a-uuid")
+        ));
+    }
+}
@@ -0,0 +1,227 @@
+//! Event-bus subscribers fed by the tick cycle.
+//!
+//! The tick cycle does not call the reputation tracker or the belief and
+//! economy detectors itself. It publishes each tick's events on the
+//! [`EventBus`] held here during the Persist phase, and every tracker
+//! subscribes to the [`EventType`]s it reads:
+//!
+//! - [`ReputationTracker`] -- `TheftOccurred` (a victim who noticed the
+//!   thief marks them a thief) and `CombatInitiated` (the defender marks
+//!   the attacker a warrior).
+//! - [`BeliefDetector`] -- `MessageSent`, scanning each message for belief
+//!   keywords.
+//! - [`EconomicDetector`] -- `TradeCompleted`, recording the exchange.
+//!
+//! Each tracker sits behind an `Arc<Mutex<_>>` shared with its subscriber
+//! closure, so the engine and observers can read it between ticks.
+
+use std::sync::{Arc, Mutex};
+
+use emergence_agents::reputation::{ActionReputationEvent, ReputationAction, ReputationTracker};
+use emergence_agents::{BeliefDetector, EconomicDetector};
+use emergence_events::EventBus;
+use emergence_types::{
+    CombatInitiatedDetails, Event, EventType, Message, TheftOccurredDetails, TradeCompletedDetails,
+};
+use tracing::warn;
+
+/// The bus the tick cycle publishes to, and the trackers subscribed to it.
+#[derive(Debug)]
+pub struct EventSubscribers {
+    /// Routes each published event to the trackers below.
+    pub bus: EventBus,
+    /// Reputation observations from thefts and fights.
+    pub reputation: Arc<Mutex<ReputationTracker>>,
+    /// Belief keywords from agent messages.
+    pub beliefs: Arc<Mutex<BeliefDetector>>,
+    /// Completed trades, for currency and market detection.
+    pub economy: Arc<Mutex<EconomicDetector>>,
+}
+
+impl EventSubscribers {
+    /// Create fresh trackers and subscribe them to a new bus.
+    pub fn new() -> Self {
+        let mut subscribers = Self {
+            bus: EventBus::new(),
+            reputation: Arc::new(Mutex::new(ReputationTracker::new())),
+            beliefs: Arc::new(Mutex::new(BeliefDetector::new())),
+            economy: Arc::new(Mutex::new(EconomicDetector::default())),
+        };
+        subscribers.subscribe_reputation();
+        subscribers.subscribe_beliefs();
+        subscribers.subscribe_economy();
+        subscribers
+    }
+
+    /// Publish `events` to every interested tracker, in order.
+    pub fn publish(&mut self, events: &[Event]) -> usize {
+        self.bus.publish_all(events)
+    }
+
+    /// Feed thefts and fights to the reputation tracker.
+    fn subscribe_reputation(&mut self) {
+        let reputation = Arc::clone(&self.reputation);
+        self.bus.subscribe_details(
+            EventType::TheftOccurred,
+            move |event: &Event, theft: TheftOccurredDetails| {
+                if theft.detected {
+                    let action = ActionReputationEvent {
+                        observer: theft.victim_id,
+                        subject: theft.thief_id,
+                        tick: event.tick,
+                        action: ReputationAction::TheftDetected,
+                    };
+                    record_reputation(&reputation, &action);
+                }
+            },
+        );
+
+        let reputation = Arc::clone(&self.reputation);
+        self.bus.subscribe_details(
+            EventType::CombatInitiated,
+            move |event: &Event, combat: CombatInitiatedDetails| {
+                let action = ActionReputationEvent {
+                    observer: combat.defender_id,
+                    subject: combat.attacker_id,
+                    tick: event.tick,
+                    action: ReputationAction::CombatInitiated,
+                };
+                record_reputation(&reputation, &action);
+            },
+        );
+    }
+
+    /// Feed agent messages to the belief detector.
+    fn subscribe_beliefs(&mut self) {
+        let beliefs = Arc::clone(&self.beliefs);
+        self.bus.subscribe_details(
+            EventType::MessageSent,
+            move |event: &Event, message: Message| {
+                if let Ok(mut beliefs) = beliefs.lock() {
+                    beliefs.record_communication(message.sender_id, event.tick, &message.content);
+                }
+            },
+        );
+    }
+
+    /// Feed completed trades to the economic detector.
+    fn subscribe_economy(&mut self) {
+        let economy = Arc::clone(&self.economy);
+        self.bus.subscribe_details(
+            EventType::TradeCompleted,
+            move |event: &Event, trade: TradeCompletedDetails| {
+                let Some(location) = event.location_id else {
+                    return;
+                };
+                if let Ok(mut economy) = economy.lock() {
+                    economy.record_trade(
+                        event.tick,
+                        trade.agent_a,
+                        trade.agent_b,
+                        trade.gave,
+                        trade.received,
+                        location,
+                    );
+                }
+            },
+        );
+    }
+}
+
+impl Default for EventSubscribers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Record `action` on the shared reputation tracker.
+fn record_reputation(reputation: &Mutex<ReputationTracker>, action: &ActionReputationEvent) {
+    let Ok(mut reputation) = reputation.lock() else {
+        return;
+    };
+    if let Err(err) = reputation.record_action_reputation(action) {
+        warn!(tick = action.tick, %err, "Reputation observation not recorded");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_agents::reputation::ReputationTag;
+    use emergence_types::{
+        AgentId, CombatIntent, Era, EventId, LocationId, Resource, Season, TradeId, Weather,
+        WorldContext,
+    };
+
+    use super::*;
+
+    fn event<T: serde::Serialize>(
+        event_type: EventType,
+        location_id: LocationId,
+        details: &T,
+    ) -> Event {
+        Event {
+            id: EventId::new(),
+            tick: 4,
+            event_type,
+            agent_id: None,
+            location_id: Some(location_id),
+            details: serde_json::to_value(details).unwrap(),
+            agent_state_snapshot: None,
+            world_context: WorldContext {
+                tick: 4,
+                era: Era::Primitive,
+                season: Season::Spring,
+                weather: Weather::Clear,
+                population: 2,
+            },
+            created_at: chrono::Utc::now(),
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn trackers_receive_the_events_they_subscribe_to() {
+        let mut subscribers = EventSubscribers::new();
+        let (a, b) = (AgentId::new(), AgentId::new());
+        let here = LocationId::new();
+        let theft = TheftOccurredDetails {
+            thief_id: a,
+            victim_id: b,
+            resource: Resource::Wood,
+            quantity_stolen: 2,
+            detected: true,
+            location_id: here,
+        };
+        let fight = CombatInitiatedDetails {
+            attacker_id: b,
+            defender_id: a,
+            intent: CombatIntent::Attack,
+            location_id: here,
+        };
+        let trade = TradeCompletedDetails {
+            trade_id: TradeId::new(),
+            agent_a: a,
+            agent_b: b,
+            gave: BTreeMap::from([(Resource::Wood, 3)]),
+            received: BTreeMap::from([(Resource::Stone, 1)]),
+        };
+        let events = [
+            event(EventType::TheftOccurred, here, &theft),
+            event(EventType::CombatInitiated, here, &fight),
+            event(EventType::TradeCompleted, here, &trade),
+        ];
+        assert_eq!(subscribers.publish(&events), 3);
+
+        let reputation = subscribers.reputation.lock().unwrap();
+        let thief = reputation.get_reputation(b, a);
+        assert_eq!(thief.first().map(|entry| entry.tag), Some(ReputationTag::Thief));
+        let attacker = reputation.get_reputation(a, b);
+        assert_eq!(attacker.first().map(|entry| entry.tag), Some(ReputationTag::Warrior));
+        let volume = subscribers.economy.lock().unwrap().get_trade_volume(4);
+        assert_eq!(volume.get(&4), Some(&1));
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! 1. **World Wake** -- advance clock, generate weather, regenerate resources,
 //!    apply vital mechanics (hunger, aging, starvation), advance travelers,
-//!    process deaths.
+//!    process deaths, and strike any natural disasters.
 //!
 //! 2. **Perception** -- assemble a [`Perception`] payload for each living agent
 //!    from world state, applying fog of war and fuzzy resource quantities.
@@ -16,36 +16,66 @@
 //!    resolve conflicts for contested resources, execute valid actions, and
 //!    reject invalid ones.
 //!
-//! 5. **Persist** -- (stub) flush state changes and events. In production this
-//!    writes to Dragonfly and `PostgreSQL`; in Phase 2 it is a no-op.
+//! 5. **Persist** -- publish the tick's events to the trackers subscribed on
+//!    the state's event bus (see [`crate::subscribers`]). Writes to Dragonfly
+//!    and `PostgreSQL` happen outside the tick cycle.
 //!
-//! 6. **Reflection** -- create memories from action results, apply goal updates.
+//! 6. **Reflection** -- create memories from action results, apply goal updates,
+//!    and apply any reflection updates (revised goals, memory summaries) the
+//!    decision source has delivered.
 //!
 //! The tick cycle is deterministic given the same initial state and decision
 //! source outputs.
 //!
 //! [`Perception`]: emergence_types::Perception
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 
+use chrono::Utc;
 use emergence_types::{
     ActionParameters, ActionRequest, ActionResult, ActionType, Agent, AgentId, AgentState,
-    LocationId, Perception, RejectionDetails, RejectionReason, Resource, Season, Weather,
+    DenseId, DenseMap, DisasterDetails, DisasterKind, Event, EventId, EventType, Group, GroupId,
+    Interner, LocationId, Message, Perception, ReflectionUpdate, RejectionDetails,
+    RejectionReason, Resource, Season, Structure, StructureBurnedDetails, StructureId,
+    StructureInheritedDetails, StructureType, TradeRouteEmergedDetails, Weather, WorldContext,
 };
 use tracing::{debug, info, warn};
 
+use crate::agent_store::AgentStore;
 use crate::clock::WorldClock;
 use crate::decision::DecisionSource;
 use crate::feasibility::{self, FeasibilityContext, FeasibilityResult};
+use crate::hooks::MechanicsHooks;
 use crate::operator::InjectedEvent;
-use crate::perception::{self, PerceptionContext};
+use crate::perception::{self, PerceptionContext, PerceptionRequest};
+use crate::scratch::TickScratch;
+use crate::subscribers::EventSubscribers;
 use emergence_agents::actions::conflict::{self, ClaimOutcome, ConflictStrategy, GatherClaim};
 use emergence_agents::actions::handlers::{self, ExecutionContext};
 use emergence_agents::actions::validation::{self, ValidationContext};
 use emergence_agents::config::VitalsConfig;
-use emergence_agents::death::DeathConsequences;
+use emergence_agents::death::{self, DeathConsequences};
+use emergence_agents::market::{self, OrderFill, OrderTerms};
+use emergence_agents::{DiplomacyState, FamilyTracker, OrderBook};
+use emergence_agents::inventory;
 use emergence_agents::vitals;
-use emergence_world::WorldMap;
+use emergence_ledger::Ledger;
+use emergence_world::{
+    CorridorChange, Disaster, DisasterSystem, FaunaChange, FaunaRegistry, FireSystem, Herd,
+    KnownMapRegistry, MineRegistry, RouteCache, TradeNetwork, WaterRegistry, WorldMap,
+    environment, husbandry, known_map, trade_network,
+};
+use emergence_world::route_building::RoutePlan;
+use emergence_world::structure as world_structure;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Ledger entity that livestock born into a herd come from.
+const WORLD_ENTITY: Uuid = Uuid::nil();
+
+/// Ledger entity that fodder eaten and starved livestock go to.
+const VOID_ENTITY: Uuid = Uuid::max();
 
 /// Errors that can occur during tick execution.
 #[derive(Debug, thiserror::Error)]
@@ -82,6 +112,22 @@ pub enum TickError {
         #[from]
         source: crate::decision::DecisionError,
     },
+
+    /// The ledger rejected an entry.
+    #[error("ledger error: {source}")]
+    Ledger {
+        /// The underlying ledger error.
+        #[from]
+        source: emergence_ledger::LedgerError,
+    },
+
+    /// Settling the standing orders at a market failed.
+    #[error("market error: {source}")]
+    Market {
+        /// The underlying trade error.
+        #[from]
+        source: emergence_agents::trade::TradeError,
+    },
 }
 
 /// Summary of a single tick's execution.
@@ -103,6 +149,16 @@ pub struct TickSummary {
     pub regeneration: BTreeMap<LocationId, BTreeMap<Resource, u32>>,
     /// Log messages from injected world events processed this tick.
     pub world_event_logs: Vec<String>,
+    /// Floods, wildfires, and earthquakes that struck this tick.
+    pub disasters: Vec<DisasterDetails>,
+    /// Structures damaged by fires burning this tick, one per structure.
+    pub fires: Vec<StructureBurnedDetails>,
+    /// Routes whose traffic made them trade corridors this tick.
+    pub trade_routes: Vec<TradeRouteEmergedDetails>,
+    /// Structures that passed from agents who died this tick to their heirs.
+    pub inheritances: Vec<StructureInheritedDetails>,
+    /// Standing orders filled against each other at markets this tick.
+    pub market_fills: Vec<OrderFill>,
 }
 
 /// Result of the World Wake phase.
@@ -117,6 +173,14 @@ struct WakeResult {
     deaths: Vec<DeathConsequences>,
     /// Log messages from injected world events processed this tick.
     world_event_logs: Vec<String>,
+    /// Disasters that struck this tick.
+    disasters: Vec<DisasterDetails>,
+    /// Structures damaged by fires this tick.
+    fires: Vec<StructureBurnedDetails>,
+    /// Routes that became trade corridors this tick.
+    trade_routes: Vec<TradeRouteEmergedDetails>,
+    /// Structures that passed to the heirs of agents who died this tick.
+    inheritances: Vec<StructureInheritedDetails>,
 }
 
 /// Result of processing a single injected world event.
@@ -128,8 +192,10 @@ struct WorldEventResult {
 /// Categorized actions after validation, split into gather claims (which
 /// need conflict resolution) and non-gather actions (executed directly).
 struct CategorizedActions {
+    /// Locations of acting agents, interned for this tick.
+    locations: Interner<LocationId>,
     /// Gather claims grouped by (location, resource) for conflict resolution.
-    gather_claims: BTreeMap<(LocationId, Resource), Vec<(AgentId, GatherClaim)>>,
+    gather_claims: BTreeMap<(DenseId<LocationId>, Resource), Vec<GatherClaim>>,
     /// Non-gather actions to execute sequentially.
     non_gather: Vec<(AgentId, ActionRequest)>,
 }
@@ -172,8 +238,8 @@ pub struct SimulationState {
     pub agents: BTreeMap<AgentId, Agent>,
     /// Agent identity data: agent\_id -> name (immutable after creation).
     pub agent_names: BTreeMap<AgentId, String>,
-    /// Agent mutable state: agent\_id -> state.
-    pub agent_states: BTreeMap<AgentId, AgentState>,
+    /// Agent mutable state, stored by column (see [`AgentStore`]).
+    pub agent_states: AgentStore,
     /// Set of agent IDs that are alive.
     pub alive_agents: Vec<AgentId>,
     /// Vitals configuration.
@@ -186,6 +252,46 @@ pub struct SimulationState {
     pub active_plagues: Vec<ActivePlague>,
     /// Active resource booms boosting location regeneration.
     pub active_resource_booms: Vec<ActiveResourceBoom>,
+    /// Seeded disaster rolls and the routes disasters have blocked.
+    pub disasters: DisasterSystem,
+    /// Fires burning at each location, and the seeded rolls that start
+    /// them.
+    pub fires: FireSystem,
+    /// Game and predator populations per location, and the herds that
+    /// overhunting has collapsed.
+    pub fauna: FaunaRegistry,
+    /// Rivers and lakes, and the fish stocks along them.
+    pub waters: WaterRegistry,
+    /// The finite ore beneath each location, and how deep its shaft runs.
+    pub mines: MineRegistry,
+    /// What each agent knows of the map, and the maps kept in libraries.
+    pub known_maps: KnownMapRegistry,
+    /// Recent traffic along each route, and the trade corridors it has
+    /// worn in.
+    pub trade_network: TradeNetwork,
+    /// Every structure in the world, by ID.
+    pub structures: BTreeMap<StructureId, Structure>,
+    /// Marriages, births, and which agents are alive, for finding heirs.
+    pub family: FamilyTracker,
+    /// Every resource the tick cycle moves into, out of, or between
+    /// holdings.
+    pub ledger: Ledger,
+    /// Standing buy and sell orders at every market.
+    pub orders: OrderBook,
+    /// The groups agents have formed, by ID.
+    pub groups: BTreeMap<GroupId, Group>,
+    /// Alliances, conflicts, and treaties between groups, and the sieges
+    /// laid in their conflicts.
+    pub diplomacy: DiplomacyState,
+    /// Custom mechanics consulted during resolution and at the end of each
+    /// tick (see [`crate::hooks`]).
+    pub hooks: Option<Arc<dyn MechanicsHooks>>,
+    /// Buffers reused by the tick cycle from one tick to the next (see
+    /// [`crate::scratch`]).
+    pub scratch: TickScratch,
+    /// The event bus each tick publishes to, and the reputation, belief,
+    /// and economy trackers subscribed to it (see [`crate::subscribers`]).
+    pub subscribers: EventSubscribers,
 }
 
 /// Execute one complete tick of the simulation.
@@ -199,21 +305,26 @@ pub struct SimulationState {
 /// 2. Perception
 /// 3. Decision (via the provided `DecisionSource`)
 /// 4. Resolution
-/// 5. Persist (stub)
+/// 5. Persist (publish events to subscribers)
 /// 6. Reflection
 pub fn run_tick(
     state: &mut SimulationState,
     decision_source: &mut dyn DecisionSource,
 ) -> Result<TickSummary, TickError> {
-    let _tick_span = tracing::info_span!("tick_cycle").entered();
+    let tick_span = tracing::info_span!("tick_cycle", tick = tracing::field::Empty).entered();
+
+    // Borrow the scratch buffers for this tick; they go back on `state`
+    // once the phases are done.
+    let mut scratch = std::mem::take(&mut state.scratch);
 
     // --- Phase 1: World Wake ---
     let wake = {
         let _span = tracing::info_span!("phase_world_wake").entered();
-        phase_world_wake(state)?
+        phase_world_wake(state, &mut scratch)?
     };
 
     let tick = state.clock.tick();
+    tick_span.record("tick", tick);
     info!(tick, season = ?wake.season, weather = ?wake.weather, "Tick started");
 
     // Remove dead agents from the alive list and update Agent records.
@@ -235,7 +346,7 @@ pub fn run_tick(
     let perceptions = {
         let _span = tracing::info_span!("phase_perception", agents = state.alive_agents.len())
             .entered();
-        phase_perception(state, wake.season, wake.weather)
+        phase_perception(state, &mut scratch, wake.season, wake.weather)
     };
 
     // --- Phase 3: Decision ---
@@ -245,23 +356,33 @@ pub fn run_tick(
     };
 
     // --- Phase 4: Resolution ---
-    let action_results = {
+    let (action_results, market_fills) = {
         let _span = tracing::info_span!("phase_resolution", actions = decisions.len()).entered();
-        phase_resolution(state, &decisions, wake.weather)
+        let results = phase_resolution(state, &mut scratch, &decisions, wake.weather);
+        (results, settle_market_orders(state, tick)?)
     };
 
-    // --- Phase 5: Persist (stub) ---
-    debug!(tick, "Persist phase (stub)");
+    // --- Phase 5: Persist ---
+    {
+        let _span = tracing::info_span!("phase_persist").entered();
+        let mut events = tick_events(state, &decisions, &action_results, &wake, tick);
+        events.extend(market_fill_events(state, &market_fills, &wake, tick));
+        let delivered = state.subscribers.publish(&events);
+        debug!(tick, events = events.len(), delivered, "Tick events published");
+    }
 
     // --- Phase 6: Reflection ---
     {
         let _span = tracing::info_span!("phase_reflection").entered();
         phase_reflection(state, &decisions, &action_results, tick);
+        let reflections = decision_source.collect_reflections(tick);
+        apply_reflections(state, &reflections, tick);
     }
+    state.scratch = scratch;
 
     let agents_alive = u32::try_from(state.alive_agents.len()).unwrap_or(u32::MAX);
 
-    Ok(TickSummary {
+    let summary = TickSummary {
         tick,
         season: wake.season,
         weather: wake.weather,
@@ -270,14 +391,30 @@ pub fn run_tick(
         action_results,
         regeneration: wake.regeneration,
         world_event_logs: wake.world_event_logs,
-    })
+        disasters: wake.disasters,
+        fires: wake.fires,
+        trade_routes: wake.trade_routes,
+        inheritances: wake.inheritances,
+        market_fills,
+    };
+
+    if let Some(hooks) = state.hooks.clone() {
+        let events = hooks.after_tick(state, &summary);
+        state.injected_events.extend(events);
+    }
+
+    Ok(summary)
 }
 
 /// Phase 1: World Wake.
 ///
 /// Advances the clock, generates weather, regenerates resources, applies
 /// vital mechanics to all agents, advances travelers, and processes deaths.
-fn phase_world_wake(state: &mut SimulationState) -> Result<WakeResult, TickError> {
+#[allow(clippy::too_many_lines)]
+fn phase_world_wake(
+    state: &mut SimulationState,
+    scratch: &mut TickScratch,
+) -> Result<WakeResult, TickError> {
     // 1a. Advance clock
     state.clock.advance()?;
     let tick = state.clock.tick();
@@ -286,17 +423,21 @@ fn phase_world_wake(state: &mut SimulationState) -> Result<WakeResult, TickError
     let season = state.clock.season()?;
     let weather = state.weather_system.generate(tick, season);
 
-    // 1c. Regenerate resources at all locations
-    let regeneration = state.world_map.regenerate_all_resources(season)?;
+    // 1c. Regenerate resources at all locations, slowed by climate drift
+    let climate_loss_pct = state.weather_system.climate().regen_loss_pct_at(tick);
+    let regeneration =
+        state.world_map.regenerate_all_resources_penalized(season, climate_loss_pct)?;
 
     // 1d. Advance travelers and apply vitals
     let mut deaths = Vec::new();
-    let agent_ids: Vec<AgentId> = state.alive_agents.clone();
+    scratch.wake_order.clear();
+    scratch.wake_order.extend_from_slice(&state.alive_agents);
 
-    for agent_id in &agent_ids {
-        let Some(agent_state) = state.agent_states.get_mut(agent_id) else {
+    for agent_id in &scratch.wake_order {
+        let Some(mut agent_state) = state.agent_states.get_mut(agent_id) else {
             continue;
         };
+        let agent_state = &mut *agent_state;
 
         // Advance travel progress for traveling agents
         if agent_state.travel_progress > 0 {
@@ -330,8 +471,8 @@ fn phase_world_wake(state: &mut SimulationState) -> Result<WakeResult, TickError
 
         // Check for death
         if let Some(cause) = vital_result.death {
-            let consequences =
-                emergence_agents::death::process_death(agent_state, cause, Vec::new());
+            let owned = owned_structures(&state.structures, *agent_id);
+            let consequences = death::process_death(agent_state, cause, owned);
             info!(
                 tick,
                 agent_id = %consequences.agent_id,
@@ -345,31 +486,142 @@ fn phase_world_wake(state: &mut SimulationState) -> Result<WakeResult, TickError
 
     // 1e. Process injected world events
     let mut world_event_logs = Vec::new();
+    let mut disasters = Vec::new();
     let injected = std::mem::take(&mut state.injected_events);
     for event in &injected {
-        if let Some(result) = process_injected_event(event, state) {
+        if let Some(kind) = injected_disaster_kind(&event.event_type) {
+            world_event_logs.push(process_disaster(kind, event, state, &mut disasters).log);
+        } else if let Some(result) = process_injected_event(event, state) {
             world_event_logs.push(result.log);
         }
     }
 
-    // 1f. Process active plagues (tick down, apply damage, spread)
+    // 1f. Reopen routes whose disaster blocks have lifted, roll for a random
+    // disaster, then burn any fires it or an unattended campfire started
+    let fires = roll_disasters_and_fires(state, weather, &mut disasters, &mut world_event_logs);
+
+    // 1g. Process active plagues (tick down, apply damage, spread)
     process_active_plagues(state, &mut deaths, tick);
 
-    // 1g. Process active resource booms (tick down)
+    // 1h. Process active resource booms (tick down)
     state.active_resource_booms.retain_mut(|boom| {
         boom.remaining_ticks = boom.remaining_ticks.saturating_sub(1);
         boom.remaining_ticks > 0
     });
 
+    // 1i. Grow and thin wildlife, collapsing overhunted herds
+    for (location_id, change) in state.fauna.tick(tick) {
+        let name = state
+            .world_map
+            .get_location(location_id)
+            .map_or_else(|| location_id.to_string(), |l| l.location.name.clone());
+        let log = match change {
+            FaunaChange::Collapsed => format!("Game at {name} collapsed from overhunting"),
+            FaunaChange::Recovered => format!("Game at {name} is breeding again"),
+        };
+        info!(tick, %location_id, ?change, "Wildlife population changed");
+        world_event_logs.push(log);
+    }
+
+    // 1j. Restock fish, carrying overflow downstream (none in a drought)
+    state.waters.tick(weather);
+
+    // 1k. Crowding pollutes the land; pollution slowly disperses
+    state.world_map.tick_pollution();
+
+    // 1l. Agents learn the places they stand in and the routes they took
+    for agent_id in &state.alive_agents {
+        if let Some(location_id) = state.agent_states.location(agent_id) {
+            state.known_maps.record_position(&state.world_map, *agent_id, location_id);
+        }
+    }
+
+    // 1m. Herds breed, or eat their owners' fodder in winter
+    tend_herds(state, season)?;
+
+    // 1n. Busy routes become trade corridors; quiet ones fade
+    let trade_routes = weigh_trade_routes(state);
+
+    // 1o. The dead's structures pass to their heirs
+    let inheritances = settle_inheritances(state, &mut deaths, tick);
+
+    // 1p. Sieges tighten or lift with who holds the land around them
+    update_sieges(state);
+
     Ok(WakeResult {
         season,
         weather,
         regeneration,
         deaths,
         world_event_logs,
+        disasters,
+        fires,
+        trade_routes,
+        inheritances,
     })
 }
 
+/// IDs of the standing structures `agent_id` owns.
+fn owned_structures(
+    structures: &BTreeMap<StructureId, Structure>,
+    agent_id: AgentId,
+) -> Vec<StructureId> {
+    structures
+        .values()
+        .filter(|s| s.owner == Some(agent_id) && s.destroyed_at_tick.is_none())
+        .map(|s| s.id)
+        .collect()
+}
+
+/// Mark this tick's dead in the family tracker, then hand each one's
+/// structures to their heir (see [`death::settle_inheritance`]).
+///
+/// Structures of an agent with no living heir lose their owner and become
+/// claimable.
+fn settle_inheritances(
+    state: &mut SimulationState,
+    deaths: &mut [DeathConsequences],
+    tick: u64,
+) -> Vec<StructureInheritedDetails> {
+    for consequences in deaths.iter() {
+        state.family.mark_dead(consequences.agent_id);
+    }
+
+    let mut inheritances = Vec::new();
+    for consequences in deaths.iter_mut() {
+        let heir = death::settle_inheritance(consequences, &state.family);
+        for structure_id in &consequences.orphaned_structures {
+            if let Some(structure) = state.structures.get_mut(structure_id) {
+                structure.owner = None;
+            }
+        }
+        let Some(heir) = heir else {
+            continue;
+        };
+        for structure_id in &consequences.inherited_structures {
+            let Some(structure) = state.structures.get_mut(structure_id) else {
+                continue;
+            };
+            structure.owner = Some(heir);
+            info!(
+                tick,
+                %structure_id,
+                deceased = %consequences.agent_id,
+                %heir,
+                "Structure inherited"
+            );
+            inheritances.push(StructureInheritedDetails {
+                structure_id: *structure_id,
+                structure_type: structure.structure_type,
+                deceased: consequences.agent_id,
+                heir,
+                location_id: structure.location_id,
+            });
+        }
+    }
+    inheritances
+}
+
 /// Process a single injected world event and apply its effects to the simulation.
 ///
 /// Returns `None` if the event type is unrecognized.
@@ -423,13 +675,13 @@ fn process_natural_disaster(
     let health_damage = severity.saturating_mul(10);
     let agents_at_loc: Vec<AgentId> = state
         .agent_states
-        .values()
-        .filter(|s| s.location_id == location_id)
-        .map(|s| s.agent_id)
+        .locations()
+        .filter(|&(_, loc)| loc == location_id)
+        .map(|(id, _)| id)
         .collect();
 
     for agent_id in &agents_at_loc {
-        if let Some(agent_state) = state.agent_states.get_mut(agent_id) {
+        if let Some(mut agent_state) = state.agent_states.get_mut(agent_id) {
             agent_state.health = agent_state.health.saturating_sub(health_damage);
         }
     }
@@ -449,6 +701,425 @@ fn process_natural_disaster(
     }
 }
 
+/// The disaster kind an injected event type names, if any.
+fn injected_disaster_kind(event_type: &str) -> Option<DisasterKind> {
+    match event_type {
+        "flood" => Some(DisasterKind::Flood),
+        "wildfire" => Some(DisasterKind::Wildfire),
+        "earthquake" => Some(DisasterKind::Earthquake),
+        _ => None,
+    }
+}
+
+/// Process an operator-injected flood, wildfire, or earthquake at the
+/// target region (see [`emergence_world::disasters`]).
+fn process_disaster(
+    kind: DisasterKind,
+    event: &InjectedEvent,
+    state: &mut SimulationState,
+    disasters: &mut Vec<DisasterDetails>,
+) -> WorldEventResult {
+    let Some(location_id) = find_target_location(event.target_region.as_deref(), state) else {
+        return WorldEventResult {
+            log: format!("{kind:?}: no valid target location found"),
+        };
+    };
+    let disaster = Disaster {
+        kind,
+        location_id,
+        severity: parse_severity(event.severity.as_deref()),
+    };
+    strike_disaster(&disaster, true, state, disasters)
+}
+
+/// Reopen lifted routes and roll for a random disaster, then roll for
+/// unattended campfires to catch in `weather` and burn every fire for the
+/// tick (see [`emergence_world::fire`]).
+fn roll_disasters_and_fires(
+    state: &mut SimulationState,
+    weather: Weather,
+    disasters: &mut Vec<DisasterDetails>,
+    world_event_logs: &mut Vec<String>,
+) -> Vec<StructureBurnedDetails> {
+    let tick = state.clock.tick();
+    state.disasters.lift_expired(tick, &mut state.world_map);
+    if let Some(disaster) = state.disasters.roll(tick, &state.world_map) {
+        let result = strike_disaster(&disaster, false, state, disasters);
+        world_event_logs.push(result.log);
+    }
+    let campfires: Vec<Structure> = state
+        .structures
+        .values()
+        .filter(|s| s.structure_type == StructureType::Campfire)
+        .cloned()
+        .collect();
+    let caught =
+        state.fires.ignite_unattended_campfires(tick, weather, &state.world_map, &campfires);
+    for location_id in caught {
+        info!(tick, %location_id, "Unattended campfire caught");
+    }
+    let burning = state.fires.burning_locations();
+    with_structures_at(&mut state.structures, &burning, |structures| {
+        state.fires.burn(tick, weather, structures)
+    })
+}
+
+/// Lend the standing structures at `locations` to `f` as a slice, then put
+/// them back in `structures` with whatever damage `f` did.
+fn with_structures_at<T>(
+    structures: &mut BTreeMap<StructureId, Structure>,
+    locations: &BTreeSet<LocationId>,
+    f: impl FnOnce(&mut [Structure]) -> T,
+) -> T {
+    let ids: Vec<StructureId> = structures
+        .values()
+        .filter(|s| locations.contains(&s.location_id) && s.destroyed_at_tick.is_none())
+        .map(|s| s.id)
+        .collect();
+    let mut lent: Vec<Structure> = ids.iter().filter_map(|id| structures.remove(id)).collect();
+    let result = f(&mut lent);
+    for structure in lent {
+        structures.insert(structure.id, structure);
+    }
+    result
+}
+
+/// Step every living agent's herd of [`Resource::Livestock`] on the
+/// pastures they own (see [`emergence_world::husbandry`]), recording the
+/// young born, the head starved, and the fodder eaten in the ledger.
+/// Young an owner has no room to carry are lost.
+fn tend_herds(state: &mut SimulationState, season: Season) -> Result<(), TickError> {
+    let tick = state.clock.tick();
+    let mut pastures: BTreeMap<AgentId, u32> = BTreeMap::new();
+    for structure in state.structures.values() {
+        if structure.structure_type == StructureType::Pasture
+            && structure.destroyed_at_tick.is_none()
+            && let Some(owner) = structure.owner
+        {
+            let count = pastures.entry(owner).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+    }
+
+    for agent_id in &state.alive_agents {
+        let Some(mut agent_state) = state.agent_states.get_mut(agent_id) else {
+            continue;
+        };
+        let agent_state = &mut *agent_state;
+        let herd = Herd {
+            head: agent_state.inventory.get(&Resource::Livestock).copied().unwrap_or(0),
+            pastures: pastures.get(agent_id).copied().unwrap_or(0),
+        };
+        let fodder = husbandry::fodder_available(&agent_state.inventory);
+        let change = herd.step(tick, season, fodder);
+        if change.is_empty() {
+            continue;
+        }
+
+        let owner = agent_id.into_inner();
+        let agent_error = |source| TickError::Agent {
+            agent_id: *agent_id,
+            source,
+        };
+        let eaten = husbandry::fodder_to_eat(&agent_state.inventory, change.fodder_eaten);
+        for (resource, amount) in eaten {
+            inventory::remove_resource(&mut agent_state.inventory, resource, amount)
+                .map_err(agent_error)?;
+            let quantity = Decimal::from(amount);
+            state.ledger.record_consumption(tick, resource, quantity, owner, VOID_ENTITY)?;
+        }
+        if change.starved > 0 {
+            inventory::remove_resource(
+                &mut agent_state.inventory,
+                Resource::Livestock,
+                change.starved,
+            )
+            .map_err(agent_error)?;
+            let quantity = Decimal::from(change.starved);
+            state.ledger.record_slaughter(tick, Resource::Livestock, quantity, owner, VOID_ENTITY)?;
+        }
+        let kept = inventory::add_resource(
+            &mut agent_state.inventory,
+            agent_state.carry_capacity,
+            Resource::Livestock,
+            change.born,
+        );
+        let born = if kept.is_ok() { change.born } else { 0 };
+        if born > 0 {
+            let quantity = Decimal::from(born);
+            state.ledger.record_breeding(tick, Resource::Livestock, quantity, WORLD_ENTITY, owner)?;
+        }
+        info!(
+            tick,
+            ?agent_id,
+            born,
+            fodder_eaten = change.fodder_eaten,
+            starved = change.starved,
+            "Herd stepped"
+        );
+    }
+    Ok(())
+}
+
+/// Fill the crossing standing orders at every market, then withdraw the
+/// expired ones and return their escrow (see [`emergence_agents::market`]).
+///
+/// Orders of the dead are neither filled nor returned.
+fn settle_market_orders(
+    state: &mut SimulationState,
+    tick: u64,
+) -> Result<Vec<OrderFill>, TickError> {
+    if state.orders.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut traders: BTreeMap<AgentId, AgentState> = state
+        .orders
+        .traders()
+        .into_iter()
+        .filter(|agent_id| state.alive_agents.contains(agent_id))
+        .filter_map(|agent_id| Some((agent_id, state.agent_states.get(&agent_id)?.clone())))
+        .collect();
+    let (orders, ledger) = (&mut state.orders, &mut state.ledger);
+    let settled = orders.match_orders(&mut traders, ledger, tick).and_then(|fills| {
+        Ok((fills, orders.expire_orders(&mut traders, ledger, tick)?))
+    });
+    for (agent_id, trader) in traders {
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            *agent_state = trader;
+        }
+    }
+
+    let (fills, expired) = settled?;
+    for fill in &fills {
+        info!(
+            tick,
+            market = %fill.market_id,
+            seller = %fill.completed.agent_a,
+            buyer = %fill.completed.agent_b,
+            unit_price = fill.unit_price,
+            "Market orders filled"
+        );
+    }
+    for order in &expired {
+        debug!(
+            tick,
+            order_id = %order.order_id,
+            agent_id = %order.agent_id,
+            "Market order expired"
+        );
+    }
+    Ok(fills)
+}
+
+/// Post the standing order a `PostOrder` action describes with `terms`,
+/// escrowing what it gives up through the ledger (see
+/// [`market::post_order`]).
+fn post_market_order(
+    (orders, ledger): (&mut OrderBook, &mut Ledger),
+    agent_state: &mut AgentState,
+    terms: &OrderTerms,
+    request: &ActionRequest,
+    tick: u64,
+) -> ActionResult {
+    let agent_id = agent_state.agent_id;
+    let expiry = market::DEFAULT_ORDER_EXPIRY_TICKS;
+    match market::post_order(agent_state, terms, ledger, tick, expiry) {
+        Ok((order, outcome)) => {
+            info!(tick, ?agent_id, order_id = %order.order_id, "Market order posted");
+            orders.insert(order);
+            ActionResult {
+                tick,
+                agent_id,
+                action_type: request.action_type,
+                success: true,
+                outcome: Some(outcome),
+                rejection: None,
+                side_effects: Vec::new(),
+            }
+        }
+        Err(err) => {
+            warn!(tick, ?agent_id, %err, "Market order could not be posted");
+            make_rejection(tick, agent_id, request.action_type, RejectionReason::InvalidAction)
+        }
+    }
+}
+
+/// Bring every siege up to date with the groups whose living members
+/// stand at each location (see [`DiplomacyState::update_sieges`]).
+fn update_sieges(state: &mut SimulationState) {
+    let tick = state.clock.tick();
+    let mut holders: BTreeMap<LocationId, BTreeSet<GroupId>> = BTreeMap::new();
+    for agent_id in &state.alive_agents {
+        let Some(location_id) = state.agent_states.location(agent_id) else {
+            continue;
+        };
+        holders.entry(location_id).or_default().extend(groups_of(&state.groups, *agent_id));
+    }
+    for siege in state.diplomacy.update_sieges(&state.world_map, &holders) {
+        info!(tick, location_id = %siege.location_id, besieger = %siege.besieger, "Siege lifted");
+    }
+}
+
+/// IDs of the groups `agent_id` belongs to.
+fn groups_of(groups: &BTreeMap<GroupId, Group>, agent_id: AgentId) -> Vec<GroupId> {
+    groups
+        .values()
+        .filter(|group| group.members.contains(&agent_id))
+        .map(|group| group.id)
+        .collect()
+}
+
+/// The standing structures at `location_id`, by ID.
+fn structures_at(
+    structures: &BTreeMap<StructureId, Structure>,
+    location_id: LocationId,
+) -> BTreeMap<StructureId, Structure> {
+    structures
+        .iter()
+        .filter(|(_, s)| s.location_id == location_id && s.destroyed_at_tick.is_none())
+        .map(|(id, s)| (*id, s.clone()))
+        .collect()
+}
+
+/// The standing fortifications at `location_id` (see
+/// [`world_structure::is_fortification`]).
+fn fortifications_at(
+    structures: &BTreeMap<StructureId, Structure>,
+    location_id: LocationId,
+) -> Vec<Structure> {
+    structures
+        .values()
+        .filter(|s| {
+            s.location_id == location_id
+                && s.destroyed_at_tick.is_none()
+                && world_structure::is_fortification(s.structure_type)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Batter the fortification barring `agent_id` from `destination` with
+/// `damage` (see [`world_structure::breach_target`]).
+///
+/// The breach counts against every conflict between the agent's groups
+/// and the groups of the fortification's owner, and lays siege to the
+/// destination in the first of them if it is not already besieged.
+fn breach_fortification(
+    structures: &mut BTreeMap<StructureId, Structure>,
+    groups: &BTreeMap<GroupId, Group>,
+    diplomacy: &mut DiplomacyState,
+    agent_id: AgentId,
+    (destination, damage): (LocationId, u32),
+    tick: u64,
+) {
+    let attackers = groups_of(groups, agent_id);
+    let fortifications = fortifications_at(structures, destination);
+    let Some(target) = world_structure::breach_target(&fortifications, agent_id, &attackers)
+    else {
+        return;
+    };
+    let Some(structure) = structures.get_mut(&target.id) else {
+        return;
+    };
+    let fell = world_structure::apply_breach(structure, damage);
+    info!(
+        tick,
+        ?agent_id,
+        %destination,
+        structure_id = %structure.id,
+        durability = structure.durability,
+        fell,
+        "Fortification breached"
+    );
+
+    let defenders = structure.owner.map(|owner| groups_of(groups, owner)).unwrap_or_default();
+    for attacker in &attackers {
+        for defender in &defenders {
+            if !diplomacy.record_breach(attacker, defender)
+                || diplomacy.siege_at(&destination).is_some()
+            {
+                continue;
+            }
+            match diplomacy.begin_siege(*attacker, *defender, destination, tick) {
+                Ok(_) => info!(tick, %destination, besieger = %attacker, "Siege laid"),
+                Err(err) => warn!(tick, %destination, %err, "Siege could not be laid"),
+            }
+        }
+    }
+}
+
+/// Promote routes whose recent traffic makes them trade corridors, and let
+/// quiet corridors fade (see [`emergence_world::trade_network`]).
+///
+/// Returns the details of the corridors that emerged.
+fn weigh_trade_routes(state: &mut SimulationState) -> Vec<TradeRouteEmergedDetails> {
+    let tick = state.clock.tick();
+    let mut emerged = Vec::new();
+    for (route_id, change) in state.trade_network.tick(tick) {
+        let trips = state.trade_network.utilization(route_id);
+        info!(tick, %route_id, ?change, trips, "Trade corridor changed");
+        if change != CorridorChange::Emerged {
+            continue;
+        }
+        if let Some(route) = state.world_map.get_route(route_id) {
+            emerged.push(TradeRouteEmergedDetails {
+                route_id,
+                from_location: route.from_location,
+                to_location: route.to_location,
+                trips,
+                window_ticks: trade_network::UTILIZATION_WINDOW_TICKS,
+            });
+        }
+    }
+    emerged
+}
+
+/// Strike `disaster` and record its details.
+fn strike_disaster(
+    disaster: &Disaster,
+    injected: bool,
+    state: &mut SimulationState,
+    disasters: &mut Vec<DisasterDetails>,
+) -> WorldEventResult {
+    let tick = state.clock.tick();
+    let loc_name = state
+        .world_map
+        .get_location(disaster.location_id)
+        .map_or_else(|| String::from("Unknown"), |loc| loc.location.name.clone());
+
+    let here = BTreeSet::from([disaster.location_id]);
+    let struck = with_structures_at(&mut state.structures, &here, |structures| {
+        state.disasters.strike(disaster, tick, &mut state.world_map, structures, injected)
+    });
+    let Ok(details) = struck else {
+        return WorldEventResult {
+            log: format!("{:?}: target location {loc_name} not found", disaster.kind),
+        };
+    };
+
+    info!(
+        kind = ?details.kind,
+        location = %loc_name,
+        severity = details.severity,
+        injected,
+        routes_blocked = details.routes_blocked.len(),
+        "Disaster struck"
+    );
+    let log = format!(
+        "{:?} (severity {}) at {loc_name}: {} resources depleted, {} routes blocked until tick {}",
+        details.kind,
+        details.severity,
+        details.resources_destroyed.len(),
+        details.routes_blocked.len(),
+        details.blocked_until_tick,
+    );
+    if details.kind == DisasterKind::Wildfire {
+        state.fires.ignite(details.location_id, details.severity, tick);
+    }
+    disasters.push(details);
+    WorldEventResult { log }
+}
+
 /// Process a resource boom event.
 ///
 /// Doubles resource regeneration at a target location for a configurable
@@ -628,36 +1299,58 @@ fn process_active_plagues(
     deaths: &mut Vec<DeathConsequences>,
     tick: u64,
 ) {
-    // Collect plague effects before mutating
+    if state.active_plagues.is_empty() {
+        return;
+    }
+
+    // Collect plague effects before mutating. Overcrowded locations suffer
+    // more: the damage is scaled by the location's disease risk.
     let plague_effects: Vec<(LocationId, u32)> = state
         .active_plagues
         .iter()
-        .map(|p| (p.location_id, p.damage_per_tick))
+        .map(|p| {
+            let risk_pct = state.world_map.get_location(p.location_id).map_or(100, |loc| {
+                emergence_world::crowding::disease_risk_pct(
+                    loc.location.capacity,
+                    loc.occupant_count(),
+                )
+            });
+            let damage = p.damage_per_tick.saturating_mul(risk_pct).saturating_div(100);
+            (p.location_id, damage)
+        })
         .collect();
 
-    // Build a set of alive agents for O(1) membership checks.
-    let alive_set: std::collections::BTreeSet<AgentId> =
-        state.alive_agents.iter().copied().collect();
+    // Build a set of agents still alive for O(1) membership checks. Agents
+    // who already died this tick (from vitals, or an earlier plague) must
+    // not die again.
+    let already_dead: std::collections::BTreeSet<AgentId> =
+        deaths.iter().map(|d| d.agent_id).collect();
+    let mut alive_set: std::collections::BTreeSet<AgentId> = state
+        .alive_agents
+        .iter()
+        .copied()
+        .filter(|id| !already_dead.contains(id))
+        .collect();
 
     // Apply plague damage to agents at affected locations
     for (location_id, damage) in &plague_effects {
         let agents_at_loc: Vec<AgentId> = state
             .agent_states
-            .values()
-            .filter(|s| s.location_id == *location_id)
-            .map(|s| s.agent_id)
+            .locations()
+            .filter(|(_, loc)| loc == location_id)
+            .map(|(id, _)| id)
             .collect();
 
         for agent_id in &agents_at_loc {
-            if let Some(agent_state) = state.agent_states.get_mut(agent_id) {
+            if let Some(mut agent_state) = state.agent_states.get_mut(agent_id) {
                 agent_state.health = agent_state.health.saturating_sub(*damage);
 
                 // Check for death from plague
-                if agent_state.health == 0 && alive_set.contains(agent_id) {
-                    let consequences = emergence_agents::death::process_death(
-                        agent_state,
-                        emergence_agents::death::DeathCause::Injury,
-                        Vec::new(),
+                if agent_state.health == 0 && alive_set.remove(agent_id) {
+                    let consequences = death::process_death(
+                        &mut agent_state,
+                        death::DeathCause::Injury,
+                        owned_structures(&state.structures, *agent_id),
                     );
                     info!(
                         tick,
@@ -745,8 +1438,12 @@ fn find_target_location(
 ///
 /// Optimization: location contexts are pre-computed once per occupied location
 /// (not per agent) so that agents sharing a location share the same context.
+/// Occupied locations are interned to dense indices for the grouping pass.
+/// The per-agent payloads are then assembled in parallel
+/// ([`perception::assemble_perceptions`]).
 fn phase_perception(
     state: &SimulationState,
+    scratch: &mut TickScratch,
     season: Season,
     weather: Weather,
 ) -> BTreeMap<AgentId, Perception> {
@@ -754,23 +1451,31 @@ fn phase_perception(
     let time_of_day = state.clock.time_of_day();
     let ticks_until_season_change = state.clock.ticks_until_season_change();
 
-    // Pre-compute the set of occupied locations to build contexts in one pass.
-    let mut location_contexts: BTreeMap<LocationId, PerceptionContext> = BTreeMap::new();
-
     // Group alive agents by location to avoid per-agent context lookups.
-    let mut agents_by_location: BTreeMap<LocationId, Vec<AgentId>> = BTreeMap::new();
+    let mut locations: Interner<LocationId> = Interner::new();
+    let mut agents_by_location: DenseMap<LocationId, Vec<AgentId>> = DenseMap::new();
     for &agent_id in &state.alive_agents {
-        if let Some(agent_state) = state.agent_states.get(&agent_id) {
-            agents_by_location
-                .entry(agent_state.location_id)
-                .or_default()
-                .push(agent_id);
+        let Some(location) = state
+            .agent_states
+            .location(&agent_id)
+            .and_then(|location_id| locations.intern(location_id))
+        else {
+            continue;
+        };
+        if let Some(agents) = agents_by_location.get_mut(location) {
+            agents.push(agent_id);
+        } else {
+            let mut agents = scratch.agent_lists.take();
+            agents.push(agent_id);
+            agents_by_location.insert(location, agents);
         }
     }
 
     // Build location contexts for each occupied location exactly once.
-    for &location_id in agents_by_location.keys() {
-        let ctx = build_location_context(
+    let mut location_contexts: DenseMap<LocationId, PerceptionContext> =
+        DenseMap::with_capacity(locations.len());
+    for (location, location_id) in locations.iter() {
+        let mut ctx = build_location_context(
             state,
             location_id,
             tick,
@@ -779,13 +1484,15 @@ fn phase_perception(
             weather,
             ticks_until_season_change,
         );
-        location_contexts.insert(location_id, ctx);
+        ctx.known_routes =
+            build_known_routes(state, &mut scratch.route_cache, location_id, weather);
+        location_contexts.insert(location, ctx);
     }
 
-    // Assemble perceptions using pre-computed contexts.
-    let mut perceptions = BTreeMap::new();
-    for (&location_id, agent_ids) in &agents_by_location {
-        let Some(ctx) = location_contexts.get(&location_id) else {
+    // Gather each agent's inputs, then assemble the perceptions in parallel.
+    let mut requests = Vec::with_capacity(state.alive_agents.len());
+    for (location, agent_ids) in agents_by_location.iter() {
+        let Some(ctx) = location_contexts.get(location) else {
             continue;
         };
         for &agent_id in agent_ids {
@@ -804,62 +1511,81 @@ fn phase_perception(
 
             let personality = agent.map(|a| &a.personality);
 
-            let p = perception::assemble_perception(
-                agent_state, agent_name, agent_sex, personality, ctx,
-            );
-            perceptions.insert(agent_id, p);
+            requests.push(PerceptionRequest {
+                agent_state,
+                agent_name,
+                agent_sex,
+                personality,
+                ctx,
+            });
         }
     }
+    let mut perceptions = perception::assemble_perceptions(&requests);
+    hide_unexplored_routes(state, &mut perceptions);
+    forecast_weather(state, &mut perceptions, season);
+
+    for agents in agents_by_location.into_values() {
+        scratch.agent_lists.give(agents);
+    }
 
     perceptions
 }
 
-/// Build a `PerceptionContext` for a specific location.
-fn build_location_context(
+/// Fog of war: blank out every route an agent sees leading to a place it
+/// does not know (see [`known_map`]).
+fn hide_unexplored_routes(
     state: &SimulationState,
-    location_id: LocationId,
-    tick: u64,
-    time_of_day: emergence_types::TimeOfDay,
+    perceptions: &mut BTreeMap<AgentId, Perception>,
+) {
+    for (agent_id, perception) in perceptions.iter_mut() {
+        for route in &mut perception.known_routes {
+            let known = uuid::Uuid::parse_str(&route.destination_id).is_ok_and(|id| {
+                let location = LocationId::from(id);
+                state.known_maps.knows_location(&state.world_map, *agent_id, location)
+            });
+            if !known {
+                known_map::UNEXPLORED.clone_into(&mut route.destination);
+                route.resources_hint.clear();
+            }
+        }
+    }
+}
+
+/// Give every agent who knows weather lore a forecast of the coming ticks,
+/// as accurate as their forecasting skill allows.
+fn forecast_weather(
+    state: &SimulationState,
+    perceptions: &mut BTreeMap<AgentId, Perception>,
     season: Season,
-    weather: Weather,
-    ticks_until_season_change: u64,
-) -> PerceptionContext {
-    let location_state = state.world_map.get_location(location_id);
+) {
+    let tick = state.clock.tick();
+    let season_at = |at: u64| state.clock.season_at(at).unwrap_or(season);
+    for (agent_id, perception) in perceptions.iter_mut() {
+        let Some(agent_state) = state.agent_states.get(agent_id) else {
+            continue;
+        };
+        if !agent_state.knowledge.contains(environment::FORECAST_KNOWLEDGE) {
+            continue;
+        }
+        let skill_level = agent_state.skills.get("forecasting").copied().unwrap_or(0);
+        perception.forecast = state.weather_system.forecast(
+            tick,
+            environment::FORECAST_TICKS,
+            environment::forecast_accuracy_pct(skill_level),
+            season_at,
+        );
+    }
+}
 
-    let (location_name, location_description, location_resources) =
-        location_state.map_or_else(
-            || {
-                (
-                    String::from("Unknown"),
-                    String::from("An unknown place."),
-                    BTreeMap::new(),
-                )
-            },
-            |loc| {
-                (
-                    loc.location.name.clone(),
-                    loc.location.description.clone(),
-                    loc.available_resources(),
-                )
-            },
-        );
-
-    // Build agent names and sexes maps for agents at this location
-    let mut agent_names = BTreeMap::new();
-    let mut agent_sexes = BTreeMap::new();
-    if let Some(loc) = location_state {
-        for &occupant in &loc.occupants {
-            if let Some(name) = state.agent_names.get(&occupant) {
-                agent_names.insert(occupant, name.clone());
-            }
-            if let Some(agent) = state.agents.get(&occupant) {
-                agent_sexes.insert(occupant, agent.sex);
-            }
-        }
-    }
-
-    // Build known routes from this location
-    let known_routes: Vec<emergence_types::KnownRoute> = state
+/// Build the routes seen from `location_id`, each costed by the quickest
+/// way to its destination in `weather`.
+fn build_known_routes(
+    state: &SimulationState,
+    route_cache: &mut RouteCache,
+    location_id: LocationId,
+    weather: Weather,
+) -> Vec<emergence_types::KnownRoute> {
+    state
         .world_map
         .neighbors(location_id)
         .iter()
@@ -867,7 +1593,12 @@ fn build_location_context(
             let dest_loc = state.world_map.get_location(*dest_id)?;
             let routes = state.world_map.routes_between(location_id, *dest_id);
             let first_route = routes.first()?;
-            let cost_str = format!("{} ticks", first_route.cost_ticks);
+            let cost_str = route_cache
+                .path(&state.world_map, location_id, *dest_id, weather)
+                .map_or_else(
+                    || String::from("impassable"),
+                    |path| format!("{} ticks", path.ticks),
+                );
             let path_str = format!("{:?}", first_route.path_type);
             let resources_hint = dest_loc
                 .location
@@ -885,7 +1616,52 @@ fn build_location_context(
                 resources_hint,
             })
         })
-        .collect();
+        .collect()
+}
+
+/// Build a `PerceptionContext` for a specific location.
+fn build_location_context(
+    state: &SimulationState,
+    location_id: LocationId,
+    tick: u64,
+    time_of_day: emergence_types::TimeOfDay,
+    season: Season,
+    weather: Weather,
+    ticks_until_season_change: u64,
+) -> PerceptionContext {
+    let location_state = state.world_map.get_location(location_id);
+
+    let (location_name, location_description, location_resources) =
+        location_state.map_or_else(
+            || {
+                (
+                    String::from("Unknown"),
+                    String::from("An unknown place."),
+                    BTreeMap::new(),
+                )
+            },
+            |loc| {
+                (
+                    loc.location.name.clone(),
+                    loc.location.description.clone(),
+                    loc.available_resources(),
+                )
+            },
+        );
+
+    // Build agent names and sexes maps for agents at this location
+    let mut agent_names = BTreeMap::new();
+    let mut agent_sexes = BTreeMap::new();
+    if let Some(loc) = location_state {
+        for &occupant in &loc.occupants {
+            if let Some(name) = state.agent_names.get(&occupant) {
+                agent_names.insert(occupant, name.clone());
+            }
+            if let Some(agent) = state.agents.get(&occupant) {
+                agent_sexes.insert(occupant, agent.sex);
+            }
+        }
+    }
 
     PerceptionContext {
         tick,
@@ -897,11 +1673,13 @@ fn build_location_context(
         location_resources,
         structures_here: Vec::new(),
         messages_here: Vec::new(),
-        known_routes,
+        known_routes: Vec::new(),
         agent_names,
         agent_sexes,
         ticks_until_season_change,
         message_expiry_ticks: perception::DEFAULT_MESSAGE_EXPIRY_TICKS,
+        overcrowded: location_state.is_some_and(emergence_world::crowding::is_overcrowded),
+        pollution: location_state.map_or(0, |l| l.pollution),
     }
 }
 
@@ -912,6 +1690,7 @@ fn build_location_context(
 #[allow(clippy::too_many_lines)]
 fn phase_resolution(
     state: &mut SimulationState,
+    scratch: &mut TickScratch,
     decisions: &BTreeMap<AgentId, ActionRequest>,
     weather: Weather,
 ) -> BTreeMap<AgentId, ActionResult> {
@@ -919,14 +1698,19 @@ fn phase_resolution(
     let mut results = BTreeMap::new();
 
     // Categorize actions for conflict resolution
-    let categorized = categorize_and_validate(state, decisions, weather, tick, &mut results);
+    let categorized =
+        categorize_and_validate(state, scratch, decisions, weather, tick, &mut results);
 
     // Resolve gather conflicts and execute
-    resolve_and_execute_gathers(state, &categorized.gather_claims, tick, &mut results);
+    resolve_and_execute_gathers(state, &categorized, tick, &mut results);
 
     // Execute non-gather actions sequentially
     execute_non_gather_actions(state, &categorized.non_gather, weather, tick, &mut results);
 
+    for claims in categorized.gather_claims.into_values() {
+        scratch.claim_lists.give(claims);
+    }
+
     results
 }
 
@@ -936,31 +1720,28 @@ fn phase_resolution(
 #[allow(clippy::too_many_lines)]
 fn categorize_and_validate(
     state: &SimulationState,
+    scratch: &mut TickScratch,
     decisions: &BTreeMap<AgentId, ActionRequest>,
     weather: Weather,
     tick: u64,
     results: &mut BTreeMap<AgentId, ActionResult>,
 ) -> CategorizedActions {
-    let mut gather_claims: BTreeMap<(LocationId, Resource), Vec<(AgentId, GatherClaim)>> =
+    let mut gather_claims: BTreeMap<(DenseId<LocationId>, Resource), Vec<GatherClaim>> =
         BTreeMap::new();
     let mut non_gather_actions: Vec<(AgentId, ActionRequest)> = Vec::new();
 
-    // Pre-build a set of alive agents for O(1) membership checks.
-    let alive_set: std::collections::BTreeSet<AgentId> =
-        state.alive_agents.iter().copied().collect();
+    // Pre-sort the alive agents for binary-search membership checks.
+    scratch.sort_alive(&state.alive_agents);
 
-    // Pre-cache location data to avoid repeated lookups per-agent.
-    let mut location_cache: BTreeMap<
-        LocationId,
-        (
-            BTreeMap<Resource, emergence_types::ResourceNode>,
-            Vec<AgentId>,
-        ),
-    > = BTreeMap::new();
+    // One validation context per occupied location, indexed by dense
+    // location ID. The location fields are filled once; the agent fields
+    // are overwritten for each acting agent.
+    let mut locations: Interner<LocationId> = Interner::new();
+    let mut location_contexts: DenseMap<LocationId, ValidationContext> = DenseMap::new();
     let travel_blocked = weather == Weather::Storm;
 
     for (&agent_id, request) in decisions {
-        if !alive_set.contains(&agent_id) {
+        if !scratch.is_alive(&agent_id) {
             continue;
         }
 
@@ -971,20 +1752,42 @@ fn categorize_and_validate(
         let location_id = agent_state.location_id;
         let is_traveling = agent_state.destination_id.is_some();
 
-        let (location_resources, agents_at_location) =
-            location_cache.entry(location_id).or_insert_with(|| {
-                let resources = state
-                    .world_map
-                    .get_location(location_id)
-                    .map(|loc| loc.resources().clone())
-                    .unwrap_or_default();
-                let agents: Vec<AgentId> = state
-                    .world_map
-                    .get_location(location_id)
-                    .map(|loc| loc.occupants.iter().copied().collect())
-                    .unwrap_or_default();
-                (resources, agents)
+        let Some(location) = locations.intern(location_id) else {
+            continue;
+        };
+        if location_contexts.get(location).is_none() {
+            let loc = state.world_map.get_location(location_id);
+            location_contexts.insert(location, ValidationContext {
+                agent_id,
+                agent_location: location_id,
+                is_traveling: false,
+                location_resources: loc.map(|l| l.resources().clone()).unwrap_or_default(),
+                agents_at_location: loc
+                    .map(|l| l.occupants.iter().copied().collect())
+                    .unwrap_or_default(),
+                travel_blocked,
+                agent_knowledge: std::collections::BTreeSet::new(),
+                is_mature: false,
+                structures_at_location: structures_at(&state.structures, location_id),
+                route_to_improve: None,
+                move_route: None,
+                destination_fortifications: Vec::new(),
+                route_to_build: None,
+                agent_groups: Vec::new(),
+                dead_agents: std::collections::BTreeSet::new(), // TODO: populate from agent manager
+                farm_registry: emergence_world::FarmRegistry::new(), // TODO: populate from world state
+                library_knowledge: std::collections::BTreeMap::new(), // TODO: populate from library state
+                game_at_location: state.fauna.game_at(location_id),
+                ore_body: state.mines.get(location_id).copied(),
+                fish_nearby: state.waters.fish_near(&state.world_map, location_id),
+                fire_intensity: state.fires.intensity_at(location_id),
+                location_type: loc.map(|l| l.location.location_type.clone()).unwrap_or_default(),
+                current_tick: tick,
             });
+        }
+        let Some(validation_ctx) = location_contexts.get_mut(location) else {
+            continue;
+        };
 
         // An agent is mature if they have lived at least `maturity_ticks` since birth.
         // Seed agents (born_at_tick = 0) become mature after maturity_ticks elapse.
@@ -992,42 +1795,42 @@ fn categorize_and_validate(
         let maturity_ticks = emergence_agents::default_maturity_ticks();
         let is_mature = emergence_agents::is_mature(agent_state.born_at_tick, tick, maturity_ticks);
 
-        // Look up the route for Move actions (needed for ACL and toll checks).
-        let move_route = if let ActionParameters::Move { destination } = &request.parameters {
-            state.world_map.find_route_from_to(location_id, *destination).cloned()
-        } else {
-            None
-        };
-
-        let validation_ctx = ValidationContext {
-            agent_id,
-            agent_location: location_id,
-            is_traveling,
-            location_resources: location_resources.clone(),
-            agents_at_location: agents_at_location.clone(),
-            travel_blocked,
-            agent_knowledge: agent_state.knowledge.clone(),
-            is_mature,
-            structures_at_location: std::collections::BTreeMap::new(),
-            route_to_improve: None,
-            move_route,
-            agent_groups: Vec::new(), // TODO: populate from social graph when available
-            dead_agents: std::collections::BTreeSet::new(), // TODO: populate from agent manager
-            farm_registry: emergence_world::FarmRegistry::new(), // TODO: populate from world state
-            library_knowledge: std::collections::BTreeMap::new(), // TODO: populate from library state
-            current_tick: tick,
+        // Look up the route and the fortifications at the destination of
+        // Move and Breach actions (needed for ACL, toll, and entry checks).
+        let destination = match &request.parameters {
+            ActionParameters::Move { destination } | ActionParameters::Breach { destination } => {
+                Some(*destination)
+            }
+            _ => None,
         };
+        let move_route = destination
+            .and_then(|d| state.world_map.find_route_from_to(location_id, d).cloned());
+
+        validation_ctx.agent_id = agent_id;
+        validation_ctx.is_traveling = is_traveling;
+        validation_ctx.agent_knowledge.clone_from(&agent_state.knowledge);
+        validation_ctx.is_mature = is_mature;
+        validation_ctx.move_route = move_route;
+        validation_ctx.destination_fortifications = destination
+            .map(|d| fortifications_at(&state.structures, d))
+            .unwrap_or_default();
+        validation_ctx.agent_groups = groups_of(&state.groups, agent_id);
+        validation_ctx.route_to_build = route_to_build(state, agent_id, &request.parameters);
 
         // Freeform actions go through the feasibility evaluator instead
         // of the standard validation pipeline.
         if request.action_type == ActionType::Freeform {
             if let ActionParameters::Freeform(ref freeform) = request.parameters {
-                let feasibility_ctx = build_feasibility_context(
-                    agent_id, agent_state, state,
-                );
-                let eval = feasibility::evaluate_feasibility(
-                    freeform, agent_state, &feasibility_ctx,
-                );
+                let hooked = state
+                    .hooks
+                    .as_ref()
+                    .and_then(|hooks| hooks.resolve_freeform(state, agent_id, freeform));
+                let eval = hooked.unwrap_or_else(|| {
+                    let feasibility_ctx = build_feasibility_context(
+                        agent_id, agent_state, state,
+                    );
+                    feasibility::evaluate_feasibility(freeform, agent_state, &feasibility_ctx)
+                });
                 match eval {
                     FeasibilityResult::Feasible { resolved_action, .. } => {
                         // Replace freeform with the resolved concrete action
@@ -1084,7 +1887,7 @@ fn categorize_and_validate(
             request.action_type,
             &request.parameters,
             agent_state,
-            &validation_ctx,
+            validation_ctx,
         );
 
         if let Err(reason) = validation_result {
@@ -1103,15 +1906,16 @@ fn categorize_and_validate(
                 submitted_at: request.submitted_at,
             };
             gather_claims
-                .entry((location_id, *resource))
-                .or_default()
-                .push((agent_id, claim));
+                .entry((location, *resource))
+                .or_insert_with(|| scratch.claim_lists.take())
+                .push(claim);
         } else {
             non_gather_actions.push((agent_id, request.clone()));
         }
     }
 
     CategorizedActions {
+        locations,
         gather_claims,
         non_gather: non_gather_actions,
     }
@@ -1153,31 +1957,34 @@ fn build_feasibility_context(
 /// Resolve gather conflicts and execute the granted gathers.
 fn resolve_and_execute_gathers(
     state: &mut SimulationState,
-    gather_claims: &BTreeMap<(LocationId, Resource), Vec<(AgentId, GatherClaim)>>,
+    categorized: &CategorizedActions,
     tick: u64,
     results: &mut BTreeMap<AgentId, ActionResult>,
 ) {
-    for ((location_id, resource), claims_group) in gather_claims {
+    for (&(location, resource), claims) in &categorized.gather_claims {
+        let Some(location_id) = categorized.locations.resolve(location) else {
+            continue;
+        };
         let available = state
             .world_map
-            .get_location(*location_id)
-            .and_then(|loc| loc.get_resource(resource).map(|n| n.available))
+            .get_location(location_id)
+            .and_then(|loc| loc.get_resource(&resource).map(|n| n.available))
             .unwrap_or(0);
 
-        let claims: Vec<GatherClaim> = claims_group.iter().map(|(_, c)| c.clone()).collect();
         let outcomes =
-            conflict::resolve_gather_conflict(available, &claims, state.conflict_strategy);
+            conflict::resolve_gather_claims(available, claims, state.conflict_strategy);
 
-        for (agent_id, _claim) in claims_group {
-            match outcomes.get(agent_id) {
-                Some(ClaimOutcome::Granted { quantity }) if *quantity > 0 => {
-                    execute_single_gather(state, *agent_id, *location_id, *resource, tick, results);
+        for (claim, outcome) in claims.iter().zip(outcomes) {
+            let agent_id = claim.agent_id;
+            match outcome {
+                ClaimOutcome::Granted { quantity } if quantity > 0 => {
+                    execute_single_gather(state, agent_id, location_id, resource, tick, results);
                 }
-                Some(ClaimOutcome::Rejected { reason }) => {
-                    results.insert(*agent_id, make_rejection(tick, *agent_id, ActionType::Gather, *reason));
+                ClaimOutcome::Rejected { reason } => {
+                    results.insert(agent_id, make_rejection(tick, agent_id, ActionType::Gather, reason));
                 }
-                _ => {
-                    results.insert(*agent_id, make_rejection(tick, *agent_id, ActionType::Gather, RejectionReason::ConflictLost));
+                ClaimOutcome::Granted { .. } => {
+                    results.insert(agent_id, make_rejection(tick, agent_id, ActionType::Gather, RejectionReason::ConflictLost));
                 }
             }
         }
@@ -1210,10 +2017,12 @@ fn execute_single_gather(
         .cloned()
         .unwrap_or_default();
 
-    let Some(agent_state) = state.agent_states.get_mut(&agent_id) else {
+    let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) else {
         return;
     };
 
+    let agent_state = &mut *agent_state;
+
     let mut exec_ctx = ExecutionContext {
         location_resources: loc_resources,
         is_sheltered: false,
@@ -1224,12 +2033,24 @@ fn execute_single_gather(
         agent_name,
         structures_at_location: std::collections::BTreeMap::new(),
         route_to_improve: None,
+        route_to_build: None,
         move_toll_cost: None,
         dead_agents: std::collections::BTreeSet::new(),
         agent_groups: std::collections::BTreeSet::new(),
         active_rules: std::collections::BTreeMap::new(),
         farm_registry: emergence_world::FarmRegistry::new(),
         library_knowledge: std::collections::BTreeMap::new(),
+        game_at_location: 0,
+        hidden_resources: Vec::new(),
+        prospect_roll: 0,
+        ore_body: None,
+        collapse_roll: 0,
+        fish_nearby: 0,
+        fire_intensity: 0,
+        fishing_yield_pct: 100,
+        farm_yield_pct: 100,
+        water_nearby: false,
+        irrigated_yield_pct: emergence_world::DEFAULT_IRRIGATION_YIELD_PCT,
     };
 
     match handlers::execute_gather(agent_state, resource, &vitals_config, &mut exec_ctx) {
@@ -1265,11 +2086,221 @@ fn execute_single_gather(
     }
 }
 
+/// Build the execution context for `agent_id`'s non-gather `request` from
+/// the state as it stands before any action this tick executes.
+///
+/// Returns the agent's location with the context, or `None` if the agent
+/// has no state.
+fn non_gather_context(
+    state: &SimulationState,
+    agent_id: AgentId,
+    request: &ActionRequest,
+    weather: Weather,
+    tick: u64,
+) -> Option<(LocationId, ExecutionContext)> {
+    let agent_state = state.agent_states.get(&agent_id)?;
+    let location_id = agent_state.location_id;
+    let loc_resources = state
+        .world_map
+        .get_location(location_id)
+        .map(emergence_world::LocationState::available_resources)
+        .unwrap_or_default();
+    let exec_ctx = ExecutionContext {
+        location_resources: loc_resources,
+        is_sheltered: false,
+        shelter_bonus_pct: 100,
+        travel_cost: compute_travel_cost_from_map(
+            &state.world_map, &state.trade_network, location_id, &request.parameters, weather,
+        ),
+        move_destination: extract_move_destination(&request.parameters),
+        current_tick: tick,
+        agent_name: state.agent_names.get(&agent_id).cloned().unwrap_or_default(),
+        structures_at_location: std::collections::BTreeMap::new(),
+        route_to_improve: None,
+        route_to_build: route_to_build(state, agent_id, &request.parameters),
+        move_toll_cost: extract_move_toll_cost(
+            &state.world_map, location_id, &request.parameters,
+        ),
+        dead_agents: std::collections::BTreeSet::new(),
+        agent_groups: std::collections::BTreeSet::new(),
+        active_rules: std::collections::BTreeMap::new(),
+        farm_registry: emergence_world::FarmRegistry::new(),
+        library_knowledge: std::collections::BTreeMap::new(),
+        game_at_location: 0,
+        hidden_resources: Vec::new(),
+        prospect_roll: emergence_world::prospecting::roll(agent_id, tick),
+        ore_body: None,
+        collapse_roll: emergence_world::mining::collapse_roll(agent_id, tick),
+        fish_nearby: 0,
+        fire_intensity: 0,
+        fishing_yield_pct: emergence_world::waters::yield_pct(weather),
+        farm_yield_pct: state
+            .world_map
+            .get_location(location_id)
+            .map_or(100, |l| emergence_world::pollution::farm_yield_pct(l.pollution)),
+        water_nearby: state.waters.waters_farms_at(&state.world_map, location_id),
+        irrigated_yield_pct: emergence_world::irrigation_yield_pct(
+            true,
+            weather,
+            state.waters.irrigation_yield_pct(),
+        ),
+    };
+    Some((location_id, exec_ctx))
+}
+
+/// Pass on the map of `agent_id` if its action taught, wrote, or read the
+/// [`known_map::MAP_CONCEPT`], or draw it onto the agent's maps if it
+/// charted.
+fn record_map_lessons(
+    known_maps: &mut KnownMapRegistry,
+    agent_id: AgentId,
+    request: &ActionRequest,
+    hr: &handlers::HandlerResult,
+) {
+    if let ActionParameters::Teach { target_agent, knowledge } = &request.parameters
+        && knowledge == known_map::MAP_CONCEPT
+    {
+        known_maps.teach(agent_id, *target_agent);
+    }
+    if let Some((library, concept)) = &hr.library_write
+        && concept == known_map::MAP_CONCEPT
+    {
+        known_maps.write(agent_id, *library);
+    }
+    if let Some((library, concept)) = &hr.library_read
+        && concept == known_map::MAP_CONCEPT
+    {
+        known_maps.read(agent_id, *library);
+    }
+    if hr.map_charted {
+        known_maps.chart(agent_id);
+    }
+}
+
+/// Count the trip `agent_state` has set out on from `from` against its
+/// route, and roll for a mishap on the way (see
+/// [`emergence_world::trade_network`]).
+fn set_out(
+    network: &mut TradeNetwork,
+    world_map: &WorldMap,
+    agent_state: &mut AgentState,
+    from: LocationId,
+    weather: Weather,
+    tick: u64,
+) {
+    let Some(destination) = agent_state.destination_id else {
+        return;
+    };
+    let Some(route_id) = world_map.routes_between(from, destination).first().map(|r| r.id) else {
+        return;
+    };
+    network.record_trip(route_id, tick);
+    let roll = trade_network::mishap_roll(agent_state.agent_id, tick);
+    if network.is_mishap(route_id, weather, roll) {
+        agent_state.health = agent_state.health.saturating_sub(trade_network::MISHAP_DAMAGE);
+        info!(tick, agent_id = ?agent_state.agent_id, %route_id, "Traveler met with a mishap");
+    }
+}
+
+/// Return what building the route of a `BuildRoute` action by `agent_id`
+/// takes, or `None` for other actions, destinations the agent does not
+/// know, and places a route cannot be built to.
+fn route_to_build(
+    state: &SimulationState,
+    agent_id: AgentId,
+    params: &ActionParameters,
+) -> Option<RoutePlan> {
+    let ActionParameters::BuildRoute { destination } = params else {
+        return None;
+    };
+    let from = state.agent_states.get(&agent_id)?.location_id;
+    if !state.known_maps.knows_location(&state.world_map, agent_id, *destination) {
+        return None;
+    }
+    state.world_map.route_plan(from, *destination)
+}
+
+/// Put `agent_id`'s tick of work into the route from `from` to
+/// `destination`. The builder learns the route once it is finished.
+fn build_route(
+    world_map: &mut emergence_world::WorldMap,
+    known_maps: &mut KnownMapRegistry,
+    agent_id: AgentId,
+    from: LocationId,
+    destination: LocationId,
+    tick: u64,
+) {
+    match world_map.work_on_route(from, destination, agent_id, tick) {
+        Ok(Some(route_id)) => {
+            known_maps.learn_route(agent_id, route_id);
+            info!(tick, ?agent_id, %route_id, "Route built");
+        }
+        Ok(None) => {}
+        Err(err) => warn!(tick, ?agent_id, %err, "Route could not be built"),
+    }
+}
+
+/// Found the settlement named `name` beside `origin` for `agent_id`.
+///
+/// Everyone at the origin learns the trail to it. A name already on the
+/// map is not founded twice, so founders acting together in one tick build
+/// one settlement between them.
+fn found_settlement(
+    world_map: &mut emergence_world::WorldMap,
+    known_maps: &mut KnownMapRegistry,
+    agent_id: AgentId,
+    origin: LocationId,
+    name: &str,
+    tick: u64,
+) {
+    if world_map.locations().any(|(_, l)| l.location.name == name) {
+        return;
+    }
+    match emergence_world::settlement::found_settlement(world_map, origin, name, agent_id, tick) {
+        Ok((settlement, route)) => {
+            let founders = world_map.get_location(origin).map(|l| l.occupants.clone());
+            for founder in founders.unwrap_or_default() {
+                known_maps.learn_route(founder, route);
+            }
+            info!(tick, ?agent_id, %settlement, name, "Settlement founded");
+        }
+        Err(err) => warn!(tick, ?agent_id, %err, "Settlement could not be founded"),
+    }
+}
+
+/// Read the herd, fish, ore body, fire, and hidden deposits at
+/// `location_id` into `exec_ctx` just before acting, so earlier hunters,
+/// fishers, miners, firefighters, and prospectors this tick have already
+/// had their effect.
+///
+/// Returns the fishing spot nearest the location, if there is one.
+fn refresh_before_acting(
+    state: &SimulationState,
+    location_id: LocationId,
+    exec_ctx: &mut ExecutionContext,
+) -> Option<LocationId> {
+    exec_ctx.game_at_location = state.fauna.game_at(location_id);
+    exec_ctx.ore_body = state.mines.get(location_id).copied();
+    exec_ctx.fire_intensity = state.fires.intensity_at(location_id);
+    let fishing_spot = state.waters.nearest_spot(&state.world_map, location_id);
+    exec_ctx.fish_nearby = fishing_spot
+        .and_then(|spot| state.waters.spot(spot))
+        .map_or(0, |spot| spot.fish);
+    exec_ctx.hidden_resources = state
+        .world_map
+        .get_location(location_id)
+        .map(|l| l.hidden_resources.keys().copied().collect())
+        .unwrap_or_default();
+    fishing_spot
+}
+
 /// Execute non-gather actions sequentially.
 ///
 /// To satisfy the borrow checker, we pre-compute all immutable reads from
-/// `state` (location resources, travel cost, vitals config clone) before
-/// taking the mutable borrow on the agent state.
+/// `state` (location resources, travel cost, vitals config clone) into each
+/// action's execution context before taking the mutable borrow on the
+/// agent state.
+#[allow(clippy::too_many_lines)]
 fn execute_non_gather_actions(
     state: &mut SimulationState,
     non_gather_actions: &[(AgentId, ActionRequest)],
@@ -1281,52 +2312,28 @@ fn execute_non_gather_actions(
     let precomputed: Vec<_> = non_gather_actions
         .iter()
         .filter_map(|(agent_id, request)| {
-            let agent_state = state.agent_states.get(agent_id)?;
-            let location_id = agent_state.location_id;
-            let loc_resources = state
-                .world_map
-                .get_location(location_id)
-                .map(emergence_world::LocationState::available_resources)
-                .unwrap_or_default();
-            let travel_cost =
-                compute_travel_cost_from_map(&state.world_map, location_id, &request.parameters, weather);
-            let move_destination = extract_move_destination(&request.parameters);
-            let move_toll_cost =
-                extract_move_toll_cost(&state.world_map, location_id, &request.parameters);
-            let agent_name = state
-                .agent_names
-                .get(agent_id)
-                .cloned()
-                .unwrap_or_default();
-            Some((*agent_id, request.clone(), location_id, loc_resources, travel_cost, move_destination, move_toll_cost, agent_name))
+            let (location_id, exec_ctx) =
+                non_gather_context(state, *agent_id, request, weather, tick)?;
+            Some((*agent_id, request, location_id, exec_ctx))
         })
         .collect();
 
     // Clone vitals config once to avoid borrowing state during mutable agent access.
     let vitals_config = state.vitals_config.clone();
 
-    for (agent_id, request, location_id, loc_resources, travel_cost, move_destination, move_toll_cost, agent_name) in &precomputed {
-        let Some(agent_state) = state.agent_states.get_mut(agent_id) else {
+    for (agent_id, request, location_id, mut exec_ctx) in precomputed {
+        let fishing_spot = refresh_before_acting(state, location_id, &mut exec_ctx);
+        let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) else {
             continue;
         };
+        let agent_state = &mut *agent_state;
 
-        let mut exec_ctx = ExecutionContext {
-            location_resources: loc_resources.clone(),
-            is_sheltered: false,
-            shelter_bonus_pct: 100,
-            travel_cost: *travel_cost,
-            move_destination: *move_destination,
-            current_tick: tick,
-            agent_name: agent_name.clone(),
-            structures_at_location: std::collections::BTreeMap::new(),
-            route_to_improve: None,
-            move_toll_cost: move_toll_cost.clone(),
-            dead_agents: std::collections::BTreeSet::new(),
-            agent_groups: std::collections::BTreeSet::new(),
-            active_rules: std::collections::BTreeMap::new(),
-            farm_registry: emergence_world::FarmRegistry::new(),
-            library_knowledge: std::collections::BTreeMap::new(),
-        };
+        if let Some(terms) = OrderTerms::from_parameters(&request.parameters) {
+            let book = (&mut state.orders, &mut state.ledger);
+            let result = post_market_order(book, agent_state, &terms, request, tick);
+            results.insert(agent_id, result);
+            continue;
+        }
 
         match handlers::execute_action(
             request.action_type,
@@ -1337,15 +2344,67 @@ fn execute_non_gather_actions(
         ) {
             Ok(hr) => {
                 for (res, qty) in &hr.location_resource_deltas {
-                    if let Some(loc) = state.world_map.get_location_mut(*location_id) {
+                    if let Some(loc) = state.world_map.get_location_mut(location_id) {
                         let _ = loc.harvest_resource(*res, *qty);
                     }
                 }
+                if let Some(animals) = hr.animals_hunted {
+                    state.fauna.hunt(location_id, animals);
+                }
+                if let (Some(fish), Some(spot)) = (hr.fish_caught, fishing_spot) {
+                    state.waters.fish(spot, fish);
+                }
+                state.fires.suppress(location_id, hr.fire_suppressed.unwrap_or(0));
+                if let Some(work) = hr.mine_work {
+                    state.mines.record(location_id, work);
+                    if work == emergence_world::MineWork::CavedIn {
+                        info!(tick, ?agent_id, %location_id, "Mine shaft caved in");
+                    }
+                }
+                record_map_lessons(&mut state.known_maps, agent_id, request, &hr);
+                if hr.began_travel {
+                    let (network, map) = (&mut state.trade_network, &state.world_map);
+                    set_out(network, map, agent_state, location_id, weather, tick);
+                }
+                if let Some(destination) = hr.route_work {
+                    let (map, known) = (&mut state.world_map, &mut state.known_maps);
+                    build_route(map, known, agent_id, location_id, destination, tick);
+                }
+                if let Some(name) = &hr.settlement_founded {
+                    let (map, known) = (&mut state.world_map, &mut state.known_maps);
+                    found_settlement(map, known, agent_id, location_id, name, tick);
+                }
+                if let Some(breach) = hr.breach {
+                    let (structures, groups) = (&mut state.structures, &state.groups);
+                    let diplomacy = &mut state.diplomacy;
+                    breach_fortification(structures, groups, diplomacy, agent_id, breach, tick);
+                }
+                let pollution = emergence_world::pollution::action_pollution(request.action_type);
+                if pollution > 0
+                    && let Some(loc) = state.world_map.get_location_mut(location_id)
+                {
+                    emergence_world::pollution::pollute(loc, pollution);
+                }
+                if let Some(resource) = hr.resource_discovered
+                    && let Some(loc) = state.world_map.get_location_mut(location_id)
+                {
+                    let _ = loc.reveal_resource(resource);
+                    info!(tick, ?agent_id, ?resource, "Hidden deposit discovered");
+                    state.mines.survey(&state.world_map, location_id);
+                }
+                if let Some(structure) = hr.structure_built {
+                    let crossing =
+                        state.world_map.build_crossing(location_id, structure.structure_type);
+                    if let Some(route_id) = crossing {
+                        info!(tick, ?agent_id, %route_id, "Route flattened by a crossing");
+                    }
+                    state.structures.insert(structure.id, structure);
+                }
                 results.insert(
-                    *agent_id,
+                    agent_id,
                     ActionResult {
                         tick,
-                        agent_id: *agent_id,
+                        agent_id,
                         action_type: request.action_type,
                         success: true,
                         outcome: Some(hr.outcome),
@@ -1356,7 +2415,7 @@ fn execute_non_gather_actions(
             }
             Err(err) => {
                 warn!(tick, ?agent_id, %err, "Action execution failed");
-                results.insert(*agent_id, make_rejection(tick, *agent_id, request.action_type, RejectionReason::InvalidAction));
+                results.insert(agent_id, make_rejection(tick, agent_id, request.action_type, RejectionReason::InvalidAction));
             }
         }
     }
@@ -1365,9 +2424,11 @@ fn execute_non_gather_actions(
 /// Compute the travel cost for a move action, or `None` for non-move actions.
 ///
 /// Takes `&WorldMap` directly to avoid borrow-checker conflicts when
-/// `SimulationState` is partially borrowed.
+/// `SimulationState` is partially borrowed. Trade corridors in `network`
+/// ease the weather's delay.
 fn compute_travel_cost_from_map(
     world_map: &emergence_world::WorldMap,
+    network: &TradeNetwork,
     from: LocationId,
     params: &ActionParameters,
     weather: Weather,
@@ -1375,7 +2436,8 @@ fn compute_travel_cost_from_map(
     if let ActionParameters::Move { destination } = params {
         let routes = world_map.routes_between(from, *destination);
         routes.first().and_then(|r| {
-            emergence_world::route::effective_travel_cost(r, weather)
+            network
+                .travel_cost(world_map, r, from, *destination, weather)
                 .ok()
                 .flatten()
         })
@@ -1434,6 +2496,109 @@ fn make_rejection(
     }
 }
 
+/// Maximum number of memory entries per agent.
+const MAX_MEMORY: usize = 50;
+
+/// Drop an agent's oldest memories beyond [`MAX_MEMORY`].
+fn cap_memory(agent_state: &mut AgentState) {
+    if agent_state.memory.len() > MAX_MEMORY {
+        let drain_count = agent_state.memory.len().saturating_sub(MAX_MEMORY);
+        agent_state.memory.drain(..drain_count);
+    }
+}
+
+/// Build the events this tick publishes to its subscribers.
+///
+/// Every successful `Communicate` or `Broadcast` becomes a `MessageSent`
+/// event whose details are the posted [`Message`].
+fn tick_events(
+    state: &SimulationState,
+    decisions: &BTreeMap<AgentId, ActionRequest>,
+    action_results: &BTreeMap<AgentId, ActionResult>,
+    wake: &WakeResult,
+    tick: u64,
+) -> Vec<Event> {
+    let world_context = world_context(state, wake, tick);
+
+    action_results
+        .iter()
+        .filter(|(_, result)| result.success)
+        .filter_map(|(agent_id, _)| {
+            let (recipient_id, content) = match &decisions.get(agent_id)?.parameters {
+                ActionParameters::Communicate {
+                    target_agent,
+                    message,
+                } => (Some(*target_agent), message),
+                ActionParameters::Broadcast { message } => (None, message),
+                _ => return None,
+            };
+            let location_id = state.agent_states.location(agent_id)?;
+            let message = Message {
+                sender_id: *agent_id,
+                sender_name: state.agent_names.get(agent_id).cloned().unwrap_or_default(),
+                recipient_id,
+                content: content.chars().take(handlers::MAX_MESSAGE_LENGTH).collect(),
+                tick,
+                is_broadcast: recipient_id.is_none(),
+                location_id,
+            };
+            Some(Event {
+                id: EventId::new(),
+                tick,
+                event_type: EventType::MessageSent,
+                agent_id: Some(*agent_id),
+                location_id: Some(location_id),
+                details: serde_json::to_value(&message).ok()?,
+                agent_state_snapshot: None,
+                world_context: world_context.clone(),
+                created_at: Utc::now(),
+                caused_by: None,
+                correlation_id: None,
+            })
+        })
+        .collect()
+}
+
+/// A `TradeCompleted` event for each of this tick's market fills, at the
+/// market's location.
+fn market_fill_events(
+    state: &SimulationState,
+    fills: &[OrderFill],
+    wake: &WakeResult,
+    tick: u64,
+) -> Vec<Event> {
+    let world_context = world_context(state, wake, tick);
+    fills
+        .iter()
+        .filter_map(|fill| {
+            Some(Event {
+                id: EventId::new(),
+                tick,
+                event_type: EventType::TradeCompleted,
+                agent_id: Some(fill.completed.agent_a),
+                location_id: state.structures.get(&fill.market_id).map(|m| m.location_id),
+                details: serde_json::to_value(&fill.completed).ok()?,
+                agent_state_snapshot: None,
+                world_context: world_context.clone(),
+                created_at: Utc::now(),
+                caused_by: None,
+                correlation_id: None,
+            })
+        })
+        .collect()
+}
+
+/// The world as events published at the end of this tick see it.
+fn world_context(state: &SimulationState, wake: &WakeResult, tick: u64) -> WorldContext {
+    WorldContext {
+        tick,
+        era: state.clock.era(),
+        season: wake.season,
+        weather: wake.weather,
+        population: u32::try_from(state.alive_agents.len()).unwrap_or(u32::MAX),
+    }
+}
+
 /// Phase 6: Reflection.
 ///
 /// After all actions are resolved:
@@ -1448,13 +2613,11 @@ fn phase_reflection(
     use emergence_types::MemoryEntry;
     use rust_decimal::Decimal;
 
-    /// Maximum number of memory entries per agent.
-    const MAX_MEMORY: usize = 50;
-
     for (&agent_id, result) in results {
-        let Some(agent_state) = state.agent_states.get_mut(&agent_id) else {
+        let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) else {
             continue;
         };
+        let agent_state = &mut *agent_state;
 
         // --- Memory creation ---
         let summary = if result.success {
@@ -1480,11 +2643,7 @@ fn phase_reflection(
         let memory = MemoryEntry::action(tick, summary, vec![agent_id.into_inner()], weight);
         agent_state.memory.push(memory);
 
-        // Cap memory at MAX_MEMORY entries (oldest first)
-        if agent_state.memory.len() > MAX_MEMORY {
-            let drain_count = agent_state.memory.len().saturating_sub(MAX_MEMORY);
-            agent_state.memory.drain(..drain_count);
-        }
+        cap_memory(agent_state);
 
         // --- Goal writeback ---
         if let Some(request) = decisions.get(&agent_id) {
@@ -1501,6 +2660,46 @@ fn phase_reflection(
     }
 }
 
+/// Phase 6 (continued): apply reflection updates.
+///
+/// Replaces each living agent's goals with its revised list (an empty list
+/// keeps the current goals) and stores the memory summary as a
+/// reflection memory. Updates for dead or unknown agents are dropped.
+fn apply_reflections(state: &mut SimulationState, updates: &[ReflectionUpdate], tick: u64) {
+    use emergence_types::MemoryEntry;
+    use rust_decimal::Decimal;
+
+    for update in updates {
+        if !state.alive_agents.contains(&update.agent_id) {
+            continue;
+        }
+        let Some(mut agent_state) = state.agent_states.get_mut(&update.agent_id) else {
+            continue;
+        };
+        let agent_state = &mut *agent_state;
+
+        if !update.goals.is_empty() {
+            agent_state.goals.clone_from(&update.goals);
+        }
+        if let Some(summary) = update.memory_summary.as_ref().filter(|s| !s.is_empty()) {
+            agent_state.memory.push(MemoryEntry::reflection(
+                tick,
+                summary.clone(),
+                vec![update.agent_id.into_inner()],
+                Decimal::new(6, 1),
+            ));
+            cap_memory(agent_state);
+        }
+        debug!(
+            tick,
+            agent_id = %update.agent_id,
+            reflected_at = update.tick,
+            goals = ?agent_state.goals,
+            "Applied reflection update"
+        );
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -1587,6 +2786,7 @@ mod tests {
             location_type: String::from("natural"),
             description: format!("Test location: {name}"),
             capacity: 20,
+            elevation: 0,
             base_resources: resources,
             discovered_by: BTreeSet::new(),
             created_at: Utc::now(),
@@ -1632,8 +2832,8 @@ mod tests {
         let mut agent_names = BTreeMap::new();
         agent_names.insert(agent_id, String::from("Alpha"));
 
-        let mut agent_states = BTreeMap::new();
-        agent_states.insert(agent_id, agent_state);
+        let mut agent_states = AgentStore::new();
+        agent_states.insert(agent_state);
 
         let mut agents = BTreeMap::new();
         agents.insert(agent_id, Agent {
@@ -1672,9 +2872,58 @@ mod tests {
             injected_events: Vec::new(),
             active_plagues: Vec::new(),
             active_resource_booms: Vec::new(),
+            disasters: emergence_world::DisasterSystem::default(),
+            fires: emergence_world::FireSystem::default(),
+            fauna: emergence_world::FaunaRegistry::default(),
+            waters: emergence_world::WaterRegistry::default(),
+            mines: emergence_world::MineRegistry::default(),
+            known_maps: emergence_world::KnownMapRegistry::default(),
+            trade_network: emergence_world::TradeNetwork::default(),
+            structures: BTreeMap::new(),
+            family: FamilyTracker::new(),
+            ledger: Ledger::new(),
+            orders: OrderBook::new(),
+            groups: BTreeMap::new(),
+            diplomacy: DiplomacyState::new(),
+            hooks: None,
+            scratch: TickScratch::new(),
+            subscribers: EventSubscribers::new(),
         }
     }
 
+    /// Add a standing structure of `structure_type` owned by `owner` at
+    /// `location_id`, returning its ID.
+    fn add_structure(
+        state: &mut SimulationState,
+        structure_type: StructureType,
+        location_id: LocationId,
+        owner: AgentId,
+    ) -> StructureId {
+        let bp = emergence_world::structure::blueprint(structure_type);
+        let structure = Structure {
+            id: StructureId::new(),
+            structure_type,
+            subtype: None,
+            location_id,
+            builder: owner,
+            owner: Some(owner),
+            built_at_tick: 0,
+            destroyed_at_tick: None,
+            materials_used: bp.material_costs,
+            durability: bp.max_durability,
+            max_durability: bp.max_durability,
+            decay_per_tick: bp.decay_per_tick,
+            capacity: bp.capacity,
+            occupants: BTreeSet::new(),
+            access_list: None,
+            properties: bp.properties,
+            inventory: BTreeMap::new(),
+        };
+        let id = structure.id;
+        state.structures.insert(id, structure);
+        id
+    }
+
     #[test]
     fn tick_advances_clock() {
         let mut state = make_simulation_state();
@@ -1686,6 +2935,20 @@ mod tests {
         assert_eq!(summary.tick, 1);
     }
 
+    #[test]
+    fn tick_keeps_scratch_buffers() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+
+        let _ = run_tick(&mut state, &mut decisions).unwrap();
+        assert!(state.scratch.wake_order.capacity() >= state.alive_agents.len());
+        assert_eq!(state.scratch.agent_lists.idle(), 1);
+
+        // The second tick reuses the buffers instead of adding more.
+        let _ = run_tick(&mut state, &mut decisions).unwrap();
+        assert_eq!(state.scratch.agent_lists.idle(), 1);
+    }
+
     #[test]
     fn tick_applies_hunger() {
         let mut state = make_simulation_state();
@@ -1761,6 +3024,714 @@ mod tests {
         assert!(agent_died, "Agent should have died from starvation");
     }
 
+    #[test]
+    fn a_dead_owners_spouse_inherits_their_structure() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+
+        let owner = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&owner).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&owner) {
+            agent_state.health = 1;
+            agent_state.hunger = 100;
+        }
+        let spouse = AgentId::new();
+        state.agent_states.insert(make_agent_state(spouse, location_id));
+        state.agent_names.insert(spouse, String::from("Beta"));
+        state.alive_agents.push(spouse);
+        state.family.register_alive(owner);
+        state.family.register_alive(spouse);
+        state.family.record_marriage(owner, spouse, 0);
+
+        let hut_id = add_structure(&mut state, StructureType::BasicHut, location_id, owner);
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        let death = summary.deaths.first().unwrap();
+        assert_eq!(death.agent_id, owner);
+        assert_eq!(death.heir, Some(spouse));
+        assert_eq!(death.inherited_structures, vec![hut_id]);
+        assert_eq!(state.structures.get(&hut_id).unwrap().owner, Some(spouse));
+
+        let inherited = summary.inheritances.first().unwrap();
+        assert_eq!(inherited.structure_id, hut_id);
+        assert_eq!(inherited.deceased, owner);
+        assert_eq!(inherited.heir, spouse);
+    }
+
+    #[test]
+    fn plague_does_not_kill_the_already_dead() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.health = 1;
+            agent_state.hunger = 100;
+        }
+        // Two overlapping plagues at the agent's location.
+        for _ in 0..2 {
+            state.active_plagues.push(ActivePlague {
+                location_id,
+                damage_per_tick: 50,
+                remaining_ticks: 5,
+                can_spread: false,
+            });
+        }
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        assert_eq!(summary.deaths.len(), 1);
+        assert!(state.alive_agents.is_empty());
+    }
+
+    #[test]
+    fn plague_hits_harder_at_crowded_locations() {
+        let health_lost = |crowded: bool| {
+            let mut state = make_simulation_state();
+            let mut decisions = StubDecisionSource::new();
+            let agent_id = *state.alive_agents.first().unwrap();
+            let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+            if crowded {
+                let loc = state.world_map.get_location_mut(location_id).unwrap();
+                loc.location.capacity = 4;
+                while loc.has_capacity() {
+                    loc.add_occupant(AgentId::new()).unwrap();
+                }
+            }
+            state.active_plagues.push(ActivePlague {
+                location_id,
+                damage_per_tick: 20,
+                remaining_ticks: 5,
+                can_spread: false,
+            });
+            let before = state.agent_states.get(&agent_id).unwrap().health;
+            let _ = run_tick(&mut state, &mut decisions).unwrap();
+            before - state.agent_states.get(&agent_id).unwrap().health
+        };
+
+        assert_eq!(health_lost(true), health_lost(false) + 5);
+    }
+
+    #[test]
+    fn injected_earthquake_blocks_routes_until_it_lifts() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+        state.injected_events.push(InjectedEvent {
+            event_type: String::from("earthquake"),
+            target_region: None,
+            severity: Some(String::from("1")),
+            description: None,
+        });
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        assert_eq!(summary.disasters.len(), 1);
+        let quake = summary.disasters.first().unwrap();
+        assert!(quake.injected);
+        assert_eq!(quake.blocked_until_tick, summary.tick + 3);
+        let blocked = quake.routes_blocked.len();
+        assert_eq!(state.disasters.blocked_count(), blocked);
+
+        for _ in 0..3 {
+            let _ = run_tick(&mut state, &mut decisions).unwrap();
+        }
+        assert_eq!(state.disasters.blocked_count(), 0);
+    }
+
+    #[test]
+    fn injected_flood_damages_the_structures_it_strikes() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+        let owner = *state.alive_agents.first().unwrap();
+        let locations = state.world_map.location_ids();
+        for location_id in &locations {
+            add_structure(&mut state, StructureType::BasicHut, *location_id, owner);
+        }
+        state.injected_events.push(InjectedEvent {
+            event_type: String::from("flood"),
+            target_region: None,
+            severity: Some(String::from("2")),
+            description: None,
+        });
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        let flood = summary.disasters.first().unwrap();
+        assert_eq!(flood.structures_damaged.len(), 1);
+        let hut = state.structures.get(flood.structures_damaged.first().unwrap()).unwrap();
+        assert_eq!(hut.location_id, flood.location_id);
+        assert!(hut.durability < hut.max_durability);
+    }
+
+    #[test]
+    fn injected_wildfire_burns_out_without_fuel() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+        state.injected_events.push(InjectedEvent {
+            event_type: String::from("wildfire"),
+            target_region: None,
+            severity: Some(String::from("3")),
+            description: None,
+        });
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        let wildfire = summary.disasters.first().unwrap();
+        assert!(state.fires.intensity_at(wildfire.location_id) > 0);
+        assert!(summary.fires.is_empty());
+
+        for _ in 0..3 {
+            let _ = run_tick(&mut state, &mut decisions).unwrap();
+        }
+        assert_eq!(state.fires.burning_count(), 0);
+    }
+
+    #[test]
+    fn fire_damages_the_wooden_structures_where_it_burns() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+        let owner = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&owner).unwrap().location_id;
+        let hut_id = add_structure(&mut state, StructureType::BasicHut, location_id, owner);
+        let max_durability = state.structures.get(&hut_id).unwrap().max_durability;
+        state.fires.ignite(location_id, 3, 0);
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        let burned = summary.fires.first().unwrap();
+        assert_eq!(burned.structure_id, hut_id);
+        assert!(burned.damage > 0);
+        let hut = state.structures.get(&hut_id).unwrap();
+        assert_eq!(hut.durability, max_durability - burned.damage);
+    }
+
+    #[test]
+    fn herds_breed_then_starve_without_winter_fodder() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let head = |state: &SimulationState| {
+            let agent = state.agent_states.get(&agent_id).unwrap();
+            agent.inventory.get(&Resource::Livestock).copied().unwrap_or(0)
+        };
+        {
+            let mut agent = state.agent_states.get_mut(&agent_id).unwrap();
+            agent.inventory.insert(Resource::Livestock, 2);
+            agent.inventory.insert(Resource::FoodWaste, 1);
+        }
+        assert_eq!(state.clock.tick(), 0);
+
+        tend_herds(&mut state, Season::Spring).unwrap();
+        assert_eq!(head(&state), 3);
+
+        // One head fed, two unfed: one starves
+        tend_herds(&mut state, Season::Winter).unwrap();
+        assert_eq!(head(&state), 2);
+        let agent = state.agent_states.get(&agent_id).unwrap();
+        assert_eq!(agent.inventory.get(&Resource::FoodWaste).copied().unwrap_or(0), 0);
+
+        let recorded: Vec<LedgerEntryType> = state
+            .ledger
+            .entries_for_entity(agent_id.into_inner())
+            .iter()
+            .map(|entry| entry.entry_type)
+            .collect();
+        assert_eq!(recorded, vec![
+            LedgerEntryType::Breeding,
+            LedgerEntryType::Consume,
+            LedgerEntryType::Slaughter,
+        ]);
+        let livestock = state.ledger.balance(agent_id.into_inner(), Resource::Livestock, 0);
+        assert_eq!(livestock, Decimal::ZERO);
+    }
+
+    #[test]
+    fn pastures_let_a_herd_outgrow_the_open_range() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        let full_range = husbandry::OPEN_RANGE_CAPACITY;
+        state
+            .agent_states
+            .get_mut(&agent_id)
+            .unwrap()
+            .inventory
+            .insert(Resource::Livestock, full_range);
+
+        tend_herds(&mut state, Season::Spring).unwrap();
+        assert!(state.ledger.entries_for_tick(0).is_empty());
+
+        add_structure(&mut state, StructureType::Pasture, location_id, agent_id);
+        tend_herds(&mut state, Season::Spring).unwrap();
+        let agent = state.agent_states.get(&agent_id).unwrap();
+        assert_eq!(agent.inventory.get(&Resource::Livestock).copied(), Some(full_range + 1));
+        assert_eq!(state.ledger.entries_for_tick(0).len(), 1);
+    }
+
+    #[test]
+    fn heavy_traffic_makes_a_trade_corridor() {
+        let mut state = make_simulation_state();
+        let mut decisions = StubDecisionSource::new();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let from = state.agent_states.get(&agent_id).unwrap().location_id;
+        let (route_id, destination) =
+            state.world_map.routes().next().map(|(id, r)| (*id, r.to_location)).unwrap();
+        {
+            let mut agent_state = state.agent_states.get_mut(&agent_id).unwrap();
+            agent_state.destination_id = Some(destination);
+            for _ in 0..trade_network::CORRIDOR_TRIPS {
+                let (network, map) = (&mut state.trade_network, &state.world_map);
+                set_out(network, map, &mut agent_state, from, Weather::Clear, 0);
+            }
+            agent_state.destination_id = None;
+        }
+
+        let summary = run_tick(&mut state, &mut decisions).unwrap();
+        let corridor = summary.trade_routes.first().unwrap();
+        assert_eq!(corridor.route_id, route_id);
+        assert_eq!(corridor.trips, trade_network::CORRIDOR_TRIPS);
+        assert!(state.trade_network.is_corridor(route_id));
+
+        // Snow delays travel along the corridor by one tick, not two
+        let params = ActionParameters::Move { destination };
+        let cost = compute_travel_cost_from_map(
+            &state.world_map, &state.trade_network, from, &params, Weather::Snow,
+        );
+        assert_eq!(cost, Some(4));
+    }
+
+    /// A decision source where every agent takes the same action.
+    struct RepeatingSource(ActionType, ActionParameters);
+
+    impl DecisionSource for RepeatingSource {
+        fn collect_decisions(
+            &mut self,
+            tick: u64,
+            perceptions: &BTreeMap<AgentId, Perception>,
+        ) -> Result<BTreeMap<AgentId, ActionRequest>, crate::decision::DecisionError> {
+            Ok(perceptions
+                .keys()
+                .map(|&agent_id| {
+                    let request = ActionRequest {
+                        agent_id,
+                        tick,
+                        action_type: self.0,
+                        parameters: self.1.clone(),
+                        submitted_at: Utc::now(),
+                        goal_updates: Vec::new(),
+                    };
+                    (agent_id, request)
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn market_orders_are_posted_and_filled_across_ticks() {
+        let mut state = make_simulation_state();
+        let seller = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&seller).unwrap().location_id;
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        let market_id = add_structure(&mut state, StructureType::Market, location_id, seller);
+        state.agent_states.get_mut(&seller).unwrap().inventory.insert(Resource::Wood, 4);
+        let sell = ActionParameters::PostOrder {
+            market_id,
+            side: OrderSide::Sell,
+            resource: Resource::Wood,
+            quantity: 4,
+            price_resource: Resource::CurrencyToken,
+            unit_price: 2,
+        };
+        let mut posting = RepeatingSource(ActionType::PostOrder, sell);
+
+        let summary = run_tick(&mut state, &mut posting).unwrap();
+        assert!(summary.action_results.get(&seller).unwrap().success);
+        assert!(summary.market_fills.is_empty());
+        assert_eq!(state.orders.len(), 1);
+        let inventory = &state.agent_states.get(&seller).unwrap().inventory;
+        assert_eq!(inventory.get(&Resource::Wood).copied().unwrap_or(0), 0);
+
+        // A buyer arrives and bids above the asking price.
+        let buyer = AgentId::new();
+        let mut buyer_state = make_agent_state(buyer, location_id);
+        buyer_state.inventory.insert(Resource::CurrencyToken, 12);
+        let terms = OrderTerms {
+            market_id,
+            side: OrderSide::Buy,
+            resource: Resource::Wood,
+            quantity: 4,
+            price_resource: Resource::CurrencyToken,
+            unit_price: 3,
+        };
+        let tick = state.clock.tick();
+        let expiry = market::DEFAULT_ORDER_EXPIRY_TICKS;
+        let (bid, _) =
+            market::post_order(&mut buyer_state, &terms, &mut state.ledger, tick, expiry).unwrap();
+        state.orders.insert(bid);
+        state.agent_states.insert(buyer_state);
+        state.alive_agents.push(buyer);
+        state.agent_names.insert(buyer, String::from("Beta"));
+
+        let summary = run_tick(&mut state, &mut StubDecisionSource::new()).unwrap();
+        assert_eq!(summary.market_fills.len(), 1);
+        assert!(state.orders.is_empty());
+        let bought = &state.agent_states.get(&buyer).unwrap().inventory;
+        assert_eq!(bought.get(&Resource::Wood).copied(), Some(4));
+        let sold = &state.agent_states.get(&seller).unwrap().inventory;
+        assert!(sold.get(&Resource::CurrencyToken).copied().unwrap_or(0) >= 8);
+    }
+
+    #[test]
+    fn overhunting_collapses_the_local_herd() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.knowledge.insert(String::from("animal_tracking"));
+        }
+        // Old enough that the agent is mature.
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        let mut herd = emergence_world::Population::new(60);
+        herd.game = 3;
+        herd.predators = 0;
+        state.fauna.insert(location_id, herd);
+        let mut hunting = RepeatingSource(ActionType::Hunt, ActionParameters::Hunt);
+
+        let summary = run_tick(&mut state, &mut hunting).unwrap();
+        let result = summary.action_results.get(&agent_id).unwrap();
+        assert!(result.success);
+        let inventory = &state.agent_states.get(&agent_id).unwrap().inventory;
+        assert_eq!(inventory.get(&Resource::FoodMeat).copied(), Some(3));
+        assert_eq!(inventory.get(&Resource::Hide).copied(), Some(1));
+
+        let summary = run_tick(&mut state, &mut hunting).unwrap();
+        assert_eq!(state.fauna.collapsed_count(), 1);
+        assert!(summary.world_event_logs.iter().any(|log| log.contains("overhunting")));
+    }
+
+    #[test]
+    fn fishing_draws_down_the_river() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.knowledge.insert(String::from("fishing"));
+        }
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        state.waters.add_body(emergence_world::WaterBody {
+            name: String::from("Test River"),
+            kind: emergence_world::WaterKind::River,
+            course: vec![location_id],
+        });
+        // Fish the spot nearly empty so regrowth cannot refill it.
+        let capacity = emergence_world::waters::DEFAULT_FISH_CAPACITY;
+        state.waters.fish(location_id, capacity.saturating_sub(1));
+        let mut fishing = RepeatingSource(ActionType::Fish, ActionParameters::Fish);
+
+        let summary = run_tick(&mut state, &mut fishing).unwrap();
+        assert!(summary.action_results.get(&agent_id).unwrap().success);
+        let inventory = &state.agent_states.get(&agent_id).unwrap().inventory;
+        let caught = inventory.get(&Resource::FoodFish).copied().unwrap_or(0);
+        assert!(caught > 0);
+        assert!(state.waters.total_fish() < u64::from(capacity));
+    }
+
+    #[test]
+    fn mining_pollutes_the_location() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.knowledge.insert(String::from("mining"));
+            agent_state.inventory.insert(Resource::Tool, 1);
+        }
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        let location = state.world_map.get_location_mut(location_id).unwrap();
+        location.location.base_resources.insert(
+            Resource::Ore,
+            ResourceNode {
+                resource: Resource::Ore,
+                available: 20,
+                regen_per_tick: 0,
+                max_capacity: 20,
+            },
+        );
+        let mut mining = RepeatingSource(ActionType::Mine, ActionParameters::Mine);
+
+        let summary = run_tick(&mut state, &mut mining).unwrap();
+        assert!(summary.action_results.get(&agent_id).unwrap().success);
+        let location = state.world_map.get_location(location_id).unwrap();
+        assert_eq!(location.pollution, emergence_world::pollution::MINE_POLLUTION);
+    }
+
+    #[test]
+    fn building_a_route_joins_two_known_places() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let meadow = state.agent_states.get(&agent_id).unwrap().location_id;
+        let (forest, _) = *state.world_map.neighbors(meadow).first().unwrap();
+        let lake = LocationId::new();
+        state.world_map.add_location(make_location(lake, "Lake")).unwrap();
+        state.world_map.add_route(make_route(forest, lake)).unwrap();
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        state.known_maps.record_position(&state.world_map, agent_id, lake);
+        state.known_maps.record_position(&state.world_map, agent_id, meadow);
+        // Half the six-tick detour through the forest.
+        let cost = emergence_world::route_building::material_cost(3);
+        state.agent_states.get_mut(&agent_id).unwrap().inventory.extend(cost);
+        let params = ActionParameters::BuildRoute { destination: lake };
+        let mut building = RepeatingSource(ActionType::BuildRoute, params);
+
+        for _ in 0..3 {
+            assert!(state.world_map.routes_between(meadow, lake).is_empty());
+            state.agent_states.get_mut(&agent_id).unwrap().energy = 100;
+            let summary = run_tick(&mut state, &mut building).unwrap();
+            assert!(summary.action_results.get(&agent_id).unwrap().success);
+        }
+        let route = *state.world_map.routes_between(meadow, lake).first().unwrap();
+        assert_eq!(route.built_by, Some(agent_id));
+        assert_eq!(route.cost_ticks, 3);
+        assert!(state.known_maps.get(agent_id).unwrap().knows_route(route.id));
+        let inventory = &state.agent_states.get(&agent_id).unwrap().inventory;
+        assert_eq!(inventory.get(&Resource::Wood).copied().unwrap_or(0), 0);
+    }
+
+    /// Raise a palisade at the neighbour of the first agent's location,
+    /// owned by a stranger whose access list admits no one, and let the
+    /// agent come of age. Returns the agent, the palisade's location, and
+    /// its ID.
+    fn wall_off_the_neighbour(state: &mut SimulationState) -> (AgentId, LocationId, StructureId) {
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let meadow = state.agent_states.get(&agent_id).unwrap().location_id;
+        let (forest, _) = *state.world_map.neighbors(meadow).first().unwrap();
+        let stranger = AgentId::new();
+        let palisade = add_structure(state, StructureType::Palisade, forest, stranger);
+        state.structures.get_mut(&palisade).unwrap().access_list = Some(AccessControlList {
+            allowed_agents: BTreeSet::from([stranger]),
+            allowed_groups: BTreeSet::new(),
+            denied_agents: BTreeSet::new(),
+            public: false,
+            toll_cost: None,
+        });
+        (agent_id, forest, palisade)
+    }
+
+    #[test]
+    fn fortifications_turn_back_movers_until_breached() {
+        let mut state = make_simulation_state();
+        let (agent_id, forest, palisade) = wall_off_the_neighbour(&mut state);
+        let durability = state.structures.get(&palisade).unwrap().durability;
+
+        let mut moving = RepeatingSource(ActionType::Move, ActionParameters::Move {
+            destination: forest,
+        });
+        let summary = run_tick(&mut state, &mut moving).unwrap();
+        let result = summary.action_results.get(&agent_id).unwrap();
+        assert_eq!(
+            result.rejection.as_ref().map(|r| r.reason),
+            Some(RejectionReason::PermissionDenied)
+        );
+
+        let mut breaching = RepeatingSource(ActionType::Breach, ActionParameters::Breach {
+            destination: forest,
+        });
+        let summary = run_tick(&mut state, &mut breaching).unwrap();
+        assert!(summary.action_results.get(&agent_id).unwrap().success);
+        let battered = state.structures.get(&palisade).unwrap().durability;
+        assert_eq!(
+            battered,
+            durability.saturating_sub(emergence_agents::actions::costs::BREACH_DAMAGE)
+        );
+    }
+
+    #[test]
+    fn a_breach_in_a_conflict_lays_siege_to_the_location() {
+        let mut state = make_simulation_state();
+        let (agent_id, forest, palisade) = wall_off_the_neighbour(&mut state);
+        let stranger = state.structures.get(&palisade).unwrap().owner.unwrap();
+        let mut group = |member: AgentId| {
+            let id = GroupId::new();
+            state.groups.insert(id, Group {
+                id,
+                name: String::from("Band"),
+                founder: member,
+                members: BTreeSet::from([member]),
+                formed_at_tick: 0,
+            });
+            id
+        };
+        let (raiders, holders) = (group(agent_id), group(stranger));
+        state.diplomacy.declare_conflict(raiders, holders, String::from("land"), 0).unwrap();
+
+        let mut breaching = RepeatingSource(ActionType::Breach, ActionParameters::Breach {
+            destination: forest,
+        });
+        let _ = run_tick(&mut state, &mut breaching).unwrap();
+        let siege = state.diplomacy.siege_at(&forest).unwrap();
+        assert_eq!((siege.besieger, siege.defender), (raiders, holders));
+        assert!(!siege.supplies_cut);
+        assert_eq!(state.diplomacy.active_conflicts().first().unwrap().breaches, 1);
+
+        // The raiders hold the forest's only neighbour, so its supplies are cut.
+        state.agent_states.get_mut(&agent_id).unwrap().energy = 100;
+        let _ = run_tick(&mut state, &mut breaching).unwrap();
+        assert!(state.diplomacy.supplies_cut(&forest));
+    }
+
+    #[test]
+    fn founding_a_settlement_grows_the_map() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        let seasons = vec![Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+        state.clock = WorldClock::from_parts(300, Era::Primitive, 90, seasons).unwrap();
+        let location = state.world_map.get_location_mut(location_id).unwrap();
+        location.add_occupant(AgentId::new()).unwrap();
+        location.add_occupant(AgentId::new()).unwrap();
+        let name = String::from("Newhome");
+        let mut settling = RepeatingSource(
+            ActionType::FoundSettlement,
+            ActionParameters::FoundSettlement { name: name.clone() },
+        );
+
+        let summary = run_tick(&mut state, &mut settling).unwrap();
+        assert!(summary.action_results.get(&agent_id).unwrap().success);
+        assert_eq!(state.world_map.location_count(), 3);
+        let (settlement, route) = state
+            .world_map
+            .neighbors(location_id)
+            .into_iter()
+            .find(|(id, _)| state.world_map.get_location(*id).unwrap().location.name == name)
+            .unwrap();
+        assert!(state.known_maps.get(agent_id).unwrap().knows_route(route));
+        assert!(state.known_maps.knows_location(&state.world_map, agent_id, settlement));
+
+        // The name is taken, so founding again adds nothing.
+        run_tick(&mut state, &mut settling).unwrap();
+        assert_eq!(state.world_map.location_count(), 3);
+    }
+
+    #[test]
+    fn unexplored_routes_stay_hidden_until_taught() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        state.known_maps.record_position(&state.world_map, agent_id, location_id);
+        let mut scratch = TickScratch::new();
+
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Clear);
+        let route = perceptions.get(&agent_id).unwrap().known_routes.first().unwrap();
+        assert_eq!(route.destination, known_map::UNEXPLORED);
+        assert!(route.resources_hint.is_empty());
+
+        // A guide who has walked to the forest shows the way.
+        let guide = AgentId::new();
+        let (forest, _) = *state.world_map.neighbors(location_id).first().unwrap();
+        state.known_maps.record_position(&state.world_map, guide, location_id);
+        state.known_maps.record_position(&state.world_map, guide, forest);
+        assert!(state.known_maps.teach(guide, agent_id) > 0);
+
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Clear);
+        let route = perceptions.get(&agent_id).unwrap().known_routes.first().unwrap();
+        assert_eq!(route.destination, "Forest");
+    }
+
+    #[test]
+    fn known_routes_are_costed_by_the_quickest_way_there() {
+        let state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        let mut scratch = TickScratch::new();
+
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Rain);
+        let route = perceptions.get(&agent_id).unwrap().known_routes.first().unwrap();
+        let destination = LocationId::from(uuid::Uuid::parse_str(&route.destination_id).unwrap());
+        let ticks = state.world_map.travel_time(location_id, destination, Weather::Rain).unwrap();
+        assert_eq!(route.cost, format!("{ticks} ticks"));
+        assert!(!scratch.route_cache.is_empty());
+
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Storm);
+        let route = perceptions.get(&agent_id).unwrap().known_routes.first().unwrap();
+        assert_eq!(route.cost, "impassable");
+    }
+
+    #[test]
+    fn only_weather_watchers_see_a_forecast() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let mut scratch = TickScratch::new();
+
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Clear);
+        assert!(perceptions.values().all(|p| p.forecast.is_empty()));
+
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.knowledge.insert(String::from("weather_lore"));
+        }
+        let perceptions = phase_perception(&state, &mut scratch, Season::Spring, Weather::Clear);
+        let ticks: Vec<u64> =
+            perceptions.get(&agent_id).unwrap().forecast.iter().map(|f| f.tick).collect();
+        let tick = state.clock.tick();
+        let expected: Vec<u64> =
+            (1..=environment::FORECAST_TICKS).map(|t| tick.saturating_add(t)).collect();
+        assert_eq!(ticks, expected);
+        assert!(
+            perceptions
+                .iter()
+                .filter(|(id, _)| **id != agent_id)
+                .all(|(_, p)| p.forecast.is_empty())
+        );
+    }
+
+    #[test]
+    fn prospecting_reveals_a_hidden_deposit() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let location_id = state.agent_states.get(&agent_id).unwrap().location_id;
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+            agent_state.skills.insert(String::from("prospecting"), 20);
+        }
+        let ore = ResourceNode {
+            resource: Resource::Ore,
+            available: 12,
+            regen_per_tick: 1,
+            max_capacity: 30,
+        };
+        emergence_world::prospecting::bury_deposit(&mut state.world_map, location_id, ore)
+            .unwrap();
+        let mut prospecting = RepeatingSource(ActionType::Prospect, ActionParameters::Prospect);
+
+        // A 90% chance each tick.
+        for _ in 0..5 {
+            if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
+                agent_state.energy = 100;
+            }
+            let _ = run_tick(&mut state, &mut prospecting).unwrap();
+        }
+        let location = state.world_map.get_location(location_id).unwrap();
+        assert!(location.hidden_resources.is_empty());
+        assert!(location.get_resource(&Resource::Ore).is_some());
+    }
+
+    #[test]
+    fn broadcasts_reach_the_belief_detector_through_the_bus() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let message = String::from("The sacred spirit watches over the river");
+        let mut preaching =
+            RepeatingSource(ActionType::Broadcast, ActionParameters::Broadcast { message });
+
+        let summary = run_tick(&mut state, &mut preaching).unwrap();
+        assert!(summary.action_results.get(&agent_id).unwrap().success);
+
+        let themes = state.subscribers.beliefs.lock().unwrap().detect_clusters();
+        let theme = themes.first().unwrap();
+        assert!(theme.keywords.contains(&String::from("sacred")));
+        assert!(theme.keywords.contains(&String::from("spirit")));
+        assert!(theme.adherent_ids.contains(&agent_id));
+    }
+
     #[test]
     fn dead_agents_removed_from_alive_list() {
         let mut state = make_simulation_state();
@@ -1768,7 +3739,7 @@ mod tests {
 
         let agent_id = *state.alive_agents.first().unwrap();
 
-        if let Some(agent_state) = state.agent_states.get_mut(&agent_id) {
+        if let Some(mut agent_state) = state.agent_states.get_mut(&agent_id) {
             agent_state.hunger = 96;
             agent_state.health = 5;
         }
@@ -1777,6 +3748,74 @@ mod tests {
         assert!(!state.alive_agents.contains(&agent_id));
     }
 
+    /// A decision source that delivers one reflection update per agent.
+    struct ReflectingSource {
+        goals: Vec<String>,
+        summary: Option<String>,
+        agents: Vec<AgentId>,
+    }
+
+    impl DecisionSource for ReflectingSource {
+        fn collect_decisions(
+            &mut self,
+            tick: u64,
+            perceptions: &BTreeMap<AgentId, Perception>,
+        ) -> Result<BTreeMap<AgentId, ActionRequest>, crate::decision::DecisionError> {
+            StubDecisionSource::new().collect_decisions(tick, perceptions)
+        }
+
+        fn collect_reflections(&mut self, tick: u64) -> Vec<ReflectionUpdate> {
+            self.agents
+                .iter()
+                .map(|&agent_id| ReflectionUpdate {
+                    agent_id,
+                    tick,
+                    goals: self.goals.clone(),
+                    memory_summary: self.summary.clone(),
+                    submitted_at: Utc::now(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn reflection_updates_goals_and_memory() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        let mut source = ReflectingSource {
+            goals: vec![String::from("build a shelter")],
+            summary: Some(String::from("I have been gathering wood near the meadow.")),
+            agents: vec![agent_id, AgentId::new()],
+        };
+
+        let _ = run_tick(&mut state, &mut source).unwrap();
+
+        let agent_state = state.agent_states.get(&agent_id).unwrap();
+        assert_eq!(agent_state.goals, vec![String::from("build a shelter")]);
+        let reflection = agent_state.memory.last().unwrap();
+        assert_eq!(reflection.memory_type, "reflection");
+        assert_eq!(reflection.tier, MemoryTier::ShortTerm);
+        assert_eq!(reflection.summary, "I have been gathering wood near the meadow.");
+    }
+
+    #[test]
+    fn empty_reflection_keeps_goals() {
+        let mut state = make_simulation_state();
+        let agent_id = *state.alive_agents.first().unwrap();
+        state.agent_states.get_mut(&agent_id).unwrap().goals = vec![String::from("stay fed")];
+        let mut source = ReflectingSource {
+            goals: Vec::new(),
+            summary: None,
+            agents: vec![agent_id],
+        };
+
+        let _ = run_tick(&mut state, &mut source).unwrap();
+
+        let agent_state = state.agent_states.get(&agent_id).unwrap();
+        assert_eq!(agent_state.goals, vec![String::from("stay fed")]);
+        assert!(agent_state.memory.iter().all(|m| m.memory_type != "reflection"));
+    }
+
     #[test]
     fn tick_summary_has_correct_agent_count() {
         let mut state = make_simulation_state();
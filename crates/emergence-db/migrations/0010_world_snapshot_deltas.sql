@@ -0,0 +1,22 @@
+-- Migration: World Snapshot Deltas
+-- Stores tick-to-tick changes to the world snapshot instead of a full
+-- snapshot every tick. A full snapshot in world_snapshots plus the deltas
+-- after it reconstructs the world at any later tick.
+
+-- =============================================================================
+-- world_snapshot_deltas
+-- =============================================================================
+-- Each row is the WorldSnapshotDelta that moves the world from base_tick to
+-- tick. The delta JSONB column holds the serialized delta as produced by
+-- emergence-types.
+
+CREATE TABLE IF NOT EXISTS world_snapshot_deltas (
+    tick            BIGINT          PRIMARY KEY,
+    base_tick       BIGINT          NOT NULL,
+    delta           JSONB           NOT NULL,
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT NOW()
+);
+
+-- Query pattern: replay deltas from a base snapshot
+CREATE INDEX IF NOT EXISTS idx_world_snapshot_deltas_base
+    ON world_snapshot_deltas(base_tick);
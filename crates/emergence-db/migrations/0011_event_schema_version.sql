@@ -0,0 +1,13 @@
+-- Migration: Event Schema Version
+-- Records which version of the event detail types each event's details
+-- were written under, so readers can upcast old payloads to the current
+-- shape instead of failing to decode them.
+
+-- =============================================================================
+-- events.schema_version
+-- =============================================================================
+-- Matches emergence_types::EVENT_SCHEMA_VERSION at write time. Events
+-- written before this column existed are version 1.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1;
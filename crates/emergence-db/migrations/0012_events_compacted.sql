@@ -0,0 +1,8 @@
+-- Migration: Event Compaction
+-- Adds the event type for the summary events that compaction writes in
+-- place of superseded events (see emergence-events, compact module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0008_event_type_expansion.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'events_compacted';
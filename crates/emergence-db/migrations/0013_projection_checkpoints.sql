@@ -0,0 +1,17 @@
+-- Migration: Projection Checkpoints
+-- Stores the folded state of each projection over the event stream, so
+-- analytics resume from the last checkpoint instead of rescanning every
+-- event.
+
+-- =============================================================================
+-- projection_checkpoints
+-- =============================================================================
+-- One row per projection, replaced on every save. The tick is the last
+-- tick whose events are folded into the state.
+
+CREATE TABLE IF NOT EXISTS projection_checkpoints (
+    name            TEXT            PRIMARY KEY,
+    tick            BIGINT          NOT NULL,
+    state           JSONB           NOT NULL DEFAULT '{}'::JSONB,
+    updated_at      TIMESTAMPTZ     NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,24 @@
+-- Migration: Event Causality
+-- Records each event's own identifier, the event that directly caused it,
+-- and the cascade it belongs to, so researchers can walk from an outcome
+-- back to what set it off (famine, theft, rule, enforcement).
+
+-- =============================================================================
+-- events.event_id / caused_by / correlation_id
+-- =============================================================================
+-- event_id is the event's EventId; caused_by references another event's
+-- event_id. Events written before this migration have none of the three.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS event_id UUID,
+    ADD COLUMN IF NOT EXISTS caused_by UUID,
+    ADD COLUMN IF NOT EXISTS correlation_id UUID;
+
+-- Query patterns: look up by event_id, find an event's consequences, and
+-- list a cascade.
+CREATE INDEX IF NOT EXISTS idx_events_event_id
+    ON events(event_id);
+CREATE INDEX IF NOT EXISTS idx_events_caused_by
+    ON events(caused_by) WHERE caused_by IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_events_correlation
+    ON events(correlation_id) WHERE correlation_id IS NOT NULL;
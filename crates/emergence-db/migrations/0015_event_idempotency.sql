@@ -0,0 +1,17 @@
+-- Migration: Event Idempotency Keys
+-- Lets a writer tag each event with a deterministic key so that retrying a
+-- failed write skips the events that were already committed instead of
+-- inserting them twice.
+
+-- =============================================================================
+-- events.idempotency_key
+-- =============================================================================
+-- NULL for events written without a key; NULLs never conflict. The key is
+-- unique per tick because unique indexes on a partitioned table must
+-- include the partition key.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS idempotency_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_idempotency
+    ON events(idempotency_key, tick);
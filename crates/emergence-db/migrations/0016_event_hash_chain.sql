@@ -0,0 +1,20 @@
+-- Migration: Event Hash Chain
+-- Lets a run link its events into a tamper-evident chain: each chained
+-- event stores the hash of the chained event appended before it and a
+-- hash over that plus its own payload, so editing, deleting, or
+-- reordering events after the fact is detectable.
+
+-- =============================================================================
+-- events.prev_hash / hash
+-- =============================================================================
+-- Both are SHA-256 digests. Events written without the hash chain enabled
+-- have neither and are not part of the chain.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS prev_hash BYTEA,
+    ADD COLUMN IF NOT EXISTS hash BYTEA;
+
+-- Query patterns: find the chain head on append, and walk the chain in
+-- append order on verification.
+CREATE INDEX IF NOT EXISTS idx_events_chained
+    ON events(id) WHERE hash IS NOT NULL;
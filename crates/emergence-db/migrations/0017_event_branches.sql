@@ -0,0 +1,80 @@
+-- Migration: Event Branches
+-- Lets an operator fork the event log at a tick into a named branch for
+-- what-if analysis. A branch shares its parent's history before the fork
+-- tick and records its own events and snapshots from the fork tick on,
+-- leaving the parent's history untouched.
+
+-- =============================================================================
+-- event_branches
+-- =============================================================================
+-- One row per branch. The main branch is the original history and has no
+-- parent; every other branch forks from its parent at fork_tick.
+
+CREATE TABLE IF NOT EXISTS event_branches (
+    name            TEXT            PRIMARY KEY,
+    parent          TEXT            REFERENCES event_branches(name),
+    fork_tick       BIGINT,
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT chk_event_branches_fork CHECK ((parent IS NULL) = (fork_tick IS NULL))
+);
+
+INSERT INTO event_branches (name) VALUES ('main') ON CONFLICT (name) DO NOTHING;
+
+-- =============================================================================
+-- branch columns
+-- =============================================================================
+-- Rows written before branching existed belong to the main branch.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS branch TEXT NOT NULL DEFAULT 'main'
+        REFERENCES event_branches(name);
+ALTER TABLE agent_snapshots
+    ADD COLUMN IF NOT EXISTS branch TEXT NOT NULL DEFAULT 'main'
+        REFERENCES event_branches(name);
+ALTER TABLE world_snapshots
+    ADD COLUMN IF NOT EXISTS branch TEXT NOT NULL DEFAULT 'main'
+        REFERENCES event_branches(name);
+ALTER TABLE world_snapshot_deltas
+    ADD COLUMN IF NOT EXISTS branch TEXT NOT NULL DEFAULT 'main'
+        REFERENCES event_branches(name);
+
+-- World snapshots and deltas are one per tick on each branch.
+ALTER TABLE world_snapshots DROP CONSTRAINT IF EXISTS world_snapshots_pkey;
+ALTER TABLE world_snapshots ADD PRIMARY KEY (branch, tick);
+ALTER TABLE world_snapshot_deltas DROP CONSTRAINT IF EXISTS world_snapshot_deltas_pkey;
+ALTER TABLE world_snapshot_deltas ADD PRIMARY KEY (branch, tick);
+
+-- Idempotency keys are unique per branch, so replaying a tick's writes on
+-- a branch does not collide with the parent's.
+DROP INDEX IF EXISTS idx_events_idempotency;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_idempotency
+    ON events(branch, idempotency_key, tick);
+
+-- Query pattern: a branch's own events by tick.
+CREATE INDEX IF NOT EXISTS idx_events_branch_tick ON events(branch, tick);
+CREATE INDEX IF NOT EXISTS idx_agent_snapshots_branch_tick
+    ON agent_snapshots(branch, tick);
+
+-- =============================================================================
+-- branch_lineage
+-- =============================================================================
+-- The branches whose rows a branch sees: the branch itself, with every
+-- tick, and each ancestor up to the earliest fork below it, with the ticks
+-- before that fork. depth is 0 for the branch itself and rises toward main,
+-- so readers prefer the nearest branch's row when several cover a tick.
+
+CREATE OR REPLACE FUNCTION branch_lineage(leaf TEXT)
+RETURNS TABLE (name TEXT, until_tick BIGINT, depth INT)
+LANGUAGE SQL STABLE AS $$
+    WITH RECURSIVE lineage (name, parent, fork_tick, until_tick, depth) AS (
+        SELECT b.name, b.parent, b.fork_tick, 9223372036854775807::BIGINT, 0
+        FROM event_branches b
+        WHERE b.name = leaf
+        UNION ALL
+        SELECT p.name, p.parent, p.fork_tick, LEAST(l.until_tick, l.fork_tick), l.depth + 1
+        FROM event_branches p
+        JOIN lineage l ON p.name = l.parent
+    )
+    SELECT lineage.name, lineage.until_tick, lineage.depth FROM lineage
+$$;
@@ -0,0 +1,22 @@
+-- Migration: Derived Events
+-- Lets detectors added after a run scan its history and write the events
+-- they would have emitted (see emergence-events, backfill module), marked
+-- so they can be told apart from the events the simulation recorded.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0008_event_type_expansion.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'famine_started';
+
+-- =============================================================================
+-- events.derived_by
+-- =============================================================================
+-- The name of the detector that derived the event; NULL for primary events
+-- recorded by the simulation itself.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS derived_by TEXT;
+
+-- Query pattern: replace one detector's output over a tick range.
+CREATE INDEX IF NOT EXISTS idx_events_derived_by
+    ON events(derived_by, tick) WHERE derived_by IS NOT NULL;
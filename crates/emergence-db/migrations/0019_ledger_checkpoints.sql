@@ -0,0 +1,10 @@
+-- Migration: Ledger Checkpoints
+-- Long runs prune old ledger entries into opening-balance checkpoint
+-- entries (see emergence-ledger, prune module). A checkpoint moves an
+-- entity's carried-over balance from one entity to another, so its from
+-- and to entity types are whatever those entities are.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'checkpoint';
@@ -0,0 +1,12 @@
+-- Migration: Ledger Escrow
+-- A pending trade offer moves its offered resources from the offerer into
+-- an escrow entity named by the trade ID (see emergence-agents, trade
+-- module). Accepting the trade releases them to the target; rejecting or
+-- expiring it releases them back to the offerer.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type and
+-- entity_type enums defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'escrow';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'escrow_release';
+ALTER TYPE entity_type ADD VALUE IF NOT EXISTS 'escrow';
@@ -0,0 +1,11 @@
+-- Migration: Ledger Energy
+-- A run may audit agent energy with the same double-entry bookkeeping as
+-- resources (see emergence-ledger, energy module): energy recovered moves
+-- from the world to an agent, and energy spent moves from an agent to the
+-- void.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'energy_spent';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'energy_recovered';
@@ -0,0 +1,10 @@
+-- Migration: Ledger Corrections
+-- A conservation anomaly is repaired by appending an explicit correction
+-- entry rather than editing history (see emergence-ledger, diagnosis
+-- module). A correction reverses the side an entry left unmatched, so it
+-- may have only a source or only a destination, of any entity type.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'correction';
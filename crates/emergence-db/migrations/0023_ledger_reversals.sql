@@ -0,0 +1,10 @@
+-- Migration: Ledger Reversals
+-- When the engine must undo a resolved action, it appends a reversal entry
+-- rather than deleting the original (see emergence-ledger, ledger module).
+-- A reversal swaps the original entry's sides, so it may have any entity
+-- types, and its reference_id holds the original entry's ID.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'reversal';
@@ -0,0 +1,11 @@
+-- Migration: Ledger Currency
+-- Once agents adopt a resource as a currency, it is issued by mint entries
+-- (world -> agent) and withdrawn by burn entries (agent -> void), so the
+-- money supply is auditable like any physical resource (see
+-- emergence-ledger, currency module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'mint';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'burn';
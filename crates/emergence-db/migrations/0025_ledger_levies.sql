@@ -0,0 +1,12 @@
+-- Migration: Ledger Levies
+-- Governance and diplomacy record levies through the ledger: a tax moves
+-- resources from a member into its group's treasury, and tribute moves them
+-- from one group's treasury to another's. Each treasury is a group entity
+-- named by the group ID.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type and
+-- entity_type enums defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'tax';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'tribute';
+ALTER TYPE entity_type ADD VALUE IF NOT EXISTS 'group';
@@ -0,0 +1,10 @@
+-- Migration: Reconciliation Mismatch
+-- The end-of-tick reconciliation scan compares ledger balances with what
+-- agents, locations, and structures actually hold, and records any drift
+-- as its own event, apart from conservation anomalies (see
+-- emergence-ledger, reconcile module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0008_event_type_expansion.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'reconciliation_mismatch';
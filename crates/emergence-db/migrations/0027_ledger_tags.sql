@@ -0,0 +1,12 @@
+-- Migration: Ledger Tags
+-- A ledger entry may carry typed tags naming what caused it -- the action,
+-- trade, or governance rule -- so the entries of one trade, such as its
+-- escrow, its transfers, and any toll, can be queried together. Tags are
+-- stored as the JSON serialization of emergence_types::LedgerTag, e.g.
+-- [{"Trade": "0194c2a3-..."}].
+
+ALTER TABLE ledger
+    ADD COLUMN IF NOT EXISTS tags JSONB NOT NULL DEFAULT '[]';
+
+-- Query pattern: all entries carrying a tag (tags @> '[{"Trade": ...}]').
+CREATE INDEX IF NOT EXISTS idx_ledger_tags ON ledger USING GIN (tags);
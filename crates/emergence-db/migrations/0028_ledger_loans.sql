@@ -0,0 +1,11 @@
+-- Migration: Ledger Loans
+-- Credit between agents is recorded through the ledger: a loan moves
+-- principal from the creditor to the debtor, a repayment moves it back,
+-- and interest is paid from the debtor to the creditor as it accrues.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum
+-- defined in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'loan_issued';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'loan_repayment';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'interest_accrued';
@@ -0,0 +1,21 @@
+-- Migration: Ledger Snapshots
+-- Stores the ledger's opening balances at snapshot ticks, so an engine
+-- restart resumes conservation checking from the latest snapshot instead of
+-- replaying every ledger entry.
+
+-- =============================================================================
+-- ledger_snapshots
+-- =============================================================================
+-- Each row is the LedgerSnapshot at the end of tick: every entity's non-zero
+-- balance of each resource, the money supply of each adopted currency, and
+-- the principal outstanding on each loan. The snapshot JSONB column holds
+-- the serialized snapshot as produced by emergence-types.
+
+CREATE TABLE IF NOT EXISTS ledger_snapshots (
+    tick            BIGINT          NOT NULL,
+    branch          TEXT            NOT NULL DEFAULT 'main'
+                                    REFERENCES event_branches(name),
+    snapshot        JSONB           NOT NULL,
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (branch, tick)
+);
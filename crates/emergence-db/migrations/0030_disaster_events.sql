@@ -0,0 +1,11 @@
+-- Migration: Disaster Events
+-- Floods, wildfires, and earthquakes strike locations at random or when
+-- injected by the operator, and each records its own event (see
+-- emergence-world, disasters module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0008_event_type_expansion.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'flood_occurred';
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'wildfire_occurred';
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'earthquake_occurred';
@@ -0,0 +1,9 @@
+-- Migration: Structure Inheritance
+-- When an agent dies, their structures pass to a living spouse or child
+-- before they become claimable, and each records its own event (see
+-- emergence-agents, death and family modules).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0008_event_type_expansion.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'structure_inherited';
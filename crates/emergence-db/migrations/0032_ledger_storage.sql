@@ -0,0 +1,10 @@
+-- Migration: Ledger Storage
+-- Granaries and storehouses hold an inventory of their own. A deposit moves
+-- resources from an agent into a storage structure, and a withdrawal moves
+-- them back out to an agent.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum defined
+-- in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'deposit';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'withdrawal';
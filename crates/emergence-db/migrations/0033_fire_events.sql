@@ -0,0 +1,9 @@
+-- Migration: Fire Events
+-- Fires started by unattended campfires or wildfires spread between the
+-- wooden structures at a location, and each structure they damage records
+-- its own event (see emergence-world, fire module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0030_disaster_events.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'structure_burned';
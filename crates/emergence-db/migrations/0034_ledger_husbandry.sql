@@ -0,0 +1,10 @@
+-- Migration: Ledger Husbandry
+-- Livestock is held like any other resource. Breeding moves new head from
+-- the world into an agent's herd, and slaughter removes a head from the
+-- herd, the meat and hide it yields being recorded separately.
+--
+-- ALTER TYPE ... ADD VALUE is appended to the ledger_entry_type enum defined
+-- in 0002_ledger.sql.
+
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'breeding';
+ALTER TYPE ledger_entry_type ADD VALUE IF NOT EXISTS 'slaughter';
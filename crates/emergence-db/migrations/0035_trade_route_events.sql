@@ -0,0 +1,9 @@
+-- Migration: Trade Route Events
+-- A route that carries enough traffic becomes a trade corridor, and each
+-- one that emerges records its own event (see emergence-world,
+-- trade_network module).
+--
+-- ALTER TYPE ... ADD VALUE is appended to the event_type enum defined in
+-- 0003_events.sql, as in 0033_fire_events.sql.
+
+ALTER TYPE event_type ADD VALUE IF NOT EXISTS 'trade_route_emerged';
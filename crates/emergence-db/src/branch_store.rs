@@ -0,0 +1,113 @@
+//! Named branches of the event log for what-if analysis.
+//!
+//! A branch forks from a parent branch at a tick. It shares the parent's
+//! events and snapshots before that tick and records its own from the fork
+//! tick on, so an operator can hydrate the branch at the fork, write a
+//! different event there, and continue the simulation on the branch while
+//! the parent's history stays as it was. [`MAIN_BRANCH`] holds the
+//! original history and has no parent.
+//!
+//! Point an [`EventStore`](crate::EventStore) or
+//! [`SnapshotStore`](crate::SnapshotStore) at a branch with its
+//! `with_branch` builder.
+
+use sqlx::PgPool;
+
+use crate::error::DbError;
+use crate::event_store::MAIN_BRANCH;
+
+/// Operations on the `event_branches` table.
+pub struct BranchStore<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> BranchStore<'a> {
+    /// Create a new branch store bound to a connection pool.
+    pub const fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Fork `parent` at `fork_tick` into a new branch `name`.
+    ///
+    /// The branch sees the parent's history with `tick < fork_tick`; its
+    /// own events should start at `fork_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::UnknownBranch`] if `parent` does not exist, or
+    /// [`DbError::Postgres`] if the insert fails, including when a branch
+    /// named `name` already exists.
+    pub async fn create(
+        &self,
+        name: &str,
+        parent: &str,
+        fork_tick: u64,
+    ) -> Result<BranchRow, DbError> {
+        let fork_i64 = i64::try_from(fork_tick).unwrap_or(i64::MAX);
+
+        let row = sqlx::query_as::<_, BranchRow>(
+            r"INSERT INTO event_branches (name, parent, fork_tick)
+              SELECT $1, name, $3 FROM event_branches WHERE name = $2
+              RETURNING name, parent, fork_tick, created_at",
+        )
+        .bind(name)
+        .bind(parent)
+        .bind(fork_i64)
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or_else(|| DbError::UnknownBranch(parent.to_owned()))?;
+
+        tracing::info!(name, parent, fork_tick, "Created event branch");
+        Ok(row)
+    }
+
+    /// Query the branch `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get(&self, name: &str) -> Result<Option<BranchRow>, DbError> {
+        let row = sqlx::query_as::<_, BranchRow>(
+            r"SELECT name, parent, fork_tick, created_at
+              FROM event_branches
+              WHERE name = $1",
+        )
+        .bind(name)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Query every branch, [`MAIN_BRANCH`] first and the rest in creation
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn list(&self) -> Result<Vec<BranchRow>, DbError> {
+        let rows = sqlx::query_as::<_, BranchRow>(
+            r"SELECT name, parent, fork_tick, created_at
+              FROM event_branches
+              ORDER BY name <> $1, created_at, name",
+        )
+        .bind(MAIN_BRANCH)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+/// A row from the `event_branches` table.
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct BranchRow {
+    /// The branch's name.
+    pub name: String,
+    /// The branch it forked from; `None` for [`MAIN_BRANCH`].
+    pub parent: Option<String>,
+    /// The first tick the branch records its own events at.
+    pub fork_tick: Option<i64>,
+    /// Real-world timestamp of the fork.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
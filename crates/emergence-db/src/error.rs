@@ -23,6 +23,21 @@ pub enum DbError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// An event's details do not match its type's details struct.
+    #[error("Invalid details for {event_type} event {event_id}: {source}")]
+    InvalidEvent {
+        /// The offending event.
+        event_id: emergence_types::EventId,
+        /// The event type, as stored.
+        event_type: &'static str,
+        /// Why the details did not decode.
+        source: serde_json::Error,
+    },
+
+    /// An event branch does not exist.
+    #[error("Unknown event branch: {0}")]
+    UnknownBranch(String),
+
     /// A key was not found in `Dragonfly`.
     #[error("Key not found: {0}")]
     KeyNotFound(String),
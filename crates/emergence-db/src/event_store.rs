@@ -4,21 +4,61 @@
 //! change produces an immutable event written to `PostgreSQL`. Events are
 //! partitioned by tick range (10,000 ticks per partition).
 //!
+//! Every appended event is first checked against the details struct for
+//! its type (see [`validate_details`]), so a malformed payload is rejected
+//! when it is written rather than discovered when it is replayed.
+//!
+//! Optionally, appended events are linked into a tamper-evident hash chain
+//! (see [`crate::hash_chain`]). Events derived from the history after the
+//! fact are written apart from it by [`EventStore::replace_derived`].
+//!
+//! [`EventStore::query`] selects events by an [`EventFilter`], the same
+//! filter the observer applies to its in-memory events.
+//!
+//! The log can fork into named branches for what-if analysis (see
+//! [`crate::branch_store`]). A store reads and writes [`MAIN_BRANCH`]
+//! unless [`EventStore::with_branch`] points it at another.
+//!
 //! See: `data-schemas.md` section 5, `world-engine.md` section 10.2
 
-use emergence_types::{Event, EventType};
+use emergence_types::{
+    ActionResult, AgentDiedDetails, CombatInitiatedDetails, CombatResolvedDetails, Comparison,
+    DetailPredicate, DisasterDetails, EnforcementAppliedDetails, Event, EventFilter, EventType,
+    EventsCompactedDetails, FamineStartedDetails, GroupFormedDetails, KnowledgeDiscoveredDetails, KnowledgeTaughtDetails, RelationshipChangedDetails,
+    ReconciliationMismatchDetails, ResourceGatheredDetails, RouteDegradedDetails,
+    RouteImprovedDetails, RuleCreatedDetails,
+    StructureBuiltDetails, StructureBurnedDetails, StructureClaimedDetails,
+    StructureDestroyedDetails,
+    StructureInheritedDetails,
+    StructureRepairedDetails, TheftFailedDetails, TheftOccurredDetails, TradeCompletedDetails,
+    TradeFailedDetails, TradeRouteEmergedDetails, EVENT_SCHEMA_VERSION,
+};
+use serde::de::DeserializeOwned;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::DbError;
+use crate::hash_chain::{self, ChainLink, ChainReport, ChainedRow, GENESIS_HASH};
 
 /// Default batch size for event inserts.
 const DEFAULT_BATCH_SIZE: usize = 100;
 
+/// Longest causal chain the chain queries follow, guarding against cycles.
+const MAX_CHAIN_DEPTH: i32 = 1_000;
+
+/// Advisory lock key serializing hash-chained appends.
+const HASH_CHAIN_LOCK: i64 = 0x6576_656e_7473_6368;
+
+/// The branch holding the original history.
+pub const MAIN_BRANCH: &str = "main";
+
 /// Operations on the `events` table.
+#[derive(Clone, Copy)]
 pub struct EventStore<'a> {
     pool: &'a PgPool,
     batch_size: usize,
+    hash_chain: bool,
+    branch: &'a str,
 }
 
 impl<'a> EventStore<'a> {
@@ -27,6 +67,8 @@ impl<'a> EventStore<'a> {
         Self {
             pool,
             batch_size: DEFAULT_BATCH_SIZE,
+            hash_chain: false,
+            branch: MAIN_BRANCH,
         }
     }
 
@@ -37,6 +79,33 @@ impl<'a> EventStore<'a> {
         self
     }
 
+    /// Link inserted events into the tamper-evident hash chain.
+    ///
+    /// Chained appends take a database-wide lock so that concurrent
+    /// writers extend the chain one at a time.
+    #[must_use]
+    pub const fn with_hash_chain(mut self, enabled: bool) -> Self {
+        self.hash_chain = enabled;
+        self
+    }
+
+    /// Read and write the event log of `branch` instead of
+    /// [`MAIN_BRANCH`].
+    ///
+    /// Reads see the branch's own events and, through its lineage, its
+    /// ancestors' events before the tick it forked at. Writes go to the
+    /// branch alone; see [`BranchStore`](crate::BranchStore).
+    #[must_use]
+    pub const fn with_branch(mut self, branch: &'a str) -> Self {
+        self.branch = branch;
+        self
+    }
+
+    /// The branch this store reads and writes.
+    pub const fn branch(&self) -> &'a str {
+        self.branch
+    }
+
     /// Batch-insert events into the `events` table.
     ///
     /// Events are inserted in batches using multi-row VALUES clauses for
@@ -53,72 +122,152 @@ impl<'a> EventStore<'a> {
     ///
     /// # Errors
     ///
-    /// Returns [`DbError::Postgres`] if the insert fails.
+    /// Returns [`DbError::InvalidEvent`] before anything is written if any
+    /// event fails [`validate_details`], or [`DbError::Postgres`] if the
+    /// insert fails.
     pub async fn batch_insert(&self, events: &[Event]) -> Result<(), DbError> {
+        self.insert_batch(events, None).await.map(drop)
+    }
+
+    /// Batch-insert events like [`batch_insert`](Self::batch_insert),
+    /// optionally under an idempotency key.
+    ///
+    /// With a key, the event at position `i` is stored under `{key}/{i}`,
+    /// and an event whose key is already stored at the same tick is
+    /// skipped. Retrying a failed write with the same key and the same
+    /// events in the same order therefore inserts only what the failed
+    /// attempt did not commit.
+    ///
+    /// With the [hash chain](Self::with_hash_chain) on, each inserted event
+    /// is linked to the chain head; skipped events are not.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::InvalidEvent`] before anything is written if any
+    /// event fails [`validate_details`], or [`DbError::Postgres`] if the
+    /// insert fails.
+    pub async fn insert_batch(
+        &self,
+        events: &[Event],
+        idempotency_key: Option<&str>,
+    ) -> Result<InsertReport, DbError> {
+        let mut report = InsertReport::default();
         if events.is_empty() {
-            return Ok(());
+            return Ok(report);
         }
+        events.iter().try_for_each(validate_details)?;
 
-        for chunk in events.chunks(self.batch_size) {
+        let keys: Vec<Option<String>> = (0..events.len())
+            .map(|i| idempotency_key.map(|key| format!("{key}/{i}")))
+            .collect();
+        let size = self.batch_size.max(1);
+        for (chunk, chunk_keys) in events.chunks(size).zip(keys.chunks(size)) {
             let mut tx = self.pool.begin().await?;
+            let links = if self.hash_chain {
+                chain_links(&mut tx, self.branch, chunk, chunk_keys).await?
+            } else {
+                vec![None; chunk.len()]
+            };
+            let inserted =
+                insert_chunk(&mut tx, self.branch, chunk, chunk_keys, &links, None).await?;
+            tx.commit().await?;
+            let skipped = u64::try_from(chunk.len()).unwrap_or(u64::MAX).saturating_sub(inserted);
+            report.inserted = report.inserted.saturating_add(inserted);
+            report.deduplicated = report.deduplicated.saturating_add(skipped);
+        }
+
+        if report.deduplicated > 0 {
+            tracing::info!(
+                key = idempotency_key,
+                inserted = report.inserted,
+                deduplicated = report.deduplicated,
+                "Skipped events already written under this key"
+            );
+        }
+        tracing::debug!(count = events.len(), "Inserted events (batch UNNEST)");
+        Ok(report)
+    }
 
-            // Pre-allocate arrays for UNNEST-based batch insert.
-            let len = chunk.len();
-            let mut ticks = Vec::with_capacity(len);
-            let mut event_types = Vec::with_capacity(len);
-            let mut agent_ids: Vec<Option<Uuid>> = Vec::with_capacity(len);
-            let mut location_ids: Vec<Option<Uuid>> = Vec::with_capacity(len);
-            let mut details_arr = Vec::with_capacity(len);
-            let mut snapshots: Vec<Option<serde_json::Value>> = Vec::with_capacity(len);
-            let mut contexts = Vec::with_capacity(len);
-            let mut timestamps = Vec::with_capacity(len);
-
-            for event in chunk {
-                ticks.push(i64::try_from(event.tick).unwrap_or(i64::MAX));
-                event_types.push(event_type_to_db(event.event_type).to_owned());
-                agent_ids.push(event.agent_id.map(emergence_types::AgentId::into_inner));
-                location_ids.push(
-                    event
-                        .location_id
-                        .map(emergence_types::LocationId::into_inner),
-                );
-                details_arr.push(event.details.clone());
-                snapshots.push(
-                    event
-                        .agent_state_snapshot
-                        .as_ref()
-                        .map(serde_json::to_value)
-                        .transpose()
-                        .map_err(DbError::Serialization)?,
-                );
-                contexts.push(
-                    serde_json::to_value(&event.world_context)
-                        .map_err(DbError::Serialization)?,
-                );
-                timestamps.push(event.created_at);
-            }
-
-            // Multi-row INSERT using UNNEST for batch efficiency.
-            sqlx::query(
-                r"INSERT INTO events (tick, event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at)
-                  SELECT * FROM UNNEST($1::BIGINT[], $2::event_type[], $3::UUID[], $4::UUID[], $5::JSONB[], $6::JSONB[], $7::JSONB[], $8::TIMESTAMPTZ[])",
-            )
-            .bind(&ticks)
-            .bind(&event_types)
-            .bind(&agent_ids)
-            .bind(&location_ids)
-            .bind(&details_arr)
-            .bind(&snapshots)
-            .bind(&contexts)
-            .bind(&timestamps)
+    /// Replace the events with IDs in `folded` by `summaries`, in one
+    /// transaction, and return the number of rows deleted.
+    ///
+    /// Used by compaction: readers see either the original events or the
+    /// summaries, never both or neither. Only this branch's own events are
+    /// deleted; the summaries are written to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::InvalidEvent`] if a summary fails
+    /// [`validate_details`], [`DbError::Postgres`] if the insert or delete
+    /// fails, or [`DbError::Serialization`] if a summary cannot be encoded.
+    pub async fn replace_events(
+        &self,
+        folded: &[i64],
+        summaries: &[Event],
+    ) -> Result<u64, DbError> {
+        summaries.iter().try_for_each(validate_details)?;
+        let mut tx = self.pool.begin().await?;
+        for chunk in summaries.chunks(self.batch_size.max(1)) {
+            let none = vec![None; chunk.len()];
+            insert_chunk(&mut tx, self.branch, chunk, &none, &none_linked(chunk), None).await?;
+        }
+        let deleted = sqlx::query("DELETE FROM events WHERE id = ANY($1) AND branch = $2")
+            .bind(folded)
+            .bind(self.branch)
             .execute(&mut *tx)
-            .await?;
+            .await?
+            .rows_affected();
+        tx.commit().await?;
 
-            tx.commit().await?;
+        tracing::debug!(deleted, summaries = summaries.len(), "Replaced compacted events");
+        Ok(deleted)
+    }
+
+    /// Replace the events `detector` derived with
+    /// `from_tick <= tick < to_tick` by `derived`, in one transaction, and
+    /// return the number of rows deleted.
+    ///
+    /// Derived events are stored marked with the detector's name, so
+    /// re-running a detector over the same ticks replaces its earlier
+    /// output instead of adding to it, and never touches the events the
+    /// simulation recorded. They are not linked into the hash chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::InvalidEvent`] if a derived event fails
+    /// [`validate_details`], [`DbError::Postgres`] if the insert or delete
+    /// fails, or [`DbError::Serialization`] if an event cannot be encoded.
+    pub async fn replace_derived(
+        &self,
+        detector: &str,
+        from_tick: u64,
+        to_tick: u64,
+        derived: &[Event],
+    ) -> Result<u64, DbError> {
+        derived.iter().try_for_each(validate_details)?;
+        let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+        let mut tx = self.pool.begin().await?;
+        let deleted = sqlx::query(
+            r"DELETE FROM events
+              WHERE derived_by = $1 AND tick >= $2 AND tick < $3 AND branch = $4",
+        )
+        .bind(detector)
+        .bind(from_i64)
+        .bind(to_i64)
+        .bind(self.branch)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        for chunk in derived.chunks(self.batch_size.max(1)) {
+            let none = vec![None; chunk.len()];
+            let marker = Some(detector);
+            insert_chunk(&mut tx, self.branch, chunk, &none, &none_linked(chunk), marker).await?;
         }
+        tx.commit().await?;
 
-        tracing::debug!(count = events.len(), "Inserted events (batch UNNEST)");
-        Ok(())
+        tracing::debug!(detector, deleted, derived = derived.len(), "Replaced derived events");
+        Ok(deleted)
     }
 
     /// Query events for a specific tick.
@@ -129,11 +278,15 @@ impl<'a> EventStore<'a> {
     pub async fn get_events_by_tick(&self, tick: u64) -> Result<Vec<EventRow>, DbError> {
         let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
         let rows = sqlx::query_as::<_, EventRow>(
-            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
               FROM events
-              WHERE tick = $1
+              JOIN branch_lineage($1) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick = $2
               ORDER BY id",
         )
+        .bind(self.branch)
         .bind(tick_i64)
         .fetch_all(self.pool)
         .await?;
@@ -141,6 +294,69 @@ impl<'a> EventStore<'a> {
         Ok(rows)
     }
 
+    /// Query events with `after_tick < tick <= to_tick`, in tick order.
+    ///
+    /// Applied in order on top of state snapshotted at `after_tick`, they
+    /// bring it forward to `to_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_events_after(
+        &self,
+        after_tick: u64,
+        to_tick: u64,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let after_i64 = i64::try_from(after_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+        let rows = sqlx::query_as::<_, EventRow>(
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
+              FROM events
+              JOIN branch_lineage($1) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick > $2 AND tick <= $3
+              ORDER BY tick, id",
+        )
+        .bind(self.branch)
+        .bind(after_i64)
+        .bind(to_i64)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Query events with `from_tick <= tick < to_tick`, in tick order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_events_in_range(
+        &self,
+        from_tick: u64,
+        to_tick: u64,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+        let rows = sqlx::query_as::<_, EventRow>(
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
+              FROM events
+              JOIN branch_lineage($1) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick >= $2 AND tick < $3
+              ORDER BY tick, id",
+        )
+        .bind(self.branch)
+        .bind(from_i64)
+        .bind(to_i64)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     /// Query events for a specific agent within a tick range.
     ///
     /// # Errors
@@ -155,26 +371,518 @@ impl<'a> EventStore<'a> {
         let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
         let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
         let rows = sqlx::query_as::<_, EventRow>(
-            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
               FROM events
+              JOIN branch_lineage($4) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
               WHERE agent_id = $1 AND tick >= $2 AND tick < $3
               ORDER BY tick, id",
         )
         .bind(agent_id)
         .bind(from_i64)
         .bind(to_i64)
+        .bind(self.branch)
         .fetch_all(self.pool)
         .await?;
 
         Ok(rows)
     }
+
+    /// Query events passing `filter`, in tick order, returning at most
+    /// `limit` of them if given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn query(
+        &self,
+        filter: &EventFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let mut query = filter_query(filter, self.branch);
+        if let Some(limit) = limit {
+            query.push(" LIMIT ").push_bind(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+        let rows = query.build_query_as::<EventRow>().fetch_all(self.pool).await?;
+
+        Ok(rows)
+    }
+
+    /// The location of each of `agent_ids` as of `tick`: that of its latest
+    /// event at or before `tick` that has one. Agents with no such event
+    /// are omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_latest_locations(
+        &self,
+        agent_ids: &[Uuid],
+        tick: u64,
+    ) -> Result<Vec<(Uuid, Uuid)>, DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+        let rows = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r"SELECT DISTINCT ON (agent_id) agent_id, location_id
+              FROM events
+              JOIN branch_lineage($3) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE agent_id = ANY($1) AND tick <= $2 AND location_id IS NOT NULL
+              ORDER BY agent_id, tick DESC, id DESC",
+        )
+        .bind(agent_ids)
+        .bind(tick_i64)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Walk `event_id`'s [`caused_by`](Event::caused_by) links back to the
+    /// root cause. Returns the chain root first, ending with the event
+    /// itself; empty if the event is not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_causal_chain(&self, event_id: Uuid) -> Result<Vec<EventRow>, DbError> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            r"WITH RECURSIVE visible AS (SELECT * FROM branch_lineage($3)),
+              chain AS (
+                  SELECT e.*, 0 AS depth FROM events e
+                  JOIN visible ON e.branch = visible.name AND e.tick < visible.until_tick
+                  WHERE e.event_id = $1
+                  UNION ALL
+                  SELECT e.*, chain.depth + 1 FROM events e
+                  JOIN visible ON e.branch = visible.name AND e.tick < visible.until_tick
+                  JOIN chain ON e.event_id = chain.caused_by
+                  WHERE chain.depth < $2
+              )
+              SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
+              FROM chain
+              ORDER BY depth DESC",
+        )
+        .bind(event_id)
+        .bind(MAX_CHAIN_DEPTH)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Every event caused, directly or transitively, by `event_id`, in tick
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_consequences(&self, event_id: Uuid) -> Result<Vec<EventRow>, DbError> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            r"WITH RECURSIVE visible AS (SELECT * FROM branch_lineage($3)),
+              effects AS (
+                  SELECT e.*, 1 AS depth FROM events e
+                  JOIN visible ON e.branch = visible.name AND e.tick < visible.until_tick
+                  WHERE e.caused_by = $1
+                  UNION ALL
+                  SELECT e.*, effects.depth + 1 FROM events e
+                  JOIN visible ON e.branch = visible.name AND e.tick < visible.until_tick
+                  JOIN effects ON e.caused_by = effects.event_id
+                  WHERE effects.depth < $2
+              )
+              SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
+              FROM effects
+              ORDER BY tick, id",
+        )
+        .bind(event_id)
+        .bind(MAX_CHAIN_DEPTH)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Every event in the cascade `correlation_id`, in tick order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_events_by_correlation(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Vec<EventRow>, DbError> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id
+              FROM events
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE correlation_id = $1
+              ORDER BY tick, id",
+        )
+        .bind(correlation_id)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Verify the hash chain over the chained events with
+    /// `from_tick <= tick < to_tick`, in append order.
+    ///
+    /// Events written without the hash chain are skipped. Verify from tick
+    /// 0 to also check the first link against the genesis hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn verify_chain(&self, from_tick: u64, to_tick: u64) -> Result<ChainReport, DbError> {
+        let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+        let rows = sqlx::query_as::<_, ChainedRow>(
+            r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                     event_id, caused_by, correlation_id, prev_hash, hash
+              FROM events
+              JOIN branch_lineage($3) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick >= $1 AND tick < $2 AND hash IS NOT NULL
+              ORDER BY id",
+        )
+        .bind(from_i64)
+        .bind(to_i64)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        let genesis = (from_tick == 0).then_some(&GENESIS_HASH[..]);
+        let report = hash_chain::verify(&rows, genesis);
+        if !report.is_intact() {
+            tracing::warn!(
+                from_tick,
+                to_tick,
+                breaks = report.breaks.len(),
+                "Event hash chain does not verify"
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// The `SELECT` of the events on `branch` passing `filter`, in tick order.
+fn filter_query<'q>(
+    filter: &'q EventFilter,
+    branch: &'q str,
+) -> sqlx::QueryBuilder<'q, sqlx::Postgres> {
+    let mut query = sqlx::QueryBuilder::new(
+        r"SELECT id, tick, event_type::TEXT as event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                 event_id, caused_by, correlation_id
+          FROM events
+          JOIN branch_lineage(",
+    );
+    query.push_bind(branch).push(
+        r") lineage
+            ON branch = lineage.name AND tick < lineage.until_tick
+          WHERE TRUE",
+    );
+    if let Some(from) = filter.from_tick {
+        query.push(" AND tick >= ").push_bind(i64::try_from(from).unwrap_or(i64::MAX));
+    }
+    if let Some(to) = filter.to_tick {
+        query.push(" AND tick < ").push_bind(i64::try_from(to).unwrap_or(i64::MAX));
+    }
+    if !filter.event_types.is_empty() {
+        let types: Vec<&str> = filter.event_types.iter().copied().map(event_type_to_db).collect();
+        query.push(" AND event_type::TEXT = ANY(").push_bind(types).push(")");
+    }
+    if let Some(agent_id) = filter.agent_id {
+        query.push(" AND agent_id = ").push_bind(agent_id.into_inner());
+    }
+    if let Some(location_id) = filter.location_id {
+        query.push(" AND location_id = ").push_bind(location_id.into_inner());
+    }
+    for predicate in &filter.details {
+        push_predicate(&mut query, predicate);
+    }
+    query.push(" ORDER BY tick, id");
+    query
+}
+
+/// Append `predicate` as a condition on `details`.
+///
+/// `JSONB` compares numbers numerically and across types by type, so
+/// ordering comparisons are restricted to fields of the value's own type
+/// to match [`DetailPredicate::matches`]. A missing field is SQL `NULL`,
+/// which no comparison passes.
+fn push_predicate(
+    query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>,
+    predicate: &DetailPredicate,
+) {
+    let path: Vec<String> = predicate.path().map(str::to_owned).collect();
+    let field = |query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>| {
+        query.push("(details #> ").push_bind(path.clone()).push(")");
+    };
+    let value = |query: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>| {
+        query.push("(").push_bind(predicate.value.clone()).push("::JSONB)");
+    };
+    query.push(" AND (");
+    let operator = match predicate.comparison {
+        Comparison::Eq => "=",
+        Comparison::Ne => "<>",
+        ordering => {
+            query.push("jsonb_typeof");
+            field(query);
+            query.push(" = jsonb_typeof");
+            value(query);
+            query.push(" AND jsonb_typeof");
+            value(query);
+            query.push(" IN ('number', 'string', 'boolean', 'null') AND ");
+            ordering.symbol()
+        }
+    };
+    field(query);
+    query.push(" ").push(operator).push(" ");
+    value(query);
+    query.push(")");
+}
+
+/// Link each event of `chunk` to the head of `branch`'s hash chain, in
+/// order.
+///
+/// Takes the chain lock for the rest of the transaction. Events whose
+/// idempotency key is already stored on the branch get no link, since the
+/// insert will skip them.
+async fn chain_links(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &str,
+    chunk: &[Event],
+    keys: &[Option<String>],
+) -> Result<Vec<Option<ChainLink>>, DbError> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(HASH_CHAIN_LOCK)
+        .execute(&mut **tx)
+        .await?;
+    let head: Option<Vec<u8>> = sqlx::query_scalar(
+        r"SELECT hash FROM events
+          JOIN branch_lineage($1) lineage
+            ON branch = lineage.name AND tick < lineage.until_tick
+          WHERE hash IS NOT NULL
+          ORDER BY id DESC
+          LIMIT 1",
+    )
+    .bind(branch)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let wanted: Vec<&str> = keys.iter().flatten().map(String::as_str).collect();
+    let stored: Vec<(String, i64)> = if wanted.is_empty() {
+        Vec::new()
+    } else {
+        sqlx::query_as(
+            r"SELECT idempotency_key, tick FROM events
+              WHERE idempotency_key = ANY($1) AND branch = $2",
+        )
+        .bind(&wanted)
+        .bind(branch)
+        .fetch_all(&mut **tx)
+        .await?
+    };
+
+    let mut prev_hash = head.unwrap_or_else(|| GENESIS_HASH.to_vec());
+    let mut links = Vec::with_capacity(chunk.len());
+    for (event, key) in chunk.iter().zip(keys) {
+        let tick = i64::try_from(event.tick).unwrap_or(i64::MAX);
+        let duplicate = key
+            .as_ref()
+            .is_some_and(|key| stored.iter().any(|(k, t)| k == key && *t == tick));
+        if duplicate {
+            links.push(None);
+            continue;
+        }
+        let hash = hash_chain::chain_hash(&prev_hash, &EventRow::from_event(event)?);
+        let prev = std::mem::replace(&mut prev_hash, hash.clone());
+        links.push(Some(ChainLink {
+            prev_hash: prev,
+            hash,
+        }));
+    }
+    Ok(links)
+}
+
+/// No hash chain links, for a chunk written outside the chain.
+fn none_linked(chunk: &[Event]) -> Vec<Option<ChainLink>> {
+    vec![None; chunk.len()]
+}
+
+/// Insert one batch of events on `branch` with a single multi-row
+/// `INSERT`, storing each under the idempotency key at the same position in
+/// `keys`. Returns the number of rows written; events whose key is already
+/// stored on the branch are skipped. `links` holds each event's hash chain
+/// link, if chained, and `derived_by` the detector that derived the
+/// events, if any.
+async fn insert_chunk(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &str,
+    chunk: &[Event],
+    keys: &[Option<String>],
+    links: &[Option<ChainLink>],
+    derived_by: Option<&str>,
+) -> Result<u64, DbError> {
+    // Pre-allocate arrays for UNNEST-based batch insert.
+    let len = chunk.len();
+    let mut ticks = Vec::with_capacity(len);
+    let mut event_types = Vec::with_capacity(len);
+    let mut agent_ids: Vec<Option<Uuid>> = Vec::with_capacity(len);
+    let mut location_ids: Vec<Option<Uuid>> = Vec::with_capacity(len);
+    let mut details_arr = Vec::with_capacity(len);
+    let mut snapshots: Vec<Option<serde_json::Value>> = Vec::with_capacity(len);
+    let mut contexts = Vec::with_capacity(len);
+    let mut timestamps = Vec::with_capacity(len);
+    let versions = vec![i32::try_from(EVENT_SCHEMA_VERSION).unwrap_or(i32::MAX); len];
+    let mut event_ids = Vec::with_capacity(len);
+    let mut causes: Vec<Option<Uuid>> = Vec::with_capacity(len);
+    let mut correlations: Vec<Option<Uuid>> = Vec::with_capacity(len);
+    let prev_hashes: Vec<Option<&[u8]>> =
+        links.iter().map(|link| link.as_ref().map(|link| &link.prev_hash[..])).collect();
+    let hashes: Vec<Option<&[u8]>> =
+        links.iter().map(|link| link.as_ref().map(|link| &link.hash[..])).collect();
+
+    for event in chunk {
+        ticks.push(i64::try_from(event.tick).unwrap_or(i64::MAX));
+        event_types.push(event_type_to_db(event.event_type).to_owned());
+        agent_ids.push(event.agent_id.map(emergence_types::AgentId::into_inner));
+        location_ids.push(event.location_id.map(emergence_types::LocationId::into_inner));
+        details_arr.push(event.details.clone());
+        snapshots.push(
+            event
+                .agent_state_snapshot
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(DbError::Serialization)?,
+        );
+        contexts.push(serde_json::to_value(&event.world_context).map_err(DbError::Serialization)?);
+        timestamps.push(event.created_at);
+        event_ids.push(event.id.into_inner());
+        causes.push(event.caused_by.map(emergence_types::EventId::into_inner));
+        correlations.push(event.correlation_id.map(emergence_types::CorrelationId::into_inner));
+    }
+
+    // Multi-row INSERT using UNNEST for batch efficiency.
+    let result = sqlx::query(
+        r"INSERT INTO events (tick, event_type, agent_id, location_id, details, agent_state_snapshot, world_context, created_at, schema_version,
+                              event_id, caused_by, correlation_id, idempotency_key, prev_hash, hash, branch, derived_by)
+          SELECT *, $16::TEXT, $17::TEXT FROM UNNEST($1::BIGINT[], $2::event_type[], $3::UUID[], $4::UUID[], $5::JSONB[], $6::JSONB[], $7::JSONB[], $8::TIMESTAMPTZ[], $9::INTEGER[],
+                                                $10::UUID[], $11::UUID[], $12::UUID[], $13::TEXT[], $14::BYTEA[], $15::BYTEA[])
+          ON CONFLICT (branch, idempotency_key, tick) DO NOTHING",
+    )
+    .bind(&ticks)
+    .bind(&event_types)
+    .bind(&agent_ids)
+    .bind(&location_ids)
+    .bind(&details_arr)
+    .bind(&snapshots)
+    .bind(&contexts)
+    .bind(&timestamps)
+    .bind(&versions)
+    .bind(&event_ids)
+    .bind(&causes)
+    .bind(&correlations)
+    .bind(keys)
+    .bind(&prev_hashes)
+    .bind(&hashes)
+    .bind(branch)
+    .bind(derived_by)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Check that `event`'s details decode as the details struct for its type.
+///
+/// Action events carry the [`ActionResult`] the engine resolved. Types with
+/// no details struct (tick markers, messages, weather and season changes,
+/// ledger alerts, ...) accept any payload.
+///
+/// # Errors
+///
+/// Returns [`DbError::InvalidEvent`] if the details do not decode.
+pub fn validate_details(event: &Event) -> Result<(), DbError> {
+    let details = &event.details;
+    let checked = match event.event_type {
+        EventType::ActionSucceeded | EventType::ActionRejected => {
+            check::<ActionResult>(details)
+        }
+        EventType::AgentDied => check::<AgentDiedDetails>(details),
+        EventType::ResourceGathered => check::<ResourceGatheredDetails>(details),
+        EventType::TradeCompleted => check::<TradeCompletedDetails>(details),
+        EventType::TradeFailed => check::<TradeFailedDetails>(details),
+        EventType::StructureBuilt => check::<StructureBuiltDetails>(details),
+        EventType::StructureDestroyed => check::<StructureDestroyedDetails>(details),
+        EventType::StructureRepaired => check::<StructureRepairedDetails>(details),
+        EventType::RouteImproved => check::<RouteImprovedDetails>(details),
+        EventType::RouteDegraded => check::<RouteDegradedDetails>(details),
+        EventType::TradeRouteEmerged => check::<TradeRouteEmergedDetails>(details),
+        EventType::KnowledgeDiscovered => check::<KnowledgeDiscoveredDetails>(details),
+        EventType::KnowledgeTaught => check::<KnowledgeTaughtDetails>(details),
+        EventType::GroupFormed => check::<GroupFormedDetails>(details),
+        EventType::RelationshipChanged => check::<RelationshipChangedDetails>(details),
+        EventType::StructureClaimed => check::<StructureClaimedDetails>(details),
+        EventType::StructureInherited => check::<StructureInheritedDetails>(details),
+        EventType::RuleCreated => check::<RuleCreatedDetails>(details),
+        EventType::EnforcementApplied => check::<EnforcementAppliedDetails>(details),
+        EventType::TheftOccurred => check::<TheftOccurredDetails>(details),
+        EventType::TheftFailed => check::<TheftFailedDetails>(details),
+        EventType::CombatInitiated => check::<CombatInitiatedDetails>(details),
+        EventType::CombatResolved => check::<CombatResolvedDetails>(details),
+        EventType::EventsCompacted => check::<EventsCompactedDetails>(details),
+        EventType::FamineStarted => check::<FamineStartedDetails>(details),
+        EventType::ReconciliationMismatch => check::<ReconciliationMismatchDetails>(details),
+        EventType::FloodOccurred
+        | EventType::WildfireOccurred
+        | EventType::EarthquakeOccurred => check::<DisasterDetails>(details),
+        EventType::StructureBurned => check::<StructureBurnedDetails>(details),
+        EventType::TickStart
+        | EventType::TickEnd
+        | EventType::AgentBorn
+        | EventType::ActionSubmitted
+        | EventType::ResourceConsumed
+        | EventType::LocationDiscovered
+        | EventType::MessageSent
+        | EventType::WeatherChanged
+        | EventType::SeasonChanged
+        | EventType::LedgerAnomaly => Ok(()),
+    };
+    checked.map_err(|source| DbError::InvalidEvent {
+        event_id: event.id,
+        event_type: event_type_to_db(event.event_type),
+        source,
+    })
+}
+
+/// Whether `details` decodes as a `T`.
+fn check<T: DeserializeOwned>(details: &serde_json::Value) -> Result<(), serde_json::Error> {
+    T::deserialize(details).map(drop)
+}
+
+/// Outcome of [`EventStore::insert_batch`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InsertReport {
+    /// Events written.
+    pub inserted: u64,
+    /// Events skipped because their idempotency key was already stored.
+    pub deduplicated: u64,
 }
 
 /// A row from the `events` table.
 ///
 /// Uses runtime types rather than compile-time checked types to
 /// avoid requiring a live database during builds.
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
 pub struct EventRow {
     /// Auto-incremented event ID.
     pub id: i64,
@@ -194,10 +902,49 @@ pub struct EventRow {
     pub world_context: Option<serde_json::Value>,
     /// Real-world timestamp.
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// [`EVENT_SCHEMA_VERSION`] the details were written under.
+    pub schema_version: i32,
+    /// The event's [`EventId`](emergence_types::EventId), if recorded.
+    pub event_id: Option<Uuid>,
+    /// The `event_id` of the event that directly caused this one.
+    pub caused_by: Option<Uuid>,
+    /// The cascade this event belongs to.
+    pub correlation_id: Option<Uuid>,
+}
+
+impl EventRow {
+    /// `event` as it is stored, with row ID 0. The timestamp is left as
+    /// given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Serialization`] if the agent state or world
+    /// context cannot be encoded.
+    pub fn from_event(event: &Event) -> Result<Self, DbError> {
+        Ok(Self {
+            id: 0,
+            tick: i64::try_from(event.tick).unwrap_or(i64::MAX),
+            event_type: event_type_to_db(event.event_type).to_owned(),
+            agent_id: event.agent_id.map(emergence_types::AgentId::into_inner),
+            location_id: event.location_id.map(emergence_types::LocationId::into_inner),
+            details: event.details.clone(),
+            agent_state_snapshot: event
+                .agent_state_snapshot
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+            world_context: Some(serde_json::to_value(&event.world_context)?),
+            created_at: event.created_at,
+            schema_version: i32::try_from(EVENT_SCHEMA_VERSION).unwrap_or(i32::MAX),
+            event_id: Some(event.id.into_inner()),
+            caused_by: event.caused_by.map(emergence_types::EventId::into_inner),
+            correlation_id: event.correlation_id.map(emergence_types::CorrelationId::into_inner),
+        })
+    }
 }
 
 /// Convert an [`EventType`] enum variant to its `PostgreSQL` enum string.
-const fn event_type_to_db(et: EventType) -> &'static str {
+pub const fn event_type_to_db(et: EventType) -> &'static str {
     match et {
         EventType::TickStart => "tick_start",
         EventType::TickEnd => "tick_end",
@@ -222,14 +969,98 @@ const fn event_type_to_db(et: EventType) -> &'static str {
         EventType::RelationshipChanged => "relationship_changed",
         EventType::WeatherChanged => "weather_changed",
         EventType::SeasonChanged => "season_changed",
+        EventType::FloodOccurred => "flood_occurred",
+        EventType::WildfireOccurred => "wildfire_occurred",
+        EventType::EarthquakeOccurred => "earthquake_occurred",
+        EventType::StructureBurned => "structure_burned",
         EventType::RouteDegraded => "route_degraded",
+        EventType::TradeRouteEmerged => "trade_route_emerged",
         EventType::StructureClaimed => "structure_claimed",
+        EventType::StructureInherited => "structure_inherited",
         EventType::RuleCreated => "rule_created",
         EventType::EnforcementApplied => "enforcement_applied",
         EventType::LedgerAnomaly => "ledger_anomaly",
+        EventType::ReconciliationMismatch => "reconciliation_mismatch",
         EventType::TheftOccurred => "theft_occurred",
         EventType::TheftFailed => "theft_failed",
         EventType::CombatInitiated => "combat_initiated",
         EventType::CombatResolved => "combat_resolved",
+        EventType::EventsCompacted => "events_compacted",
+        EventType::FamineStarted => "famine_started",
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::{Era, EventId, Season, Weather, WorldContext};
+
+    use super::*;
+
+    fn event(event_type: EventType, details: serde_json::Value) -> Event {
+        Event {
+            id: EventId::new(),
+            tick: 1,
+            event_type,
+            agent_id: None,
+            location_id: None,
+            details,
+            agent_state_snapshot: None,
+            world_context: WorldContext {
+                tick: 1,
+                era: Era::Primitive,
+                season: Season::Spring,
+                weather: Weather::Clear,
+                population: 1,
+            },
+            created_at: chrono::Utc::now(),
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn filters_build_one_condition_per_field() {
+        let everything = EventFilter::new();
+        let query = filter_query(&everything, MAIN_BRANCH);
+        assert!(query.sql().ends_with("WHERE TRUE ORDER BY tick, id"));
+
+        let filter = EventFilter::new()
+            .with_ticks(10, 20)
+            .with_event_type(EventType::ResourceGathered)
+            .with_agent(emergence_types::AgentId::new())
+            .with_detail(DetailPredicate::new("resource", Comparison::Eq, "Wood"))
+            .with_detail(DetailPredicate::new("terms.quantity", Comparison::Ge, 5));
+        let query = filter_query(&filter, "drought");
+        let sql = query.sql();
+        assert!(sql.contains("JOIN branch_lineage($1) lineage"));
+        assert!(sql.contains("tick >= $2 AND tick < $3"));
+        assert!(sql.contains("event_type::TEXT = ANY($4)"));
+        assert!(sql.contains("agent_id = $5"));
+        assert!(sql.contains("((details #> $6) = ($7::JSONB))"));
+        assert!(sql.contains("jsonb_typeof(details #> $8) = jsonb_typeof($9::JSONB)"));
+        assert!(sql.contains("(details #> $11) >= ($12::JSONB)"));
+        assert!(sql.ends_with("ORDER BY tick, id"));
+    }
+
+    #[test]
+    fn validates_details_against_their_type() {
+        let discovered = serde_json::json!({
+            "knowledge": "fire",
+            "method": "experimentation",
+            "prerequisites": [],
+        });
+        assert!(validate_details(&event(EventType::KnowledgeDiscovered, discovered)).is_ok());
+
+        let malformed = event(EventType::KnowledgeDiscovered, serde_json::json!({"idea": 1}));
+        let err = validate_details(&malformed).unwrap_err();
+        assert!(matches!(
+            err,
+            DbError::InvalidEvent { event_id, event_type: "knowledge_discovered", .. }
+                if event_id == malformed.id
+        ));
+
+        // Types without a details struct take any payload.
+        assert!(validate_details(&event(EventType::TickStart, serde_json::json!(null))).is_ok());
     }
 }
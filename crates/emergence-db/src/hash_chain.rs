@@ -0,0 +1,239 @@
+//! Tamper-evident hash chain over the event log.
+//!
+//! With [`EventStore::with_hash_chain`](crate::EventStore::with_hash_chain)
+//! enabled, every appended event stores `prev_hash`, the hash of the
+//! chained event appended before it, and `hash`, the SHA-256 of
+//! `prev_hash` followed by the event's canonical [`payload`]. The first
+//! chained event links to [`GENESIS_HASH`].
+//!
+//! Editing a chained event's content makes its hash wrong; deleting or
+//! reordering events breaks the link from the next one.
+//! [`EventStore::verify_chain`](crate::EventStore::verify_chain) reports
+//! both. Rewriting a whole tail of the chain consistently is only caught
+//! against a head hash recorded elsewhere, so a research run should
+//! publish [`ChainReport::head`] alongside its results.
+//!
+//! Compaction deletes events and so breaks the chain; runs that need
+//! verification should not be compacted.
+
+use sha2::{Digest, Sha256};
+
+use crate::event_store::EventRow;
+
+/// The `prev_hash` of the first chained event.
+pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// A chained event as stored: its row plus both chain hashes.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct ChainedRow {
+    /// The event.
+    #[sqlx(flatten)]
+    pub(crate) row: EventRow,
+    /// The hash of the chained event before it.
+    pub(crate) prev_hash: Vec<u8>,
+    /// The hash over `prev_hash` and the event's payload.
+    pub(crate) hash: Vec<u8>,
+}
+
+/// The hashes linking one appended event into the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChainLink {
+    /// The hash of the chained event before it.
+    pub(crate) prev_hash: Vec<u8>,
+    /// The hash over `prev_hash` and the event's payload.
+    pub(crate) hash: Vec<u8>,
+}
+
+/// How the chain is broken at an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainBreakKind {
+    /// The event's stored hash does not match its content: the event, or
+    /// its hashes, were edited.
+    Tampered,
+    /// The event's `prev_hash` is not the previous chained event's hash:
+    /// an event before it was deleted, inserted, or moved.
+    Unlinked,
+}
+
+/// One point where the chain does not verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainBreak {
+    /// The event's row ID.
+    pub id: i64,
+    /// The tick the event occurred at.
+    pub tick: i64,
+    /// What is wrong.
+    pub kind: ChainBreakKind,
+}
+
+/// The result of verifying a range of the chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainReport {
+    /// Number of chained events checked.
+    pub events: u64,
+    /// The hash of the last chained event checked.
+    pub head: Option<Vec<u8>>,
+    /// Every break found, in chain order.
+    pub breaks: Vec<ChainBreak>,
+}
+
+impl ChainReport {
+    /// Whether every checked event verified.
+    pub const fn is_intact(&self) -> bool {
+        self.breaks.is_empty()
+    }
+}
+
+/// The content of `row` that its hash covers.
+///
+/// Everything the event says is included; the row ID and `created_at` are
+/// not, because the database assigns the first and rounds the second.
+pub fn payload(row: &EventRow) -> serde_json::Value {
+    serde_json::json!({
+        "tick": row.tick,
+        "event_type": row.event_type,
+        "agent_id": row.agent_id,
+        "location_id": row.location_id,
+        "details": row.details,
+        "agent_state_snapshot": row.agent_state_snapshot,
+        "world_context": row.world_context,
+        "schema_version": row.schema_version,
+        "event_id": row.event_id,
+        "caused_by": row.caused_by,
+        "correlation_id": row.correlation_id,
+    })
+}
+
+/// The chain hash of `row` following an event hashed to `prev_hash`.
+///
+/// The payload is hashed as compact JSON with object keys sorted, which is
+/// also how it reads back from `JSONB`.
+pub fn chain_hash(prev_hash: &[u8], row: &EventRow) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash);
+    hasher.update(payload(row).to_string().as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// Check `rows`, in chain order.
+///
+/// The first row's link is checked against `first_prev_hash` if given
+/// ([`GENESIS_HASH`] when verifying from the start of the run) and taken
+/// on trust otherwise.
+pub(crate) fn verify(rows: &[ChainedRow], first_prev_hash: Option<&[u8]>) -> ChainReport {
+    let mut report = ChainReport::default();
+    let mut expected = first_prev_hash.map(<[u8]>::to_vec);
+    for chained in rows {
+        let break_at = |kind| ChainBreak {
+            id: chained.row.id,
+            tick: chained.row.tick,
+            kind,
+        };
+        if expected.as_ref().is_some_and(|expected| *expected != chained.prev_hash) {
+            report.breaks.push(break_at(ChainBreakKind::Unlinked));
+        }
+        if chain_hash(&chained.prev_hash, &chained.row) != chained.hash {
+            report.breaks.push(break_at(ChainBreakKind::Tampered));
+        }
+        report.events = report.events.saturating_add(1);
+        expected = Some(chained.hash.clone());
+        report.head = Some(chained.hash.clone());
+    }
+    report
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn row(id: i64, details: serde_json::Value) -> EventRow {
+        EventRow {
+            id,
+            tick: 5,
+            event_type: "tick_end".to_owned(),
+            agent_id: None,
+            location_id: None,
+            details,
+            agent_state_snapshot: None,
+            world_context: None,
+            created_at: chrono::Utc::now(),
+            schema_version: 1,
+            event_id: Some(uuid::Uuid::now_v7()),
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    /// A chain of `len` correctly linked rows.
+    fn chain(len: i64) -> Vec<ChainedRow> {
+        let mut prev_hash = GENESIS_HASH.to_vec();
+        (1..=len)
+            .map(|id| {
+                let row = row(id, serde_json::json!({"n": id}));
+                let hash = chain_hash(&prev_hash, &row);
+                let prev = std::mem::replace(&mut prev_hash, hash.clone());
+                ChainedRow {
+                    row,
+                    prev_hash: prev,
+                    hash,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hash_ignores_row_id_and_timestamp_but_not_content() {
+        let a = row(1, serde_json::json!({"b": 1, "a": [1, 2]}));
+        let mut b = a.clone();
+        b.id = 99;
+        b.created_at = chrono::Utc::now();
+        assert_eq!(chain_hash(&GENESIS_HASH, &a), chain_hash(&GENESIS_HASH, &b));
+
+        b.details = serde_json::json!({"b": 2, "a": [1, 2]});
+        assert_ne!(chain_hash(&GENESIS_HASH, &a), chain_hash(&GENESIS_HASH, &b));
+        assert_ne!(chain_hash(&GENESIS_HASH, &a), chain_hash(&[1; 32], &a));
+    }
+
+    #[test]
+    fn intact_chains_verify() {
+        let rows = chain(4);
+        let report = verify(&rows, Some(&GENESIS_HASH));
+        assert!(report.is_intact());
+        assert_eq!(report.events, 4);
+        assert_eq!(report.head, rows.last().map(|chained| chained.hash.clone()));
+
+        // A range starting mid-run trusts its first link; from the start
+        // it must link to genesis.
+        let tail = rows.get(2..).unwrap();
+        assert!(verify(tail, None).is_intact());
+        let report = verify(tail, Some(&GENESIS_HASH));
+        let first = report.breaks.first().map(|broken| (broken.id, broken.kind));
+        assert_eq!(first, Some((3, ChainBreakKind::Unlinked)));
+    }
+
+    #[test]
+    fn edits_and_deletions_are_reported() {
+        let mut rows = chain(4);
+        rows.get_mut(1).unwrap().row.details = serde_json::json!({"n": 200});
+        rows.remove(2);
+
+        let report = verify(&rows, None);
+        assert_eq!(report.events, 3);
+        assert_eq!(
+            report.breaks,
+            [
+                ChainBreak {
+                    id: 2,
+                    tick: 5,
+                    kind: ChainBreakKind::Tampered,
+                },
+                ChainBreak {
+                    id: 4,
+                    tick: 5,
+                    kind: ChainBreakKind::Unlinked,
+                },
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@
 //!
 //! See: `data-schemas.md` section 6, `world-engine.md` section 4.2
 
-use emergence_types::{EntityType, LedgerEntry, LedgerEntryType, Resource};
+use emergence_types::{EntityType, LedgerEntry, LedgerEntryType, LedgerTag, Resource};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -52,7 +52,8 @@ impl<'a> LedgerStore<'a> {
     ///
     /// # Errors
     ///
-    /// Returns [`DbError::Postgres`] if the insert fails.
+    /// Returns [`DbError::Serialization`] if an entry's tags cannot be
+    /// serialized, or [`DbError::Postgres`] if the insert fails.
     pub async fn batch_insert(&self, entries: &[LedgerEntry]) -> Result<(), DbError> {
         if entries.is_empty() {
             return Ok(());
@@ -73,6 +74,7 @@ impl<'a> LedgerStore<'a> {
             let mut quantities = Vec::with_capacity(len);
             let mut reasons = Vec::with_capacity(len);
             let mut reference_ids: Vec<Option<Uuid>> = Vec::with_capacity(len);
+            let mut tags = Vec::with_capacity(len);
             let mut timestamps = Vec::with_capacity(len);
 
             for entry in chunk {
@@ -89,12 +91,13 @@ impl<'a> LedgerStore<'a> {
                 quantities.push(entry.quantity);
                 reasons.push(entry.reason.clone());
                 reference_ids.push(entry.reference_id);
+                tags.push(serde_json::to_value(&entry.tags).map_err(DbError::Serialization)?);
                 timestamps.push(entry.created_at);
             }
 
             sqlx::query(
-                r"INSERT INTO ledger (id, tick, entry_type, from_entity, from_entity_type, to_entity, to_entity_type, resource, quantity, reason, reference_id, created_at)
-                  SELECT * FROM UNNEST($1::UUID[], $2::BIGINT[], $3::ledger_entry_type[], $4::UUID[], $5::entity_type[], $6::UUID[], $7::entity_type[], $8::TEXT[], $9::NUMERIC[], $10::TEXT[], $11::UUID[], $12::TIMESTAMPTZ[])",
+                r"INSERT INTO ledger (id, tick, entry_type, from_entity, from_entity_type, to_entity, to_entity_type, resource, quantity, reason, reference_id, tags, created_at)
+                  SELECT * FROM UNNEST($1::UUID[], $2::BIGINT[], $3::ledger_entry_type[], $4::UUID[], $5::entity_type[], $6::UUID[], $7::entity_type[], $8::TEXT[], $9::NUMERIC[], $10::TEXT[], $11::UUID[], $12::JSONB[], $13::TIMESTAMPTZ[])",
             )
             .bind(&ids)
             .bind(&ticks)
@@ -107,6 +110,7 @@ impl<'a> LedgerStore<'a> {
             .bind(&quantities)
             .bind(&reasons)
             .bind(&reference_ids)
+            .bind(&tags)
             .bind(&timestamps)
             .execute(&mut *tx)
             .await?;
@@ -126,7 +130,7 @@ impl<'a> LedgerStore<'a> {
     pub async fn get_entries_by_tick(&self, tick: u64) -> Result<Vec<LedgerRow>, DbError> {
         let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
         let rows = sqlx::query_as::<_, LedgerRow>(
-            r"SELECT id, tick, entry_type::TEXT as entry_type, from_entity, from_entity_type::TEXT as from_entity_type, to_entity, to_entity_type::TEXT as to_entity_type, resource, quantity, reason, reference_id, created_at
+            r"SELECT id, tick, entry_type::TEXT as entry_type, from_entity, from_entity_type::TEXT as from_entity_type, to_entity, to_entity_type::TEXT as to_entity_type, resource, quantity, reason, reference_id, tags, created_at
               FROM ledger
               WHERE tick = $1
               ORDER BY created_at",
@@ -145,7 +149,7 @@ impl<'a> LedgerStore<'a> {
     /// Returns [`DbError::Postgres`] if the query fails.
     pub async fn get_entries_by_entity(&self, entity_id: Uuid) -> Result<Vec<LedgerRow>, DbError> {
         let rows = sqlx::query_as::<_, LedgerRow>(
-            r"SELECT id, tick, entry_type::TEXT as entry_type, from_entity, from_entity_type::TEXT as from_entity_type, to_entity, to_entity_type::TEXT as to_entity_type, resource, quantity, reason, reference_id, created_at
+            r"SELECT id, tick, entry_type::TEXT as entry_type, from_entity, from_entity_type::TEXT as from_entity_type, to_entity, to_entity_type::TEXT as to_entity_type, resource, quantity, reason, reference_id, tags, created_at
               FROM ledger
               WHERE from_entity = $1 OR to_entity = $1
               ORDER BY tick, created_at",
@@ -156,10 +160,32 @@ impl<'a> LedgerStore<'a> {
 
         Ok(rows)
     }
+
+    /// Query all ledger entries carrying `tag`, such as every entry of one
+    /// trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Serialization`] if the tag cannot be serialized,
+    /// or [`DbError::Postgres`] if the query fails.
+    pub async fn get_entries_by_tag(&self, tag: LedgerTag) -> Result<Vec<LedgerRow>, DbError> {
+        let tags = serde_json::to_value([tag]).map_err(DbError::Serialization)?;
+        let rows = sqlx::query_as::<_, LedgerRow>(
+            r"SELECT id, tick, entry_type::TEXT as entry_type, from_entity, from_entity_type::TEXT as from_entity_type, to_entity, to_entity_type::TEXT as to_entity_type, resource, quantity, reason, reference_id, tags, created_at
+              FROM ledger
+              WHERE tags @> $1::JSONB
+              ORDER BY tick, created_at",
+        )
+        .bind(tags)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
 }
 
 /// A row from the `ledger` table.
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
 pub struct LedgerRow {
     /// Ledger entry UUID.
     pub id: Uuid,
@@ -183,6 +209,8 @@ pub struct LedgerRow {
     pub reason: String,
     /// Related entity ID (trade, structure, etc.).
     pub reference_id: Option<Uuid>,
+    /// Tags naming what caused the transfer.
+    pub tags: sqlx::types::Json<Vec<LedgerTag>>,
     /// Real-world timestamp.
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -201,6 +229,24 @@ const fn ledger_entry_type_to_db(entry_type: LedgerEntryType) -> &'static str {
         LedgerEntryType::Pickup => "pickup",
         LedgerEntryType::Theft => "theft",
         LedgerEntryType::CombatLoot => "combat_loot",
+        LedgerEntryType::Checkpoint => "checkpoint",
+        LedgerEntryType::Correction => "correction",
+        LedgerEntryType::Reversal => "reversal",
+        LedgerEntryType::Mint => "mint",
+        LedgerEntryType::Burn => "burn",
+        LedgerEntryType::Deposit => "deposit",
+        LedgerEntryType::Withdrawal => "withdrawal",
+        LedgerEntryType::Tax => "tax",
+        LedgerEntryType::Tribute => "tribute",
+        LedgerEntryType::LoanIssued => "loan_issued",
+        LedgerEntryType::LoanRepayment => "loan_repayment",
+        LedgerEntryType::InterestAccrued => "interest_accrued",
+        LedgerEntryType::Escrow => "escrow",
+        LedgerEntryType::EscrowRelease => "escrow_release",
+        LedgerEntryType::EnergySpent => "energy_spent",
+        LedgerEntryType::EnergyRecovered => "energy_recovered",
+        LedgerEntryType::Breeding => "breeding",
+        LedgerEntryType::Slaughter => "slaughter",
     }
 }
 
@@ -212,6 +258,8 @@ const fn entity_type_to_db(entity_type: EntityType) -> &'static str {
         EntityType::Structure => "structure",
         EntityType::World => "world",
         EntityType::Void => "void",
+        EntityType::Escrow => "escrow",
+        EntityType::Group => "group",
     }
 }
 
@@ -237,5 +285,8 @@ const fn resource_to_db(resource: Resource) -> &'static str {
         Resource::ToolAdvanced => "tool_advanced",
         Resource::CurrencyToken => "currency_token",
         Resource::WrittenRecord => "written_record",
+        Resource::Map => "map",
+        Resource::FoodWaste => "food_waste",
+        Resource::Livestock => "livestock",
     }
 }
@@ -23,25 +23,41 @@
 //! - [`dragonfly`] -- `Dragonfly` (Redis-compatible) hot state operations
 //! - [`postgres`] -- `PostgreSQL` connection pool and configuration
 //! - [`event_store`] -- Batch event insertion and querying
+//! - [`branch_store`] -- Named branches of the event log for what-if analysis
 //! - [`ledger_store`] -- Batch ledger entry insertion and querying
 //! - [`snapshot_store`] -- World and agent snapshot persistence
+//! - [`projection_store`] -- Projection checkpoint persistence
+//! - [`hash_chain`] -- Tamper-evident hash chain over the event log
+//! - [`spool`] -- Dead-letter spool for event batches that failed to persist
+//! - [`metrics`] -- Flush latency histogram for the Observer's `/metrics` endpoint
 //! - [`error`] -- Shared error types
 
+pub mod branch_store;
 pub mod dragonfly;
 pub mod error;
 pub mod event_store;
 pub mod experiment_store;
+pub mod hash_chain;
 pub mod ledger_store;
+pub mod metrics;
 pub mod postgres;
+pub mod projection_store;
 pub mod snapshot_store;
+pub mod spool;
 pub mod tick_persist;
 
 // Re-export primary types for convenience.
+pub use branch_store::{BranchRow, BranchStore};
 pub use dragonfly::DragonflyPool;
 pub use error::DbError;
-pub use event_store::{EventRow, EventStore};
+pub use event_store::{
+    event_type_to_db, validate_details, EventRow, EventStore, InsertReport, MAIN_BRANCH,
+};
 pub use experiment_store::{ExperimentSnapshotRow, ExperimentStore};
+pub use hash_chain::{ChainBreak, ChainBreakKind, ChainReport};
 pub use ledger_store::{LedgerRow, LedgerStore};
 pub use postgres::{PostgresConfig, PostgresPool};
+pub use projection_store::{ProjectionCheckpointRow, ProjectionStore};
 pub use snapshot_store::{AgentSnapshotRow, SnapshotStore, WorldSnapshotRow};
+pub use spool::{DrainReport, EventSpool, Persisted, SpoolError, SpooledBatch};
 pub use tick_persist::PersistError;
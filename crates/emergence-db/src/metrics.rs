@@ -0,0 +1,12 @@
+//! Persistence metrics, exported through the Observer's `/metrics`
+//! endpoint (see [`emergence_metrics`]).
+
+use emergence_metrics::{Histogram, LATENCY_MS_BUCKETS};
+
+/// Duration of each end-of-tick flush, by destination table.
+pub static FLUSH_LATENCY_MS: Histogram = Histogram::labeled(
+    "emergence_db_flush_duration_ms",
+    "Duration of end-of-tick persistence flushes in milliseconds, by table.",
+    "table",
+    LATENCY_MS_BUCKETS,
+);
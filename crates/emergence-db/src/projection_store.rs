@@ -0,0 +1,86 @@
+//! Checkpoint persistence for projections over the event stream.
+//!
+//! A projection folds committed events into a derived view (population
+//! over time, wealth per agent, ...). Its checkpoint is that view plus the
+//! last tick folded into it, so a restarted reader resumes from there
+//! instead of rescanning the event log.
+
+use sqlx::PgPool;
+
+use crate::error::DbError;
+
+/// Operations on the `projection_checkpoints` table.
+pub struct ProjectionStore<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ProjectionStore<'a> {
+    /// Create a new projection store bound to a connection pool.
+    pub const fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Save the checkpoint for projection `name`, replacing any earlier one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the upsert fails.
+    pub async fn save_checkpoint(
+        &self,
+        name: &str,
+        tick: u64,
+        state: &serde_json::Value,
+    ) -> Result<(), DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+
+        sqlx::query(
+            r"INSERT INTO projection_checkpoints (name, tick, state, updated_at)
+              VALUES ($1, $2, $3, NOW())
+              ON CONFLICT (name) DO UPDATE SET
+                tick = EXCLUDED.tick,
+                state = EXCLUDED.state,
+                updated_at = EXCLUDED.updated_at",
+        )
+        .bind(name)
+        .bind(tick_i64)
+        .bind(state)
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Load the checkpoint for projection `name`, if one was saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn load_checkpoint(
+        &self,
+        name: &str,
+    ) -> Result<Option<ProjectionCheckpointRow>, DbError> {
+        let row = sqlx::query_as::<_, ProjectionCheckpointRow>(
+            r"SELECT name, tick, state, updated_at
+              FROM projection_checkpoints
+              WHERE name = $1",
+        )
+        .bind(name)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(row)
+    }
+}
+
+/// A row from the `projection_checkpoints` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProjectionCheckpointRow {
+    /// The projection's name.
+    pub name: String,
+    /// The last tick whose events are folded into the state.
+    pub tick: i64,
+    /// The projection's state as JSON.
+    pub state: serde_json::Value,
+    /// Real-world timestamp of the last save.
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
@@ -1,25 +1,48 @@
 //! Snapshot persistence for world and agent state.
 //!
 //! World snapshots are written at the end of each tick to record
-//! population, economy, and environment metrics. Agent snapshots are
-//! written periodically or on significant events.
+//! population, economy, and environment metrics. Between full snapshots,
+//! [`WorldSnapshotDelta`]s record only what changed. Agent snapshots are
+//! written periodically or on significant events. Ledger snapshots record
+//! the ledger's opening balances at snapshot ticks, so a restarted engine
+//! resumes from a [`LedgerSnapshot`] instead of replaying the ledger.
+//!
+//! Snapshots belong to an event branch like the events they summarize. A
+//! branch's snapshot reads fall back to its ancestors' snapshots taken
+//! before it forked, so hydrating a fresh branch starts from the parent's
+//! latest snapshot before the fork.
 //!
 //! See: `data-schemas.md` sections 4.3, 9, `world-engine.md` section 10.2
 
+use emergence_types::{LedgerSnapshot, WorldSnapshotDelta};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::DbError;
+use crate::event_store::MAIN_BRANCH;
 
-/// Operations on the `world_snapshots` and `agent_snapshots` tables.
+/// Operations on the `world_snapshots`, `agent_snapshots`, and
+/// `ledger_snapshots` tables.
 pub struct SnapshotStore<'a> {
     pool: &'a PgPool,
+    branch: &'a str,
 }
 
 impl<'a> SnapshotStore<'a> {
     /// Create a new snapshot store bound to a connection pool.
     pub const fn new(pool: &'a PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            branch: MAIN_BRANCH,
+        }
+    }
+
+    /// Read and write the snapshots of `branch` instead of
+    /// [`MAIN_BRANCH`].
+    #[must_use]
+    pub const fn with_branch(mut self, branch: &'a str) -> Self {
+        self.branch = branch;
+        self
     }
 
     // =========================================================================
@@ -54,9 +77,9 @@ impl<'a> SnapshotStore<'a> {
 
         sqlx::query(
             r"INSERT INTO world_snapshots
-              (tick, era, season, weather, population, births, deaths, total_resources, wealth_distribution, trades_this_tick, discoveries_count, summary)
-              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-              ON CONFLICT (tick) DO UPDATE SET
+              (tick, era, season, weather, population, births, deaths, total_resources, wealth_distribution, trades_this_tick, discoveries_count, summary, branch)
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+              ON CONFLICT (branch, tick) DO UPDATE SET
                 era = EXCLUDED.era,
                 season = EXCLUDED.season,
                 weather = EXCLUDED.weather,
@@ -81,6 +104,7 @@ impl<'a> SnapshotStore<'a> {
         .bind(trades_this_tick)
         .bind(discoveries_count)
         .bind(summary)
+        .bind(self.branch)
         .execute(self.pool)
         .await?;
 
@@ -101,9 +125,44 @@ impl<'a> SnapshotStore<'a> {
                      total_resources, wealth_distribution, trades_this_tick,
                      discoveries_count, summary, created_at
               FROM world_snapshots
-              WHERE tick = $1",
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick = $1
+              ORDER BY lineage.depth
+              LIMIT 1",
         )
         .bind(tick_i64)
+        .bind(self.branch)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Query the latest world snapshot taken at or before `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_world_snapshot_at_or_before(
+        &self,
+        tick: u64,
+    ) -> Result<Option<WorldSnapshotRow>, DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+
+        let row = sqlx::query_as::<_, WorldSnapshotRow>(
+            r"SELECT tick, era, season, weather, population, births, deaths,
+                     total_resources, wealth_distribution, trades_this_tick,
+                     discoveries_count, summary, created_at
+              FROM world_snapshots
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick <= $1
+              ORDER BY tick DESC, lineage.depth
+              LIMIT 1",
+        )
+        .bind(tick_i64)
+        .bind(self.branch)
         .fetch_optional(self.pool)
         .await?;
 
@@ -126,16 +185,163 @@ impl<'a> SnapshotStore<'a> {
                      total_resources, wealth_distribution, trades_this_tick,
                      discoveries_count, summary, created_at
               FROM world_snapshots
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
               ORDER BY tick DESC
               LIMIT $1",
         )
         .bind(count)
+        .bind(self.branch)
         .fetch_all(self.pool)
         .await?;
 
         Ok(rows)
     }
 
+    // =========================================================================
+    // World Snapshot Deltas
+    // =========================================================================
+
+    /// Insert the delta that produces the world snapshot at `delta.tick`.
+    ///
+    /// Uses `ON CONFLICT` to replace an existing delta for the same tick
+    /// (idempotent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Serialization`] if the delta cannot be encoded,
+    /// or [`DbError::Postgres`] if the insert fails.
+    pub async fn insert_world_snapshot_delta(
+        &self,
+        delta: &WorldSnapshotDelta,
+    ) -> Result<(), DbError> {
+        let tick_i64 = i64::try_from(delta.tick).unwrap_or(i64::MAX);
+        let base_tick_i64 = i64::try_from(delta.base_tick).unwrap_or(i64::MAX);
+        let body = serde_json::to_value(delta)?;
+
+        sqlx::query(
+            r"INSERT INTO world_snapshot_deltas (tick, base_tick, delta, branch)
+              VALUES ($1, $2, $3, $4)
+              ON CONFLICT (branch, tick) DO UPDATE SET
+                base_tick = EXCLUDED.base_tick,
+                delta = EXCLUDED.delta",
+        )
+        .bind(tick_i64)
+        .bind(base_tick_i64)
+        .bind(body)
+        .bind(self.branch)
+        .execute(self.pool)
+        .await?;
+
+        tracing::debug!(tick = delta.tick, base_tick = delta.base_tick, "Inserted world delta");
+        Ok(())
+    }
+
+    /// Query the deltas with `from_tick < tick <= to_tick`, in tick order.
+    ///
+    /// Applying them in order to the world snapshot at `from_tick` yields
+    /// the snapshot at `to_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails, or
+    /// [`DbError::Serialization`] if a stored delta cannot be decoded.
+    pub async fn get_world_snapshot_deltas(
+        &self,
+        from_tick: u64,
+        to_tick: u64,
+    ) -> Result<Vec<WorldSnapshotDelta>, DbError> {
+        let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+
+        let rows: Vec<(serde_json::Value,)> = sqlx::query_as(
+            r"SELECT delta
+              FROM world_snapshot_deltas
+              JOIN branch_lineage($3) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick > $1 AND tick <= $2
+              ORDER BY tick ASC",
+        )
+        .bind(from_i64)
+        .bind(to_i64)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        let deltas = rows
+            .into_iter()
+            .map(|(body,)| serde_json::from_value(body))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deltas)
+    }
+
+    // =========================================================================
+    // Ledger Snapshots
+    // =========================================================================
+
+    /// Insert the ledger snapshot for `snapshot.tick`.
+    ///
+    /// Uses `ON CONFLICT` to replace an existing snapshot for the same tick
+    /// (idempotent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Serialization`] if the snapshot cannot be encoded,
+    /// or [`DbError::Postgres`] if the insert fails.
+    pub async fn insert_ledger_snapshot(&self, snapshot: &LedgerSnapshot) -> Result<(), DbError> {
+        let tick_i64 = i64::try_from(snapshot.tick).unwrap_or(i64::MAX);
+        let body = serde_json::to_value(snapshot)?;
+
+        sqlx::query(
+            r"INSERT INTO ledger_snapshots (tick, snapshot, branch)
+              VALUES ($1, $2, $3)
+              ON CONFLICT (branch, tick) DO UPDATE SET
+                snapshot = EXCLUDED.snapshot",
+        )
+        .bind(tick_i64)
+        .bind(body)
+        .bind(self.branch)
+        .execute(self.pool)
+        .await?;
+
+        tracing::debug!(
+            tick = snapshot.tick,
+            balances = snapshot.balances.len(),
+            "Inserted ledger snapshot"
+        );
+        Ok(())
+    }
+
+    /// Query the latest ledger snapshot taken at or before `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails, or
+    /// [`DbError::Serialization`] if the stored snapshot cannot be decoded.
+    pub async fn get_ledger_snapshot_at_or_before(
+        &self,
+        tick: u64,
+    ) -> Result<Option<LedgerSnapshot>, DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+
+        let row: Option<(serde_json::Value,)> = sqlx::query_as(
+            r"SELECT snapshot
+              FROM ledger_snapshots
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick <= $1
+              ORDER BY tick DESC, lineage.depth
+              LIMIT 1",
+        )
+        .bind(tick_i64)
+        .bind(self.branch)
+        .fetch_optional(self.pool)
+        .await?;
+
+        let snapshot = row.map(|(body,)| serde_json::from_value(body)).transpose()?;
+        Ok(snapshot)
+    }
+
     // =========================================================================
     // Agent Snapshots
     // =========================================================================
@@ -154,12 +360,13 @@ impl<'a> SnapshotStore<'a> {
         let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
 
         sqlx::query(
-            r"INSERT INTO agent_snapshots (tick, agent_id, full_state)
-              VALUES ($1, $2, $3)",
+            r"INSERT INTO agent_snapshots (tick, agent_id, full_state, branch)
+              VALUES ($1, $2, $3, $4)",
         )
         .bind(tick_i64)
         .bind(agent_id)
         .bind(full_state)
+        .bind(self.branch)
         .execute(self.pool)
         .await?;
 
@@ -185,12 +392,13 @@ impl<'a> SnapshotStore<'a> {
         for (tick, agent_id, full_state) in snapshots {
             let tick_i64 = i64::try_from(*tick).unwrap_or(i64::MAX);
             sqlx::query(
-                r"INSERT INTO agent_snapshots (tick, agent_id, full_state)
-                  VALUES ($1, $2, $3)",
+                r"INSERT INTO agent_snapshots (tick, agent_id, full_state, branch)
+                  VALUES ($1, $2, $3, $4)",
             )
             .bind(tick_i64)
             .bind(agent_id)
             .bind(full_state)
+            .bind(self.branch)
             .execute(&mut *tx)
             .await?;
         }
@@ -213,17 +421,109 @@ impl<'a> SnapshotStore<'a> {
         let row = sqlx::query_as::<_, AgentSnapshotRow>(
             r"SELECT id, tick, agent_id, full_state, created_at
               FROM agent_snapshots
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
               WHERE agent_id = $1
-              ORDER BY tick DESC
+              ORDER BY tick DESC, lineage.depth
               LIMIT 1",
         )
         .bind(agent_id)
+        .bind(self.branch)
         .fetch_optional(self.pool)
         .await?;
 
         Ok(row)
     }
 
+    /// Query `agent_id`'s latest snapshot taken at or before `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_agent_snapshot_at_or_before(
+        &self,
+        agent_id: Uuid,
+        tick: u64,
+    ) -> Result<Option<AgentSnapshotRow>, DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+
+        let row = sqlx::query_as::<_, AgentSnapshotRow>(
+            r"SELECT id, tick, agent_id, full_state, created_at
+              FROM agent_snapshots
+              JOIN branch_lineage($3) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE agent_id = $1 AND tick <= $2
+              ORDER BY tick DESC, lineage.depth, id DESC
+              LIMIT 1",
+        )
+        .bind(agent_id)
+        .bind(tick_i64)
+        .bind(self.branch)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Query each agent's latest snapshot taken at or before `tick`, one row
+    /// per agent, in agent ID order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_agent_snapshots_at_or_before(
+        &self,
+        tick: u64,
+    ) -> Result<Vec<AgentSnapshotRow>, DbError> {
+        let tick_i64 = i64::try_from(tick).unwrap_or(i64::MAX);
+
+        let rows = sqlx::query_as::<_, AgentSnapshotRow>(
+            r"SELECT DISTINCT ON (agent_id) id, tick, agent_id, full_state, created_at
+              FROM agent_snapshots
+              JOIN branch_lineage($2) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick <= $1
+              ORDER BY agent_id, tick DESC, lineage.depth, id DESC",
+        )
+        .bind(tick_i64)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Query every agent's snapshots with `from_tick <= tick < to_tick`, in
+    /// tick order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DbError::Postgres`] if the query fails.
+    pub async fn get_agent_snapshots_in_range(
+        &self,
+        from_tick: u64,
+        to_tick: u64,
+    ) -> Result<Vec<AgentSnapshotRow>, DbError> {
+        let from_i64 = i64::try_from(from_tick).unwrap_or(i64::MAX);
+        let to_i64 = i64::try_from(to_tick).unwrap_or(i64::MAX);
+
+        let rows = sqlx::query_as::<_, AgentSnapshotRow>(
+            r"SELECT id, tick, agent_id, full_state, created_at
+              FROM agent_snapshots
+              JOIN branch_lineage($3) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
+              WHERE tick >= $1 AND tick < $2
+              ORDER BY tick, agent_id, id",
+        )
+        .bind(from_i64)
+        .bind(to_i64)
+        .bind(self.branch)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     /// Query all snapshots for a specific agent, optionally within a tick range.
     ///
     /// # Errors
@@ -241,12 +541,15 @@ impl<'a> SnapshotStore<'a> {
         let rows = sqlx::query_as::<_, AgentSnapshotRow>(
             r"SELECT id, tick, agent_id, full_state, created_at
               FROM agent_snapshots
+              JOIN branch_lineage($4) lineage
+                ON branch = lineage.name AND tick < lineage.until_tick
               WHERE agent_id = $1 AND tick >= $2 AND tick < $3
               ORDER BY tick",
         )
         .bind(agent_id)
         .bind(from_i64)
         .bind(to_i64)
+        .bind(self.branch)
         .fetch_all(self.pool)
         .await?;
 
@@ -255,7 +558,7 @@ impl<'a> SnapshotStore<'a> {
 }
 
 /// A row from the `world_snapshots` table.
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
 pub struct WorldSnapshotRow {
     /// The tick this snapshot represents.
     pub tick: i64,
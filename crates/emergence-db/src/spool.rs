@@ -0,0 +1,453 @@
+//! Dead-letter spool for event batches that could not be persisted.
+//!
+//! A `PostgreSQL` outage during the persist phase must not lose a tick's
+//! events. [`EventSpool::insert`] retries [`EventStore::insert_batch`] and,
+//! if every attempt fails, appends the batch to a local append-only file
+//! instead. [`EventSpool::drain`] replays the spooled batches in order once
+//! the database is reachable again and removes them from the file.
+//!
+//! Batches are replayed under the idempotency key they were first written
+//! with, so a batch whose insert committed before the connection dropped is
+//! not inserted twice. The spool expects a single writer.
+//!
+//! The file holds one JSON-encoded [`SpooledBatch`] per line. A line torn
+//! by a crash mid-append is ended before the next append, so the batches
+//! after it stay readable. On drain, lines that do not decode are counted
+//! as corrupt and moved to a quarantine file beside the spool (see
+//! [`EventSpool::quarantine_path`]) for inspection.
+
+use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use emergence_types::Event;
+use serde::{Deserialize, Serialize};
+
+use crate::error::DbError;
+use crate::event_store::{EventStore, InsertReport};
+
+/// Default number of retries after a failed insert before spooling.
+const DEFAULT_RETRIES: u32 = 3;
+
+/// Default delay before the first retry; it doubles on each retry.
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Errors that can occur while spooling or draining event batches.
+#[derive(Debug, thiserror::Error)]
+pub enum SpoolError {
+    /// The spool file could not be read or written.
+    #[error("spool file {path}: {source}")]
+    Io {
+        /// The spool file.
+        path: PathBuf,
+        /// The I/O error.
+        source: std::io::Error,
+    },
+
+    /// A batch could not be encoded for the spool file.
+    #[error("cannot encode spooled batch: {0}")]
+    Encode(#[from] serde_json::Error),
+
+    /// The batch was rejected for a reason retrying cannot fix, such as
+    /// malformed details.
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+/// One event batch waiting in the spool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpooledBatch {
+    /// The branch the batch was written to.
+    pub branch: String,
+    /// The idempotency key the batch was written under, if any.
+    pub idempotency_key: Option<String>,
+    /// The events, in order.
+    pub events: Vec<Event>,
+}
+
+/// How a batch given to [`EventSpool::insert`] was persisted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Persisted {
+    /// The batch reached `PostgreSQL`.
+    Inserted(InsertReport),
+    /// Every attempt failed; the batch was appended to the spool.
+    Spooled {
+        /// The last attempt's error.
+        error: String,
+    },
+}
+
+/// What a drain replayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Batches written to `PostgreSQL` and removed from the spool.
+    pub batches: usize,
+    /// Events inserted from those batches; replayed events already stored
+    /// under their key are not counted.
+    pub inserted: u64,
+    /// Batches still spooled because `PostgreSQL` is still unreachable.
+    pub remaining: usize,
+    /// Torn or unreadable lines moved from the spool to quarantine.
+    pub corrupt: usize,
+}
+
+/// An append-only file of event batches that failed to persist.
+#[derive(Debug, Clone)]
+pub struct EventSpool {
+    path: PathBuf,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl EventSpool {
+    /// Create a spool backed by the file at `path`, created on first use.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+        }
+    }
+
+    /// Set the number of retries after a failed insert before spooling.
+    #[must_use]
+    pub const fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the delay before the first retry; it doubles on each retry.
+    #[must_use]
+    pub const fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// The spool file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file that lines which do not decode are moved to on drain.
+    pub fn quarantine_path(&self) -> PathBuf {
+        self.path.with_extension("corrupt")
+    }
+
+    /// Insert `events` with [`EventStore::insert_batch`], retrying with
+    /// exponential backoff, and spool them if every attempt fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpoolError::Db`] without retrying if the batch fails
+    /// validation, or [`SpoolError::Io`] or [`SpoolError::Encode`] if it
+    /// cannot be spooled.
+    pub async fn insert(
+        &self,
+        store: &EventStore<'_>,
+        events: &[Event],
+        idempotency_key: Option<&str>,
+    ) -> Result<Persisted, SpoolError> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        let error = loop {
+            match store.insert_batch(events, idempotency_key).await {
+                Ok(report) => return Ok(Persisted::Inserted(report)),
+                Err(e @ DbError::Postgres(_)) if attempt < self.retries => {
+                    tracing::warn!(attempt, error = %e, "Event insert failed, retrying");
+                }
+                Err(e @ DbError::Postgres(_)) => break e,
+                Err(e) => return Err(e.into()),
+            }
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+            attempt = attempt.saturating_add(1);
+        };
+
+        self.append(&SpooledBatch {
+            branch: store.branch().to_owned(),
+            idempotency_key: idempotency_key.map(str::to_owned),
+            events: events.to_vec(),
+        })?;
+        tracing::error!(
+            path = %self.path.display(),
+            events = events.len(),
+            error = %error,
+            "Event insert failed after retries; spooled the batch"
+        );
+        Ok(Persisted::Spooled {
+            error: error.to_string(),
+        })
+    }
+
+    /// Append `batch` to the spool file and flush it to disk.
+    ///
+    /// If a crash left the file's last line without its newline, the line
+    /// is ended first so `batch` is not glued onto it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpoolError::Encode`] if the batch cannot be encoded, or
+    /// [`SpoolError::Io`] if the write fails.
+    pub fn append(&self, batch: &SpooledBatch) -> Result<(), SpoolError> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|source| self.io(source))?;
+        let mut line = Vec::new();
+        if !ends_with_newline(&mut file).map_err(|source| self.io(source))? {
+            tracing::warn!(path = %self.path.display(), "Ending a torn spool line");
+            line.push(b'\n');
+        }
+        serde_json::to_writer(&mut line, batch)?;
+        line.push(b'\n');
+        file.write_all(&line).map_err(|source| self.io(source))?;
+        file.sync_data().map_err(|source| self.io(source))
+    }
+
+    /// The spooled batches, oldest first, and the number of lines that did
+    /// not decode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpoolError::Io`] if the file exists but cannot be read.
+    pub fn pending(&self) -> Result<(Vec<SpooledBatch>, usize), SpoolError> {
+        let (batches, corrupt) = self.read()?;
+        Ok((batches, corrupt.len()))
+    }
+
+    /// The spooled batches, oldest first, and the lines that did not
+    /// decode.
+    fn read(&self) -> Result<(Vec<SpooledBatch>, Vec<String>), SpoolError> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok((Vec::new(), Vec::new()));
+            }
+            Err(e) => return Err(self.io(e)),
+        };
+        let mut batches = Vec::new();
+        let mut corrupt = Vec::new();
+        for line in std::io::BufReader::new(file).lines() {
+            let line = line.map_err(|source| self.io(source))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(batch) => batches.push(batch),
+                Err(e) => {
+                    let path = self.path.display();
+                    tracing::warn!(%path, error = %e, "Skipping torn spool line");
+                    corrupt.push(line);
+                }
+            }
+        }
+        Ok((batches, corrupt))
+    }
+
+    /// Replay every spooled batch through `store` in order, on the branch
+    /// it was spooled from, removing each from the spool once written.
+    ///
+    /// Stops at the first batch that fails to insert, keeping it and every
+    /// later batch for the next drain.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpoolError::Io`] if the spool file cannot be read or
+    /// rewritten, or [`SpoolError::Db`] if a batch fails validation; the
+    /// batches already written are removed from the spool first.
+    pub async fn drain(&self, store: &EventStore<'_>) -> Result<DrainReport, SpoolError> {
+        let (batches, corrupt) = self.read()?;
+        let mut report = DrainReport {
+            corrupt: corrupt.len(),
+            ..DrainReport::default()
+        };
+        if batches.is_empty() && corrupt.is_empty() {
+            return Ok(report);
+        }
+
+        let mut failure = None;
+        for batch in &batches {
+            let store = store.with_branch(&batch.branch);
+            match store.insert_batch(&batch.events, batch.idempotency_key.as_deref()).await {
+                Ok(inserted) => {
+                    report.batches = report.batches.saturating_add(1);
+                    report.inserted = report.inserted.saturating_add(inserted.inserted);
+                }
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+        let remaining = batches.get(report.batches..).unwrap_or_default();
+        report.remaining = remaining.len();
+        self.quarantine(&corrupt)?;
+        self.rewrite(remaining)?;
+
+        match failure {
+            Some(e @ DbError::Postgres(_)) => {
+                tracing::warn!(remaining = report.remaining, error = %e, "Spool drain stopped");
+            }
+            Some(e) => return Err(e.into()),
+            None => {}
+        }
+        if report.batches > 0 {
+            tracing::info!(
+                batches = report.batches,
+                inserted = report.inserted,
+                remaining = report.remaining,
+                "Drained spooled event batches"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Replace the spool's contents with `batches`, or remove the file if
+    /// there are none.
+    fn rewrite(&self, batches: &[SpooledBatch]) -> Result<(), SpoolError> {
+        if batches.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(self.io(e)),
+                _ => Ok(()),
+            };
+        }
+        // Write the survivors beside the spool and rename over it, so a
+        // crash leaves either the old contents or the new.
+        let staging = self.path.with_extension("rewrite");
+        let mut contents = Vec::new();
+        for batch in batches {
+            serde_json::to_writer(&mut contents, batch)?;
+            contents.push(b'\n');
+        }
+        std::fs::write(&staging, contents).map_err(|source| self.io(source))?;
+        std::fs::rename(&staging, &self.path).map_err(|source| self.io(source))
+    }
+
+    /// Append `lines` to the quarantine file, so rewriting the spool does
+    /// not lose them.
+    fn quarantine(&self, lines: &[String]) -> Result<(), SpoolError> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let path = self.quarantine_path();
+        let quarantine_err = |source| SpoolError::Io {
+            path: path.clone(),
+            source,
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(quarantine_err)?;
+        let mut contents = lines.join("\n");
+        contents.push('\n');
+        file.write_all(contents.as_bytes()).map_err(quarantine_err)?;
+        file.sync_data().map_err(quarantine_err)?;
+        tracing::warn!(path = %path.display(), lines = lines.len(), "Quarantined torn spool lines");
+        Ok(())
+    }
+
+    /// An I/O error on the spool file.
+    fn io(&self, source: std::io::Error) -> SpoolError {
+        SpoolError::Io {
+            path: self.path.clone(),
+            source,
+        }
+    }
+}
+
+/// Whether `file` is empty or its last byte is a newline.
+fn ends_with_newline(file: &mut std::fs::File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0_u8];
+    file.read_exact(&mut last)?;
+    Ok(last == [b'\n'])
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::{Era, EventType, Season, Weather, WorldContext};
+
+    use super::*;
+
+    fn batch(key: &str) -> SpooledBatch {
+        let context = WorldContext {
+            tick: 4,
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 1,
+        };
+        SpooledBatch {
+            branch: crate::event_store::MAIN_BRANCH.to_owned(),
+            idempotency_key: Some(key.to_owned()),
+            events: vec![Event::builder(EventType::TickEnd, context).build().unwrap()],
+        }
+    }
+
+    fn spool() -> EventSpool {
+        let name = format!("emergence-spool-{}.jsonl", uuid::Uuid::now_v7());
+        EventSpool::new(std::env::temp_dir().join(name))
+    }
+
+    #[test]
+    fn batches_are_kept_in_order_and_torn_lines_skipped() {
+        let spool = spool();
+        assert_eq!(spool.pending().unwrap(), (Vec::new(), 0));
+
+        let (early, late) = (batch("tick-4"), batch("tick-5"));
+        spool.append(&early).unwrap();
+        spool.append(&late).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(spool.path()).unwrap();
+        file.write_all(b"{\"branch\":\"ma").unwrap();
+
+        let (batches, corrupt) = spool.pending().unwrap();
+        assert_eq!(batches, [early, late]);
+        assert_eq!(corrupt, 1);
+        std::fs::remove_file(spool.path()).unwrap();
+    }
+
+    #[test]
+    fn appending_after_a_torn_write_starts_a_new_line() {
+        let spool = spool();
+        let (early, late) = (batch("tick-4"), batch("tick-5"));
+        spool.append(&early).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(spool.path()).unwrap();
+        file.write_all(b"{\"branch\":\"ma").unwrap();
+        spool.append(&late).unwrap();
+
+        let (batches, corrupt) = spool.read().unwrap();
+        assert_eq!(batches, [early, late.clone()]);
+        assert_eq!(corrupt, ["{\"branch\":\"ma"]);
+
+        // Draining keeps the torn line in quarantine rather than dropping it.
+        spool.quarantine(&corrupt).unwrap();
+        spool.rewrite(std::slice::from_ref(&late)).unwrap();
+        assert_eq!(spool.pending().unwrap(), (vec![late], 0));
+        let quarantined = std::fs::read_to_string(spool.quarantine_path()).unwrap();
+        assert_eq!(quarantined, "{\"branch\":\"ma\n");
+        std::fs::remove_file(spool.path()).unwrap();
+        std::fs::remove_file(spool.quarantine_path()).unwrap();
+    }
+
+    #[test]
+    fn rewriting_keeps_only_the_remaining_batches() {
+        let spool = spool();
+        let (early, late) = (batch("tick-4"), batch("tick-5"));
+        spool.append(&early).unwrap();
+        spool.append(&late).unwrap();
+
+        spool.rewrite(std::slice::from_ref(&late)).unwrap();
+        assert_eq!(spool.pending().unwrap(), (vec![late], 0));
+
+        spool.rewrite(&[]).unwrap();
+        assert!(!spool.path().exists());
+        spool.rewrite(&[]).unwrap();
+    }
+}
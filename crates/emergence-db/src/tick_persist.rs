@@ -14,8 +14,10 @@
 //!   +-- persist_agent_states_to_dragonfly()    --> Dragonfly
 //!   +-- persist_world_state_to_dragonfly()     --> Dragonfly
 //!   +-- persist_events_to_postgres()           --> PostgreSQL events table
-//!   +-- persist_tick_snapshot()                 --> PostgreSQL world_snapshots table
 //! ```
+//!
+//! Each call runs in a `db_flush` span tagged with the store and table, and
+//! its duration is recorded in [`metrics::FLUSH_LATENCY_MS`].
 
 use std::collections::BTreeMap;
 
@@ -25,7 +27,8 @@ use sqlx::PgPool;
 use crate::dragonfly::DragonflyPool;
 use crate::error::DbError;
 use crate::event_store::EventStore;
-use crate::snapshot_store::SnapshotStore;
+use crate::metrics;
+use crate::spool::{EventSpool, Persisted};
 
 // =========================================================================
 // Error type
@@ -67,11 +70,18 @@ pub enum PersistError {
 ///
 /// Returns [`PersistError::Dragonfly`] if any write to `Dragonfly` fails.
 /// Returns [`PersistError::Serialization`] if agent state serialization fails.
+#[tracing::instrument(
+    name = "db_flush",
+    skip_all,
+    fields(tick, store = "dragonfly", table = "agent_states", rows = agent_states.len())
+)]
 pub async fn persist_agent_states_to_dragonfly(
     dragonfly: &DragonflyPool,
     agent_states: &BTreeMap<AgentId, AgentState>,
     tick: u64,
 ) -> Result<(), PersistError> {
+    let _timer = metrics::FLUSH_LATENCY_MS.start_timer("agent_states");
+
     // Build all key-value pairs for batch MSET.
     let keys: Vec<String> = agent_states
         .keys()
@@ -114,6 +124,11 @@ pub async fn persist_agent_states_to_dragonfly(
 /// # Errors
 ///
 /// Returns [`PersistError::Dragonfly`] if any write to `Dragonfly` fails.
+#[tracing::instrument(
+    name = "db_flush",
+    skip_all,
+    fields(tick, store = "dragonfly", table = "world_state")
+)]
 pub async fn persist_world_state_to_dragonfly(
     dragonfly: &DragonflyPool,
     tick: u64,
@@ -121,6 +136,8 @@ pub async fn persist_world_state_to_dragonfly(
     season: Season,
     weather: Weather,
 ) -> Result<(), PersistError> {
+    let _timer = metrics::FLUSH_LATENCY_MS.start_timer("world_state");
+
     dragonfly.set_world_tick(tick).await?;
 
     let season_str = format!("{season:?}");
@@ -150,22 +167,78 @@ pub async fn persist_world_state_to_dragonfly(
 /// Batch insert tick events to `PostgreSQL` from action results.
 ///
 /// Converts each [`ActionResult`] into an [`emergence_types::Event`] and
-/// delegates to the existing [`EventStore::batch_insert`] method. Events
-/// record the permanent history of agent actions.
+/// writes them with [`EventStore::insert_batch`] under a key derived from
+/// the tick, so retrying a failed persist of the same tick does not insert
+/// its events twice. Events record the permanent history of agent actions.
+///
+/// With a `spool`, batches spooled by earlier ticks are drained first, and
+/// a batch that still fails after the spool's retries is appended to it
+/// instead of being dropped.
 ///
 /// # Errors
 ///
-/// Returns [`PersistError::Postgres`] if the batch insert fails.
+/// Returns [`PersistError::Postgres`] if the batch insert fails, or, with
+/// a `spool`, if the batch can be neither inserted nor spooled.
 /// Returns [`PersistError::Serialization`] if event construction fails.
+#[tracing::instrument(
+    name = "db_flush",
+    skip_all,
+    fields(tick, store = "postgres", table = "events", rows = action_results.len())
+)]
 pub async fn persist_events_to_postgres(
     pool: &PgPool,
     tick: u64,
     action_results: &BTreeMap<AgentId, ActionResult>,
+    spool: Option<&EventSpool>,
 ) -> Result<(), PersistError> {
+    let _timer = metrics::FLUSH_LATENCY_MS.start_timer("events");
+
     if action_results.is_empty() {
         return Ok(());
     }
 
+    let events = action_events(tick, action_results)?;
+
+    let store = EventStore::new(pool);
+    let key = format!("tick-{tick}-actions");
+    let report = match spool {
+        Some(spool) => {
+            let spooled = |e| PersistError::Postgres(format!("Event spool failed: {e}"));
+            spool.drain(&store).await.map_err(spooled)?;
+            match spool.insert(&store, &events, Some(&key)).await.map_err(spooled)? {
+                Persisted::Inserted(report) => report,
+                Persisted::Spooled { .. } => return Ok(()),
+            }
+        }
+        None => store
+            .insert_batch(&events, Some(&key))
+            .await
+            .map_err(|e| PersistError::Postgres(format!("Event batch insert failed: {e}")))?,
+    };
+
+    tracing::debug!(
+        tick,
+        events = report.inserted,
+        deduplicated = report.deduplicated,
+        "Persisted events to PostgreSQL"
+    );
+
+    Ok(())
+}
+
+/// Build the history events for one tick's action results.
+///
+/// This is the CPU side of [`persist_events_to_postgres`], split out so it
+/// can be benchmarked and tested without a database.
+///
+/// # Errors
+///
+/// Returns [`PersistError::Serialization`] if an action result fails to
+/// serialize.
+pub fn action_events(
+    tick: u64,
+    action_results: &BTreeMap<AgentId, ActionResult>,
+) -> Result<Vec<emergence_types::Event>, PersistError> {
     let mut events = Vec::with_capacity(action_results.len());
     let now = chrono::Utc::now();
 
@@ -198,84 +271,13 @@ pub async fn persist_events_to_postgres(
             agent_state_snapshot: None,
             world_context,
             created_at: now,
+            caused_by: None,
+            correlation_id: None,
         };
         events.push(event);
     }
 
-    let store = EventStore::new(pool);
-    store
-        .batch_insert(&events)
-        .await
-        .map_err(|e| PersistError::Postgres(format!("Event batch insert failed: {e}")))?;
-
-    tracing::debug!(
-        tick,
-        events = events.len(),
-        "Persisted events to PostgreSQL"
-    );
-
-    Ok(())
-}
-
-/// Persist a tick summary as a world snapshot to `PostgreSQL`.
-///
-/// Writes a row to the `world_snapshots` table via [`SnapshotStore`].
-/// This captures population, deaths, action counts, and environment
-/// state at the end of each tick.
-///
-/// # Errors
-///
-/// Returns [`PersistError::Postgres`] if the snapshot insert fails.
-/// Returns [`PersistError::Serialization`] if summary serialization fails.
-pub async fn persist_tick_snapshot(
-    pool: &PgPool,
-    tick: u64,
-    season: Season,
-    weather: Weather,
-    agents_alive: u32,
-    deaths_count: u32,
-    action_results_count: u32,
-) -> Result<(), PersistError> {
-    let store = SnapshotStore::new(pool);
-
-    let season_str = format!("{season:?}");
-    let weather_str = format!("{weather:?}");
-
-    let agents_alive_i32 =
-        i32::try_from(agents_alive).unwrap_or(i32::MAX);
-    let deaths_i32 = i32::try_from(deaths_count).unwrap_or(i32::MAX);
-    let actions_i32 =
-        i32::try_from(action_results_count).unwrap_or(i32::MAX);
-
-    let total_resources = serde_json::Value::Object(serde_json::Map::new());
-    let wealth_distribution = serde_json::Value::Object(serde_json::Map::new());
-    let summary_json = serde_json::json!({
-        "tick": tick,
-        "deaths": deaths_count,
-        "actions_resolved": action_results_count,
-    });
-
-    store
-        .insert_world_snapshot(
-            tick,
-            "primitive",
-            &season_str,
-            &weather_str,
-            agents_alive_i32,
-            0, // births -- not tracked in TickSummary yet
-            deaths_i32,
-            &total_resources,
-            &wealth_distribution,
-            actions_i32,
-            0, // discoveries -- not tracked in TickSummary yet
-            &summary_json,
-        )
-        .await
-        .map_err(|e| PersistError::Postgres(format!("Snapshot insert failed: {e}")))?;
-
-    tracing::debug!(tick, "Persisted tick snapshot to PostgreSQL");
-
-    Ok(())
+    Ok(events)
 }
 
 #[cfg(test)]
@@ -296,4 +298,38 @@ mod tests {
         let msg = format!("{persist_err}");
         assert!(msg.contains("world:tick"));
     }
+
+    #[test]
+    fn action_events_follow_success() {
+        let mut results = BTreeMap::new();
+        for success in [true, false] {
+            let agent_id = AgentId::new();
+            results.insert(agent_id, ActionResult {
+                tick: 7,
+                agent_id,
+                action_type: emergence_types::ActionType::Rest,
+                success,
+                outcome: None,
+                rejection: None,
+                side_effects: Vec::new(),
+            });
+        }
+
+        let events = action_events(7, &results).unwrap_or_default();
+        assert_eq!(events.len(), 2);
+        for event in &events {
+            let success = event
+                .agent_id
+                .and_then(|id| results.get(&id))
+                .is_some_and(|r| r.success);
+            let expected = if success {
+                emergence_types::EventType::ActionSucceeded
+            } else {
+                emergence_types::EventType::ActionRejected
+            };
+            assert_eq!(event.event_type, expected);
+            assert_eq!(event.tick, 7);
+            assert!(crate::event_store::validate_details(event).is_ok());
+        }
+    }
 }
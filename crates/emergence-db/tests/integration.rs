@@ -25,12 +25,14 @@
 
 use chrono::Utc;
 use emergence_db::{
-    AgentSnapshotRow, DbError, DragonflyPool, EventRow, EventStore, LedgerRow, LedgerStore,
-    PostgresConfig, PostgresPool, SnapshotStore, WorldSnapshotRow,
+    AgentSnapshotRow, BranchStore, ChainBreakKind, DbError, DragonflyPool, EventRow, EventStore,
+    LedgerRow, LedgerStore, PostgresConfig, PostgresPool, ProjectionStore, SnapshotStore,
+    WorldSnapshotRow, MAIN_BRANCH,
 };
 use emergence_types::{
-    AgentId, AgentStateSnapshot, EntityType, Event, EventId, EventType, LedgerEntry,
-    LedgerEntryId, LedgerEntryType, LocationId, Resource, WorldContext,
+    AgentId, AgentStateSnapshot, Comparison, CorrelationId, DetailPredicate, EntityType, Event,
+    EventFilter, EventId, EventType, LedgerEntry, LedgerEntryId, LedgerEntryType, LedgerSnapshot,
+    LedgerTag, LocationId, OpeningBalance, Resource, ResourceGatheredDetails, TradeId, WorldContext,
 };
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -411,6 +413,8 @@ async fn event_store_batch_insert_and_query() {
             agent_state_snapshot: None,
             world_context: world_ctx.clone(),
             created_at: now,
+            caused_by: None,
+            correlation_id: None,
         },
         Event {
             id: EventId::new(),
@@ -418,10 +422,13 @@ async fn event_store_batch_insert_and_query() {
             event_type: EventType::ResourceGathered,
             agent_id: Some(agent_id),
             location_id: Some(location_id),
-            details: serde_json::json!({
-                "resource": "water",
-                "quantity": 5
-            }),
+            details: serde_json::to_value(ResourceGatheredDetails {
+                resource: Resource::Water,
+                quantity: 5,
+                location_id,
+                skill_xp_gained: 1,
+            })
+            .expect("Failed to encode details"),
             agent_state_snapshot: Some(AgentStateSnapshot {
                 energy: 80,
                 health: 100,
@@ -432,6 +439,8 @@ async fn event_store_batch_insert_and_query() {
             }),
             world_context: world_ctx.clone(),
             created_at: now,
+            caused_by: None,
+            correlation_id: None,
         },
         Event {
             id: EventId::new(),
@@ -443,6 +452,8 @@ async fn event_store_batch_insert_and_query() {
             agent_state_snapshot: None,
             world_context: world_ctx,
             created_at: now,
+            caused_by: None,
+            correlation_id: None,
         },
     ];
 
@@ -468,6 +479,32 @@ async fn event_store_batch_insert_and_query() {
     assert_eq!(agent_rows.len(), 1);
     assert_eq!(agent_rows[0].event_type, "resource_gathered");
 
+    // A batch with malformed details is rejected before anything is written.
+    let mut malformed = events[1].clone();
+    malformed.details = serde_json::json!({"resource": "water"});
+    let err = store
+        .batch_insert(&[events[0].clone(), malformed])
+        .await
+        .expect_err("Malformed details should be rejected");
+    assert!(matches!(err, DbError::InvalidEvent { event_type: "resource_gathered", .. }));
+    let rows = store.get_events_by_tick(9999).await.expect("Failed to query");
+    assert_eq!(rows.len(), 3);
+
+    // A write retried under the same idempotency key inserts nothing new.
+    let key = "integration-tick-9999";
+    let first = store
+        .insert_batch(&events, Some(key))
+        .await
+        .expect("Failed to insert keyed batch");
+    assert_eq!((first.inserted, first.deduplicated), (3, 0));
+    let retry = store
+        .insert_batch(&events, Some(key))
+        .await
+        .expect("Failed to retry keyed batch");
+    assert_eq!((retry.inserted, retry.deduplicated), (0, 3));
+    let rows = store.get_events_by_tick(9999).await.expect("Failed to query");
+    assert_eq!(rows.len(), 6);
+
     // Clean up
     sqlx::query("DELETE FROM events WHERE tick = 9999")
         .execute(pg)
@@ -477,6 +514,250 @@ async fn event_store_batch_insert_and_query() {
     pool.close().await;
 }
 
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn event_store_causal_chain() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+
+    sqlx::query("DELETE FROM events WHERE tick = 9998")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    let world_ctx = WorldContext {
+        tick: 9998,
+        era: emergence_types::Era::Primitive,
+        season: emergence_types::Season::Spring,
+        weather: emergence_types::Weather::Clear,
+        population: 10,
+    };
+    // A three-event cascade. The types take any details, so the events
+    // need no payload to pass validation.
+    let correlation = CorrelationId::new();
+    let famine = Event::builder(EventType::WeatherChanged, world_ctx.clone())
+        .correlation(correlation)
+        .build()
+        .expect("Failed to build cause");
+    let rule = Event::builder(EventType::TickEnd, world_ctx.clone())
+        .caused_by(famine.id)
+        .correlation(correlation)
+        .build()
+        .expect("Failed to build rule");
+    let enforcement = Event::builder(EventType::TickEnd, world_ctx)
+        .caused_by(rule.id)
+        .correlation(correlation)
+        .build()
+        .expect("Failed to build effect");
+
+    let store = EventStore::new(pg);
+    store
+        .batch_insert(&[famine.clone(), rule.clone(), enforcement.clone()])
+        .await
+        .expect("Failed to insert chain");
+
+    let chain = store
+        .get_causal_chain(enforcement.id.into_inner())
+        .await
+        .expect("Failed to walk chain");
+    let ids: Vec<_> = chain.iter().filter_map(|r| r.event_id).collect();
+    assert_eq!(ids, [famine.id, rule.id, enforcement.id].map(EventId::into_inner));
+
+    let effects = store
+        .get_consequences(famine.id.into_inner())
+        .await
+        .expect("Failed to find consequences");
+    assert_eq!(effects.len(), 2);
+
+    let cascade = store
+        .get_events_by_correlation(correlation.into_inner())
+        .await
+        .expect("Failed to query cascade");
+    assert_eq!(cascade.len(), 3);
+
+    sqlx::query("DELETE FROM events WHERE tick = 9998")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    pool.close().await;
+}
+
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn event_store_hash_chain_detects_edits() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+
+    sqlx::query("DELETE FROM events WHERE tick = 9997")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    let world_ctx = WorldContext {
+        tick: 9997,
+        era: emergence_types::Era::Primitive,
+        season: emergence_types::Season::Spring,
+        weather: emergence_types::Weather::Clear,
+        population: 10,
+    };
+    let events: Vec<Event> = (0..3)
+        .map(|n| {
+            Event::builder(EventType::TickEnd, world_ctx.clone())
+                .details(serde_json::json!({"n": n, "rate": 0.1}))
+                .build()
+                .expect("Failed to build event")
+        })
+        .collect();
+
+    let store = EventStore::new(pg).with_hash_chain(true);
+    store
+        .insert_batch(&events, Some("integration-chain"))
+        .await
+        .expect("Failed to insert chained batch");
+    // A retried write neither duplicates events nor forks the chain.
+    store
+        .insert_batch(&events, Some("integration-chain"))
+        .await
+        .expect("Failed to retry chained batch");
+
+    let report = store.verify_chain(9997, 9998).await.expect("Failed to verify");
+    assert!(report.is_intact(), "{report:?}");
+    assert_eq!(report.events, 3);
+    assert!(report.head.is_some());
+
+    sqlx::query(
+        r#"UPDATE events SET details = '{"n": 7, "rate": 0.1}'
+           WHERE tick = 9997 AND details->>'n' = '1'"#,
+    )
+    .execute(pg)
+    .await
+    .expect("Failed to tamper with event");
+    let report = store.verify_chain(9997, 9998).await.expect("Failed to verify");
+    assert_eq!(report.breaks.len(), 1);
+    assert_eq!(report.breaks[0].kind, ChainBreakKind::Tampered);
+
+    sqlx::query("DELETE FROM events WHERE tick = 9997")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    pool.close().await;
+}
+
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn event_store_query_by_filter() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+
+    sqlx::query("DELETE FROM events WHERE tick = 9996")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    let world_ctx = WorldContext {
+        tick: 9996,
+        era: emergence_types::Era::Primitive,
+        season: emergence_types::Season::Spring,
+        weather: emergence_types::Weather::Clear,
+        population: 10,
+    };
+    let events: Vec<Event> = [("Alpha", 1), ("Beta", 5), ("Gamma", 9)]
+        .into_iter()
+        .map(|(name, n)| {
+            Event::builder(EventType::TickEnd, world_ctx.clone())
+                .details(serde_json::json!({"name": name, "stats": {"n": n}}))
+                .build()
+                .expect("Failed to build event")
+        })
+        .collect();
+    let store = EventStore::new(pg);
+    store.batch_insert(&events).await.expect("Failed to insert events");
+
+    let filter = EventFilter::new()
+        .with_tick(9996)
+        .with_event_type(EventType::TickEnd)
+        .with_detail(DetailPredicate::new("stats.n", Comparison::Ge, 5));
+    let rows = store.query(&filter, None).await.expect("Failed to query");
+    assert_eq!(rows.len(), 2);
+    // The same filter selects the same events in memory.
+    assert_eq!(events.iter().filter(|event| filter.matches(event)).count(), 2);
+
+    let filter = filter.with_detail(DetailPredicate::new("name", Comparison::Ne, "Beta"));
+    let rows = store.query(&filter, Some(5)).await.expect("Failed to query");
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].details["name"], "Gamma");
+
+    sqlx::query("DELETE FROM events WHERE tick = 9996")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up test events");
+
+    pool.close().await;
+}
+
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn event_store_branches_share_history_before_the_fork() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+
+    let cleanup = || async {
+        sqlx::query("DELETE FROM events WHERE tick IN (9994, 9995)")
+            .execute(pg)
+            .await
+            .expect("Failed to clean up test events");
+        sqlx::query("DELETE FROM event_branches WHERE name = 'test-what-if'")
+            .execute(pg)
+            .await
+            .expect("Failed to clean up test branch");
+    };
+    cleanup().await;
+
+    let tick_end = |tick: u64, label: &str| {
+        let world_ctx = WorldContext {
+            tick,
+            era: emergence_types::Era::Primitive,
+            season: emergence_types::Season::Spring,
+            weather: emergence_types::Weather::Clear,
+            population: 10,
+        };
+        Event::builder(EventType::TickEnd, world_ctx)
+            .details(serde_json::json!({"label": label}))
+            .build()
+            .expect("Failed to build event")
+    };
+    let main = EventStore::new(pg);
+    main.batch_insert(&[tick_end(9994, "shared"), tick_end(9995, "original")])
+        .await
+        .expect("Failed to insert main events");
+
+    let branches = BranchStore::new(pg);
+    let branch = branches.create("test-what-if", MAIN_BRANCH, 9995).await.expect("Failed to fork");
+    assert_eq!(branch.fork_tick, Some(9995));
+    assert!(matches!(
+        branches.create("test-orphan", "no-such-branch", 1).await,
+        Err(DbError::UnknownBranch(name)) if name == "no-such-branch"
+    ));
+
+    let what_if = EventStore::new(pg).with_branch("test-what-if");
+    what_if.batch_insert(&[tick_end(9995, "injected")]).await.expect("Failed to insert on branch");
+
+    let labels = |rows: Vec<EventRow>| -> Vec<String> {
+        rows.iter()
+            .map(|row| row.details["label"].as_str().unwrap_or_default().to_owned())
+            .collect()
+    };
+    let on_branch = what_if.get_events_in_range(9994, 9996).await.expect("Failed to query branch");
+    assert_eq!(labels(on_branch), ["shared", "injected"]);
+    let on_main = main.get_events_in_range(9994, 9996).await.expect("Failed to query main");
+    assert_eq!(labels(on_main), ["shared", "original"]);
+
+    cleanup().await;
+    pool.close().await;
+}
+
 #[tokio::test]
 #[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
 async fn event_store_empty_batch() {
@@ -514,6 +795,7 @@ async fn ledger_store_batch_insert_and_query() {
     let agent_id = Uuid::now_v7();
     let location_id = Uuid::now_v7();
     let now = Utc::now();
+    let trade = LedgerTag::Trade(TradeId::new());
 
     let entries = vec![
         LedgerEntry {
@@ -528,6 +810,7 @@ async fn ledger_store_batch_insert_and_query() {
             quantity: Decimal::new(10, 0),
             reason: "REGEN".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
             created_at: now,
         },
         LedgerEntry {
@@ -542,6 +825,7 @@ async fn ledger_store_batch_insert_and_query() {
             quantity: Decimal::new(5, 0),
             reason: "GATHER".to_owned(),
             reference_id: None,
+            tags: vec![trade],
             created_at: now,
         },
         LedgerEntry {
@@ -556,6 +840,7 @@ async fn ledger_store_batch_insert_and_query() {
             quantity: Decimal::new(2, 0),
             reason: "DRINK".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
             created_at: now,
         },
     ];
@@ -586,6 +871,14 @@ async fn ledger_store_batch_insert_and_query() {
         .expect("Failed to query ledger by entity");
     assert_eq!(entity_rows.len(), 2);
 
+    // Query by tag (only the gather was tagged)
+    let tagged_rows: Vec<LedgerRow> = store
+        .get_entries_by_tag(trade)
+        .await
+        .expect("Failed to query ledger by tag");
+    assert_eq!(tagged_rows.len(), 1);
+    assert_eq!(tagged_rows[0].tags.0, vec![trade]);
+
     // Clean up
     sqlx::query("DELETE FROM ledger WHERE tick = 9998")
         .execute(pg)
@@ -638,6 +931,7 @@ async fn ledger_store_custom_batch_size() {
             quantity: Decimal::new(i64::from(i) + 1, 0),
             reason: format!("REGEN_{i}"),
             reference_id: None,
+            tags: Vec::new(),
             created_at: now,
         })
         .collect();
@@ -808,6 +1102,64 @@ async fn snapshot_store_recent_world_snapshots() {
     pool.close().await;
 }
 
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn snapshot_store_ledger_snapshot_roundtrip() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+
+    // Clean up test range
+    sqlx::query("DELETE FROM ledger_snapshots WHERE tick BETWEEN 9980 AND 9989")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up");
+
+    let store = SnapshotStore::new(pg);
+
+    let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+    let opening = |entity_id, entity_type, balance| OpeningBalance {
+        entity_id,
+        entity_type: Some(entity_type),
+        resource: Resource::Wood,
+        balance: Decimal::new(balance, 0),
+    };
+    for tick in [9980, 9985] {
+        let snapshot = LedgerSnapshot {
+            tick,
+            balances: vec![
+                opening(location, EntityType::Location, -7),
+                opening(agent, EntityType::Agent, 7),
+            ],
+            currencies: Vec::new(),
+            loans: Vec::new(),
+        };
+        store.insert_ledger_snapshot(&snapshot).await.expect("Failed to insert snapshot");
+    }
+
+    let latest = store
+        .get_ledger_snapshot_at_or_before(9989)
+        .await
+        .expect("Failed to query ledger snapshot")
+        .expect("Snapshot should exist");
+    assert_eq!(latest.tick, 9985);
+    assert_eq!(latest.balances.len(), 2);
+    assert_eq!(latest.balances[1], opening(agent, EntityType::Agent, 7));
+
+    let earlier = store
+        .get_ledger_snapshot_at_or_before(9984)
+        .await
+        .expect("Failed to query ledger snapshot");
+    assert_eq!(earlier.map(|s| s.tick), Some(9980));
+
+    // Clean up
+    sqlx::query("DELETE FROM ledger_snapshots WHERE tick BETWEEN 9980 AND 9989")
+        .execute(pg)
+        .await
+        .expect("Failed to clean up");
+
+    pool.close().await;
+}
+
 #[tokio::test]
 #[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
 async fn snapshot_store_agent_snapshot_roundtrip() {
@@ -895,6 +1247,18 @@ async fn snapshot_store_agent_snapshot_roundtrip() {
         .expect("Failed to query latest");
     assert_eq!(latest2.expect("should exist").tick, 300);
 
+    // Latest at or before tick 250 should be tick 200
+    let at_250: Vec<AgentSnapshotRow> = store
+        .get_agent_snapshots_at_or_before(250)
+        .await
+        .expect("Failed to query agent snapshots at tick");
+    let ours: Vec<i64> = at_250
+        .iter()
+        .filter(|row| row.agent_id == agent_uuid)
+        .map(|row| row.tick)
+        .collect();
+    assert_eq!(ours, vec![200]);
+
     // Clean up (snapshots first due to FK)
     sqlx::query("DELETE FROM agent_snapshots WHERE agent_id = $1")
         .bind(agent_uuid)
@@ -978,6 +1342,45 @@ async fn snapshot_store_batch_insert_agent_snapshots() {
     pool.close().await;
 }
 
+// =============================================================================
+// Projection Store Tests
+// =============================================================================
+
+#[tokio::test]
+#[ignore = "requires live PostgreSQL instance (docker compose up -d)"]
+async fn projection_store_checkpoint_roundtrip() {
+    let pool = setup_postgres().await;
+    let pg = pool.pool();
+    let store = ProjectionStore::new(pg);
+    let name = "integration_test_projection";
+
+    store
+        .save_checkpoint(name, 10, &serde_json::json!({"count": 1}))
+        .await
+        .expect("Failed to save checkpoint");
+    store
+        .save_checkpoint(name, 20, &serde_json::json!({"count": 2}))
+        .await
+        .expect("Failed to replace checkpoint");
+
+    let row = store
+        .load_checkpoint(name)
+        .await
+        .expect("Failed to load checkpoint")
+        .expect("Checkpoint should exist");
+    assert_eq!(row.tick, 20);
+    assert_eq!(row.state, serde_json::json!({"count": 2}));
+    assert!(store.load_checkpoint("missing").await.expect("query").is_none());
+
+    sqlx::query("DELETE FROM projection_checkpoints WHERE name = $1")
+        .bind(name)
+        .execute(pg)
+        .await
+        .expect("Failed to clean up checkpoint");
+
+    pool.close().await;
+}
+
 // =============================================================================
 // Cross-Store Tests
 // =============================================================================
@@ -1057,6 +1460,8 @@ async fn full_tick_data_flow() {
             agent_state_snapshot: None,
             world_context: world_ctx,
             created_at: Utc::now(),
+            caused_by: None,
+            correlation_id: None,
         }])
         .await
         .expect("Failed to insert events");
@@ -1076,6 +1481,7 @@ async fn full_tick_data_flow() {
             quantity: Decimal::new(10, 0),
             reason: "REGEN".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
             created_at: Utc::now(),
         }])
         .await
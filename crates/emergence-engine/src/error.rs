@@ -41,6 +41,14 @@ pub enum EngineError {
         source: emergence_core::runner::RunnerError,
     },
 
+    /// A configured WASM plugin failed to load.
+    #[error("plugin error: {source}")]
+    Plugin {
+        /// The underlying plugin error.
+        #[from]
+        source: emergence_plugins::PluginError,
+    },
+
     /// NATS connection or messaging failed.
     #[error("NATS error: {message}")]
     Nats {
@@ -7,42 +7,57 @@
 //!
 //! # Startup Sequence
 //!
-//! 1. Initialize structured logging (tracing)
+//! 1. Initialize structured logging and OTLP trace export (tracing)
 //! 2. Load configuration from `emergence-config.yaml`
 //! 3. Create world clock from time config
 //! 4. Create starting world map (12 locations, 17 routes)
 //! 5. Spawn seed agents across locations
 //! 6. Connect to NATS and create decision source
 //! 7. Create operator state from simulation bounds
-//! 8. Run the simulation loop
-//! 9. Log the result
+//! 8. Load WASM plugins, if any are configured
+//! 9. Connect to `PostgreSQL` for periodic state snapshots
+//! 10. Run the simulation loop
+//! 11. Log the result
 
 mod error;
 mod nats_decision;
 mod observer_callback;
+mod snapshots;
 mod spawner;
 
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
 use emergence_agents::actions::conflict::ConflictStrategy;
 use emergence_agents::config::VitalsConfig;
+use emergence_agents::{DiplomacyState, FamilyTracker, OrderBook};
 use emergence_core::clock::WorldClock;
 use emergence_core::config::SimulationConfig;
 use emergence_core::operator::OperatorState;
 use emergence_core::runner;
+use emergence_core::scratch::TickScratch;
+use emergence_core::subscribers::EventSubscribers;
 use emergence_core::tick::SimulationState;
+use emergence_ledger::Ledger;
 use emergence_observer::state::AppState;
-use emergence_world::WeatherSystem;
+use emergence_plugins::PluginHost;
+use emergence_world::{
+    DisasterSystem, FaunaRegistry, FireSystem, KnownMapRegistry, MineRegistry, TradeNetwork,
+    WaterRegistry, WeatherSystem, WorldMap,
+};
 use tracing::info;
-use tracing_subscriber::EnvFilter;
 
 use crate::error::EngineError;
 use crate::nats_decision::NatsDecisionSource;
 use crate::observer_callback::ObserverCallback;
+use crate::snapshots::TickSnapshots;
 use crate::spawner::SpawnerConfig;
 
+/// Most decision records folded into one observer snapshot update.
+const DECISION_BATCH: usize = 256;
+
 /// Application entry point for the World Engine.
 ///
 /// Initializes all subsystems and runs the simulation loop. Returns
@@ -54,15 +69,10 @@ use crate::spawner::SpawnerConfig;
 #[tokio::main]
 #[allow(clippy::too_many_lines)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 1. Initialize structured logging.
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_target(true)
-        .init();
+    // 1. Initialize structured logging and, if configured, trace export.
+    let telemetry = emergence_telemetry::init("emergence-engine")?;
 
-    info!("emergence-engine starting");
+    info!(otlp = telemetry.exporting(), "emergence-engine starting");
 
     // 2. Load configuration.
     let config = load_config()?;
@@ -78,13 +88,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let clock = WorldClock::new(&config.time)?;
     info!("World clock initialized");
 
-    // 4. Create starting world map.
-    let (mut world_map, location_ids) = emergence_world::create_starting_world()?;
-    info!(
-        location_count = world_map.location_count(),
-        first_location = %location_ids.riverbank,
-        "Starting world created"
-    );
+    // 4. Create starting world map, loaded from a world file or generated
+    //    from the seed if configured.
+    let (mut world_map, structures) = if let Some(path) = &config.map.file {
+        info!(path = path, "Loading world file");
+        let loaded = WorldMap::from_file(Path::new(path))?;
+        info!(
+            structures = loaded.structures.len(),
+            planted_farms = loaded.farms.active_count(),
+            "World file loaded"
+        );
+        (loaded.map, loaded.structures)
+    } else if config.map.procedural {
+        let params = config.map.gen_params(config.world.seed);
+        info!(
+            location_count = params.location_count,
+            region_count = params.region_count,
+            "Generating world map"
+        );
+        (emergence_world::generate_world(&params)?, BTreeMap::new())
+    } else {
+        let (world_map, location_ids) = emergence_world::create_starting_world()?;
+        info!(first_location = %location_ids.riverbank, "Using fixed starting map");
+        (world_map, BTreeMap::new())
+    };
+    world_map.set_regen_curves(config.environment.regeneration.clone());
+    info!(location_count = world_map.location_count(), "Starting world created");
 
     // 5. Spawn seed agents.
     let spawner_config = load_spawner_config()?;
@@ -136,59 +165,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         })?;
     info!(port = observer_port, "Observer API server started");
 
-    // 8b. Subscribe to decision records from the runner.
+    // 8b. Subscribe to decision records, cost metrics, and containment
+    //     quarantine events from the runner.
     //     Uses a separate NATS connection so the decision collector runs
     //     independently from the tick-cycle decision source.
     {
-        let decisions_state = Arc::clone(&app_state);
         match async_nats::connect(nats_url).await {
             Ok(decisions_client) => {
-                match decisions_client
-                    .subscribe("emergence.decisions.>".to_owned())
-                    .await
-                {
-                    Ok(mut sub) => {
-                        tokio::spawn(async move {
-                            use emergence_observer::state::MAX_DECISIONS;
-                            use futures::StreamExt as _;
-                            while let Some(msg) = sub.next().await {
-                                match serde_json::from_slice::<
-                                    emergence_types::DecisionRecord,
-                                >(&msg.payload)
-                                {
-                                    Ok(record) => {
-                                        if let Ok(mut snap) =
-                                            decisions_state.snapshot.try_write()
-                                        {
-                                            snap.decisions.push(record);
-                                            if snap.decisions.len() > MAX_DECISIONS {
-                                                let drain_count = snap
-                                                    .decisions
-                                                    .len()
-                                                    .saturating_sub(MAX_DECISIONS);
-                                                snap.decisions.drain(..drain_count);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!(
-                                            error = %e,
-                                            "failed to deserialize decision record"
-                                        );
-                                    }
-                                }
-                            }
-                        });
-                        info!("Decision record collector started");
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            error = %e,
-                            "failed to subscribe to decision records, \
-                             decision logging disabled"
-                        );
-                    }
-                }
+                spawn_runner_metrics_collector(&decisions_client, Arc::clone(&app_state)).await;
+                spawn_quarantine_collector(&decisions_client, Arc::clone(&app_state)).await;
+                spawn_decision_collector(&decisions_client, Arc::clone(&app_state)).await;
             }
             Err(e) => {
                 tracing::warn!(
@@ -202,24 +188,74 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 9. Assemble simulation state.
     let weather_seed = config.world.seed;
+    let fauna = FaunaRegistry::from_map(&world_map);
+    let waters = WaterRegistry::from_map(&world_map)
+        .with_irrigation_yield_pct(config.environment.irrigation_yield_pct);
+    let mines = MineRegistry::from_map(&world_map);
+    let mut family = FamilyTracker::new();
+    for agent_id in &spawn_result.alive_agents {
+        family.register_alive(*agent_id);
+    }
     let mut sim_state = SimulationState {
         clock,
         world_map,
-        weather_system: WeatherSystem::new(weather_seed),
+        weather_system: WeatherSystem::new(weather_seed)
+            .with_climate(config.environment.climate.climate()),
         agents: spawn_result.agents,
         agent_names: spawn_result.agent_names,
-        agent_states: spawn_result.agent_states,
+        agent_states: spawn_result.agent_states.into_values().collect(),
         alive_agents: spawn_result.alive_agents,
         vitals_config: VitalsConfig::default(),
         conflict_strategy: ConflictStrategy::FirstComeFirstServed,
         injected_events: Vec::new(),
         active_plagues: Vec::new(),
         active_resource_booms: Vec::new(),
+        disasters: DisasterSystem::new(
+            weather_seed,
+            config.environment.disaster_chance_per_million,
+        ),
+        fires: FireSystem::new(weather_seed),
+        fauna,
+        waters,
+        mines,
+        known_maps: KnownMapRegistry::new(),
+        trade_network: TradeNetwork::new(),
+        structures,
+        family,
+        ledger: Ledger::new(),
+        orders: OrderBook::new(),
+        groups: BTreeMap::new(),
+        diplomacy: DiplomacyState::new(),
+        hooks: None,
+        scratch: TickScratch::new(),
+        subscribers: EventSubscribers::new(),
     };
 
+    // 9a. Load WASM plugins for custom mechanics.
+    if !config.plugins.modules.is_empty() {
+        let host = PluginHost::from_config(&config.plugins)?;
+        info!(plugins = host.registrations().len(), "WASM plugins loaded");
+        sim_state.hooks = Some(Arc::new(host));
+    }
+
+    // 9b. Connect to PostgreSQL for periodic state snapshots.
     let mut callback = ObserverCallback::new(app_state);
+    let postgres_url = &config.infrastructure.postgres_url;
+    match emergence_db::PostgresPool::connect_url(postgres_url).await {
+        Ok(pool) => {
+            let interval_ticks = config.logging.snapshot_interval_ticks;
+            callback = callback.with_snapshots(TickSnapshots::new(pool, interval_ticks));
+            info!(interval_ticks, "State snapshots enabled");
+        }
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "failed to connect to PostgreSQL, state snapshots disabled"
+            );
+        }
+    }
 
-    // 9b. Create spawn handler for mid-simulation agent injection.
+    // 9c. Create spawn handler for mid-simulation agent injection.
     let mut spawn_handler =
         spawner::EngineSpawnHandler::new(spawner_config.seed_knowledge.clone());
     let min_population = config.simulation.min_population;
@@ -248,10 +284,150 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         total_ticks = result.total_ticks,
         "emergence-engine shutdown complete"
     );
+    telemetry.shutdown();
 
     Ok(())
 }
 
+/// Collect the runners' decision records into the observer snapshot.
+///
+/// Records that arrive together are published as one snapshot update, so
+/// a burst of decisions costs one snapshot copy rather than one per record.
+/// Subscription failures are logged and leave the observer without
+/// decision records.
+async fn spawn_decision_collector(client: &async_nats::Client, state: Arc<AppState>) {
+    let sub = match client.subscribe("emergence.decisions.>".to_owned()).await {
+        Ok(sub) => sub,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "failed to subscribe to decision records, decision logging disabled"
+            );
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        use emergence_observer::state::MAX_DECISIONS;
+        use futures::StreamExt as _;
+        let mut batches = sub.ready_chunks(DECISION_BATCH);
+        while let Some(batch) = batches.next().await {
+            let records: Vec<emergence_types::DecisionRecord> =
+                batch.iter().filter_map(decode_decision).collect();
+            if records.is_empty() {
+                continue;
+            }
+            state.snapshot.update(|snap| {
+                snap.decisions.extend(records.iter().cloned());
+                if snap.decisions.len() > MAX_DECISIONS {
+                    let drain_count = snap.decisions.len().saturating_sub(MAX_DECISIONS);
+                    snap.decisions.drain(..drain_count);
+                }
+            });
+        }
+    });
+    info!("Decision record collector started");
+}
+
+/// Decode one decision record message, logging and skipping bad ones.
+fn decode_decision(msg: &async_nats::Message) -> Option<emergence_types::DecisionRecord> {
+    if let Err(e) = nats_decision::check_envelope(msg, emergence_types::PayloadType::Decision) {
+        tracing::warn!(error = %e, "dropping decision record with unsupported envelope");
+        return None;
+    }
+    match serde_json::from_slice(&msg.payload) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to deserialize decision record");
+            None
+        }
+    }
+}
+
+/// Collect the runners' periodic cost metrics into the observer snapshot.
+///
+/// Keeps the latest [`emergence_types::RunnerMetrics`] per runner
+/// partition. Subscription failures are logged and leave the observer
+/// without runner metrics.
+async fn spawn_runner_metrics_collector(client: &async_nats::Client, state: Arc<AppState>) {
+    let mut sub = match client.subscribe("emergence.runner.metrics.>".to_owned()).await {
+        Ok(sub) => sub,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "failed to subscribe to runner metrics, runner metrics disabled"
+            );
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        use futures::StreamExt as _;
+        while let Some(msg) = sub.next().await {
+            match serde_json::from_slice::<emergence_types::RunnerMetrics>(&msg.payload) {
+                Ok(metrics) => state.snapshot.update(|snap| {
+                    snap.runner_metrics.insert(metrics.partition_id, metrics.clone());
+                }),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to deserialize runner metrics");
+                }
+            }
+        }
+    });
+    info!("Runner metrics collector started");
+}
+
+/// Raise a containment alert for each piece of peer content the runners
+/// quarantine.
+///
+/// Subscription failures are logged and leave the observer without
+/// quarantine alerts; the runners still withhold the content.
+async fn spawn_quarantine_collector(client: &async_nats::Client, state: Arc<AppState>) {
+    use emergence_observer::alerts::{AlertCategory, AlertSeverity};
+
+    let mut sub = match client
+        .subscribe("emergence.containment.quarantine.>".to_owned())
+        .await
+    {
+        Ok(sub) => sub,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "failed to subscribe to quarantine events, containment alerts disabled"
+            );
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        use futures::StreamExt as _;
+        while let Some(msg) = sub.next().await {
+            match serde_json::from_slice::<emergence_types::QuarantinedContent>(&msg.payload) {
+                Ok(record) => {
+                    let author = record
+                        .author
+                        .as_deref()
+                        .map_or_else(String::new, |a| format!(" by {a}"));
+                    let message = format!(
+                        "Quarantined {field}{author} before it reached agent {recipient}'s \
+                         prompt (matched: {patterns})",
+                        field = record.field,
+                        recipient = record.recipient_id,
+                        patterns = record.patterns.join(", "),
+                    );
+                    state.alert_store.write().await.raise(
+                        AlertSeverity::Warning,
+                        AlertCategory::Containment,
+                        message,
+                        record.tick,
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to deserialize quarantine event");
+                }
+            }
+        }
+    });
+    info!("Containment quarantine collector started");
+}
+
 /// Load the main simulation configuration from `emergence-config.yaml`.
 ///
 /// Looks for the config file relative to the current working directory.
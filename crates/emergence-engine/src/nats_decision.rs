@@ -9,10 +9,34 @@
 //!
 //! - **Perception publish:** `tick.{N}.perception.{agent_id}`
 //! - **Action subscribe:** `tick.{N}.action.*`
+//! - **Reflection subscribe:** `tick.*.reflection.*`
 //!
 //! These match the patterns used by `emergence-runner`'s `NatsClient`:
 //! the runner subscribes to `tick.*.perception.*` and publishes to
-//! `tick.{N}.action.{agent_id}`.
+//! `tick.{N}.action.{agent_id}` and `tick.{N}.reflection.{agent_id}`.
+//!
+//! # Protocol Version
+//!
+//! Messages carry a [`WireEnvelope`] in their headers (see
+//! [`emergence_types::wire`]). Until a runner has answered the protocol
+//! hello, perceptions go out in the legacy unversioned form and the hello
+//! is retried each tick. Actions and reflections whose envelope this build
+//! cannot read are dropped with a warning.
+//!
+//! # Tracing
+//!
+//! Each perception is published inside an `agent_perception` span whose
+//! trace context travels in the message headers, and each action is
+//! received inside an `action_received` span continuing the context the
+//! runner sent back, so one agent's round trip forms a single trace (see
+//! [`emergence_telemetry`]).
+//!
+//! # Reflections
+//!
+//! Reflection updates are not awaited. A standing subscription buffers
+//! them as they arrive, and the Reflection phase drains whatever is
+//! waiting. Updates more than [`MAX_REFLECTION_AGE_TICKS`] ticks old are
+//! dropped; if an agent has several, the newest wins.
 //!
 //! # Sync/Async Bridge
 //!
@@ -25,9 +49,34 @@ use std::time::Duration;
 
 use chrono::Utc;
 use emergence_core::decision::{DecisionError, DecisionSource};
-use emergence_types::{ActionParameters, ActionRequest, ActionType, AgentId, Perception};
-use futures::StreamExt as _;
-use tracing::{debug, warn};
+use emergence_types::wire::{negotiate, HELLO_SUBJECT, LEGACY_VERSION};
+use emergence_types::{
+    ActionParameters, ActionRequest, ActionType, AgentId, PayloadType, Perception,
+    ProtocolSupport, ReflectionUpdate, WireEnvelope, WireError, PROTOCOL_VERSION,
+};
+use futures::{FutureExt as _, StreamExt as _};
+use tracing::{debug, info, warn, Instrument as _};
+
+/// Reflection updates older than this many ticks are discarded.
+pub const MAX_REFLECTION_AGE_TICKS: u64 = 10;
+
+/// How long to wait for a runner to answer the protocol hello.
+const HELLO_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Check the protocol envelope of a received message.
+///
+/// # Errors
+///
+/// Returns a [`WireError`] if the envelope is missing required fields for
+/// its version, or the message cannot be read by this build.
+pub fn check_envelope(
+    msg: &async_nats::Message,
+    expected: PayloadType,
+) -> Result<WireEnvelope, WireError> {
+    WireEnvelope::read(expected, |name| {
+        msg.headers.as_ref()?.get(name).map(async_nats::HeaderValue::as_str)
+    })
+}
 
 /// A decision source that communicates with the agent runner via NATS.
 ///
@@ -39,6 +88,10 @@ pub struct NatsDecisionSource {
     client: async_nats::Client,
     /// Maximum time to wait for all agent responses.
     timeout: Duration,
+    /// Standing subscription buffering reflection updates, if any.
+    reflections: Option<async_nats::Subscriber>,
+    /// Protocol version perceptions are published in.
+    protocol_version: u16,
 }
 
 impl NatsDecisionSource {
@@ -46,24 +99,92 @@ impl NatsDecisionSource {
     ///
     /// The `client` must already be connected. The `timeout` controls how
     /// long to wait for agent responses each tick before falling back to
-    /// `NoAction`.
+    /// `NoAction`. Sources built this way ignore reflection updates.
     #[allow(dead_code)]
     pub const fn new(client: async_nats::Client, timeout: Duration) -> Self {
-        Self { client, timeout }
+        Self {
+            client,
+            timeout,
+            reflections: None,
+            protocol_version: LEGACY_VERSION,
+        }
     }
 
     /// Connect to a NATS server and create a decision source.
     ///
+    /// Also opens the standing subscription for reflection updates.
+    ///
     /// # Errors
     ///
-    /// Returns [`DecisionError::Internal`] if the connection fails.
+    /// Returns [`DecisionError::Internal`] if the connection or the
+    /// reflection subscription fails.
     pub async fn connect(url: &str, timeout: Duration) -> Result<Self, DecisionError> {
         let client = async_nats::connect(url).await.map_err(|e| {
             DecisionError::Internal {
                 message: format!("failed to connect to NATS at {url}: {e}"),
             }
         })?;
-        Ok(Self { client, timeout })
+        let reflection_subject = "tick.*.reflection.*";
+        let reflections = client
+            .subscribe(reflection_subject.to_owned())
+            .await
+            .map_err(|e| DecisionError::Internal {
+                message: format!("failed to subscribe to {reflection_subject}: {e}"),
+            })?;
+        Ok(Self {
+            client,
+            timeout,
+            reflections: Some(reflections),
+            protocol_version: LEGACY_VERSION,
+        })
+    }
+
+    /// Ask the runner which protocol versions it speaks and adopt the
+    /// highest common one.
+    ///
+    /// Keeps the current version if nobody answers, the answer is
+    /// unreadable, or the two sides share no version.
+    async fn negotiate_protocol(&mut self) {
+        let local = ProtocolSupport::local();
+        let Ok(request) = serde_json::to_vec(&local) else {
+            return;
+        };
+        let reply = match tokio::time::timeout(
+            HELLO_TIMEOUT,
+            self.client.request(HELLO_SUBJECT, request.into()),
+        )
+        .await
+        {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => {
+                debug!(error = %e, "No runner answered the protocol hello");
+                return;
+            }
+            Err(_) => {
+                debug!("Protocol hello timed out");
+                return;
+            }
+        };
+        let remote = match serde_json::from_slice::<ProtocolSupport>(&reply.payload) {
+            Ok(remote) => remote,
+            Err(e) => {
+                warn!(error = %e, "Failed to deserialize runner protocol support");
+                return;
+            }
+        };
+        match negotiate(&local, &remote) {
+            Ok(agreed) => {
+                if agreed.version != self.protocol_version {
+                    info!(
+                        version = agreed.version,
+                        runner_max = remote.max_version,
+                        "Negotiated wire protocol version"
+                    );
+                }
+                self.protocol_version = agreed.version;
+            }
+            Err(e) => warn!(error = %e, "Runner protocol is incompatible"),
+        }
     }
 
     /// The async implementation of decision collection.
@@ -71,10 +192,14 @@ impl NatsDecisionSource {
     /// Publishes perceptions, subscribes to action responses, and collects
     /// them within the timeout window.
     async fn collect_decisions_async(
-        &self,
+        &mut self,
         tick: u64,
         perceptions: &BTreeMap<AgentId, Perception>,
     ) -> Result<BTreeMap<AgentId, ActionRequest>, DecisionError> {
+        if self.protocol_version < PROTOCOL_VERSION {
+            self.negotiate_protocol().await;
+        }
+
         // Subscribe to action responses BEFORE publishing perceptions
         // to avoid a race condition where responses arrive before the
         // subscription is active.
@@ -109,7 +234,17 @@ impl NatsDecisionSource {
         tick: u64,
         perceptions: &BTreeMap<AgentId, Perception>,
     ) -> Result<(), DecisionError> {
+        let mut headers = async_nats::HeaderMap::new();
+        for (name, value) in
+            WireEnvelope::new(self.protocol_version, PayloadType::Perception).headers()
+        {
+            headers.insert(name, value);
+        }
+
         for (&agent_id, perception) in perceptions {
+            let span = tracing::info_span!("agent_perception", tick, agent_id = %agent_id);
+            let mut agent_headers = headers.clone();
+            span.in_scope(|| emergence_telemetry::inject_headers(&mut agent_headers));
             let subject = format!("tick.{tick}.perception.{agent_id}");
             let payload = serde_json::to_vec(perception).map_err(|e| {
                 DecisionError::Internal {
@@ -120,7 +255,8 @@ impl NatsDecisionSource {
             })?;
 
             self.client
-                .publish(subject.clone(), payload.into())
+                .publish_with_headers(subject.clone(), agent_headers, payload.into())
+                .instrument(span)
                 .await
                 .map_err(|e| DecisionError::Internal {
                     message: format!("failed to publish perception on {subject}: {e}"),
@@ -161,10 +297,24 @@ async fn collect_responses(
 
         match tokio::time::timeout(remaining, action_sub.next()).await {
             Ok(Some(msg)) => {
+                if let Err(e) = check_envelope(&msg, PayloadType::Action) {
+                    warn!(tick, error = %e, "Dropping action with unsupported envelope");
+                    continue;
+                }
                 match serde_json::from_slice::<ActionRequest>(&msg.payload) {
                     Ok(action) => {
                         if action.tick == tick && perceptions.contains_key(&action.agent_id)
                         {
+                            let span = tracing::info_span!(
+                                "action_received",
+                                tick,
+                                agent_id = %action.agent_id
+                            );
+                            emergence_telemetry::set_parent_from_headers(
+                                &span,
+                                msg.headers.as_ref(),
+                            );
+                            let _entered = span.enter();
                             debug!(
                                 tick,
                                 agent_id = %action.agent_id,
@@ -197,6 +347,38 @@ async fn collect_responses(
     decisions
 }
 
+/// Keep the reflection updates that are still fresh at `tick`.
+///
+/// Drops updates from the future or more than
+/// [`MAX_REFLECTION_AGE_TICKS`] old, and keeps only the newest update per
+/// agent. Returns them in agent order.
+fn select_reflections(
+    tick: u64,
+    updates: impl IntoIterator<Item = ReflectionUpdate>,
+) -> Vec<ReflectionUpdate> {
+    let mut latest: BTreeMap<AgentId, ReflectionUpdate> = BTreeMap::new();
+    for update in updates {
+        let fresh = update.tick <= tick
+            && tick.saturating_sub(update.tick) <= MAX_REFLECTION_AGE_TICKS;
+        if !fresh {
+            debug!(
+                tick,
+                agent_id = %update.agent_id,
+                reflected_at = update.tick,
+                "Discarding stale reflection update"
+            );
+            continue;
+        }
+        match latest.get(&update.agent_id) {
+            Some(existing) if existing.tick > update.tick => {}
+            _ => {
+                latest.insert(update.agent_id, update);
+            }
+        }
+    }
+    latest.into_values().collect()
+}
+
 /// Build a `NoAction` request for an agent that did not respond.
 fn make_no_action(agent_id: AgentId, tick: u64) -> ActionRequest {
     ActionRequest {
@@ -246,6 +428,8 @@ impl std::fmt::Debug for NatsDecisionSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NatsDecisionSource")
             .field("timeout_ms", &self.timeout.as_millis())
+            .field("reflections", &self.reflections.is_some())
+            .field("protocol_version", &self.protocol_version)
             .finish_non_exhaustive()
     }
 }
@@ -272,6 +456,34 @@ impl DecisionSource for NatsDecisionSource {
             handle.block_on(self.collect_decisions_async(tick, perceptions))
         })
     }
+
+    fn collect_reflections(&mut self, tick: u64) -> Vec<ReflectionUpdate> {
+        let Some(subscription) = self.reflections.as_mut() else {
+            return Vec::new();
+        };
+
+        // Drain only what is already buffered; the tick never waits on
+        // reflections.
+        let mut received = Vec::new();
+        while let Some(Some(msg)) = subscription.next().now_or_never() {
+            if let Err(e) = check_envelope(&msg, PayloadType::Reflection) {
+                warn!(tick, error = %e, "Dropping reflection with unsupported envelope");
+                continue;
+            }
+            match serde_json::from_slice::<ReflectionUpdate>(&msg.payload) {
+                Ok(update) => received.push(update),
+                Err(e) => {
+                    warn!(tick, error = %e, "Failed to deserialize reflection update");
+                }
+            }
+        }
+
+        let updates = select_reflections(tick, received);
+        if !updates.is_empty() {
+            debug!(tick, count = updates.len(), "Collected reflection updates");
+        }
+        updates
+    }
 }
 
 // -----------------------------------------------------------------------
@@ -314,12 +526,15 @@ mod tests {
                 structures_here: Vec::new(),
                 agents_here: Vec::new(),
                 messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
             },
             known_routes: Vec::new(),
             recent_memory: Vec::new(),
             available_actions: Vec::new(),
             notifications: Vec::new(),
             personality: None,
+            forecast: Vec::new(),
         }
     }
 
@@ -402,6 +617,46 @@ mod tests {
         );
     }
 
+    fn make_reflection(agent_id: AgentId, tick: u64, goal: &str) -> ReflectionUpdate {
+        ReflectionUpdate {
+            agent_id,
+            tick,
+            goals: vec![goal.to_owned()],
+            memory_summary: None,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    /// Stale and future reflections are dropped; the newest per agent wins.
+    #[test]
+    fn select_reflections_keeps_fresh_newest() {
+        let a1 = AgentId::new();
+        let a2 = AgentId::new();
+        let a3 = AgentId::new();
+
+        let selected = select_reflections(
+            20,
+            vec![
+                make_reflection(a1, 19, "newer"),
+                make_reflection(a1, 18, "older"),
+                make_reflection(a2, 5, "stale"),
+                make_reflection(a3, 21, "future"),
+                make_reflection(a3, 10, "edge"),
+            ],
+        );
+
+        assert_eq!(selected.len(), 2);
+        let goal_for = |id: AgentId| {
+            selected
+                .iter()
+                .find(|u| u.agent_id == id)
+                .and_then(|u| u.goals.first().cloned())
+        };
+        assert_eq!(goal_for(a1).as_deref(), Some("newer"));
+        assert_eq!(goal_for(a2), None);
+        assert_eq!(goal_for(a3).as_deref(), Some("edge"));
+    }
+
     /// Test that a published action is correctly deserialized.
     #[tokio::test]
     async fn action_deserialization_round_trip() {
@@ -422,4 +677,44 @@ mod tests {
         assert_eq!(deserialized.tick, 42);
         assert_eq!(deserialized.action_type, ActionType::Rest);
     }
+
+    fn message_with_headers(headers: Option<async_nats::HeaderMap>) -> async_nats::Message {
+        async_nats::Message {
+            subject: "tick.1.action.agent".into(),
+            reply: None,
+            payload: "{}".into(),
+            headers,
+            status: None,
+            description: None,
+            length: 0,
+        }
+    }
+
+    /// Unversioned messages from older runners are still accepted; versions
+    /// newer than this build and mismatched payloads are not.
+    #[test]
+    fn check_envelope_accepts_legacy_and_current() {
+        let legacy = message_with_headers(None);
+        let envelope = check_envelope(&legacy, PayloadType::Action).unwrap();
+        assert_eq!(envelope.protocol_version, LEGACY_VERSION);
+
+        let mut headers = async_nats::HeaderMap::new();
+        for (name, value) in WireEnvelope::new(PROTOCOL_VERSION, PayloadType::Action).headers() {
+            headers.insert(name, value);
+        }
+        let current = message_with_headers(Some(headers.clone()));
+        let envelope = check_envelope(&current, PayloadType::Action).unwrap();
+        assert_eq!(envelope.protocol_version, PROTOCOL_VERSION);
+        assert!(check_envelope(&current, PayloadType::Reflection).is_err());
+
+        headers.insert(
+            emergence_types::wire::VERSION_HEADER,
+            PROTOCOL_VERSION.saturating_add(1).to_string(),
+        );
+        let newer = message_with_headers(Some(headers));
+        assert!(matches!(
+            check_envelope(&newer, PayloadType::Action),
+            Err(WireError::UnsupportedVersion(_))
+        ));
+    }
 }
@@ -3,36 +3,76 @@
 //! After each tick, this callback updates the in-memory
 //! [`SimulationSnapshot`] and broadcasts a [`TickBroadcast`] to all
 //! connected WebSocket clients.
+//!
+//! The snapshot is updated incrementally. The first tick copies every
+//! agent, location, and route; after that only agents whose state changed
+//! since the last tick, the tick's deaths and births, and locations or
+//! routes that differ from the published copy are written, and new events
+//! are appended. Anything the simulation dropped is pruned.
+//!
+//! [`SimulationSnapshot`]: emergence_observer::SimulationSnapshot
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use chrono::Utc;
+use im::OrdMap;
 use emergence_core::runner::TickCallback;
 use emergence_core::tick::{SimulationState, TickSummary};
-use emergence_observer::state::{AppState, TickBroadcast, MAX_EVENTS};
+use emergence_observer::state::{AppState, SimulationSnapshot, TickBroadcast, MAX_EVENTS};
 use emergence_types::{
-    AgentStateSnapshot, EconomyStats, Event, EventId, EventType, PopulationStats, WorldContext,
-    WorldSnapshot,
+    Agent, AgentId, AgentState, AgentStateSnapshot, EconomyStats, Event, EventId, EventType,
+    PopulationStats, WorldContext, WorldSnapshot, WorldSnapshotDelta,
 };
 use rust_decimal::Decimal;
 use tracing::debug;
 
+use crate::snapshots::TickSnapshots;
+
 /// Callback that bridges the tick cycle to the Observer API.
 pub struct ObserverCallback {
     state: Arc<AppState>,
+    /// World snapshot from the previous tick, used to compute the delta.
+    last_world: Option<WorldSnapshot>,
+    /// Agent store revision last mirrored into the snapshot, `None` until
+    /// the first full copy.
+    agents_seen: Option<u64>,
+    /// Periodic state snapshots, `None` when no database is connected.
+    snapshots: Option<TickSnapshots>,
 }
 
 impl ObserverCallback {
     /// Create a new observer callback backed by the given app state.
     pub const fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            last_world: None,
+            agents_seen: None,
+            snapshots: None,
+        }
+    }
+
+    /// Also write periodic state snapshots through `snapshots`.
+    #[must_use]
+    pub fn with_snapshots(mut self, snapshots: TickSnapshots) -> Self {
+        self.snapshots = Some(snapshots);
+        self
     }
 }
 
 impl TickCallback for ObserverCallback {
     #[allow(clippy::too_many_lines)]
     fn on_tick(&mut self, summary: &TickSummary, sim: &SimulationState) {
+        let _span = tracing::info_span!("observer_update", tick = summary.tick).entered();
+        let world = build_world_snapshot(summary, sim);
+        if let Some(snapshots) = &mut self.snapshots {
+            snapshots.on_tick(&world, sim);
+        }
+        let world_delta = self
+            .last_world
+            .as_ref()
+            .map(|prev| WorldSnapshotDelta::between(prev, &world));
+
         // Build the broadcast message.
         let broadcast = TickBroadcast {
             tick: summary.tick,
@@ -43,41 +83,39 @@ impl TickCallback for ObserverCallback {
             deaths_this_tick: summary.deaths.len() as u32,
             #[allow(clippy::cast_possible_truncation)]
             actions_resolved: summary.action_results.len() as u32,
+            world_delta: world_delta.clone(),
         };
 
         // Broadcast to WebSocket clients.
         let receivers = self.state.broadcast(&broadcast);
         debug!(tick = summary.tick, receivers, "Tick broadcast sent");
 
-        // Update the snapshot. Use try_write to avoid blocking the tick
-        // loop — if a REST handler holds the read lock, skip this update;
-        // the next tick will catch up.
-        if let Ok(mut snap) = self.state.snapshot.try_write() {
+        // Publish the updated snapshot. REST handlers keep reading the
+        // previous one until the swap, so nothing here waits on them.
+        let agents_seen = self.agents_seen;
+        self.state.snapshot.update(|snap| {
             // Basic fields
             snap.current_tick = summary.tick;
             snap.season = summary.season;
             snap.weather = summary.weather;
             snap.era = sim.clock.era();
 
-            // Agent identity records
-            snap.agents = sim.agents.clone();
-
-            // Agent mutable state
-            snap.agent_states = sim.agent_states.clone();
-
-            // Locations from world map
-            snap.locations = sim
-                .world_map
-                .locations()
-                .map(|(id, loc_state)| (*id, loc_state.location.clone()))
-                .collect();
-
-            // Routes from world map
-            snap.routes = sim
-                .world_map
-                .routes()
-                .map(|(id, route)| (*id, route.clone()))
-                .collect();
+            // Agents, locations, and routes
+            if let Some(seen) = agents_seen {
+                sync_agents(&mut snap.agents, summary, sim);
+                sync_agent_states(&mut snap.agent_states, sim, seen);
+                sync_world_map(snap, sim);
+            } else {
+                snap.agents = sim.agents.iter().map(|(id, a)| (*id, a.clone())).collect();
+                snap.agent_states =
+                    sim.agent_states.iter().map(|(id, s)| (id, s.clone())).collect();
+                snap.locations = sim
+                    .world_map
+                    .locations()
+                    .map(|(id, loc_state)| (*id, loc_state.location.clone()))
+                    .collect();
+                snap.routes = sim.world_map.routes().map(|(id, r)| (*id, r.clone())).collect();
+            }
 
             // Build reusable world context for events
             let world_ctx = WorldContext {
@@ -105,11 +143,108 @@ impl TickCallback for ObserverCallback {
                     agent_state_snapshot: None,
                     world_context: world_ctx.clone(),
                     created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
+                });
+            }
+
+            // Disaster events
+            for disaster in &summary.disasters {
+                new_events.push(Event {
+                    id: EventId::new(),
+                    tick: summary.tick,
+                    event_type: emergence_world::disaster_event_type(disaster.kind),
+                    agent_id: None,
+                    location_id: Some(disaster.location_id),
+                    details: serde_json::to_value(disaster).unwrap_or_default(),
+                    agent_state_snapshot: None,
+                    world_context: world_ctx.clone(),
+                    created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
+                });
+            }
+
+            // Structure-burned events, one per structure a fire damaged.
+            for burned in &summary.fires {
+                new_events.push(Event {
+                    id: EventId::new(),
+                    tick: summary.tick,
+                    event_type: EventType::StructureBurned,
+                    agent_id: None,
+                    location_id: Some(burned.location_id),
+                    details: serde_json::to_value(burned).unwrap_or_default(),
+                    agent_state_snapshot: None,
+                    world_context: world_ctx.clone(),
+                    created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
+                });
+            }
+
+            // Trade-route events, one per route that became a corridor.
+            for corridor in &summary.trade_routes {
+                new_events.push(Event {
+                    id: EventId::new(),
+                    tick: summary.tick,
+                    event_type: EventType::TradeRouteEmerged,
+                    agent_id: None,
+                    location_id: Some(corridor.from_location),
+                    details: serde_json::to_value(corridor).unwrap_or_default(),
+                    agent_state_snapshot: None,
+                    world_context: world_ctx.clone(),
+                    created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
+                });
+            }
+
+            // Inheritance events, one per structure that passed to an heir.
+            for inherited in &summary.inheritances {
+                new_events.push(Event {
+                    id: EventId::new(),
+                    tick: summary.tick,
+                    event_type: EventType::StructureInherited,
+                    agent_id: Some(inherited.heir),
+                    location_id: Some(inherited.location_id),
+                    details: serde_json::to_value(inherited).unwrap_or_default(),
+                    agent_state_snapshot: None,
+                    world_context: world_ctx.clone(),
+                    created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
+                });
+            }
+
+            // Trade-completed events, one per pair of market orders filled.
+            for fill in &summary.market_fills {
+                new_events.push(Event {
+                    id: EventId::new(),
+                    tick: summary.tick,
+                    event_type: EventType::TradeCompleted,
+                    agent_id: Some(fill.completed.agent_a),
+                    location_id: sim.structures.get(&fill.market_id).map(|m| m.location_id),
+                    details: serde_json::to_value(&fill.completed).unwrap_or_default(),
+                    agent_state_snapshot: None,
+                    world_context: world_ctx.clone(),
+                    created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
                 });
             }
 
-            // Action events
+            // Action events. Each result also resolves the outcome of the
+            // runner's decision record for that agent and tick.
             for (agent_id, result) in &summary.action_results {
+                if let Some(record) = snap
+                    .decisions
+                    .iter_mut()
+                    .rev()
+                    .find(|d| d.agent_id == *agent_id && d.tick == summary.tick)
+                {
+                    let outcome = if result.success { "succeeded" } else { "rejected" };
+                    record.outcome = Some(outcome.to_owned());
+                }
                 let event_type = if result.success {
                     EventType::ActionSucceeded
                 } else {
@@ -131,7 +266,7 @@ impl TickCallback for ObserverCallback {
                     tick: summary.tick,
                     event_type,
                     agent_id: Some(*agent_id),
-                    location_id: sim.agent_states.get(agent_id).map(|s| s.location_id),
+                    location_id: sim.agent_states.location(agent_id),
                     details: serde_json::json!({
                         "action_type": format!("{:?}", result.action_type),
                         "success": result.success,
@@ -142,6 +277,8 @@ impl TickCallback for ObserverCallback {
                     agent_state_snapshot: agent_snap,
                     world_context: world_ctx.clone(),
                     created_at: Utc::now(),
+                    caused_by: None,
+                    correlation_id: None,
                 });
             }
 
@@ -152,99 +289,181 @@ impl TickCallback for ObserverCallback {
                 snap.events.drain(..drain_count);
             }
 
-            // --- Compute WorldSnapshot ---
-
-            #[allow(clippy::cast_possible_truncation)]
-            let total_dead = sim
-                .agents
-                .values()
-                .filter(|a| a.died_at_tick.is_some())
-                .count() as u32;
-
-            let alive_states: Vec<_> = sim
-                .alive_agents
-                .iter()
-                .filter_map(|id| sim.agent_states.get(id))
-                .collect();
-
-            #[allow(clippy::arithmetic_side_effects)]
-            let average_age = if alive_states.is_empty() {
-                Decimal::ZERO
-            } else {
-                let total_age: u32 = alive_states.iter().map(|s| s.age).sum();
-                #[allow(clippy::cast_possible_truncation)]
-                let count = alive_states.len() as u32;
-                Decimal::from(total_age) / Decimal::from(count)
+            // Move the world snapshot forward by this tick's delta; fall
+            // back to the full snapshot if the last update was skipped.
+            let applied = match (&mut snap.world_snapshot, &world_delta) {
+                (Some(current), Some(delta)) => current.apply_delta(delta).is_ok(),
+                _ => false,
             };
+            if !applied {
+                snap.world_snapshot = Some(world.clone());
+            }
+        });
 
-            let oldest_agent = alive_states
-                .iter()
-                .max_by_key(|s| s.age)
-                .map(|s| s.agent_id);
-
-            let population = PopulationStats {
-                total_alive: summary.agents_alive,
-                total_dead,
-                births_this_tick: 0,
-                #[allow(clippy::cast_possible_truncation)]
-                deaths_this_tick: summary.deaths.len() as u32,
-                average_age,
-                oldest_agent,
-            };
+        self.last_world = Some(world);
+        self.agents_seen = Some(sim.agent_states.revision());
+    }
+}
 
-            // Economy: sum resources across agents and locations
-            let mut resources_in_circulation = BTreeMap::new();
-            for state in sim.agent_states.values() {
-                for (&res, &qty) in &state.inventory {
-                    let entry = resources_in_circulation.entry(res).or_insert(0u32);
-                    *entry = entry.saturating_add(qty);
-                }
-            }
+/// Bring the published identity records up to date: the tick's deaths,
+/// plus any agents born or dropped since the last tick.
+fn sync_agents(agents: &mut OrdMap<AgentId, Agent>, summary: &TickSummary, sim: &SimulationState) {
+    for death in &summary.deaths {
+        if let Some(agent) = sim.agents.get(&death.agent_id) {
+            agents.insert(death.agent_id, agent.clone());
+        }
+    }
+    if agents.len() == sim.agents.len() {
+        return;
+    }
+    for (id, agent) in &sim.agents {
+        if !agents.contains_key(id) {
+            agents.insert(*id, agent.clone());
+        }
+    }
+    prune(agents, |id| sim.agents.contains_key(id), sim.agents.len());
+}
 
-            let mut resources_at_nodes = BTreeMap::new();
-            for (_, loc_state) in sim.world_map.locations() {
-                for node in loc_state.location.base_resources.values() {
-                    let entry = resources_at_nodes.entry(node.resource).or_insert(0u32);
-                    *entry = entry.saturating_add(node.available);
-                }
-            }
+/// Copy the agent states changed after revision `seen`, and drop any the
+/// store no longer holds.
+fn sync_agent_states(
+    states: &mut OrdMap<AgentId, AgentState>,
+    sim: &SimulationState,
+    seen: u64,
+) {
+    for (id, state) in sim.agent_states.changed_since(seen) {
+        states.insert(id, state.clone());
+    }
+    prune(states, |id| sim.agent_states.contains_key(id), sim.agent_states.len());
+}
 
-            let mut total_resources = resources_in_circulation.clone();
-            for (&res, &qty) in &resources_at_nodes {
-                let entry = total_resources.entry(res).or_insert(0u32);
-                *entry = entry.saturating_add(qty);
-            }
+/// Replace the locations and routes that differ from the world map.
+fn sync_world_map(snap: &mut SimulationSnapshot, sim: &SimulationState) {
+    for (id, loc_state) in sim.world_map.locations() {
+        if snap.locations.get(id) != Some(&loc_state.location) {
+            snap.locations.insert(*id, loc_state.location.clone());
+        }
+    }
+    let live = sim.world_map.location_count();
+    prune(&mut snap.locations, |id| sim.world_map.get_location(*id).is_some(), live);
 
-            let economy = EconomyStats {
-                total_resources,
-                resources_in_circulation,
-                resources_at_nodes,
-                trades_this_tick: 0,
-                gini_coefficient: Decimal::ZERO,
-            };
+    for (id, route) in sim.world_map.routes() {
+        if snap.routes.get(id) != Some(route) {
+            snap.routes.insert(*id, route.clone());
+        }
+    }
+    let live = sim.world_map.route_count();
+    prune(&mut snap.routes, |id| sim.world_map.get_route(*id).is_some(), live);
+}
 
-            // All agent knowledge as discoveries
-            let discoveries: Vec<String> = sim
-                .agent_states
-                .values()
-                .flat_map(|s| s.knowledge.iter().cloned())
-                .collect::<BTreeSet<String>>()
-                .into_iter()
-                .collect();
+/// Remove the entries of `map` that `keep` rejects. Skipped when `map`
+/// already holds exactly `live` entries, since every live entry has been
+/// written by then.
+fn prune<K, V>(map: &mut OrdMap<K, V>, keep: impl Fn(&K) -> bool, live: usize)
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    if map.len() == live {
+        return;
+    }
+    let stale: Vec<K> = map.keys().filter(|id| !keep(id)).cloned().collect();
+    for id in stale {
+        map.remove(&id);
+    }
+}
 
-            snap.world_snapshot = Some(WorldSnapshot {
-                tick: summary.tick,
-                era: sim.clock.era(),
-                season: summary.season,
-                weather: summary.weather,
-                population,
-                economy,
-                discoveries,
-                summary: format!(
-                    "Tick {} complete. {} agents alive.",
-                    summary.tick, summary.agents_alive
-                ),
-            });
+/// Summarize the world at the end of a tick.
+fn build_world_snapshot(summary: &TickSummary, sim: &SimulationState) -> WorldSnapshot {
+    #[allow(clippy::cast_possible_truncation)]
+    let total_dead = sim
+        .agents
+        .values()
+        .filter(|a| a.died_at_tick.is_some())
+        .count() as u32;
+
+    let alive_ages: Vec<(AgentId, u32)> = sim
+        .alive_agents
+        .iter()
+        .filter_map(|id| sim.agent_states.vitals(id).map(|v| (*id, v.age)))
+        .collect();
+
+    #[allow(clippy::arithmetic_side_effects)]
+    let average_age = if alive_ages.is_empty() {
+        Decimal::ZERO
+    } else {
+        let total_age: u32 = alive_ages.iter().map(|&(_, age)| age).sum();
+        #[allow(clippy::cast_possible_truncation)]
+        let count = alive_ages.len() as u32;
+        Decimal::from(total_age) / Decimal::from(count)
+    };
+
+    let oldest_agent = alive_ages
+        .iter()
+        .max_by_key(|&&(_, age)| age)
+        .map(|&(id, _)| id);
+
+    let population = PopulationStats {
+        total_alive: summary.agents_alive,
+        total_dead,
+        births_this_tick: 0,
+        #[allow(clippy::cast_possible_truncation)]
+        deaths_this_tick: summary.deaths.len() as u32,
+        average_age,
+        oldest_agent,
+    };
+
+    // Economy: sum resources across agents and locations
+    let mut resources_in_circulation = BTreeMap::new();
+    for state in sim.agent_states.values() {
+        for (&res, &qty) in &state.inventory {
+            let entry = resources_in_circulation.entry(res).or_insert(0u32);
+            *entry = entry.saturating_add(qty);
+        }
+    }
+
+    let mut resources_at_nodes = BTreeMap::new();
+    for (_, loc_state) in sim.world_map.locations() {
+        for node in loc_state.location.base_resources.values() {
+            let entry = resources_at_nodes.entry(node.resource).or_insert(0u32);
+            *entry = entry.saturating_add(node.available);
         }
     }
+
+    let mut total_resources = resources_in_circulation.clone();
+    for (&res, &qty) in &resources_at_nodes {
+        let entry = total_resources.entry(res).or_insert(0u32);
+        *entry = entry.saturating_add(qty);
+    }
+
+    let economy = EconomyStats {
+        total_resources,
+        resources_in_circulation,
+        resources_at_nodes,
+        trades_this_tick: 0,
+        gini_coefficient: Decimal::ZERO,
+    };
+
+    // All agent knowledge as discoveries
+    let discoveries: Vec<String> = sim
+        .agent_states
+        .values()
+        .flat_map(|s| s.knowledge.iter().cloned())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect();
+
+    WorldSnapshot {
+        tick: summary.tick,
+        era: sim.clock.era(),
+        season: summary.season,
+        weather: summary.weather,
+        population,
+        economy,
+        discoveries,
+        summary: format!(
+            "Tick {} complete. {} agents alive.",
+            summary.tick, summary.agents_alive
+        ),
+    }
 }
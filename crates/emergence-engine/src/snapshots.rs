@@ -0,0 +1,51 @@
+//! Periodic state snapshots taken from the tick loop.
+//!
+//! [`TickSnapshots`] owns a [`SnapshotScheduler`] on the cadence set by
+//! `logging.snapshot_interval_ticks` and offers it every tick's world
+//! summary. When a snapshot is due, the world and every agent's full state
+//! are written to `PostgreSQL` before the next tick starts, so hydration
+//! and compaction always see a snapshot that matches one tick exactly.
+
+use std::time::Instant;
+
+use emergence_core::tick::SimulationState;
+use emergence_db::PostgresPool;
+use emergence_events::{SnapshotCadence, SnapshotScheduler};
+use emergence_types::{AgentState, WorldSnapshot};
+use tokio::runtime::Handle;
+use tracing::warn;
+
+/// Writes world and agent snapshots on the configured tick interval.
+pub struct TickSnapshots {
+    pool: PostgresPool,
+    scheduler: SnapshotScheduler,
+}
+
+impl TickSnapshots {
+    /// Snapshot through `pool` every `interval_ticks` ticks.
+    pub fn new(pool: PostgresPool, interval_ticks: u64) -> Self {
+        Self {
+            pool,
+            scheduler: SnapshotScheduler::new()
+                .with_cadence(SnapshotCadence::EveryTicks(interval_ticks)),
+        }
+    }
+
+    /// Offer the end-of-tick `world` summary to the scheduler, writing a
+    /// snapshot of `world` and every agent in `sim` if one is due.
+    ///
+    /// A failed write is logged and retried on the next tick.
+    pub fn on_tick(&mut self, world: &WorldSnapshot, sim: &SimulationState) {
+        // Copying every agent is only worth it when a snapshot is taken.
+        if self.scheduler.due(world, Instant::now()).is_none() {
+            return;
+        }
+        let agents: Vec<AgentState> = sim.agent_states.values().cloned().collect();
+        let result = tokio::task::block_in_place(|| {
+            Handle::current().block_on(self.scheduler.on_tick(self.pool.pool(), world, &agents))
+        });
+        if let Err(e) = result {
+            warn!(tick = world.tick, error = %e, "failed to write state snapshot");
+        }
+    }
+}
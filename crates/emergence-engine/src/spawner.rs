@@ -8,7 +8,9 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::Utc;
-use emergence_types::{Agent, AgentId, AgentState, LocationId, MemoryEntry, Personality, Resource, Sex};
+use emergence_types::{
+    Agent, AgentId, AgentState, AgentStateBuilder, LocationId, Personality, Resource, Sex,
+};
 use emergence_world::WorldMap;
 use rand::Rng;
 use rust_decimal::Decimal;
@@ -181,31 +183,13 @@ pub fn spawn_single_agent(
     let agent_id = AgentId::new();
     let knowledge: BTreeSet<String> = seed_knowledge.iter().cloned().collect();
 
-    let agent_state = AgentState {
-        agent_id,
-        energy: 80,
-        health: 100,
-        hunger: 0,
-        thirst: 0,
-        age: 0,
-        born_at_tick: current_tick,
-        location_id,
-        destination_id: None,
-        travel_progress: 0,
-        inventory: {
-            let mut inv = BTreeMap::new();
-            inv.insert(Resource::FoodBerry, 5);
-            inv.insert(Resource::Water, 5);
-            inv
-        },
-        carry_capacity: 50,
-        knowledge,
-        skills: BTreeMap::new(),
-        skill_xp: BTreeMap::new(),
-        goals: Vec::new(),
-        relationships: BTreeMap::new(),
-        memory: Vec::<MemoryEntry>::new(),
-    };
+    let agent_state = starting_state(agent_id, location_id)
+        .born_at_tick(current_tick)
+        .knowledge(knowledge)
+        .build()
+        .map_err(|e| EngineError::Spawner {
+            message: format!("invalid starting state for agent {agent_id}: {e}"),
+        })?;
 
     // Use preferred sex if provided, otherwise random 50/50.
     let sex = preferred_sex.unwrap_or_else(|| {
@@ -347,31 +331,12 @@ pub fn spawn_seed_agents(
                 location_ids.first().copied().unwrap_or_else(LocationId::new)
             });
 
-        let state = AgentState {
-            agent_id,
-            energy: 80,
-            health: 100,
-            hunger: 0,
-            thirst: 0,
-            age: 0,
-            born_at_tick: 0,
-            location_id,
-            destination_id: None,
-            travel_progress: 0,
-            inventory: {
-                let mut inv = BTreeMap::new();
-                inv.insert(Resource::FoodBerry, 5);
-                inv.insert(Resource::Water, 5);
-                inv
-            },
-            carry_capacity: 50,
-            knowledge: knowledge.clone(),
-            skills: BTreeMap::new(),
-            skill_xp: BTreeMap::new(),
-            goals: Vec::new(),
-            relationships: BTreeMap::new(),
-            memory: Vec::<MemoryEntry>::new(),
-        };
+        let state = starting_state(agent_id, location_id)
+            .knowledge(knowledge.clone())
+            .build()
+            .map_err(|e| EngineError::Spawner {
+                message: format!("invalid starting state for agent {agent_id}: {e}"),
+            })?;
 
         // Build the immutable Agent identity record.
         let agent = Agent {
@@ -453,6 +418,14 @@ fn pick_unique_names<R: Rng>(rng: &mut R, count: u32) -> Result<Vec<String>, Eng
     Ok(names)
 }
 
+/// Starting state shared by seed and operator-spawned agents: default
+/// vitals plus a few days of berries and water.
+fn starting_state(agent_id: AgentId, location_id: LocationId) -> AgentStateBuilder {
+    AgentState::builder(agent_id, location_id)
+        .resource(Resource::FoodBerry, 5)
+        .resource(Resource::Water, 5)
+}
+
 /// Generate a random decimal between 0.00 and 1.00.
 #[allow(clippy::arithmetic_side_effects)]
 fn rand_decimal(rng: &mut impl Rng) -> Decimal {
@@ -563,8 +536,9 @@ impl emergence_core::runner::SpawnHandler for EngineSpawnHandler {
                 let name = result.agent.name.clone();
                 state.agents.insert(agent_id, result.agent);
                 state.agent_names.insert(agent_id, name);
-                state.agent_states.insert(agent_id, result.agent_state);
+                state.agent_states.insert(result.agent_state);
                 state.alive_agents.push(agent_id);
+                state.family.register_alive(agent_id);
                 true
             }
             Err(err) => {
@@ -0,0 +1,360 @@
+//! Backfilling derived events into recorded runs.
+//!
+//! Some events are not recorded by the simulation but derived from what it
+//! recorded: a famine is a tick at which most living agents are starving.
+//! A [`Detector`] folds the history one tick at a time and returns the
+//! events it derives. [`backfill`] runs detectors over a stored run and
+//! writes their output with [`EventStore::replace_derived`], marked with
+//! the detector's name, so a detector added after a run still covers it,
+//! and re-running one replaces its earlier output instead of doubling it.
+//!
+//! Detectors see the whole history, including events derived earlier, and
+//! should ignore the event types they derive themselves.
+//!
+//! Built-in detectors:
+//!
+//! - [`FamineDetector`] -- [`EventType::FamineStarted`] when at least half
+//!   of the living agents are starving
+
+use std::collections::BTreeMap;
+
+use emergence_db::{DbError, EventRow, EventStore};
+use emergence_types::{
+    AgentId, AgentStateSnapshot, Event, EventId, EventType, FamineStartedDetails, WorldContext,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::tick_of;
+
+/// Ticks of events read and rewritten at a time.
+const WINDOW_TICKS: u64 = 1_000;
+
+/// Hunger at which an agent starves under the default vitals
+/// configuration.
+const DEFAULT_HUNGER_THRESHOLD: u32 = 100;
+
+/// Errors that can occur during a backfill.
+#[derive(Debug, thiserror::Error)]
+pub enum BackfillError {
+    /// An event query, or writing the derived events, failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// A detector could not decode the events of a tick.
+    #[error("detector {detector} failed at tick {tick}: {source}")]
+    Detect {
+        /// The detector's name.
+        detector: &'static str,
+        /// The tick whose events it was fed.
+        tick: u64,
+        /// The decoding or encoding error.
+        source: serde_json::Error,
+    },
+}
+
+/// Derives events from the recorded history.
+pub trait Detector: Send {
+    /// Unique name; the marker its derived events are stored under.
+    fn name(&self) -> &'static str;
+
+    /// Fold every event of `tick`, in order, and return the events
+    /// derived at that tick. Ticks are fed in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a decoding error if an event is malformed, or an encoding
+    /// error if a derived event's details cannot be serialized.
+    fn detect(&mut self, tick: u64, events: &[EventRow]) -> Result<Vec<Event>, serde_json::Error>;
+}
+
+/// Every built-in detector.
+pub fn builtin_detectors() -> Vec<Box<dyn Detector>> {
+    vec![Box::new(FamineDetector::new())]
+}
+
+/// What a backfill run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillReport {
+    /// Events with ticks up to this one were scanned.
+    pub through_tick: u64,
+    /// Number of recorded events scanned.
+    pub scanned: usize,
+    /// Number of events each detector derived.
+    pub derived: BTreeMap<&'static str, usize>,
+    /// Number of previously derived events replaced.
+    pub replaced: u64,
+}
+
+/// Feed `events`, in tick order, to each detector a tick at a time, and
+/// collect what each derives.
+///
+/// # Errors
+///
+/// Returns [`BackfillError::Detect`] if a detector fails.
+pub fn detect_all(
+    detectors: &mut [Box<dyn Detector>],
+    events: &[EventRow],
+) -> Result<BTreeMap<&'static str, Vec<Event>>, BackfillError> {
+    let mut derived: BTreeMap<&'static str, Vec<Event>> =
+        detectors.iter().map(|detector| (detector.name(), Vec::new())).collect();
+    for tick_events in events.chunk_by(|a, b| a.tick == b.tick) {
+        let tick = tick_events.first().map_or(0, |event| tick_of(event.tick));
+        for detector in detectors.iter_mut() {
+            let detector_name = detector.name();
+            let found = detector.detect(tick, tick_events).map_err(|source| {
+                BackfillError::Detect {
+                    detector: detector_name,
+                    tick,
+                    source,
+                }
+            })?;
+            derived.entry(detector_name).or_default().extend(found);
+        }
+    }
+    Ok(derived)
+}
+
+/// Run `detectors` over the recorded events through `to_tick`, replacing
+/// whatever each derived before.
+///
+/// The history is scanned from tick 0 so that every detector sees it all,
+/// a window of ticks at a time; each window's derived events are written
+/// in one transaction per detector.
+///
+/// # Errors
+///
+/// Returns [`BackfillError::Db`] if a query or write fails, or
+/// [`BackfillError::Detect`] if a detector fails.
+pub async fn backfill(
+    pool: &PgPool,
+    detectors: &mut [Box<dyn Detector>],
+    to_tick: u64,
+) -> Result<BackfillReport, BackfillError> {
+    let store = EventStore::new(pool);
+    let mut report = BackfillReport {
+        through_tick: to_tick,
+        derived: detectors.iter().map(|detector| (detector.name(), 0)).collect(),
+        ..BackfillReport::default()
+    };
+
+    let end = to_tick.saturating_add(1);
+    let mut start = 0;
+    while start < end {
+        let stop = start.saturating_add(WINDOW_TICKS).min(end);
+        let events = store.get_events_in_range(start, stop).await?;
+        report.scanned = report.scanned.saturating_add(events.len());
+        for (detector, derived) in detect_all(detectors, &events)? {
+            let replaced = store.replace_derived(detector, start, stop, &derived).await?;
+            report.replaced = report.replaced.saturating_add(replaced);
+            let count = report.derived.entry(detector).or_default();
+            *count = count.saturating_add(derived.len());
+        }
+        start = stop;
+    }
+
+    tracing::info!(
+        through_tick = to_tick,
+        scanned = report.scanned,
+        derived = ?report.derived,
+        replaced = report.replaced,
+        "Backfilled derived events"
+    );
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Built-in detectors
+// ---------------------------------------------------------------------------
+
+/// Derives [`EventType::FamineStarted`] at each tick at which at least half
+/// of the living agents became starving, after a tick at which fewer were.
+///
+/// An agent's hunger is read from the state snapshot on its latest event;
+/// agents enter through their `agent_born` event or any event that
+/// carries one, and leave through their `agent_died` event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FamineDetector {
+    hunger_threshold: u32,
+    /// Latest known hunger of each living agent.
+    hunger: BTreeMap<AgentId, u32>,
+    /// Whether a famine is under way.
+    in_famine: bool,
+    /// World context of the latest event seen.
+    context: Option<WorldContext>,
+}
+
+impl FamineDetector {
+    /// Create a detector with the default starvation threshold.
+    pub const fn new() -> Self {
+        Self {
+            hunger_threshold: DEFAULT_HUNGER_THRESHOLD,
+            hunger: BTreeMap::new(),
+            in_famine: false,
+            context: None,
+        }
+    }
+
+    /// Count agents with at least `hunger` as starving.
+    #[must_use]
+    pub const fn with_hunger_threshold(mut self, hunger: u32) -> Self {
+        self.hunger_threshold = hunger;
+        self
+    }
+
+    /// Update the living agents' hunger from `event`.
+    fn observe(&mut self, event: &EventRow) -> Result<(), serde_json::Error> {
+        if let Some(context) = &event.world_context {
+            self.context = Some(WorldContext::deserialize(context)?);
+        }
+        let Some(agent_id) = event.agent_id.map(AgentId::from) else {
+            return Ok(());
+        };
+        if event.event_type == "agent_died" {
+            self.hunger.remove(&agent_id);
+            return Ok(());
+        }
+        let snapshot = event
+            .agent_state_snapshot
+            .as_ref()
+            .map(AgentStateSnapshot::deserialize)
+            .transpose()?;
+        if let Some(snapshot) = snapshot {
+            self.hunger.insert(agent_id, snapshot.hunger);
+        } else if event.event_type == "agent_born" {
+            self.hunger.entry(agent_id).or_insert(0);
+        }
+        Ok(())
+    }
+}
+
+impl Default for FamineDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Detector for FamineDetector {
+    fn name(&self) -> &'static str {
+        "famine"
+    }
+
+    fn detect(&mut self, tick: u64, events: &[EventRow]) -> Result<Vec<Event>, serde_json::Error> {
+        for event in events {
+            self.observe(event)?;
+        }
+        let population = self.hunger.len();
+        let starving = self.hunger.values().filter(|h| **h >= self.hunger_threshold).count();
+        let famine = population > 0 && starving.saturating_mul(2) >= population;
+        let started = famine && !self.in_famine;
+        self.in_famine = famine;
+
+        let (true, Some(context)) = (started, &self.context) else {
+            return Ok(Vec::new());
+        };
+        let details = FamineStartedDetails {
+            starving: u32::try_from(starving).unwrap_or(u32::MAX),
+            population: u32::try_from(population).unwrap_or(u32::MAX),
+            hunger_threshold: self.hunger_threshold,
+        };
+        Ok(vec![Event {
+            id: EventId::new(),
+            tick,
+            event_type: EventType::FamineStarted,
+            agent_id: None,
+            location_id: None,
+            details: serde_json::to_value(details)?,
+            agent_state_snapshot: None,
+            world_context: context.clone(),
+            created_at: events.last().map_or_else(chrono::Utc::now, |event| event.created_at),
+            caused_by: None,
+            correlation_id: None,
+        }])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{Era, LocationId, Season, Weather};
+
+    use super::*;
+
+    fn row(id: i64, tick: i64, event_type: &str, agent_id: AgentId, hunger: u32) -> EventRow {
+        let context = WorldContext {
+            tick: u64::try_from(tick).unwrap(),
+            era: Era::Primitive,
+            season: Season::Winter,
+            weather: Weather::Clear,
+            population: 3,
+        };
+        let snapshot = AgentStateSnapshot {
+            energy: 50,
+            health: 50,
+            hunger,
+            age: 1,
+            location_id: LocationId::new(),
+            inventory_summary: BTreeMap::new(),
+        };
+        EventRow {
+            id,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: Some(agent_id.into_inner()),
+            location_id: None,
+            details: serde_json::json!({}),
+            agent_state_snapshot: Some(serde_json::to_value(snapshot).unwrap()),
+            world_context: Some(serde_json::to_value(context).unwrap()),
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn famines_start_when_half_the_living_starve() {
+        let (a, b, c) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let events = [
+            row(1, 1, "agent_born", a, 0),
+            row(2, 1, "agent_born", b, 0),
+            row(3, 1, "agent_born", c, 0),
+            row(4, 5, "action_succeeded", a, 100),
+            // Two of three starving: the famine starts.
+            row(5, 6, "action_succeeded", b, 100),
+            // Still a famine after a starving agent dies: no new event.
+            row(6, 7, "agent_died", a, 100),
+            row(7, 8, "action_succeeded", b, 20),
+            // Over, then back.
+            row(8, 9, "action_succeeded", c, 100),
+        ];
+
+        let mut detectors = builtin_detectors();
+        let derived = detect_all(&mut detectors, &events).unwrap();
+        let famines = derived.get("famine").unwrap();
+        let ticks: Vec<u64> = famines.iter().map(|event| event.tick).collect();
+        assert_eq!(ticks, [6, 9]);
+
+        let first = famines.first().unwrap();
+        assert_eq!(first.event_type, EventType::FamineStarted);
+        assert!(emergence_db::validate_details(first).is_ok());
+        let details: FamineStartedDetails =
+            serde_json::from_value(first.details.clone()).unwrap();
+        assert_eq!((details.starving, details.population), (2, 3));
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let agent = AgentId::new();
+        let events = [row(1, 1, "agent_born", agent, 0), row(2, 2, "action_succeeded", agent, 60)];
+
+        let mut lenient: Vec<Box<dyn Detector>> = vec![Box::new(FamineDetector::new())];
+        assert!(detect_all(&mut lenient, &events).unwrap().get("famine").unwrap().is_empty());
+
+        let strict = FamineDetector::new().with_hunger_threshold(50);
+        let mut strict: Vec<Box<dyn Detector>> = vec![Box::new(strict)];
+        assert_eq!(detect_all(&mut strict, &events).unwrap().get("famine").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,283 @@
+//! In-process publish/subscribe for simulation events.
+//!
+//! Subsystems that react to events -- the reputation tracker, the belief
+//! and economy detectors -- subscribe to the [`EventType`]s they care
+//! about on an [`EventBus`] instead of the tick cycle calling each one by
+//! hand. Whoever produces a tick's events publishes them once; the bus
+//! hands each event to every subscriber interested in its type, in the
+//! order they subscribed.
+//!
+//! Delivery is synchronous and runs on the publisher's thread. A
+//! subscriber that needs to be read from elsewhere keeps its state behind
+//! a shared handle (for example an `Arc<Mutex<ReputationTracker>>`
+//! captured by a closure).
+
+use std::collections::BTreeMap;
+
+use emergence_types::{Event, EventType};
+use serde::de::DeserializeOwned;
+
+/// A handler for published events.
+///
+/// Any `FnMut(&Event) + Send` closure is a subscriber.
+pub trait Subscriber: Send {
+    /// Handle one event of a type this subscriber is interested in.
+    fn handle(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event) + Send> Subscriber for F {
+    fn handle(&mut self, event: &Event) {
+        self(event);
+    }
+}
+
+/// Identifies a subscription, for [`EventBus::unsubscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Routes published events to the subscribers of their type.
+#[derive(Default)]
+pub struct EventBus {
+    /// Every subscriber, in subscription order.
+    subscribers: BTreeMap<SubscriptionId, Box<dyn Subscriber>>,
+    /// Subscribers of each event type, in subscription order.
+    routes: BTreeMap<EventType, Vec<SubscriptionId>>,
+    /// Subscribers of every event type, in subscription order.
+    wildcard: Vec<SubscriptionId>,
+    /// The ID the next subscription gets.
+    next_id: u64,
+}
+
+impl EventBus {
+    /// Create a bus with no subscribers.
+    pub const fn new() -> Self {
+        Self {
+            subscribers: BTreeMap::new(),
+            routes: BTreeMap::new(),
+            wildcard: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Deliver events of any of `event_types` to `subscriber`.
+    pub fn subscribe(
+        &mut self,
+        event_types: &[EventType],
+        subscriber: impl Subscriber + 'static,
+    ) -> SubscriptionId {
+        let id = self.add(subscriber);
+        for &event_type in event_types {
+            let route = self.routes.entry(event_type).or_default();
+            if !route.contains(&id) {
+                route.push(id);
+            }
+        }
+        id
+    }
+
+    /// Deliver every event to `subscriber`.
+    pub fn subscribe_all(&mut self, subscriber: impl Subscriber + 'static) -> SubscriptionId {
+        let id = self.add(subscriber);
+        self.wildcard.push(id);
+        id
+    }
+
+    /// Deliver events of `event_type` to `handler` with their details
+    /// decoded as `T`.
+    ///
+    /// Events whose details do not decode are logged and skipped.
+    pub fn subscribe_details<T, F>(
+        &mut self,
+        event_type: EventType,
+        mut handler: F,
+    ) -> SubscriptionId
+    where
+        T: DeserializeOwned,
+        F: FnMut(&Event, T) + Send + 'static,
+    {
+        self.subscribe(&[event_type], move |event: &Event| {
+            match T::deserialize(&event.details) {
+                Ok(details) => handler(event, details),
+                Err(error) => tracing::warn!(
+                    event_id = %event.id,
+                    ?event_type,
+                    %error,
+                    "Skipping event with malformed details"
+                ),
+            }
+        })
+    }
+
+    /// Register `subscriber` under a fresh ID.
+    fn add(&mut self, subscriber: impl Subscriber + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id = self.next_id.saturating_add(1);
+        self.subscribers.insert(id, Box::new(subscriber));
+        id
+    }
+
+    /// Remove a subscription. Returns whether it existed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        for route in self.routes.values_mut() {
+            route.retain(|routed| *routed != id);
+        }
+        self.routes.retain(|_, route| !route.is_empty());
+        self.wildcard.retain(|routed| *routed != id);
+        self.subscribers.remove(&id).is_some()
+    }
+
+    /// Number of subscriptions.
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Whether nothing is subscribed.
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Deliver `event` to its type's subscribers. Returns how many
+    /// received it.
+    pub fn publish(&mut self, event: &Event) -> usize {
+        let routed = self.routes.get(&event.event_type).map_or(&[][..], Vec::as_slice);
+        let mut ids: Vec<SubscriptionId> = routed.iter().chain(&self.wildcard).copied().collect();
+        ids.sort_unstable();
+
+        let mut delivered = 0_usize;
+        for id in ids {
+            if let Some(subscriber) = self.subscribers.get_mut(&id) {
+                subscriber.handle(event);
+                delivered = delivered.saturating_add(1);
+            }
+        }
+        delivered
+    }
+
+    /// Deliver each of `events` in order. Returns the total number of
+    /// deliveries.
+    pub fn publish_all(&mut self, events: &[Event]) -> usize {
+        events
+            .iter()
+            .fold(0, |delivered, event| delivered.saturating_add(self.publish(event)))
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.subscribers.len())
+            .field("routes", &self.routes)
+            .field("wildcard", &self.wildcard)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::Utc;
+    use emergence_types::{Era, EventId, KnowledgeDiscoveredDetails, Season, Weather, WorldContext};
+
+    use super::*;
+
+    fn event(event_type: EventType, details: serde_json::Value) -> Event {
+        Event {
+            id: EventId::new(),
+            tick: 1,
+            event_type,
+            agent_id: None,
+            location_id: None,
+            details,
+            agent_state_snapshot: None,
+            world_context: WorldContext {
+                tick: 1,
+                era: Era::Primitive,
+                season: Season::Spring,
+                weather: Weather::Clear,
+                population: 1,
+            },
+            created_at: Utc::now(),
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    /// A subscriber recording the types it saw under `label`.
+    fn recorder(
+        log: &Arc<Mutex<Vec<(&'static str, EventType)>>>,
+        label: &'static str,
+    ) -> impl Subscriber + 'static {
+        let log = Arc::clone(log);
+        move |event: &Event| log.lock().unwrap().push((label, event.event_type))
+    }
+
+    #[test]
+    fn routes_events_by_type_in_subscription_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut bus = EventBus::new();
+        bus.subscribe(&[EventType::TradeCompleted], recorder(&log, "economy"));
+        bus.subscribe_all(recorder(&log, "audit"));
+        bus.subscribe(
+            &[EventType::TradeCompleted, EventType::TheftOccurred],
+            recorder(&log, "reputation"),
+        );
+        assert_eq!(bus.len(), 3);
+
+        let events = [
+            event(EventType::TradeCompleted, serde_json::json!({})),
+            event(EventType::TheftOccurred, serde_json::json!({})),
+            event(EventType::TickEnd, serde_json::json!({})),
+        ];
+        assert_eq!(bus.publish_all(&events), 6);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                ("economy", EventType::TradeCompleted),
+                ("audit", EventType::TradeCompleted),
+                ("reputation", EventType::TradeCompleted),
+                ("audit", EventType::TheftOccurred),
+                ("reputation", EventType::TheftOccurred),
+                ("audit", EventType::TickEnd),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsubscribed_handlers_stop_receiving() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut bus = EventBus::new();
+        let id = bus.subscribe(&[EventType::TickEnd], recorder(&log, "a"));
+        let all = bus.subscribe_all(recorder(&log, "b"));
+
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+        assert_eq!(bus.publish(&event(EventType::TickEnd, serde_json::json!({}))), 1);
+        assert!(bus.unsubscribe(all));
+        assert!(bus.is_empty());
+        assert_eq!(bus.publish(&event(EventType::TickEnd, serde_json::json!({}))), 0);
+    }
+
+    #[test]
+    fn decodes_details_for_typed_subscribers() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut bus = EventBus::new();
+        let sink = Arc::clone(&seen);
+        bus.subscribe_details(
+            EventType::KnowledgeDiscovered,
+            move |_: &Event, details: KnowledgeDiscoveredDetails| {
+                sink.lock().unwrap().push(details.knowledge);
+            },
+        );
+
+        let discovered = serde_json::json!({
+            "knowledge": "fire",
+            "method": "experimentation",
+            "prerequisites": [],
+        });
+        bus.publish(&event(EventType::KnowledgeDiscovered, discovered));
+        bus.publish(&event(EventType::KnowledgeDiscovered, serde_json::json!({})));
+        assert_eq!(*seen.lock().unwrap(), ["fire"]);
+    }
+}
@@ -0,0 +1,419 @@
+//! Folding superseded events into summaries so the event log stops growing
+//! without bound.
+//!
+//! Most of a long run's events are routine: every gather, every consumed
+//! ration, every submitted and resolved action. Once a snapshot covers
+//! them they no longer matter for [hydration](crate::hydrate) -- only as
+//! history -- so compaction replaces each agent's routine events of one
+//! type within a window of ticks by a single
+//! [`EventType::EventsCompacted`] summary carrying their count, tick span,
+//! and summed resource quantities.
+//!
+//! An event is folded only when all of these hold:
+//!
+//! - its type is in [`FOLDABLE`]; births, deaths, trades, discoveries, and
+//!   every other narrative event are always kept,
+//! - it is older than the retention window, and
+//! - a snapshot covers it: the agent's own snapshot for agent events, the
+//!   world snapshot for the rest.
+
+use std::collections::BTreeMap;
+
+use emergence_db::{EventRow, EventStore, SnapshotStore};
+use emergence_types::{
+    AgentId, Event, EventId, EventType, EventsCompactedDetails, Resource, WorldContext,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+/// Event types compaction may fold.
+pub const FOLDABLE: &[EventType] = &[
+    EventType::TickStart,
+    EventType::TickEnd,
+    EventType::ActionSubmitted,
+    EventType::ActionSucceeded,
+    EventType::ActionRejected,
+    EventType::ResourceGathered,
+    EventType::ResourceConsumed,
+];
+
+/// Default number of most recent ticks whose events are never compacted.
+const DEFAULT_RETENTION_TICKS: u64 = 10_000;
+
+/// Default width of the tick windows events are folded within.
+const DEFAULT_WINDOW_TICKS: u64 = 1_000;
+
+/// Errors that can occur during compaction.
+#[derive(Debug, thiserror::Error)]
+pub enum CompactError {
+    /// A snapshot or event query, or the rewrite itself, failed.
+    #[error("database error: {0}")]
+    Db(#[from] emergence_db::DbError),
+
+    /// A folded event's world context could not be decoded.
+    #[error("event {event_id} at tick {tick} has a malformed world context: {source}")]
+    WorldContext {
+        /// The event's row ID.
+        event_id: i64,
+        /// The tick the event occurred at.
+        tick: i64,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+
+    /// A summary's details could not be encoded.
+    #[error("cannot encode a compaction summary: {0}")]
+    Encode(serde_json::Error),
+}
+
+/// Compaction settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionConfig {
+    /// Events from the most recent `retention_ticks` ticks are kept as
+    /// they are.
+    pub retention_ticks: u64,
+    /// Events are folded within windows of this many ticks: one summary
+    /// per agent, event type, and window.
+    pub window_ticks: u64,
+}
+
+impl CompactionConfig {
+    /// Create the default settings.
+    pub const fn new() -> Self {
+        Self {
+            retention_ticks: DEFAULT_RETENTION_TICKS,
+            window_ticks: DEFAULT_WINDOW_TICKS,
+        }
+    }
+
+    /// Set the retention window.
+    #[must_use]
+    pub const fn with_retention_ticks(mut self, ticks: u64) -> Self {
+        self.retention_ticks = ticks;
+        self
+    }
+
+    /// Set the folding window width.
+    #[must_use]
+    pub const fn with_window_ticks(mut self, ticks: u64) -> Self {
+        self.window_ticks = ticks;
+        self
+    }
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The ticks up to which snapshots cover the event log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Tick of the latest world snapshot, if any.
+    pub world: Option<u64>,
+    /// Tick of each agent's latest snapshot.
+    pub agents: BTreeMap<AgentId, u64>,
+}
+
+impl Coverage {
+    /// Whether a snapshot covers an event of `agent_id` (or the world) at
+    /// `tick`.
+    fn covers(&self, agent_id: Option<AgentId>, tick: u64) -> bool {
+        let base = agent_id.map_or(self.world, |id| self.agents.get(&id).copied());
+        base.is_some_and(|base| tick <= base)
+    }
+}
+
+/// The rewrite compaction makes to one window of events.
+#[derive(Debug, Clone, Default)]
+pub struct CompactionPlan {
+    /// Row IDs of the events to remove.
+    pub folded: Vec<i64>,
+    /// The summaries that replace them.
+    pub summaries: Vec<Event>,
+}
+
+impl CompactionPlan {
+    /// Whether the plan changes nothing.
+    pub const fn is_empty(&self) -> bool {
+        self.folded.is_empty()
+    }
+}
+
+/// What a compaction run did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Events with ticks up to this one have been considered; pass it as
+    /// `after_tick` next time to skip them.
+    pub through_tick: u64,
+    /// Number of events removed.
+    pub folded: u64,
+    /// Number of summary events written.
+    pub summaries: usize,
+}
+
+/// The quantity fields shared by resource event details.
+#[derive(Deserialize)]
+struct Quantity {
+    resource: Resource,
+    quantity: u32,
+}
+
+/// Events folded into one summary so far.
+struct Group<'a> {
+    rows: Vec<&'a EventRow>,
+    resources: BTreeMap<Resource, u32>,
+}
+
+/// Plan the compaction of `events`, all older than the retention window.
+///
+/// Events are grouped by agent and type; a group is folded only if it
+/// holds more than one event and its latest event carries the world
+/// context the summary inherits.
+///
+/// # Errors
+///
+/// Returns [`CompactError::WorldContext`] if that context is malformed, or
+/// [`CompactError::Encode`] if a summary cannot be encoded.
+pub fn plan(events: &[EventRow], coverage: &Coverage) -> Result<CompactionPlan, CompactError> {
+    let mut groups: BTreeMap<(Option<AgentId>, EventType), Group<'_>> = BTreeMap::new();
+    for row in events {
+        let Some(event_type) = foldable_type(&row.event_type) else {
+            continue;
+        };
+        let agent_id = row.agent_id.map(AgentId::from);
+        let tick = u64::try_from(row.tick).unwrap_or(0);
+        if !coverage.covers(agent_id, tick) {
+            continue;
+        }
+        let group = groups.entry((agent_id, event_type)).or_insert_with(|| Group {
+            rows: Vec::new(),
+            resources: BTreeMap::new(),
+        });
+        if let Ok(q) = Quantity::deserialize(&row.details) {
+            let total = group.resources.entry(q.resource).or_insert(0);
+            *total = total.saturating_add(q.quantity);
+        }
+        group.rows.push(row);
+    }
+
+    let mut plan = CompactionPlan::default();
+    for ((agent_id, event_type), group) in groups {
+        if group.rows.len() < 2 {
+            continue;
+        }
+        let (Some(first), Some(last)) = (group.rows.first(), group.rows.last()) else {
+            continue;
+        };
+        let Some(context) = &last.world_context else {
+            continue;
+        };
+        let world_context: WorldContext =
+            WorldContext::deserialize(context).map_err(|source| CompactError::WorldContext {
+                event_id: last.id,
+                tick: last.tick,
+                source,
+            })?;
+        let first_tick = u64::try_from(first.tick).unwrap_or(0);
+        let last_tick = u64::try_from(last.tick).unwrap_or(0);
+        let details = EventsCompactedDetails {
+            event_type,
+            count: u32::try_from(group.rows.len()).unwrap_or(u32::MAX),
+            first_tick,
+            last_tick,
+            resources: group.resources,
+        };
+        plan.summaries.push(Event {
+            id: EventId::new(),
+            tick: last_tick,
+            event_type: EventType::EventsCompacted,
+            agent_id,
+            location_id: None,
+            details: serde_json::to_value(details).map_err(CompactError::Encode)?,
+            agent_state_snapshot: None,
+            world_context,
+            created_at: last.created_at,
+            caused_by: None,
+            correlation_id: None,
+        });
+        plan.folded.extend(group.rows.iter().map(|row| row.id));
+    }
+    Ok(plan)
+}
+
+/// Compact the event log up to the retention window.
+///
+/// "Now" is the latest world snapshot's tick; with no world snapshot there
+/// is nothing to compact. Windows of
+/// [`window_ticks`](CompactionConfig::window_ticks) after `after_tick` are
+/// folded one at a time, each in its own transaction.
+///
+/// # Errors
+///
+/// Returns [`CompactError::Db`] if a query or rewrite fails, or another
+/// [`CompactError`] from [`plan`].
+pub async fn compact(
+    pool: &PgPool,
+    after_tick: u64,
+    config: &CompactionConfig,
+) -> Result<CompactionReport, CompactError> {
+    let snapshots = SnapshotStore::new(pool);
+    let Some(world) = snapshots.get_world_snapshot_at_or_before(u64::MAX).await? else {
+        return Ok(CompactionReport {
+            through_tick: after_tick,
+            ..CompactionReport::default()
+        });
+    };
+    let now = u64::try_from(world.tick).unwrap_or(0);
+    let coverage = Coverage {
+        world: Some(now),
+        agents: snapshots
+            .get_agent_snapshots_at_or_before(now)
+            .await?
+            .into_iter()
+            .map(|row| (AgentId::from(row.agent_id), u64::try_from(row.tick).unwrap_or(0)))
+            .collect(),
+    };
+
+    let through_tick = now.saturating_sub(config.retention_ticks).max(after_tick);
+    let store = EventStore::new(pool);
+    let mut report = CompactionReport {
+        through_tick,
+        ..CompactionReport::default()
+    };
+    let mut start = after_tick;
+    while start < through_tick {
+        let end = start.saturating_add(config.window_ticks.max(1)).min(through_tick);
+        let events = store.get_events_after(start, end).await?;
+        let window = plan(&events, &coverage)?;
+        if !window.is_empty() {
+            report.folded = report
+                .folded
+                .saturating_add(store.replace_events(&window.folded, &window.summaries).await?);
+            report.summaries = report.summaries.saturating_add(window.summaries.len());
+        }
+        start = end;
+    }
+
+    tracing::info!(
+        through_tick,
+        folded = report.folded,
+        summaries = report.summaries,
+        "Compacted event log"
+    );
+    Ok(report)
+}
+
+/// The [`EventType`] stored as `name`, if compaction may fold it.
+fn foldable_type(name: &str) -> Option<EventType> {
+    FOLDABLE
+        .iter()
+        .copied()
+        .find(|t| emergence_db::event_type_to_db(*t) == name)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{Era, Season, Weather};
+
+    use super::*;
+
+    fn row(id: i64, tick: i64, event_type: &str, agent_id: Option<AgentId>) -> EventRow {
+        let context = WorldContext {
+            tick: u64::try_from(tick).unwrap(),
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 1,
+        };
+        EventRow {
+            id,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: agent_id.map(AgentId::into_inner),
+            location_id: None,
+            details: serde_json::json!({}),
+            agent_state_snapshot: None,
+            world_context: Some(serde_json::to_value(context).unwrap()),
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn gather(id: i64, tick: i64, agent_id: AgentId, quantity: u32) -> EventRow {
+        let mut row = row(id, tick, "resource_gathered", Some(agent_id));
+        row.details = serde_json::json!({"resource": "Wood", "quantity": quantity});
+        row
+    }
+
+    #[test]
+    fn folds_covered_routine_events_per_agent_and_type() {
+        let (dead, other) = (AgentId::new(), AgentId::new());
+        let coverage = Coverage {
+            world: Some(100),
+            agents: [(dead, 100), (other, 100)].into_iter().collect(),
+        };
+        let events = vec![
+            gather(1, 10, dead, 2),
+            gather(2, 20, dead, 3),
+            row(3, 25, "agent_died", Some(dead)),
+            gather(4, 30, other, 1),
+            row(5, 30, "tick_end", None),
+            row(6, 40, "tick_end", None),
+        ];
+
+        let plan = plan(&events, &coverage).unwrap();
+        assert_eq!(plan.folded, vec![5, 6, 1, 2]);
+        assert_eq!(plan.summaries.len(), 2);
+
+        let summary = plan
+            .summaries
+            .iter()
+            .find(|e| e.agent_id == Some(dead))
+            .unwrap();
+        let details: EventsCompactedDetails =
+            serde_json::from_value(summary.details.clone()).unwrap();
+        assert_eq!(summary.event_type, EventType::EventsCompacted);
+        assert_eq!(summary.tick, 20);
+        assert_eq!(details.event_type, EventType::ResourceGathered);
+        assert_eq!((details.count, details.first_tick, details.last_tick), (2, 10, 20));
+        assert_eq!(details.resources.get(&Resource::Wood), Some(&5));
+    }
+
+    #[test]
+    fn uncovered_events_are_kept() {
+        let covered = AgentId::new();
+        let uncovered = AgentId::new();
+        let coverage = Coverage {
+            world: None,
+            agents: std::iter::once((covered, 15)).collect(),
+        };
+        let events = vec![
+            gather(1, 10, covered, 1),
+            gather(2, 12, covered, 1),
+            gather(3, 20, covered, 1),
+            gather(4, 10, uncovered, 1),
+            gather(5, 11, uncovered, 1),
+            row(6, 10, "tick_start", None),
+            row(7, 11, "tick_start", None),
+        ];
+
+        let plan = plan(&events, &coverage).unwrap();
+        assert_eq!(plan.folded, vec![1, 2]);
+        assert_eq!(plan.summaries.len(), 1);
+    }
+
+    #[test]
+    fn defaults_and_builders() {
+        let config = CompactionConfig::default().with_retention_ticks(50).with_window_ticks(5);
+        assert_eq!(config.retention_ticks, 50);
+        assert_eq!(config.window_ticks, 5);
+        assert_eq!(CompactionConfig::new().retention_ticks, DEFAULT_RETENTION_TICKS);
+    }
+}
@@ -0,0 +1,488 @@
+//! Verifying that the event log fully determines derived state.
+//!
+//! A run is reproducible only if its seed and event log always rebuild
+//! the same world. [`verify_run`] replays a completed run twice, side by
+//! side, through [hydration](crate::hydrate) and every built-in
+//! [projection](crate::projection), and compares each [`Subsystem`]'s
+//! state after every tick. It also compares the replayed agents against
+//! the agent snapshots recorded during the run. The report names the first
+//! tick and subsystem at which anything differs.
+//!
+//! Two replays diverging means the replay logic itself is
+//! nondeterministic; a replay diverging from a snapshot means the events
+//! do not carry everything the engine changed.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use emergence_db::{AgentSnapshotRow, DbError, EventRow, EventStore, SnapshotStore};
+use emergence_types::{AgentId, AgentState};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::hydrate::{HydrateError, HydratedState};
+use crate::projection::{ProjectionError, ProjectionRunner};
+use crate::tick_of;
+use crate::upcast::Upcasters;
+
+/// Ticks whose events and snapshots [`verify_run`] loads at a time.
+const WINDOW_TICKS: u64 = 1_000;
+
+/// Errors that can occur while verifying a run.
+#[derive(Debug, thiserror::Error)]
+pub enum DeterminismError {
+    /// An event or snapshot query failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// An event or recorded snapshot could not be replayed.
+    #[error("replay failed: {0}")]
+    Hydrate(#[from] HydrateError),
+
+    /// A projection could not fold an event.
+    #[error("projection failed: {0}")]
+    Projection(#[from] ProjectionError),
+
+    /// Replayed state could not be encoded for comparison.
+    #[error("cannot encode replayed state: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// A part of the derived state that is compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Subsystem {
+    /// Every agent's hydrated state.
+    Agents,
+    /// The set of dead agents.
+    Deaths,
+    /// The world context of the latest event.
+    WorldContext,
+    /// A projection's view, by name.
+    Projection(&'static str),
+    /// One agent's replayed state against its recorded snapshot.
+    AgentSnapshot(AgentId),
+}
+
+impl std::fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Agents => f.write_str("agents"),
+            Self::Deaths => f.write_str("deaths"),
+            Self::WorldContext => f.write_str("world context"),
+            Self::Projection(name) => write!(f, "projection {name}"),
+            Self::AgentSnapshot(agent_id) => write!(f, "snapshot of agent {agent_id}"),
+        }
+    }
+}
+
+/// The first point at which derived state differed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Divergence {
+    /// The tick after which the states differed.
+    pub tick: u64,
+    /// Which state differed.
+    pub subsystem: Subsystem,
+    /// The state from the first replay, or the recorded snapshot.
+    pub expected: serde_json::Value,
+    /// The state from the second replay, or the replayed agent.
+    pub actual: serde_json::Value,
+}
+
+/// The result of verifying a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeterminismReport {
+    /// Number of ticks with events that were replayed.
+    pub ticks: u64,
+    /// Number of events replayed (each replay counted once).
+    pub events: u64,
+    /// Number of recorded agent snapshots compared.
+    pub snapshots: u64,
+    /// The first divergence, if any. Verification stops there.
+    pub divergence: Option<Divergence>,
+}
+
+impl DeterminismReport {
+    /// Whether every replayed tick matched.
+    pub const fn is_deterministic(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// One independent replay of the run.
+struct Replica {
+    state: HydratedState,
+    projections: ProjectionRunner,
+}
+
+impl Replica {
+    /// An empty replay up to `to_tick`, reading events through
+    /// `upcasters`.
+    fn new(to_tick: u64, upcasters: Arc<Upcasters>) -> Result<Self, DeterminismError> {
+        Ok(Self {
+            state: HydratedState::from_snapshots(to_tick, None, Vec::new())?
+                .with_upcasters(Arc::clone(&upcasters)),
+            projections: ProjectionRunner::with_builtins().with_upcasters(upcasters),
+        })
+    }
+
+    /// Apply every event of one tick.
+    fn apply(&mut self, events: &[EventRow]) -> Result<(), DeterminismError> {
+        for event in events {
+            self.state.apply(event)?;
+        }
+        self.projections.feed(events)?;
+        Ok(())
+    }
+
+    /// Every subsystem's current state, in a fixed order.
+    fn subsystems(&self) -> Result<Vec<(Subsystem, serde_json::Value)>, DeterminismError> {
+        let mut states = vec![
+            (Subsystem::Agents, serde_json::to_value(&self.state.agents)?),
+            (Subsystem::Deaths, serde_json::to_value(&self.state.dead)?),
+            (Subsystem::WorldContext, serde_json::to_value(&self.state.world_context)?),
+        ];
+        for name in self.projections.names() {
+            if let Some(projection) = self.projections.get(name) {
+                states.push((Subsystem::Projection(name), projection.state()?));
+            }
+        }
+        Ok(states)
+    }
+}
+
+/// The parts of an agent's state that events carry.
+fn observed(state: &AgentState) -> serde_json::Value {
+    serde_json::json!({
+        "energy": state.energy,
+        "health": state.health,
+        "hunger": state.hunger,
+        "age": state.age,
+        "location_id": state.location_id,
+        "inventory": state.inventory,
+    })
+}
+
+/// Replays a run twice, tick by tick, and compares the results.
+pub struct DeterminismVerifier {
+    first: Replica,
+    second: Replica,
+    report: DeterminismReport,
+}
+
+impl DeterminismVerifier {
+    /// A verifier for a run ending at `to_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeterminismError::Hydrate`] if the empty replay state
+    /// cannot be built.
+    pub fn new(to_tick: u64) -> Result<Self, DeterminismError> {
+        Self::with_upcasters(to_tick, Arc::new(Upcasters::builtin()))
+    }
+
+    /// A verifier for a run ending at `to_tick` that reads events through
+    /// `upcasters` instead of [`Upcasters::builtin`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeterminismError::Hydrate`] if the empty replay state
+    /// cannot be built.
+    pub fn with_upcasters(
+        to_tick: u64,
+        upcasters: Arc<Upcasters>,
+    ) -> Result<Self, DeterminismError> {
+        Ok(Self {
+            first: Replica::new(to_tick, Arc::clone(&upcasters))?,
+            second: Replica::new(to_tick, upcasters)?,
+            report: DeterminismReport::default(),
+        })
+    }
+
+    /// Replay every event of `tick` in both replays and compare them, then
+    /// compare the replayed agents with the snapshots `recorded` at `tick`.
+    /// Ticks must be checked in order. Returns the divergence, if any has
+    /// been found; after one, further ticks are not replayed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DeterminismError`] if an event, a projection, or a
+    /// recorded snapshot fails to replay or decode.
+    pub fn check_tick(
+        &mut self,
+        tick: u64,
+        events: &[EventRow],
+        recorded: &[AgentSnapshotRow],
+    ) -> Result<Option<&Divergence>, DeterminismError> {
+        if self.report.divergence.is_none() {
+            self.report.divergence = self.compare_tick(tick, events, recorded)?;
+        }
+        Ok(self.report.divergence.as_ref())
+    }
+
+    /// The first divergence at `tick`, if any.
+    fn compare_tick(
+        &mut self,
+        tick: u64,
+        events: &[EventRow],
+        recorded: &[AgentSnapshotRow],
+    ) -> Result<Option<Divergence>, DeterminismError> {
+        if !events.is_empty() {
+            self.first.apply(events)?;
+            self.second.apply(events)?;
+            self.report.ticks = self.report.ticks.saturating_add(1);
+            let count = u64::try_from(events.len()).unwrap_or(u64::MAX);
+            self.report.events = self.report.events.saturating_add(count);
+
+            let second = self.second.subsystems()?;
+            let first = self.first.subsystems()?;
+            for ((subsystem, expected), (_, actual)) in first.into_iter().zip(second) {
+                if expected != actual {
+                    return Ok(Some(Divergence {
+                        tick,
+                        subsystem,
+                        expected,
+                        actual,
+                    }));
+                }
+            }
+        }
+
+        for row in recorded {
+            self.report.snapshots = self.report.snapshots.saturating_add(1);
+            let agent_id = AgentId::from(row.agent_id);
+            let snapshot: AgentState = serde_json::from_value(row.full_state.clone())
+                .map_err(|source| HydrateError::AgentSnapshot {
+                    agent_id: row.agent_id,
+                    tick: row.tick,
+                    source,
+                })?;
+            let expected = observed(&snapshot);
+            let actual = self
+                .first
+                .state
+                .agents
+                .get(&agent_id)
+                .map_or(serde_json::Value::Null, observed);
+            if expected != actual {
+                return Ok(Some(Divergence {
+                    tick,
+                    subsystem: Subsystem::AgentSnapshot(agent_id),
+                    expected,
+                    actual,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The report so far.
+    pub const fn report(&self) -> &DeterminismReport {
+        &self.report
+    }
+
+    /// The final report.
+    pub fn into_report(self) -> DeterminismReport {
+        self.report
+    }
+}
+
+impl std::fmt::Debug for DeterminismVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeterminismVerifier")
+            .field("report", &self.report)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replay the run's events through `to_tick` twice and against its
+/// recorded agent snapshots, reporting the first divergence.
+///
+/// # Errors
+///
+/// Returns [`DeterminismError::Db`] if a query fails, or another
+/// [`DeterminismError`] if an event or snapshot cannot be replayed.
+pub async fn verify_run(
+    pool: &PgPool,
+    to_tick: u64,
+) -> Result<DeterminismReport, DeterminismError> {
+    let events = EventStore::new(pool);
+    let snapshots = SnapshotStore::new(pool);
+    let mut verifier = DeterminismVerifier::new(to_tick)?;
+
+    let end = to_tick.saturating_add(1);
+    let mut start = 0;
+    while start < end {
+        let stop = start.saturating_add(WINDOW_TICKS).min(end);
+        let mut ticks: BTreeMap<u64, (Vec<EventRow>, Vec<AgentSnapshotRow>)> = BTreeMap::new();
+        for event in events.get_events_in_range(start, stop).await? {
+            ticks.entry(tick_of(event.tick)).or_default().0.push(event);
+        }
+        for row in snapshots.get_agent_snapshots_in_range(start, stop).await? {
+            ticks.entry(tick_of(row.tick)).or_default().1.push(row);
+        }
+        for (tick, (tick_events, recorded)) in &ticks {
+            if verifier.check_tick(*tick, tick_events, recorded)?.is_some() {
+                break;
+            }
+        }
+        if verifier.report().divergence.is_some() {
+            break;
+        }
+        start = stop;
+    }
+
+    let report = verifier.into_report();
+    if let Some(divergence) = &report.divergence {
+        tracing::warn!(
+            tick = divergence.tick,
+            subsystem = %divergence.subsystem,
+            "Replay diverged"
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{
+        AgentStateSnapshot, Era, EventType, LocationId, Resource, Season, StructureId, Weather,
+        WorldContext,
+    };
+
+    use super::*;
+    use crate::upcast::tests::RenameField;
+
+    fn event(id: i64, tick: i64, event_type: &str, agent: &AgentState) -> EventRow {
+        let context = WorldContext {
+            tick: tick_of(tick),
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 1,
+        };
+        let snapshot = AgentStateSnapshot {
+            energy: agent.energy,
+            health: agent.health,
+            hunger: agent.hunger,
+            age: agent.age,
+            location_id: agent.location_id,
+            inventory_summary: agent.inventory.clone(),
+        };
+        EventRow {
+            id,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: Some(agent.agent_id.into_inner()),
+            location_id: Some(agent.location_id.into_inner()),
+            details: serde_json::json!({}),
+            agent_state_snapshot: Some(serde_json::to_value(snapshot).unwrap()),
+            world_context: Some(serde_json::to_value(context).unwrap()),
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn recorded(tick: i64, agent: &AgentState) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            id: tick,
+            tick,
+            agent_id: agent.agent_id.into_inner(),
+            full_state: serde_json::to_value(agent).unwrap(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn agent() -> AgentState {
+        let mut agent = AgentState::builder(AgentId::new(), LocationId::new()).build().unwrap();
+        agent.inventory.insert(Resource::Wood, 3);
+        agent
+    }
+
+    #[test]
+    fn snapshots_the_events_disagree_with_are_reported() {
+        let mut agent = agent();
+        let mut verifier = DeterminismVerifier::new(10).unwrap();
+        let born = event(1, 1, "agent_born", &agent);
+        assert!(verifier.check_tick(1, &[born], &[]).unwrap().is_none());
+
+        // The engine made the agent hungrier than its event says.
+        agent.energy = 40;
+        let acted = event(2, 2, "action_succeeded", &agent);
+        let mut engine = agent.clone();
+        engine.hunger = 30;
+        let snapshot = recorded(2, &engine);
+
+        let divergence = verifier.check_tick(2, &[acted], &[snapshot]).unwrap().cloned().unwrap();
+        assert_eq!(divergence.tick, 2);
+        assert_eq!(divergence.subsystem, Subsystem::AgentSnapshot(agent.agent_id));
+        assert_eq!(divergence.expected.get("hunger").unwrap(), 30);
+        assert_eq!(divergence.actual.get("energy").unwrap(), 40);
+
+        let report = verifier.into_report();
+        assert_eq!((report.ticks, report.events, report.snapshots), (2, 2, 1));
+    }
+
+    #[test]
+    fn matching_snapshots_verify() {
+        let agent = agent();
+        let mut verifier = DeterminismVerifier::new(10).unwrap();
+        let born = event(1, 1, "agent_born", &agent);
+        verifier.check_tick(1, &[born], &[recorded(1, &agent)]).unwrap();
+        assert!(verifier.report().is_deterministic());
+    }
+
+    #[test]
+    fn old_events_replay_through_the_upcasters() {
+        // Version 1 of structure-built details called the location `site`.
+        let agent = agent();
+        let mut built = event(1, 1, "structure_built", &agent);
+        built.details = serde_json::json!({
+            "structure_id": StructureId::new(),
+            "structure_type": "Campfire",
+            "site": agent.location_id,
+            "builder": agent.agent_id,
+            "materials_used": {},
+        });
+
+        let mut stale = DeterminismVerifier::new(10).unwrap();
+        let err = stale.check_tick(1, std::slice::from_ref(&built), &[]).unwrap_err();
+        assert!(matches!(err, DeterminismError::Projection(ProjectionError::Apply { .. })));
+
+        let mut upcasters = Upcasters::new().migrating_to(2);
+        upcasters.register(RenameField {
+            event_type: EventType::StructureBuilt,
+            source_version: 1,
+            from: "site",
+            to: "location_id",
+        });
+        let mut verifier = DeterminismVerifier::with_upcasters(10, Arc::new(upcasters)).unwrap();
+        assert!(verifier.check_tick(1, &[built], &[]).unwrap().is_none());
+        let structures = verifier.first.projections.get("structures_per_location").unwrap();
+        let standing = structures.state().unwrap();
+        assert!(standing.to_string().contains(&agent.location_id.to_string()));
+    }
+
+    #[test]
+    fn nondeterministic_replay_is_reported() {
+        // A newborn without a location is placed at a random one, so the
+        // two replays disagree.
+        let mut born = event(1, 3, "agent_born", &agent());
+        born.location_id = None;
+        born.agent_state_snapshot = None;
+
+        let mut verifier = DeterminismVerifier::new(10).unwrap();
+        let divergence = verifier.check_tick(3, &[born], &[]).unwrap().cloned().unwrap();
+        assert_eq!((divergence.tick, divergence.subsystem), (3, Subsystem::Agents));
+        assert_ne!(divergence.expected, divergence.actual);
+
+        // Later ticks are not replayed once diverged.
+        let later = event(2, 4, "action_succeeded", &agent());
+        verifier.check_tick(4, &[later], &[]).unwrap();
+        assert_eq!(verifier.report().ticks, 1);
+    }
+}
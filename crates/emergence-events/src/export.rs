@@ -0,0 +1,712 @@
+//! Exporting a tick range of events to files for offline analysis.
+//!
+//! [`export_events`] reads the event log window by window and writes it
+//! either as JSON lines (one `events.jsonl`) or as Parquet (one
+//! `events_<type>.parquet` per event type, so every file has a fixed
+//! schema). Both load directly into pandas or `DuckDB`.
+//!
+//! Each row is flattened: the world context becomes `era`, `season`,
+//! `weather`, and `population` columns, and the details of the common
+//! event types in [`FLATTENED`] become `detail_<field>` columns. Nested
+//! values -- resource maps, lists -- stay JSON. Every row also keeps its
+//! full `details`, so fields without a column of their own are not lost.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use emergence_db::{event_type_to_db, EventRow, EventStore};
+use emergence_types::EventType;
+use parquet::basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DataType, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::types::{Type, TypePtr};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Default width of the tick windows events are read in.
+const DEFAULT_WINDOW_TICKS: u64 = 1_000;
+
+/// Event types whose details get their own columns, and the fields
+/// flattened for each.
+pub const FLATTENED: &[(EventType, &[(&str, ColumnKind)])] = &[
+    (
+        EventType::ResourceGathered,
+        &[
+            ("resource", ColumnKind::Text),
+            ("quantity", ColumnKind::Int),
+            ("location_id", ColumnKind::Text),
+            ("skill_xp_gained", ColumnKind::Int),
+        ],
+    ),
+    (
+        EventType::TradeCompleted,
+        &[
+            ("trade_id", ColumnKind::Text),
+            ("agent_a", ColumnKind::Text),
+            ("agent_b", ColumnKind::Text),
+            ("gave", ColumnKind::Text),
+            ("received", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::TradeFailed,
+        &[
+            ("trade_id", ColumnKind::Text),
+            ("reason", ColumnKind::Text),
+            ("offerer_id", ColumnKind::Text),
+            ("target_id", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::AgentDied,
+        &[
+            ("cause", ColumnKind::Text),
+            ("final_age", ColumnKind::Int),
+            ("inventory_dropped", ColumnKind::Text),
+            ("structures_orphaned", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::StructureBuilt,
+        &[
+            ("structure_id", ColumnKind::Text),
+            ("structure_type", ColumnKind::Text),
+            ("location_id", ColumnKind::Text),
+            ("builder", ColumnKind::Text),
+            ("materials_used", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::StructureDestroyed,
+        &[
+            ("structure_id", ColumnKind::Text),
+            ("structure_type", ColumnKind::Text),
+            ("location_id", ColumnKind::Text),
+            ("cause", ColumnKind::Text),
+            ("materials_salvaged", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::KnowledgeDiscovered,
+        &[
+            ("knowledge", ColumnKind::Text),
+            ("method", ColumnKind::Text),
+            ("prerequisites", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::KnowledgeTaught,
+        &[
+            ("knowledge", ColumnKind::Text),
+            ("teacher_id", ColumnKind::Text),
+            ("student_id", ColumnKind::Text),
+            ("success", ColumnKind::Bool),
+        ],
+    ),
+    (
+        EventType::TheftOccurred,
+        &[
+            ("thief_id", ColumnKind::Text),
+            ("victim_id", ColumnKind::Text),
+            ("resource", ColumnKind::Text),
+            ("quantity_stolen", ColumnKind::Int),
+            ("detected", ColumnKind::Bool),
+            ("location_id", ColumnKind::Text),
+        ],
+    ),
+    (
+        EventType::CombatResolved,
+        &[
+            ("attacker_id", ColumnKind::Text),
+            ("defender_id", ColumnKind::Text),
+            ("intent", ColumnKind::Text),
+            ("winner", ColumnKind::Text),
+            ("attacker_damage", ColumnKind::Int),
+            ("defender_damage", ColumnKind::Int),
+            ("attacker_died", ColumnKind::Bool),
+            ("defender_died", ColumnKind::Bool),
+            ("location_id", ColumnKind::Text),
+        ],
+    ),
+];
+
+/// Columns every exported row has, before its detail columns.
+const BASE_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("id", ColumnKind::Int),
+    ("tick", ColumnKind::Int),
+    ("event_type", ColumnKind::Text),
+    ("agent_id", ColumnKind::Text),
+    ("location_id", ColumnKind::Text),
+    ("era", ColumnKind::Text),
+    ("season", ColumnKind::Text),
+    ("weather", ColumnKind::Text),
+    ("population", ColumnKind::Int),
+    ("created_at", ColumnKind::Timestamp),
+    ("schema_version", ColumnKind::Int),
+    ("event_id", ColumnKind::Text),
+    ("caused_by", ColumnKind::Text),
+    ("correlation_id", ColumnKind::Text),
+];
+
+/// Errors that can occur during an export.
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    /// An event query failed.
+    #[error("database error: {0}")]
+    Db(#[from] emergence_db::DbError),
+
+    /// An output file could not be created or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A Parquet file could not be written.
+    #[error("parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+
+    /// A JSON line could not be encoded.
+    #[error("cannot encode an exported event: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// The file format to export to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line, all event types in one file.
+    Jsonl,
+    /// One Parquet file per event type.
+    Parquet,
+}
+
+/// The value type of an exported column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// A 64-bit integer.
+    Int,
+    /// A string; nested JSON values are stored as their JSON text.
+    Text,
+    /// A boolean.
+    Bool,
+    /// A UTC timestamp, in microseconds in Parquet and RFC 3339 in JSON.
+    Timestamp,
+}
+
+/// Export settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// The output format.
+    pub format: ExportFormat,
+    /// Events are read, and Parquet row groups written, in windows of this
+    /// many ticks.
+    pub window_ticks: u64,
+}
+
+impl ExportConfig {
+    /// Create the default settings: JSON lines, in windows of 1000 ticks.
+    pub const fn new() -> Self {
+        Self {
+            format: ExportFormat::Jsonl,
+            window_ticks: DEFAULT_WINDOW_TICKS,
+        }
+    }
+
+    /// Set the output format.
+    #[must_use]
+    pub const fn with_format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the read window width.
+    #[must_use]
+    pub const fn with_window_ticks(mut self, ticks: u64) -> Self {
+        self.window_ticks = ticks;
+        self
+    }
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What an export wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportReport {
+    /// Number of events exported.
+    pub events: usize,
+    /// The files written, in event type order for Parquet.
+    pub files: Vec<PathBuf>,
+}
+
+/// One column of an exported row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Column {
+    /// The column name.
+    name: String,
+    /// The column's value type.
+    kind: ColumnKind,
+}
+
+/// One value of an exported row.
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    /// No value.
+    Null,
+    /// An integer.
+    Int(i64),
+    /// A string.
+    Text(String),
+    /// A boolean.
+    Bool(bool),
+    /// A timestamp.
+    Timestamp(DateTime<Utc>),
+    /// A nested value, kept as JSON.
+    Json(serde_json::Value),
+}
+
+impl Cell {
+    /// The cell for `value` in a column of `kind`.
+    fn from_json(kind: ColumnKind, value: Option<&serde_json::Value>) -> Self {
+        match (kind, value) {
+            (_, None | Some(serde_json::Value::Null)) => Self::Null,
+            (ColumnKind::Int, Some(value)) => value.as_i64().map_or(Self::Null, Self::Int),
+            (ColumnKind::Bool, Some(value)) => value.as_bool().map_or(Self::Null, Self::Bool),
+            (ColumnKind::Text | ColumnKind::Timestamp, Some(serde_json::Value::String(text))) => {
+                Self::Text(text.clone())
+            }
+            (ColumnKind::Text | ColumnKind::Timestamp, Some(value)) => Self::Json(value.clone()),
+        }
+    }
+
+    /// The cell for an optional UUID.
+    fn uuid(value: Option<Uuid>) -> Self {
+        value.map_or(Self::Null, |id| Self::Text(id.to_string()))
+    }
+
+    /// The cell as a JSON value.
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Null => serde_json::Value::Null,
+            Self::Int(value) => serde_json::Value::from(*value),
+            Self::Text(text) => serde_json::Value::String(text.clone()),
+            Self::Bool(value) => serde_json::Value::Bool(*value),
+            Self::Timestamp(at) => serde_json::Value::String(at.to_rfc3339()),
+            Self::Json(value) => value.clone(),
+        }
+    }
+
+    /// The cell as a Parquet `INT64`.
+    const fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            Self::Timestamp(at) => Some(at.timestamp_micros()),
+            Self::Null | Self::Text(_) | Self::Bool(_) | Self::Json(_) => None,
+        }
+    }
+
+    /// The cell as a Parquet string.
+    fn as_bytes(&self) -> Option<ByteArray> {
+        match self {
+            Self::Text(text) => Some(ByteArray::from(text.as_str())),
+            Self::Json(value) => Some(ByteArray::from(value.to_string().into_bytes())),
+            Self::Null | Self::Int(_) | Self::Bool(_) | Self::Timestamp(_) => None,
+        }
+    }
+
+    /// The cell as a Parquet `BOOLEAN`.
+    const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            Self::Null | Self::Int(_) | Self::Text(_) | Self::Timestamp(_) | Self::Json(_) => None,
+        }
+    }
+}
+
+/// The fields of `event_type`'s details that get their own columns.
+fn detail_fields(event_type: &str) -> &'static [(&'static str, ColumnKind)] {
+    FLATTENED
+        .iter()
+        .find(|(flattened, _)| event_type_to_db(*flattened) == event_type)
+        .map_or(&[], |(_, fields)| *fields)
+}
+
+/// The columns of an exported `event_type` row.
+fn columns(event_type: &str) -> Vec<Column> {
+    let base = BASE_COLUMNS.iter().map(|&(name, kind)| Column {
+        name: name.to_owned(),
+        kind,
+    });
+    let details = detail_fields(event_type).iter().map(|&(field, kind)| Column {
+        name: format!("detail_{field}"),
+        kind,
+    });
+    let full = Column {
+        name: "details".to_owned(),
+        kind: ColumnKind::Text,
+    };
+    base.chain(details).chain(std::iter::once(full)).collect()
+}
+
+/// `row` flattened into one cell per column of [`columns`] for its type.
+fn flatten(row: &EventRow) -> Vec<Cell> {
+    let context = |key: &str| row.world_context.as_ref().and_then(|context| context.get(key));
+    let mut cells = vec![
+        Cell::Int(row.id),
+        Cell::Int(row.tick),
+        Cell::Text(row.event_type.clone()),
+        Cell::uuid(row.agent_id),
+        Cell::uuid(row.location_id),
+        Cell::from_json(ColumnKind::Text, context("era")),
+        Cell::from_json(ColumnKind::Text, context("season")),
+        Cell::from_json(ColumnKind::Text, context("weather")),
+        Cell::from_json(ColumnKind::Int, context("population")),
+        Cell::Timestamp(row.created_at),
+        Cell::Int(i64::from(row.schema_version)),
+        Cell::uuid(row.event_id),
+        Cell::uuid(row.caused_by),
+        Cell::uuid(row.correlation_id),
+    ];
+    cells.extend(
+        detail_fields(&row.event_type)
+            .iter()
+            .map(|&(field, kind)| Cell::from_json(kind, row.details.get(field))),
+    );
+    cells.push(Cell::Json(row.details.clone()));
+    cells
+}
+
+/// `row` as one flat JSON object.
+fn to_json_line(row: &EventRow) -> serde_json::Value {
+    let object = columns(&row.event_type)
+        .into_iter()
+        .zip(flatten(row))
+        .map(|(column, cell)| (column.name, cell.to_json()))
+        .collect();
+    serde_json::Value::Object(object)
+}
+
+/// The Parquet schema for rows with `columns`. Every column is optional.
+fn parquet_schema(columns: &[Column]) -> Result<TypePtr, ParquetError> {
+    let fields = columns
+        .iter()
+        .map(|column| {
+            let (physical, logical) = match column.kind {
+                ColumnKind::Int => (PhysicalType::INT64, None),
+                ColumnKind::Text => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                ColumnKind::Bool => (PhysicalType::BOOLEAN, None),
+                ColumnKind::Timestamp => (
+                    PhysicalType::INT64,
+                    Some(LogicalType::Timestamp {
+                        is_adjusted_to_u_t_c: true,
+                        unit: TimeUnit::MICROS,
+                    }),
+                ),
+            };
+            Type::primitive_type_builder(&column.name, physical)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Type::group_type_builder("event")
+        .with_fields(fields)
+        .build()
+        .map(Arc::new)
+}
+
+/// Write one column chunk of optional values.
+fn write_column<T: DataType>(
+    writer: &mut SerializedColumnWriter<'_>,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<(), ParquetError> {
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+    for value in values {
+        if let Some(value) = value {
+            present.push(value);
+            levels.push(1);
+        } else {
+            levels.push(0);
+        }
+    }
+    writer.typed::<T>().write_batch(&present, Some(&levels), None)?;
+    Ok(())
+}
+
+/// An open Parquet file for one event type.
+struct ParquetSink {
+    /// The file's columns.
+    columns: Vec<Column>,
+    /// The file writer.
+    writer: SerializedFileWriter<File>,
+}
+
+impl ParquetSink {
+    /// Create `path` for rows of `event_type`.
+    fn create(path: &Path, event_type: &str) -> Result<Self, ExportError> {
+        let columns = columns(event_type);
+        let properties = Arc::new(WriterProperties::builder().build());
+        let writer =
+            SerializedFileWriter::new(File::create(path)?, parquet_schema(&columns)?, properties)?;
+        Ok(Self { columns, writer })
+    }
+
+    /// Write `rows` as one row group.
+    fn write(&mut self, rows: &[Vec<Cell>]) -> Result<(), ExportError> {
+        let mut group = self.writer.next_row_group()?;
+        for (index, column) in self.columns.iter().enumerate() {
+            let Some(mut writer) = group.next_column()? else {
+                break;
+            };
+            let cells = rows.iter().map(|row| row.get(index).unwrap_or(&Cell::Null));
+            match column.kind {
+                ColumnKind::Int | ColumnKind::Timestamp => {
+                    write_column::<Int64Type>(&mut writer, cells.map(Cell::as_i64))?;
+                }
+                ColumnKind::Text => {
+                    write_column::<ByteArrayType>(&mut writer, cells.map(Cell::as_bytes))?;
+                }
+                ColumnKind::Bool => {
+                    write_column::<BoolType>(&mut writer, cells.map(Cell::as_bool))?;
+                }
+            }
+            writer.close()?;
+        }
+        group.close()?;
+        Ok(())
+    }
+
+    /// Write the file footer.
+    fn finish(self) -> Result<(), ExportError> {
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Where exported events go.
+enum Sink {
+    /// One JSON lines file.
+    Jsonl(BufWriter<File>),
+    /// One Parquet file per event type, opened on its first event.
+    Parquet {
+        /// The output directory.
+        dir: PathBuf,
+        /// The open files, by event type.
+        files: BTreeMap<String, ParquetSink>,
+    },
+}
+
+impl Sink {
+    /// Write one window's events.
+    fn write(&mut self, events: &[EventRow]) -> Result<(), ExportError> {
+        match self {
+            Self::Jsonl(out) => {
+                for row in events {
+                    serde_json::to_writer(&mut *out, &to_json_line(row))?;
+                    out.write_all(b"\n")?;
+                }
+            }
+            Self::Parquet { dir, files } => {
+                let mut groups: BTreeMap<&str, Vec<Vec<Cell>>> = BTreeMap::new();
+                for row in events {
+                    groups.entry(row.event_type.as_str()).or_default().push(flatten(row));
+                }
+                for (event_type, rows) in groups {
+                    let sink = match files.entry(event_type.to_owned()) {
+                        std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                        std::collections::btree_map::Entry::Vacant(entry) => {
+                            let path = parquet_path(dir, event_type);
+                            entry.insert(ParquetSink::create(&path, event_type)?)
+                        }
+                    };
+                    sink.write(&rows)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush and close every file, returning their paths.
+    fn finish(self, dir: &Path) -> Result<Vec<PathBuf>, ExportError> {
+        match self {
+            Self::Jsonl(mut out) => {
+                out.flush()?;
+                Ok(vec![dir.join("events.jsonl")])
+            }
+            Self::Parquet { files, .. } => {
+                let mut paths = Vec::with_capacity(files.len());
+                for (event_type, sink) in files {
+                    sink.finish()?;
+                    paths.push(parquet_path(dir, &event_type));
+                }
+                Ok(paths)
+            }
+        }
+    }
+}
+
+/// The Parquet file for `event_type` under `dir`.
+fn parquet_path(dir: &Path, event_type: &str) -> PathBuf {
+    dir.join(format!("events_{event_type}.parquet"))
+}
+
+/// Export the events of ticks `from_tick..=to_tick` into `dir`.
+///
+/// The directory is created if missing; existing export files in it are
+/// overwritten. Events are read in windows of
+/// [`window_ticks`](ExportConfig::window_ticks), so memory use is bounded
+/// by the busiest window rather than the whole range.
+///
+/// # Errors
+///
+/// Returns [`ExportError::Db`] if a query fails, or another
+/// [`ExportError`] if an output file cannot be written.
+pub async fn export_events(
+    pool: &PgPool,
+    dir: &Path,
+    from_tick: u64,
+    to_tick: u64,
+    config: &ExportConfig,
+) -> Result<ExportReport, ExportError> {
+    std::fs::create_dir_all(dir)?;
+    let mut sink = match config.format {
+        ExportFormat::Jsonl => Sink::Jsonl(BufWriter::new(File::create(dir.join("events.jsonl"))?)),
+        ExportFormat::Parquet => Sink::Parquet {
+            dir: dir.to_path_buf(),
+            files: BTreeMap::new(),
+        },
+    };
+
+    let store = EventStore::new(pool);
+    let end_tick = to_tick.saturating_add(1);
+    let mut report = ExportReport::default();
+    let mut start = from_tick;
+    while start < end_tick {
+        let end = start.saturating_add(config.window_ticks.max(1)).min(end_tick);
+        let events = store.get_events_in_range(start, end).await?;
+        sink.write(&events)?;
+        report.events = report.events.saturating_add(events.len());
+        start = end;
+    }
+    report.files = sink.finish(dir)?;
+
+    tracing::info!(
+        from_tick,
+        to_tick,
+        events = report.events,
+        files = report.files.len(),
+        "Exported events"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+
+    use super::*;
+
+    fn row(id: i64, event_type: &str, details: serde_json::Value) -> EventRow {
+        EventRow {
+            id,
+            tick: 7,
+            event_type: event_type.to_owned(),
+            agent_id: Some(Uuid::nil()),
+            location_id: None,
+            details,
+            agent_state_snapshot: None,
+            world_context: Some(serde_json::json!({
+                "tick": 7,
+                "era": "Primitive",
+                "season": "Spring",
+                "weather": "Clear",
+                "population": 12,
+            })),
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn gathered(id: i64) -> EventRow {
+        row(
+            id,
+            "resource_gathered",
+            serde_json::json!({
+                "resource": "wood",
+                "quantity": 3,
+                "location_id": Uuid::nil(),
+                "skill_xp_gained": 1,
+            }),
+        )
+    }
+
+    #[test]
+    fn json_lines_flatten_context_and_common_details() {
+        let line = to_json_line(&gathered(1));
+        assert_eq!(line.get("tick").unwrap(), &7);
+        assert_eq!(line.get("era").unwrap(), &"Primitive");
+        assert_eq!(line.get("population").unwrap(), &12);
+        assert_eq!(line.get("detail_resource").unwrap(), &"wood");
+        assert_eq!(line.get("detail_quantity").unwrap(), &3);
+        assert_eq!(line.get("caused_by").unwrap(), &serde_json::Value::Null);
+        assert_eq!(line.pointer("/details/skill_xp_gained").unwrap(), &1);
+
+        let traded = row(
+            2,
+            "trade_completed",
+            serde_json::json!({"gave": {"wood": 2}, "received": {"stone": 1}}),
+        );
+        let line = to_json_line(&traded);
+        assert_eq!(line.get("detail_gave").unwrap(), &serde_json::json!({"wood": 2}));
+        assert_eq!(line.get("detail_trade_id").unwrap(), &serde_json::Value::Null);
+
+        // Types without flattened fields keep only the base columns.
+        let line = to_json_line(&row(3, "tick_end", serde_json::json!({})));
+        assert_eq!(line.as_object().unwrap().len(), BASE_COLUMNS.len() + 1);
+    }
+
+    #[test]
+    fn parquet_files_round_trip() {
+        let dir = std::env::temp_dir().join(format!("emergence-export-{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut sink = Sink::Parquet {
+            dir: dir.clone(),
+            files: BTreeMap::new(),
+        };
+        sink.write(&[gathered(1), row(2, "tick_end", serde_json::json!({}))]).unwrap();
+        sink.write(&[gathered(3)]).unwrap();
+        let files = sink.finish(&dir).unwrap();
+        assert_eq!(
+            files,
+            [parquet_path(&dir, "resource_gathered"), parquet_path(&dir, "tick_end")]
+        );
+
+        let path = parquet_path(&dir, "resource_gathered");
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        let last = rows.last().unwrap();
+        let fields: BTreeMap<&str, &Field> =
+            last.get_column_iter().map(|(name, field)| (name.as_str(), field)).collect();
+        assert_eq!(*fields.get("id").unwrap(), &Field::Long(3));
+        assert_eq!(*fields.get("detail_resource").unwrap(), &Field::Str("wood".to_owned()));
+        assert_eq!(*fields.get("detail_quantity").unwrap(), &Field::Long(3));
+        assert_eq!(*fields.get("location_id").unwrap(), &Field::Null);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
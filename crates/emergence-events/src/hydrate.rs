@@ -0,0 +1,414 @@
+//! Restoring simulation state at a tick from snapshots plus an event tail.
+//!
+//! Replaying every event from tick 0 takes minutes once a run is 100k+
+//! ticks long. [`hydrate_at_tick`] instead starts from the latest world
+//! snapshot and each agent's latest snapshot at or before the target tick,
+//! then applies only the events recorded after them.
+//!
+//! Snapshots are not all taken on the same tick, so every agent (and the
+//! world) keeps its own base tick: an event is applied to an agent only if
+//! it happened after that agent's snapshot, and the event tail is fetched
+//! from the oldest base. Agents with no snapshot enter the state through
+//! their `agent_born` event.
+//!
+//! [`hydrate_on_branch`] does the same on a branch of the event log, so a
+//! what-if branch can be restored at its fork tick and continued from
+//! there.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use emergence_db::{
+    AgentSnapshotRow, DbError, EventRow, EventStore, SnapshotStore, WorldSnapshotRow, MAIN_BRANCH,
+};
+use emergence_types::{
+    AgentId, AgentState, AgentStateSnapshot, BuildError, LocationId, WorldContext,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::tick_of;
+use crate::upcast::{UpcastError, Upcasters};
+
+/// Errors that can occur while hydrating state.
+#[derive(Debug, thiserror::Error)]
+pub enum HydrateError {
+    /// A snapshot or event query failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// An agent snapshot does not hold a valid agent state.
+    #[error("agent snapshot for {agent_id} at tick {tick} is malformed: {source}")]
+    AgentSnapshot {
+        /// The agent the snapshot belongs to.
+        agent_id: Uuid,
+        /// The tick the snapshot was taken at.
+        tick: i64,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+
+    /// An event's agent state or world context could not be decoded.
+    #[error("event {event_id} at tick {tick} has a malformed {field}: {source}")]
+    Event {
+        /// The event's row ID.
+        event_id: i64,
+        /// The tick the event occurred at.
+        tick: i64,
+        /// The column that failed to decode.
+        field: &'static str,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+
+    /// A newborn agent's initial state could not be built.
+    #[error("cannot build a newborn agent's state: {0}")]
+    NewAgent(#[from] BuildError),
+
+    /// An event could not be migrated to the current schema.
+    #[error("cannot upcast event: {0}")]
+    Upcast(#[from] UpcastError),
+}
+
+/// Simulation state restored at a tick.
+#[derive(Debug, Clone)]
+pub struct HydratedState {
+    /// The tick the state was restored at.
+    pub tick: u64,
+    /// The world snapshot the state started from, if any was taken.
+    pub world: Option<WorldSnapshotRow>,
+    /// The world context of the latest replayed event, if it is newer than
+    /// [`world`](Self::world).
+    pub world_context: Option<WorldContext>,
+    /// Every known agent's state, the dead included.
+    pub agents: BTreeMap<AgentId, AgentState>,
+    /// Agents that have died.
+    pub dead: BTreeSet<AgentId>,
+    /// Number of events applied on top of the snapshots.
+    pub events_applied: usize,
+    /// Tick of each agent's snapshot; events at or before it are skipped.
+    agent_bases: BTreeMap<AgentId, u64>,
+    /// Tick of the world snapshot, 0 if there is none.
+    world_base: u64,
+    /// Migrates each event in the tail to the current schema.
+    upcasters: Arc<Upcasters>,
+}
+
+impl HydratedState {
+    /// Start from the given snapshots, none of which may be newer than
+    /// `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HydrateError::AgentSnapshot`] if an agent snapshot is not
+    /// a valid agent state.
+    pub fn from_snapshots(
+        tick: u64,
+        world: Option<WorldSnapshotRow>,
+        agent_rows: Vec<AgentSnapshotRow>,
+    ) -> Result<Self, HydrateError> {
+        let mut agents = BTreeMap::new();
+        let mut agent_bases = BTreeMap::new();
+        for row in agent_rows {
+            let state: AgentState =
+                serde_json::from_value(row.full_state).map_err(|source| {
+                    HydrateError::AgentSnapshot {
+                        agent_id: row.agent_id,
+                        tick: row.tick,
+                        source,
+                    }
+                })?;
+            let agent_id = AgentId::from(row.agent_id);
+            agent_bases.insert(agent_id, tick_of(row.tick));
+            agents.insert(agent_id, state);
+        }
+        Ok(Self {
+            tick,
+            world_base: world.as_ref().map_or(0, |w| tick_of(w.tick)),
+            world,
+            world_context: None,
+            agents,
+            dead: BTreeSet::new(),
+            events_applied: 0,
+            agent_bases,
+            upcasters: Arc::new(Upcasters::builtin()),
+        })
+    }
+
+    /// Read the event tail through `upcasters` instead of
+    /// [`Upcasters::builtin`].
+    #[must_use]
+    pub fn with_upcasters(mut self, upcasters: Arc<Upcasters>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    /// The tick the event tail must start after: the oldest snapshot.
+    pub fn replay_from(&self) -> u64 {
+        self.agent_bases
+            .values()
+            .copied()
+            .fold(self.world_base, u64::min)
+    }
+
+    /// Apply one event from the tail. Events must be applied in tick
+    /// order; those later than [`tick`](Self::tick), or already covered
+    /// by the snapshot they would change, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HydrateError::Upcast`] if the event cannot be migrated to
+    /// the current schema, [`HydrateError::Event`] if its agent state or
+    /// world context is malformed, or [`HydrateError::NewAgent`] if a
+    /// newborn's state cannot be built.
+    pub fn apply(&mut self, event: &EventRow) -> Result<(), HydrateError> {
+        let tick = tick_of(event.tick);
+        if tick > self.tick {
+            return Ok(());
+        }
+        let event = &*self.upcasters.upgrade(event)?;
+
+        let to_world = tick > self.world_base && event.world_context.is_some();
+        if let Some(context) = event.world_context.as_ref().filter(|_| to_world) {
+            self.world_context = Some(decode(event, "world_context", context)?);
+        }
+
+        let agent_id = event
+            .agent_id
+            .map(AgentId::from)
+            .filter(|id| self.agent_bases.get(id).is_none_or(|base| tick > *base));
+        if let Some(agent_id) = agent_id {
+            self.apply_to_agent(agent_id, tick, event)?;
+        }
+
+        if to_world || agent_id.is_some() {
+            self.events_applied = self.events_applied.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// Apply `event` to `agent_id`'s state.
+    fn apply_to_agent(
+        &mut self,
+        agent_id: AgentId,
+        tick: u64,
+        event: &EventRow,
+    ) -> Result<(), HydrateError> {
+        if event.event_type == "agent_born" && !self.agents.contains_key(&agent_id) {
+            let location_id = event.location_id.map_or_else(LocationId::new, LocationId::from);
+            let mut state = AgentState::builder(agent_id, location_id).build()?;
+            state.born_at_tick = tick;
+            self.agents.insert(agent_id, state);
+        }
+        if event.event_type == "agent_died" {
+            self.dead.insert(agent_id);
+        }
+
+        let Some(snapshot) = &event.agent_state_snapshot else {
+            return Ok(());
+        };
+        let snapshot: AgentStateSnapshot = decode(event, "agent_state_snapshot", snapshot)?;
+        if let Some(state) = self.agents.get_mut(&agent_id) {
+            state.energy = snapshot.energy;
+            state.health = snapshot.health;
+            state.hunger = snapshot.hunger;
+            state.age = snapshot.age;
+            state.location_id = snapshot.location_id;
+            state.inventory = snapshot.inventory_summary;
+        }
+        Ok(())
+    }
+}
+
+/// Restore the simulation state at `tick` from the latest snapshots at or
+/// before it and the events recorded since.
+///
+/// # Errors
+///
+/// Returns [`HydrateError::Db`] if a query fails, or another
+/// [`HydrateError`] if a snapshot or event is malformed.
+pub async fn hydrate_at_tick(pool: &PgPool, tick: u64) -> Result<HydratedState, HydrateError> {
+    hydrate_on_branch(pool, MAIN_BRANCH, tick).await
+}
+
+/// Restore the simulation state at `tick` on the event branch `branch`,
+/// like [`hydrate_at_tick`] does on the main branch.
+///
+/// # Errors
+///
+/// Returns [`HydrateError::Db`] if a query fails, or another
+/// [`HydrateError`] if a snapshot or event is malformed.
+pub async fn hydrate_on_branch(
+    pool: &PgPool,
+    branch: &str,
+    tick: u64,
+) -> Result<HydratedState, HydrateError> {
+    let snapshots = SnapshotStore::new(pool).with_branch(branch);
+    let world = snapshots.get_world_snapshot_at_or_before(tick).await?;
+    let agent_rows = snapshots.get_agent_snapshots_at_or_before(tick).await?;
+    let mut state = HydratedState::from_snapshots(tick, world, agent_rows)?;
+
+    let events = EventStore::new(pool)
+        .with_branch(branch)
+        .get_events_after(state.replay_from(), tick)
+        .await?;
+    for event in &events {
+        state.apply(event)?;
+    }
+
+    tracing::debug!(
+        branch,
+        tick,
+        replay_from = state.replay_from(),
+        agents = state.agents.len(),
+        events = state.events_applied,
+        "Hydrated state from snapshots"
+    );
+    Ok(state)
+}
+
+/// Decode one JSON column of `event`.
+fn decode<T: serde::de::DeserializeOwned>(
+    event: &EventRow,
+    field: &'static str,
+    value: &serde_json::Value,
+) -> Result<T, HydrateError> {
+    T::deserialize(value).map_err(|source| HydrateError::Event {
+        event_id: event.id,
+        tick: event.tick,
+        field,
+        source,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{Era, Resource, Season, Weather};
+
+    use super::*;
+
+    fn agent_row(tick: i64, state: &AgentState) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            id: tick,
+            tick,
+            agent_id: state.agent_id.into_inner(),
+            full_state: serde_json::to_value(state).unwrap(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn world_row(tick: i64) -> WorldSnapshotRow {
+        WorldSnapshotRow {
+            tick,
+            era: "primitive".to_owned(),
+            season: "spring".to_owned(),
+            weather: "clear".to_owned(),
+            population: 2,
+            births: 0,
+            deaths: 0,
+            total_resources: serde_json::json!({}),
+            wealth_distribution: serde_json::json!({}),
+            trades_this_tick: 0,
+            discoveries_count: 0,
+            summary: serde_json::json!({}),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn event(tick: i64, event_type: &str, agent: Option<&AgentState>, energy: u32) -> EventRow {
+        let context = WorldContext {
+            tick: tick_of(tick),
+            era: Era::Primitive,
+            season: Season::Summer,
+            weather: Weather::Rain,
+            population: 2,
+        };
+        EventRow {
+            id: tick,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: agent.map(|a| a.agent_id.into_inner()),
+            location_id: agent.map(|a| a.location_id.into_inner()),
+            details: serde_json::json!({}),
+            agent_state_snapshot: agent.map(|a| {
+                serde_json::to_value(AgentStateSnapshot {
+                    energy,
+                    health: a.health,
+                    hunger: a.hunger,
+                    age: a.age,
+                    location_id: a.location_id,
+                    inventory_summary: std::iter::once((Resource::Wood, energy)).collect(),
+                })
+                .unwrap()
+            }),
+            world_context: Some(serde_json::to_value(context).unwrap()),
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn agent() -> AgentState {
+        AgentState::builder(AgentId::new(), LocationId::new()).build().unwrap()
+    }
+
+    #[test]
+    fn applies_only_events_after_each_snapshot() {
+        let (early, late) = (agent(), agent());
+        let mut state = HydratedState::from_snapshots(
+            200,
+            Some(world_row(150)),
+            vec![agent_row(100, &early), agent_row(150, &late)],
+        )
+        .unwrap();
+        assert_eq!(state.replay_from(), 100);
+
+        for e in [
+            event(120, "action_succeeded", Some(&early), 70),
+            event(120, "action_succeeded", Some(&late), 10),
+            event(160, "action_succeeded", Some(&late), 60),
+            event(250, "action_succeeded", Some(&early), 5),
+        ] {
+            state.apply(&e).unwrap();
+        }
+
+        assert_eq!(state.agents.get(&early.agent_id).unwrap().energy, 70);
+        assert_eq!(state.agents.get(&late.agent_id).unwrap().energy, 60);
+        assert_eq!(state.events_applied, 2);
+        assert_eq!(state.world_context.map(|c| c.tick), Some(160));
+    }
+
+    #[test]
+    fn newborns_and_deaths_come_from_the_tail() {
+        let (elder, child) = (agent(), agent());
+        let mut state =
+            HydratedState::from_snapshots(80, None, vec![agent_row(10, &elder)]).unwrap();
+
+        state.apply(&event(20, "agent_born", Some(&child), 55)).unwrap();
+        state.apply(&event(30, "agent_died", Some(&elder), 0)).unwrap();
+
+        let born = state.agents.get(&child.agent_id).unwrap();
+        assert_eq!(born.born_at_tick, 20);
+        assert_eq!(born.energy, 55);
+        assert_eq!(born.location_id, child.location_id);
+        assert_eq!(state.dead.iter().collect::<Vec<_>>(), vec![&elder.agent_id]);
+    }
+
+    #[test]
+    fn malformed_rows_are_reported() {
+        let mut row = agent_row(5, &agent());
+        row.full_state = serde_json::json!({"energy": "lots"});
+        let err = HydratedState::from_snapshots(10, None, vec![row]).unwrap_err();
+        assert!(matches!(err, HydrateError::AgentSnapshot { tick: 5, .. }));
+
+        let mut state = HydratedState::from_snapshots(10, None, Vec::new()).unwrap();
+        let mut bad = event(7, "tick_end", None, 0);
+        bad.world_context = Some(serde_json::json!([1, 2]));
+        let err = state.apply(&bad).unwrap_err();
+        assert!(matches!(err, HydrateError::Event { field: "world_context", .. }));
+    }
+}
@@ -4,3 +4,58 @@
 //! Events are the source of truth -- state can be reconstructed by replaying
 //! them. This crate defines event types, the event store interface, and
 //! periodic state snapshot logic.
+//!
+//! # Modules
+//!
+//! - [`bus`] -- In-process publish/subscribe for subsystem subscribers
+//! - [`hydrate`] -- Restoring state at a tick from snapshots plus the event tail
+//! - [`upcast`] -- Migrating old event payloads to the current detail types
+//! - [`determinism`] -- Verifying that replaying the event log rebuilds the same state
+//! - [`compact`] -- Folding superseded events into summaries
+//! - [`backfill`] -- Writing events derived by later-added detectors into recorded runs
+//! - [`projection`] -- Derived views folded incrementally from the event stream
+//! - [`rebuild`] -- Rebuilding one agent's or location's state at a past tick
+//! - [`export`] -- Writing event ranges to JSON lines or Parquet for analysis
+//! - [`snapshot`] -- Scheduling and writing periodic state snapshots
+//! - [`scenario`] -- Given/When/Then test scenarios over the replay logic
+
+pub mod backfill;
+pub mod bus;
+pub mod compact;
+pub mod determinism;
+pub mod export;
+pub mod hydrate;
+pub mod projection;
+pub mod rebuild;
+pub mod scenario;
+pub mod snapshot;
+pub mod upcast;
+
+pub use backfill::{
+    backfill, builtin_detectors, detect_all, BackfillError, BackfillReport, Detector,
+    FamineDetector,
+};
+pub use bus::{EventBus, Subscriber, SubscriptionId};
+pub use compact::{compact, CompactError, CompactionConfig, CompactionReport};
+pub use determinism::{
+    verify_run, DeterminismError, DeterminismReport, DeterminismVerifier, Divergence, Subsystem,
+};
+pub use export::{
+    export_events, ColumnKind, ExportConfig, ExportError, ExportFormat, ExportReport, FLATTENED,
+};
+pub use hydrate::{hydrate_at_tick, hydrate_on_branch, HydrateError, HydratedState};
+pub use projection::{
+    PopulationOverTime, Projection, ProjectionDelta, ProjectionError, ProjectionRunner,
+    StructuresPerLocation, WealthPerAgent,
+};
+pub use rebuild::{rebuild_agent, rebuild_location, RebuildError, RebuiltAgent, RebuiltLocation};
+pub use scenario::{given, Given, Replay, ScenarioError, When};
+pub use snapshot::{
+    write_snapshot, SnapshotCadence, SnapshotError, SnapshotScheduler, SnapshotTrigger,
+};
+pub use upcast::{UpcastError, Upcaster, Upcasters};
+
+/// A row's tick as a tick number; negative ticks never occur.
+pub(crate) fn tick_of(tick: i64) -> u64 {
+    u64::try_from(tick).unwrap_or(0)
+}
@@ -0,0 +1,722 @@
+//! Derived views folded incrementally from the event stream.
+//!
+//! Answering "how has the population changed" or "what stands at this
+//! location" from raw events means rescanning the whole log. A
+//! [`Projection`] instead folds each committed event into a small view as
+//! it arrives and reports what changed. A [`ProjectionRunner`] feeds events
+//! to every registered projection and saves each view, with the last tick
+//! it covers, as a checkpoint; after a restart it resumes from there.
+//!
+//! Projections advance a whole tick at a time: a projection positioned at
+//! tick `t` skips every event at or before `t`, so all of a tick's events
+//! must be fed together. Compaction summaries are written at ticks that
+//! were already committed, so a projection that has caught up never folds
+//! them twice.
+//!
+//! The runner migrates every event to the current schema through its
+//! [`Upcasters`] before a projection sees it, so projections decode only
+//! today's detail types.
+//!
+//! Built-in projections:
+//!
+//! - [`PopulationOverTime`] -- the population at each tick it changed
+//! - [`WealthPerAgent`] -- each living agent's latest inventory
+//! - [`StructuresPerLocation`] -- the standing structures at each location
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use emergence_db::{DbError, EventRow, EventStore, ProjectionStore};
+use emergence_types::{
+    AgentId, AgentStateSnapshot, LocationId, Resource, StructureBuiltDetails,
+    StructureDestroyedDetails, StructureId, WorldContext,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::tick_of;
+use crate::upcast::{UpcastError, Upcasters};
+
+/// Errors that can occur while running projections.
+#[derive(Debug, thiserror::Error)]
+pub enum ProjectionError {
+    /// An event or checkpoint query, or a checkpoint save, failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// A projection could not decode the event it was fed.
+    #[error("projection {projection} cannot apply event {event_id} at tick {tick}: {source}")]
+    Apply {
+        /// The projection's name.
+        projection: &'static str,
+        /// The event's row ID.
+        event_id: i64,
+        /// The tick the event occurred at.
+        tick: i64,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+
+    /// An event could not be migrated to the current schema.
+    #[error("cannot upcast event: {0}")]
+    Upcast(#[from] UpcastError),
+
+    /// A projection's state could not be encoded, or its saved checkpoint
+    /// could not be restored.
+    #[error("projection {projection} checkpoint is malformed: {source}")]
+    Checkpoint {
+        /// The projection's name.
+        projection: &'static str,
+        /// The encoding or decoding error.
+        source: serde_json::Error,
+    },
+}
+
+/// A view folded from the event stream.
+pub trait Projection: Send {
+    /// Unique name; the key the checkpoint is stored under.
+    fn name(&self) -> &'static str;
+
+    /// Fold `event` into the view, returning what changed, or `None` if
+    /// the view is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a decoding error if the event is malformed.
+    fn apply(&mut self, event: &EventRow) -> Result<Option<serde_json::Value>, serde_json::Error>;
+
+    /// The whole view as JSON, for checkpoints and for serving.
+    ///
+    /// # Errors
+    ///
+    /// Returns an encoding error if the view cannot be serialized.
+    fn state(&self) -> Result<serde_json::Value, serde_json::Error>;
+
+    /// Replace the view with one previously returned by
+    /// [`state`](Self::state).
+    ///
+    /// # Errors
+    ///
+    /// Returns a decoding error if `state` is not this projection's view.
+    fn restore(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error>;
+}
+
+/// One change a projection reported while folding an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectionDelta {
+    /// The projection that changed.
+    pub projection: &'static str,
+    /// The tick of the event that changed it.
+    pub tick: u64,
+    /// The row ID of the event that changed it.
+    pub event_id: i64,
+    /// What changed, in the projection's own shape.
+    pub delta: serde_json::Value,
+}
+
+/// A registered projection and the last tick folded into it.
+struct Registered {
+    projection: Box<dyn Projection>,
+    position: u64,
+}
+
+/// Feeds committed events to registered projections and checkpoints them.
+pub struct ProjectionRunner {
+    projections: Vec<Registered>,
+    upcasters: Arc<Upcasters>,
+}
+
+impl Default for ProjectionRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectionRunner {
+    /// Create a runner with no projections.
+    pub fn new() -> Self {
+        Self {
+            projections: Vec::new(),
+            upcasters: Arc::new(Upcasters::builtin()),
+        }
+    }
+
+    /// Migrate events through `upcasters` instead of
+    /// [`Upcasters::builtin`].
+    #[must_use]
+    pub fn with_upcasters(mut self, upcasters: Arc<Upcasters>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    /// Create a runner with every built-in projection registered.
+    pub fn with_builtins() -> Self {
+        let mut runner = Self::new();
+        runner.register(PopulationOverTime::new());
+        runner.register(WealthPerAgent::new());
+        runner.register(StructuresPerLocation::new());
+        runner
+    }
+
+    /// Register `projection` at tick 0, replacing any projection already
+    /// registered under the same name.
+    pub fn register(&mut self, projection: impl Projection + 'static) {
+        let name = projection.name();
+        self.projections.retain(|r| r.projection.name() != name);
+        self.projections.push(Registered {
+            projection: Box::new(projection),
+            position: 0,
+        });
+    }
+
+    /// Number of registered projections.
+    pub const fn len(&self) -> usize {
+        self.projections.len()
+    }
+
+    /// Whether no projections are registered.
+    pub const fn is_empty(&self) -> bool {
+        self.projections.is_empty()
+    }
+
+    /// The names of the registered projections, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.projections.iter().map(|r| r.projection.name())
+    }
+
+    /// The projection registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn Projection> {
+        self.find(name).map(|r| r.projection.as_ref())
+    }
+
+    /// The last tick folded into the projection registered under `name`.
+    pub fn position(&self, name: &str) -> Option<u64> {
+        self.find(name).map(|r| r.position)
+    }
+
+    /// The registration for `name`.
+    fn find(&self, name: &str) -> Option<&Registered> {
+        self.projections.iter().find(|r| r.projection.name() == name)
+    }
+
+    /// The tick the next batch of events must start after: the position
+    /// of the furthest-behind projection.
+    pub fn replay_from(&self) -> u64 {
+        self.projections.iter().map(|r| r.position).min().unwrap_or(0)
+    }
+
+    /// Fold `events`, which must be in tick order and hold every event of
+    /// each tick they cover, into each projection not already past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProjectionError::Upcast`] if an event cannot be migrated,
+    /// or [`ProjectionError::Apply`] if a projection cannot decode one.
+    /// Projections keep whatever they folded before the failure, but their
+    /// positions do not advance.
+    pub fn feed(&mut self, events: &[EventRow]) -> Result<Vec<ProjectionDelta>, ProjectionError> {
+        let mut deltas = Vec::new();
+        let mut through = 0;
+        for event in events {
+            let tick = tick_of(event.tick);
+            through = through.max(tick);
+            let event = &*self.upcasters.upgrade(event)?;
+            for registered in self.projections.iter_mut().filter(|r| tick > r.position) {
+                let projection = registered.projection.name();
+                let delta = registered.projection.apply(event).map_err(|source| {
+                    ProjectionError::Apply {
+                        projection,
+                        event_id: event.id,
+                        tick: event.tick,
+                        source,
+                    }
+                })?;
+                if let Some(delta) = delta {
+                    deltas.push(ProjectionDelta {
+                        projection,
+                        tick,
+                        event_id: event.id,
+                        delta,
+                    });
+                }
+            }
+        }
+        self.advance(through);
+        Ok(deltas)
+    }
+
+    /// Move every projection's position up to `tick`.
+    fn advance(&mut self, tick: u64) {
+        for registered in &mut self.projections {
+            registered.position = registered.position.max(tick);
+        }
+    }
+
+    /// Restore every projection that has a saved checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProjectionError::Db`] if a query fails, or
+    /// [`ProjectionError::Checkpoint`] if a checkpoint does not restore.
+    pub async fn restore(&mut self, pool: &PgPool) -> Result<(), ProjectionError> {
+        let store = ProjectionStore::new(pool);
+        for registered in &mut self.projections {
+            let projection = registered.projection.name();
+            let Some(row) = store.load_checkpoint(projection).await? else {
+                continue;
+            };
+            registered
+                .projection
+                .restore(row.state)
+                .map_err(|source| ProjectionError::Checkpoint { projection, source })?;
+            registered.position = tick_of(row.tick);
+        }
+        Ok(())
+    }
+
+    /// Save every projection's checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProjectionError::Checkpoint`] if a view cannot be encoded,
+    /// or [`ProjectionError::Db`] if a save fails.
+    pub async fn save(&self, pool: &PgPool) -> Result<(), ProjectionError> {
+        let store = ProjectionStore::new(pool);
+        for registered in &self.projections {
+            let projection = registered.projection.name();
+            let state = registered
+                .projection
+                .state()
+                .map_err(|source| ProjectionError::Checkpoint { projection, source })?;
+            store.save_checkpoint(projection, registered.position, &state).await?;
+        }
+        Ok(())
+    }
+
+    /// Fold every committed event up to and including `to_tick` that a
+    /// projection has not yet seen, then checkpoint. `to_tick` must be a
+    /// tick whose events have all been flushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any [`ProjectionError`] from reading events, folding them,
+    /// or saving checkpoints.
+    pub async fn catch_up(
+        &mut self,
+        pool: &PgPool,
+        to_tick: u64,
+    ) -> Result<Vec<ProjectionDelta>, ProjectionError> {
+        let from = self.replay_from();
+        let events = EventStore::new(pool).get_events_after(from, to_tick).await?;
+        let deltas = self.feed(&events)?;
+        self.advance(to_tick);
+        self.save(pool).await?;
+
+        tracing::debug!(
+            from,
+            to_tick,
+            events = events.len(),
+            deltas = deltas.len(),
+            "Caught up projections"
+        );
+        Ok(deltas)
+    }
+}
+
+impl std::fmt::Debug for ProjectionRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.projections.iter().map(|r| (r.projection.name(), r.position)))
+            .finish()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Built-in projections
+// ---------------------------------------------------------------------------
+
+/// The population at each tick it changed, read from events' world
+/// context.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PopulationOverTime {
+    series: BTreeMap<u64, u32>,
+}
+
+impl PopulationOverTime {
+    /// Create an empty series.
+    pub const fn new() -> Self {
+        Self {
+            series: BTreeMap::new(),
+        }
+    }
+
+    /// The population at each tick it changed.
+    pub const fn series(&self) -> &BTreeMap<u64, u32> {
+        &self.series
+    }
+
+    /// The population at `tick`, if any event at or before it was seen.
+    pub fn at(&self, tick: u64) -> Option<u32> {
+        self.series.range(..=tick).next_back().map(|(_, population)| *population)
+    }
+}
+
+impl Projection for PopulationOverTime {
+    fn name(&self) -> &'static str {
+        "population_over_time"
+    }
+
+    fn apply(&mut self, event: &EventRow) -> Result<Option<serde_json::Value>, serde_json::Error> {
+        let Some(context) = &event.world_context else {
+            return Ok(None);
+        };
+        let population = WorldContext::deserialize(context)?.population;
+        let tick = tick_of(event.tick);
+        if self.at(tick) == Some(population) {
+            return Ok(None);
+        }
+        self.series.insert(tick, population);
+        Ok(Some(serde_json::json!({ "tick": tick, "population": population })))
+    }
+
+    fn state(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn restore(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+/// Each living agent's inventory as of its latest event.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WealthPerAgent {
+    inventories: BTreeMap<AgentId, BTreeMap<Resource, u32>>,
+}
+
+impl WealthPerAgent {
+    /// Create an empty view.
+    pub const fn new() -> Self {
+        Self {
+            inventories: BTreeMap::new(),
+        }
+    }
+
+    /// Every living agent's inventory.
+    pub const fn inventories(&self) -> &BTreeMap<AgentId, BTreeMap<Resource, u32>> {
+        &self.inventories
+    }
+
+    /// Total units of every resource `agent_id` holds.
+    pub fn total(&self, agent_id: AgentId) -> u32 {
+        self.inventories
+            .get(&agent_id)
+            .map_or(0, |inventory| inventory.values().fold(0, |sum, n| sum.saturating_add(*n)))
+    }
+}
+
+impl Projection for WealthPerAgent {
+    fn name(&self) -> &'static str {
+        "wealth_per_agent"
+    }
+
+    fn apply(&mut self, event: &EventRow) -> Result<Option<serde_json::Value>, serde_json::Error> {
+        let Some(agent_id) = event.agent_id.map(AgentId::from) else {
+            return Ok(None);
+        };
+        if event.event_type == "agent_died" {
+            return Ok(self
+                .inventories
+                .remove(&agent_id)
+                .map(|_| serde_json::json!({ "agent_id": agent_id, "inventory": null })));
+        }
+
+        let Some(snapshot) = &event.agent_state_snapshot else {
+            return Ok(None);
+        };
+        let inventory = AgentStateSnapshot::deserialize(snapshot)?.inventory_summary;
+        if self.inventories.get(&agent_id) == Some(&inventory) {
+            return Ok(None);
+        }
+        let delta = serde_json::json!({ "agent_id": agent_id, "inventory": inventory });
+        self.inventories.insert(agent_id, inventory);
+        Ok(Some(delta))
+    }
+
+    fn state(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn restore(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+/// The structures standing at each location.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuresPerLocation {
+    structures: BTreeMap<LocationId, BTreeSet<StructureId>>,
+}
+
+impl StructuresPerLocation {
+    /// Create an empty view.
+    pub const fn new() -> Self {
+        Self {
+            structures: BTreeMap::new(),
+        }
+    }
+
+    /// The standing structures at every location that has any.
+    pub const fn structures(&self) -> &BTreeMap<LocationId, BTreeSet<StructureId>> {
+        &self.structures
+    }
+
+    /// Number of structures standing at `location_id`.
+    pub fn count(&self, location_id: LocationId) -> usize {
+        self.structures.get(&location_id).map_or(0, BTreeSet::len)
+    }
+}
+
+impl Projection for StructuresPerLocation {
+    fn name(&self) -> &'static str {
+        "structures_per_location"
+    }
+
+    fn apply(&mut self, event: &EventRow) -> Result<Option<serde_json::Value>, serde_json::Error> {
+        let (location_id, structure_id, standing) = match event.event_type.as_str() {
+            "structure_built" => {
+                let details = StructureBuiltDetails::deserialize(&event.details)?;
+                (details.location_id, details.structure_id, true)
+            }
+            "structure_destroyed" => {
+                let details = StructureDestroyedDetails::deserialize(&event.details)?;
+                (details.location_id, details.structure_id, false)
+            }
+            _ => return Ok(None),
+        };
+
+        let changed = if standing {
+            self.structures.entry(location_id).or_default().insert(structure_id)
+        } else {
+            let removed = self
+                .structures
+                .get_mut(&location_id)
+                .is_some_and(|ids| ids.remove(&structure_id));
+            self.structures.retain(|_, ids| !ids.is_empty());
+            removed
+        };
+        Ok(changed.then(|| {
+            serde_json::json!({
+                "location_id": location_id,
+                "structure_id": structure_id,
+                "standing": standing,
+            })
+        }))
+    }
+
+    fn state(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn restore(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::{Era, Season, StructureType, Weather};
+
+    use super::*;
+
+    fn event(id: i64, tick: i64, event_type: &str) -> EventRow {
+        EventRow {
+            id,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: None,
+            location_id: None,
+            details: serde_json::json!({}),
+            agent_state_snapshot: None,
+            world_context: None,
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn tick_start(id: i64, tick: i64, population: u32) -> EventRow {
+        let context = WorldContext {
+            tick: tick_of(tick),
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population,
+        };
+        EventRow {
+            world_context: Some(serde_json::to_value(context).unwrap()),
+            ..event(id, tick, "tick_start")
+        }
+    }
+
+    fn gathered(id: i64, tick: i64, agent_id: AgentId, wood: u32) -> EventRow {
+        let snapshot = AgentStateSnapshot {
+            energy: 80,
+            health: 100,
+            hunger: 10,
+            age: 5,
+            location_id: LocationId::new(),
+            inventory_summary: std::iter::once((Resource::Wood, wood)).collect(),
+        };
+        EventRow {
+            agent_id: Some(agent_id.into_inner()),
+            agent_state_snapshot: Some(serde_json::to_value(snapshot).unwrap()),
+            ..event(id, tick, "resource_gathered")
+        }
+    }
+
+    fn structure(id: i64, tick: i64, built: bool, location_id: LocationId) -> EventRow {
+        let structure_id = StructureId::from(uuid::Uuid::from_u128(7));
+        let details = if built {
+            serde_json::to_value(StructureBuiltDetails {
+                structure_id,
+                structure_type: StructureType::Campfire,
+                location_id,
+                builder: AgentId::new(),
+                materials_used: BTreeMap::new(),
+            })
+        } else {
+            serde_json::to_value(StructureDestroyedDetails {
+                structure_id,
+                structure_type: StructureType::Campfire,
+                location_id,
+                cause: "decay".to_owned(),
+                materials_salvaged: BTreeMap::new(),
+            })
+        };
+        let event_type = if built { "structure_built" } else { "structure_destroyed" };
+        EventRow {
+            details: details.unwrap(),
+            ..event(id, tick, event_type)
+        }
+    }
+
+    #[test]
+    fn folds_each_tick_once() {
+        let agent_id = AgentId::new();
+        let location_id = LocationId::new();
+        let events = [
+            tick_start(1, 1, 2),
+            gathered(2, 1, agent_id, 3),
+            structure(3, 1, true, location_id),
+            tick_start(4, 2, 2),
+            gathered(5, 2, agent_id, 3),
+            tick_start(6, 3, 1),
+            EventRow {
+                agent_id: Some(agent_id.into_inner()),
+                ..event(7, 3, "agent_died")
+            },
+        ];
+
+        let mut runner = ProjectionRunner::with_builtins();
+        assert_eq!(runner.len(), 3);
+        let deltas = runner.feed(&events).unwrap();
+        let changed: Vec<(&str, i64)> = deltas.iter().map(|d| (d.projection, d.event_id)).collect();
+        assert_eq!(
+            changed,
+            [
+                ("population_over_time", 1),
+                ("wealth_per_agent", 2),
+                ("structures_per_location", 3),
+                ("population_over_time", 6),
+                ("wealth_per_agent", 7),
+            ]
+        );
+        assert_eq!(runner.position("wealth_per_agent"), Some(3));
+        assert_eq!(runner.replay_from(), 3);
+
+        // Replaying ticks already folded changes nothing.
+        assert!(runner.feed(&events).unwrap().is_empty());
+
+        // A projection registered late starts from the beginning.
+        runner.register(PopulationOverTime::new());
+        assert_eq!(runner.len(), 3);
+        assert_eq!(runner.replay_from(), 0);
+        assert_eq!(runner.feed(&events).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn builtins_track_their_views() {
+        let agent_id = AgentId::new();
+        let location_id = LocationId::new();
+
+        let mut population = PopulationOverTime::new();
+        for e in [tick_start(1, 10, 4), tick_start(2, 20, 4), tick_start(3, 30, 5)] {
+            population.apply(&e).unwrap();
+        }
+        assert_eq!(population.series().len(), 2);
+        assert_eq!(population.at(5), None);
+        assert_eq!(population.at(25), Some(4));
+        assert_eq!(population.at(30), Some(5));
+
+        let mut wealth = WealthPerAgent::new();
+        wealth.apply(&gathered(1, 1, agent_id, 6)).unwrap();
+        assert_eq!(wealth.total(agent_id), 6);
+
+        let mut structures = StructuresPerLocation::new();
+        structures.apply(&structure(1, 1, true, location_id)).unwrap();
+        assert_eq!(structures.count(location_id), 1);
+        assert!(structures.apply(&structure(2, 2, true, location_id)).unwrap().is_none());
+        structures.apply(&structure(3, 3, false, location_id)).unwrap();
+        assert_eq!(structures.count(location_id), 0);
+        assert!(structures.structures().is_empty());
+
+        let malformed = EventRow {
+            world_context: Some(serde_json::json!({"population": "many"})),
+            ..event(4, 4, "tick_start")
+        };
+        let mut runner = ProjectionRunner::new();
+        runner.register(PopulationOverTime::new());
+        let err = runner.feed(&[malformed]).unwrap_err();
+        assert!(matches!(err, ProjectionError::Apply { event_id: 4, .. }));
+        assert_eq!(runner.position("population_over_time"), Some(0));
+    }
+
+    #[test]
+    fn checkpoints_restore_the_view() {
+        let agent_id = AgentId::new();
+        let location_id = LocationId::new();
+        let mut runner = ProjectionRunner::with_builtins();
+        runner
+            .feed(&[
+                tick_start(1, 1, 3),
+                gathered(2, 1, agent_id, 2),
+                structure(3, 1, true, location_id),
+            ])
+            .unwrap();
+
+        let mut population = PopulationOverTime::new();
+        let mut wealth = WealthPerAgent::new();
+        let mut structures = StructuresPerLocation::new();
+        for restored in [
+            &mut population as &mut dyn Projection,
+            &mut wealth,
+            &mut structures,
+        ] {
+            let state = runner.get(restored.name()).unwrap().state().unwrap();
+            restored.restore(state).unwrap();
+        }
+        assert_eq!(population.at(1), Some(3));
+        assert_eq!(wealth.total(agent_id), 2);
+        assert_eq!(structures.count(location_id), 1);
+
+        assert!(wealth.restore(serde_json::json!([])).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Rebuilding one entity's state at a past tick.
+//!
+//! Showing "this agent at tick 5000" should not hydrate the whole world.
+//! [`rebuild_agent`] starts from the agent's own latest snapshot at or
+//! before the tick and replays only its events since, through the same
+//! logic as [hydration](crate::hydrate). [`rebuild_location`] folds the
+//! events recorded at the location: which structures stood there, what
+//! had been gathered there, and which agents were last seen there.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use emergence_db::{DbError, EventRow, EventStore, SnapshotStore};
+use emergence_types::{
+    AgentId, AgentState, EventFilter, LocationId, Resource, ResourceGatheredDetails, StructureId,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::hydrate::{HydrateError, HydratedState};
+use crate::projection::{Projection, StructuresPerLocation};
+use crate::tick_of;
+use crate::upcast::{UpcastError, Upcasters};
+
+/// Errors that can occur while rebuilding an entity.
+#[derive(Debug, thiserror::Error)]
+pub enum RebuildError {
+    /// A snapshot or event query failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// An agent snapshot or event could not be replayed.
+    #[error("replay failed: {0}")]
+    Hydrate(#[from] HydrateError),
+
+    /// An event at the location has malformed details.
+    #[error("event {event_id} at tick {tick} has malformed details: {source}")]
+    Details {
+        /// The event's row ID.
+        event_id: i64,
+        /// The tick the event occurred at.
+        tick: i64,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+
+    /// An event at the location could not be migrated to the current
+    /// schema.
+    #[error("cannot upcast event: {0}")]
+    Upcast(#[from] UpcastError),
+}
+
+/// One agent's state rebuilt at a tick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RebuiltAgent {
+    /// The tick the state was rebuilt at.
+    pub tick: u64,
+    /// The agent's state.
+    pub state: AgentState,
+    /// Whether the agent had died by then.
+    pub dead: bool,
+    /// Tick of the snapshot the rebuild started from, if any.
+    pub snapshot_tick: Option<u64>,
+    /// Number of events replayed on top of the snapshot.
+    pub events_applied: usize,
+}
+
+/// Rebuild `agent_id`'s state at `tick`.
+///
+/// Starts from the agent's latest snapshot at or before `tick` and replays
+/// its events since. Returns `None` if neither a snapshot nor an
+/// `agent_born` event places the agent in the world by then.
+///
+/// # Errors
+///
+/// Returns [`RebuildError::Db`] if a query fails, or
+/// [`RebuildError::Hydrate`] if the snapshot or an event is malformed.
+pub async fn rebuild_agent(
+    pool: &PgPool,
+    agent_id: AgentId,
+    tick: u64,
+) -> Result<Option<RebuiltAgent>, RebuildError> {
+    let snapshot = SnapshotStore::new(pool)
+        .get_agent_snapshot_at_or_before(agent_id.into_inner(), tick)
+        .await?;
+    let snapshot_tick = snapshot.as_ref().map(|row| tick_of(row.tick));
+    let from = snapshot_tick.map_or(0, |base| base.saturating_add(1));
+    let events = EventStore::new(pool)
+        .get_events_by_agent(agent_id.into_inner(), from, tick.saturating_add(1))
+        .await?;
+    Ok(replay_agent(agent_id, tick, snapshot.into_iter().collect(), &events)?)
+}
+
+/// Replay `events` onto `agent_id`'s snapshot, if any.
+fn replay_agent(
+    agent_id: AgentId,
+    tick: u64,
+    snapshot: Vec<emergence_db::AgentSnapshotRow>,
+    events: &[EventRow],
+) -> Result<Option<RebuiltAgent>, HydrateError> {
+    let snapshot_tick = snapshot.first().map(|row| tick_of(row.tick));
+    let mut hydrated = HydratedState::from_snapshots(tick, None, snapshot)?;
+    for event in events {
+        hydrated.apply(event)?;
+    }
+    let dead = hydrated.dead.contains(&agent_id);
+    Ok(hydrated.agents.remove(&agent_id).map(|state| RebuiltAgent {
+        tick,
+        state,
+        dead,
+        snapshot_tick,
+        events_applied: hydrated.events_applied,
+    }))
+}
+
+/// One location's state rebuilt at a tick from the events recorded there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebuiltLocation {
+    /// The location.
+    pub location_id: LocationId,
+    /// The tick the state was rebuilt at.
+    pub tick: u64,
+    /// Structures standing at the location.
+    pub structures: BTreeSet<StructureId>,
+    /// Total quantity of each resource gathered at the location so far.
+    pub gathered: BTreeMap<Resource, u32>,
+    /// Living agents whose latest located event placed them here.
+    pub agents_present: BTreeSet<AgentId>,
+    /// Number of events recorded at the location.
+    pub events_applied: usize,
+}
+
+impl RebuiltLocation {
+    /// An empty location at `tick`.
+    pub const fn new(location_id: LocationId, tick: u64) -> Self {
+        Self {
+            location_id,
+            tick,
+            structures: BTreeSet::new(),
+            gathered: BTreeMap::new(),
+            agents_present: BTreeSet::new(),
+            events_applied: 0,
+        }
+    }
+}
+
+/// Folds the events recorded at one location.
+struct LocationFold {
+    location: RebuiltLocation,
+    structures: StructuresPerLocation,
+    /// Agents seen at the location, and whether they have since died.
+    visitors: BTreeMap<AgentId, bool>,
+    /// Migrates each event to the current schema before it is folded.
+    upcasters: Upcasters,
+}
+
+impl LocationFold {
+    const fn new(location_id: LocationId, tick: u64) -> Self {
+        Self {
+            location: RebuiltLocation::new(location_id, tick),
+            structures: StructuresPerLocation::new(),
+            visitors: BTreeMap::new(),
+            upcasters: Upcasters::builtin(),
+        }
+    }
+
+    /// Fold one event at the location.
+    fn apply(&mut self, event: &EventRow) -> Result<(), RebuildError> {
+        let malformed = |source| RebuildError::Details {
+            event_id: event.id,
+            tick: event.tick,
+            source,
+        };
+        let current = self.upcasters.upgrade(event)?;
+        self.structures.apply(&current).map_err(malformed)?;
+        if event.event_type == "resource_gathered" {
+            let details: ResourceGatheredDetails = self.upcasters.decode(event)?;
+            if details.location_id == self.location.location_id {
+                let total = self.location.gathered.entry(details.resource).or_default();
+                *total = total.saturating_add(details.quantity);
+            }
+        }
+        if let Some(agent_id) = event.agent_id.map(AgentId::from) {
+            let died = event.event_type == "agent_died";
+            *self.visitors.entry(agent_id).or_default() |= died;
+        }
+        self.location.events_applied = self.location.events_applied.saturating_add(1);
+        Ok(())
+    }
+
+    /// The living agents seen here, as candidates for presence.
+    fn living_visitors(&self) -> Vec<AgentId> {
+        self.visitors
+            .iter()
+            .filter(|(_, dead)| !**dead)
+            .map(|(agent_id, _)| *agent_id)
+            .collect()
+    }
+
+    /// The rebuilt location, with `present` as the agents present.
+    fn finish(mut self, present: impl IntoIterator<Item = AgentId>) -> RebuiltLocation {
+        let location_id = self.location.location_id;
+        self.location.structures = self
+            .structures
+            .structures()
+            .get(&location_id)
+            .cloned()
+            .unwrap_or_default();
+        self.location.agents_present = present.into_iter().collect();
+        self.location
+    }
+}
+
+/// Rebuild `location_id`'s state at `tick` from the events recorded there
+/// at or before it.
+///
+/// # Errors
+///
+/// Returns [`RebuildError::Db`] if a query fails,
+/// [`RebuildError::Upcast`] if an event cannot be migrated to the current
+/// schema, or [`RebuildError::Details`] if an event's details are
+/// malformed.
+pub async fn rebuild_location(
+    pool: &PgPool,
+    location_id: LocationId,
+    tick: u64,
+) -> Result<RebuiltLocation, RebuildError> {
+    let store = EventStore::new(pool);
+    let filter = EventFilter::new()
+        .with_ticks(0, tick.saturating_add(1))
+        .with_location(location_id);
+    let mut fold = LocationFold::new(location_id, tick);
+    for event in &store.query(&filter, None).await? {
+        fold.apply(event)?;
+    }
+
+    // A visitor is still here if its latest located event anywhere is.
+    let visitors: Vec<_> = fold.living_visitors().into_iter().map(AgentId::into_inner).collect();
+    let present = store
+        .get_latest_locations(&visitors, tick)
+        .await?
+        .into_iter()
+        .filter(|(_, at)| *at == location_id.into_inner())
+        .map(|(agent_id, _)| AgentId::from(agent_id));
+    Ok(fold.finish(present))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+    use emergence_db::AgentSnapshotRow;
+    use emergence_types::{AgentStateSnapshot, EventType, StructureBuiltDetails, StructureType};
+
+    use super::*;
+    use crate::upcast::tests::RenameField;
+
+    fn event(id: i64, tick: i64, event_type: &str, agent: Option<&AgentState>) -> EventRow {
+        EventRow {
+            id,
+            tick,
+            event_type: event_type.to_owned(),
+            agent_id: agent.map(|a| a.agent_id.into_inner()),
+            location_id: agent.map(|a| a.location_id.into_inner()),
+            details: serde_json::json!({}),
+            agent_state_snapshot: agent.map(|a| {
+                serde_json::to_value(AgentStateSnapshot {
+                    energy: a.energy,
+                    health: a.health,
+                    hunger: a.hunger,
+                    age: a.age,
+                    location_id: a.location_id,
+                    inventory_summary: a.inventory.clone(),
+                })
+                .unwrap()
+            }),
+            world_context: None,
+            created_at: Utc::now(),
+            schema_version: 1,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    fn agent(location_id: LocationId) -> AgentState {
+        AgentState::builder(AgentId::new(), location_id).build().unwrap()
+    }
+
+    #[test]
+    fn agents_replay_from_their_snapshot() {
+        let mut state = agent(LocationId::new());
+        let snapshot = AgentSnapshotRow {
+            id: 1,
+            tick: 100,
+            agent_id: state.agent_id.into_inner(),
+            full_state: serde_json::to_value(&state).unwrap(),
+            created_at: Utc::now(),
+        };
+        state.energy = 25;
+        let events = [
+            event(7, 120, "action_succeeded", Some(&state)),
+            event(8, 130, "agent_died", Some(&state)),
+        ];
+
+        let rebuilt =
+            replay_agent(state.agent_id, 125, vec![snapshot.clone()], &events).unwrap().unwrap();
+        assert_eq!(rebuilt.state.energy, 25);
+        assert!(!rebuilt.dead, "the death comes after the tick");
+        assert_eq!((rebuilt.snapshot_tick, rebuilt.events_applied), (Some(100), 1));
+
+        let rebuilt = replay_agent(state.agent_id, 130, vec![snapshot], &events).unwrap();
+        assert!(rebuilt.unwrap().dead);
+
+        let unknown = agent(LocationId::new());
+        assert!(replay_agent(unknown.agent_id, 130, Vec::new(), &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn locations_fold_structures_gathering_and_visitors() {
+        let clearing = LocationId::new();
+        let (stayed, died) = (agent(clearing), agent(clearing));
+        let hut = StructureId::new();
+
+        let mut built = event(1, 10, "structure_built", Some(&stayed));
+        built.details = serde_json::to_value(StructureBuiltDetails {
+            structure_id: hut,
+            structure_type: StructureType::Campfire,
+            location_id: clearing,
+            builder: stayed.agent_id,
+            materials_used: BTreeMap::new(),
+        })
+        .unwrap();
+        let mut gathered = event(2, 11, "resource_gathered", Some(&stayed));
+        gathered.details = serde_json::to_value(ResourceGatheredDetails {
+            resource: Resource::Wood,
+            quantity: 4,
+            location_id: clearing,
+            skill_xp_gained: 1,
+        })
+        .unwrap();
+        let death = event(3, 12, "agent_died", Some(&died));
+
+        let mut fold = LocationFold::new(clearing, 20);
+        for e in [&built, &gathered, &gathered, &death] {
+            fold.apply(e).unwrap();
+        }
+        assert_eq!(fold.living_visitors(), [stayed.agent_id]);
+
+        let location = fold.finish([stayed.agent_id]);
+        assert_eq!(location.structures, BTreeSet::from([hut]));
+        assert_eq!(location.gathered, BTreeMap::from([(Resource::Wood, 8)]));
+        assert_eq!(location.agents_present, BTreeSet::from([stayed.agent_id]));
+        assert_eq!(location.events_applied, 4);
+    }
+
+    #[test]
+    fn old_gathering_events_are_upcast_before_folding() {
+        // Version 1 of resource-gathered details called the quantity `amount`.
+        let clearing = LocationId::new();
+        let mut gathered = event(1, 10, "resource_gathered", Some(&agent(clearing)));
+        gathered.details = serde_json::json!({
+            "resource": "Wood",
+            "amount": 4,
+            "location_id": clearing,
+            "skill_xp_gained": 1,
+        });
+
+        let mut stale = LocationFold::new(clearing, 20);
+        assert!(matches!(stale.apply(&gathered), Err(RebuildError::Upcast(_))));
+
+        let mut fold = LocationFold::new(clearing, 20);
+        fold.upcasters = Upcasters::new().migrating_to(2);
+        fold.upcasters.register(RenameField {
+            event_type: EventType::ResourceGathered,
+            source_version: 1,
+            from: "amount",
+            to: "quantity",
+        });
+        fold.apply(&gathered).unwrap();
+        let location = fold.finish([]);
+        assert_eq!(location.gathered, BTreeMap::from([(Resource::Wood, 4)]));
+    }
+}
@@ -0,0 +1,302 @@
+//! Given/When/Then scenarios over the replay logic.
+//!
+//! Agent-behaviour regressions are easiest to state at the event level:
+//! after these events, deciding for the agents should emit those. A
+//! scenario replays the `given` events into a [`Replay`] state, runs the
+//! `when` action against it, checks the events the action emits against
+//! one [`EventFilter`] each, and replays them too, returning the state for
+//! any further assertions:
+//!
+//! ```ignore
+//! let state = given::<WealthPerAgent>(history)
+//!     .when(|wealth| decide(wealth))
+//!     .then([EventFilter::new().with_event_type(EventType::TradeCompleted)])
+//!     .unwrap();
+//! ```
+//!
+//! Events are replayed as the rows the event store would return, numbered
+//! from 1 in the order given, so a scenario exercises the same code paths
+//! as hydration and the projection runner without a database.
+
+use emergence_db::{DbError, EventRow};
+use emergence_types::{Event, EventFilter, EventType};
+
+use crate::hydrate::{HydrateError, HydratedState};
+use crate::projection::Projection;
+
+/// Errors that fail a scenario.
+#[derive(Debug, thiserror::Error)]
+pub enum ScenarioError {
+    /// An event could not be converted to a stored row.
+    #[error("cannot encode scenario event: {0}")]
+    Encode(#[from] DbError),
+
+    /// The state rejected an event while replaying it.
+    #[error("{phase} event {index} ({event_type:?} at tick {tick}) failed to replay: {reason}")]
+    Replay {
+        /// `"given"` or `"when"`.
+        phase: &'static str,
+        /// The event's position within its phase.
+        index: usize,
+        /// The event's type.
+        event_type: EventType,
+        /// The tick the event occurred at.
+        tick: u64,
+        /// Why it was rejected.
+        reason: String,
+    },
+
+    /// The action emitted a different number of events than expected.
+    #[error("expected {expected} events, but the action emitted {emitted:?}")]
+    Count {
+        /// Number of events expected.
+        expected: usize,
+        /// The types of the events the action emitted.
+        emitted: Vec<EventType>,
+    },
+
+    /// An emitted event does not match its expectation.
+    #[error("emitted event {index} ({event:?}) does not match {expected:?}")]
+    Mismatch {
+        /// The event's position among those emitted.
+        index: usize,
+        /// The emitted event.
+        event: Box<Event>,
+        /// The filter it was expected to pass.
+        expected: Box<EventFilter>,
+    },
+}
+
+/// State that events are replayed into.
+pub trait Replay {
+    /// Why an event could not be replayed.
+    type Error: std::fmt::Display;
+
+    /// Apply one stored event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be applied.
+    fn replay(&mut self, event: &EventRow) -> Result<(), Self::Error>;
+}
+
+impl Replay for HydratedState {
+    type Error = HydrateError;
+
+    fn replay(&mut self, event: &EventRow) -> Result<(), Self::Error> {
+        self.apply(event)
+    }
+}
+
+impl<P: Projection> Replay for P {
+    type Error = serde_json::Error;
+
+    fn replay(&mut self, event: &EventRow) -> Result<(), Self::Error> {
+        self.apply(event).map(drop)
+    }
+}
+
+/// A scenario whose history has been replayed.
+#[derive(Debug)]
+#[must_use = "a scenario does nothing until `when` and `then` are called"]
+pub struct Given<S> {
+    /// The state after the history, or the error that stopped it.
+    state: Result<S, ScenarioError>,
+    /// Row ID the next replayed event gets.
+    next_id: i64,
+}
+
+/// Start a scenario from `S`'s default state.
+pub fn given<S: Replay + Default>(events: impl IntoIterator<Item = Event>) -> Given<S> {
+    Given::on(S::default(), events)
+}
+
+impl<S: Replay> Given<S> {
+    /// Start a scenario from `state`, replaying `events` into it.
+    pub fn on(state: S, events: impl IntoIterator<Item = Event>) -> Self {
+        let mut next_id = 1;
+        let events: Vec<Event> = events.into_iter().collect();
+        let state = replay_all(state, "given", &events, &mut next_id);
+        Self { state, next_id }
+    }
+
+    /// Run `action` against the replayed state; it returns the events it
+    /// emits.
+    pub fn when(self, action: impl FnOnce(&S) -> Vec<Event>) -> When<S> {
+        let emitted = self.state.as_ref().map(action).unwrap_or_default();
+        When {
+            state: self.state,
+            emitted,
+            next_id: self.next_id,
+        }
+    }
+}
+
+/// A scenario whose action has run.
+#[derive(Debug)]
+#[must_use = "a scenario does nothing until `then` is called"]
+pub struct When<S> {
+    /// The state after the history, or the error that stopped it.
+    state: Result<S, ScenarioError>,
+    /// The events the action emitted.
+    emitted: Vec<Event>,
+    /// Row ID the next replayed event gets.
+    next_id: i64,
+}
+
+impl<S: Replay> When<S> {
+    /// The events the action emitted.
+    pub fn emitted(&self) -> &[Event] {
+        &self.emitted
+    }
+
+    /// Check that the action emitted exactly one event per filter in
+    /// `expected`, each passing its filter, in order. Returns the state
+    /// with the emitted events replayed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScenarioError::Count`] or [`ScenarioError::Mismatch`] if
+    /// the emitted events differ from `expected`, or another
+    /// [`ScenarioError`] if an event could not be replayed.
+    pub fn then(self, expected: impl IntoIterator<Item = EventFilter>) -> Result<S, ScenarioError> {
+        let state = self.state?;
+        let expected: Vec<EventFilter> = expected.into_iter().collect();
+        if expected.len() != self.emitted.len() {
+            return Err(ScenarioError::Count {
+                expected: expected.len(),
+                emitted: self.emitted.iter().map(|event| event.event_type).collect(),
+            });
+        }
+        for (index, (event, filter)) in self.emitted.iter().zip(expected).enumerate() {
+            if !filter.matches(event) {
+                return Err(ScenarioError::Mismatch {
+                    index,
+                    event: Box::new(event.clone()),
+                    expected: Box::new(filter),
+                });
+            }
+        }
+        let mut next_id = self.next_id;
+        replay_all(state, "when", &self.emitted, &mut next_id)
+    }
+
+    /// Check that the action emitted nothing. Returns the state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScenarioError::Count`] if the action emitted events, or
+    /// another [`ScenarioError`] if the history could not be replayed.
+    pub fn then_nothing(self) -> Result<S, ScenarioError> {
+        self.then([])
+    }
+}
+
+/// Replay `events` into `state` in order, numbering their rows from
+/// `next_id`.
+fn replay_all<S: Replay>(
+    mut state: S,
+    phase: &'static str,
+    events: &[Event],
+    next_id: &mut i64,
+) -> Result<S, ScenarioError> {
+    for (index, event) in events.iter().enumerate() {
+        let mut row = EventRow::from_event(event)?;
+        row.id = *next_id;
+        *next_id = next_id.saturating_add(1);
+        state.replay(&row).map_err(|e| ScenarioError::Replay {
+            phase,
+            index,
+            event_type: event.event_type,
+            tick: event.tick,
+            reason: e.to_string(),
+        })?;
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::{
+        AgentId, AgentStateSnapshot, Era, LocationId, Resource, Season, Weather, WorldContext,
+    };
+
+    use super::*;
+    use crate::projection::WealthPerAgent;
+
+    fn context(tick: u64) -> WorldContext {
+        WorldContext {
+            tick,
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 2,
+        }
+    }
+
+    fn event(tick: u64, event_type: EventType, agent_id: AgentId, energy: u32) -> Event {
+        Event::builder(event_type, context(tick))
+            .agent(agent_id)
+            .agent_state(AgentStateSnapshot {
+                energy,
+                health: 100,
+                hunger: 0,
+                age: tick.try_into().unwrap(),
+                location_id: LocationId::new(),
+                inventory_summary: std::iter::once((Resource::Wood, energy)).collect(),
+            })
+            .build()
+            .unwrap()
+    }
+
+    /// The behaviour under test: every living agent below 20 energy dies.
+    fn starve(state: &HydratedState) -> Vec<Event> {
+        state
+            .agents
+            .values()
+            .filter(|agent| agent.energy < 20 && !state.dead.contains(&agent.agent_id))
+            .map(|agent| event(state.tick, EventType::AgentDied, agent.agent_id, 0))
+            .collect()
+    }
+
+    fn hydrated() -> HydratedState {
+        HydratedState::from_snapshots(50, None, Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn scenarios_replay_the_history_and_the_action() {
+        let (strong, weak) = (AgentId::new(), AgentId::new());
+        let history = [
+            event(1, EventType::AgentBorn, strong, 80),
+            event(1, EventType::AgentBorn, weak, 80),
+            event(5, EventType::ActionSucceeded, weak, 10),
+        ];
+
+        let state = Given::on(hydrated(), history.clone())
+            .when(starve)
+            .then([EventFilter::new().with_event_type(EventType::AgentDied).with_agent(weak)])
+            .unwrap();
+        assert_eq!(state.dead.iter().collect::<Vec<_>>(), [&weak]);
+        assert_eq!(state.events_applied, 4);
+
+        let wealth = given::<WealthPerAgent>(history).when(|_| Vec::new()).then_nothing().unwrap();
+        assert_eq!(wealth.total(weak), 10);
+    }
+
+    #[test]
+    fn unexpected_events_fail_the_scenario() {
+        let agent = AgentId::new();
+        let history = [event(1, EventType::AgentBorn, agent, 5)];
+
+        let err = Given::on(hydrated(), history.clone()).when(starve).then_nothing().unwrap_err();
+        assert!(matches!(
+            err,
+            ScenarioError::Count { expected: 0, ref emitted } if emitted == &[EventType::AgentDied]
+        ));
+
+        let other = EventFilter::new().with_agent(AgentId::new());
+        let err = Given::on(hydrated(), history).when(starve).then([other]).unwrap_err();
+        assert!(matches!(err, ScenarioError::Mismatch { index: 0, .. }));
+    }
+}
@@ -0,0 +1,288 @@
+//! Periodic state snapshots.
+//!
+//! Snapshots bound how much of the event log [hydration](crate::hydrate)
+//! has to replay, and tell [compaction](crate::compact) which events are
+//! safe to fold. A [`SnapshotScheduler`] decides when one is due -- every
+//! N ticks, every N minutes of wall-clock time, on an era change, or any
+//! combination -- and writes the world snapshot and every agent's full
+//! state through [`SnapshotStore`] when it is.
+//!
+//! The tick loop calls [`SnapshotScheduler::on_tick`] once per tick with
+//! that tick's state. The first call always snapshots, so a run has a base
+//! to hydrate from as soon as it starts.
+
+use std::time::{Duration, Instant};
+
+use emergence_db::{DbError, SnapshotStore};
+use emergence_types::{AgentState, Era, WorldSnapshot};
+use sqlx::PgPool;
+
+/// Errors that can occur while writing a snapshot.
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// A snapshot insert failed.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+
+    /// A snapshot could not be encoded.
+    #[error("cannot encode a snapshot: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// When snapshots are due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotCadence {
+    /// Every this many ticks.
+    EveryTicks(u64),
+    /// Every time this much wall-clock time has passed.
+    Every(Duration),
+    /// Whenever the era changes.
+    OnEraChange,
+}
+
+impl SnapshotCadence {
+    /// Every `minutes` minutes of wall-clock time.
+    pub const fn every_minutes(minutes: u64) -> Self {
+        Self::Every(Duration::from_secs(minutes.saturating_mul(60)))
+    }
+}
+
+/// Why a snapshot was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotTrigger {
+    /// No snapshot had been taken yet.
+    First,
+    /// The tick interval passed.
+    Ticks,
+    /// The wall-clock interval passed.
+    Elapsed,
+    /// The era changed.
+    EraChanged,
+}
+
+/// The most recent snapshot a scheduler took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LastSnapshot {
+    /// The tick it captured.
+    tick: u64,
+    /// When it was taken.
+    at: Instant,
+    /// The era at the time.
+    era: Era,
+}
+
+/// Decides when to snapshot state, and writes the snapshots.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotScheduler {
+    /// The configured cadences; a snapshot is due when any of them is.
+    cadences: Vec<SnapshotCadence>,
+    /// The most recent snapshot, `None` before the first.
+    last: Option<LastSnapshot>,
+}
+
+impl SnapshotScheduler {
+    /// Create a scheduler with no cadence. It never snapshots until one is
+    /// added.
+    pub const fn new() -> Self {
+        Self {
+            cadences: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Also snapshot on `cadence`.
+    #[must_use]
+    pub fn with_cadence(mut self, cadence: SnapshotCadence) -> Self {
+        self.cadences.push(cadence);
+        self
+    }
+
+    /// The configured cadences.
+    pub fn cadences(&self) -> &[SnapshotCadence] {
+        &self.cadences
+    }
+
+    /// The tick of the most recent snapshot, if one was taken.
+    pub fn last_tick(&self) -> Option<u64> {
+        self.last.map(|last| last.tick)
+    }
+
+    /// Why a snapshot of `world` is due at `now`, or `None` if it is not.
+    pub fn due(&self, world: &WorldSnapshot, now: Instant) -> Option<SnapshotTrigger> {
+        if self.cadences.is_empty() {
+            return None;
+        }
+        let Some(last) = self.last else {
+            return Some(SnapshotTrigger::First);
+        };
+        self.cadences.iter().find_map(|cadence| {
+            let (due, trigger) = match *cadence {
+                SnapshotCadence::EveryTicks(ticks) => (
+                    world.tick >= last.tick.saturating_add(ticks.max(1)),
+                    SnapshotTrigger::Ticks,
+                ),
+                SnapshotCadence::Every(interval) => (
+                    now.saturating_duration_since(last.at) >= interval,
+                    SnapshotTrigger::Elapsed,
+                ),
+                SnapshotCadence::OnEraChange => {
+                    (world.era != last.era, SnapshotTrigger::EraChanged)
+                }
+            };
+            due.then_some(trigger)
+        })
+    }
+
+    /// Record that `world` was snapshotted at `now`.
+    pub const fn record(&mut self, world: &WorldSnapshot, now: Instant) {
+        self.last = Some(LastSnapshot {
+            tick: world.tick,
+            at: now,
+            era: world.era,
+        });
+    }
+
+    /// Snapshot `world` and `agents` if a snapshot is due, returning why.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SnapshotError`] if the snapshot cannot be written; the
+    /// scheduler then still considers it due.
+    pub async fn on_tick(
+        &mut self,
+        pool: &PgPool,
+        world: &WorldSnapshot,
+        agents: &[AgentState],
+    ) -> Result<Option<SnapshotTrigger>, SnapshotError> {
+        let now = Instant::now();
+        let Some(trigger) = self.due(world, now) else {
+            return Ok(None);
+        };
+        write_snapshot(pool, world, agents).await?;
+        self.record(world, now);
+        tracing::info!(tick = world.tick, ?trigger, agents = agents.len(), "Took state snapshot");
+        Ok(Some(trigger))
+    }
+}
+
+/// Write `world` and every agent's full state as snapshots at
+/// `world.tick`.
+///
+/// The world row's `summary` holds the whole [`WorldSnapshot`], so it can
+/// be decoded back; the other columns hold the figures the dashboard
+/// queries.
+///
+/// # Errors
+///
+/// Returns [`SnapshotError::Db`] if an insert fails, or
+/// [`SnapshotError::Encode`] if a snapshot cannot be encoded.
+pub async fn write_snapshot(
+    pool: &PgPool,
+    world: &WorldSnapshot,
+    agents: &[AgentState],
+) -> Result<(), SnapshotError> {
+    let store = SnapshotStore::new(pool);
+    let count = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    let figure = |n: u32| i32::try_from(n).unwrap_or(i32::MAX);
+    let wealth = serde_json::json!({
+        "gini_coefficient": world.economy.gini_coefficient,
+        "resources_in_circulation": world.economy.resources_in_circulation,
+        "resources_at_nodes": world.economy.resources_at_nodes,
+    });
+    store
+        .insert_world_snapshot(
+            world.tick,
+            &format!("{:?}", world.era),
+            &format!("{:?}", world.season),
+            &format!("{:?}", world.weather),
+            figure(world.population.total_alive),
+            figure(world.population.births_this_tick),
+            figure(world.population.deaths_this_tick),
+            &serde_json::to_value(&world.economy.total_resources)?,
+            &wealth,
+            figure(world.economy.trades_this_tick),
+            count(world.discoveries.len()),
+            &serde_json::to_value(world)?,
+        )
+        .await?;
+
+    let rows = agents
+        .iter()
+        .map(|agent| Ok((world.tick, agent.agent_id.into_inner(), serde_json::to_value(agent)?)))
+        .collect::<Result<Vec<_>, serde_json::Error>>()?;
+    store.batch_insert_agent_snapshots(&rows).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{EconomyStats, PopulationStats, Season, Weather};
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn world(tick: u64, era: Era) -> WorldSnapshot {
+        WorldSnapshot {
+            tick,
+            era,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: PopulationStats {
+                total_alive: 3,
+                total_dead: 0,
+                births_this_tick: 0,
+                deaths_this_tick: 0,
+                average_age: Decimal::ZERO,
+                oldest_agent: None,
+            },
+            economy: EconomyStats {
+                total_resources: BTreeMap::new(),
+                resources_in_circulation: BTreeMap::new(),
+                resources_at_nodes: BTreeMap::new(),
+                trades_this_tick: 0,
+                gini_coefficient: Decimal::ZERO,
+            },
+            discoveries: Vec::new(),
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn without_a_cadence_nothing_is_due() {
+        let scheduler = SnapshotScheduler::new();
+        assert_eq!(scheduler.due(&world(0, Era::Primitive), Instant::now()), None);
+    }
+
+    #[test]
+    fn tick_and_era_cadences() {
+        let mut scheduler = SnapshotScheduler::new()
+            .with_cadence(SnapshotCadence::EveryTicks(10))
+            .with_cadence(SnapshotCadence::OnEraChange);
+        let now = Instant::now();
+        assert_eq!(scheduler.due(&world(3, Era::Primitive), now), Some(SnapshotTrigger::First));
+        scheduler.record(&world(3, Era::Primitive), now);
+        assert_eq!(scheduler.last_tick(), Some(3));
+
+        assert_eq!(scheduler.due(&world(12, Era::Primitive), now), None);
+        assert_eq!(scheduler.due(&world(13, Era::Primitive), now), Some(SnapshotTrigger::Ticks));
+        let due = scheduler.due(&world(5, Era::Tribal), now);
+        assert_eq!(due, Some(SnapshotTrigger::EraChanged));
+    }
+
+    #[test]
+    fn wall_clock_cadence() {
+        let mut scheduler =
+            SnapshotScheduler::new().with_cadence(SnapshotCadence::every_minutes(1));
+        let start = Instant::now();
+        scheduler.record(&world(0, Era::Primitive), start);
+
+        let soon = start.checked_add(Duration::from_secs(59)).unwrap();
+        assert_eq!(scheduler.due(&world(1, Era::Primitive), soon), None);
+        let later = start.checked_add(Duration::from_mins(1)).unwrap();
+        let due = scheduler.due(&world(1, Era::Primitive), later);
+        assert_eq!(due, Some(SnapshotTrigger::Elapsed));
+    }
+}
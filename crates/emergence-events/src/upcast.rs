@@ -0,0 +1,318 @@
+//! Migrating old event payloads to the current detail types at read time.
+//!
+//! Every persisted event records the [`EVENT_SCHEMA_VERSION`] its details
+//! were written under. When a details struct in `emergence-types` changes
+//! shape, the version is bumped and an [`Upcaster`] is registered that
+//! rewrites that event type's details from the previous version. Reading
+//! an old event through [`Upcasters`] runs every step between its version
+//! and the current one, so replaying a historical run decodes into today's
+//! structs instead of failing.
+//!
+//! Event types whose details did not change in a version need no step for
+//! it; their payload passes through unchanged.
+//!
+//! [Hydration](crate::hydrate), [rebuilds](crate::rebuild),
+//! [projections](crate::projection) and the
+//! [determinism check](crate::determinism) all read events through a
+//! registry, [`Upcasters::builtin`] unless one is given.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use emergence_db::{event_type_to_db, EventRow};
+use emergence_types::{EventType, EVENT_SCHEMA_VERSION};
+use serde::de::DeserializeOwned;
+
+/// Errors that can occur while upcasting an event.
+#[derive(Debug, thiserror::Error)]
+pub enum UpcastError {
+    /// The event was written by a newer build than this one.
+    #[error("event {event_id} has schema version {version}, newer than {EVENT_SCHEMA_VERSION}")]
+    FutureVersion {
+        /// The event's row ID.
+        event_id: i64,
+        /// The version the event was written under.
+        version: i32,
+    },
+
+    /// An upcaster could not migrate a payload.
+    #[error("cannot upcast {event_type} details from version {source_version}: {reason}")]
+    Migration {
+        /// The event type as stored.
+        event_type: String,
+        /// The version the failing step reads.
+        source_version: u32,
+        /// Why the payload could not be migrated.
+        reason: String,
+    },
+
+    /// The upcast details do not match the requested type.
+    #[error("event {event_id} details do not decode: {source}")]
+    Decode {
+        /// The event's row ID.
+        event_id: i64,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+}
+
+/// One step in an event type's schema history.
+pub trait Upcaster: Send + Sync {
+    /// The event type whose details this step migrates.
+    fn event_type(&self) -> EventType;
+
+    /// The schema version this step reads. It produces the next one.
+    fn source_version(&self) -> u32;
+
+    /// Rewrite `details` from [`source_version`](Self::source_version) to the
+    /// version after it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a reason if the payload cannot be migrated.
+    fn upcast(&self, details: serde_json::Value) -> Result<serde_json::Value, String>;
+}
+
+/// The registered upcasters, keyed by event type and the version they read.
+pub struct Upcasters {
+    steps: BTreeMap<(&'static str, u32), Box<dyn Upcaster>>,
+    /// The version events are migrated to: [`EVENT_SCHEMA_VERSION`] outside
+    /// of tests.
+    current: u32,
+}
+
+impl Default for Upcasters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Upcasters {
+    /// Create an empty registry.
+    pub const fn new() -> Self {
+        Self {
+            steps: BTreeMap::new(),
+            current: EVENT_SCHEMA_VERSION,
+        }
+    }
+
+    /// This registry migrating to `version` instead, so tests can replay
+    /// events older than a version this build has not reached.
+    #[cfg(test)]
+    pub(crate) const fn migrating_to(mut self, version: u32) -> Self {
+        self.current = version;
+        self
+    }
+
+    /// This build's schema history: a step for every version in which a
+    /// details struct changed shape. Empty until the first such change.
+    pub const fn builtin() -> Self {
+        Self::new()
+    }
+
+    /// Register `upcaster`, replacing any step already registered for the
+    /// same event type and version.
+    pub fn register(&mut self, upcaster: impl Upcaster + 'static) {
+        let key = (event_type_to_db(upcaster.event_type()), upcaster.source_version());
+        self.steps.insert(key, Box::new(upcaster));
+    }
+
+    /// Number of registered steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether no steps are registered.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// `row`'s details migrated to [`EVENT_SCHEMA_VERSION`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UpcastError::FutureVersion`] if the event is newer than
+    /// this build, or [`UpcastError::Migration`] if a step fails.
+    pub fn upcast(&self, row: &EventRow) -> Result<serde_json::Value, UpcastError> {
+        self.upcast_to(row, self.current)
+    }
+
+    /// `row`'s details migrated to version `target`.
+    fn upcast_to(&self, row: &EventRow, target: u32) -> Result<serde_json::Value, UpcastError> {
+        // Rows from before versioning default to 1; treat anything lower
+        // the same way.
+        let mut version = u32::try_from(row.schema_version).unwrap_or(0).max(1);
+        if version > target {
+            return Err(UpcastError::FutureVersion {
+                event_id: row.id,
+                version: row.schema_version,
+            });
+        }
+
+        let mut details = row.details.clone();
+        while version < target {
+            if let Some(step) = self.steps.get(&(row.event_type.as_str(), version)) {
+                details = step.upcast(details).map_err(|reason| UpcastError::Migration {
+                    event_type: row.event_type.clone(),
+                    source_version: version,
+                    reason,
+                })?;
+            }
+            version = version.saturating_add(1);
+        }
+        Ok(details)
+    }
+
+    /// `row` with its details migrated to [`EVENT_SCHEMA_VERSION`] and
+    /// stamped with it, or `row` itself if it is already current.
+    ///
+    /// # Errors
+    ///
+    /// Returns any [`upcast`](Self::upcast) error.
+    pub fn upgrade<'a>(&self, row: &'a EventRow) -> Result<Cow<'a, EventRow>, UpcastError> {
+        let current = i32::try_from(self.current).unwrap_or(i32::MAX);
+        if row.schema_version == current {
+            return Ok(Cow::Borrowed(row));
+        }
+        let details = self.upcast(row)?;
+        let mut upgraded = row.clone();
+        upgraded.details = details;
+        upgraded.schema_version = current;
+        Ok(Cow::Owned(upgraded))
+    }
+
+    /// `row`'s details, upcast and decoded into the current details type.
+    ///
+    /// # Errors
+    ///
+    /// Returns any [`upcast`](Self::upcast) error, or
+    /// [`UpcastError::Decode`] if the result is not a `T`.
+    pub fn decode<T: DeserializeOwned>(&self, row: &EventRow) -> Result<T, UpcastError> {
+        let details = self.upcast(row)?;
+        serde_json::from_value(details).map_err(|source| UpcastError::Decode {
+            event_id: row.id,
+            source,
+        })
+    }
+}
+
+impl std::fmt::Debug for Upcasters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Upcasters")
+            .field("steps", &self.steps.keys().collect::<Vec<_>>())
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) mod tests {
+    use chrono::Utc;
+    use emergence_types::KnowledgeDiscoveredDetails;
+
+    use super::*;
+
+    /// A step renaming one field of an event type's details.
+    pub(crate) struct RenameField {
+        pub(crate) event_type: EventType,
+        pub(crate) source_version: u32,
+        pub(crate) from: &'static str,
+        pub(crate) to: &'static str,
+    }
+
+    impl RenameField {
+        /// A step renaming `from` to `to` in knowledge-discovered details.
+        const fn knowledge(source_version: u32, from: &'static str, to: &'static str) -> Self {
+            Self {
+                event_type: EventType::KnowledgeDiscovered,
+                source_version,
+                from,
+                to,
+            }
+        }
+    }
+
+    impl Upcaster for RenameField {
+        fn event_type(&self) -> EventType {
+            self.event_type
+        }
+
+        fn source_version(&self) -> u32 {
+            self.source_version
+        }
+
+        fn upcast(&self, mut details: serde_json::Value) -> Result<serde_json::Value, String> {
+            let object = details.as_object_mut().ok_or("details are not an object")?;
+            let value = object.remove(self.from).ok_or("field missing")?;
+            object.insert(self.to.to_owned(), value);
+            Ok(details)
+        }
+    }
+
+    fn row(schema_version: i32, details: serde_json::Value) -> EventRow {
+        EventRow {
+            id: 1,
+            tick: 10,
+            event_type: "knowledge_discovered".to_owned(),
+            agent_id: None,
+            location_id: None,
+            details,
+            agent_state_snapshot: None,
+            world_context: None,
+            created_at: Utc::now(),
+            schema_version,
+            event_id: None,
+            caused_by: None,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn current_events_pass_through() {
+        let mut upcasters = Upcasters::new();
+        upcasters.register(RenameField::knowledge(0, "idea", "knowledge"));
+        let details = serde_json::json!({
+            "knowledge": "fire",
+            "method": "experimentation",
+            "prerequisites": [],
+        });
+        let current = row(i32::try_from(EVENT_SCHEMA_VERSION).unwrap(), details.clone());
+
+        assert_eq!(upcasters.upcast(&current).unwrap(), details);
+        assert!(matches!(upcasters.upgrade(&current).unwrap(), Cow::Borrowed(_)));
+        let decoded: KnowledgeDiscoveredDetails = upcasters.decode(&current).unwrap();
+        assert_eq!(decoded.knowledge, "fire");
+    }
+
+    #[test]
+    fn future_versions_are_rejected() {
+        let upcasters = Upcasters::new();
+        let newer = i32::try_from(EVENT_SCHEMA_VERSION).unwrap() + 1;
+        let err = upcasters.upcast(&row(newer, serde_json::json!({}))).unwrap_err();
+        assert!(matches!(err, UpcastError::FutureVersion { version, .. } if version == newer));
+    }
+
+    #[test]
+    fn steps_chain_from_the_stored_version() {
+        let mut upcasters = Upcasters::new();
+        upcasters.register(RenameField::knowledge(1, "idea", "concept"));
+        upcasters.register(RenameField::knowledge(2, "concept", "knowledge"));
+        assert_eq!(upcasters.len(), 2);
+
+        let v1 = row(1, serde_json::json!({"idea": "fire"}));
+        let v2 = row(2, serde_json::json!({"concept": "fire"}));
+        let expected = serde_json::json!({"knowledge": "fire"});
+        assert_eq!(upcasters.upcast_to(&v1, 3).unwrap(), expected);
+        assert_eq!(upcasters.upcast_to(&v2, 3).unwrap(), expected);
+
+        // Steps belong to one event type; others pass through.
+        let mut other = row(1, serde_json::json!({"idea": "fire"}));
+        other.event_type = "knowledge_taught".to_owned();
+        assert_eq!(upcasters.upcast_to(&other, 3).unwrap(), other.details);
+
+        let broken = row(1, serde_json::json!([]));
+        let err = upcasters.upcast_to(&broken, 3).unwrap_err();
+        assert!(matches!(err, UpcastError::Migration { source_version: 1, .. }));
+    }
+}
@@ -0,0 +1,245 @@
+//! Wealth distribution analytics computed from ledger entries.
+//!
+//! An agent's holdings at a tick are its net balance of each resource over
+//! every entry recorded at or before the tick, and its wealth is the sum of
+//! those holdings in units. From these, [`wealth_distribution`] derives the
+//! Gini coefficient, wealth percentiles, and how concentrated each resource
+//! is among its holders.
+//!
+//! Only agents are counted: locations, structures, the world, and the void
+//! hold resources too, but they are not part of the economy being measured.
+//! An agent whose balance has gone negative through corrupt entries counts
+//! as holding nothing.
+//!
+//! The Gini coefficient uses the sorted-rank form
+//!
+//! ```text
+//! G = 2 * sum(i * x_i) / (n * sum(x_i)) - (n + 1) / n
+//! ```
+//!
+//! over wealth sorted ascending with 1-based ranks `i`. It is 0 for perfect
+//! equality and approaches 1 as one agent comes to hold everything.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use emergence_types::{EntityType, LedgerEntry, Resource};
+
+/// One agent's net holdings of each resource.
+type Holdings = BTreeMap<Resource, Decimal>;
+
+/// The percentiles reported in [`WealthDistribution::percentiles`].
+pub const PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+/// How wealth was distributed among agents at a tick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WealthDistribution {
+    /// The tick the distribution was taken at.
+    pub tick: u64,
+    /// Number of agents that had held any resource by the tick.
+    pub agents: usize,
+    /// Total wealth held by those agents, in resource units.
+    pub total_wealth: Decimal,
+    /// Gini coefficient of agent wealth.
+    pub gini: Decimal,
+    /// Agent wealth at each of [`PERCENTILES`], by the nearest-rank method.
+    pub percentiles: BTreeMap<u8, Decimal>,
+    /// Concentration of each resource among the agents holding it.
+    pub resources: BTreeMap<Resource, ResourceConcentration>,
+}
+
+/// How concentrated one resource was among agents at a tick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceConcentration {
+    /// Total quantity held by agents.
+    pub total: Decimal,
+    /// Number of agents holding a positive quantity.
+    pub holders: usize,
+    /// Gini coefficient of the quantity held, over every counted agent.
+    pub gini: Decimal,
+    /// Share of the total held by the richest tenth of counted agents,
+    /// rounded up to at least one agent.
+    pub top_decile_share: Decimal,
+}
+
+/// Compute the wealth distribution among agents after `tick` from
+/// `entries`.
+pub fn wealth_distribution(entries: &[LedgerEntry], tick: u64) -> WealthDistribution {
+    let holdings = agent_holdings(entries, tick);
+
+    let mut wealth: Vec<Decimal> = holdings
+        .values()
+        .map(|held| held.values().fold(Decimal::ZERO, |sum, q| sum.saturating_add(*q)))
+        .collect();
+    wealth.sort_unstable();
+
+    let mut by_resource: BTreeMap<Resource, Vec<Decimal>> = BTreeMap::new();
+    for held in holdings.values() {
+        for (resource, quantity) in held {
+            by_resource.entry(*resource).or_default().push(*quantity);
+        }
+    }
+    let resources = by_resource
+        .into_iter()
+        .map(|(resource, mut quantities)| {
+            // Agents that never held the resource hold none of it.
+            quantities.resize(holdings.len(), Decimal::ZERO);
+            quantities.sort_unstable();
+            (resource, concentration(&quantities))
+        })
+        .collect();
+
+    WealthDistribution {
+        tick,
+        agents: wealth.len(),
+        total_wealth: total(&wealth),
+        gini: gini(&wealth),
+        percentiles: PERCENTILES
+            .iter()
+            .map(|p| (*p, percentile(&wealth, *p)))
+            .collect(),
+        resources,
+    }
+}
+
+/// Each agent's net holdings of each resource after `tick`, clamped at
+/// zero.
+fn agent_holdings(entries: &[LedgerEntry], tick: u64) -> BTreeMap<Uuid, Holdings> {
+    let mut holdings: BTreeMap<Uuid, Holdings> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.tick <= tick) {
+        if let (Some(agent), Some(EntityType::Agent)) = (entry.to_entity, entry.to_entity_type) {
+            let held = holdings.entry(agent).or_default().entry(entry.resource).or_default();
+            *held = held.saturating_add(entry.quantity);
+        }
+        if let (Some(agent), Some(EntityType::Agent)) =
+            (entry.from_entity, entry.from_entity_type)
+        {
+            let held = holdings.entry(agent).or_default().entry(entry.resource).or_default();
+            *held = held.saturating_sub(entry.quantity);
+        }
+    }
+    for held in holdings.values_mut() {
+        for quantity in held.values_mut() {
+            *quantity = (*quantity).max(Decimal::ZERO);
+        }
+    }
+    holdings
+}
+
+/// The concentration of a resource held in `sorted` quantities.
+fn concentration(sorted: &[Decimal]) -> ResourceConcentration {
+    let total_held = total(sorted);
+    let top = sorted.len().div_ceil(10);
+    let top_held = total(sorted.get(sorted.len().saturating_sub(top)..).unwrap_or_default());
+    ResourceConcentration {
+        total: total_held,
+        holders: sorted.iter().filter(|q| **q > Decimal::ZERO).count(),
+        gini: gini(sorted),
+        top_decile_share: top_held.checked_div(total_held).unwrap_or(Decimal::ZERO),
+    }
+}
+
+/// The Gini coefficient of `sorted` values, in ascending order.
+///
+/// Returns zero for an empty population or one holding nothing.
+fn gini(sorted: &[Decimal]) -> Decimal {
+    let sum = total(sorted);
+    let n = Decimal::from(sorted.len());
+    let Some(denominator) = n.checked_mul(sum).filter(|d| !d.is_zero()) else {
+        return Decimal::ZERO;
+    };
+    let ranked = sorted
+        .iter()
+        .zip(1_u64..)
+        .fold(Decimal::ZERO, |acc, (x, rank)| {
+            acc.saturating_add(x.saturating_mul(Decimal::from(rank)))
+        });
+    let spread = Decimal::TWO
+        .saturating_mul(ranked)
+        .checked_div(denominator)
+        .unwrap_or(Decimal::ZERO);
+    let offset = n.saturating_add(Decimal::ONE).checked_div(n).unwrap_or(Decimal::ZERO);
+    spread.saturating_sub(offset).max(Decimal::ZERO)
+}
+
+/// The `p`th percentile of `sorted` values by the nearest-rank method.
+fn percentile(sorted: &[Decimal], p: u8) -> Decimal {
+    // Nearest rank: ceil(p / 100 * n), as a 1-based index.
+    let rank = usize::from(p).saturating_mul(sorted.len()).div_ceil(100);
+    rank.checked_sub(1)
+        .and_then(|i| sorted.get(i))
+        .or_else(|| sorted.first())
+        .copied()
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// The sum of `values`.
+fn total(values: &[Decimal]) -> Decimal {
+    values.iter().fold(Decimal::ZERO, |sum, v| sum.saturating_add(*v))
+}
+
+#[cfg(test)]
+mod tests {
+    use emergence_types::LedgerEntryType;
+
+    use super::*;
+    use crate::TransactionBuilder;
+
+    fn gather(tick: u64, resource: Resource, quantity: i64, agent: Uuid) -> Option<LedgerEntry> {
+        TransactionBuilder::new(tick, LedgerEntryType::Gather, resource)
+            .from(Uuid::now_v7(), EntityType::Location)
+            .to(agent, EntityType::Agent)
+            .quantity(Decimal::new(quantity, 0))
+            .reason("GATHER".to_owned())
+            .build()
+            .ok()
+    }
+
+    #[test]
+    fn gini_spans_equality_to_concentration() {
+        let equal = [Decimal::new(5, 0); 4];
+        assert_eq!(gini(&equal), Decimal::ZERO);
+        assert_eq!(gini(&[]), Decimal::ZERO);
+        assert_eq!(gini(&[Decimal::ZERO; 3]), Decimal::ZERO);
+
+        // One of four agents holds everything: G = (n - 1) / n.
+        let hoarded = [Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::new(12, 0)];
+        assert_eq!(gini(&hoarded), Decimal::new(75, 2));
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let wealth: Vec<Decimal> = (1..=10).map(Decimal::from).collect();
+        assert_eq!(percentile(&wealth, 10), Decimal::ONE);
+        assert_eq!(percentile(&wealth, 50), Decimal::from(5));
+        assert_eq!(percentile(&wealth, 90), Decimal::from(9));
+        assert_eq!(percentile(&[], 50), Decimal::ZERO);
+    }
+
+    #[test]
+    fn distribution_is_taken_from_agent_holdings_at_the_tick() {
+        let (rich, poor) = (Uuid::now_v7(), Uuid::now_v7());
+        let entries: Vec<LedgerEntry> = [
+            gather(1, Resource::Wood, 9, rich),
+            gather(1, Resource::Wood, 1, poor),
+            gather(2, Resource::Stone, 6, rich),
+            gather(5, Resource::Stone, 100, poor),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let distribution = wealth_distribution(&entries, 2);
+        assert_eq!(distribution.agents, 2);
+        assert_eq!(distribution.total_wealth, Decimal::from(16));
+        assert_eq!(distribution.percentiles.get(&50), Some(&Decimal::ONE));
+
+        let stone = distribution.resources.get(&Resource::Stone);
+        assert_eq!(stone.map(|s| (s.holders, s.top_decile_share)), Some((1, Decimal::ONE)));
+        let wood = distribution.resources.get(&Resource::Wood);
+        assert_eq!(wood.map(|w| w.top_decile_share), Some(Decimal::new(9, 1)));
+    }
+}
@@ -0,0 +1,158 @@
+//! Point-in-time balances folded from the ledger.
+//!
+//! A [`BalanceIndex`] keeps, for every entity and resource, the entity's
+//! running balance after each tick it was credited or debited in. The
+//! [`Ledger`](crate::Ledger) updates it as entries are appended, so asking
+//! for a balance at a tick is a binary search over that entity's history
+//! instead of a scan of the whole log.
+//!
+//! Credits (the entity is the `to_entity`) add to the balance and debits
+//! (the entity is the `from_entity`) subtract from it, so a location that
+//! has been gathered from more than regenerated holds a negative balance.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use emergence_types::{LedgerEntry, Resource};
+
+/// An entity's running balance of one resource: the balance after each
+/// tick it changed in, in tick order.
+type History = Vec<(u64, Decimal)>;
+
+/// Running balances per entity and resource.
+#[derive(Debug, Default)]
+pub struct BalanceIndex {
+    histories: BTreeMap<Uuid, BTreeMap<Resource, History>>,
+}
+
+impl BalanceIndex {
+    /// Create an empty index.
+    pub const fn new() -> Self {
+        Self {
+            histories: BTreeMap::new(),
+        }
+    }
+
+    /// Fold `entry` into the balances of its source and destination.
+    ///
+    /// Entries may arrive out of tick order; a late entry also shifts the
+    /// balances recorded for every later tick.
+    pub fn record(&mut self, entry: &LedgerEntry) {
+        if let Some(to) = entry.to_entity {
+            self.apply(to, entry.resource, entry.tick, entry.quantity);
+        }
+        if let Some(from) = entry.from_entity {
+            let debit = Decimal::ZERO.saturating_sub(entry.quantity);
+            self.apply(from, entry.resource, entry.tick, debit);
+        }
+    }
+
+    /// `entity_id`'s balance of `resource` after `tick`.
+    pub fn balance(&self, entity_id: Uuid, resource: Resource, tick: u64) -> Decimal {
+        self.histories
+            .get(&entity_id)
+            .and_then(|resources| resources.get(&resource))
+            .map_or(Decimal::ZERO, |history| balance_at(history, tick))
+    }
+
+    /// `entity_id`'s balance of every resource it had been credited or
+    /// debited by `tick`, including those it no longer holds.
+    pub fn balances(&self, entity_id: Uuid, tick: u64) -> BTreeMap<Resource, Decimal> {
+        self.histories
+            .get(&entity_id)
+            .into_iter()
+            .flatten()
+            .filter(|(_, history)| history.first().is_some_and(|(first, _)| *first <= tick))
+            .map(|(resource, history)| (*resource, balance_at(history, tick)))
+            .collect()
+    }
+
+    /// Add `delta` to `entity_id`'s balance of `resource` from `tick` on.
+    fn apply(&mut self, entity_id: Uuid, resource: Resource, tick: u64, delta: Decimal) {
+        let history = self
+            .histories
+            .entry(entity_id)
+            .or_default()
+            .entry(resource)
+            .or_default();
+        let at = history.partition_point(|(t, _)| *t < tick);
+        if history.get(at).is_none_or(|(t, _)| *t != tick) {
+            history.insert(at, (tick, balance_at(history, tick)));
+        }
+        for (_, balance) in history.iter_mut().skip(at) {
+            *balance = balance.saturating_add(delta);
+        }
+    }
+}
+
+/// The balance in `history` after `tick`.
+fn balance_at(history: &[(u64, Decimal)], tick: u64) -> Decimal {
+    let after = history.partition_point(|(t, _)| *t <= tick);
+    after
+        .checked_sub(1)
+        .and_then(|i| history.get(i))
+        .map_or(Decimal::ZERO, |(_, balance)| *balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use emergence_types::{EntityType, LedgerEntryType};
+
+    use super::*;
+    use crate::TransactionBuilder;
+
+    fn gather(tick: u64, quantity: i64, location: Uuid, agent: Uuid) -> Option<LedgerEntry> {
+        TransactionBuilder::new(tick, LedgerEntryType::Gather, Resource::Wood)
+            .from(location, EntityType::Location)
+            .to(agent, EntityType::Agent)
+            .quantity(Decimal::new(quantity, 0))
+            .reason("GATHER".to_owned())
+            .build()
+            .ok()
+    }
+
+    #[test]
+    fn balances_are_taken_at_the_tick() {
+        let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let mut index = BalanceIndex::new();
+        let entries = [gather(5, 4, location, agent), gather(9, 3, location, agent)];
+        for entry in entries.iter().flatten() {
+            index.record(entry);
+        }
+
+        assert_eq!(index.balance(agent, Resource::Wood, 4), Decimal::ZERO);
+        assert_eq!(index.balance(agent, Resource::Wood, 5), Decimal::new(4, 0));
+        assert_eq!(index.balance(agent, Resource::Wood, 8), Decimal::new(4, 0));
+        assert_eq!(index.balance(agent, Resource::Wood, 9), Decimal::new(7, 0));
+        assert_eq!(index.balance(location, Resource::Wood, 100), Decimal::new(-7, 0));
+        assert_eq!(index.balance(agent, Resource::Stone, 100), Decimal::ZERO);
+
+        assert!(index.balances(agent, 4).is_empty());
+        let wood = BTreeMap::from([(Resource::Wood, Decimal::new(7, 0))]);
+        assert_eq!(index.balances(agent, 9), wood);
+    }
+
+    #[test]
+    fn late_entries_shift_later_balances() {
+        let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let mut index = BalanceIndex::new();
+        for entry in [
+            gather(9, 3, location, agent),
+            gather(2, 1, location, agent),
+            gather(9, 2, location, agent),
+            gather(5, 4, location, agent),
+        ]
+        .iter()
+        .flatten()
+        {
+            index.record(entry);
+        }
+
+        assert_eq!(index.balance(agent, Resource::Wood, 1), Decimal::ZERO);
+        assert_eq!(index.balance(agent, Resource::Wood, 2), Decimal::new(1, 0));
+        assert_eq!(index.balance(agent, Resource::Wood, 5), Decimal::new(5, 0));
+        assert_eq!(index.balance(agent, Resource::Wood, 9), Decimal::new(10, 0));
+    }
+}
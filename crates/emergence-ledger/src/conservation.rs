@@ -3,7 +3,8 @@
 //! The conservation law enforces that internal resource movements always
 //! balance: every debit from one entity must match a credit to another.
 //! Resources enter the simulation via `Regeneration` and leave via
-//! `Consume` or `Decay` -- these are source/sink flows that do not need
+//! `Consume` or `Decay`, and currencies enter via `Mint` and leave via
+//! `Burn` -- these are source/sink flows that do not need
 //! to balance within a single tick.
 //!
 //! For each resource R in tick T, the check is:
@@ -13,18 +14,32 @@
 //! ```
 //!
 //! Internal entry types: `Gather`, `Transfer`, `Build`, `Salvage`, `Drop`,
-//! `Pickup`. Each entry adds its quantity to both the credit and debit
-//! side equally, so this check is guaranteed by construction -- it exists
-//! as defense-in-depth against data corruption or future bugs.
+//! `Pickup`, `Deposit`, `Withdrawal`, `Checkpoint`, `Tax`, `Tribute`,
+//! `LoanIssued`, `LoanRepayment`, `InterestAccrued`, `Escrow`,
+//! `EscrowRelease`, `Correction`. An internal entry credits its quantity if
+//! it has a destination and debits it if it has a source, so a well-formed
+//! entry adds to both sides equally and the check holds by construction --
+//! it exists as defense-in-depth against data corruption or future bugs.
+//!
+//! A `Reversal` undoes an earlier entry by swapping its sides, and is netted
+//! against that original in the original's tick rather than counted in its
+//! own: a reversed internal entry leaves its tick's totals as if it had
+//! never been recorded, and a reversed source or sink flow leaves its
+//! tick's inflow or outflow. A reversal whose original has been pruned is
+//! not counted, since the checkpoints already carry the reversed balances.
+//!
+//! A [`ConservationTracker`] keeps these totals as entries are recorded,
+//! so a tick's check needs no scan and an entry that breaks the balance is
+//! reported, with its ID, the moment it is recorded.
 //!
 //! A violation produces a [`LedgerAnomaly`] -- the simulation's most
 //! critical integrity alert.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeMap;
 
 use rust_decimal::Decimal;
 
-use emergence_types::{LedgerEntry, LedgerEntryType, Resource};
+use emergence_types::{LedgerEntry, LedgerEntryId, LedgerEntryType, Resource};
 
 use crate::LedgerAnomaly;
 
@@ -42,7 +57,7 @@ pub enum ConservationResult {
 /// Internal movements transfer resources between entities without creating
 /// or destroying them. Every internal entry must have matching credit and
 /// debit quantities.
-const fn is_internal(entry_type: LedgerEntryType) -> bool {
+pub(crate) const fn is_internal(entry_type: LedgerEntryType) -> bool {
     matches!(
         entry_type,
         LedgerEntryType::Gather
@@ -51,9 +66,33 @@ const fn is_internal(entry_type: LedgerEntryType) -> bool {
             | LedgerEntryType::Salvage
             | LedgerEntryType::Drop
             | LedgerEntryType::Pickup
+            | LedgerEntryType::Deposit
+            | LedgerEntryType::Withdrawal
+            | LedgerEntryType::Checkpoint
+            | LedgerEntryType::Tax
+            | LedgerEntryType::Tribute
+            | LedgerEntryType::LoanIssued
+            | LedgerEntryType::LoanRepayment
+            | LedgerEntryType::InterestAccrued
+            | LedgerEntryType::Escrow
+            | LedgerEntryType::EscrowRelease
+            | LedgerEntryType::Correction
     )
 }
 
+/// Returns the entry `entry` reverses, if it is a reversal of an entry in
+/// `entries`.
+pub(crate) fn original_of<'a>(
+    entry: &LedgerEntry,
+    entries: &'a [LedgerEntry],
+) -> Option<&'a LedgerEntry> {
+    if entry.entry_type != LedgerEntryType::Reversal {
+        return None;
+    }
+    let original = entry.reference_id?;
+    entries.iter().find(|e| e.id.into_inner() == original)
+}
+
 /// Verify the conservation law for all entries in a single tick.
 ///
 /// Checks that internal resource movements (Gather, Transfer, Build,
@@ -65,88 +104,187 @@ const fn is_internal(entry_type: LedgerEntryType) -> bool {
 /// Each well-formed internal entry adds its quantity to both the credit
 /// and debit accumulators equally, so this check passes by construction
 /// for valid entries. It exists as defense-in-depth against corruption.
+///
+/// This scans `entries`; the [`Ledger`](crate::Ledger) answers the same
+/// question from its [`ConservationTracker`] instead.
 pub fn verify_conservation(tick: u64, entries: &[LedgerEntry]) -> ConservationResult {
-    // Per-resource accumulators for internal movements only.
-    let mut internal_credit: BTreeMap<Resource, Decimal> = BTreeMap::new();
-    let mut internal_debit: BTreeMap<Resource, Decimal> = BTreeMap::new();
-
-    for entry in entries {
-        if entry.tick != tick {
-            continue;
-        }
-
-        if is_internal(entry.entry_type) {
-            // Credit side: to_entity receives the resource.
-            let c = internal_credit
-                .entry(entry.resource)
-                .or_insert(Decimal::ZERO);
-            *c = match c.checked_add(entry.quantity) {
-                Some(val) => val,
-                None => return overflow_anomaly(tick, entry.resource),
-            };
-
-            // Debit side: from_entity loses the resource.
-            let d = internal_debit
-                .entry(entry.resource)
-                .or_insert(Decimal::ZERO);
-            *d = match d.checked_add(entry.quantity) {
-                Some(val) => val,
-                None => return overflow_anomaly(tick, entry.resource),
-            };
-        }
-        // Regeneration, Consume, and Decay are source/sink flows.
-        // They do not participate in the internal balance check.
+    let mut tracker = ConservationTracker::new();
+    for entry in entries
+        .iter()
+        .filter(|e| e.tick == tick || e.entry_type == LedgerEntryType::Reversal)
+    {
+        tracker.record_among(entry, entries);
     }
+    tracker.verify(tick)
+}
 
-    // Collect all resource keys from both maps.
-    let all_resources: BTreeSet<Resource> = internal_credit
-        .keys()
-        .chain(internal_debit.keys())
-        .copied()
-        .collect();
+/// Running internal credit and debit totals for one tick.
+#[derive(Debug, Default)]
+struct TickTotals {
+    /// Per-resource (debit, credit) totals.
+    totals: BTreeMap<Resource, (Decimal, Decimal)>,
+    /// The first entry that broke the balance, if any.
+    offending: Option<LedgerEntryId>,
+    /// The resource whose totals overflowed, if any.
+    overflowed: Option<Resource>,
+}
 
-    let mut imbalances: BTreeMap<Resource, (Decimal, Decimal)> = BTreeMap::new();
+/// Incremental conservation check over recorded entries.
+///
+/// Maintains per-tick, per-resource internal credit and debit totals as
+/// entries are recorded, so [`ConservationTracker::verify`] reads the
+/// totals instead of scanning the tick's entries.
+#[derive(Debug, Default)]
+pub struct ConservationTracker {
+    ticks: BTreeMap<u64, TickTotals>,
+}
 
-    for resource in &all_resources {
-        let total_credit = internal_credit
-            .get(resource)
-            .copied()
-            .unwrap_or(Decimal::ZERO);
-        let total_debit = internal_debit
-            .get(resource)
-            .copied()
-            .unwrap_or(Decimal::ZERO);
-
-        if total_credit != total_debit {
-            imbalances.insert(*resource, (total_debit, total_credit));
+impl ConservationTracker {
+    /// Create a tracker with no recorded entries.
+    pub const fn new() -> Self {
+        Self {
+            ticks: BTreeMap::new(),
         }
     }
 
-    if imbalances.is_empty() {
-        ConservationResult::Balanced
-    } else {
-        let count = imbalances.len();
-        ConservationResult::Anomaly(LedgerAnomaly {
-            tick,
-            imbalances,
+    /// Add `entry` to its tick's totals.
+    ///
+    /// Returns an anomaly naming the entry if it debits and credits
+    /// different quantities, unless it is a correction, or if adding it
+    /// overflows the totals.
+    /// Source and sink flows are not tracked.
+    pub fn record(&mut self, entry: &LedgerEntry) -> Option<LedgerAnomaly> {
+        if !is_internal(entry.entry_type) {
+            return None;
+        }
+        // Credit side: to_entity receives the resource. Debit side:
+        // from_entity loses the resource.
+        let debit = entry.from_entity.map_or(Decimal::ZERO, |_| entry.quantity);
+        let credit = entry.to_entity.map_or(Decimal::ZERO, |_| entry.quantity);
+
+        let tick = self.ticks.entry(entry.tick).or_default();
+        let (total_debit, total_credit) = tick.totals.entry(entry.resource).or_default();
+        let (Some(new_debit), Some(new_credit)) =
+            (total_debit.checked_add(debit), total_credit.checked_add(credit))
+        else {
+            tick.overflowed.get_or_insert(entry.resource);
+            tick.offending.get_or_insert(entry.id);
+            return Some(overflow_anomaly(entry.tick, entry.resource, Some(entry.id)));
+        };
+        (*total_debit, *total_credit) = (new_debit, new_credit);
+
+        // A correction is one-sided by design: it repairs the side another
+        // entry left unmatched.
+        if debit == credit || entry.entry_type == LedgerEntryType::Correction {
+            return None;
+        }
+        tick.offending.get_or_insert(entry.id);
+        Some(LedgerAnomaly {
+            tick: entry.tick,
+            imbalances: BTreeMap::from([(entry.resource, (debit, credit))]),
+            entry_id: Some(entry.id),
             message: format!(
-                "LEDGER_ANOMALY at tick {tick}: conservation law violated for {count} resource(s)",
+                "LEDGER_ANOMALY at tick {}: entry {} debits {debit} but credits {credit} {:?}",
+                entry.tick, entry.id, entry.resource,
             ),
         })
     }
+
+    /// Add `entry` to the totals, netting it against its original if it is a
+    /// reversal of one of the `recorded` entries.
+    pub fn record_among(
+        &mut self,
+        entry: &LedgerEntry,
+        recorded: &[LedgerEntry],
+    ) -> Option<LedgerAnomaly> {
+        match original_of(entry, recorded) {
+            Some(original) => self.record_reversal(entry, original),
+            None => self.record(entry),
+        }
+    }
+
+    /// Remove `original`'s contribution from its tick's totals, as undone
+    /// by `reversal`.
+    ///
+    /// The reversal's credit is the original's debit and its debit the
+    /// original's credit. Returns an anomaly if removing it overflows the
+    /// totals. Reversed source and sink flows were never tracked.
+    pub fn record_reversal(
+        &mut self,
+        reversal: &LedgerEntry,
+        original: &LedgerEntry,
+    ) -> Option<LedgerAnomaly> {
+        if !is_internal(original.entry_type) {
+            return None;
+        }
+        let debit = reversal.to_entity.map_or(Decimal::ZERO, |_| reversal.quantity);
+        let credit = reversal.from_entity.map_or(Decimal::ZERO, |_| reversal.quantity);
+
+        let tick = self.ticks.entry(original.tick).or_default();
+        let (total_debit, total_credit) = tick.totals.entry(reversal.resource).or_default();
+        let (Some(new_debit), Some(new_credit)) =
+            (total_debit.checked_sub(debit), total_credit.checked_sub(credit))
+        else {
+            tick.overflowed.get_or_insert(reversal.resource);
+            tick.offending.get_or_insert(reversal.id);
+            return Some(overflow_anomaly(original.tick, reversal.resource, Some(reversal.id)));
+        };
+        (*total_debit, *total_credit) = (new_debit, new_credit);
+        None
+    }
+
+    /// Check the totals recorded for `tick`.
+    ///
+    /// An anomaly names the first entry recorded for the tick that broke
+    /// the balance.
+    pub fn verify(&self, tick: u64) -> ConservationResult {
+        let Some(totals) = self.ticks.get(&tick) else {
+            return ConservationResult::Balanced;
+        };
+        if let Some(resource) = totals.overflowed {
+            let anomaly = overflow_anomaly(tick, resource, totals.offending);
+            return ConservationResult::Anomaly(anomaly);
+        }
+
+        let imbalances: BTreeMap<Resource, (Decimal, Decimal)> = totals
+            .totals
+            .iter()
+            .filter(|(_, (debit, credit))| debit != credit)
+            .map(|(resource, totals)| (*resource, *totals))
+            .collect();
+        if imbalances.is_empty() {
+            ConservationResult::Balanced
+        } else {
+            let count = imbalances.len();
+            ConservationResult::Anomaly(LedgerAnomaly {
+                tick,
+                imbalances,
+                entry_id: totals.offending,
+                message: format!(
+                    "LEDGER_ANOMALY at tick {tick}: conservation law violated for \
+                     {count} resource(s)",
+                ),
+            })
+        }
+    }
 }
 
-/// Construct an anomaly result for arithmetic overflow during summation.
-fn overflow_anomaly(tick: u64, resource: Resource) -> ConservationResult {
+/// Construct an anomaly for arithmetic overflow during summation.
+fn overflow_anomaly(
+    tick: u64,
+    resource: Resource,
+    entry_id: Option<LedgerEntryId>,
+) -> LedgerAnomaly {
     let mut imbalances = BTreeMap::new();
     imbalances.insert(resource, (Decimal::ZERO, Decimal::ZERO));
-    ConservationResult::Anomaly(LedgerAnomaly {
+    LedgerAnomaly {
         tick,
         imbalances,
+        entry_id,
         message: format!(
             "LEDGER_ANOMALY at tick {tick}: arithmetic overflow while summing {resource:?}",
         ),
-    })
+    }
 }
 
 /// Verify conservation with additional flow-direction checks.
@@ -155,6 +293,8 @@ fn overflow_anomaly(tick: u64, resource: Resource) -> ConservationResult {
 /// and then validates that source/sink flows have non-negative totals
 /// (no negative regeneration or negative consumption). This is a stricter
 /// form for callers who want to verify flow semantics beyond balance.
+///
+/// A reversed source or sink flow is netted out of its original's tick.
 pub fn verify_conservation_strict(tick: u64, entries: &[LedgerEntry]) -> ConservationResult {
     // First, run the standard internal balance check.
     let result = verify_conservation(tick, entries);
@@ -167,24 +307,38 @@ pub fn verify_conservation_strict(tick: u64, entries: &[LedgerEntry]) -> Conserv
     let mut outflow: BTreeMap<Resource, Decimal> = BTreeMap::new();
 
     for entry in entries {
-        if entry.tick != tick {
+        let (counted, reversed) =
+            original_of(entry, entries).map_or((entry, false), |original| (original, true));
+        if counted.tick != tick {
             continue;
         }
+        let net = |v: Decimal| {
+            if reversed {
+                v.checked_sub(entry.quantity)
+            } else {
+                v.checked_add(entry.quantity)
+            }
+        };
 
-        match entry.entry_type {
-            LedgerEntryType::Regeneration => {
+        match counted.entry_type {
+            LedgerEntryType::Regeneration | LedgerEntryType::Mint | LedgerEntryType::Breeding => {
                 let v = inflow.entry(entry.resource).or_insert(Decimal::ZERO);
-                *v = match v.checked_add(entry.quantity) {
-                    Some(val) => val,
-                    None => return overflow_anomaly(tick, entry.resource),
+                let Some(total) = net(*v) else {
+                    let anomaly = overflow_anomaly(tick, entry.resource, Some(entry.id));
+                    return ConservationResult::Anomaly(anomaly);
                 };
+                *v = total;
             }
-            LedgerEntryType::Consume | LedgerEntryType::Decay => {
+            LedgerEntryType::Consume
+            | LedgerEntryType::Decay
+            | LedgerEntryType::Burn
+            | LedgerEntryType::Slaughter => {
                 let v = outflow.entry(entry.resource).or_insert(Decimal::ZERO);
-                *v = match v.checked_add(entry.quantity) {
-                    Some(val) => val,
-                    None => return overflow_anomaly(tick, entry.resource),
+                let Some(total) = net(*v) else {
+                    let anomaly = overflow_anomaly(tick, entry.resource, Some(entry.id));
+                    return ConservationResult::Anomaly(anomaly);
                 };
+                *v = total;
             }
             LedgerEntryType::Gather
             | LedgerEntryType::Transfer
@@ -193,12 +347,27 @@ pub fn verify_conservation_strict(tick: u64, entries: &[LedgerEntry]) -> Conserv
             | LedgerEntryType::Drop
             | LedgerEntryType::Pickup
             | LedgerEntryType::Theft
-            | LedgerEntryType::CombatLoot => {}
+            | LedgerEntryType::CombatLoot
+            | LedgerEntryType::Deposit
+            | LedgerEntryType::Withdrawal
+            | LedgerEntryType::Checkpoint
+            | LedgerEntryType::Tax
+            | LedgerEntryType::Tribute
+            | LedgerEntryType::LoanIssued
+            | LedgerEntryType::LoanRepayment
+            | LedgerEntryType::InterestAccrued
+            | LedgerEntryType::Escrow
+            | LedgerEntryType::EscrowRelease
+            | LedgerEntryType::EnergySpent
+            | LedgerEntryType::EnergyRecovered
+            | LedgerEntryType::Correction
+            | LedgerEntryType::Reversal => {}
         }
     }
 
-    // Check for negative totals (should be impossible with positive-only
-    // quantities, but defense-in-depth).
+    // Check for negative totals (impossible with positive-only quantities
+    // unless a flow was reversed by more than it moved, but
+    // defense-in-depth).
     let mut imbalances: BTreeMap<Resource, (Decimal, Decimal)> = BTreeMap::new();
 
     for (resource, total) in &inflow {
@@ -221,6 +390,7 @@ pub fn verify_conservation_strict(tick: u64, entries: &[LedgerEntry]) -> Conserv
         ConservationResult::Anomaly(LedgerAnomaly {
             tick,
             imbalances,
+            entry_id: None,
             message: format!(
                 "LEDGER_ANOMALY at tick {tick}: negative flow detected for {count} resource(s)",
             ),
@@ -233,7 +403,7 @@ mod tests {
     use chrono::Utc;
     use uuid::Uuid;
 
-    use emergence_types::{EntityType, LedgerEntryId};
+    use emergence_types::EntityType;
 
     use super::*;
 
@@ -258,6 +428,7 @@ mod tests {
             quantity,
             reason: format!("{entry_type:?}"),
             reference_id: None,
+            tags: Vec::new(),
             created_at: Utc::now(),
         }
     }
@@ -481,6 +652,7 @@ mod tests {
         let anomaly = LedgerAnomaly {
             tick: 42,
             imbalances,
+            entry_id: None,
             message: "LEDGER_ANOMALY at tick 42: test".to_owned(),
         };
 
@@ -499,6 +671,7 @@ mod tests {
         let anomaly = LedgerAnomaly {
             tick: 5,
             imbalances: BTreeMap::new(),
+            entry_id: None,
             message: "LEDGER_ANOMALY at tick 5: test display".to_owned(),
         };
         let display = format!("{anomaly}");
@@ -513,6 +686,7 @@ mod tests {
         let anomaly = ConservationResult::Anomaly(LedgerAnomaly {
             tick: 1,
             imbalances: BTreeMap::new(),
+            entry_id: None,
             message: "test".to_owned(),
         });
 
@@ -520,6 +694,47 @@ mod tests {
         assert_ne!(balanced, anomaly);
     }
 
+    #[test]
+    fn tracker_reports_the_offending_entry_when_it_lands() {
+        let mut tracker = ConservationTracker::new();
+        let gather = make_entry(
+            3,
+            LedgerEntryType::Gather,
+            Resource::Wood,
+            Decimal::new(4, 0),
+            EntityType::Location,
+            EntityType::Agent,
+        );
+        assert!(tracker.record(&gather).is_none());
+
+        // A transfer that credits an agent without debiting anyone.
+        let mut forged = make_entry(
+            3,
+            LedgerEntryType::Transfer,
+            Resource::Wood,
+            Decimal::new(2, 0),
+            EntityType::Agent,
+            EntityType::Agent,
+        );
+        forged.from_entity = None;
+        let landed = tracker.record(&forged);
+        assert_eq!(landed.as_ref().and_then(|a| a.entry_id), Some(forged.id));
+        assert!(landed.is_some_and(|a| a.message.contains(&forged.id.to_string())));
+
+        let result = tracker.verify(3);
+        let anomaly = match &result {
+            ConservationResult::Anomaly(anomaly) => Some(anomaly),
+            ConservationResult::Balanced => None,
+        };
+        assert_eq!(anomaly.and_then(|a| a.entry_id), Some(forged.id));
+        assert_eq!(
+            anomaly.and_then(|a| a.imbalances.get(&Resource::Wood)),
+            Some(&(Decimal::new(4, 0), Decimal::new(6, 0)))
+        );
+        assert_eq!(tracker.verify(4), ConservationResult::Balanced);
+        assert_eq!(verify_conservation(3, &[gather, forged]), result);
+    }
+
     #[test]
     fn multi_resource_all_balanced() {
         // Multiple resources, each with internal movements that balance.
@@ -566,5 +781,38 @@ mod tests {
         assert!(!is_internal(LedgerEntryType::Regeneration));
         assert!(!is_internal(LedgerEntryType::Consume));
         assert!(!is_internal(LedgerEntryType::Decay));
+        assert!(!is_internal(LedgerEntryType::Reversal));
+    }
+
+    #[test]
+    fn reversal_nets_against_its_original_tick() {
+        // A gather whose location side was lost, reversed two ticks later.
+        let mut original = make_entry(
+            1,
+            LedgerEntryType::Gather,
+            Resource::Wood,
+            Decimal::new(4, 0),
+            EntityType::Location,
+            EntityType::Agent,
+        );
+        (original.from_entity, original.from_entity_type) = (None, None);
+        let reversal = LedgerEntry {
+            id: LedgerEntryId::new(),
+            tick: 3,
+            entry_type: LedgerEntryType::Reversal,
+            from_entity: original.to_entity,
+            from_entity_type: original.to_entity_type,
+            to_entity: None,
+            to_entity_type: None,
+            reference_id: Some(original.id.into_inner()),
+            tags: Vec::new(),
+            ..original.clone()
+        };
+
+        let unreversed = [original.clone()];
+        assert!(matches!(verify_conservation(1, &unreversed), ConservationResult::Anomaly(_)));
+        let entries = [original, reversal];
+        assert_eq!(verify_conservation(1, &entries), ConservationResult::Balanced);
+        assert_eq!(verify_conservation(3, &entries), ConservationResult::Balanced);
     }
 }
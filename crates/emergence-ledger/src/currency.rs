@@ -0,0 +1,163 @@
+//! Currency resources and their money supply.
+//!
+//! Any resource can come to be used as money; the
+//! `EconomicDetector` in `emergence-agents` notices when one dominates
+//! trade. Once a resource is adopted as a currency, the ledger accounts
+//! for it as money:
+//!
+//! | Type | From (debit) | To (credit) |
+//! |------|-------------|-------------|
+//! | Mint | World | Agent |
+//! | Burn | Agent | Void |
+//!
+//! A currency enters the simulation only by `Mint` and leaves it only by
+//! `Burn`, so regenerating, consuming, or decaying it is rejected, and
+//! minting or burning a resource that is not a currency is rejected too.
+//! Between the two, a currency moves by the same internal entries as any
+//! physical resource and is held to the same conservation law. The money
+//! supply -- minted less burned -- is therefore exactly what the currency's
+//! holders hold, less whatever they already held when it was adopted.
+//!
+//! A [`CurrencyRegistry`] tracks the adopted currencies and their supply as
+//! entries are recorded, so the supply survives pruning.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rust_decimal::Decimal;
+
+use emergence_types::{LedgerEntry, LedgerEntryType, Resource};
+
+use crate::LedgerError;
+
+/// The adopted currencies and each one's money supply.
+#[derive(Debug, Default)]
+pub struct CurrencyRegistry {
+    adopted: BTreeSet<Resource>,
+    supply: BTreeMap<Resource, Decimal>,
+}
+
+impl CurrencyRegistry {
+    /// Create a registry with no currencies.
+    pub const fn new() -> Self {
+        Self {
+            adopted: BTreeSet::new(),
+            supply: BTreeMap::new(),
+        }
+    }
+
+    /// Adopt `resource` as a currency. Returns `false` if it already was one.
+    pub fn adopt(&mut self, resource: Resource) -> bool {
+        self.adopted.insert(resource)
+    }
+
+    /// Return whether `resource` has been adopted as a currency.
+    pub fn is_currency(&self, resource: Resource) -> bool {
+        self.adopted.contains(&resource)
+    }
+
+    /// Return the adopted currencies.
+    pub fn currencies(&self) -> impl Iterator<Item = Resource> + '_ {
+        self.adopted.iter().copied()
+    }
+
+    /// Check `entry` against the currency rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::NotACurrency`] if it mints or burns a resource
+    /// that is not a currency, and [`LedgerError::CurrencySourceOrSink`] if
+    /// it creates or destroys a currency other than by minting or burning.
+    pub fn validate(&self, entry: &LedgerEntry) -> Result<(), LedgerError> {
+        let currency = self.is_currency(entry.resource);
+        match entry.entry_type {
+            LedgerEntryType::Mint | LedgerEntryType::Burn if !currency => {
+                Err(LedgerError::NotACurrency(entry.resource))
+            }
+            LedgerEntryType::Regeneration | LedgerEntryType::Consume | LedgerEntryType::Decay
+                if currency =>
+            {
+                Err(LedgerError::CurrencySourceOrSink {
+                    entry_type: entry.entry_type,
+                    resource: entry.resource,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fold `entry` into the money supply. `original` is the entry it
+    /// reverses, if it is a reversal, so a reversed mint or burn is undone.
+    pub fn record(&mut self, entry: &LedgerEntry, original: Option<&LedgerEntry>) {
+        let (counted, reversed) = original.map_or((entry, false), |original| (original, true));
+        let minted = match counted.entry_type {
+            LedgerEntryType::Mint => true,
+            LedgerEntryType::Burn => false,
+            _ => return,
+        };
+        let supply = self.supply.entry(entry.resource).or_default();
+        *supply = if minted == reversed {
+            supply.saturating_sub(entry.quantity)
+        } else {
+            supply.saturating_add(entry.quantity)
+        };
+    }
+
+    /// Return the money supply of `currency`: everything minted, less
+    /// everything burned.
+    pub fn supply(&self, currency: Resource) -> Decimal {
+        self.supply.get(&currency).copied().unwrap_or_default()
+    }
+
+    /// Adopt `currency` with the money supply `supply`, as when restoring
+    /// a ledger from a snapshot.
+    pub fn restore(&mut self, currency: Resource, supply: Decimal) {
+        self.adopted.insert(currency);
+        self.supply.insert(currency, supply);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::Ledger;
+
+    #[test]
+    fn only_currencies_are_minted_and_burned() {
+        let (world, agent, void) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let token = Resource::CurrencyToken;
+        let mut ledger = Ledger::new();
+        assert!(matches!(
+            ledger.record_mint(1, token, Decimal::TEN, world, agent),
+            Err(LedgerError::NotACurrency(Resource::CurrencyToken))
+        ));
+        let _ = ledger.record_regeneration(1, token, Decimal::ONE, world, Uuid::now_v7());
+
+        assert!(ledger.adopt_currency(token));
+        assert!(!ledger.adopt_currency(token));
+        assert!(ledger.record_mint(2, token, Decimal::TEN, world, agent).is_ok());
+        assert!(ledger.record_burn(3, token, Decimal::new(4, 0), agent, void).is_ok());
+        assert!(matches!(
+            ledger.record_consumption(3, token, Decimal::ONE, agent, void),
+            Err(LedgerError::CurrencySourceOrSink { entry_type: LedgerEntryType::Consume, .. })
+        ));
+        assert_eq!(ledger.money_supply(token), Decimal::new(6, 0));
+        assert_eq!(ledger.entity_balance(agent, token), Decimal::new(6, 0));
+    }
+
+    #[test]
+    fn reversing_a_mint_shrinks_the_supply() {
+        let (world, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let token = Resource::CurrencyToken;
+        let mut ledger = Ledger::new();
+        ledger.adopt_currency(token);
+        let _ = ledger.record_mint(1, token, Decimal::TEN, world, agent);
+        let mint = ledger.record_mint(1, token, Decimal::TWO, world, agent).map(|e| e.id);
+        let _ = mint.map(|id| ledger.reverse(id, 2, "MINT_OVERTURNED".to_owned()));
+
+        assert_eq!(ledger.money_supply(token), Decimal::TEN);
+        let flows = ledger.net_flow_for_tick(1);
+        assert_eq!(flows.get(&token), Some(&Decimal::TEN));
+    }
+}
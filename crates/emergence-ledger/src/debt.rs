@@ -0,0 +1,182 @@
+//! Loans between agents and the principal still owed on them.
+//!
+//! Credit moves through the ledger like any other resource:
+//!
+//! | Type | From (debit) | To (credit) |
+//! |------|-------------|-------------|
+//! | `LoanIssued` | Creditor | Debtor |
+//! | `LoanRepayment` | Debtor | Creditor |
+//! | `InterestAccrued` | Debtor | Creditor |
+//!
+//! All three are internal entries held to the conservation law. Interest is
+//! paid as it accrues, so it never adds to the principal: only issuing a
+//! loan raises what a debtor owes, and only repaying it lowers it. A
+//! repayment larger than the principal outstanding is rejected.
+//!
+//! A [`DebtRegistry`] tracks the outstanding principal per creditor and
+//! debtor as entries are recorded, so it survives pruning. The
+//! `EconomicDetector` in `emergence-agents` reads it to classify the
+//! simulation's credit system.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use emergence_types::{LedgerEntry, LedgerEntryType, Resource};
+
+use crate::LedgerError;
+
+/// The principal each debtor owes each creditor.
+#[derive(Debug, Default)]
+pub struct DebtRegistry {
+    principal: BTreeMap<(Uuid, Uuid), BTreeMap<Resource, Decimal>>,
+}
+
+impl DebtRegistry {
+    /// Create a registry with no loans.
+    pub const fn new() -> Self {
+        Self {
+            principal: BTreeMap::new(),
+        }
+    }
+
+    /// Check `entry` against the loans outstanding.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::RepaymentExceedsPrincipal`] if it repays more
+    /// of a resource than its debtor owes its creditor.
+    pub fn validate(&self, entry: &LedgerEntry) -> Result<(), LedgerError> {
+        if entry.entry_type != LedgerEntryType::LoanRepayment {
+            return Ok(());
+        }
+        let (Some(debtor), Some(creditor)) = (entry.from_entity, entry.to_entity) else {
+            return Ok(());
+        };
+        let outstanding = self.outstanding(creditor, debtor, entry.resource);
+        if entry.quantity > outstanding {
+            return Err(LedgerError::RepaymentExceedsPrincipal {
+                resource: entry.resource,
+                repaid: entry.quantity,
+                outstanding,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fold `entry` into the principal outstanding. `original` is the entry
+    /// it reverses, if it is a reversal, so a reversed loan or repayment is
+    /// undone.
+    pub fn record(&mut self, entry: &LedgerEntry, original: Option<&LedgerEntry>) {
+        let (counted, reversed) = original.map_or((entry, false), |original| (original, true));
+        let issued = match counted.entry_type {
+            LedgerEntryType::LoanIssued => true,
+            LedgerEntryType::LoanRepayment => false,
+            _ => return,
+        };
+        // The creditor is the source of a loan and the destination of a
+        // repayment, whichever way the counted entry runs.
+        let pair = if issued {
+            (counted.from_entity, counted.to_entity)
+        } else {
+            (counted.to_entity, counted.from_entity)
+        };
+        let (Some(creditor), Some(debtor)) = pair else {
+            return;
+        };
+        let owed = self.principal.entry((creditor, debtor)).or_default();
+        let principal = owed.entry(entry.resource).or_default();
+        *principal = if issued == reversed {
+            principal.saturating_sub(entry.quantity)
+        } else {
+            principal.saturating_add(entry.quantity)
+        };
+        if principal.is_zero() {
+            owed.remove(&entry.resource);
+        }
+        if owed.is_empty() {
+            self.principal.remove(&(creditor, debtor));
+        }
+    }
+
+    /// Return the principal of `resource` that `debtor` still owes
+    /// `creditor`.
+    pub fn outstanding(&self, creditor: Uuid, debtor: Uuid, resource: Resource) -> Decimal {
+        self.principal
+            .get(&(creditor, debtor))
+            .and_then(|owed| owed.get(&resource))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Return every (creditor, debtor) pair with principal outstanding, and
+    /// what is owed of each resource.
+    pub fn loans(&self) -> impl Iterator<Item = ((Uuid, Uuid), &BTreeMap<Resource, Decimal>)> {
+        self.principal.iter().map(|(pair, owed)| (*pair, owed))
+    }
+
+    /// Set the principal of `resource` that `debtor` owes `creditor`, as when
+    /// restoring a ledger from a snapshot.
+    pub fn restore(
+        &mut self,
+        creditor: Uuid,
+        debtor: Uuid,
+        resource: Resource,
+        principal: Decimal,
+    ) {
+        if principal.is_zero() {
+            return;
+        }
+        self.principal.entry((creditor, debtor)).or_default().insert(resource, principal);
+    }
+
+    /// Return the number of (creditor, debtor) pairs with principal
+    /// outstanding.
+    pub fn len(&self) -> usize {
+        self.principal.len()
+    }
+
+    /// Return whether no principal is outstanding.
+    pub fn is_empty(&self) -> bool {
+        self.principal.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ledger;
+
+    #[test]
+    fn repayments_reduce_principal_and_interest_does_not() {
+        let (lender, borrower) = (Uuid::now_v7(), Uuid::now_v7());
+        let food = Resource::FoodBerry;
+        let mut ledger = Ledger::new();
+        let _ = ledger.record_loan(1, food, Decimal::TEN, lender, borrower, None);
+        let _ = ledger.record_interest(2, food, Decimal::ONE, borrower, lender, None);
+        let _ = ledger.record_loan_repayment(3, food, Decimal::new(4, 0), borrower, lender, None);
+
+        assert_eq!(ledger.outstanding_principal(lender, borrower, food), Decimal::new(6, 0));
+        assert_eq!(ledger.outstanding_principal(borrower, lender, food), Decimal::ZERO);
+        assert_eq!(ledger.entity_balance(lender, food), Decimal::new(-5, 0));
+        assert_eq!(ledger.debts().len(), 1);
+
+        let overpaid = ledger.record_loan_repayment(4, food, Decimal::TEN, borrower, lender, None);
+        assert!(matches!(overpaid, Err(LedgerError::RepaymentExceedsPrincipal { .. })));
+        let _ = ledger.record_loan_repayment(4, food, Decimal::new(6, 0), borrower, lender, None);
+        assert!(ledger.debts().is_empty());
+    }
+
+    #[test]
+    fn reversing_a_loan_forgives_it() {
+        let (lender, borrower) = (Uuid::now_v7(), Uuid::now_v7());
+        let wood = Resource::Wood;
+        let mut ledger = Ledger::new();
+        let loan = ledger.record_loan(1, wood, Decimal::TEN, lender, borrower, None).map(|e| e.id);
+        let _ = ledger.record_loan(1, wood, Decimal::TWO, lender, borrower, None);
+        let _ = loan.map(|id| ledger.reverse(id, 2, "LOAN_VOIDED".to_owned()));
+
+        assert_eq!(ledger.outstanding_principal(lender, borrower, wood), Decimal::TWO);
+    }
+}
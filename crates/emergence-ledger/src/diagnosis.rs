@@ -0,0 +1,224 @@
+//! Diagnosing conservation anomalies and suggesting corrections.
+//!
+//! A well-formed internal entry debits and credits the same quantity, so a
+//! tick can only fall out of balance through an internal entry with one side
+//! unset: a credit nobody was debited for, or a debit nobody was credited
+//! with. [`diagnose`] finds those entries for each imbalanced resource,
+//! ranks them by how well they explain the imbalance, and suggests a
+//! [`Correction`] entry that reverses the unmatched side of the likeliest
+//! one.
+//!
+//! History is never edited. The [`Ledger`](crate::Ledger) quarantines the
+//! candidate entries for review, and a suggested correction is applied by
+//! appending it like any other entry.
+//!
+//! [`Correction`]: LedgerEntryType::Correction
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+use emergence_types::{LedgerEntry, LedgerEntryId, LedgerEntryType, Resource};
+
+use crate::conservation::is_internal;
+use crate::{LedgerAnomaly, LedgerError, TransactionBuilder};
+
+/// The likely causes of a [`LedgerAnomaly`] and how to correct it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// The tick the anomaly was detected in.
+    pub tick: u64,
+    /// The diagnosis of each imbalanced resource.
+    pub resources: BTreeMap<Resource, ResourceDiagnosis>,
+}
+
+impl Diagnosis {
+    /// Every candidate entry, across all resources.
+    pub fn candidates(&self) -> impl Iterator<Item = LedgerEntryId> + '_ {
+        self.resources.values().flat_map(|r| r.candidates.iter().copied())
+    }
+}
+
+/// One imbalanced resource in a [`Diagnosis`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceDiagnosis {
+    /// Total internal debits of the resource in the tick.
+    pub debits: Decimal,
+    /// Total internal credits of the resource in the tick.
+    pub credits: Decimal,
+    /// One-sided internal entries that could have caused the imbalance,
+    /// likeliest first: entries whose quantity matches the imbalance
+    /// exactly, then the rest by quantity, largest first.
+    pub candidates: Vec<LedgerEntryId>,
+    /// A correction reversing the unmatched side of the likeliest
+    /// candidate by the imbalance, if there is a candidate to reverse.
+    pub suggestion: Option<LedgerEntry>,
+}
+
+/// Diagnose `anomaly` from the `entries` recorded in its tick.
+///
+/// # Errors
+///
+/// Returns [`LedgerError`] if a suggested correction fails validation.
+pub fn diagnose(
+    entries: &[LedgerEntry],
+    anomaly: &LedgerAnomaly,
+) -> Result<Diagnosis, LedgerError> {
+    let mut resources = BTreeMap::new();
+    for resource in anomaly.imbalances.keys() {
+        let mut debits = Decimal::ZERO;
+        let mut credits = Decimal::ZERO;
+        let mut one_sided = Vec::new();
+        for entry in entries.iter().filter(|e| {
+            e.tick == anomaly.tick && e.resource == *resource && is_internal(e.entry_type)
+        }) {
+            if entry.from_entity.is_some() {
+                debits = debits.saturating_add(entry.quantity);
+            }
+            if entry.to_entity.is_some() {
+                credits = credits.saturating_add(entry.quantity);
+            }
+            if entry.from_entity.is_some() != entry.to_entity.is_some() {
+                one_sided.push(entry);
+            }
+        }
+
+        // Only entries on the heavier side can explain the imbalance.
+        let excess_credit = credits > debits;
+        let imbalance = credits.saturating_sub(debits).abs();
+        one_sided.retain(|e| e.to_entity.is_some() == excess_credit);
+        one_sided.sort_by(|a, b| {
+            let inexact = |e: &LedgerEntry| e.quantity != imbalance;
+            inexact(a).cmp(&inexact(b)).then(b.quantity.cmp(&a.quantity))
+        });
+
+        let suggestion = match one_sided.first() {
+            Some(likeliest) if !imbalance.is_zero() => {
+                Some(correction(likeliest, anomaly.tick, imbalance)?)
+            }
+            _ => None,
+        };
+        resources.insert(
+            *resource,
+            ResourceDiagnosis {
+                debits,
+                credits,
+                candidates: one_sided.iter().map(|e| e.id).collect(),
+                suggestion,
+            },
+        );
+    }
+    Ok(Diagnosis {
+        tick: anomaly.tick,
+        resources,
+    })
+}
+
+/// A correction at `tick` reversing `quantity` of the side `entry` left
+/// unmatched: a debit from the entity it credited, or a credit to the
+/// entity it debited.
+fn correction(
+    entry: &LedgerEntry,
+    tick: u64,
+    quantity: Decimal,
+) -> Result<LedgerEntry, LedgerError> {
+    let mut builder = TransactionBuilder::new(tick, LedgerEntryType::Correction, entry.resource)
+        .quantity(quantity)
+        .reason("CORRECTION".to_owned())
+        .reference_id(entry.id.into_inner());
+    if let (Some(to), Some(to_type)) = (entry.to_entity, entry.to_entity_type) {
+        builder = builder.from(to, to_type);
+    }
+    if let (Some(from), Some(from_type)) = (entry.from_entity, entry.from_entity_type) {
+        builder = builder.to(from, from_type);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use emergence_types::EntityType;
+
+    use super::*;
+    use crate::conservation::ConservationResult;
+    use crate::Ledger;
+
+    /// A gather whose location side was lost, crediting `agent` from
+    /// nowhere.
+    fn unmatched_gather(tick: u64, quantity: i64, agent: Uuid) -> LedgerEntry {
+        unmatched(tick, quantity, None, Some(agent))
+    }
+
+    /// A wood gather with only the given sides set.
+    fn unmatched(tick: u64, quantity: i64, from: Option<Uuid>, to: Option<Uuid>) -> LedgerEntry {
+        LedgerEntry {
+            id: LedgerEntryId::new(),
+            tick,
+            entry_type: LedgerEntryType::Gather,
+            from_entity: from,
+            from_entity_type: from.map(|_| EntityType::Location),
+            to_entity: to,
+            to_entity_type: to.map(|_| EntityType::Agent),
+            resource: Resource::Wood,
+            quantity: Decimal::new(quantity, 0),
+            reason: "GATHER".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn candidates_are_ranked_by_how_well_they_explain_the_imbalance() {
+        let (agent_a, agent_b) = (Uuid::now_v7(), Uuid::now_v7());
+        let (large, exact) = (unmatched_gather(3, 9, agent_a), unmatched_gather(3, 4, agent_b));
+        let (large_id, exact_id) = (large.id, exact.id);
+        // A lost credit elsewhere in the tick offsets all but 4 of the 13.
+        let debit = unmatched(3, 9, Some(Uuid::now_v7()), None);
+        let entries = [large, exact, debit];
+        let totals = (Decimal::new(9, 0), Decimal::new(13, 0));
+        let anomaly = LedgerAnomaly {
+            tick: 3,
+            imbalances: BTreeMap::from([(Resource::Wood, totals)]),
+            entry_id: None,
+            message: String::new(),
+        };
+
+        let diagnosis = diagnose(&entries, &anomaly).ok();
+        let wood = diagnosis.as_ref().and_then(|d| d.resources.get(&Resource::Wood));
+        assert_eq!(wood.map(|w| w.candidates.clone()), Some(vec![exact_id, large_id]));
+        let suggestion = wood.and_then(|w| w.suggestion.as_ref());
+        assert_eq!(suggestion.map(|s| (s.from_entity, s.to_entity)), Some((Some(agent_b), None)));
+        assert_eq!(suggestion.map(|s| s.quantity), Some(Decimal::new(4, 0)));
+    }
+
+    #[test]
+    fn applying_the_suggestion_rebalances_and_releases_the_tick() {
+        let agent = Uuid::now_v7();
+        let mut ledger = Ledger::new();
+        let entry = unmatched_gather(2, 5, agent);
+        let entry_id = entry.id;
+        let anomaly = ledger.append(&entry).ok().flatten();
+
+        let diagnosis = anomaly.as_ref().and_then(|a| ledger.quarantine(a).ok());
+        assert!(ledger.is_quarantined(entry_id));
+        assert!(matches!(ledger.verify_conservation(2), ConservationResult::Anomaly(_)));
+
+        let suggestion = diagnosis
+            .and_then(|d| d.resources.get(&Resource::Wood).cloned())
+            .and_then(|w| w.suggestion);
+        let result = suggestion.map(|s| ledger.apply_correction(&s));
+        assert!(matches!(result, Some(Ok(ConservationResult::Balanced))));
+        assert!(!ledger.is_quarantined(entry_id));
+        assert_eq!(ledger.entity_balance(agent, Resource::Wood), Decimal::ZERO);
+
+        let not_a_correction = unmatched_gather(2, 1, agent);
+        assert!(matches!(
+            ledger.apply_correction(&not_a_correction),
+            Err(LedgerError::NotACorrection(LedgerEntryType::Gather))
+        ));
+    }
+}
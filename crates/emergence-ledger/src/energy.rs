@@ -0,0 +1,287 @@
+//! Energy accounting in the same double-entry form as resources.
+//!
+//! Agent energy is not a [`Resource`](emergence_types::Resource) -- it never
+//! sits in an inventory or changes hands -- but it is an economy of its own,
+//! and a bug that creates or destroys energy is otherwise only discoverable
+//! through the behavior it causes. An [`EnergyLedger`] records every change
+//! to an agent's energy as an entry from one entity to another:
+//!
+//! | Type | From (debit) | To (credit) |
+//! |------|-------------|-------------|
+//! | `EnergyRecovered` | World | Agent |
+//! | `EnergySpent` | Agent | Void |
+//!
+//! An agent's ledgered balance is therefore the energy it should hold, and
+//! [`EnergyLedger::reconcile`] compares it against the energy the agent
+//! actually holds. A mismatch is an [`EnergyAnomaly`], reported the same way
+//! as a resource [`LedgerAnomaly`](crate::LedgerAnomaly).
+//!
+//! The energy ledger is optional: a run keeps one alongside its resource
+//! [`Ledger`](crate::Ledger) only when it wants energy audited.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use emergence_types::{EntityType, LedgerEntryId, LedgerEntryType};
+
+use crate::transaction::validate_entity_types;
+use crate::{metrics, LedgerError};
+
+/// One change to an agent's energy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnergyEntry {
+    /// Unique entry identifier.
+    pub id: LedgerEntryId,
+    /// The tick the change happened in.
+    pub tick: u64,
+    /// [`LedgerEntryType::EnergySpent`] or [`LedgerEntryType::EnergyRecovered`].
+    pub entry_type: LedgerEntryType,
+    /// Source entity (debit side).
+    pub from_entity: Uuid,
+    /// Source entity type.
+    pub from_entity_type: EntityType,
+    /// Destination entity (credit side).
+    pub to_entity: Uuid,
+    /// Destination entity type.
+    pub to_entity_type: EntityType,
+    /// Energy moved.
+    pub quantity: u32,
+    /// Human-readable reason (e.g. "ACTION", "REST", "EAT").
+    pub reason: String,
+}
+
+/// An agent whose energy does not match its ledgered balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnergyAnomaly {
+    /// The tick the mismatch was found in.
+    pub tick: u64,
+    /// The agent.
+    pub agent_id: Uuid,
+    /// The energy the ledger says the agent holds.
+    pub ledgered: i64,
+    /// The energy the agent actually holds.
+    pub actual: u32,
+    /// Human-readable description of the anomaly.
+    pub message: String,
+}
+
+impl core::fmt::Display for EnergyAnomaly {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// An append-only log of changes to agent energy, with each agent's
+/// running balance.
+#[derive(Debug)]
+pub struct EnergyLedger {
+    world: Uuid,
+    void: Uuid,
+    entries: Vec<EnergyEntry>,
+    balances: BTreeMap<Uuid, i64>,
+}
+
+impl EnergyLedger {
+    /// Create an empty energy ledger whose recovered energy comes from
+    /// `world` and whose spent energy goes to `void`.
+    pub const fn new(world: Uuid, void: Uuid) -> Self {
+        Self {
+            world,
+            void,
+            entries: Vec::new(),
+            balances: BTreeMap::new(),
+        }
+    }
+
+    /// Return the number of entries.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if no entries have been recorded.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Record `agent_id` spending `quantity` energy (agent to void).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::ZeroQuantity`] if `quantity` is zero.
+    pub fn record_spent(
+        &mut self,
+        tick: u64,
+        agent_id: Uuid,
+        quantity: u32,
+        reason: String,
+    ) -> Result<&EnergyEntry, LedgerError> {
+        self.push(EnergyEntry {
+            id: LedgerEntryId::new(),
+            tick,
+            entry_type: LedgerEntryType::EnergySpent,
+            from_entity: agent_id,
+            from_entity_type: EntityType::Agent,
+            to_entity: self.void,
+            to_entity_type: EntityType::Void,
+            quantity,
+            reason,
+        })
+    }
+
+    /// Record `agent_id` recovering `quantity` energy (world to agent).
+    ///
+    /// An agent's starting energy is recorded the same way, when it is
+    /// born.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::ZeroQuantity`] if `quantity` is zero.
+    pub fn record_recovered(
+        &mut self,
+        tick: u64,
+        agent_id: Uuid,
+        quantity: u32,
+        reason: String,
+    ) -> Result<&EnergyEntry, LedgerError> {
+        self.push(EnergyEntry {
+            id: LedgerEntryId::new(),
+            tick,
+            entry_type: LedgerEntryType::EnergyRecovered,
+            from_entity: self.world,
+            from_entity_type: EntityType::World,
+            to_entity: agent_id,
+            to_entity_type: EntityType::Agent,
+            quantity,
+            reason,
+        })
+    }
+
+    /// Record the change from `before` to `after` in `agent_id`'s energy as
+    /// spent or recovered energy, or nothing if it did not change.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_change(
+        &mut self,
+        tick: u64,
+        agent_id: Uuid,
+        before: u32,
+        after: u32,
+        reason: String,
+    ) -> Result<Option<&EnergyEntry>, LedgerError> {
+        match after.cmp(&before) {
+            Ordering::Greater => {
+                let gained = after.saturating_sub(before);
+                self.record_recovered(tick, agent_id, gained, reason).map(Some)
+            }
+            Ordering::Less => {
+                let lost = before.saturating_sub(after);
+                self.record_spent(tick, agent_id, lost, reason).map(Some)
+            }
+            Ordering::Equal => Ok(None),
+        }
+    }
+
+    /// `agent_id`'s ledgered energy balance.
+    pub fn balance(&self, agent_id: Uuid) -> i64 {
+        self.balances.get(&agent_id).copied().unwrap_or(0)
+    }
+
+    /// Return all entries for a specific tick.
+    pub fn entries_for_tick(&self, tick: u64) -> Vec<&EnergyEntry> {
+        self.entries.iter().filter(|e| e.tick == tick).collect()
+    }
+
+    /// Return a reference to all entries.
+    pub fn all_entries(&self) -> &[EnergyEntry] {
+        &self.entries
+    }
+
+    /// Compare `agent_id`'s ledgered balance with the `actual` energy it
+    /// holds at `tick`, returning an anomaly if they differ.
+    ///
+    /// A difference means energy was created or destroyed by a change that
+    /// was never recorded.
+    pub fn reconcile(&self, tick: u64, agent_id: Uuid, actual: u32) -> Option<EnergyAnomaly> {
+        let ledgered = self.balance(agent_id);
+        if ledgered == i64::from(actual) {
+            return None;
+        }
+        let anomaly = EnergyAnomaly {
+            tick,
+            agent_id,
+            ledgered,
+            actual,
+            message: format!(
+                "ENERGY_ANOMALY at tick {tick}: agent {agent_id} holds {actual} energy \
+                 but the ledger records {ledgered}"
+            ),
+        };
+        metrics::ANOMALIES.increment(1);
+        tracing::error!(%agent_id, "{anomaly}");
+        Some(anomaly)
+    }
+
+    /// Validate `entry`, append it, and update the balances of both sides.
+    fn push(&mut self, entry: EnergyEntry) -> Result<&EnergyEntry, LedgerError> {
+        if entry.quantity == 0 {
+            return Err(LedgerError::ZeroQuantity);
+        }
+        validate_entity_types(
+            entry.entry_type,
+            Some(entry.from_entity_type),
+            Some(entry.to_entity_type),
+        )?;
+        metrics::ENTRIES.increment_with(&format!("{:?}", entry.entry_type), 1);
+        let quantity = i64::from(entry.quantity);
+        let to = self.balances.entry(entry.to_entity).or_insert(0);
+        *to = to.saturating_add(quantity);
+        let from = self.balances.entry(entry.from_entity).or_insert(0);
+        *from = from.saturating_sub(quantity);
+        self.entries.push(entry);
+        self.entries.last().ok_or(LedgerError::InternalError(
+            "failed to retrieve entry after append",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_follow_recorded_changes() {
+        let (world, void, agent) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = EnergyLedger::new(world, void);
+
+        let _ = ledger.record_recovered(0, agent, 80, "BIRTH".to_owned());
+        let _ = ledger.record_change(1, agent, 80, 78, "ACTION".to_owned());
+        let _ = ledger.record_change(2, agent, 78, 90, "REST".to_owned());
+        assert!(matches!(ledger.record_change(3, agent, 90, 90, "EAT".to_owned()), Ok(None)));
+
+        assert_eq!(ledger.len(), 3);
+        assert_eq!(ledger.balance(agent), 90);
+        assert_eq!(ledger.balance(world), -92);
+        assert_eq!(ledger.balance(void), 2);
+        let spent = ledger.entries_for_tick(1);
+        assert_eq!(spent.first().map(|e| e.entry_type), Some(LedgerEntryType::EnergySpent));
+        assert!(matches!(
+            ledger.record_spent(4, agent, 0, "ACTION".to_owned()),
+            Err(LedgerError::ZeroQuantity)
+        ));
+    }
+
+    #[test]
+    fn unrecorded_changes_are_anomalies() {
+        let agent = Uuid::now_v7();
+        let mut ledger = EnergyLedger::new(Uuid::now_v7(), Uuid::now_v7());
+        let _ = ledger.record_recovered(0, agent, 80, "BIRTH".to_owned());
+
+        assert_eq!(ledger.reconcile(1, agent, 80), None);
+        let anomaly = ledger.reconcile(1, agent, 95);
+        assert_eq!(anomaly.as_ref().map(|a| (a.ledgered, a.actual)), Some((80, 95)));
+    }
+}
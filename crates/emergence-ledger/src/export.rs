@@ -0,0 +1,208 @@
+//! Exporting ledger entries for accounting and analysis tools.
+//!
+//! Two formats are written, both to any [`Write`]:
+//!
+//! - [`write_csv`] -- one row per entry with every field, for spreadsheets
+//!   and data frames.
+//! - [`write_journal`] -- a plain-text double-entry journal in the format
+//!   read by `hledger` and `ledger`, one transaction per entry.
+//!
+//! Accounts are named by entity type and ID, such as
+//! `agent:0194c2a3-...`, so a tool's account tree groups entities by type.
+//! A side left unset is posted to the `unset` account, which keeps every
+//! journal transaction balanced.
+//!
+//! In the journal, the destination's posting is positive and the source's
+//! negative, following those tools' convention that an account's balance is
+//! what it holds. The resource is the commodity.
+
+use std::borrow::Borrow;
+use std::io::Write;
+
+use uuid::Uuid;
+
+use emergence_types::{EntityType, LedgerEntry};
+
+/// The CSV header row, in column order.
+pub const CSV_HEADER: &str = "id,tick,entry_type,from_entity_type,from_entity,to_entity_type,\
+                              to_entity,resource,quantity,reason,reference_id,tags,created_at";
+
+/// Write `entries` as CSV with a [`CSV_HEADER`] row.
+///
+/// `entries` may be a slice or an iterator of materialized entries, so a
+/// large ledger is written without collecting it first.
+///
+/// Unset fields are left empty. An entry's tags share one column,
+/// separated by semicolons, such as `trade:0194c2a3-...;action:0194c2a3-...`.
+///
+/// # Errors
+///
+/// Returns the error of the first failed write.
+pub fn write_csv<E, W>(entries: impl IntoIterator<Item = E>, mut out: W) -> std::io::Result<()>
+where
+    E: Borrow<LedgerEntry>,
+    W: Write,
+{
+    writeln!(out, "{CSV_HEADER}")?;
+    for entry in entries {
+        let entry = entry.borrow();
+        writeln!(out, "{},{}", csv_fields(entry), entry.created_at.to_rfc3339())?;
+    }
+    out.flush()
+}
+
+/// Every CSV column of `entry` but the last, `created_at`.
+///
+/// This is also the content a [`merkle`](crate::merkle) leaf hashes.
+pub(crate) fn csv_fields(entry: &LedgerEntry) -> String {
+    format!(
+        "{},{},{:?},{},{},{},{},{:?},{},{},{},{}",
+        entry.id,
+        entry.tick,
+        entry.entry_type,
+        entry.from_entity_type.map(type_name).unwrap_or_default(),
+        entry.from_entity.map(|id| id.to_string()).unwrap_or_default(),
+        entry.to_entity_type.map(type_name).unwrap_or_default(),
+        entry.to_entity.map(|id| id.to_string()).unwrap_or_default(),
+        entry.resource,
+        entry.quantity,
+        csv_field(&entry.reason),
+        entry.reference_id.map(|id| id.to_string()).unwrap_or_default(),
+        entry.tags.iter().map(ToString::to_string).collect::<Vec<_>>().join(";"),
+    )
+}
+
+/// Write `entries` as a double-entry journal, one transaction per entry.
+///
+/// Each transaction is dated with the day the entry was recorded and
+/// described by its tick, type, and reason:
+///
+/// ```text
+/// 2026-03-01 tick 12 Gather | GATHER
+///     ; id: 0194c2a3-...
+///     agent:0194c2a3-...          5 Wood
+///     location:0194c2a3-...      -5 Wood
+/// ```
+///
+/// A reference and each tag add a comment line, such as
+/// `; trade:0194c2a3-...`, which those tools read as a tag.
+///
+/// # Errors
+///
+/// Returns the error of the first failed write.
+pub fn write_journal<E, W>(entries: impl IntoIterator<Item = E>, mut out: W) -> std::io::Result<()>
+where
+    E: Borrow<LedgerEntry>,
+    W: Write,
+{
+    for entry in entries {
+        let entry = entry.borrow();
+        let reason = entry.reason.replace(['\n', '\r'], " ");
+        writeln!(
+            out,
+            "{} tick {} {:?} | {reason}",
+            entry.created_at.format("%Y-%m-%d"),
+            entry.tick,
+            entry.entry_type,
+        )?;
+        writeln!(out, "    ; id: {}", entry.id)?;
+        if let Some(reference_id) = entry.reference_id {
+            writeln!(out, "    ; reference: {reference_id}")?;
+        }
+        for tag in &entry.tags {
+            writeln!(out, "    ; {tag}")?;
+        }
+        let to = account(entry.to_entity, entry.to_entity_type);
+        let from = account(entry.from_entity, entry.from_entity_type);
+        writeln!(out, "    {to:<48} {} {:?}", entry.quantity, entry.resource)?;
+        writeln!(out, "    {from:<48} -{} {:?}", entry.quantity, entry.resource)?;
+        writeln!(out)?;
+    }
+    out.flush()
+}
+
+/// The journal account of an entry side.
+fn account(entity: Option<Uuid>, entity_type: Option<EntityType>) -> String {
+    match (entity, entity_type) {
+        (Some(id), Some(entity_type)) => format!("{}:{id}", type_name(entity_type)),
+        (Some(id), None) => format!("unknown:{id}"),
+        (None, _) => "unset".to_owned(),
+    }
+}
+
+/// The lowercase name of an entity type, as used in account names.
+const fn type_name(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Agent => "agent",
+        EntityType::Location => "location",
+        EntityType::Structure => "structure",
+        EntityType::World => "world",
+        EntityType::Void => "void",
+        EntityType::Escrow => "escrow",
+        EntityType::Group => "group",
+    }
+}
+
+/// `field` quoted for CSV if it contains a delimiter, quote, or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use emergence_types::{LedgerEntryType, Resource};
+
+    use super::*;
+    use crate::TransactionBuilder;
+
+    fn gather(reason: &str) -> Option<LedgerEntry> {
+        TransactionBuilder::new(7, LedgerEntryType::Gather, Resource::FoodBerry)
+            .from(Uuid::now_v7(), EntityType::Location)
+            .to(Uuid::now_v7(), EntityType::Agent)
+            .quantity(Decimal::new(25, 1))
+            .reason(reason.to_owned())
+            .build()
+            .ok()
+    }
+
+    #[test]
+    fn csv_rows_follow_the_header_and_quote_free_text() {
+        let entries: Vec<LedgerEntry> = gather("picked, \"ripe\"").into_iter().collect();
+        let mut out = Vec::new();
+        assert!(write_csv(&entries, &mut out).is_ok());
+
+        let csv = String::from_utf8(out).unwrap_or_default();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        let row = lines.next().unwrap_or_default();
+        assert!(row.contains(",7,Gather,location,"));
+        assert!(row.contains(",agent,"));
+        assert!(row.contains(",FoodBerry,2.5,\"picked, \"\"ripe\"\"\",,,"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn journal_transactions_balance() {
+        let entries: Vec<LedgerEntry> = gather("GATHER").into_iter().collect();
+        let mut out = Vec::new();
+        assert!(write_journal(&entries, &mut out).is_ok());
+
+        let journal = String::from_utf8(out).unwrap_or_default();
+        let postings: Vec<&str> = journal
+            .lines()
+            .filter(|l| l.starts_with("    ") && !l.trim_start().starts_with(';'))
+            .collect();
+        assert_eq!(postings.len(), 2);
+        assert!(postings.first().is_some_and(|p| p.starts_with("    agent:")));
+        assert!(postings.first().is_some_and(|p| p.ends_with(" 2.5 FoodBerry")));
+        assert!(postings.get(1).is_some_and(|p| p.starts_with("    location:")));
+        assert!(postings.get(1).is_some_and(|p| p.ends_with(" -2.5 FoodBerry")));
+        assert!(journal.contains(" tick 7 Gather | GATHER\n"));
+    }
+}
@@ -1,26 +1,50 @@
-//! The central ledger: an append-only log of all resource transfers.
+//! The central ledger: a log of all resource transfers.
 //!
 //! The [`Ledger`] struct is the in-memory representation of the ledger
 //! for the current simulation run. It holds all [`LedgerEntry`] values
 //! and provides methods for recording transactions, querying balances,
-//! and verifying the conservation law.
+//! and verifying the conservation law. Entries are kept in a columnar
+//! [`EntryStore`], and balances are answered from a [`BalanceIndex`] kept
+//! up to date as entries are appended.
 //!
 //! # Design
 //!
-//! - **Append-only**: entries are never modified or deleted.
+//! - **Append-only, then pruned**: entries are never modified. Once they
+//!   fall outside the retention window, [`Ledger::prune_before`] replaces
+//!   them with checkpoint entries carrying each entity's balances forward.
 //! - **Double-entry**: every transfer has a debit (from) and credit (to).
 //! - **Conservation**: total resources in == total resources out per tick.
+//! - **Reversible**: an undone action is reversed by a new entry that
+//!   references the original, never by removing it.
 //! - **Precision**: all quantities use [`Decimal`] -- no floating point.
 
 use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use emergence_types::{EntityType, LedgerEntry, LedgerEntryType, Resource};
-
-use crate::conservation::{verify_conservation, verify_conservation_strict, ConservationResult};
-use crate::{LedgerError, TransactionBuilder};
+use emergence_types::{
+    CurrencySupply, EntityType, LedgerEntry, LedgerEntryId, LedgerEntryType, LedgerSnapshot,
+    LedgerTag, OutstandingLoan, Resource,
+};
+
+use crate::analytics::{wealth_distribution, WealthDistribution};
+use crate::balance::BalanceIndex;
+use crate::conservation::{
+    original_of, verify_conservation_strict, ConservationResult, ConservationTracker,
+};
+use crate::currency::CurrencyRegistry;
+use crate::debt::DebtRegistry;
+use crate::diagnosis::{diagnose, Diagnosis};
+use crate::export::{write_csv, write_journal};
+use crate::merkle::{prove, root, Hash, MerkleProof};
+use crate::prune::{checkpoint_entries, PruneReport};
+use crate::reconcile::{reconcile, ReconciliationMismatch, WorldHoldings};
+use crate::report::{report, LedgerReport};
+use crate::snapshot::{opening_balances, opening_entries};
+use crate::store::EntryStore;
+use crate::{metrics, LedgerAnomaly, LedgerError, TransactionBatch, TransactionBuilder};
 
 // ---------------------------------------------------------------------------
 // Transfer parameters
@@ -42,6 +66,8 @@ pub struct AgentTransferParams {
     pub reason: String,
     /// Optional reference to a related entity (e.g. trade ID).
     pub reference_id: Option<Uuid>,
+    /// What caused the transfer, such as the trade it settles.
+    pub tags: Vec<LedgerTag>,
 }
 
 /// Parameters for recording a general ledger transfer.
@@ -69,6 +95,8 @@ pub struct TransferParams {
     pub reason: String,
     /// Optional reference to a related entity.
     pub reference_id: Option<Uuid>,
+    /// What caused the transfer.
+    pub tags: Vec<LedgerTag>,
 }
 
 // ---------------------------------------------------------------------------
@@ -88,17 +116,43 @@ pub struct TransferParams {
 #[derive(Debug, Default)]
 pub struct Ledger {
     /// All entries, in insertion order.
-    entries: Vec<LedgerEntry>,
+    entries: EntryStore,
+    /// Running balances folded from `entries`.
+    balances: BalanceIndex,
+    /// Running conservation totals folded from `entries`.
+    conservation: ConservationTracker,
+    /// Number of recent ticks [`Ledger::prune`] keeps entries for.
+    retention: Option<u64>,
+    /// Entries quarantined by [`Ledger::quarantine`], with their ticks.
+    quarantined: BTreeMap<LedgerEntryId, u64>,
+    /// Adopted currencies and their money supply.
+    currencies: CurrencyRegistry,
+    /// Principal outstanding on loans between agents.
+    debts: DebtRegistry,
 }
 
 impl Ledger {
     /// Create a new empty ledger.
     pub const fn new() -> Self {
         Self {
-            entries: Vec::new(),
+            entries: EntryStore::new(),
+            balances: BalanceIndex::new(),
+            conservation: ConservationTracker::new(),
+            retention: None,
+            quarantined: BTreeMap::new(),
+            currencies: CurrencyRegistry::new(),
+            debts: DebtRegistry::new(),
         }
     }
 
+    /// Keep only the entries of the last `ticks` ticks when pruning with
+    /// [`Ledger::prune`].
+    #[must_use]
+    pub const fn with_retention(mut self, ticks: u64) -> Self {
+        self.retention = Some(ticks);
+        self
+    }
+
     /// Return the number of entries in the ledger.
     pub const fn len(&self) -> usize {
         self.entries.len()
@@ -115,11 +169,54 @@ impl Ledger {
     /// from the database). For new entries, prefer [`record_transfer`],
     /// [`record_regeneration`], [`record_consumption`], etc.
     ///
+    /// Returns the anomaly if the entry breaks the conservation law, as
+    /// an entry loaded from a corrupt source can.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::InternalError`] if the ledger is full.
+    ///
     /// [`record_transfer`]: Ledger::record_transfer
     /// [`record_regeneration`]: Ledger::record_regeneration
     /// [`record_consumption`]: Ledger::record_consumption
-    pub fn append(&mut self, entry: LedgerEntry) {
-        self.entries.push(entry);
+    pub fn append(&mut self, entry: &LedgerEntry) -> Result<Option<LedgerAnomaly>, LedgerError> {
+        self.push(entry)
+    }
+
+    /// Append `entry`, counting it and folding it into the balances and
+    /// conservation totals.
+    fn push(&mut self, entry: &LedgerEntry) -> Result<Option<LedgerAnomaly>, LedgerError> {
+        let original = self.original_of(entry);
+        self.entries.push(entry)?;
+        metrics::ENTRIES.increment_with(&format!("{:?}", entry.entry_type), 1);
+        self.balances.record(entry);
+        self.currencies.record(entry, original.as_ref());
+        self.debts.record(entry, original.as_ref());
+        let anomaly = match &original {
+            Some(original) => self.conservation.record_reversal(entry, original),
+            None => self.conservation.record(entry),
+        };
+        if let Some(anomaly) = &anomaly {
+            tracing::error!(entry_id = %entry.id, "{anomaly}");
+        }
+        Ok(anomaly)
+    }
+
+    /// Return the entry `entry` reverses, if it is a reversal of a recorded
+    /// entry.
+    fn original_of(&self, entry: &LedgerEntry) -> Option<LedgerEntry> {
+        if entry.entry_type != LedgerEntryType::Reversal {
+            return None;
+        }
+        self.entries.find(LedgerEntryId::from(entry.reference_id?))
+    }
+
+    /// Return `tick`'s entries and every reversal, which may net against
+    /// them.
+    fn entries_with_reversals(&self, tick: u64) -> Vec<LedgerEntry> {
+        let mut entries = self.entries.tick(tick);
+        entries.extend(self.entries.reversals().into_iter().filter(|e| e.tick != tick));
+        entries
     }
 
     /// Record a resource transfer between two entities.
@@ -129,11 +226,12 @@ impl Ledger {
     ///
     /// # Errors
     ///
-    /// Returns [`LedgerError`] if the entry fails validation.
+    /// Returns [`LedgerError`] if the entry fails validation or breaks a
+    /// [`currency`](crate::currency) rule.
     pub fn record_transfer(
         &mut self,
         params: TransferParams,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         let mut builder = TransactionBuilder::new(params.tick, params.entry_type, params.resource)
             .from(params.from_entity, params.from_entity_type)
             .to(params.to_entity, params.to_entity_type)
@@ -143,14 +241,66 @@ impl Ledger {
         if let Some(ref_id) = params.reference_id {
             builder = builder.reference_id(ref_id);
         }
+        for tag in params.tags {
+            builder = builder.tag(tag);
+        }
 
         let entry = builder.build()?;
-        self.entries.push(entry);
+        self.currencies.validate(&entry)?;
+        self.debts.validate(&entry)?;
+        self.push(&entry)?;
+        Ok(entry)
+    }
 
-        // Return a reference to the entry we just pushed.
-        self.entries.last().ok_or(LedgerError::InternalError(
-            "failed to retrieve entry after append",
-        ))
+    /// Record every transfer of `transfers`, or none of them.
+    ///
+    /// The tick's transfers are recorded this way rather than one
+    /// [`record_transfer`](Self::record_transfer) at a time: the batch is
+    /// validated as a whole before any entry is appended. Returns the
+    /// recorded entries in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::BatchEntry`] if any transfer fails validation
+    /// or breaks a [`currency`](crate::currency) or [`debt`](crate::debt)
+    /// rule.
+    pub fn record_transfers(
+        &mut self,
+        transfers: &[TransferParams],
+    ) -> Result<Vec<LedgerEntry>, LedgerError> {
+        self.record_batch(TransactionBatch::transfers(transfers)?)
+    }
+
+    /// Record every entry of `batch`, or none of them.
+    ///
+    /// All entries are validated before any is appended, so an operation
+    /// that moves several resources cannot leave the ledger half-applied.
+    /// Returns the recorded entries in batch order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::BatchEntry`] if any entry fails validation or
+    /// breaks a [`currency`](crate::currency) or [`debt`](crate::debt) rule.
+    /// Repayments are checked against the principal outstanding before the
+    /// batch.
+    pub fn record_batch(
+        &mut self,
+        batch: TransactionBatch,
+    ) -> Result<Vec<LedgerEntry>, LedgerError> {
+        let entries = batch.build()?;
+        for (index, entry) in entries.iter().enumerate() {
+            self.currencies
+                .validate(entry)
+                .and_then(|()| self.debts.validate(entry))
+                .map_err(|source| LedgerError::BatchEntry {
+                    index,
+                    source: Box::new(source),
+                })?;
+        }
+        for entry in &entries {
+            self.push(entry)?;
+        }
+        Ok(entries)
     }
 
     /// Record resource regeneration (world to location).
@@ -165,7 +315,7 @@ impl Ledger {
         quantity: Decimal,
         world_entity: Uuid,
         location_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Regeneration,
@@ -177,6 +327,7 @@ impl Ledger {
             to_entity_type: EntityType::Location,
             reason: "REGENERATION".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
         })
     }
 
@@ -192,7 +343,7 @@ impl Ledger {
         quantity: Decimal,
         location_entity: Uuid,
         agent_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Gather,
@@ -204,6 +355,7 @@ impl Ledger {
             to_entity_type: EntityType::Agent,
             reason: "GATHER".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
         })
     }
 
@@ -219,7 +371,7 @@ impl Ledger {
         quantity: Decimal,
         agent_entity: Uuid,
         void_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Consume,
@@ -231,6 +383,7 @@ impl Ledger {
             to_entity_type: EntityType::Void,
             reason: "CONSUME".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
         })
     }
 
@@ -246,7 +399,7 @@ impl Ledger {
         quantity: Decimal,
         structure_entity: Uuid,
         void_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Decay,
@@ -258,9 +411,153 @@ impl Ledger {
             to_entity_type: EntityType::Void,
             reason: "DECAY".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record livestock born into an agent's herd (world to agent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_breeding(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        world_entity: Uuid,
+        agent_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Breeding,
+            resource,
+            quantity,
+            from_entity: world_entity,
+            from_entity_type: EntityType::World,
+            to_entity: agent_entity,
+            to_entity_type: EntityType::Agent,
+            reason: "BREEDING".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record livestock slaughtered by its owner (agent to void).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_slaughter(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        agent_entity: Uuid,
+        void_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Slaughter,
+            resource,
+            quantity,
+            from_entity: agent_entity,
+            from_entity_type: EntityType::Agent,
+            to_entity: void_entity,
+            to_entity_type: EntityType::Void,
+            reason: "SLAUGHTER".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record new currency issued to an agent (world to agent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::NotACurrency`] if `currency` has not been
+    /// adopted, or [`LedgerError`] if the entry fails validation.
+    pub fn record_mint(
+        &mut self,
+        tick: u64,
+        currency: Resource,
+        quantity: Decimal,
+        world_entity: Uuid,
+        agent_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Mint,
+            resource: currency,
+            quantity,
+            from_entity: world_entity,
+            from_entity_type: EntityType::World,
+            to_entity: agent_entity,
+            to_entity_type: EntityType::Agent,
+            reason: "MINT".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
         })
     }
 
+    /// Record currency taken out of circulation (agent to void).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::NotACurrency`] if `currency` has not been
+    /// adopted, or [`LedgerError`] if the entry fails validation.
+    pub fn record_burn(
+        &mut self,
+        tick: u64,
+        currency: Resource,
+        quantity: Decimal,
+        agent_entity: Uuid,
+        void_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Burn,
+            resource: currency,
+            quantity,
+            from_entity: agent_entity,
+            from_entity_type: EntityType::Agent,
+            to_entity: void_entity,
+            to_entity_type: EntityType::Void,
+            reason: "BURN".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Adopt `resource` as a currency, as when the economy detector finds
+    /// it being used as money. Returns `false` if it already was one.
+    ///
+    /// From then on it can only enter by [`Ledger::record_mint`] and leave
+    /// by [`Ledger::record_burn`]; see [`currency`](crate::currency).
+    pub fn adopt_currency(&mut self, resource: Resource) -> bool {
+        let adopted = self.currencies.adopt(resource);
+        if adopted {
+            tracing::info!(?resource, "Adopted resource as a currency");
+        }
+        adopted
+    }
+
+    /// Return whether `resource` has been adopted as a currency.
+    pub fn is_currency(&self, resource: Resource) -> bool {
+        self.currencies.is_currency(resource)
+    }
+
+    /// Return the adopted currencies.
+    pub fn currencies(&self) -> impl Iterator<Item = Resource> + '_ {
+        self.currencies.currencies()
+    }
+
+    /// Return the money supply of `currency`: everything minted, less
+    /// everything burned.
+    pub fn money_supply(&self, currency: Resource) -> Decimal {
+        self.currencies.supply(currency)
+    }
+
     /// Record an agent-to-agent resource transfer (trade, gift).
     ///
     /// # Errors
@@ -269,7 +566,7 @@ impl Ledger {
     pub fn record_agent_transfer(
         &mut self,
         params: AgentTransferParams,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick: params.tick,
             entry_type: LedgerEntryType::Transfer,
@@ -281,6 +578,7 @@ impl Ledger {
             to_entity_type: EntityType::Agent,
             reason: params.reason,
             reference_id: params.reference_id,
+            tags: params.tags,
         })
     }
 
@@ -296,7 +594,7 @@ impl Ledger {
         quantity: Decimal,
         agent_entity: Uuid,
         structure_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Build,
@@ -308,6 +606,7 @@ impl Ledger {
             to_entity_type: EntityType::Structure,
             reason: "BUILD".to_owned(),
             reference_id: Some(structure_entity),
+            tags: Vec::new(),
         })
     }
 
@@ -323,7 +622,7 @@ impl Ledger {
         quantity: Decimal,
         structure_entity: Uuid,
         agent_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Salvage,
@@ -335,6 +634,7 @@ impl Ledger {
             to_entity_type: EntityType::Agent,
             reason: "SALVAGE".to_owned(),
             reference_id: Some(structure_entity),
+            tags: Vec::new(),
         })
     }
 
@@ -350,7 +650,7 @@ impl Ledger {
         quantity: Decimal,
         agent_entity: Uuid,
         location_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Drop,
@@ -362,6 +662,7 @@ impl Ledger {
             to_entity_type: EntityType::Location,
             reason: "DROP".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
         })
     }
 
@@ -377,7 +678,7 @@ impl Ledger {
         quantity: Decimal,
         location_entity: Uuid,
         agent_entity: Uuid,
-    ) -> Result<&LedgerEntry, LedgerError> {
+    ) -> Result<LedgerEntry, LedgerError> {
         self.record_transfer(TransferParams {
             tick,
             entry_type: LedgerEntryType::Pickup,
@@ -389,15 +690,309 @@ impl Ledger {
             to_entity_type: EntityType::Agent,
             reason: "PICKUP".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record resources deposited into a storage structure (agent to
+    /// structure).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_deposit(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        agent_entity: Uuid,
+        structure_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Deposit,
+            resource,
+            quantity,
+            from_entity: agent_entity,
+            from_entity_type: EntityType::Agent,
+            to_entity: structure_entity,
+            to_entity_type: EntityType::Structure,
+            reason: "DEPOSIT".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
         })
     }
 
+    /// Record resources withdrawn from a storage structure (structure to
+    /// agent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_withdrawal(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        structure_entity: Uuid,
+        agent_entity: Uuid,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Withdrawal,
+            resource,
+            quantity,
+            from_entity: structure_entity,
+            from_entity_type: EntityType::Structure,
+            to_entity: agent_entity,
+            to_entity_type: EntityType::Agent,
+            reason: "WITHDRAWAL".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record a tax paid by a member into a group's treasury (agent to
+    /// group).
+    ///
+    /// `levy_id` names the governance rule or decision that imposed the tax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_tax(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        agent_entity: Uuid,
+        group_entity: Uuid,
+        levy_id: Option<Uuid>,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Tax,
+            resource,
+            quantity,
+            from_entity: agent_entity,
+            from_entity_type: EntityType::Agent,
+            to_entity: group_entity,
+            to_entity_type: EntityType::Group,
+            reason: "TAX".to_owned(),
+            reference_id: levy_id,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record tribute paid from one group's treasury to another's (group to
+    /// group).
+    ///
+    /// `levy_id` names the treaty or demand the tribute satisfies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_tribute(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        from_group: Uuid,
+        to_group: Uuid,
+        levy_id: Option<Uuid>,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::Tribute,
+            resource,
+            quantity,
+            from_entity: from_group,
+            from_entity_type: EntityType::Group,
+            to_entity: to_group,
+            to_entity_type: EntityType::Group,
+            reason: "TRIBUTE".to_owned(),
+            reference_id: levy_id,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record a loan of principal from a creditor to a debtor (agent to
+    /// agent).
+    ///
+    /// `loan_id` names the agreement the loan was made under.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_loan(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        creditor: Uuid,
+        debtor: Uuid,
+        loan_id: Option<Uuid>,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::LoanIssued,
+            resource,
+            quantity,
+            from_entity: creditor,
+            from_entity_type: EntityType::Agent,
+            to_entity: debtor,
+            to_entity_type: EntityType::Agent,
+            reason: "LOAN".to_owned(),
+            reference_id: loan_id,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record principal paid back by a debtor to its creditor (agent to
+    /// agent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::RepaymentExceedsPrincipal`] if the debtor owes
+    /// the creditor less than `quantity`, or [`LedgerError`] if the entry
+    /// fails validation.
+    pub fn record_loan_repayment(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        debtor: Uuid,
+        creditor: Uuid,
+        loan_id: Option<Uuid>,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::LoanRepayment,
+            resource,
+            quantity,
+            from_entity: debtor,
+            from_entity_type: EntityType::Agent,
+            to_entity: creditor,
+            to_entity_type: EntityType::Agent,
+            reason: "LOAN_REPAYMENT".to_owned(),
+            reference_id: loan_id,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Record interest paid by a debtor to its creditor as it accrues
+    /// (agent to agent). Interest leaves the principal unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the entry fails validation.
+    pub fn record_interest(
+        &mut self,
+        tick: u64,
+        resource: Resource,
+        quantity: Decimal,
+        debtor: Uuid,
+        creditor: Uuid,
+        loan_id: Option<Uuid>,
+    ) -> Result<LedgerEntry, LedgerError> {
+        self.record_transfer(TransferParams {
+            tick,
+            entry_type: LedgerEntryType::InterestAccrued,
+            resource,
+            quantity,
+            from_entity: debtor,
+            from_entity_type: EntityType::Agent,
+            to_entity: creditor,
+            to_entity_type: EntityType::Agent,
+            reason: "INTEREST".to_owned(),
+            reference_id: loan_id,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Return the principal of `resource` that `debtor` still owes
+    /// `creditor`.
+    pub fn outstanding_principal(
+        &self,
+        creditor: Uuid,
+        debtor: Uuid,
+        resource: Resource,
+    ) -> Decimal {
+        self.debts.outstanding(creditor, debtor, resource)
+    }
+
+    /// Return the loans outstanding between agents; see
+    /// [`debt`](crate::debt).
+    pub const fn debts(&self) -> &DebtRegistry {
+        &self.debts
+    }
+
+    /// Reverse the entry `original_id`, undoing it at `tick`.
+    ///
+    /// The reversal moves the original's quantity back from its destination
+    /// to its source, references the original, and carries its tags, so it
+    /// is grouped with the entries it belongs to. Balances change at
+    /// `tick`, while conservation nets the reversal against the original in
+    /// the original's tick; see [`conservation`](crate::conservation).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::EntryNotFound`] if no entry has the ID,
+    /// [`LedgerError::NotReversible`] if it is a checkpoint or a reversal,
+    /// and [`LedgerError::AlreadyReversed`] if it has been reversed before.
+    pub fn reverse(
+        &mut self,
+        original_id: LedgerEntryId,
+        tick: u64,
+        reason: String,
+    ) -> Result<LedgerEntry, LedgerError> {
+        let original = self
+            .entries
+            .find(original_id)
+            .ok_or(LedgerError::EntryNotFound(original_id))?;
+        if matches!(
+            original.entry_type,
+            LedgerEntryType::Checkpoint | LedgerEntryType::Reversal
+        ) {
+            return Err(LedgerError::NotReversible(original.entry_type));
+        }
+        if self.is_reversed(original_id) {
+            return Err(LedgerError::AlreadyReversed(original_id));
+        }
+
+        let mut builder =
+            TransactionBuilder::new(tick, LedgerEntryType::Reversal, original.resource)
+                .quantity(original.quantity)
+                .reason(reason)
+                .reference_id(original_id.into_inner());
+        if let (Some(to), Some(to_type)) = (original.to_entity, original.to_entity_type) {
+            builder = builder.from(to, to_type);
+        }
+        if let (Some(from), Some(from_type)) = (original.from_entity, original.from_entity_type) {
+            builder = builder.to(from, from_type);
+        }
+        for tag in original.tags {
+            builder = builder.tag(tag);
+        }
+        let reversal = builder.build()?;
+        tracing::info!(entry_id = %original_id, tick, "Reversing ledger entry");
+        self.push(&reversal)?;
+        Ok(reversal)
+    }
+
+    /// Return whether the entry `id` has been reversed.
+    pub fn is_reversed(&self, id: LedgerEntryId) -> bool {
+        self.entries.is_reversed(id)
+    }
+
     /// Verify the conservation law for a given tick.
     ///
     /// Returns [`ConservationResult::Balanced`] if the ledger is balanced,
     /// or [`ConservationResult::Anomaly`] with details about the imbalance.
+    /// Reads the totals kept as entries were recorded rather than scanning
+    /// the tick's entries.
     pub fn verify_conservation(&self, tick: u64) -> ConservationResult {
-        verify_conservation(tick, &self.entries)
+        count_anomaly(self.conservation.verify(tick))
     }
 
     /// Verify the conservation law with strict flow semantics.
@@ -405,17 +1000,105 @@ impl Ledger {
     /// This performs the basic double-entry balance check plus validates
     /// the flow direction semantics for each entry type.
     pub fn verify_conservation_strict(&self, tick: u64) -> ConservationResult {
-        verify_conservation_strict(tick, &self.entries)
+        count_anomaly(verify_conservation_strict(tick, &self.entries_with_reversals(tick)))
+    }
+
+    /// Compare the ledger balances after `tick` with what the entities in
+    /// `holdings` actually hold, returning every resource that differs.
+    ///
+    /// Run at the end of a tick, this catches world changes that were
+    /// never recorded; see [`reconcile`](crate::reconcile).
+    pub fn reconcile(&self, tick: u64, holdings: &WorldHoldings) -> Vec<ReconciliationMismatch> {
+        let mismatches = reconcile(tick, holdings, |entity_id| self.balances(entity_id, tick));
+        for mismatch in &mismatches {
+            tracing::warn!(entity_id = %mismatch.entity_id, "{mismatch}");
+        }
+        metrics::MISMATCHES.increment(u64::try_from(mismatches.len()).unwrap_or(u64::MAX));
+        mismatches
+    }
+
+    /// Diagnose `anomaly` and quarantine its candidate entries for review.
+    ///
+    /// Quarantined entries stay in the ledger and keep counting towards
+    /// balances; they are only flagged, until a correction rebalances their
+    /// tick. See [`diagnosis`](crate::diagnosis) for how candidates and the
+    /// suggested corrections are found.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if a suggested correction fails validation.
+    pub fn quarantine(&mut self, anomaly: &LedgerAnomaly) -> Result<Diagnosis, LedgerError> {
+        let diagnosis = diagnose(&self.entries_for_tick(anomaly.tick), anomaly)?;
+        self.quarantined.extend(diagnosis.candidates().map(|id| (id, diagnosis.tick)));
+        tracing::warn!(
+            tick = diagnosis.tick,
+            candidates = diagnosis.candidates().count(),
+            "Quarantined ledger entries for review"
+        );
+        Ok(diagnosis)
+    }
+
+    /// Return whether the entry `id` is quarantined.
+    pub fn is_quarantined(&self, id: LedgerEntryId) -> bool {
+        self.quarantined.contains_key(&id)
+    }
+
+    /// Return the quarantined entries.
+    pub fn quarantined(&self) -> impl Iterator<Item = LedgerEntryId> + '_ {
+        self.quarantined.keys().copied()
+    }
+
+    /// Append `correction` and, if its tick now balances, release that
+    /// tick's quarantined entries.
+    ///
+    /// Returns the tick's conservation result after the correction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::NotACorrection`] if `correction` is not a
+    /// [`LedgerEntryType::Correction`] entry, or
+    /// [`LedgerError::InternalError`] if the ledger is full.
+    pub fn apply_correction(
+        &mut self,
+        correction: &LedgerEntry,
+    ) -> Result<ConservationResult, LedgerError> {
+        if correction.entry_type != LedgerEntryType::Correction {
+            return Err(LedgerError::NotACorrection(correction.entry_type));
+        }
+        let tick = correction.tick;
+        tracing::info!(entry_id = %correction.id, tick, "Applying ledger correction");
+        self.push(correction)?;
+        let result = self.conservation.verify(tick);
+        if matches!(result, ConservationResult::Balanced) {
+            self.quarantined.retain(|_, quarantined| *quarantined != tick);
+        }
+        Ok(result)
     }
 
     /// Return all entries for a given tick.
-    pub fn entries_for_tick(&self, tick: u64) -> Vec<&LedgerEntry> {
-        self.entries.iter().filter(|e| e.tick == tick).collect()
+    pub fn entries_for_tick(&self, tick: u64) -> Vec<LedgerEntry> {
+        self.entries.tick(tick)
+    }
+
+    /// Return all entries an entity is the source or destination of.
+    pub fn entries_for_entity(&self, entity_id: Uuid) -> Vec<LedgerEntry> {
+        self.entries.entity(entity_id)
+    }
+
+    /// Return all entries moving a resource.
+    pub fn entries_for_resource(&self, resource: Resource) -> Vec<LedgerEntry> {
+        self.entries.resource(resource)
+    }
+
+    /// Return all entries tagged with `tag`, such as every entry of one
+    /// trade.
+    pub fn entries_tagged(&self, tag: LedgerTag) -> Vec<LedgerEntry> {
+        self.entries.tagged(tag)
     }
 
     /// Return all entries, in insertion order.
-    pub fn all_entries(&self) -> &[LedgerEntry] {
-        &self.entries
+    pub fn all_entries(&self) -> Vec<LedgerEntry> {
+        self.entries.iter().collect()
     }
 
     /// Calculate the net balance for a specific entity and resource.
@@ -423,48 +1106,232 @@ impl Ledger {
     /// Positive balance means the entity has received more than it has sent.
     /// Negative balance means the entity has sent more than it has received.
     pub fn entity_balance(&self, entity_id: Uuid, resource: Resource) -> Decimal {
-        let mut balance = Decimal::ZERO;
+        self.balance(entity_id, resource, u64::MAX)
+    }
 
-        for entry in &self.entries {
-            if entry.resource != resource {
-                continue;
-            }
+    /// Return the net balance of `resource` for `entity_id` after `tick`,
+    /// counting every entry recorded at or before it.
+    pub fn balance(&self, entity_id: Uuid, resource: Resource, tick: u64) -> Decimal {
+        self.balances.balance(entity_id, resource, tick)
+    }
 
-            // Credit: entity receives resources (is the `to_entity`).
-            if entry.to_entity == Some(entity_id) {
-                balance = balance.saturating_add(entry.quantity);
-            }
+    /// Return the net balance of every resource `entity_id` had been
+    /// credited or debited by `tick`, as of that tick.
+    pub fn balances(&self, entity_id: Uuid, tick: u64) -> BTreeMap<Resource, Decimal> {
+        self.balances.balances(entity_id, tick)
+    }
 
-            // Debit: entity loses resources (is the `from_entity`).
-            if entry.from_entity == Some(entity_id) {
-                balance = balance.saturating_sub(entry.quantity);
+    /// Prune the entries that fall outside the retention window at
+    /// `current_tick`, if a retention was set with [`Ledger::with_retention`].
+    ///
+    /// Entries before `current_tick - ticks` are collapsed as by
+    /// [`Ledger::prune_before`].
+    pub fn prune(&mut self, current_tick: u64) -> PruneReport {
+        self.retention.map_or_else(PruneReport::default, |ticks| {
+            self.prune_before(current_tick.saturating_sub(ticks))
+        })
+    }
+
+    /// Replace every entry before `tick` with checkpoint entries at
+    /// `tick - 1` carrying each entity's balances forward.
+    ///
+    /// Balances and conservation from `tick - 1` on are unchanged; see
+    /// [`prune`](crate::prune) for how the checkpoints are built.
+    pub fn prune_before(&mut self, tick: u64) -> PruneReport {
+        let Some(checkpoint_tick) = tick.checked_sub(1) else {
+            return PruneReport::default();
+        };
+        let already_pruned = |e: &LedgerEntry| {
+            e.tick == checkpoint_tick && e.entry_type == LedgerEntryType::Checkpoint
+        };
+        let old = self.entries.ticks(..tick);
+        if old.iter().all(already_pruned) {
+            return PruneReport::default();
+        }
+
+        let checkpoints = checkpoint_entries(&old, checkpoint_tick);
+        let report = PruneReport {
+            pruned: old.len(),
+            checkpoints: checkpoints.len(),
+        };
+        let entries: Vec<LedgerEntry> =
+            checkpoints.into_iter().chain(self.entries.ticks(tick..)).collect();
+        self.quarantined.retain(|_, quarantined| *quarantined >= tick);
+        self.entries = EntryStore::new();
+        self.balances = BalanceIndex::new();
+        self.conservation = ConservationTracker::new();
+        for entry in &entries {
+            if let Err(error) = self.entries.push(entry) {
+                tracing::error!(%error, "Ledger full while restoring pruned entries");
+                break;
             }
+            self.balances.record(entry);
+            self.conservation.record_among(entry, &entries);
+        }
+        tracing::debug!(
+            tick,
+            pruned = report.pruned,
+            checkpoints = report.checkpoints,
+            "Pruned ledger entries into checkpoints"
+        );
+        report
+    }
+
+    /// Take a snapshot of the ledger's opening balances after `tick`, for
+    /// restarting with [`Ledger::from_snapshot`].
+    ///
+    /// Take it at the end of `tick`, before any later entry is recorded:
+    /// the balances count only entries up to `tick`, but the currency
+    /// supplies and loans are the ledger's current ones.
+    pub fn snapshot(&self, tick: u64) -> LedgerSnapshot {
+        let currencies = self
+            .currencies
+            .currencies()
+            .map(|currency| CurrencySupply {
+                currency,
+                supply: self.currencies.supply(currency),
+            })
+            .collect();
+        let loans = self
+            .debts
+            .loans()
+            .flat_map(|((creditor, debtor), owed)| {
+                owed.iter().map(move |(resource, principal)| OutstandingLoan {
+                    creditor,
+                    debtor,
+                    resource: *resource,
+                    principal: *principal,
+                })
+            })
+            .collect();
+        LedgerSnapshot {
+            tick,
+            balances: opening_balances(&self.entries.ticks(..=tick)),
+            currencies,
+            loans,
         }
+    }
+
+    /// Start a ledger from `snapshot`, carrying its balances forward as
+    /// checkpoint entries at the snapshot tick; see
+    /// [`snapshot`](crate::snapshot).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::InternalError`] if the ledger is full.
+    pub fn from_snapshot(snapshot: &LedgerSnapshot) -> Result<Self, LedgerError> {
+        let mut ledger = Self::new();
+        for supply in &snapshot.currencies {
+            ledger.currencies.restore(supply.currency, supply.supply);
+        }
+        for loan in &snapshot.loans {
+            ledger.debts.restore(loan.creditor, loan.debtor, loan.resource, loan.principal);
+        }
+        for entry in &opening_entries(snapshot) {
+            ledger.push(entry)?;
+        }
+        tracing::debug!(
+            tick = snapshot.tick,
+            balances = snapshot.balances.len(),
+            "Restored ledger from snapshot"
+        );
+        Ok(ledger)
+    }
+
+    /// Compute how wealth was distributed among agents after `tick`.
+    ///
+    /// See [`analytics`](crate::analytics) for how holdings and the
+    /// statistics are derived.
+    pub fn wealth_distribution(&self, tick: u64) -> WealthDistribution {
+        wealth_distribution(&self.entries.ticks(..=tick), tick)
+    }
+
+    /// Aggregate the entries recorded in `ticks` into per-resource totals by
+    /// entry type and per-entity net flows.
+    pub fn report(&self, ticks: RangeInclusive<u64>) -> LedgerReport {
+        report(&self.entries.ticks(ticks.clone()), ticks)
+    }
+
+    /// Return the Merkle root over `tick`'s entries, or `None` if it has
+    /// none; see [`merkle`](crate::merkle).
+    pub fn tick_root(&self, tick: u64) -> Option<Hash> {
+        root(&self.entries_for_tick(tick))
+    }
+
+    /// Return the Merkle root of every tick with entries, for publishing.
+    pub fn tick_roots(&self) -> BTreeMap<u64, Hash> {
+        self.entries
+            .recorded_ticks()
+            .filter_map(|tick| Some((tick, root(&self.entries.tick(tick))?)))
+            .collect()
+    }
+
+    /// Prove that the entry `entry_id` is included under its tick's root,
+    /// or return `None` if no entry has the ID.
+    ///
+    /// The proof is checked with [`verify_proof`](crate::merkle::verify_proof).
+    pub fn prove_entry(&self, entry_id: LedgerEntryId) -> Option<MerkleProof> {
+        let tick = self.entries.find(entry_id)?.tick;
+        prove(&self.entries_for_tick(tick), entry_id)
+    }
+
+    /// Write every entry as CSV; see [`export`](crate::export).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failed write.
+    pub fn export_csv<W: std::io::Write>(&self, out: W) -> std::io::Result<()> {
+        write_csv(self.entries.iter(), out)
+    }
 
-        balance
+    /// Write every entry as a double-entry journal; see
+    /// [`export`](crate::export).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failed write.
+    pub fn export_journal<W: std::io::Write>(&self, out: W) -> std::io::Result<()> {
+        write_journal(self.entries.iter(), out)
     }
 
     /// Calculate net resource flow for a specific tick.
     ///
     /// Returns a map of (resource, net change) for the given tick.
-    /// Positive means net inflow (regeneration exceeds consumption and decay),
-    /// negative means net outflow.
+    /// Positive means net inflow (regeneration and minting exceed
+    /// consumption, decay, and burning), negative means net outflow. A
+    /// reversed flow is netted out of its original's tick.
     pub fn net_flow_for_tick(&self, tick: u64) -> BTreeMap<Resource, Decimal> {
         let mut flows: BTreeMap<Resource, Decimal> = BTreeMap::new();
 
-        for entry in &self.entries {
-            if entry.tick != tick {
+        let entries = self.entries_with_reversals(tick);
+        for entry in &entries {
+            let (counted, reversed) = original_of(entry, &entries)
+                .map_or((entry, false), |original| (original, true));
+            if counted.tick != tick {
                 continue;
             }
 
-            match entry.entry_type {
-                LedgerEntryType::Regeneration => {
+            match counted.entry_type {
+                LedgerEntryType::Regeneration
+                | LedgerEntryType::Mint
+                | LedgerEntryType::Breeding => {
                     let v = flows.entry(entry.resource).or_insert(Decimal::ZERO);
-                    *v = v.saturating_add(entry.quantity);
+                    *v = if reversed {
+                        v.saturating_sub(entry.quantity)
+                    } else {
+                        v.saturating_add(entry.quantity)
+                    };
                 }
-                LedgerEntryType::Consume | LedgerEntryType::Decay => {
+                LedgerEntryType::Consume
+                | LedgerEntryType::Decay
+                | LedgerEntryType::Burn
+                | LedgerEntryType::Slaughter => {
                     let v = flows.entry(entry.resource).or_insert(Decimal::ZERO);
-                    *v = v.saturating_sub(entry.quantity);
+                    *v = if reversed {
+                        v.saturating_add(entry.quantity)
+                    } else {
+                        v.saturating_sub(entry.quantity)
+                    };
                 }
                 // Internal movements do not change the total resource count.
                 LedgerEntryType::Gather
@@ -474,7 +1341,21 @@ impl Ledger {
                 | LedgerEntryType::Drop
                 | LedgerEntryType::Pickup
                 | LedgerEntryType::Theft
-                | LedgerEntryType::CombatLoot => {}
+                | LedgerEntryType::CombatLoot
+                | LedgerEntryType::Deposit
+                | LedgerEntryType::Withdrawal
+                | LedgerEntryType::Checkpoint
+                | LedgerEntryType::Tax
+                | LedgerEntryType::Tribute
+                | LedgerEntryType::LoanIssued
+                | LedgerEntryType::LoanRepayment
+                | LedgerEntryType::InterestAccrued
+                | LedgerEntryType::Escrow
+                | LedgerEntryType::EscrowRelease
+                | LedgerEntryType::EnergySpent
+                | LedgerEntryType::EnergyRecovered
+                | LedgerEntryType::Correction
+                | LedgerEntryType::Reversal => {}
             }
         }
 
@@ -482,6 +1363,14 @@ impl Ledger {
     }
 }
 
+/// Count `result` towards [`metrics::ANOMALIES`] and pass it through.
+fn count_anomaly(result: ConservationResult) -> ConservationResult {
+    if matches!(result, ConservationResult::Anomaly(_)) {
+        metrics::ANOMALIES.increment(1);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,6 +1396,7 @@ mod tests {
             to_agent,
             reason: "TRADE".to_owned(),
             reference_id: None,
+            tags: Vec::new(),
         }
     }
 
@@ -543,6 +1433,44 @@ mod tests {
         assert_eq!(result, ConservationResult::Balanced);
     }
 
+    #[test]
+    fn deposits_and_withdrawals_balance_a_granary() {
+        let mut ledger = Ledger::new();
+        let (agent, granary) = (id(), id());
+        let fish = Resource::FoodFish;
+
+        let _ = ledger.record_deposit(1, fish, Decimal::new(8, 0), agent, granary);
+        let _ = ledger.record_withdrawal(1, fish, Decimal::new(3, 0), granary, agent);
+
+        assert_eq!(ledger.entity_balance(granary, fish), Decimal::new(5, 0));
+        assert_eq!(ledger.verify_conservation_strict(1), ConservationResult::Balanced);
+        assert!(!ledger.net_flow_for_tick(1).contains_key(&fish));
+    }
+
+    #[test]
+    fn taxes_and_tribute_move_between_treasuries() {
+        let mut ledger = Ledger::new();
+        let (agent, group, overlord) = (id(), id(), id());
+        let stone = Resource::Stone;
+        let levy = Some(id());
+
+        let _ = ledger.record_tax(1, stone, Decimal::new(6, 0), agent, group, levy);
+        let tribute = ledger.record_tribute(1, stone, Decimal::new(4, 0), group, overlord, levy);
+
+        assert_eq!(tribute.ok().and_then(|e| e.reference_id), levy);
+        assert_eq!(ledger.entity_balance(group, stone), Decimal::TWO);
+        assert_eq!(ledger.entity_balance(overlord, stone), Decimal::new(4, 0));
+        assert_eq!(ledger.verify_conservation_strict(1), ConservationResult::Balanced);
+        assert!(!ledger.net_flow_for_tick(1).contains_key(&stone));
+        let from_agent = TransactionBuilder::new(1, LedgerEntryType::Tribute, stone)
+            .from(agent, EntityType::Agent)
+            .to(group, EntityType::Group)
+            .quantity(Decimal::ONE)
+            .reason("TRIBUTE".to_owned())
+            .build();
+        assert!(matches!(from_agent, Err(LedgerError::InvalidEntityType { side: "from", .. })));
+    }
+
     #[test]
     fn regeneration_adds_to_world_total() {
         let mut ledger = Ledger::new();
@@ -583,6 +1511,29 @@ mod tests {
         assert_eq!(result, ConservationResult::Balanced);
     }
 
+    #[test]
+    fn breeding_and_slaughter_move_livestock_in_and_out() {
+        let mut ledger = Ledger::new();
+        let world = id();
+        let agent = id();
+        let void = id();
+
+        let _ = ledger.record_breeding(1, Resource::Livestock, Decimal::new(3, 0), world, agent);
+        let _ = ledger.record_slaughter(1, Resource::Livestock, Decimal::new(1, 0), agent, void);
+
+        let flows = ledger.net_flow_for_tick(1);
+        assert_eq!(
+            flows
+                .get(&Resource::Livestock)
+                .copied()
+                .unwrap_or(Decimal::ZERO),
+            Decimal::new(2, 0),
+        );
+
+        let result = ledger.verify_conservation(1);
+        assert_eq!(result, ConservationResult::Balanced);
+    }
+
     #[test]
     fn multi_resource_tick_balances() {
         let mut ledger = Ledger::new();
@@ -649,6 +1600,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn batch_records_all_entries_or_none() {
+        let mut ledger = Ledger::new();
+        let (agent_a, agent_b) = (id(), id());
+        let transfer = |resource, quantity| {
+            TransactionBuilder::new(1, LedgerEntryType::Transfer, resource)
+                .from(agent_a, EntityType::Agent)
+                .to(agent_b, EntityType::Agent)
+                .quantity(Decimal::new(quantity, 0))
+                .reason("TRADE".to_owned())
+        };
+
+        let batch = TransactionBuilder::batch()
+            .entry(transfer(Resource::Wood, 2))
+            .entry(transfer(Resource::Stone, 0));
+        assert!(ledger.record_batch(batch).is_err());
+        assert!(ledger.is_empty());
+        assert_eq!(ledger.entity_balance(agent_b, Resource::Wood), Decimal::ZERO);
+
+        let batch = TransactionBuilder::batch()
+            .entry(transfer(Resource::Wood, 2))
+            .entry(transfer(Resource::Stone, 1));
+        assert_eq!(ledger.record_batch(batch).map(|entries| entries.len()).ok(), Some(2));
+        assert_eq!(ledger.entity_balance(agent_b, Resource::Stone), Decimal::ONE);
+        assert_eq!(ledger.verify_conservation(1), ConservationResult::Balanced);
+    }
+
     #[test]
     fn zero_quantity_rejected_via_ledger() {
         let mut ledger = Ledger::new();
@@ -745,4 +1723,73 @@ mod tests {
         let result = ledger.verify_conservation_strict(1);
         assert_eq!(result, ConservationResult::Balanced);
     }
+
+    #[test]
+    fn reverse_undoes_an_entry_once() {
+        let mut ledger = Ledger::new();
+        let (location, agent) = (id(), id());
+        let gather = ledger.record_gather(1, Resource::Wood, Decimal::new(5, 0), location, agent);
+        let gather_id = gather.map(|e| e.id).unwrap_or_default();
+
+        let reversal = ledger.reverse(gather_id, 2, "CONFLICT_OVERTURNED".to_owned());
+        assert_eq!(
+            reversal.ok().map(|r| (r.from_entity, r.to_entity, r.reference_id)),
+            Some((Some(agent), Some(location), Some(gather_id.into_inner())))
+        );
+        assert!(ledger.is_reversed(gather_id));
+        assert_eq!(ledger.balance(agent, Resource::Wood, 1), Decimal::new(5, 0));
+        assert_eq!(ledger.entity_balance(agent, Resource::Wood), Decimal::ZERO);
+        assert_eq!(ledger.verify_conservation(1), ConservationResult::Balanced);
+        assert_eq!(ledger.verify_conservation(2), ConservationResult::Balanced);
+
+        assert!(matches!(
+            ledger.reverse(gather_id, 2, "AGAIN".to_owned()),
+            Err(LedgerError::AlreadyReversed(_))
+        ));
+        let reversal_id = ledger.all_entries().last().map(|e| e.id).unwrap_or_default();
+        assert!(matches!(
+            ledger.reverse(reversal_id, 2, "AGAIN".to_owned()),
+            Err(LedgerError::NotReversible(LedgerEntryType::Reversal))
+        ));
+        assert!(matches!(
+            ledger.reverse(LedgerEntryId::new(), 2, "MISSING".to_owned()),
+            Err(LedgerError::EntryNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn reversed_flows_net_out_of_the_original_tick() {
+        let mut ledger = Ledger::new();
+        let (world, location) = (id(), id());
+        let _ = ledger.record_regeneration(1, Resource::Wood, Decimal::new(10, 0), world, location);
+        let regen = ledger.record_regeneration(1, Resource::Wood, Decimal::TWO, world, location);
+        let regen_id = regen.map(|e| e.id).unwrap_or_default();
+        let _ = ledger.reverse(regen_id, 4, "REGENERATION_OVERTURNED".to_owned());
+
+        let flows = ledger.net_flow_for_tick(1);
+        assert_eq!(flows.get(&Resource::Wood), Some(&Decimal::new(10, 0)));
+        assert!(ledger.net_flow_for_tick(4).is_empty());
+        assert_eq!(ledger.verify_conservation_strict(1), ConservationResult::Balanced);
+        assert_eq!(ledger.verify_conservation_strict(4), ConservationResult::Balanced);
+    }
+
+    #[test]
+    fn tagged_entries_are_grouped_with_their_reversals() {
+        let mut ledger = Ledger::new();
+        let (agent_a, agent_b) = (id(), id());
+        let trade_tag = LedgerTag::Trade(emergence_types::TradeId::new());
+        let mut params = trade(1, Resource::Wood, Decimal::new(5, 0), agent_a, agent_b);
+        params.tags = vec![trade_tag, trade_tag];
+        let tagged = ledger.record_agent_transfer(params).map(|e| e.id).unwrap_or_default();
+        let untagged = trade(1, Resource::Stone, Decimal::ONE, agent_b, agent_a);
+        let _ = ledger.record_agent_transfer(untagged);
+        let _ = ledger.reverse(tagged, 2, "TRADE_VOIDED".to_owned());
+
+        let group = ledger.entries_tagged(trade_tag);
+        assert_eq!(group.len(), 2);
+        assert_eq!(group.first().map(|e| e.tags.clone()), Some(vec![trade_tag]));
+        assert_eq!(group.get(1).map(|e| e.entry_type), Some(LedgerEntryType::Reversal));
+        let rule_tag = LedgerTag::Rule(emergence_types::RuleId::new());
+        assert!(ledger.entries_tagged(rule_tag).is_empty());
+    }
 }
@@ -7,11 +7,26 @@
 //!
 //! # Architecture
 //!
-//! The ledger crate provides three modules:
+//! The ledger crate provides seventeen modules:
 //!
-//! - [`ledger`] -- The [`Ledger`] struct: append-only log with recording methods.
-//! - [`transaction`] -- The [`TransactionBuilder`] for validated entry construction.
+//! - [`ledger`] -- The [`Ledger`] struct: log with recording methods and checkpoint pruning.
+//! - [`store`] -- The columnar [`EntryStore`] the ledger keeps its entries in.
+//! - [`balance`] -- Point-in-time balances per entity, indexed as entries are appended.
+//! - [`transaction`] -- The [`TransactionBuilder`] for validated entry construction, and
+//!   the all-or-nothing [`TransactionBatch`].
 //! - [`conservation`] -- Conservation law verification and anomaly detection.
+//! - [`diagnosis`] -- Candidate causes of an anomaly and suggested corrections.
+//! - [`reconcile`] -- End-of-tick comparison of ledger balances with the world state.
+//! - [`currency`] -- Mint and burn rules and the money supply of adopted currencies.
+//! - [`debt`] -- Loans between agents and the principal outstanding on them.
+//! - [`prune`] -- Collapsing old entries into opening-balance checkpoints.
+//! - [`snapshot`] -- Opening-balance snapshots for restarting a ledger without replaying it.
+//! - [`energy`] -- The optional [`EnergyLedger`] auditing agent energy the same way.
+//! - [`report`] -- Per-resource and per-entity totals over a range of ticks.
+//! - [`analytics`] -- Wealth distribution: Gini coefficient, percentiles, concentration.
+//! - [`export`] -- CSV and double-entry journal exports for accounting tools.
+//! - [`merkle`] -- Per-tick Merkle roots and inclusion proofs for auditing single entries.
+//! - [`metrics`] -- Entry and anomaly counters for the Observer's `/metrics` endpoint.
 //!
 //! # Conservation Law
 //!
@@ -43,6 +58,20 @@
 //! | Decay | Structure | Void |
 //! | Drop | Agent | Location |
 //! | Pickup | Location | Agent |
+//! | Deposit | Agent | Structure |
+//! | Withdrawal | Structure | Agent |
+//! | Tax | Agent | Group |
+//! | Tribute | Group | Group |
+//! | `LoanIssued` | Agent | Agent |
+//! | `LoanRepayment` | Agent | Agent |
+//! | `InterestAccrued` | Agent | Agent |
+//! | Escrow | Agent | Escrow |
+//! | `EscrowRelease` | Escrow | Agent |
+//! | Checkpoint | Any | Any |
+//! | Correction | Any | Any |
+//! | Reversal | Any | Any |
+//! | Mint | World | Agent |
+//! | Burn | Agent | Void |
 //!
 //! # Usage
 //!
@@ -70,20 +99,45 @@
 //! assert_eq!(ledger.verify_conservation(1), ConservationResult::Balanced);
 //! ```
 
+pub mod analytics;
+pub mod balance;
 pub mod conservation;
+pub mod currency;
+pub mod debt;
+pub mod diagnosis;
+pub mod energy;
+pub mod export;
 pub mod ledger;
+pub mod merkle;
+pub mod metrics;
+pub mod prune;
+pub mod reconcile;
+pub mod report;
+pub mod snapshot;
+pub mod store;
 pub mod transaction;
 
 // Re-export primary types at crate root.
-pub use conservation::ConservationResult;
+pub use analytics::{ResourceConcentration, WealthDistribution};
+pub use balance::BalanceIndex;
+pub use conservation::{ConservationResult, ConservationTracker};
+pub use currency::CurrencyRegistry;
+pub use debt::DebtRegistry;
+pub use diagnosis::{Diagnosis, ResourceDiagnosis};
+pub use energy::{EnergyAnomaly, EnergyEntry, EnergyLedger};
 pub use ledger::{AgentTransferParams, Ledger, TransferParams};
-pub use transaction::TransactionBuilder;
+pub use merkle::{verify_proof, MerkleProof};
+pub use prune::PruneReport;
+pub use reconcile::{ReconciliationMismatch, WorldHoldings};
+pub use report::{EntityFlows, LedgerReport, ResourceTotals};
+pub use store::EntryStore;
+pub use transaction::{TransactionBatch, TransactionBuilder};
 
 use std::collections::BTreeMap;
 
 use rust_decimal::Decimal;
 
-use emergence_types::{LedgerEntryType, Resource};
+use emergence_types::{LedgerEntryId, LedgerEntryType, Resource};
 
 // ---------------------------------------------------------------------------
 // Error types
@@ -123,6 +177,58 @@ pub enum LedgerError {
         actual: String,
     },
 
+    /// An entry of a [`TransactionBatch`] failed validation, so none of
+    /// the batch was recorded.
+    #[error("batch entry {index} is invalid: {source}")]
+    BatchEntry {
+        /// Position of the entry in the batch.
+        index: usize,
+        /// Why the entry is invalid.
+        source: Box<Self>,
+    },
+
+    /// Only [`LedgerEntryType::Correction`] entries can be applied as
+    /// corrections.
+    #[error("{0:?} entry is not a correction")]
+    NotACorrection(LedgerEntryType),
+
+    /// No entry with the ID has been recorded.
+    #[error("no ledger entry {0}")]
+    EntryNotFound(LedgerEntryId),
+
+    /// Checkpoints and reversals cannot be reversed.
+    #[error("{0:?} entry cannot be reversed")]
+    NotReversible(LedgerEntryType),
+
+    /// The entry has already been reversed.
+    #[error("ledger entry {0} is already reversed")]
+    AlreadyReversed(LedgerEntryId),
+
+    /// A loan repayment exceeds the principal its debtor owes its creditor.
+    #[error("repayment of {repaid} {resource:?} exceeds the {outstanding} outstanding")]
+    RepaymentExceedsPrincipal {
+        /// The resource repaid.
+        resource: Resource,
+        /// The quantity repaid.
+        repaid: Decimal,
+        /// The principal outstanding.
+        outstanding: Decimal,
+    },
+
+    /// Only adopted currencies can be minted or burned.
+    #[error("{0:?} is not a currency")]
+    NotACurrency(Resource),
+
+    /// An adopted currency is only created by minting and destroyed by
+    /// burning.
+    #[error("{entry_type:?} entries cannot create or destroy currency {resource:?}")]
+    CurrencySourceOrSink {
+        /// The entry type that was rejected.
+        entry_type: LedgerEntryType,
+        /// The currency.
+        resource: Resource,
+    },
+
     /// An internal error that should not occur in normal operation.
     #[error("internal ledger error: {0}")]
     InternalError(&'static str),
@@ -144,6 +250,8 @@ pub struct LedgerAnomaly {
     /// Per-resource imbalance: (`debit_total`, `credit_total`) for each
     /// resource that did not balance.
     pub imbalances: BTreeMap<Resource, (Decimal, Decimal)>,
+    /// The first entry that broke the balance, when it is known.
+    pub entry_id: Option<LedgerEntryId>,
     /// Human-readable description of the anomaly.
     pub message: String,
 }
@@ -0,0 +1,198 @@
+//! Merkle roots and inclusion proofs over each tick's entries.
+//!
+//! A research run publishes one root per tick from [`Ledger::tick_roots`].
+//! Anyone holding a single entry and its [`MerkleProof`] can then check,
+//! with [`verify_proof`], that the entry is part of the published ledger
+//! exactly as given -- without the rest of the tick's entries.
+//!
+//! A tick's tree is built over its entries in insertion order. Each leaf is
+//! the SHA-256 of a `0x00` byte followed by the entry's CSV row from
+//! [`export`](crate::export) without its final `created_at` column, which
+//! the database rounds. Each inner node is the SHA-256 of a `0x01` byte
+//! followed by its two children; the distinct prefixes keep a leaf from
+//! passing as a node. A level with an odd number of nodes promotes its
+//! last node to the next level unchanged.
+//!
+//! Pruning replaces old entries with checkpoints, so roots of pruned ticks
+//! should be published before the ledger is pruned.
+//!
+//! [`Ledger::tick_roots`]: crate::Ledger::tick_roots
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use emergence_types::{LedgerEntry, LedgerEntryId};
+
+use crate::export::csv_fields;
+
+/// A SHA-256 hash in a tick's tree.
+pub type Hash = [u8; 32];
+
+/// Evidence that an entry is included in its tick's tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The proven entry.
+    pub entry_id: LedgerEntryId,
+    /// The entry's tick, whose root the proof leads to.
+    pub tick: u64,
+    /// Position of the entry among the tick's entries.
+    pub index: usize,
+    /// Number of entries in the tick.
+    pub leaves: usize,
+    /// Sibling hashes on the path from the entry's leaf to the root,
+    /// bottom up. Levels where the path's node was promoted have none.
+    pub siblings: Vec<Hash>,
+}
+
+/// The leaf hash of `entry`.
+pub fn leaf_hash(entry: &LedgerEntry) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(csv_fields(entry).as_bytes());
+    hasher.finalize().into()
+}
+
+/// The hash of an inner node with children `left` and `right`.
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The level above `level`: each pair hashed, an odd last node promoted.
+fn parent_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [only, ..] => *only,
+            [] => Hash::default(),
+        })
+        .collect()
+}
+
+/// The root of the tree over `entries`, or `None` if there are none.
+pub fn root(entries: &[LedgerEntry]) -> Option<Hash> {
+    let mut level: Vec<Hash> = entries.iter().map(leaf_hash).collect();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level.first().copied()
+}
+
+/// A proof that the entry `entry_id` is included in the tree over
+/// `entries`, or `None` if it is not one of them.
+pub fn prove(entries: &[LedgerEntry], entry_id: LedgerEntryId) -> Option<MerkleProof> {
+    let index = entries.iter().position(|e| e.id == entry_id)?;
+    let tick = entries.get(index)?.tick;
+    let mut level: Vec<Hash> = entries.iter().map(leaf_hash).collect();
+    let mut position = index;
+    let mut siblings = Vec::new();
+    while level.len() > 1 {
+        let sibling = if position % 2 == 0 {
+            position.checked_add(1)
+        } else {
+            position.checked_sub(1)
+        };
+        if let Some(hash) = sibling.and_then(|s| level.get(s)) {
+            siblings.push(*hash);
+        }
+        level = parent_level(&level);
+        position /= 2;
+    }
+    Some(MerkleProof {
+        entry_id,
+        tick,
+        index,
+        leaves: entries.len(),
+        siblings,
+    })
+}
+
+/// Check that `proof` shows `entry` is included in the tree with `root`.
+///
+/// Fails if the entry differs in any hashed field from the one proven, or
+/// if the proof is not for this entry.
+pub fn verify_proof(entry: &LedgerEntry, proof: &MerkleProof, root: &Hash) -> bool {
+    if (entry.id, entry.tick) != (proof.entry_id, proof.tick) || proof.index >= proof.leaves {
+        return false;
+    }
+    let mut hash = leaf_hash(entry);
+    let (mut position, mut width) = (proof.index, proof.leaves);
+    let mut siblings = proof.siblings.iter();
+    while width > 1 {
+        if position % 2 == 1 {
+            let Some(left) = siblings.next() else {
+                return false;
+            };
+            hash = node_hash(left, &hash);
+        } else if position.saturating_add(1) < width {
+            let Some(right) = siblings.next() else {
+                return false;
+            };
+            hash = node_hash(&hash, right);
+        }
+        position /= 2;
+        width = width.div_ceil(2);
+    }
+    siblings.next().is_none() && hash == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    use emergence_types::Resource;
+
+    use super::*;
+    use crate::Ledger;
+
+    fn ledger_with_gathers(count: i64) -> Ledger {
+        let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = Ledger::new();
+        for quantity in 1..=count {
+            let wood = Decimal::new(quantity, 0);
+            let _ = ledger.record_gather(4, Resource::Wood, wood, location, agent);
+        }
+        ledger
+    }
+
+    #[test]
+    fn every_entry_proves_against_its_tick_root() {
+        for count in 1..=7 {
+            let ledger = ledger_with_gathers(count);
+            let root = ledger.tick_root(4).unwrap_or_default();
+            for entry in ledger.all_entries() {
+                let proof = ledger.prove_entry(entry.id);
+                assert!(proof.is_some_and(|p| verify_proof(&entry, &p, &root)));
+            }
+        }
+        assert_eq!(ledger_with_gathers(3).tick_root(5), None);
+    }
+
+    #[test]
+    fn altered_entries_and_proofs_fail() {
+        let ledger = ledger_with_gathers(5);
+        let root = ledger.tick_roots().get(&4).copied().unwrap_or_default();
+        let entries = ledger.all_entries();
+        let proven = entries.get(2).and_then(|e| Some((e, ledger.prove_entry(e.id)?)));
+        assert!(proven.is_some());
+        let Some((entry, proof)) = proven else {
+            return;
+        };
+        assert!(verify_proof(entry, &proof, &root));
+
+        let mut inflated = entry.clone();
+        inflated.quantity = Decimal::ONE_HUNDRED;
+        assert!(!verify_proof(&inflated, &proof, &root));
+        let mut moved = proof.clone();
+        moved.index = 3;
+        assert!(!verify_proof(entry, &moved, &root));
+        let mut truncated = proof;
+        truncated.siblings.pop();
+        assert!(!verify_proof(entry, &truncated, &root));
+    }
+}
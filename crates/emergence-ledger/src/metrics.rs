@@ -0,0 +1,23 @@
+//! Ledger metrics, exported through the Observer's `/metrics` endpoint
+//! (see [`emergence_metrics`]).
+
+use emergence_metrics::Counter;
+
+/// Ledger entries recorded, by entry type.
+pub static ENTRIES: Counter = Counter::labeled(
+    "emergence_ledger_entries_total",
+    "Ledger entries recorded, by entry type.",
+    "entry_type",
+);
+
+/// Conservation checks that found an anomaly.
+pub static ANOMALIES: Counter = Counter::new(
+    "emergence_ledger_anomalies_total",
+    "Conservation checks that found an imbalance.",
+);
+
+/// Resources found by reconciliation to differ from the world state.
+pub static MISMATCHES: Counter = Counter::new(
+    "emergence_ledger_reconciliation_mismatches_total",
+    "Entity resources whose ledger balance differed from the world state.",
+);
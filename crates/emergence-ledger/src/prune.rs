@@ -0,0 +1,223 @@
+//! Pruning old ledger entries into opening-balance checkpoints.
+//!
+//! A long run records a gather or consume entry for nearly every agent on
+//! every tick, and keeping all of them bounds how long a run can go. Pruning
+//! replaces every entry before a cutoff tick with [`Checkpoint`] entries
+//! that carry each entity's net balance of each resource forward, recorded
+//! at the tick just before the cutoff.
+//!
+//! Checkpoints are double-entry like any other entry: each one moves a
+//! quantity from an entity whose carried balance is negative to one whose
+//! balance is positive, so the diminished ledger still conserves every
+//! resource and every entity's balance from the checkpoint tick on is the
+//! same as before pruning. Before the checkpoint tick, balances read as
+//! zero. Entries whose source or destination was left unset carry their
+//! balance against an unset side the same way.
+//!
+//! [`Checkpoint`]: LedgerEntryType::Checkpoint
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use emergence_types::{EntityType, LedgerEntry, LedgerEntryId, LedgerEntryType, Resource};
+
+/// What a prune removed and recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Entries removed from the ledger.
+    pub pruned: usize,
+    /// Checkpoint entries recorded in their place.
+    pub checkpoints: usize,
+}
+
+/// Each entity's net balance of each resource, and the entity types
+/// recorded for them.
+#[derive(Debug, Default)]
+pub(crate) struct CarriedBalances {
+    /// Net balance of each entity, per resource.
+    pub(crate) balances: BTreeMap<Resource, BTreeMap<Uuid, Decimal>>,
+    /// The type of each entity, if any entry recorded it.
+    pub(crate) entity_types: BTreeMap<Uuid, EntityType>,
+}
+
+/// Build checkpoint entries at `tick` carrying forward the net balances
+/// left by `entries`.
+///
+/// Entities whose balance of a resource nets to zero get no checkpoint for
+/// it.
+pub fn checkpoint_entries(entries: &[LedgerEntry], tick: u64) -> Vec<LedgerEntry> {
+    checkpoints(&carried_balances(entries), tick)
+}
+
+/// Net the balances left by `entries`.
+pub(crate) fn carried_balances(entries: &[LedgerEntry]) -> CarriedBalances {
+    let mut balances: BTreeMap<Resource, BTreeMap<Uuid, Decimal>> = BTreeMap::new();
+    let mut entity_types: BTreeMap<Uuid, EntityType> = BTreeMap::new();
+    for entry in entries {
+        let resource = balances.entry(entry.resource).or_default();
+        if let Some(to) = entry.to_entity {
+            let balance = resource.entry(to).or_default();
+            *balance = balance.saturating_add(entry.quantity);
+            if let Some(to_type) = entry.to_entity_type {
+                entity_types.insert(to, to_type);
+            }
+        }
+        if let Some(from) = entry.from_entity {
+            let balance = resource.entry(from).or_default();
+            *balance = balance.saturating_sub(entry.quantity);
+            if let Some(from_type) = entry.from_entity_type {
+                entity_types.insert(from, from_type);
+            }
+        }
+    }
+    CarriedBalances {
+        balances,
+        entity_types,
+    }
+}
+
+/// Build checkpoint entries at `tick` carrying forward `carried`.
+pub(crate) fn checkpoints(carried: &CarriedBalances, tick: u64) -> Vec<LedgerEntry> {
+    let entity_types = &carried.entity_types;
+    let mut checkpoints = Vec::new();
+    for (resource, entities) in &carried.balances {
+        let resource = *resource;
+        let mut checkpoint = |from: Option<Uuid>, to: Option<Uuid>, quantity: Decimal| {
+            checkpoints.push(LedgerEntry {
+                id: LedgerEntryId::new(),
+                tick,
+                entry_type: LedgerEntryType::Checkpoint,
+                from_entity: from,
+                from_entity_type: from.and_then(|id| entity_types.get(&id).copied()),
+                to_entity: to,
+                to_entity_type: to.and_then(|id| entity_types.get(&id).copied()),
+                resource,
+                quantity,
+                reason: "CHECKPOINT".to_owned(),
+                reference_id: None,
+                tags: Vec::new(),
+                created_at: Utc::now(),
+            });
+        };
+
+        // Settle each debtor's carried debt against the creditors, both in
+        // entity order, so pruning the same entries yields the same
+        // checkpoints.
+        let mut debtors = entities
+            .iter()
+            .filter(|(_, balance)| balance.is_sign_negative() && !balance.is_zero())
+            .map(|(id, balance)| (*id, balance.abs()));
+        let mut creditors = entities
+            .iter()
+            .filter(|(_, balance)| balance.is_sign_positive() && !balance.is_zero())
+            .map(|(id, balance)| (*id, *balance));
+        let (mut debt, mut credit) = (debtors.next(), creditors.next());
+        loop {
+            match (debt, credit) {
+                (Some((from, owed)), Some((to, due))) => {
+                    let quantity = owed.min(due);
+                    checkpoint(Some(from), Some(to), quantity);
+                    let owed = owed.saturating_sub(quantity);
+                    let due = due.saturating_sub(quantity);
+                    debt = if owed.is_zero() { debtors.next() } else { Some((from, owed)) };
+                    credit = if due.is_zero() { creditors.next() } else { Some((to, due)) };
+                }
+                (Some((from, owed)), None) => {
+                    checkpoint(Some(from), None, owed);
+                    debt = debtors.next();
+                }
+                (None, Some((to, due))) => {
+                    checkpoint(None, Some(to), due);
+                    credit = creditors.next();
+                }
+                (None, None) => break,
+            }
+        }
+    }
+    checkpoints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conservation::ConservationResult;
+    use crate::{AgentTransferParams, Ledger};
+
+    fn trade(tick: u64, quantity: i64, from_agent: Uuid, to_agent: Uuid) -> AgentTransferParams {
+        AgentTransferParams {
+            tick,
+            resource: Resource::Wood,
+            quantity: Decimal::new(quantity, 0),
+            from_agent,
+            to_agent,
+            reason: "TRADE".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pruning_keeps_balances_and_conservation() {
+        let (world, location, agent_a, agent_b) =
+            (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = Ledger::new().with_retention(5);
+        for tick in 1..=10 {
+            let wood = Resource::Wood;
+            let _ = ledger.record_regeneration(tick, wood, Decimal::TEN, world, location);
+            let _ = ledger.record_gather(tick, wood, Decimal::new(4, 0), location, agent_a);
+            let _ = ledger.record_agent_transfer(trade(tick, 1, agent_a, agent_b));
+        }
+        let before: Vec<Decimal> = [world, location, agent_a, agent_b]
+            .iter()
+            .map(|id| ledger.balance(*id, Resource::Wood, 10))
+            .collect();
+
+        let report = ledger.prune(10);
+        assert_eq!(report.pruned, 12);
+        assert_eq!(ledger.len(), 18_usize.saturating_add(report.checkpoints));
+
+        let after: Vec<Decimal> = [world, location, agent_a, agent_b]
+            .iter()
+            .map(|id| ledger.balance(*id, Resource::Wood, 10))
+            .collect();
+        assert_eq!(before, after);
+        assert_eq!(ledger.balance(agent_a, Resource::Wood, 4), Decimal::new(12, 0));
+        assert_eq!(ledger.balance(agent_a, Resource::Wood, 3), Decimal::ZERO);
+        for tick in 4..=10 {
+            assert_eq!(ledger.verify_conservation(tick), ConservationResult::Balanced);
+        }
+        let carried = ledger.entries_for_tick(4);
+        assert!(carried.iter().all(|e| e.entry_type == LedgerEntryType::Checkpoint));
+
+        assert_eq!(ledger.prune(10), PruneReport::default());
+    }
+
+    #[test]
+    fn unset_sides_carry_against_an_unset_side() {
+        let agent = Uuid::now_v7();
+        let entry = LedgerEntry {
+            id: LedgerEntryId::new(),
+            tick: 1,
+            entry_type: LedgerEntryType::Regeneration,
+            from_entity: None,
+            from_entity_type: None,
+            to_entity: Some(agent),
+            to_entity_type: Some(EntityType::Agent),
+            resource: Resource::Stone,
+            quantity: Decimal::new(3, 0),
+            reason: "REGENERATION".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+        };
+
+        let checkpoints = checkpoint_entries(&[entry], 1);
+        assert_eq!(checkpoints.len(), 1);
+        let checkpoint = checkpoints.first();
+        assert_eq!(checkpoint.map(|c| (c.from_entity, c.to_entity)), Some((None, Some(agent))));
+        assert_eq!(checkpoint.map(|c| c.to_entity_type), Some(Some(EntityType::Agent)));
+    }
+}
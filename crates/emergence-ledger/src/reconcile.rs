@@ -0,0 +1,235 @@
+//! Reconciliation of ledger balances against the world state.
+//!
+//! The conservation law only proves that the ledger agrees with itself. A
+//! system that changes an inventory without recording the move -- or
+//! records a move it never made -- leaves the ledger balanced but wrong.
+//! At the end of a tick, a reconciliation scan compares what each entity
+//! holds according to the ledger with what it actually holds:
+//!
+//! | Entity | World quantity |
+//! |--------|----------------|
+//! | Agent | `AgentState::inventory` |
+//! | Location | `available` of its `ResourceNode`s |
+//! | Structure | `materials_used`, or nothing once destroyed |
+//!
+//! Every difference is reported as a [`ReconciliationMismatch`], the
+//! `RECONCILIATION_MISMATCH` alert, which is kept apart from a
+//! [`LedgerAnomaly`](crate::LedgerAnomaly).
+//!
+//! Only the entities added to the [`WorldHoldings`] are compared, and only
+//! the sources above count towards them; anything else an entity holds,
+//! such as items dropped at a location, is added with
+//! [`WorldHoldings::add`].
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use emergence_types::{
+    AgentState, EntityType, Location, ReconciliationMismatchDetails, Resource, Structure,
+};
+
+/// A resource an entity holds a different quantity of than the ledger says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconciliationMismatch {
+    /// The tick whose end-of-tick state was compared.
+    pub tick: u64,
+    /// The entity whose holdings drifted.
+    pub entity_id: Uuid,
+    /// The type of the entity.
+    pub entity_type: EntityType,
+    /// The resource that drifted.
+    pub resource: Resource,
+    /// The entity's balance according to the ledger.
+    pub ledger_balance: Decimal,
+    /// The quantity the entity actually holds.
+    pub world_quantity: Decimal,
+}
+
+impl ReconciliationMismatch {
+    /// How much more the entity holds than the ledger says; negative if it
+    /// holds less.
+    pub fn drift(&self) -> Decimal {
+        self.world_quantity.saturating_sub(self.ledger_balance)
+    }
+
+    /// The mismatch as the details of a `ReconciliationMismatch` event.
+    pub const fn details(&self) -> ReconciliationMismatchDetails {
+        ReconciliationMismatchDetails {
+            entity_id: self.entity_id,
+            entity_type: self.entity_type,
+            resource: self.resource,
+            ledger_balance: self.ledger_balance,
+            world_quantity: self.world_quantity,
+        }
+    }
+}
+
+impl core::fmt::Display for ReconciliationMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "RECONCILIATION_MISMATCH at tick {}: {:?} {} holds {} {:?}, ledger balance is {}",
+            self.tick,
+            self.entity_type,
+            self.entity_id,
+            self.world_quantity,
+            self.resource,
+            self.ledger_balance,
+        )
+    }
+}
+
+/// What each entity actually holds, gathered from the world state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldHoldings {
+    holdings: BTreeMap<Uuid, (EntityType, BTreeMap<Resource, Decimal>)>,
+}
+
+impl WorldHoldings {
+    /// Create holdings with no entities.
+    pub const fn new() -> Self {
+        Self {
+            holdings: BTreeMap::new(),
+        }
+    }
+
+    /// Add `quantity` of `resource` to what `entity_id` holds, adding the
+    /// entity if it is new.
+    pub fn add(
+        &mut self,
+        entity_id: Uuid,
+        entity_type: EntityType,
+        resource: Resource,
+        quantity: Decimal,
+    ) {
+        let held = self.entity(entity_id, entity_type).entry(resource).or_default();
+        *held = held.saturating_add(quantity);
+    }
+
+    /// Add an agent's inventory.
+    pub fn add_agent(&mut self, state: &AgentState) {
+        let held = self.entity(state.agent_id.into_inner(), EntityType::Agent);
+        add_quantities(held, state.inventory.iter().map(|(r, q)| (*r, *q)));
+    }
+
+    /// Add the resources available at a location's nodes.
+    pub fn add_location(&mut self, location: &Location) {
+        let held = self.entity(location.id.into_inner(), EntityType::Location);
+        let nodes = location.base_resources.values();
+        add_quantities(held, nodes.map(|node| (node.resource, node.available)));
+    }
+
+    /// Add a structure's materials. A destroyed structure holds nothing.
+    pub fn add_structure(&mut self, structure: &Structure) {
+        let held = self.entity(structure.id.into_inner(), EntityType::Structure);
+        if structure.destroyed_at_tick.is_none() {
+            add_quantities(held, structure.materials_used.iter().map(|(r, q)| (*r, *q)));
+        }
+    }
+
+    /// Return the number of entities added.
+    pub fn len(&self) -> usize {
+        self.holdings.len()
+    }
+
+    /// Return whether no entities have been added.
+    pub fn is_empty(&self) -> bool {
+        self.holdings.is_empty()
+    }
+
+    /// The holdings of `entity_id`, added empty if it is new.
+    fn entity(
+        &mut self,
+        entity_id: Uuid,
+        entity_type: EntityType,
+    ) -> &mut BTreeMap<Resource, Decimal> {
+        &mut self.holdings.entry(entity_id).or_insert_with(|| (entity_type, BTreeMap::new())).1
+    }
+}
+
+/// Add whole-unit `quantities` to `held`.
+fn add_quantities(
+    held: &mut BTreeMap<Resource, Decimal>,
+    quantities: impl Iterator<Item = (Resource, u32)>,
+) {
+    for (resource, quantity) in quantities {
+        let total = held.entry(resource).or_default();
+        *total = total.saturating_add(Decimal::from(quantity));
+    }
+}
+
+/// Compare `holdings` with the ledger balances after `tick`, given by
+/// `balances` for each entity, returning every resource that differs.
+pub fn reconcile(
+    tick: u64,
+    holdings: &WorldHoldings,
+    balances: impl Fn(Uuid) -> BTreeMap<Resource, Decimal>,
+) -> Vec<ReconciliationMismatch> {
+    let mut mismatches = Vec::new();
+    for (entity_id, (entity_type, held)) in &holdings.holdings {
+        let ledger = balances(*entity_id);
+        let mut resources: Vec<Resource> = ledger.keys().chain(held.keys()).copied().collect();
+        resources.sort_unstable();
+        resources.dedup();
+        for resource in resources {
+            let ledger_balance = ledger.get(&resource).copied().unwrap_or_default();
+            let world_quantity = held.get(&resource).copied().unwrap_or_default();
+            if ledger_balance != world_quantity {
+                mismatches.push(ReconciliationMismatch {
+                    tick,
+                    entity_id: *entity_id,
+                    entity_type: *entity_type,
+                    resource,
+                    ledger_balance,
+                    world_quantity,
+                });
+            }
+        }
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ledger;
+
+    #[test]
+    fn matching_holdings_reconcile() {
+        let (world, location, agent) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = Ledger::new();
+        let _ = ledger.record_regeneration(1, Resource::Wood, Decimal::TEN, world, location);
+        let _ = ledger.record_gather(1, Resource::Wood, Decimal::new(4, 0), location, agent);
+
+        let mut holdings = WorldHoldings::new();
+        holdings.add(location, EntityType::Location, Resource::Wood, Decimal::new(6, 0));
+        holdings.add(agent, EntityType::Agent, Resource::Wood, Decimal::new(4, 0));
+        holdings.add(agent, EntityType::Agent, Resource::Stone, Decimal::ZERO);
+
+        assert_eq!(holdings.len(), 2);
+        assert!(ledger.reconcile(1, &holdings).is_empty());
+    }
+
+    #[test]
+    fn unrecorded_changes_are_reported() {
+        let (world, location, agent) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = Ledger::new();
+        let _ = ledger.record_regeneration(1, Resource::Wood, Decimal::TEN, world, location);
+        let _ = ledger.record_gather(1, Resource::Wood, Decimal::new(4, 0), location, agent);
+
+        let mut holdings = WorldHoldings::new();
+        holdings.add(location, EntityType::Location, Resource::Wood, Decimal::new(6, 0));
+        holdings.add(agent, EntityType::Agent, Resource::Wood, Decimal::new(3, 0));
+        holdings.add(agent, EntityType::Agent, Resource::Stone, Decimal::TWO);
+
+        let mismatches = ledger.reconcile(1, &holdings);
+        assert_eq!(mismatches.len(), 2);
+        let wood = mismatches.iter().find(|m| m.resource == Resource::Wood);
+        assert_eq!(wood.map(ReconciliationMismatch::drift), Some(Decimal::NEGATIVE_ONE));
+        assert_eq!(wood.map(|m| (m.entity_id, m.entity_type)), Some((agent, EntityType::Agent)));
+        let stone = mismatches.iter().find(|m| m.resource == Resource::Stone);
+        assert_eq!(stone.map(|m| m.details().world_quantity), Some(Decimal::TWO));
+    }
+}
@@ -0,0 +1,141 @@
+//! Aggregated ledger reports over a range of ticks.
+//!
+//! A [`LedgerReport`] totals each resource by entry type and nets each
+//! entity's flows over the range, so consumers such as the Observer's
+//! economy page read a summary instead of iterating raw entries.
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use emergence_types::{EntityType, LedgerEntry, LedgerEntryType, Resource};
+
+/// Totals of the entries recorded over a range of ticks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LedgerReport {
+    /// The first tick of the range.
+    pub from_tick: u64,
+    /// The last tick of the range, inclusive.
+    pub to_tick: u64,
+    /// Number of entries in the range.
+    pub entries: usize,
+    /// Totals of each resource moved in the range.
+    pub resources: BTreeMap<Resource, ResourceTotals>,
+    /// Net flows of each entity that took part in the range.
+    pub entities: BTreeMap<Uuid, EntityFlows>,
+}
+
+/// How much of one resource moved over a report's range.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceTotals {
+    /// Quantity regenerated at locations.
+    pub regenerated: Decimal,
+    /// Quantity gathered by agents.
+    pub gathered: Decimal,
+    /// Quantity consumed by agents.
+    pub consumed: Decimal,
+    /// Quantity lost to structure decay.
+    pub decayed: Decimal,
+    /// Quantity transferred between agents by trade or gift.
+    pub transferred: Decimal,
+    /// Quantity of currency minted.
+    pub minted: Decimal,
+    /// Quantity of currency burned.
+    pub burned: Decimal,
+    /// Quantity moved by every entry type, including those above.
+    pub by_type: BTreeMap<LedgerEntryType, Decimal>,
+}
+
+/// One entity's net flows over a report's range.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EntityFlows {
+    /// The entity's type, if any entry recorded it.
+    pub entity_type: Option<EntityType>,
+    /// Credits minus debits of each resource; negative if the entity gave
+    /// out more than it received.
+    pub net: BTreeMap<Resource, Decimal>,
+}
+
+/// Aggregate the `entries` recorded in `ticks`.
+pub fn report(entries: &[LedgerEntry], ticks: RangeInclusive<u64>) -> LedgerReport {
+    let mut report = LedgerReport {
+        from_tick: *ticks.start(),
+        to_tick: *ticks.end(),
+        ..LedgerReport::default()
+    };
+    for entry in entries.iter().filter(|e| ticks.contains(&e.tick)) {
+        report.entries = report.entries.saturating_add(1);
+
+        let totals = report.resources.entry(entry.resource).or_default();
+        let named = match entry.entry_type {
+            LedgerEntryType::Regeneration => Some(&mut totals.regenerated),
+            LedgerEntryType::Gather => Some(&mut totals.gathered),
+            LedgerEntryType::Consume => Some(&mut totals.consumed),
+            LedgerEntryType::Decay => Some(&mut totals.decayed),
+            LedgerEntryType::Transfer => Some(&mut totals.transferred),
+            LedgerEntryType::Mint => Some(&mut totals.minted),
+            LedgerEntryType::Burn => Some(&mut totals.burned),
+            _ => None,
+        };
+        if let Some(total) = named {
+            *total = total.saturating_add(entry.quantity);
+        }
+        let by_type = totals.by_type.entry(entry.entry_type).or_default();
+        *by_type = by_type.saturating_add(entry.quantity);
+
+        if let Some(to) = entry.to_entity {
+            let flows = report.entities.entry(to).or_default();
+            flows.entity_type = flows.entity_type.or(entry.to_entity_type);
+            let net = flows.net.entry(entry.resource).or_default();
+            *net = net.saturating_add(entry.quantity);
+        }
+        if let Some(from) = entry.from_entity {
+            let flows = report.entities.entry(from).or_default();
+            flows.entity_type = flows.entity_type.or(entry.from_entity_type);
+            let net = flows.net.entry(entry.resource).or_default();
+            *net = net.saturating_sub(entry.quantity);
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ledger;
+
+    #[test]
+    fn report_totals_only_the_range() {
+        let (world, location, agent, void) =
+            (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let mut ledger = Ledger::new();
+        for tick in 1..=4 {
+            let wood = Resource::Wood;
+            let _ = ledger.record_regeneration(tick, wood, Decimal::TEN, world, location);
+            let _ = ledger.record_gather(tick, wood, Decimal::new(4, 0), location, agent);
+            let _ = ledger.record_consumption(tick, wood, Decimal::ONE, agent, void);
+        }
+
+        let report = ledger.report(2..=3);
+        assert_eq!((report.from_tick, report.to_tick, report.entries), (2, 3, 6));
+        let wood = report.resources.get(&Resource::Wood);
+        assert_eq!(wood.map(|w| w.regenerated), Some(Decimal::new(20, 0)));
+        assert_eq!(wood.map(|w| w.gathered), Some(Decimal::new(8, 0)));
+        assert_eq!(wood.map(|w| w.consumed), Some(Decimal::new(2, 0)));
+        assert_eq!(wood.map(|w| w.transferred), Some(Decimal::ZERO));
+        let consumed = wood.and_then(|w| w.by_type.get(&LedgerEntryType::Consume));
+        assert_eq!(consumed, Some(&Decimal::new(2, 0)));
+
+        let agent_flows = report.entities.get(&agent);
+        assert_eq!(agent_flows.and_then(|f| f.entity_type), Some(EntityType::Agent));
+        let agent_net = agent_flows.and_then(|f| f.net.get(&Resource::Wood));
+        assert_eq!(agent_net, Some(&Decimal::new(6, 0)));
+        let location_net = report.entities.get(&location).and_then(|f| f.net.get(&Resource::Wood));
+        assert_eq!(location_net, Some(&Decimal::new(12, 0)));
+
+        assert_eq!(ledger.report(9..=12).entries, 0);
+    }
+}
@@ -0,0 +1,109 @@
+//! Opening-balance snapshots for restarting a ledger.
+//!
+//! Resuming a run would otherwise mean replaying every entry ever recorded,
+//! just to know each entity's balances and to resume conservation checking.
+//! A [`LedgerSnapshot`] taken with [`Ledger::snapshot`] at the end of a tick
+//! records instead each entity's net balance of each resource, the money
+//! supply of each adopted currency, and the principal outstanding on each
+//! loan. It is persisted through the `SnapshotStore` in `emergence-db`.
+//!
+//! [`Ledger::from_snapshot`] starts a ledger from one. The balances are
+//! carried forward as [`Checkpoint`] entries at the snapshot tick, built the
+//! same way as when [`prune`](crate::prune) collapses old entries, so the
+//! restored ledger conserves every resource and every entity's balance from
+//! the snapshot tick on is the same as before the restart. Entries of later
+//! ticks are then recorded or appended as usual.
+//!
+//! [`Ledger::snapshot`]: crate::Ledger::snapshot
+//! [`Ledger::from_snapshot`]: crate::Ledger::from_snapshot
+//! [`Checkpoint`]: emergence_types::LedgerEntryType::Checkpoint
+
+use emergence_types::{LedgerEntry, LedgerSnapshot, OpeningBalance};
+
+use crate::prune::{carried_balances, checkpoints, CarriedBalances};
+
+/// The opening balances left by `entries`, in resource and entity order.
+///
+/// Entities whose balance of a resource nets to zero are left out.
+pub fn opening_balances(entries: &[LedgerEntry]) -> Vec<OpeningBalance> {
+    let carried = carried_balances(entries);
+    let mut balances = Vec::new();
+    for (resource, entities) in &carried.balances {
+        for (entity_id, balance) in entities {
+            if !balance.is_zero() {
+                balances.push(OpeningBalance {
+                    entity_id: *entity_id,
+                    entity_type: carried.entity_types.get(entity_id).copied(),
+                    resource: *resource,
+                    balance: *balance,
+                });
+            }
+        }
+    }
+    balances
+}
+
+/// The checkpoint entries at `snapshot.tick` that carry its balances
+/// forward.
+pub fn opening_entries(snapshot: &LedgerSnapshot) -> Vec<LedgerEntry> {
+    let mut carried = CarriedBalances::default();
+    for opening in &snapshot.balances {
+        let balances = carried.balances.entry(opening.resource).or_default();
+        let balance = balances.entry(opening.entity_id).or_default();
+        *balance = balance.saturating_add(opening.balance);
+        if let Some(entity_type) = opening.entity_type {
+            carried.entity_types.insert(opening.entity_id, entity_type);
+        }
+    }
+    checkpoints(&carried, snapshot.tick)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    use emergence_types::{EntityType, Resource};
+
+    use crate::conservation::ConservationResult;
+    use crate::Ledger;
+
+    #[test]
+    fn restored_ledger_keeps_balances_registries_and_conservation() {
+        let (world, location, lender, borrower) =
+            (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let (wood, token) = (Resource::Wood, Resource::CurrencyToken);
+        let mut ledger = Ledger::new();
+        ledger.adopt_currency(token);
+        for tick in 1..=4 {
+            let _ = ledger.record_regeneration(tick, wood, Decimal::TEN, world, location);
+            let _ = ledger.record_gather(tick, wood, Decimal::new(6, 0), location, lender);
+        }
+        let _ = ledger.record_mint(4, token, Decimal::TEN, world, lender);
+        let _ = ledger.record_loan(4, wood, Decimal::new(5, 0), lender, borrower, None);
+
+        let snapshot = ledger.snapshot(4);
+        assert_eq!(snapshot.tick, 4);
+        let restored = Ledger::from_snapshot(&snapshot);
+        assert!(restored.is_ok());
+        let Ok(mut restored) = restored else {
+            return;
+        };
+
+        for entity in [world, location, lender, borrower] {
+            assert_eq!(restored.balances(entity, u64::MAX), ledger.balances(entity, u64::MAX));
+        }
+        let opening = snapshot.balances.iter().find(|b| b.entity_id == location);
+        assert_eq!(opening.and_then(|b| b.entity_type), Some(EntityType::Location));
+        assert_eq!(restored.money_supply(token), Decimal::TEN);
+        assert_eq!(restored.outstanding_principal(lender, borrower, wood), Decimal::new(5, 0));
+        assert_eq!(restored.verify_conservation(4), ConservationResult::Balanced);
+
+        let _ = restored.record_gather(5, wood, Decimal::TWO, location, borrower);
+        let repaid = restored.record_loan_repayment(5, wood, Decimal::TWO, borrower, lender, None);
+        assert!(repaid.is_ok());
+        assert!(restored.record_mint(5, token, Decimal::ONE, world, borrower).is_ok());
+        assert_eq!(restored.verify_conservation(5), ConservationResult::Balanced);
+        assert_eq!(restored.entity_balance(location, wood), Decimal::new(14, 0));
+    }
+}
@@ -0,0 +1,395 @@
+//! Columnar in-memory storage for ledger entries.
+//!
+//! A long run records millions of entries, and a [`LedgerEntry`] spends
+//! most of its size on values that repeat: the same few entities on either
+//! side, the same handful of reasons, and a reference that is usually
+//! unset. An [`EntryStore`] keeps each field in its own array, one row per
+//! entry, with:
+//!
+//! - each side's entity and entity type interned into a shared table and
+//!   stored as a 4-byte index,
+//! - reasons interned the same way,
+//! - references and tags kept only for the rows that have them.
+//!
+//! That stores an entry, indexes included, in about half the space of the
+//! struct and its reason string. Indexes from tick, entity, resource, and
+//! tag to rows answer a tick's, an entity's, a resource's, or a tag's
+//! entries without a scan. Entries are materialized back into [`LedgerEntry`] values when
+//! they are read. An index from ID to row, and the set of reversed IDs,
+//! answer lookups by ID and reversal checks the same way.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeBounds;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use emergence_types::{
+    EntityType, LedgerEntry, LedgerEntryId, LedgerEntryType, LedgerTag, Resource,
+};
+
+use crate::LedgerError;
+
+/// The position of an entry in an [`EntryStore`], in insertion order.
+type Row = u32;
+
+/// One side of an entry: an entity and its type, either of which may be
+/// unset.
+type Side = (Option<Uuid>, Option<EntityType>);
+
+/// A table of distinct values, each stored once and referred to by index.
+#[derive(Debug, Default)]
+struct Interner<T> {
+    values: Vec<T>,
+    indexes: BTreeMap<T, u32>,
+}
+
+impl<T: Ord + Clone> Interner<T> {
+    /// Create an empty table.
+    const fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            indexes: BTreeMap::new(),
+        }
+    }
+
+    /// The index of `value`, adding it if it is new, or `None` if the table
+    /// is full.
+    fn intern(&mut self, value: &T) -> Option<u32> {
+        if let Some(index) = self.indexes.get(value) {
+            return Some(*index);
+        }
+        let index = u32::try_from(self.values.len()).ok()?;
+        self.values.push(value.clone());
+        self.indexes.insert(value.clone(), index);
+        Some(index)
+    }
+
+    /// The value at `index`.
+    fn get(&self, index: u32) -> Option<&T> {
+        self.values.get(usize::try_from(index).ok()?)
+    }
+}
+
+/// Ledger entries stored column by column, with indexes by tick, entity,
+/// resource, and tag.
+#[derive(Debug, Default)]
+pub struct EntryStore {
+    ids: Vec<LedgerEntryId>,
+    ticks: Vec<u64>,
+    entry_types: Vec<LedgerEntryType>,
+    resources: Vec<Resource>,
+    /// Index into `sides` of each row's source.
+    from: Vec<u32>,
+    /// Index into `sides` of each row's destination.
+    to: Vec<u32>,
+    quantities: Vec<Decimal>,
+    /// Index into `reasons` of each row's reason.
+    reason_ids: Vec<u32>,
+    created_at: Vec<DateTime<Utc>>,
+    /// References of the rows that have one.
+    references: BTreeMap<Row, Uuid>,
+    /// Tags of the rows that have any.
+    tags: BTreeMap<Row, Vec<LedgerTag>>,
+    sides: Interner<Side>,
+    reasons: Interner<String>,
+    by_tick: BTreeMap<u64, Vec<Row>>,
+    by_entity: BTreeMap<Uuid, Vec<Row>>,
+    by_resource: BTreeMap<Resource, Vec<Row>>,
+    by_tag: BTreeMap<LedgerTag, Vec<Row>>,
+    by_id: BTreeMap<LedgerEntryId, Row>,
+    /// IDs of the entries a stored reversal refers to.
+    reversed: BTreeSet<LedgerEntryId>,
+}
+
+impl EntryStore {
+    /// Create an empty store.
+    pub const fn new() -> Self {
+        Self {
+            ids: Vec::new(),
+            ticks: Vec::new(),
+            entry_types: Vec::new(),
+            resources: Vec::new(),
+            from: Vec::new(),
+            to: Vec::new(),
+            quantities: Vec::new(),
+            reason_ids: Vec::new(),
+            created_at: Vec::new(),
+            references: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            sides: Interner::new(),
+            reasons: Interner::new(),
+            by_tick: BTreeMap::new(),
+            by_entity: BTreeMap::new(),
+            by_resource: BTreeMap::new(),
+            by_tag: BTreeMap::new(),
+            by_id: BTreeMap::new(),
+            reversed: BTreeSet::new(),
+        }
+    }
+
+    /// Return the number of entries.
+    pub const fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Return whether the store has no entries.
+    pub const fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Append `entry` as the last row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::InternalError`] if the store already holds
+    /// `u32::MAX` entries.
+    pub fn push(&mut self, entry: &LedgerEntry) -> Result<(), LedgerError> {
+        let (Ok(row), Some(from), Some(to), Some(reason)) = (
+            Row::try_from(self.ids.len()),
+            self.sides.intern(&(entry.from_entity, entry.from_entity_type)),
+            self.sides.intern(&(entry.to_entity, entry.to_entity_type)),
+            self.reasons.intern(&entry.reason),
+        ) else {
+            return Err(LedgerError::InternalError("ledger store is full"));
+        };
+
+        self.ids.push(entry.id);
+        self.ticks.push(entry.tick);
+        self.entry_types.push(entry.entry_type);
+        self.resources.push(entry.resource);
+        self.from.push(from);
+        self.to.push(to);
+        self.quantities.push(entry.quantity);
+        self.reason_ids.push(reason);
+        self.created_at.push(entry.created_at);
+        if let Some(reference_id) = entry.reference_id {
+            self.references.insert(row, reference_id);
+        }
+        if !entry.tags.is_empty() {
+            self.tags.insert(row, entry.tags.clone());
+        }
+
+        self.by_tick.entry(entry.tick).or_default().push(row);
+        self.by_resource.entry(entry.resource).or_default().push(row);
+        if let Some(to) = entry.to_entity {
+            self.by_entity.entry(to).or_default().push(row);
+        }
+        if let Some(from) = entry.from_entity.filter(|from| entry.to_entity != Some(*from)) {
+            self.by_entity.entry(from).or_default().push(row);
+        }
+        for tag in &entry.tags {
+            let rows = self.by_tag.entry(*tag).or_default();
+            if rows.last() != Some(&row) {
+                rows.push(row);
+            }
+        }
+        self.by_id.insert(entry.id, row);
+        if let Some(original) = entry.reference_id.filter(|_| is_reversal(entry.entry_type)) {
+            self.reversed.insert(LedgerEntryId::from(original));
+        }
+        Ok(())
+    }
+
+    /// Materialize the entry at `row`.
+    fn get(&self, row: Row) -> Option<LedgerEntry> {
+        let i = usize::try_from(row).ok()?;
+        let (from_entity, from_entity_type) = *self.sides.get(*self.from.get(i)?)?;
+        let (to_entity, to_entity_type) = *self.sides.get(*self.to.get(i)?)?;
+        Some(LedgerEntry {
+            id: *self.ids.get(i)?,
+            tick: *self.ticks.get(i)?,
+            entry_type: *self.entry_types.get(i)?,
+            from_entity,
+            from_entity_type,
+            to_entity,
+            to_entity_type,
+            resource: *self.resources.get(i)?,
+            quantity: *self.quantities.get(i)?,
+            reason: self.reasons.get(*self.reason_ids.get(i)?)?.clone(),
+            reference_id: self.references.get(&row).copied(),
+            tags: self.tags.get(&row).cloned().unwrap_or_default(),
+            created_at: *self.created_at.get(i)?,
+        })
+    }
+
+    /// Materialize the entries at `rows`.
+    fn rows(&self, rows: &[Row]) -> Vec<LedgerEntry> {
+        rows.iter().filter_map(|row| self.get(*row)).collect()
+    }
+
+    /// Iterate over every entry, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = LedgerEntry> + '_ {
+        let rows = Row::try_from(self.ids.len()).unwrap_or(Row::MAX);
+        (0..rows).filter_map(|row| self.get(row))
+    }
+
+    /// Return the last entry appended.
+    pub fn last(&self) -> Option<LedgerEntry> {
+        let row = Row::try_from(self.ids.len().checked_sub(1)?).ok()?;
+        self.get(row)
+    }
+
+    /// Return the entry `id`.
+    pub fn find(&self, id: LedgerEntryId) -> Option<LedgerEntry> {
+        self.get(*self.by_id.get(&id)?)
+    }
+
+    /// Return the entries recorded for `tick`, in insertion order.
+    pub fn tick(&self, tick: u64) -> Vec<LedgerEntry> {
+        self.by_tick.get(&tick).map(|rows| self.rows(rows)).unwrap_or_default()
+    }
+
+    /// Return the entries recorded for every tick in `ticks`, in
+    /// insertion order.
+    pub fn ticks(&self, ticks: impl RangeBounds<u64>) -> Vec<LedgerEntry> {
+        let mut rows: Vec<Row> = self.by_tick.range(ticks).flat_map(|(_, r)| r).copied().collect();
+        rows.sort_unstable();
+        self.rows(&rows)
+    }
+
+    /// Iterate over the ticks that have entries, in order.
+    pub fn recorded_ticks(&self) -> impl Iterator<Item = u64> + '_ {
+        self.by_tick.keys().copied()
+    }
+
+    /// Return the entries `entity_id` is a source or destination of, in
+    /// insertion order.
+    pub fn entity(&self, entity_id: Uuid) -> Vec<LedgerEntry> {
+        self.by_entity.get(&entity_id).map(|rows| self.rows(rows)).unwrap_or_default()
+    }
+
+    /// Return the entries moving `resource`, in insertion order.
+    pub fn resource(&self, resource: Resource) -> Vec<LedgerEntry> {
+        self.by_resource.get(&resource).map(|rows| self.rows(rows)).unwrap_or_default()
+    }
+
+    /// Return the entries tagged with `tag`, in insertion order.
+    pub fn tagged(&self, tag: LedgerTag) -> Vec<LedgerEntry> {
+        self.by_tag.get(&tag).map(|rows| self.rows(rows)).unwrap_or_default()
+    }
+
+    /// Return every reversal entry, in insertion order.
+    pub fn reversals(&self) -> Vec<LedgerEntry> {
+        let rows: Vec<Row> = self
+            .references
+            .keys()
+            .copied()
+            .filter(|row| self.entry_type(*row).is_some_and(is_reversal))
+            .collect();
+        self.rows(&rows)
+    }
+
+    /// Return whether a reversal of the entry `id` has been stored.
+    pub fn is_reversed(&self, id: LedgerEntryId) -> bool {
+        self.reversed.contains(&id)
+    }
+
+    /// The entry type at `row`.
+    fn entry_type(&self, row: Row) -> Option<LedgerEntryType> {
+        self.entry_types.get(usize::try_from(row).ok()?).copied()
+    }
+}
+
+/// Whether entries of `entry_type` reverse the entry they refer to.
+fn is_reversal(entry_type: LedgerEntryType) -> bool {
+    entry_type == LedgerEntryType::Reversal
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TransactionBuilder;
+
+    use super::*;
+
+    fn gather(tick: u64, location: Uuid, agent: Uuid, reason: &str) -> Option<LedgerEntry> {
+        TransactionBuilder::new(tick, LedgerEntryType::Gather, Resource::Wood)
+            .from(location, EntityType::Location)
+            .to(agent, EntityType::Agent)
+            .quantity(Decimal::new(15, 1))
+            .reason(reason.to_owned())
+            .reference_id(Uuid::now_v7())
+            .build()
+            .ok()
+    }
+
+    #[test]
+    fn entries_read_back_as_stored() {
+        let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let entries: Vec<LedgerEntry> = [(3, "GATHER"), (1, "GATHER"), (3, "FORAGE")]
+            .into_iter()
+            .filter_map(|(tick, reason)| gather(tick, location, agent, reason))
+            .collect();
+        let mut store = EntryStore::new();
+        for entry in &entries {
+            assert!(store.push(entry).is_ok());
+        }
+
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.iter().collect::<Vec<_>>(), entries);
+        assert_eq!(store.sides.values.len(), 2);
+        assert_eq!(store.reasons.values.len(), 2);
+        let third = entries.get(2).cloned();
+        assert_eq!(store.last(), third);
+        assert_eq!(third.and_then(|e| store.find(e.id)), entries.get(2).cloned());
+    }
+
+    #[test]
+    fn indexes_return_entries_in_insertion_order() {
+        let (location, agent, other) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let entries: Vec<LedgerEntry> = [(5, agent), (2, other), (5, other), (7, agent)]
+            .into_iter()
+            .filter_map(|(tick, to)| gather(tick, location, to, "GATHER"))
+            .collect();
+        let mut store = EntryStore::new();
+        for entry in &entries {
+            let _ = store.push(entry);
+        }
+
+        let ids = |entries: Vec<LedgerEntry>| entries.iter().map(|e| e.id).collect::<Vec<_>>();
+        let all = ids(entries);
+        let at = |i: usize| all.get(i).copied();
+        assert_eq!(ids(store.tick(5)).first().copied(), at(0));
+        assert_eq!(ids(store.ticks(5..=7)).len(), 3);
+        assert_eq!(ids(store.ticks(..6)).get(1).copied(), at(1));
+        assert_eq!(ids(store.entity(other)).len(), 2);
+        assert_eq!(ids(store.entity(location)).len(), 4);
+        assert_eq!(store.resource(Resource::Wood).len(), 4);
+        assert!(store.resource(Resource::Stone).is_empty());
+        assert_eq!(store.recorded_ticks().collect::<Vec<_>>(), vec![2, 5, 7]);
+    }
+
+    #[test]
+    fn reversals_mark_the_entry_they_refer_to() {
+        let (location, agent) = (Uuid::now_v7(), Uuid::now_v7());
+        let original = gather(1, location, agent, "GATHER");
+        let original_id = original.as_ref().map(|e| e.id);
+        let reversal = original.as_ref().and_then(|o| {
+            TransactionBuilder::new(2, LedgerEntryType::Reversal, Resource::Wood)
+                .from(agent, EntityType::Agent)
+                .to(location, EntityType::Location)
+                .quantity(o.quantity)
+                .reason("REVERSAL".to_owned())
+                .reference_id(o.id.into_inner())
+                .build()
+                .ok()
+        });
+        let mut store = EntryStore::new();
+        if let Some(entry) = &original {
+            let _ = store.push(entry);
+        }
+        assert!(original_id.is_some_and(|id| !store.is_reversed(id)));
+        // A plain reference does not count as a reversal.
+        let referenced = original.as_ref().and_then(|e| e.reference_id);
+        assert!(referenced.is_some_and(|r| !store.is_reversed(r.into())));
+
+        if let Some(entry) = &reversal {
+            let _ = store.push(entry);
+        }
+        assert!(original_id.is_some_and(|id| store.is_reversed(id)));
+        assert_eq!(original_id.and_then(|id| store.find(id)), original);
+        assert_eq!(reversal.as_ref().and_then(|e| store.find(e.id)), reversal);
+        assert_eq!(store.reversals().len(), 1);
+    }
+}
@@ -4,14 +4,25 @@
 //! every resource transfer must specify a source entity (debit) and a
 //! destination entity (credit). Builders validate inputs before producing
 //! a [`LedgerEntry`].
+//!
+//! A [`TransactionBatch`] groups the builders of one multi-entry operation,
+//! such as a trade moving several resources each way, so that either every
+//! entry validates and is recorded or none is. A batch can also be built
+//! from a slice of [`TransferParams`], as when a tick's transfers are
+//! recorded together. A batch validates each distinct entity-type pairing
+//! once, and its entries share one `created_at`.
+
+use std::collections::BTreeSet;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use emergence_types::{EntityType, LedgerEntry, LedgerEntryId, LedgerEntryType, Resource};
+use emergence_types::{
+    EntityType, LedgerEntry, LedgerEntryId, LedgerEntryType, LedgerTag, Resource,
+};
 
-use crate::LedgerError;
+use crate::{LedgerError, TransferParams};
 
 // ---------------------------------------------------------------------------
 // Transaction builder
@@ -52,6 +63,7 @@ pub struct TransactionBuilder {
     quantity: Option<Decimal>,
     reason: Option<String>,
     reference_id: Option<Uuid>,
+    tags: Vec<LedgerTag>,
 }
 
 impl TransactionBuilder {
@@ -69,6 +81,7 @@ impl TransactionBuilder {
             quantity: None,
             reason: None,
             reference_id: None,
+            tags: Vec::new(),
         }
     }
 
@@ -102,6 +115,12 @@ impl TransactionBuilder {
         self
     }
 
+    /// Start a [`TransactionBatch`] of entries to be validated and recorded
+    /// together.
+    pub const fn batch() -> TransactionBatch {
+        TransactionBatch::new()
+    }
+
     /// Set an optional reference ID linking to a related entity.
     #[must_use]
     pub const fn reference_id(mut self, id: Uuid) -> Self {
@@ -109,6 +128,31 @@ impl TransactionBuilder {
         self
     }
 
+    /// Tag the entry with what caused it. Adding a tag twice has no effect.
+    #[must_use]
+    pub fn tag(mut self, tag: LedgerTag) -> Self {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    /// Start a builder with every field of `params` set.
+    pub fn from_params(params: &TransferParams) -> Self {
+        let mut builder = Self::new(params.tick, params.entry_type, params.resource)
+            .from(params.from_entity, params.from_entity_type)
+            .to(params.to_entity, params.to_entity_type)
+            .quantity(params.quantity)
+            .reason(params.reason.clone());
+        if let Some(ref_id) = params.reference_id {
+            builder = builder.reference_id(ref_id);
+        }
+        for tag in &params.tags {
+            builder = builder.tag(*tag);
+        }
+        builder
+    }
+
     /// Validate inputs and produce a [`LedgerEntry`].
     ///
     /// # Errors
@@ -119,6 +163,12 @@ impl TransactionBuilder {
     /// Returns [`LedgerError::InvalidEntityType`] if the from/to entity types
     /// do not match the expected types for the entry type.
     pub fn build(self) -> Result<LedgerEntry, LedgerError> {
+        self.build_in(&mut BatchContext::new())
+    }
+
+    /// Validate inputs and produce a [`LedgerEntry`] stamped with
+    /// `context`'s time, skipping pairings `context` has already validated.
+    fn build_in(self, context: &mut BatchContext) -> Result<LedgerEntry, LedgerError> {
         let quantity = self.quantity.ok_or(LedgerError::MissingField("quantity"))?;
         let reason = self.reason.ok_or(LedgerError::MissingField("reason"))?;
 
@@ -131,11 +181,7 @@ impl TransactionBuilder {
         }
 
         // Validate entity types match the entry type contract.
-        validate_entity_types(
-            self.entry_type,
-            self.from_entity_type,
-            self.to_entity_type,
-        )?;
+        context.validate(self.entry_type, self.from_entity_type, self.to_entity_type)?;
 
         Ok(LedgerEntry {
             id: LedgerEntryId::new(),
@@ -149,19 +195,151 @@ impl TransactionBuilder {
             quantity,
             reason,
             reference_id: self.reference_id,
-            created_at: Utc::now(),
+            tags: self.tags,
+            created_at: context.created_at,
         })
     }
 }
 
+/// What the entries built together share: one timestamp, and the
+/// entity-type pairings already found valid.
+struct BatchContext {
+    created_at: DateTime<Utc>,
+    valid_pairings: BTreeSet<(LedgerEntryType, Option<EntityType>, Option<EntityType>)>,
+}
+
+impl BatchContext {
+    /// Start a context stamped with the current time.
+    fn new() -> Self {
+        Self {
+            created_at: Utc::now(),
+            valid_pairings: BTreeSet::new(),
+        }
+    }
+
+    /// Validate a pairing, as [`validate_entity_types`] does, unless it has
+    /// already been found valid.
+    fn validate(
+        &mut self,
+        entry_type: LedgerEntryType,
+        from_type: Option<EntityType>,
+        to_type: Option<EntityType>,
+    ) -> Result<(), LedgerError> {
+        let pairing = (entry_type, from_type, to_type);
+        if self.valid_pairings.contains(&pairing) {
+            return Ok(());
+        }
+        validate_entity_types(entry_type, from_type, to_type)?;
+        self.valid_pairings.insert(pairing);
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Transaction batch
+// ---------------------------------------------------------------------------
+
+/// A group of [`TransactionBuilder`]s validated all-or-nothing.
+///
+/// Record a batch with [`Ledger::record_batch`](crate::Ledger::record_batch);
+/// if any entry fails validation, no entry of the batch is recorded.
+#[derive(Debug, Default)]
+pub struct TransactionBatch {
+    builders: Vec<TransactionBuilder>,
+}
+
+impl TransactionBatch {
+    /// Start an empty batch.
+    pub const fn new() -> Self {
+        Self {
+            builders: Vec::new(),
+        }
+    }
+
+    /// Start a batch of `transfers`, in order.
+    ///
+    /// Every transfer's entity-type pairing is checked before the batch is
+    /// returned, so a malformed transfer is caught before anything is
+    /// recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::BatchEntry`] naming the first transfer whose
+    /// pairing is invalid.
+    pub fn transfers(transfers: &[TransferParams]) -> Result<Self, LedgerError> {
+        let mut context = BatchContext::new();
+        let mut batch = Self::new();
+        for (index, params) in transfers.iter().enumerate() {
+            context
+                .validate(
+                    params.entry_type,
+                    Some(params.from_entity_type),
+                    Some(params.to_entity_type),
+                )
+                .map_err(|source| LedgerError::BatchEntry {
+                    index,
+                    source: Box::new(source),
+                })?;
+            batch.push(TransactionBuilder::from_params(params));
+        }
+        Ok(batch)
+    }
+
+    /// Add an entry to the batch.
+    #[must_use]
+    pub fn entry(mut self, builder: TransactionBuilder) -> Self {
+        self.builders.push(builder);
+        self
+    }
+
+    /// Add an entry to the batch in place.
+    pub fn push(&mut self, builder: TransactionBuilder) {
+        self.builders.push(builder);
+    }
+
+    /// Return the number of entries in the batch.
+    pub const fn len(&self) -> usize {
+        self.builders.len()
+    }
+
+    /// Return whether the batch has no entries.
+    pub const fn is_empty(&self) -> bool {
+        self.builders.is_empty()
+    }
+
+    /// Validate every entry and produce them in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::BatchEntry`] naming the first entry that
+    /// fails validation.
+    pub fn build(self) -> Result<Vec<LedgerEntry>, LedgerError> {
+        let mut context = BatchContext::new();
+        self.builders
+            .into_iter()
+            .enumerate()
+            .map(|(index, builder)| {
+                builder.build_in(&mut context).map_err(|source| LedgerError::BatchEntry {
+                    index,
+                    source: Box::new(source),
+                })
+            })
+            .collect()
+    }
+}
+
 /// Validate that the from/to entity types match the contract for the
 /// given [`LedgerEntryType`].
-fn validate_entity_types(
+pub(crate) fn validate_entity_types(
     entry_type: LedgerEntryType,
     from_type: Option<EntityType>,
     to_type: Option<EntityType>,
 ) -> Result<(), LedgerError> {
-    let (expected_from, expected_to) = expected_entity_types(entry_type);
+    // Checkpoints carry balances between entities of any type, and
+    // corrections repair whichever side an entry left unmatched.
+    let Some((expected_from, expected_to)) = expected_entity_types(entry_type) else {
+        return Ok(());
+    };
 
     if from_type != expected_from {
         return Err(LedgerError::InvalidEntityType {
@@ -184,25 +362,50 @@ fn validate_entity_types(
     Ok(())
 }
 
-/// Return the expected (from, to) entity types for each [`LedgerEntryType`].
+/// Return the expected (from, to) entity types for each [`LedgerEntryType`],
+/// or `None` if any types are allowed.
 const fn expected_entity_types(
     entry_type: LedgerEntryType,
-) -> (Option<EntityType>, Option<EntityType>) {
-    match entry_type {
+) -> Option<(Option<EntityType>, Option<EntityType>)> {
+    let expected = match entry_type {
         LedgerEntryType::Regeneration => (Some(EntityType::World), Some(EntityType::Location)),
         LedgerEntryType::Gather | LedgerEntryType::Pickup => {
             (Some(EntityType::Location), Some(EntityType::Agent))
         }
-        LedgerEntryType::Consume => (Some(EntityType::Agent), Some(EntityType::Void)),
+        LedgerEntryType::Consume
+        | LedgerEntryType::EnergySpent
+        | LedgerEntryType::Burn
+        | LedgerEntryType::Slaughter => {
+            (Some(EntityType::Agent), Some(EntityType::Void))
+        }
         LedgerEntryType::Transfer => (Some(EntityType::Agent), Some(EntityType::Agent)),
-        LedgerEntryType::Build => (Some(EntityType::Agent), Some(EntityType::Structure)),
-        LedgerEntryType::Salvage => (Some(EntityType::Structure), Some(EntityType::Agent)),
+        LedgerEntryType::Build | LedgerEntryType::Deposit => {
+            (Some(EntityType::Agent), Some(EntityType::Structure))
+        }
+        LedgerEntryType::Salvage | LedgerEntryType::Withdrawal => {
+            (Some(EntityType::Structure), Some(EntityType::Agent))
+        }
         LedgerEntryType::Decay => (Some(EntityType::Structure), Some(EntityType::Void)),
         LedgerEntryType::Drop => (Some(EntityType::Agent), Some(EntityType::Location)),
-        LedgerEntryType::Theft | LedgerEntryType::CombatLoot => {
+        LedgerEntryType::Theft
+        | LedgerEntryType::CombatLoot
+        | LedgerEntryType::LoanIssued
+        | LedgerEntryType::LoanRepayment
+        | LedgerEntryType::InterestAccrued => {
             (Some(EntityType::Agent), Some(EntityType::Agent))
         }
-    }
+        LedgerEntryType::Tax => (Some(EntityType::Agent), Some(EntityType::Group)),
+        LedgerEntryType::Tribute => (Some(EntityType::Group), Some(EntityType::Group)),
+        LedgerEntryType::Escrow => (Some(EntityType::Agent), Some(EntityType::Escrow)),
+        LedgerEntryType::EscrowRelease => (Some(EntityType::Escrow), Some(EntityType::Agent)),
+        LedgerEntryType::EnergyRecovered | LedgerEntryType::Mint | LedgerEntryType::Breeding => {
+            (Some(EntityType::World), Some(EntityType::Agent))
+        }
+        LedgerEntryType::Checkpoint
+        | LedgerEntryType::Correction
+        | LedgerEntryType::Reversal => return None,
+    };
+    Some(expected)
 }
 
 #[cfg(test)]
@@ -323,7 +526,7 @@ mod tests {
         ];
 
         for entry_type in all_types {
-            let (from, to) = expected_entity_types(entry_type);
+            let (from, to) = expected_entity_types(entry_type).unwrap_or_default();
             // Every entry type must have both a source and destination.
             assert!(
                 from.is_some(),
@@ -335,4 +538,62 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn batch_fails_on_its_first_invalid_entry() {
+        let gather = |quantity| {
+            TransactionBuilder::new(1, LedgerEntryType::Gather, Resource::Wood)
+                .from(Uuid::now_v7(), EntityType::Location)
+                .to(Uuid::now_v7(), EntityType::Agent)
+                .quantity(Decimal::new(quantity, 0))
+                .reason("GATHER".to_owned())
+        };
+
+        let batch = TransactionBuilder::batch().entry(gather(2)).entry(gather(3));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.build().map(|entries| entries.len()).ok(), Some(2));
+
+        let batch = TransactionBuilder::batch().entry(gather(2)).entry(gather(0)).entry(gather(-1));
+        let result = batch.build();
+        assert!(matches!(result, Err(LedgerError::BatchEntry { index: 1, .. })));
+    }
+
+    #[test]
+    fn transfer_batches_validate_every_pairing_up_front() {
+        let transfer = |entry_type, from_entity_type| TransferParams {
+            tick: 3,
+            entry_type,
+            resource: Resource::Wood,
+            quantity: Decimal::TWO,
+            from_entity: Uuid::now_v7(),
+            from_entity_type,
+            to_entity: Uuid::now_v7(),
+            to_entity_type: EntityType::Agent,
+            reason: "GATHER".to_owned(),
+            reference_id: None,
+            tags: Vec::new(),
+        };
+        let gather = || transfer(LedgerEntryType::Gather, EntityType::Location);
+
+        let batch = TransactionBatch::transfers(&[gather(), gather(), gather()]);
+        let entries = batch.and_then(TransactionBatch::build).unwrap_or_default();
+        assert_eq!(entries.len(), 3);
+        let first = entries.first().map(|e| e.created_at);
+        assert!(entries.iter().all(|e| Some(e.created_at) == first));
+
+        let stolen = transfer(LedgerEntryType::Gather, EntityType::Agent);
+        let result = TransactionBatch::transfers(&[gather(), gather(), stolen]);
+        assert!(matches!(result, Err(LedgerError::BatchEntry { index: 2, .. })));
+    }
+
+    #[test]
+    fn checkpoints_accept_any_entity_types() {
+        let result = TransactionBuilder::new(4, LedgerEntryType::Checkpoint, Resource::Wood)
+            .from(Uuid::now_v7(), EntityType::Location)
+            .to(Uuid::now_v7(), EntityType::Structure)
+            .quantity(Decimal::new(2, 0))
+            .reason("CHECKPOINT".to_owned())
+            .build();
+        assert!(result.is_ok());
+    }
 }
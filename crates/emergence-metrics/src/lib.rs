@@ -0,0 +1,348 @@
+//! Process-wide counters and histograms for the Emergence simulation.
+//!
+//! A deliberately small facade: metrics are `static` items declared next
+//! to the code they measure, register themselves with a global registry on
+//! first use, and are rendered in the Prometheus text format by the
+//! Observer's `/metrics` endpoint. There is no exporter thread and no
+//! dependency beyond `std`.
+//!
+//! ```
+//! use emergence_metrics::{Counter, Histogram, LATENCY_MS_BUCKETS};
+//!
+//! static GATHERS: Counter = Counter::new("example_gathers_total", "Gather actions.");
+//! static FLUSH_MS: Histogram =
+//!     Histogram::labeled("example_flush_ms", "Flush latency.", "table", LATENCY_MS_BUCKETS);
+//!
+//! GATHERS.increment(1);
+//! FLUSH_MS.observe_with("events", 12);
+//! assert!(emergence_metrics::render().contains("example_gathers_total 1"));
+//! ```
+//!
+//! Each metric takes at most one label. A metric that has never been
+//! recorded does not appear in the output.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+use std::time::Instant;
+
+/// Histogram bucket bounds for latencies in milliseconds.
+pub const LATENCY_MS_BUCKETS: &[u64] = &[1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+// =========================================================================
+// Registry
+// =========================================================================
+
+/// A metric that can render itself.
+trait Family: Sync {
+    /// The metric name.
+    fn name(&self) -> &'static str;
+    /// Append this metric in the Prometheus text format.
+    fn render(&self, out: &mut String);
+}
+
+/// Every metric recorded so far.
+static REGISTRY: Mutex<Vec<&'static dyn Family>> = Mutex::new(Vec::new());
+
+/// Lock a mutex, recovering the data if a holder panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Render every recorded metric in the Prometheus text format, sorted by
+/// name.
+pub fn render() -> String {
+    let mut families = lock(&REGISTRY).clone();
+    families.sort_by_key(|family| family.name());
+    let mut out = String::new();
+    for family in families {
+        family.render(&mut out);
+    }
+    out
+}
+
+/// Write the `# HELP` and `# TYPE` lines for a metric.
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Format a label set, escaping the value; empty when `label` is `None`.
+fn label_set(label: Option<&str>, value: &str, extra: Option<(&str, &str)>) -> String {
+    let mut pairs: Vec<String> = Vec::new();
+    if let Some(label) = label {
+        pairs.push(format!("{label}=\"{}\"", escape(value)));
+    }
+    if let Some((name, value)) = extra {
+        pairs.push(format!("{name}=\"{value}\""));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// Escape a label value for the Prometheus text format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// =========================================================================
+// Counter
+// =========================================================================
+
+/// A monotonically increasing count, optionally split by one label.
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+    label: Option<&'static str>,
+    series: Mutex<BTreeMap<String, u64>>,
+    registered: Once,
+}
+
+impl Counter {
+    /// An unlabeled counter.
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self::build(name, help, None)
+    }
+
+    /// A counter split by the label `label`.
+    pub const fn labeled(name: &'static str, help: &'static str, label: &'static str) -> Self {
+        Self::build(name, help, Some(label))
+    }
+
+    const fn build(name: &'static str, help: &'static str, label: Option<&'static str>) -> Self {
+        Self {
+            name,
+            help,
+            label,
+            series: Mutex::new(BTreeMap::new()),
+            registered: Once::new(),
+        }
+    }
+
+    /// Add `by` to the counter.
+    pub fn increment(&'static self, by: u64) {
+        self.increment_with("", by);
+    }
+
+    /// Add `by` to the series where the label equals `value`.
+    pub fn increment_with(&'static self, value: &str, by: u64) {
+        self.registered.call_once(|| lock(&REGISTRY).push(self));
+        let mut series = lock(&self.series);
+        if let Some(count) = series.get_mut(value) {
+            *count = count.saturating_add(by);
+        } else {
+            series.insert(value.to_owned(), by);
+        }
+    }
+
+    /// The current value of the series where the label equals `value`
+    /// (`""` for an unlabeled counter).
+    pub fn get(&self, value: &str) -> u64 {
+        lock(&self.series).get(value).copied().unwrap_or(0)
+    }
+}
+
+impl Family for Counter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn render(&self, out: &mut String) {
+        write_header(out, self.name, self.help, "counter");
+        for (value, count) in lock(&self.series).iter() {
+            let labels = label_set(self.label, value, None);
+            let _ = writeln!(out, "{}{labels} {count}", self.name);
+        }
+    }
+}
+
+// =========================================================================
+// Histogram
+// =========================================================================
+
+/// Bucket counts for one label value.
+struct HistogramSeries {
+    /// Observations at or below each bound, then above the last one.
+    buckets: Vec<u64>,
+    sum: u64,
+    count: u64,
+}
+
+/// A distribution of `u64` observations over fixed bucket bounds,
+/// optionally split by one label.
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    label: Option<&'static str>,
+    bounds: &'static [u64],
+    series: Mutex<BTreeMap<String, HistogramSeries>>,
+    registered: Once,
+}
+
+impl Histogram {
+    /// An unlabeled histogram with the given ascending bucket bounds.
+    pub const fn new(name: &'static str, help: &'static str, bounds: &'static [u64]) -> Self {
+        Self::build(name, help, None, bounds)
+    }
+
+    /// A histogram split by the label `label`.
+    pub const fn labeled(
+        name: &'static str,
+        help: &'static str,
+        label: &'static str,
+        bounds: &'static [u64],
+    ) -> Self {
+        Self::build(name, help, Some(label), bounds)
+    }
+
+    const fn build(
+        name: &'static str,
+        help: &'static str,
+        label: Option<&'static str>,
+        bounds: &'static [u64],
+    ) -> Self {
+        Self {
+            name,
+            help,
+            label,
+            bounds,
+            series: Mutex::new(BTreeMap::new()),
+            registered: Once::new(),
+        }
+    }
+
+    /// Record one observation.
+    pub fn observe(&'static self, sample: u64) {
+        self.observe_with("", sample);
+    }
+
+    /// Record one observation in the series where the label equals `value`.
+    pub fn observe_with(&'static self, value: &str, sample: u64) {
+        self.registered.call_once(|| lock(&REGISTRY).push(self));
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|&bound| sample <= bound)
+            .unwrap_or(self.bounds.len());
+        let mut series = lock(&self.series);
+        if !series.contains_key(value) {
+            series.insert(
+                value.to_owned(),
+                HistogramSeries {
+                    buckets: vec![0; self.bounds.len().saturating_add(1)],
+                    sum: 0,
+                    count: 0,
+                },
+            );
+        }
+        if let Some(entry) = series.get_mut(value) {
+            if let Some(slot) = entry.buckets.get_mut(bucket) {
+                *slot = slot.saturating_add(1);
+            }
+            entry.sum = entry.sum.saturating_add(sample);
+            entry.count = entry.count.saturating_add(1);
+        }
+    }
+
+    /// Start timing; the elapsed milliseconds are recorded in the series
+    /// for `value` when the returned guard is dropped.
+    pub fn start_timer(&'static self, value: &'static str) -> Timer {
+        Timer {
+            histogram: self,
+            value,
+            started: Instant::now(),
+        }
+    }
+
+    /// Number of observations in the series where the label equals `value`.
+    pub fn count(&self, value: &str) -> u64 {
+        lock(&self.series).get(value).map_or(0, |series| series.count)
+    }
+}
+
+impl Family for Histogram {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn render(&self, out: &mut String) {
+        write_header(out, self.name, self.help, "histogram");
+        for (value, series) in lock(&self.series).iter() {
+            let mut cumulative: u64 = 0;
+            let bounds = self.bounds.iter().map(u64::to_string).chain(["+Inf".to_owned()]);
+            for (bound, count) in bounds.zip(&series.buckets) {
+                cumulative = cumulative.saturating_add(*count);
+                let labels = label_set(self.label, value, Some(("le", &bound)));
+                let _ = writeln!(out, "{}_bucket{labels} {cumulative}", self.name);
+            }
+            let labels = label_set(self.label, value, None);
+            let _ = writeln!(out, "{}_sum{labels} {}", self.name, series.sum);
+            let _ = writeln!(out, "{}_count{labels} {}", self.name, series.count);
+        }
+    }
+}
+
+/// Records the time since it was created into a [`Histogram`] when dropped.
+#[must_use = "the timer records when dropped"]
+pub struct Timer {
+    histogram: &'static Histogram,
+    value: &'static str,
+    started: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let elapsed = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.histogram.observe_with(self.value, elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ACTIONS: Counter = Counter::labeled("test_actions_total", "Actions.", "action");
+    static LATENCY: Histogram = Histogram::new("test_latency_ms", "Latency.", &[10, 100]);
+    static ESCAPED: Counter = Counter::labeled("test_escaped_total", "Escaping.", "reason");
+
+    #[test]
+    fn counters_accumulate_per_label() {
+        ACTIONS.increment_with("Gather", 2);
+        ACTIONS.increment_with("Gather", 1);
+        ACTIONS.increment_with("Eat", 1);
+        assert_eq!(ACTIONS.get("Gather"), 3);
+        assert_eq!(ACTIONS.get("Move"), 0);
+
+        let text = render();
+        assert!(text.contains("# TYPE test_actions_total counter"));
+        assert!(text.contains("test_actions_total{action=\"Eat\"} 1"));
+        assert!(text.contains("test_actions_total{action=\"Gather\"} 3"));
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        LATENCY.observe(5);
+        LATENCY.observe(50);
+        LATENCY.observe(500);
+        assert_eq!(LATENCY.count(""), 3);
+
+        let text = render();
+        assert!(text.contains("test_latency_ms_bucket{le=\"10\"} 1"));
+        assert!(text.contains("test_latency_ms_bucket{le=\"100\"} 2"));
+        assert!(text.contains("test_latency_ms_bucket{le=\"+Inf\"} 3"));
+        assert!(text.contains("test_latency_ms_sum 555"));
+        assert!(text.contains("test_latency_ms_count 3"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        ESCAPED.increment_with("say \"hi\"", 1);
+        assert!(render().contains("test_escaped_total{reason=\"say \\\"hi\\\"\"} 1"));
+    }
+}
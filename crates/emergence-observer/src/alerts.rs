@@ -13,9 +13,9 @@
 //!
 //! # Alert Categories
 //!
-//! - `containment` -- escape detection triggered in the runner
+//! - `containment` -- escape detection or peer-content quarantine in the runner
 //! - `population` -- population collapse or extinction risk
-//! - `economy` -- ledger anomaly or economic crisis
+//! - `economy` -- ledger anomaly, reconciliation mismatch, or economic crisis
 //! - `milestone` -- first-instance achievement (first trade, first death, etc.)
 //! - `anomaly` -- behavioral anomaly flagged by the detection layer
 
@@ -176,6 +176,7 @@ impl AlertStore {
 ///
 /// - Population collapse (alive count dropped below 2)
 /// - Economic anomaly (Gini coefficient above 0.9)
+/// - Ledger anomalies and reconciliation mismatches recorded this tick
 /// - First-instance milestones (first trade, first death, first structure)
 pub fn check_for_alerts(
     snapshot: &crate::state::SimulationSnapshot,
@@ -218,24 +219,40 @@ pub fn check_for_alerts(
         );
     }
 
-    // Check for ledger anomaly events.
-    for event in &snapshot.events {
-        if event.tick == tick
-            && matches!(
-                event.event_type,
-                emergence_types::EventType::LedgerAnomaly
-            )
-        {
-            alert_store.raise(
+    // Check for ledger anomaly and reconciliation mismatch events.
+    for event in snapshot.events.iter().filter(|e| e.tick == tick) {
+        match event.event_type {
+            emergence_types::EventType::LedgerAnomaly => alert_store.raise(
                 AlertSeverity::Critical,
                 AlertCategory::Economy,
                 "LEDGER_ANOMALY: conservation law violated".to_owned(),
                 tick,
-            );
+            ),
+            emergence_types::EventType::ReconciliationMismatch => alert_store.raise(
+                AlertSeverity::Critical,
+                AlertCategory::Economy,
+                reconciliation_message(&event.details),
+                tick,
+            ),
+            _ => {}
         }
     }
 }
 
+/// The alert message for a reconciliation mismatch event's `details`.
+fn reconciliation_message(details: &serde_json::Value) -> String {
+    serde_json::from_value::<emergence_types::ReconciliationMismatchDetails>(details.clone())
+        .map_or_else(
+            |_| "RECONCILIATION_MISMATCH: ledger balances differ from world state".to_owned(),
+            |d| {
+                format!(
+                    "RECONCILIATION_MISMATCH: {:?} {} holds {} {:?}, ledger balance is {}",
+                    d.entity_type, d.entity_id, d.world_quantity, d.resource, d.ledger_balance,
+                )
+            },
+        )
+}
+
 // ---------------------------------------------------------------------------
 // REST Handlers
 // ---------------------------------------------------------------------------
@@ -408,4 +425,18 @@ mod tests {
         let unack = store.unacknowledged();
         assert_eq!(unack.len(), 2);
     }
+
+    #[test]
+    fn reconciliation_message_names_the_drift() {
+        let details = emergence_types::ReconciliationMismatchDetails {
+            entity_id: Uuid::nil(),
+            entity_type: emergence_types::EntityType::Agent,
+            resource: emergence_types::Resource::Wood,
+            ledger_balance: rust_decimal::Decimal::TEN,
+            world_quantity: rust_decimal::Decimal::TWO,
+        };
+        let message = reconciliation_message(&serde_json::to_value(details).unwrap_or_default());
+        assert!(message.contains("holds 2 Wood, ledger balance is 10"));
+        assert!(reconciliation_message(&serde_json::Value::Null).starts_with("RECONCILIATION"));
+    }
 }
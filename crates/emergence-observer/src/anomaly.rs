@@ -639,7 +639,7 @@ pub fn run_anomaly_analysis(
 pub async fn get_clusters(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     // Run analysis on demand from the current snapshot.
     let analysis = run_anomaly_analysis(&snapshot);
@@ -656,7 +656,7 @@ pub async fn get_clusters(
 pub async fn get_flags(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     // Run analysis on demand from the current snapshot.
     let analysis = run_anomaly_analysis(&snapshot);
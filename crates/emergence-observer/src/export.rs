@@ -0,0 +1,279 @@
+//! Fine-tuning dataset export.
+//!
+//! Joins each retained LLM decision record -- perception, prompt, raw LLM
+//! response, reasoning, parsed action -- with how the action turned out, and emits
+//! one JSON training pair per line (JSONL). Operators can fine-tune a
+//! cheaper model on the simulation's accumulated experience, keeping only
+//! decisions that worked.
+//!
+//! # Endpoints
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | `GET` | `/api/export/training` | JSONL training pairs |
+//!
+//! # Outcome Quality
+//!
+//! From worst to best:
+//!
+//! - `died` -- the agent died within `death_window` ticks of the decision
+//! - `rejected` -- the engine rejected the action
+//! - `unresolved` -- the tick has not resolved the action yet
+//! - `succeeded` -- the action succeeded
+//!
+//! Only decisions the LLM made (with a stored prompt and response) are
+//! exported. The export covers the records retained in memory, up to
+//! [`MAX_DECISIONS`](crate::state::MAX_DECISIONS).
+
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use emergence_types::DecisionRecord;
+
+use crate::error::ObserverError;
+use crate::state::AppState;
+
+/// Default number of ticks after a decision in which a death counts
+/// against it.
+pub const DEFAULT_DEATH_WINDOW: u64 = 10;
+
+// ---------------------------------------------------------------------------
+// Outcome quality
+// ---------------------------------------------------------------------------
+
+/// How well a decision turned out, ordered from worst to best.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OutcomeQuality {
+    /// The agent died shortly after the decision.
+    Died,
+    /// The engine rejected the action.
+    Rejected,
+    /// No outcome has been recorded yet.
+    Unresolved,
+    /// The action succeeded.
+    Succeeded,
+}
+
+impl OutcomeQuality {
+    /// Parse a `min_quality` query value.
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "died" => Some(Self::Died),
+            "rejected" => Some(Self::Rejected),
+            "unresolved" => Some(Self::Unresolved),
+            "succeeded" => Some(Self::Succeeded),
+            _ => None,
+        }
+    }
+}
+
+/// Grade a decision by its recorded outcome and the agent's fate.
+///
+/// A death at or within `death_window` ticks after the decision outranks
+/// the action's own outcome.
+pub fn outcome_quality(
+    record: &DecisionRecord,
+    died_at_tick: Option<u64>,
+    death_window: u64,
+) -> OutcomeQuality {
+    if died_at_tick.is_some_and(|died| {
+        died >= record.tick && died <= record.tick.saturating_add(death_window)
+    }) {
+        return OutcomeQuality::Died;
+    }
+    match record.outcome.as_deref() {
+        Some("succeeded") => OutcomeQuality::Succeeded,
+        Some("rejected") => OutcomeQuality::Rejected,
+        _ => OutcomeQuality::Unresolved,
+    }
+}
+
+/// Build a training pair from an LLM decision record.
+///
+/// The completion is the parsed action as canonical JSON rather than the
+/// raw response, so repaired or name-resolved answers train the clean
+/// form; the raw response is kept alongside. Returns `None` for records
+/// without an LLM prompt and response.
+pub fn training_pair(record: &DecisionRecord, quality: OutcomeQuality) -> Option<serde_json::Value> {
+    if record.decision_source != "llm" {
+        return None;
+    }
+    let prompt = record.prompt_sent.as_deref()?;
+    let raw_response = record.raw_llm_response.as_deref()?;
+    let action = serde_json::json!({
+        "action_type": record.action_type,
+        "parameters": record.action_params,
+    });
+    Some(serde_json::json!({
+        "prompt": prompt,
+        "completion": action.to_string(),
+        "perception": record.perception,
+        "raw_response": raw_response,
+        "reasoning": record.reasoning,
+        "action": action,
+        "outcome": record.outcome,
+        "quality": quality,
+        "metadata": {
+            "agent_id": record.agent_id,
+            "tick": record.tick,
+            "llm_backend": record.llm_backend,
+            "model": record.model,
+            "prompt_variant": record.prompt_variant,
+            "prompt_version": record.prompt_version,
+            "latency_ms": record.latency_ms,
+        },
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// REST Handlers
+// ---------------------------------------------------------------------------
+
+/// Query parameters for `GET /api/export/training`.
+#[derive(Debug, serde::Deserialize)]
+pub struct TrainingExportQuery {
+    /// Lowest outcome quality to include (default `succeeded`).
+    pub min_quality: Option<String>,
+    /// Ticks after a decision in which a death counts against it
+    /// (default [`DEFAULT_DEATH_WINDOW`]).
+    pub death_window: Option<u64>,
+    /// Only export decisions from this tick onward.
+    pub since_tick: Option<u64>,
+}
+
+/// `GET /api/export/training` -- JSONL fine-tuning pairs.
+///
+/// Responds with `application/x-ndjson`, oldest decision first.
+pub async fn training_export(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TrainingExportQuery>,
+) -> Result<impl IntoResponse, ObserverError> {
+    let min_quality = match params.min_quality.as_deref() {
+        None => OutcomeQuality::Succeeded,
+        Some(value) => OutcomeQuality::parse(value).ok_or_else(|| {
+            ObserverError::InvalidQuery(format!(
+                "min_quality must be died, rejected, unresolved, or succeeded (got {value})"
+            ))
+        })?,
+    };
+    let death_window = params.death_window.unwrap_or(DEFAULT_DEATH_WINDOW);
+    let since_tick = params.since_tick.unwrap_or(0);
+
+    let snapshot = state.snapshot.load();
+    let mut body = String::new();
+    for record in snapshot.decisions.iter().filter(|d| d.tick >= since_tick) {
+        let died_at_tick = snapshot
+            .agents
+            .get(&record.agent_id)
+            .and_then(|agent| agent.died_at_tick);
+        let quality = outcome_quality(record, died_at_tick, death_window);
+        if quality < min_quality {
+            continue;
+        }
+        if let Some(pair) = training_pair(record, quality) {
+            body.push_str(&serde_json::to_string(&pair)?);
+            body.push('\n');
+        }
+    }
+
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use emergence_types::AgentId;
+
+    use super::*;
+
+    fn record(source: &str, tick: u64, outcome: Option<&str>) -> DecisionRecord {
+        DecisionRecord {
+            agent_id: AgentId::new(),
+            tick,
+            decision_source: source.to_owned(),
+            action_type: "Gather".to_owned(),
+            action_params: serde_json::json!({"resource": "Wood"}),
+            llm_backend: Some("openai-compatible".to_owned()),
+            model: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            cost_usd: None,
+            latency_ms: Some(420),
+            raw_llm_response: Some(
+                r#"Sure! {"action_type": "gather", "parameters": {"resource": "wood"}}"#.to_owned(),
+            ),
+            reasoning: Some("Sure!".to_owned()),
+            prompt_sent: Some("You are Ada.\n\nTick 5".to_owned()),
+            rule_matched: None,
+            prompt_variant: Some("control".to_owned()),
+            prompt_version: Some("v1".to_owned()),
+            perception: Some(serde_json::json!({"tick": tick})),
+            outcome: outcome.map(ToOwned::to_owned),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn quality_follows_outcome_and_death() {
+        let ok = record("llm", 5, Some("succeeded"));
+        assert_eq!(outcome_quality(&ok, None, 10), OutcomeQuality::Succeeded);
+        assert_eq!(outcome_quality(&ok, Some(15), 10), OutcomeQuality::Died);
+        assert_eq!(outcome_quality(&ok, Some(16), 10), OutcomeQuality::Succeeded);
+        assert_eq!(outcome_quality(&ok, Some(4), 10), OutcomeQuality::Succeeded);
+
+        let rejected = record("llm", 5, Some("rejected"));
+        assert_eq!(outcome_quality(&rejected, None, 10), OutcomeQuality::Rejected);
+        let pending = record("llm", 5, None);
+        assert_eq!(outcome_quality(&pending, None, 10), OutcomeQuality::Unresolved);
+
+        assert!(OutcomeQuality::Died < OutcomeQuality::Rejected);
+        assert!(OutcomeQuality::Unresolved < OutcomeQuality::Succeeded);
+    }
+
+    #[test]
+    fn training_pair_uses_clean_action_as_completion() {
+        let rec = record("llm", 5, Some("succeeded"));
+        let pair = training_pair(&rec, OutcomeQuality::Succeeded).unwrap_or_default();
+        assert_eq!(
+            pair.get("prompt").and_then(serde_json::Value::as_str),
+            Some("You are Ada.\n\nTick 5")
+        );
+        let completion: serde_json::Value = pair
+            .get("completion")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_default();
+        assert_eq!(
+            completion,
+            serde_json::json!({"action_type": "Gather", "parameters": {"resource": "Wood"}})
+        );
+        assert_eq!(pair.get("quality"), Some(&serde_json::json!("succeeded")));
+        assert_eq!(pair.get("perception"), Some(&serde_json::json!({"tick": 5})));
+        assert_eq!(pair.get("reasoning"), Some(&serde_json::json!("Sure!")));
+    }
+
+    #[test]
+    fn training_pair_skips_non_llm_decisions() {
+        let rule = record("rule_engine", 5, Some("succeeded"));
+        assert!(training_pair(&rule, OutcomeQuality::Succeeded).is_none());
+
+        let mut no_prompt = record("llm", 5, Some("succeeded"));
+        no_prompt.prompt_sent = None;
+        assert!(training_pair(&no_prompt, OutcomeQuality::Succeeded).is_none());
+    }
+
+    #[test]
+    fn parse_min_quality() {
+        assert_eq!(OutcomeQuality::parse("rejected"), Some(OutcomeQuality::Rejected));
+        assert_eq!(OutcomeQuality::parse("great"), None);
+    }
+}
@@ -12,8 +12,11 @@
 //! | `GET` | `/api/agents/:id` | Get single agent + state |
 //! | `GET` | `/api/locations` | List all locations |
 //! | `GET` | `/api/locations/:id` | Get single location |
-//! | `GET` | `/api/events` | Query events (by tick or agent) |
+//! | `GET` | `/api/events` | Query events (by tick, type, agent, location, or details) |
 //! | `GET` | `/api/world` | Current world snapshot |
+//! | `GET` | `/api/decisions` | Query decision records |
+//! | `GET` | `/api/runner/metrics` | Runner LLM cost metrics |
+//! | `GET` | `/metrics` | Engine metrics (Prometheus text format) |
 
 use std::sync::Arc;
 
@@ -34,12 +37,75 @@ use crate::state::AppState;
 pub struct EventsQuery {
     /// Filter events by tick number.
     pub tick: Option<u64>,
+    /// Filter events to ticks at or after this one.
+    pub from_tick: Option<u64>,
+    /// Filter events to ticks before this one.
+    pub to_tick: Option<u64>,
+    /// Filter by event type; a comma-separated list of names such as
+    /// `ResourceGathered,TradeCompleted`.
+    pub event_type: Option<String>,
     /// Filter events by agent ID.
     pub agent_id: Option<String>,
+    /// Filter events by location ID.
+    pub location_id: Option<String>,
+    /// Filter by details; a comma-separated list of predicates such as
+    /// `resource=Wood,quantity>=5`.
+    pub detail: Option<String>,
     /// Maximum number of events to return (default 100).
     pub limit: Option<usize>,
 }
 
+impl EventsQuery {
+    /// The [`EventFilter`](emergence_types::EventFilter) these parameters
+    /// describe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObserverError::InvalidUuid`] for a malformed ID, or
+    /// [`ObserverError::InvalidQuery`] for an unknown event type or a
+    /// malformed detail predicate.
+    pub fn filter(&self) -> Result<emergence_types::EventFilter, ObserverError> {
+        let mut filter = emergence_types::EventFilter::new();
+        if let Some(tick) = self.tick {
+            filter = filter.with_tick(tick);
+        }
+        if self.from_tick.is_some() {
+            filter.from_tick = self.from_tick;
+        }
+        if self.to_tick.is_some() {
+            filter.to_tick = self.to_tick;
+        }
+        for name in list(self.event_type.as_deref()) {
+            let event_type = serde_json::from_value(serde_json::Value::from(name))
+                .map_err(|e| ObserverError::InvalidQuery(format!("event type {name}: {e}")))?;
+            filter = filter.with_event_type(event_type);
+        }
+        if let Some(agent_id) = self.agent_id.as_deref() {
+            filter = filter.with_agent(emergence_types::AgentId::from(parse_uuid(agent_id)?));
+        }
+        if let Some(location_id) = self.location_id.as_deref() {
+            filter =
+                filter.with_location(emergence_types::LocationId::from(parse_uuid(location_id)?));
+        }
+        for predicate in list(self.detail.as_deref()) {
+            let predicate = predicate
+                .parse()
+                .map_err(|e| ObserverError::InvalidQuery(format!("detail: {e}")))?;
+            filter = filter.with_detail(predicate);
+        }
+        Ok(filter)
+    }
+}
+
+/// The non-empty items of a comma-separated query parameter.
+fn list(param: Option<&str>) -> impl Iterator<Item = &str> {
+    param
+        .into_iter()
+        .flat_map(|param| param.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
 /// Query parameters for the `GET /api/agents` endpoint.
 #[derive(Debug, serde::Deserialize)]
 pub struct AgentsQuery {
@@ -56,7 +122,7 @@ pub struct AgentsQuery {
 /// This is the placeholder dashboard until the React frontend is built
 /// in Phase 4.
 pub async fn index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
     let tick = snapshot.current_tick;
     let era = format!("{:?}", snapshot.era);
     let season = format!("{:?}", snapshot.season);
@@ -148,7 +214,7 @@ pub async fn index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
         <li><a href="/api/agents/:id">/api/agents/:id</a> -- Single agent detail</li>
         <li><a href="/api/locations">/api/locations</a> -- List all locations</li>
         <li><a href="/api/locations/:id">/api/locations/:id</a> -- Single location detail</li>
-        <li><a href="/api/events">/api/events</a> -- Query events (?tick=N or ?agent_id=X)</li>
+        <li><a href="/api/events">/api/events</a> -- Query events (?tick=N, ?event_type=X, ?detail=P)</li>
     </ul>
 
     <h2>WebSocket</h2>
@@ -169,7 +235,7 @@ pub async fn index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
 pub async fn get_world(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     if let Some(ws) = &snapshot.world_snapshot {
         Ok(Json(serde_json::to_value(ws)?))
@@ -200,7 +266,7 @@ pub async fn list_agents(
     State(state): State<Arc<AppState>>,
     Query(params): Query<AgentsQuery>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let filter = params.status.as_deref().unwrap_or("all");
 
@@ -251,7 +317,7 @@ pub async fn get_agent(
     let id = parse_uuid(&id_str)?;
     let agent_id = emergence_types::AgentId::from(id);
 
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let agent = snapshot
         .agents
@@ -276,7 +342,7 @@ pub async fn get_agent(
 pub async fn list_locations(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let locations: Vec<serde_json::Value> = snapshot
         .locations
@@ -311,7 +377,7 @@ pub async fn get_location(
     let id = parse_uuid(&id_str)?;
     let location_id = emergence_types::LocationId::from(id);
 
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let location = snapshot
         .locations
@@ -347,7 +413,7 @@ pub async fn get_location(
 pub async fn list_routes(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let routes: Vec<serde_json::Value> = snapshot
         .routes
@@ -365,44 +431,31 @@ pub async fn list_routes(
 // GET /api/events -- query events
 // ---------------------------------------------------------------------------
 
-/// Query simulation events by tick or agent ID.
+/// Query simulation events through an [`EventFilter`](emergence_types::EventFilter).
 ///
 /// # Query Parameters
 ///
 /// - `tick`: Return events for a specific tick.
+/// - `from_tick`, `to_tick`: Return events with `from_tick <= tick < to_tick`.
+/// - `event_type`: Return events of these types (comma-separated).
 /// - `agent_id`: Return events involving a specific agent (UUID).
+/// - `location_id`: Return events at a specific location (UUID).
+/// - `detail`: Return events whose details satisfy every predicate
+///   (comma-separated, e.g. `resource=Wood,quantity>=5`).
 /// - `limit`: Maximum number of events to return (default 100, max 1000).
 pub async fn list_events(
     State(state): State<Arc<AppState>>,
     Query(params): Query<EventsQuery>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let limit = params.limit.unwrap_or(100).min(1000);
-
-    let agent_filter = params
-        .agent_id
-        .as_deref()
-        .map(parse_uuid)
-        .transpose()?
-        .map(emergence_types::AgentId::from);
+    let filter = params.filter()?;
 
     let events: Vec<&emergence_types::Event> = snapshot
         .events
         .iter()
-        .filter(|e| {
-            if let Some(tick) = params.tick
-                && e.tick != tick
-            {
-                return false;
-            }
-            if let Some(ref agent_id) = agent_filter
-                && e.agent_id.as_ref() != Some(agent_id)
-            {
-                return false;
-            }
-            true
-        })
+        .filter(|e| filter.matches(e))
         .take(limit)
         .collect();
 
@@ -442,7 +495,7 @@ pub async fn list_decisions(
     State(state): State<Arc<AppState>>,
     Query(params): Query<DecisionsQuery>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
     let limit = params.limit.unwrap_or(100).min(1000);
 
     let agent_filter = params
@@ -478,6 +531,70 @@ pub async fn list_decisions(
     })))
 }
 
+// ---------------------------------------------------------------------------
+// GET /api/runner/metrics -- runner LLM cost metrics
+// ---------------------------------------------------------------------------
+
+/// Latest LLM cost metrics from each agent runner.
+///
+/// Returns the most recent snapshot per runner partition (calls, errors,
+/// tokens, estimated cost, budget denials, per-backend breakdown) plus
+/// totals summed across runners. Costs are decimal strings.
+pub async fn runner_metrics(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ObserverError> {
+    let snapshot = state.snapshot.load();
+    let runners: Vec<&emergence_types::RunnerMetrics> =
+        snapshot.runner_metrics.values().collect();
+
+    let mut total_calls: u64 = 0;
+    let mut total_errors: u64 = 0;
+    let mut total_input_tokens: u64 = 0;
+    let mut total_output_tokens: u64 = 0;
+    let mut total_estimated_cost = rust_decimal::Decimal::ZERO;
+    let mut budget_denials: u64 = 0;
+    for runner in &runners {
+        total_calls = total_calls.saturating_add(runner.total_calls);
+        total_errors = total_errors.saturating_add(runner.total_errors);
+        total_input_tokens = total_input_tokens.saturating_add(runner.total_input_tokens);
+        total_output_tokens = total_output_tokens.saturating_add(runner.total_output_tokens);
+        total_estimated_cost = total_estimated_cost
+            .checked_add(runner.total_estimated_cost)
+            .unwrap_or(total_estimated_cost);
+        budget_denials = budget_denials.saturating_add(runner.budget_denials);
+    }
+
+    Ok(Json(serde_json::json!({
+        "count": runners.len(),
+        "totals": {
+            "total_calls": total_calls,
+            "total_errors": total_errors,
+            "total_input_tokens": total_input_tokens,
+            "total_output_tokens": total_output_tokens,
+            "total_estimated_cost": total_estimated_cost,
+            "budget_denials": budget_denials,
+        },
+        "runners": runners,
+    })))
+}
+
+// ---------------------------------------------------------------------------
+// GET /metrics -- engine metrics
+// ---------------------------------------------------------------------------
+
+/// Counters and histograms recorded by the engine's crates (actions and
+/// rejections, ledger entries and anomalies, regeneration and decay, flush
+/// latency), in the Prometheus text exposition format.
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        emergence_metrics::render(),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -29,6 +29,7 @@
 pub mod alerts;
 pub mod anomaly;
 pub mod error;
+pub mod export;
 pub mod handlers;
 pub mod operator;
 pub mod router;
@@ -42,4 +43,4 @@ pub mod ws;
 pub use router::build_router;
 pub use server::{start_server, ServerConfig, ServerError};
 pub use startup::{spawn_observer, StartupError};
-pub use state::{AppState, SimulationSnapshot, TickBroadcast};
+pub use state::{AppState, SimulationSnapshot, SnapshotCell, TickBroadcast};
@@ -167,7 +167,7 @@ pub async fn status(
         .as_ref()
         .ok_or_else(|| ObserverError::Internal("operator state not available".to_owned()))?;
 
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let agents_alive = snapshot
         .agent_states
@@ -12,6 +12,7 @@ use tower_http::trace::TraceLayer;
 
 use crate::alerts;
 use crate::anomaly;
+use crate::export;
 use crate::handlers;
 use crate::operator;
 use crate::social;
@@ -29,6 +30,10 @@ use crate::ws;
 /// - `GET /api/locations` -- list locations
 /// - `GET /api/locations/:id` -- single location
 /// - `GET /api/events` -- query events
+/// - `GET /api/decisions` -- query decision records
+/// - `GET /api/runner/metrics` -- agent runner LLM cost metrics
+/// - `GET /metrics` -- engine metrics in the Prometheus text format
+/// - `GET /api/export/training` -- JSONL fine-tuning pairs from LLM decisions
 /// - `POST /api/operator/pause` -- pause the tick loop
 /// - `POST /api/operator/resume` -- resume the tick loop
 /// - `POST /api/operator/speed` -- set tick interval
@@ -69,6 +74,9 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/api/events", get(handlers::list_events))
         .route("/api/routes", get(handlers::list_routes))
         .route("/api/decisions", get(handlers::list_decisions))
+        .route("/api/runner/metrics", get(handlers::runner_metrics))
+        .route("/metrics", get(handlers::metrics))
+        .route("/api/export/training", get(export::training_export))
         // Operator API (control endpoints)
         .route("/api/operator/pause", post(operator::pause))
         .route("/api/operator/resume", post(operator::resume))
@@ -22,6 +22,7 @@ use std::sync::Arc;
 use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Json;
+use im::OrdMap;
 use rust_decimal::Decimal;
 
 use crate::error::ObserverError;
@@ -40,7 +41,7 @@ use crate::state::AppState;
 pub async fn beliefs(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     // Detect belief systems by looking for shared knowledge clusters.
     let belief_keywords = [
@@ -156,7 +157,7 @@ fn format_belief_name(concept: &str) -> String {
 pub async fn governance(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let governance_knowledge = [
         "governance", "leadership", "law", "legislation", "territorial_claim",
@@ -277,7 +278,7 @@ pub async fn governance(
 pub async fn families(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let (lineage, max_generation) = build_lineage(&snapshot);
     let (family_units, parent_pair_count) = build_family_units(&snapshot);
@@ -436,7 +437,7 @@ fn build_family_units(
 pub async fn economy(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let alive_count = snapshot
         .agent_states
@@ -564,7 +565,7 @@ pub async fn economy(
 pub async fn crime(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ObserverError> {
-    let snapshot = state.snapshot.read().await;
+    let snapshot = state.snapshot.load();
 
     let alive_count = snapshot
         .agent_states
@@ -700,7 +701,7 @@ fn count_crimes(
 /// Build the serial offenders list from crime-by-agent counts.
 fn build_serial_offenders(
     crime_by_agent: &BTreeMap<emergence_types::AgentId, u32>,
-    agents: &BTreeMap<emergence_types::AgentId, emergence_types::Agent>,
+    agents: &OrdMap<emergence_types::AgentId, emergence_types::Agent>,
     events: &[emergence_types::Event],
     crime_types: &[&str],
 ) -> Vec<serde_json::Value> {
@@ -743,7 +744,7 @@ fn build_serial_offenders(
 /// Build crime hotspots from location crime counts.
 fn build_crime_hotspots(
     crime_by_location: &BTreeMap<emergence_types::LocationId, u32>,
-    locations: &BTreeMap<emergence_types::LocationId, emergence_types::Location>,
+    locations: &OrdMap<emergence_types::LocationId, emergence_types::Location>,
 ) -> Vec<serde_json::Value> {
     let mut hotspots: Vec<serde_json::Value> = Vec::new();
     let mut sorted_hotspots: Vec<_> = crime_by_location.iter().collect();
@@ -11,8 +11,10 @@ use std::sync::Arc;
 use emergence_core::operator::OperatorState;
 use emergence_types::{
     Agent, AgentId, AgentState, DecisionRecord, Era, Event, Location, LocationId, Route, RouteId,
-    Season, Weather, WorldSnapshot,
+    RunnerMetrics, Season, Weather, WorldSnapshot, WorldSnapshotDelta,
 };
+use arc_swap::ArcSwap;
+use im::OrdMap;
 use tokio::sync::{broadcast, RwLock};
 
 use crate::alerts::AlertStore;
@@ -50,26 +52,38 @@ pub struct TickBroadcast {
     pub deaths_this_tick: u32,
     /// Number of actions resolved this tick.
     pub actions_resolved: u32,
+    /// Changes to the world snapshot since the previous tick. `None` on the
+    /// first tick after startup; clients should fetch the full snapshot
+    /// over REST first.
+    #[serde(default)]
+    pub world_delta: Option<WorldSnapshotDelta>,
 }
 
 /// In-memory snapshot of the simulation state served by REST endpoints.
 ///
 /// Updated each tick by the engine. All reads are served from this
 /// snapshot so the observer never blocks the tick cycle.
+///
+/// The maps that grow with the world are persistent [`OrdMap`]s: cloning
+/// the snapshot shares them, and a tick's changes copy only the entries
+/// they touch, so publishing a new snapshot costs what changed rather
+/// than the size of the world.
 #[derive(Debug, Clone)]
 pub struct SimulationSnapshot {
     /// Agent identity records keyed by agent ID.
-    pub agents: BTreeMap<AgentId, Agent>,
+    pub agents: OrdMap<AgentId, Agent>,
     /// Agent mutable state keyed by agent ID.
-    pub agent_states: BTreeMap<AgentId, AgentState>,
+    pub agent_states: OrdMap<AgentId, AgentState>,
     /// Location definitions keyed by location ID.
-    pub locations: BTreeMap<LocationId, Location>,
+    pub locations: OrdMap<LocationId, Location>,
     /// Route definitions keyed by route ID.
-    pub routes: BTreeMap<RouteId, Route>,
+    pub routes: OrdMap<RouteId, Route>,
     /// Event log (most recent first, capped for memory).
     pub events: Vec<Event>,
     /// Recent decision records from the agent runner.
     pub decisions: Vec<DecisionRecord>,
+    /// Latest cost metrics from each agent runner, keyed by partition ID.
+    pub runner_metrics: BTreeMap<u32, RunnerMetrics>,
     /// The latest world snapshot.
     pub world_snapshot: Option<WorldSnapshot>,
     /// Current tick number.
@@ -85,12 +99,13 @@ pub struct SimulationSnapshot {
 impl Default for SimulationSnapshot {
     fn default() -> Self {
         Self {
-            agents: BTreeMap::new(),
-            agent_states: BTreeMap::new(),
-            locations: BTreeMap::new(),
-            routes: BTreeMap::new(),
+            agents: OrdMap::new(),
+            agent_states: OrdMap::new(),
+            locations: OrdMap::new(),
+            routes: OrdMap::new(),
             events: Vec::new(),
             decisions: Vec::new(),
+            runner_metrics: BTreeMap::new(),
             world_snapshot: None,
             current_tick: 0,
             era: Era::Primitive,
@@ -100,18 +115,66 @@ impl Default for SimulationSnapshot {
     }
 }
 
+/// The published [`SimulationSnapshot`], swapped atomically.
+///
+/// Readers take an [`Arc`] to the current snapshot and never wait on a
+/// writer; a snapshot, once published, is never modified. Writers clone the
+/// current snapshot, change the copy, and swap it in, retrying if another
+/// writer published first, so concurrent updates are never lost.
+#[derive(Debug)]
+pub struct SnapshotCell {
+    current: ArcSwap<SimulationSnapshot>,
+}
+
+impl SnapshotCell {
+    /// Create a cell publishing `snapshot`.
+    pub fn new(snapshot: SimulationSnapshot) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(snapshot),
+        }
+    }
+
+    /// The current snapshot.
+    pub fn load(&self) -> Arc<SimulationSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Replace the current snapshot.
+    pub fn publish(&self, snapshot: SimulationSnapshot) {
+        self.current.store(Arc::new(snapshot));
+    }
+
+    /// Publish a modified copy of the current snapshot.
+    ///
+    /// `f` may run more than once if another writer publishes while it is
+    /// running; each run starts from a fresh copy of the latest snapshot.
+    pub fn update(&self, mut f: impl FnMut(&mut SimulationSnapshot)) {
+        self.current.rcu(|current| {
+            let mut next = SimulationSnapshot::clone(current);
+            f(&mut next);
+            next
+        });
+    }
+}
+
+impl Default for SnapshotCell {
+    fn default() -> Self {
+        Self::new(SimulationSnapshot::default())
+    }
+}
+
 /// Shared state for the Axum application.
 ///
 /// Wrapped in [`Arc`] and injected via Axum's `State` extractor.
 /// The broadcast sender is used to push tick summaries to all
-/// connected `WebSocket` clients. The snapshot is a read-write
-/// lock protecting the simulation state.
+/// connected `WebSocket` clients. The snapshot is published through a
+/// [`SnapshotCell`], so REST handlers never block the engine.
 #[derive(Clone)]
 pub struct AppState {
     /// Broadcast sender for tick summary messages.
     pub tx: broadcast::Sender<TickBroadcast>,
     /// The current simulation snapshot (updated each tick).
-    pub snapshot: Arc<RwLock<SimulationSnapshot>>,
+    pub snapshot: Arc<SnapshotCell>,
     /// Shared operator control state (present when the simulation is running).
     pub operator_state: Option<Arc<OperatorState>>,
     /// In-memory alert store for containment and monitoring alerts.
@@ -124,7 +187,7 @@ impl AppState {
         let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
         Self {
             tx,
-            snapshot: Arc::new(RwLock::new(SimulationSnapshot::default())),
+            snapshot: Arc::new(SnapshotCell::default()),
             operator_state: None,
             alert_store: Arc::new(RwLock::new(AlertStore::new())),
         }
@@ -135,7 +198,7 @@ impl AppState {
         let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
         Self {
             tx,
-            snapshot: Arc::new(RwLock::new(SimulationSnapshot::default())),
+            snapshot: Arc::new(SnapshotCell::default()),
             operator_state: Some(operator),
             alert_store: Arc::new(RwLock::new(AlertStore::new())),
         }
@@ -165,3 +228,39 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readers_keep_the_snapshot_they_loaded() {
+        let cell = SnapshotCell::default();
+        let before = cell.load();
+        cell.update(|snap| snap.current_tick = 7);
+        assert_eq!(before.current_tick, 0);
+        assert_eq!(cell.load().current_tick, 7);
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let cell = Arc::new(SnapshotCell::default());
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        cell.update(|snap| {
+                            snap.current_tick = snap.current_tick.saturating_add(1);
+                        });
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            assert!(writer.join().is_ok());
+        }
+        let snap = cell.load();
+        assert_eq!(snap.current_tick, 200);
+    }
+}
@@ -13,7 +13,7 @@ use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use chrono::Utc;
 use emergence_observer::router::build_router;
-use emergence_observer::state::{AppState, TickBroadcast};
+use emergence_observer::state::{AppState, SimulationSnapshot, TickBroadcast};
 use emergence_types::{
     Agent, AgentId, AgentState, Era, Event, EventId, EventType, Location, LocationId, Resource,
     ResourceNode, Season, Weather, WorldContext,
@@ -22,7 +22,7 @@ use rust_decimal::Decimal;
 use serde_json::Value;
 use tower::ServiceExt;
 
-async fn make_test_state() -> Arc<AppState> {
+fn make_test_state() -> Arc<AppState> {
     let state = Arc::new(AppState::new());
 
     let agent_id = AgentId::new();
@@ -90,6 +90,7 @@ async fn make_test_state() -> Arc<AppState> {
         location_type: String::from("natural"),
         description: String::from("A grassy meadow."),
         capacity: 20,
+        elevation: 0,
         base_resources,
         discovered_by: BTreeSet::new(),
         created_at: Utc::now(),
@@ -111,20 +112,21 @@ async fn make_test_state() -> Arc<AppState> {
             population: 1,
         },
         created_at: Utc::now(),
+        caused_by: None,
+        correlation_id: None,
     };
 
     // Populate snapshot
-    {
-        let mut snap = state.snapshot.write().await;
-        snap.agents.insert(agent_id, agent);
-        snap.agent_states.insert(agent_id, agent_state);
-        snap.locations.insert(location_id, location);
-        snap.events.push(event);
-        snap.current_tick = 1;
-        snap.era = Era::Primitive;
-        snap.season = Season::Spring;
-        snap.weather = Weather::Clear;
-    }
+    let mut snap = SimulationSnapshot::default();
+    snap.agents.insert(agent_id, agent);
+    snap.agent_states.insert(agent_id, agent_state);
+    snap.locations.insert(location_id, location);
+    snap.events.push(event);
+    snap.current_tick = 1;
+    snap.era = Era::Primitive;
+    snap.season = Season::Spring;
+    snap.weather = Weather::Clear;
+    state.snapshot.publish(snap);
 
     state
 }
@@ -140,7 +142,7 @@ async fn body_to_json(body: Body) -> Value {
 
 #[tokio::test]
 async fn test_index_returns_html() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -160,7 +162,7 @@ async fn test_index_returns_html() {
 
 #[tokio::test]
 async fn test_get_world() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -175,7 +177,7 @@ async fn test_get_world() {
 
 #[tokio::test]
 async fn test_list_agents() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -195,7 +197,7 @@ async fn test_list_agents() {
 
 #[tokio::test]
 async fn test_list_agents_filter_alive() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -214,7 +216,7 @@ async fn test_list_agents_filter_alive() {
 
 #[tokio::test]
 async fn test_list_agents_filter_dead_returns_empty() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -233,10 +235,10 @@ async fn test_list_agents_filter_dead_returns_empty() {
 
 #[tokio::test]
 async fn test_get_agent_by_id() {
-    let state = make_test_state().await;
+    let state = make_test_state();
 
     let agent_id = {
-        let snap = state.snapshot.read().await;
+        let snap = state.snapshot.load();
         *snap.agents.keys().next().unwrap()
     };
 
@@ -255,7 +257,7 @@ async fn test_get_agent_by_id() {
 
 #[tokio::test]
 async fn test_get_agent_not_found() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let fake_id = uuid::Uuid::now_v7();
@@ -270,7 +272,7 @@ async fn test_get_agent_not_found() {
 
 #[tokio::test]
 async fn test_get_agent_invalid_uuid() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -287,7 +289,7 @@ async fn test_get_agent_invalid_uuid() {
 
 #[tokio::test]
 async fn test_list_locations() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -307,10 +309,10 @@ async fn test_list_locations() {
 
 #[tokio::test]
 async fn test_get_location_by_id() {
-    let state = make_test_state().await;
+    let state = make_test_state();
 
     let location_id = {
-        let snap = state.snapshot.read().await;
+        let snap = state.snapshot.load();
         *snap.locations.keys().next().unwrap()
     };
 
@@ -329,7 +331,7 @@ async fn test_get_location_by_id() {
 
 #[tokio::test]
 async fn test_list_events() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -348,7 +350,7 @@ async fn test_list_events() {
 
 #[tokio::test]
 async fn test_list_events_filter_by_tick() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -367,7 +369,7 @@ async fn test_list_events_filter_by_tick() {
 
 #[tokio::test]
 async fn test_list_events_filter_by_tick_no_match() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -384,6 +386,49 @@ async fn test_list_events_filter_by_tick_no_match() {
     assert_eq!(json["count"], 0);
 }
 
+#[tokio::test]
+async fn test_list_events_filter_by_type_and_detail() {
+    let state = make_test_state();
+    let router = build_router(state);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::get("/api/events?from_tick=0&to_tick=5&event_type=TickStart,TickEnd")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_to_json(response.into_body()).await;
+    assert_eq!(json.get("count").unwrap(), 1);
+
+    // The test event's details are empty, so no detail predicate holds.
+    let response = router
+        .clone()
+        .oneshot(
+            Request::get("/api/events?detail=quantity%3E%3D5")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_to_json(response.into_body()).await;
+    assert_eq!(json.get("count").unwrap(), 0);
+
+    let response = router
+        .oneshot(
+            Request::get("/api/events?event_type=NoSuchEvent")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_broadcast_channel() {
     let state = AppState::new();
@@ -396,6 +441,7 @@ async fn test_broadcast_channel() {
         agents_alive: 10,
         deaths_this_tick: 0,
         actions_resolved: 10,
+        world_delta: None,
     };
 
     let receivers = state.broadcast(&summary);
@@ -408,7 +454,7 @@ async fn test_broadcast_channel() {
 
 #[tokio::test]
 async fn test_nonexistent_route_returns_404() {
-    let state = make_test_state().await;
+    let state = make_test_state();
     let router = build_router(state);
 
     let response = router
@@ -422,3 +468,31 @@ async fn test_nonexistent_route_returns_404() {
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_metrics_endpoint_renders_prometheus_text() {
+    static PROBE: emergence_metrics::Counter =
+        emergence_metrics::Counter::new("test_observer_probe_total", "Probe counter.");
+    PROBE.increment(2);
+
+    let state = make_test_state();
+    let router = build_router(state);
+
+    let response = router
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(content_type.starts_with("text/plain"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(text.contains("# TYPE test_observer_probe_total counter"));
+    assert!(text.contains("test_observer_probe_total 2"));
+}
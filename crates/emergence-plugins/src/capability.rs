@@ -0,0 +1,73 @@
+//! Capabilities an operator can grant a plugin.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::PluginError;
+
+/// One permission granted to a plugin in its configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    /// See the season, era, and weather (the tick is always visible).
+    ReadClock,
+    /// See living agents' location, vitals, and inventory.
+    ReadAgents,
+    /// See locations, their occupants, and resource levels.
+    ReadLocations,
+    /// Register handlers for freeform action categories.
+    ResolveActions,
+    /// Queue events from the tick hook.
+    InjectEvents,
+}
+
+impl Capability {
+    /// Every capability, in declaration order.
+    pub const ALL: [Self; 5] = [
+        Self::ReadClock,
+        Self::ReadAgents,
+        Self::ReadLocations,
+        Self::ResolveActions,
+        Self::InjectEvents,
+    ];
+
+    /// The name used in configuration files.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadClock => "read_clock",
+            Self::ReadAgents => "read_agents",
+            Self::ReadLocations => "read_locations",
+            Self::ResolveActions => "resolve_actions",
+            Self::InjectEvents => "inject_events",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Capability {
+    type Err = PluginError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|capability| capability.as_str() == s)
+            .ok_or_else(|| PluginError::UnknownCapability(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for capability in Capability::ALL {
+            assert_eq!(capability.as_str().parse::<Capability>().ok(), Some(capability));
+        }
+        assert!("write_world".parse::<Capability>().is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! Error types for plugin loading and calls.
+
+/// Errors from loading or calling a plugin.
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    /// The module file could not be read.
+    #[error("failed to read plugin {path}: {source}")]
+    Io {
+        /// The module path.
+        path: String,
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+
+    /// The module failed to compile or instantiate.
+    #[error("failed to load plugin: {0}")]
+    Load(String),
+
+    /// The configuration names a capability that does not exist.
+    #[error("unknown plugin capability `{0}`")]
+    UnknownCapability(String),
+
+    /// The plugin registered something its capabilities do not allow, or
+    /// that its exports cannot serve.
+    #[error("plugin `{plugin}` rejected: {reason}")]
+    Registration {
+        /// The plugin's registered name.
+        plugin: String,
+        /// What was wrong.
+        reason: String,
+    },
+
+    /// A call into the plugin trapped, ran out of fuel, or broke the ABI.
+    #[error("plugin `{plugin}` failed: {message}")]
+    Call {
+        /// The plugin's registered name.
+        plugin: String,
+        /// What went wrong.
+        message: String,
+    },
+}
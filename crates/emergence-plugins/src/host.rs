@@ -0,0 +1,638 @@
+//! Loading plugins and calling into them.
+
+use std::collections::BTreeSet;
+use std::sync::{Mutex, PoisonError};
+
+use emergence_core::config::{PluginModuleConfig, PluginsConfig};
+use emergence_core::feasibility::{FeasibilityResult, ResolvedAction};
+use emergence_core::hooks::MechanicsHooks;
+use emergence_core::operator::InjectedEvent;
+use emergence_core::tick::{SimulationState, TickSummary};
+use emergence_types::{ActionParameters, ActionType, AgentId, FreeformAction};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{info, warn};
+use wasmtime::{
+    Caller, Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+use crate::capability::Capability;
+use crate::error::PluginError;
+use crate::view;
+
+/// Largest message a plugin may return, in bytes.
+const MAX_OUTPUT_BYTES: u32 = 1024 * 1024;
+
+/// What a plugin says it provides, returned by its `register` export.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Registration {
+    /// Name used in logs and errors.
+    pub name: String,
+    /// Freeform action categories this plugin resolves.
+    #[serde(default)]
+    pub action_categories: Vec<String>,
+    /// Whether `on_tick` should be called after every tick.
+    #[serde(default)]
+    pub tick_hook: bool,
+}
+
+/// The reply to a `resolve_action` call.
+#[derive(Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum ActionOutcome {
+    Resolved {
+        action_type: ActionType,
+        parameters: ActionParameters,
+        #[serde(default)]
+        energy_cost: u32,
+    },
+    Infeasible {
+        reason: String,
+    },
+    Pass,
+}
+
+/// The reply to an `on_tick` call.
+#[derive(Deserialize)]
+struct TickOutput {
+    #[serde(default)]
+    events: Vec<InjectedEvent>,
+}
+
+/// Per-store data: the name for log lines and the memory limiter.
+struct PluginData {
+    name: String,
+    limits: StoreLimits,
+}
+
+/// One instantiated plugin.
+struct Plugin {
+    registration: Registration,
+    capabilities: BTreeSet<Capability>,
+    fuel_per_call: u64,
+    store: Store<PluginData>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    resolve_action: Option<TypedFunc<(i32, i32), i64>>,
+    on_tick: Option<TypedFunc<(i32, i32), i64>>,
+}
+
+impl Plugin {
+    /// Call `func` with `input` and return the JSON it hands back, if any.
+    fn call(
+        &mut self,
+        func: &TypedFunc<(i32, i32), i64>,
+        input: &Value,
+    ) -> Result<Option<Value>, PluginError> {
+        let input = input.to_string();
+        let len = i32::try_from(input.len())
+            .map_err(|e| self.error(&format!("input too large: {e}")))?;
+        self.refuel()?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(|e| self.error(&format!("alloc trapped: {e}")))?;
+        self.memory
+            .write(&mut self.store, offset(ptr), input.as_bytes())
+            .map_err(|e| self.error(&format!("alloc returned an out-of-bounds buffer: {e}")))?;
+        let packed = func
+            .call(&mut self.store, (ptr, len))
+            .map_err(|e| self.error(&format!("trapped: {e}")))?;
+        self.read_output(packed)
+    }
+
+    /// Call the `register` export.
+    fn register(&mut self, func: &TypedFunc<(), i64>) -> Result<Option<Value>, PluginError> {
+        self.refuel()?;
+        let packed = func
+            .call(&mut self.store, ())
+            .map_err(|e| self.error(&format!("register trapped: {e}")))?;
+        self.read_output(packed)
+    }
+
+    fn refuel(&mut self) -> Result<(), PluginError> {
+        self.store
+            .set_fuel(self.fuel_per_call)
+            .map_err(|e| self.error(&format!("cannot set fuel: {e}")))
+    }
+
+    /// Decode a packed `(ptr << 32) | len` result into JSON.
+    fn read_output(&self, packed: i64) -> Result<Option<Value>, PluginError> {
+        if packed == 0 {
+            return Ok(None);
+        }
+        let bits = packed.cast_unsigned();
+        let ptr = u32::try_from(bits.checked_shr(32).unwrap_or(0)).unwrap_or(u32::MAX);
+        let len = u32::try_from(bits & u64::from(u32::MAX)).unwrap_or(u32::MAX);
+        if len > MAX_OUTPUT_BYTES {
+            return Err(self.error("output exceeds 1 MiB"));
+        }
+        let mut buf = vec![0_u8; usize::try_from(len).unwrap_or(0)];
+        self.memory
+            .read(&self.store, usize::try_from(ptr).unwrap_or(usize::MAX), &mut buf)
+            .map_err(|e| self.error(&format!("output pointer is out of bounds: {e}")))?;
+        serde_json::from_slice(&buf)
+            .map(Some)
+            .map_err(|e| self.error(&format!("invalid output JSON: {e}")))
+    }
+
+    fn error(&self, message: &str) -> PluginError {
+        PluginError::Call {
+            plugin: self.registration.name.clone(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Whether this plugin handles freeform actions of `category`.
+    fn handles(&self, category: &str) -> bool {
+        self.resolve_action.is_some()
+            && self
+                .registration
+                .action_categories
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(category))
+    }
+}
+
+/// A WASM offset from a plugin-supplied `i32`.
+fn offset(ptr: i32) -> usize {
+    usize::try_from(ptr.cast_unsigned()).unwrap_or(usize::MAX)
+}
+
+/// The set of loaded plugins, called in load order.
+pub struct PluginHost {
+    engine: Engine,
+    plugins: Vec<Mutex<Plugin>>,
+}
+
+impl std::fmt::Debug for PluginHost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginHost")
+            .field("plugins", &self.registrations())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PluginHost {
+    /// Create a host with no plugins.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginError::Load`] if the WASM engine cannot be created.
+    pub fn new() -> Result<Self, PluginError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| PluginError::Load(e.to_string()))?;
+        Ok(Self {
+            engine,
+            plugins: Vec::new(),
+        })
+    }
+
+    /// Create a host and load every module in `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`PluginHost::load_module`].
+    pub fn from_config(config: &PluginsConfig) -> Result<Self, PluginError> {
+        let mut host = Self::new()?;
+        for module in &config.modules {
+            host.load_module(module)?;
+        }
+        Ok(host)
+    }
+
+    /// Load one configured module from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginError::Io`] if the file cannot be read, and
+    /// otherwise as [`PluginHost::load`].
+    pub fn load_module(
+        &mut self,
+        module: &PluginModuleConfig,
+    ) -> Result<&Registration, PluginError> {
+        let bytes = std::fs::read(&module.path).map_err(|source| PluginError::Io {
+            path: module.path.clone(),
+            source,
+        })?;
+        let capabilities = module
+            .capabilities
+            .iter()
+            .map(|c| c.parse())
+            .collect::<Result<BTreeSet<Capability>, _>>()?;
+        let max_memory = usize::try_from(module.max_memory_mib)
+            .unwrap_or(usize::MAX)
+            .saturating_mul(1024 * 1024);
+        self.load(&bytes, capabilities, module.fuel_per_call, max_memory)
+    }
+
+    /// Compile and instantiate a module (binary or text format) with the
+    /// given capabilities and limits, then check its registration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginError::Load`] if the module does not compile, links
+    /// against anything but `emergence.log`, or lacks a required export,
+    /// and [`PluginError::Registration`] if it registers something its
+    /// capabilities or exports do not support.
+    pub fn load(
+        &mut self,
+        bytes: &[u8],
+        capabilities: BTreeSet<Capability>,
+        fuel_per_call: u64,
+        max_memory_bytes: usize,
+    ) -> Result<&Registration, PluginError> {
+        let load = |e: wasmtime::Error| PluginError::Load(e.to_string());
+        let module = Module::new(&self.engine, bytes).map_err(load)?;
+        let mut store = Store::new(
+            &self.engine,
+            PluginData {
+                name: String::from("<loading>"),
+                limits: StoreLimitsBuilder::new().memory_size(max_memory_bytes).build(),
+            },
+        );
+        store.limiter(|data| &mut data.limits);
+        store.set_fuel(fuel_per_call).map_err(load)?;
+
+        let mut linker = wasmtime::Linker::new(&self.engine);
+        linker
+            .func_wrap("emergence", "log", plugin_log)
+            .map_err(load)?;
+        let instance: Instance = linker.instantiate(&mut store, &module).map_err(load)?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| PluginError::Load("module does not export `memory`".to_owned()))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(load)?;
+        let register = instance
+            .get_typed_func::<(), i64>(&mut store, "register")
+            .map_err(load)?;
+        let resolve_action = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "resolve_action")
+            .ok();
+        let on_tick = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "on_tick")
+            .ok();
+
+        let mut plugin = Plugin {
+            registration: Registration {
+                name: String::from("<unregistered>"),
+                action_categories: Vec::new(),
+                tick_hook: false,
+            },
+            capabilities,
+            fuel_per_call,
+            store,
+            memory,
+            alloc,
+            resolve_action,
+            on_tick,
+        };
+        let registration: Registration = plugin
+            .register(&register)?
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| plugin.error(&format!("invalid registration: {e}")))?
+            .ok_or_else(|| plugin.error("register returned nothing"))?;
+        check_registration(&registration, &plugin)?;
+        plugin.store.data_mut().name.clone_from(&registration.name);
+
+        info!(
+            plugin = registration.name,
+            action_categories = ?registration.action_categories,
+            tick_hook = registration.tick_hook,
+            capabilities = ?plugin.capabilities,
+            "Loaded plugin"
+        );
+        plugin.registration = registration;
+        self.plugins.push(Mutex::new(plugin));
+        Ok(&self
+            .plugins
+            .last_mut()
+            .map(|p| p.get_mut().unwrap_or_else(PoisonError::into_inner))
+            .ok_or_else(|| PluginError::Load("plugin vanished".to_owned()))?
+            .registration)
+    }
+
+    /// Registrations of every loaded plugin, in call order.
+    pub fn registrations(&self) -> Vec<Registration> {
+        self.plugins
+            .iter()
+            .map(|p| p.lock().unwrap_or_else(PoisonError::into_inner).registration.clone())
+            .collect()
+    }
+
+    /// Offer a freeform action to each plugin registered for its
+    /// category, given the world view builder `world`. The first plugin
+    /// that does not pass decides.
+    fn resolve(
+        &self,
+        tick: u64,
+        agent_id: AgentId,
+        action: &FreeformAction,
+        world: impl Fn(&BTreeSet<Capability>) -> Value,
+    ) -> Option<FeasibilityResult> {
+        for plugin in &self.plugins {
+            let mut plugin = plugin.lock().unwrap_or_else(PoisonError::into_inner);
+            if !plugin.handles(&action.action_category) {
+                continue;
+            }
+            let Some(func) = plugin.resolve_action.clone() else {
+                continue;
+            };
+            let input = json!({
+                "tick": tick,
+                "agent_id": agent_id,
+                "action": action,
+                "world": world(&plugin.capabilities),
+            });
+            match plugin.call(&func, &input).map(|out| out.map(serde_json::from_value)) {
+                Ok(Some(Ok(ActionOutcome::Resolved {
+                    action_type,
+                    parameters,
+                    energy_cost,
+                }))) if action_type != ActionType::Freeform => {
+                    return Some(FeasibilityResult::Feasible {
+                        resolved_action: ResolvedAction {
+                            action_type,
+                            parameters,
+                        },
+                        energy_cost,
+                    });
+                }
+                Ok(Some(Ok(ActionOutcome::Infeasible { reason }))) => {
+                    return Some(FeasibilityResult::Infeasible { reason });
+                }
+                Ok(None | Some(Ok(ActionOutcome::Pass))) => {}
+                Ok(Some(Ok(ActionOutcome::Resolved { .. }))) => {
+                    warn!(plugin = plugin.registration.name, "Plugin resolved to Freeform");
+                }
+                Ok(Some(Err(e))) => {
+                    warn!(
+                        plugin = plugin.registration.name,
+                        error = %e,
+                        "Bad resolve_action reply"
+                    );
+                }
+                Err(e) => warn!(error = %e, "Plugin action handler failed"),
+            }
+        }
+        None
+    }
+
+    /// Run every tick hook, returning the events plugins may inject.
+    fn tick(
+        &self,
+        summary: &TickSummary,
+        world: impl Fn(&BTreeSet<Capability>) -> Value,
+    ) -> Vec<InjectedEvent> {
+        let mut injected = Vec::new();
+        for plugin in &self.plugins {
+            let mut plugin = plugin.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(func) = plugin.on_tick.clone().filter(|_| plugin.registration.tick_hook)
+            else {
+                continue;
+            };
+            let input = json!({
+                "tick": summary.tick,
+                "agents_alive": summary.agents_alive,
+                "deaths": summary.deaths.len(),
+                "world": world(&plugin.capabilities),
+            });
+            let output = match plugin.call(&func, &input) {
+                Ok(Some(output)) => output,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(error = %e, "Plugin tick hook failed");
+                    continue;
+                }
+            };
+            let events = match serde_json::from_value::<TickOutput>(output) {
+                Ok(output) => output.events,
+                Err(e) => {
+                    warn!(plugin = plugin.registration.name, error = %e, "Bad on_tick reply");
+                    continue;
+                }
+            };
+            if events.is_empty() {
+                continue;
+            }
+            if plugin.capabilities.contains(&Capability::InjectEvents) {
+                injected.extend(events);
+            } else {
+                warn!(
+                    plugin = plugin.registration.name,
+                    dropped = events.len(),
+                    "Plugin returned events without the inject_events capability"
+                );
+            }
+        }
+        injected
+    }
+}
+
+impl MechanicsHooks for PluginHost {
+    fn resolve_freeform(
+        &self,
+        state: &SimulationState,
+        agent_id: AgentId,
+        action: &FreeformAction,
+    ) -> Option<FeasibilityResult> {
+        self.resolve(state.clock.tick(), agent_id, action, |caps| {
+            view::world_view(state, caps)
+        })
+    }
+
+    fn after_tick(&self, state: &SimulationState, summary: &TickSummary) -> Vec<InjectedEvent> {
+        self.tick(summary, |caps| view::world_view(state, caps))
+    }
+}
+
+/// Reject registrations the plugin is not allowed or able to serve.
+fn check_registration(registration: &Registration, plugin: &Plugin) -> Result<(), PluginError> {
+    let reject = |reason: &str| PluginError::Registration {
+        plugin: registration.name.clone(),
+        reason: reason.to_owned(),
+    };
+    if registration.name.trim().is_empty() {
+        return Err(reject("name is empty"));
+    }
+    if !registration.action_categories.is_empty() {
+        if !plugin.capabilities.contains(&Capability::ResolveActions) {
+            return Err(reject("action handlers need the resolve_actions capability"));
+        }
+        if plugin.resolve_action.is_none() {
+            return Err(reject("action handlers need a `resolve_action` export"));
+        }
+    }
+    if registration.tick_hook && plugin.on_tick.is_none() {
+        return Err(reject("a tick hook needs an `on_tick` export"));
+    }
+    Ok(())
+}
+
+/// The `emergence.log` import: log a UTF-8 message from plugin memory.
+fn plugin_log(mut caller: Caller<'_, PluginData>, ptr: i32, len: i32) {
+    let Some(memory) = caller.get_export("memory").and_then(wasmtime::Extern::into_memory) else {
+        return;
+    };
+    let start = offset(ptr);
+    let end = start.saturating_add(offset(len));
+    let message = memory
+        .data(&caller)
+        .get(start..end)
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+    if let Some(message) = message {
+        info!(plugin = caller.data().name, "{message}");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::arithmetic_side_effects)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{Season, Weather};
+
+    use std::fmt::Write as _;
+
+    use super::*;
+
+    const FUEL: u64 = 1_000_000;
+    const MEMORY: usize = 4 * 1024 * 1024;
+
+    /// A WAT plugin whose `register` returns `registration` and whose
+    /// `resolve_action` / `on_tick` bodies are `funcs`. Each `(offset, json)`
+    /// in `replies` is laid out as a data segment.
+    fn plugin(registration: &str, replies: &[(u32, &str)], funcs: &str) -> String {
+        let mut data = format!("(data (i32.const 1024) \"{}\")\n", escape(registration));
+        for (offset, json) in replies {
+            let _ = writeln!(data, "(data (i32.const {offset}) \"{}\")", escape(json));
+        }
+        format!(
+            r#"(module
+                (import "emergence" "log" (func $log (param i32 i32)))
+                (memory (export "memory") 1)
+                {data}
+                (func (export "alloc") (param i32) (result i32) i32.const 32768)
+                (func (export "register") (result i64) i64.const {})
+                {funcs})"#,
+            packed(1024, registration)
+        )
+    }
+
+    fn escape(json: &str) -> String {
+        json.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    fn packed(offset: u32, json: &str) -> i64 {
+        (i64::from(offset) << 32) | i64::try_from(json.len()).unwrap()
+    }
+
+    fn load(wat: &str, capabilities: &[Capability]) -> Result<PluginHost, PluginError> {
+        let mut host = PluginHost::new()?;
+        host.load(wat.as_bytes(), capabilities.iter().copied().collect(), FUEL, MEMORY)?;
+        Ok(host)
+    }
+
+    fn freeform(category: &str) -> FreeformAction {
+        FreeformAction {
+            intent: String::from("kneel at the altar"),
+            action_category: category.to_owned(),
+            target: None,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    fn summary() -> TickSummary {
+        TickSummary {
+            tick: 7,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            agents_alive: 3,
+            deaths: Vec::new(),
+            action_results: BTreeMap::new(),
+            regeneration: BTreeMap::new(),
+            world_event_logs: Vec::new(),
+            disasters: Vec::new(),
+            fires: Vec::new(),
+            trade_routes: Vec::new(),
+            inheritances: Vec::new(),
+            market_fills: Vec::new(),
+        }
+    }
+
+    const ALTAR: &str = r#"{"name":"altar","action_categories":["pray"]}"#;
+    const RESOLVED: &str =
+        r#"{"outcome":"resolved","action_type":"Rest","parameters":"Rest","energy_cost":2}"#;
+
+    #[test]
+    fn resolves_registered_category() {
+        let funcs = format!(
+            "(func (export \"resolve_action\") (param i32 i32) (result i64)
+                local.get 0 local.get 1 call $log
+                i64.const {})",
+            packed(4096, RESOLVED)
+        );
+        let wat = plugin(ALTAR, &[(4096, RESOLVED)], &funcs);
+        let host = load(&wat, &[Capability::ResolveActions]).unwrap();
+        assert_eq!(host.registrations().first().map(|r| r.name.as_str()), Some("altar"));
+
+        let agent = AgentId::new();
+        let result = host.resolve(1, agent, &freeform("Pray"), |_| json!({}));
+        assert!(matches!(
+            result,
+            Some(FeasibilityResult::Feasible {
+                resolved_action: ResolvedAction {
+                    action_type: ActionType::Rest,
+                    parameters: ActionParameters::Rest,
+                },
+                energy_cost: 2,
+            })
+        ));
+        assert!(host.resolve(1, agent, &freeform("dance"), |_| json!({})).is_none());
+    }
+
+    #[test]
+    fn runaway_plugin_runs_out_of_fuel() {
+        let funcs = "(func (export \"resolve_action\") (param i32 i32) (result i64)
+                (loop br 0)
+                i64.const 0)";
+        let host = load(&plugin(ALTAR, &[], funcs), &[Capability::ResolveActions]).unwrap();
+        let result = host.resolve(1, AgentId::new(), &freeform("pray"), |_| json!({}));
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tick_events_need_inject_capability() {
+        let registration = r#"{"name":"omens","tick_hook":true}"#;
+        let reply = r#"{"events":[{"event_type":"plague","description":"an omen"}]}"#;
+        let funcs = format!(
+            "(func (export \"on_tick\") (param i32 i32) (result i64) i64.const {})",
+            packed(4096, reply)
+        );
+        let wat = plugin(registration, &[(4096, reply)], &funcs);
+
+        let granted = load(&wat, &[Capability::InjectEvents]).unwrap();
+        let events = granted.tick(&summary(), |_| json!({}));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events.first().map(|e| e.event_type.as_str()), Some("plague"));
+
+        let denied = load(&wat, &[]).unwrap();
+        assert!(denied.tick(&summary(), |_| json!({})).is_empty());
+    }
+
+    #[test]
+    fn rejects_handlers_without_capability() {
+        let funcs = "(func (export \"resolve_action\") (param i32 i32) (result i64) i64.const 0)";
+        let result = load(&plugin(ALTAR, &[], funcs), &[Capability::ReadAgents]);
+        assert!(matches!(result, Err(PluginError::Registration { .. })));
+
+        let missing_export = load(&plugin(ALTAR, &[], ""), &[Capability::ResolveActions]);
+        assert!(matches!(missing_export, Err(PluginError::Registration { .. })));
+    }
+}
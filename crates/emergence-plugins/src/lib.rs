@@ -0,0 +1,63 @@
+//! Sandboxed WASM plugins for custom Emergence mechanics.
+//!
+//! A plugin is a WebAssembly module loaded by the engine at startup (see
+//! the `plugins` section of `emergence-config.yaml`). It can register
+//!
+//! - **action handlers** for freeform action categories (e.g. `pray`,
+//!   `marry`), which resolve the action into a concrete one or reject it
+//!   before the built-in feasibility evaluator runs, and
+//! - a **tick hook**, which observes each finished tick and may return
+//!   events to inject at the start of the next one.
+//!
+//! [`PluginHost`] implements [`emergence_core::hooks::MechanicsHooks`], so
+//! attaching it to `SimulationState::hooks` is all the wiring needed.
+//!
+//! # Sandbox
+//!
+//! Plugins run without WASI: the only host import is `emergence.log`.
+//! Each call gets a fixed fuel budget and the module's memory is capped,
+//! so a runaway plugin traps instead of stalling the tick. A plugin that
+//! traps or returns garbage is logged and treated as having no opinion.
+//!
+//! World state is passed in as JSON, and only the parts covered by the
+//! plugin's granted [`Capability`] set are included.
+//!
+//! # ABI
+//!
+//! All messages are UTF-8 JSON in the plugin's linear memory. A pointer
+//! and length are returned packed into one `i64` as `(ptr << 32) | len`;
+//! `0` means "no output".
+//!
+//! | Export | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `memory` | memory | Linear memory shared with the host |
+//! | `alloc` | `(len: i32) -> i32` | Reserve `len` bytes for host input |
+//! | `register` | `() -> i64` | Return the [`Registration`] JSON |
+//! | `resolve_action` | `(ptr: i32, len: i32) -> i64` | Handle a freeform action (optional) |
+//! | `on_tick` | `(ptr: i32, len: i32) -> i64` | Observe a finished tick (optional) |
+//!
+//! | Import | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `emergence.log` | `(ptr: i32, len: i32)` | Write a UTF-8 message to the engine log |
+//!
+//! `resolve_action` receives `{"tick", "agent_id", "action", "world"}` and
+//! returns one of
+//!
+//! ```json
+//! {"outcome": "resolved", "action_type": "Rest", "parameters": "Rest", "energy_cost": 5}
+//! {"outcome": "infeasible", "reason": "no altar here"}
+//! {"outcome": "pass"}
+//! ```
+//!
+//! `on_tick` receives `{"tick", "agents_alive", "deaths", "world"}` and
+//! returns `{"events": [...]}` with the same fields as an operator
+//! `inject-event` request.
+
+mod capability;
+mod error;
+mod host;
+mod view;
+
+pub use capability::Capability;
+pub use error::PluginError;
+pub use host::{PluginHost, Registration};
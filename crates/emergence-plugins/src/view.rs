@@ -0,0 +1,72 @@
+//! The capability-scoped world view handed to plugins.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use emergence_core::tick::SimulationState;
+use emergence_types::Resource;
+use serde_json::{Map, Value, json};
+
+use crate::capability::Capability;
+
+/// Build the `world` object a plugin may see.
+///
+/// `tick` is always present; everything else is included only when the
+/// matching capability is granted.
+pub fn world_view(state: &SimulationState, capabilities: &BTreeSet<Capability>) -> Value {
+    let mut view = Map::new();
+    view.insert("tick".to_owned(), json!(state.clock.tick()));
+
+    if capabilities.contains(&Capability::ReadClock) {
+        view.insert("era".to_owned(), json!(state.clock.era()));
+        view.insert("season".to_owned(), json!(state.clock.season().ok()));
+        view.insert("weather".to_owned(), json!(state.weather_system.previous_weather()));
+    }
+
+    if capabilities.contains(&Capability::ReadAgents) {
+        let agents: Vec<Value> = state
+            .alive_agents
+            .iter()
+            .filter_map(|id| state.agent_states.get(id).map(|s| (id, s)))
+            .map(|(id, s)| {
+                json!({
+                    "id": id,
+                    "name": state.agent_names.get(id),
+                    "location_id": s.location_id,
+                    "traveling": s.destination_id.is_some(),
+                    "energy": s.energy,
+                    "health": s.health,
+                    "hunger": s.hunger,
+                    "thirst": s.thirst,
+                    "age": s.age,
+                    "inventory": s.inventory,
+                    "knowledge": s.knowledge,
+                })
+            })
+            .collect();
+        view.insert("agents".to_owned(), Value::Array(agents));
+    }
+
+    if capabilities.contains(&Capability::ReadLocations) {
+        let locations: Vec<Value> = state
+            .world_map
+            .locations()
+            .map(|(id, loc)| {
+                let resources: BTreeMap<Resource, u32> = loc
+                    .resources()
+                    .iter()
+                    .map(|(resource, node)| (*resource, node.available))
+                    .collect();
+                json!({
+                    "id": id,
+                    "name": loc.location.name,
+                    "region": loc.location.region,
+                    "occupants": loc.occupants,
+                    "resources": resources,
+                })
+            })
+            .collect();
+        view.insert("locations".to_owned(), Value::Array(locations));
+    }
+
+    Value::Object(view)
+}
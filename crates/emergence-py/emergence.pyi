@@ -0,0 +1,100 @@
+"""Read-only access to Emergence simulation runs."""
+
+import datetime
+import decimal
+from typing import Any
+
+class StoreError(Exception):
+    """A query against the simulation database failed."""
+
+class AgentStateSnapshot:
+    energy: int
+    health: int
+    hunger: int
+    age: int
+    location_id: str
+    inventory_summary: dict[str, int]
+
+class WorldContext:
+    tick: int
+    era: str
+    season: str
+    weather: str
+    population: int
+
+class Event:
+    id: int
+    tick: int
+    event_type: str
+    agent_id: str | None
+    location_id: str | None
+    event_id: str | None
+    caused_by: str | None
+    correlation_id: str | None
+    @property
+    def details(self) -> Any: ...
+    @property
+    def created_at(self) -> datetime.datetime: ...
+    def agent_state(self) -> AgentStateSnapshot | None: ...
+    def world_context(self) -> WorldContext | None: ...
+
+class LedgerEntry:
+    id: str
+    tick: int
+    entry_type: str
+    from_entity: str | None
+    from_entity_type: str | None
+    to_entity: str | None
+    to_entity_type: str | None
+    resource: str
+    reason: str
+    reference_id: str | None
+    @property
+    def quantity(self) -> decimal.Decimal: ...
+    @property
+    def created_at(self) -> datetime.datetime: ...
+
+class WorldSnapshot:
+    tick: int
+    era: str
+    season: str
+    weather: str
+    population: int
+    births: int
+    deaths: int
+    trades_this_tick: int
+    discoveries_count: int
+    @property
+    def total_resources(self) -> Any: ...
+    @property
+    def wealth_distribution(self) -> Any: ...
+    @property
+    def summary(self) -> Any: ...
+    @property
+    def created_at(self) -> datetime.datetime: ...
+
+class AgentSnapshot:
+    id: int
+    tick: int
+    agent_id: str
+    @property
+    def full_state(self) -> Any: ...
+    @property
+    def created_at(self) -> datetime.datetime: ...
+
+class Store:
+    def __init__(self, url: str) -> None: ...
+    def events_at_tick(self, tick: int) -> list[Event]: ...
+    def agent_events(self, agent_id: str, from_tick: int, to_tick: int) -> list[Event]: ...
+    def causal_chain(self, event_id: str) -> list[Event]: ...
+    def consequences(self, event_id: str) -> list[Event]: ...
+    def correlated_events(self, correlation_id: str) -> list[Event]: ...
+    def ledger_at_tick(self, tick: int) -> list[LedgerEntry]: ...
+    def entity_ledger(self, entity_id: str) -> list[LedgerEntry]: ...
+    def world_snapshot(self, tick: int) -> WorldSnapshot | None: ...
+    def recent_world_snapshots(self, count: int) -> list[WorldSnapshot]: ...
+    def latest_agent_snapshot(self, agent_id: str) -> AgentSnapshot | None: ...
+    def agent_snapshots(
+        self, agent_id: str, from_tick: int, to_tick: int
+    ) -> list[AgentSnapshot]: ...
+    def close(self) -> None: ...
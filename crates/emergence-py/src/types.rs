@@ -0,0 +1,460 @@
+//! Python classes for stored rows and canonical types.
+//!
+//! Each class is a frozen, read-only view built from an `emergence-db` row
+//! or an `emergence-types` struct. Plain columns are attributes; JSON
+//! columns become Python objects on access, decimals become
+//! `decimal.Decimal`, and timestamps become timezone-aware
+//! `datetime.datetime` values.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use emergence_db::{AgentSnapshotRow, EventRow, LedgerRow, WorldSnapshotRow};
+use pyo3::prelude::*;
+
+// ---------------------------------------------------------------------------
+// Conversions
+// ---------------------------------------------------------------------------
+
+/// Convert a JSON value into the equivalent Python object.
+pub fn json_to_py<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (value.to_string(),))
+}
+
+/// Convert a UTC timestamp into an aware `datetime.datetime`.
+fn datetime_to_py<'py>(py: Python<'py>, value: &DateTime<Utc>) -> PyResult<Bound<'py, PyAny>> {
+    // Microsecond precision: `fromisoformat` rejects longer fractions.
+    let iso = value.to_rfc3339_opts(SecondsFormat::Micros, false);
+    py.import("datetime")?
+        .getattr("datetime")?
+        .call_method1("fromisoformat", (iso,))
+}
+
+/// The serde name of an enum variant, e.g. `Wood` for `Resource::Wood`.
+fn variant_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Event
+// ---------------------------------------------------------------------------
+
+/// A row from the `events` table.
+#[pyclass(frozen, module = "emergence")]
+pub struct Event {
+    /// Auto-incremented event ID.
+    #[pyo3(get)]
+    id: i64,
+    /// The tick when this event occurred.
+    #[pyo3(get)]
+    tick: i64,
+    /// Event type, e.g. `"resource_gathered"`.
+    #[pyo3(get)]
+    event_type: String,
+    /// Primary agent involved, if any.
+    #[pyo3(get)]
+    agent_id: Option<String>,
+    /// Location where the event occurred, if any.
+    #[pyo3(get)]
+    location_id: Option<String>,
+    /// The event's own UUID, if recorded.
+    #[pyo3(get)]
+    event_id: Option<String>,
+    /// UUID of the event that directly caused this one, if any.
+    #[pyo3(get)]
+    caused_by: Option<String>,
+    /// The cascade this event belongs to, if any.
+    #[pyo3(get)]
+    correlation_id: Option<String>,
+    details: serde_json::Value,
+    agent_state_snapshot: Option<serde_json::Value>,
+    world_context: Option<serde_json::Value>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<EventRow> for Event {
+    fn from(row: EventRow) -> Self {
+        Self {
+            id: row.id,
+            tick: row.tick,
+            event_type: row.event_type,
+            agent_id: row.agent_id.map(|id| id.to_string()),
+            location_id: row.location_id.map(|id| id.to_string()),
+            event_id: row.event_id.map(|id| id.to_string()),
+            caused_by: row.caused_by.map(|id| id.to_string()),
+            correlation_id: row.correlation_id.map(|id| id.to_string()),
+            details: row.details,
+            agent_state_snapshot: row.agent_state_snapshot,
+            world_context: row.world_context,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[pymethods]
+impl Event {
+    /// Type-specific payload as a Python object.
+    #[getter]
+    fn details<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.details)
+    }
+
+    /// The agent's state when the event happened, if recorded.
+    ///
+    /// Returns `None` when the event has no snapshot or it does not match
+    /// the canonical shape.
+    fn agent_state(&self) -> Option<AgentStateSnapshot> {
+        let value = self.agent_state_snapshot.clone()?;
+        serde_json::from_value::<emergence_types::AgentStateSnapshot>(value)
+            .ok()
+            .map(AgentStateSnapshot::from)
+    }
+
+    /// The world context when the event happened, if recorded.
+    fn world_context(&self) -> Option<WorldContext> {
+        let value = self.world_context.clone()?;
+        serde_json::from_value::<emergence_types::WorldContext>(value)
+            .ok()
+            .map(WorldContext::from)
+    }
+
+    /// Real-world timestamp.
+    #[getter]
+    fn created_at<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        datetime_to_py(py, &self.created_at)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Event(id={}, tick={}, event_type={:?}, agent_id={:?})",
+            self.id, self.tick, self.event_type, self.agent_id
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ledger
+// ---------------------------------------------------------------------------
+
+/// A row from the `ledger` table.
+#[pyclass(frozen, module = "emergence")]
+pub struct LedgerEntry {
+    /// Ledger entry UUID.
+    #[pyo3(get)]
+    id: String,
+    /// Tick when the transfer occurred.
+    #[pyo3(get)]
+    tick: i64,
+    /// Entry type, e.g. `"transfer"`.
+    #[pyo3(get)]
+    entry_type: String,
+    /// Source entity UUID.
+    #[pyo3(get)]
+    from_entity: Option<String>,
+    /// Source entity type.
+    #[pyo3(get)]
+    from_entity_type: Option<String>,
+    /// Destination entity UUID.
+    #[pyo3(get)]
+    to_entity: Option<String>,
+    /// Destination entity type.
+    #[pyo3(get)]
+    to_entity_type: Option<String>,
+    /// Resource type name.
+    #[pyo3(get)]
+    resource: String,
+    quantity: rust_decimal::Decimal,
+    /// Reason for the transfer.
+    #[pyo3(get)]
+    reason: String,
+    /// Related entity ID (trade, structure, etc.).
+    #[pyo3(get)]
+    reference_id: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<LedgerRow> for LedgerEntry {
+    fn from(row: LedgerRow) -> Self {
+        Self {
+            id: row.id.to_string(),
+            tick: row.tick,
+            entry_type: row.entry_type,
+            from_entity: row.from_entity.map(|id| id.to_string()),
+            from_entity_type: row.from_entity_type,
+            to_entity: row.to_entity.map(|id| id.to_string()),
+            to_entity_type: row.to_entity_type,
+            resource: row.resource,
+            quantity: row.quantity,
+            reason: row.reason,
+            reference_id: row.reference_id.map(|id| id.to_string()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[pymethods]
+impl LedgerEntry {
+    /// Quantity transferred, as a `decimal.Decimal`.
+    #[getter]
+    fn quantity<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import("decimal")?
+            .getattr("Decimal")?
+            .call1((self.quantity.to_string(),))
+    }
+
+    /// Real-world timestamp.
+    #[getter]
+    fn created_at<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        datetime_to_py(py, &self.created_at)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LedgerEntry(tick={}, entry_type={:?}, resource={:?}, quantity={})",
+            self.tick, self.entry_type, self.resource, self.quantity
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Snapshots
+// ---------------------------------------------------------------------------
+
+/// A row from the `world_snapshots` table.
+#[pyclass(frozen, module = "emergence")]
+pub struct WorldSnapshot {
+    /// The tick this snapshot represents.
+    #[pyo3(get)]
+    tick: i64,
+    /// Current era.
+    #[pyo3(get)]
+    era: String,
+    /// Current season.
+    #[pyo3(get)]
+    season: String,
+    /// Current weather.
+    #[pyo3(get)]
+    weather: String,
+    /// Number of living agents.
+    #[pyo3(get)]
+    population: i32,
+    /// Agents born this tick.
+    #[pyo3(get)]
+    births: i32,
+    /// Agents who died this tick.
+    #[pyo3(get)]
+    deaths: i32,
+    total_resources: serde_json::Value,
+    wealth_distribution: serde_json::Value,
+    /// Number of trades this tick.
+    #[pyo3(get)]
+    trades_this_tick: i32,
+    /// Total discoveries to date.
+    #[pyo3(get)]
+    discoveries_count: i32,
+    summary: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
+impl From<WorldSnapshotRow> for WorldSnapshot {
+    fn from(row: WorldSnapshotRow) -> Self {
+        Self {
+            tick: row.tick,
+            era: row.era,
+            season: row.season,
+            weather: row.weather,
+            population: row.population,
+            births: row.births,
+            deaths: row.deaths,
+            total_resources: row.total_resources,
+            wealth_distribution: row.wealth_distribution,
+            trades_this_tick: row.trades_this_tick,
+            discoveries_count: row.discoveries_count,
+            summary: row.summary,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[pymethods]
+impl WorldSnapshot {
+    /// Total resources in the simulation.
+    #[getter]
+    fn total_resources<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.total_resources)
+    }
+
+    /// Wealth distribution statistics.
+    #[getter]
+    fn wealth_distribution<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.wealth_distribution)
+    }
+
+    /// Narrative summary.
+    #[getter]
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.summary)
+    }
+
+    /// Real-world timestamp.
+    #[getter]
+    fn created_at<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        datetime_to_py(py, &self.created_at)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WorldSnapshot(tick={}, era={:?}, population={})",
+            self.tick, self.era, self.population
+        )
+    }
+}
+
+/// A row from the `agent_snapshots` table.
+#[pyclass(frozen, module = "emergence")]
+pub struct AgentSnapshot {
+    /// Auto-incremented snapshot ID.
+    #[pyo3(get)]
+    id: i64,
+    /// The tick this snapshot was taken at.
+    #[pyo3(get)]
+    tick: i64,
+    /// The agent this snapshot belongs to.
+    #[pyo3(get)]
+    agent_id: String,
+    full_state: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AgentSnapshotRow> for AgentSnapshot {
+    fn from(row: AgentSnapshotRow) -> Self {
+        Self {
+            id: row.id,
+            tick: row.tick,
+            agent_id: row.agent_id.to_string(),
+            full_state: row.full_state,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[pymethods]
+impl AgentSnapshot {
+    /// Full agent state.
+    #[getter]
+    fn full_state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.full_state)
+    }
+
+    /// Real-world timestamp.
+    #[getter]
+    fn created_at<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        datetime_to_py(py, &self.created_at)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AgentSnapshot(tick={}, agent_id={:?})",
+            self.tick, self.agent_id
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Canonical types
+// ---------------------------------------------------------------------------
+
+/// [`emergence_types::AgentStateSnapshot`]: an agent's vitals at event time.
+#[pyclass(frozen, module = "emergence")]
+pub struct AgentStateSnapshot {
+    /// Energy at event time.
+    #[pyo3(get)]
+    energy: u32,
+    /// Health at event time.
+    #[pyo3(get)]
+    health: u32,
+    /// Hunger at event time.
+    #[pyo3(get)]
+    hunger: u32,
+    /// Age in ticks at event time.
+    #[pyo3(get)]
+    age: u32,
+    /// Location at event time.
+    #[pyo3(get)]
+    location_id: String,
+    /// Inventory summary, keyed by resource name.
+    #[pyo3(get)]
+    inventory_summary: BTreeMap<String, u32>,
+}
+
+impl From<emergence_types::AgentStateSnapshot> for AgentStateSnapshot {
+    fn from(snapshot: emergence_types::AgentStateSnapshot) -> Self {
+        Self {
+            energy: snapshot.energy,
+            health: snapshot.health,
+            hunger: snapshot.hunger,
+            age: snapshot.age,
+            location_id: snapshot.location_id.to_string(),
+            inventory_summary: snapshot
+                .inventory_summary
+                .iter()
+                .map(|(resource, quantity)| (variant_name(resource), *quantity))
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl AgentStateSnapshot {
+    fn __repr__(&self) -> String {
+        format!(
+            "AgentStateSnapshot(energy={}, health={}, hunger={}, age={})",
+            self.energy, self.health, self.hunger, self.age
+        )
+    }
+}
+
+/// [`emergence_types::WorldContext`]: world conditions at event time.
+#[pyclass(frozen, module = "emergence")]
+pub struct WorldContext {
+    /// Tick number.
+    #[pyo3(get)]
+    tick: u64,
+    /// Civilizational era.
+    #[pyo3(get)]
+    era: String,
+    /// Season.
+    #[pyo3(get)]
+    season: String,
+    /// Weather.
+    #[pyo3(get)]
+    weather: String,
+    /// Number of living agents.
+    #[pyo3(get)]
+    population: u32,
+}
+
+impl From<emergence_types::WorldContext> for WorldContext {
+    fn from(context: emergence_types::WorldContext) -> Self {
+        Self {
+            tick: context.tick,
+            era: variant_name(&context.era),
+            season: variant_name(&context.season),
+            weather: variant_name(&context.weather),
+            population: context.population,
+        }
+    }
+}
+
+#[pymethods]
+impl WorldContext {
+    fn __repr__(&self) -> String {
+        format!(
+            "WorldContext(tick={}, era={:?}, season={:?}, population={})",
+            self.tick, self.era, self.season, self.population
+        )
+    }
+}
@@ -0,0 +1,176 @@
+//! Batched multi-agent inference.
+//!
+//! For large populations most LLM-bound decisions are low-complexity, and
+//! the fixed per-request overhead (HTTP round trip, system prompt tokens)
+//! dominates their cost. In batch mode the runner packs several such agents
+//! into one LLM call:
+//!
+//! - Each agent gets a short label (`A1`, `A2`, ...) and its own delimited
+//!   section holding that agent's full rendered prompt.
+//! - The model answers with one JSON object, `{"decisions": [...]}`, where
+//!   every entry carries the `agent` label plus the usual action fields.
+//! - [`split_batch_response`] maps entries back to agents and returns each
+//!   one as a standalone action object for the normal per-agent parser.
+//!
+//! Agents missing from the answer are reported back to the caller so they
+//! can fall back individually.
+
+use std::collections::BTreeMap;
+
+use emergence_types::AgentId;
+
+use crate::parse::extract_json_value;
+use crate::prompt::RenderedPrompt;
+
+/// The short label used for the agent at `index` in a batch.
+pub fn batch_label(index: usize) -> String {
+    format!("A{}", index.saturating_add(1))
+}
+
+/// Build a single prompt that asks for one decision per agent.
+///
+/// `prompts` are the agents' individually rendered prompts, in the same
+/// order as the agent list later passed to [`split_batch_response`].
+pub fn batch_prompt(prompts: &[&RenderedPrompt]) -> RenderedPrompt {
+    let labels: Vec<String> = (0..prompts.len()).map(batch_label).collect();
+    let system = format!(
+        "You decide for {count} independent agents in a simulated world. Each agent's \
+         section below is a complete prompt written to that agent alone; decide for each \
+         agent using only its own section. Agents cannot see each other's sections.\n\n\
+         Reply with exactly one JSON object and no other text:\n\
+         {{\"decisions\": [{{\"agent\": \"A1\", \"action_type\": \"...\", \"parameters\": {{...}}, \
+         \"reasoning\": \"...\", \"goal_update\": [...]}}, ...]}}\n\n\
+         Include exactly one entry for each of: {labels}.",
+        count = prompts.len(),
+        labels = labels.join(", "),
+    );
+
+    let user = labels
+        .iter()
+        .zip(prompts)
+        .map(|(label, prompt)| {
+            format!(
+                "=== BEGIN AGENT {label} ===\n{}\n\n{}\n=== END AGENT {label} ===",
+                prompt.stable_prefix(),
+                prompt.user,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    RenderedPrompt {
+        system,
+        persona: String::new(),
+        user,
+    }
+}
+
+/// Split a batched response into per-agent action objects.
+///
+/// Accepts either `{"decisions": [...]}` or a bare array. Each returned
+/// string is a JSON action object (with the `agent` label removed) ready for
+/// [`crate::parse::parse_llm_response`]. Entries with unknown labels are
+/// ignored; if a label appears twice the first entry wins.
+pub fn split_batch_response(raw: &str, agents: &[AgentId]) -> BTreeMap<AgentId, String> {
+    let mut decisions = BTreeMap::new();
+    let Some(value) = extract_json_value(raw) else {
+        return decisions;
+    };
+    let entries = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(mut obj) => match obj.remove("decisions") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => return decisions,
+        },
+        _ => return decisions,
+    };
+
+    for entry in entries {
+        let serde_json::Value::Object(mut obj) = entry else {
+            continue;
+        };
+        let Some(agent_id) = obj
+            .remove("agent")
+            .as_ref()
+            .and_then(serde_json::Value::as_str)
+            .and_then(|label| resolve_label(label, agents))
+        else {
+            continue;
+        };
+        if let std::collections::btree_map::Entry::Vacant(slot) = decisions.entry(agent_id) {
+            slot.insert(serde_json::Value::Object(obj).to_string());
+        }
+    }
+    decisions
+}
+
+/// Map a label such as `"A3"` (case-insensitive, surrounding whitespace
+/// allowed) back to the agent at that position.
+fn resolve_label(label: &str, agents: &[AgentId]) -> Option<AgentId> {
+    let trimmed = label.trim();
+    let digits = trimmed
+        .strip_prefix('A')
+        .or_else(|| trimmed.strip_prefix('a'))?;
+    let position: usize = digits.parse().ok()?;
+    agents.get(position.checked_sub(1)?).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(system: &str, user: &str) -> RenderedPrompt {
+        RenderedPrompt {
+            system: system.to_owned(),
+            persona: String::new(),
+            user: user.to_owned(),
+        }
+    }
+
+    #[test]
+    fn batch_prompt_delimits_each_agent() {
+        let first = prompt("You are Luna.", "Tick 1");
+        let second = prompt("You are Bo.", "Tick 1");
+        let batch = batch_prompt(&[&first, &second]);
+        assert!(batch.system.contains("2 independent agents"));
+        assert!(batch.system.contains("A1, A2"));
+        assert!(batch.user.contains("=== BEGIN AGENT A1 ===\nYou are Luna."));
+        assert!(batch.user.contains("=== BEGIN AGENT A2 ===\nYou are Bo."));
+        assert!(batch.persona.is_empty());
+    }
+
+    #[test]
+    fn split_maps_labels_back_to_agents() {
+        let (luna, bo, ghost) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let agents = [luna, bo, ghost];
+        let raw = r#"```json
+{"decisions": [
+  {"agent": "A2", "action_type": "Rest", "parameters": {}},
+  {"agent": "a1", "action_type": "Eat", "parameters": {"food_type": "FoodBerry"}},
+  {"agent": "A9", "action_type": "Rest", "parameters": {}},
+]}
+```"#;
+        let split = split_batch_response(raw, &agents);
+        assert_eq!(split.len(), 2);
+        let first = split.get(&luna).cloned().unwrap_or_default();
+        assert!(first.contains("\"Eat\""));
+        assert!(!first.contains("agent"), "label should be stripped");
+        assert!(split.get(&bo).is_some_and(|s| s.contains("Rest")));
+        assert!(!split.contains_key(&ghost));
+    }
+
+    #[test]
+    fn split_accepts_bare_array_and_keeps_first_duplicate() {
+        let luna = AgentId::new();
+        let raw = r#"[{"agent": "A1", "action_type": "Rest"}, {"agent": "A1", "action_type": "Eat"}]"#;
+        let split = split_batch_response(raw, &[luna]);
+        assert!(split.get(&luna).is_some_and(|s| s.contains("Rest")));
+    }
+
+    #[test]
+    fn split_garbage_yields_nothing() {
+        let agents = [AgentId::new()];
+        assert!(split_batch_response("I cannot help with that.", &agents).is_empty());
+        assert!(split_batch_response(r#"{"action_type": "Rest"}"#, &agents).is_empty());
+    }
+}
@@ -9,9 +9,15 @@
 //! from the perception payload and produces a [`ComplexityLevel`] that
 //! the runner uses to route the LLM call to the appropriate backend.
 //!
-//! Scoring factors are documented in `build-plan.md` task 6.2.2.
+//! Scoring factors are documented in `build-plan.md` task 6.2.2. The
+//! factors are a configurable [`ComplexityRules`] set; the runner can load
+//! its own from YAML (`COMPLEXITY_RULES_PATH`) and logs which rules fired
+//! for every decision.
 
 use emergence_types::Perception;
+use serde::Deserialize;
+
+use crate::error::RunnerError;
 
 // ---------------------------------------------------------------------------
 // Complexity level
@@ -55,193 +61,463 @@ impl std::fmt::Display for ComplexityLevel {
 }
 
 // ---------------------------------------------------------------------------
-// Scoring thresholds
+// Rule set
 // ---------------------------------------------------------------------------
 
-/// Score at or above which the decision is classified as [`ComplexityLevel::Medium`].
-const MEDIUM_THRESHOLD: u32 = 3;
+/// A measurable feature of the perception that complexity rules test.
+///
+/// Count signals measure how many items are present; flag signals measure
+/// `1` when present and `0` otherwise; vital signals measure the raw
+/// 0-100 value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    /// Count of other agents at the location.
+    AgentsNearby,
+    /// Count of messages visible at the location.
+    MessagesHere,
+    /// Count of structures at the location.
+    StructuresHere,
+    /// Count of system notifications.
+    Notifications,
+    /// Flag: storm or snow.
+    SevereWeather,
+    /// Flag: communicate, broadcast, teach, group, or reproduce available.
+    SocialActions,
+    /// Flag: trade offer, accept, or reject available.
+    TradeActions,
+    /// Flag: legislate, enforce, or claim available.
+    GovernanceActions,
+    /// Count of trade offers awaiting an answer (trade accept/reject
+    /// actions, plus notifications and memories mentioning a trade offer).
+    PendingTrades,
+    /// Count of recent memories describing a failed or rejected action.
+    RecentFailures,
+    /// Count of recent memories.
+    RecentMemories,
+    /// Hunger (0-100).
+    Hunger,
+    /// Thirst (0-100).
+    Thirst,
+    /// Energy (0-100).
+    Energy,
+    /// Health (0-100).
+    Health,
+}
 
-/// Score at or above which the decision is classified as [`ComplexityLevel::High`].
-const HIGH_THRESHOLD: u32 = 7;
+impl Signal {
+    /// Measure this signal on a perception.
+    fn measure(self, perception: &Perception) -> u32 {
+        let count = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
+        let flag = |b: bool| u32::from(b);
+        let actions = &perception.available_actions;
+        match self {
+            Self::AgentsNearby => count(perception.surroundings.agents_here.len()),
+            Self::MessagesHere => count(perception.surroundings.messages_here.len()),
+            Self::StructuresHere => count(perception.surroundings.structures_here.len()),
+            Self::Notifications => count(perception.notifications.len()),
+            Self::SevereWeather => flag(matches!(
+                perception.weather,
+                emergence_types::Weather::Storm | emergence_types::Weather::Snow
+            )),
+            Self::SocialActions => flag(any_action(actions, SOCIAL_ACTIONS)),
+            Self::TradeActions => flag(any_action(actions, TRADE_ACTIONS)),
+            Self::GovernanceActions => flag(any_action(actions, GOVERNANCE_ACTIONS)),
+            Self::PendingTrades => {
+                let answerable = actions
+                    .iter()
+                    .filter(|a| matches_any(a, PENDING_TRADE_ACTIONS))
+                    .count();
+                let mentioned = perception
+                    .notifications
+                    .iter()
+                    .chain(&perception.recent_memory)
+                    .filter(|text| text.to_lowercase().contains("trade offer"))
+                    .count();
+                count(answerable.saturating_add(mentioned))
+            }
+            Self::RecentFailures => count(
+                perception
+                    .recent_memory
+                    .iter()
+                    .filter(|m| matches_any(m, FAILURE_MARKERS))
+                    .count(),
+            ),
+            Self::RecentMemories => count(perception.recent_memory.len()),
+            Self::Hunger => perception.self_state.hunger,
+            Self::Thirst => perception.self_state.thirst,
+            Self::Energy => perception.self_state.energy,
+            Self::Health => perception.self_state.health,
+        }
+    }
+}
 
-// ---------------------------------------------------------------------------
-// Public API
-// ---------------------------------------------------------------------------
+/// Action name fragments that indicate social actions.
+const SOCIAL_ACTIONS: &[&str] = &[
+    "communicate",
+    "broadcast",
+    "teach",
+    "formgroup",
+    "form_group",
+    "reproduce",
+];
+
+/// Action name fragments that indicate trade actions.
+const TRADE_ACTIONS: &[&str] = &[
+    "tradeoffer",
+    "trade_offer",
+    "tradeaccept",
+    "trade_accept",
+    "tradereject",
+    "trade_reject",
+];
+
+/// Action name fragments that only appear when a trade offer is pending.
+const PENDING_TRADE_ACTIONS: &[&str] = &["tradeaccept", "trade_accept", "tradereject", "trade_reject"];
+
+/// Action name fragments that indicate governance actions.
+const GOVERNANCE_ACTIONS: &[&str] = &["legislate", "enforce", "claim"];
+
+/// Memory fragments that indicate a failed action.
+const FAILURE_MARKERS: &[&str] = &["rejected", "failed"];
+
+/// Whether `text` contains any of `fragments`, case-insensitively.
+fn matches_any(text: &str, fragments: &[&str]) -> bool {
+    let lower = text.to_lowercase();
+    fragments.iter().any(|fragment| lower.contains(fragment))
+}
 
-/// Score the complexity of an agent's decision context.
-///
-/// Examines the perception payload to count indicators of decision
-/// difficulty. Returns a [`ComplexityLevel`] that the runner uses to
-/// select the appropriate LLM backend.
-///
-/// # Scoring factors
-///
-/// Each factor adds points to a running total:
-///
-/// | Factor | Points |
-/// |--------|--------|
-/// | Other agents present | 1 per agent (max 3) |
-/// | Pending messages | 1 per message (max 2) |
-/// | Structures at location | 1 |
-/// | Notifications present | 1 per notification (max 3) |
-/// | Severe weather (Storm/Snow) | 2 |
-/// | Social actions available | 2 |
-/// | Governance actions available | 3 |
-/// | Trade actions available | 2 |
-/// | High hunger (>= 70) | 1 |
-/// | Low health (< 30) | 1 |
-/// | Multiple recent memories | 1 |
+/// Whether any available action matches one of `fragments`.
+fn any_action(actions: &[String], fragments: &[&str]) -> bool {
+    actions.iter().any(|a| matches_any(a, fragments))
+}
+
+/// One scoring rule: a condition on a [`Signal`] and the points it adds.
 ///
-/// The total is then compared against thresholds:
-/// - `< 3` => [`Low`](ComplexityLevel::Low)
-/// - `3..7` => [`Medium`](ComplexityLevel::Medium)
-/// - `>= 7` => [`High`](ComplexityLevel::High)
-pub fn score_complexity(perception: &Perception) -> ComplexityLevel {
-    let score = compute_raw_score(perception);
+/// The rule fires when the measured value is at least `at_least` and below
+/// `below` (each bound optional). With neither bound it fires on any
+/// non-zero value. A `per_unit` rule adds `points` for every unit of the
+/// value instead of once. `max_points` caps what one rule can add.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComplexityRule {
+    /// Name logged when the rule fires.
+    pub name: String,
+    /// What the rule measures.
+    pub signal: Signal,
+    /// Inclusive lower bound on the measured value.
+    #[serde(default)]
+    pub at_least: Option<u32>,
+    /// Exclusive upper bound on the measured value.
+    #[serde(default)]
+    pub below: Option<u32>,
+    /// Points added when the rule fires (per unit if `per_unit`).
+    pub points: u32,
+    /// Multiply `points` by the measured value.
+    #[serde(default)]
+    pub per_unit: bool,
+    /// Maximum points this rule can add.
+    #[serde(default)]
+    pub max_points: Option<u32>,
+}
 
-    if score >= HIGH_THRESHOLD {
-        ComplexityLevel::High
-    } else if score >= MEDIUM_THRESHOLD {
-        ComplexityLevel::Medium
-    } else {
-        ComplexityLevel::Low
+impl ComplexityRule {
+    /// Points this rule adds for `perception`, or `None` if it does not fire.
+    fn evaluate(&self, perception: &Perception) -> Option<u32> {
+        let value = self.signal.measure(perception);
+        let fires = match (self.at_least, self.below) {
+            (None, None) => value > 0,
+            (at_least, below) => {
+                at_least.is_none_or(|min| value >= min) && below.is_none_or(|max| value < max)
+            }
+        };
+        if !fires {
+            return None;
+        }
+        let points = if self.per_unit {
+            self.points.saturating_mul(value)
+        } else {
+            self.points
+        };
+        let points = self.max_points.map_or(points, |cap| points.min(cap));
+        (points > 0).then_some(points)
     }
-}
 
-/// Compute the raw numeric complexity score from a perception payload.
-///
-/// Exposed as a separate function so tests can verify exact scores.
-fn compute_raw_score(perception: &Perception) -> u32 {
-    let mut score: u32 = 0;
-
-    // --- Social context: other agents present ---
-    // Each nearby agent increases decision complexity (capped at 3).
-    let agent_count = perception.surroundings.agents_here.len();
-    let agent_points = std::cmp::min(agent_count, 3);
-    // SAFETY: agent_points is at most 3, score starts at 0 and max total
-    // across all factors is bounded well below u32::MAX.
-    #[allow(clippy::arithmetic_side_effects)]
-    {
-        score += u32::try_from(agent_points).unwrap_or(3);
-    }
-
-    // --- Messages pending at location ---
-    let message_count = perception.surroundings.messages_here.len();
-    let message_points = std::cmp::min(message_count, 2);
-    #[allow(clippy::arithmetic_side_effects)]
-    {
-        score += u32::try_from(message_points).unwrap_or(2);
-    }
-
-    // --- Structures at location (building/crafting context) ---
-    if !perception.surroundings.structures_here.is_empty() {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 1;
+    fn new(name: &str, signal: Signal, points: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            signal,
+            at_least: None,
+            below: None,
+            points,
+            per_unit: false,
+            max_points: None,
         }
     }
 
-    // --- Notifications (approaching winter, shelter damage, deaths, etc.) ---
-    let notification_count = perception.notifications.len();
-    let notification_points = std::cmp::min(notification_count, 3);
-    #[allow(clippy::arithmetic_side_effects)]
-    {
-        score += u32::try_from(notification_points).unwrap_or(3);
+    const fn at_least(mut self, min: u32) -> Self {
+        self.at_least = Some(min);
+        self
     }
 
-    // --- Severe weather increases complexity ---
-    if matches!(
-        perception.weather,
-        emergence_types::Weather::Storm | emergence_types::Weather::Snow
-    ) {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 2;
-        }
+    const fn below(mut self, max: u32) -> Self {
+        self.below = Some(max);
+        self
     }
 
-    // --- Available action types signal complexity ---
-    score = score_available_actions(score, &perception.available_actions);
+    const fn per_unit_capped(mut self, cap: u32) -> Self {
+        self.per_unit = true;
+        self.max_points = Some(cap);
+        self
+    }
+}
+
+/// The complete, configurable complexity scoring rule set.
+///
+/// The default reproduces the built-in scoring table documented on
+/// [`ComplexityRules::assess`]. A custom set can be loaded from YAML with
+/// [`ComplexityRules::load`]; see `complexity-rules.yaml` in this crate for
+/// the defaults written out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComplexityRules {
+    /// Score at or above which the decision is [`ComplexityLevel::Medium`].
+    pub medium_threshold: u32,
+    /// Score at or above which the decision is [`ComplexityLevel::High`].
+    pub high_threshold: u32,
+    /// Scoring rules, evaluated in order.
+    pub rules: Vec<ComplexityRule>,
+}
 
-    // --- Survival pressure ---
-    if perception.self_state.hunger >= 70 {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 1;
+impl Default for ComplexityRules {
+    fn default() -> Self {
+        use Signal as S;
+        Self {
+            medium_threshold: 3,
+            high_threshold: 7,
+            rules: vec![
+                ComplexityRule::new("agents_nearby", S::AgentsNearby, 1).per_unit_capped(3),
+                ComplexityRule::new("messages_here", S::MessagesHere, 1).per_unit_capped(2),
+                ComplexityRule::new("structures_here", S::StructuresHere, 1),
+                ComplexityRule::new("notifications", S::Notifications, 1).per_unit_capped(3),
+                ComplexityRule::new("severe_weather", S::SevereWeather, 2),
+                ComplexityRule::new("social_actions", S::SocialActions, 2),
+                ComplexityRule::new("trade_actions", S::TradeActions, 2),
+                ComplexityRule::new("governance_actions", S::GovernanceActions, 3),
+                ComplexityRule::new("pending_trades", S::PendingTrades, 1).per_unit_capped(2),
+                ComplexityRule::new("recent_failures", S::RecentFailures, 1).at_least(2),
+                ComplexityRule::new("high_hunger", S::Hunger, 1).at_least(70),
+                ComplexityRule::new("low_health", S::Health, 1).below(30),
+                ComplexityRule::new("rich_memory", S::RecentMemories, 1).at_least(3),
+            ],
         }
     }
-    if perception.self_state.health < 30 {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 1;
+}
+
+impl ComplexityRules {
+    /// Parse and validate a rule set from YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Config`] if the YAML is malformed, the
+    /// thresholds are out of order, or two rules share a name.
+    pub fn from_yaml(source: &str) -> Result<Self, RunnerError> {
+        let rules: Self = serde_yml::from_str(source)
+            .map_err(|e| RunnerError::Config(format!("invalid complexity rules: {e}")))?;
+        if rules.medium_threshold > rules.high_threshold {
+            return Err(RunnerError::Config(format!(
+                "complexity medium_threshold ({}) must not exceed high_threshold ({})",
+                rules.medium_threshold, rules.high_threshold
+            )));
         }
+        let mut names = std::collections::BTreeSet::new();
+        for rule in &rules.rules {
+            if !names.insert(rule.name.as_str()) {
+                return Err(RunnerError::Config(format!(
+                    "duplicate complexity rule name: {}",
+                    rule.name
+                )));
+            }
+        }
+        Ok(rules)
     }
 
-    // --- Memory richness: many recent memories suggest complex ongoing situation ---
-    if perception.recent_memory.len() >= 3 {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 1;
-        }
+    /// Load a rule set from a YAML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Config`] if the file cannot be read or fails
+    /// [`ComplexityRules::from_yaml`].
+    pub fn load(path: &str) -> Result<Self, RunnerError> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            RunnerError::Config(format!("failed to read complexity rules {path}: {e}"))
+        })?;
+        Self::from_yaml(&source)
     }
 
-    score
+    /// Score the complexity of an agent's decision context.
+    ///
+    /// Sums the points of every rule that fires and compares the total
+    /// against the thresholds. The returned assessment lists the rules
+    /// that fired so the runner can log them for tuning.
+    ///
+    /// # Default scoring table
+    ///
+    /// | Rule | Points |
+    /// |------|--------|
+    /// | Other agents present | 1 per agent (max 3) |
+    /// | Pending messages | 1 per message (max 2) |
+    /// | Structures at location | 1 |
+    /// | Notifications present | 1 per notification (max 3) |
+    /// | Severe weather (Storm/Snow) | 2 |
+    /// | Social actions available | 2 |
+    /// | Trade actions available | 2 |
+    /// | Governance actions available | 3 |
+    /// | Pending trade offers | 1 per offer (max 2) |
+    /// | Two or more recent failures | 1 |
+    /// | High hunger (>= 70) | 1 |
+    /// | Low health (< 30) | 1 |
+    /// | Multiple recent memories | 1 |
+    ///
+    /// With the default thresholds:
+    /// - `< 3` => [`Low`](ComplexityLevel::Low)
+    /// - `3..7` => [`Medium`](ComplexityLevel::Medium)
+    /// - `>= 7` => [`High`](ComplexityLevel::High)
+    pub fn assess(&self, perception: &Perception) -> ComplexityAssessment<'_> {
+        let fired: Vec<(&str, u32)> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.evaluate(perception).map(|points| (rule.name.as_str(), points)))
+            .collect();
+        let score = fired
+            .iter()
+            .fold(0_u32, |total, (_, points)| total.saturating_add(*points));
+
+        let level = if score >= self.high_threshold {
+            ComplexityLevel::High
+        } else if score >= self.medium_threshold {
+            ComplexityLevel::Medium
+        } else {
+            ComplexityLevel::Low
+        };
+        ComplexityAssessment { level, score, fired }
+    }
 }
 
-/// Add points for social, trade, and governance actions in the available
-/// actions list.
-///
-/// Returns the updated score.
-fn score_available_actions(mut score: u32, available_actions: &[String]) -> u32 {
-    let has_social = available_actions.iter().any(|a| {
-        let lower = a.to_lowercase();
-        lower.contains("communicate")
-            || lower.contains("broadcast")
-            || lower.contains("teach")
-            || lower.contains("formgroup")
-            || lower.contains("form_group")
-            || lower.contains("reproduce")
-    });
-
-    let has_trade = available_actions.iter().any(|a| {
-        let lower = a.to_lowercase();
-        lower.contains("tradeoffer")
-            || lower.contains("trade_offer")
-            || lower.contains("tradeaccept")
-            || lower.contains("trade_accept")
-            || lower.contains("tradereject")
-            || lower.contains("trade_reject")
-    });
-
-    let has_governance = available_actions.iter().any(|a| {
-        let lower = a.to_lowercase();
-        lower.contains("legislate")
-            || lower.contains("enforce")
-            || lower.contains("claim")
-    });
-
-    if has_social {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 2;
-        }
-    }
+/// The result of scoring one perception against a [`ComplexityRules`] set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplexityAssessment<'a> {
+    /// The complexity tier.
+    pub level: ComplexityLevel,
+    /// The raw score.
+    pub score: u32,
+    /// Name and points of each rule that fired, in rule order.
+    pub fired: Vec<(&'a str, u32)>,
+}
 
-    if has_trade {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 2;
-        }
+impl ComplexityAssessment<'_> {
+    /// The fired rules formatted for logging, e.g. `"agents_nearby+2,
+    /// trade_actions+2"`.
+    pub fn fired_summary(&self) -> String {
+        self.fired
+            .iter()
+            .map(|(name, points)| format!("{name}+{points}"))
+            .collect::<Vec<_>>()
+            .join(", ")
     }
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// Score the complexity of an agent's decision context with the default
+/// rules.
+///
+/// See [`ComplexityRules::assess`] for the scoring table.
+#[cfg(test)]
+pub fn score_complexity(perception: &Perception) -> ComplexityLevel {
+    ComplexityRules::default().assess(perception).level
+}
+
+/// Compute the raw numeric complexity score with the default rules.
+///
+/// Exposed as a separate function so tests can verify exact scores.
+#[cfg(test)]
+fn compute_raw_score(perception: &Perception) -> u32 {
+    ComplexityRules::default().assess(perception).score
+}
 
-    if has_governance {
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            score += 3;
+// ---------------------------------------------------------------------------
+// Decision priority
+// ---------------------------------------------------------------------------
+
+/// How urgently an agent needs an LLM decision when the budget is tight.
+///
+/// Ordered so that sorting descending puts critical agents first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecisionPriority {
+    /// Everyday decisions; first to fall back to the bypass policy.
+    Normal,
+    /// Low vitals or an active conflict; may spend the reserved budget.
+    Critical,
+}
+
+impl std::fmt::Display for DecisionPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Normal => f.write_str("normal"),
+            Self::Critical => f.write_str("critical"),
         }
     }
+}
+
+/// Health below this is critical.
+const CRITICAL_HEALTH: u32 = 30;
+
+/// Energy below this is critical.
+const CRITICAL_ENERGY: u32 = 15;
+
+/// Hunger or thirst at or above this is critical.
+const CRITICAL_NEED: u32 = 80;
 
-    score
+/// Words in memories or notifications that indicate an active conflict.
+const CONFLICT_MARKERS: [&str; 7] = [
+    "attack", "fight", "combat", "stole", "theft", "intimidat", "threaten",
+];
+
+/// Score how urgently an agent needs an LLM decision.
+///
+/// An agent is [`Critical`](DecisionPriority::Critical) when its vitals are
+/// low (health < 30, energy < 15, hunger or thirst >= 80) or it is in an
+/// active conflict: a hostile agent is present, or a recent memory or
+/// notification mentions an attack, fight, theft, or threat.
+pub fn score_priority(perception: &Perception) -> DecisionPriority {
+    let vitals = &perception.self_state;
+    let low_vitals = vitals.health < CRITICAL_HEALTH
+        || vitals.energy < CRITICAL_ENERGY
+        || vitals.hunger >= CRITICAL_NEED
+        || vitals.thirst >= CRITICAL_NEED;
+
+    let hostile_present = perception
+        .surroundings
+        .agents_here
+        .iter()
+        .any(|a| a.relationship.to_lowercase().contains("hostile"));
+    let conflict_mentioned = perception
+        .recent_memory
+        .iter()
+        .chain(&perception.notifications)
+        .any(|text| {
+            let lower = text.to_lowercase();
+            CONFLICT_MARKERS.iter().any(|marker| lower.contains(marker))
+        });
+
+    if low_vitals || hostile_present || conflict_mentioned {
+        DecisionPriority::Critical
+    } else {
+        DecisionPriority::Normal
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -287,12 +563,15 @@ mod tests {
                 structures_here: Vec::new(),
                 agents_here: Vec::new(),
                 messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
             },
             known_routes: Vec::new(),
             recent_memory: Vec::new(),
             available_actions: vec!["gather".to_owned(), "rest".to_owned(), "move".to_owned()],
             notifications: Vec::new(),
             personality: None,
+            forecast: Vec::new(),
         }
     }
 
@@ -552,4 +831,110 @@ mod tests {
         let raw = compute_raw_score(&perception);
         assert_eq!(raw, 3);
     }
+
+    // -----------------------------------------------------------------------
+    // Decision priority
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn shipped_rules_file_matches_defaults() {
+        let source = include_str!("../complexity-rules.yaml");
+        let rules = ComplexityRules::from_yaml(source);
+        assert_eq!(rules.ok(), Some(ComplexityRules::default()));
+    }
+
+    #[test]
+    fn custom_rules_change_routing_and_report_what_fired() {
+        let yaml = r"
+medium_threshold: 2
+high_threshold: 4
+rules:
+  - name: thirsty
+    signal: thirst
+    at_least: 60
+    points: 2
+  - name: repeated_failures
+    signal: recent_failures
+    at_least: 1
+    points: 3
+";
+        let rules = ComplexityRules::from_yaml(yaml).unwrap_or_default();
+        assert_eq!(rules.rules.len(), 2);
+        let mut perception = solo_survival_perception();
+        assert_eq!(rules.assess(&perception).level, ComplexityLevel::Low);
+
+        perception.self_state.thirst = 65;
+        perception.recent_memory = vec!["I tried Move but it was rejected: RouteBlocked.".to_owned()];
+        let assessment = rules.assess(&perception);
+        assert_eq!(assessment.level, ComplexityLevel::High);
+        assert_eq!(assessment.score, 5);
+        assert_eq!(assessment.fired, vec![("thirsty", 2), ("repeated_failures", 3)]);
+        assert_eq!(assessment.fired_summary(), "thirsty+2, repeated_failures+3");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let inverted = "medium_threshold: 9\nhigh_threshold: 3\nrules: []\n";
+        assert!(ComplexityRules::from_yaml(inverted).is_err());
+
+        let duplicate = "medium_threshold: 1\nhigh_threshold: 2\nrules:\n  \
+            - {name: a, signal: hunger, points: 1}\n  \
+            - {name: a, signal: health, points: 1}\n";
+        assert!(ComplexityRules::from_yaml(duplicate).is_err());
+
+        let unknown = "medium_threshold: 1\nhigh_threshold: 2\nrules:\n  \
+            - {name: a, signal: mood, points: 1}\n";
+        assert!(ComplexityRules::from_yaml(unknown).is_err());
+    }
+
+    #[test]
+    fn pending_trades_count_offers() {
+        let mut perception = solo_survival_perception();
+        perception.available_actions = vec!["TradeAccept".to_owned(), "TradeReject".to_owned()];
+        perception.notifications = vec!["Bo sent you a trade offer.".to_owned()];
+        assert_eq!(Signal::PendingTrades.measure(&perception), 3);
+    }
+
+    #[test]
+    fn calm_agent_is_normal_priority() {
+        let perception = solo_survival_perception();
+        assert_eq!(score_priority(&perception), DecisionPriority::Normal);
+    }
+
+    #[test]
+    fn low_vitals_are_critical() {
+        let mut perception = solo_survival_perception();
+        perception.self_state.thirst = 85;
+        assert_eq!(score_priority(&perception), DecisionPriority::Critical);
+
+        let mut perception = solo_survival_perception();
+        perception.self_state.health = 20;
+        assert_eq!(score_priority(&perception), DecisionPriority::Critical);
+    }
+
+    #[test]
+    fn active_conflict_is_critical() {
+        let mut perception = solo_survival_perception();
+        perception
+            .recent_memory
+            .push("Bo attacked me near the river".to_owned());
+        assert_eq!(score_priority(&perception), DecisionPriority::Critical);
+
+        let mut perception = solo_survival_perception();
+        perception.surroundings.agents_here = vec![VisibleAgent {
+            id: emergence_types::AgentId::new(),
+            name: "Rival".to_owned(),
+            sex: Sex::Male,
+            relationship: "hostile (-0.7)".to_owned(),
+            activity: "idle".to_owned(),
+        }];
+        assert_eq!(score_priority(&perception), DecisionPriority::Critical);
+    }
+
+    #[test]
+    fn critical_sorts_before_normal() {
+        let mut priorities = [DecisionPriority::Normal, DecisionPriority::Critical];
+        priorities.sort_by(|a, b| b.cmp(a));
+        assert_eq!(priorities.first(), Some(&DecisionPriority::Critical));
+    }
 }
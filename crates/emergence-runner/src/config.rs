@@ -8,13 +8,18 @@ use std::time::Duration;
 
 use rust_decimal::Decimal;
 
+use crate::cost::BudgetLimits;
 use crate::error::RunnerError;
 
 /// Complete runner configuration loaded from the environment.
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct RunnerConfig {
     /// NATS server URL (e.g. `nats://localhost:4222`).
     pub nats_url: String,
+    /// `JetStream` delivery settings. `None` uses plain core NATS
+    /// subscriptions, which lose in-flight perceptions on restart.
+    pub jetstream: Option<JetStreamConfig>,
     /// Primary LLM backend configuration.
     pub primary_backend: LlmBackendConfig,
     /// Secondary (fallback) LLM backend configuration.
@@ -25,6 +30,8 @@ pub struct RunnerConfig {
     pub max_concurrent_calls: usize,
     /// Path to the templates directory.
     pub templates_dir: String,
+    /// Path to a YAML complexity rule set. `None` uses the built-in rules.
+    pub complexity_rules_path: Option<String>,
     /// Whether to route LLM calls based on tick complexity scoring.
     ///
     /// When enabled, high-complexity decisions are sent to the escalation
@@ -42,6 +49,34 @@ pub struct RunnerConfig {
     ///
     /// Corresponds to `llm.night_cycle_skip` in `emergence-config.yaml`.
     pub night_cycle_skip: bool,
+    /// When true, responses that fail parsing and mechanical repair get one
+    /// cheap "fix this JSON" re-prompt on the primary backend before the
+    /// agent falls back to `NoAction`.
+    pub parse_repair_reprompt: bool,
+    /// Number of memory summaries the runner retains per agent beyond the
+    /// perception window. Zero disables memory retention.
+    pub persona_memory_capacity: usize,
+    /// Names of experimental prompt variants loaded from
+    /// `{templates_dir}/variants/<name>/`. Empty runs the control templates
+    /// only.
+    pub prompt_variants: Vec<String>,
+    /// Maximum number of low-complexity agents packed into one LLM call.
+    /// `1` (the default) disables batched inference.
+    pub batch_size: usize,
+    /// How long the runner waits for more perceptions after the first one
+    /// of a batch.
+    pub batch_window: Duration,
+    /// Per-tick and per-day LLM spending caps. Unlimited by default.
+    pub budget: BudgetLimits,
+    /// How often cost metrics are published to NATS for the observer.
+    /// `None` disables publishing.
+    pub metrics_interval: Option<Duration>,
+    /// Percentage of agents (0-100) decided by the heuristic policy alone,
+    /// without LLM calls. `0` (the default) disables the no-LLM tier.
+    pub heuristic_agent_percent: u32,
+    /// Ticks between each agent's reflection call. `0` (the default)
+    /// disables reflection.
+    pub reflection_interval_ticks: u64,
     /// `OpenRouter`-specific headers (referer, app title).
     ///
     /// Populated when either backend is configured as `openrouter`.
@@ -82,6 +117,74 @@ pub struct LlmBackendConfig {
     ///
     /// When `None`, cost tracking records the call but estimates zero cost.
     pub cost_per_m_output: Option<Decimal>,
+    /// How the backend is asked to format its response.
+    pub output_mode: OutputMode,
+    /// Whether to request provider prompt caching for the stable prompt
+    /// prefix (system rules + persona).
+    ///
+    /// Only affects backends that need explicit cache markers (Anthropic);
+    /// `OpenAI`-compatible providers cache stable prefixes automatically.
+    pub prompt_cache: bool,
+    /// Retry, timeout, and circuit breaker settings for this backend.
+    pub resilience: ResilienceConfig,
+}
+
+/// Retry, timeout, and circuit breaker settings for a single backend.
+///
+/// Consumed by [`crate::resilience::ResilientBackend`].
+#[derive(Debug, Clone)]
+pub struct ResilienceConfig {
+    /// Per-request HTTP timeout. Should be well under the decision deadline
+    /// so retries and fallbacks still fit inside it.
+    pub request_timeout: Duration,
+    /// Retries after the first attempt for transient failures.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each subsequent retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on any single retry delay.
+    pub max_backoff: Duration,
+    /// Consecutive failed calls that trip the circuit breaker.
+    pub breaker_failure_threshold: u32,
+    /// How long a tripped breaker rejects calls before allowing a trial.
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for ResilienceConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(3),
+            max_retries: 1,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(2),
+            breaker_failure_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// `JetStream` delivery settings for the perception and action subjects.
+///
+/// Consumed by [`crate::nats::NatsClient::enable_jetstream`].
+#[derive(Debug, Clone)]
+pub struct JetStreamConfig {
+    /// How long a delivered perception may stay unacknowledged before the
+    /// server redelivers it. Must exceed the decision deadline.
+    pub ack_wait: Duration,
+    /// Maximum deliveries of one perception, including the first.
+    pub max_deliver: i64,
+    /// How long the streams retain messages. Perceptions older than this
+    /// are never redelivered.
+    pub max_age: Duration,
+}
+
+impl Default for JetStreamConfig {
+    fn default() -> Self {
+        Self {
+            ack_wait: Duration::from_secs(30),
+            max_deliver: 3,
+            max_age: Duration::from_mins(5),
+        }
+    }
 }
 
 /// `OpenRouter`-specific configuration loaded from environment variables.
@@ -97,6 +200,19 @@ pub struct OpenRouterConfig {
     pub app_title: Option<String>,
 }
 
+/// How an LLM backend is asked to format its response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Free-text response expected to contain JSON, recovered by the
+    /// multi-strategy parser.
+    #[default]
+    Text,
+    /// Native structured output constrained by the action schema from
+    /// [`crate::schema`]: `json_schema` response format on `OpenAI`-compatible
+    /// APIs, a forced tool call on the Anthropic Messages API.
+    Structured,
+}
+
 /// Supported LLM backend types.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BackendType {
@@ -117,6 +233,10 @@ impl RunnerConfig {
     /// - `LLM_DEFAULT_MODEL` -- primary model name
     ///
     /// Optional variables:
+    /// - `NATS_JETSTREAM` -- use `JetStream` durable consumers (default `true`)
+    /// - `JETSTREAM_ACK_WAIT_MS` -- redelivery timeout for unacked perceptions (default `30000`)
+    /// - `JETSTREAM_MAX_DELIVER` -- delivery attempts per perception (default `3`)
+    /// - `JETSTREAM_MAX_AGE_SECS` -- stream retention in seconds (default `300`)
     /// - `LLM_ESCALATION_BACKEND` -- secondary backend type
     /// - `LLM_ESCALATION_API_URL` -- secondary API base URL
     /// - `LLM_ESCALATION_API_KEY` -- secondary API key
@@ -124,11 +244,26 @@ impl RunnerConfig {
     /// - `DECISION_TIMEOUT_MS` -- decision deadline in milliseconds (default 7000)
     /// - `MAX_CONCURRENT_CALLS` -- max parallel LLM calls (default 20)
     /// - `TEMPLATES_DIR` -- path to prompt templates (default `templates`)
+    /// - `PROMPT_VARIANTS` -- comma-separated experimental template variants
     /// - `COMPLEXITY_ROUTING_ENABLED` -- enable complexity-based backend routing (default `true`)
+    /// - `COMPLEXITY_RULES_PATH` -- YAML complexity scoring rules (default built-in rules)
     /// - `ROUTINE_ACTION_BYPASS` -- bypass LLM for obvious survival actions (default `true`)
     /// - `NIGHT_CYCLE_SKIP` -- skip LLM for sleeping agents at night (default `true`)
+    /// - `PARSE_REPAIR_REPROMPT` -- re-prompt once for unparseable responses (default `true`)
+    /// - `PERSONA_MEMORY_CAPACITY` -- memory lines retained per agent (default `20`)
+    /// - `BATCH_SIZE` -- low-complexity agents per batched LLM call (default `1`, off)
+    /// - `BATCH_WINDOW_MS` -- batch collection window in milliseconds (default `200`)
+    /// - `BUDGET_MAX_TOKENS_PER_TICK` -- token cap per simulation tick (optional)
+    /// - `BUDGET_MAX_COST_PER_TICK` -- dollar cap per simulation tick (optional)
+    /// - `BUDGET_MAX_TOKENS_PER_DAY` -- token cap per UTC day (optional)
+    /// - `BUDGET_MAX_COST_PER_DAY` -- dollar cap per UTC day (optional)
+    /// - `BUDGET_CRITICAL_RESERVE` -- share of each cap reserved for critical agents (default `0.2`)
+    /// - `METRICS_INTERVAL_SECS` -- cost metrics publish interval in seconds (default `10`, `0` disables)
+    /// - `HEURISTIC_AGENT_PERCENT` -- percentage of agents in the no-LLM policy tier (default `0`)
+    /// - `REFLECTION_INTERVAL_TICKS` -- ticks between each agent's reflection (default `0`, off)
     /// - `PARTITION_ID` -- this runner's partition index (default `0`)
     /// - `TOTAL_PARTITIONS` -- total runner instances (default `1`)
+    #[allow(clippy::too_many_lines)]
     pub fn from_env() -> Result<Self, RunnerError> {
         let nats_url = env_var("NATS_URL")?;
         let primary_backend = load_backend_config("LLM_DEFAULT")?;
@@ -140,6 +275,8 @@ impl RunnerConfig {
             .parse()
             .map_err(|e| RunnerError::Config(format!("invalid DECISION_TIMEOUT_MS: {e}")))?;
 
+        let jetstream = load_jetstream_config(decision_timeout_ms)?;
+
         let max_concurrent_calls: usize = std::env::var("MAX_CONCURRENT_CALLS")
             .unwrap_or_else(|_| "20".to_owned())
             .parse()
@@ -155,6 +292,10 @@ impl RunnerConfig {
                 RunnerError::Config(format!("invalid COMPLEXITY_ROUTING_ENABLED: {e}"))
             })?;
 
+        let complexity_rules_path = std::env::var("COMPLEXITY_RULES_PATH")
+            .ok()
+            .filter(|s| !s.is_empty());
+
         let routine_action_bypass: bool = std::env::var("ROUTINE_ACTION_BYPASS")
             .unwrap_or_else(|_| "true".to_owned())
             .parse()
@@ -169,6 +310,49 @@ impl RunnerConfig {
                 RunnerError::Config(format!("invalid NIGHT_CYCLE_SKIP: {e}"))
             })?;
 
+        let parse_repair_reprompt: bool = std::env::var("PARSE_REPAIR_REPROMPT")
+            .unwrap_or_else(|_| "true".to_owned())
+            .parse()
+            .map_err(|e| {
+                RunnerError::Config(format!("invalid PARSE_REPAIR_REPROMPT: {e}"))
+            })?;
+
+        let prompt_variants: Vec<String> = std::env::var("PROMPT_VARIANTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+
+        let batch_size: usize = parse_env_or("BATCH_SIZE", 1)?;
+        if batch_size == 0 {
+            return Err(RunnerError::Config(
+                "BATCH_SIZE must be at least 1".to_owned(),
+            ));
+        }
+        let batch_window = Duration::from_millis(parse_env_or("BATCH_WINDOW_MS", 200)?);
+
+        let budget = load_budget_limits()?;
+
+        let metrics_interval_secs: u64 = parse_env_or("METRICS_INTERVAL_SECS", 10)?;
+        let metrics_interval =
+            (metrics_interval_secs > 0).then(|| Duration::from_secs(metrics_interval_secs));
+
+        let heuristic_agent_percent: u32 = parse_env_or("HEURISTIC_AGENT_PERCENT", 0)?;
+        if heuristic_agent_percent > 100 {
+            return Err(RunnerError::Config(format!(
+                "HEURISTIC_AGENT_PERCENT ({heuristic_agent_percent}) must be at most 100"
+            )));
+        }
+
+        let reflection_interval_ticks: u64 = parse_env_or("REFLECTION_INTERVAL_TICKS", 0)?;
+
+        let persona_memory_capacity: usize = parse_env_or(
+            "PERSONA_MEMORY_CAPACITY",
+            crate::persona::DEFAULT_MEMORY_CAPACITY,
+        )?;
+
         let openrouter_config = load_openrouter_config();
 
         let partition_id: u32 = std::env::var("PARTITION_ID")
@@ -195,14 +379,25 @@ impl RunnerConfig {
 
         Ok(Self {
             nats_url,
+            jetstream,
             primary_backend,
             secondary_backend,
             decision_timeout: Duration::from_millis(decision_timeout_ms),
             max_concurrent_calls,
             templates_dir,
+            complexity_rules_path,
             complexity_routing_enabled,
             routine_action_bypass,
             night_cycle_skip,
+            parse_repair_reprompt,
+            persona_memory_capacity,
+            prompt_variants,
+            batch_size,
+            batch_window,
+            budget,
+            metrics_interval,
+            heuristic_agent_percent,
+            reflection_interval_ticks,
             openrouter_config,
             partition_id,
             total_partitions,
@@ -220,7 +415,10 @@ fn env_var(name: &str) -> Result<String, RunnerError> {
 ///
 /// Reads `{prefix}_BACKEND`, `{prefix}_API_URL`, `{prefix}_API_KEY`,
 /// `{prefix}_MODEL`, and optionally `{prefix}_COST_PER_M_INPUT` /
-/// `{prefix}_COST_PER_M_OUTPUT` for cost tracking.
+/// `{prefix}_COST_PER_M_OUTPUT` for cost tracking and `{prefix}_OUTPUT_MODE`
+/// for the response format (default `text`), `{prefix}_PROMPT_CACHE`
+/// to toggle prompt-prefix caching (default `true`), and the retry and
+/// circuit breaker variables read by [`load_resilience_config`].
 fn load_backend_config(prefix: &str) -> Result<LlmBackendConfig, RunnerError> {
     let backend_str = env_var(&format!("{prefix}_BACKEND"))?;
     let api_url = env_var(&format!("{prefix}_API_URL"))?;
@@ -232,6 +430,18 @@ fn load_backend_config(prefix: &str) -> Result<LlmBackendConfig, RunnerError> {
     let cost_per_m_input = parse_optional_decimal(&format!("{prefix}_COST_PER_M_INPUT"))?;
     let cost_per_m_output = parse_optional_decimal(&format!("{prefix}_COST_PER_M_OUTPUT"))?;
 
+    let output_mode = match std::env::var(format!("{prefix}_OUTPUT_MODE")) {
+        Ok(val) if !val.is_empty() => parse_output_mode(&val)?,
+        _ => OutputMode::default(),
+    };
+
+    let prompt_cache: bool = std::env::var(format!("{prefix}_PROMPT_CACHE"))
+        .unwrap_or_else(|_| "true".to_owned())
+        .parse()
+        .map_err(|e| RunnerError::Config(format!("invalid {prefix}_PROMPT_CACHE: {e}")))?;
+
+    let resilience = load_resilience_config(prefix)?;
+
     Ok(LlmBackendConfig {
         backend_type,
         api_url,
@@ -239,6 +449,127 @@ fn load_backend_config(prefix: &str) -> Result<LlmBackendConfig, RunnerError> {
         model,
         cost_per_m_input,
         cost_per_m_output,
+        output_mode,
+        prompt_cache,
+        resilience,
+    })
+}
+
+/// Load retry and circuit breaker settings for a backend.
+///
+/// Reads `{prefix}_REQUEST_TIMEOUT_MS`, `{prefix}_MAX_RETRIES`,
+/// `{prefix}_RETRY_BACKOFF_MS`, `{prefix}_RETRY_MAX_BACKOFF_MS`,
+/// `{prefix}_BREAKER_FAILURE_THRESHOLD`, and `{prefix}_BREAKER_COOLDOWN_MS`.
+/// Unset variables fall back to [`ResilienceConfig::default`].
+fn load_resilience_config(prefix: &str) -> Result<ResilienceConfig, RunnerError> {
+    let defaults = ResilienceConfig::default();
+    let millis = |name: &str, default: Duration| -> Result<Duration, RunnerError> {
+        Ok(Duration::from_millis(parse_env_or(
+            &format!("{prefix}_{name}"),
+            u64::try_from(default.as_millis()).unwrap_or(u64::MAX),
+        )?))
+    };
+
+    let breaker_failure_threshold = parse_env_or(
+        &format!("{prefix}_BREAKER_FAILURE_THRESHOLD"),
+        defaults.breaker_failure_threshold,
+    )?;
+    if breaker_failure_threshold == 0 {
+        return Err(RunnerError::Config(format!(
+            "{prefix}_BREAKER_FAILURE_THRESHOLD must be >= 1"
+        )));
+    }
+
+    Ok(ResilienceConfig {
+        request_timeout: millis("REQUEST_TIMEOUT_MS", defaults.request_timeout)?,
+        max_retries: parse_env_or(&format!("{prefix}_MAX_RETRIES"), defaults.max_retries)?,
+        initial_backoff: millis("RETRY_BACKOFF_MS", defaults.initial_backoff)?,
+        max_backoff: millis("RETRY_MAX_BACKOFF_MS", defaults.max_backoff)?,
+        breaker_failure_threshold,
+        breaker_cooldown: millis("BREAKER_COOLDOWN_MS", defaults.breaker_cooldown)?,
+    })
+}
+
+/// Load `JetStream` settings from `NATS_JETSTREAM` and `JETSTREAM_*`.
+///
+/// Returns `None` when `NATS_JETSTREAM` is `false`. The ack wait must
+/// exceed the decision deadline, or a slow decision would be redelivered
+/// while it is still in progress.
+fn load_jetstream_config(decision_timeout_ms: u64) -> Result<Option<JetStreamConfig>, RunnerError> {
+    if !parse_env_or("NATS_JETSTREAM", true)? {
+        return Ok(None);
+    }
+    let defaults = JetStreamConfig::default();
+
+    let ack_wait_ms: u64 = parse_env_or(
+        "JETSTREAM_ACK_WAIT_MS",
+        u64::try_from(defaults.ack_wait.as_millis()).unwrap_or(u64::MAX),
+    )?;
+    if ack_wait_ms <= decision_timeout_ms {
+        return Err(RunnerError::Config(format!(
+            "JETSTREAM_ACK_WAIT_MS ({ack_wait_ms}) must exceed DECISION_TIMEOUT_MS \
+             ({decision_timeout_ms})"
+        )));
+    }
+    let max_deliver: i64 = parse_env_or("JETSTREAM_MAX_DELIVER", defaults.max_deliver)?;
+    if max_deliver < 1 {
+        return Err(RunnerError::Config("JETSTREAM_MAX_DELIVER must be >= 1".to_owned()));
+    }
+    let max_age_secs: u64 = parse_env_or("JETSTREAM_MAX_AGE_SECS", defaults.max_age.as_secs())?;
+
+    Ok(Some(JetStreamConfig {
+        ack_wait: Duration::from_millis(ack_wait_ms),
+        max_deliver,
+        max_age: Duration::from_secs(max_age_secs),
+    }))
+}
+
+/// Parse an environment variable, returning `default` when unset or empty.
+fn parse_env_or<T>(name: &str, default: T) -> Result<T, RunnerError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(name) {
+        Ok(val) if !val.is_empty() => val
+            .parse()
+            .map_err(|e| RunnerError::Config(format!("invalid {name}: {e}"))),
+        _ => Ok(default),
+    }
+}
+
+/// Parse an optional value from an environment variable.
+///
+/// Returns `Ok(None)` if the variable is not set or empty.
+fn parse_optional_env<T>(name: &str) -> Result<Option<T>, RunnerError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(name) {
+        Ok(val) if !val.is_empty() => val
+            .parse()
+            .map(Some)
+            .map_err(|e| RunnerError::Config(format!("invalid {name}: {e}"))),
+        _ => Ok(None),
+    }
+}
+
+/// Load the LLM spending caps from `BUDGET_*` environment variables.
+fn load_budget_limits() -> Result<BudgetLimits, RunnerError> {
+    let critical_reserve =
+        parse_optional_decimal("BUDGET_CRITICAL_RESERVE")?.unwrap_or_else(|| Decimal::new(2, 1));
+    if critical_reserve < Decimal::ZERO || critical_reserve > Decimal::ONE {
+        return Err(RunnerError::Config(format!(
+            "BUDGET_CRITICAL_RESERVE ({critical_reserve}) must be between 0 and 1"
+        )));
+    }
+    Ok(BudgetLimits {
+        max_tokens_per_tick: parse_optional_env("BUDGET_MAX_TOKENS_PER_TICK")?,
+        max_cost_per_tick: parse_optional_decimal("BUDGET_MAX_COST_PER_TICK")?,
+        max_tokens_per_day: parse_optional_env("BUDGET_MAX_TOKENS_PER_DAY")?,
+        max_cost_per_day: parse_optional_decimal("BUDGET_MAX_COST_PER_DAY")?,
+        critical_reserve,
     })
 }
 
@@ -257,6 +588,21 @@ fn parse_backend_type(s: &str) -> Result<BackendType, RunnerError> {
     }
 }
 
+/// Parse an output mode string into an [`OutputMode`].
+///
+/// Recognized strings (case-insensitive):
+/// - `text` -> [`OutputMode::Text`]
+/// - `structured`, `json_schema`, `tool` -> [`OutputMode::Structured`]
+fn parse_output_mode(s: &str) -> Result<OutputMode, RunnerError> {
+    match s.to_lowercase().as_str() {
+        "text" => Ok(OutputMode::Text),
+        "structured" | "json_schema" | "tool" => Ok(OutputMode::Structured),
+        other => Err(RunnerError::Config(format!(
+            "unknown output mode: {other}"
+        ))),
+    }
+}
+
 /// Parse an optional `Decimal` from an environment variable.
 ///
 /// Returns `Ok(None)` if the variable is not set or empty. Returns an error
@@ -298,6 +644,9 @@ mod tests {
             model: "gpt-5-nano".to_owned(),
             cost_per_m_input: None,
             cost_per_m_output: None,
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         assert_eq!(config.backend_type, BackendType::OpenAi);
 
@@ -308,6 +657,9 @@ mod tests {
             model: "claude-haiku-4-5".to_owned(),
             cost_per_m_input: None,
             cost_per_m_output: None,
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         assert_eq!(anthropic.backend_type, BackendType::Anthropic);
     }
@@ -334,6 +686,9 @@ mod tests {
             model: "deepseek/deepseek-chat-v3-0324".to_owned(),
             cost_per_m_input: Some(Decimal::new(30, 2)),
             cost_per_m_output: Some(Decimal::new(88, 2)),
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         assert_eq!(config.backend_type, BackendType::OpenAi);
         assert!(config.cost_per_m_input.is_some());
@@ -372,6 +727,63 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_output_mode_recognized_strings() {
+        for (name, expected) in [
+            ("text", OutputMode::Text),
+            ("TEXT", OutputMode::Text),
+            ("structured", OutputMode::Structured),
+            ("json_schema", OutputMode::Structured),
+            ("tool", OutputMode::Structured),
+        ] {
+            let result = parse_output_mode(name);
+            assert!(result.is_ok(), "output mode '{name}' should be recognized");
+            assert_eq!(result.unwrap_or_default(), expected);
+        }
+        assert!(parse_output_mode("freeform").is_err());
+    }
+
+    #[test]
+    fn resilience_defaults_fit_inside_decision_deadline() {
+        let cfg = ResilienceConfig::default();
+        let worst_case = cfg
+            .request_timeout
+            .saturating_mul(cfg.max_retries.saturating_add(1))
+            .saturating_add(cfg.initial_backoff);
+        assert!(worst_case <= Duration::from_secs(7));
+        assert!(cfg.breaker_failure_threshold >= 1);
+        assert!(cfg.initial_backoff <= cfg.max_backoff);
+    }
+
+    #[test]
+    fn jetstream_defaults_outlast_decision_deadline() {
+        let cfg = JetStreamConfig::default();
+        assert!(cfg.ack_wait > Duration::from_secs(7));
+        assert!(cfg.max_deliver >= 1);
+        assert!(cfg.max_age > cfg.ack_wait);
+    }
+
+    #[test]
+    fn parse_env_or_uses_default_when_unset() {
+        let value: u32 = parse_env_or("EMERGENCE_TEST_SURELY_UNSET_VAR", 7).unwrap_or(0);
+        assert_eq!(value, 7);
+    }
+
+    #[test]
+    fn budget_limits_default_to_unlimited() {
+        let limits = load_budget_limits().unwrap_or_else(|_| BudgetLimits {
+            max_tokens_per_tick: Some(0),
+            ..BudgetLimits::default()
+        });
+        assert!(limits.is_unlimited());
+        assert_eq!(limits.critical_reserve, Decimal::new(2, 1));
+    }
+
+    #[test]
+    fn output_mode_defaults_to_text() {
+        assert_eq!(OutputMode::default(), OutputMode::Text);
+    }
+
     #[test]
     fn decimal_parsing_valid_values() {
         // Test the Decimal parsing logic used by parse_optional_decimal
@@ -431,6 +843,9 @@ mod tests {
             model: "model".to_owned(),
             cost_per_m_input: None,
             cost_per_m_output: None,
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         assert!(config.cost_per_m_input.is_none());
         assert!(config.cost_per_m_output.is_none());
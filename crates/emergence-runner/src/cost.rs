@@ -7,17 +7,81 @@
 //!
 //! All monetary calculations use [`rust_decimal::Decimal`] for financial
 //! precision -- no floating-point arithmetic.
+//!
+//! # Budget enforcement
+//!
+//! With [`BudgetLimits`] attached the tracker also enforces token and cost
+//! caps per simulation tick and per UTC day. The runner asks
+//! [`CostTracker::exhausted_cap`] before each LLM call; once a cap is hit,
+//! the agent falls back to the rule-based bypass policy for the rest of the
+//! window. A configurable share of every cap is reserved for
+//! [`DecisionPriority::Critical`] agents so they keep getting LLM decisions
+//! after routine agents have been cut off.
+//!
+//! # Metrics publishing
+//!
+//! [`spawn_metrics_publisher`] periodically publishes a [`RunnerMetrics`]
+//! snapshot (totals, per-backend breakdown, error counts) to NATS for the
+//! observer dashboard.
 
+use std::collections::BTreeMap;
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use chrono::{NaiveDate, Utc};
+use emergence_types::{BackendUsage, RunnerMetrics};
 use rust_decimal::Decimal;
+use tracing::debug;
+
+use crate::complexity::DecisionPriority;
+use crate::nats::NatsClient;
 
 /// One million, used as the denominator for per-million-token pricing.
 ///
 /// Stored as a constant to avoid repeated construction.
 const ONE_MILLION: Decimal = Decimal::from_parts(1_000_000, 0, 0, false, 0);
 
+/// Token and cost caps enforced by a [`CostTracker`].
+///
+/// Every cap is optional; `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BudgetLimits {
+    /// Maximum tokens (input + output) spent within one simulation tick.
+    pub max_tokens_per_tick: Option<u64>,
+    /// Maximum estimated cost in dollars within one simulation tick.
+    pub max_cost_per_tick: Option<Decimal>,
+    /// Maximum tokens (input + output) spent within one UTC day.
+    pub max_tokens_per_day: Option<u64>,
+    /// Maximum estimated cost in dollars within one UTC day.
+    pub max_cost_per_day: Option<Decimal>,
+    /// Fraction of each cap (0 to 1) that only critical agents may use.
+    pub critical_reserve: Decimal,
+}
+
+impl BudgetLimits {
+    /// Whether no cap is configured.
+    pub const fn is_unlimited(&self) -> bool {
+        self.max_tokens_per_tick.is_none()
+            && self.max_cost_per_tick.is_none()
+            && self.max_tokens_per_day.is_none()
+            && self.max_cost_per_day.is_none()
+    }
+
+    /// The share of a cap available to agents of the given priority.
+    fn effective(&self, cap: Decimal, priority: DecisionPriority) -> Decimal {
+        match priority {
+            DecisionPriority::Critical => cap,
+            DecisionPriority::Normal => {
+                let share = Decimal::ONE
+                    .checked_sub(self.critical_reserve.clamp(Decimal::ZERO, Decimal::ONE))
+                    .unwrap_or(Decimal::ONE);
+                cap.checked_mul(share).unwrap_or(cap)
+            }
+        }
+    }
+}
+
 /// Thread-safe LLM cost tracker.
 ///
 /// Holds per-million-token pricing for two backend tiers (primary and
@@ -47,6 +111,8 @@ pub struct CostTracker {
     escalation_input_rate: Decimal,
     /// Price per million output tokens for the escalation backend.
     escalation_output_rate: Decimal,
+    /// Caps enforced by [`CostTracker::exhausted_cap`].
+    limits: BudgetLimits,
     /// Mutable interior state protected by a mutex.
     inner: Mutex<CostTrackerInner>,
 }
@@ -66,6 +132,60 @@ struct CostTrackerInner {
     primary_calls: u64,
     /// Number of calls routed to the escalation backend.
     escalation_calls: u64,
+    /// Usage within the current simulation tick.
+    tick_window: BudgetWindow<u64>,
+    /// Usage within the current UTC day.
+    day_window: BudgetWindow<NaiveDate>,
+    /// Number of LLM decisions refused because a cap was exhausted.
+    budget_denials: u64,
+    /// Number of failed LLM calls.
+    total_errors: u64,
+    /// Per-backend usage keyed by backend label.
+    backends: BTreeMap<String, BackendUsage>,
+}
+
+impl CostTrackerInner {
+    /// The usage entry for `label`, created empty if missing.
+    fn backend(&mut self, label: &str) -> &mut BackendUsage {
+        self.backends
+            .entry(label.to_owned())
+            .or_insert_with(|| BackendUsage {
+                backend: label.to_owned(),
+                model: String::new(),
+                calls: 0,
+                errors: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                estimated_cost: Decimal::ZERO,
+            })
+    }
+}
+
+/// Token and cost usage accumulated within one budget window.
+#[derive(Debug, Default)]
+struct BudgetWindow<K> {
+    /// Which tick or day this window covers.
+    key: K,
+    /// Tokens (input + output) spent in the window.
+    tokens: u64,
+    /// Estimated cost spent in the window.
+    cost: Decimal,
+}
+
+impl<K: PartialEq> BudgetWindow<K> {
+    /// Reset the window if it belongs to a different tick or day.
+    fn roll(&mut self, key: K) {
+        if self.key != key {
+            self.key = key;
+            self.tokens = 0;
+            self.cost = Decimal::ZERO;
+        }
+    }
+
+    fn add(&mut self, tokens: u64, cost: Decimal) {
+        self.tokens = self.tokens.saturating_add(tokens);
+        self.cost = self.cost.checked_add(cost).unwrap_or(self.cost);
+    }
 }
 
 /// Snapshot of cost tracking state returned by [`CostTracker::summary`].
@@ -87,6 +207,8 @@ pub struct CostSummary {
     pub primary_calls: u64,
     /// Number of calls routed to the escalation backend.
     pub escalation_calls: u64,
+    /// Number of LLM decisions refused because a budget cap was exhausted.
+    pub budget_denials: u64,
 }
 
 impl CostTracker {
@@ -105,6 +227,13 @@ impl CostTracker {
             primary_output_rate,
             escalation_input_rate,
             escalation_output_rate,
+            limits: BudgetLimits {
+                max_tokens_per_tick: None,
+                max_cost_per_tick: None,
+                max_tokens_per_day: None,
+                max_cost_per_day: None,
+                critical_reserve: Decimal::ZERO,
+            },
             inner: Mutex::new(CostTrackerInner {
                 total_calls: 0,
                 total_input_tokens: 0,
@@ -112,10 +241,102 @@ impl CostTracker {
                 total_estimated_cost: Decimal::ZERO,
                 primary_calls: 0,
                 escalation_calls: 0,
+                tick_window: BudgetWindow {
+                    key: 0,
+                    tokens: 0,
+                    cost: Decimal::ZERO,
+                },
+                day_window: BudgetWindow {
+                    key: NaiveDate::MIN,
+                    tokens: 0,
+                    cost: Decimal::ZERO,
+                },
+                budget_denials: 0,
+                total_errors: 0,
+                backends: BTreeMap::new(),
             }),
         }
     }
 
+    /// Register the model served by a backend so metrics can break usage
+    /// down per model.
+    pub fn register_backend(&self, backend_label: &str, model: &str) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        model.clone_into(&mut inner.backend(backend_label).model);
+    }
+
+    /// Record a failed LLM call (transport error, error status, or an
+    /// unreadable response body).
+    pub fn record_error(&self, backend_label: &str) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.total_errors = inner.total_errors.saturating_add(1);
+        let usage = inner.backend(backend_label);
+        usage.errors = usage.errors.saturating_add(1);
+    }
+
+    /// Attach spending caps to enforce via [`CostTracker::exhausted_cap`].
+    pub const fn with_limits(mut self, limits: BudgetLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Move the per-tick window to `tick`, resetting it if the tick changed.
+    ///
+    /// The runner calls this before each budget check so per-tick caps
+    /// apply to the tick currently being decided.
+    pub fn begin_tick(&self, tick: u64) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.tick_window.roll(tick);
+    }
+
+    /// Check the budget for an agent of the given priority.
+    ///
+    /// Returns the name of the first exhausted cap (for example
+    /// `"tokens_per_tick"`), or `None` if the LLM call may proceed. Normal
+    /// priority agents are held to the caps minus the critical reserve.
+    /// Each refusal is counted in [`CostSummary::budget_denials`].
+    pub fn exhausted_cap(&self, priority: DecisionPriority) -> Option<&'static str> {
+        self.exhausted_cap_on(priority, Utc::now().date_naive())
+    }
+
+    /// [`CostTracker::exhausted_cap`] with an explicit current date.
+    fn exhausted_cap_on(&self, priority: DecisionPriority, today: NaiveDate) -> Option<&'static str> {
+        if self.limits.is_unlimited() {
+            return None;
+        }
+        let Ok(mut inner) = self.inner.lock() else {
+            return None;
+        };
+        inner.day_window.roll(today);
+
+        let over = |cap: Option<Decimal>, used: Decimal| {
+            cap.is_some_and(|cap| used >= self.limits.effective(cap, priority))
+        };
+        let as_dec = |cap: Option<u64>| cap.map(Decimal::from);
+        let exhausted = if over(as_dec(self.limits.max_tokens_per_tick), Decimal::from(inner.tick_window.tokens)) {
+            Some("tokens_per_tick")
+        } else if over(self.limits.max_cost_per_tick, inner.tick_window.cost) {
+            Some("cost_per_tick")
+        } else if over(as_dec(self.limits.max_tokens_per_day), Decimal::from(inner.day_window.tokens)) {
+            Some("tokens_per_day")
+        } else if over(self.limits.max_cost_per_day, inner.day_window.cost) {
+            Some("cost_per_day")
+        } else {
+            None
+        };
+
+        if exhausted.is_some() {
+            inner.budget_denials = inner.budget_denials.saturating_add(1);
+        }
+        exhausted
+    }
+
     /// Record a completed LLM call with token usage.
     ///
     /// `backend_label` should be `"primary"` or `"escalation"` to select
@@ -125,6 +346,17 @@ impl CostTracker {
     /// Token counts that would overflow the running totals are clamped
     /// via saturating addition.
     pub fn record_call(&self, backend_label: &str, input_tokens: u64, output_tokens: u64) {
+        self.record_call_on(backend_label, input_tokens, output_tokens, Utc::now().date_naive());
+    }
+
+    /// [`CostTracker::record_call`] with an explicit current date.
+    fn record_call_on(
+        &self,
+        backend_label: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        today: NaiveDate,
+    ) {
         let is_escalation = backend_label == "escalation";
 
         let (input_rate, output_rate) = if is_escalation {
@@ -175,6 +407,20 @@ impl CostTracker {
         } else {
             inner.primary_calls = inner.primary_calls.saturating_add(1);
         }
+
+        let usage = inner.backend(backend_label);
+        usage.calls = usage.calls.saturating_add(1);
+        usage.input_tokens = usage.input_tokens.saturating_add(input_tokens);
+        usage.output_tokens = usage.output_tokens.saturating_add(output_tokens);
+        usage.estimated_cost = usage
+            .estimated_cost
+            .checked_add(call_cost)
+            .unwrap_or(usage.estimated_cost);
+
+        let call_tokens = input_tokens.saturating_add(output_tokens);
+        inner.tick_window.add(call_tokens, call_cost);
+        inner.day_window.roll(today);
+        inner.day_window.add(call_tokens, call_cost);
     }
 
     /// Return a snapshot of the current cost tracking state.
@@ -193,6 +439,7 @@ impl CostTracker {
                 total_estimated_cost: Decimal::ZERO,
                 primary_calls: 0,
                 escalation_calls: 0,
+                budget_denials: 0,
             };
         };
 
@@ -203,8 +450,68 @@ impl CostTracker {
             total_estimated_cost: inner.total_estimated_cost,
             primary_calls: inner.primary_calls,
             escalation_calls: inner.escalation_calls,
+            budget_denials: inner.budget_denials,
         }
     }
+
+    /// Build a [`RunnerMetrics`] snapshot for publishing.
+    ///
+    /// Returns zeroed totals if the mutex is poisoned.
+    pub fn metrics(&self, partition_id: u32) -> RunnerMetrics {
+        let published_at = Utc::now();
+        let Ok(inner) = self.inner.lock() else {
+            return RunnerMetrics {
+                partition_id,
+                total_calls: 0,
+                total_errors: 0,
+                total_input_tokens: 0,
+                total_output_tokens: 0,
+                total_estimated_cost: Decimal::ZERO,
+                budget_denials: 0,
+                backends: Vec::new(),
+                published_at,
+            };
+        };
+
+        RunnerMetrics {
+            partition_id,
+            total_calls: inner.total_calls,
+            total_errors: inner.total_errors,
+            total_input_tokens: inner.total_input_tokens,
+            total_output_tokens: inner.total_output_tokens,
+            total_estimated_cost: inner.total_estimated_cost,
+            budget_denials: inner.budget_denials,
+            backends: inner.backends.values().cloned().collect(),
+            published_at,
+        }
+    }
+}
+
+/// Publish the tracker's [`RunnerMetrics`] to NATS every `interval`.
+///
+/// The task runs until the runtime shuts down. Publishing is
+/// fire-and-forget, like decision records.
+pub fn spawn_metrics_publisher(
+    tracker: Arc<CostTracker>,
+    nats: NatsClient,
+    partition_id: u32,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let metrics = tracker.metrics(partition_id);
+            debug!(
+                partition_id = partition_id,
+                total_calls = metrics.total_calls,
+                total_errors = metrics.total_errors,
+                "publishing runner metrics"
+            );
+            nats.publish_metrics(&metrics);
+        }
+    })
 }
 
 impl fmt::Display for CostSummary {
@@ -213,13 +520,14 @@ impl fmt::Display for CostSummary {
             f,
             "LLM Cost Summary: {} calls ({} primary, {} escalation) | \
              {} input tokens, {} output tokens | \
-             estimated cost: ${}",
+             estimated cost: ${} | {} budget denials",
             self.total_calls,
             self.primary_calls,
             self.escalation_calls,
             self.total_input_tokens,
             self.total_output_tokens,
             self.total_estimated_cost,
+            self.budget_denials,
         )
     }
 }
@@ -365,4 +673,112 @@ mod tests {
         assert_eq!(summary.total_input_tokens, 1_000_000);
         assert_eq!(summary.total_output_tokens, 200_000);
     }
+
+    // -----------------------------------------------------------------------
+    // Budget enforcement
+    // -----------------------------------------------------------------------
+
+    fn day(n: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, n).unwrap_or_default()
+    }
+
+    #[test]
+    fn unlimited_budget_never_denies() {
+        let tracker = test_tracker();
+        tracker.record_call("primary", 10_000_000, 10_000_000);
+        assert_eq!(tracker.exhausted_cap(DecisionPriority::Normal), None);
+        assert_eq!(tracker.summary().budget_denials, 0);
+    }
+
+    #[test]
+    fn tick_cap_denies_until_next_tick() {
+        let tracker = test_tracker().with_limits(BudgetLimits {
+            max_tokens_per_tick: Some(1_000),
+            ..BudgetLimits::default()
+        });
+        tracker.begin_tick(5);
+        tracker.record_call_on("primary", 800, 200, day(1));
+        assert_eq!(
+            tracker.exhausted_cap_on(DecisionPriority::Critical, day(1)),
+            Some("tokens_per_tick")
+        );
+
+        tracker.begin_tick(6);
+        assert_eq!(tracker.exhausted_cap_on(DecisionPriority::Critical, day(1)), None);
+        assert_eq!(tracker.summary().budget_denials, 1);
+    }
+
+    #[test]
+    fn critical_reserve_keeps_budget_for_critical_agents() {
+        let tracker = test_tracker().with_limits(BudgetLimits {
+            max_cost_per_tick: Some(Decimal::new(100, 2)),
+            critical_reserve: Decimal::new(25, 2),
+            ..BudgetLimits::default()
+        });
+        tracker.begin_tick(1);
+        // $0.88 spent: past the 75% normal share, under the full cap.
+        tracker.record_call_on("primary", 0, 1_000_000, day(1));
+        assert_eq!(
+            tracker.exhausted_cap_on(DecisionPriority::Normal, day(1)),
+            Some("cost_per_tick")
+        );
+        assert_eq!(tracker.exhausted_cap_on(DecisionPriority::Critical, day(1)), None);
+    }
+
+    #[test]
+    fn day_cap_rolls_over_at_midnight() {
+        let tracker = test_tracker().with_limits(BudgetLimits {
+            max_tokens_per_day: Some(500),
+            ..BudgetLimits::default()
+        });
+        tracker.begin_tick(1);
+        tracker.record_call_on("primary", 400, 100, day(1));
+        tracker.begin_tick(2);
+        assert_eq!(
+            tracker.exhausted_cap_on(DecisionPriority::Normal, day(1)),
+            Some("tokens_per_day")
+        );
+        assert_eq!(tracker.exhausted_cap_on(DecisionPriority::Normal, day(2)), None);
+    }
+
+    // -----------------------------------------------------------------------
+    // Metrics snapshot
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn metrics_break_usage_down_per_backend() {
+        let tracker = test_tracker();
+        tracker.register_backend("primary", "deepseek-chat");
+        tracker.register_backend("escalation", "claude-sonnet");
+        tracker.record_call("primary", 1_000_000, 0);
+        tracker.record_call("primary", 1_000_000, 0);
+        tracker.record_error("primary");
+        tracker.record_error("escalation");
+
+        let metrics = tracker.metrics(3);
+        assert_eq!(metrics.partition_id, 3);
+        assert_eq!(metrics.total_calls, 2);
+        assert_eq!(metrics.total_errors, 2);
+        assert_eq!(metrics.total_estimated_cost, Decimal::new(60, 2));
+
+        assert_eq!(metrics.backends.len(), 2);
+        let usage = |label: &str| {
+            metrics
+                .backends
+                .iter()
+                .find(|b| b.backend == label)
+                .map(|b| (b.model.as_str(), b.calls, b.errors, b.input_tokens))
+        };
+        assert_eq!(usage("primary"), Some(("deepseek-chat", 2, 1, 2_000_000)));
+        assert_eq!(usage("escalation"), Some(("claude-sonnet", 0, 1, 0)));
+    }
+
+    #[test]
+    fn metrics_serialize_for_nats() {
+        let tracker = test_tracker();
+        tracker.record_call("primary", 10, 5);
+        let json = serde_json::to_value(tracker.metrics(0)).unwrap_or_default();
+        assert_eq!(json.get("total_calls"), Some(&serde_json::json!(1)));
+        assert!(json.get("backends").is_some_and(serde_json::Value::is_array));
+    }
 }
@@ -18,17 +18,29 @@ pub enum RunnerError {
     #[error("LLM backend error: {0}")]
     LlmBackend(String),
 
+    /// An LLM backend responded with a non-success HTTP status.
+    #[error("LLM backend returned {status}: {message}")]
+    LlmStatus {
+        /// The HTTP status code.
+        status: u16,
+        /// The provider name and response body, for diagnostics.
+        message: String,
+    },
+
+    /// The backend's circuit breaker is open; no request was sent.
+    #[error("circuit open for LLM backend {0}")]
+    CircuitOpen(String),
+
     /// The LLM response could not be parsed into a valid action.
     #[error("response parse error: {0}")]
     Parse(String),
 
-    /// The decision deadline was exceeded.
+    /// A deadline was exceeded.
     ///
-    /// Currently timeouts are handled inline via `tokio::time::timeout`
-    /// in the runner module, but this variant exists for future use in
-    /// explicit timeout error propagation.
-    #[error("timeout: agent decision exceeded deadline")]
-    #[allow(dead_code)]
+    /// Returned by backends when the per-request timeout elapses. The
+    /// overall decision deadline is still handled inline via
+    /// `tokio::time::timeout` in the runner module.
+    #[error("timeout: LLM request exceeded deadline")]
     Timeout,
 
     /// Configuration is invalid or missing.
@@ -10,15 +10,26 @@
 //!
 //! When a `CostTracker` is attached, successful responses are inspected
 //! for the `usage` field and token counts are recorded for cost estimation.
+//!
+//! Both backends send the prompt's stable prefix (system rules + persona)
+//! as the system message so provider prompt caches can reuse it across
+//! ticks. The Anthropic backend additionally marks the prefix with a
+//! `cache_control` breakpoint when prompt caching is enabled.
+//!
+//! Backends configured with [`OutputMode::Structured`] attach the action
+//! schema from [`crate::schema`] to every request so the provider enforces
+//! the response shape natively. The returned text is still the action JSON,
+//! so the parser handles both modes identically.
 
 use std::sync::Arc;
 
 use tracing::debug;
 
-use crate::config::{BackendType, LlmBackendConfig, OpenRouterConfig};
+use crate::config::{BackendType, LlmBackendConfig, OpenRouterConfig, OutputMode};
 use crate::cost::CostTracker;
 use crate::error::RunnerError;
 use crate::prompt::RenderedPrompt;
+use crate::schema::{ACTION_SCHEMA_NAME, action_response_schema};
 
 // ---------------------------------------------------------------------------
 // Unified backend enum (dyn-compatible alternative to async trait)
@@ -38,17 +49,32 @@ pub enum LlmBackend {
 impl LlmBackend {
     /// Send a prompt to the LLM and return the response text.
     ///
-    /// Dispatches to the concrete backend implementation.
+    /// Dispatches to the concrete backend implementation. Failed calls are
+    /// counted in the backend's cost tracker.
     ///
     /// # Errors
     ///
     /// Returns [`RunnerError::LlmBackend`] if the HTTP call fails or the
     /// response cannot be extracted.
     pub async fn complete(&self, prompt: &RenderedPrompt) -> Result<String, RunnerError> {
-        match self {
-            Self::OpenAi(backend) => backend.complete(prompt).await,
-            Self::Anthropic(backend) => backend.complete(prompt).await,
+        let (result, tracker, label) = match self {
+            Self::OpenAi(backend) => (
+                backend.complete(prompt).await,
+                backend.cost_tracker.as_ref(),
+                &backend.backend_label,
+            ),
+            Self::Anthropic(backend) => (
+                backend.complete(prompt).await,
+                backend.cost_tracker.as_ref(),
+                &backend.backend_label,
+            ),
+        };
+        if result.is_err()
+            && let Some(tracker) = tracker
+        {
+            tracker.record_error(label);
         }
+        result
     }
 
     /// Human-readable name for logging.
@@ -64,11 +90,14 @@ impl LlmBackend {
 ///
 /// Not all providers return this field; when absent, token counts are zero.
 #[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_field_names)]
 pub struct TokenUsage {
     /// Number of tokens in the prompt (input).
     pub prompt_tokens: u64,
     /// Number of tokens in the completion (output).
     pub completion_tokens: u64,
+    /// Number of prompt tokens served from the provider's prompt cache.
+    pub cached_prompt_tokens: u64,
 }
 
 // ---------------------------------------------------------------------------
@@ -94,6 +123,8 @@ pub struct OpenAiBackend {
     cost_tracker: Option<Arc<CostTracker>>,
     /// Human-readable backend label for cost tracking entries.
     backend_label: String,
+    /// Action response schema, present when running in structured mode.
+    response_schema: Option<serde_json::Value>,
 }
 
 impl OpenAiBackend {
@@ -105,13 +136,14 @@ impl OpenAiBackend {
         backend_label: String,
     ) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: http_client(config),
             api_url: config.api_url.clone(),
             api_key: config.api_key.clone(),
             model: config.model.clone(),
             openrouter_config: openrouter_config.clone(),
             cost_tracker,
             backend_label,
+            response_schema: structured_schema(config.output_mode),
         }
     }
 
@@ -122,12 +154,12 @@ impl OpenAiBackend {
         let body = serde_json::json!({
             "model": self.model,
             "messages": [
-                {"role": "system", "content": prompt.system},
+                {"role": "system", "content": prompt.stable_prefix()},
                 {"role": "user", "content": prompt.user}
             ],
             "temperature": 0.7,
             "max_tokens": 512,
-            "response_format": {"type": "json_object"}
+            "response_format": openai_response_format(self.response_schema.as_ref())
         });
 
         let mut request = self
@@ -148,7 +180,7 @@ impl OpenAiBackend {
             .json(&body)
             .send()
             .await
-            .map_err(|e| RunnerError::LlmBackend(format!("OpenAI request failed: {e}")))?;
+            .map_err(|e| request_error("OpenAI", &e))?;
 
         let status = response.status();
         if !status.is_success() {
@@ -156,9 +188,10 @@ impl OpenAiBackend {
                 .text()
                 .await
                 .unwrap_or_else(|_| "unable to read error body".to_owned());
-            return Err(RunnerError::LlmBackend(format!(
-                "OpenAI returned {status}: {error_body}"
-            )));
+            return Err(RunnerError::LlmStatus {
+                status: status.as_u16(),
+                message: format!("OpenAI: {error_body}"),
+            });
         }
 
         let json: serde_json::Value = response
@@ -178,6 +211,7 @@ impl OpenAiBackend {
                 backend = self.backend_label,
                 prompt_tokens = usage.prompt_tokens,
                 completion_tokens = usage.completion_tokens,
+                cached_prompt_tokens = usage.cached_prompt_tokens,
                 "token usage recorded"
             );
         }
@@ -186,6 +220,26 @@ impl OpenAiBackend {
     }
 }
 
+/// Build the `response_format` field for an `OpenAI` chat completions request.
+///
+/// Text mode asks for a generic JSON object; structured mode supplies the
+/// action schema so the provider constrains decoding to it.
+fn openai_response_format(schema: Option<&serde_json::Value>) -> serde_json::Value {
+    schema.map_or_else(
+        || serde_json::json!({"type": "json_object"}),
+        |schema| {
+            serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": ACTION_SCHEMA_NAME,
+                    "strict": false,
+                    "schema": schema
+                }
+            })
+        },
+    )
+}
+
 /// Extract the text content from an `OpenAI` chat completions response.
 fn extract_openai_content(json: &serde_json::Value) -> Result<String, RunnerError> {
     json.get("choices")
@@ -218,6 +272,10 @@ fn extract_openai_usage(json: &serde_json::Value) -> TokenUsage {
             .get("completion_tokens")
             .and_then(serde_json::Value::as_u64)
             .unwrap_or(0),
+        cached_prompt_tokens: usage
+            .pointer("/prompt_tokens_details/cached_tokens")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0),
     }
 }
 
@@ -232,6 +290,7 @@ fn extract_openai_usage(json: &serde_json::Value) -> TokenUsage {
 /// - Messages array does not include system (system is a top-level field)
 /// - Response structure differs: `content[0].text`
 /// - Usage is returned as `usage.input_tokens` / `usage.output_tokens`
+/// - Prompt caching is opt-in per request via `cache_control` blocks
 pub struct AnthropicBackend {
     client: reqwest::Client,
     api_url: String,
@@ -241,6 +300,10 @@ pub struct AnthropicBackend {
     cost_tracker: Option<Arc<CostTracker>>,
     /// Human-readable backend label for cost tracking entries.
     backend_label: String,
+    /// Action response schema, present when running in structured mode.
+    response_schema: Option<serde_json::Value>,
+    /// Whether to mark the stable prompt prefix with a cache breakpoint.
+    prompt_cache: bool,
 }
 
 impl AnthropicBackend {
@@ -251,12 +314,14 @@ impl AnthropicBackend {
         backend_label: String,
     ) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: http_client(config),
             api_url: config.api_url.clone(),
             api_key: config.api_key.clone(),
             model: config.model.clone(),
             cost_tracker,
             backend_label,
+            response_schema: structured_schema(config.output_mode),
+            prompt_cache: config.prompt_cache,
         }
     }
 
@@ -264,15 +329,33 @@ impl AnthropicBackend {
     async fn complete(&self, prompt: &RenderedPrompt) -> Result<String, RunnerError> {
         let url = format!("{}/messages", self.api_url);
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "model": self.model,
             "max_tokens": 512,
-            "system": prompt.system,
+            "system": anthropic_system_blocks(prompt, self.prompt_cache),
             "messages": [
                 {"role": "user", "content": prompt.user}
             ]
         });
 
+        // Structured mode: force a single tool call whose input is the action.
+        if let Some(schema) = &self.response_schema
+            && let Some(obj) = body.as_object_mut()
+        {
+            obj.insert(
+                "tools".to_owned(),
+                serde_json::json!([{
+                    "name": ACTION_SCHEMA_NAME,
+                    "description": "Submit the single action you take this tick.",
+                    "input_schema": schema
+                }]),
+            );
+            obj.insert(
+                "tool_choice".to_owned(),
+                serde_json::json!({"type": "tool", "name": ACTION_SCHEMA_NAME}),
+            );
+        }
+
         let response = self
             .client
             .post(&url)
@@ -282,7 +365,7 @@ impl AnthropicBackend {
             .json(&body)
             .send()
             .await
-            .map_err(|e| RunnerError::LlmBackend(format!("Anthropic request failed: {e}")))?;
+            .map_err(|e| request_error("Anthropic", &e))?;
 
         let status = response.status();
         if !status.is_success() {
@@ -290,9 +373,10 @@ impl AnthropicBackend {
                 .text()
                 .await
                 .unwrap_or_else(|_| "unable to read error body".to_owned());
-            return Err(RunnerError::LlmBackend(format!(
-                "Anthropic returned {status}: {error_body}"
-            )));
+            return Err(RunnerError::LlmStatus {
+                status: status.as_u16(),
+                message: format!("Anthropic: {error_body}"),
+            });
         }
 
         let json: serde_json::Value = response
@@ -314,14 +398,40 @@ impl AnthropicBackend {
                 backend = self.backend_label,
                 prompt_tokens = usage.prompt_tokens,
                 completion_tokens = usage.completion_tokens,
+                cached_prompt_tokens = usage.cached_prompt_tokens,
                 "token usage recorded"
             );
         }
 
+        if self.response_schema.is_some()
+            && let Some(input) = extract_anthropic_tool_input(&json)
+        {
+            return Ok(input);
+        }
+
         extract_anthropic_content(&json)
     }
 }
 
+/// Build the `system` field for an Anthropic Messages API request.
+///
+/// The stable prefix is sent as a single text block. When caching is
+/// enabled the block carries an ephemeral `cache_control` breakpoint so
+/// subsequent ticks for the same agent read it from the prompt cache.
+fn anthropic_system_blocks(prompt: &RenderedPrompt, prompt_cache: bool) -> serde_json::Value {
+    let mut block = serde_json::json!({
+        "type": "text",
+        "text": prompt.stable_prefix()
+    });
+    if prompt_cache && let Some(obj) = block.as_object_mut() {
+        obj.insert(
+            "cache_control".to_owned(),
+            serde_json::json!({"type": "ephemeral"}),
+        );
+    }
+    serde_json::json!([block])
+}
+
 /// Extract the text content from an Anthropic Messages API response.
 fn extract_anthropic_content(json: &serde_json::Value) -> Result<String, RunnerError> {
     json.get("content")
@@ -334,6 +444,24 @@ fn extract_anthropic_content(json: &serde_json::Value) -> Result<String, RunnerE
         })
 }
 
+/// Extract the forced tool call input from an Anthropic Messages API response.
+///
+/// Returns the `input` of the first `tool_use` block named
+/// [`ACTION_SCHEMA_NAME`], re-serialized as JSON text for the parser.
+/// Returns `None` if the model answered with plain text instead.
+fn extract_anthropic_tool_input(json: &serde_json::Value) -> Option<String> {
+    json.get("content")
+        .and_then(serde_json::Value::as_array)?
+        .iter()
+        .find(|block| {
+            block.get("type").and_then(serde_json::Value::as_str) == Some("tool_use")
+                && block.get("name").and_then(serde_json::Value::as_str)
+                    == Some(ACTION_SCHEMA_NAME)
+        })
+        .and_then(|block| block.get("input"))
+        .map(serde_json::Value::to_string)
+}
+
 /// Extract token usage from an Anthropic Messages API response.
 ///
 /// Anthropic uses `usage.input_tokens` / `usage.output_tokens` rather
@@ -352,6 +480,10 @@ fn extract_anthropic_usage(json: &serde_json::Value) -> TokenUsage {
             .get("output_tokens")
             .and_then(serde_json::Value::as_u64)
             .unwrap_or(0),
+        cached_prompt_tokens: usage
+            .get("cache_read_input_tokens")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0),
     }
 }
 
@@ -359,6 +491,34 @@ fn extract_anthropic_usage(json: &serde_json::Value) -> TokenUsage {
 // Factory
 // ---------------------------------------------------------------------------
 
+/// Build the HTTP client for a backend, applying its per-request timeout.
+fn http_client(config: &LlmBackendConfig) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(config.resilience.request_timeout)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+/// Map a `reqwest` send failure to a [`RunnerError`].
+///
+/// Timeouts become [`RunnerError::Timeout`] so the retry layer can classify
+/// them; everything else is a transport-level [`RunnerError::LlmBackend`].
+fn request_error(provider: &str, error: &reqwest::Error) -> RunnerError {
+    if error.is_timeout() {
+        RunnerError::Timeout
+    } else {
+        RunnerError::LlmBackend(format!("{provider} request failed: {error}"))
+    }
+}
+
+/// Build the action response schema when a backend runs in structured mode.
+fn structured_schema(mode: OutputMode) -> Option<serde_json::Value> {
+    match mode {
+        OutputMode::Text => None,
+        OutputMode::Structured => Some(action_response_schema()),
+    }
+}
+
 /// Create an LLM backend from configuration.
 ///
 /// Dispatches to [`OpenAiBackend`] or [`AnthropicBackend`] based on the
@@ -370,6 +530,9 @@ pub fn create_backend(
     cost_tracker: Option<Arc<CostTracker>>,
     backend_label: &str,
 ) -> LlmBackend {
+    if let Some(tracker) = &cost_tracker {
+        tracker.register_backend(backend_label, &config.model);
+    }
     match config.backend_type {
         BackendType::OpenAi => LlmBackend::OpenAi(OpenAiBackend::new(
             config,
@@ -388,6 +551,7 @@ pub fn create_backend(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ResilienceConfig;
     use rust_decimal::Decimal;
 
     #[test]
@@ -468,6 +632,49 @@ mod tests {
         assert_eq!(usage.completion_tokens, 50);
     }
 
+    #[test]
+    fn extract_usage_cached_tokens() {
+        let openai = serde_json::json!({
+            "usage": {
+                "prompt_tokens": 1500,
+                "completion_tokens": 40,
+                "prompt_tokens_details": {"cached_tokens": 1024}
+            }
+        });
+        assert_eq!(extract_openai_usage(&openai).cached_prompt_tokens, 1024);
+
+        let anthropic = serde_json::json!({
+            "usage": {
+                "input_tokens": 300,
+                "output_tokens": 40,
+                "cache_read_input_tokens": 1200
+            }
+        });
+        assert_eq!(extract_anthropic_usage(&anthropic).cached_prompt_tokens, 1200);
+    }
+
+    #[test]
+    fn anthropic_system_blocks_cache_control() {
+        let prompt = RenderedPrompt {
+            system: "rules".to_owned(),
+            persona: "Name: Luna".to_owned(),
+            user: "Tick: 1".to_owned(),
+        };
+
+        let cached = anthropic_system_blocks(&prompt, true);
+        assert_eq!(
+            cached.pointer("/0/cache_control/type"),
+            Some(&serde_json::json!("ephemeral"))
+        );
+        assert_eq!(
+            cached.pointer("/0/text"),
+            Some(&serde_json::json!("rules\n\nName: Luna"))
+        );
+
+        let uncached = anthropic_system_blocks(&prompt, false);
+        assert!(uncached.pointer("/0/cache_control").is_none());
+    }
+
     #[test]
     fn extract_anthropic_usage_missing() {
         let json = serde_json::json!({"content": [{"type": "text", "text": "test"}]});
@@ -476,6 +683,60 @@ mod tests {
         assert_eq!(usage.completion_tokens, 0);
     }
 
+    #[test]
+    fn openai_response_format_text_mode() {
+        let format = openai_response_format(None);
+        assert_eq!(format, serde_json::json!({"type": "json_object"}));
+    }
+
+    #[test]
+    fn openai_response_format_structured_mode() {
+        let schema = action_response_schema();
+        let format = openai_response_format(Some(&schema));
+        assert_eq!(format.get("type"), Some(&serde_json::json!("json_schema")));
+        assert_eq!(
+            format.pointer("/json_schema/name"),
+            Some(&serde_json::json!(ACTION_SCHEMA_NAME))
+        );
+        assert!(format.pointer("/json_schema/schema/properties/action_type").is_some());
+    }
+
+    #[test]
+    fn extract_anthropic_tool_input_valid() {
+        let json = serde_json::json!({
+            "content": [
+                {"type": "text", "text": "Deciding..."},
+                {
+                    "type": "tool_use",
+                    "id": "toolu_01",
+                    "name": ACTION_SCHEMA_NAME,
+                    "input": {"action_type": "Gather", "parameters": {"resource": "Wood"}}
+                }
+            ]
+        });
+        let input = extract_anthropic_tool_input(&json).unwrap_or_default();
+        let decision = crate::parse::parse_llm_response(
+            &input,
+            &[],
+            &std::collections::BTreeMap::new(),
+        );
+        assert_eq!(decision.action_type, emergence_types::ActionType::Gather);
+    }
+
+    #[test]
+    fn extract_anthropic_tool_input_missing() {
+        let json = serde_json::json!({
+            "content": [{"type": "text", "text": "{\"action_type\": \"Rest\"}"}]
+        });
+        assert!(extract_anthropic_tool_input(&json).is_none());
+    }
+
+    #[test]
+    fn structured_schema_only_in_structured_mode() {
+        assert!(structured_schema(OutputMode::Text).is_none());
+        assert!(structured_schema(OutputMode::Structured).is_some());
+    }
+
     #[test]
     fn create_backend_dispatches_correctly() {
         let or_config = OpenRouterConfig::default();
@@ -487,6 +748,9 @@ mod tests {
             model: "test-model".to_owned(),
             cost_per_m_input: None,
             cost_per_m_output: None,
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         let backend = create_backend(&openai_config, &or_config, None, "primary");
         assert_eq!(backend.name(), "openai-compatible");
@@ -498,6 +762,9 @@ mod tests {
             model: "test-model".to_owned(),
             cost_per_m_input: None,
             cost_per_m_output: None,
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         let backend = create_backend(&anthropic_config, &or_config, None, "escalation");
         assert_eq!(backend.name(), "anthropic");
@@ -523,6 +790,9 @@ mod tests {
             model: "deepseek/deepseek-chat-v3-0324".to_owned(),
             cost_per_m_input: Some(Decimal::new(30, 2)),
             cost_per_m_output: Some(Decimal::new(88, 2)),
+            output_mode: OutputMode::Text,
+            prompt_cache: true,
+            resilience: ResilienceConfig::default(),
         };
         let backend = create_backend(
             &config,
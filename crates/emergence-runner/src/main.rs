@@ -14,6 +14,7 @@
 //! Every agent gets one decision per tick. If the LLM fails or times out,
 //! the runner submits `NoAction` so the agent never misses a tick.
 
+mod batch;
 mod complexity;
 mod config;
 mod containment;
@@ -22,21 +23,26 @@ mod error;
 mod llm;
 mod nats;
 mod parse;
+mod persona;
 mod prompt;
+mod reflection;
+mod resilience;
 mod rule_engine;
 mod runner;
+mod schema;
 
 use std::sync::Arc;
 
 use rust_decimal::Decimal;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
 
-use crate::config::RunnerConfig;
-use crate::cost::CostTracker;
+use crate::complexity::ComplexityRules;
+use crate::config::{OutputMode, RunnerConfig};
+use crate::cost::{spawn_metrics_publisher, CostTracker};
 use crate::llm::create_backend;
 use crate::nats::NatsClient;
 use crate::prompt::PromptEngine;
+use crate::resilience::ResilientBackend;
 use crate::runner::AgentRunner;
 
 /// Application entry point.
@@ -49,17 +55,12 @@ use crate::runner::AgentRunner;
 ///
 /// Returns an error if initialization or the main event loop fails.
 #[tokio::main]
+#[allow(clippy::too_many_lines)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize structured logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_target(true)
-        .init();
+    // Initialize structured logging and, if configured, trace export
+    let telemetry = emergence_telemetry::init("emergence-runner")?;
 
-    info!("emergence-runner starting");
+    info!(otlp = telemetry.exporting(), "emergence-runner starting");
 
     // Load configuration from environment
     let config = RunnerConfig::from_env()?;
@@ -72,14 +73,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Connect to NATS
-    let nats = NatsClient::connect(&config.nats_url).await?;
+    let mut nats = NatsClient::connect(&config.nats_url).await?;
+    nats.serve_protocol_hello().await?;
+    if let Some(jetstream) = &config.jetstream {
+        nats = nats.with_jetstream(jetstream, config.partition_id).await?;
+        info!(
+            ack_wait_ms = jetstream.ack_wait.as_millis(),
+            max_deliver = jetstream.max_deliver,
+            max_age_secs = jetstream.max_age.as_secs(),
+            "JetStream durable delivery enabled"
+        );
+    } else {
+        info!("JetStream disabled, using core NATS subscriptions");
+    }
 
     // Load prompt templates
-    let prompt_engine = PromptEngine::new(&config.templates_dir)?;
+    let prompt_engine =
+        PromptEngine::with_variants(&config.templates_dir, &config.prompt_variants)?;
     info!(
         templates_dir = config.templates_dir,
         "prompt templates loaded"
     );
+    for variant in prompt_engine.variants() {
+        info!(
+            variant = variant.name(),
+            version = variant.version(),
+            "prompt variant available"
+        );
+    }
+
+    // Load complexity scoring rules
+    let complexity_rules = match &config.complexity_rules_path {
+        Some(path) => {
+            let rules = ComplexityRules::load(path)?;
+            info!(
+                path = path,
+                rules = rules.rules.len(),
+                "complexity rules loaded"
+            );
+            rules
+        }
+        None => ComplexityRules::default(),
+    };
 
     // Build the shared cost tracker from configured rates.
     let cost_tracker = {
@@ -101,53 +136,114 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )
             });
 
-        Arc::new(CostTracker::new(
-            primary_input,
-            primary_output,
-            esc_input,
-            esc_output,
-        ))
+        Arc::new(
+            CostTracker::new(primary_input, primary_output, esc_input, esc_output)
+                .with_limits(config.budget.clone()),
+        )
     };
 
     info!("cost tracker initialized");
+    if !config.budget.is_unlimited() {
+        info!(
+            max_tokens_per_tick = ?config.budget.max_tokens_per_tick,
+            max_cost_per_tick = ?config.budget.max_cost_per_tick,
+            max_tokens_per_day = ?config.budget.max_tokens_per_day,
+            max_cost_per_day = ?config.budget.max_cost_per_day,
+            critical_reserve = %config.budget.critical_reserve,
+            "LLM budget caps enabled"
+        );
+    }
 
     // Create LLM backends
-    let primary = create_backend(
-        &config.primary_backend,
-        &config.openrouter_config,
-        Some(Arc::clone(&cost_tracker)),
-        "primary",
+    let primary = ResilientBackend::new(
+        create_backend(
+            &config.primary_backend,
+            &config.openrouter_config,
+            Some(Arc::clone(&cost_tracker)),
+            "primary",
+        ),
+        &config.primary_backend.resilience,
     );
     info!(
         backend = primary.name(),
         model = config.primary_backend.model,
+        output_mode = ?config.primary_backend.output_mode,
+        resilience = ?config.primary_backend.resilience,
         "primary LLM backend configured"
     );
 
     let escalation = config.secondary_backend.as_ref().map(|cfg| {
-        let backend = create_backend(
-            cfg,
-            &config.openrouter_config,
-            Some(Arc::clone(&cost_tracker)),
-            "escalation",
+        let backend = ResilientBackend::new(
+            create_backend(
+                cfg,
+                &config.openrouter_config,
+                Some(Arc::clone(&cost_tracker)),
+                "escalation",
+            ),
+            &cfg.resilience,
         );
         info!(
             backend = backend.name(),
             model = cfg.model,
+            output_mode = ?cfg.output_mode,
+            resilience = ?cfg.resilience,
             "escalation LLM backend configured"
         );
         backend
     });
 
+    // Batched prompts need free-form JSON; a single-action response schema
+    // would force one decision per call.
+    let batch_size = if config.batch_size > 1
+        && config.primary_backend.output_mode == OutputMode::Structured
+    {
+        warn!(
+            batch_size = config.batch_size,
+            "batched inference requires text output mode on the default backend, disabling"
+        );
+        1
+    } else {
+        config.batch_size
+    };
+
+    // Reflection answers are not actions, so they need free-form JSON too.
+    let reflection_interval = if config.reflection_interval_ticks > 0
+        && config.primary_backend.output_mode == OutputMode::Structured
+    {
+        warn!(
+            reflection_interval_ticks = config.reflection_interval_ticks,
+            "reflection requires text output mode on the default backend, disabling"
+        );
+        0
+    } else {
+        config.reflection_interval_ticks
+    };
+
     // Build and run the agent runner
     info!(
         routine_action_bypass = config.routine_action_bypass,
         night_cycle_skip = config.night_cycle_skip,
         complexity_routing_enabled = config.complexity_routing_enabled,
+        parse_repair_reprompt = config.parse_repair_reprompt,
+        persona_memory_capacity = config.persona_memory_capacity,
+        batch_size = batch_size,
+        batch_window_ms = config.batch_window.as_millis(),
+        heuristic_agent_percent = config.heuristic_agent_percent,
+        reflection_interval_ticks = reflection_interval,
         "decision optimization configuration"
     );
 
-    let agent_runner = AgentRunner::new(
+    if let Some(interval) = config.metrics_interval {
+        spawn_metrics_publisher(
+            Arc::clone(&cost_tracker),
+            nats.clone(),
+            config.partition_id,
+            interval,
+        );
+        info!(interval_secs = interval.as_secs(), "runner metrics publishing enabled");
+    }
+
+    let mut agent_runner = AgentRunner::new(
         nats,
         prompt_engine,
         primary,
@@ -157,14 +253,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config.night_cycle_skip,
         config.complexity_routing_enabled,
     )
-    .with_partitioning(config.partition_id, config.total_partitions);
+    .with_partitioning(config.partition_id, config.total_partitions)
+    .with_parse_repair(config.parse_repair_reprompt)
+    .with_persona_memory(config.persona_memory_capacity)
+    .with_batching(batch_size, config.batch_window)
+    .with_complexity_rules(complexity_rules)
+    .with_heuristic_tier(config.heuristic_agent_percent)
+    .with_reflection(reflection_interval);
+    if !config.budget.is_unlimited() {
+        agent_runner = agent_runner.with_budget(Arc::clone(&cost_tracker));
+    }
 
     info!(
         partition_id = config.partition_id,
         total_partitions = config.total_partitions,
         "agent runner initialized, entering decision loop"
     );
-    agent_runner.run().await?;
+    let result = agent_runner.run().await;
+    telemetry.shutdown();
+    result?;
 
     Ok(())
 }
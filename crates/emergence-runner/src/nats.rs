@@ -3,19 +3,163 @@
 //! The World Engine publishes perception payloads on subjects matching
 //! `tick.{N}.perception.{agent_id}`. The runner subscribes to all perception
 //! subjects, processes each one through the LLM pipeline, and publishes
-//! the resulting action on `tick.{N}.action.{agent_id}`.
+//! the resulting action on `tick.{N}.action.{agent_id}`. Periodic
+//! reflection updates go to `tick.{N}.reflection.{agent_id}`.
+//!
+//! Decision records go to `emergence.decisions.{tick}.{agent_id}`, periodic
+//! cost metrics to `emergence.runner.metrics.{partition_id}`, and
+//! quarantined peer content to
+//! `emergence.containment.quarantine.{tick}.{agent_id}`.
+//!
+//! # Durable Delivery
+//!
+//! With [`NatsClient::with_jetstream`], perceptions are read through a
+//! durable pull consumer (`emergence-runner-{partition_id}`) on the
+//! [`PERCEPTION_STREAM`] stream instead of a core subscription. Every
+//! delivery must be acknowledged; a perception the runner took but never
+//! acknowledged -- because it crashed or restarted mid-tick -- is
+//! redelivered after the ack wait. Actions and reflections are published
+//! into the [`ACTION_STREAM`] stream and the runner waits for the server's
+//! publish acknowledgement, so a perception is only acknowledged once its
+//! action is stored. Publishes carry a message ID built from the tick and
+//! agent, so a redelivered perception that is decided twice stores one
+//! action.
+//!
+//! `JetStream` publishes still reach core subscribers, so the engine's
+//! subscriptions are unchanged. Decision records, metrics, and quarantine
+//! events stay on core NATS.
+//!
+//! # Protocol Version
+//!
+//! Perceptions, actions, reflections, and decision records carry a
+//! [`WireEnvelope`] in their headers (see [`emergence_types::wire`]). The
+//! runner answers [`HELLO_SUBJECT`] requests with its supported versions,
+//! remembers the version of the last perception it read, and writes
+//! everything it publishes in that version. Outgoing headers also carry
+//! the trace context of the current span.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Duration;
 
-use emergence_types::{ActionRequest, DecisionRecord, Perception};
+use async_nats::jetstream;
+use async_nats::jetstream::AckKind;
+use async_nats::jetstream::consumer::{AckPolicy, DeliverPolicy};
+use emergence_types::wire::{HELLO_SUBJECT, LEGACY_VERSION};
+use emergence_types::{
+    ActionRequest, DecisionRecord, PayloadType, Perception, ProtocolSupport, QuarantinedContent,
+    ReflectionUpdate, RunnerMetrics, WireEnvelope,
+};
+use futures::StreamExt;
 use tracing::{debug, info, warn};
 
+use crate::config::JetStreamConfig;
 use crate::error::RunnerError;
 
+/// `JetStream` stream holding perception deliveries.
+pub const PERCEPTION_STREAM: &str = "EMERGENCE_PERCEPTIONS";
+
+/// `JetStream` stream holding submitted actions and reflections.
+pub const ACTION_STREAM: &str = "EMERGENCE_ACTIONS";
+
+/// Subject filter for all perception deliveries.
+const PERCEPTION_SUBJECTS: &str = "tick.*.perception.*";
+
+/// How long the action stream remembers message IDs for deduplication.
+const DUPLICATE_WINDOW: Duration = Duration::from_mins(2);
+
+/// `JetStream` state attached to a [`NatsClient`].
+#[derive(Clone)]
+struct JetStreamLink {
+    context: jetstream::Context,
+    config: JetStreamConfig,
+    consumer_name: String,
+}
+
+/// A stream of perception deliveries.
+///
+/// Either a core subscription or a `JetStream` durable consumer, depending on
+/// how the [`NatsClient`] was set up.
+pub enum PerceptionFeed {
+    /// Core NATS subscription: at-most-once delivery.
+    Core(async_nats::Subscriber),
+    /// `JetStream` pull consumer: redelivered until acknowledged.
+    JetStream(Box<jetstream::consumer::pull::Stream>),
+}
+
+impl PerceptionFeed {
+    /// Wait for the next perception delivery.
+    ///
+    /// Returns `None` when the subscription or consumer ends. `JetStream`
+    /// delivery errors (e.g. a missed heartbeat) are logged and skipped.
+    pub async fn next(&mut self) -> Option<PerceptionMessage> {
+        match self {
+            Self::Core(subscriber) => subscriber.next().await.map(PerceptionMessage::Core),
+            Self::JetStream(messages) => loop {
+                match messages.next().await? {
+                    Ok(message) => return Some(PerceptionMessage::JetStream(message)),
+                    Err(e) => warn!(error = %e, "JetStream perception delivery error"),
+                }
+            },
+        }
+    }
+}
+
+/// A single perception delivery.
+///
+/// The acknowledgement methods are no-ops for core NATS messages.
+pub enum PerceptionMessage {
+    /// Delivered by a core subscription.
+    Core(async_nats::Message),
+    /// Delivered by a `JetStream` consumer; must be acknowledged.
+    JetStream(jetstream::Message),
+}
+
+impl PerceptionMessage {
+    /// The underlying NATS message.
+    pub const fn message(&self) -> &async_nats::Message {
+        match self {
+            Self::Core(message) => message,
+            Self::JetStream(message) => &message.message,
+        }
+    }
+
+    /// Acknowledge the delivery: the perception is handled and must not be
+    /// redelivered.
+    pub async fn ack(&self) {
+        self.acknowledge(AckKind::Ack).await;
+    }
+
+    /// Reject the delivery so the server redelivers it right away.
+    pub async fn nak(&self) {
+        self.acknowledge(AckKind::Nak(None)).await;
+    }
+
+    async fn acknowledge(&self, kind: AckKind) {
+        if let Self::JetStream(message) = self
+            && let Err(e) = message.ack_with(kind).await
+        {
+            warn!(
+                subject = %message.subject,
+                error = %e,
+                "failed to acknowledge perception"
+            );
+        }
+    }
+}
+
 /// NATS client wrapper for the agent runner.
 ///
 /// Manages a single NATS connection and provides methods for subscribing
 /// to perception deliveries and publishing action submissions.
+///
+/// Cloning is cheap and shares the underlying connection.
+#[derive(Clone)]
 pub struct NatsClient {
     client: async_nats::Client,
+    jetstream: Option<JetStreamLink>,
+    /// Protocol version of the last perception read from the engine.
+    engine_version: Arc<AtomicU16>,
 }
 
 impl NatsClient {
@@ -30,31 +174,154 @@ impl NatsClient {
             .await
             .map_err(|e| RunnerError::Nats(format!("failed to connect to {url}: {e}")))?;
         info!("NATS connection established");
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            jetstream: None,
+            engine_version: Arc::new(AtomicU16::new(LEGACY_VERSION)),
+        })
+    }
+
+    /// Answer protocol negotiation requests from the engine.
+    ///
+    /// Subscribes to [`HELLO_SUBJECT`] and replies to each request with
+    /// [`ProtocolSupport::local`] from a background task.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Nats`] if the subscription fails.
+    pub async fn serve_protocol_hello(&self) -> Result<(), RunnerError> {
+        let mut requests = self
+            .client
+            .subscribe(HELLO_SUBJECT.to_owned())
+            .await
+            .map_err(|e| {
+                RunnerError::Nats(format!("failed to subscribe to {HELLO_SUBJECT}: {e}"))
+            })?;
+        let payload = serde_json::to_vec(&ProtocolSupport::local())
+            .map_err(|e| RunnerError::Nats(format!("failed to serialize protocol support: {e}")))?;
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            while let Some(request) = requests.next().await {
+                let Some(reply) = request.reply else {
+                    continue;
+                };
+                if let Err(e) = client.publish(reply, payload.clone().into()).await {
+                    warn!(error = %e, "failed to answer protocol hello");
+                }
+            }
+        });
+        info!(subject = HELLO_SUBJECT, "answering protocol negotiation");
+        Ok(())
+    }
+
+    /// Switch perception intake and action submission to `JetStream`.
+    ///
+    /// Creates the [`PERCEPTION_STREAM`] and [`ACTION_STREAM`] streams if
+    /// they do not exist yet. The durable consumer itself is created (or
+    /// resumed) by [`Self::subscribe_perceptions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Nats`] if `JetStream` is unavailable on the
+    /// server or a stream cannot be created.
+    pub async fn with_jetstream(
+        mut self,
+        config: &JetStreamConfig,
+        partition_id: u32,
+    ) -> Result<Self, RunnerError> {
+        let context = jetstream::new(self.client.clone());
+        context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: PERCEPTION_STREAM.to_owned(),
+                subjects: vec![PERCEPTION_SUBJECTS.to_owned()],
+                max_age: config.max_age,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| {
+                RunnerError::Nats(format!("failed to create stream {PERCEPTION_STREAM}: {e}"))
+            })?;
+        context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: ACTION_STREAM.to_owned(),
+                subjects: vec!["tick.*.action.*".to_owned(), "tick.*.reflection.*".to_owned()],
+                max_age: config.max_age,
+                duplicate_window: DUPLICATE_WINDOW,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| {
+                RunnerError::Nats(format!("failed to create stream {ACTION_STREAM}: {e}"))
+            })?;
+        info!(
+            perception_stream = PERCEPTION_STREAM,
+            action_stream = ACTION_STREAM,
+            "JetStream streams ready"
+        );
+        self.jetstream = Some(JetStreamLink {
+            context,
+            config: config.clone(),
+            consumer_name: format!("emergence-runner-{partition_id}"),
+        });
+        Ok(self)
     }
 
     /// Subscribe to all perception subjects.
     ///
-    /// Returns a subscription that yields messages matching
-    /// `tick.*.perception.*` (all agents, all ticks).
+    /// Returns a feed that yields messages matching `tick.*.perception.*`
+    /// (all agents, all ticks). With `JetStream` enabled this binds the
+    /// durable consumer, picking up any deliveries left unacknowledged by a
+    /// previous run.
     ///
     /// # Errors
     ///
     /// Returns [`RunnerError::Nats`] if the subscription fails.
-    pub async fn subscribe_perceptions(
-        &self,
-    ) -> Result<async_nats::Subscriber, RunnerError> {
-        let subject = "tick.*.perception.*";
+    pub async fn subscribe_perceptions(&self) -> Result<PerceptionFeed, RunnerError> {
+        let subject = PERCEPTION_SUBJECTS;
         debug!(subject = subject, "subscribing to perception subjects");
-        let subscriber = self
-            .client
-            .subscribe(subject.to_owned())
+        let Some(link) = &self.jetstream else {
+            let subscriber = self
+                .client
+                .subscribe(subject.to_owned())
+                .await
+                .map_err(|e| {
+                    RunnerError::Nats(format!("failed to subscribe to {subject}: {e}"))
+                })?;
+            info!("subscribed to perception subjects");
+            return Ok(PerceptionFeed::Core(subscriber));
+        };
+
+        let stream = link.context.get_stream(PERCEPTION_STREAM).await.map_err(|e| {
+            RunnerError::Nats(format!("failed to open stream {PERCEPTION_STREAM}: {e}"))
+        })?;
+        let consumer = stream
+            .get_or_create_consumer(
+                &link.consumer_name,
+                jetstream::consumer::pull::Config {
+                    durable_name: Some(link.consumer_name.clone()),
+                    filter_subject: subject.to_owned(),
+                    deliver_policy: DeliverPolicy::New,
+                    ack_policy: AckPolicy::Explicit,
+                    ack_wait: link.config.ack_wait,
+                    max_deliver: link.config.max_deliver,
+                    ..Default::default()
+                },
+            )
             .await
             .map_err(|e| {
-                RunnerError::Nats(format!("failed to subscribe to {subject}: {e}"))
+                RunnerError::Nats(format!(
+                    "failed to create consumer {}: {e}",
+                    link.consumer_name
+                ))
             })?;
-        info!("subscribed to perception subjects");
-        Ok(subscriber)
+        let messages = consumer.messages().await.map_err(|e| {
+            RunnerError::Nats(format!("failed to consume {PERCEPTION_STREAM}: {e}"))
+        })?;
+        info!(
+            consumer = link.consumer_name,
+            "bound durable perception consumer"
+        );
+        Ok(PerceptionFeed::JetStream(Box::new(messages)))
     }
 
     /// Check whether a given agent ID belongs to this runner's partition.
@@ -109,10 +376,72 @@ impl NatsClient {
             action_type = ?action.action_type,
             "publishing action"
         );
-        self.client
-            .publish(subject.clone(), payload.into())
+        let message_id = format!("{tick}.action.{}", action.agent_id);
+        let headers = self.envelope_headers(PayloadType::Action);
+        self.publish_submission(subject, &message_id, headers, payload).await
+    }
+
+    /// Publish a reflection update for a specific agent and tick.
+    ///
+    /// The subject is `tick.{tick}.reflection.{agent_id}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Nats`] if serialization or publishing fails.
+    pub async fn publish_reflection(
+        &self,
+        tick: u64,
+        update: &ReflectionUpdate,
+    ) -> Result<(), RunnerError> {
+        let subject = format!("tick.{tick}.reflection.{}", update.agent_id);
+        let payload = serde_json::to_vec(update)
+            .map_err(|e| RunnerError::Nats(format!("failed to serialize reflection: {e}")))?;
+        debug!(
+            subject = subject,
+            agent_id = %update.agent_id,
+            goals = update.goals.len(),
+            "publishing reflection"
+        );
+        let message_id = format!("{tick}.reflection.{}", update.agent_id);
+        let headers = self.envelope_headers(PayloadType::Reflection);
+        self.publish_submission(subject, &message_id, headers, payload).await
+    }
+
+    /// Publish an action or reflection payload.
+    ///
+    /// With `JetStream` enabled, waits for the server to acknowledge that the
+    /// message is stored; `message_id` deduplicates repeat submissions.
+    async fn publish_submission(
+        &self,
+        subject: String,
+        message_id: &str,
+        headers: async_nats::HeaderMap,
+        payload: Vec<u8>,
+    ) -> Result<(), RunnerError> {
+        let Some(link) = &self.jetstream else {
+            return self
+                .client
+                .publish_with_headers(subject.clone(), headers, payload.into())
+                .await
+                .map_err(|e| {
+                    RunnerError::Nats(format!("failed to publish to {subject}: {e}"))
+                });
+        };
+        let publish = jetstream::context::Publish::build()
+            .headers(headers)
+            .payload(payload.into())
+            .message_id(message_id);
+        let ack = link
+            .context
+            .send_publish(subject.clone(), publish)
             .await
             .map_err(|e| RunnerError::Nats(format!("failed to publish to {subject}: {e}")))?;
+        let ack = ack.await.map_err(|e| {
+            RunnerError::Nats(format!("no JetStream ack for {subject}: {e}"))
+        })?;
+        if ack.duplicate {
+            debug!(subject = subject, "duplicate submission ignored by JetStream");
+        }
         Ok(())
     }
 
@@ -129,8 +458,12 @@ impl NatsClient {
         match serde_json::to_vec(record) {
             Ok(payload) => {
                 let client = self.client.clone();
+                let headers = self.envelope_headers(PayloadType::Decision);
                 tokio::spawn(async move {
-                    if let Err(e) = client.publish(subject.clone(), payload.into()).await {
+                    if let Err(e) = client
+                        .publish_with_headers(subject.clone(), headers, payload.into())
+                        .await
+                    {
                         warn!(
                             subject = subject,
                             error = %e,
@@ -149,6 +482,103 @@ impl NatsClient {
         }
     }
 
+    /// Publish a runner cost metrics snapshot (fire-and-forget).
+    ///
+    /// The subject is `emergence.runner.metrics.<partition_id>`. Failures
+    /// are logged but do not propagate.
+    pub fn publish_metrics(&self, metrics: &RunnerMetrics) {
+        let subject = format!("emergence.runner.metrics.{}", metrics.partition_id);
+        match serde_json::to_vec(metrics) {
+            Ok(payload) => {
+                let client = self.client.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = client.publish(subject.clone(), payload.into()).await {
+                        warn!(
+                            subject = subject,
+                            error = %e,
+                            "failed to publish runner metrics"
+                        );
+                    }
+                });
+            }
+            Err(e) => {
+                warn!(
+                    subject = subject,
+                    error = %e,
+                    "failed to serialize runner metrics"
+                );
+            }
+        }
+    }
+
+    /// Publish a quarantined-content event (fire-and-forget).
+    ///
+    /// The subject is `emergence.containment.quarantine.<tick>.<agent_id>`,
+    /// keyed by the agent whose prompt the content was withheld from.
+    /// Failures are logged but do not propagate.
+    pub fn publish_quarantine(&self, record: &QuarantinedContent) {
+        let subject = format!(
+            "emergence.containment.quarantine.{}.{}",
+            record.tick, record.recipient_id
+        );
+        match serde_json::to_vec(record) {
+            Ok(payload) => {
+                let client = self.client.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = client.publish(subject.clone(), payload.into()).await {
+                        warn!(
+                            subject = subject,
+                            error = %e,
+                            "failed to publish quarantine event"
+                        );
+                    }
+                });
+            }
+            Err(e) => {
+                warn!(
+                    subject = subject,
+                    error = %e,
+                    "failed to serialize quarantine event"
+                );
+            }
+        }
+    }
+
+    /// Read a perception message, checking its protocol envelope.
+    ///
+    /// Remembers the message's protocol version so replies are written in
+    /// the version the engine speaks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Parse`] if the envelope is not supported or
+    /// the payload does not deserialize.
+    pub fn read_perception(
+        &self,
+        message: &async_nats::Message,
+    ) -> Result<Perception, RunnerError> {
+        let envelope = WireEnvelope::read(PayloadType::Perception, |name| {
+            message.headers.as_ref()?.get(name).map(async_nats::HeaderValue::as_str)
+        })
+        .map_err(|e| RunnerError::Parse(format!("unsupported perception envelope: {e}")))?;
+        let perception = Self::deserialize_perception(&message.payload)?;
+        self.engine_version
+            .store(envelope.protocol_version, Ordering::Relaxed);
+        Ok(perception)
+    }
+
+    /// Headers for an outgoing message, in the engine's protocol version,
+    /// carrying the current span's trace context.
+    fn envelope_headers(&self, payload_type: PayloadType) -> async_nats::HeaderMap {
+        let version = self.engine_version.load(Ordering::Relaxed);
+        let mut headers = async_nats::HeaderMap::new();
+        for (name, value) in WireEnvelope::new(version, payload_type).headers() {
+            headers.insert(name, value);
+        }
+        emergence_telemetry::inject_headers(&mut headers);
+        headers
+    }
+
     /// Deserialize a NATS message payload into a [`Perception`].
     ///
     /// # Errors
@@ -293,12 +723,15 @@ mod tests {
                         structures_here: Vec::new(),
                         agents_here: Vec::new(),
                         messages_here: Vec::new(),
+                        overcrowded: false,
+                        pollution: 0,
                     },
                     known_routes: Vec::new(),
                     recent_memory: Vec::new(),
                     available_actions: Vec::new(),
                     notifications: Vec::new(),
                     personality: None,
+                    forecast: Vec::new(),
                 }
             })
         });
@@ -339,6 +772,36 @@ mod tests {
             });
     }
 
+    #[tokio::test]
+    #[ignore = "requires a NATS server running with JetStream"]
+    async fn jetstream_redelivers_unacked_perception() {
+        let config = JetStreamConfig {
+            ack_wait: Duration::from_secs(1),
+            ..JetStreamConfig::default()
+        };
+        let Ok(client) = NatsClient::connect("nats://localhost:4222").await else {
+            return;
+        };
+        let Ok(client) = client.with_jetstream(&config, 999).await else {
+            return;
+        };
+        let Ok(mut feed) = client.subscribe_perceptions().await else {
+            return;
+        };
+        let subject = format!("tick.1.perception.{}", emergence_types::AgentId::new());
+        let _ = client.client.publish(subject.clone(), "{}".into()).await;
+
+        // First delivery is left unacknowledged; it must come back.
+        let first = tokio::time::timeout(Duration::from_secs(5), feed.next()).await;
+        assert!(first.is_ok_and(|m| m.is_some_and(|m| m.message().subject.as_str() == subject)));
+        let again = tokio::time::timeout(Duration::from_secs(5), feed.next()).await;
+        let Ok(Some(again)) = again else {
+            return;
+        };
+        assert_eq!(again.message().subject.as_str(), subject);
+        again.ack().await;
+    }
+
     #[test]
     fn single_partition_accepts_all_agents() {
         let agent_id = emergence_types::AgentId::new();
@@ -2,7 +2,27 @@
 //!
 //! The LLM returns raw text (ideally JSON). This module extracts and
 //! validates the response into an [`ActionParameters`] from `emergence-types`.
-//! Malformed responses are handled gracefully by returning `NoAction`.
+//!
+//! Recovery happens in layers:
+//! 1. Extraction: direct JSON, markdown code blocks, the first balanced
+//!    object embedded in surrounding prose, trailing-comma cleanup.
+//! 2. Mechanical repair: action type and enum values are canonicalized
+//!    (`"food_berry"` -> `"FoodBerry"`) against the schema in
+//!    [`crate::schema`], then parameters are validated against it.
+//! 3. Re-prompt: the runner sends the failure and the raw text back to the
+//!    cheap backend with [`repair_prompt`] and parses the answer once more.
+//!
+//! Only when every layer fails is the response replaced with `NoAction`.
+//!
+//! # Reasoning
+//!
+//! Models often explain themselves outside the action JSON: in thinking
+//! blocks (`<think>...</think>`), or in prose before and after the object.
+//! [`split_reasoning`] separates that text from the action before
+//! extraction, so braces in a thinking block cannot be mistaken for the
+//! action, and keeps it on [`ParsedDecision::explanation`] next to the
+//! object's own `reasoning` field. Together they end up on the
+//! `DecisionRecord` for auditing.
 
 use std::collections::BTreeMap;
 
@@ -10,6 +30,8 @@ use emergence_types::{ActionParameters, ActionType, AgentId, KnownRoute};
 use tracing::warn;
 
 use crate::error::RunnerError;
+use crate::prompt::RenderedPrompt;
+use crate::schema::{canonical_action_type, repair_parameters, validate_parameters};
 
 /// The parsed decision from an LLM response.
 #[derive(Debug, Clone)]
@@ -18,8 +40,11 @@ pub struct ParsedDecision {
     pub action_type: ActionType,
     /// The typed action parameters.
     pub parameters: ActionParameters,
-    /// The agent's reasoning (logged for debugging, not used by the engine).
+    /// The `reasoning` field of the action object, if present.
     pub reasoning: Option<String>,
+    /// Free text the model wrote outside the action object: thinking
+    /// blocks and surrounding prose.
+    pub explanation: Option<String>,
     /// Goal updates the agent wants to make.
     ///
     /// Will be used by the reflection system in Phase 3 to update
@@ -28,6 +53,23 @@ pub struct ParsedDecision {
     pub goal_updates: Vec<String>,
 }
 
+impl ParsedDecision {
+    /// Everything the model said about its choice: the explanation, then
+    /// the `reasoning` field. `None` when it said neither.
+    pub fn full_reasoning(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.explanation.as_deref(), self.reasoning.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+}
+
+/// Tags whose contents are treated as the model's thinking.
+const THINKING_TAGS: [&str; 3] = ["think", "thinking", "reasoning"];
+
 /// Intermediate struct for deserializing the LLM's raw JSON response.
 ///
 /// The LLM produces a flat JSON object with `action_type` and `parameters`
@@ -59,12 +101,15 @@ struct RawLlmResponse {
 /// When `agent_name_map` is provided, actions with `target_agent` fields
 /// can resolve agent names to UUIDs (fallback for LLMs that send names
 /// like "Iris" instead of agent UUIDs).
+///
+/// Text outside the action object is kept as the decision's explanation
+/// (see [`split_reasoning`]).
 pub fn parse_llm_response(
     raw: &str,
     known_routes: &[KnownRoute],
     agent_name_map: &BTreeMap<String, AgentId>,
 ) -> ParsedDecision {
-    match try_parse(raw, known_routes, agent_name_map) {
+    match try_parse_llm_response(raw, known_routes, agent_name_map) {
         Ok(decision) => decision,
         Err(e) => {
             warn!(
@@ -77,13 +122,34 @@ pub fn parse_llm_response(
     }
 }
 
-/// Attempt to parse the response through multiple recovery strategies.
-fn try_parse(
+/// Parse an LLM response without the `NoAction` fallback.
+///
+/// Runs the same extraction and repair strategies as [`parse_llm_response`]
+/// but returns the failure so the caller can attempt a re-prompt.
+///
+/// # Errors
+///
+/// Returns [`RunnerError::Parse`] describing why no strategy produced a
+/// valid action.
+pub fn try_parse_llm_response(
     raw: &str,
     known_routes: &[KnownRoute],
     agent_name_map: &BTreeMap<String, AgentId>,
 ) -> Result<ParsedDecision, RunnerError> {
-    let trimmed = raw.trim();
+    let (body, explanation) = split_reasoning(raw);
+    let mut decision = parse_action_text(&body, known_routes, agent_name_map)?;
+    decision.explanation = explanation;
+    Ok(decision)
+}
+
+/// Run the extraction strategies over response text with thinking
+/// blocks already removed.
+fn parse_action_text(
+    text: &str,
+    known_routes: &[KnownRoute],
+    agent_name_map: &BTreeMap<String, AgentId>,
+) -> Result<ParsedDecision, RunnerError> {
+    let trimmed = text.trim();
 
     // Strategy 1: direct parse
     if let Ok(parsed) = serde_json::from_str::<RawLlmResponse>(trimmed) {
@@ -111,11 +177,110 @@ fn try_parse(
         }
     }
 
+    // Strategy 5: first balanced object embedded in prose, then strip commas
+    if let Some(json_str) = extract_first_json_object(trimmed) {
+        if let Ok(parsed) = serde_json::from_str::<RawLlmResponse>(json_str) {
+            return convert_raw_response(parsed, known_routes, agent_name_map);
+        }
+        let cleaned_inner = strip_trailing_commas(json_str);
+        if let Ok(parsed) = serde_json::from_str::<RawLlmResponse>(&cleaned_inner) {
+            return convert_raw_response(parsed, known_routes, agent_name_map);
+        }
+    }
+
     Err(RunnerError::Parse(format!(
         "all parse strategies failed for: {trimmed}"
     )))
 }
 
+/// Extract an arbitrary JSON value from raw LLM text.
+///
+/// Runs the same extraction strategies as [`try_parse_llm_response`]
+/// (direct, code block, trailing-comma cleanup, first embedded object)
+/// without requiring the action shape, after dropping thinking blocks.
+/// Used for batched responses.
+pub fn extract_json_value(raw: &str) -> Option<serde_json::Value> {
+    let (body, _) = split_reasoning(raw);
+    let trimmed = body.trim();
+    let mut candidates: Vec<&str> = vec![trimmed];
+    candidates.extend(extract_json_from_codeblock(trimmed));
+    candidates.extend(extract_first_json_object(trimmed));
+    candidates.into_iter().find_map(|text| {
+        serde_json::from_str(text)
+            .ok()
+            .or_else(|| serde_json::from_str(&strip_trailing_commas(text)).ok())
+    })
+}
+
+/// Separate the model's reasoning from the text holding the action.
+///
+/// Returns the response with thinking blocks removed, and the reasoning:
+/// the contents of any `<think>`, `<thinking>`, or `<reasoning>` blocks
+/// followed by prose before and after the JSON (code fences excluded).
+/// Prose is only taken when a JSON object can be located; an unclosed
+/// thinking tag is left in place.
+pub fn split_reasoning(raw: &str) -> (String, Option<String>) {
+    let mut body = raw.to_owned();
+    let mut parts: Vec<String> = Vec::new();
+
+    for tag in THINKING_TAGS {
+        let open = format!("<{tag}>");
+        let close = format!("</{tag}>");
+        while let Some(start) = find_ignore_case(&body, &open) {
+            let Some(inner_start) = start.checked_add(open.len()) else {
+                break;
+            };
+            let Some(inner_len) = body
+                .get(inner_start..)
+                .and_then(|rest| find_ignore_case(rest, &close))
+            else {
+                break;
+            };
+            let Some(inner_end) = inner_start.checked_add(inner_len) else {
+                break;
+            };
+            let Some(end) = inner_end.checked_add(close.len()) else {
+                break;
+            };
+            if let Some(inner) = body.get(inner_start..inner_end) {
+                parts.push(inner.trim().to_owned());
+            }
+            body.replace_range(start..end, "");
+        }
+    }
+
+    let trimmed = body.trim();
+    if let Some((before, after)) = json_surroundings(trimmed) {
+        parts.push(before.trim().to_owned());
+        parts.push(after.trim().to_owned());
+    }
+
+    parts.retain(|part| !part.is_empty());
+    let reasoning = (!parts.is_empty()).then(|| parts.join("\n\n"));
+    (body, reasoning)
+}
+
+/// The text before and after the action JSON in `text`: the first code
+/// block (fences included) if there is one, else the first balanced
+/// object.
+fn json_surroundings(text: &str) -> Option<(&str, &str)> {
+    if let Some(fence) = text.find("```") {
+        let after_open = fence.checked_add(3)?;
+        let close = text.get(after_open..)?.find("```")?;
+        let end = after_open.checked_add(close)?.checked_add(3)?;
+        return Some((text.get(..fence)?, text.get(end..)?));
+    }
+    let object = extract_first_json_object(text)?;
+    let start = text.find(object)?;
+    let end = start.checked_add(object.len())?;
+    Some((text.get(..start)?, text.get(end..)?))
+}
+
+/// ASCII case-insensitive `find`.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(&needle.to_ascii_lowercase())
+}
+
 /// Convert a deserialized raw response into a typed decision.
 fn convert_raw_response(
     raw: RawLlmResponse,
@@ -123,12 +288,20 @@ fn convert_raw_response(
     agent_name_map: &BTreeMap<String, AgentId>,
 ) -> Result<ParsedDecision, RunnerError> {
     let action_type = parse_action_type(&raw.action_type)?;
-    let parameters = build_parameters(action_type, &raw.parameters, known_routes, agent_name_map)?;
+    let repaired = repair_parameters(action_type, &raw.parameters);
+    validate_parameters(action_type, &repaired).map_err(|violations| {
+        RunnerError::Parse(format!(
+            "invalid parameters for {action_type:?}: {}",
+            violations.join("; ")
+        ))
+    })?;
+    let parameters = build_parameters(action_type, &repaired, known_routes, agent_name_map)?;
 
     Ok(ParsedDecision {
         action_type,
         parameters,
         reasoning: raw.reasoning,
+        explanation: None,
         goal_updates: raw.goal_update.unwrap_or_default(),
     })
 }
@@ -150,28 +323,49 @@ fn parse_action_type(s: &str) -> Result<ActionType, RunnerError> {
         "move" => Ok(ActionType::Move),
         "build" => Ok(ActionType::Build),
         "repair" => Ok(ActionType::Repair),
+        "extinguish" | "douse" | "put_out" => Ok(ActionType::Extinguish),
         "demolish" => Ok(ActionType::Demolish),
+        "upgradestructure" | "upgrade_structure" | "upgrade" => {
+            Ok(ActionType::UpgradeStructure)
+        }
         "improveroute" | "improve_route" => Ok(ActionType::ImproveRoute),
+        "buildroute" | "build_route" => Ok(ActionType::BuildRoute),
         "communicate" => Ok(ActionType::Communicate),
         "broadcast" => Ok(ActionType::Broadcast),
         "tradeoffer" | "trade_offer" => Ok(ActionType::TradeOffer),
         "tradeaccept" | "trade_accept" => Ok(ActionType::TradeAccept),
         "tradereject" | "trade_reject" => Ok(ActionType::TradeReject),
+        "postorder" | "post_order" | "order" => Ok(ActionType::PostOrder),
         "formgroup" | "form_group" => Ok(ActionType::FormGroup),
         "teach" => Ok(ActionType::Teach),
         "farmplant" | "farm_plant" => Ok(ActionType::FarmPlant),
         "farmharvest" | "farm_harvest" => Ok(ActionType::FarmHarvest),
+        "fertilize" | "fertilise" | "compost" => Ok(ActionType::Fertilize),
         "craft" => Ok(ActionType::Craft),
         "mine" => Ok(ActionType::Mine),
+        "deepenmine" | "deepen_mine" | "deepen" => Ok(ActionType::DeepenMine),
+        "hunt" => Ok(ActionType::Hunt),
+        "slaughter" | "butcher" => Ok(ActionType::Slaughter),
+        "prospect" => Ok(ActionType::Prospect),
+        "fish" => Ok(ActionType::Fish),
         "smelt" => Ok(ActionType::Smelt),
         "write" => Ok(ActionType::Write),
         "read" => Ok(ActionType::Read),
+        "chart" => Ok(ActionType::Chart),
+        "deposit" => Ok(ActionType::Deposit),
+        "withdraw" => Ok(ActionType::Withdraw),
         "claim" => Ok(ActionType::Claim),
+        "transferownership" | "transfer_ownership" | "transfer" => {
+            Ok(ActionType::TransferOwnership)
+        }
+        "foundsettlement" | "found_settlement" | "settle" => Ok(ActionType::FoundSettlement),
         "legislate" => Ok(ActionType::Legislate),
         "enforce" => Ok(ActionType::Enforce),
         "reproduce" => Ok(ActionType::Reproduce),
+        "breach" | "besiege" => Ok(ActionType::Breach),
         "noaction" | "no_action" | "none" => Ok(ActionType::NoAction),
-        other => Err(RunnerError::Parse(format!("unknown action type: {other}"))),
+        other => canonical_action_type(other)
+            .ok_or_else(|| RunnerError::Parse(format!("unknown action type: {other}"))),
     }
 }
 
@@ -270,6 +464,7 @@ fn build_parameters(
                 .to_owned();
             Ok(ActionParameters::Broadcast { message })
         }
+        ActionType::Chart => Ok(ActionParameters::Chart),
         ActionType::NoAction => Ok(ActionParameters::NoAction),
         // For all other action types, attempt a direct serde deserialize
         // of the parameters into the matching ActionParameters variant.
@@ -387,6 +582,61 @@ fn extract_json_from_codeblock(text: &str) -> Option<&str> {
     remaining.get(..end).map(str::trim)
 }
 
+/// Extract the first balanced `{ ... }` object from text with surrounding prose.
+///
+/// Tracks string literals and escapes so braces inside strings do not end
+/// the object early. Returns `None` if no complete object is found.
+fn extract_first_json_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let body = text.get(start..)?;
+
+    let mut depth: u32 = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in body.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth = depth.saturating_add(1),
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    let end = offset.checked_add(c.len_utf8())?;
+                    return body.get(..end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Build the cheap "fix this JSON" re-prompt for a response that failed to parse.
+///
+/// The prompt carries the parse error and the original text, and asks for
+/// the corrected action object only. It has no persona so it is identical
+/// in shape for every agent.
+pub fn repair_prompt(raw: &str, error: &RunnerError) -> RenderedPrompt {
+    RenderedPrompt {
+        system: "You repair malformed JSON. Reply with exactly one JSON object and no other text. \
+                 The object must have the keys \"action_type\" (PascalCase action name), \
+                 \"parameters\" (object), and optionally \"reasoning\" and \"goal_update\". \
+                 Keep the original intent; fix only structure, field names, and value spelling."
+            .to_owned(),
+        persona: String::new(),
+        user: format!("Parse error: {error}\n\nOriginal response:\n{raw}"),
+    }
+}
+
 /// Strip trailing commas before closing braces and brackets (common LLM error).
 fn strip_trailing_commas(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
@@ -422,6 +672,7 @@ fn no_action_decision() -> ParsedDecision {
         action_type: ActionType::NoAction,
         parameters: ActionParameters::NoAction,
         reasoning: Some("Failed to parse LLM response".to_owned()),
+        explanation: None,
         goal_updates: Vec::new(),
     }
 }
@@ -510,6 +761,120 @@ I chose to drink because I am thirsty."#;
         assert_eq!(decision.goal_updates.len(), 2);
     }
 
+    #[test]
+    fn parse_object_embedded_in_prose() {
+        let raw = r#"Sure! {"action_type": "Rest", "parameters": {}, "reasoning": "tired {very}"} Hope that helps."#;
+        let decision = parse_llm_response(raw, &[], &no_names());
+        assert_eq!(decision.action_type, ActionType::Rest);
+        assert_eq!(decision.reasoning.as_deref(), Some("tired {very}"));
+    }
+
+    #[test]
+    fn prose_around_object_becomes_explanation() {
+        let raw = r#"Sure! {"action_type": "Rest", "parameters": {}, "reasoning": "tired"} Hope that helps."#;
+        let decision = parse_llm_response(raw, &[], &no_names());
+        assert_eq!(decision.explanation.as_deref(), Some("Sure!\n\nHope that helps."));
+        assert_eq!(
+            decision.full_reasoning().as_deref(),
+            Some("Sure!\n\nHope that helps.\n\ntired")
+        );
+    }
+
+    #[test]
+    fn thinking_blocks_are_split_from_the_action() {
+        let raw = r#"<think>The river {north} is closer than the lake.</think>
+```json
+{"action_type": "Drink", "parameters": {}}
+```
+Staying hydrated."#;
+        let decision = parse_llm_response(raw, &[], &no_names());
+        assert_eq!(decision.action_type, ActionType::Drink);
+        assert_eq!(
+            decision.explanation.as_deref(),
+            Some("The river {north} is closer than the lake.\n\nStaying hydrated.")
+        );
+        assert!(decision.reasoning.is_none());
+    }
+
+    #[test]
+    fn split_reasoning_handles_case_and_unclosed_tags() {
+        let (body, reasoning) = split_reasoning("<THINKING>hmm</THINKING>{\"a\": 1}");
+        assert_eq!(body, "{\"a\": 1}");
+        assert_eq!(reasoning.as_deref(), Some("hmm"));
+
+        let (body, reasoning) = split_reasoning("<think>still going {\"a\": 1}");
+        assert_eq!(body, "<think>still going {\"a\": 1}");
+        assert_eq!(reasoning.as_deref(), Some("<think>still going"));
+
+        let (_, reasoning) = split_reasoning(r#"{"action_type": "Rest"}"#);
+        assert!(reasoning.is_none());
+    }
+
+    #[test]
+    fn bare_json_has_no_full_reasoning() {
+        let decision = parse_llm_response(r#"{"action_type": "Rest"}"#, &[], &no_names());
+        assert!(decision.full_reasoning().is_none());
+    }
+
+    #[test]
+    fn parse_repairs_enum_casing() {
+        let raw = r#"{"action_type": "gather", "parameters": {"resource": "food_berry"}}"#;
+        let decision = parse_llm_response(raw, &[], &no_names());
+        assert!(matches!(
+            decision.parameters,
+            ActionParameters::Gather { resource: emergence_types::Resource::FoodBerry }
+        ));
+
+        let raw = r#"{"action_type": "build", "parameters": {"structure_type": "lean-to"}}"#;
+        let decision = parse_llm_response(raw, &[], &no_names());
+        assert!(matches!(
+            decision.parameters,
+            ActionParameters::Build { structure_type: emergence_types::StructureType::LeanTo }
+        ));
+    }
+
+    #[test]
+    fn parse_repairs_loose_action_type() {
+        let target_id = AgentId::new();
+        let raw = format!(
+            r#"{{"action_type": "STEAL", "parameters": {{"target_agent": "{target_id}", "resource": "wood"}}}}"#
+        );
+        let decision = parse_llm_response(&raw, &[], &no_names());
+        assert_eq!(decision.action_type, ActionType::Steal);
+    }
+
+    #[test]
+    fn try_parse_reports_schema_violations() {
+        let raw = r#"{"action_type": "Gather", "parameters": {}}"#;
+        let result = try_parse_llm_response(raw, &[], &no_names());
+        let message = result.err().map(|e| e.to_string()).unwrap_or_default();
+        assert!(message.contains("missing required field 'resource'"), "got: {message}");
+    }
+
+    #[test]
+    fn extract_json_value_handles_prose_and_trailing_commas() {
+        let raw = "Here you go: {\"decisions\": [{\"agent\": \"A1\",},]} done";
+        let value = extract_json_value(raw).unwrap_or_default();
+        assert!(value.get("decisions").is_some_and(serde_json::Value::is_array));
+        assert!(extract_json_value("no json here").is_none());
+    }
+
+    #[test]
+    fn repair_prompt_carries_error_and_raw() {
+        let raw = "I will gather wood";
+        let error = RunnerError::Parse("all parse strategies failed".to_owned());
+        let prompt = repair_prompt(raw, &error);
+        assert!(prompt.persona.is_empty());
+        assert!(prompt.user.contains("all parse strategies failed"));
+        assert!(prompt.user.contains(raw));
+    }
+
+    #[test]
+    fn extract_first_json_object_none_when_unbalanced() {
+        assert_eq!(extract_first_json_object("prefix {\"a\": 1"), None);
+        assert_eq!(extract_first_json_object("no json"), None);
+    }
+
     #[test]
     fn extract_json_from_markdown() {
         let text = "```json\n{\"key\": \"value\"}\n```";
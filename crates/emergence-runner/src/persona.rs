@@ -0,0 +1,352 @@
+//! Per-agent persona store for prompt continuity.
+//!
+//! The engine only ships a short window of recent memories with each
+//! perception, and the LLM backends are stateless. Without help, an agent
+//! reacts to every tick as if it had just woken up. The [`PersonaStore`]
+//! keeps a small amount of state per agent across ticks:
+//!
+//! - **Personality** -- the last personality the engine sent, so prompts
+//!   keep the agent's nature even if a payload omits it.
+//! - **Long-term goals** -- the most recent non-empty goal list, either
+//!   from the engine's `active_goals` or from the agent's own
+//!   `goal_update` in a previous decision.
+//! - **Memories** -- the compressed memory summaries received from the
+//!   engine, retained past the perception window in a bounded rolling log.
+//!   Consecutive repeats are folded into one line with a count.
+//!
+//! [`AgentPersona::merge_into`] merges this state into the perception JSON under
+//! a `persona` key before prompt rendering. The store lives in the runner
+//! process only; a restarted runner rebuilds it from incoming perceptions.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+use emergence_types::{AgentId, Perception, Personality};
+use serde::Serialize;
+
+/// Default number of memory lines retained per agent.
+pub const DEFAULT_MEMORY_CAPACITY: usize = 20;
+
+/// Persistent state for a single agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AgentPersona {
+    /// The agent's personality, if the engine has ever sent one.
+    pub personality: Option<Personality>,
+    /// The agent's most recent non-empty goal list.
+    pub long_term_goals: Vec<String>,
+    /// Retained memory summaries, oldest first.
+    pub memories: Vec<String>,
+}
+
+impl AgentPersona {
+    /// Merge this persona into a serialized perception.
+    ///
+    /// Inserts a `persona` object for the templates and back-fills a
+    /// missing top-level `personality` from the store. Memories already in
+    /// the perception's own window are left out to avoid repeating them.
+    pub fn merge_into(&self, perception: &Perception, perception_json: &mut serde_json::Value) {
+        let Some(obj) = perception_json.as_object_mut() else {
+            return;
+        };
+        if obj.get("personality").is_none_or(serde_json::Value::is_null)
+            && let Some(personality) = &self.personality
+            && let Ok(value) = serde_json::to_value(personality)
+        {
+            obj.insert("personality".to_owned(), value);
+        }
+        let older: Vec<&String> = self
+            .memories
+            .iter()
+            .filter(|m| !perception.recent_memory.contains(m))
+            .collect();
+        obj.insert(
+            "persona".to_owned(),
+            serde_json::json!({
+                "long_term_goals": self.long_term_goals,
+                "memories": older,
+            }),
+        );
+    }
+}
+
+/// A retained memory line and how many consecutive times it was seen.
+#[derive(Debug, Clone)]
+struct MemoryLine {
+    summary: String,
+    repeats: u32,
+}
+
+impl MemoryLine {
+    fn render(&self) -> String {
+        if self.repeats > 1 {
+            format!("{} (x{})", self.summary, self.repeats)
+        } else {
+            self.summary.clone()
+        }
+    }
+}
+
+/// Internal per-agent record.
+#[derive(Debug, Clone, Default)]
+struct PersonaRecord {
+    personality: Option<Personality>,
+    long_term_goals: Vec<String>,
+    memories: VecDeque<MemoryLine>,
+    /// The memory window from the previous perception, used to detect
+    /// which entries are new this tick.
+    last_window: Vec<String>,
+}
+
+/// Thread-safe store of [`AgentPersona`] state keyed by agent.
+#[derive(Debug)]
+pub struct PersonaStore {
+    records: Mutex<BTreeMap<AgentId, PersonaRecord>>,
+    memory_capacity: usize,
+}
+
+impl PersonaStore {
+    /// Create an empty store retaining up to `memory_capacity` memory lines
+    /// per agent.
+    pub const fn new(memory_capacity: usize) -> Self {
+        Self {
+            records: Mutex::new(BTreeMap::new()),
+            memory_capacity,
+        }
+    }
+
+    /// Fold a perception into the agent's persona and return the merged
+    /// state.
+    pub fn observe(&self, perception: &Perception) -> AgentPersona {
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let record = records.entry(perception.self_state.id).or_default();
+
+        if let Some(personality) = &perception.personality {
+            record.personality = Some(personality.clone());
+        }
+        if !perception.self_state.active_goals.is_empty() {
+            record
+                .long_term_goals
+                .clone_from(&perception.self_state.active_goals);
+        }
+
+        // The engine sends the newest memories first. Anything not in the
+        // previous window is new this tick; append oldest-first.
+        let window = &perception.recent_memory;
+        for summary in window.iter().rev() {
+            if !record.last_window.contains(summary) {
+                push_memory(&mut record.memories, summary, self.memory_capacity);
+            }
+        }
+        record.last_window.clone_from(window);
+
+        snapshot(record)
+    }
+
+    /// The agent's current persona, without folding in a new perception.
+    ///
+    /// Unknown agents get an empty persona.
+    pub fn get(&self, agent_id: AgentId) -> AgentPersona {
+        let records = self
+            .records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        records.get(&agent_id).map(snapshot).unwrap_or_default()
+    }
+
+    /// Record goals the agent set for itself in a decision.
+    ///
+    /// Empty lists are ignored so a decision without a `goal_update` does
+    /// not erase the agent's standing goals.
+    pub fn record_goals(&self, agent_id: AgentId, goals: &[String]) {
+        if goals.is_empty() {
+            return;
+        }
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        records.entry(agent_id).or_default().long_term_goals = goals.to_vec();
+    }
+}
+
+impl Default for PersonaStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_CAPACITY)
+    }
+}
+
+/// Append a memory, folding it into the previous line if identical and
+/// evicting the oldest line past capacity.
+fn push_memory(memories: &mut VecDeque<MemoryLine>, summary: &str, capacity: usize) {
+    if capacity == 0 {
+        return;
+    }
+    if let Some(last) = memories.back_mut()
+        && last.summary == summary
+    {
+        last.repeats = last.repeats.saturating_add(1);
+        return;
+    }
+    memories.push_back(MemoryLine {
+        summary: summary.to_owned(),
+        repeats: 1,
+    });
+    while memories.len() > capacity {
+        memories.pop_front();
+    }
+}
+
+fn snapshot(record: &PersonaRecord) -> AgentPersona {
+    AgentPersona {
+        personality: record.personality.clone(),
+        long_term_goals: record.long_term_goals.clone(),
+        memories: record.memories.iter().map(MemoryLine::render).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{
+        Perception, Personality, Season, SelfState, Sex, Surroundings, TimeOfDay, Weather,
+    };
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn make_perception(id: AgentId, memories: &[&str], goals: &[&str]) -> Perception {
+        Perception {
+            tick: 1,
+            time_of_day: TimeOfDay::Morning,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            self_state: SelfState {
+                id,
+                name: "Ada".to_owned(),
+                sex: Sex::Female,
+                age: 10,
+                energy: 80,
+                health: 100,
+                hunger: 10,
+                thirst: 10,
+                location_name: "Meadow".to_owned(),
+                inventory: BTreeMap::new(),
+                carry_load: "0/50".to_owned(),
+                active_goals: goals.iter().map(|g| (*g).to_owned()).collect(),
+                known_skills: Vec::new(),
+            },
+            surroundings: Surroundings {
+                location_description: String::new(),
+                visible_resources: BTreeMap::new(),
+                structures_here: Vec::new(),
+                agents_here: Vec::new(),
+                messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
+            },
+            known_routes: Vec::new(),
+            recent_memory: memories.iter().map(|m| (*m).to_owned()).collect(),
+            available_actions: Vec::new(),
+            notifications: Vec::new(),
+            personality: None,
+            forecast: Vec::new(),
+        }
+    }
+
+    fn make_personality() -> Personality {
+        let half = Decimal::new(5, 1);
+        Personality {
+            curiosity: half,
+            cooperation: half,
+            aggression: half,
+            risk_tolerance: half,
+            industriousness: half,
+            sociability: half,
+            honesty: half,
+            loyalty: half,
+        }
+    }
+
+    #[test]
+    fn memories_outlive_the_perception_window() {
+        let store = PersonaStore::new(10);
+        let id = AgentId::new();
+        store.observe(&make_perception(id, &["found berries"], &[]));
+        store.observe(&make_perception(id, &["met Bo", "found berries"], &[]));
+        let persona = store.observe(&make_perception(id, &["rained"], &[]));
+        assert_eq!(persona.memories, vec!["found berries", "met Bo", "rained"]);
+    }
+
+    #[test]
+    fn repeated_memories_are_folded() {
+        let store = PersonaStore::new(10);
+        let id = AgentId::new();
+        store.observe(&make_perception(id, &["gathered wood"], &[]));
+        store.observe(&make_perception(id, &["slept"], &[]));
+        store.observe(&make_perception(id, &["slept"], &[]));
+        let persona = store.observe(&make_perception(id, &["gathered wood"], &[]));
+        assert_eq!(persona.memories, vec!["gathered wood", "slept", "gathered wood"]);
+
+        let mut lines = VecDeque::new();
+        push_memory(&mut lines, "slept", 10);
+        push_memory(&mut lines, "slept", 10);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines.front().map(MemoryLine::render).as_deref(), Some("slept (x2)"));
+    }
+
+    #[test]
+    fn memory_capacity_evicts_oldest() {
+        let store = PersonaStore::new(2);
+        let id = AgentId::new();
+        store.observe(&make_perception(id, &["a"], &[]));
+        store.observe(&make_perception(id, &["b"], &[]));
+        let persona = store.observe(&make_perception(id, &["c"], &[]));
+        assert_eq!(persona.memories, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn goals_persist_until_replaced() {
+        let store = PersonaStore::default();
+        let id = AgentId::new();
+        store.observe(&make_perception(id, &[], &["build a shelter"]));
+        let persona = store.observe(&make_perception(id, &[], &[]));
+        assert_eq!(persona.long_term_goals, vec!["build a shelter"]);
+
+        store.record_goals(id, &["find a mate".to_owned()]);
+        store.record_goals(id, &[]);
+        assert_eq!(store.get(id).long_term_goals, vec!["find a mate"]);
+        let persona = store.observe(&make_perception(id, &[], &[]));
+        assert_eq!(persona.long_term_goals, vec!["find a mate"]);
+        assert_eq!(store.get(AgentId::new()), AgentPersona::default());
+    }
+
+    #[test]
+    fn apply_backfills_personality_and_adds_persona() {
+        let store = PersonaStore::default();
+        let id = AgentId::new();
+        let mut first = make_perception(id, &["found berries"], &[]);
+        first.personality = Some(make_personality());
+        store.observe(&first);
+
+        let second = make_perception(id, &["met Bo"], &["stay fed"]);
+        let mut json = serde_json::to_value(&second).unwrap_or_default();
+        store.observe(&second).merge_into(&second, &mut json);
+
+        assert!(json.get("personality").is_some_and(|p| !p.is_null()));
+        let persona = json.get("persona").cloned().unwrap_or_default();
+        assert_eq!(persona.get("memories"), Some(&serde_json::json!(["found berries"])));
+        assert_eq!(persona.get("long_term_goals"), Some(&serde_json::json!(["stay fed"])));
+    }
+
+    #[test]
+    fn zero_capacity_disables_memory_retention() {
+        let store = PersonaStore::new(0);
+        let id = AgentId::new();
+        let persona = store.observe(&make_perception(id, &["a"], &[]));
+        assert!(persona.memories.is_empty());
+    }
+}
@@ -4,17 +4,58 @@
 //! so operators can tune agent behavior without recompiling. The template
 //! engine renders perception data into a structured LLM prompt following
 //! `agent-system.md` section 6.2.
+//!
+//! The rendered prompt is split into a stable prefix and a per-tick suffix.
+//! The prefix (system rules plus the agent's persona) is byte-identical from
+//! tick to tick for a given agent, so providers can serve it from their
+//! prompt cache: Anthropic via explicit `cache_control` breakpoints, `OpenAI`
+//! and `DeepSeek` via automatic prefix caching, and local servers such as
+//! Ollama via KV-cache reuse. Everything that changes each tick (vitals,
+//! perception, memory, available actions) lives in the suffix.
+//!
+//! # Variants
+//!
+//! For prompt-design experiments the engine can hold several template sets
+//! at once. The top-level directory is the `control` variant; each extra
+//! variant lives in `variants/<name>/` and overrides only the files it
+//! contains, falling back to the control templates for the rest. Agents are
+//! assigned to a variant deterministically from their ID, so an agent keeps
+//! the same prompt for its whole life.
+//!
+//! Every variant carries a version tag: the trimmed contents of a `VERSION`
+//! file in its directory, or else a short hash of its template sources. The
+//! variant name and version are recorded on each LLM `DecisionRecord`.
 
+use std::collections::BTreeMap;
+
+use emergence_types::AgentId;
 use minijinja::Environment;
 
 use crate::error::RunnerError;
 
+/// Name of the variant built from the top-level templates directory.
+pub const CONTROL_VARIANT: &str = "control";
+
+/// Templates every variant must provide (after fallback to the control set).
+const REQUIRED_TEMPLATES: [&str; 5] = ["system", "identity", "perception", "memory", "actions"];
+
+/// Templates that may be omitted entirely.
+const OPTIONAL_TEMPLATES: [&str; 1] = ["persona"];
+
 /// Manages prompt template loading and rendering.
 ///
-/// Wraps a `minijinja` [`Environment`] with all agent prompt templates
-/// pre-loaded. Templates can be edited on disk and will be picked up on
-/// the next call to [`PromptEngine::new`].
+/// Wraps one `minijinja` [`Environment`] per [`PromptVariant`] with all
+/// agent prompt templates pre-loaded. Templates can be edited on disk and
+/// will be picked up on the next call to [`PromptEngine::new`].
 pub struct PromptEngine {
+    control: PromptVariant,
+    variants: Vec<PromptVariant>,
+}
+
+/// A named, versioned set of prompt templates.
+pub struct PromptVariant {
+    name: String,
+    version: String,
     env: Environment<'static>,
 }
 
@@ -23,43 +64,155 @@ pub struct PromptEngine {
 pub struct RenderedPrompt {
     /// System message establishing the agent's reality.
     pub system: String,
-    /// User message containing identity, perception, memory, and actions.
+    /// Stable per-agent persona block (name, sex, personality).
+    ///
+    /// Empty when the templates directory has no `persona.j2`.
+    pub persona: String,
+    /// User message containing per-tick state, perception, memory, and actions.
     pub user: String,
 }
 
+impl RenderedPrompt {
+    /// The cacheable prefix: system rules followed by the persona block.
+    ///
+    /// Backends send this as the system message so its bytes stay stable
+    /// across ticks for the same agent.
+    pub fn stable_prefix(&self) -> String {
+        if self.persona.is_empty() {
+            self.system.clone()
+        } else {
+            format!("{}\n\n{}", self.system, self.persona)
+        }
+    }
+}
+
 impl PromptEngine {
     /// Create a new prompt engine loading templates from the given directory.
     ///
     /// The directory must contain: `system.j2`, `identity.j2`,
-    /// `perception.j2`, `memory.j2`, `actions.j2`.
+    /// `perception.j2`, `memory.j2`, `actions.j2`. An optional `persona.j2`
+    /// holds the stable part of the agent's identity and is rendered into
+    /// the cacheable prefix.
+    #[allow(dead_code)]
     pub fn new(templates_dir: &str) -> Result<Self, RunnerError> {
-        let mut env = Environment::new();
+        Self::with_variants(templates_dir, &[])
+    }
+
+    /// Create a prompt engine with the control templates plus the named
+    /// experimental variants from `{templates_dir}/variants/<name>/`.
+    ///
+    /// Returns an error if a variant directory is missing, a name is
+    /// repeated, or a variant is named `control`.
+    pub fn with_variants(templates_dir: &str, variants: &[String]) -> Result<Self, RunnerError> {
+        let base = load_sources(templates_dir, &BTreeMap::new(), true)?;
+        let control = PromptVariant::build(CONTROL_VARIANT, templates_dir, base.clone())?;
 
-        let system_tpl = load_template(templates_dir, "system.j2")?;
-        let identity_tpl = load_template(templates_dir, "identity.j2")?;
-        let perception_tpl = load_template(templates_dir, "perception.j2")?;
-        let memory_tpl = load_template(templates_dir, "memory.j2")?;
-        let actions_tpl = load_template(templates_dir, "actions.j2")?;
+        let mut loaded: Vec<PromptVariant> = Vec::with_capacity(variants.len());
+        for name in variants {
+            if name == CONTROL_VARIANT || loaded.iter().any(|v| v.name == *name) {
+                return Err(RunnerError::Template(format!(
+                    "duplicate prompt variant name: {name}"
+                )));
+            }
+            let dir = format!("{templates_dir}/variants/{name}");
+            if !std::path::Path::new(&dir).is_dir() {
+                return Err(RunnerError::Template(format!(
+                    "prompt variant directory not found: {dir}"
+                )));
+            }
+            let sources = load_sources(&dir, &base, false)?;
+            loaded.push(PromptVariant::build(name, &dir, sources)?);
+        }
 
-        env.add_template_owned("system", system_tpl)
-            .map_err(|e| RunnerError::Template(format!("failed to add system template: {e}")))?;
-        env.add_template_owned("identity", identity_tpl)
-            .map_err(|e| RunnerError::Template(format!("failed to add identity template: {e}")))?;
-        env.add_template_owned("perception", perception_tpl).map_err(|e| {
-            RunnerError::Template(format!("failed to add perception template: {e}"))
-        })?;
-        env.add_template_owned("memory", memory_tpl)
-            .map_err(|e| RunnerError::Template(format!("failed to add memory template: {e}")))?;
-        env.add_template_owned("actions", actions_tpl)
-            .map_err(|e| RunnerError::Template(format!("failed to add actions template: {e}")))?;
+        Ok(Self {
+            control,
+            variants: loaded,
+        })
+    }
+
+    /// All loaded variants, control first.
+    pub fn variants(&self) -> impl Iterator<Item = &PromptVariant> {
+        std::iter::once(&self.control).chain(self.variants.iter())
+    }
 
-        Ok(Self { env })
+    /// The variant assigned to an agent.
+    ///
+    /// Assignment hashes bytes 12..16 of the agent's UUID -- the random
+    /// tail of a v7 ID -- so it is stable across ticks and restarts and
+    /// evenly spread even for agents spawned in the same millisecond.
+    pub fn variant_for(&self, agent_id: &AgentId) -> &PromptVariant {
+        if self.variants.is_empty() {
+            return &self.control;
+        }
+        let uuid = agent_id.into_inner();
+        let bytes = uuid.as_bytes();
+        let hash = u32::from_be_bytes([
+            *bytes.get(12).unwrap_or(&0),
+            *bytes.get(13).unwrap_or(&0),
+            *bytes.get(14).unwrap_or(&0),
+            *bytes.get(15).unwrap_or(&0),
+        ]);
+        let arms = u32::try_from(self.variants.len())
+            .unwrap_or(u32::MAX)
+            .saturating_add(1);
+        let slot = usize::try_from(hash.checked_rem(arms).unwrap_or(0)).unwrap_or(0);
+        slot.checked_sub(1)
+            .and_then(|i| self.variants.get(i))
+            .unwrap_or(&self.control)
     }
 
-    /// Render the full prompt for an agent's decision.
+    /// Render the full prompt for an agent's decision using the control
+    /// variant.
     ///
     /// Takes the perception data serialized as a `serde_json::Value` and
-    /// produces a [`RenderedPrompt`] with system and user messages.
+    /// produces a [`RenderedPrompt`] with the stable system/persona prefix
+    /// and the per-tick user message.
+    #[allow(dead_code)]
+    pub fn render(
+        &self,
+        perception: &serde_json::Value,
+    ) -> Result<RenderedPrompt, RunnerError> {
+        self.control.render(perception)
+    }
+}
+
+impl PromptVariant {
+    /// Compile a variant from its template sources.
+    fn build(
+        name: &str,
+        dir: &str,
+        sources: BTreeMap<&'static str, String>,
+    ) -> Result<Self, RunnerError> {
+        let version = match load_optional_template(dir, "VERSION")? {
+            Some(tag) if !tag.trim().is_empty() => tag.trim().to_owned(),
+            _ => content_hash(&sources),
+        };
+
+        let mut env = Environment::new();
+        for (template, source) in sources {
+            env.add_template_owned(template, source).map_err(|e| {
+                RunnerError::Template(format!("failed to add {template} template: {e}"))
+            })?;
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            version,
+            env,
+        })
+    }
+
+    /// The variant name (`control` for the top-level templates).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The variant's version tag.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Render the full prompt for an agent's decision with this variant.
     pub fn render(
         &self,
         perception: &serde_json::Value,
@@ -71,6 +224,15 @@ impl PromptEngine {
             .render(perception)
             .map_err(|e| RunnerError::Template(format!("system render failed: {e}")))?;
 
+        let persona = match self.env.get_template("persona") {
+            Ok(tpl) => tpl
+                .render(perception)
+                .map_err(|e| RunnerError::Template(format!("persona render failed: {e}")))?
+                .trim()
+                .to_owned(),
+            Err(_) => String::new(),
+        };
+
         let identity = self
             .env
             .get_template("identity")
@@ -101,8 +263,51 @@ impl PromptEngine {
 
         let user = format!("{identity}\n\n{perception_text}\n\n{memory}\n\n{actions}");
 
-        Ok(RenderedPrompt { system, user })
+        Ok(RenderedPrompt {
+            system,
+            persona,
+            user,
+        })
+    }
+}
+
+/// Load template sources from `dir`, falling back to `base` for files the
+/// directory does not contain.
+///
+/// With `require` set, every required template must exist in `dir` itself.
+fn load_sources(
+    dir: &str,
+    base: &BTreeMap<&'static str, String>,
+    require: bool,
+) -> Result<BTreeMap<&'static str, String>, RunnerError> {
+    let mut sources = base.clone();
+    for name in REQUIRED_TEMPLATES {
+        let filename = format!("{name}.j2");
+        if require {
+            sources.insert(name, load_template(dir, &filename)?);
+        } else if let Some(source) = load_optional_template(dir, &filename)? {
+            sources.insert(name, source);
+        }
+    }
+    for name in OPTIONAL_TEMPLATES {
+        if let Some(source) = load_optional_template(dir, &format!("{name}.j2"))? {
+            sources.insert(name, source);
+        }
+    }
+    Ok(sources)
+}
+
+/// Short, stable hash of a variant's template sources (32-bit FNV-1a).
+fn content_hash(sources: &BTreeMap<&'static str, String>) -> String {
+    const FNV_OFFSET: u32 = 0x811c_9dc5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+    let mut hash = FNV_OFFSET;
+    for (name, source) in sources {
+        for byte in name.bytes().chain([0]).chain(source.bytes()).chain([0]) {
+            hash = (hash ^ u32::from(byte)).wrapping_mul(FNV_PRIME);
+        }
     }
+    format!("{hash:08x}")
 }
 
 /// Read a template file from disk.
@@ -112,6 +317,16 @@ fn load_template(dir: &str, filename: &str) -> Result<String, RunnerError> {
         .map_err(|e| RunnerError::Template(format!("failed to read {path}: {e}")))
 }
 
+/// Read a template file from disk, returning `None` if it does not exist.
+fn load_optional_template(dir: &str, filename: &str) -> Result<Option<String>, RunnerError> {
+    let path = format!("{dir}/{filename}");
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(RunnerError::Template(format!("failed to read {path}: {e}"))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +439,55 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn persona_renders_into_stable_prefix() {
+        let unique = format!(
+            "emergence_persona_templates_{}_{:?}",
+            std::process::id(),
+            std::thread::current().id(),
+        );
+        let dir = std::env::temp_dir().join(unique);
+        std::fs::create_dir_all(&dir).ok();
+        write_test_templates(&dir);
+        std::fs::write(dir.join("persona.j2"), "## Who You Are\nName: {{ self_state.name }}").ok();
+
+        let Ok(engine) = PromptEngine::new(dir.to_str().unwrap_or("")) else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+
+        let tick_one = serde_json::json!({"tick": 1, "self_state": {"name": "Luna", "age": 1}});
+        let tick_two = serde_json::json!({"tick": 2, "self_state": {"name": "Luna", "age": 2}});
+        let first = engine.render(&tick_one);
+        let second = engine.render(&tick_two);
+        assert!(first.is_ok() && second.is_ok());
+        let (Ok(first), Ok(second)) = (first, second) else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+
+        assert_eq!(first.persona, "## Who You Are\nName: Luna");
+        assert!(first.stable_prefix().ends_with(&first.persona));
+        assert_eq!(
+            first.stable_prefix(),
+            second.stable_prefix(),
+            "prefix should not change between ticks"
+        );
+        assert_ne!(first.user, second.user, "suffix should carry per-tick data");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_persona_leaves_prefix_as_system() {
+        let prompt = RenderedPrompt {
+            system: "rules".to_owned(),
+            persona: String::new(),
+            user: "tick".to_owned(),
+        };
+        assert_eq!(prompt.stable_prefix(), "rules");
+    }
+
     #[test]
     fn missing_template_returns_error() {
         let unique = format!(
@@ -241,4 +505,114 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    fn variant_test_dir(label: &str) -> std::path::PathBuf {
+        let unique = format!(
+            "emergence_{label}_templates_{}_{:?}",
+            std::process::id(),
+            std::thread::current().id(),
+        );
+        let dir = std::env::temp_dir().join(unique);
+        std::fs::create_dir_all(dir.join("variants/terse")).ok();
+        write_test_templates(&dir);
+        std::fs::write(
+            dir.join("variants/terse/actions.j2"),
+            "Pick one: {% for a in available_actions %}{{ a }} {% endfor %}",
+        )
+        .ok();
+        dir
+    }
+
+    #[test]
+    fn variant_overrides_and_falls_back_to_control() {
+        let dir = variant_test_dir("variant_fallback");
+        let Ok(engine) =
+            PromptEngine::with_variants(dir.to_str().unwrap_or(""), &["terse".to_owned()])
+        else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+
+        let perception = serde_json::json!({
+            "tick": 3,
+            "self_state": {"name": "Luna", "age": 1},
+            "recent_memory": [],
+            "available_actions": ["gather", "rest"]
+        });
+        let names: Vec<&str> = engine.variants().map(PromptVariant::name).collect();
+        assert_eq!(names, vec![CONTROL_VARIANT, "terse"]);
+
+        let terse = engine.variants().find(|v| v.name() == "terse");
+        let rendered = terse.map(|v| v.render(&perception));
+        let Some(Ok(prompt)) = rendered else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+        assert!(prompt.user.contains("Pick one: gather rest"));
+        assert!(prompt.user.contains("Tick: 3"), "perception falls back to control");
+        assert!(prompt.system.contains("Luna"), "system falls back to control");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn variant_version_uses_version_file_or_hash() {
+        let dir = variant_test_dir("variant_version");
+        std::fs::write(dir.join("variants/terse/VERSION"), "terse-v2\n").ok();
+        let Ok(engine) =
+            PromptEngine::with_variants(dir.to_str().unwrap_or(""), &["terse".to_owned()])
+        else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+
+        let versions: Vec<&str> = engine.variants().map(PromptVariant::version).collect();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions.get(1).copied(), Some("terse-v2"));
+        let control = versions.first().copied().unwrap_or_default();
+        assert_eq!(control.len(), 8, "control version should be a short hash");
+
+        // Editing a template changes the hashed version.
+        std::fs::write(dir.join("system.j2"), "Changed rules.").ok();
+        let edited = PromptEngine::new(dir.to_str().unwrap_or(""));
+        assert!(edited.is_ok_and(|e| e.variants().all(|v| v.version() != control)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn variant_assignment_is_deterministic_and_spread() {
+        let dir = variant_test_dir("variant_assign");
+        let Ok(engine) =
+            PromptEngine::with_variants(dir.to_str().unwrap_or(""), &["terse".to_owned()])
+        else {
+            std::fs::remove_dir_all(&dir).ok();
+            return;
+        };
+
+        let mut terse_count = 0_u32;
+        for _ in 0..200 {
+            let id = AgentId::new();
+            let first = engine.variant_for(&id).name();
+            assert_eq!(first, engine.variant_for(&id).name());
+            if first == "terse" {
+                terse_count = terse_count.saturating_add(1);
+            }
+        }
+        assert!(terse_count > 50 && terse_count < 150, "got {terse_count}/200");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn unknown_or_duplicate_variant_is_rejected() {
+        let dir = variant_test_dir("variant_reject");
+        let path = dir.to_str().unwrap_or("");
+        assert!(PromptEngine::with_variants(path, &["missing".to_owned()]).is_err());
+        assert!(PromptEngine::with_variants(path, &["control".to_owned()]).is_err());
+        assert!(
+            PromptEngine::with_variants(path, &["terse".to_owned(), "terse".to_owned()]).is_err()
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,305 @@
+//! Reflection-phase LLM calls.
+//!
+//! Action prompts ask an agent what to do *now*; they leave little room to
+//! step back. Every `interval` ticks each LLM agent is also asked to
+//! reflect: revise its goals and condense its recent memories into a short
+//! summary. The answer is sent to the engine as a [`ReflectionUpdate`] on
+//! `tick.{N}.reflection.{agent_id}` -- not as an action -- and the engine
+//! applies it during the Reflection phase of the tick cycle.
+//!
+//! Agents are spread across the interval by ID, so only about
+//! `1 / interval` of the population reflects on any given tick.
+//!
+//! The model answers with one JSON object:
+//!
+//! ```json
+//! {"goals": ["build a shelter", "stay fed"], "memory_summary": "..."}
+//! ```
+
+use chrono::Utc;
+use emergence_types::{AgentId, Perception, ReflectionUpdate};
+
+use crate::parse::extract_json_value;
+use crate::persona::AgentPersona;
+use crate::prompt::RenderedPrompt;
+
+/// Maximum number of goals kept from a reflection.
+pub const MAX_REFLECTION_GOALS: usize = 5;
+
+/// Maximum length of a single goal, in characters.
+const MAX_GOAL_LEN: usize = 200;
+
+/// Maximum length of the memory summary, in characters.
+const MAX_SUMMARY_LEN: usize = 600;
+
+/// Whether the agent is due to reflect on this tick.
+///
+/// Each agent reflects once every `interval` ticks, offset by a hash of
+/// its ID (bytes 4..8 of the UUID). An `interval` of `0` disables
+/// reflection.
+pub fn reflection_due(agent_id: AgentId, tick: u64, interval: u64) -> bool {
+    if interval == 0 {
+        return false;
+    }
+    let uuid = agent_id.into_inner();
+    let bytes = uuid.as_bytes();
+    let hash = u32::from_be_bytes([
+        *bytes.get(4).unwrap_or(&0),
+        *bytes.get(5).unwrap_or(&0),
+        *bytes.get(6).unwrap_or(&0),
+        *bytes.get(7).unwrap_or(&0),
+    ]);
+    let offset = u64::from(hash).checked_rem(interval).unwrap_or(0);
+    tick.checked_rem(interval) == Some(offset)
+}
+
+/// Build the reflection prompt for an agent.
+///
+/// Uses the persona's retained memories when there are any, and the
+/// perception's own memory window otherwise. Returns `None` when the agent
+/// has nothing to reflect on yet.
+pub fn reflection_prompt(
+    perception: &Perception,
+    persona: &AgentPersona,
+) -> Option<RenderedPrompt> {
+    let memories: Vec<&String> = if persona.memories.is_empty() {
+        perception.recent_memory.iter().rev().collect()
+    } else {
+        persona.memories.iter().collect()
+    };
+    if memories.is_empty() {
+        return None;
+    }
+
+    let me = &perception.self_state;
+    let goals = if persona.long_term_goals.is_empty() {
+        &me.active_goals
+    } else {
+        &persona.long_term_goals
+    };
+
+    let system = format!(
+        "You are {name}, an agent in a simulated world, pausing to reflect. Nothing you \
+         write here is an action; it only shapes what you will pursue and remember.\n\n\
+         Reply with exactly one JSON object and no other text:\n\
+         {{\"goals\": [\"...\"], \"memory_summary\": \"...\"}}\n\n\
+         - goals: up to {max_goals} short goals in priority order. Keep goals that still \
+         matter, drop ones that are done or hopeless, add new ones your experience \
+         suggests.\n\
+         - memory_summary: two or three sentences, in the first person, capturing what \
+         matters most from your recent memories.",
+        name = me.name,
+        max_goals = MAX_REFLECTION_GOALS,
+    );
+
+    let goal_lines = if goals.is_empty() {
+        "- (none)".to_owned()
+    } else {
+        goals
+            .iter()
+            .map(|g| format!("- {g}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let memory_lines = memories
+        .iter()
+        .map(|m| format!("- {m}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let user = format!(
+        "Tick {tick}. You are at {location}. Health {health}, energy {energy}, hunger \
+         {hunger}, thirst {thirst}.\n\n\
+         Current goals:\n{goal_lines}\n\n\
+         Recent memories (oldest first):\n{memory_lines}",
+        tick = perception.tick,
+        location = me.location_name,
+        health = me.health,
+        energy = me.energy,
+        hunger = me.hunger,
+        thirst = me.thirst,
+    );
+
+    Some(RenderedPrompt {
+        system,
+        persona: String::new(),
+        user,
+    })
+}
+
+/// Parse a reflection response into an update for the engine.
+///
+/// Accepts `goals` (or `goal_update`) as a list of strings and
+/// `memory_summary` (or `summary`) as a string. Blank entries are dropped
+/// and long ones truncated. Returns `None` if the response has no JSON
+/// object or neither field carries anything.
+pub fn parse_reflection(raw: &str, agent_id: AgentId, tick: u64) -> Option<ReflectionUpdate> {
+    let value = extract_json_value(raw)?;
+    let obj = value.as_object()?;
+
+    let goals: Vec<String> = obj
+        .get("goals")
+        .or_else(|| obj.get("goal_update"))
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|g| !g.is_empty())
+                .take(MAX_REFLECTION_GOALS)
+                .map(|g| truncate_chars(g, MAX_GOAL_LEN))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let memory_summary = obj
+        .get("memory_summary")
+        .or_else(|| obj.get("summary"))
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| truncate_chars(s, MAX_SUMMARY_LEN));
+
+    if goals.is_empty() && memory_summary.is_none() {
+        return None;
+    }
+    Some(ReflectionUpdate {
+        agent_id,
+        tick,
+        goals,
+        memory_summary,
+        submitted_at: Utc::now(),
+    })
+}
+
+/// Truncate to at most `max` characters.
+fn truncate_chars(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{Season, SelfState, Sex, Surroundings, TimeOfDay, Weather};
+
+    use super::*;
+
+    fn make_perception(memories: &[&str]) -> Perception {
+        Perception {
+            tick: 12,
+            time_of_day: TimeOfDay::Dusk,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            self_state: SelfState {
+                id: AgentId::new(),
+                name: "Ada".to_owned(),
+                sex: Sex::Female,
+                age: 12,
+                energy: 60,
+                health: 90,
+                hunger: 20,
+                thirst: 15,
+                location_name: "Meadow".to_owned(),
+                inventory: BTreeMap::new(),
+                carry_load: "0/50".to_owned(),
+                active_goals: vec!["find water".to_owned()],
+                known_skills: Vec::new(),
+            },
+            surroundings: Surroundings {
+                location_description: String::new(),
+                visible_resources: BTreeMap::new(),
+                structures_here: Vec::new(),
+                agents_here: Vec::new(),
+                messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
+            },
+            known_routes: Vec::new(),
+            recent_memory: memories.iter().map(|m| (*m).to_owned()).collect(),
+            available_actions: Vec::new(),
+            notifications: Vec::new(),
+            personality: None,
+            forecast: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn each_agent_reflects_once_per_interval() {
+        let agent = AgentId::new();
+        let due = (0..20_u64).filter(|&tick| reflection_due(agent, tick, 5)).count();
+        assert_eq!(due, 4);
+        assert!((0..20_u64).all(|tick| !reflection_due(agent, tick, 0)));
+        assert!((0..3_u64).all(|tick| reflection_due(agent, tick, 1)));
+    }
+
+    #[test]
+    fn prompt_lists_goals_and_memories_oldest_first() {
+        let perception = make_perception(&["met Bo", "found berries"]);
+        let (system, user) = reflection_prompt(&perception, &AgentPersona::default())
+            .map(|p| (p.system, p.user))
+            .unwrap_or_default();
+        assert!(system.contains("You are Ada"));
+        assert!(system.contains("\"memory_summary\""));
+        assert!(user.contains("- find water"));
+        assert!(user.contains("- found berries\n- met Bo"));
+    }
+
+    #[test]
+    fn prompt_prefers_persona_state() {
+        let perception = make_perception(&["met Bo"]);
+        let persona = AgentPersona {
+            personality: None,
+            long_term_goals: vec!["build a shelter".to_owned()],
+            memories: vec!["crossed the river".to_owned(), "met Bo".to_owned()],
+        };
+        let user = reflection_prompt(&perception, &persona)
+            .map(|p| p.user)
+            .unwrap_or_default();
+        assert!(user.contains("- build a shelter"));
+        assert!(!user.contains("find water"));
+        assert!(user.contains("- crossed the river\n- met Bo"));
+    }
+
+    #[test]
+    fn nothing_to_reflect_on_yields_no_prompt() {
+        let perception = make_perception(&[]);
+        assert!(reflection_prompt(&perception, &AgentPersona::default()).is_none());
+    }
+
+    #[test]
+    fn parse_reads_goals_and_summary() {
+        let agent = AgentId::new();
+        let raw = r#"Here you go:
+```json
+{"goals": ["build a shelter", "  ", "stay fed",], "memory_summary": " I found berries and met Bo. "}
+```"#;
+        let update = parse_reflection(raw, agent, 12);
+        assert_eq!(update.as_ref().map(|u| u.agent_id), Some(agent));
+        assert_eq!(update.as_ref().map(|u| u.tick), Some(12));
+        assert_eq!(
+            update.as_ref().map(|u| u.goals.clone()).unwrap_or_default(),
+            vec!["build a shelter".to_owned(), "stay fed".to_owned()]
+        );
+        assert_eq!(
+            update.and_then(|u| u.memory_summary).as_deref(),
+            Some("I found berries and met Bo.")
+        );
+    }
+
+    #[test]
+    fn parse_accepts_aliases_and_caps_goals() {
+        let agent = AgentId::new();
+        let raw = r#"{"goal_update": ["a", "b", "c", "d", "e", "f", "g"], "summary": ""}"#;
+        let update = parse_reflection(raw, agent, 3);
+        assert_eq!(update.as_ref().map(|u| u.goals.len()), Some(MAX_REFLECTION_GOALS));
+        assert!(update.and_then(|u| u.memory_summary).is_none());
+    }
+
+    #[test]
+    fn parse_rejects_empty_or_garbage() {
+        let agent = AgentId::new();
+        assert!(parse_reflection("I would rather not.", agent, 1).is_none());
+        assert!(parse_reflection(r#"{"goals": [], "memory_summary": "  "}"#, agent, 1).is_none());
+    }
+}
@@ -0,0 +1,396 @@
+//! Retry policy and circuit breaker for LLM backends.
+//!
+//! Each configured backend is wrapped in a [`ResilientBackend`] that owns
+//! its own [`RetryPolicy`] and [`CircuitBreaker`]. Failures are sorted into
+//! [`FailureClass`]es: transient classes (timeouts, rate limits, server
+//! errors, transport failures) are retried with exponential backoff, while
+//! client errors fail fast.
+//!
+//! When a backend keeps failing its breaker trips and subsequent calls are
+//! rejected immediately with [`RunnerError::CircuitOpen`] until the cooldown
+//! elapses. The runner then routes around the tripped backend -- to the
+//! escalation backend, or to the heuristic policy if both are down -- instead
+//! of stalling every agent until the decision deadline.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use crate::config::ResilienceConfig;
+use crate::error::RunnerError;
+use crate::llm::LlmBackend;
+use crate::prompt::RenderedPrompt;
+
+// ---------------------------------------------------------------------------
+// Failure classification
+// ---------------------------------------------------------------------------
+
+/// Category of an LLM call failure, used to decide whether to retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    /// The per-request timeout elapsed.
+    Timeout,
+    /// The provider returned HTTP 429.
+    RateLimited,
+    /// The provider returned a 5xx (or 408) status.
+    ServerError,
+    /// The provider rejected the request (4xx other than 408/429).
+    ClientError,
+    /// Connection, TLS, or response-decoding failure.
+    Transport,
+    /// The backend's circuit breaker is open; no request was sent.
+    CircuitOpen,
+}
+
+impl FailureClass {
+    /// Classify a backend error.
+    pub const fn of(error: &RunnerError) -> Self {
+        match error {
+            RunnerError::Timeout => Self::Timeout,
+            RunnerError::LlmStatus { status, .. } => match *status {
+                429 => Self::RateLimited,
+                408 | 500..=599 => Self::ServerError,
+                _ => Self::ClientError,
+            },
+            RunnerError::CircuitOpen(_) => Self::CircuitOpen,
+            _ => Self::Transport,
+        }
+    }
+
+    /// Whether another attempt might succeed.
+    pub const fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::RateLimited | Self::ServerError | Self::Transport
+        )
+    }
+
+    /// Whether the failure counts against the circuit breaker.
+    ///
+    /// Client errors indicate a bad request rather than an unhealthy
+    /// backend, so they do not trip the breaker.
+    pub const fn counts_against_breaker(self) -> bool {
+        self.is_retryable()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Retry policy
+// ---------------------------------------------------------------------------
+
+/// Exponential backoff retry policy.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 disables retrying).
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on any single delay.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-indexed): `initial * 2^retry`,
+    /// capped at `max_backoff`.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = 2_u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Circuit breaker
+// ---------------------------------------------------------------------------
+
+/// Observable state of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls flow normally.
+    Closed,
+    /// Calls are rejected until the cooldown elapses.
+    Open,
+    /// The cooldown elapsed; a single trial call is allowed through.
+    HalfOpen,
+}
+
+/// Mutable breaker state held inside the mutex.
+#[derive(Debug)]
+struct BreakerInner {
+    /// Consecutive failures since the last success.
+    consecutive_failures: u32,
+    /// When the breaker tripped, if it is open or half-open.
+    opened_at: Option<Instant>,
+    /// Whether the half-open trial call is currently in flight.
+    trial_in_flight: bool,
+}
+
+/// Consecutive-failure circuit breaker.
+///
+/// Trips after `failure_threshold` consecutive failures. While open, calls
+/// are rejected. After `cooldown`, one trial call is allowed; success closes
+/// the breaker, failure re-opens it for another cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker.
+    pub const fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            inner: Mutex::new(BreakerInner {
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_in_flight: false,
+            }),
+        }
+    }
+
+    /// Current state, evaluated against `now`.
+    pub fn state_at(&self, now: Instant) -> BreakerState {
+        let Ok(inner) = self.inner.lock() else {
+            return BreakerState::Closed;
+        };
+        Self::state_of(&inner, self.cooldown, now)
+    }
+
+    /// Decide whether a call may proceed at `now`.
+    ///
+    /// In the half-open state only the first caller is admitted; others are
+    /// rejected until that trial resolves.
+    pub fn try_acquire_at(&self, now: Instant) -> bool {
+        // A poisoned mutex must not block decisions; fail open.
+        let Ok(mut inner) = self.inner.lock() else {
+            return true;
+        };
+        match Self::state_of(&inner, self.cooldown, now) {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => {
+                if inner.trial_in_flight {
+                    false
+                } else {
+                    inner.trial_in_flight = true;
+                    true
+                }
+            }
+        }
+    }
+
+    /// Record a successful call, closing the breaker.
+    pub fn record_success(&self) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.trial_in_flight = false;
+    }
+
+    /// Record a failed call at `now`. Returns `true` if this failure tripped
+    /// (or re-tripped) the breaker.
+    pub fn record_failure_at(&self, now: Instant) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let was_trial = inner.trial_in_flight;
+        inner.trial_in_flight = false;
+        if was_trial || inner.consecutive_failures >= self.failure_threshold {
+            inner.opened_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    /// Derive the state from the inner fields.
+    fn state_of(inner: &BreakerInner, cooldown: Duration, now: Instant) -> BreakerState {
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened) if now.saturating_duration_since(opened) < cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Resilient backend wrapper
+// ---------------------------------------------------------------------------
+
+/// An [`LlmBackend`] guarded by a retry policy and a circuit breaker.
+pub struct ResilientBackend {
+    backend: LlmBackend,
+    retry: RetryPolicy,
+    breaker: CircuitBreaker,
+}
+
+impl ResilientBackend {
+    /// Wrap a backend using the given resilience configuration.
+    pub const fn new(backend: LlmBackend, config: &ResilienceConfig) -> Self {
+        Self {
+            backend,
+            retry: RetryPolicy {
+                max_retries: config.max_retries,
+                initial_backoff: config.initial_backoff,
+                max_backoff: config.max_backoff,
+            },
+            breaker: CircuitBreaker::new(config.breaker_failure_threshold, config.breaker_cooldown),
+        }
+    }
+
+    /// Human-readable name of the wrapped backend.
+    pub const fn name(&self) -> &str {
+        self.backend.name()
+    }
+
+    /// Current breaker state.
+    #[allow(dead_code)]
+    pub fn breaker_state(&self) -> BreakerState {
+        self.breaker.state_at(Instant::now())
+    }
+
+    /// Send a prompt, retrying transient failures with backoff.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::CircuitOpen`] without calling the backend if
+    /// the breaker is open, or the last backend error once retries are
+    /// exhausted or a non-retryable failure occurs.
+    pub async fn complete(&self, prompt: &RenderedPrompt) -> Result<String, RunnerError> {
+        if !self.breaker.try_acquire_at(Instant::now()) {
+            return Err(RunnerError::CircuitOpen(self.name().to_owned()));
+        }
+
+        let mut retry: u32 = 0;
+        loop {
+            match self.backend.complete(prompt).await {
+                Ok(response) => {
+                    self.breaker.record_success();
+                    return Ok(response);
+                }
+                Err(e) => {
+                    let class = FailureClass::of(&e);
+                    if class.is_retryable() && retry < self.retry.max_retries {
+                        let delay = self.retry.backoff_for(retry);
+                        debug!(
+                            backend = self.name(),
+                            failure_class = ?class,
+                            retry = retry.saturating_add(1),
+                            delay_ms = delay.as_millis(),
+                            error = %e,
+                            "retrying LLM call"
+                        );
+                        tokio::time::sleep(delay).await;
+                        retry = retry.saturating_add(1);
+                        continue;
+                    }
+
+                    if class.counts_against_breaker() {
+                        if self.breaker.record_failure_at(Instant::now()) {
+                            warn!(
+                                backend = self.name(),
+                                failure_class = ?class,
+                                "circuit breaker tripped"
+                            );
+                        }
+                    } else {
+                        // A bad request still resolves a half-open trial.
+                        self.breaker.record_success();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_classes() {
+        let status = |status| RunnerError::LlmStatus {
+            status,
+            message: String::new(),
+        };
+        assert_eq!(
+            FailureClass::of(&RunnerError::Timeout),
+            FailureClass::Timeout
+        );
+        assert_eq!(FailureClass::of(&status(429)), FailureClass::RateLimited);
+        assert_eq!(FailureClass::of(&status(503)), FailureClass::ServerError);
+        assert_eq!(FailureClass::of(&status(400)), FailureClass::ClientError);
+        assert_eq!(
+            FailureClass::of(&RunnerError::LlmBackend("connection reset".to_owned())),
+            FailureClass::Transport
+        );
+        assert!(!FailureClass::ClientError.is_retryable());
+        assert!(!FailureClass::CircuitOpen.is_retryable());
+        assert!(FailureClass::RateLimited.is_retryable());
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        assert_eq!(policy.backoff_for(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff_for(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff_for(40), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn breaker_trips_after_threshold() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(30));
+        let now = Instant::now();
+        assert!(!breaker.record_failure_at(now));
+        assert!(!breaker.record_failure_at(now));
+        assert_eq!(breaker.state_at(now), BreakerState::Closed);
+        assert!(breaker.record_failure_at(now));
+        assert_eq!(breaker.state_at(now), BreakerState::Open);
+        assert!(!breaker.try_acquire_at(now));
+    }
+
+    #[test]
+    fn breaker_half_open_admits_single_trial() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        let now = Instant::now();
+        breaker.record_failure_at(now);
+
+        let later = now.checked_add(Duration::from_secs(11)).unwrap_or(now);
+        assert_eq!(breaker.state_at(later), BreakerState::HalfOpen);
+        assert!(breaker.try_acquire_at(later));
+        assert!(
+            !breaker.try_acquire_at(later),
+            "second caller must wait for the trial"
+        );
+
+        // Trial fails: re-open for another cooldown.
+        assert!(breaker.record_failure_at(later));
+        assert_eq!(breaker.state_at(later), BreakerState::Open);
+    }
+
+    #[test]
+    fn breaker_success_closes() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        let now = Instant::now();
+        breaker.record_failure_at(now);
+        let later = now.checked_add(Duration::from_secs(11)).unwrap_or(now);
+        assert!(breaker.try_acquire_at(later));
+        breaker.record_success();
+        assert_eq!(breaker.state_at(later), BreakerState::Closed);
+        assert!(breaker.try_acquire_at(later));
+    }
+}
@@ -9,15 +9,23 @@
 //! The night cycle optimization also lives here: sleeping or low-energy
 //! agents during `Night` ticks auto-rest without an LLM call.
 //!
+//! [`heuristic_policy`] extends the routine rules into a complete
+//! needs-based survival and economic policy (drink, eat, rest, flee danger,
+//! accept good trades, travel toward missing resources, stockpile). It
+//! decides for agents when no LLM is available, when a budget cap is hit,
+//! and for the no-LLM agent tier used as a baseline.
+//!
 //! See `build-plan.md` tasks 6.2.1 and 6.2.4.
 
 use emergence_types::{
-    ActionParameters, ActionRequest, ActionType, AgentId, Perception, Resource, TimeOfDay,
+    ActionParameters, ActionRequest, ActionType, AgentId, KnownRoute, LocationId, Perception,
+    Resource, TimeOfDay, TradeId, Weather,
 };
 use chrono::Utc;
 use tracing::info;
+use uuid::Uuid;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Mutex;
 
 // ---------------------------------------------------------------------------
@@ -54,6 +62,23 @@ const NIGHT_REST_ENERGY: u32 = 50;
 /// Number of consecutive identical rule firings before escalating to the LLM.
 const LOOP_DETECTION_THRESHOLD: u32 = 10;
 
+/// Food units the heuristic policy keeps in inventory when nothing is urgent.
+const FOOD_RESERVE: u32 = 3;
+
+/// Water units the heuristic policy keeps in inventory when nothing is urgent.
+const WATER_RESERVE: u32 = 2;
+
+/// Words in notifications that mean the agent is under threat right now.
+const DANGER_MARKERS: &[&str] = &["attack", "fight", "combat", "stole", "theft", "threaten"];
+
+/// Materials the heuristic policy stockpiles, most useful first.
+const MATERIAL_PRIORITY: &[Resource] = &[
+    Resource::Wood,
+    Resource::Stone,
+    Resource::Fiber,
+    Resource::Clay,
+];
+
 // ---------------------------------------------------------------------------
 // Loop detection state
 // ---------------------------------------------------------------------------
@@ -105,6 +130,15 @@ pub enum DecisionSource {
     RuleEngine,
     /// Decision was made by the night cycle optimization.
     NightCycle,
+    /// Decision was made by the heuristic policy because every LLM
+    /// backend failed or had its circuit breaker open.
+    Heuristic,
+    /// Decision was made by the bypass policy because an LLM budget cap
+    /// was exhausted.
+    Budget,
+    /// Decision was made by the heuristic policy for an agent in the
+    /// no-LLM tier.
+    Policy,
 }
 
 impl DecisionSource {
@@ -114,6 +148,9 @@ impl DecisionSource {
             Self::Llm => "llm",
             Self::RuleEngine => "rule_engine",
             Self::NightCycle => "night_cycle",
+            Self::Heuristic => "heuristic",
+            Self::Budget => "budget",
+            Self::Policy => "policy",
         }
     }
 }
@@ -134,10 +171,6 @@ const FOOD_PRIORITY: &[Resource] = &[
 ];
 
 /// Check whether a resource counts as food.
-///
-/// Currently used only in tests but kept as a utility for future rule
-/// engine extensions (e.g., checking if any inventory item is edible).
-#[allow(dead_code)]
 const fn is_food(resource: Resource) -> bool {
     matches!(
         resource,
@@ -232,12 +265,25 @@ fn has_inventory_room(perception: &Perception) -> bool {
 }
 
 /// Check whether the given action name is in the agent's available actions list.
+///
+/// Compares only the first word, case-insensitively and ignoring
+/// underscores, so `"trade_accept"` matches `"TradeAccept <trade_id>"`.
 fn action_available(perception: &Perception, action_name: &str) -> bool {
-    let lower = action_name.to_lowercase();
+    let wanted = normalize_action_name(action_name);
     perception
         .available_actions
         .iter()
-        .any(|a| a.to_lowercase() == lower)
+        .any(|a| normalize_action_name(a) == wanted)
+}
+
+/// Lowercased first word of an action listing, without underscores.
+fn normalize_action_name(listing: &str) -> String {
+    listing
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+        .replace('_', "")
 }
 
 // ---------------------------------------------------------------------------
@@ -287,6 +333,322 @@ pub fn try_routine_action(perception: &Perception) -> Option<ActionRequest> {
     None
 }
 
+/// Choose an action without any LLM call.
+///
+/// Used when no LLM backend is available, when a budget cap is exhausted,
+/// and for every decision of an agent in the no-LLM tier. There is no
+/// loop detection: there is no LLM to escalate to. Returns the rule name
+/// and action, or `None` if not even `Rest` is available.
+///
+/// # Policy (in priority order)
+///
+/// 1. **Critical needs**: routine rules 1-4 (medicine, critical thirst,
+///    starving, exhausted)
+/// 2. **Flee danger**: a hostile agent is present or a notification reports
+///    an attack or theft -- move along the cheapest route
+/// 3. **Accept good trades**: a pending offer the agent can pay for that
+///    covers a need or gives at least as many units as it asks
+/// 4. **Routine needs**: routine rules 5-7 (thirsty, very hungry, gather food)
+/// 5. **Seek water / food**: thirsty or hungry with none here or carried --
+///    move to the cheapest route whose destination lists it
+/// 6. **Low energy**: routine rule 8 (rest)
+/// 7. **Stockpile**: gather water, then food, up to a small reserve, then
+///    building materials while there is inventory room
+/// 8. **Rest**: nothing else to do
+///
+/// Travel is skipped during storms, when routes are blocked.
+pub fn heuristic_policy(perception: &Perception) -> Option<(String, ActionRequest)> {
+    let state = &perception.self_state;
+    let inventory = &state.inventory;
+    let agent_id = state.id;
+    let tick = perception.tick;
+
+    let critical = try_routine_action_inner(perception).filter(|(rule, _)| {
+        matches!(
+            rule.as_str(),
+            "critical_health" | "critical_thirst" | "starving" | "exhausted"
+        )
+    });
+    if critical.is_some() {
+        return critical;
+    }
+
+    if in_danger(perception)
+        && let Some(route) = cheapest_route(perception, |_| true)
+        && let Some(action) = make_move_action(agent_id, tick, route)
+    {
+        info!(
+            agent_id = %agent_id,
+            destination = route.destination,
+            rule = "flee_danger",
+            "heuristic policy: fleeing danger"
+        );
+        return Some(("flee_danger".to_owned(), action));
+    }
+
+    if action_available(perception, "trade_accept")
+        && let Some(offer) = pending_offers(perception)
+            .into_iter()
+            .find(|offer| is_good_trade(perception, offer))
+    {
+        info!(
+            agent_id = %agent_id,
+            trade_id = %offer.trade_id,
+            rule = "accept_trade",
+            "heuristic policy: accepting trade offer"
+        );
+        return Some((
+            "accept_trade".to_owned(),
+            make_trade_accept_action(agent_id, tick, offer.trade_id),
+        ));
+    }
+
+    if let Some(matched) = try_routine_action_inner(perception)
+        && matched.0 != "low_energy"
+    {
+        return Some(matched);
+    }
+
+    let needs_water = state.thirst >= THIRSTY && !has_water(inventory) && !at_water_source(perception);
+    if needs_water
+        && let Some(route) = cheapest_route(perception, |r| route_offers(r, Resource::Water))
+        && let Some(action) = make_move_action(agent_id, tick, route)
+    {
+        info!(
+            agent_id = %agent_id,
+            destination = route.destination,
+            rule = "seek_water",
+            "heuristic policy: travelling to water"
+        );
+        return Some(("seek_water".to_owned(), action));
+    }
+
+    let needs_food = state.hunger >= GATHER_FOOD_HUNGER
+        && best_food_in_inventory(inventory).is_none()
+        && food_at_location(perception).is_none();
+    if needs_food
+        && let Some(route) = cheapest_route(perception, |r| {
+            FOOD_PRIORITY.iter().any(|&food| route_offers(r, food))
+        })
+        && let Some(action) = make_move_action(agent_id, tick, route)
+    {
+        info!(
+            agent_id = %agent_id,
+            destination = route.destination,
+            rule = "seek_food",
+            "heuristic policy: travelling to food"
+        );
+        return Some(("seek_food".to_owned(), action));
+    }
+
+    if let Some(matched) = try_routine_action_inner(perception) {
+        return Some(matched);
+    }
+
+    if let Some((rule, resource)) = stockpile_target(perception) {
+        info!(
+            agent_id = %agent_id,
+            resource = ?resource,
+            rule = rule,
+            "heuristic policy: stockpiling"
+        );
+        return Some((rule.to_owned(), make_gather_action(agent_id, tick, resource)));
+    }
+
+    if action_available(perception, "rest") {
+        return Some(("fallback_rest".to_owned(), make_rest_action(agent_id, tick)));
+    }
+    None
+}
+
+/// Whether an agent belongs to the no-LLM tier that `percent` percent of
+/// agents are assigned to.
+///
+/// Assignment hashes bytes 9..13 of the agent's UUID, which are random in a
+/// v7 ID and independent of the bytes used for partitioning and prompt
+/// variants, so it is stable across ticks and restarts.
+pub fn in_heuristic_tier(agent_id: AgentId, percent: u32) -> bool {
+    if percent == 0 {
+        return false;
+    }
+    let uuid = agent_id.into_inner();
+    let bytes = uuid.as_bytes();
+    let hash = u32::from_be_bytes([
+        *bytes.get(9).unwrap_or(&0),
+        *bytes.get(10).unwrap_or(&0),
+        *bytes.get(11).unwrap_or(&0),
+        *bytes.get(12).unwrap_or(&0),
+    ]);
+    hash.checked_rem(100).unwrap_or(0) < percent
+}
+
+/// Whether the agent is under immediate threat: a hostile agent is present
+/// or this tick's notifications report an attack, fight, or theft.
+fn in_danger(perception: &Perception) -> bool {
+    let hostile_present = perception.surroundings.agents_here.iter().any(|a| {
+        let lower = a.relationship.to_lowercase();
+        lower.contains("hostile") || lower.contains("enemy")
+    });
+    hostile_present
+        || perception.notifications.iter().any(|note| {
+            let lower = note.to_lowercase();
+            DANGER_MARKERS.iter().any(|marker| lower.contains(marker))
+        })
+}
+
+/// The cheapest known route matching `filter`, or `None` if travel is not
+/// possible (no `move` action, a storm, or no matching route).
+fn cheapest_route(
+    perception: &Perception,
+    filter: impl Fn(&KnownRoute) -> bool,
+) -> Option<&KnownRoute> {
+    if perception.weather == Weather::Storm || !action_available(perception, "move") {
+        return None;
+    }
+    perception
+        .known_routes
+        .iter()
+        .filter(|route| filter(route))
+        .min_by_key(|route| route_cost(route))
+}
+
+/// Parse a route cost such as `"3 ticks"` into its tick count. Unparseable
+/// costs sort last.
+fn route_cost(route: &KnownRoute) -> u32 {
+    route
+        .cost
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(u32::MAX)
+}
+
+/// Whether a route's destination lists `resource` in its resource hint.
+fn route_offers(route: &KnownRoute, resource: Resource) -> bool {
+    route
+        .resources_hint
+        .split(',')
+        .any(|hint| hint.trim() == format!("{resource:?}"))
+}
+
+/// Total units of food in an inventory.
+fn food_units(inventory: &BTreeMap<Resource, u32>) -> u32 {
+    FOOD_PRIORITY
+        .iter()
+        .filter_map(|food| inventory.get(food))
+        .fold(0, |total, &qty| total.saturating_add(qty))
+}
+
+/// Pick what to gather when no need is pressing: water up to
+/// [`WATER_RESERVE`], food up to [`FOOD_RESERVE`], then building materials.
+fn stockpile_target(perception: &Perception) -> Option<(&'static str, Resource)> {
+    if !action_available(perception, "gather") || !has_inventory_room(perception) {
+        return None;
+    }
+    let inventory = &perception.self_state.inventory;
+    let water = inventory.get(&Resource::Water).copied().unwrap_or(0);
+    if water < WATER_RESERVE && at_water_source(perception) {
+        return Some(("stockpile_water", Resource::Water));
+    }
+    if food_units(inventory) < FOOD_RESERVE
+        && let Some(food) = food_at_location(perception)
+    {
+        return Some(("stockpile_food", food));
+    }
+    MATERIAL_PRIORITY
+        .iter()
+        .find(|material| perception.surroundings.visible_resources.contains_key(material))
+        .map(|&material| ("gather_materials", material))
+}
+
+/// A trade offer awaiting this agent's answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingOffer {
+    trade_id: TradeId,
+    /// What the other agent gives.
+    offer: BTreeMap<Resource, u32>,
+    /// What the other agent asks for in return.
+    request: BTreeMap<Resource, u32>,
+}
+
+/// Read pending trade offers from notifications and recent memories.
+///
+/// A line counts when it mentions a trade offer, carries the trade's UUID,
+/// and lists `offer` and `request` as JSON resource maps (for example
+/// `Trade offer 0194...: offer {"Wood": 5}, request {"FoodBerry": 2}`).
+/// Lines in any other shape are ignored.
+fn pending_offers(perception: &Perception) -> Vec<PendingOffer> {
+    perception
+        .notifications
+        .iter()
+        .chain(&perception.recent_memory)
+        .filter(|line| line.to_lowercase().contains("trade offer"))
+        .filter_map(|line| {
+            let trade_id = line
+                .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+                .find_map(|token| Uuid::parse_str(token).ok())
+                .map(TradeId::from)?;
+            Some(PendingOffer {
+                trade_id,
+                offer: resource_map_after(line, "offer")?,
+                request: resource_map_after(line, "request")?,
+            })
+        })
+        .collect()
+}
+
+/// Parse the JSON resource map that follows the first `key` keyword
+/// (quoted or not) in `line`.
+fn resource_map_after(line: &str, key: &str) -> Option<BTreeMap<Resource, u32>> {
+    let lower = line.to_lowercase();
+    let key_end = lower
+        .match_indices(key)
+        .map(|(start, matched)| start.saturating_add(matched.len()))
+        .find(|&end| {
+            lower
+                .get(end..)
+                .is_some_and(|rest| rest.trim_start_matches(['"', ':', ' ']).starts_with('{'))
+        })?;
+    let rest = line.get(key_end..)?;
+    let open = rest.find('{')?;
+    let close = rest.get(open..)?.find('}')?.checked_add(open)?;
+    serde_json::from_str(rest.get(open..=close)?).ok()
+}
+
+/// Whether a pending offer is worth accepting.
+///
+/// The agent must hold everything requested and never pays with food or
+/// water it currently needs. The offer is good when it covers an unmet
+/// need (food while hungry, water while thirsty) or gives at least as many
+/// units as it asks for.
+fn is_good_trade(perception: &Perception, offer: &PendingOffer) -> bool {
+    let state = &perception.self_state;
+    let inventory = &state.inventory;
+    let affordable = offer
+        .request
+        .iter()
+        .all(|(resource, &qty)| inventory.get(resource).copied().unwrap_or(0) >= qty);
+    if !affordable {
+        return false;
+    }
+
+    let hungry = state.hunger >= VERY_HUNGRY;
+    let thirsty = state.thirst >= THIRSTY;
+    let pays_with_needed = offer.request.keys().any(|&resource| {
+        (hungry && is_food(resource)) || (thirsty && resource == Resource::Water)
+    });
+    if pays_with_needed {
+        return false;
+    }
+
+    let covers_need = offer.offer.keys().any(|&resource| {
+        (hungry && is_food(resource) && best_food_in_inventory(inventory).is_none())
+            || (thirsty && resource == Resource::Water && !has_water(inventory))
+    });
+    let units = |map: &BTreeMap<Resource, u32>| map.values().fold(0u32, |t, &q| t.saturating_add(q));
+    covers_need || units(&offer.offer) >= units(&offer.request)
+}
+
 /// Inner implementation of routine action matching. Returns the rule name
 /// and action request if a rule matches, or `None` if no rule applies.
 fn try_routine_action_inner(perception: &Perception) -> Option<(String, ActionRequest)> {
@@ -503,6 +865,35 @@ fn make_gather_action(agent_id: AgentId, tick: u64, resource: Resource) -> Actio
     }
 }
 
+/// Build a `Move` action request along a known route.
+///
+/// Returns `None` if the route's destination ID is not a valid UUID.
+fn make_move_action(agent_id: AgentId, tick: u64, route: &KnownRoute) -> Option<ActionRequest> {
+    let destination = Uuid::parse_str(&route.destination_id).ok()?;
+    Some(ActionRequest {
+        agent_id,
+        tick,
+        action_type: ActionType::Move,
+        parameters: ActionParameters::Move {
+            destination: LocationId::from(destination),
+        },
+        submitted_at: Utc::now(),
+        goal_updates: Vec::new(),
+    })
+}
+
+/// Build a `TradeAccept` action request.
+fn make_trade_accept_action(agent_id: AgentId, tick: u64, trade_id: TradeId) -> ActionRequest {
+    ActionRequest {
+        agent_id,
+        tick,
+        action_type: ActionType::TradeAccept,
+        parameters: ActionParameters::TradeAccept { trade_id },
+        submitted_at: Utc::now(),
+        goal_updates: Vec::new(),
+    }
+}
+
 /// Build a `Rest` action request.
 fn make_rest_action(agent_id: AgentId, tick: u64) -> ActionRequest {
     ActionRequest {
@@ -525,7 +916,7 @@ mod tests {
     use std::collections::BTreeMap;
 
     use emergence_types::{
-        Season, SelfState, Sex, Surroundings, Weather,
+        Season, SelfState, Sex, Surroundings, VisibleAgent, Weather,
     };
 
     /// Build a minimal perception with customizable vitals and inventory.
@@ -582,12 +973,15 @@ mod tests {
                 structures_here: Vec::new(),
                 agents_here: Vec::new(),
                 messages_here: Vec::new(),
+                overcrowded: false,
+                pollution: 0,
             },
             known_routes: Vec::new(),
             recent_memory: Vec::new(),
             available_actions,
             notifications: Vec::new(),
             personality: None,
+            forecast: Vec::new(),
         }
     }
 
@@ -1121,6 +1515,8 @@ mod tests {
         assert_eq!(DecisionSource::Llm.as_str(), "llm");
         assert_eq!(DecisionSource::RuleEngine.as_str(), "rule_engine");
         assert_eq!(DecisionSource::NightCycle.as_str(), "night_cycle");
+        assert_eq!(DecisionSource::Budget.as_str(), "budget");
+        assert_eq!(DecisionSource::Policy.as_str(), "policy");
     }
 
     // -----------------------------------------------------------------------
@@ -1351,4 +1747,211 @@ mod tests {
         // Clean up
         reset_loop_detection(agent_id);
     }
+
+    // -----------------------------------------------------------------------
+    // Heuristic policy
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn heuristic_policy_applies_survival_rules() {
+        let mut inv = BTreeMap::new();
+        inv.insert(Resource::FoodBerry, 3);
+        let p = make_perception(
+            60,
+            100,
+            85,
+            inv,
+            TimeOfDay::Morning,
+            BTreeMap::new(),
+            default_actions(),
+        );
+        let (rule, action) = heuristic_policy(&p)
+            .unwrap_or_else(|| (String::new(), make_rest_action(AgentId::new(), 0)));
+        assert_eq!(rule, "starving");
+        assert_eq!(action.action_type, ActionType::Eat);
+    }
+
+    #[test]
+    fn heuristic_policy_rests_when_idle() {
+        let p = make_perception(
+            90,
+            100,
+            10,
+            BTreeMap::new(),
+            TimeOfDay::Morning,
+            BTreeMap::new(),
+            default_actions(),
+        );
+        let (rule, action) = heuristic_policy(&p)
+            .unwrap_or_else(|| (String::new(), make_drink_action(AgentId::new(), 0)));
+        assert_eq!(rule, "fallback_rest");
+        assert_eq!(action.action_type, ActionType::Rest);
+    }
+
+    #[test]
+    fn heuristic_policy_none_without_rest() {
+        let p = make_perception(
+            90,
+            100,
+            10,
+            BTreeMap::new(),
+            TimeOfDay::Morning,
+            BTreeMap::new(),
+            vec!["move".to_owned()],
+        );
+        assert!(heuristic_policy(&p).is_none());
+    }
+
+    // -----------------------------------------------------------------------
+    // Heuristic policy: danger, trades, travel, stockpiling
+    // -----------------------------------------------------------------------
+
+    fn route(cost: &str, resources: &str) -> KnownRoute {
+        KnownRoute {
+            destination_id: LocationId::new().to_string(),
+            destination: format!("Place {cost}"),
+            cost: cost.to_owned(),
+            path_type: "DirtTrail".to_owned(),
+            resources_hint: resources.to_owned(),
+        }
+    }
+
+    fn idle_perception() -> Perception {
+        make_perception(
+            90, 100, 10, BTreeMap::new(), TimeOfDay::Morning,
+            BTreeMap::new(), default_actions(),
+        )
+    }
+
+    fn policy_action(p: &Perception) -> (String, ActionRequest) {
+        heuristic_policy(p).unwrap_or_else(|| (String::new(), make_rest_action(AgentId::new(), 0)))
+    }
+
+    #[test]
+    fn policy_flees_hostile_agent_by_cheapest_route() {
+        let mut p = idle_perception();
+        p.surroundings.agents_here.push(VisibleAgent {
+            id: AgentId::new(),
+            name: "Brute".to_owned(),
+            sex: Sex::Male,
+            relationship: "hostile (-0.8)".to_owned(),
+            activity: "idle".to_owned(),
+        });
+        let near = route("2 ticks", "Wood");
+        p.known_routes = vec![route("5 ticks", "Water"), near.clone()];
+        let (rule, action) = policy_action(&p);
+        assert_eq!(rule, "flee_danger");
+        let expected = Uuid::parse_str(&near.destination_id).map(LocationId::from).ok();
+        assert!(matches!(
+            action.parameters,
+            ActionParameters::Move { destination } if Some(destination) == expected
+        ));
+
+        // Storms block travel, so the agent stays put.
+        p.weather = Weather::Storm;
+        assert_ne!(policy_action(&p).0, "flee_danger");
+    }
+
+    #[test]
+    fn policy_critical_need_beats_fleeing() {
+        let mut inv = BTreeMap::new();
+        inv.insert(Resource::FoodBerry, 2);
+        let mut p = make_perception(
+            60, 100, 90, inv, TimeOfDay::Morning,
+            BTreeMap::new(), default_actions(),
+        );
+        p.notifications.push("Brute attacks you!".to_owned());
+        p.known_routes = vec![route("1 ticks", "")];
+        assert_eq!(policy_action(&p).0, "starving");
+    }
+
+    #[test]
+    fn policy_accepts_good_trades_only() {
+        let trade_id = TradeId::new();
+        let mut inv = BTreeMap::new();
+        inv.insert(Resource::Wood, 5);
+        let mut actions = default_actions();
+        actions.push("trade_accept <trade_id>".to_owned());
+        let mut p = make_perception(
+            60, 100, 60, inv, TimeOfDay::Morning,
+            BTreeMap::new(), actions,
+        );
+        p.notifications.push(format!(
+            r#"Trade offer {trade_id} from Bo: offer {{"FoodBerry": 2}}, request {{"Wood": 3}}"#
+        ));
+        let (rule, action) = policy_action(&p);
+        assert_eq!(rule, "accept_trade");
+        assert_eq!(action.parameters, ActionParameters::TradeAccept { trade_id });
+
+        // Asking for more wood than the agent holds is not affordable.
+        p.notifications = vec![format!(
+            r#"Trade offer {trade_id}: offer {{"FoodBerry": 2}}, request {{"Wood": 9}}"#
+        )];
+        assert_ne!(policy_action(&p).0, "accept_trade");
+    }
+
+    #[test]
+    fn pending_offers_ignore_unstructured_lines() {
+        let mut p = idle_perception();
+        p.notifications.push("Bo made you a trade offer but I forgot what".to_owned());
+        p.recent_memory.push(format!("Trade offer {}: offer {{}}", TradeId::new()));
+        assert!(pending_offers(&p).is_empty());
+    }
+
+    #[test]
+    fn policy_travels_to_water_when_thirsty() {
+        let mut p = make_perception_with_thirst(
+            60, 100, 10, 60, BTreeMap::new(), TimeOfDay::Morning,
+            BTreeMap::new(), default_actions(),
+        );
+        p.known_routes = vec![route("1 ticks", "Wood"), route("3 ticks", "FoodBerry, Water")];
+        let (rule, action) = policy_action(&p);
+        assert_eq!(rule, "seek_water");
+        assert_eq!(action.action_type, ActionType::Move);
+    }
+
+    #[test]
+    fn policy_stockpiles_water_then_materials() {
+        let mut visible = BTreeMap::new();
+        visible.insert(Resource::Water, "plenty".to_owned());
+        visible.insert(Resource::Wood, "some".to_owned());
+        let mut inv = BTreeMap::new();
+        inv.insert(Resource::FoodBerry, FOOD_RESERVE);
+        let mut p = make_perception(
+            90, 100, 10, inv, TimeOfDay::Morning,
+            visible, default_actions(),
+        );
+        let (rule, action) = policy_action(&p);
+        assert_eq!(rule, "stockpile_water");
+        assert_eq!(action.parameters, ActionParameters::Gather { resource: Resource::Water });
+
+        p.self_state.inventory.insert(Resource::Water, WATER_RESERVE);
+        let (rule, action) = policy_action(&p);
+        assert_eq!(rule, "gather_materials");
+        assert_eq!(action.parameters, ActionParameters::Gather { resource: Resource::Wood });
+
+        p.self_state.carry_load = "50/50".to_owned();
+        assert_eq!(policy_action(&p).0, "fallback_rest");
+    }
+
+    #[test]
+    fn action_names_match_engine_listings() {
+        let p = make_perception(
+            90, 100, 10, BTreeMap::new(), TimeOfDay::Morning, BTreeMap::new(),
+            vec!["eat <food_type>".to_owned(), "TradeAccept".to_owned()],
+        );
+        assert!(action_available(&p, "eat"));
+        assert!(action_available(&p, "trade_accept"));
+        assert!(!action_available(&p, "rest"));
+    }
+
+    #[test]
+    fn heuristic_tier_assignment_is_stable_and_bounded() {
+        let agents: Vec<AgentId> = (0..200).map(|_| AgentId::new()).collect();
+        assert!(agents.iter().all(|&a| !in_heuristic_tier(a, 0)));
+        assert!(agents.iter().all(|&a| in_heuristic_tier(a, 100)));
+        let half = agents.iter().filter(|&&a| in_heuristic_tier(a, 50)).count();
+        assert!((50..=150).contains(&half), "got {half} of 200");
+        assert!(agents.iter().all(|&a| in_heuristic_tier(a, 50) == in_heuristic_tier(a, 50)));
+    }
 }
@@ -7,7 +7,7 @@
 //! 4. Select LLM backend based on complexity (task 6.2.3)
 //! 5. Render prompt from templates
 //! 6. Call LLM backend (with timeout and fallback)
-//! 7. Parse structured action from response
+//! 7. Parse structured action from response (with repair and one re-prompt)
 //! 8. Submit action to World Engine via NATS
 //!
 //! Timeout handling ensures an agent never misses a tick -- if the LLM
@@ -20,22 +20,56 @@
 //! When complexity routing is enabled (task 6.2.3), high-complexity
 //! decisions are routed to the escalation backend first, while
 //! low/medium decisions use the cheap primary backend.
+//!
+//! Each backend is a [`ResilientBackend`] with its own retries and circuit
+//! breaker. A tripped backend fails fast, so the chain moves straight to
+//! the other backend; if the whole chain fails, the heuristic policy picks
+//! a safe survival action instead of submitting `NoAction`.
+//!
+//! With batching enabled, perceptions are collected for a short window and
+//! low-complexity agents share a single LLM call (see [`crate::batch`]).
+//!
+//! With a budget attached, every LLM decision is first checked against the
+//! [`CostTracker`] caps. Agents over budget get the bypass policy instead,
+//! and critical agents (see [`score_priority`]) may spend the reserved part
+//! of each cap. In batch mode critical agents are also decided first.
+//!
+//! With reflection enabled, each LLM agent is periodically asked to revise
+//! its goals and summarize its memories after its action is submitted (see
+//! [`crate::reflection`]). The result goes to the engine as a reflection
+//! update, not an action.
+//!
+//! Over `JetStream`, each perception delivery is acknowledged once its
+//! action is published, and handed back for redelivery if publishing
+//! fails. Deliveries the runner skips (other partitions, malformed
+//! payloads) are acknowledged straight away.
+//!
+//! Each agent's decision runs in an `agent_decision` span parented to the
+//! trace context in its perception's headers, and the action it publishes
+//! carries that span's context back to the engine.
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use emergence_types::{ActionParameters, ActionRequest, ActionType, AgentId, DecisionRecord, Perception};
-use futures::StreamExt;
 use tokio::time::timeout;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Instrument as _};
 
-use crate::complexity::{score_complexity, ComplexityLevel};
+use crate::batch::{batch_prompt, split_batch_response};
+use crate::complexity::{score_priority, ComplexityLevel, ComplexityRules, DecisionPriority};
 use crate::containment;
+use crate::cost::CostTracker;
 use crate::error::RunnerError;
-use crate::llm::LlmBackend;
-use crate::nats::NatsClient;
-use crate::parse::parse_llm_response;
-use crate::prompt::PromptEngine;
+use crate::nats::{NatsClient, PerceptionFeed, PerceptionMessage};
+use crate::parse::{
+    parse_llm_response, repair_prompt, split_reasoning, try_parse_llm_response, ParsedDecision,
+};
+use crate::prompt::{PromptEngine, PromptVariant, RenderedPrompt};
+use crate::persona::{AgentPersona, PersonaStore};
+use crate::reflection;
+use crate::resilience::ResilientBackend;
 use crate::rule_engine::{self, DecisionSource};
 
 /// Maximum length for the raw LLM response stored in a [`DecisionRecord`].
@@ -44,16 +78,28 @@ const MAX_RAW_RESPONSE_LEN: usize = 4000;
 /// Maximum length for the prompt stored in a [`DecisionRecord`].
 const MAX_PROMPT_LEN: usize = 8000;
 
+/// Maximum length for the reasoning stored in a [`DecisionRecord`].
+const MAX_REASONING_LEN: usize = 2000;
+
 /// Metadata captured from an LLM decision for the [`DecisionRecord`].
 struct LlmDecisionMeta {
     /// The rendered prompt (system + user) sent to the LLM.
     prompt_sent: String,
     /// The raw text response from the LLM.
     raw_response: String,
+    /// The model's explanation and `reasoning` field, separated from the
+    /// action by the parser.
+    reasoning: Option<String>,
     /// Which backend answered (e.g. `"openai-compatible"`, `"anthropic"`).
     backend_name: String,
     /// Wall-clock latency of the LLM call in milliseconds.
     latency_ms: u64,
+    /// The perception the prompt was rendered from.
+    perception: serde_json::Value,
+    /// Prompt template variant used for this agent.
+    prompt_variant: String,
+    /// Version tag of the prompt variant.
+    prompt_version: String,
 }
 
 /// The agent decision runner.
@@ -62,11 +108,12 @@ struct LlmDecisionMeta {
 /// NATS client, prompt engine, LLM backends (primary + optional
 /// escalation), and configuration flags for rule engine bypass and
 /// complexity-based routing.
+#[allow(clippy::struct_excessive_bools)]
 pub struct AgentRunner {
     nats: NatsClient,
     prompt_engine: PromptEngine,
-    primary_backend: LlmBackend,
-    escalation_backend: Option<LlmBackend>,
+    primary_backend: ResilientBackend,
+    escalation_backend: Option<ResilientBackend>,
     decision_timeout: Duration,
     /// When true, the rule engine checks for obvious survival actions
     /// before calling the LLM.
@@ -81,16 +128,35 @@ pub struct AgentRunner {
     partition_id: u32,
     /// Total number of runner partitions.
     total_partitions: u32,
+    /// When true, a response that fails parsing and mechanical repair is
+    /// sent back to the primary backend once with a "fix this JSON" prompt.
+    parse_repair_reprompt: bool,
+    /// Per-agent personality, goals, and retained memories merged into
+    /// each prompt.
+    persona_store: PersonaStore,
+    /// Maximum number of low-complexity agents packed into one LLM call.
+    /// `1` disables batching.
+    batch_size: usize,
+    /// How long to wait for more perceptions after the first one of a batch.
+    batch_window: Duration,
+    /// Spending caps checked before each LLM decision, if any.
+    budget: Option<Arc<CostTracker>>,
+    /// Rules that score decision complexity for routing and batching.
+    complexity_rules: ComplexityRules,
+    /// Percentage of agents decided by the heuristic policy alone.
+    heuristic_agent_percent: u32,
+    /// Ticks between an agent's reflections. `0` disables reflection.
+    reflection_interval: u64,
 }
 
 impl AgentRunner {
     /// Create a new agent runner with all required components.
     #[allow(clippy::too_many_arguments)]
-    pub const fn new(
+    pub fn new(
         nats: NatsClient,
         prompt_engine: PromptEngine,
-        primary_backend: LlmBackend,
-        escalation_backend: Option<LlmBackend>,
+        primary_backend: ResilientBackend,
+        escalation_backend: Option<ResilientBackend>,
         decision_timeout: Duration,
         routine_action_bypass: bool,
         night_cycle_skip: bool,
@@ -107,6 +173,14 @@ impl AgentRunner {
             complexity_routing_enabled,
             partition_id: 0,
             total_partitions: 1,
+            parse_repair_reprompt: false,
+            persona_store: PersonaStore::new(crate::persona::DEFAULT_MEMORY_CAPACITY),
+            batch_size: 1,
+            batch_window: Duration::ZERO,
+            budget: None,
+            complexity_rules: ComplexityRules::default(),
+            heuristic_agent_percent: 0,
+            reflection_interval: 0,
         }
     }
 
@@ -120,6 +194,64 @@ impl AgentRunner {
         self
     }
 
+    /// Enable or disable the "fix this JSON" re-prompt for unparseable
+    /// responses.
+    pub const fn with_parse_repair(mut self, enabled: bool) -> Self {
+        self.parse_repair_reprompt = enabled;
+        self
+    }
+
+    /// Set how many memory summaries are retained per agent for prompts.
+    pub fn with_persona_memory(mut self, capacity: usize) -> Self {
+        self.persona_store = PersonaStore::new(capacity);
+        self
+    }
+
+    /// Enable batched multi-agent inference.
+    ///
+    /// Up to `batch_size` low-complexity agents collected within `window`
+    /// share one LLM call. A `batch_size` of `1` keeps per-agent calls.
+    pub const fn with_batching(mut self, batch_size: usize, window: Duration) -> Self {
+        self.batch_size = batch_size;
+        self.batch_window = window;
+        self
+    }
+
+    /// Replace the default complexity scoring rules.
+    pub fn with_complexity_rules(mut self, rules: ComplexityRules) -> Self {
+        self.complexity_rules = rules;
+        self
+    }
+
+    /// Put `percent` percent of agents in the no-LLM tier.
+    ///
+    /// Tier agents still go through the night cycle and routine action
+    /// fast paths; every decision that would reach the LLM is made by
+    /// [`rule_engine::heuristic_policy`] instead. `100` runs the whole
+    /// partition without LLM calls, for baselines or degraded operation.
+    pub const fn with_heuristic_tier(mut self, percent: u32) -> Self {
+        self.heuristic_agent_percent = percent;
+        self
+    }
+
+    /// Ask each LLM agent to reflect once every `interval` ticks.
+    ///
+    /// Reflections use the primary backend and the decision timeout. `0`
+    /// disables them.
+    pub const fn with_reflection(mut self, interval: u64) -> Self {
+        self.reflection_interval = interval;
+        self
+    }
+
+    /// Enforce the budget caps configured on `tracker` before LLM calls.
+    ///
+    /// The same tracker should be shared with the LLM backends so the caps
+    /// see the tokens they record.
+    pub fn with_budget(mut self, tracker: Arc<CostTracker>) -> Self {
+        self.budget = Some(tracker);
+        self
+    }
+
     /// Run the main decision loop.
     ///
     /// Subscribes to perception messages from NATS and processes each one
@@ -130,65 +262,430 @@ impl AgentRunner {
     ///
     /// Returns [`RunnerError`] if NATS subscription fails.
     pub async fn run(&self) -> Result<(), RunnerError> {
-        let mut subscriber = self.nats.subscribe_perceptions().await?;
+        let mut feed = self.nats.subscribe_perceptions().await?;
         info!(
             partition_id = self.partition_id,
             total_partitions = self.total_partitions,
             "agent runner started, awaiting perception payloads"
         );
 
-        while let Some(message) = subscriber.next().await {
-            let subject = message.subject.to_string();
-            let tick = NatsClient::extract_tick_from_subject(&subject).unwrap_or(0);
+        if self.batch_size > 1 {
+            self.run_batched(&mut feed).await;
+        } else {
+            while let Some(message) = feed.next().await {
+                let Some((tick, perception)) = self.accept_message(message.message()) else {
+                    message.ack().await;
+                    continue;
+                };
+                let span = decision_span(tick, perception.self_state.id, Some(&message));
+                async {
+                    let action = self.decide(tick, &perception).await;
+                    self.submit_and_settle(tick, &action, Some(&message)).await;
+                }
+                .instrument(span)
+                .await;
+                self.reflect(tick, &perception).await;
+            }
+        }
 
-            debug!(
-                subject = subject,
-                tick = tick,
-                payload_size = message.payload.len(),
-                "received perception message"
-            );
+        info!("NATS subscription ended, runner shutting down");
+        Ok(())
+    }
 
-            match NatsClient::deserialize_perception(&message.payload) {
-                Ok(perception) => {
-                    let agent_id = perception.self_state.id;
-
-                    // Multi-runner partitioning: skip agents that belong to
-                    // other runner instances.
-                    if !NatsClient::is_my_agent(
-                        &agent_id,
-                        self.partition_id,
-                        self.total_partitions,
-                    ) {
-                        debug!(
-                            agent_id = %agent_id,
-                            partition_id = self.partition_id,
-                            "skipping agent (not my partition)"
-                        );
-                        continue;
+    /// Batched variant of the decision loop.
+    ///
+    /// Collects up to `batch_size` perceptions, waiting at most
+    /// `batch_window` after the first one, then decides them together:
+    /// fast paths first, low-complexity LLM decisions packed into one call,
+    /// and everything else through the normal per-agent pipeline.
+    async fn run_batched(&self, feed: &mut PerceptionFeed) {
+        loop {
+            let Some(first) = feed.next().await else {
+                return;
+            };
+            let mut messages = vec![first];
+            let deadline = tokio::time::Instant::now()
+                .checked_add(self.batch_window)
+                .unwrap_or_else(tokio::time::Instant::now);
+            let mut ended = false;
+            while messages.len() < self.batch_size {
+                match tokio::time::timeout_at(deadline, feed.next()).await {
+                    Ok(Some(message)) => messages.push(message),
+                    Ok(None) => {
+                        ended = true;
+                        break;
                     }
+                    Err(_) => break,
+                }
+            }
 
-                    let action = self.decide(tick, &perception).await;
-                    if let Err(e) = self.nats.publish_action(tick, &action).await {
-                        warn!(
-                            agent_id = %agent_id,
-                            tick = tick,
-                            error = %e,
-                            "failed to publish action"
-                        );
-                    }
+            let mut accepted: Vec<(u64, Perception)> = Vec::with_capacity(messages.len());
+            let mut deliveries: BTreeMap<(AgentId, u64), &PerceptionMessage> = BTreeMap::new();
+            for message in &messages {
+                if let Some((tick, perception)) = self.accept_message(message.message()) {
+                    deliveries.insert((perception.self_state.id, tick), message);
+                    accepted.push((tick, perception));
+                } else {
+                    message.ack().await;
                 }
-                Err(e) => {
-                    warn!(
-                        subject = subject,
-                        error = %e,
-                        "failed to deserialize perception, skipping"
+            }
+            self.decide_collected(&accepted, &deliveries).await;
+
+            if ended {
+                return;
+            }
+        }
+    }
+
+    /// Deserialize a perception message, check partition ownership, and
+    /// sanitize peer-authored content.
+    ///
+    /// Returns `None` (after logging) for malformed payloads and agents that
+    /// belong to another runner instance. Quarantined content (Phase 5.4.5)
+    /// is published before the perception goes anywhere else.
+    fn accept_message(&self, message: &async_nats::Message) -> Option<(u64, Perception)> {
+        let subject = message.subject.to_string();
+        let tick = NatsClient::extract_tick_from_subject(&subject).unwrap_or(0);
+
+        debug!(
+            subject = subject,
+            tick = tick,
+            payload_size = message.payload.len(),
+            "received perception message"
+        );
+
+        match self.nats.read_perception(message) {
+            Ok(mut perception) => {
+                let agent_id = perception.self_state.id;
+
+                // Multi-runner partitioning: skip agents that belong to
+                // other runner instances.
+                if !NatsClient::is_my_agent(
+                    &agent_id,
+                    self.partition_id,
+                    self.total_partitions,
+                ) {
+                    debug!(
+                        agent_id = %agent_id,
+                        partition_id = self.partition_id,
+                        "skipping agent (not my partition)"
                     );
+                    return None;
                 }
+                for record in containment::sanitize_perception(&mut perception) {
+                    self.nats.publish_quarantine(&record);
+                }
+                Some((tick, perception))
+            }
+            Err(e) => {
+                warn!(
+                    subject = subject,
+                    error = %e,
+                    "failed to read perception, skipping"
+                );
+                None
             }
         }
+    }
 
-        info!("NATS subscription ended, runner shutting down");
-        Ok(())
+    /// Publish an action to the World Engine and settle the perception
+    /// delivery it answers.
+    ///
+    /// The delivery is acknowledged once the action is published. If
+    /// publishing fails it is handed back for redelivery, so the agent
+    /// gets another chance at the tick instead of silently missing it.
+    async fn submit_and_settle(
+        &self,
+        tick: u64,
+        action: &ActionRequest,
+        delivery: Option<&PerceptionMessage>,
+    ) {
+        match self.nats.publish_action(tick, action).await {
+            Ok(()) => {
+                if let Some(message) = delivery {
+                    message.ack().await;
+                }
+            }
+            Err(e) => {
+                warn!(
+                    agent_id = %action.agent_id,
+                    tick = tick,
+                    error = %e,
+                    "failed to publish action"
+                );
+                if let Some(message) = delivery {
+                    message.nak().await;
+                }
+            }
+        }
+    }
+
+    /// Decide and submit a collected group of perceptions.
+    ///
+    /// Fast paths run per agent. Critical agents are decided first, each
+    /// with its own call, so they reach the LLM before a budget cap is hit.
+    /// Remaining low-complexity agents are batched into a single
+    /// primary-backend call when there are at least two of them; medium and
+    /// high complexity agents always get their own call so complexity
+    /// routing still applies.
+    ///
+    /// `deliveries` maps `(agent, tick)` to the message each perception
+    /// arrived in; each is settled as soon as its action is submitted.
+    async fn decide_collected(
+        &self,
+        perceptions: &[(u64, Perception)],
+        deliveries: &BTreeMap<(AgentId, u64), &PerceptionMessage>,
+    ) {
+        let delivery =
+            |agent_id: AgentId, tick: u64| deliveries.get(&(agent_id, tick)).copied();
+        let mut critical: Vec<(u64, &Perception, AgentPersona)> = Vec::new();
+        let mut batchable: Vec<(u64, &Perception, AgentPersona)> = Vec::new();
+        let mut individual: Vec<(u64, &Perception, AgentPersona)> = Vec::new();
+        for (tick, perception) in perceptions {
+            let persona = self.persona_store.observe(perception);
+            if let Some(action) = self.try_fast_path(*tick, perception) {
+                let message = delivery(action.agent_id, *tick);
+                self.submit_and_settle(*tick, &action, message)
+                    .instrument(decision_span(*tick, action.agent_id, message))
+                    .await;
+            } else if rule_engine::in_heuristic_tier(
+                perception.self_state.id,
+                self.heuristic_agent_percent,
+            ) {
+                individual.push((*tick, perception, persona));
+            } else if score_priority(perception) == DecisionPriority::Critical {
+                critical.push((*tick, perception, persona));
+            } else if self.complexity_rules.assess(perception).level == ComplexityLevel::Low {
+                batchable.push((*tick, perception, persona));
+            } else {
+                individual.push((*tick, perception, persona));
+            }
+        }
+
+        for (tick, perception, persona) in &critical {
+            let message = delivery(perception.self_state.id, *tick);
+            self.decide_llm_and_submit(*tick, perception, persona, message).await;
+        }
+
+        if batchable.len() < 2 {
+            individual.append(&mut batchable);
+        } else {
+            let batch_span = tracing::info_span!("agent_decision_batch", agents = batchable.len());
+            for action in self.decide_batch(&batchable).instrument(batch_span).await {
+                let message = delivery(action.agent_id, action.tick);
+                self.submit_and_settle(action.tick, &action, message)
+                    .instrument(decision_span(action.tick, action.agent_id, message))
+                    .await;
+            }
+        }
+
+        for (tick, perception, persona) in &individual {
+            let message = delivery(perception.self_state.id, *tick);
+            self.decide_llm_and_submit(*tick, perception, persona, message).await;
+        }
+
+        for (tick, perception) in perceptions {
+            self.reflect(*tick, perception).await;
+        }
+    }
+
+    /// Decide one agent through the LLM pipeline and submit the action,
+    /// inside its `agent_decision` span.
+    async fn decide_llm_and_submit(
+        &self,
+        tick: u64,
+        perception: &Perception,
+        persona: &AgentPersona,
+        delivery: Option<&PerceptionMessage>,
+    ) {
+        let span = decision_span(tick, perception.self_state.id, delivery);
+        async {
+            let action = self.decide_llm(tick, perception, persona).await;
+            self.submit_and_settle(tick, &action, delivery).await;
+        }
+        .instrument(span)
+        .await;
+    }
+
+    /// Run the agent's reflection if one is due this tick.
+    ///
+    /// Called after the tick's action is submitted, so a slow reflection
+    /// never costs the agent its action. Agents in the no-LLM tier or over
+    /// budget skip it, as do agents with no memories yet. Failures are
+    /// logged and dropped; the agent simply reflects again next interval.
+    /// New goals are also recorded in the persona store.
+    async fn reflect(&self, tick: u64, perception: &Perception) {
+        let agent_id = perception.self_state.id;
+        if !reflection::reflection_due(agent_id, tick, self.reflection_interval)
+            || rule_engine::in_heuristic_tier(agent_id, self.heuristic_agent_percent)
+        {
+            return;
+        }
+        if let Some(tracker) = &self.budget {
+            tracker.begin_tick(tick);
+            if let Some(cap) = tracker.exhausted_cap(score_priority(perception)) {
+                debug!(
+                    agent_id = %agent_id,
+                    tick = tick,
+                    cap = cap,
+                    "skipping reflection, over budget"
+                );
+                return;
+            }
+        }
+
+        let persona = self.persona_store.get(agent_id);
+        let Some(prompt) = reflection::reflection_prompt(perception, &persona) else {
+            return;
+        };
+
+        let raw_response =
+            match timeout(self.decision_timeout, self.primary_backend.complete(&prompt)).await {
+                Ok(Ok(raw)) => raw,
+                Ok(Err(e)) => {
+                    warn!(agent_id = %agent_id, tick = tick, error = %e, "reflection call failed");
+                    return;
+                }
+                Err(_) => {
+                    warn!(agent_id = %agent_id, tick = tick, "reflection call exceeded deadline");
+                    return;
+                }
+            };
+
+        let containment_result = containment::scan_response(&raw_response);
+        if containment_result.threats_detected {
+            warn!(
+                agent_id = %agent_id,
+                tick = tick,
+                threat_count = containment_result.findings.len(),
+                "containment: threats detected in reflection response"
+            );
+        }
+
+        let Some(update) = reflection::parse_reflection(&raw_response, agent_id, tick) else {
+            warn!(agent_id = %agent_id, tick = tick, "reflection response had no goals or summary");
+            return;
+        };
+        self.persona_store.record_goals(agent_id, &update.goals);
+        if let Err(e) = self.nats.publish_reflection(tick, &update).await {
+            warn!(agent_id = %agent_id, tick = tick, error = %e, "failed to publish reflection");
+            return;
+        }
+        info!(
+            agent_id = %agent_id,
+            tick = tick,
+            goals = ?update.goals,
+            summarized = update.memory_summary.is_some(),
+            "reflection submitted"
+        );
+    }
+
+    /// Decide for several low-complexity agents with one LLM call.
+    ///
+    /// Each agent's prompt is rendered as usual (persona, assigned variant)
+    /// and packed into a delimited batch prompt. The answer is split back
+    /// per agent and parsed with the normal recovery strategies. Agents
+    /// whose prompt fails to render, or who are missing from the answer,
+    /// and every agent when the call itself fails, fall back to the
+    /// heuristic policy. Agents over budget are left out of the call.
+    async fn decide_batch(&self, batch: &[(u64, &Perception, AgentPersona)]) -> Vec<ActionRequest> {
+        let mut actions = Vec::with_capacity(batch.len());
+        let mut members: Vec<(u64, &Perception, RenderedPrompt, &PromptVariant)> =
+            Vec::with_capacity(batch.len());
+        for (tick, perception, persona) in batch {
+            if let Some(action) = self.budget_bypass(*tick, perception) {
+                actions.push(action);
+                continue;
+            }
+            rule_engine::reset_loop_detection(perception.self_state.id);
+            match self.render_prompt(perception, persona) {
+                Ok((prompt, variant)) => members.push((*tick, perception, prompt, variant)),
+                Err(e) => actions.push(self.fallback_decision(*tick, perception, &e)),
+            }
+        }
+
+        if members.is_empty() {
+            return actions;
+        }
+
+        let prompts: Vec<&RenderedPrompt> = members.iter().map(|(_, _, p, _)| p).collect();
+        let agents: Vec<AgentId> = members.iter().map(|(_, p, _, _)| p.self_state.id).collect();
+        let prompt = batch_prompt(&prompts);
+
+        let start = Instant::now();
+        let raw_response =
+            match timeout(self.decision_timeout, self.primary_backend.complete(&prompt)).await {
+                Ok(Ok(raw)) => raw,
+                Ok(Err(e)) => {
+                    warn!(batch_size = agents.len(), error = %e, "batched LLM call failed");
+                    for (tick, perception, _, _) in &members {
+                        actions.push(self.fallback_decision(*tick, perception, &e));
+                    }
+                    return actions;
+                }
+                Err(_) => {
+                    let e = RunnerError::Timeout;
+                    warn!(batch_size = agents.len(), "batched LLM call exceeded deadline");
+                    for (tick, perception, _, _) in &members {
+                        actions.push(self.fallback_decision(*tick, perception, &e));
+                    }
+                    return actions;
+                }
+            };
+        // LLM calls take at most a few seconds; millis will never exceed u64.
+        #[allow(clippy::cast_possible_truncation)]
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        let containment_result = containment::scan_response(&raw_response);
+        if containment_result.threats_detected {
+            warn!(
+                batch_size = agents.len(),
+                threat_count = containment_result.findings.len(),
+                "containment: threats detected in batched LLM response"
+            );
+        }
+
+        let mut split = split_batch_response(&raw_response, &agents);
+        info!(
+            batch_size = agents.len(),
+            answered = split.len(),
+            latency_ms = latency_ms,
+            "batched decision parsed"
+        );
+        let backend_name = format!("{} (batch of {})", self.primary_backend.name(), agents.len());
+
+        for (tick, perception, prompt, variant) in &members {
+            let agent_id = perception.self_state.id;
+            let Some(agent_raw) = split.remove(&agent_id) else {
+                let e = RunnerError::Parse("agent missing from batched response".to_owned());
+                actions.push(self.fallback_decision(*tick, perception, &e));
+                continue;
+            };
+            let decision = parse_llm_response(
+                &agent_raw,
+                &perception.known_routes,
+                &agent_name_map(perception),
+            );
+            let reasoning = decision
+                .full_reasoning()
+                .map(|r| truncate_string(&r, MAX_REASONING_LEN));
+            let action = action_from_decision(agent_id, *tick, decision);
+            let prompt_text = format!("{}\n\n{}", prompt.stable_prefix(), prompt.user);
+            let meta = LlmDecisionMeta {
+                prompt_sent: truncate_string(&prompt_text, MAX_PROMPT_LEN),
+                raw_response: truncate_string(&agent_raw, MAX_RAW_RESPONSE_LEN),
+                reasoning,
+                backend_name: backend_name.clone(),
+                latency_ms,
+                perception: serde_json::to_value(*perception).unwrap_or_default(),
+                prompt_variant: variant.name().to_owned(),
+                prompt_version: variant.version().to_owned(),
+            };
+            self.persona_store.record_goals(agent_id, &action.goal_updates);
+            self.publish_decision_record(&action, DecisionSource::Llm, Some(&meta), None);
+            actions.push(action);
+        }
+        actions
     }
 
     /// Execute the full decision pipeline for a single agent tick.
@@ -198,9 +695,27 @@ impl AgentRunner {
     /// LLM pipeline with timeout. If the deadline is exceeded, returns a
     /// `NoAction` request so the agent does not miss the tick.
     ///
+    /// If the LLM pipeline fails outright (every backend errored or was
+    /// tripped), the heuristic policy chooses the action instead.
+    ///
+    /// Every perception is folded into the agent's persona first, so
+    /// memories seen on rule-engine ticks are still remembered later.
+    ///
     /// After each decision (regardless of source), publishes a
     /// [`DecisionRecord`] to NATS for the Observer dashboard.
     async fn decide(&self, tick: u64, perception: &Perception) -> ActionRequest {
+        let persona = self.persona_store.observe(perception);
+        if let Some(action) = self.try_fast_path(tick, perception) {
+            return action;
+        }
+        self.decide_llm(tick, perception, &persona).await
+    }
+
+    /// Night cycle and routine action fast paths.
+    ///
+    /// Returns the action (already recorded) if a rule applies, or `None`
+    /// when the decision needs the LLM.
+    fn try_fast_path(&self, tick: u64, perception: &Perception) -> Option<ActionRequest> {
         let agent_id = perception.self_state.id;
 
         // Fast-path: night cycle optimization (task 6.2.4)
@@ -222,7 +737,7 @@ impl AgentRunner {
                 None,
                 Some("night_rest"),
             );
-            return action;
+            return Some(action);
         }
 
         // Fast-path: routine action bypass (task 6.2.1)
@@ -242,15 +757,45 @@ impl AgentRunner {
                 None,
                 Some(&format!("{:?}", action.action_type)),
             );
+            return Some(action);
+        }
+
+        None
+    }
+
+    /// The LLM pipeline with timeout, heuristic fallback, and decision
+    /// record publishing.
+    ///
+    /// Agents in the no-LLM tier are decided by the heuristic policy here
+    /// instead.
+    async fn decide_llm(
+        &self,
+        tick: u64,
+        perception: &Perception,
+        persona: &AgentPersona,
+    ) -> ActionRequest {
+        if rule_engine::in_heuristic_tier(perception.self_state.id, self.heuristic_agent_percent) {
+            return self.policy_decision(tick, perception);
+        }
+        if let Some(action) = self.budget_bypass(tick, perception) {
             return action;
         }
 
+        let agent_id = perception.self_state.id;
+
         // If we get here, the LLM is making the decision -- reset loop detection.
         rule_engine::reset_loop_detection(agent_id);
 
         // Full LLM pipeline with timeout
-        match timeout(self.decision_timeout, self.decide_inner(tick, perception)).await {
+        match timeout(
+            self.decision_timeout,
+            self.decide_inner(tick, perception, persona),
+        )
+        .await
+        {
             Ok(Ok((action, meta))) => {
+                self.persona_store
+                    .record_goals(agent_id, &action.goal_updates);
                 debug!(
                     agent_id = %agent_id,
                     tick = tick,
@@ -265,22 +810,7 @@ impl AgentRunner {
                 );
                 action
             }
-            Ok(Err(e)) => {
-                warn!(
-                    agent_id = %agent_id,
-                    tick = tick,
-                    error = %e,
-                    "decision pipeline failed, submitting NoAction"
-                );
-                let action = no_action_request(agent_id, tick);
-                self.publish_decision_record(
-                    &action,
-                    DecisionSource::Llm,
-                    None,
-                    None,
-                );
-                action
-            }
+            Ok(Err(e)) => self.fallback_decision(tick, perception, &e),
             Err(_) => {
                 warn!(
                     agent_id = %agent_id,
@@ -300,10 +830,92 @@ impl AgentRunner {
         }
     }
 
+    /// Decide for an agent in the no-LLM tier.
+    ///
+    /// Uses the heuristic policy, or `NoAction` if it has nothing to offer.
+    fn policy_decision(&self, tick: u64, perception: &Perception) -> ActionRequest {
+        let agent_id = perception.self_state.id;
+        let (rule, action) = rule_engine::heuristic_policy(perception).unwrap_or_else(|| {
+            ("no_action".to_owned(), no_action_request(agent_id, tick))
+        });
+        debug!(
+            agent_id = %agent_id,
+            tick = tick,
+            rule = rule,
+            action_type = ?action.action_type,
+            decision_source = DecisionSource::Policy.as_str(),
+            "decision made by heuristic policy tier"
+        );
+        self.publish_decision_record(&action, DecisionSource::Policy, None, Some(&rule));
+        action
+    }
+
+    /// Budget check before an LLM decision.
+    ///
+    /// Returns the bypass action (already recorded) when the agent's
+    /// priority has no budget left this tick or day, or `None` when the
+    /// LLM call may proceed. The bypass uses the heuristic survival policy
+    /// when it has a matching rule, and `NoAction` otherwise.
+    fn budget_bypass(&self, tick: u64, perception: &Perception) -> Option<ActionRequest> {
+        let tracker = self.budget.as_ref()?;
+        tracker.begin_tick(tick);
+        let priority = score_priority(perception);
+        let cap = tracker.exhausted_cap(priority)?;
+
+        let agent_id = perception.self_state.id;
+        let action = rule_engine::heuristic_policy(perception)
+            .map_or_else(|| no_action_request(agent_id, tick), |(_, action)| action);
+        info!(
+            agent_id = %agent_id,
+            tick = tick,
+            priority = %priority,
+            cap = cap,
+            action_type = ?action.action_type,
+            decision_source = DecisionSource::Budget.as_str(),
+            "LLM budget exhausted, using bypass policy"
+        );
+        self.publish_decision_record(&action, DecisionSource::Budget, None, Some(cap));
+        Some(action)
+    }
+
+    /// Chooses an action after the LLM pipeline failed outright.
+    ///
+    /// Uses the heuristic survival policy when it has a matching rule, and
+    /// `NoAction` otherwise.
+    fn fallback_decision(
+        &self,
+        tick: u64,
+        perception: &Perception,
+        error: &RunnerError,
+    ) -> ActionRequest {
+        let agent_id = perception.self_state.id;
+        if let Some((rule, action)) = rule_engine::heuristic_policy(perception) {
+            warn!(
+                agent_id = %agent_id,
+                tick = tick,
+                error = %error,
+                rule = rule,
+                decision_source = DecisionSource::Heuristic.as_str(),
+                "decision pipeline failed, using heuristic policy"
+            );
+            self.publish_decision_record(&action, DecisionSource::Heuristic, None, Some(&rule));
+            return action;
+        }
+        warn!(
+            agent_id = %agent_id,
+            tick = tick,
+            error = %error,
+            "decision pipeline failed, submitting NoAction"
+        );
+        let action = no_action_request(agent_id, tick);
+        self.publish_decision_record(&action, DecisionSource::Llm, None, None);
+        action
+    }
+
     /// Inner decision logic (without timeout wrapper).
     ///
     /// 1. Score decision complexity
-    /// 2. Serialize perception and render prompt
+    /// 2. Serialize perception, merge the agent's persona, and render prompt
     /// 3. Call LLM with complexity-aware backend routing
     /// 4. Parse response into action
     ///
@@ -312,24 +924,26 @@ impl AgentRunner {
         &self,
         tick: u64,
         perception: &Perception,
+        persona: &AgentPersona,
     ) -> Result<(ActionRequest, LlmDecisionMeta), RunnerError> {
         let agent_id = perception.self_state.id;
 
-        // Step 1: Score complexity
-        let complexity = score_complexity(perception);
+        // Step 1: Score complexity, logging the rules that fired for tuning
+        let assessment = self.complexity_rules.assess(perception);
+        let complexity = assessment.level;
 
-        debug!(
+        info!(
             agent_id = %agent_id,
             tick = tick,
             complexity = %complexity,
+            score = assessment.score,
+            rules_fired = %assessment.fired_summary(),
             "decision complexity scored"
         );
 
-        // Step 2: Serialize perception to JSON for template rendering
-        let perception_json = serde_json::to_value(perception)?;
-
-        // Step 3: Render prompt
-        let prompt = self.prompt_engine.render(&perception_json)?;
+        // Step 2-3: Serialize perception, merge persona, and render prompt
+        // with the agent's assigned template variant
+        let (prompt, variant) = self.render_prompt(perception, persona)?;
 
         // Step 4: Call LLM with complexity-aware backend selection
         let start = Instant::now();
@@ -353,13 +967,10 @@ impl AgentRunner {
 
         // Step 6: Parse the response (pass known_routes for Move name→UUID fallback,
         // and agent name map for target_agent name→UUID fallback)
-        let agent_name_map: std::collections::BTreeMap<String, AgentId> = perception
-            .surroundings
-            .agents_here
-            .iter()
-            .map(|a| (a.name.clone(), a.id))
-            .collect();
-        let decision = parse_llm_response(&raw_response, &perception.known_routes, &agent_name_map);
+        let agent_name_map = agent_name_map(perception);
+        let decision = self
+            .parse_with_repair(agent_id, tick, &raw_response, perception, &agent_name_map)
+            .await;
 
         // Step 7: Scan communication messages for exploitation (Phase 5.4.3)
         if let ActionParameters::Communicate { ref message, .. }
@@ -386,26 +997,82 @@ impl AgentRunner {
             "decision parsed"
         );
 
-        let prompt_text = format!("{}\n\n{}", prompt.system, prompt.user);
+        let prompt_text = format!("{}\n\n{}", prompt.stable_prefix(), prompt.user);
 
         let meta = LlmDecisionMeta {
             prompt_sent: truncate_string(&prompt_text, MAX_PROMPT_LEN),
             raw_response: truncate_string(&raw_response, MAX_RAW_RESPONSE_LEN),
+            reasoning: decision
+                .full_reasoning()
+                .map(|r| truncate_string(&r, MAX_REASONING_LEN)),
             backend_name,
             latency_ms,
+            perception: serde_json::to_value(perception).unwrap_or_default(),
+            prompt_variant: variant.name().to_owned(),
+            prompt_version: variant.version().to_owned(),
         };
 
-        Ok((
-            ActionRequest {
-                agent_id,
-                tick,
-                action_type: decision.action_type,
-                parameters: decision.parameters,
-                submitted_at: Utc::now(),
-                goal_updates: decision.goal_updates,
-            },
-            meta,
-        ))
+        Ok((action_from_decision(agent_id, tick, decision), meta))
+    }
+
+    /// Serialize a perception, merge the agent's persona into it, and
+    /// render it with the agent's assigned template variant.
+    fn render_prompt(
+        &self,
+        perception: &Perception,
+        persona: &AgentPersona,
+    ) -> Result<(RenderedPrompt, &PromptVariant), RunnerError> {
+        let mut perception_json = serde_json::to_value(perception)?;
+        persona.merge_into(perception, &mut perception_json);
+        let variant = self.prompt_engine.variant_for(&perception.self_state.id);
+        Ok((variant.render(&perception_json)?, variant))
+    }
+
+    /// Parse a raw response, re-prompting once for a JSON fix if enabled.
+    ///
+    /// Extraction and mechanical repair run first. If they fail and the
+    /// re-prompt is enabled, the failure and raw text go back to the primary
+    /// backend; its answer is parsed with the usual `NoAction` fallback,
+    /// keeping the original response's explanation when the fix has none.
+    async fn parse_with_repair(
+        &self,
+        agent_id: AgentId,
+        tick: u64,
+        raw_response: &str,
+        perception: &Perception,
+        agent_name_map: &BTreeMap<String, AgentId>,
+    ) -> ParsedDecision {
+        let routes = &perception.known_routes;
+        let err = match try_parse_llm_response(raw_response, routes, agent_name_map) {
+            Ok(decision) => return decision,
+            Err(e) if self.parse_repair_reprompt => e,
+            Err(_) => return parse_llm_response(raw_response, routes, agent_name_map),
+        };
+
+        warn!(
+            agent_id = %agent_id,
+            tick = tick,
+            error = %err,
+            "parse failed after mechanical repair, re-prompting for JSON fix"
+        );
+        match self.primary_backend.complete(&repair_prompt(raw_response, &err)).await {
+            Ok(fixed) => {
+                let mut decision = parse_llm_response(&fixed, routes, agent_name_map);
+                if decision.explanation.is_none() {
+                    decision.explanation = split_reasoning(raw_response).1;
+                }
+                decision
+            }
+            Err(reprompt_err) => {
+                warn!(
+                    agent_id = %agent_id,
+                    tick = tick,
+                    error = %reprompt_err,
+                    "repair re-prompt failed"
+                );
+                parse_llm_response(raw_response, routes, agent_name_map)
+            }
+        }
     }
 
     /// Call the LLM with complexity-aware backend routing and fallback.
@@ -427,7 +1094,7 @@ impl AgentRunner {
         &self,
         agent_id: AgentId,
         complexity: ComplexityLevel,
-        prompt: &crate::prompt::RenderedPrompt,
+        prompt: &RenderedPrompt,
     ) -> Result<(String, String), RunnerError> {
         let use_escalation_first = self.complexity_routing_enabled
             && complexity == ComplexityLevel::High
@@ -448,7 +1115,7 @@ impl AgentRunner {
     async fn call_primary_then_escalation(
         &self,
         agent_id: AgentId,
-        prompt: &crate::prompt::RenderedPrompt,
+        prompt: &RenderedPrompt,
     ) -> Result<(String, String), RunnerError> {
         match self.primary_backend.complete(prompt).await {
             Ok(response) => {
@@ -479,7 +1146,7 @@ impl AgentRunner {
     async fn call_escalation_then_primary(
         &self,
         agent_id: AgentId,
-        prompt: &crate::prompt::RenderedPrompt,
+        prompt: &RenderedPrompt,
     ) -> Result<(String, String), RunnerError> {
         if let Some(escalation) = &self.escalation_backend {
             match escalation.complete(prompt).await {
@@ -534,7 +1201,7 @@ impl AgentRunner {
     async fn try_escalation_fallback(
         &self,
         agent_id: AgentId,
-        prompt: &crate::prompt::RenderedPrompt,
+        prompt: &RenderedPrompt,
     ) -> Result<(String, String), RunnerError> {
         if let Some(escalation) = &self.escalation_backend {
             match escalation.complete(prompt).await {
@@ -610,8 +1277,13 @@ impl AgentRunner {
             cost_usd: None,
             latency_ms: llm_meta.map(|m| m.latency_ms),
             raw_llm_response: llm_meta.map(|m| m.raw_response.clone()),
+            reasoning: llm_meta.and_then(|m| m.reasoning.clone()),
             prompt_sent: llm_meta.map(|m| m.prompt_sent.clone()),
             rule_matched: rule_matched.map(ToOwned::to_owned),
+            prompt_variant: llm_meta.map(|m| m.prompt_variant.clone()),
+            prompt_version: llm_meta.map(|m| m.prompt_version.clone()),
+            perception: llm_meta.map(|m| m.perception.clone()),
+            outcome: None,
             created_at: Utc::now(),
         };
 
@@ -620,6 +1292,21 @@ impl AgentRunner {
 }
 
 /// Truncate a string to at most `max_len` bytes on a valid UTF-8 boundary.
+/// Span for one agent's decision, continuing the trace the engine started
+/// when it published the perception.
+fn decision_span(
+    tick: u64,
+    agent_id: AgentId,
+    delivery: Option<&PerceptionMessage>,
+) -> tracing::Span {
+    let span = tracing::info_span!("agent_decision", tick, agent_id = %agent_id);
+    emergence_telemetry::set_parent_from_headers(
+        &span,
+        delivery.and_then(|message| message.message().headers.as_ref()),
+    );
+    span
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_owned()
@@ -635,6 +1322,28 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Visible agents by name, for resolving `target_agent` names to IDs.
+fn agent_name_map(perception: &Perception) -> BTreeMap<String, AgentId> {
+    perception
+        .surroundings
+        .agents_here
+        .iter()
+        .map(|a| (a.name.clone(), a.id))
+        .collect()
+}
+
+/// Turn a parsed decision into an action request for the World Engine.
+fn action_from_decision(agent_id: AgentId, tick: u64, decision: ParsedDecision) -> ActionRequest {
+    ActionRequest {
+        agent_id,
+        tick,
+        action_type: decision.action_type,
+        parameters: decision.parameters,
+        submitted_at: Utc::now(),
+        goal_updates: decision.goal_updates,
+    }
+}
+
 /// Construct a `NoAction` request for an agent that could not decide.
 fn no_action_request(agent_id: AgentId, tick: u64) -> ActionRequest {
     ActionRequest {
@@ -650,7 +1359,7 @@ fn no_action_request(agent_id: AgentId, tick: u64) -> ActionRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::prompt::RenderedPrompt;
+    use crate::complexity::score_complexity;
 
     fn test_perception() -> Perception {
         serde_json::from_value(serde_json::json!({
@@ -710,12 +1419,15 @@ mod tests {
                     structures_here: Vec::new(),
                     agents_here: Vec::new(),
                     messages_here: Vec::new(),
+                    overcrowded: false,
+                    pollution: 0,
                 },
                 known_routes: Vec::new(),
                 recent_memory: Vec::new(),
                 available_actions: vec!["gather".to_owned(), "rest".to_owned(), "move".to_owned()],
                 notifications: Vec::new(),
                 personality: None,
+                forecast: Vec::new(),
             }
         })
     }
@@ -756,6 +1468,7 @@ mod tests {
         let prompt = prompt_engine.render(&perception_json).unwrap_or_else(|_| {
             RenderedPrompt {
                 system: String::new(),
+                persona: String::new(),
                 user: String::new(),
             }
         });
@@ -806,6 +1519,7 @@ mod tests {
         let _prompt = prompt_engine.render(&perception_json).unwrap_or_else(|_| {
             RenderedPrompt {
                 system: String::new(),
+                persona: String::new(),
                 user: String::new(),
             }
         });
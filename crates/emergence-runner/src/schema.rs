@@ -0,0 +1,566 @@
+//! JSON schema for the agent action response.
+//!
+//! Backends running in [`OutputMode::Structured`](crate::config::OutputMode)
+//! hand this schema to the provider (`response_format: json_schema` for
+//! `OpenAI`-compatible APIs, a forced tool call for Anthropic) so the model
+//! is constrained to emit a well-formed action object instead of free text.
+//!
+//! The schema mirrors the shape consumed by [`crate::parse`]:
+//!
+//! ```text
+//! {
+//!   "action_type": "<ActionType variant>",
+//!   "parameters": { ... fields of the matching ActionParameters variant ... },
+//!   "reasoning": "...",
+//!   "goal_update": ["..."]
+//! }
+//! ```
+//!
+//! Parameter schemas are derived per [`ActionType`] with an exhaustive
+//! match, so adding a new action variant without a schema fails to compile.
+//!
+//! The same schemas drive the parser's repair layer: [`repair_parameters`]
+//! canonicalizes enum casing in place, and [`validate_parameters`] reports
+//! missing or mistyped fields before typed deserialization is attempted.
+
+use emergence_types::{ActionType, Resource, StructureType};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Name of the schema / tool presented to the provider.
+pub const ACTION_SCHEMA_NAME: &str = "submit_action";
+
+/// Every [`ActionType`] an agent may choose, in declaration order.
+///
+/// Derived from [`ActionType::ALL`], so new variants join the schema
+/// without a second list to keep in sync.
+pub fn choosable_action_types() -> impl Iterator<Item = ActionType> {
+    ActionType::ALL
+        .iter()
+        .copied()
+        .filter(|at| *at != ActionType::NoAction)
+}
+
+/// Serialized names of every variant in `variants`.
+fn variant_names<T: Serialize>(variants: &[T]) -> Vec<String> {
+    variants
+        .iter()
+        .filter_map(|v| match serde_json::to_value(v) {
+            Ok(Value::String(name)) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Serialized names of every `Resource` variant.
+fn resource_names() -> Vec<String> {
+    variant_names(Resource::ALL)
+}
+
+/// Serialized names of every `StructureType` variant.
+fn structure_type_names() -> Vec<String> {
+    variant_names(StructureType::ALL)
+}
+
+/// Every serialized `ActionType` name, including `NoAction`.
+fn action_type_names() -> Vec<String> {
+    variant_names(ActionType::ALL)
+}
+
+/// Build the full JSON schema for an action response.
+///
+/// The top level is always a plain `object` (Anthropic rejects `oneOf`
+/// at the root of a tool input schema). `parameters` is an `anyOf` over
+/// the per-action parameter schemas; the parser resolves which variant
+/// applies from `action_type`.
+pub fn action_response_schema() -> Value {
+    let action_names = action_type_names();
+
+    let parameter_variants: Vec<Value> = choosable_action_types().map(parameters_schema).collect();
+
+    json!({
+        "type": "object",
+        "properties": {
+            "action_type": {
+                "type": "string",
+                "enum": action_names,
+                "description": "The single action to take this tick."
+            },
+            "parameters": {
+                "anyOf": parameter_variants,
+                "description": "Parameters for the chosen action_type."
+            },
+            "reasoning": {
+                "type": "string",
+                "description": "Why you chose this action."
+            },
+            "goal_update": {
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "Any new or updated goals."
+            }
+        },
+        "required": ["action_type", "parameters"],
+        "additionalProperties": false
+    })
+}
+
+/// Build the parameter object schema for a single [`ActionType`].
+///
+/// Field names and types follow the matching `ActionParameters` variant.
+/// Unit variants (`Drink`, `Rest`, `NoAction`, ...) produce an empty object.
+#[allow(clippy::too_many_lines)] // One arm per parameter shape; splitting would scatter the table.
+pub fn parameters_schema(action_type: ActionType) -> Value {
+    match action_type {
+        ActionType::Gather => object(&[("resource", resource())]),
+        ActionType::Eat => object(&[("food_type", resource())]),
+        ActionType::Move
+        | ActionType::ImproveRoute
+        | ActionType::BuildRoute
+        | ActionType::Breach => {
+            object(&[("destination", uuid())])
+        }
+        ActionType::Build => object(&[(
+            "structure_type",
+            json!({"type": "string", "enum": structure_type_names()}),
+        )]),
+        ActionType::Repair
+        | ActionType::Demolish
+        | ActionType::UpgradeStructure
+        | ActionType::Claim => object(&[("structure_id", uuid())]),
+        ActionType::TransferOwnership => {
+            object(&[("structure_id", uuid()), ("new_owner", uuid())])
+        }
+        ActionType::Deposit | ActionType::Withdraw => object(&[
+            ("structure_id", uuid()),
+            ("resource", resource()),
+            ("quantity", json!({"type": "integer", "minimum": 1})),
+        ]),
+        ActionType::Communicate => object(&[("target_agent", uuid()), ("message", text())]),
+        ActionType::Broadcast => object(&[("message", text())]),
+        ActionType::TradeOffer => object(&[
+            ("target_agent", uuid()),
+            ("offer", resource_map()),
+            ("request", resource_map()),
+        ]),
+        ActionType::TradeAccept | ActionType::TradeReject => object(&[("trade_id", uuid())]),
+        ActionType::PostOrder => object(&[
+            ("market_id", uuid()),
+            ("side", json!({"type": "string", "enum": ["Buy", "Sell"]})),
+            ("resource", resource()),
+            ("quantity", json!({"type": "integer", "minimum": 1})),
+            ("price_resource", resource()),
+            ("unit_price", json!({"type": "integer", "minimum": 1})),
+        ]),
+        ActionType::FoundSettlement => object(&[("name", text())]),
+        ActionType::FormGroup => object(&[
+            ("name", text()),
+            ("invited_members", uuid_list()),
+        ]),
+        ActionType::Teach => object(&[("target_agent", uuid()), ("knowledge", text())]),
+        ActionType::Craft => object(&[("output", resource())]),
+        ActionType::Write | ActionType::Read => object(&[("knowledge", text())]),
+        ActionType::Legislate => object(&[
+            ("rule_name", text()),
+            ("rule_description", text()),
+            ("group_id", uuid()),
+        ]),
+        ActionType::Enforce => object(&[
+            ("target_agent", uuid()),
+            ("rule_id", uuid()),
+            ("consequence", text()),
+        ]),
+        ActionType::Reproduce | ActionType::Marry | ActionType::Divorce => {
+            object(&[("partner_agent", uuid())])
+        }
+        ActionType::Steal => object(&[("target_agent", uuid()), ("resource", resource())]),
+        ActionType::Attack | ActionType::Intimidate => object(&[("target_agent", uuid())]),
+        ActionType::Propose => object(&[("group_id", uuid()), ("proposal", text())]),
+        ActionType::Vote => object(&[("group_id", uuid()), ("in_favor", json!({"type": "boolean"}))]),
+        ActionType::Conspire => object(&[("co_conspirators", uuid_list()), ("plan", text())]),
+        ActionType::Pray => json!({
+            "type": "object",
+            "properties": {"intent": text()},
+            "additionalProperties": false
+        }),
+        ActionType::Freeform => json!({
+            "type": "object",
+            "properties": {
+                "intent": text(),
+                "action_category": text(),
+                "target": {"type": ["object", "null"]},
+                "parameters": {"type": "object"}
+            },
+            "required": ["intent", "action_category"],
+            "additionalProperties": false
+        }),
+        ActionType::Drink
+        | ActionType::Rest
+        | ActionType::FarmPlant
+        | ActionType::FarmHarvest
+        | ActionType::Fertilize
+        | ActionType::Slaughter
+        | ActionType::Mine
+        | ActionType::DeepenMine
+        | ActionType::Extinguish
+        | ActionType::Hunt
+        | ActionType::Prospect
+        | ActionType::Fish
+        | ActionType::Smelt
+        | ActionType::Chart
+        | ActionType::NoAction => json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }),
+    }
+}
+
+/// Resolve a loosely-cased action type name to an [`ActionType`].
+///
+/// Matches case-insensitively and ignores `_`, `-`, and spaces, so
+/// `"farm_plant"`, `"Farm Plant"`, and `"FARMPLANT"` all resolve to
+/// [`ActionType::FarmPlant`].
+pub fn canonical_action_type(raw: &str) -> Option<ActionType> {
+    let wanted = fold_name(raw);
+    ActionType::ALL
+        .iter()
+        .copied()
+        .find(|at| fold_name(&format!("{at:?}")) == wanted)
+}
+
+/// Canonicalize enum-valued fields of `params` against the schema for
+/// `action_type`.
+///
+/// String fields constrained by an `enum` are rewritten to the matching
+/// variant name (`"wood"` -> `"Wood"`, `"food_berry"` -> `"FoodBerry"`).
+/// Keys of resource quantity maps are rewritten the same way. Values that
+/// match nothing are left untouched so validation can report them.
+pub fn repair_parameters(action_type: ActionType, params: &Value) -> Value {
+    let schema = parameters_schema(action_type);
+    let (Some(obj), Some(properties)) = (
+        params.as_object(),
+        schema.get("properties").and_then(Value::as_object),
+    ) else {
+        return params.clone();
+    };
+
+    let mut repaired = obj.clone();
+    for (field, field_schema) in properties {
+        let Some(value) = repaired.get_mut(field) else {
+            continue;
+        };
+        if let Some(names) = enum_names(field_schema)
+            && let Some(raw) = value.as_str()
+            && let Some(canonical) = canonical_enum_value(raw, &names)
+        {
+            *value = Value::String(canonical);
+        }
+        if let Some(names) = field_schema.get("propertyNames").and_then(enum_names)
+            && let Some(map) = value.as_object()
+        {
+            let rekeyed: serde_json::Map<String, Value> = map
+                .iter()
+                .map(|(key, qty)| {
+                    let key = canonical_enum_value(key, &names).unwrap_or_else(|| key.clone());
+                    (key, qty.clone())
+                })
+                .collect();
+            *value = Value::Object(rekeyed);
+        }
+    }
+    Value::Object(repaired)
+}
+
+/// Check `params` against the schema for `action_type`.
+///
+/// Reports required fields that are missing and fields whose JSON type
+/// does not match. Format constraints (UUIDs) are not enforced here because
+/// the parser resolves agent and location names to IDs afterwards.
+///
+/// Returns the list of violations, or `Ok(())` when none were found.
+pub fn validate_parameters(action_type: ActionType, params: &Value) -> Result<(), Vec<String>> {
+    let schema = parameters_schema(action_type);
+    let empty = serde_json::Map::new();
+    let obj = match params {
+        Value::Object(obj) => obj,
+        Value::Null => &empty,
+        other => {
+            return Err(vec![format!(
+                "parameters must be an object, got {}",
+                json_type_name(other)
+            )]);
+        }
+    };
+
+    let mut violations = Vec::new();
+    for field in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if !obj.contains_key(field) {
+            violations.push(format!("missing required field '{field}'"));
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (field, value) in obj {
+        let Some(expected) = properties
+            .and_then(|p| p.get(field))
+            .and_then(|f| f.get("type"))
+        else {
+            continue;
+        };
+        if !type_matches(expected, value) {
+            violations.push(format!(
+                "field '{field}' should be {expected}, got {}",
+                json_type_name(value)
+            ));
+        }
+        if let Some(names) = properties.and_then(|p| p.get(field)).and_then(enum_names)
+            && let Some(raw) = value.as_str()
+            && !names.iter().any(|n| n == raw)
+        {
+            violations.push(format!("field '{field}' has unknown value '{raw}'"));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Lowercase and strip separators for loose name comparison.
+fn fold_name(raw: &str) -> String {
+    raw.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find the canonical spelling of `raw` among `names`.
+fn canonical_enum_value(raw: &str, names: &[String]) -> Option<String> {
+    let wanted = fold_name(raw);
+    names.iter().find(|n| fold_name(n) == wanted).cloned()
+}
+
+/// The string values of a schema's `enum` keyword, if present.
+fn enum_names(schema: &Value) -> Option<Vec<String>> {
+    schema.get("enum").and_then(Value::as_array).map(|values| {
+        values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(ToOwned::to_owned)
+            .collect()
+    })
+}
+
+/// Whether `value` satisfies a schema `type` keyword (string or array form).
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(t) => single_type_matches(t, value),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|t| single_type_matches(t, value)),
+        _ => true,
+    }
+}
+
+/// Whether `value` is of the single JSON schema type `expected`.
+fn single_type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// JSON type name of a value, for violation messages.
+const fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// An object schema where every listed field is required.
+fn object(fields: &[(&str, Value)]) -> Value {
+    let properties: serde_json::Map<String, Value> = fields
+        .iter()
+        .map(|(name, schema)| ((*name).to_owned(), schema.clone()))
+        .collect();
+    let required: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    })
+}
+
+/// Schema for an entity ID (agent, location, structure, trade, ...).
+fn uuid() -> Value {
+    json!({"type": "string", "format": "uuid"})
+}
+
+/// Schema for a list of entity IDs.
+fn uuid_list() -> Value {
+    json!({"type": "array", "items": uuid()})
+}
+
+/// Schema for free text (messages, names, descriptions).
+fn text() -> Value {
+    json!({"type": "string"})
+}
+
+/// Schema for a single `Resource` name.
+fn resource() -> Value {
+    json!({"type": "string", "enum": resource_names()})
+}
+
+/// Schema for a `BTreeMap<Resource, u32>` quantity map.
+fn resource_map() -> Value {
+    json!({
+        "type": "object",
+        "propertyNames": {"enum": resource_names()},
+        "additionalProperties": {"type": "integer", "minimum": 0}
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_names_round_trip() {
+        for name in resource_names() {
+            let parsed = serde_json::from_value::<Resource>(json!(name));
+            assert!(parsed.is_ok(), "'{name}' should be a valid Resource");
+        }
+    }
+
+    #[test]
+    fn structure_type_names_round_trip() {
+        for name in structure_type_names() {
+            let parsed = serde_json::from_value::<StructureType>(json!(name));
+            assert!(parsed.is_ok(), "'{name}' should be a valid StructureType");
+        }
+    }
+
+    #[test]
+    fn schema_lists_every_action_type() {
+        let schema = action_response_schema();
+        let names = schema
+            .pointer("/properties/action_type/enum")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(names.len(), ActionType::ALL.len());
+        for at in ActionType::ALL {
+            assert!(names.contains(&json!(format!("{at:?}"))), "{at:?} missing from schema");
+        }
+    }
+
+    #[test]
+    fn schema_lists_every_resource_and_structure_type() {
+        let gather = parameters_schema(ActionType::Gather);
+        let resources = gather
+            .pointer("/properties/resource/enum")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(resources.len(), Resource::ALL.len());
+        for resource in Resource::ALL {
+            assert!(resources.contains(&json!(format!("{resource:?}"))), "{resource:?} missing");
+        }
+
+        let build = parameters_schema(ActionType::Build);
+        let structures = build
+            .pointer("/properties/structure_type/enum")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(structures.len(), StructureType::ALL.len());
+        for st in [StructureType::Hearth, StructureType::House, StructureType::Longhouse] {
+            assert!(structures.contains(&json!(format!("{st:?}"))), "{st:?} missing");
+        }
+    }
+
+    #[test]
+    fn schema_root_is_plain_object() {
+        let schema = action_response_schema();
+        assert_eq!(schema.get("type"), Some(&json!("object")));
+        assert!(schema.get("oneOf").is_none());
+        assert!(schema.get("anyOf").is_none());
+    }
+
+    #[test]
+    fn canonical_action_type_loose_matching() {
+        assert_eq!(canonical_action_type("farm_plant"), Some(ActionType::FarmPlant));
+        assert_eq!(canonical_action_type("Farm Plant"), Some(ActionType::FarmPlant));
+        assert_eq!(canonical_action_type("STEAL"), Some(ActionType::Steal));
+        assert_eq!(canonical_action_type("no-action"), Some(ActionType::NoAction));
+        assert_eq!(canonical_action_type("dance"), None);
+    }
+
+    #[test]
+    fn repair_parameters_fixes_enum_casing() {
+        let repaired = repair_parameters(ActionType::Gather, &json!({"resource": "food_berry"}));
+        assert_eq!(repaired, json!({"resource": "FoodBerry"}));
+
+        let repaired = repair_parameters(
+            ActionType::TradeOffer,
+            &json!({"target_agent": "Iris", "offer": {"wood": 5}, "request": {"STONE": 2}}),
+        );
+        assert_eq!(repaired.pointer("/offer/Wood"), Some(&json!(5)));
+        assert_eq!(repaired.pointer("/request/Stone"), Some(&json!(2)));
+        assert_eq!(repaired.get("target_agent"), Some(&json!("Iris")));
+    }
+
+    #[test]
+    fn validate_parameters_reports_violations() {
+        assert!(validate_parameters(ActionType::Gather, &json!({"resource": "Wood"})).is_ok());
+        assert!(validate_parameters(ActionType::Rest, &Value::Null).is_ok());
+
+        let missing = validate_parameters(ActionType::Gather, &json!({})).err().unwrap_or_default();
+        assert_eq!(missing, vec!["missing required field 'resource'".to_owned()]);
+
+        let wrong_type = validate_parameters(ActionType::Vote, &json!({"group_id": "g", "in_favor": "yes"}))
+            .err()
+            .unwrap_or_default();
+        assert_eq!(wrong_type.len(), 1);
+
+        let unknown = validate_parameters(ActionType::Gather, &json!({"resource": "Gold"}))
+            .err()
+            .unwrap_or_default();
+        assert_eq!(unknown, vec!["field 'resource' has unknown value 'Gold'".to_owned()]);
+    }
+
+    #[test]
+    fn parameters_schema_matches_action_parameters_fields() {
+        let gather = parameters_schema(ActionType::Gather);
+        assert_eq!(gather.pointer("/required/0"), Some(&json!("resource")));
+
+        let trade = parameters_schema(ActionType::TradeOffer);
+        let required = trade.get("required").and_then(Value::as_array).cloned().unwrap_or_default();
+        assert_eq!(required.len(), 3);
+
+        let rest = parameters_schema(ActionType::Rest);
+        assert_eq!(rest.get("properties"), Some(&json!({})));
+    }
+}
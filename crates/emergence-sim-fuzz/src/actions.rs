@@ -0,0 +1,209 @@
+//! Randomized action streams.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeDelta};
+use emergence_core::decision::{DecisionError, DecisionSource};
+use emergence_types::{
+    ActionParameters, ActionRequest, ActionType, AgentId, FreeformAction, LocationId, Perception,
+    Resource, StructureId, StructureType, TradeId,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Action types drawn from when an action is deliberately mislabelled.
+const ACTION_TYPES: [ActionType; 16] = [
+    ActionType::Gather,
+    ActionType::Eat,
+    ActionType::Drink,
+    ActionType::Rest,
+    ActionType::Move,
+    ActionType::Build,
+    ActionType::Repair,
+    ActionType::Communicate,
+    ActionType::TradeOffer,
+    ActionType::TradeAccept,
+    ActionType::Teach,
+    ActionType::Craft,
+    ActionType::Steal,
+    ActionType::Attack,
+    ActionType::Marry,
+    ActionType::Freeform,
+];
+
+/// Resources drawn from for gathers, meals, trades, and theft.
+const RESOURCES: [Resource; 6] = [
+    Resource::Water,
+    Resource::FoodBerry,
+    Resource::FoodFish,
+    Resource::Wood,
+    Resource::Stone,
+    Resource::Fiber,
+];
+
+/// A decision source that answers for every agent with a random action.
+///
+/// Most actions are well-formed: parameters match the action type and
+/// name something the agent can see (a visible resource, a neighbouring
+/// location, another agent). With probability `invalid_rate` the action is
+/// malformed instead -- a mismatched action type, or a target that does
+/// not exist.
+pub struct RandomDecisionSource {
+    rng: StdRng,
+    routes: BTreeMap<String, Vec<LocationId>>,
+    invalid_rate: f64,
+}
+
+impl RandomDecisionSource {
+    /// Create a source seeded with `seed`. `routes` maps each location name
+    /// to its neighbours (see [`crate::world::routes_by_name`]).
+    pub fn new(seed: u64, routes: BTreeMap<String, Vec<LocationId>>, invalid_rate: f64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            routes,
+            invalid_rate,
+        }
+    }
+
+    fn parameters(
+        &mut self,
+        perception: &Perception,
+        agents: &[AgentId],
+        valid: bool,
+    ) -> ActionParameters {
+        let rng = &mut self.rng;
+        let resource = if valid {
+            let visible: Vec<Resource> =
+                perception.surroundings.visible_resources.keys().copied().collect();
+            pick(rng, &visible).unwrap_or(Resource::Water)
+        } else {
+            pick(rng, &RESOURCES).unwrap_or(Resource::Ore)
+        };
+        let other = pick(rng, agents)
+            .filter(|_| valid)
+            .unwrap_or_else(|| AgentId::from(uuid::Uuid::from_u128(rng.random())));
+        let destination = self
+            .routes
+            .get(&perception.self_state.location_name)
+            .and_then(|neighbors| pick(rng, neighbors))
+            .filter(|_| valid)
+            .unwrap_or_else(|| LocationId::from(uuid::Uuid::from_u128(rng.random())));
+
+        match rng.random_range(0_u8..20) {
+            0..=3 => ActionParameters::Gather { resource },
+            4 => ActionParameters::Eat {
+                food_type: Resource::FoodBerry,
+            },
+            5 => ActionParameters::Drink,
+            6 | 7 => ActionParameters::Rest,
+            8 | 9 => ActionParameters::Move { destination },
+            10 => ActionParameters::Build {
+                structure_type: StructureType::Campfire,
+            },
+            11 => ActionParameters::Repair {
+                structure_id: StructureId::new(),
+            },
+            12 => ActionParameters::Communicate {
+                target_agent: other,
+                message: String::from("hello"),
+            },
+            13 => ActionParameters::TradeOffer {
+                target_agent: other,
+                offer: BTreeMap::from([(resource, rng.random_range(0..=3))]),
+                request: BTreeMap::from([(Resource::Water, rng.random_range(0..=3))]),
+            },
+            14 => ActionParameters::TradeAccept {
+                trade_id: TradeId::new(),
+            },
+            15 => ActionParameters::Teach {
+                target_agent: other,
+                knowledge: String::from("fire_making"),
+            },
+            16 => ActionParameters::Steal {
+                target_agent: other,
+                resource,
+            },
+            17 => ActionParameters::Attack {
+                target_agent: other,
+            },
+            18 => ActionParameters::Freeform(Box::new(FreeformAction {
+                intent: String::from("dance around the fire"),
+                action_category: String::from("dance"),
+                target: None,
+                parameters: BTreeMap::new(),
+            })),
+            _ => ActionParameters::NoAction,
+        }
+    }
+}
+
+impl DecisionSource for RandomDecisionSource {
+    fn collect_decisions(
+        &mut self,
+        tick: u64,
+        perceptions: &BTreeMap<AgentId, Perception>,
+    ) -> Result<BTreeMap<AgentId, ActionRequest>, DecisionError> {
+        let agents: Vec<AgentId> = perceptions.keys().copied().collect();
+        let base = DateTime::from_timestamp(i64::try_from(tick).unwrap_or(i64::MAX), 0)
+            .unwrap_or_default();
+        let mut decisions = BTreeMap::new();
+
+        for (&agent_id, perception) in perceptions {
+            let valid = !chance(&mut self.rng, self.invalid_rate);
+            let parameters = self.parameters(perception, &agents, valid);
+            let action_type = if valid {
+                action_type_of(&parameters)
+            } else {
+                pick(&mut self.rng, &ACTION_TYPES).unwrap_or(ActionType::NoAction)
+            };
+            let offset = TimeDelta::milliseconds(self.rng.random_range(0..1000));
+            decisions.insert(
+                agent_id,
+                ActionRequest {
+                    agent_id,
+                    tick,
+                    action_type,
+                    parameters,
+                    submitted_at: base.checked_add_signed(offset).unwrap_or(base),
+                    goal_updates: Vec::new(),
+                },
+            );
+        }
+
+        Ok(decisions)
+    }
+}
+
+/// The action type matching the parameters this module generates.
+const fn action_type_of(parameters: &ActionParameters) -> ActionType {
+    match parameters {
+        ActionParameters::Gather { .. } => ActionType::Gather,
+        ActionParameters::Eat { .. } => ActionType::Eat,
+        ActionParameters::Drink => ActionType::Drink,
+        ActionParameters::Rest => ActionType::Rest,
+        ActionParameters::Move { .. } => ActionType::Move,
+        ActionParameters::Build { .. } => ActionType::Build,
+        ActionParameters::Repair { .. } => ActionType::Repair,
+        ActionParameters::Communicate { .. } => ActionType::Communicate,
+        ActionParameters::TradeOffer { .. } => ActionType::TradeOffer,
+        ActionParameters::TradeAccept { .. } => ActionType::TradeAccept,
+        ActionParameters::Teach { .. } => ActionType::Teach,
+        ActionParameters::Steal { .. } => ActionType::Steal,
+        ActionParameters::Attack { .. } => ActionType::Attack,
+        ActionParameters::Freeform(_) => ActionType::Freeform,
+        _ => ActionType::NoAction,
+    }
+}
+
+/// `true` with probability `p`; never panics on out-of-range `p`.
+pub fn chance(rng: &mut StdRng, p: f64) -> bool {
+    rng.random::<f64>() < p
+}
+
+/// A uniformly random element of `items`.
+pub fn pick<T: Copy>(rng: &mut StdRng, items: &[T]) -> Option<T> {
+    if items.is_empty() {
+        return None;
+    }
+    items.get(rng.random_range(0..items.len())).copied()
+}
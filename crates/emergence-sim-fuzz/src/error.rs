@@ -0,0 +1,20 @@
+//! Error types for the fuzz harness.
+//!
+//! These cover failures to *set up* a run. Problems found while running
+//! are reported as [`Violation`](crate::invariants::Violation)s instead.
+
+/// Errors that stop a fuzz run before it starts.
+#[derive(Debug, thiserror::Error)]
+pub enum FuzzError {
+    /// The starting world could not be built.
+    #[error("world error: {0}")]
+    World(#[from] emergence_world::WorldError),
+
+    /// The world clock could not be created.
+    #[error("clock error: {0}")]
+    Clock(#[from] emergence_core::clock::ClockError),
+
+    /// A seed agent could not be built.
+    #[error("failed to build seed agent: {0}")]
+    Agent(String),
+}
@@ -0,0 +1,228 @@
+//! Injected infrastructure failures.
+//!
+//! The tick cycle only meets the outside world through its decision
+//! source and whatever persists its state, so that is where faults go:
+//!
+//! - [`FaultyDecisionSource`] wraps a source and loses, delays, or
+//!   misaddresses decisions the way a lossy NATS link does, and now and
+//!   then fails outright;
+//! - [`FlakyStore`] stands in for the snapshot database and times out.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use emergence_core::decision::{DecisionError, DecisionSource};
+use emergence_core::experiment::SimulationSnapshot;
+use emergence_types::{ActionParameters, ActionRequest, ActionType, AgentId, Perception};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::actions::chance;
+
+/// Probabilities for each injected fault. All rates are per decision,
+/// except `batch_timeout_rate` and `source_error_rate` (per tick) and
+/// `persist_timeout_rate` (per snapshot write).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultConfig {
+    /// A decision never arrives (dropped NATS message).
+    pub drop_rate: f64,
+    /// A decision arrives stamped with the wrong tick (late delivery).
+    pub stale_rate: f64,
+    /// A reply arrives for an agent ID the engine does not know.
+    pub misaddress_rate: f64,
+    /// The whole batch misses the deadline and every agent gets `NoAction`.
+    pub batch_timeout_rate: f64,
+    /// The decision source fails and the tick aborts.
+    pub source_error_rate: f64,
+    /// A snapshot write times out and must be retried.
+    pub persist_timeout_rate: f64,
+}
+
+impl FaultConfig {
+    /// No faults at all.
+    pub const NONE: Self = Self {
+        drop_rate: 0.0,
+        stale_rate: 0.0,
+        misaddress_rate: 0.0,
+        batch_timeout_rate: 0.0,
+        source_error_rate: 0.0,
+        persist_timeout_rate: 0.0,
+    };
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            drop_rate: 0.05,
+            stale_rate: 0.02,
+            misaddress_rate: 0.02,
+            batch_timeout_rate: 0.02,
+            source_error_rate: 0.005,
+            persist_timeout_rate: 0.1,
+        }
+    }
+}
+
+/// How many of each fault were injected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultCounts {
+    /// Decisions dropped.
+    pub dropped: u64,
+    /// Decisions delivered with the wrong tick.
+    pub stale: u64,
+    /// Replies for unknown agents.
+    pub misaddressed: u64,
+    /// Whole-batch timeouts.
+    pub batch_timeouts: u64,
+    /// Aborted ticks.
+    pub source_errors: u64,
+    /// Snapshot write timeouts.
+    pub persist_timeouts: u64,
+}
+
+/// A [`DecisionSource`] wrapper that injects delivery faults.
+pub struct FaultyDecisionSource<S> {
+    inner: S,
+    config: FaultConfig,
+    rng: StdRng,
+    counts: FaultCounts,
+    failed: bool,
+}
+
+impl<S: DecisionSource> FaultyDecisionSource<S> {
+    /// Wrap `inner`, injecting faults from `config` seeded with `seed`.
+    pub fn new(inner: S, config: FaultConfig, seed: u64) -> Self {
+        Self {
+            inner,
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            counts: FaultCounts::default(),
+            failed: false,
+        }
+    }
+
+    /// Faults injected so far.
+    pub const fn counts(&self) -> FaultCounts {
+        self.counts
+    }
+
+    /// Whether the last error returned was injected, clearing the flag.
+    pub const fn take_injected_error(&mut self) -> bool {
+        let failed = self.failed;
+        self.failed = false;
+        failed
+    }
+}
+
+impl<S: DecisionSource> DecisionSource for FaultyDecisionSource<S> {
+    fn collect_decisions(
+        &mut self,
+        tick: u64,
+        perceptions: &BTreeMap<AgentId, Perception>,
+    ) -> Result<BTreeMap<AgentId, ActionRequest>, DecisionError> {
+        if chance(&mut self.rng, self.config.source_error_rate) {
+            self.counts.source_errors = self.counts.source_errors.saturating_add(1);
+            self.failed = true;
+            return Err(DecisionError::Internal {
+                message: String::from("injected fault: decision source unavailable"),
+            });
+        }
+
+        if chance(&mut self.rng, self.config.batch_timeout_rate) {
+            self.counts.batch_timeouts = self.counts.batch_timeouts.saturating_add(1);
+            return Ok(perceptions
+                .keys()
+                .map(|&agent_id| (agent_id, no_action(agent_id, tick)))
+                .collect());
+        }
+
+        let decisions = self.inner.collect_decisions(tick, perceptions)?;
+        let mut delivered = BTreeMap::new();
+        for (agent_id, mut request) in decisions {
+            if chance(&mut self.rng, self.config.drop_rate) {
+                self.counts.dropped = self.counts.dropped.saturating_add(1);
+                continue;
+            }
+            if chance(&mut self.rng, self.config.stale_rate) {
+                self.counts.stale = self.counts.stale.saturating_add(1);
+                request.tick = tick.saturating_sub(self.rng.random_range(1..=3));
+            }
+            if chance(&mut self.rng, self.config.misaddress_rate) {
+                self.counts.misaddressed = self.counts.misaddressed.saturating_add(1);
+                let stranger = AgentId::from(uuid::Uuid::from_u128(self.rng.random()));
+                request.agent_id = stranger;
+                delivered.insert(stranger, request);
+                continue;
+            }
+            delivered.insert(agent_id, request);
+        }
+        Ok(delivered)
+    }
+}
+
+fn no_action(agent_id: AgentId, tick: u64) -> ActionRequest {
+    ActionRequest {
+        agent_id,
+        tick,
+        action_type: ActionType::NoAction,
+        parameters: ActionParameters::NoAction,
+        submitted_at: Utc::now(),
+        goal_updates: Vec::new(),
+    }
+}
+
+/// An in-memory snapshot store whose writes time out.
+///
+/// A timed-out write leaves the snapshot pending; the next write replaces
+/// it (only the newest state is worth persisting) and [`FlakyStore::flush`]
+/// always succeeds, as a retry after the database recovers would.
+pub struct FlakyStore {
+    timeout_rate: f64,
+    rng: StdRng,
+    pending: Option<SimulationSnapshot>,
+    committed: Option<SimulationSnapshot>,
+    timeouts: u64,
+}
+
+impl FlakyStore {
+    /// Create a store that times out with probability `timeout_rate`.
+    pub fn new(timeout_rate: f64, seed: u64) -> Self {
+        Self {
+            timeout_rate,
+            rng: StdRng::seed_from_u64(seed),
+            pending: None,
+            committed: None,
+            timeouts: 0,
+        }
+    }
+
+    /// Try to write `snapshot`. Returns `false` if the write timed out.
+    pub fn write(&mut self, snapshot: SimulationSnapshot) -> bool {
+        if chance(&mut self.rng, self.timeout_rate) {
+            self.timeouts = self.timeouts.saturating_add(1);
+            self.pending = Some(snapshot);
+            return false;
+        }
+        self.pending = None;
+        self.committed = Some(snapshot);
+        true
+    }
+
+    /// Commit whatever is pending, ignoring timeouts.
+    pub fn flush(&mut self) {
+        if let Some(snapshot) = self.pending.take() {
+            self.committed = Some(snapshot);
+        }
+    }
+
+    /// The last committed snapshot.
+    pub const fn committed(&self) -> Option<&SimulationSnapshot> {
+        self.committed.as_ref()
+    }
+
+    /// How many writes timed out.
+    pub const fn timeouts(&self) -> u64 {
+        self.timeouts
+    }
+}
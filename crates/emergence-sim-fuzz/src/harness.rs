@@ -0,0 +1,434 @@
+//! The fuzz loop.
+//!
+//! A run is a sequence of short *chunks*. Before each chunk the harness
+//! issues a few random operator commands against a fresh
+//! [`OperatorState`] whose tick limit ends the chunk, then hands the state
+//! to the real runner ([`runner::run_simulation_with_spawner`]). Splitting
+//! the run this way lets commands land between ticks deterministically
+//! while still going through the runner's own queue draining, pause, stop,
+//! auto-recovery, and extinction handling.
+
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use emergence_core::config::SimulationBoundsConfig;
+use emergence_core::experiment::capture_snapshot;
+use emergence_core::operator::{InjectedEvent, OperatorState, SimulationEndReason, SpawnRequest};
+use emergence_core::runner::{self, RunnerError, TickCallback};
+use emergence_core::tick::{SimulationState, TickError, TickSummary};
+use emergence_types::LocationId;
+use futures::FutureExt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::actions::{RandomDecisionSource, chance, pick};
+use crate::error::FuzzError;
+use crate::faults::{FaultConfig, FaultCounts, FaultyDecisionSource, FlakyStore};
+use crate::invariants::{Books, Violation};
+use crate::world::{self, FuzzSpawner};
+
+/// Longest chunk of ticks run between operator commands.
+const MAX_CHUNK_TICKS: u64 = 8;
+
+/// Injected event types, including one the engine does not know.
+const EVENT_TYPES: [&str; 8] = [
+    "natural_disaster",
+    "flood",
+    "wildfire",
+    "earthquake",
+    "resource_boom",
+    "plague",
+    "migration",
+    "meteor",
+];
+
+/// Severities, including out-of-range and unparsable ones.
+const SEVERITIES: [Option<&str>; 6] =
+    [None, Some("1"), Some("3"), Some("5"), Some("0"), Some("dire")];
+
+/// Settings for one fuzz run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzConfig {
+    /// Seed for every random stream in the run.
+    pub seed: u64,
+    /// Ticks to run (fewer if the population goes extinct).
+    pub ticks: u64,
+    /// Seed agents.
+    pub agents: u32,
+    /// Population floor for the runner's auto-recovery (0 disables it).
+    pub min_population: u32,
+    /// Fraction of actions that are deliberately malformed.
+    pub invalid_action_rate: f64,
+    /// Chance of each of up to three operator commands before a chunk.
+    pub operator_rate: f64,
+    /// Injected infrastructure failures.
+    pub faults: FaultConfig,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            ticks: 5000,
+            agents: 8,
+            min_population: 2,
+            invalid_action_rate: 0.2,
+            operator_rate: 0.3,
+            faults: FaultConfig::default(),
+        }
+    }
+}
+
+/// What a fuzz run did and found.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzReport {
+    /// The run's seed.
+    pub seed: u64,
+    /// Ticks the clock advanced.
+    pub ticks: u64,
+    /// Runner invocations.
+    pub chunks: u64,
+    /// Operator commands issued.
+    pub operator_commands: u64,
+    /// Whether the run ended early in extinction, i.e. everyone died and
+    /// auto-recovery could not place new agents.
+    pub extinct: bool,
+    /// Agent deaths.
+    pub deaths: u64,
+    /// Agents spawned after the start.
+    pub spawns: u64,
+    /// Faults injected.
+    pub faults: FaultCounts,
+    /// Broken invariants, in the order found.
+    pub violations: Vec<Violation>,
+}
+
+impl FuzzReport {
+    /// Whether every invariant held.
+    pub const fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// An operator command issued between chunks.
+enum Command {
+    Pause,
+    Speed(u64),
+    Inject(InjectedEvent),
+    Spawn(SpawnRequest),
+    Stop,
+}
+
+/// Checks every tick and writes a snapshot of it to a [`FlakyStore`].
+struct FuzzCallback {
+    books: Arc<Mutex<Books>>,
+    store: FlakyStore,
+}
+
+impl TickCallback for FuzzCallback {
+    fn on_tick(&mut self, summary: &TickSummary, state: &SimulationState) {
+        let mut books = self.books.lock().unwrap_or_else(PoisonError::into_inner);
+        books.check_tick(summary, state);
+
+        let previous = self.store.committed().map(|s| s.tick);
+        match capture_snapshot(state) {
+            Ok(snapshot) if snapshot.tick != summary.tick => books.violation(
+                summary.tick,
+                "persistence",
+                format!("snapshot of tick {} taken after tick {}", snapshot.tick, summary.tick),
+            ),
+            Ok(snapshot) => {
+                if self.store.write(snapshot) && previous >= Some(summary.tick) {
+                    books.violation(
+                        summary.tick,
+                        "persistence",
+                        format!("committed tick {} after tick {previous:?}", summary.tick),
+                    );
+                }
+            }
+            Err(e) => books.violation(summary.tick, "persistence", e.to_string()),
+        }
+    }
+}
+
+/// Run one fuzz run to completion.
+///
+/// Must be called inside a Tokio runtime: pauses are resumed from a
+/// spawned task.
+#[allow(clippy::too_many_lines)]
+pub async fn run(config: &FuzzConfig) -> Result<FuzzReport, FuzzError> {
+    let mut state = world::seed_state(config.seed, config.agents)?;
+    let locations = world::sorted_locations(&state.world_map);
+    let mut regions: Vec<String> = state
+        .world_map
+        .locations()
+        .map(|(_, loc)| loc.location.region.clone())
+        .collect();
+    regions.sort_unstable();
+    regions.dedup();
+    regions.push(String::from("Atlantis"));
+
+    let books = Arc::new(Mutex::new(Books::open(&state)));
+    let actions = RandomDecisionSource::new(
+        config.seed.wrapping_add(1),
+        world::routes_by_name(&state.world_map),
+        config.invalid_action_rate,
+    );
+    let mut source = FaultyDecisionSource::new(actions, config.faults, config.seed.wrapping_add(2));
+    let mut callback = FuzzCallback {
+        books: Arc::clone(&books),
+        store: FlakyStore::new(config.faults.persist_timeout_rate, config.seed.wrapping_add(3)),
+    };
+    let mut spawner = FuzzSpawner::new(config.seed.wrapping_add(4), Arc::clone(&books));
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut report = FuzzReport {
+        seed: config.seed,
+        ..FuzzReport::default()
+    };
+
+    let start = state.clock.tick();
+    let end = start.saturating_add(config.ticks);
+    while state.clock.tick() < end {
+        let tick = state.clock.tick();
+        let commands = random_commands(&mut rng, config.operator_rate, &locations, &regions);
+        let stop = commands.iter().any(|c| matches!(c, Command::Stop));
+        // An accepted speed change makes the runner sleep between ticks,
+        // so keep that chunk to a single tick.
+        let slowed = commands.iter().any(|c| matches!(c, Command::Speed(ms) if *ms >= 100));
+        let chunk = if slowed {
+            1
+        } else {
+            rng.random_range(1..=MAX_CHUNK_TICKS).min(end.saturating_sub(tick))
+        };
+        let bounds = SimulationBoundsConfig {
+            max_ticks: tick.saturating_add(chunk),
+            max_real_time_seconds: 0,
+            ..SimulationBoundsConfig::default()
+        };
+        let operator = Arc::new(OperatorState::new(0, &bounds));
+        for command in commands {
+            report.operator_commands = report.operator_commands.saturating_add(1);
+            if let Some(detail) = apply(&operator, command).await {
+                lock(&books).violation(tick, "operator", detail);
+            }
+        }
+
+        let outcome = AssertUnwindSafe(runner::run_simulation_with_spawner(
+            &mut state,
+            &mut source,
+            &operator,
+            &mut callback,
+            &mut spawner,
+            config.min_population,
+        ))
+        .catch_unwind()
+        .await;
+        report.chunks = report.chunks.saturating_add(1);
+
+        let ran = state.clock.tick().saturating_sub(tick);
+        let mut books = lock(&books);
+        match outcome {
+            Err(payload) => {
+                books.violation(state.clock.tick(), "no_panic", panic_message(payload.as_ref()));
+                break;
+            }
+            Ok(Err(RunnerError::Tick {
+                source: TickError::Decision { .. },
+            })) if source.take_injected_error() => books.resync(&state),
+            Ok(Err(e)) => {
+                books.violation(state.clock.tick(), "runner", e.to_string());
+                break;
+            }
+            Ok(Ok(result)) => {
+                let expected = if stop {
+                    (SimulationEndReason::OperatorStop, 0)
+                } else if result.end_reason == SimulationEndReason::Extinction {
+                    report.extinct = true;
+                    (SimulationEndReason::Extinction, ran)
+                } else {
+                    (SimulationEndReason::MaxTicksReached, chunk)
+                };
+                if (result.end_reason.clone(), ran) != expected {
+                    books.violation(
+                        state.clock.tick(),
+                        "runner",
+                        format!(
+                            "chunk of {chunk} ended {:?} after {ran} ticks, expected {expected:?}",
+                            result.end_reason
+                        ),
+                    );
+                }
+                if operator.is_paused() {
+                    books.violation(tick, "operator", String::from("still paused after chunk"));
+                }
+                if report.extinct {
+                    break;
+                }
+            }
+        }
+    }
+
+    callback.store.flush();
+    let final_tick = state.clock.tick();
+    let mut books = lock(&books);
+    if let Some(snapshot) = callback.store.committed()
+        && snapshot.tick != final_tick
+    {
+        books.violation(
+            final_tick,
+            "persistence",
+            format!("last committed snapshot is of tick {}", snapshot.tick),
+        );
+    }
+
+    report.ticks = final_tick.saturating_sub(start);
+    report.deaths = books.deaths;
+    report.spawns = books.spawns;
+    report.faults = FaultCounts {
+        persist_timeouts: callback.store.timeouts(),
+        ..source.counts()
+    };
+    report.violations = std::mem::take(&mut books.violations);
+    Ok(report)
+}
+
+fn lock(books: &Mutex<Books>) -> std::sync::MutexGuard<'_, Books> {
+    books.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Up to three random operator commands.
+fn random_commands(
+    rng: &mut StdRng,
+    rate: f64,
+    locations: &[LocationId],
+    regions: &[String],
+) -> Vec<Command> {
+    let mut commands = Vec::new();
+    for _ in 0..3 {
+        if !chance(rng, rate) {
+            continue;
+        }
+        let command = match rng.random_range(0_u8..20) {
+            0..=3 => Command::Pause,
+            4..=6 => Command::Speed(rng.random_range(0..=400)),
+            7..=13 => Command::Inject(InjectedEvent {
+                event_type: pick(rng, &EVENT_TYPES).unwrap_or("plague").to_owned(),
+                target_region: rng
+                    .random_bool(0.8)
+                    .then(|| regions.get(rng.random_range(0..regions.len().max(1))).cloned())
+                    .flatten(),
+                severity: pick(rng, &SEVERITIES).flatten().map(str::to_owned),
+                description: Some(String::from("sim-fuzz")),
+            }),
+            14..=18 => Command::Spawn(SpawnRequest {
+                name: None,
+                location_id: match rng.random_range(0_u8..3) {
+                    0 => None,
+                    1 => Some(LocationId::new()),
+                    _ => pick(rng, locations),
+                },
+                personality_mode: String::from("random"),
+            }),
+            _ => Command::Stop,
+        };
+        commands.push(command);
+    }
+    commands
+}
+
+/// Issue `command`, returning a description of any misbehaviour.
+async fn apply(operator: &Arc<OperatorState>, command: Command) -> Option<String> {
+    match command {
+        Command::Pause => {
+            operator.pause();
+            let operator = Arc::clone(operator);
+            tokio::spawn(async move {
+                tokio::task::yield_now().await;
+                operator.resume();
+            });
+        }
+        Command::Speed(ms) => {
+            let before = operator.tick_interval_ms();
+            let accepted = operator.set_tick_interval_ms(ms);
+            let after = operator.tick_interval_ms();
+            let ok = if ms < 100 {
+                accepted.is_none() && after == before
+            } else {
+                accepted == Some(before) && after == ms
+            };
+            if !ok {
+                return Some(format!("speed {ms}ms: returned {accepted:?}, now {after}ms"));
+            }
+        }
+        Command::Inject(event) => operator.inject_event(event).await,
+        Command::Spawn(request) => operator.queue_agent_spawn(request).await,
+        Command::Stop => operator.request_stop(),
+    }
+    None
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("panic with a non-string payload"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn short_runs_hold_invariants() {
+        for seed in 0..3 {
+            let config = FuzzConfig {
+                seed,
+                ticks: 400,
+                ..FuzzConfig::default()
+            };
+            let report = run(&config).await.unwrap();
+            assert!(report.ticks > 0);
+            assert!(report.chunks > 0);
+            let found: Vec<String> = report.violations.iter().map(ToString::to_string).collect();
+            assert!(found.is_empty(), "seed {seed}: {found:#?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn heavy_faults_are_injected_and_survived() {
+        let config = FuzzConfig {
+            seed: 11,
+            ticks: 200,
+            faults: FaultConfig {
+                drop_rate: 0.3,
+                stale_rate: 0.3,
+                misaddress_rate: 0.3,
+                batch_timeout_rate: 0.1,
+                source_error_rate: 0.05,
+                persist_timeout_rate: 0.5,
+            },
+            ..FuzzConfig::default()
+        };
+        let report = run(&config).await.unwrap();
+        let f = report.faults;
+        assert!(f.dropped > 0 && f.stale > 0 && f.misaddressed > 0);
+        assert!(f.batch_timeouts > 0 && f.source_errors > 0 && f.persist_timeouts > 0);
+        assert!(report.is_clean(), "{:#?}", report.violations);
+    }
+
+    #[tokio::test]
+    async fn same_seed_replays_the_same_streams() {
+        let config = FuzzConfig {
+            seed: 5,
+            ticks: 150,
+            ..FuzzConfig::default()
+        };
+        let a = run(&config).await.unwrap();
+        let b = run(&config).await.unwrap();
+        assert_eq!(a.chunks, b.chunks);
+        assert_eq!(a.operator_commands, b.operator_commands);
+    }
+}
@@ -0,0 +1,296 @@
+//! Invariants checked after every tick.
+//!
+//! [`Books`] carries what the harness knows between ticks -- inventory
+//! totals, the living population, and who has died -- and
+//! [`Books::check_tick`] compares it against what the tick reports and
+//! the state it leaves behind:
+//!
+//! - **conservation**: agent inventories change only by the
+//!   `resource_changes` of successful actions and the inventories dropped
+//!   on death;
+//! - **population**: the living count moves only by spawns and reported
+//!   deaths, `alive_agents` has no duplicates, every living agent has a
+//!   state and an identity record, nobody dies twice, and the dead are
+//!   marked with `died_at_tick`;
+//! - **bounds**: vitals stay in `0..=100` and no resource node exceeds its
+//!   capacity.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use emergence_core::tick::{SimulationState, TickSummary};
+use emergence_types::{AgentId, AgentState, Resource};
+
+/// One broken invariant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The tick after which it was detected.
+    pub tick: u64,
+    /// Which invariant (e.g. `conservation`, `population`, `no_panic`).
+    pub invariant: &'static str,
+    /// What was observed.
+    pub detail: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tick {} [{}] {}", self.tick, self.invariant, self.detail)
+    }
+}
+
+/// Running accounts the invariants are checked against.
+#[derive(Debug, Default)]
+pub struct Books {
+    inventory: BTreeMap<Resource, i128>,
+    alive: usize,
+    dead: BTreeSet<AgentId>,
+    /// Deaths seen so far.
+    pub deaths: u64,
+    /// Agents spawned after the start.
+    pub spawns: u64,
+    /// Everything found so far.
+    pub violations: Vec<Violation>,
+}
+
+impl Books {
+    /// Open the books on a starting state.
+    pub fn open(state: &SimulationState) -> Self {
+        let mut books = Self::default();
+        books.resync(state);
+        books.dead = state
+            .agents
+            .values()
+            .filter(|a| a.died_at_tick.is_some())
+            .map(|a| a.id)
+            .collect();
+        books
+    }
+
+    /// Re-read inventory totals and the living count from `state`.
+    ///
+    /// Used after a tick that failed part-way, whose changes were never
+    /// reported in a summary.
+    pub fn resync(&mut self, state: &SimulationState) {
+        self.inventory = inventory_totals(state.agent_states.values());
+        self.alive = state.alive_agents.len();
+    }
+
+    /// Account for an agent added between ticks.
+    pub fn record_spawn(&mut self, agent_state: &AgentState) {
+        add_inventory(&mut self.inventory, agent_state);
+        self.alive = self.alive.saturating_add(1);
+        self.spawns = self.spawns.saturating_add(1);
+    }
+
+    /// Record a violation found outside [`Books::check_tick`].
+    pub fn violation(&mut self, tick: u64, invariant: &'static str, detail: String) {
+        self.violations.push(Violation {
+            tick,
+            invariant,
+            detail,
+        });
+    }
+
+    /// Check a finished tick and roll the books forward.
+    pub fn check_tick(&mut self, summary: &TickSummary, state: &SimulationState) {
+        let tick = summary.tick;
+        self.check_conservation(summary, state);
+        self.check_population(summary, state);
+        check_bounds(state, &mut |detail| self.violation(tick, "bounds", detail));
+        self.resync(state);
+    }
+
+    fn check_conservation(&mut self, summary: &TickSummary, state: &SimulationState) {
+        let mut expected = self.inventory.clone();
+        for result in summary.action_results.values().filter(|r| r.success) {
+            let Some(outcome) = &result.outcome else {
+                continue;
+            };
+            for (resource, delta) in &outcome.resource_changes {
+                let entry = expected.entry(*resource).or_default();
+                *entry = entry.saturating_add(i128::from(*delta));
+            }
+        }
+        for death in &summary.deaths {
+            for (resource, quantity) in &death.dropped_inventory {
+                let entry = expected.entry(*resource).or_default();
+                *entry = entry.saturating_sub(i128::from(*quantity));
+            }
+        }
+        expected.retain(|_, quantity| *quantity != 0);
+
+        let actual = inventory_totals(state.agent_states.values());
+        if actual != expected {
+            self.violation(
+                summary.tick,
+                "conservation",
+                format!("agent inventories {actual:?}, expected {expected:?}"),
+            );
+        }
+    }
+
+    fn check_population(&mut self, summary: &TickSummary, state: &SimulationState) {
+        let tick = summary.tick;
+        let mut found = Vec::new();
+
+        let alive: BTreeSet<AgentId> = state.alive_agents.iter().copied().collect();
+        if alive.len() != state.alive_agents.len() {
+            found.push(String::from("alive_agents contains duplicates"));
+        }
+        if usize::try_from(summary.agents_alive).ok() != Some(state.alive_agents.len()) {
+            found.push(format!(
+                "summary reports {} alive, state has {}",
+                summary.agents_alive,
+                state.alive_agents.len()
+            ));
+        }
+        let expected_alive = self.alive.saturating_sub(summary.deaths.len());
+        if state.alive_agents.len() != expected_alive {
+            found.push(format!(
+                "{} alive after {} deaths from {}, expected {expected_alive}",
+                state.alive_agents.len(),
+                summary.deaths.len(),
+                self.alive
+            ));
+        }
+
+        for id in &alive {
+            if !state.agent_states.contains_key(id) {
+                found.push(format!("living agent {id} has no state"));
+            }
+            match state.agents.get(id) {
+                None => found.push(format!("living agent {id} has no identity record")),
+                Some(agent) if agent.died_at_tick.is_some() => {
+                    found.push(format!("living agent {id} is marked dead"));
+                }
+                Some(_) => {}
+            }
+        }
+
+        for death in &summary.deaths {
+            let id = death.agent_id;
+            if !self.dead.insert(id) {
+                found.push(format!("agent {id} died twice"));
+            }
+            if alive.contains(&id) {
+                found.push(format!("agent {id} died but is still alive"));
+            }
+            if state.agents.get(&id).and_then(|a| a.died_at_tick) != Some(tick) {
+                found.push(format!("agent {id} died without died_at_tick = {tick}"));
+            }
+        }
+        self.deaths = self
+            .deaths
+            .saturating_add(u64::try_from(summary.deaths.len()).unwrap_or(u64::MAX));
+
+        for detail in found {
+            self.violation(tick, "population", detail);
+        }
+    }
+}
+
+/// Vitals in range and resource nodes within capacity.
+fn check_bounds(state: &SimulationState, report: &mut impl FnMut(String)) {
+    for id in &state.alive_agents {
+        let Some(s) = state.agent_states.vitals(id) else {
+            continue;
+        };
+        let vitals = [
+            ("energy", s.energy),
+            ("health", s.health),
+            ("hunger", s.hunger),
+            ("thirst", s.thirst),
+        ];
+        for (name, value) in vitals {
+            if value > 100 {
+                report(format!("agent {id} {name} is {value}"));
+            }
+        }
+    }
+    for (id, loc) in state.world_map.locations() {
+        for (resource, node) in loc.resources() {
+            if node.available > node.max_capacity {
+                report(format!(
+                    "location {id} holds {} {resource:?}, capacity {}",
+                    node.available, node.max_capacity
+                ));
+            }
+        }
+    }
+}
+
+fn inventory_totals<'a>(
+    agent_states: impl Iterator<Item = &'a AgentState>,
+) -> BTreeMap<Resource, i128> {
+    let mut totals = BTreeMap::new();
+    for agent_state in agent_states {
+        add_inventory(&mut totals, agent_state);
+    }
+    totals.retain(|_, quantity| *quantity != 0);
+    totals
+}
+
+fn add_inventory(totals: &mut BTreeMap<Resource, i128>, agent_state: &AgentState) {
+    for (resource, quantity) in &agent_state.inventory {
+        let entry = totals.entry(*resource).or_default();
+        *entry = entry.saturating_add(i128::from(*quantity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use emergence_types::{Season, Weather};
+
+    use super::*;
+    use crate::world::seed_state;
+
+    fn empty_summary(state: &SimulationState) -> TickSummary {
+        TickSummary {
+            tick: 1,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            agents_alive: u32::try_from(state.alive_agents.len()).unwrap_or(u32::MAX),
+            deaths: Vec::new(),
+            action_results: BTreeMap::new(),
+            regeneration: BTreeMap::new(),
+            world_event_logs: Vec::new(),
+            disasters: Vec::new(),
+            fires: Vec::new(),
+            trade_routes: Vec::new(),
+            inheritances: Vec::new(),
+            market_fills: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unexplained_inventory_is_a_conservation_violation() {
+        let Ok(mut state) = seed_state(3, 4) else {
+            return;
+        };
+        let mut books = Books::open(&state);
+        books.check_tick(&empty_summary(&state), &state);
+        assert!(books.violations.is_empty(), "{:?}", books.violations);
+
+        let first = state.agent_states.ids().next();
+        if let Some(mut s) = first.and_then(|id| state.agent_states.get_mut(&id)) {
+            s.inventory.insert(Resource::Wood, 3);
+        }
+        books.check_tick(&empty_summary(&state), &state);
+        assert_eq!(books.violations.len(), 1);
+        assert_eq!(books.violations.first().map(|v| v.invariant), Some("conservation"));
+    }
+
+    #[test]
+    fn vanished_agent_is_a_population_violation() {
+        let Ok(mut state) = seed_state(3, 4) else {
+            return;
+        };
+        let mut books = Books::open(&state);
+        state.alive_agents.pop();
+        let summary = empty_summary(&state);
+        books.check_tick(&summary, &state);
+        assert!(books.violations.iter().any(|v| v.invariant == "population"));
+    }
+}
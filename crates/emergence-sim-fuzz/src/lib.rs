@@ -0,0 +1,33 @@
+//! Chaos and fuzz testing harness for the Emergence tick cycle.
+//!
+//! `sim-fuzz` runs the real runner and tick cycle over the starting world
+//! for thousands of ticks while everything around them misbehaves:
+//!
+//! - agents answer with a seeded mix of well-formed and malformed actions
+//!   ([`actions::RandomDecisionSource`]);
+//! - the operator pauses, resumes, changes speed, injects world events
+//!   (known and unknown, with garbage severities and regions), queues
+//!   spawns at real and missing locations, and stops the run;
+//! - the infrastructure fails: decisions are dropped, arrive late or
+//!   misaddressed, whole batches time out, the decision source dies
+//!   mid-tick, and snapshot writes time out ([`faults`]).
+//!
+//! After every tick the harness checks the [`invariants`] -- resource
+//! conservation in agent inventories, population bookkeeping, and value
+//! bounds -- and it catches panics anywhere in the runner. Each run is
+//! driven by one seed, so a failing seed replays the same action,
+//! operator, and fault streams.
+//!
+//! ```text
+//! sim-fuzz --seed 7 --runs 20 --ticks 5000
+//! ```
+
+pub mod actions;
+pub mod error;
+pub mod faults;
+pub mod harness;
+pub mod invariants;
+pub mod world;
+
+pub use error::FuzzError;
+pub use harness::{FuzzConfig, FuzzReport, run};
@@ -0,0 +1,105 @@
+//! `sim-fuzz` -- chaos/fuzz runs of the Emergence tick cycle.
+//!
+//! Runs `--runs` consecutive seeds starting at `--seed`, prints one line
+//! per run and every violation found, and exits non-zero if any invariant
+//! broke.
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use emergence_sim_fuzz::faults::FaultConfig;
+use emergence_sim_fuzz::{FuzzConfig, FuzzReport};
+
+/// Drive the tick cycle with random actions, operator commands, and
+/// injected failures, checking invariants after every tick.
+#[derive(Debug, Parser)]
+#[command(name = "sim-fuzz", version)]
+struct Cli {
+    /// First seed.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Number of runs (consecutive seeds).
+    #[arg(long, default_value_t = 1)]
+    runs: u64,
+
+    /// Ticks per run.
+    #[arg(long, default_value_t = 5000)]
+    ticks: u64,
+
+    /// Seed agents per run.
+    #[arg(long, default_value_t = 8)]
+    agents: u32,
+
+    /// Population floor for auto-recovery (0 lets runs go extinct).
+    #[arg(long, default_value_t = 2)]
+    min_population: u32,
+
+    /// Fraction of malformed actions.
+    #[arg(long, default_value_t = 0.2)]
+    invalid_rate: f64,
+
+    /// Chance of each operator command slot between chunks.
+    #[arg(long, default_value_t = 0.3)]
+    operator_rate: f64,
+
+    /// Disable injected infrastructure failures.
+    #[arg(long)]
+    no_faults: bool,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut failed = false;
+
+    for seed in cli.seed..cli.seed.saturating_add(cli.runs) {
+        let config = FuzzConfig {
+            seed,
+            ticks: cli.ticks,
+            agents: cli.agents,
+            min_population: cli.min_population,
+            invalid_action_rate: cli.invalid_rate,
+            operator_rate: cli.operator_rate,
+            faults: if cli.no_faults { FaultConfig::NONE } else { FaultConfig::default() },
+        };
+        match emergence_sim_fuzz::run(&config).await {
+            Ok(report) => {
+                print_report(&report);
+                failed |= !report.is_clean();
+            }
+            Err(e) => {
+                eprintln!("seed {seed}: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn print_report(report: &FuzzReport) {
+    let f = &report.faults;
+    println!(
+        "seed {}: {} ticks in {} chunks, {} operator commands, {} deaths, {} spawns{}; \
+         faults: {} dropped, {} stale, {} misaddressed, {} batch timeouts, \
+         {} source errors, {} persist timeouts; {} violations",
+        report.seed,
+        report.ticks,
+        report.chunks,
+        report.operator_commands,
+        report.deaths,
+        report.spawns,
+        if report.extinct { ", extinct" } else { "" },
+        f.dropped,
+        f.stale,
+        f.misaddressed,
+        f.batch_timeouts,
+        f.source_errors,
+        f.persist_timeouts,
+        report.violations.len(),
+    );
+    for violation in &report.violations {
+        println!("  {violation}");
+    }
+}
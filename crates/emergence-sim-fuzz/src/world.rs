@@ -0,0 +1,207 @@
+//! The starting state for a fuzz run and the agent spawner it uses.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::Utc;
+use emergence_agents::actions::conflict::ConflictStrategy;
+use emergence_agents::config::VitalsConfig;
+use emergence_agents::{DiplomacyState, FamilyTracker, OrderBook};
+use emergence_core::agent_store::AgentStore;
+use emergence_core::clock::WorldClock;
+use emergence_core::config::TimeConfig;
+use emergence_core::operator::SpawnRequest;
+use emergence_core::runner::SpawnHandler;
+use emergence_core::scratch::TickScratch;
+use emergence_core::subscribers::EventSubscribers;
+use emergence_core::tick::SimulationState;
+use emergence_ledger::Ledger;
+use emergence_types::{Agent, AgentId, AgentState, LocationId, Personality, Resource, Sex};
+use emergence_world::{WeatherSystem, WorldMap};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+
+use crate::error::FuzzError;
+use crate::invariants::Books;
+
+/// Build the starting world with `agents` seed agents spread across it.
+///
+/// Agent IDs, names, and placement come from `seed`; the starting world's
+/// location IDs are generated by `emergence-world` and differ per run.
+pub fn seed_state(seed: u64, agents: u32) -> Result<SimulationState, FuzzError> {
+    let (mut world_map, _) = emergence_world::create_starting_world()?;
+    let clock = WorldClock::new(&TimeConfig::default())?;
+    let mut state = SimulationState {
+        clock,
+        world_map: WorldMap::new(),
+        weather_system: WeatherSystem::new(seed),
+        agents: BTreeMap::new(),
+        agent_names: BTreeMap::new(),
+        agent_states: AgentStore::new(),
+        alive_agents: Vec::new(),
+        vitals_config: VitalsConfig::default(),
+        conflict_strategy: ConflictStrategy::FirstComeFirstServed,
+        injected_events: Vec::new(),
+        active_plagues: Vec::new(),
+        active_resource_booms: Vec::new(),
+        disasters: emergence_world::DisasterSystem::default(),
+        fires: emergence_world::FireSystem::default(),
+        fauna: emergence_world::FaunaRegistry::default(),
+        waters: emergence_world::WaterRegistry::default(),
+        mines: emergence_world::MineRegistry::default(),
+        known_maps: emergence_world::KnownMapRegistry::default(),
+        trade_network: emergence_world::TradeNetwork::default(),
+        structures: BTreeMap::new(),
+        family: FamilyTracker::new(),
+        ledger: Ledger::new(),
+        orders: OrderBook::new(),
+        groups: BTreeMap::new(),
+        diplomacy: DiplomacyState::new(),
+        hooks: None,
+        scratch: TickScratch::new(),
+        subscribers: EventSubscribers::new(),
+    };
+
+    let locations = sorted_locations(&world_map);
+    let mut rng = StdRng::seed_from_u64(seed);
+    for n in 0..agents {
+        let index = rng.random_range(0..locations.len().max(1));
+        let Some(&location_id) = locations.get(index) else {
+            break;
+        };
+        let (agent, agent_state) = make_agent(&mut rng, &format!("Seed-{n}"), location_id, 0)?;
+        if let Some(loc) = world_map.get_location_mut(location_id) {
+            loc.add_occupant(agent.id)?;
+        }
+        insert_agent(&mut state, agent, agent_state);
+    }
+    state.world_map = world_map;
+    Ok(state)
+}
+
+/// Location IDs ordered by name, so seeded picks are stable across runs.
+pub fn sorted_locations(world_map: &WorldMap) -> Vec<LocationId> {
+    let mut named: Vec<(&str, LocationId)> = world_map
+        .locations()
+        .map(|(id, loc)| (loc.location.name.as_str(), *id))
+        .collect();
+    named.sort_unstable();
+    named.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Location name -> neighbouring location IDs, for generating moves.
+pub fn routes_by_name(world_map: &WorldMap) -> BTreeMap<String, Vec<LocationId>> {
+    world_map
+        .locations()
+        .map(|(id, loc)| {
+            let neighbors = world_map.neighbors(*id).into_iter().map(|(to, _)| to).collect();
+            (loc.location.name.clone(), neighbors)
+        })
+        .collect()
+}
+
+/// Create an agent with a seeded ID, sex, and personality and the same
+/// starting inventory the engine gives seed agents.
+fn make_agent(
+    rng: &mut StdRng,
+    name: &str,
+    location_id: LocationId,
+    born_at_tick: u64,
+) -> Result<(Agent, AgentState), FuzzError> {
+    let agent_id = AgentId::from(uuid::Uuid::from_u128(rng.random()));
+    let agent_state = AgentState::builder(agent_id, location_id)
+        .resource(Resource::FoodBerry, 5)
+        .resource(Resource::Water, 5)
+        .born_at_tick(born_at_tick)
+        .knowledge(BTreeSet::new())
+        .build()
+        .map_err(|e| FuzzError::Agent(format!("{e:?}")))?;
+    let mut trait_value = || Decimal::new(rng.random_range(0..=100), 2);
+    let personality = Personality {
+        curiosity: trait_value(),
+        cooperation: trait_value(),
+        aggression: trait_value(),
+        risk_tolerance: trait_value(),
+        industriousness: trait_value(),
+        sociability: trait_value(),
+        honesty: trait_value(),
+        loyalty: trait_value(),
+    };
+    let agent = Agent {
+        id: agent_id,
+        name: name.to_owned(),
+        sex: if rng.random_bool(0.5) { Sex::Male } else { Sex::Female },
+        born_at_tick,
+        died_at_tick: None,
+        cause_of_death: None,
+        parent_a: None,
+        parent_b: None,
+        generation: 0,
+        personality,
+        created_at: Utc::now(),
+    };
+    Ok((agent, agent_state))
+}
+
+fn insert_agent(state: &mut SimulationState, agent: Agent, agent_state: AgentState) {
+    let agent_id = agent.id;
+    state.agent_names.insert(agent_id, agent.name.clone());
+    state.agents.insert(agent_id, agent);
+    state.agent_states.insert(agent_state);
+    state.alive_agents.push(agent_id);
+    state.family.register_alive(agent_id);
+}
+
+/// Spawns operator-requested, migrant, and recovery agents, recording
+/// each one in the shared [`Books`] so the invariants expect them.
+pub struct FuzzSpawner {
+    rng: StdRng,
+    books: Arc<Mutex<Books>>,
+    spawned: u64,
+}
+
+impl FuzzSpawner {
+    /// Create a spawner that records into `books`.
+    pub fn new(seed: u64, books: Arc<Mutex<Books>>) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            books,
+            spawned: 0,
+        }
+    }
+}
+
+impl SpawnHandler for FuzzSpawner {
+    fn handle_spawn(&mut self, request: &SpawnRequest, state: &mut SimulationState) -> bool {
+        let location_id = match request.location_id {
+            Some(id) if state.world_map.get_location(id).is_some() => id,
+            Some(_) => return false,
+            None => {
+                let locations = sorted_locations(&state.world_map);
+                let index = self.rng.random_range(0..locations.len().max(1));
+                let Some(&id) = locations.get(index) else {
+                    return false;
+                };
+                id
+            }
+        };
+        self.spawned = self.spawned.saturating_add(1);
+        let name = request.name.clone().unwrap_or_else(|| format!("Spawn-{}", self.spawned));
+        let tick = state.clock.tick();
+        let Ok((agent, agent_state)) = make_agent(&mut self.rng, &name, location_id, tick) else {
+            return false;
+        };
+        if let Some(loc) = state.world_map.get_location_mut(location_id)
+            && loc.add_occupant(agent.id).is_err()
+        {
+            return false;
+        }
+        self.books
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record_spawn(&agent_state);
+        insert_agent(state, agent, agent_state);
+        true
+    }
+}
@@ -0,0 +1,219 @@
+//! Logging and distributed tracing for the Emergence simulation.
+//!
+//! Every binary installs its `tracing` subscriber through [`init`]. Logs go
+//! to stdout as before. When `OTEL_EXPORTER_OTLP_ENDPOINT` is set, spans are
+//! also exported over OTLP (gRPC), so the tick phases, agent decisions, and
+//! database flushes of one tick show up as a single trace.
+//!
+//! # Trace propagation
+//!
+//! The engine and the runner talk over NATS, so a trace only crosses the
+//! process boundary if its context travels with the message. The publisher
+//! calls [`inject_headers`] to write the current span's W3C `traceparent`
+//! into the message headers; the subscriber calls [`set_parent_from_headers`]
+//! to continue the trace in its own span:
+//!
+//! ```text
+//! engine: tick_cycle > phase_decision > agent_perception --NATS--> runner: agent_decision
+//!                                       action_received <--NATS--/
+//! ```
+//!
+//! Both are no-ops when export is disabled.
+
+use std::time::Duration;
+
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Environment variable naming the OTLP collector endpoint.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// How long [`Telemetry::shutdown`] waits for buffered spans to export.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors from telemetry setup.
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    /// The OTLP exporter could not be built.
+    #[error("failed to build OTLP exporter: {0}")]
+    Exporter(String),
+
+    /// A global `tracing` subscriber was already installed.
+    #[error("failed to install tracing subscriber: {0}")]
+    Subscriber(String),
+}
+
+/// Handle to the installed tracing pipeline.
+///
+/// Call [`Telemetry::shutdown`] before exiting so buffered spans are
+/// flushed to the collector.
+#[derive(Debug)]
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Whether spans are being exported over OTLP.
+    pub const fn exporting(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Flush buffered spans and stop the exporter.
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider
+            && let Err(e) = provider.shutdown_with_timeout(SHUTDOWN_TIMEOUT)
+        {
+            tracing::warn!(error = %e, "failed to flush trace exporter");
+        }
+    }
+}
+
+/// Install the global `tracing` subscriber for `service_name`.
+///
+/// Log filtering follows `RUST_LOG` and defaults to `info`. If
+/// [`OTLP_ENDPOINT_ENV`] is set, an OpenTelemetry layer exporting to that
+/// endpoint is added and the W3C trace-context propagator is registered.
+/// Must be called from within a tokio runtime.
+///
+/// # Errors
+///
+/// Returns [`TelemetryError::Exporter`] if the OTLP exporter cannot be
+/// built, or [`TelemetryError::Subscriber`] if a subscriber is already set.
+pub fn init(service_name: &'static str) -> Result<Telemetry, TelemetryError> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt = tracing_subscriber::fmt::layer().with_target(true);
+
+    let endpoint = std::env::var(OTLP_ENDPOINT_ENV)
+        .ok()
+        .filter(|endpoint| !endpoint.is_empty());
+    let provider = endpoint
+        .map(|endpoint| tracer_provider(service_name, &endpoint))
+        .transpose()?;
+    let otel = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name)));
+    if provider.is_some() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    }
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt)
+        .with(otel)
+        .try_init()
+        .map_err(|e| TelemetryError::Subscriber(e.to_string()))?;
+
+    Ok(Telemetry { provider })
+}
+
+/// Build a tracer provider that batches spans to the collector at `endpoint`.
+fn tracer_provider(
+    service_name: &'static str,
+    endpoint: &str,
+) -> Result<SdkTracerProvider, TelemetryError> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| TelemetryError::Exporter(e.to_string()))?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build())
+}
+
+// =========================================================================
+// NATS header propagation
+// =========================================================================
+
+/// Writes trace-context fields into NATS headers.
+struct HeaderInjector<'a>(&'a mut async_nats::HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key, value);
+    }
+}
+
+/// Reads trace-context fields from NATS headers.
+struct HeaderExtractor<'a>(&'a async_nats::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(async_nats::HeaderValue::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|(name, _)| name.as_ref()).collect()
+    }
+}
+
+/// Write the current span's trace context into `headers`.
+pub fn inject_headers(headers: &mut async_nats::HeaderMap) {
+    let cx = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&cx, &mut HeaderInjector(headers));
+    });
+}
+
+/// Make `span` a child of the trace context carried in `headers`, if any.
+///
+/// Must be called before `span` is first entered.
+pub fn set_parent_from_headers(span: &tracing::Span, headers: Option<&async_nats::HeaderMap>) {
+    let Some(headers) = headers else {
+        return;
+    };
+    let cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    });
+    // Fails only when no OpenTelemetry layer is installed.
+    let _ = span.set_parent(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::TraceContextExt;
+
+    use super::*;
+
+    #[test]
+    fn trace_context_round_trips_through_headers() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut headers = async_nats::HeaderMap::new();
+            let publisher = tracing::info_span!("publish");
+            let sent = publisher.in_scope(|| {
+                inject_headers(&mut headers);
+                tracing::Span::current().context().span().span_context().clone()
+            });
+            assert!(headers.get("traceparent").is_some());
+
+            let receiver = tracing::info_span!("receive");
+            set_parent_from_headers(&receiver, Some(&headers));
+            let child = receiver.context().span().span_context().clone();
+            assert_eq!(child.trace_id(), sent.trace_id());
+            assert_ne!(child.span_id(), sent.span_id());
+        });
+    }
+
+    #[test]
+    fn missing_headers_leave_span_unparented() {
+        let span = tracing::Span::none();
+        set_parent_from_headers(&span, None);
+        let mut headers = async_nats::HeaderMap::new();
+        inject_headers(&mut headers);
+        assert!(headers.get("traceparent").is_none());
+    }
+}
@@ -3,6 +3,7 @@ import type { AgentId } from "./AgentId";
 import type { FreeformAction } from "./FreeformAction";
 import type { GroupId } from "./GroupId";
 import type { LocationId } from "./LocationId";
+import type { OrderSide } from "./OrderSide";
 import type { Resource } from "./Resource";
 import type { RuleId } from "./RuleId";
 import type { StructureId } from "./StructureId";
@@ -35,14 +36,22 @@ structure_type: StructureType, } } | { "Repair": {
 /**
  * The structure to repair.
  */
-structure_id: StructureId, } } | { "Demolish": { 
+structure_id: StructureId, } } | "Extinguish" | { "Demolish": { 
 /**
  * The structure to demolish.
  */
+structure_id: StructureId, } } | { "UpgradeStructure": { 
+/**
+ * The structure to upgrade.
+ */
 structure_id: StructureId, } } | { "ImproveRoute": { 
 /**
  * The route to improve (identified by destination).
  */
+destination: LocationId, } } | { "BuildRoute": { 
+/**
+ * The location the new route will reach.
+ */
 destination: LocationId, } } | { "Communicate": { 
 /**
  * The agent to send a message to.
@@ -75,7 +84,32 @@ trade_id: TradeId, } } | { "TradeReject": {
 /**
  * The trade to reject.
  */
-trade_id: TradeId, } } | { "FormGroup": { 
+trade_id: TradeId, } } | { "PostOrder": { 
+/**
+ * The market to post the order at.
+ */
+market_id: StructureId, 
+/**
+ * Whether the order buys or sells `resource`.
+ */
+side: OrderSide, 
+/**
+ * The resource bought or sold.
+ */
+resource: Resource, 
+/**
+ * How much of `resource` to buy or sell.
+ */
+quantity: number, 
+/**
+ * The resource paid in, such as `CurrencyToken`.
+ */
+price_resource: Resource, 
+/**
+ * How much of `price_resource` each unit of `resource` costs: the
+ * most a buyer pays or the least a seller takes.
+ */
+unit_price: number, } } | { "FormGroup": { 
 /**
  * Proposed group name.
  */
@@ -91,11 +125,11 @@ target_agent: AgentId,
 /**
  * The knowledge concept to teach.
  */
-knowledge: string, } } | "FarmPlant" | "FarmHarvest" | { "Craft": { 
+knowledge: string, } } | "FarmPlant" | "FarmHarvest" | "Fertilize" | "Slaughter" | { "Craft": { 
 /**
  * What to craft (resource output).
  */
-output: Resource, } } | "Mine" | "Smelt" | { "Write": { 
+output: Resource, } } | "Mine" | "DeepenMine" | "Hunt" | "Prospect" | "Fish" | "Smelt" | { "Write": { 
 /**
  * Knowledge to persist to the library.
  */
@@ -103,11 +137,47 @@ knowledge: string, } } | { "Read": {
 /**
  * Knowledge to retrieve from the library.
  */
-knowledge: string, } } | { "Claim": { 
+knowledge: string, } } | "Chart" | { "Deposit": { 
+/**
+ * The storage structure to deposit into.
+ */
+structure_id: StructureId, 
+/**
+ * The resource to deposit.
+ */
+resource: Resource, 
+/**
+ * How much to deposit.
+ */
+quantity: number, } } | { "Withdraw": { 
+/**
+ * The storage structure to withdraw from.
+ */
+structure_id: StructureId, 
+/**
+ * The resource to withdraw.
+ */
+resource: Resource, 
+/**
+ * How much to withdraw.
+ */
+quantity: number, } } | { "Claim": { 
 /**
  * The structure to claim.
  */
-structure_id: StructureId, } } | { "Legislate": { 
+structure_id: StructureId, } } | { "TransferOwnership": { 
+/**
+ * The structure to give away.
+ */
+structure_id: StructureId, 
+/**
+ * The agent who receives it.
+ */
+new_owner: AgentId, } } | { "FoundSettlement": { 
+/**
+ * Display name for the new settlement.
+ */
+name: string, } } | { "Legislate": { 
 /**
  * Display name for the rule or law.
  */
@@ -151,7 +221,11 @@ target_agent: AgentId, } } | { "Intimidate": {
 /**
  * The agent to intimidate.
  */
-target_agent: AgentId, } } | { "Propose": { 
+target_agent: AgentId, } } | { "Breach": { 
+/**
+ * The adjacent location whose fortifications to batter.
+ */
+destination: LocationId, } } | { "Propose": { 
 /**
  * The group to propose to.
  */
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Cumulative LLM usage for one backend of an agent runner.
+ */
+export type BackendUsage = { 
+/**
+ * Backend role: `"primary"` or `"escalation"`.
+ */
+backend: string, 
+/**
+ * Model ID served by this backend.
+ */
+model: string, 
+/**
+ * Successful LLM calls.
+ */
+calls: bigint, 
+/**
+ * Failed LLM calls (transport errors, error statuses, bad bodies).
+ */
+errors: bigint, 
+/**
+ * Input/prompt tokens consumed.
+ */
+input_tokens: bigint, 
+/**
+ * Output/completion tokens consumed.
+ */
+output_tokens: bigint, 
+/**
+ * Estimated cost in USD.
+ */
+estimated_cost: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Resource } from "./Resource";
+
+/**
+ * An adopted currency's money supply in a [`LedgerSnapshot`].
+ */
+export type CurrencySupply = { 
+/**
+ * The currency.
+ */
+currency: Resource, 
+/**
+ * Everything minted, less everything burned.
+ */
+supply: string, };
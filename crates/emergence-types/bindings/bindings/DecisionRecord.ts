@@ -22,7 +22,7 @@ agent_id: AgentId,
  */
 tick: bigint, 
 /**
- * How the decision was made: `"llm"`, `"rule_engine"`, `"night_cycle"`, `"timeout"`.
+ * How the decision was made: `"llm"`, `"rule_engine"`, `"night_cycle"`, `"heuristic"`, `"budget"`, `"policy"`, `"timeout"`.
  */
 decision_source: string, 
 /**
@@ -61,6 +61,12 @@ latency_ms: bigint | null,
  * The raw LLM response text (if LLM decision). Truncated to 4000 chars.
  */
 raw_llm_response: string | null, 
+/**
+ * Why the model chose the action (if LLM decision): thinking and prose
+ * outside the action JSON, then its `reasoning` field. Truncated to
+ * 2000 chars.
+ */
+reasoning: string | null, 
 /**
  * The assembled prompt sent to the LLM (if LLM decision). Truncated to 8000 chars.
  */
@@ -69,6 +75,24 @@ prompt_sent: string | null,
  * Which rule matched (if rule\_engine decision).
  */
 rule_matched: string | null, 
+/**
+ * Prompt template variant the agent is assigned to (if LLM decision).
+ */
+prompt_variant: string | null, 
+/**
+ * Version tag of that prompt variant (if LLM decision).
+ */
+prompt_version: string | null, 
+/**
+ * The perception the decision was made from, after containment
+ * sanitization (if LLM decision).
+ */
+perception: JsonValue | null, 
+/**
+ * How the engine resolved the action: `"succeeded"` or `"rejected"`.
+ * Filled in by the engine once the tick resolves.
+ */
+outcome: string | null, 
 /**
  * Timestamp of the decision.
  */
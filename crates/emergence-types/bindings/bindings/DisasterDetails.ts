@@ -0,0 +1,52 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DisasterKind } from "./DisasterKind";
+import type { LocationId } from "./LocationId";
+import type { Resource } from "./Resource";
+import type { RouteId } from "./RouteId";
+import type { StructureId } from "./StructureId";
+
+/**
+ * Details for a flood, wildfire, or earthquake event.
+ */
+export type DisasterDetails = { 
+/**
+ * The kind of disaster.
+ */
+kind: DisasterKind, 
+/**
+ * The location that was struck.
+ */
+location_id: LocationId, 
+/**
+ * Magnitude from 1 (minor) to 5 (catastrophic).
+ */
+severity: number, 
+/**
+ * Whether the operator injected the disaster rather than it striking
+ * at random.
+ */
+injected: boolean, 
+/**
+ * Resources lost at the location.
+ */
+resources_destroyed: { [key in Resource]?: number }, 
+/**
+ * Resource nodes wiped out entirely.
+ */
+nodes_destroyed: Array<Resource>, 
+/**
+ * Structures that lost durability but still stand.
+ */
+structures_damaged: Array<StructureId>, 
+/**
+ * Structures that collapsed.
+ */
+structures_destroyed: Array<StructureId>, 
+/**
+ * Routes out of the location that cannot be travelled for a while.
+ */
+routes_blocked: Array<RouteId>, 
+/**
+ * The first tick at which the blocked routes open again.
+ */
+blocked_until_tick: bigint, };
@@ -3,4 +3,4 @@
 /**
  * The type of entity participating in a ledger transfer.
  */
-export type EntityType = "Agent" | "Location" | "Structure" | "World" | "Void";
+export type EntityType = "Agent" | "Location" | "Structure" | "World" | "Void" | "Escrow" | "Group";
@@ -1,6 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AgentId } from "./AgentId";
 import type { AgentStateSnapshot } from "./AgentStateSnapshot";
+import type { CorrelationId } from "./CorrelationId";
 import type { EventId } from "./EventId";
 import type { EventType } from "./EventType";
 import type { JsonValue } from "../serde_json/JsonValue";
@@ -12,6 +13,10 @@ import type { WorldContext } from "./WorldContext";
  *
  * Events are the source of truth for the simulation's history. State can
  * be reconstructed by replaying the event log.
+ *
+ * [`caused_by`](Self::caused_by) and [`correlation_id`](Self::correlation_id)
+ * record why the event happened: the event that directly triggered it, and
+ * the cascade it belongs to (for example famine, theft, rule, enforcement).
  */
 export type Event = { 
 /**
@@ -49,4 +54,12 @@ world_context: WorldContext,
 /**
  * Real-world timestamp when the event was created.
  */
-created_at: string, };
+created_at: string, 
+/**
+ * The event that directly triggered this one, if any.
+ */
+caused_by: EventId | null, 
+/**
+ * The cascade of related events this one belongs to, if any.
+ */
+correlation_id: CorrelationId | null, };
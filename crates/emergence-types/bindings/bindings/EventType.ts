@@ -3,4 +3,4 @@
 /**
  * A type of event recorded in the event store.
  */
-export type EventType = "TickStart" | "TickEnd" | "AgentBorn" | "AgentDied" | "ActionSubmitted" | "ActionSucceeded" | "ActionRejected" | "ResourceGathered" | "ResourceConsumed" | "TradeCompleted" | "TradeFailed" | "StructureBuilt" | "StructureDestroyed" | "StructureRepaired" | "RouteImproved" | "RouteDegraded" | "LocationDiscovered" | "KnowledgeDiscovered" | "KnowledgeTaught" | "MessageSent" | "GroupFormed" | "RelationshipChanged" | "StructureClaimed" | "RuleCreated" | "EnforcementApplied" | "WeatherChanged" | "SeasonChanged" | "TheftOccurred" | "TheftFailed" | "CombatInitiated" | "CombatResolved" | "LedgerAnomaly";
+export type EventType = "TickStart" | "TickEnd" | "AgentBorn" | "AgentDied" | "ActionSubmitted" | "ActionSucceeded" | "ActionRejected" | "ResourceGathered" | "ResourceConsumed" | "TradeCompleted" | "TradeFailed" | "StructureBuilt" | "StructureDestroyed" | "StructureRepaired" | "RouteImproved" | "RouteDegraded" | "TradeRouteEmerged" | "LocationDiscovered" | "KnowledgeDiscovered" | "KnowledgeTaught" | "MessageSent" | "GroupFormed" | "RelationshipChanged" | "StructureClaimed" | "StructureInherited" | "RuleCreated" | "EnforcementApplied" | "WeatherChanged" | "SeasonChanged" | "FloodOccurred" | "WildfireOccurred" | "EarthquakeOccurred" | "StructureBurned" | "TheftOccurred" | "TheftFailed" | "CombatInitiated" | "CombatResolved" | "LedgerAnomaly" | "ReconciliationMismatch" | "EventsCompacted" | "FamineStarted";
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventType } from "./EventType";
+import type { Resource } from "./Resource";
+
+/**
+ * Details for a summary event that replaced compacted events.
+ *
+ * One summary stands in for every folded event of one type, for one
+ * agent, within one compaction window.
+ */
+export type EventsCompactedDetails = { 
+/**
+ * The type of the folded events.
+ */
+event_type: EventType, 
+/**
+ * Number of events folded.
+ */
+count: number, 
+/**
+ * Tick of the earliest folded event.
+ */
+first_tick: bigint, 
+/**
+ * Tick of the latest folded event.
+ */
+last_tick: bigint, 
+/**
+ * Resource quantities summed across the folded events, for event
+ * types that carry them.
+ */
+resources: { [key in Resource]?: number }, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Details for a famine detected in the event history.
+ *
+ * A famine starts at the first tick at which at least half of the living
+ * agents are starving.
+ */
+export type FamineStartedDetails = { 
+/**
+ * Number of living agents at or above the hunger threshold.
+ */
+starving: number, 
+/**
+ * Number of living agents.
+ */
+population: number, 
+/**
+ * Hunger at which an agent counts as starving.
+ */
+hunger_threshold: number, };
@@ -2,6 +2,7 @@
 import type { EntityType } from "./EntityType";
 import type { LedgerEntryId } from "./LedgerEntryId";
 import type { LedgerEntryType } from "./LedgerEntryType";
+import type { LedgerTag } from "./LedgerTag";
 import type { Resource } from "./Resource";
 
 /**
@@ -55,6 +56,11 @@ reason: string,
  * Related entity such as a trade or structure ID.
  */
 reference_id: string | null, 
+/**
+ * Typed links to what caused the transfer, for grouping the entries
+ * of one action, trade, or rule.
+ */
+tags: Array<LedgerTag>, 
 /**
  * Real-world timestamp.
  */
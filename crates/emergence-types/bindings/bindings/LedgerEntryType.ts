@@ -3,4 +3,4 @@
 /**
  * The category of a resource transfer in the central ledger.
  */
-export type LedgerEntryType = "Regeneration" | "Gather" | "Consume" | "Transfer" | "Build" | "Salvage" | "Decay" | "Drop" | "Pickup" | "Theft" | "CombatLoot";
+export type LedgerEntryType = "Regeneration" | "Gather" | "Consume" | "Transfer" | "Build" | "Salvage" | "Decay" | "Drop" | "Pickup" | "Theft" | "CombatLoot" | "Checkpoint" | "Correction" | "Reversal" | "Mint" | "Burn" | "Deposit" | "Withdrawal" | "Tax" | "Tribute" | "LoanIssued" | "LoanRepayment" | "InterestAccrued" | "Escrow" | "EscrowRelease" | "EnergySpent" | "EnergyRecovered" | "Breeding" | "Slaughter";
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CurrencySupply } from "./CurrencySupply";
+import type { OpeningBalance } from "./OpeningBalance";
+import type { OutstandingLoan } from "./OutstandingLoan";
+
+/**
+ * The ledger's opening balances at the end of a tick.
+ *
+ * A ledger restarted from a snapshot carries these balances forward
+ * instead of replaying every entry recorded before the snapshot tick.
+ */
+export type LedgerSnapshot = { 
+/**
+ * The tick whose end-of-tick balances are recorded.
+ */
+tick: bigint, 
+/**
+ * Every entity's non-zero balance of each resource.
+ */
+balances: Array<OpeningBalance>, 
+/**
+ * The money supply of each adopted currency.
+ */
+currencies: Array<CurrencySupply>, 
+/**
+ * The principal outstanding on each loan between agents.
+ */
+loans: Array<OutstandingLoan>, };
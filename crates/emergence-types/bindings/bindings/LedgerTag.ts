@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventId } from "./EventId";
+import type { RuleId } from "./RuleId";
+import type { TradeId } from "./TradeId";
+
+/**
+ * Typed metadata linking a [`LedgerEntry`] to what caused it.
+ *
+ * An entry may carry several tags, so a trade's transfers, its escrow, and
+ * any toll can all be found by the trade, and each still by its action.
+ */
+export type LedgerTag = { "Action": EventId } | { "Trade": TradeId } | { "Rule": RuleId };
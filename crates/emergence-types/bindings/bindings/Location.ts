@@ -32,6 +32,11 @@ description: string,
  * Maximum number of agents.
  */
 capacity: number, 
+/**
+ * Height above sea level in meters. Climbing and descending between
+ * locations adds to travel time.
+ */
+elevation: number, 
 /**
  * Resource availability at this location.
  */
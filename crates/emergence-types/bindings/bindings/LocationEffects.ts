@@ -6,7 +6,8 @@ import type { Resource } from "./Resource";
  *
  * Computed from the set of standing structures to determine bonuses
  * for agents resting, weather protection, production output, and
- * additional storage capacity.
+ * additional storage capacity, along with the location's crowding
+ * threshold and the penalties of exceeding it.
  */
 export type LocationEffects = { 
 /**
@@ -32,4 +33,20 @@ has_fire: boolean,
 /**
  * Resources produced per tick by structures at this location.
  */
-production: { [key in Resource]?: number }, };
+production: { [key in Resource]?: number }, 
+/**
+ * Occupants the location holds before it is overcrowded.
+ */
+crowding_threshold: number, 
+/**
+ * Agents currently at the location.
+ */
+occupants: number, 
+/**
+ * Percentage of resource regeneration lost to overcrowding.
+ */
+regen_penalty_pct: number, 
+/**
+ * Disease risk as a percentage of normal (100 = not overcrowded).
+ */
+disease_risk_pct: number, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+import type { Resource } from "./Resource";
+
+/**
+ * One entity's balance of one resource in a [`LedgerSnapshot`].
+ */
+export type OpeningBalance = { 
+/**
+ * The entity holding the balance.
+ */
+entity_id: string, 
+/**
+ * The type of the entity, if any entry recorded it.
+ */
+entity_type: EntityType | null, 
+/**
+ * The resource held.
+ */
+resource: Resource, 
+/**
+ * Credits minus debits; negative for a source such as a location.
+ */
+balance: string, };
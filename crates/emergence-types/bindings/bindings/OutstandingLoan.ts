@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Resource } from "./Resource";
+
+/**
+ * The principal a debtor owes a creditor in a [`LedgerSnapshot`].
+ */
+export type OutstandingLoan = { 
+/**
+ * The agent owed.
+ */
+creditor: string, 
+/**
+ * The agent owing.
+ */
+debtor: string, 
+/**
+ * The resource lent.
+ */
+resource: Resource, 
+/**
+ * The principal not yet repaid.
+ */
+principal: string, };
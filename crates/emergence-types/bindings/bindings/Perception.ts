@@ -6,6 +6,7 @@ import type { SelfState } from "./SelfState";
 import type { Surroundings } from "./Surroundings";
 import type { TimeOfDay } from "./TimeOfDay";
 import type { Weather } from "./Weather";
+import type { WeatherForecast } from "./WeatherForecast";
 
 /**
  * The complete perception payload delivered to an agent at the start of
@@ -55,4 +56,9 @@ notifications: Array<string>,
 /**
  * The agent's personality traits (if known). Shapes decision-making.
  */
-personality: Personality | null, };
+personality: Personality | null, 
+/**
+ * The weather the agent expects over the coming ticks. Empty unless
+ * they know weather lore.
+ */
+forecast: Array<WeatherForecast>, };
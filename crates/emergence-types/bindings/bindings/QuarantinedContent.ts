@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentId } from "./AgentId";
+
+/**
+ * Agent-authored text withheld from another agent's prompt.
+ *
+ * Published to NATS by the runner whenever containment quarantines a
+ * message, name, or other peer-written field before prompt rendering.
+ * The engine raises a containment alert for each one.
+ */
+export type QuarantinedContent = { 
+/**
+ * Tick of the perception the content arrived in.
+ */
+tick: bigint, 
+/**
+ * The agent whose prompt the content was withheld from.
+ */
+recipient_id: AgentId, 
+/**
+ * Perception field the content came from (e.g. `"message"`, `"agent_name"`).
+ */
+field: string, 
+/**
+ * Display name of the author, when the field records one.
+ */
+author: string | null, 
+/**
+ * Injection patterns that triggered the quarantine.
+ */
+patterns: Array<string>, 
+/**
+ * The offending text, truncated to 200 characters.
+ */
+excerpt: string, 
+/**
+ * When the content was quarantined.
+ */
+quarantined_at: string, };
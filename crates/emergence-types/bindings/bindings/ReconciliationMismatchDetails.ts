@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+import type { Resource } from "./Resource";
+
+/**
+ * Details for a ledger balance that disagrees with the world state.
+ *
+ * Emitted by the end-of-tick reconciliation scan when an agent's
+ * inventory, a location's resource nodes, or a structure's materials hold
+ * a different quantity than the ledger says they should.
+ */
+export type ReconciliationMismatchDetails = { 
+/**
+ * The entity whose holdings drifted.
+ */
+entity_id: string, 
+/**
+ * The type of the entity.
+ */
+entity_type: EntityType, 
+/**
+ * The resource that drifted.
+ */
+resource: Resource, 
+/**
+ * The entity's balance according to the ledger.
+ */
+ledger_balance: string, 
+/**
+ * The quantity the entity actually holds.
+ */
+world_quantity: string, };
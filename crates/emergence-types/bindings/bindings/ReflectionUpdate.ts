@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentId } from "./AgentId";
+
+/**
+ * An agent's periodic reflection, submitted by the runner for the
+ * reflection phase.
+ *
+ * Unlike an [`ActionRequest`] it does nothing in the world: the engine
+ * replaces the agent's goals and stores the summary as a memory.
+ */
+export type ReflectionUpdate = { 
+/**
+ * The reflecting agent.
+ */
+agent_id: AgentId, 
+/**
+ * The tick whose perception the reflection was based on.
+ */
+tick: bigint, 
+/**
+ * The agent's revised goals, highest priority first. Empty keeps the
+ * current goals.
+ */
+goals: Array<string>, 
+/**
+ * A condensed summary of the agent's recent memories.
+ */
+memory_summary: string | null, 
+/**
+ * Real-world submission timestamp.
+ */
+submitted_at: string, };
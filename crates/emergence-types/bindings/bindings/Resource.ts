@@ -9,4 +9,4 @@
  * - Tier 2: Advanced resources requiring multi-step processes
  * - Tier 3: Complex resources requiring civilization-level coordination
  */
-export type Resource = "Water" | "FoodBerry" | "FoodFish" | "FoodRoot" | "FoodMeat" | "FoodFarmed" | "FoodCooked" | "Wood" | "Stone" | "Fiber" | "Clay" | "Hide" | "Ore" | "Metal" | "Medicine" | "Tool" | "ToolAdvanced" | "CurrencyToken" | "WrittenRecord";
+export type Resource = "Water" | "FoodBerry" | "FoodFish" | "FoodRoot" | "FoodMeat" | "FoodFarmed" | "FoodCooked" | "Wood" | "Stone" | "Fiber" | "Clay" | "Hide" | "Ore" | "Metal" | "Medicine" | "Tool" | "ToolAdvanced" | "CurrencyToken" | "WrittenRecord" | "Map" | "FoodWaste" | "Livestock";
@@ -0,0 +1,46 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendUsage } from "./BackendUsage";
+
+/**
+ * Periodic snapshot of an agent runner's cumulative LLM spend.
+ *
+ * Published to NATS by each runner instance and collected by the engine
+ * for the Observer REST API. Totals are cumulative since runner start.
+ */
+export type RunnerMetrics = { 
+/**
+ * Partition index of the publishing runner.
+ */
+partition_id: number, 
+/**
+ * Successful LLM calls across all backends.
+ */
+total_calls: bigint, 
+/**
+ * Failed LLM calls across all backends.
+ */
+total_errors: bigint, 
+/**
+ * Input/prompt tokens across all backends.
+ */
+total_input_tokens: bigint, 
+/**
+ * Output/completion tokens across all backends.
+ */
+total_output_tokens: bigint, 
+/**
+ * Estimated cost in USD across all backends.
+ */
+total_estimated_cost: string, 
+/**
+ * LLM decisions refused because a budget cap was exhausted.
+ */
+budget_denials: bigint, 
+/**
+ * Per-backend breakdown.
+ */
+backends: Array<BackendUsage>, 
+/**
+ * When this snapshot was taken.
+ */
+published_at: string, };
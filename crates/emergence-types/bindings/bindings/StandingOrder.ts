@@ -0,0 +1,58 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentId } from "./AgentId";
+import type { OrderId } from "./OrderId";
+import type { OrderSide } from "./OrderSide";
+import type { Resource } from "./Resource";
+import type { StructureId } from "./StructureId";
+
+/**
+ * A standing order on a market's order book stored in `Dragonfly`.
+ *
+ * Created when an agent submits a [`PostOrder`] action. What the order
+ * gives up is held in escrow until the order is filled, at the tick it
+ * crosses an opposite order at the same market, or it expires after
+ * `expires_at_tick`. The counterparty need not be present when it fills.
+ *
+ * [`PostOrder`]: crate::ActionType::PostOrder
+ */
+export type StandingOrder = { 
+/**
+ * Unique order identifier, also naming its escrow.
+ */
+order_id: OrderId, 
+/**
+ * Agent who posted the order.
+ */
+agent_id: AgentId, 
+/**
+ * The market structure the order stands at.
+ */
+market_id: StructureId, 
+/**
+ * Whether the order buys or sells `resource`.
+ */
+side: OrderSide, 
+/**
+ * The resource bought or sold.
+ */
+resource: Resource, 
+/**
+ * How much of `resource` is still to be bought or sold.
+ */
+quantity: number, 
+/**
+ * The resource paid in.
+ */
+price_resource: Resource, 
+/**
+ * The limit price per unit of `resource`, in `price_resource`.
+ */
+unit_price: number, 
+/**
+ * Tick when the order was posted.
+ */
+placed_at_tick: bigint, 
+/**
+ * Tick when the order expires if not filled.
+ */
+expires_at_tick: bigint, };
@@ -74,4 +74,8 @@ access_list: AccessControlList | null,
 /**
  * Type-specific properties.
  */
-properties: StructureProperties, };
+properties: StructureProperties, 
+/**
+ * Resources stored in the structure, for shared storage structures.
+ */
+inventory: { [key in Resource]?: number }, };
@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocationId } from "./LocationId";
+import type { StructureId } from "./StructureId";
+import type { StructureType } from "./StructureType";
+
+/**
+ * Details for a structure damaged by a fire burning at its location.
+ *
+ * A fire that spreads between structures emits one of these per
+ * structure per tick it burns.
+ */
+export type StructureBurnedDetails = { 
+/**
+ * The structure that burned.
+ */
+structure_id: StructureId, 
+/**
+ * The type of structure that burned.
+ */
+structure_type: StructureType, 
+/**
+ * The location where the fire burns.
+ */
+location_id: LocationId, 
+/**
+ * Durability lost to the flames this tick.
+ */
+damage: number, 
+/**
+ * Durability left after the fire.
+ */
+durability: number, 
+/**
+ * Whether the structure collapsed.
+ */
+destroyed: boolean, 
+/**
+ * The fire's intensity, from 1 to 5, when it burned the structure.
+ */
+fire_intensity: number, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentId } from "./AgentId";
+import type { LocationId } from "./LocationId";
+import type { StructureId } from "./StructureId";
+import type { StructureType } from "./StructureType";
+
+/**
+ * Details for a structure inherited event.
+ *
+ * Emitted when a structure passes to its owner's heir -- a living spouse,
+ * or failing that a living child -- on the owner's death, instead of
+ * becoming claimable.
+ */
+export type StructureInheritedDetails = { 
+/**
+ * The structure that was inherited.
+ */
+structure_id: StructureId, 
+/**
+ * The type of structure that was inherited.
+ */
+structure_type: StructureType, 
+/**
+ * The agent whose death passed the structure on.
+ */
+deceased: AgentId, 
+/**
+ * The family member who now owns the structure.
+ */
+heir: AgentId, 
+/**
+ * The location where the structure exists.
+ */
+location_id: LocationId, };
@@ -3,4 +3,4 @@
 /**
  * A type of structure that can be built at a location.
  */
-export type StructureType = "Campfire" | "LeanTo" | "BasicHut" | "StoragePit" | "Well" | "FarmPlot" | "Workshop" | "MeetingHall" | "Forge" | "Library" | "Market" | "Wall" | "Bridge";
+export type StructureType = "Campfire" | "LeanTo" | "BasicHut" | "StoragePit" | "Granary" | "Storehouse" | "Well" | "FarmPlot" | "Workshop" | "MeetingHall" | "Palisade" | "Forge" | "Library" | "Market" | "Wall" | "Bridge" | "Tunnel" | "Irrigation" | "Pasture" | "Hearth" | "House" | "Longhouse";
@@ -27,4 +27,14 @@ agents_here: Array<VisibleAgent>,
 /**
  * Broadcast messages posted at this location.
  */
-messages_here: Array<VisibleMessage>, };
+messages_here: Array<VisibleMessage>, 
+/**
+ * Whether the location holds more agents than it comfortably supports.
+ * Resources regrow more slowly and disease hits harder while it does.
+ */
+overcrowded: boolean, 
+/**
+ * Pollution at the location, from 0 (clean) to 100. Polluted land
+ * yields poorer harvests and its water regrows slowly.
+ */
+pollution: number, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocationId } from "./LocationId";
+import type { RouteId } from "./RouteId";
+
+/**
+ * Details for a trade route emerged event (a route's traffic made it a
+ * trade corridor).
+ */
+export type TradeRouteEmergedDetails = { 
+/**
+ * The route that became a corridor.
+ */
+route_id: RouteId, 
+/**
+ * One end of the route.
+ */
+from_location: LocationId, 
+/**
+ * The other end of the route.
+ */
+to_location: LocationId, 
+/**
+ * Trips along the route within the utilization window.
+ */
+trips: number, 
+/**
+ * Ticks of traffic counted towards the route's utilization.
+ */
+window_ticks: bigint, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Weather } from "./Weather";
+
+/**
+ * The weather an agent expects at a coming tick. Forecasts can be wrong;
+ * skilled forecasters are wrong less often.
+ */
+export type WeatherForecast = { 
+/**
+ * The tick forecast for.
+ */
+tick: bigint, 
+/**
+ * The weather expected then.
+ */
+weather: Weather, };
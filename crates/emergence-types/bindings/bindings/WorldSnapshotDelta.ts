@@ -0,0 +1,53 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EconomyStats } from "./EconomyStats";
+import type { Era } from "./Era";
+import type { PopulationStats } from "./PopulationStats";
+import type { Season } from "./Season";
+import type { Weather } from "./Weather";
+
+/**
+ * The changes between two world snapshots.
+ *
+ * Each `Option` field is `None` when the value did not change.
+ */
+export type WorldSnapshotDelta = { 
+/**
+ * Tick of the snapshot this delta applies to.
+ */
+base_tick: bigint, 
+/**
+ * Tick of the snapshot this delta produces.
+ */
+tick: bigint, 
+/**
+ * New era, if it changed.
+ */
+era: Era | null, 
+/**
+ * New season, if it changed.
+ */
+season: Season | null, 
+/**
+ * New weather, if it changed.
+ */
+weather: Weather | null, 
+/**
+ * New population metrics, if any changed.
+ */
+population: PopulationStats | null, 
+/**
+ * New economic metrics, if any changed.
+ */
+economy: EconomyStats | null, 
+/**
+ * Discoveries made since the base snapshot, sorted.
+ */
+discoveries_added: Array<string>, 
+/**
+ * Discoveries no longer listed since the base snapshot, sorted.
+ */
+discoveries_removed: Array<string>, 
+/**
+ * New narrative summary, if it changed.
+ */
+summary: string | null, };
@@ -2,7 +2,8 @@
 //!
 //! Defines the structs from `data-schemas.md` section 7: the request an agent
 //! submits, the parameters for each action type, and the result returned after
-//! resolution.
+//! resolution. Also holds the [`ReflectionUpdate`] an agent submits for the
+//! reflection phase.
 
 use std::collections::BTreeMap;
 
@@ -12,7 +13,7 @@ use ts_rs::TS;
 
 use crate::enums::{ActionType, Resource, StructureType};
 use crate::ids::{AgentId, GroupId, LocationId, RuleId, StructureId, TradeId};
-use crate::structs::RejectionDetails;
+use crate::structs::{OrderSide, RejectionDetails};
 
 // ---------------------------------------------------------------------------
 // Freeform Action Types
@@ -101,16 +102,28 @@ pub enum ActionParameters {
         /// The structure to repair.
         structure_id: StructureId,
     },
+    /// Parameters for [`ActionType::Extinguish`].
+    Extinguish,
     /// Parameters for [`ActionType::Demolish`].
     Demolish {
         /// The structure to demolish.
         structure_id: StructureId,
     },
+    /// Parameters for [`ActionType::UpgradeStructure`].
+    UpgradeStructure {
+        /// The structure to upgrade.
+        structure_id: StructureId,
+    },
     /// Parameters for [`ActionType::ImproveRoute`].
     ImproveRoute {
         /// The route to improve (identified by destination).
         destination: LocationId,
     },
+    /// Parameters for [`ActionType::BuildRoute`].
+    BuildRoute {
+        /// The location the new route will reach.
+        destination: LocationId,
+    },
     /// Parameters for [`ActionType::Communicate`].
     Communicate {
         /// The agent to send a message to.
@@ -142,6 +155,22 @@ pub enum ActionParameters {
         /// The trade to reject.
         trade_id: TradeId,
     },
+    /// Parameters for [`ActionType::PostOrder`].
+    PostOrder {
+        /// The market to post the order at.
+        market_id: StructureId,
+        /// Whether the order buys or sells `resource`.
+        side: OrderSide,
+        /// The resource bought or sold.
+        resource: Resource,
+        /// How much of `resource` to buy or sell.
+        quantity: u32,
+        /// The resource paid in, such as `CurrencyToken`.
+        price_resource: Resource,
+        /// How much of `price_resource` each unit of `resource` costs: the
+        /// most a buyer pays or the least a seller takes.
+        unit_price: u32,
+    },
     /// Parameters for [`ActionType::FormGroup`].
     FormGroup {
         /// Proposed group name.
@@ -160,6 +189,10 @@ pub enum ActionParameters {
     FarmPlant,
     /// Parameters for [`ActionType::FarmHarvest`].
     FarmHarvest,
+    /// Parameters for [`ActionType::Fertilize`].
+    Fertilize,
+    /// Parameters for [`ActionType::Slaughter`].
+    Slaughter,
     /// Parameters for [`ActionType::Craft`].
     Craft {
         /// What to craft (resource output).
@@ -167,6 +200,14 @@ pub enum ActionParameters {
     },
     /// Parameters for [`ActionType::Mine`].
     Mine,
+    /// Parameters for [`ActionType::DeepenMine`].
+    DeepenMine,
+    /// Parameters for [`ActionType::Hunt`].
+    Hunt,
+    /// Parameters for [`ActionType::Prospect`].
+    Prospect,
+    /// Parameters for [`ActionType::Fish`].
+    Fish,
     /// Parameters for [`ActionType::Smelt`].
     Smelt,
     /// Parameters for [`ActionType::Write`].
@@ -179,11 +220,43 @@ pub enum ActionParameters {
         /// Knowledge to retrieve from the library.
         knowledge: String,
     },
+    /// Parameters for [`ActionType::Chart`].
+    Chart,
+    /// Parameters for [`ActionType::Deposit`].
+    Deposit {
+        /// The storage structure to deposit into.
+        structure_id: StructureId,
+        /// The resource to deposit.
+        resource: Resource,
+        /// How much to deposit.
+        quantity: u32,
+    },
+    /// Parameters for [`ActionType::Withdraw`].
+    Withdraw {
+        /// The storage structure to withdraw from.
+        structure_id: StructureId,
+        /// The resource to withdraw.
+        resource: Resource,
+        /// How much to withdraw.
+        quantity: u32,
+    },
     /// Parameters for [`ActionType::Claim`].
     Claim {
         /// The structure to claim.
         structure_id: StructureId,
     },
+    /// Parameters for [`ActionType::TransferOwnership`].
+    TransferOwnership {
+        /// The structure to give away.
+        structure_id: StructureId,
+        /// The agent who receives it.
+        new_owner: AgentId,
+    },
+    /// Parameters for [`ActionType::FoundSettlement`].
+    FoundSettlement {
+        /// Display name for the new settlement.
+        name: String,
+    },
     /// Parameters for [`ActionType::Legislate`].
     Legislate {
         /// Display name for the rule or law.
@@ -224,6 +297,11 @@ pub enum ActionParameters {
         /// The agent to intimidate.
         target_agent: AgentId,
     },
+    /// Parameters for [`ActionType::Breach`].
+    Breach {
+        /// The adjacent location whose fortifications to batter.
+        destination: LocationId,
+    },
     /// Parameters for [`ActionType::Propose`].
     Propose {
         /// The group to propose to.
@@ -337,3 +415,30 @@ pub struct ActionResult {
     /// Observable consequences of the action.
     pub side_effects: Vec<String>,
 }
+
+// ---------------------------------------------------------------------------
+// 7.4 ReflectionUpdate
+// ---------------------------------------------------------------------------
+
+/// An agent's periodic reflection, submitted by the runner for the
+/// reflection phase.
+///
+/// Unlike an [`ActionRequest`] it does nothing in the world: the engine
+/// replaces the agent's goals and stores the summary as a memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct ReflectionUpdate {
+    /// The reflecting agent.
+    pub agent_id: AgentId,
+    /// The tick whose perception the reflection was based on.
+    pub tick: u64,
+    /// The agent's revised goals, highest priority first. Empty keeps the
+    /// current goals.
+    #[serde(default)]
+    pub goals: Vec<String>,
+    /// A condensed summary of the agent's recent memories.
+    #[serde(default)]
+    pub memory_summary: Option<String>,
+    /// Real-world submission timestamp.
+    pub submitted_at: DateTime<Utc>,
+}
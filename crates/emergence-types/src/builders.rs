@@ -0,0 +1,779 @@
+//! Validating builders for the larger entity structs.
+//!
+//! [`AgentState`], [`Structure`], [`Route`], and [`Event`] each carry a
+//! dozen or more fields, most of which start out empty or at a known
+//! default. The builders here take only the fields that have no sensible
+//! default, fill in the rest, and check the result before handing it back,
+//! so call sites name just what they care about and do not change when a
+//! defaulted field is added.
+//!
+//! ```
+//! use emergence_types::{AgentId, AgentState, LocationId, Resource};
+//!
+//! let state = AgentState::builder(AgentId::new(), LocationId::new())
+//!     .energy(60)
+//!     .resource(Resource::Water, 3)
+//!     .build();
+//!
+//! assert!(state.is_ok());
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+use crate::enums::{EventType, PathType, Resource};
+use crate::ids::{AgentId, CorrelationId, EventId, LocationId, RouteId, StructureId};
+use crate::structs::{
+    AccessControlList, AgentState, AgentStateSnapshot, Event, MemoryEntry, Route, Structure,
+    StructureBlueprint, WorldContext,
+};
+
+/// Upper bound of the 0--100 vital scales (energy, health, hunger, thirst).
+const MAX_VITAL: u32 = 100;
+
+/// Maximum number of active goals an agent may hold.
+const MAX_GOALS: usize = 5;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// Why a builder refused to produce a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// A numeric field exceeds its upper bound.
+    OutOfRange {
+        /// The offending field.
+        field: &'static str,
+        /// The value supplied.
+        value: u32,
+        /// The largest allowed value.
+        max: u32,
+    },
+    /// A collection field holds more entries than allowed.
+    TooMany {
+        /// The offending field.
+        field: &'static str,
+        /// The number of entries supplied.
+        count: usize,
+        /// The largest allowed count.
+        max: usize,
+    },
+    /// A rate that must not be negative is negative.
+    Negative(&'static str),
+    /// Fields that are individually valid contradict each other.
+    Inconsistent(&'static str),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange { field, value, max } => {
+                write!(f, "{field} is {value}, above the maximum of {max}")
+            }
+            Self::TooMany { field, count, max } => {
+                write!(f, "{field} has {count} entries, above the maximum of {max}")
+            }
+            Self::Negative(field) => write!(f, "{field} must not be negative"),
+            Self::Inconsistent(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Reject `value` if it exceeds `max`.
+const fn check_max(field: &'static str, value: u32, max: u32) -> Result<(), BuildError> {
+    if value > max {
+        return Err(BuildError::OutOfRange { field, value, max });
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// AgentState
+// ---------------------------------------------------------------------------
+
+/// Builder for [`AgentState`].
+///
+/// Starts from a newly spawned agent: energy 80, health 100, no hunger or
+/// thirst, age 0, carry capacity 50, and empty inventory, knowledge,
+/// skills, goals, relationships, and memory.
+#[derive(Debug, Clone)]
+pub struct AgentStateBuilder {
+    state: AgentState,
+}
+
+impl AgentState {
+    /// Start building the state of `agent_id`, standing at `location_id`.
+    pub const fn builder(agent_id: AgentId, location_id: LocationId) -> AgentStateBuilder {
+        AgentStateBuilder::new(agent_id, location_id)
+    }
+}
+
+impl AgentStateBuilder {
+    /// Start building the state of `agent_id`, standing at `location_id`.
+    pub const fn new(agent_id: AgentId, location_id: LocationId) -> Self {
+        Self {
+            state: AgentState {
+                agent_id,
+                energy: 80,
+                health: MAX_VITAL,
+                hunger: 0,
+                thirst: 0,
+                age: 0,
+                born_at_tick: 0,
+                location_id,
+                destination_id: None,
+                travel_progress: 0,
+                inventory: BTreeMap::new(),
+                carry_capacity: 50,
+                knowledge: BTreeSet::new(),
+                skills: BTreeMap::new(),
+                skill_xp: BTreeMap::new(),
+                goals: Vec::new(),
+                relationships: BTreeMap::new(),
+                memory: Vec::new(),
+            },
+        }
+    }
+
+    /// Set the energy level (0--100).
+    #[must_use]
+    pub const fn energy(mut self, energy: u32) -> Self {
+        self.state.energy = energy;
+        self
+    }
+
+    /// Set the health level (0--100).
+    #[must_use]
+    pub const fn health(mut self, health: u32) -> Self {
+        self.state.health = health;
+        self
+    }
+
+    /// Set the hunger level (0--100).
+    #[must_use]
+    pub const fn hunger(mut self, hunger: u32) -> Self {
+        self.state.hunger = hunger;
+        self
+    }
+
+    /// Set the thirst level (0--100).
+    #[must_use]
+    pub const fn thirst(mut self, thirst: u32) -> Self {
+        self.state.thirst = thirst;
+        self
+    }
+
+    /// Set the age in ticks.
+    #[must_use]
+    pub const fn age(mut self, age: u32) -> Self {
+        self.state.age = age;
+        self
+    }
+
+    /// Set the tick the agent entered the simulation.
+    #[must_use]
+    pub const fn born_at_tick(mut self, tick: u64) -> Self {
+        self.state.born_at_tick = tick;
+        self
+    }
+
+    /// Put the agent in transit to `destination`, `progress` ticks from
+    /// arrival.
+    #[must_use]
+    pub const fn traveling(mut self, destination: LocationId, progress: u32) -> Self {
+        self.state.destination_id = Some(destination);
+        self.state.travel_progress = progress;
+        self
+    }
+
+    /// Replace the whole inventory.
+    #[must_use]
+    pub fn inventory(mut self, inventory: BTreeMap<Resource, u32>) -> Self {
+        self.state.inventory = inventory;
+        self
+    }
+
+    /// Set the carried quantity of one resource.
+    #[must_use]
+    pub fn resource(mut self, resource: Resource, quantity: u32) -> Self {
+        self.state.inventory.insert(resource, quantity);
+        self
+    }
+
+    /// Set the maximum carry weight.
+    #[must_use]
+    pub const fn carry_capacity(mut self, capacity: u32) -> Self {
+        self.state.carry_capacity = capacity;
+        self
+    }
+
+    /// Set the known concepts.
+    #[must_use]
+    pub fn knowledge(mut self, knowledge: BTreeSet<String>) -> Self {
+        self.state.knowledge = knowledge;
+        self
+    }
+
+    /// Set one skill's level.
+    #[must_use]
+    pub fn skill(mut self, name: &str, level: u32) -> Self {
+        self.state.skills.insert(name.to_owned(), level);
+        self
+    }
+
+    /// Set the active goals (at most 5).
+    #[must_use]
+    pub fn goals(mut self, goals: Vec<String>) -> Self {
+        self.state.goals = goals;
+        self
+    }
+
+    /// Set the relationship score toward another agent.
+    #[must_use]
+    pub fn relationship(mut self, other: AgentId, score: Decimal) -> Self {
+        self.state.relationships.insert(other, score);
+        self
+    }
+
+    /// Set the memory entries.
+    #[must_use]
+    pub fn memory(mut self, memory: Vec<MemoryEntry>) -> Self {
+        self.state.memory = memory;
+        self
+    }
+
+    /// Validate and produce the [`AgentState`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::OutOfRange`] if a vital exceeds 100,
+    /// [`BuildError::TooMany`] if there are more than 5 goals, and
+    /// [`BuildError::Inconsistent`] if travel progress is set without a
+    /// destination.
+    pub fn build(self) -> Result<AgentState, BuildError> {
+        let state = self.state;
+        check_max("energy", state.energy, MAX_VITAL)?;
+        check_max("health", state.health, MAX_VITAL)?;
+        check_max("hunger", state.hunger, MAX_VITAL)?;
+        check_max("thirst", state.thirst, MAX_VITAL)?;
+        if state.goals.len() > MAX_GOALS {
+            return Err(BuildError::TooMany {
+                field: "goals",
+                count: state.goals.len(),
+                max: MAX_GOALS,
+            });
+        }
+        if state.travel_progress > 0 && state.destination_id.is_none() {
+            return Err(BuildError::Inconsistent("travel progress set without a destination"));
+        }
+        Ok(state)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Structure
+// ---------------------------------------------------------------------------
+
+/// Builder for [`Structure`].
+///
+/// Starts from a freshly built structure: durability, decay, capacity,
+/// materials, and properties come from the blueprint; the builder owns
+/// it; it is unoccupied, standing, and has no access list.
+#[derive(Debug, Clone)]
+pub struct StructureBuilder {
+    structure: Structure,
+}
+
+impl Structure {
+    /// Start building a structure from `blueprint`, raised by `builder` at
+    /// `location_id` on tick `built_at_tick`.
+    pub fn builder(
+        blueprint: &StructureBlueprint,
+        location_id: LocationId,
+        builder: AgentId,
+        built_at_tick: u64,
+    ) -> StructureBuilder {
+        StructureBuilder::from_blueprint(blueprint, location_id, builder, built_at_tick)
+    }
+}
+
+impl StructureBuilder {
+    /// Start building a structure from `blueprint`, raised by `builder` at
+    /// `location_id` on tick `built_at_tick`.
+    pub fn from_blueprint(
+        blueprint: &StructureBlueprint,
+        location_id: LocationId,
+        builder: AgentId,
+        built_at_tick: u64,
+    ) -> Self {
+        Self {
+            structure: Structure {
+                id: StructureId::new(),
+                structure_type: blueprint.structure_type,
+                subtype: None,
+                location_id,
+                builder,
+                owner: Some(builder),
+                built_at_tick,
+                destroyed_at_tick: None,
+                materials_used: blueprint.material_costs.clone(),
+                durability: blueprint.max_durability,
+                max_durability: blueprint.max_durability,
+                decay_per_tick: blueprint.decay_per_tick,
+                capacity: blueprint.capacity,
+                occupants: BTreeSet::new(),
+                access_list: None,
+                properties: blueprint.properties.clone(),
+                inventory: BTreeMap::new(),
+            },
+        }
+    }
+
+    /// Use a specific ID instead of a fresh one.
+    #[must_use]
+    pub const fn id(mut self, id: StructureId) -> Self {
+        self.structure.id = id;
+        self
+    }
+
+    /// Set the variant within the structure type.
+    #[must_use]
+    pub fn subtype(mut self, subtype: String) -> Self {
+        self.structure.subtype = Some(subtype);
+        self
+    }
+
+    /// Set the owner (`None` for unowned).
+    #[must_use]
+    pub const fn owner(mut self, owner: Option<AgentId>) -> Self {
+        self.structure.owner = owner;
+        self
+    }
+
+    /// Set the current durability.
+    #[must_use]
+    pub const fn durability(mut self, durability: u32) -> Self {
+        self.structure.durability = durability;
+        self
+    }
+
+    /// Mark the structure destroyed on `tick`.
+    #[must_use]
+    pub const fn destroyed_at_tick(mut self, tick: u64) -> Self {
+        self.structure.destroyed_at_tick = Some(tick);
+        self
+    }
+
+    /// Set the current occupants.
+    #[must_use]
+    pub fn occupants(mut self, occupants: BTreeSet<AgentId>) -> Self {
+        self.structure.occupants = occupants;
+        self
+    }
+
+    /// Restrict access.
+    #[must_use]
+    pub fn access_list(mut self, acl: AccessControlList) -> Self {
+        self.structure.access_list = Some(acl);
+        self
+    }
+
+    /// Validate and produce the [`Structure`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::OutOfRange`] if durability exceeds the
+    /// maximum, [`BuildError::Negative`] if decay is negative,
+    /// [`BuildError::TooMany`] if occupants exceed capacity, and
+    /// [`BuildError::Inconsistent`] if the structure is destroyed before it
+    /// was built.
+    pub fn build(self) -> Result<Structure, BuildError> {
+        let structure = self.structure;
+        check_max("durability", structure.durability, structure.max_durability)?;
+        if structure.decay_per_tick.is_sign_negative() {
+            return Err(BuildError::Negative("decay_per_tick"));
+        }
+        let capacity = usize::try_from(structure.capacity).unwrap_or(usize::MAX);
+        if structure.occupants.len() > capacity {
+            return Err(BuildError::TooMany {
+                field: "occupants",
+                count: structure.occupants.len(),
+                max: capacity,
+            });
+        }
+        if structure.destroyed_at_tick.is_some_and(|tick| tick < structure.built_at_tick) {
+            return Err(BuildError::Inconsistent("structure destroyed before it was built"));
+        }
+        Ok(structure)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Route
+// ---------------------------------------------------------------------------
+
+/// Builder for [`Route`].
+///
+/// Starts from a natural, public, two-way route in perfect condition
+/// (durability 100 of 100) that does not decay.
+#[derive(Debug, Clone)]
+pub struct RouteBuilder {
+    route: Route,
+}
+
+impl Route {
+    /// Start building a route from `from_location` to `to_location` that
+    /// takes `cost_ticks` to travel.
+    pub fn builder(
+        from_location: LocationId,
+        to_location: LocationId,
+        cost_ticks: u32,
+        path_type: PathType,
+    ) -> RouteBuilder {
+        RouteBuilder::new(from_location, to_location, cost_ticks, path_type)
+    }
+}
+
+impl RouteBuilder {
+    /// Start building a route from `from_location` to `to_location` that
+    /// takes `cost_ticks` to travel.
+    pub fn new(
+        from_location: LocationId,
+        to_location: LocationId,
+        cost_ticks: u32,
+        path_type: PathType,
+    ) -> Self {
+        Self {
+            route: Route {
+                id: RouteId::new(),
+                from_location,
+                to_location,
+                cost_ticks,
+                path_type,
+                durability: 100,
+                max_durability: 100,
+                decay_per_tick: Decimal::ZERO,
+                acl: None,
+                bidirectional: true,
+                built_by: None,
+                built_at_tick: None,
+            },
+        }
+    }
+
+    /// Use a specific ID instead of a fresh one.
+    #[must_use]
+    pub const fn id(mut self, id: RouteId) -> Self {
+        self.route.id = id;
+        self
+    }
+
+    /// Set the current and maximum condition.
+    #[must_use]
+    pub const fn durability(mut self, durability: u32, max_durability: u32) -> Self {
+        self.route.durability = durability;
+        self.route.max_durability = max_durability;
+        self
+    }
+
+    /// Set the degradation rate per tick.
+    #[must_use]
+    pub const fn decay_per_tick(mut self, decay: Decimal) -> Self {
+        self.route.decay_per_tick = decay;
+        self
+    }
+
+    /// Restrict access.
+    #[must_use]
+    pub fn acl(mut self, acl: AccessControlList) -> Self {
+        self.route.acl = Some(acl);
+        self
+    }
+
+    /// Make the route usable only from origin to destination.
+    #[must_use]
+    pub const fn one_way(mut self) -> Self {
+        self.route.bidirectional = false;
+        self
+    }
+
+    /// Record that `agent` built the route on `tick`.
+    #[must_use]
+    pub const fn built_by(mut self, agent: AgentId, tick: u64) -> Self {
+        self.route.built_by = Some(agent);
+        self.route.built_at_tick = Some(tick);
+        self
+    }
+
+    /// Validate and produce the [`Route`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::OutOfRange`] if durability exceeds the
+    /// maximum, [`BuildError::Negative`] if decay is negative, and
+    /// [`BuildError::Inconsistent`] if the route leads back to its origin.
+    pub fn build(self) -> Result<Route, BuildError> {
+        let route = self.route;
+        check_max("durability", route.durability, route.max_durability)?;
+        if route.decay_per_tick.is_sign_negative() {
+            return Err(BuildError::Negative("decay_per_tick"));
+        }
+        if route.from_location == route.to_location {
+            return Err(BuildError::Inconsistent("route leads back to its origin"));
+        }
+        Ok(route)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event
+// ---------------------------------------------------------------------------
+
+/// Builder for [`Event`].
+///
+/// The tick comes from the world context. Starts with no agent, location,
+/// or agent snapshot and an empty details object.
+#[derive(Debug, Clone)]
+pub struct EventBuilder {
+    event: Event,
+}
+
+impl Event {
+    /// Start building an event of `event_type` recorded in `world_context`.
+    pub fn builder(event_type: EventType, world_context: WorldContext) -> EventBuilder {
+        EventBuilder::new(event_type, world_context)
+    }
+}
+
+impl EventBuilder {
+    /// Start building an event of `event_type` recorded in `world_context`.
+    pub fn new(event_type: EventType, world_context: WorldContext) -> Self {
+        Self {
+            event: Event {
+                id: EventId::new(),
+                tick: world_context.tick,
+                event_type,
+                agent_id: None,
+                location_id: None,
+                details: serde_json::Value::Object(serde_json::Map::new()),
+                agent_state_snapshot: None,
+                world_context,
+                created_at: Utc::now(),
+                caused_by: None,
+                correlation_id: None,
+            },
+        }
+    }
+
+    /// Set the primary agent involved.
+    #[must_use]
+    pub const fn agent(mut self, agent_id: AgentId) -> Self {
+        self.event.agent_id = Some(agent_id);
+        self
+    }
+
+    /// Set where the event occurred.
+    #[must_use]
+    pub const fn location(mut self, location_id: LocationId) -> Self {
+        self.event.location_id = Some(location_id);
+        self
+    }
+
+    /// Set the type-specific payload.
+    #[must_use]
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.event.details = details;
+        self
+    }
+
+    /// Attach the agent's state at event time.
+    #[must_use]
+    pub fn agent_state(mut self, snapshot: AgentStateSnapshot) -> Self {
+        self.event.agent_state_snapshot = Some(snapshot);
+        self
+    }
+
+    /// Record the event that directly triggered this one.
+    #[must_use]
+    pub const fn caused_by(mut self, cause: EventId) -> Self {
+        self.event.caused_by = Some(cause);
+        self
+    }
+
+    /// Place the event in a cascade of related events.
+    #[must_use]
+    pub const fn correlation(mut self, correlation_id: CorrelationId) -> Self {
+        self.event.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Validate and produce the [`Event`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::Inconsistent`] if the details are not a JSON
+    /// object, or if an agent snapshot is attached without an agent.
+    pub fn build(self) -> Result<Event, BuildError> {
+        let event = self.event;
+        if !event.details.is_object() {
+            return Err(BuildError::Inconsistent("event details must be a JSON object"));
+        }
+        if event.agent_state_snapshot.is_some() && event.agent_id.is_none() {
+            return Err(BuildError::Inconsistent("agent snapshot attached without an agent"));
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{Era, Season, StructureCategory, StructureType, Weather};
+    use crate::structs::StructureProperties;
+
+    fn blueprint() -> StructureBlueprint {
+        StructureBlueprint {
+            structure_type: StructureType::Campfire,
+            category: StructureCategory::Utility,
+            material_costs: BTreeMap::from([(Resource::Wood, 3)]),
+            required_knowledge: String::from("build_campfire"),
+            max_durability: 50,
+            decay_per_tick: Decimal::new(5, 1),
+            capacity: 2,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: false,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        }
+    }
+
+    fn context() -> WorldContext {
+        WorldContext {
+            tick: 7,
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: 3,
+        }
+    }
+
+    #[test]
+    fn agent_state_defaults_to_fresh_spawn() {
+        let agent = AgentId::new();
+        let location = LocationId::new();
+        let state = AgentState::builder(agent, location)
+            .resource(Resource::Water, 5)
+            .build();
+        assert_eq!(state.as_ref().map(|s| s.agent_id).ok(), Some(agent));
+        assert_eq!(state.as_ref().map(|s| s.energy).ok(), Some(80));
+        assert_eq!(state.as_ref().map(|s| s.carry_capacity).ok(), Some(50));
+        assert_eq!(
+            state.map(|s| s.inventory).unwrap_or_default(),
+            BTreeMap::from([(Resource::Water, 5)])
+        );
+    }
+
+    #[test]
+    fn agent_state_rejects_invalid_fields() {
+        let builder = AgentState::builder(AgentId::new(), LocationId::new());
+        assert_eq!(
+            builder.clone().health(101).build().err(),
+            Some(BuildError::OutOfRange { field: "health", value: 101, max: 100 })
+        );
+        let goals = vec![String::from("g"); 6];
+        assert!(matches!(
+            builder.clone().goals(goals).build(),
+            Err(BuildError::TooMany { field: "goals", .. })
+        ));
+        let mut traveling = builder.traveling(LocationId::new(), 2);
+        traveling.state.destination_id = None;
+        assert!(matches!(traveling.build(), Err(BuildError::Inconsistent(_))));
+    }
+
+    #[test]
+    fn structure_starts_from_blueprint() {
+        let bp = blueprint();
+        let builder_id = AgentId::new();
+        let structure = Structure::builder(&bp, LocationId::new(), builder_id, 10).build();
+        assert_eq!(structure.as_ref().map(|s| s.durability).ok(), Some(50));
+        assert_eq!(structure.as_ref().map(|s| s.owner).ok(), Some(Some(builder_id)));
+        assert_eq!(structure.map(|s| s.materials_used).unwrap_or_default(), bp.material_costs);
+    }
+
+    #[test]
+    fn structure_rejects_invalid_fields() {
+        let bp = blueprint();
+        let builder = Structure::builder(&bp, LocationId::new(), AgentId::new(), 10);
+        assert!(matches!(
+            builder.clone().durability(51).build(),
+            Err(BuildError::OutOfRange { field: "durability", .. })
+        ));
+        let crowd = (0..3).map(|_| AgentId::new()).collect();
+        assert!(matches!(
+            builder.clone().occupants(crowd).build(),
+            Err(BuildError::TooMany { field: "occupants", .. })
+        ));
+        assert!(matches!(
+            builder.destroyed_at_tick(9).build(),
+            Err(BuildError::Inconsistent(_))
+        ));
+    }
+
+    #[test]
+    fn route_validates_endpoints_and_condition() {
+        let here = LocationId::new();
+        let there = LocationId::new();
+        let route = Route::builder(here, there, 3, PathType::DirtTrail).one_way().build();
+        assert_eq!(route.as_ref().map(|r| r.bidirectional).ok(), Some(false));
+        assert_eq!(route.map(|r| r.durability).ok(), Some(100));
+
+        let loop_back = Route::builder(here, here, 3, PathType::DirtTrail).build();
+        assert!(matches!(loop_back, Err(BuildError::Inconsistent(_))));
+        let worn = Route::builder(here, there, 3, PathType::DirtTrail)
+            .durability(120, 100)
+            .build();
+        assert!(matches!(worn, Err(BuildError::OutOfRange { field: "durability", .. })));
+        let growing = Route::builder(here, there, 3, PathType::DirtTrail)
+            .decay_per_tick(Decimal::NEGATIVE_ONE)
+            .build();
+        assert_eq!(growing.err(), Some(BuildError::Negative("decay_per_tick")));
+    }
+
+    #[test]
+    fn event_takes_tick_from_context() {
+        let agent = AgentId::new();
+        let event = Event::builder(EventType::TickStart, context())
+            .agent(agent)
+            .details(serde_json::json!({"note": "dawn"}))
+            .build();
+        assert_eq!(event.as_ref().map(|e| e.tick).ok(), Some(7));
+        assert_eq!(event.map(|e| e.agent_id).ok(), Some(Some(agent)));
+
+        let bad = Event::builder(EventType::TickStart, context())
+            .details(serde_json::json!([1, 2]))
+            .build();
+        assert!(matches!(bad, Err(BuildError::Inconsistent(_))));
+    }
+
+    #[test]
+    fn event_records_its_cause() {
+        let cause = Event::builder(EventType::RuleCreated, context()).build().ok();
+        let cause_id = cause.as_ref().map_or_else(EventId::new, |e| e.id);
+        let correlation = CorrelationId::new();
+        let effect = Event::builder(EventType::EnforcementApplied, context())
+            .caused_by(cause_id)
+            .correlation(correlation)
+            .build();
+        assert_eq!(effect.as_ref().map(|e| e.caused_by).ok(), Some(Some(cause_id)));
+        assert_eq!(effect.map(|e| e.correlation_id).ok(), Some(Some(correlation)));
+        assert_eq!(cause.and_then(|e| e.caused_by), None);
+    }
+}
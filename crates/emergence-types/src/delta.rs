@@ -0,0 +1,305 @@
+//! Tick-to-tick deltas of the world snapshot.
+//!
+//! A [`WorldSnapshot`] carries the full population and economy summaries
+//! and every discovery made so far, yet little of it changes from one tick
+//! to the next. A [`WorldSnapshotDelta`] records only what did, so the
+//! observer can push it over the `WebSocket` and the snapshot store can
+//! append it instead of rewriting the whole snapshot.
+//!
+//! - [`WorldSnapshotDelta::between`] diffs two snapshots.
+//! - [`WorldSnapshot::apply_delta`] moves a snapshot forward by a delta.
+//! - [`WorldSnapshotDelta::compose`] merges consecutive deltas into one.
+//!
+//! Discoveries are treated as a sorted set: applying a delta keeps them
+//! sorted and free of duplicates.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::enums::{Era, Season, Weather};
+use crate::structs::{EconomyStats, PopulationStats, WorldSnapshot};
+
+/// The changes between two world snapshots.
+///
+/// Each `Option` field is `None` when the value did not change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct WorldSnapshotDelta {
+    /// Tick of the snapshot this delta applies to.
+    pub base_tick: u64,
+    /// Tick of the snapshot this delta produces.
+    pub tick: u64,
+    /// New era, if it changed.
+    #[serde(default)]
+    pub era: Option<Era>,
+    /// New season, if it changed.
+    #[serde(default)]
+    pub season: Option<Season>,
+    /// New weather, if it changed.
+    #[serde(default)]
+    pub weather: Option<Weather>,
+    /// New population metrics, if any changed.
+    #[serde(default)]
+    pub population: Option<PopulationStats>,
+    /// New economic metrics, if any changed.
+    #[serde(default)]
+    pub economy: Option<EconomyStats>,
+    /// Discoveries made since the base snapshot, sorted.
+    #[serde(default)]
+    pub discoveries_added: Vec<String>,
+    /// Discoveries no longer listed since the base snapshot, sorted.
+    #[serde(default)]
+    pub discoveries_removed: Vec<String>,
+    /// New narrative summary, if it changed.
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+/// A delta did not line up with the snapshot or delta it was combined with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeltaMismatch {
+    /// The tick the delta needed as its base.
+    pub expected: u64,
+    /// The tick it was given.
+    pub found: u64,
+}
+
+impl std::fmt::Display for DeltaMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "delta is based on tick {} but was applied to tick {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for DeltaMismatch {}
+
+/// `Some(next)` if it differs from `prev`.
+fn changed<T: PartialEq + Clone>(prev: &T, next: &T) -> Option<T> {
+    (prev != next).then(|| next.clone())
+}
+
+impl WorldSnapshotDelta {
+    /// The changes that turn `prev` into `next`.
+    pub fn between(prev: &WorldSnapshot, next: &WorldSnapshot) -> Self {
+        let before: BTreeSet<&String> = prev.discoveries.iter().collect();
+        let after: BTreeSet<&String> = next.discoveries.iter().collect();
+        Self {
+            base_tick: prev.tick,
+            tick: next.tick,
+            era: changed(&prev.era, &next.era),
+            season: changed(&prev.season, &next.season),
+            weather: changed(&prev.weather, &next.weather),
+            population: changed(&prev.population, &next.population),
+            economy: changed(&prev.economy, &next.economy),
+            discoveries_added: after.difference(&before).map(|d| (*d).clone()).collect(),
+            discoveries_removed: before.difference(&after).map(|d| (*d).clone()).collect(),
+            summary: changed(&prev.summary, &next.summary),
+        }
+    }
+
+    /// Merge this delta with the one that follows it.
+    ///
+    /// Applying the result is equivalent to applying `self` and then
+    /// `later`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeltaMismatch`] if `later` is not based on this delta's
+    /// tick.
+    pub fn compose(self, later: Self) -> Result<Self, DeltaMismatch> {
+        if later.base_tick != self.tick {
+            return Err(DeltaMismatch {
+                expected: later.base_tick,
+                found: self.tick,
+            });
+        }
+        let added_first: BTreeSet<String> = self.discoveries_added.into_iter().collect();
+        let removed_first: BTreeSet<String> = self.discoveries_removed.into_iter().collect();
+        let added_later: BTreeSet<String> = later.discoveries_added.into_iter().collect();
+        let removed_later: BTreeSet<String> = later.discoveries_removed.into_iter().collect();
+
+        // A discovery added then removed (or removed then re-added) cancels.
+        let added = added_first
+            .difference(&removed_later)
+            .chain(added_later.difference(&removed_first))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let removed = removed_first
+            .difference(&added_later)
+            .chain(removed_later.difference(&added_first))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        Ok(Self {
+            base_tick: self.base_tick,
+            tick: later.tick,
+            era: later.era.or(self.era),
+            season: later.season.or(self.season),
+            weather: later.weather.or(self.weather),
+            population: later.population.or(self.population),
+            economy: later.economy.or(self.economy),
+            discoveries_added: added.into_iter().collect(),
+            discoveries_removed: removed.into_iter().collect(),
+            summary: later.summary.or(self.summary),
+        })
+    }
+}
+
+impl WorldSnapshot {
+    /// Move this snapshot forward by `delta`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeltaMismatch`] if `delta` is not based on this
+    /// snapshot's tick; the snapshot is left unchanged.
+    pub fn apply_delta(&mut self, delta: &WorldSnapshotDelta) -> Result<(), DeltaMismatch> {
+        if delta.base_tick != self.tick {
+            return Err(DeltaMismatch {
+                expected: delta.base_tick,
+                found: self.tick,
+            });
+        }
+        self.tick = delta.tick;
+        if let Some(era) = delta.era {
+            self.era = era;
+        }
+        if let Some(season) = delta.season {
+            self.season = season;
+        }
+        if let Some(weather) = delta.weather {
+            self.weather = weather;
+        }
+        if let Some(population) = &delta.population {
+            self.population.clone_from(population);
+        }
+        if let Some(economy) = &delta.economy {
+            self.economy.clone_from(economy);
+        }
+        if let Some(summary) = &delta.summary {
+            self.summary.clone_from(summary);
+        }
+
+        let mut discoveries: BTreeSet<String> = std::mem::take(&mut self.discoveries)
+            .into_iter()
+            .collect();
+        for removed in &delta.discoveries_removed {
+            discoveries.remove(removed);
+        }
+        discoveries.extend(delta.discoveries_added.iter().cloned());
+        self.discoveries = discoveries.into_iter().collect();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::enums::Resource;
+
+    fn snapshot(tick: u64, alive: u32, discoveries: &[&str]) -> WorldSnapshot {
+        WorldSnapshot {
+            tick,
+            era: Era::Primitive,
+            season: Season::Spring,
+            weather: Weather::Clear,
+            population: PopulationStats {
+                total_alive: alive,
+                total_dead: 0,
+                births_this_tick: 0,
+                deaths_this_tick: 0,
+                average_age: Decimal::ZERO,
+                oldest_agent: None,
+            },
+            economy: EconomyStats {
+                total_resources: BTreeMap::from([(Resource::Wood, 40)]),
+                resources_in_circulation: BTreeMap::new(),
+                resources_at_nodes: BTreeMap::from([(Resource::Wood, 40)]),
+                trades_this_tick: 0,
+                gini_coefficient: Decimal::ZERO,
+            },
+            discoveries: discoveries.iter().map(|d| (*d).to_owned()).collect(),
+            summary: format!("Tick {tick} complete."),
+        }
+    }
+
+    #[test]
+    fn delta_records_only_changes() {
+        let prev = snapshot(1, 10, &["fire"]);
+        let mut next = snapshot(2, 10, &["fire", "pottery"]);
+        next.weather = Weather::Rain;
+
+        let delta = WorldSnapshotDelta::between(&prev, &next);
+        assert_eq!(delta.weather, Some(Weather::Rain));
+        assert!(delta.season.is_none());
+        assert!(delta.population.is_none());
+        assert!(delta.economy.is_none());
+        assert_eq!(delta.discoveries_added, vec!["pottery".to_owned()]);
+        assert!(delta.discoveries_removed.is_empty());
+
+        let json = serde_json::to_value(&delta).unwrap_or_default();
+        assert_eq!(json.get("population"), Some(&serde_json::Value::Null));
+        assert_eq!(json.get("discoveries_removed"), Some(&serde_json::json!([])));
+    }
+
+    #[test]
+    fn apply_reproduces_next_snapshot() {
+        let prev = snapshot(1, 10, &["fire", "weaving"]);
+        let mut next = snapshot(2, 9, &["fire", "pottery"]);
+        next.economy.trades_this_tick = 3;
+
+        let mut rebuilt = prev.clone();
+        let applied = rebuilt.apply_delta(&WorldSnapshotDelta::between(&prev, &next));
+        assert!(applied.is_ok());
+        assert_eq!(rebuilt, next);
+    }
+
+    #[test]
+    fn apply_rejects_wrong_base() {
+        let prev = snapshot(1, 10, &[]);
+        let next = snapshot(2, 10, &[]);
+        let delta = WorldSnapshotDelta::between(&prev, &next);
+
+        let mut stale = snapshot(5, 10, &[]);
+        assert_eq!(
+            stale.apply_delta(&delta),
+            Err(DeltaMismatch {
+                expected: 1,
+                found: 5
+            })
+        );
+        assert_eq!(stale.tick, 5);
+    }
+
+    #[test]
+    fn compose_matches_sequential_apply() {
+        let first = snapshot(1, 10, &["fire", "weaving"]);
+        let mut second = snapshot(2, 11, &["fire", "pottery"]);
+        second.season = Season::Summer;
+        let third = snapshot(3, 11, &["fire", "weaving"]);
+
+        let a = WorldSnapshotDelta::between(&first, &second);
+        let b = WorldSnapshotDelta::between(&second, &third);
+        assert!(b.clone().compose(a.clone()).is_err());
+
+        let combined = a.compose(b);
+        assert_eq!(combined.as_ref().map(|d| (d.base_tick, d.tick)).ok(), Some((1, 3)));
+        // Pottery came and went; weaving went and came back.
+        assert_eq!(
+            combined.as_ref().map(|d| d.discoveries_added.len() + d.discoveries_removed.len()).ok(),
+            Some(0)
+        );
+
+        let mut rebuilt = first;
+        assert!(combined.is_ok_and(|d| rebuilt.apply_delta(&d).is_ok()));
+        assert_eq!(rebuilt, third);
+    }
+}
@@ -6,116 +6,175 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+/// Generates a fieldless enum with standard derives and an `ALL` constant
+/// listing every variant, so callers never keep a hand-copied variant list.
+macro_rules! define_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+        #[ts(export, export_to = "bindings/")]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// Every variant, in declaration order.
+            pub const ALL: &[Self] = &[$(Self::$variant),*];
+        }
+    };
+}
+
 // ---------------------------------------------------------------------------
 // 3.1 Resource Types
 // ---------------------------------------------------------------------------
 
-/// A resource that exists in the simulation world.
-///
-/// Resources are organized into tiers reflecting technological progression:
-/// - Tier 0: Survival basics available from tick 0
-/// - Tier 1: Developed resources requiring discovery or effort
-/// - Tier 2: Advanced resources requiring multi-step processes
-/// - Tier 3: Complex resources requiring civilization-level coordination
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "bindings/")]
-pub enum Resource {
-    // --- Tier 0: Survival ---
-    /// Fresh water for hydration.
-    Water,
-    /// Wild berries gathered from bushes.
-    FoodBerry,
-    /// Fish caught from rivers or coastline.
-    FoodFish,
-    /// Edible roots dug from soil.
-    FoodRoot,
-
-    // --- Tier 1: Survival (developed) ---
-    /// Meat from hunted animals.
-    FoodMeat,
-    /// Crops grown via agriculture.
-    FoodFarmed,
-    /// Food prepared with fire for higher nutritional value.
-    FoodCooked,
-
-    // --- Tier 0: Material ---
-    /// Lumber harvested from forests.
-    Wood,
-    /// Raw stone from rocky areas.
-    Stone,
-
-    // --- Tier 1: Material ---
-    /// Plant fiber for rope, baskets, and textiles.
-    Fiber,
-    /// Malleable clay from riverbanks.
-    Clay,
-    /// Animal hides for clothing and shelter.
-    Hide,
-
-    // --- Tier 2: Material ---
-    /// Raw ore extracted from mines.
-    Ore,
-    /// Refined metal smelted from ore.
-    Metal,
-
-    // --- Tier 2: Consumable ---
-    /// Herbal medicine for health restoration.
-    Medicine,
-
-    // --- Tier 1: Equipment ---
-    /// Basic tools crafted from wood and stone.
-    Tool,
-
-    // --- Tier 2: Equipment ---
-    /// Advanced tools crafted with metal.
-    ToolAdvanced,
-
-    // --- Tier 3: Abstract ---
-    /// Collectively agreed-upon medium of exchange.
-    CurrencyToken,
-    /// Persistent knowledge stored on a physical medium.
-    WrittenRecord,
+define_enum! {
+    /// A resource that exists in the simulation world.
+    ///
+    /// Resources are organized into tiers reflecting technological progression:
+    /// - Tier 0: Survival basics available from tick 0
+    /// - Tier 1: Developed resources requiring discovery or effort
+    /// - Tier 2: Advanced resources requiring multi-step processes
+    /// - Tier 3: Complex resources requiring civilization-level coordination
+    pub enum Resource {
+        // --- Tier 0: Survival ---
+        /// Fresh water for hydration.
+        Water,
+        /// Wild berries gathered from bushes.
+        FoodBerry,
+        /// Fish caught from rivers or coastline.
+        FoodFish,
+        /// Edible roots dug from soil.
+        FoodRoot,
+
+        // --- Tier 1: Survival (developed) ---
+        /// Meat from hunted animals.
+        FoodMeat,
+        /// Crops grown via agriculture.
+        FoodFarmed,
+        /// Food prepared with fire for higher nutritional value.
+        FoodCooked,
+
+        // --- Tier 0: Material ---
+        /// Lumber harvested from forests.
+        Wood,
+        /// Raw stone from rocky areas.
+        Stone,
+
+        // --- Tier 1: Material ---
+        /// Plant fiber for rope, baskets, and textiles.
+        Fiber,
+        /// Malleable clay from riverbanks.
+        Clay,
+        /// Animal hides for clothing and shelter.
+        Hide,
+
+        // --- Tier 2: Material ---
+        /// Raw ore extracted from mines.
+        Ore,
+        /// Refined metal smelted from ore.
+        Metal,
+
+        // --- Tier 2: Consumable ---
+        /// Herbal medicine for health restoration.
+        Medicine,
+
+        // --- Tier 1: Equipment ---
+        /// Basic tools crafted from wood and stone.
+        Tool,
+
+        // --- Tier 2: Equipment ---
+        /// Advanced tools crafted with metal.
+        ToolAdvanced,
+
+        // --- Tier 3: Abstract ---
+        /// Collectively agreed-upon medium of exchange.
+        CurrencyToken,
+        /// Persistent knowledge stored on a physical medium.
+        WrittenRecord,
+        /// A chart of places and the routes between them.
+        Map,
+
+        // --- Tier 1: Byproduct ---
+        /// Chaff and scraps left over from harvests, worked into farm soil.
+        FoodWaste,
+
+        // --- Tier 2: Capital ---
+        /// Domesticated animals, a herd that breeds while its owner keeps it.
+        Livestock,
+    }
 }
 
 // ---------------------------------------------------------------------------
 // 3.2 Structure Types
 // ---------------------------------------------------------------------------
 
-/// A type of structure that can be built at a location.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "bindings/")]
-pub enum StructureType {
-    // --- Tier 0 ---
-    /// A fire for warmth, cooking, and light.
-    Campfire,
-    /// A minimal shelter providing basic rest bonus.
-    LeanTo,
-    /// A full shelter with weather protection and storage.
-    BasicHut,
-
-    // --- Tier 1 ---
-    /// Underground storage for extra inventory at a location.
-    StoragePit,
-    /// A reliable water source independent of rivers.
-    Well,
-    /// Agricultural plot for growing crops.
-    FarmPlot,
-    /// Workbench for crafting tools and processed materials.
-    Workshop,
-    /// Gathering place for group decisions and governance.
-    MeetingHall,
-
-    // --- Tier 2 ---
-    /// High-temperature facility for smelting ore into metal.
-    Forge,
-    /// Knowledge repository for reading and writing records.
-    Library,
-    /// Formal trading venue with price memory.
-    Market,
-    /// Defensive fortification restricting location access.
-    Wall,
-    /// Infrastructure connecting locations across obstacles.
-    Bridge,
+define_enum! {
+    /// A type of structure that can be built at a location.
+    pub enum StructureType {
+        // --- Tier 0 ---
+        /// A fire for warmth, cooking, and light.
+        Campfire,
+        /// A minimal shelter providing basic rest bonus.
+        LeanTo,
+        /// A full shelter with weather protection and storage.
+        BasicHut,
+
+        // --- Tier 1 ---
+        /// Underground storage for extra inventory at a location.
+        StoragePit,
+        /// A raised store holding a communal food stockpile.
+        Granary,
+        /// A walled store holding communal goods of any kind.
+        Storehouse,
+        /// A reliable water source independent of rivers.
+        Well,
+        /// Agricultural plot for growing crops.
+        FarmPlot,
+        /// Workbench for crafting tools and processed materials.
+        Workshop,
+        /// Gathering place for group decisions and governance.
+        MeetingHall,
+        /// Timber stockade restricting location access, quicker to raise than a wall.
+        Palisade,
+
+        // --- Tier 2 ---
+        /// High-temperature facility for smelting ore into metal.
+        Forge,
+        /// Knowledge repository for reading and writing records.
+        Library,
+        /// Formal trading venue with price memory.
+        Market,
+        /// Defensive fortification restricting location access.
+        Wall,
+        /// Infrastructure connecting locations across obstacles.
+        Bridge,
+        /// Passage cut through high ground, levelling the climb out of a location.
+        Tunnel,
+        /// Channel carrying water to the farm plots at a location.
+        Irrigation,
+        /// Fenced grazing land where livestock is kept and bred.
+        Pasture,
+
+        // --- Upgrades (reached only by upgrading a lower tier) ---
+        /// A stone-lined fire pit, upgraded from a [`StructureType::Campfire`].
+        Hearth,
+        /// A timber-and-stone dwelling, upgraded from a [`StructureType::BasicHut`].
+        House,
+        /// A long communal hall, upgraded from a [`StructureType::House`].
+        Longhouse,
+    }
 }
 
 /// The functional category of a structure.
@@ -148,109 +207,141 @@ pub enum StructureCategory {
 // 3.3 Action Types
 // ---------------------------------------------------------------------------
 
-/// An action that an agent can submit to the World Engine.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "bindings/")]
-pub enum ActionType {
-    // --- Survival ---
-    /// Collect resources from the current location.
-    Gather,
-    /// Consume food to reduce hunger and restore energy.
-    Eat,
-    /// Consume water for hydration.
-    Drink,
-    /// Recover energy (bonus if sheltered).
-    Rest,
-
-    // --- Movement ---
-    /// Travel to an adjacent location via a known route.
-    Move,
-
-    // --- Construction ---
-    /// Create a new structure at the current location.
-    Build,
-    /// Restore durability to an existing structure.
-    Repair,
-    /// Destroy a structure and salvage materials.
-    Demolish,
-    /// Upgrade the path type of a route.
-    ImproveRoute,
-
-    // --- Social ---
-    /// Send a direct message to a co-located agent.
-    Communicate,
-    /// Post a message visible to all agents at the location.
-    Broadcast,
-    /// Propose a resource exchange to another agent.
-    TradeOffer,
-    /// Accept a pending trade offer.
-    TradeAccept,
-    /// Reject a pending trade offer.
-    TradeReject,
-    /// Create a named social group.
-    FormGroup,
-    /// Transfer knowledge to another agent.
-    Teach,
-
-    // --- Advanced ---
-    /// Plant crops on a farm plot.
-    FarmPlant,
-    /// Harvest mature crops from a farm plot.
-    FarmHarvest,
-    /// Create tools or processed goods at a workshop.
-    Craft,
-    /// Extract ore from rocky terrain.
-    Mine,
-    /// Convert ore to metal at a forge.
-    Smelt,
-    /// Persist knowledge to a library.
-    Write,
-    /// Acquire knowledge from a library.
-    Read,
-    /// Take ownership of an unowned structure or location.
-    Claim,
-    /// Create a rule or law via group consensus.
-    Legislate,
-    /// Apply consequences for rule violations.
-    Enforce,
-    /// Spawn a child agent with a consenting partner.
-    Reproduce,
-
-    // --- Conflict ---
-    /// Take resources from a co-located agent by force or stealth.
-    Steal,
-    /// Engage in physical confrontation with another agent.
-    Attack,
-    /// Intimidate a co-located agent without dealing damage.
-    Intimidate,
-
-    // --- Diplomacy ---
-    /// Propose a group decision, alliance, or treaty.
-    Propose,
-    /// Cast a vote on a pending group proposal.
-    Vote,
-    /// Enter a formal partnership with another agent.
-    Marry,
-    /// Dissolve a formal partnership with another agent.
-    Divorce,
-    /// Engage in secret coordination with a subset of agents.
-    Conspire,
-
-    // --- Spiritual ---
-    /// Perform a spiritual or ritualistic action.
-    Pray,
-
-    // --- Freeform ---
-    /// A novel action proposed by an agent beyond the base catalog.
-    ///
-    /// Freeform actions are evaluated by the feasibility engine before
-    /// execution. If the engine can map the action to a known category,
-    /// it resolves it; otherwise it queues it for LLM adjudication.
-    Freeform,
-
-    // --- System ---
-    /// Agent did not act this tick (timeout or explicit forfeit).
-    NoAction,
+define_enum! {
+    /// An action that an agent can submit to the World Engine.
+    pub enum ActionType {
+        // --- Survival ---
+        /// Collect resources from the current location.
+        Gather,
+        /// Consume food to reduce hunger and restore energy.
+        Eat,
+        /// Consume water for hydration.
+        Drink,
+        /// Recover energy (bonus if sheltered).
+        Rest,
+
+        // --- Movement ---
+        /// Travel to an adjacent location via a known route.
+        Move,
+
+        // --- Construction ---
+        /// Create a new structure at the current location.
+        Build,
+        /// Restore durability to an existing structure.
+        Repair,
+        /// Put out a fire burning at the location.
+        Extinguish,
+        /// Destroy a structure and salvage materials.
+        Demolish,
+        /// Raise a structure to the next tier of its blueprint chain.
+        UpgradeStructure,
+        /// Upgrade the path type of a route.
+        ImproveRoute,
+        /// Lay a new route to a known location with no route to it yet.
+        BuildRoute,
+
+        // --- Social ---
+        /// Send a direct message to a co-located agent.
+        Communicate,
+        /// Post a message visible to all agents at the location.
+        Broadcast,
+        /// Propose a resource exchange to another agent.
+        TradeOffer,
+        /// Accept a pending trade offer.
+        TradeAccept,
+        /// Reject a pending trade offer.
+        TradeReject,
+        /// Post a standing buy or sell order at a market.
+        PostOrder,
+        /// Create a named social group.
+        FormGroup,
+        /// Transfer knowledge to another agent.
+        Teach,
+
+        // --- Advanced ---
+        /// Plant crops on a farm plot.
+        FarmPlant,
+        /// Harvest mature crops from a farm plot.
+        FarmHarvest,
+        /// Work food waste into a farm plot's soil to restore its fertility.
+        Fertilize,
+        /// Slaughter a head of livestock for meat and hide.
+        Slaughter,
+        /// Create tools or processed goods at a workshop.
+        Craft,
+        /// Extract ore from rocky terrain.
+        Mine,
+        /// Sink a location's mine shaft a level to reach more ore.
+        DeepenMine,
+        /// Hunt game for meat and hides.
+        Hunt,
+        /// Search the location for hidden resource deposits.
+        Prospect,
+        /// Catch fish from a river or lake at or next to the location.
+        Fish,
+        /// Convert ore to metal at a forge.
+        Smelt,
+        /// Persist knowledge to a library.
+        Write,
+        /// Acquire knowledge from a library.
+        Read,
+        /// Draw the places and routes the agent knows onto a map.
+        Chart,
+        /// Put resources into a shared storage structure.
+        Deposit,
+        /// Take resources out of a shared storage structure.
+        Withdraw,
+        /// Take ownership of an unowned structure or location.
+        Claim,
+        /// Give a structure the agent owns to another agent.
+        TransferOwnership,
+        /// Found a new settlement beside a wilderness location, with others.
+        FoundSettlement,
+        /// Create a rule or law via group consensus.
+        Legislate,
+        /// Apply consequences for rule violations.
+        Enforce,
+        /// Spawn a child agent with a consenting partner.
+        Reproduce,
+
+        // --- Conflict ---
+        /// Take resources from a co-located agent by force or stealth.
+        Steal,
+        /// Engage in physical confrontation with another agent.
+        Attack,
+        /// Intimidate a co-located agent without dealing damage.
+        Intimidate,
+        /// Batter the fortifications barring entry to an adjacent location.
+        Breach,
+
+        // --- Diplomacy ---
+        /// Propose a group decision, alliance, or treaty.
+        Propose,
+        /// Cast a vote on a pending group proposal.
+        Vote,
+        /// Enter a formal partnership with another agent.
+        Marry,
+        /// Dissolve a formal partnership with another agent.
+        Divorce,
+        /// Engage in secret coordination with a subset of agents.
+        Conspire,
+
+        // --- Spiritual ---
+        /// Perform a spiritual or ritualistic action.
+        Pray,
+
+        // --- Freeform ---
+        /// A novel action proposed by an agent beyond the base catalog.
+        ///
+        /// Freeform actions are evaluated by the feasibility engine before
+        /// execution. If the engine can map the action to a known category,
+        /// it resolves it; otherwise it queues it for LLM adjudication.
+        Freeform,
+
+        // --- System ---
+        /// Agent did not act this tick (timeout or explicit forfeit).
+        NoAction,
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -302,6 +393,8 @@ pub enum EventType {
     RouteImproved,
     /// A route's durability reached zero and it degraded to a lower path type.
     RouteDegraded,
+    /// Heavy traffic made a route a trade corridor.
+    TradeRouteEmerged,
     /// An agent discovered a previously unknown location.
     LocationDiscovered,
 
@@ -322,6 +415,8 @@ pub enum EventType {
     // --- Governance ---
     /// An agent claimed ownership of a structure.
     StructureClaimed,
+    /// A structure passed to the family of its owner on the owner's death.
+    StructureInherited,
     /// A governance rule was created by a group.
     RuleCreated,
     /// A governance rule was enforced against an agent.
@@ -332,6 +427,14 @@ pub enum EventType {
     WeatherChanged,
     /// The season transitioned.
     SeasonChanged,
+    /// A flood struck a location.
+    FloodOccurred,
+    /// A wildfire struck a location.
+    WildfireOccurred,
+    /// An earthquake struck a location.
+    EarthquakeOccurred,
+    /// A fire burning at a location damaged a structure.
+    StructureBurned,
 
     // --- Conflict ---
     /// A theft was successfully committed (resources transferred).
@@ -346,6 +449,16 @@ pub enum EventType {
     // --- System (alert) ---
     /// Conservation law violated -- critical ledger alert.
     LedgerAnomaly,
+    /// Ledger balances disagree with the world state they account for.
+    ReconciliationMismatch,
+
+    // --- System (maintenance) ---
+    /// Superseded events were folded into this summary by compaction.
+    EventsCompacted,
+
+    // --- Derived ---
+    /// Most living agents became starving at once.
+    FamineStarted,
 }
 
 // ---------------------------------------------------------------------------
@@ -424,6 +537,22 @@ pub enum Weather {
     Snow,
 }
 
+// ---------------------------------------------------------------------------
+// 3.7b Disasters
+// ---------------------------------------------------------------------------
+
+/// A natural disaster that can strike a location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub enum DisasterKind {
+    /// Rising water: washes away food, damages structures, floods trails.
+    Flood,
+    /// Fire: burns wood, fiber, and crops, and damages structures badly.
+    Wildfire,
+    /// Shaking ground: topples structures and blocks routes the longest.
+    Earthquake,
+}
+
 // ---------------------------------------------------------------------------
 // 3.8 Path Types
 // ---------------------------------------------------------------------------
@@ -524,6 +653,44 @@ pub enum LedgerEntryType {
     Theft,
     /// Resources looted from a defeated agent (agent -> agent).
     CombatLoot,
+    /// Opening balance carried over from pruned entries (any -> any).
+    Checkpoint,
+    /// Explicit repair of a conservation anomaly (any -> any).
+    Correction,
+    /// Undo of an earlier entry, with its sides swapped (any -> any).
+    Reversal,
+    /// New currency issued to an agent (world -> agent).
+    Mint,
+    /// Currency taken out of circulation (agent -> void).
+    Burn,
+    /// Resources put into a shared storage structure (agent -> structure).
+    Deposit,
+    /// Resources taken out of a shared storage structure
+    /// (structure -> agent).
+    Withdrawal,
+    /// Levy paid by a member into a group's treasury (agent -> group).
+    Tax,
+    /// Levy paid by one group's treasury to another's (group -> group).
+    Tribute,
+    /// Principal lent by a creditor to a debtor (agent -> agent).
+    LoanIssued,
+    /// Principal paid back by a debtor to its creditor (agent -> agent).
+    LoanRepayment,
+    /// Interest paid by a debtor to its creditor as it accrues
+    /// (agent -> agent).
+    InterestAccrued,
+    /// Resources reserved by a pending trade offer (agent -> escrow).
+    Escrow,
+    /// Reserved resources released from a trade's escrow (escrow -> agent).
+    EscrowRelease,
+    /// Energy spent by an agent (agent -> void).
+    EnergySpent,
+    /// Energy recovered by an agent (world -> agent).
+    EnergyRecovered,
+    /// Livestock born into an owner's herd (world -> agent).
+    Breeding,
+    /// Livestock slaughtered or starved (agent -> void).
+    Slaughter,
 }
 
 // ---------------------------------------------------------------------------
@@ -544,6 +711,10 @@ pub enum EntityType {
     World,
     /// The void (destination for consumption and decay).
     Void,
+    /// A pending trade holding its offered resources.
+    Escrow,
+    /// A group's treasury, collecting taxes and tribute.
+    Group,
 }
 
 // ---------------------------------------------------------------------------
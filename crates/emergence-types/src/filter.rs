@@ -0,0 +1,360 @@
+//! Filter expressions over events.
+//!
+//! An [`EventFilter`] selects events by tick range, event type, acting
+//! agent, location, and predicates on fields of the event's details. The
+//! same filter runs in memory through [`EventFilter::matches`] and in
+//! `PostgreSQL` through `EventStore::query`, so the observer's `/events`
+//! endpoint and offline queries agree on what a filter means.
+//!
+//! Every condition that is set must hold. A detail predicate names a field
+//! by a dotted path into the details object (`"resource"`,
+//! `"terms.quantity"`) and never matches an event without that field.
+//! Ordering comparisons only hold between two numbers, two strings, or two
+//! booleans.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enums::EventType;
+use crate::ids::{AgentId, LocationId};
+use crate::structs::Event;
+
+/// How a detail field is compared with a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    /// The field equals the value.
+    Eq,
+    /// The field differs from the value.
+    Ne,
+    /// The field is less than the value.
+    Lt,
+    /// The field is at most the value.
+    Le,
+    /// The field is greater than the value.
+    Gt,
+    /// The field is at least the value.
+    Ge,
+}
+
+impl Comparison {
+    /// Operators as written in a predicate, two-character ones first so
+    /// that `>=` is not read as `>`.
+    const OPERATORS: [(&'static str, Self); 6] = [
+        (">=", Self::Ge),
+        ("<=", Self::Le),
+        ("!=", Self::Ne),
+        ("=", Self::Eq),
+        (">", Self::Gt),
+        ("<", Self::Lt),
+    ];
+
+    /// The operator as written in a predicate.
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+
+    /// Whether fields ordered `ordering` against the value satisfy this
+    /// comparison.
+    const fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+/// A condition on one field of an event's details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetailPredicate {
+    /// Dotted path to the field within the details object.
+    pub field: String,
+    /// How the field is compared.
+    pub comparison: Comparison,
+    /// The value compared against.
+    pub value: serde_json::Value,
+}
+
+impl DetailPredicate {
+    /// A predicate comparing `field` with `value`.
+    pub fn new(
+        field: impl Into<String>,
+        comparison: Comparison,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            comparison,
+            value: value.into(),
+        }
+    }
+
+    /// The segments of [`field`](Self::field).
+    pub fn path(&self) -> impl Iterator<Item = &str> {
+        self.field.split('.')
+    }
+
+    /// Whether `details` satisfies this predicate.
+    pub fn matches(&self, details: &serde_json::Value) -> bool {
+        let Some(field) = self.path().try_fold(details, |value, segment| value.get(segment))
+        else {
+            return false;
+        };
+        // Values that cannot be compared are unequal, and unordered.
+        compare(field, &self.value).map_or_else(
+            || self.comparison == Comparison::Ne,
+            |ordering| self.comparison.holds(ordering),
+        )
+    }
+}
+
+/// How `field` orders against `value`, if the two are comparable.
+fn compare(field: &serde_json::Value, value: &serde_json::Value) -> Option<Ordering> {
+    use serde_json::Value;
+    match (field, value) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+impl FromStr for DetailPredicate {
+    type Err = FilterParseError;
+
+    /// Parse `field<op>value`, where `<op>` is one of `=`, `!=`, `<`, `<=`,
+    /// `>`, `>=`. The value is read as JSON if it parses as JSON and as a
+    /// plain string otherwise, so `resource=Wood` and `quantity>=5` both
+    /// work.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (at, symbol, comparison) = Comparison::OPERATORS
+            .iter()
+            .filter_map(|(symbol, comparison)| {
+                s.find(symbol).map(|at| (at, *symbol, *comparison))
+            })
+            .min_by_key(|(at, _, _)| *at)
+            .ok_or_else(|| FilterParseError::MissingOperator(s.to_owned()))?;
+        let field = s.get(..at).unwrap_or_default().trim();
+        let raw = s.get(at.saturating_add(symbol.len())..).unwrap_or_default().trim();
+        if field.is_empty() || field.split('.').any(str::is_empty) {
+            return Err(FilterParseError::InvalidField(s.to_owned()));
+        }
+        let value = serde_json::from_str(raw)
+            .unwrap_or_else(|_| serde_json::Value::String(raw.to_owned()));
+        Ok(Self::new(field, comparison, value))
+    }
+}
+
+/// A predicate that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterParseError {
+    /// The predicate has no comparison operator.
+    MissingOperator(String),
+    /// The predicate's field path is empty or has an empty segment.
+    InvalidField(String),
+}
+
+impl std::fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingOperator(predicate) => {
+                write!(f, "predicate {predicate:?} has no comparison operator")
+            }
+            Self::InvalidField(predicate) => {
+                write!(f, "predicate {predicate:?} has an invalid field path")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+/// A filter selecting events.
+///
+/// The default filter matches every event.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Earliest tick, inclusive.
+    pub from_tick: Option<u64>,
+    /// Latest tick, exclusive.
+    pub to_tick: Option<u64>,
+    /// Event types to include; empty means all.
+    pub event_types: Vec<EventType>,
+    /// The acting agent.
+    pub agent_id: Option<AgentId>,
+    /// The location the event occurred at.
+    pub location_id: Option<LocationId>,
+    /// Conditions on the event's details.
+    pub details: Vec<DetailPredicate>,
+}
+
+impl EventFilter {
+    /// A filter matching every event.
+    pub const fn new() -> Self {
+        Self {
+            from_tick: None,
+            to_tick: None,
+            event_types: Vec::new(),
+            agent_id: None,
+            location_id: None,
+            details: Vec::new(),
+        }
+    }
+
+    /// Only events with `from <= tick < to`.
+    #[must_use]
+    pub const fn with_ticks(mut self, from: u64, to: u64) -> Self {
+        self.from_tick = Some(from);
+        self.to_tick = Some(to);
+        self
+    }
+
+    /// Only events at `tick`.
+    #[must_use]
+    pub const fn with_tick(self, tick: u64) -> Self {
+        self.with_ticks(tick, tick.saturating_add(1))
+    }
+
+    /// Also include events of `event_type`.
+    #[must_use]
+    pub fn with_event_type(mut self, event_type: EventType) -> Self {
+        self.event_types.push(event_type);
+        self
+    }
+
+    /// Only events whose acting agent is `agent_id`.
+    #[must_use]
+    pub const fn with_agent(mut self, agent_id: AgentId) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    /// Only events at `location_id`.
+    #[must_use]
+    pub const fn with_location(mut self, location_id: LocationId) -> Self {
+        self.location_id = Some(location_id);
+        self
+    }
+
+    /// Also require `predicate` of the event's details.
+    #[must_use]
+    pub fn with_detail(mut self, predicate: DetailPredicate) -> Self {
+        self.details.push(predicate);
+        self
+    }
+
+    /// Whether `event` passes the filter.
+    pub fn matches(&self, event: &Event) -> bool {
+        self.from_tick.is_none_or(|from| event.tick >= from)
+            && self.to_tick.is_none_or(|to| event.tick < to)
+            && (self.event_types.is_empty() || self.event_types.contains(&event.event_type))
+            && self.agent_id.is_none_or(|agent_id| event.agent_id == Some(agent_id))
+            && self.location_id.is_none_or(|location_id| event.location_id == Some(location_id))
+            && self.details.iter().all(|predicate| predicate.matches(&event.details))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::enums::{Era, Season, Weather};
+    use crate::ids::EventId;
+    use crate::structs::WorldContext;
+
+    #[test]
+    fn predicates_parse() {
+        let predicate: DetailPredicate = "quantity>=5".parse().unwrap();
+        assert_eq!(predicate, DetailPredicate::new("quantity", Comparison::Ge, 5));
+        let predicate: DetailPredicate = "terms.resource = Wood".parse().unwrap();
+        assert_eq!(predicate, DetailPredicate::new("terms.resource", Comparison::Eq, "Wood"));
+        let predicate: DetailPredicate = "note!=\"a=b\"".parse().unwrap();
+        assert_eq!(predicate, DetailPredicate::new("note", Comparison::Ne, "a=b"));
+
+        assert!(matches!(
+            "quantity".parse::<DetailPredicate>(),
+            Err(FilterParseError::MissingOperator(_))
+        ));
+        assert!(matches!(
+            "a..b=1".parse::<DetailPredicate>(),
+            Err(FilterParseError::InvalidField(_))
+        ));
+    }
+
+    #[test]
+    fn predicates_match_details() {
+        let details = serde_json::json!({
+            "resource": "Wood",
+            "quantity": 5,
+            "terms": {"price": 2.5},
+        });
+        let holds = |predicate: &str| {
+            predicate.parse::<DetailPredicate>().unwrap().matches(&details)
+        };
+
+        assert!(holds("resource=Wood"));
+        assert!(holds("quantity=5.0"));
+        assert!(holds("quantity>4"));
+        assert!(!holds("quantity<5"));
+        assert!(holds("terms.price<=2.5"));
+        assert!(holds("resource!=Stone"));
+        assert!(holds("resource!=5"));
+        // Missing fields and mismatched types never order.
+        assert!(!holds("missing!=1"));
+        assert!(!holds("resource>1"));
+    }
+
+    #[test]
+    fn filters_combine_every_condition() {
+        let (agent, location) = (AgentId::new(), LocationId::new());
+        let event = Event {
+            id: EventId::new(),
+            tick: 40,
+            event_type: EventType::ResourceGathered,
+            agent_id: Some(agent),
+            location_id: Some(location),
+            details: serde_json::json!({"resource": "Wood", "quantity": 3}),
+            agent_state_snapshot: None,
+            world_context: WorldContext {
+                tick: 40,
+                era: Era::Primitive,
+                season: Season::Spring,
+                weather: Weather::Clear,
+                population: 1,
+            },
+            created_at: Utc::now(),
+            caused_by: None,
+            correlation_id: None,
+        };
+
+        assert!(EventFilter::new().matches(&event));
+        let filter = EventFilter::new()
+            .with_ticks(40, 41)
+            .with_event_type(EventType::ResourceGathered)
+            .with_agent(agent)
+            .with_location(location)
+            .with_detail(DetailPredicate::new("quantity", Comparison::Lt, 5));
+        assert!(filter.matches(&event));
+
+        assert!(!EventFilter::new().with_tick(41).matches(&event));
+        assert!(!EventFilter::new().with_event_type(EventType::TickEnd).matches(&event));
+        assert!(!EventFilter::new().with_agent(AgentId::new()).matches(&event));
+        let wood = DetailPredicate::new("resource", Comparison::Eq, "Stone");
+        assert!(!filter.with_detail(wood).matches(&event));
+    }
+}
@@ -86,11 +86,21 @@ define_id! {
     EventId
 }
 
+define_id! {
+    /// Identifier shared by the events of one causal cascade.
+    CorrelationId
+}
+
 define_id! {
     /// Unique identifier for a trade between two agents.
     TradeId
 }
 
+define_id! {
+    /// Unique identifier for a standing order on a market's order book.
+    OrderId
+}
+
 define_id! {
     /// Unique identifier for a social group of agents.
     GroupId
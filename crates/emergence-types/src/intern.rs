@@ -0,0 +1,301 @@
+//! Dense integer indices for UUID identifiers.
+//!
+//! Entity IDs are 128-bit UUIDs, which is what persistence and the wire
+//! need, but the engine's per-tick loops key maps by them over and over.
+//! An [`Interner`] assigns each ID a [`DenseId`] -- a `u32` counting up
+//! from zero in first-seen order -- so those loops can index a
+//! [`DenseMap`] (a plain `Vec`) instead of comparing UUIDs in a tree.
+//!
+//! Dense indices are only meaningful for the interner that issued them and
+//! are never persisted; build one per tick (or per pass) and resolve back
+//! to the UUID at the boundary.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+// ---------------------------------------------------------------------------
+// DenseId
+// ---------------------------------------------------------------------------
+
+/// A compact index standing in for an ID of type `T`.
+///
+/// The type parameter keeps indices for different ID kinds apart, so an
+/// agent index cannot be used to look up a location.
+pub struct DenseId<T> {
+    index: u32,
+    _kind: PhantomData<fn() -> T>,
+}
+
+impl<T> DenseId<T> {
+    const fn new(index: u32) -> Self {
+        Self {
+            index,
+            _kind: PhantomData,
+        }
+    }
+
+    /// The raw index.
+    pub const fn get(self) -> u32 {
+        self.index
+    }
+
+    /// The index as a `usize`, for slice access.
+    pub fn as_usize(self) -> usize {
+        usize::try_from(self.index).unwrap_or(usize::MAX)
+    }
+}
+
+// Manual impls: deriving would require the same bounds on `T`.
+
+impl<T> Clone for DenseId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DenseId<T> {}
+
+impl<T> PartialEq for DenseId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for DenseId<T> {}
+
+impl<T> PartialOrd for DenseId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for DenseId<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<T> Hash for DenseId<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for DenseId<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DenseId({})", self.index)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Interner
+// ---------------------------------------------------------------------------
+
+/// Two-way mapping between IDs and dense indices.
+///
+/// Indices are handed out in first-seen order, so interning the same IDs
+/// in the same order always yields the same indices.
+#[derive(Debug, Clone)]
+pub struct Interner<T> {
+    ids: Vec<T>,
+    lookup: HashMap<T, DenseId<T>>,
+}
+
+impl<T: Copy + Eq + Hash> Interner<T> {
+    /// Create an empty interner.
+    pub fn new() -> Self {
+        Self {
+            ids: Vec::new(),
+            lookup: HashMap::new(),
+        }
+    }
+
+    /// Create an empty interner with room for `capacity` IDs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            lookup: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Return the index of `id`, assigning the next one if it is new.
+    ///
+    /// Returns `None` only if `u32::MAX` IDs are already interned.
+    pub fn intern(&mut self, id: T) -> Option<DenseId<T>> {
+        if let Some(&dense) = self.lookup.get(&id) {
+            return Some(dense);
+        }
+        let dense = DenseId::new(u32::try_from(self.ids.len()).ok()?);
+        self.ids.push(id);
+        self.lookup.insert(id, dense);
+        Some(dense)
+    }
+
+    /// The index of `id`, if it has been interned.
+    pub fn get(&self, id: &T) -> Option<DenseId<T>> {
+        self.lookup.get(id).copied()
+    }
+
+    /// The ID behind `dense`, if this interner issued it.
+    pub fn resolve(&self, dense: DenseId<T>) -> Option<T> {
+        self.ids.get(dense.as_usize()).copied()
+    }
+
+    /// Number of interned IDs.
+    pub const fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether nothing has been interned.
+    pub const fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// All interned IDs with their indices, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (DenseId<T>, T)> + '_ {
+        self.ids
+            .iter()
+            .zip(0_u32..)
+            .map(|(&id, index)| (DenseId::new(index), id))
+    }
+}
+
+impl<T: Copy + Eq + Hash> Default for Interner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Eq + Hash> FromIterator<T> for Interner<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        for id in iter {
+            let _ = interner.intern(id);
+        }
+        interner
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DenseMap
+// ---------------------------------------------------------------------------
+
+/// A map from [`DenseId`] to `V` backed by a `Vec`.
+///
+/// Lookups are a bounds check and an offset. Iteration is in index order.
+#[derive(Debug, Clone)]
+pub struct DenseMap<T, V> {
+    slots: Vec<Option<V>>,
+    _kind: PhantomData<fn() -> T>,
+}
+
+impl<T, V> DenseMap<T, V> {
+    /// Create an empty map.
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            _kind: PhantomData,
+        }
+    }
+
+    /// Create an empty map with room for indices below `capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            _kind: PhantomData,
+        }
+    }
+
+    /// Insert `value` at `key`, returning the previous value.
+    pub fn insert(&mut self, key: DenseId<T>, value: V) -> Option<V> {
+        let index = key.as_usize();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index.saturating_add(1), || None);
+        }
+        self.slots.get_mut(index).and_then(|slot| slot.replace(value))
+    }
+
+    /// The value at `key`.
+    pub fn get(&self, key: DenseId<T>) -> Option<&V> {
+        self.slots.get(key.as_usize()).and_then(Option::as_ref)
+    }
+
+    /// The value at `key`, mutably.
+    pub fn get_mut(&mut self, key: DenseId<T>) -> Option<&mut V> {
+        self.slots.get_mut(key.as_usize()).and_then(Option::as_mut)
+    }
+
+    /// Occupied entries in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (DenseId<T>, &V)> + '_ {
+        self.slots
+            .iter()
+            .zip(0_u32..)
+            .filter_map(|(slot, index)| slot.as_ref().map(|v| (DenseId::new(index), v)))
+    }
+
+    /// Consume the map, yielding occupied values in index order.
+    pub fn into_values(self) -> impl Iterator<Item = V> {
+        self.slots.into_iter().flatten()
+    }
+
+    /// Number of occupied entries.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Whether no entry is occupied.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+}
+
+impl<T, V> Default for DenseMap<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::{AgentId, LocationId};
+
+    #[test]
+    fn interning_is_stable_and_reversible() {
+        let a = AgentId::new();
+        let b = AgentId::new();
+        let mut interner = Interner::new();
+        let first = interner.intern(a);
+        let second = interner.intern(b);
+        assert_eq!(first.map(DenseId::get), Some(0));
+        assert_eq!(second.map(DenseId::get), Some(1));
+        assert_eq!(interner.intern(a), first);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get(&b), second);
+        assert_eq!(second.and_then(|d| interner.resolve(d)), Some(b));
+        assert!(interner.get(&AgentId::new()).is_none());
+        let order: Vec<AgentId> = interner.iter().map(|(_, id)| id).collect();
+        assert_eq!(order, vec![a, b]);
+    }
+
+    #[test]
+    fn dense_map_indexes_by_dense_id() {
+        let locations: Interner<LocationId> =
+            (0..3).map(|_| LocationId::new()).collect();
+        let keys: Vec<DenseId<LocationId>> = locations.iter().map(|(dense, _)| dense).collect();
+        let mut map = DenseMap::new();
+        for key in keys.iter().rev().step_by(2) {
+            map.insert(*key, key.get());
+        }
+        assert_eq!(map.len(), 2);
+        assert_eq!(keys.get(1).and_then(|k| map.get(*k)), None);
+        assert_eq!(keys.get(2).and_then(|k| map.get(*k)), Some(&2));
+        if let Some(v) = keys.first().and_then(|k| map.get_mut(*k)) {
+            *v = 10;
+        }
+        let entries: Vec<(u32, u32)> = map.iter().map(|(k, v)| (k.get(), *v)).collect();
+        assert_eq!(entries, vec![(0, 10), (2, 2)]);
+        assert_eq!(map.into_values().collect::<Vec<_>>(), vec![10, 2]);
+    }
+}
@@ -7,42 +7,71 @@
 //! # Modules
 //!
 //! - [`ids`] -- Type-safe UUID wrappers for all entity identifiers
+//! - [`intern`] -- Dense `u32` indices for IDs in the engine's per-tick loops
 //! - [`enums`] -- Enumeration types (resources, actions, events, environment)
 //! - [`structs`] -- Core entity structs (agents, locations, structures, ledger)
+//! - [`builders`] -- Validating builders for agent state, structures, routes, and events
+//! - [`filter`] -- [`EventFilter`] expressions selecting events by tick, type, agent, and details
+//! - [`delta`] -- Tick-to-tick [`WorldSnapshotDelta`] with apply and compose
 //! - [`actions`] -- Action request/result types for agent-engine communication
 //! - [`perception`] -- Perception payload delivered to agents each tick
+//! - [`wire`] -- Versioned NATS message envelope and protocol negotiation
 
 pub mod actions;
+pub mod builders;
+pub mod delta;
 pub mod enums;
+pub mod filter;
 pub mod ids;
+pub mod intern;
 pub mod perception;
 pub mod structs;
+pub mod wire;
 
 // Re-export all public types at crate root for convenience.
 pub use actions::{
     ActionOutcome, ActionParameters, ActionRequest, ActionResult, ActionTarget, FreeformAction,
+    ReflectionUpdate,
 };
+pub use builders::{AgentStateBuilder, BuildError, EventBuilder, RouteBuilder, StructureBuilder};
+pub use delta::{DeltaMismatch, WorldSnapshotDelta};
 pub use enums::{
-    ActionType, EntityType, Era, EventType, LedgerEntryType, MemoryTier, PathType, RejectionReason,
-    Resource, Season, StructureCategory, StructureType, TimeOfDay, Weather,
+    ActionType, DisasterKind, EntityType, Era, EventType, LedgerEntryType, MemoryTier, PathType,
+    RejectionReason, Resource, Season, StructureCategory, StructureType, TimeOfDay, Weather,
 };
+pub use filter::{Comparison, DetailPredicate, EventFilter, FilterParseError};
 pub use ids::{
-    AgentId, EventId, GroupId, LedgerEntryId, LocationId, RouteId, RuleId, StructureId, TradeId,
+    AgentId, CorrelationId, EventId, GroupId, LedgerEntryId, LocationId, OrderId, RouteId, RuleId,
+    StructureId, TradeId,
+};
+pub use intern::{DenseId, DenseMap, Interner};
+pub use perception::{
+    KnownRoute, Perception, SelfState, Surroundings, VisibleAgent, WeatherForecast,
 };
-pub use perception::{KnownRoute, Perception, SelfState, Surroundings, VisibleAgent};
 pub use structs::{
     AccessControlList, ActionRejectedDetails, ActionSucceededDetails, Agent, AgentDiedDetails,
-    AgentState, AgentStateSnapshot, CombatInitiatedDetails, CombatIntent, CombatResolvedDetails,
-    DecisionRecord, EconomyStats, EnforcementAppliedDetails, Event, Group, GroupFormedDetails,
-    InteractionCause, KnowledgeDiscoveredDetails, KnowledgeTaughtDetails, LedgerEntry, Location,
-    LocationEffects, MemoryEntry, Message, PendingTrade, Personality, PopulationStats,
+    AgentState, AgentStateSnapshot, BackendUsage, CombatInitiatedDetails, CombatIntent, CombatResolvedDetails,
+    CurrencySupply, DisasterDetails,
+    DecisionRecord, EconomyStats, EnforcementAppliedDetails, Event, EventsCompactedDetails,
+    FamineStartedDetails, Group, GroupFormedDetails,
+    InteractionCause, KnowledgeDiscoveredDetails, KnowledgeTaughtDetails, LedgerEntry, LedgerTag,
+    LedgerSnapshot, Location,
+    LocationEffects, MemoryEntry, Message, OpeningBalance, OrderSide, OutstandingLoan, PendingTrade,
+    Personality, PopulationStats,
+    QuarantinedContent, ReconciliationMismatchDetails,
     RejectionDetails, RelationshipChangedDetails, ResourceGatheredDetails, ResourceNode, Route,
-    RouteDegradedDetails, RouteImprovedDetails, Rule, RuleCreatedDetails, Sex, Structure,
-    StructureBlueprint, StructureBuiltDetails, StructureClaimedDetails,
-    StructureDestroyedDetails, StructureProperties, StructureRepairedDetails, TheftFailedDetails,
+    RouteDegradedDetails, RouteImprovedDetails, Rule, RuleCreatedDetails, RunnerMetrics, Sex, Structure,
+    StructureBlueprint, StructureBuiltDetails, StructureClaimedDetails, StructureInheritedDetails,
+    StandingOrder, StructureBurnedDetails, StructureDestroyedDetails, StructureProperties,
+    StructureRepairedDetails,
+    TheftFailedDetails,
     TheftFailureReason, TheftOccurredDetails, TradeCompletedDetails, TradeFailReason,
-    TradeFailedDetails, VisibleMessage, VisibleStructure, WorldContext, WorldSnapshot,
-    memory_types,
+    TradeFailedDetails, TradeRouteEmergedDetails, VisibleMessage, VisibleStructure, WorldContext,
+    WorldSnapshot,
+    EVENT_SCHEMA_VERSION, memory_types,
+};
+pub use wire::{
+    NegotiatedProtocol, PayloadType, ProtocolSupport, WireEnvelope, WireError, PROTOCOL_VERSION,
 };
 
 #[cfg(test)]
@@ -55,20 +84,34 @@ mod tests {
         // #[ts(export)] are used. Importing them here triggers generation.
         // The actual files are written to the `bindings/` directory
         // relative to the crate root.
+        export_ids();
+        export_enums();
+        export_structs();
+        export_actions();
+        export_perception();
+    }
+
+    /// Export the ID newtypes in [`crate::ids`].
+    fn export_ids() {
         use ts_rs::TS;
 
-        // IDs
         let _ = crate::ids::AgentId::export_all();
         let _ = crate::ids::LocationId::export_all();
         let _ = crate::ids::StructureId::export_all();
         let _ = crate::ids::RouteId::export_all();
         let _ = crate::ids::EventId::export_all();
+        let _ = crate::ids::CorrelationId::export_all();
         let _ = crate::ids::TradeId::export_all();
+        let _ = crate::ids::OrderId::export_all();
         let _ = crate::ids::GroupId::export_all();
         let _ = crate::ids::LedgerEntryId::export_all();
         let _ = crate::ids::RuleId::export_all();
+    }
+
+    /// Export the enums in [`crate::enums`].
+    fn export_enums() {
+        use ts_rs::TS;
 
-        // Enums
         let _ = crate::enums::Resource::export_all();
         let _ = crate::enums::StructureType::export_all();
         let _ = crate::enums::ActionType::export_all();
@@ -83,8 +126,12 @@ mod tests {
         let _ = crate::enums::EntityType::export_all();
         let _ = crate::enums::MemoryTier::export_all();
         let _ = crate::enums::StructureCategory::export_all();
+    }
+
+    /// Export the structs in [`crate::structs`] and [`crate::delta`].
+    fn export_structs() {
+        use ts_rs::TS;
 
-        // Structs
         let _ = crate::structs::Personality::export_all();
         let _ = crate::structs::MemoryEntry::export_all();
         let _ = crate::structs::ResourceNode::export_all();
@@ -100,6 +147,7 @@ mod tests {
         let _ = crate::structs::Route::export_all();
         let _ = crate::structs::Structure::export_all();
         let _ = crate::structs::WorldSnapshot::export_all();
+        let _ = crate::delta::WorldSnapshotDelta::export_all();
         let _ = crate::structs::PopulationStats::export_all();
         let _ = crate::structs::EconomyStats::export_all();
         let _ = crate::structs::ActionSucceededDetails::export_all();
@@ -115,6 +163,8 @@ mod tests {
         let _ = crate::structs::RejectionDetails::export_all();
         let _ = crate::structs::PendingTrade::export_all();
         let _ = crate::structs::TradeFailReason::export_all();
+        let _ = crate::structs::OrderSide::export_all();
+        let _ = crate::structs::StandingOrder::export_all();
         let _ = crate::structs::TradeFailedDetails::export_all();
         let _ = crate::structs::InteractionCause::export_all();
         let _ = crate::structs::RelationshipChangedDetails::export_all();
@@ -127,8 +177,12 @@ mod tests {
         let _ = crate::structs::StructureDestroyedDetails::export_all();
         let _ = crate::structs::RouteImprovedDetails::export_all();
         let _ = crate::structs::RouteDegradedDetails::export_all();
+        let _ = crate::structs::TradeRouteEmergedDetails::export_all();
+        let _ = crate::structs::DisasterDetails::export_all();
+        let _ = crate::structs::StructureBurnedDetails::export_all();
         let _ = crate::structs::Rule::export_all();
         let _ = crate::structs::StructureClaimedDetails::export_all();
+        let _ = crate::structs::StructureInheritedDetails::export_all();
         let _ = crate::structs::RuleCreatedDetails::export_all();
         let _ = crate::structs::EnforcementAppliedDetails::export_all();
         let _ = crate::structs::TheftOccurredDetails::export_all();
@@ -138,21 +192,34 @@ mod tests {
         let _ = crate::structs::CombatInitiatedDetails::export_all();
         let _ = crate::structs::CombatResolvedDetails::export_all();
         let _ = crate::structs::DecisionRecord::export_all();
+        let _ = crate::structs::BackendUsage::export_all();
+        let _ = crate::structs::RunnerMetrics::export_all();
+        let _ = crate::structs::QuarantinedContent::export_all();
         let _ = crate::structs::Sex::export_all();
+    }
+
+    /// Export the action types in [`crate::actions`].
+    fn export_actions() {
+        use ts_rs::TS;
 
-        // Actions
         let _ = crate::actions::ActionParameters::export_all();
         let _ = crate::actions::ActionRequest::export_all();
         let _ = crate::actions::ActionOutcome::export_all();
         let _ = crate::actions::ActionResult::export_all();
         let _ = crate::actions::FreeformAction::export_all();
         let _ = crate::actions::ActionTarget::export_all();
+        let _ = crate::actions::ReflectionUpdate::export_all();
+    }
+
+    /// Export the perception types in [`crate::perception`].
+    fn export_perception() {
+        use ts_rs::TS;
 
-        // Perception
         let _ = crate::perception::Perception::export_all();
         let _ = crate::perception::SelfState::export_all();
         let _ = crate::perception::Surroundings::export_all();
         let _ = crate::perception::VisibleAgent::export_all();
         let _ = crate::perception::KnownRoute::export_all();
+        let _ = crate::perception::WeatherForecast::export_all();
     }
 }
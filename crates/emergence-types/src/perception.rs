@@ -46,6 +46,10 @@ pub struct Perception {
     pub notifications: Vec<String>,
     /// The agent's personality traits (if known). Shapes decision-making.
     pub personality: Option<Personality>,
+    /// The weather the agent expects over the coming ticks. Empty unless
+    /// they know weather lore.
+    #[serde(default)]
+    pub forecast: Vec<WeatherForecast>,
 }
 
 // ---------------------------------------------------------------------------
@@ -106,6 +110,14 @@ pub struct Surroundings {
     pub agents_here: Vec<VisibleAgent>,
     /// Broadcast messages posted at this location.
     pub messages_here: Vec<VisibleMessage>,
+    /// Whether the location holds more agents than it comfortably supports.
+    /// Resources regrow more slowly and disease hits harder while it does.
+    #[serde(default)]
+    pub overcrowded: bool,
+    /// Pollution at the location, from 0 (clean) to 100. Polluted land
+    /// yields poorer harvests and its water regrows slowly.
+    #[serde(default)]
+    pub pollution: u32,
 }
 
 // ---------------------------------------------------------------------------
@@ -147,3 +159,18 @@ pub struct KnownRoute {
     /// Key resources available at the destination (fuzzy quantities).
     pub resources_hint: String,
 }
+
+// ---------------------------------------------------------------------------
+// 8.6 WeatherForecast
+// ---------------------------------------------------------------------------
+
+/// The weather an agent expects at a coming tick. Forecasts can be wrong;
+/// skilled forecasters are wrong less often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct WeatherForecast {
+    /// The tick forecast for.
+    pub tick: u64,
+    /// The weather expected then.
+    pub weather: Weather,
+}
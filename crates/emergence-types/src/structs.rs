@@ -12,10 +12,12 @@ use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::enums::{
-    Era, EventType, LedgerEntryType, MemoryTier, Resource, Season, StructureType, Weather,
+    EntityType, Era, EventType, LedgerEntryType, MemoryTier, Resource, Season, StructureType,
+    Weather,
 };
 use crate::ids::{
-    AgentId, EventId, GroupId, LedgerEntryId, LocationId, RouteId, RuleId, StructureId, TradeId,
+    AgentId, CorrelationId, EventId, GroupId, LedgerEntryId, LocationId, OrderId, RouteId,
+    RuleId, StructureId, TradeId,
 };
 
 // ---------------------------------------------------------------------------
@@ -93,6 +95,8 @@ pub mod memory_types {
     pub const DISCOVERY: &str = "discovery";
     /// A social event (relationship change, group formation, death notification).
     pub const SOCIAL: &str = "social";
+    /// A summary the agent wrote of its own recent memories.
+    pub const REFLECTION: &str = "reflection";
 }
 
 impl MemoryEntry {
@@ -196,6 +200,27 @@ impl MemoryEntry {
         }
     }
 
+    /// Create a new memory entry for an agent's reflection summary.
+    ///
+    /// `emotional_weight` is clamped to the 0.0--1.0 range. Reflections
+    /// condense earlier memories, so they start in the
+    /// [`MemoryTier::ShortTerm`] tier.
+    pub fn reflection(
+        tick: u64,
+        summary: String,
+        entities: Vec<Uuid>,
+        emotional_weight: Decimal,
+    ) -> Self {
+        Self {
+            tick,
+            memory_type: String::from(memory_types::REFLECTION),
+            summary,
+            entities,
+            emotional_weight: clamp_weight(emotional_weight),
+            tier: MemoryTier::ShortTerm,
+        }
+    }
+
     /// Check whether this memory references a specific entity (agent, location, etc.).
     pub fn involves_entity(&self, entity_id: Uuid) -> bool {
         self.entities.contains(&entity_id)
@@ -359,7 +384,7 @@ pub struct DecisionRecord {
     pub agent_id: AgentId,
     /// The tick this decision was for.
     pub tick: u64,
-    /// How the decision was made: `"llm"`, `"rule_engine"`, `"night_cycle"`, `"timeout"`.
+    /// How the decision was made: `"llm"`, `"rule_engine"`, `"night_cycle"`, `"heuristic"`, `"budget"`, `"policy"`, `"timeout"`.
     pub decision_source: String,
     /// The action type chosen.
     pub action_type: String,
@@ -379,14 +404,114 @@ pub struct DecisionRecord {
     pub latency_ms: Option<u64>,
     /// The raw LLM response text (if LLM decision). Truncated to 4000 chars.
     pub raw_llm_response: Option<String>,
+    /// Why the model chose the action (if LLM decision): thinking and prose
+    /// outside the action JSON, then its `reasoning` field. Truncated to
+    /// 2000 chars.
+    #[serde(default)]
+    pub reasoning: Option<String>,
     /// The assembled prompt sent to the LLM (if LLM decision). Truncated to 8000 chars.
     pub prompt_sent: Option<String>,
     /// Which rule matched (if rule\_engine decision).
     pub rule_matched: Option<String>,
+    /// Prompt template variant the agent is assigned to (if LLM decision).
+    #[serde(default)]
+    pub prompt_variant: Option<String>,
+    /// Version tag of that prompt variant (if LLM decision).
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+    /// The perception the decision was made from, after containment
+    /// sanitization (if LLM decision).
+    #[serde(default)]
+    pub perception: Option<serde_json::Value>,
+    /// How the engine resolved the action: `"succeeded"` or `"rejected"`.
+    /// Filled in by the engine once the tick resolves.
+    #[serde(default)]
+    pub outcome: Option<String>,
     /// Timestamp of the decision.
     pub created_at: DateTime<Utc>,
 }
 
+// ---------------------------------------------------------------------------
+// Runner cost metrics
+// ---------------------------------------------------------------------------
+
+/// Cumulative LLM usage for one backend of an agent runner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct BackendUsage {
+    /// Backend role: `"primary"` or `"escalation"`.
+    pub backend: String,
+    /// Model ID served by this backend.
+    pub model: String,
+    /// Successful LLM calls.
+    pub calls: u64,
+    /// Failed LLM calls (transport errors, error statuses, bad bodies).
+    pub errors: u64,
+    /// Input/prompt tokens consumed.
+    pub input_tokens: u64,
+    /// Output/completion tokens consumed.
+    pub output_tokens: u64,
+    /// Estimated cost in USD.
+    #[ts(as = "String")]
+    pub estimated_cost: Decimal,
+}
+
+/// Periodic snapshot of an agent runner's cumulative LLM spend.
+///
+/// Published to NATS by each runner instance and collected by the engine
+/// for the Observer REST API. Totals are cumulative since runner start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct RunnerMetrics {
+    /// Partition index of the publishing runner.
+    pub partition_id: u32,
+    /// Successful LLM calls across all backends.
+    pub total_calls: u64,
+    /// Failed LLM calls across all backends.
+    pub total_errors: u64,
+    /// Input/prompt tokens across all backends.
+    pub total_input_tokens: u64,
+    /// Output/completion tokens across all backends.
+    pub total_output_tokens: u64,
+    /// Estimated cost in USD across all backends.
+    #[ts(as = "String")]
+    pub total_estimated_cost: Decimal,
+    /// LLM decisions refused because a budget cap was exhausted.
+    pub budget_denials: u64,
+    /// Per-backend breakdown.
+    pub backends: Vec<BackendUsage>,
+    /// When this snapshot was taken.
+    pub published_at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Prompt-injection containment
+// ---------------------------------------------------------------------------
+
+/// Agent-authored text withheld from another agent's prompt.
+///
+/// Published to NATS by the runner whenever containment quarantines a
+/// message, name, or other peer-written field before prompt rendering.
+/// The engine raises a containment alert for each one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct QuarantinedContent {
+    /// Tick of the perception the content arrived in.
+    pub tick: u64,
+    /// The agent whose prompt the content was withheld from.
+    pub recipient_id: AgentId,
+    /// Perception field the content came from (e.g. `"message"`, `"agent_name"`).
+    pub field: String,
+    /// Display name of the author, when the field records one.
+    pub author: Option<String>,
+    /// Injection patterns that triggered the quarantine.
+    pub patterns: Vec<String>,
+    /// The offending text, truncated to 200 characters.
+    pub excerpt: String,
+    /// When the content was quarantined.
+    pub quarantined_at: DateTime<Utc>,
+}
+
 // ---------------------------------------------------------------------------
 // 5.1 Base Event
 // ---------------------------------------------------------------------------
@@ -395,6 +520,10 @@ pub struct DecisionRecord {
 ///
 /// Events are the source of truth for the simulation's history. State can
 /// be reconstructed by replaying the event log.
+///
+/// [`caused_by`](Self::caused_by) and [`correlation_id`](Self::correlation_id)
+/// record why the event happened: the event that directly triggered it, and
+/// the cascade it belongs to (for example famine, theft, rule, enforcement).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
 pub struct Event {
@@ -416,6 +545,12 @@ pub struct Event {
     pub world_context: WorldContext,
     /// Real-world timestamp when the event was created.
     pub created_at: DateTime<Utc>,
+    /// The event that directly triggered this one, if any.
+    #[serde(default)]
+    pub caused_by: Option<EventId>,
+    /// The cascade of related events this one belongs to, if any.
+    #[serde(default)]
+    pub correlation_id: Option<CorrelationId>,
 }
 
 // ---------------------------------------------------------------------------
@@ -452,10 +587,99 @@ pub struct LedgerEntry {
     pub reason: String,
     /// Related entity such as a trade or structure ID.
     pub reference_id: Option<Uuid>,
+    /// Typed links to what caused the transfer, for grouping the entries
+    /// of one action, trade, or rule.
+    #[serde(default)]
+    pub tags: Vec<LedgerTag>,
     /// Real-world timestamp.
     pub created_at: DateTime<Utc>,
 }
 
+/// Typed metadata linking a [`LedgerEntry`] to what caused it.
+///
+/// An entry may carry several tags, so a trade's transfers, its escrow, and
+/// any toll can all be found by the trade, and each still by its action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub enum LedgerTag {
+    /// The action that moved the resources, by its `ActionSubmitted` event.
+    Action(EventId),
+    /// The trade the entry belongs to.
+    Trade(TradeId),
+    /// The governance rule that imposed the entry, such as a tax.
+    Rule(RuleId),
+}
+
+impl std::fmt::Display for LedgerTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Action(id) => write!(f, "action:{id}"),
+            Self::Trade(id) => write!(f, "trade:{id}"),
+            Self::Rule(id) => write!(f, "rule:{id}"),
+        }
+    }
+}
+
+/// The ledger's opening balances at the end of a tick.
+///
+/// A ledger restarted from a snapshot carries these balances forward
+/// instead of replaying every entry recorded before the snapshot tick.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct LedgerSnapshot {
+    /// The tick whose end-of-tick balances are recorded.
+    pub tick: u64,
+    /// Every entity's non-zero balance of each resource.
+    pub balances: Vec<OpeningBalance>,
+    /// The money supply of each adopted currency.
+    #[serde(default)]
+    pub currencies: Vec<CurrencySupply>,
+    /// The principal outstanding on each loan between agents.
+    #[serde(default)]
+    pub loans: Vec<OutstandingLoan>,
+}
+
+/// One entity's balance of one resource in a [`LedgerSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct OpeningBalance {
+    /// The entity holding the balance.
+    pub entity_id: Uuid,
+    /// The type of the entity, if any entry recorded it.
+    pub entity_type: Option<crate::enums::EntityType>,
+    /// The resource held.
+    pub resource: Resource,
+    /// Credits minus debits; negative for a source such as a location.
+    #[ts(as = "String")]
+    pub balance: Decimal,
+}
+
+/// An adopted currency's money supply in a [`LedgerSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct CurrencySupply {
+    /// The currency.
+    pub currency: Resource,
+    /// Everything minted, less everything burned.
+    #[ts(as = "String")]
+    pub supply: Decimal,
+}
+
+/// The principal a debtor owes a creditor in a [`LedgerSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct OutstandingLoan {
+    /// The agent owed.
+    pub creditor: Uuid,
+    /// The agent owing.
+    pub debtor: Uuid,
+    /// The resource lent.
+    pub resource: Resource,
+    /// The principal not yet repaid.
+    #[ts(as = "String")]
+    pub principal: Decimal,
+}
+
 // ---------------------------------------------------------------------------
 // 4.0 Sex
 // ---------------------------------------------------------------------------
@@ -585,6 +809,10 @@ pub struct Location {
     pub description: String,
     /// Maximum number of agents.
     pub capacity: u32,
+    /// Height above sea level in meters. Climbing and descending between
+    /// locations adds to travel time.
+    #[serde(default)]
+    pub elevation: i32,
     /// Resource availability at this location.
     pub base_resources: BTreeMap<Resource, ResourceNode>,
     /// Agents who know about this location.
@@ -669,6 +897,9 @@ pub struct Structure {
     pub access_list: Option<AccessControlList>,
     /// Type-specific properties.
     pub properties: StructureProperties,
+    /// Resources stored in the structure, for shared storage structures.
+    #[serde(default)]
+    pub inventory: BTreeMap<Resource, u32>,
 }
 
 // ---------------------------------------------------------------------------
@@ -745,6 +976,13 @@ pub struct EconomyStats {
 // 5.4 Event Detail types
 // ---------------------------------------------------------------------------
 
+/// Schema version of the event detail types below.
+///
+/// Stored with every persisted event. Bump it whenever a details struct
+/// changes shape, and register an upcaster from the previous version in
+/// `emergence-events` so historical runs still replay.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
 /// Details for a successful action event.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
@@ -829,6 +1067,49 @@ pub struct PendingTrade {
     pub location_id: LocationId,
 }
 
+/// Which side of a market an order is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub enum OrderSide {
+    /// The order pays to receive the resource.
+    Buy,
+    /// The order gives the resource to be paid.
+    Sell,
+}
+
+/// A standing order on a market's order book stored in `Dragonfly`.
+///
+/// Created when an agent submits a [`PostOrder`] action. What the order
+/// gives up is held in escrow until the order is filled, at the tick it
+/// crosses an opposite order at the same market, or it expires after
+/// `expires_at_tick`. The counterparty need not be present when it fills.
+///
+/// [`PostOrder`]: crate::ActionType::PostOrder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct StandingOrder {
+    /// Unique order identifier, also naming its escrow.
+    pub order_id: OrderId,
+    /// Agent who posted the order.
+    pub agent_id: AgentId,
+    /// The market structure the order stands at.
+    pub market_id: StructureId,
+    /// Whether the order buys or sells `resource`.
+    pub side: OrderSide,
+    /// The resource bought or sold.
+    pub resource: Resource,
+    /// How much of `resource` is still to be bought or sold.
+    pub quantity: u32,
+    /// The resource paid in.
+    pub price_resource: Resource,
+    /// The limit price per unit of `resource`, in `price_resource`.
+    pub unit_price: u32,
+    /// Tick when the order was posted.
+    pub placed_at_tick: u64,
+    /// Tick when the order expires if not filled.
+    pub expires_at_tick: u64,
+}
+
 /// The reason a trade failed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
@@ -1087,7 +1368,8 @@ pub struct StructureBlueprint {
 ///
 /// Computed from the set of standing structures to determine bonuses
 /// for agents resting, weather protection, production output, and
-/// additional storage capacity.
+/// additional storage capacity, along with the location's crowding
+/// threshold and the penalties of exceeding it.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
 pub struct LocationEffects {
@@ -1103,6 +1385,14 @@ pub struct LocationEffects {
     pub has_fire: bool,
     /// Resources produced per tick by structures at this location.
     pub production: BTreeMap<Resource, u32>,
+    /// Occupants the location holds before it is overcrowded.
+    pub crowding_threshold: u32,
+    /// Agents currently at the location.
+    pub occupants: u32,
+    /// Percentage of resource regeneration lost to overcrowding.
+    pub regen_penalty_pct: u32,
+    /// Disease risk as a percentage of normal (100 = not overcrowded).
+    pub disease_risk_pct: u32,
 }
 
 // ---------------------------------------------------------------------------
@@ -1197,6 +1487,77 @@ pub struct RouteDegradedDetails {
     pub weather: Weather,
 }
 
+/// Details for a trade route emerged event (a route's traffic made it a
+/// trade corridor).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct TradeRouteEmergedDetails {
+    /// The route that became a corridor.
+    pub route_id: RouteId,
+    /// One end of the route.
+    pub from_location: LocationId,
+    /// The other end of the route.
+    pub to_location: LocationId,
+    /// Trips along the route within the utilization window.
+    pub trips: u32,
+    /// Ticks of traffic counted towards the route's utilization.
+    pub window_ticks: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Disaster Event Details
+// ---------------------------------------------------------------------------
+
+/// Details for a flood, wildfire, or earthquake event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct DisasterDetails {
+    /// The kind of disaster.
+    pub kind: crate::enums::DisasterKind,
+    /// The location that was struck.
+    pub location_id: LocationId,
+    /// Magnitude from 1 (minor) to 5 (catastrophic).
+    pub severity: u32,
+    /// Whether the operator injected the disaster rather than it striking
+    /// at random.
+    pub injected: bool,
+    /// Resources lost at the location.
+    pub resources_destroyed: BTreeMap<Resource, u32>,
+    /// Resource nodes wiped out entirely.
+    pub nodes_destroyed: Vec<Resource>,
+    /// Structures that lost durability but still stand.
+    pub structures_damaged: Vec<StructureId>,
+    /// Structures that collapsed.
+    pub structures_destroyed: Vec<StructureId>,
+    /// Routes out of the location that cannot be travelled for a while.
+    pub routes_blocked: Vec<RouteId>,
+    /// The first tick at which the blocked routes open again.
+    pub blocked_until_tick: u64,
+}
+
+/// Details for a structure damaged by a fire burning at its location.
+///
+/// A fire that spreads between structures emits one of these per
+/// structure per tick it burns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct StructureBurnedDetails {
+    /// The structure that burned.
+    pub structure_id: StructureId,
+    /// The type of structure that burned.
+    pub structure_type: StructureType,
+    /// The location where the fire burns.
+    pub location_id: LocationId,
+    /// Durability lost to the flames this tick.
+    pub damage: u32,
+    /// Durability left after the fire.
+    pub durability: u32,
+    /// Whether the structure collapsed.
+    pub destroyed: bool,
+    /// The fire's intensity, from 1 to 5, when it burned the structure.
+    pub fire_intensity: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Governance Types (Phase 4.4)
 // ---------------------------------------------------------------------------
@@ -1243,6 +1604,26 @@ pub struct StructureClaimedDetails {
     pub location_id: LocationId,
 }
 
+/// Details for a structure inherited event.
+///
+/// Emitted when a structure passes to its owner's heir -- a living spouse,
+/// or failing that a living child -- on the owner's death, instead of
+/// becoming claimable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct StructureInheritedDetails {
+    /// The structure that was inherited.
+    pub structure_id: StructureId,
+    /// The type of structure that was inherited.
+    pub structure_type: StructureType,
+    /// The agent whose death passed the structure on.
+    pub deceased: AgentId,
+    /// The family member who now owns the structure.
+    pub heir: AgentId,
+    /// The location where the structure exists.
+    pub location_id: LocationId,
+}
+
 /// Details for a rule created event.
 ///
 /// Emitted when an agent successfully creates a governance rule via
@@ -1406,3 +1787,60 @@ pub struct CombatResolvedDetails {
     /// The location where combat occurred.
     pub location_id: LocationId,
 }
+
+/// Details for a summary event that replaced compacted events.
+///
+/// One summary stands in for every folded event of one type, for one
+/// agent, within one compaction window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct EventsCompactedDetails {
+    /// The type of the folded events.
+    pub event_type: EventType,
+    /// Number of events folded.
+    pub count: u32,
+    /// Tick of the earliest folded event.
+    pub first_tick: u64,
+    /// Tick of the latest folded event.
+    pub last_tick: u64,
+    /// Resource quantities summed across the folded events, for event
+    /// types that carry them.
+    pub resources: BTreeMap<Resource, u32>,
+}
+
+/// Details for a famine detected in the event history.
+///
+/// A famine starts at the first tick at which at least half of the living
+/// agents are starving.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct FamineStartedDetails {
+    /// Number of living agents at or above the hunger threshold.
+    pub starving: u32,
+    /// Number of living agents.
+    pub population: u32,
+    /// Hunger at which an agent counts as starving.
+    pub hunger_threshold: u32,
+}
+
+/// Details for a ledger balance that disagrees with the world state.
+///
+/// Emitted by the end-of-tick reconciliation scan when an agent's
+/// inventory, a location's resource nodes, or a structure's materials hold
+/// a different quantity than the ledger says they should.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+pub struct ReconciliationMismatchDetails {
+    /// The entity whose holdings drifted.
+    pub entity_id: Uuid,
+    /// The type of the entity.
+    pub entity_type: EntityType,
+    /// The resource that drifted.
+    pub resource: Resource,
+    /// The entity's balance according to the ledger.
+    #[ts(as = "String")]
+    pub ledger_balance: Decimal,
+    /// The quantity the entity actually holds.
+    #[ts(as = "String")]
+    pub world_quantity: Decimal,
+}
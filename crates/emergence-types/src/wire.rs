@@ -0,0 +1,417 @@
+//! Versioned wire protocol for NATS messages.
+//!
+//! Perceptions, actions, reflections, and decision records travel between
+//! the World Engine and the agent runner, which ship as separate binaries.
+//! Each message carries a [`WireEnvelope`] -- protocol version, payload
+//! type, and compression flag -- in NATS headers, while the body stays
+//! plain JSON. Binaries that predate the envelope ignore the headers, and
+//! messages without them are read as [`LEGACY_VERSION`], so either binary
+//! can be upgraded first.
+//!
+//! # Negotiation
+//!
+//! The runner answers requests on [`HELLO_SUBJECT`] with its
+//! [`ProtocolSupport`]. The engine asks before publishing perceptions and
+//! settles on the highest version both sides speak with [`negotiate`]; with
+//! no answer (no runner yet, or one that predates the envelope) it speaks
+//! [`LEGACY_VERSION`] and asks again later. The runner answers each
+//! perception in the version it was addressed in.
+//!
+//! # Compression
+//!
+//! The compression flag is reserved for a future codec. This build
+//! advertises no compression support, never sets the flag, and rejects
+//! compressed payloads with [`WireError::UnsupportedCompression`].
+
+use serde::{Deserialize, Serialize};
+
+/// The newest protocol version this build speaks.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// The version of messages sent without an envelope, before the wire
+/// protocol was versioned. The body format is the same as version 1.
+pub const LEGACY_VERSION: u16 = 0;
+
+/// The oldest protocol version this build still reads and writes.
+pub const MIN_PROTOCOL_VERSION: u16 = LEGACY_VERSION;
+
+/// Subject the runner answers with its [`ProtocolSupport`].
+pub const HELLO_SUBJECT: &str = "emergence.protocol.hello";
+
+/// Header carrying the protocol version.
+pub const VERSION_HEADER: &str = "Emergence-Protocol-Version";
+
+/// Header carrying the [`PayloadType`].
+pub const PAYLOAD_TYPE_HEADER: &str = "Emergence-Payload-Type";
+
+/// Header set to `true` when the body is compressed.
+pub const COMPRESSED_HEADER: &str = "Emergence-Compressed";
+
+// ---------------------------------------------------------------------------
+// Envelope
+// ---------------------------------------------------------------------------
+
+/// The kind of payload a message carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadType {
+    /// A [`Perception`](crate::Perception) from engine to runner.
+    Perception,
+    /// An [`ActionRequest`](crate::ActionRequest) from runner to engine.
+    Action,
+    /// A [`ReflectionUpdate`](crate::ReflectionUpdate) from runner to engine.
+    Reflection,
+    /// A [`DecisionRecord`](crate::DecisionRecord) from runner to observer.
+    Decision,
+}
+
+impl PayloadType {
+    /// The header value for this payload type.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Perception => "perception",
+            Self::Action => "action",
+            Self::Reflection => "reflection",
+            Self::Decision => "decision",
+        }
+    }
+
+    /// Parse a header value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "perception" => Some(Self::Perception),
+            "action" => Some(Self::Action),
+            "reflection" => Some(Self::Reflection),
+            "decision" => Some(Self::Decision),
+            _ => None,
+        }
+    }
+}
+
+/// Protocol metadata attached to a NATS message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireEnvelope {
+    /// Protocol version the body is written in.
+    pub protocol_version: u16,
+    /// What the body holds.
+    pub payload_type: PayloadType,
+    /// Whether the body is compressed.
+    pub compressed: bool,
+}
+
+impl WireEnvelope {
+    /// An uncompressed envelope.
+    pub const fn new(protocol_version: u16, payload_type: PayloadType) -> Self {
+        Self {
+            protocol_version,
+            payload_type,
+            compressed: false,
+        }
+    }
+
+    /// The headers to publish with the message.
+    ///
+    /// Legacy envelopes produce no headers, so the message is exactly what
+    /// an unversioned binary would send.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        if self.protocol_version == LEGACY_VERSION {
+            return Vec::new();
+        }
+        let mut headers = vec![
+            (VERSION_HEADER, self.protocol_version.to_string()),
+            (PAYLOAD_TYPE_HEADER, self.payload_type.as_str().to_owned()),
+        ];
+        if self.compressed {
+            headers.push((COMPRESSED_HEADER, "true".to_owned()));
+        }
+        headers
+    }
+
+    /// Read and validate the envelope of a received message.
+    ///
+    /// `header` looks up a header value by name. A message without a
+    /// version header is a legacy message of the `expected` type.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`WireError`] if a header is malformed, the version is
+    /// outside the supported range, the payload is not of the `expected`
+    /// type, or the body is compressed.
+    pub fn read<'a>(
+        expected: PayloadType,
+        header: impl Fn(&str) -> Option<&'a str>,
+    ) -> Result<Self, WireError> {
+        let Some(version) = header(VERSION_HEADER) else {
+            return Ok(Self::new(LEGACY_VERSION, expected));
+        };
+        let protocol_version: u16 = version
+            .trim()
+            .parse()
+            .map_err(|e| WireError::Malformed(format!("{VERSION_HEADER}: {version} ({e})")))?;
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&protocol_version) {
+            return Err(WireError::UnsupportedVersion(protocol_version));
+        }
+
+        let payload_type = match header(PAYLOAD_TYPE_HEADER) {
+            None => expected,
+            Some(value) => PayloadType::parse(value.trim())
+                .ok_or_else(|| WireError::Malformed(format!("{PAYLOAD_TYPE_HEADER}: {value}")))?,
+        };
+        if payload_type != expected {
+            return Err(WireError::UnexpectedPayload {
+                expected,
+                found: payload_type,
+            });
+        }
+
+        let compressed = header(COMPRESSED_HEADER).is_some_and(|v| v.trim() == "true");
+        if compressed {
+            return Err(WireError::UnsupportedCompression);
+        }
+        Ok(Self {
+            protocol_version,
+            payload_type,
+            compressed,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Negotiation
+// ---------------------------------------------------------------------------
+
+/// The protocol versions and features a binary supports.
+///
+/// Exchanged as JSON on [`HELLO_SUBJECT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolSupport {
+    /// Oldest version spoken.
+    pub min_version: u16,
+    /// Newest version spoken.
+    pub max_version: u16,
+    /// Whether compressed payloads can be read.
+    #[serde(default)]
+    pub compression: bool,
+}
+
+impl ProtocolSupport {
+    /// What this build supports.
+    pub const fn local() -> Self {
+        Self {
+            min_version: MIN_PROTOCOL_VERSION,
+            max_version: PROTOCOL_VERSION,
+            compression: false,
+        }
+    }
+}
+
+/// The protocol two binaries agreed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedProtocol {
+    /// Version both sides speak.
+    pub version: u16,
+    /// Whether both sides read compressed payloads.
+    pub compression: bool,
+}
+
+/// Pick the highest protocol version both sides support.
+///
+/// Compression is used only if both sides support it.
+///
+/// # Errors
+///
+/// Returns [`WireError::Incompatible`] if the version ranges do not
+/// overlap.
+pub fn negotiate(
+    local: &ProtocolSupport,
+    remote: &ProtocolSupport,
+) -> Result<NegotiatedProtocol, WireError> {
+    let version = local.max_version.min(remote.max_version);
+    if version < local.min_version || version < remote.min_version {
+        return Err(WireError::Incompatible {
+            local: *local,
+            remote: *remote,
+        });
+    }
+    Ok(NegotiatedProtocol {
+        version,
+        compression: local.compression && remote.compression,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// A message or peer that cannot be handled under the wire protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireError {
+    /// A protocol header could not be parsed.
+    Malformed(String),
+    /// The message uses a protocol version this build does not speak.
+    UnsupportedVersion(u16),
+    /// The message holds a different payload than the subject implies.
+    UnexpectedPayload {
+        /// The payload type the receiver expected.
+        expected: PayloadType,
+        /// The payload type the envelope declared.
+        found: PayloadType,
+    },
+    /// The body is compressed and this build has no codec.
+    UnsupportedCompression,
+    /// The two sides share no protocol version.
+    Incompatible {
+        /// This build's support.
+        local: ProtocolSupport,
+        /// The peer's support.
+        remote: ProtocolSupport,
+    },
+}
+
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(header) => write!(f, "malformed protocol header {header}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported protocol version {version} (supported \
+                 {MIN_PROTOCOL_VERSION}..={PROTOCOL_VERSION})"
+            ),
+            Self::UnexpectedPayload { expected, found } => write!(
+                f,
+                "expected {} payload, got {}",
+                expected.as_str(),
+                found.as_str()
+            ),
+            Self::UnsupportedCompression => write!(f, "compressed payloads are not supported"),
+            Self::Incompatible { local, remote } => write!(
+                f,
+                "no common protocol version (local {}..={}, remote {}..={})",
+                local.min_version, local.max_version, remote.min_version, remote.max_version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn read_from(
+        expected: PayloadType,
+        headers: &[(&'static str, String)],
+    ) -> Result<WireEnvelope, WireError> {
+        let map: BTreeMap<&str, &str> = headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        WireEnvelope::read(expected, |name| map.get(name).copied())
+    }
+
+    #[test]
+    fn envelope_round_trips_through_headers() {
+        let envelope = WireEnvelope::new(PROTOCOL_VERSION, PayloadType::Action);
+        let headers = envelope.headers();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(read_from(PayloadType::Action, &headers), Ok(envelope));
+    }
+
+    #[test]
+    fn legacy_messages_have_no_headers_and_still_read() {
+        let legacy = WireEnvelope::new(LEGACY_VERSION, PayloadType::Perception);
+        assert!(legacy.headers().is_empty());
+        assert_eq!(read_from(PayloadType::Perception, &[]), Ok(legacy));
+    }
+
+    #[test]
+    fn newer_and_malformed_versions_are_rejected() {
+        let too_new = PROTOCOL_VERSION.saturating_add(1);
+        let headers = [(VERSION_HEADER, too_new.to_string())];
+        assert_eq!(
+            read_from(PayloadType::Action, &headers),
+            Err(WireError::UnsupportedVersion(too_new))
+        );
+        let headers = [(VERSION_HEADER, "one".to_owned())];
+        assert!(matches!(
+            read_from(PayloadType::Action, &headers),
+            Err(WireError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn payload_type_must_match_subject() {
+        let headers = WireEnvelope::new(PROTOCOL_VERSION, PayloadType::Reflection).headers();
+        assert_eq!(
+            read_from(PayloadType::Action, &headers),
+            Err(WireError::UnexpectedPayload {
+                expected: PayloadType::Action,
+                found: PayloadType::Reflection,
+            })
+        );
+        let headers = [
+            (VERSION_HEADER, "1".to_owned()),
+            (PAYLOAD_TYPE_HEADER, "gossip".to_owned()),
+        ];
+        assert!(matches!(
+            read_from(PayloadType::Action, &headers),
+            Err(WireError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn compressed_payloads_are_rejected() {
+        let mut envelope = WireEnvelope::new(PROTOCOL_VERSION, PayloadType::Decision);
+        envelope.compressed = true;
+        assert_eq!(
+            read_from(PayloadType::Decision, &envelope.headers()),
+            Err(WireError::UnsupportedCompression)
+        );
+    }
+
+    #[test]
+    fn negotiation_picks_highest_common_version() {
+        let local = ProtocolSupport::local();
+        let newer = ProtocolSupport {
+            min_version: 0,
+            max_version: PROTOCOL_VERSION.saturating_add(2),
+            compression: true,
+        };
+        let agreed = negotiate(&local, &newer);
+        assert_eq!(
+            agreed,
+            Ok(NegotiatedProtocol {
+                version: PROTOCOL_VERSION,
+                compression: false,
+            })
+        );
+
+        let legacy_only = ProtocolSupport {
+            min_version: LEGACY_VERSION,
+            max_version: LEGACY_VERSION,
+            compression: false,
+        };
+        assert_eq!(negotiate(&local, &legacy_only).map(|p| p.version), Ok(LEGACY_VERSION));
+    }
+
+    #[test]
+    fn negotiation_fails_without_overlap() {
+        let local = ProtocolSupport::local();
+        let future = ProtocolSupport {
+            min_version: PROTOCOL_VERSION.saturating_add(1),
+            max_version: PROTOCOL_VERSION.saturating_add(3),
+            compression: false,
+        };
+        assert!(matches!(
+            negotiate(&local, &future),
+            Err(WireError::Incompatible { .. })
+        ));
+    }
+
+    #[test]
+    fn support_parses_without_compression_field() {
+        let support: Option<ProtocolSupport> =
+            serde_json::from_str(r#"{"min_version": 0, "max_version": 4}"#).ok();
+        assert_eq!(support.map(|s| (s.max_version, s.compression)), Some((4, false)));
+    }
+}
@@ -0,0 +1,60 @@
+//! Biomes: the kind of country a location lies in.
+//!
+//! A location's biome follows from its region, so every location in a
+//! region shares one. The starting world and generated maps name their
+//! regions after the [`Biome`]s below, numbering repeats ("Highlands 2");
+//! a region with any other name, such as one given in a hand-authored
+//! world file, is [`Biome::Temperate`].
+//!
+//! Biomes shape how resources regrow through the year (see
+//! [`crate::resource::RegenCurves`]).
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of country a location lies in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Biome {
+    /// River valleys and open woodland: the "Central Valley".
+    Temperate,
+    /// Rocky uplands with hard winters: the "Highlands".
+    Highland,
+    /// Shores with mild winters: the "Coastal Lowlands".
+    Coastal,
+    /// Marshes and bogs: the "Wetlands".
+    Wetland,
+    /// Dry grassland with parched summers: the "Steppe".
+    Steppe,
+}
+
+impl Biome {
+    /// Return the biome of locations in `region`.
+    pub fn of_region(region: &str) -> Self {
+        if region.starts_with("Highlands") {
+            Self::Highland
+        } else if region.starts_with("Coastal Lowlands") {
+            Self::Coastal
+        } else if region.starts_with("Wetlands") {
+            Self::Wetland
+        } else if region.starts_with("Steppe") {
+            Self::Steppe
+        } else {
+            Self::Temperate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_map_to_biomes() {
+        assert_eq!(Biome::of_region("Central Valley"), Biome::Temperate);
+        assert_eq!(Biome::of_region("Highlands"), Biome::Highland);
+        assert_eq!(Biome::of_region("Highlands 2"), Biome::Highland);
+        assert_eq!(Biome::of_region("Coastal Lowlands"), Biome::Coastal);
+        assert_eq!(Biome::of_region("Wetlands"), Biome::Wetland);
+        assert_eq!(Biome::of_region("Steppe"), Biome::Steppe);
+        assert_eq!(Biome::of_region("Somewhere Else"), Biome::Temperate);
+    }
+}
@@ -0,0 +1,149 @@
+//! Long-term climate drift layered over the weather system.
+//!
+//! Seasons repeat, but the climate need not. A [`Climate`] shifts the
+//! season-weighted weather probabilities and the base regeneration of every
+//! resource node a little further each tick, reaching its full strength
+//! after [`Climate::drift_ticks`] ticks and holding there:
+//!
+//! - **Warming** turns snow into rain: [`Climate::warming_pct`] of each
+//!   season's snow weight moves to rain.
+//! - **Drying** turns rain into drought: [`Climate::drying_pct`] of each
+//!   season's rain weight moves to drought. Drying is applied after
+//!   warming, so a warming, drying world loses its winter snow to drought.
+//! - **Regeneration loss** slows every resource node by
+//!   [`Climate::regen_loss_pct`] percent, on top of any crowding penalty.
+//!
+//! The default climate does not drift, leaving the weather exactly as
+//! [`SeasonWeights::for_season`] describes it.
+
+use emergence_types::{Season, Weather};
+
+use crate::environment::SeasonWeights;
+
+/// A climate drifting in a straight line from the seasonal norm to its full
+/// strength over a number of ticks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Climate {
+    /// Ticks until the drift reaches full strength. 0 disables drift.
+    pub drift_ticks: u64,
+    /// Percentage of snow weight that has turned to rain at full strength.
+    pub warming_pct: u32,
+    /// Percentage of rain weight that has turned to drought at full
+    /// strength.
+    pub drying_pct: u32,
+    /// Percentage of regeneration lost at full strength.
+    pub regen_loss_pct: u32,
+}
+
+impl Climate {
+    /// Create a climate that reaches full strength after `drift_ticks`
+    /// ticks, with no warming, drying, or regeneration loss yet.
+    pub const fn new(drift_ticks: u64) -> Self {
+        Self {
+            drift_ticks,
+            warming_pct: 0,
+            drying_pct: 0,
+            regen_loss_pct: 0,
+        }
+    }
+
+    /// Set the share of snow that turns to rain at full strength.
+    #[must_use]
+    pub const fn with_warming_pct(mut self, pct: u32) -> Self {
+        self.warming_pct = pct;
+        self
+    }
+
+    /// Set the share of rain that turns to drought at full strength.
+    #[must_use]
+    pub const fn with_drying_pct(mut self, pct: u32) -> Self {
+        self.drying_pct = pct;
+        self
+    }
+
+    /// Set the regeneration lost at full strength.
+    #[must_use]
+    pub const fn with_regen_loss_pct(mut self, pct: u32) -> Self {
+        self.regen_loss_pct = pct;
+        self
+    }
+
+    /// Return how far the drift has come by `tick`, from 0 to 100 percent.
+    pub fn progress_pct(&self, tick: u64) -> u32 {
+        let progress = tick
+            .saturating_mul(100)
+            .checked_div(self.drift_ticks)
+            .unwrap_or(0)
+            .min(100);
+        u32::try_from(progress).unwrap_or(100)
+    }
+
+    /// Return the weather weights for `season` at `tick`, shifted by the
+    /// drift so far.
+    pub fn season_weights(&self, season: Season, tick: u64) -> SeasonWeights {
+        let mut weights = SeasonWeights::for_season(season);
+        let progress = self.progress_pct(tick);
+        weights.shift(Weather::Snow, Weather::Rain, scale_pct(self.warming_pct, progress));
+        weights.shift(Weather::Rain, Weather::Drought, scale_pct(self.drying_pct, progress));
+        weights
+    }
+
+    /// Return the percentage of regeneration lost to the climate at `tick`.
+    pub fn regen_loss_pct_at(&self, tick: u64) -> u32 {
+        scale_pct(self.regen_loss_pct, self.progress_pct(tick)).min(100)
+    }
+}
+
+/// `pct` percent of `progress` percent, rounded down.
+const fn scale_pct(pct: u32, progress: u32) -> u32 {
+    pct.saturating_mul(progress).saturating_div(100)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::WeatherSystem;
+
+    #[test]
+    fn drift_ramps_up_then_holds() {
+        let climate = Climate::new(400).with_regen_loss_pct(30);
+        assert_eq!(climate.progress_pct(0), 0);
+        assert_eq!(climate.progress_pct(200), 50);
+        assert_eq!(climate.progress_pct(400), 100);
+        assert_eq!(climate.progress_pct(10_000), 100);
+        assert_eq!(climate.regen_loss_pct_at(200), 15);
+        assert_eq!(climate.regen_loss_pct_at(900), 30);
+
+        assert_eq!(Climate::default().progress_pct(10_000), 0);
+        assert_eq!(Climate::default().regen_loss_pct_at(10_000), 0);
+    }
+
+    #[test]
+    fn warming_and_drying_move_weight_towards_drought() {
+        let climate = Climate::new(100).with_warming_pct(50).with_drying_pct(100);
+        let norm = SeasonWeights::for_season(Season::Winter);
+        assert_eq!(climate.season_weights(Season::Winter, 0), norm);
+
+        // Half the snow has turned to rain, and all the rain to drought.
+        let drifted = climate.season_weights(Season::Winter, 100);
+        assert_eq!(drifted.weight_of(Weather::Snow), 20);
+        assert_eq!(drifted.weight_of(Weather::Rain), 0);
+        assert_eq!(drifted.weight_of(Weather::Drought), 30);
+        assert_eq!(drifted.total_weight(), norm.total_weight());
+    }
+
+    #[test]
+    fn a_drying_climate_brings_more_droughts() {
+        let climate = Climate::new(100).with_drying_pct(100);
+        let mut steady = WeatherSystem::new(42);
+        let mut drying = WeatherSystem::new(42).with_climate(climate);
+
+        let droughts = |system: &mut WeatherSystem| {
+            (500..900)
+                .filter(|tick| system.generate(*tick, Season::Spring) == Weather::Drought)
+                .count()
+        };
+        assert!(droughts(&mut drying) > droughts(&mut steady));
+    }
+}
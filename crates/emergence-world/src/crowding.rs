@@ -0,0 +1,135 @@
+//! Carrying capacity and the effects of overcrowding a location.
+//!
+//! A location's `capacity` is a hard cap on how many agents can stand
+//! there. Well before that cap is reached the land starts to suffer: once
+//! more than [`COMFORTABLE_OCCUPANCY_PCT`] of the capacity is occupied, the
+//! location is overcrowded, and each agent over that threshold
+//!
+//! - slows resource regeneration by [`REGEN_PENALTY_PER_AGENT_PCT`], up to
+//!   [`MAX_REGEN_PENALTY_PCT`], and
+//! - raises the harm a plague does there by [`DISEASE_RISK_PER_AGENT_PCT`],
+//!   up to [`MAX_DISEASE_RISK_PCT`].
+//!
+//! [`location_effects`] reports the threshold and both modifiers through
+//! [`LocationEffects`], and agents see an overcrowding flag in their
+//! perception.
+
+use emergence_types::{LocationEffects, Structure};
+
+use crate::location::LocationState;
+use crate::structure::structure_effects_at_location;
+
+/// Percentage of a location's capacity it holds before it is overcrowded.
+pub const COMFORTABLE_OCCUPANCY_PCT: u32 = 75;
+
+/// Percentage of regeneration lost per agent over the crowding threshold.
+pub const REGEN_PENALTY_PER_AGENT_PCT: u32 = 10;
+
+/// The most regeneration, in percent, crowding can take away.
+pub const MAX_REGEN_PENALTY_PCT: u32 = 60;
+
+/// Percentage disease risk added per agent over the crowding threshold.
+pub const DISEASE_RISK_PER_AGENT_PCT: u32 = 25;
+
+/// The highest disease risk, in percent of normal, crowding can cause.
+pub const MAX_DISEASE_RISK_PCT: u32 = 300;
+
+/// Return the number of occupants a location of `capacity` holds before it
+/// is overcrowded. Always at least one.
+pub fn crowding_threshold(capacity: u32) -> u32 {
+    let threshold = u64::from(capacity)
+        .saturating_mul(u64::from(COMFORTABLE_OCCUPANCY_PCT))
+        .checked_div(100)
+        .unwrap_or(0);
+    u32::try_from(threshold).unwrap_or(u32::MAX).max(1)
+}
+
+/// Return how many of `occupants` are over the crowding threshold of a
+/// location of `capacity`.
+pub fn excess_occupants(capacity: u32, occupants: u32) -> u32 {
+    occupants.saturating_sub(crowding_threshold(capacity))
+}
+
+/// Return the percentage of regeneration lost at a location of `capacity`
+/// holding `occupants`.
+pub fn regen_penalty_pct(capacity: u32, occupants: u32) -> u32 {
+    excess_occupants(capacity, occupants)
+        .saturating_mul(REGEN_PENALTY_PER_AGENT_PCT)
+        .min(MAX_REGEN_PENALTY_PCT)
+}
+
+/// Return the disease risk, in percent of normal, at a location of
+/// `capacity` holding `occupants`. 100 when the location is not
+/// overcrowded.
+pub fn disease_risk_pct(capacity: u32, occupants: u32) -> u32 {
+    excess_occupants(capacity, occupants)
+        .saturating_mul(DISEASE_RISK_PER_AGENT_PCT)
+        .saturating_add(100)
+        .min(MAX_DISEASE_RISK_PCT)
+}
+
+/// Return whether `state` holds more agents than its crowding threshold.
+pub fn is_overcrowded(state: &LocationState) -> bool {
+    excess_occupants(state.location.capacity, state.occupant_count()) > 0
+}
+
+/// Compute the effects at `state` of its `structures` and its crowding.
+pub fn location_effects(state: &LocationState, structures: &[Structure]) -> LocationEffects {
+    let capacity = state.location.capacity;
+    let occupants = state.occupant_count();
+    LocationEffects {
+        crowding_threshold: crowding_threshold(capacity),
+        occupants,
+        regen_penalty_pct: regen_penalty_pct(capacity, occupants),
+        disease_risk_pct: disease_risk_pct(capacity, occupants),
+        ..structure_effects_at_location(structures)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::AgentId;
+
+    use super::*;
+
+    #[test]
+    fn threshold_is_three_quarters_of_capacity() {
+        assert_eq!(crowding_threshold(20), 15);
+        assert_eq!(crowding_threshold(10), 7);
+        assert_eq!(crowding_threshold(1), 1);
+        assert_eq!(crowding_threshold(0), 1);
+    }
+
+    #[test]
+    fn penalties_grow_with_each_agent_over_the_threshold() {
+        assert_eq!(regen_penalty_pct(20, 15), 0);
+        assert_eq!(disease_risk_pct(20, 15), 100);
+
+        assert_eq!(regen_penalty_pct(20, 17), 20);
+        assert_eq!(disease_risk_pct(20, 17), 150);
+
+        assert_eq!(regen_penalty_pct(100, 100), MAX_REGEN_PENALTY_PCT);
+        assert_eq!(disease_risk_pct(100, 100), MAX_DISEASE_RISK_PCT);
+    }
+
+    #[test]
+    fn location_effects_report_crowding() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let state = map.get_location_mut(ids.open_field).unwrap();
+        state.location.capacity = 4;
+        assert!(!is_overcrowded(state));
+
+        for _ in 0..4 {
+            state.add_occupant(AgentId::new()).unwrap();
+        }
+        assert!(is_overcrowded(state));
+
+        let effects = location_effects(state, &[]);
+        assert_eq!(effects.crowding_threshold, 3);
+        assert_eq!(effects.occupants, 4);
+        assert_eq!(effects.regen_penalty_pct, REGEN_PENALTY_PER_AGENT_PCT);
+        assert_eq!(effects.disease_risk_pct, 125);
+        assert!(!effects.has_shelter);
+    }
+}
@@ -0,0 +1,403 @@
+//! Natural disasters: floods, wildfires, and earthquakes.
+//!
+//! A [`DisasterSystem`] rolls for a disaster every tick from the world seed,
+//! the same way [`WeatherSystem`](crate::WeatherSystem) rolls for weather,
+//! so two runs with the same seed suffer the same disasters. The operator
+//! can also inject one. A disaster strikes a single location with a
+//! severity from 1 (minor) to 5 (catastrophic):
+//!
+//! | Kind | Resources lost | Structure damage | Routes blocked for |
+//! |------|----------------|------------------|--------------------|
+//! | Flood | berries, roots, crops, clay | 10% × severity | 2 × severity ticks |
+//! | Wildfire | wood, fiber, hide, berries, roots, crops | 15% × severity | severity ticks |
+//! | Earthquake | stone, ore, water | 20% × severity | 3 × severity ticks |
+//!
+//! Each affected resource node loses 20% of its stock per severity level; at
+//! severity 5 the node is wiped out entirely. Structure damage is a share
+//! of maximum durability (see [`apply_disaster_damage`]).
+//!
+//! A blocked route denies traversal to every agent: its access list is set
+//! aside and replaced with a closed one until
+//! [`DisasterSystem::lift_expired`] restores it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use emergence_types::{
+    AccessControlList, DisasterDetails, DisasterKind, EventType, LocationId, Resource, RouteId,
+    Structure,
+};
+
+use crate::environment::deterministic_random;
+use crate::error::WorldError;
+use crate::metrics;
+use crate::structure::apply_disaster_damage;
+use crate::world_map::WorldMap;
+
+/// Highest disaster severity.
+pub const MAX_SEVERITY: u32 = 5;
+
+/// Salt mixed into the world seed so disaster rolls are independent of the
+/// weather rolls made from the same seed.
+const DISASTER_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Rolls are made out of this many; the chance per tick is given in parts
+/// per million.
+const CHANCE_SCALE: u64 = 1_000_000;
+
+/// A disaster about to strike a location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disaster {
+    /// The kind of disaster.
+    pub kind: DisasterKind,
+    /// The location it strikes.
+    pub location_id: LocationId,
+    /// Magnitude from 1 to [`MAX_SEVERITY`].
+    pub severity: u32,
+}
+
+/// A route closed by a disaster, and the access list to restore.
+#[derive(Debug, Clone)]
+struct BlockedRoute {
+    until_tick: u64,
+    acl: Option<AccessControlList>,
+}
+
+/// Seeded disaster rolls and the routes disasters have blocked.
+#[derive(Debug, Clone, Default)]
+pub struct DisasterSystem {
+    /// World seed for deterministic rolls.
+    world_seed: u64,
+    /// Chance of a disaster each tick, in parts per million.
+    chance_per_million: u32,
+    /// Routes currently blocked.
+    blocked: BTreeMap<RouteId, BlockedRoute>,
+}
+
+impl DisasterSystem {
+    /// Create a disaster system for the given world seed that strikes with
+    /// `chance_per_million` parts per million each tick. A chance of 0
+    /// disables random disasters, leaving only injected ones.
+    pub const fn new(world_seed: u64, chance_per_million: u32) -> Self {
+        Self {
+            world_seed,
+            chance_per_million,
+            blocked: BTreeMap::new(),
+        }
+    }
+
+    /// Roll for a random disaster at `tick`.
+    ///
+    /// Returns `None` on most ticks, or if the map has no locations. The
+    /// same seed, tick, and map always give the same result.
+    pub fn roll(&self, tick: u64, map: &WorldMap) -> Option<Disaster> {
+        let roll = deterministic_random(self.world_seed ^ DISASTER_SEED_SALT, tick);
+        if roll.checked_rem(CHANCE_SCALE).unwrap_or(0) >= u64::from(self.chance_per_million) {
+            return None;
+        }
+        // Draw the rest of the disaster from a second, independent value.
+        let pick = deterministic_random(roll, tick);
+        let locations = map.location_ids();
+        let index = pick.checked_rem(locations.len() as u64).and_then(|i| usize::try_from(i).ok());
+        let location_id = index.and_then(|i| locations.get(i)).copied()?;
+        let kind = match (pick >> 32).checked_rem(3) {
+            Some(0) => DisasterKind::Flood,
+            Some(1) => DisasterKind::Wildfire,
+            _ => DisasterKind::Earthquake,
+        };
+        let severity = (pick >> 48).checked_rem(u64::from(MAX_SEVERITY)).unwrap_or(0);
+        Some(Disaster {
+            kind,
+            location_id,
+            severity: u32::try_from(severity).unwrap_or(0).saturating_add(1),
+        })
+    }
+
+    /// Strike `disaster` at `tick`: destroy resources at its location,
+    /// damage the standing structures there, and block the routes out of it.
+    ///
+    /// `structures` may include structures elsewhere; only those at the
+    /// location are affected. Collapsed structures are marked destroyed at
+    /// `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::LocationNotFound`] if the location does not
+    /// exist.
+    pub fn strike(
+        &mut self,
+        disaster: &Disaster,
+        tick: u64,
+        map: &mut WorldMap,
+        structures: &mut [Structure],
+        injected: bool,
+    ) -> Result<DisasterDetails, WorldError> {
+        let location_id = disaster.location_id;
+        let severity = disaster.severity.clamp(1, MAX_SEVERITY);
+        let Some(location) = map.get_location_mut(location_id) else {
+            return Err(WorldError::LocationNotFound(location_id));
+        };
+
+        let mut resources_destroyed = BTreeMap::new();
+        let mut nodes_destroyed = Vec::new();
+        for resource in affected_resources(disaster.kind) {
+            let Some(node) = location.get_resource_mut(resource) else {
+                continue;
+            };
+            let lost = if severity >= MAX_SEVERITY {
+                node.available
+            } else {
+                percent_of(node.available, severity.saturating_mul(20))
+            };
+            node.available = node.available.saturating_sub(lost);
+            if lost > 0 {
+                resources_destroyed.insert(*resource, lost);
+            }
+            if severity >= MAX_SEVERITY {
+                location.location.base_resources.remove(resource);
+                nodes_destroyed.push(*resource);
+            }
+        }
+
+        let damage_pct = structure_damage_pct(disaster.kind).saturating_mul(severity);
+        let mut structures_damaged = Vec::new();
+        let mut structures_destroyed = Vec::new();
+        let standing = structures
+            .iter_mut()
+            .filter(|s| s.location_id == location_id && s.destroyed_at_tick.is_none());
+        for structure in standing {
+            if apply_disaster_damage(structure, damage_pct) {
+                structure.destroyed_at_tick = Some(tick);
+                structures_destroyed.push(structure.id);
+            } else {
+                structures_damaged.push(structure.id);
+            }
+        }
+
+        let blocked_until_tick =
+            tick.saturating_add(u64::from(block_ticks(disaster.kind).saturating_mul(severity)));
+        let routes: BTreeSet<RouteId> =
+            map.neighbors(location_id).into_iter().map(|(_, route)| route).collect();
+        for route_id in &routes {
+            self.block(*route_id, blocked_until_tick, map);
+        }
+
+        metrics::DISASTERS.increment_with(&format!("{:?}", disaster.kind), 1);
+        Ok(DisasterDetails {
+            kind: disaster.kind,
+            location_id,
+            severity,
+            injected,
+            resources_destroyed,
+            nodes_destroyed,
+            structures_damaged,
+            structures_destroyed,
+            routes_blocked: routes.into_iter().collect(),
+            blocked_until_tick,
+        })
+    }
+
+    /// Reopen every route whose block has run out by `tick`, restoring its
+    /// access list. Returns the reopened routes.
+    pub fn lift_expired(&mut self, tick: u64, map: &mut WorldMap) -> Vec<RouteId> {
+        let expired: Vec<RouteId> = self
+            .blocked
+            .iter()
+            .filter(|(_, block)| block.until_tick <= tick)
+            .map(|(id, _)| *id)
+            .collect();
+        for route_id in &expired {
+            if let Some(block) = self.blocked.remove(route_id)
+                && let Some(route) = map.get_route_mut(*route_id)
+            {
+                route.acl = block.acl;
+            }
+        }
+        expired
+    }
+
+    /// Return whether a disaster has blocked `route_id`.
+    pub fn is_blocked(&self, route_id: RouteId) -> bool {
+        self.blocked.contains_key(&route_id)
+    }
+
+    /// Return the number of routes currently blocked.
+    pub fn blocked_count(&self) -> usize {
+        self.blocked.len()
+    }
+
+    /// Block `route_id` until `until_tick`, extending an existing block.
+    fn block(&mut self, route_id: RouteId, until_tick: u64, map: &mut WorldMap) {
+        if let Some(block) = self.blocked.get_mut(&route_id) {
+            block.until_tick = block.until_tick.max(until_tick);
+            return;
+        }
+        let Some(route) = map.get_route_mut(route_id) else {
+            return;
+        };
+        let acl = route.acl.replace(closed_acl());
+        self.blocked.insert(route_id, BlockedRoute { until_tick, acl });
+    }
+}
+
+/// The event type recorded when a disaster of `kind` strikes.
+pub const fn disaster_event_type(kind: DisasterKind) -> EventType {
+    match kind {
+        DisasterKind::Flood => EventType::FloodOccurred,
+        DisasterKind::Wildfire => EventType::WildfireOccurred,
+        DisasterKind::Earthquake => EventType::EarthquakeOccurred,
+    }
+}
+
+/// The resources a disaster of `kind` destroys.
+const fn affected_resources(kind: DisasterKind) -> &'static [Resource] {
+    match kind {
+        DisasterKind::Flood => &[
+            Resource::FoodBerry,
+            Resource::FoodRoot,
+            Resource::FoodFarmed,
+            Resource::Clay,
+        ],
+        DisasterKind::Wildfire => &[
+            Resource::Wood,
+            Resource::Fiber,
+            Resource::Hide,
+            Resource::FoodBerry,
+            Resource::FoodRoot,
+            Resource::FoodFarmed,
+        ],
+        DisasterKind::Earthquake => &[Resource::Stone, Resource::Ore, Resource::Water],
+    }
+}
+
+/// Percentage of maximum durability a structure loses per severity level.
+const fn structure_damage_pct(kind: DisasterKind) -> u32 {
+    match kind {
+        DisasterKind::Flood => 10,
+        DisasterKind::Wildfire => 15,
+        DisasterKind::Earthquake => 20,
+    }
+}
+
+/// Ticks the routes out of the location stay blocked per severity level.
+const fn block_ticks(kind: DisasterKind) -> u32 {
+    match kind {
+        DisasterKind::Flood => 2,
+        DisasterKind::Wildfire => 1,
+        DisasterKind::Earthquake => 3,
+    }
+}
+
+/// An access list that admits nobody.
+const fn closed_acl() -> AccessControlList {
+    AccessControlList {
+        allowed_agents: BTreeSet::new(),
+        allowed_groups: BTreeSet::new(),
+        denied_agents: BTreeSet::new(),
+        public: false,
+        toll_cost: None,
+    }
+}
+
+/// `pct` percent of `quantity`, rounded down.
+fn percent_of(quantity: u32, pct: u32) -> u32 {
+    let share = u64::from(quantity).saturating_mul(u64::from(pct)).checked_div(100).unwrap_or(0);
+    u32::try_from(share).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::arithmetic_side_effects)]
+mod tests {
+    use emergence_types::{AgentId, StructureId, StructureType};
+
+    use super::*;
+    use crate::route::can_traverse;
+    use crate::starting_world::create_starting_world;
+    use crate::structure::blueprint;
+
+    fn structure_at(location_id: LocationId) -> Structure {
+        let bp = blueprint(StructureType::LeanTo);
+        Structure {
+            id: StructureId::new(),
+            structure_type: StructureType::LeanTo,
+            subtype: None,
+            location_id,
+            builder: AgentId::new(),
+            owner: None,
+            built_at_tick: 1,
+            destroyed_at_tick: None,
+            materials_used: bp.material_costs.clone(),
+            durability: bp.max_durability,
+            max_durability: bp.max_durability,
+            decay_per_tick: bp.decay_per_tick,
+            capacity: bp.capacity,
+            occupants: BTreeSet::new(),
+            access_list: None,
+            properties: bp.properties,
+            inventory: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn rolls_are_deterministic_and_respect_the_chance() {
+        let (map, _) = create_starting_world().unwrap();
+        let always = DisasterSystem::new(42, 1_000_000);
+        let never = DisasterSystem::new(42, 0);
+        for tick in 1..50 {
+            let rolled = always.roll(tick, &map);
+            assert!(rolled.is_some());
+            assert_eq!(rolled, DisasterSystem::new(42, 1_000_000).roll(tick, &map));
+            assert!(rolled.is_some_and(|d| (1..=MAX_SEVERITY).contains(&d.severity)));
+            assert!(never.roll(tick, &map).is_none());
+        }
+    }
+
+    #[test]
+    fn earthquake_damages_structures_destroys_nodes_and_blocks_routes() {
+        let (mut map, ids) = create_starting_world().unwrap();
+        let mut structures = vec![structure_at(ids.rocky_outcrop), structure_at(ids.beach)];
+        let mut system = DisasterSystem::new(1, 0);
+        let quake = Disaster {
+            kind: DisasterKind::Earthquake,
+            location_id: ids.rocky_outcrop,
+            severity: 2,
+        };
+
+        let details = system.strike(&quake, 10, &mut map, &mut structures, true).unwrap();
+        assert_eq!(details.resources_destroyed.get(&Resource::Stone).copied(), Some(16));
+        assert!(details.nodes_destroyed.is_empty());
+        let before = blueprint(StructureType::LeanTo).max_durability;
+        assert_eq!(structures.first().map(|s| s.durability), Some(before - before * 40 / 100));
+        assert_eq!(structures.get(1).map(|s| s.durability), Some(before));
+        assert_eq!(details.blocked_until_tick, 16);
+        assert!(!details.routes_blocked.is_empty());
+
+        let route_id = details.routes_blocked.first().copied().unwrap();
+        let route = map.get_route(route_id).unwrap();
+        assert!(!can_traverse(route, AgentId::new(), &[]));
+        assert!(system.lift_expired(15, &mut map).is_empty());
+        assert_eq!(system.lift_expired(16, &mut map).len(), details.routes_blocked.len());
+        assert!(can_traverse(map.get_route(route_id).unwrap(), AgentId::new(), &[]));
+        assert_eq!(system.blocked_count(), 0);
+    }
+
+    #[test]
+    fn catastrophic_disasters_wipe_out_nodes_and_structures() {
+        let (mut map, ids) = create_starting_world().unwrap();
+        let mut structures = vec![structure_at(ids.rocky_outcrop)];
+        let mut system = DisasterSystem::new(1, 0);
+        let quake = Disaster {
+            kind: DisasterKind::Earthquake,
+            location_id: ids.rocky_outcrop,
+            severity: MAX_SEVERITY,
+        };
+
+        let details = system.strike(&quake, 3, &mut map, &mut structures, false).unwrap();
+        assert_eq!(details.nodes_destroyed, vec![Resource::Stone, Resource::Ore]);
+        let location = map.get_location(ids.rocky_outcrop).unwrap();
+        assert!(location.get_resource(&Resource::Stone).is_none());
+        assert!(location.get_resource(&Resource::FoodRoot).is_some());
+        assert_eq!(details.structures_destroyed.len(), 1);
+        assert_eq!(structures.first().and_then(|s| s.destroyed_at_tick), Some(3));
+        assert_eq!(disaster_event_type(details.kind), EventType::EarthquakeOccurred);
+    }
+}
@@ -20,20 +20,69 @@
 //! The "repeat" weight means the previous tick's weather persists, giving
 //! weather streaks a natural feel.
 //!
+//! A [`Climate`] can shift these weights over hundreds of ticks; see
+//! [`crate::climate`].
+//!
+//! # Forecasts
+//!
+//! Agents who know [`FORECAST_KNOWLEDGE`] see a forecast of the next
+//! [`FORECAST_TICKS`] ticks (see [`WeatherSystem::forecast`]). Each tick is
+//! called right with a chance of [`FORECAST_BASE_ACCURACY_PCT`], plus
+//! [`FORECAST_SKILL_BONUS_PCT`] per level of forecasting skill, capped at
+//! [`FORECAST_MAX_ACCURACY_PCT`].
+//!
 //! # Determinism
 //!
 //! The RNG is a simple `xorshift64` seeded from `(world_seed, tick)`. This
 //! means the same seed and tick always produce the same weather, enabling
 //! reproducible simulation runs and event replay.
 
-use emergence_types::{Season, Weather};
+use emergence_types::{Season, Weather, WeatherForecast};
+
+use crate::climate::Climate;
+
+/// The knowledge an agent needs to forecast the weather.
+pub const FORECAST_KNOWLEDGE: &str = "weather_lore";
+
+/// Ticks ahead a forecast covers.
+pub const FORECAST_TICKS: u64 = 5;
+
+/// Chance in percent that an unskilled forecaster calls a tick right.
+pub const FORECAST_BASE_ACCURACY_PCT: u32 = 50;
+
+/// Added chance in percent per level of forecasting skill.
+pub const FORECAST_SKILL_BONUS_PCT: u32 = 3;
+
+/// The highest chance in percent any forecaster calls a tick right.
+pub const FORECAST_MAX_ACCURACY_PCT: u32 = 95;
+
+/// Mixed into the seed for forecast rolls, so they are not the weather
+/// rolls themselves.
+const FORECAST_SALT: u64 = 0x6f72_6563_6173_7421;
+
+/// Every kind of weather, for picking a wrong forecast.
+const ALL_WEATHER: [Weather; 5] = [
+    Weather::Clear,
+    Weather::Rain,
+    Weather::Storm,
+    Weather::Drought,
+    Weather::Snow,
+];
+
+/// Return the chance in percent that an agent with `skill_level` in
+/// forecasting calls a tick's weather right.
+pub fn forecast_accuracy_pct(skill_level: u32) -> u32 {
+    FORECAST_BASE_ACCURACY_PCT
+        .saturating_add(skill_level.saturating_mul(FORECAST_SKILL_BONUS_PCT))
+        .min(FORECAST_MAX_ACCURACY_PCT)
+}
 
 /// Seasonal weather weights for probability-based generation.
 ///
 /// Each entry is `(weather_variant, weight)`. Weights are summed and a
 /// random value in `[0, total_weight)` selects the weather. The special
 /// `None` entry means "repeat previous weather".
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SeasonWeights {
     /// Weighted entries: `(Some(weather), weight)` or `(None, weight)` for repeat.
     entries: Vec<(Option<Weather>, u32)>,
@@ -96,8 +145,35 @@ impl SeasonWeights {
         Some(Weather::Clear)
     }
 
+    /// Return the weight of `weather`, or 0 if it has no entry.
+    pub(crate) fn weight_of(&self, weather: Weather) -> u32 {
+        self.entries
+            .iter()
+            .filter(|(entry, _)| *entry == Some(weather))
+            .map(|(_, weight)| *weight)
+            .fold(0, u32::saturating_add)
+    }
+
+    /// Move `pct` percent of the weight of `from` to `to`, rounded down.
+    /// The total weight is unchanged.
+    pub(crate) fn shift(&mut self, from: Weather, to: Weather, pct: u32) {
+        let moved = self.weight_of(from).saturating_mul(pct.min(100)).saturating_div(100);
+        if moved == 0 {
+            return;
+        }
+        for (entry, weight) in &mut self.entries {
+            if *entry == Some(from) {
+                *weight = weight.saturating_sub(moved);
+            }
+        }
+        match self.entries.iter_mut().find(|(entry, _)| *entry == Some(to)) {
+            Some((_, weight)) => *weight = weight.saturating_add(moved),
+            None => self.entries.push((Some(to), moved)),
+        }
+    }
+
     /// Return the total weight (sum of all entry weights).
-    fn total_weight(&self) -> u32 {
+    pub(crate) fn total_weight(&self) -> u32 {
         let mut total: u32 = 0;
         for &(_, weight) in &self.entries {
             total = total.saturating_add(weight);
@@ -117,6 +193,9 @@ pub struct WeatherSystem {
 
     /// The weather from the previous tick (for "repeat" rolls).
     previous_weather: Weather,
+
+    /// The long-term drift applied to the seasonal weights.
+    climate: Climate,
 }
 
 impl WeatherSystem {
@@ -127,9 +206,17 @@ impl WeatherSystem {
         Self {
             world_seed,
             previous_weather: Weather::Clear,
+            climate: Climate::new(0),
         }
     }
 
+    /// Drift the seasonal weights with `climate`.
+    #[must_use]
+    pub const fn with_climate(mut self, climate: Climate) -> Self {
+        self.climate = climate;
+        self
+    }
+
     /// Generate the weather for a given tick and season.
     ///
     /// This method is idempotent for the same `(tick, season)` pair when
@@ -139,7 +226,7 @@ impl WeatherSystem {
     /// Updates the internal `previous_weather` state for repeat rolls on
     /// subsequent ticks.
     pub fn generate(&mut self, tick: u64, season: Season) -> Weather {
-        let weights = SeasonWeights::for_season(season);
+        let weights = self.climate.season_weights(season, tick);
         let total = weights.total_weight();
 
         if total == 0 {
@@ -164,7 +251,7 @@ impl WeatherSystem {
     /// Peek at what the weather would be for a given tick and season
     /// without updating internal state.
     pub fn peek(&self, tick: u64, season: Season) -> Weather {
-        let weights = SeasonWeights::for_season(season);
+        let weights = self.climate.season_weights(season, tick);
         let total = weights.total_weight();
 
         if total == 0 {
@@ -192,6 +279,51 @@ impl WeatherSystem {
     pub const fn world_seed(&self) -> u64 {
         self.world_seed
     }
+
+    /// Return the climate drifting the seasonal weights.
+    pub const fn climate(&self) -> &Climate {
+        &self.climate
+    }
+
+    /// Forecast the weather for the `ticks` ticks after `tick`, as called
+    /// by a forecaster who is right `accuracy_pct` percent of the time.
+    ///
+    /// The true weather is found by running a copy of this system ahead,
+    /// so `tick` should be the tick last generated. Whether each tick is
+    /// called right is rolled from the seed and the tick, so a more
+    /// accurate forecaster is right wherever a less accurate one is; a
+    /// wrong call names some other weather.
+    pub fn forecast(
+        &self,
+        tick: u64,
+        ticks: u64,
+        accuracy_pct: u32,
+        season_at: impl Fn(u64) -> Season,
+    ) -> Vec<WeatherForecast> {
+        let mut ahead = self.clone();
+        (1..=ticks)
+            .filter_map(|offset| tick.checked_add(offset))
+            .map(|at| {
+                let actual = ahead.generate(at, season_at(at));
+                let roll = deterministic_random(self.world_seed ^ FORECAST_SALT, at);
+                let called_right =
+                    roll.checked_rem(100).is_some_and(|r| r < u64::from(accuracy_pct));
+                let weather = if called_right { actual } else { mistaken(actual, roll >> 8) };
+                WeatherForecast { tick: at, weather }
+            })
+            .collect()
+    }
+}
+
+/// Return a weather other than `actual`, chosen by `random`.
+fn mistaken(actual: Weather, random: u64) -> Weather {
+    let others: Vec<Weather> = ALL_WEATHER.into_iter().filter(|w| *w != actual).collect();
+    let count = u64::try_from(others.len()).unwrap_or(0);
+    random
+        .checked_rem(count)
+        .and_then(|i| usize::try_from(i).ok())
+        .and_then(|i| others.get(i).copied())
+        .unwrap_or(actual)
 }
 
 /// Deterministic pseudo-random number generator using `xorshift64`.
@@ -199,7 +331,7 @@ impl WeatherSystem {
 /// Combines the world seed and tick number to produce a unique random
 /// value for each `(seed, tick)` pair. The same inputs always produce
 /// the same output.
-const fn deterministic_random(world_seed: u64, tick: u64) -> u64 {
+pub(crate) const fn deterministic_random(world_seed: u64, tick: u64) -> u64 {
     // Combine seed and tick with a mixing step to avoid trivial patterns.
     // The constant 0x517cc1b727220a95 is a well-known mixing constant.
     let mut state = world_seed.wrapping_add(tick.wrapping_mul(0x517c_c1b7_2722_0a95));
@@ -268,6 +400,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn forecasts_are_as_right_as_the_forecaster() {
+        let mut system = WeatherSystem::new(42);
+        system.generate(10, Season::Autumn);
+        let actual: Vec<Weather> = {
+            let mut ahead = system.clone();
+            (11_u64..=40).map(|tick| ahead.generate(tick, Season::Autumn)).collect()
+        };
+
+        let perfect = system.forecast(10, 30, 100, |_| Season::Autumn);
+        assert_eq!(perfect.first().map(|f| f.tick), Some(11));
+        assert!(perfect.iter().map(|f| f.weather).eq(actual.iter().copied()));
+
+        let hopeless = system.forecast(10, 30, 0, |_| Season::Autumn);
+        assert!(hopeless.iter().zip(&actual).all(|(f, w)| f.weather != *w));
+
+        // A skilled forecaster is right wherever an unskilled one is.
+        let novice = system.forecast(10, 30, forecast_accuracy_pct(0), |_| Season::Autumn);
+        let expert = system.forecast(10, 30, forecast_accuracy_pct(20), |_| Season::Autumn);
+        for ((n, e), w) in novice.iter().zip(&expert).zip(&actual) {
+            assert!(n.weather != *w || e.weather == *w);
+        }
+        assert_eq!(forecast_accuracy_pct(20), FORECAST_MAX_ACCURACY_PCT);
+    }
+
     #[test]
     fn weather_system_is_reproducible() {
         let mut system_a = WeatherSystem::new(42);
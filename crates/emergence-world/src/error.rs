@@ -3,7 +3,7 @@
 //! All fallible operations in this crate return [`WorldError`] through the
 //! standard [`Result`] type alias.
 
-use emergence_types::{AgentId, LocationId, Resource, RouteId};
+use emergence_types::{AgentId, LocationId, Resource, RouteId, StructureId};
 
 /// Errors that can occur during world-graph operations.
 #[derive(Debug, thiserror::Error)]
@@ -61,6 +61,33 @@ pub enum WorldError {
         location: LocationId,
     },
 
+    /// The structure does not store this resource.
+    #[error("structure {structure} cannot store {resource:?}")]
+    NotStorable {
+        /// The structure.
+        structure: StructureId,
+        /// The refused resource.
+        resource: Resource,
+    },
+
+    /// The storage structure has no room for the deposit.
+    #[error("structure {structure} is full ({capacity})")]
+    StorageFull {
+        /// The full structure.
+        structure: StructureId,
+        /// Its storage capacity.
+        capacity: u32,
+    },
+
+    /// The storage structure holds less of a resource than was withdrawn.
+    #[error("structure {structure} does not hold enough {resource:?}")]
+    NotInStorage {
+        /// The structure.
+        structure: StructureId,
+        /// The missing resource.
+        resource: Resource,
+    },
+
     /// Arithmetic overflow during a checked operation.
     #[error("arithmetic overflow in world calculation")]
     ArithmeticOverflow,
@@ -72,4 +99,21 @@ pub enum WorldError {
     /// A duplicate route was inserted where uniqueness is required.
     #[error("duplicate route id: {0}")]
     DuplicateRoute(RouteId),
+
+    /// World generation parameters are out of range.
+    #[error("invalid world generation parameters: {0}")]
+    InvalidGenParams(&'static str),
+
+    /// A world file could not be read, written, or parsed.
+    #[error("world file error: {0}")]
+    WorldFile(String),
+
+    /// A world file is in a format version this build does not read.
+    #[error("unsupported world file version {found} (expected {supported})")]
+    UnsupportedWorldFileVersion {
+        /// The version in the file.
+        found: u32,
+        /// The version this build reads.
+        supported: u32,
+    },
 }
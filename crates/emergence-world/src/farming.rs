@@ -4,6 +4,21 @@
 //! crops that grow over time. This module tracks per-farm-plot growth state
 //! and provides helpers for planting and harvesting.
 //!
+//! Watered plots yield more: a plot on or next to a river or lake, or fed
+//! by an [`Irrigation`](emergence_types::StructureType::Irrigation)
+//! channel drawing on its location's water, yields a configurable
+//! multiple of the usual harvest (see [`irrigation_yield_pct`]), which a
+//! drought cuts back.
+//!
+//! Each plot's soil has a fertility that scales its harvest (see
+//! [`fertility_yield_pct`]). A harvest drains [`HARVEST_DEPLETION`] for
+//! every harvest in a row of the same crop, so a plot sown with the same
+//! seed again and again wears out ever faster while rotating seeds keeps
+//! the drain at its lightest. Fallow plots recover
+//! [`FALLOW_RECOVERY_PER_TICK`] a tick, and the `Fertilize` action works
+//! [`FERTILIZER_WASTE`] food waste into the soil for
+//! [`FERTILIZER_GAIN`]. Every harvest leaves [`HARVEST_WASTE`] behind.
+//!
 //! See `world-engine.md` section 7.1 (Advanced Actions) and section 5.2
 //! (`FarmPlot` structure).
 
@@ -11,7 +26,7 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use emergence_types::StructureId;
+use emergence_types::{Resource, StructureId, Weather};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -23,6 +38,31 @@ pub const DEFAULT_GROWTH_TICKS: u64 = 10;
 /// Base harvest yield in units of `FoodFarmed`.
 pub const BASE_HARVEST_YIELD: u32 = 5;
 
+/// Default percentage of the usual harvest a watered farm plot yields.
+pub const DEFAULT_IRRIGATION_YIELD_PCT: u32 = 150;
+
+/// Units of `Water` an irrigation channel draws from its location for
+/// each harvest it waters.
+pub const IRRIGATION_WATER_PER_HARVEST: u32 = 2;
+
+/// Fertility of untouched soil, and the most any soil can hold.
+pub const MAX_FERTILITY: u32 = 100;
+
+/// Fertility a harvest drains for each harvest in a row of the same crop.
+pub const HARVEST_DEPLETION: u32 = 10;
+
+/// Fertility a fallow plot recovers each tick.
+pub const FALLOW_RECOVERY_PER_TICK: u32 = 1;
+
+/// Units of `FoodWaste` the `Fertilize` action works into a plot.
+pub const FERTILIZER_WASTE: u32 = 3;
+
+/// Fertility the `Fertilize` action adds to a plot.
+pub const FERTILIZER_GAIN: u32 = 30;
+
+/// Units of `FoodWaste` left over from each harvest.
+pub const HARVEST_WASTE: u32 = 2;
+
 // ---------------------------------------------------------------------------
 // FarmCropState
 // ---------------------------------------------------------------------------
@@ -55,17 +95,76 @@ impl FarmCropState {
     }
 }
 
+// ---------------------------------------------------------------------------
+// SoilState
+// ---------------------------------------------------------------------------
+
+/// Soil fertility of a single farm plot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoilState {
+    /// Fertility as of `fallow_since`, or as of sowing while crops grow.
+    pub fertility: u32,
+    /// The tick the plot last fell fallow, `None` while crops grow.
+    pub fallow_since: Option<u64>,
+    /// The seed the growing crops were sown from.
+    pub sown_crop: Option<Resource>,
+    /// The seed of the last crop harvested.
+    pub last_crop: Option<Resource>,
+    /// Harvests in a row of `last_crop`.
+    pub repeat_harvests: u32,
+}
+
+impl Default for SoilState {
+    fn default() -> Self {
+        Self {
+            fertility: MAX_FERTILITY,
+            fallow_since: None,
+            sown_crop: None,
+            last_crop: None,
+            repeat_harvests: 0,
+        }
+    }
+}
+
+impl SoilState {
+    /// Return the fertility at `current_tick`, counting what a fallow plot
+    /// has recovered since it fell fallow.
+    pub fn fertility_at(&self, current_tick: u64) -> u32 {
+        let Some(since) = self.fallow_since else {
+            return self.fertility;
+        };
+        let rested = u32::try_from(current_tick.saturating_sub(since)).unwrap_or(u32::MAX);
+        self.fertility
+            .saturating_add(rested.saturating_mul(FALLOW_RECOVERY_PER_TICK))
+            .min(MAX_FERTILITY)
+    }
+
+    /// Fold the fertility recovered so far into `fertility`, restarting the
+    /// fallow count at `current_tick` if the plot is still fallow.
+    fn settle(&mut self, current_tick: u64) {
+        self.fertility = self.fertility_at(current_tick);
+        if self.fallow_since.is_some() {
+            self.fallow_since = Some(current_tick);
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FarmRegistry
 // ---------------------------------------------------------------------------
 
-/// Registry mapping farm plot structure IDs to their crop growth state.
+/// Registry mapping farm plot structure IDs to their crop growth state
+/// and soil.
 ///
-/// Farms without an entry in this registry have no crops planted.
+/// Farms without a crop entry in this registry have no crops planted;
+/// farms without a soil entry have untouched soil.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FarmRegistry {
     /// Maps structure ID of each farm plot to its crop state.
     crops: BTreeMap<StructureId, FarmCropState>,
+    /// Maps structure ID of each worked farm plot to its soil.
+    #[serde(default)]
+    soils: BTreeMap<StructureId, SoilState>,
 }
 
 impl FarmRegistry {
@@ -73,6 +172,7 @@ impl FarmRegistry {
     pub const fn new() -> Self {
         Self {
             crops: BTreeMap::new(),
+            soils: BTreeMap::new(),
         }
     }
 
@@ -124,9 +224,62 @@ impl FarmRegistry {
         self.crops.get(&farm_id)
     }
 
-    /// Remove crop state for a farm that was demolished or collapsed.
+    /// Remove crop and soil state for a farm that was demolished or
+    /// collapsed.
     pub fn remove_farm(&mut self, farm_id: StructureId) {
         self.crops.remove(&farm_id);
+        self.soils.remove(&farm_id);
+    }
+
+    /// Return the soil of a farm plot, untouched if it was never worked.
+    pub fn soil(&self, farm_id: StructureId) -> SoilState {
+        self.soils.get(&farm_id).copied().unwrap_or_default()
+    }
+
+    /// Return the fertility of a farm plot's soil at `current_tick`.
+    pub fn fertility_at(&self, farm_id: StructureId, current_tick: u64) -> u32 {
+        self.soil(farm_id).fertility_at(current_tick)
+    }
+
+    /// Record that a farm plot was sown from `crop` at `current_tick`,
+    /// ending any fallow.
+    pub fn sow(&mut self, farm_id: StructureId, crop: Resource, current_tick: u64) {
+        let soil = self.soils.entry(farm_id).or_default();
+        soil.settle(current_tick);
+        soil.fallow_since = None;
+        soil.sown_crop = Some(crop);
+    }
+
+    /// Drain a harvested plot's soil and leave it fallow from
+    /// `current_tick`.
+    ///
+    /// The drain is [`HARVEST_DEPLETION`] times the harvests in a row of
+    /// the crop just harvested. Returns the fertility left.
+    pub fn deplete(&mut self, farm_id: StructureId, current_tick: u64) -> u32 {
+        let soil = self.soils.entry(farm_id).or_default();
+        soil.settle(current_tick);
+        let crop = soil.sown_crop.take();
+        soil.repeat_harvests = if crop.is_some() && crop == soil.last_crop {
+            soil.repeat_harvests.saturating_add(1)
+        } else {
+            1
+        };
+        soil.last_crop = crop;
+        let drain = HARVEST_DEPLETION.saturating_mul(soil.repeat_harvests);
+        soil.fertility = soil.fertility.saturating_sub(drain);
+        soil.fallow_since = Some(current_tick);
+        soil.fertility
+    }
+
+    /// Work fertilizer into a plot's soil at `current_tick`, adding
+    /// [`FERTILIZER_GAIN`] up to [`MAX_FERTILITY`].
+    ///
+    /// Returns the fertility after fertilizing.
+    pub fn fertilize(&mut self, farm_id: StructureId, current_tick: u64) -> u32 {
+        let soil = self.soils.entry(farm_id).or_default();
+        soil.settle(current_tick);
+        soil.fertility = soil.fertility.saturating_add(FERTILIZER_GAIN).min(MAX_FERTILITY);
+        soil.fertility
     }
 
     /// Return the number of farms with active crops.
@@ -139,14 +292,42 @@ impl FarmRegistry {
 // Harvest yield calculation
 // ---------------------------------------------------------------------------
 
-/// Compute the harvest yield modified by the agent's farming skill level.
+/// Return the percentage of the usual harvest a farm plot yields in
+/// `weather`.
+///
+/// A dry plot yields 100. A watered one yields `irrigated_pct`, except in
+/// a drought, when the water runs low and only half its gain over a dry
+/// plot remains.
+pub const fn irrigation_yield_pct(watered: bool, weather: Weather, irrigated_pct: u32) -> u32 {
+    if !watered {
+        return 100;
+    }
+    match weather {
+        Weather::Drought => 100_u32.saturating_add(irrigated_pct.saturating_sub(100) / 2),
+        Weather::Clear | Weather::Rain | Weather::Storm | Weather::Snow => irrigated_pct,
+    }
+}
+
+/// Return the percentage of the usual harvest soil of `fertility` yields.
+///
+/// Untouched soil yields 100; worn-out soil yields half that.
+pub fn fertility_yield_pct(fertility: u32) -> u32 {
+    50_u32.saturating_add(fertility.min(MAX_FERTILITY) / 2)
+}
+
+/// Compute the harvest yield modified by the agent's farming skill level
+/// and the plot's watering.
 ///
-/// Formula: `BASE_HARVEST_YIELD + skill_level / 2`
+/// Formula: `(BASE_HARVEST_YIELD + skill_level / 2) * irrigation_pct / 100`,
+/// where `irrigation_pct` comes from [`irrigation_yield_pct`].
 ///
 /// Returns `None` on arithmetic overflow.
-pub fn harvest_yield(skill_level: u32) -> Option<u32> {
+pub fn harvest_yield(skill_level: u32, irrigation_pct: u32) -> Option<u32> {
     let bonus = skill_level.checked_div(2)?;
-    BASE_HARVEST_YIELD.checked_add(bonus)
+    BASE_HARVEST_YIELD
+        .checked_add(bonus)?
+        .checked_mul(irrigation_pct)?
+        .checked_div(100)
 }
 
 // ---------------------------------------------------------------------------
@@ -216,18 +397,68 @@ mod tests {
 
     #[test]
     fn harvest_yield_no_skill() {
-        assert_eq!(harvest_yield(0), Some(5));
+        assert_eq!(harvest_yield(0, 100), Some(5));
     }
 
     #[test]
     fn harvest_yield_with_skill() {
-        assert_eq!(harvest_yield(4), Some(7)); // 5 + 4/2 = 7
-        assert_eq!(harvest_yield(10), Some(10)); // 5 + 10/2 = 10
+        assert_eq!(harvest_yield(4, 100), Some(7)); // 5 + 4/2 = 7
+        assert_eq!(harvest_yield(10, 100), Some(10)); // 5 + 10/2 = 10
     }
 
     #[test]
     fn harvest_yield_odd_skill() {
-        assert_eq!(harvest_yield(3), Some(6)); // 5 + 3/2 = 5 + 1 = 6
+        assert_eq!(harvest_yield(3, 100), Some(6)); // 5 + 3/2 = 5 + 1 = 6
+    }
+
+    #[test]
+    fn watered_plots_yield_more_unless_in_drought() {
+        let pct = DEFAULT_IRRIGATION_YIELD_PCT;
+        assert_eq!(irrigation_yield_pct(false, Weather::Rain, pct), 100);
+        assert_eq!(irrigation_yield_pct(true, Weather::Rain, pct), 150);
+        assert_eq!(irrigation_yield_pct(true, Weather::Drought, pct), 125);
+        assert_eq!(irrigation_yield_pct(false, Weather::Drought, pct), 100);
+
+        assert_eq!(harvest_yield(4, 150), Some(10)); // 7 * 1.5 = 10.5
+        assert_eq!(harvest_yield(4, 125), Some(8)); // 7 * 1.25 = 8.75
+    }
+
+    #[test]
+    fn repeating_a_crop_drains_the_soil_faster_than_rotating() {
+        let mut same = FarmRegistry::new();
+        let mut rotated = FarmRegistry::new();
+        let farm_id = StructureId::new();
+        let seeds = [Resource::FoodBerry, Resource::FoodRoot];
+
+        for (tick, seed) in (0..4).zip(seeds.iter().cycle()) {
+            same.sow(farm_id, Resource::FoodBerry, tick);
+            same.deplete(farm_id, tick);
+            rotated.sow(farm_id, *seed, tick);
+            rotated.deplete(farm_id, tick);
+        }
+
+        // 10 + 20 + 30 + 40 drained, less 3 ticks of fallow recovery
+        assert_eq!(same.fertility_at(farm_id, 3), 3);
+        assert_eq!(rotated.fertility_at(farm_id, 3), 63);
+        assert!(fertility_yield_pct(3) < fertility_yield_pct(63));
+    }
+
+    #[test]
+    fn fallow_and_fertilized_soil_recovers() {
+        let mut reg = FarmRegistry::new();
+        let farm_id = StructureId::new();
+        assert_eq!(reg.fertility_at(farm_id, 0), MAX_FERTILITY);
+        assert_eq!(fertility_yield_pct(MAX_FERTILITY), 100);
+
+        reg.sow(farm_id, Resource::FoodBerry, 0);
+        assert_eq!(reg.deplete(farm_id, 10), 90);
+        assert_eq!(reg.fertility_at(farm_id, 15), 95);
+        assert_eq!(reg.fertility_at(farm_id, 500), MAX_FERTILITY);
+
+        reg.sow(farm_id, Resource::FoodBerry, 12);
+        assert_eq!(reg.fertility_at(farm_id, 500), 92); // no rest while growing
+        assert_eq!(reg.fertilize(farm_id, 20), MAX_FERTILITY);
+        assert_eq!(fertility_yield_pct(0), 50);
     }
 
     #[test]
@@ -0,0 +1,339 @@
+//! Wildlife populations, predation, and hunting pressure.
+//!
+//! Each natural location hosts a herd of game animals and the predators
+//! that feed on it. Once per tick, during World Wake,
+//! [`FaunaRegistry::tick`] advances every population:
+//!
+//! - **Growth** -- game breeds logistically towards the location's carrying
+//!   capacity, [`GAME_GROWTH_PCT`] per tick at low density.
+//! - **Predation** -- predators catch game in proportion to how dense it
+//!   is, breed on what they catch, and starve when they catch nothing.
+//! - **Collapse** -- a herd hunted below [`COLLAPSE_PCT`] of its capacity
+//!   collapses and stops breeding for [`COLLAPSE_RECOVERY_TICKS`] ticks. A
+//!   herd hunted out entirely is restocked by [`RESTOCK_GAME`] animals
+//!   wandering in once that time has passed.
+//!
+//! Agents take game with the `Hunt` action. [`FaunaRegistry::hunt`] records
+//! each kill, so the next tick can tell overhunting from predation:
+//! predators alone never collapse a herd.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::LocationId;
+
+use crate::metrics;
+use crate::world_map::WorldMap;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Carrying capacity of game at a natural location.
+pub const DEFAULT_GAME_CAPACITY: u32 = 60;
+
+/// Game a full herd supports per predator.
+pub const GAME_PER_PREDATOR: u32 = 12;
+
+/// Percentage growth of game per tick at low density.
+pub const GAME_GROWTH_PCT: u32 = 25;
+
+/// Percentage of capacity below which a hunted herd collapses.
+pub const COLLAPSE_PCT: u32 = 10;
+
+/// Ticks a collapsed herd goes without breeding.
+pub const COLLAPSE_RECOVERY_TICKS: u64 = 20;
+
+/// Animals that wander into a hunted-out location once it recovers.
+pub const RESTOCK_GAME: u32 = 4;
+
+/// Units of `FoodMeat` yielded by each animal hunted.
+pub const MEAT_PER_ANIMAL: u32 = 3;
+
+/// Units of `Hide` yielded by each animal hunted.
+pub const HIDE_PER_ANIMAL: u32 = 1;
+
+// ---------------------------------------------------------------------------
+// Population
+// ---------------------------------------------------------------------------
+
+/// Game and predators at a single location.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Population {
+    /// Game animals present.
+    pub game: u32,
+    /// Predators present.
+    pub predators: u32,
+    /// The most game the location can support.
+    pub capacity: u32,
+    /// Game hunted by agents since the last tick.
+    pub hunted: u32,
+    /// The tick a collapsed herd starts breeding again, if it has collapsed.
+    pub collapsed_until: Option<u64>,
+}
+
+/// A change in a population's standing reported by [`FaunaRegistry::tick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FaunaChange {
+    /// The herd was hunted below [`COLLAPSE_PCT`] of its capacity.
+    Collapsed,
+    /// A collapsed herd started breeding again.
+    Recovered,
+}
+
+impl Population {
+    /// Create a full herd at `capacity`, with the predators it supports.
+    pub const fn new(capacity: u32) -> Self {
+        Self {
+            game: capacity,
+            predators: capacity.saturating_div(GAME_PER_PREDATOR),
+            capacity,
+            hunted: 0,
+            collapsed_until: None,
+        }
+    }
+
+    /// Return whether the herd has collapsed and is not yet breeding.
+    pub const fn is_collapsed(&self) -> bool {
+        self.collapsed_until.is_some()
+    }
+
+    /// Advance the population by one tick.
+    fn step(&mut self, tick: u64) -> Option<FaunaChange> {
+        let recovered = self.collapsed_until.is_some_and(|until| tick >= until);
+        if recovered {
+            self.collapsed_until = None;
+            if self.game == 0 {
+                self.game = RESTOCK_GAME.min(self.capacity);
+            }
+        }
+
+        let kills = self.predation();
+        let births = if self.is_collapsed() { 0 } else { self.growth() };
+        self.game = self.game.saturating_sub(kills).saturating_add(births).min(self.capacity);
+
+        // Predators breed on one in two kills and lose one in five of their
+        // number when they catch nothing.
+        let max_predators = self.capacity.saturating_div(GAME_PER_PREDATOR);
+        self.predators = if kills == 0 {
+            let starved = self.predators.saturating_div(5).max(1);
+            self.predators.saturating_sub(starved)
+        } else {
+            self.predators.saturating_add(kills.saturating_div(2)).min(max_predators)
+        };
+
+        let threshold = scale(self.capacity, COLLAPSE_PCT, 100);
+        let collapsed = self.hunted > 0 && !self.is_collapsed() && self.game < threshold;
+        self.hunted = 0;
+        if collapsed {
+            self.collapsed_until = Some(tick.saturating_add(COLLAPSE_RECOVERY_TICKS));
+            Some(FaunaChange::Collapsed)
+        } else if recovered {
+            Some(FaunaChange::Recovered)
+        } else {
+            None
+        }
+    }
+
+    /// Logistic growth: `game * (capacity - game) / capacity` at
+    /// [`GAME_GROWTH_PCT`], at least one animal while any pair can breed.
+    fn growth(&self) -> u32 {
+        if self.game < 2 || self.game >= self.capacity {
+            return 0;
+        }
+        let room = self.capacity.saturating_sub(self.game);
+        let pct = scale(room, GAME_GROWTH_PCT, 100);
+        scale(self.game, pct, self.capacity).max(1)
+    }
+
+    /// Game caught by predators: half a kill per predator on a full herd,
+    /// falling with the herd's density, rounded up.
+    fn predation(&self) -> u32 {
+        let encounters = u64::from(self.predators).saturating_mul(u64::from(self.game));
+        let per_kill = u64::from(self.capacity).saturating_mul(2).max(1);
+        let kills = encounters.saturating_add(per_kill.saturating_sub(1)).checked_div(per_kill);
+        let kills = kills.unwrap_or(0);
+        u32::try_from(kills).unwrap_or(u32::MAX).min(self.game)
+    }
+}
+
+/// `value * numerator / denominator`, without overflow; zero if
+/// `denominator` is zero.
+fn scale(value: u32, numerator: u32, denominator: u32) -> u32 {
+    let scaled = u64::from(value)
+        .saturating_mul(u64::from(numerator))
+        .checked_div(u64::from(denominator))
+        .unwrap_or(0);
+    u32::try_from(scaled).unwrap_or(u32::MAX)
+}
+
+// ---------------------------------------------------------------------------
+// FaunaRegistry
+// ---------------------------------------------------------------------------
+
+/// The wildlife of every location that has any.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaunaRegistry {
+    populations: BTreeMap<LocationId, Population>,
+}
+
+impl FaunaRegistry {
+    /// Create a registry with no wildlife.
+    pub const fn new() -> Self {
+        Self {
+            populations: BTreeMap::new(),
+        }
+    }
+
+    /// Stock every natural location of `map` with a full herd at
+    /// [`DEFAULT_GAME_CAPACITY`].
+    pub fn from_map(map: &WorldMap) -> Self {
+        let populations = map
+            .locations()
+            .filter(|(_, state)| state.location.location_type == "natural")
+            .map(|(id, _)| (*id, Population::new(DEFAULT_GAME_CAPACITY)))
+            .collect();
+        Self { populations }
+    }
+
+    /// Set the wildlife of `location`, replacing any it had.
+    pub fn insert(&mut self, location: LocationId, population: Population) {
+        self.populations.insert(location, population);
+    }
+
+    /// Return the wildlife of `location`, if it has any.
+    pub fn get(&self, location: LocationId) -> Option<&Population> {
+        self.populations.get(&location)
+    }
+
+    /// Return the game available to hunt at `location`.
+    pub fn game_at(&self, location: LocationId) -> u32 {
+        self.populations.get(&location).map_or(0, |p| p.game)
+    }
+
+    /// Hunt up to `wanted` game at `location`.
+    ///
+    /// Returns the number of animals taken, which is less than `wanted`
+    /// when the herd is too small.
+    pub fn hunt(&mut self, location: LocationId, wanted: u32) -> u32 {
+        let Some(population) = self.populations.get_mut(&location) else {
+            return 0;
+        };
+        let taken = wanted.min(population.game);
+        population.game = population.game.saturating_sub(taken);
+        population.hunted = population.hunted.saturating_add(taken);
+        taken
+    }
+
+    /// Advance every population by one tick: growth, predation, and
+    /// collapse or recovery of hunted herds.
+    ///
+    /// Returns the locations whose herd collapsed or recovered this tick.
+    pub fn tick(&mut self, tick: u64) -> Vec<(LocationId, FaunaChange)> {
+        let mut changes = Vec::new();
+        for (location, population) in &mut self.populations {
+            if let Some(change) = population.step(tick) {
+                if change == FaunaChange::Collapsed {
+                    metrics::FAUNA_COLLAPSES.increment(1);
+                }
+                changes.push((*location, change));
+            }
+        }
+        changes
+    }
+
+    /// Return the total game across all locations.
+    pub fn total_game(&self) -> u64 {
+        self.populations.values().map(|p| u64::from(p.game)).sum()
+    }
+
+    /// Return the number of locations whose herd has collapsed.
+    pub fn collapsed_count(&self) -> usize {
+        self.populations.values().filter(|p| p.is_collapsed()).count()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn registry_with(population: Population) -> (FaunaRegistry, LocationId) {
+        let location = LocationId::new();
+        let mut registry = FaunaRegistry::new();
+        registry.insert(location, population);
+        (registry, location)
+    }
+
+    #[test]
+    fn game_regrows_towards_capacity_without_predators() {
+        let mut population = Population::new(DEFAULT_GAME_CAPACITY);
+        population.game = 10;
+        population.predators = 0;
+        let (mut registry, location) = registry_with(population);
+
+        let mut previous = registry.game_at(location);
+        for tick in 1..=40 {
+            registry.tick(tick);
+            let game = registry.game_at(location);
+            assert!(game >= previous);
+            previous = game;
+        }
+        assert!(previous > 50);
+        assert!(previous <= DEFAULT_GAME_CAPACITY);
+    }
+
+    #[test]
+    fn predators_thin_game_and_starve_without_it() {
+        let mut population = Population::new(DEFAULT_GAME_CAPACITY);
+        population.predators = 5;
+        let (mut registry, location) = registry_with(population);
+        registry.tick(1);
+        assert!(registry.game_at(location) < DEFAULT_GAME_CAPACITY);
+
+        let mut empty = Population::new(DEFAULT_GAME_CAPACITY);
+        empty.game = 0;
+        let (mut registry, location) = registry_with(empty);
+        for tick in 1..=20 {
+            registry.tick(tick);
+        }
+        assert_eq!(registry.get(location).unwrap().predators, 0);
+        assert_eq!(registry.collapsed_count(), 0);
+    }
+
+    #[test]
+    fn overhunting_collapses_the_herd_until_it_recovers() {
+        let (mut registry, location) = registry_with(Population::new(DEFAULT_GAME_CAPACITY));
+        assert_eq!(registry.hunt(location, 100), DEFAULT_GAME_CAPACITY);
+        assert_eq!(registry.hunt(location, 1), 0);
+
+        let changes = registry.tick(1);
+        assert_eq!(changes, vec![(location, FaunaChange::Collapsed)]);
+        assert_eq!(registry.collapsed_count(), 1);
+
+        for tick in 2..=COLLAPSE_RECOVERY_TICKS {
+            assert!(registry.tick(tick).is_empty());
+            assert_eq!(registry.game_at(location), 0);
+        }
+        let changes = registry.tick(COLLAPSE_RECOVERY_TICKS.saturating_add(1));
+        assert_eq!(changes, vec![(location, FaunaChange::Recovered)]);
+        assert!(registry.game_at(location) >= RESTOCK_GAME);
+        assert_eq!(registry.collapsed_count(), 0);
+    }
+
+    #[test]
+    fn from_map_stocks_natural_locations() {
+        let (map, _) = crate::create_starting_world().unwrap();
+        let registry = FaunaRegistry::from_map(&map);
+        let natural = map
+            .locations()
+            .filter(|(_, state)| state.location.location_type == "natural")
+            .count();
+        assert!(natural > 0);
+        assert_eq!(
+            registry.total_game(),
+            u64::from(DEFAULT_GAME_CAPACITY).saturating_mul(natural as u64)
+        );
+    }
+}
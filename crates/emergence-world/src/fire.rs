@@ -0,0 +1,378 @@
+//! Fires that spread between the structures at a location.
+//!
+//! A fire burns at a location with an intensity from 1 to
+//! [`MAX_INTENSITY`]. It starts in one of two ways:
+//!
+//! - **Unattended campfires** -- in dry or windy weather (drought or
+//!   storm), a standing `Campfire` at a location with no one present
+//!   catches with a [`IGNITION_CHANCE_PCT`] chance each tick.
+//! - **Wildfires** -- a wildfire disaster leaves a fire burning at the
+//!   location it struck, as intense as the disaster was severe.
+//!
+//! Each tick, during World Wake, [`FireSystem::burn`] spreads every fire
+//! to the wooden structures around it (see [`is_flammable`]): each loses
+//! [`BURN_DAMAGE_PCT`] of its maximum durability per level of intensity,
+//! and collapses at zero. A fire with fuel grows a level in dry or windy
+//! weather; rain or snow dampens it by [`DAMPENING`] levels; one with
+//! nothing left to burn dies down a level. A fire at zero intensity is out.
+//!
+//! Agents fight fires with the `Extinguish` action, which pours
+//! [`WATER_PER_SUPPRESSION`] water on the flames and lowers the intensity
+//! by [`SUPPRESSION_PER_ACTION`] (see [`FireSystem::suppress`]).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use emergence_types::{
+    LocationId, Resource, Structure, StructureBurnedDetails, StructureType, Weather,
+};
+
+use crate::environment::deterministic_random;
+use crate::structure::{apply_disaster_damage, blueprint};
+use crate::world_map::WorldMap;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// The most intense a fire burns.
+pub const MAX_INTENSITY: u32 = 5;
+
+/// Chance in percent that an unattended campfire catches in dry or windy
+/// weather each tick.
+pub const IGNITION_CHANCE_PCT: u32 = 20;
+
+/// Percentage of a structure's maximum durability burned per tick, per
+/// level of intensity.
+pub const BURN_DAMAGE_PCT: u32 = 5;
+
+/// Levels of intensity rain or snow takes off a fire each tick.
+pub const DAMPENING: u32 = 2;
+
+/// Levels of intensity each `Extinguish` action takes off a fire.
+pub const SUPPRESSION_PER_ACTION: u32 = 2;
+
+/// Units of water each `Extinguish` action pours on the flames.
+pub const WATER_PER_SUPPRESSION: u32 = 2;
+
+/// Salt mixed into the world seed so ignition rolls are independent of the
+/// weather and disaster rolls made from the same seed.
+const FIRE_SEED_SALT: u64 = 0x6669_7265_7370_7264;
+
+// ---------------------------------------------------------------------------
+// Flammability
+// ---------------------------------------------------------------------------
+
+/// Return whether fire spreads to structures of `structure_type`: those
+/// built chiefly of wood, except the campfires and hearths made to hold a
+/// fire.
+pub fn is_flammable(structure_type: StructureType) -> bool {
+    if matches!(structure_type, StructureType::Campfire | StructureType::Hearth) {
+        return false;
+    }
+    let costs = blueprint(structure_type).material_costs;
+    let wood = costs.get(&Resource::Wood).copied().unwrap_or(0);
+    wood > 0 && costs.values().all(|qty| *qty <= wood)
+}
+
+/// Return whether `weather` fans the flames: a drought dries the wood and a
+/// storm's wind carries the sparks.
+pub const fn fans_flames(weather: Weather) -> bool {
+    matches!(weather, Weather::Drought | Weather::Storm)
+}
+
+/// Return whether `weather` dampens the flames.
+pub const fn dampens_flames(weather: Weather) -> bool {
+    matches!(weather, Weather::Rain | Weather::Snow)
+}
+
+// ---------------------------------------------------------------------------
+// FireSystem
+// ---------------------------------------------------------------------------
+
+/// A fire burning at a location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fire {
+    /// How fiercely it burns, from 1 to [`MAX_INTENSITY`].
+    pub intensity: u32,
+    /// The tick it started.
+    pub started_at_tick: u64,
+}
+
+/// Seeded ignition rolls and the fires burning across the world.
+#[derive(Debug, Clone, Default)]
+pub struct FireSystem {
+    /// World seed for deterministic rolls.
+    world_seed: u64,
+    /// The fire at each burning location.
+    fires: BTreeMap<LocationId, Fire>,
+}
+
+impl FireSystem {
+    /// Create a fire system for the given world seed, with nothing burning.
+    pub const fn new(world_seed: u64) -> Self {
+        Self {
+            world_seed,
+            fires: BTreeMap::new(),
+        }
+    }
+
+    /// Start a fire of `intensity` at `location`, or fan the one already
+    /// burning there to at least that intensity.
+    pub fn ignite(&mut self, location: LocationId, intensity: u32, tick: u64) {
+        let intensity = intensity.clamp(1, MAX_INTENSITY);
+        self.fires
+            .entry(location)
+            .and_modify(|fire| fire.intensity = fire.intensity.max(intensity))
+            .or_insert(Fire {
+                intensity,
+                started_at_tick: tick,
+            });
+    }
+
+    /// Roll for every standing campfire left unattended in dry or windy
+    /// `weather` to catch at `tick`, igniting a fire at its location.
+    ///
+    /// A campfire is unattended when no agent is at its location on `map`.
+    /// Returns the locations that caught fire.
+    pub fn ignite_unattended_campfires(
+        &mut self,
+        tick: u64,
+        weather: Weather,
+        map: &WorldMap,
+        structures: &[Structure],
+    ) -> Vec<LocationId> {
+        if !fans_flames(weather) {
+            return Vec::new();
+        }
+        let mut ignited = Vec::new();
+        let campfires = structures.iter().filter(|s| {
+            s.structure_type == StructureType::Campfire && s.destroyed_at_tick.is_none()
+        });
+        for campfire in campfires {
+            let location = campfire.location_id;
+            let attended = map
+                .get_location(location)
+                .is_none_or(|l| !l.occupants.is_empty());
+            if attended || self.fires.contains_key(&location) {
+                continue;
+            }
+            let (high, low) = campfire.id.into_inner().as_u64_pair();
+            let seed = self.world_seed ^ FIRE_SEED_SALT ^ high ^ low;
+            let roll = deterministic_random(seed, tick).checked_rem(100).unwrap_or(0);
+            if roll < u64::from(IGNITION_CHANCE_PCT) {
+                self.ignite(location, 1, tick);
+                ignited.push(location);
+            }
+        }
+        ignited
+    }
+
+    /// Burn every fire for one tick in `weather`: damage the flammable
+    /// structures at each burning location, then grow, dampen, or die
+    /// down each fire.
+    ///
+    /// `structures` may include structures elsewhere; only standing ones
+    /// at a burning location are affected. Collapsed structures are marked
+    /// destroyed at `tick`. Returns one record per structure damaged.
+    pub fn burn(
+        &mut self,
+        tick: u64,
+        weather: Weather,
+        structures: &mut [Structure],
+    ) -> Vec<StructureBurnedDetails> {
+        let mut burned = Vec::new();
+        for (location, fire) in &mut self.fires {
+            let damage_pct = BURN_DAMAGE_PCT.saturating_mul(fire.intensity);
+            let fuel = structures.iter_mut().filter(|s| {
+                s.location_id == *location
+                    && s.destroyed_at_tick.is_none()
+                    && is_flammable(s.structure_type)
+            });
+            let mut fueled = false;
+            for structure in fuel {
+                fueled = true;
+                let before = structure.durability;
+                let destroyed = apply_disaster_damage(structure, damage_pct);
+                if destroyed {
+                    structure.destroyed_at_tick = Some(tick);
+                }
+                burned.push(StructureBurnedDetails {
+                    structure_id: structure.id,
+                    structure_type: structure.structure_type,
+                    location_id: *location,
+                    damage: before.saturating_sub(structure.durability),
+                    durability: structure.durability,
+                    destroyed,
+                    fire_intensity: fire.intensity,
+                });
+            }
+
+            fire.intensity = if dampens_flames(weather) {
+                fire.intensity.saturating_sub(DAMPENING)
+            } else if !fueled {
+                fire.intensity.saturating_sub(1)
+            } else if fans_flames(weather) {
+                fire.intensity.saturating_add(1).min(MAX_INTENSITY)
+            } else {
+                fire.intensity
+            };
+        }
+        self.fires.retain(|_, fire| fire.intensity > 0);
+        burned
+    }
+
+    /// Lower the fire at `location` by `amount` levels of intensity,
+    /// putting it out at zero.
+    ///
+    /// Returns the intensity left, zero if nothing burns there.
+    pub fn suppress(&mut self, location: LocationId, amount: u32) -> u32 {
+        let Some(fire) = self.fires.get_mut(&location) else {
+            return 0;
+        };
+        fire.intensity = fire.intensity.saturating_sub(amount);
+        let left = fire.intensity;
+        if left == 0 {
+            self.fires.remove(&location);
+        }
+        left
+    }
+
+    /// Return the intensity of the fire at `location`, zero if none burns.
+    pub fn intensity_at(&self, location: LocationId) -> u32 {
+        self.fires.get(&location).map_or(0, |fire| fire.intensity)
+    }
+
+    /// Return the fire at `location`, if one burns.
+    pub fn get(&self, location: LocationId) -> Option<&Fire> {
+        self.fires.get(&location)
+    }
+
+    /// Return the number of locations on fire.
+    pub fn burning_count(&self) -> usize {
+        self.fires.len()
+    }
+
+    /// Return the locations on fire.
+    pub fn burning_locations(&self) -> BTreeSet<LocationId> {
+        self.fires.keys().copied().collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use emergence_types::{AgentId, StructureId};
+
+    use super::*;
+    use crate::create_starting_world;
+
+    fn make_structure(structure_type: StructureType, location_id: LocationId) -> Structure {
+        let bp = blueprint(structure_type);
+        Structure {
+            id: StructureId::new(),
+            structure_type,
+            subtype: None,
+            location_id,
+            builder: AgentId::new(),
+            owner: None,
+            built_at_tick: 0,
+            destroyed_at_tick: None,
+            materials_used: bp.material_costs,
+            durability: bp.max_durability,
+            max_durability: bp.max_durability,
+            decay_per_tick: bp.decay_per_tick,
+            capacity: bp.capacity,
+            occupants: BTreeSet::new(),
+            access_list: None,
+            properties: bp.properties,
+            inventory: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn wooden_structures_burn_but_hearths_do_not() {
+        assert!(is_flammable(StructureType::BasicHut));
+        assert!(is_flammable(StructureType::Palisade));
+        assert!(!is_flammable(StructureType::Campfire));
+        assert!(!is_flammable(StructureType::Hearth));
+        assert!(!is_flammable(StructureType::Wall));
+    }
+
+    #[test]
+    fn fire_spreads_to_co_located_wooden_structures_and_dies_out() {
+        let location = LocationId::new();
+        let mut structures = vec![
+            make_structure(StructureType::BasicHut, location),
+            make_structure(StructureType::Campfire, location),
+            make_structure(StructureType::BasicHut, LocationId::new()),
+        ];
+        let mut fires = FireSystem::new(7);
+        fires.ignite(location, 2, 0);
+
+        let burned = fires.burn(1, Weather::Drought, &mut structures);
+        assert_eq!(burned.len(), 1);
+        let first = burned.first().unwrap();
+        assert_eq!(first.structure_id, structures.first().unwrap().id);
+        assert_eq!(first.fire_intensity, 2);
+        assert!(first.damage > 0);
+        assert_eq!(fires.intensity_at(location), 3);
+
+        // Burn until the hut collapses, then the fire starves.
+        let mut tick = 2;
+        while structures.first().unwrap().destroyed_at_tick.is_none() {
+            fires.burn(tick, Weather::Drought, &mut structures);
+            tick = tick.saturating_add(1);
+        }
+        assert_eq!(structures.first().unwrap().durability, 0);
+        let elsewhere = structures.get(2).unwrap();
+        assert_eq!(elsewhere.durability, elsewhere.max_durability);
+        for tick in tick..tick.saturating_add(u64::from(MAX_INTENSITY)) {
+            assert!(fires.burn(tick, Weather::Drought, &mut structures).is_empty());
+        }
+        assert_eq!(fires.burning_count(), 0);
+    }
+
+    #[test]
+    fn rain_and_suppression_put_fires_out() {
+        let location = LocationId::new();
+        let mut fires = FireSystem::new(7);
+        fires.ignite(location, MAX_INTENSITY, 0);
+        fires.burn(1, Weather::Rain, &mut []);
+        assert_eq!(fires.intensity_at(location), MAX_INTENSITY.saturating_sub(DAMPENING));
+
+        assert_eq!(fires.suppress(location, SUPPRESSION_PER_ACTION), 1);
+        assert_eq!(fires.suppress(location, SUPPRESSION_PER_ACTION), 0);
+        assert!(fires.get(location).is_none());
+        assert_eq!(fires.suppress(location, 1), 0);
+    }
+
+    #[test]
+    fn only_unattended_campfires_catch_and_only_in_dry_or_windy_weather() {
+        let (mut map, ids) = create_starting_world().unwrap();
+        let campfires: Vec<Structure> = (0..50)
+            .map(|_| make_structure(StructureType::Campfire, ids.riverbank))
+            .collect();
+        let mut fires = FireSystem::new(7);
+        for tick in 0..20 {
+            assert!(
+                fires
+                    .ignite_unattended_campfires(tick, Weather::Clear, &map, &campfires)
+                    .is_empty()
+            );
+        }
+
+        map.get_location_mut(ids.riverbank).unwrap().occupants.insert(AgentId::new());
+        assert!(
+            fires
+                .ignite_unattended_campfires(1, Weather::Drought, &map, &campfires)
+                .is_empty()
+        );
+
+        map.get_location_mut(ids.riverbank).unwrap().occupants.clear();
+        let ignited = fires.ignite_unattended_campfires(1, Weather::Storm, &map, &campfires);
+        assert_eq!(ignited, vec![ids.riverbank]);
+        assert_eq!(fires.intensity_at(ids.riverbank), 1);
+    }
+}
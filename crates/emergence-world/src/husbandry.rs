@@ -0,0 +1,190 @@
+//! Animal husbandry: herds of livestock kept as capital.
+//!
+//! An agent's herd is the [`Resource::Livestock`] it holds, so herds are
+//! traded, stolen, and left behind at death like any other holding.
+//! Animals are first corralled alive by hunting where a
+//! [`Pasture`](emergence_types::StructureType::Pasture) stands, and the
+//! `Slaughter` action turns a head back into meat and hide.
+//!
+//! Every [`BREEDING_INTERVAL_TICKS`] ticks, during World Wake, each herd
+//! steps once (see [`Herd::step`]):
+//!
+//! - **Breeding** -- outside winter, a herd of at least
+//!   [`MIN_BREEDING_HEAD`] grows by [`BREEDING_PCT`], up to the capacity
+//!   of its owner's pastures plus [`OPEN_RANGE_CAPACITY`].
+//! - **Winter feeding** -- in winter, each head eats [`FODDER_PER_HEAD`]
+//!   of its owner's fodder (see [`FODDER`]). Half of the head left unfed,
+//!   rounded up, starve.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::{Resource, Season};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Ticks between herd steps.
+pub const BREEDING_INTERVAL_TICKS: u64 = 10;
+
+/// Smallest herd that breeds.
+pub const MIN_BREEDING_HEAD: u32 = 2;
+
+/// Percentage a herd grows by each step outside winter, at least one head.
+pub const BREEDING_PCT: u32 = 20;
+
+/// Head each standing pasture of the owner's holds.
+pub const PASTURE_CAPACITY: u32 = 20;
+
+/// Head a herd can reach with no pasture at all.
+pub const OPEN_RANGE_CAPACITY: u32 = 4;
+
+/// Units of fodder each head eats each step in winter.
+pub const FODDER_PER_HEAD: u32 = 1;
+
+/// The resources a herd eats in winter, in the order they are used up.
+pub const FODDER: [Resource; 3] = [Resource::FoodWaste, Resource::FoodFarmed, Resource::FoodRoot];
+
+// ---------------------------------------------------------------------------
+// Herd
+// ---------------------------------------------------------------------------
+
+/// One owner's herd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Herd {
+    /// Livestock in the herd.
+    pub head: u32,
+    /// Standing pastures the owner has for the herd to graze.
+    pub pastures: u32,
+}
+
+/// What one step did to a herd.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HerdChange {
+    /// Head born into the herd.
+    pub born: u32,
+    /// Units of fodder the herd ate.
+    pub fodder_eaten: u32,
+    /// Head that starved for want of fodder.
+    pub starved: u32,
+}
+
+impl HerdChange {
+    /// Return whether the step left the herd and its owner's fodder as
+    /// they were.
+    pub const fn is_empty(&self) -> bool {
+        self.born == 0 && self.fodder_eaten == 0 && self.starved == 0
+    }
+}
+
+impl Herd {
+    /// Return the most head the herd can grow to.
+    pub const fn capacity(&self) -> u32 {
+        self.pastures
+            .saturating_mul(PASTURE_CAPACITY)
+            .saturating_add(OPEN_RANGE_CAPACITY)
+    }
+
+    /// Step the herd at `tick` in `season`, with `fodder` units of fodder
+    /// to hand.
+    ///
+    /// Herds step only every [`BREEDING_INTERVAL_TICKS`] ticks; at other
+    /// ticks nothing changes.
+    pub fn step(&self, tick: u64, season: Season, fodder: u32) -> HerdChange {
+        if self.head == 0 || tick.checked_rem(BREEDING_INTERVAL_TICKS) != Some(0) {
+            return HerdChange::default();
+        }
+        if season == Season::Winter {
+            let needed = self.head.saturating_mul(FODDER_PER_HEAD);
+            let fodder_eaten = needed.min(fodder);
+            let fed = fodder_eaten.checked_div(FODDER_PER_HEAD).unwrap_or(self.head);
+            let unfed = self.head.saturating_sub(fed);
+            return HerdChange {
+                born: 0,
+                fodder_eaten,
+                starved: unfed.div_ceil(2),
+            };
+        }
+        if self.head < MIN_BREEDING_HEAD {
+            return HerdChange::default();
+        }
+        let growth = self.head.saturating_mul(BREEDING_PCT) / 100;
+        let room = self.capacity().saturating_sub(self.head);
+        HerdChange {
+            born: growth.max(1).min(room),
+            fodder_eaten: 0,
+            starved: 0,
+        }
+    }
+}
+
+/// Return the units of fodder in `inventory`.
+pub fn fodder_available(inventory: &BTreeMap<Resource, u32>) -> u32 {
+    FODDER
+        .iter()
+        .map(|r| inventory.get(r).copied().unwrap_or(0))
+        .fold(0, u32::saturating_add)
+}
+
+/// Split `amount` units of fodder eaten across the resources in
+/// `inventory`, using them up in [`FODDER`] order.
+pub fn fodder_to_eat(inventory: &BTreeMap<Resource, u32>, amount: u32) -> Vec<(Resource, u32)> {
+    let mut left = amount;
+    let mut eaten = Vec::new();
+    for resource in FODDER {
+        let take = left.min(inventory.get(&resource).copied().unwrap_or(0));
+        if take > 0 {
+            eaten.push((resource, take));
+            left = left.saturating_sub(take);
+        }
+    }
+    eaten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn herds_breed_up_to_their_pastures() {
+        let herd = Herd { head: 10, pastures: 1 };
+        assert_eq!(herd.capacity(), 24);
+        assert_eq!(herd.step(10, Season::Summer, 0).born, 2);
+        // Only every interval
+        assert!(herd.step(11, Season::Summer, 0).is_empty());
+
+        let pair = Herd { head: 2, pastures: 0 };
+        assert_eq!(pair.step(20, Season::Spring, 0).born, 1);
+        let full = Herd { head: 4, pastures: 0 };
+        assert_eq!(full.step(20, Season::Spring, 0).born, 0);
+        let lone = Herd { head: 1, pastures: 3 };
+        assert!(lone.step(20, Season::Spring, 0).is_empty());
+    }
+
+    #[test]
+    fn unfed_herds_starve_in_winter() {
+        let herd = Herd { head: 9, pastures: 1 };
+        let fed = herd.step(30, Season::Winter, 20);
+        assert_eq!(fed, HerdChange { born: 0, fodder_eaten: 9, starved: 0 });
+
+        let hungry = herd.step(30, Season::Winter, 4);
+        assert_eq!(hungry.fodder_eaten, 4);
+        assert_eq!(hungry.starved, 3); // half of 5, rounded up
+    }
+
+    #[test]
+    fn fodder_is_eaten_waste_first() {
+        let inventory = BTreeMap::from([
+            (Resource::FoodRoot, 5),
+            (Resource::FoodWaste, 2),
+            (Resource::FoodBerry, 9),
+        ]);
+        assert_eq!(fodder_available(&inventory), 7);
+        assert_eq!(
+            fodder_to_eat(&inventory, 4),
+            vec![(Resource::FoodWaste, 2), (Resource::FoodRoot, 2)]
+        );
+    }
+}
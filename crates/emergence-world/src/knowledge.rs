@@ -291,7 +291,8 @@ fn seed_items() -> Vec<KnowledgeItem> {
 
         // Level 2 -- Ancient / Bronze Age foundations
         item("observe_seasons", "Seasonal Observation", KnowledgeEra::Primitive, &["perceive"], "Recognition of seasonal patterns in the environment.", None),
-        item("animal_tracking", "Animal Tracking", KnowledgeEra::Primitive, &["perceive", "gather_food"], "Ability to track and hunt animals.", Some("gather (meat)")),
+        item("weather_lore", "Weather Lore", KnowledgeEra::Primitive, &["observe_seasons"], "Reading clouds, wind, and animals to foretell the coming weather.", None),
+        item("animal_tracking", "Animal Tracking", KnowledgeEra::Primitive, &["perceive", "gather_food"], "Ability to track and hunt animals.", Some("hunt")),
         item("cooking", "Cooking", KnowledgeEra::Primitive, &["gather_food", "build_campfire"], "Ability to cook food for improved nutrition.", Some("craft (cooked food)")),
         item("fire_mastery", "Fire Mastery", KnowledgeEra::Primitive, &["build_campfire"], "Advanced understanding of fire and its uses.", None),
     ]
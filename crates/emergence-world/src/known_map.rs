@@ -0,0 +1,322 @@
+//! Per-agent map knowledge and the fog of war.
+//!
+//! No agent knows the whole world. Each one carries a [`KnownMap`]: the
+//! locations it has visited or heard of, and the routes it has travelled
+//! or heard about. An agent standing at a location can see every route
+//! leading out of it, but only knows where a route goes, and what can be
+//! found there, if it knows the destination -- that is, if it has been
+//! there, heard of it, or knows a route that reaches it. Routes to places
+//! it does not know are shown as leading somewhere unexplored.
+//!
+//! Knowledge grows in four ways:
+//!
+//! - **Exploration** -- [`KnownMapRegistry::record_position`] marks each
+//!   location an agent stands in as visited and, when it has moved, the
+//!   route it came by as known.
+//! - **Teaching** -- teaching the [`MAP_CONCEPT`] pseudo-concept hands the
+//!   teacher's routes and places to the student, who has then heard of
+//!   them without visiting them ([`KnownMapRegistry::teach`]).
+//! - **Cartography** -- writing the [`MAP_CONCEPT`] to a library stores a
+//!   copy of the writer's map there, and reading it merges that copy into
+//!   the reader's ([`KnownMapRegistry::write`], [`KnownMapRegistry::read`]).
+//! - **Charts** -- the `Chart` action draws the places an agent has been
+//!   and the routes it knows onto a [`Resource::Map`] it carries
+//!   ([`KnownMapRegistry::chart`]). A map traded to another agent takes that
+//!   geography with it, and the buyer reads it on receipt
+//!   ([`KnownMapRegistry::hand_over_chart`]).
+//!
+//! [`Resource::Map`]: emergence_types::Resource::Map
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::{AgentId, LocationId, RouteId, StructureId};
+
+use crate::world_map::WorldMap;
+
+/// The concept an agent teaches, writes, or reads to pass on its map.
+///
+/// Every agent knows its own map, so this needs no prior learning.
+pub const MAP_CONCEPT: &str = "map";
+
+/// Shown in place of the name of a destination an agent does not know.
+pub const UNEXPLORED: &str = "Unexplored";
+
+// ---------------------------------------------------------------------------
+// KnownMap
+// ---------------------------------------------------------------------------
+
+/// The part of the world one agent knows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownMap {
+    /// Locations the agent has stood in.
+    visited: BTreeSet<LocationId>,
+    /// Locations the agent has been taught about or read of.
+    heard_of: BTreeSet<LocationId>,
+    /// Routes the agent has travelled or heard about.
+    routes: BTreeSet<RouteId>,
+    /// Where the agent was last seen, to tell which route it took next.
+    position: Option<LocationId>,
+}
+
+impl KnownMap {
+    /// Create an empty map.
+    pub const fn new() -> Self {
+        Self {
+            visited: BTreeSet::new(),
+            heard_of: BTreeSet::new(),
+            routes: BTreeSet::new(),
+            position: None,
+        }
+    }
+
+    /// Return the locations the agent has visited.
+    pub const fn visited(&self) -> &BTreeSet<LocationId> {
+        &self.visited
+    }
+
+    /// Return the routes the agent knows.
+    pub const fn routes(&self) -> &BTreeSet<RouteId> {
+        &self.routes
+    }
+
+    /// Return whether the agent knows `route`.
+    pub fn knows_route(&self, route: RouteId) -> bool {
+        self.routes.contains(&route)
+    }
+
+    /// Return whether the agent knows `location`: it has been there,
+    /// heard of it, or knows a route that reaches it.
+    pub fn knows_location(&self, map: &WorldMap, location: LocationId) -> bool {
+        self.visited.contains(&location)
+            || self.heard_of.contains(&location)
+            || self.routes.iter().any(|id| {
+                map.get_route(*id)
+                    .is_some_and(|r| r.from_location == location || r.to_location == location)
+            })
+    }
+
+    /// Learn `route`. Returns whether it was new.
+    pub fn learn_route(&mut self, route: RouteId) -> bool {
+        self.routes.insert(route)
+    }
+
+    /// Learn everything `other` knows of the map. Places `other` has
+    /// visited or heard of become places this map has heard of.
+    ///
+    /// Returns the number of locations and routes learned.
+    pub fn merge(&mut self, other: &Self) -> usize {
+        let before = self.known_count();
+        let places = other.visited.iter().chain(&other.heard_of);
+        let new_places: Vec<LocationId> =
+            places.filter(|id| !self.visited.contains(*id)).copied().collect();
+        self.heard_of.extend(new_places);
+        self.routes.extend(other.routes.iter().copied());
+        self.known_count().saturating_sub(before)
+    }
+
+    /// Return the part of this map the agent knows first hand: the places
+    /// it has visited and the routes it knows, without the places it has
+    /// only heard of.
+    #[must_use]
+    pub fn first_hand(&self) -> Self {
+        Self {
+            visited: self.visited.clone(),
+            heard_of: BTreeSet::new(),
+            routes: self.routes.clone(),
+            position: None,
+        }
+    }
+
+    /// The number of locations and routes on this map.
+    fn known_count(&self) -> usize {
+        self.visited
+            .len()
+            .saturating_add(self.heard_of.len())
+            .saturating_add(self.routes.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// KnownMapRegistry
+// ---------------------------------------------------------------------------
+
+/// Every agent's map, the maps written down in libraries, and the charts
+/// agents carry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownMapRegistry {
+    maps: BTreeMap<AgentId, KnownMap>,
+    archives: BTreeMap<StructureId, KnownMap>,
+    /// The geography drawn on the map items each agent carries.
+    #[serde(default)]
+    charts: BTreeMap<AgentId, KnownMap>,
+}
+
+impl KnownMapRegistry {
+    /// Create a registry in which no one knows anything.
+    pub const fn new() -> Self {
+        Self {
+            maps: BTreeMap::new(),
+            archives: BTreeMap::new(),
+            charts: BTreeMap::new(),
+        }
+    }
+
+    /// Return the map of `agent`, if it has one.
+    pub fn get(&self, agent: AgentId) -> Option<&KnownMap> {
+        self.maps.get(&agent)
+    }
+
+    /// Return whether `agent` knows `location`.
+    pub fn knows_location(&self, map: &WorldMap, agent: AgentId, location: LocationId) -> bool {
+        self.maps.get(&agent).is_some_and(|m| m.knows_location(map, location))
+    }
+
+    /// Record that `agent` stands at `location`.
+    ///
+    /// The location becomes visited. If the agent was last somewhere else,
+    /// the routes between there and here become known too.
+    pub fn record_position(&mut self, map: &WorldMap, agent: AgentId, location: LocationId) {
+        let known = self.maps.entry(agent).or_default();
+        if let Some(previous) = known.position
+            && previous != location
+        {
+            for route in map.routes_between(previous, location) {
+                known.routes.insert(route.id);
+            }
+        }
+        known.heard_of.remove(&location);
+        known.visited.insert(location);
+        known.position = Some(location);
+    }
+
+    /// Teach `agent` the route `route`, and so the places it joins.
+    ///
+    /// Returns whether the route was new to the agent.
+    pub fn learn_route(&mut self, agent: AgentId, route: RouteId) -> bool {
+        self.maps.entry(agent).or_default().learn_route(route)
+    }
+
+    /// Teach `student` everything `teacher` knows of the map.
+    ///
+    /// Returns the number of locations and routes the student learned.
+    pub fn teach(&mut self, teacher: AgentId, student: AgentId) -> usize {
+        let Some(lesson) = self.maps.get(&teacher).cloned() else {
+            return 0;
+        };
+        self.maps.entry(student).or_default().merge(&lesson)
+    }
+
+    /// Copy the map of `agent` into `library`, adding to any map already
+    /// written there.
+    pub fn write(&mut self, agent: AgentId, library: StructureId) {
+        if let Some(known) = self.maps.get(&agent) {
+            self.archives.entry(library).or_default().merge(known);
+        }
+    }
+
+    /// Merge the map written in `library` into the map of `agent`.
+    ///
+    /// Returns the number of locations and routes the agent learned.
+    pub fn read(&mut self, agent: AgentId, library: StructureId) -> usize {
+        let Some(archive) = self.archives.get(&library) else {
+            return 0;
+        };
+        self.maps.entry(agent).or_default().merge(archive)
+    }
+
+    /// Return the geography on the map items `agent` carries, if any.
+    pub fn chart_of(&self, agent: AgentId) -> Option<&KnownMap> {
+        self.charts.get(&agent)
+    }
+
+    /// Draw what `author` knows first hand onto the map items it carries.
+    pub fn chart(&mut self, author: AgentId) {
+        if let Some(known) = self.maps.get(&author) {
+            let drawn = known.first_hand();
+            self.charts.entry(author).or_default().merge(&drawn);
+        }
+    }
+
+    /// Hand a map item from `giver` to `recipient`.
+    ///
+    /// The giver's chart is added to the recipient's, and the recipient
+    /// reads it at once. The giver keeps its own copy of the geography.
+    /// Returns the number of locations and routes the recipient learned.
+    pub fn hand_over_chart(&mut self, giver: AgentId, recipient: AgentId) -> usize {
+        let Some(chart) = self.charts.get(&giver).cloned() else {
+            return 0;
+        };
+        self.charts.entry(recipient).or_default().merge(&chart);
+        self.maps.entry(recipient).or_default().merge(&chart)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn travelling_reveals_the_route_taken() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let agent = AgentId::new();
+        let mut registry = KnownMapRegistry::new();
+
+        registry.record_position(&map, agent, ids.open_field);
+        assert!(registry.knows_location(&map, agent, ids.open_field));
+        assert!(!registry.knows_location(&map, agent, ids.riverbank));
+        assert!(registry.get(agent).unwrap().routes().is_empty());
+
+        registry.record_position(&map, agent, ids.riverbank);
+        let known = registry.get(agent).unwrap();
+        let taken = map.routes_between(ids.open_field, ids.riverbank);
+        assert!(taken.iter().all(|r| known.knows_route(r.id)));
+        assert_eq!(known.visited().len(), 2);
+    }
+
+    #[test]
+    fn maps_pass_on_through_teaching_and_libraries() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let (explorer, student, reader) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let mut registry = KnownMapRegistry::new();
+        registry.record_position(&map, explorer, ids.open_field);
+        registry.record_position(&map, explorer, ids.riverbank);
+
+        assert!(registry.teach(explorer, student) > 0);
+        assert!(registry.knows_location(&map, student, ids.riverbank));
+        assert_eq!(registry.teach(explorer, student), 0);
+        // Hearing about a place is not visiting it.
+        assert!(registry.get(student).unwrap().visited().is_empty());
+
+        let library = StructureId::new();
+        assert_eq!(registry.read(reader, library), 0);
+        registry.write(explorer, library);
+        assert!(registry.read(reader, library) > 0);
+        assert!(registry.knows_location(&map, reader, ids.open_field));
+    }
+
+    #[test]
+    fn charts_carry_first_hand_geography_to_buyers() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let (cartographer, student, buyer) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let mut registry = KnownMapRegistry::new();
+        registry.record_position(&map, cartographer, ids.open_field);
+        registry.record_position(&map, cartographer, ids.riverbank);
+        registry.teach(cartographer, student);
+
+        // Nothing is handed over before a chart is drawn.
+        assert_eq!(registry.hand_over_chart(cartographer, buyer), 0);
+        registry.chart(cartographer);
+        assert!(registry.chart_of(cartographer).unwrap().knows_location(&map, ids.riverbank));
+
+        // Places the student only heard of are not drawn on its chart.
+        assert!(registry.get(student).unwrap().first_hand().heard_of.is_empty());
+
+        assert!(registry.hand_over_chart(cartographer, buyer) > 0);
+        assert!(registry.knows_location(&map, buyer, ids.riverbank));
+        assert!(registry.chart_of(buyer).is_some());
+        assert!(registry.chart_of(cartographer).is_some());
+    }
+}
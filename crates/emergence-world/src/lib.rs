@@ -7,59 +7,133 @@
 //!
 //! # Modules
 //!
+//! - [`biome`] -- The kind of country each region is, which shapes how
+//!   resources regrow through the year.
+//! - [`climate`] -- Long-term climate drift: warming and drying weather and
+//!   falling regeneration over hundreds of ticks.
 //! - [`cultural_knowledge`] -- Non-mechanical cultural knowledge (philosophy,
 //!   art, music, mythology, ethics) that influences agent behavior and social
 //!   cohesion without unlocking mechanical actions.
+//! - [`crowding`] -- Carrying capacity: overcrowded locations regenerate
+//!   more slowly and suffer worse from plague.
+//! - [`disasters`] -- Seeded or operator-injected floods, wildfires, and
+//!   earthquakes that destroy resources, damage structures, and block routes.
 //! - [`diffusion`] -- Technology and cultural knowledge diffusion tracking:
 //!   adoption curves, resistance rates, diffusion speed, knowledge hoarders.
 //! - [`environment`] -- Weather generation with season-weighted probabilities
 //!   and deterministic randomness for reproducible simulations.
 //! - [`error`] -- Error types for world-graph operations.
+//! - [`fire`] -- Fires from unattended campfires and wildfires spreading
+//!   between the wooden structures at a location until put out.
+//! - [`fauna`] -- Game and predator populations per location: growth,
+//!   predation, hunting, and the collapse of overhunted herds.
 //! - [`farming`] -- Farm plot crop state tracking, planting, growth timers,
-//!   and harvest yield calculation.
+//!   soil fertility, and harvest yield calculation.
+//! - [`husbandry`] -- Herds of livestock kept as capital: breeding up to
+//!   their owners' pastures, and winter fodder.
 //! - [`innovation`] -- Open innovation proposals: agents combine knowledge
 //!   to propose new inventions evaluated by the engine.
 //! - [`knowledge`] -- Knowledge tree and tech progression from Primitive
 //!   through Early Industrial era with prerequisite chains.
+//! - [`known_map`] -- Per-agent map knowledge: the places and routes each
+//!   agent has explored or been told about.
 //! - [`location`] -- [`LocationState`] wraps the canonical [`Location`] type
 //!   with mutable runtime state (occupants, structures).
-//! - [`resource`] -- Regeneration and harvesting logic for resource nodes.
-//! - [`route`] -- Traversal checks, travel cost calculation with weather.
+//! - [`metrics`] -- Regeneration and decay counters for the Observer's
+//!   `/metrics` endpoint.
+//! - [`mining`] -- Finite ore bodies behind each mine face, deepened at a
+//!   rising cost in materials and risk of cave-ins.
+//! - [`pathfinding`] -- A* paths weighed by weather, slope, tolls, and
+//!   access lists, and a cache of them kept until the routes change.
+//! - [`pollution`] -- Pollution from smelting, mining, and crowding that
+//!   degrades farm yields and water, and decays slowly.
+//! - [`prospecting`] -- Hidden resource deposits and the skill- and
+//!   knowledge-gated odds of discovering them.
+//! - [`resource`] -- Regeneration and harvesting logic for resource nodes,
+//!   with configurable season curves per resource and biome.
+//! - [`route`] -- Traversal checks, travel cost calculation with weather
+//!   and slope.
+//! - [`route_building`] -- New routes laid between unconnected locations
+//!   over several ticks of work.
+//! - [`settlement`] -- Agents founding new settlements, growing the world
+//!   graph at runtime.
 //! - [`world_map`] -- The world graph: locations as nodes, routes as edges,
 //!   with pathfinding, neighbor queries, and batch operations.
 //! - [`starting_world`] -- Default 12-location starting map across 3 regions.
+//! - [`trade_network`] -- Route utilization, and the trade corridors heavy
+//!   traffic wears in: faster in foul weather and safe from mishaps.
+//! - [`world_file`] -- Versioned world files for saving maps and loading
+//!   hand-authored worlds.
+//! - [`waters`] -- Rivers and lakes flowing between locations, and the
+//!   fish stocks along them.
+//! - [`world_gen`] -- Seeded procedural maps of any size, region count,
+//!   resource richness, and connectivity.
 //!
 //! [`Location`]: emergence_types::Location
 //! [`LocationState`]: location::LocationState
 
+pub mod biome;
+pub mod climate;
+pub mod crowding;
 pub mod cultural_knowledge;
 pub mod diffusion;
+pub mod disasters;
 pub mod environment;
 pub mod error;
 pub mod farming;
+pub mod fauna;
+pub mod fire;
+pub mod husbandry;
 pub mod innovation;
 pub mod knowledge;
+pub mod known_map;
 pub mod location;
+pub mod metrics;
+pub mod mining;
+pub mod pathfinding;
+pub mod pollution;
+pub mod prospecting;
 pub mod resource;
 pub mod route;
+pub mod route_building;
+pub mod settlement;
 pub mod starting_world;
 pub mod structure;
+pub mod trade_network;
+pub mod waters;
+pub mod world_file;
+pub mod world_gen;
 pub mod world_map;
 
 // Re-export primary types at crate root.
+pub use biome::Biome;
+pub use climate::Climate;
+pub use disasters::{Disaster, DisasterSystem, disaster_event_type};
 pub use environment::WeatherSystem;
 pub use error::WorldError;
 pub use innovation::{InnovationEvaluator, InnovationProposal, InnovationResult};
 pub use knowledge::{KnowledgeEra, KnowledgeItem, KnowledgeTree, build_extended_tech_tree};
+pub use known_map::{KnownMap, KnownMapRegistry};
 pub use location::LocationState;
+pub use pathfinding::{Path, RouteCache, Traveler};
+pub use resource::{RegenCurves, SeasonCurve};
 pub use starting_world::{StartingLocationIds, create_starting_world};
 pub use structure::{
-    apply_decay, apply_repair, blueprint, compute_repair_cost, compute_salvage,
-    structure_effects_at_location,
+    apply_decay, apply_repair, apply_upgrade, blueprint, compute_repair_cost, compute_salvage,
+    next_tier, structure_effects_at_location,
 };
 pub use farming::{
-    BASE_HARVEST_YIELD, DEFAULT_GROWTH_TICKS, FarmCropState, FarmRegistry, harvest_yield,
+    BASE_HARVEST_YIELD, DEFAULT_GROWTH_TICKS, DEFAULT_IRRIGATION_YIELD_PCT, FarmCropState,
+    FarmRegistry, SoilState, fertility_yield_pct, harvest_yield, irrigation_yield_pct,
 };
+pub use fauna::{FaunaChange, FaunaRegistry, Population};
+pub use fire::{Fire, FireSystem};
+pub use husbandry::{Herd, HerdChange};
+pub use mining::{MineRegistry, MineWork, OreBody};
+pub use trade_network::{CorridorChange, TradeNetwork};
+pub use waters::{FishingSpot, WaterBody, WaterKind, WaterRegistry};
+pub use world_file::{LoadedWorld, WORLD_FILE_VERSION, WorldFile};
+pub use world_gen::{WorldGenParams, generate_world};
 pub use world_map::WorldMap;
 pub use cultural_knowledge::{
     AggregateModifiers, BehavioralInfluence, CulturalCategory, CulturalKnowledge,
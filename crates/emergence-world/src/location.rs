@@ -2,7 +2,8 @@
 //!
 //! A [`LocationState`] wraps the canonical [`Location`] type from
 //! `emergence-types` and adds mutable runtime state: the set of agents
-//! currently present and the set of structures built here.
+//! currently present, the set of structures built here, the resource
+//! deposits no one has found yet, and how polluted the land is.
 //!
 //! The separation exists because [`Location`] is the persistent identity
 //! (stored in `PostgreSQL`) while [`LocationState`] is the hot, per-tick
@@ -12,8 +13,11 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use emergence_types::{AgentId, Location, Resource, ResourceNode, Season, StructureId};
 
+use crate::biome::Biome;
+use crate::crowding;
 use crate::error::WorldError;
-use crate::resource;
+use crate::pollution;
+use crate::resource::{self, RegenCurves};
 
 /// Mutable runtime state for a location in the world graph.
 ///
@@ -29,17 +33,27 @@ pub struct LocationState {
     pub occupants: BTreeSet<AgentId>,
     /// Structures built at this location.
     pub structures: BTreeSet<StructureId>,
+    /// Resource nodes not yet discovered. They cannot be gathered from until
+    /// prospecting reveals them (see [`crate::prospecting`]).
+    #[serde(default)]
+    pub hidden_resources: BTreeMap<Resource, ResourceNode>,
+    /// Pollution from 0 (clean) to [`pollution::MAX_POLLUTION`].
+    #[serde(default)]
+    pub pollution: u32,
 }
 
 impl LocationState {
     /// Create a new [`LocationState`] from a [`Location`] definition.
     ///
-    /// Starts with no occupants and no structures.
+    /// Starts clean, with no occupants, no structures, and no hidden
+    /// deposits.
     pub const fn new(location: Location) -> Self {
         Self {
             location,
             occupants: BTreeSet::new(),
             structures: BTreeSet::new(),
+            hidden_resources: BTreeMap::new(),
+            pollution: 0,
         }
     }
 
@@ -131,6 +145,33 @@ impl LocationState {
         &self.location.base_resources
     }
 
+    /// Hide a resource node at this location until it is discovered.
+    ///
+    /// A node of a resource already hidden here replaces it.
+    pub fn hide_resource(&mut self, node: ResourceNode) {
+        self.hidden_resources.insert(node.resource, node);
+    }
+
+    /// Reveal a hidden resource node, making it harvestable.
+    ///
+    /// A deposit of a resource the location already has enlarges the
+    /// known node. Returns the node as it now stands, or `None` if no
+    /// deposit of `res` was hidden here.
+    pub fn reveal_resource(&mut self, res: Resource) -> Option<&ResourceNode> {
+        let hidden = self.hidden_resources.remove(&res)?;
+        let node = self
+            .location
+            .base_resources
+            .entry(res)
+            .and_modify(|known| {
+                known.available = known.available.saturating_add(hidden.available);
+                known.regen_per_tick = known.regen_per_tick.saturating_add(hidden.regen_per_tick);
+                known.max_capacity = known.max_capacity.saturating_add(hidden.max_capacity);
+            })
+            .or_insert(hidden);
+        Some(node)
+    }
+
     /// Return available quantities for all resources at this location.
     pub fn available_resources(&self) -> BTreeMap<Resource, u32> {
         self.location
@@ -140,7 +181,18 @@ impl LocationState {
             .collect()
     }
 
-    /// Regenerate all resource nodes at this location for one tick.
+    /// Return the biome this location lies in, from its region.
+    pub fn biome(&self) -> Biome {
+        Biome::of_region(&self.location.region)
+    }
+
+    /// Regenerate all resource nodes at this location for one tick,
+    /// following `curves` for the season and this location's biome.
+    ///
+    /// Regeneration is slowed when the location is overcrowded (see
+    /// [`crowding::regen_penalty_pct`]), and water regrows more slowly
+    /// while the location is polluted (see
+    /// [`pollution::water_regen_penalty_pct`]).
     ///
     /// Returns a map of resource to the number of units regenerated.
     ///
@@ -150,13 +202,45 @@ impl LocationState {
     pub fn regenerate_all(
         &mut self,
         season: Season,
+        curves: &RegenCurves,
+    ) -> Result<BTreeMap<Resource, u32>, WorldError> {
+        self.regenerate_all_penalized(season, curves, 0)
+    }
+
+    /// Regenerate all resource nodes at this location for one tick, losing
+    /// a further `extra_penalty_pct` percent on top of any crowding penalty.
+    ///
+    /// Used for climate drift; see [`crate::climate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
+    pub fn regenerate_all_penalized(
+        &mut self,
+        season: Season,
+        curves: &RegenCurves,
+        extra_penalty_pct: u32,
     ) -> Result<BTreeMap<Resource, u32>, WorldError> {
         let mut results = BTreeMap::new();
+        let biome = self.biome();
+        let penalty_pct =
+            crowding::regen_penalty_pct(self.location.capacity, self.occupant_count())
+                .saturating_add(extra_penalty_pct);
         // Collect keys first to avoid borrowing conflicts.
         let keys: Vec<Resource> = self.location.base_resources.keys().copied().collect();
+        let water_penalty_pct =
+            penalty_pct.saturating_add(pollution::water_regen_penalty_pct(self.pollution));
         for key in keys {
+            let node_penalty_pct =
+                if key == Resource::Water { water_penalty_pct } else { penalty_pct };
             if let Some(node) = self.location.base_resources.get_mut(&key) {
-                let added = resource::regenerate(node, season)?;
+                let added = resource::regenerate_penalized(
+                    node,
+                    season,
+                    biome,
+                    curves,
+                    node_penalty_pct,
+                )?;
                 if added > 0 {
                     results.insert(key, added);
                 }
@@ -238,6 +322,7 @@ mod tests {
             location_type: "natural".to_string(),
             description: "A test location.".to_string(),
             capacity,
+            elevation: 0,
             base_resources: resources,
             discovered_by: BTreeSet::new(),
             created_at: Utc::now(),
@@ -333,7 +418,7 @@ mod tests {
         let mut state = LocationState::new(loc);
         // Wood: 50, regen 5, max 100 -> should add 5 in summer
         // Stone: 8, regen 0, max 8 -> should add 0
-        let results = state.regenerate_all(Season::Summer);
+        let results = state.regenerate_all(Season::Summer, &RegenCurves::default());
         assert!(results.is_ok());
         let map = results.ok().unwrap_or_default();
         assert_eq!(map.get(&Resource::Wood).copied(), Some(5));
@@ -347,6 +432,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn overcrowding_slows_regeneration() {
+        // Capacity 4 is crowded past 3 occupants: the fourth costs 10%.
+        let loc = make_location(4);
+        let mut state = LocationState::new(loc);
+        for _ in 0..4 {
+            assert!(state.add_occupant(AgentId::new()).is_ok());
+        }
+        let map = state.regenerate_all(Season::Summer, &RegenCurves::default()).ok().unwrap_or_default();
+        assert_eq!(map.get(&Resource::Wood).copied(), Some(4));
+    }
+
+    #[test]
+    fn highlands_regrow_nothing_in_winter() {
+        let mut loc = make_location(5);
+        loc.region = "Highlands".to_string();
+        let mut state = LocationState::new(loc);
+        assert_eq!(state.biome(), Biome::Highland);
+        let curves = RegenCurves::default();
+        let winter = state.regenerate_all(Season::Winter, &curves).ok().unwrap_or_default();
+        assert!(winter.is_empty());
+        // Wood in summer: 5 * 100% * 100% = 5.
+        let summer = state.regenerate_all(Season::Summer, &curves).ok().unwrap_or_default();
+        assert_eq!(summer.get(&Resource::Wood).copied(), Some(5));
+    }
+
     #[test]
     fn discover_location() {
         let loc = make_location(5);
@@ -365,4 +476,33 @@ mod tests {
         assert_eq!(avail.get(&Resource::Wood).copied(), Some(50));
         assert_eq!(avail.get(&Resource::Stone).copied(), Some(8));
     }
+
+    #[test]
+    fn hidden_resources_harvestable_only_once_revealed() {
+        let loc = make_location(5);
+        let mut state = LocationState::new(loc);
+        state.hide_resource(ResourceNode {
+            resource: Resource::Ore,
+            available: 12,
+            regen_per_tick: 1,
+            max_capacity: 30,
+        });
+        state.hide_resource(ResourceNode {
+            resource: Resource::Stone,
+            available: 10,
+            regen_per_tick: 1,
+            max_capacity: 20,
+        });
+        assert!(state.harvest_resource(Resource::Ore, 1).is_err());
+        assert!(!state.available_resources().contains_key(&Resource::Ore));
+
+        assert_eq!(state.reveal_resource(Resource::Ore).map(|n| n.available), Some(12));
+        assert_eq!(state.harvest_resource(Resource::Ore, 5).ok(), Some(5));
+        assert!(state.reveal_resource(Resource::Ore).is_none());
+
+        // A deposit of a known resource enlarges the known node.
+        let stone = state.reveal_resource(Resource::Stone).cloned();
+        assert_eq!(stone.map(|n| (n.available, n.max_capacity)), Some((18, 28)));
+        assert!(state.hidden_resources.is_empty());
+    }
 }
@@ -0,0 +1,55 @@
+//! World upkeep metrics, exported through the Observer's `/metrics`
+//! endpoint (see [`emergence_metrics`]).
+
+use emergence_metrics::Counter;
+
+/// Resource units added by regeneration, by resource.
+pub static RESOURCES_REGENERATED: Counter = Counter::labeled(
+    "emergence_resources_regenerated_total",
+    "Resource units added to location nodes by regeneration, by resource.",
+    "resource",
+);
+
+/// Durability points structures lost to decay.
+pub static STRUCTURE_DECAY: Counter = Counter::new(
+    "emergence_structure_decay_total",
+    "Durability points lost by structures to decay.",
+);
+
+/// Structures whose durability decayed to zero.
+pub static STRUCTURES_COLLAPSED: Counter = Counter::new(
+    "emergence_structures_collapsed_total",
+    "Structures that collapsed because decay brought durability to zero.",
+);
+
+/// Durability points routes lost to decay.
+pub static ROUTE_DECAY: Counter = Counter::new(
+    "emergence_route_decay_total",
+    "Durability points lost by routes to decay.",
+);
+
+/// Routes downgraded to a lower path type by decay, by the new path type.
+pub static ROUTE_DEGRADATIONS: Counter = Counter::labeled(
+    "emergence_route_degradations_total",
+    "Routes downgraded by decay, by the path type they degraded to.",
+    "path_type",
+);
+
+/// Floods, wildfires, and earthquakes that struck a location, by kind.
+pub static DISASTERS: Counter = Counter::labeled(
+    "emergence_disasters_total",
+    "Natural disasters that struck a location, by kind.",
+    "kind",
+);
+
+/// Herds hunted below their collapse threshold.
+pub static FAUNA_COLLAPSES: Counter = Counter::new(
+    "emergence_fauna_collapses_total",
+    "Herds of game that collapsed after being hunted below their collapse threshold.",
+);
+
+/// Routes whose traffic made them trade corridors.
+pub static TRADE_CORRIDORS_EMERGED: Counter = Counter::new(
+    "emergence_trade_corridors_emerged_total",
+    "Routes that became trade corridors through heavy use.",
+);
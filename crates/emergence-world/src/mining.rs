@@ -0,0 +1,343 @@
+//! Ore bodies: the finite ore beneath the locations that have any.
+//!
+//! The ore a location shows is only what its mine face reaches. Behind the
+//! face lies an [`OreBody`] of limited size. Every unit mined comes out of
+//! the face, and once the face is worked out the `Mine` action finds
+//! nothing, however far the location's ore node regrows.
+//!
+//! The `DeepenMine` action sinks the shaft one level and opens a fresh face
+//! of up to [`FACE_CAPACITY`] from what the body has left. Each level costs
+//! more wood and tools than the last (see [`deepen_cost`]) and adds
+//! [`COLLAPSE_RISK_PER_LEVEL_PCT`] to the chance that mining there caves
+//! in, burying the face and injuring the miner. A body with nothing left
+//! behind its face cannot be deepened.
+//!
+//! Cave-in rolls are derived from the agent and tick, so a replayed tick
+//! collapses the same shafts.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::{AgentId, LocationId, Resource};
+
+use crate::environment::deterministic_random;
+use crate::world_map::WorldMap;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Ore in a location's body per unit of its ore node's capacity.
+pub const ORE_RESERVE_PER_CAPACITY: u32 = 5;
+
+/// The most ore a single mine face exposes.
+pub const FACE_CAPACITY: u32 = 40;
+
+/// Wood needed to deepen a shaft from the surface.
+pub const DEEPEN_BASE_WOOD: u32 = 10;
+
+/// Extra wood needed for each level the shaft already has.
+pub const DEEPEN_WOOD_PER_LEVEL: u32 = 5;
+
+/// Added chance in percent that mining caves in, per level of depth.
+pub const COLLAPSE_RISK_PER_LEVEL_PCT: u32 = 5;
+
+/// The highest chance in percent that mining caves in.
+pub const MAX_COLLAPSE_RISK_PCT: u32 = 50;
+
+/// Health lost by a miner caught in a cave-in.
+pub const CAVE_IN_DAMAGE: u32 = 15;
+
+/// Mixed into the cave-in roll so it differs from other rolls made for the
+/// same agent and tick.
+const CAVE_IN_SALT: u64 = 0x6d69_6e65;
+
+// ---------------------------------------------------------------------------
+// OreBody
+// ---------------------------------------------------------------------------
+
+/// The ore beneath a single location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OreBody {
+    /// Ore the current face exposes.
+    pub face: u32,
+    /// Ore still behind the face, reached only by deepening.
+    pub remaining: u32,
+    /// Levels the shaft has been deepened.
+    pub depth: u32,
+}
+
+impl OreBody {
+    /// Create an unworked body of `reserve` ore, its first face open.
+    pub const fn new(reserve: u32) -> Self {
+        let face = if reserve < FACE_CAPACITY {
+            reserve
+        } else {
+            FACE_CAPACITY
+        };
+        Self {
+            face,
+            remaining: reserve.saturating_sub(face),
+            depth: 0,
+        }
+    }
+
+    /// Return the ore left in the body, at the face and behind it.
+    pub const fn total(&self) -> u32 {
+        self.face.saturating_add(self.remaining)
+    }
+
+    /// Return whether the shaft can be deepened to reach more ore.
+    pub const fn can_deepen(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Return whether the body has no ore left at all.
+    pub const fn is_exhausted(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Return the chance in percent that mining this shaft caves in.
+    pub fn collapse_risk_pct(&self) -> u32 {
+        self.depth
+            .saturating_mul(COLLAPSE_RISK_PER_LEVEL_PCT)
+            .min(MAX_COLLAPSE_RISK_PCT)
+    }
+
+    /// Take up to `wanted` ore from the face, returning the amount taken.
+    pub fn extract(&mut self, wanted: u32) -> u32 {
+        let taken = wanted.min(self.face);
+        self.face = self.face.saturating_sub(taken);
+        taken
+    }
+
+    /// Sink the shaft one level, moving up to [`FACE_CAPACITY`] ore from
+    /// behind the face onto it.
+    ///
+    /// Returns the ore newly exposed, or `None` if there is none left to
+    /// reach.
+    pub fn deepen(&mut self) -> Option<u32> {
+        if !self.can_deepen() {
+            return None;
+        }
+        let room = FACE_CAPACITY.saturating_sub(self.face);
+        let opened = room.min(self.remaining);
+        self.face = self.face.saturating_add(opened);
+        self.remaining = self.remaining.saturating_sub(opened);
+        self.depth = self.depth.saturating_add(1);
+        Some(opened)
+    }
+
+    /// Bury the face under a cave-in: its ore falls back behind it, to be
+    /// reached again only by deepening.
+    pub const fn cave_in(&mut self) {
+        self.remaining = self.remaining.saturating_add(self.face);
+        self.face = 0;
+    }
+}
+
+/// Return the wood and tools needed to deepen a shaft that is `depth`
+/// levels deep.
+///
+/// The wood grows by [`DEEPEN_WOOD_PER_LEVEL`] per level; one tool is
+/// needed from the surface and one more for every two levels.
+pub fn deepen_cost(depth: u32) -> BTreeMap<Resource, u32> {
+    let wood = DEEPEN_BASE_WOOD.saturating_add(depth.saturating_mul(DEEPEN_WOOD_PER_LEVEL));
+    let tools = depth.saturating_div(2).saturating_add(1);
+    BTreeMap::from([(Resource::Wood, wood), (Resource::Tool, tools)])
+}
+
+/// Return the roll in `0..100` for `agent` mining at `tick`.
+///
+/// The shaft caves in when the roll is below its collapse risk.
+pub fn collapse_roll(agent: AgentId, tick: u64) -> u32 {
+    let (high, low) = agent.into_inner().as_u64_pair();
+    let folded = high ^ low ^ CAVE_IN_SALT;
+    let value = deterministic_random(folded, tick).checked_rem(100).unwrap_or(0);
+    u32::try_from(value).unwrap_or(0)
+}
+
+/// What an action did to an ore body, reported by the action handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MineWork {
+    /// The given ore was taken from the face.
+    Mined(u32),
+    /// The shaft was sunk one level.
+    Deepened,
+    /// The shaft caved in, burying the face.
+    CavedIn,
+}
+
+// ---------------------------------------------------------------------------
+// MineRegistry
+// ---------------------------------------------------------------------------
+
+/// The ore bodies of every location that has ore.
+///
+/// A location with no body tracked here is mined without limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MineRegistry {
+    bodies: BTreeMap<LocationId, OreBody>,
+}
+
+impl MineRegistry {
+    /// Create a registry with no ore bodies.
+    pub const fn new() -> Self {
+        Self {
+            bodies: BTreeMap::new(),
+        }
+    }
+
+    /// Survey every location of `map` with a known ore node.
+    pub fn from_map(map: &WorldMap) -> Self {
+        let mut registry = Self::new();
+        for id in map.location_ids() {
+            registry.survey(map, id);
+        }
+        registry
+    }
+
+    /// Track the ore body beneath `location` if it has a known ore node
+    /// and none is tracked yet, sized by [`ORE_RESERVE_PER_CAPACITY`].
+    ///
+    /// Called for deposits found by prospecting, as well as from
+    /// [`from_map`](Self::from_map).
+    pub fn survey(&mut self, map: &WorldMap, location: LocationId) {
+        if self.bodies.contains_key(&location) {
+            return;
+        }
+        let node = map
+            .get_location(location)
+            .and_then(|l| l.resources().get(&Resource::Ore));
+        if let Some(node) = node {
+            let reserve = node.max_capacity.saturating_mul(ORE_RESERVE_PER_CAPACITY);
+            self.bodies.insert(location, OreBody::new(reserve));
+        }
+    }
+
+    /// Set the ore body of `location`, replacing any it had.
+    pub fn insert(&mut self, location: LocationId, body: OreBody) {
+        self.bodies.insert(location, body);
+    }
+
+    /// Return the ore body of `location`, if one is tracked.
+    pub fn get(&self, location: LocationId) -> Option<&OreBody> {
+        self.bodies.get(&location)
+    }
+
+    /// Take up to `wanted` ore from the face at `location`.
+    ///
+    /// Returns the ore taken: all of `wanted` where no body is tracked.
+    pub fn extract(&mut self, location: LocationId, wanted: u32) -> u32 {
+        self.bodies
+            .get_mut(&location)
+            .map_or(wanted, |body| body.extract(wanted))
+    }
+
+    /// Deepen the shaft at `location`, returning the ore newly exposed.
+    pub fn deepen(&mut self, location: LocationId) -> Option<u32> {
+        self.bodies.get_mut(&location).and_then(OreBody::deepen)
+    }
+
+    /// Bury the face at `location` under a cave-in.
+    pub fn cave_in(&mut self, location: LocationId) {
+        if let Some(body) = self.bodies.get_mut(&location) {
+            body.cave_in();
+        }
+    }
+
+    /// Apply `work` done at `location` to its ore body, if one is tracked.
+    pub fn record(&mut self, location: LocationId, work: MineWork) {
+        match work {
+            MineWork::Mined(ore) => {
+                self.extract(location, ore);
+            }
+            MineWork::Deepened => {
+                self.deepen(location);
+            }
+            MineWork::CavedIn => self.cave_in(location),
+        }
+    }
+
+    /// Return the number of tracked bodies with no ore left.
+    pub fn exhausted_count(&self) -> usize {
+        self.bodies.values().filter(|b| b.is_exhausted()).count()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_worked_out_face_yields_nothing_until_deepened() {
+        let mut body = OreBody::new(100);
+        assert_eq!(body.face, FACE_CAPACITY);
+        assert_eq!(body.total(), 100);
+
+        assert_eq!(body.extract(30), 30);
+        assert_eq!(body.extract(30), 10);
+        assert_eq!(body.extract(30), 0);
+
+        assert_eq!(body.deepen(), Some(FACE_CAPACITY));
+        assert_eq!(body.deepen(), Some(0));
+        assert_eq!(body.extract(100), FACE_CAPACITY);
+        assert_eq!(body.deepen(), Some(20));
+        assert_eq!(body.extract(100), 20);
+        assert!(body.is_exhausted());
+        assert_eq!(body.deepen(), None);
+        assert_eq!(body.depth, 3);
+    }
+
+    #[test]
+    fn deeper_shafts_cost_more_and_risk_more() {
+        let surface = deepen_cost(0);
+        assert_eq!(surface.get(&Resource::Wood), Some(&DEEPEN_BASE_WOOD));
+        assert_eq!(surface.get(&Resource::Tool), Some(&1));
+        let deep = deepen_cost(4);
+        assert_eq!(deep.get(&Resource::Wood), Some(&30));
+        assert_eq!(deep.get(&Resource::Tool), Some(&3));
+
+        let mut body = OreBody::new(1000);
+        assert_eq!(body.collapse_risk_pct(), 0);
+        body.deepen();
+        assert_eq!(body.collapse_risk_pct(), COLLAPSE_RISK_PER_LEVEL_PCT);
+        body.depth = 100;
+        assert_eq!(body.collapse_risk_pct(), MAX_COLLAPSE_RISK_PCT);
+    }
+
+    #[test]
+    fn a_cave_in_buries_the_face_but_keeps_the_ore() {
+        let mut body = OreBody::new(60);
+        body.cave_in();
+        assert_eq!(body.face, 0);
+        assert_eq!(body.total(), 60);
+        assert_eq!(body.deepen(), Some(FACE_CAPACITY));
+    }
+
+    #[test]
+    fn from_map_tracks_ore_locations_only() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let mut registry = MineRegistry::from_map(&map);
+        let ore_locations = map
+            .locations()
+            .filter(|(_, state)| state.resources().contains_key(&Resource::Ore))
+            .count();
+        assert!(ore_locations > 0);
+        assert_eq!(registry.bodies.len(), ore_locations);
+        assert_eq!(registry.exhausted_count(), 0);
+
+        // Locations without a body are mined without limit.
+        assert!(registry.get(ids.beach).is_none());
+        assert_eq!(registry.extract(ids.beach, 7), 7);
+    }
+
+    #[test]
+    fn collapse_rolls_are_reproducible() {
+        let agent = AgentId::new();
+        assert_eq!(collapse_roll(agent, 5), collapse_roll(agent, 5));
+        assert!(collapse_roll(agent, 5) < 100);
+    }
+}
@@ -0,0 +1,228 @@
+//! Paths through the world graph, and a cache of them.
+//!
+//! [`WorldMap::find_path`] runs A* over the routes, weighing each by its
+//! travel cost in the weather. Given a [`Traveler`], it also keeps to the
+//! routes their access lists allow and steers around tolls charged to
+//! that traveler.
+//!
+//! Perception wants the same paths for every agent at a location, tick
+//! after tick, while the routes rarely change. A [`RouteCache`] remembers
+//! the paths it has found for the map's current
+//! [`generation`](WorldMap::generation) and weather, and forgets them all
+//! as soon as either changes.
+
+use std::collections::BTreeMap;
+
+use emergence_types::{AgentId, GroupId, LocationId, Route, RouteId, Weather};
+
+use crate::route;
+use crate::world_map::WorldMap;
+
+/// Extra ticks a route that charges the traveler a toll counts for when
+/// choosing a path.
+pub const TOLL_WEIGHT_TICKS: u32 = 2;
+
+/// The agent a path is found for, whose access and tolls depend on who
+/// they are.
+#[derive(Debug, Clone, Copy)]
+pub struct Traveler<'a> {
+    /// The travelling agent.
+    pub agent: AgentId,
+    /// The groups the agent belongs to.
+    pub groups: &'a [GroupId],
+}
+
+/// A path between two locations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    /// The locations along the path, from start to goal inclusive.
+    pub locations: Vec<LocationId>,
+    /// The route taken for each leg, one fewer than the locations.
+    pub routes: Vec<RouteId>,
+    /// The ticks the whole path takes to travel.
+    pub ticks: u32,
+}
+
+/// Return the extra weight of `route` for `agent`: [`TOLL_WEIGHT_TICKS`]
+/// if it charges a toll and the agent did not build it, otherwise zero.
+pub fn toll_weight(route: &Route, agent: AgentId) -> u32 {
+    if route::toll_cost(route).is_some() && route.built_by != Some(agent) {
+        TOLL_WEIGHT_TICKS
+    } else {
+        0
+    }
+}
+
+/// Paths found on a map, kept until its routes or the weather change.
+///
+/// Cached paths are found without a traveler, so they ignore access lists
+/// and tolls.
+#[derive(Debug, Clone, Default)]
+pub struct RouteCache {
+    /// The map generation the paths were found in.
+    generation: u64,
+    /// The weather the paths were found in.
+    weather: Option<Weather>,
+    /// The path from each start to each goal, or `None` where none exists.
+    paths: BTreeMap<(LocationId, LocationId), Option<Path>>,
+}
+
+impl RouteCache {
+    /// Create an empty cache.
+    pub const fn new() -> Self {
+        Self {
+            generation: 0,
+            weather: None,
+            paths: BTreeMap::new(),
+        }
+    }
+
+    /// Return the quickest path from `start` to `goal` on `map` in
+    /// `weather`, finding it only if it is not already cached.
+    pub fn path(
+        &mut self,
+        map: &WorldMap,
+        start: LocationId,
+        goal: LocationId,
+        weather: Weather,
+    ) -> Option<&Path> {
+        if self.generation != map.generation() || self.weather != Some(weather) {
+            self.paths.clear();
+            self.generation = map.generation();
+            self.weather = Some(weather);
+        }
+        self.paths
+            .entry((start, goal))
+            .or_insert_with(|| map.find_path(start, goal, weather, None))
+            .as_ref()
+    }
+
+    /// Return the number of paths cached, including those found not to
+    /// exist.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Return whether no paths are cached.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use emergence_types::{AccessControlList, PathType, Resource};
+
+    use super::*;
+    use crate::create_starting_world;
+
+    #[test]
+    fn travelers_avoid_barred_and_tolled_routes() {
+        let (mut map, ids) = create_starting_world().unwrap();
+        let agent = AgentId::new();
+        let traveler = Traveler { agent, groups: &[] };
+        let open = map
+            .find_path(ids.riverbank, ids.beach, Weather::Clear, None)
+            .unwrap();
+        assert_eq!(open.routes.len().saturating_add(1), open.locations.len());
+
+        let first = *open.routes.first().unwrap();
+        map.get_route_mut(first).unwrap().acl = Some(AccessControlList {
+            allowed_agents: BTreeSet::new(),
+            allowed_groups: BTreeSet::new(),
+            denied_agents: BTreeSet::from([agent]),
+            public: false,
+            toll_cost: None,
+        });
+        let detour = map.find_path(ids.riverbank, ids.beach, Weather::Clear, Some(traveler));
+        assert!(detour.is_none_or(|path| !path.routes.contains(&first)));
+
+        // A toll only steers the path; open to all, it still counts ticks.
+        let route = map.get_route_mut(first).unwrap();
+        route.acl = Some(AccessControlList {
+            allowed_agents: BTreeSet::new(),
+            allowed_groups: BTreeSet::new(),
+            denied_agents: BTreeSet::new(),
+            public: true,
+            toll_cost: Some(BTreeMap::from([(Resource::Wood, 1)])),
+        });
+        assert_eq!(toll_weight(route, agent), TOLL_WEIGHT_TICKS);
+        route.built_by = Some(agent);
+        assert_eq!(toll_weight(route, agent), 0);
+        let own_road = map.find_path(ids.riverbank, ids.beach, Weather::Clear, Some(traveler));
+        assert_eq!(own_road, Some(open));
+    }
+
+    #[test]
+    fn a_star_matches_travel_time_and_blocks_in_storms() {
+        let (map, ids) = create_starting_world().unwrap();
+        for target in map.location_ids() {
+            let path = map
+                .find_path(ids.riverbank, target, Weather::Rain, None)
+                .unwrap();
+            assert_eq!(path.locations.first(), Some(&ids.riverbank));
+            assert_eq!(path.locations.last(), Some(&target));
+            let legs = path.locations.windows(2).zip(&path.routes);
+            let ticks = legs.fold(0_u32, |total, (leg, id)| {
+                let route = map.get_route(*id).unwrap();
+                let cost = map
+                    .travel_cost(route, leg[0], leg[1], Weather::Rain)
+                    .unwrap();
+                total.saturating_add(cost.unwrap())
+            });
+            assert_eq!(path.ticks, ticks);
+        }
+        assert!(
+            map.find_path(ids.riverbank, ids.beach, Weather::Storm, None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn cache_forgets_paths_when_routes_or_weather_change() {
+        let (mut map, ids) = create_starting_world().unwrap();
+        let mut cache = RouteCache::new();
+        let before = cache
+            .path(&map, ids.riverbank, ids.beach, Weather::Clear)
+            .cloned();
+        cache.path(&map, ids.beach, ids.riverbank, Weather::Clear);
+        assert_eq!(cache.len(), 2);
+
+        // The same query is answered from the cache.
+        assert_eq!(
+            cache
+                .path(&map, ids.riverbank, ids.beach, Weather::Clear)
+                .cloned(),
+            before
+        );
+        assert_eq!(cache.len(), 2);
+
+        cache.path(&map, ids.riverbank, ids.beach, Weather::Rain);
+        assert_eq!(cache.len(), 1);
+
+        // A highway straight to the beach replaces the cached path.
+        map.add_route(Route {
+            id: RouteId::new(),
+            from_location: ids.riverbank,
+            to_location: ids.beach,
+            cost_ticks: 1,
+            path_type: PathType::Highway,
+            durability: 100,
+            max_durability: 100,
+            decay_per_tick: rust_decimal::Decimal::ZERO,
+            acl: None,
+            bidirectional: true,
+            built_by: None,
+            built_at_tick: None,
+        })
+        .unwrap();
+        let after = cache
+            .path(&map, ids.riverbank, ids.beach, Weather::Rain)
+            .unwrap();
+        assert_eq!(after.locations, vec![ids.riverbank, ids.beach]);
+        assert_eq!(cache.len(), 1);
+    }
+}
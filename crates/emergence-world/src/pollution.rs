@@ -0,0 +1,124 @@
+//! Pollution and the slow degradation of the land.
+//!
+//! Every location carries a pollution level from 0 (clean) to
+//! [`MAX_POLLUTION`], kept in [`LocationState::pollution`]. It rises when
+//! agents work the land hard:
+//!
+//! - each successful `Smelt` adds [`SMELT_POLLUTION`] and each `Mine` adds
+//!   [`MINE_POLLUTION`] at the worker's location (see [`action_pollution`]),
+//!   and
+//! - each tick, every agent over the location's crowding threshold adds
+//!   [`CROWDING_POLLUTION_PER_AGENT`].
+//!
+//! Pollution then falls by [`DECAY_PER_TICK`], so a location left alone
+//! recovers, but slowly. While it lasts, a polluted location
+//!
+//! - yields less from its farm plots: one percent of the harvest lost per
+//!   two points of pollution (see [`farm_yield_pct`]), and
+//! - regrows its water more slowly: three percent of regeneration lost per
+//!   four points (see [`water_regen_penalty_pct`]).
+//!
+//! [`LocationState::pollution`]: crate::LocationState
+
+use emergence_types::ActionType;
+
+use crate::crowding;
+use crate::location::LocationState;
+
+/// The highest pollution level a location can reach.
+pub const MAX_POLLUTION: u32 = 100;
+
+/// Pollution added by each successful `Smelt`.
+pub const SMELT_POLLUTION: u32 = 4;
+
+/// Pollution added by each successful `Mine`.
+pub const MINE_POLLUTION: u32 = 2;
+
+/// Pollution added each tick per agent over the crowding threshold.
+pub const CROWDING_POLLUTION_PER_AGENT: u32 = 2;
+
+/// Pollution that disperses from every location each tick.
+pub const DECAY_PER_TICK: u32 = 1;
+
+/// Pollution level at and above which agents are told the land is fouled.
+pub const NOTICEABLE_POLLUTION: u32 = 20;
+
+/// Return the pollution a successful action of `action_type` leaves at the
+/// actor's location.
+pub const fn action_pollution(action_type: ActionType) -> u32 {
+    match action_type {
+        ActionType::Smelt => SMELT_POLLUTION,
+        ActionType::Mine => MINE_POLLUTION,
+        _ => 0,
+    }
+}
+
+/// Return the percentage of the usual farm harvest a location with
+/// `pollution` yields.
+pub fn farm_yield_pct(pollution: u32) -> u32 {
+    100_u32.saturating_sub(pollution.min(MAX_POLLUTION).saturating_div(2))
+}
+
+/// Return the percentage of water regeneration lost at a location with
+/// `pollution`.
+pub fn water_regen_penalty_pct(pollution: u32) -> u32 {
+    pollution.min(MAX_POLLUTION).saturating_mul(3).saturating_div(4)
+}
+
+/// Add `amount` pollution to `state`, up to [`MAX_POLLUTION`].
+pub fn pollute(state: &mut LocationState, amount: u32) {
+    state.pollution = state.pollution.saturating_add(amount).min(MAX_POLLUTION);
+}
+
+/// Advance the pollution of `state` by one tick: crowding adds to it, then
+/// it decays by [`DECAY_PER_TICK`].
+pub fn tick(state: &mut LocationState) {
+    let excess = crowding::excess_occupants(state.location.capacity, state.occupant_count());
+    pollute(state, excess.saturating_mul(CROWDING_POLLUTION_PER_AGENT));
+    state.pollution = state.pollution.saturating_sub(DECAY_PER_TICK);
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::AgentId;
+
+    use super::*;
+
+    #[test]
+    fn industry_pollutes_and_the_land_recovers_slowly() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let state = map.get_location_mut(ids.open_field).unwrap();
+        assert_eq!(action_pollution(ActionType::Smelt), SMELT_POLLUTION);
+        assert_eq!(action_pollution(ActionType::Gather), 0);
+
+        pollute(state, 30);
+        tick(state);
+        assert_eq!(state.pollution, 29);
+        pollute(state, 500);
+        assert_eq!(state.pollution, MAX_POLLUTION);
+    }
+
+    #[test]
+    fn crowding_outpaces_decay() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let state = map.get_location_mut(ids.open_field).unwrap();
+        state.location.capacity = 4;
+        for _ in 0..4 {
+            state.add_occupant(AgentId::new()).unwrap();
+        }
+        tick(state);
+        tick(state);
+        assert_eq!(state.pollution, 2);
+    }
+
+    #[test]
+    fn pollution_degrades_farms_and_water() {
+        assert_eq!(farm_yield_pct(0), 100);
+        assert_eq!(farm_yield_pct(40), 80);
+        assert_eq!(farm_yield_pct(1_000), 50);
+        assert_eq!(water_regen_penalty_pct(0), 0);
+        assert_eq!(water_regen_penalty_pct(40), 30);
+        assert_eq!(water_regen_penalty_pct(MAX_POLLUTION), 75);
+    }
+}
@@ -0,0 +1,123 @@
+//! Hidden resource deposits and the odds of finding them.
+//!
+//! Some locations hold resource nodes no one knows about. A hidden node is
+//! kept in [`LocationState::hidden_resources`](crate::LocationState) and
+//! cannot be gathered from, mined, or seen in perception until an agent
+//! discovers it with the `Prospect` action, which moves it into the
+//! location's known resources.
+//!
+//! Each attempt succeeds with a probability of [`BASE_CHANCE_PCT`], plus
+//! [`SKILL_BONUS_PCT`] per level of prospecting skill and a bonus for each
+//! piece of relevant knowledge in [`KNOWLEDGE_BONUSES`], capped at
+//! [`MAX_CHANCE_PCT`]. The roll is derived from the agent and tick, so
+//! a replayed tick finds the same deposits.
+
+use std::collections::BTreeSet;
+
+use emergence_types::{AgentId, LocationId, ResourceNode};
+
+use crate::environment::deterministic_random;
+use crate::error::WorldError;
+use crate::world_map::WorldMap;
+
+/// Chance in percent that an unskilled, unschooled agent finds a deposit.
+pub const BASE_CHANCE_PCT: u32 = 15;
+
+/// Added chance in percent per level of prospecting skill.
+pub const SKILL_BONUS_PCT: u32 = 5;
+
+/// The highest chance in percent any prospector has.
+pub const MAX_CHANCE_PCT: u32 = 90;
+
+/// Knowledge that helps an agent read the ground, and the chance in percent
+/// each adds.
+pub const KNOWLEDGE_BONUSES: &[(&str, u32)] = &[
+    ("gather_stone", 10),
+    ("mining", 20),
+    ("masonry", 10),
+];
+
+/// Return the chance in percent that a prospecting attempt succeeds for an
+/// agent with `skill_level` in prospecting and the given `knowledge`.
+pub fn success_chance_pct(skill_level: u32, knowledge: &BTreeSet<String>) -> u32 {
+    let from_knowledge: u32 = KNOWLEDGE_BONUSES
+        .iter()
+        .filter(|(concept, _)| knowledge.contains(*concept))
+        .map(|(_, bonus)| *bonus)
+        .fold(0, u32::saturating_add);
+    BASE_CHANCE_PCT
+        .saturating_add(skill_level.saturating_mul(SKILL_BONUS_PCT))
+        .saturating_add(from_knowledge)
+        .min(MAX_CHANCE_PCT)
+}
+
+/// Return the roll in `0..100` for `agent` prospecting at `tick`.
+///
+/// An attempt succeeds when the roll is below its success chance.
+pub fn roll(agent: AgentId, tick: u64) -> u32 {
+    let (high, low) = agent.into_inner().as_u64_pair();
+    let folded = high ^ low;
+    let value = deterministic_random(folded, tick).checked_rem(100).unwrap_or(0);
+    u32::try_from(value).unwrap_or(0)
+}
+
+/// Hide `node` at `location` until it is prospected.
+///
+/// # Errors
+///
+/// Returns [`WorldError::LocationNotFound`] if the map has no such location.
+pub fn bury_deposit(
+    map: &mut WorldMap,
+    location: LocationId,
+    node: ResourceNode,
+) -> Result<(), WorldError> {
+    let state = map
+        .get_location_mut(location)
+        .ok_or(WorldError::LocationNotFound(location))?;
+    state.hide_resource(node);
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::Resource;
+
+    use super::*;
+
+    #[test]
+    fn chance_grows_with_skill_and_knowledge_up_to_the_cap() {
+        let mut knowledge = BTreeSet::new();
+        assert_eq!(success_chance_pct(0, &knowledge), BASE_CHANCE_PCT);
+        assert_eq!(success_chance_pct(2, &knowledge), 25);
+
+        knowledge.insert(String::from("mining"));
+        knowledge.insert(String::from("gather_stone"));
+        knowledge.insert(String::from("cooking"));
+        assert_eq!(success_chance_pct(2, &knowledge), 55);
+        assert_eq!(success_chance_pct(20, &knowledge), MAX_CHANCE_PCT);
+    }
+
+    #[test]
+    fn rolls_are_reproducible_and_in_range() {
+        let agent = AgentId::new();
+        assert_eq!(roll(agent, 7), roll(agent, 7));
+        assert!((0..200).all(|tick| roll(agent, tick) < 100));
+    }
+
+    #[test]
+    fn buried_deposits_stay_hidden() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let ore = ResourceNode {
+            resource: Resource::Ore,
+            available: 5,
+            regen_per_tick: 0,
+            max_capacity: 5,
+        };
+        bury_deposit(&mut map, ids.open_field, ore.clone()).unwrap();
+        let field = map.get_location(ids.open_field).unwrap();
+        assert!(field.hidden_resources.contains_key(&Resource::Ore));
+        assert!(field.get_resource(&Resource::Ore).is_none());
+        assert!(bury_deposit(&mut map, LocationId::new(), ore).is_err());
+    }
+}
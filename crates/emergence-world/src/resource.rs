@@ -5,18 +5,119 @@
 //! during the World Wake phase of the tick cycle and is capped so that
 //! `available` never exceeds `max_capacity`.
 //!
-//! Season modifiers adjust the effective regeneration rate:
-//! - Spring: +25%
-//! - Summer: normal
-//! - Autumn: -25%
-//! - Winter: -75%
+//! `regen_per_tick` is a base rate, scaled each tick by two season curves
+//! from [`RegenCurves`]: one for the resource and one for the biome the
+//! location lies in. By default most resources regrow 25% faster in
+//! spring and 75% slower in winter; berries crowd into summer, roots into
+//! autumn, and neither regrows at all in winter; in the highlands nothing
+//! does. The rate is rounded down, so slow nodes stall off-season.
 
-use emergence_types::{ResourceNode, Season};
+use std::collections::BTreeMap;
 
+use emergence_types::{Resource, ResourceNode, Season};
+use serde::{Deserialize, Serialize};
+
+use crate::biome::Biome;
 use crate::error::WorldError;
+use crate::metrics;
+
+/// Regeneration through the year, as a percentage of the base rate in
+/// each season.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonCurve {
+    /// Percentage of the base rate in spring.
+    pub spring: u32,
+    /// Percentage of the base rate in summer.
+    pub summer: u32,
+    /// Percentage of the base rate in autumn.
+    pub autumn: u32,
+    /// Percentage of the base rate in winter.
+    pub winter: u32,
+}
+
+impl SeasonCurve {
+    /// Create a curve from its spring, summer, autumn, and winter
+    /// percentages.
+    pub const fn new(spring: u32, summer: u32, autumn: u32, winter: u32) -> Self {
+        Self {
+            spring,
+            summer,
+            autumn,
+            winter,
+        }
+    }
+
+    /// Return the percentage of the base rate in `season`.
+    pub const fn pct(&self, season: Season) -> u32 {
+        match season {
+            Season::Spring => self.spring,
+            Season::Summer => self.summer,
+            Season::Autumn => self.autumn,
+            Season::Winter => self.winter,
+        }
+    }
+}
 
-/// Apply one tick of regeneration to a [`ResourceNode`], respecting the
-/// seasonal modifier.
+impl Default for SeasonCurve {
+    fn default() -> Self {
+        Self::new(125, 100, 75, 25)
+    }
+}
+
+/// The season curves regeneration follows.
+///
+/// A resource without a curve of its own follows `default`; a biome
+/// without one regrows at the resource's rate. Configured maps replace the
+/// defaults whole, so a configuration listing only berries leaves roots on
+/// the `default` curve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegenCurves {
+    /// The curve for resources not listed in `resources`.
+    pub default: SeasonCurve,
+    /// The curve for each listed resource.
+    pub resources: BTreeMap<Resource, SeasonCurve>,
+    /// How each listed biome scales the resource's curve.
+    pub biomes: BTreeMap<Biome, SeasonCurve>,
+}
+
+impl Default for RegenCurves {
+    fn default() -> Self {
+        Self {
+            default: SeasonCurve::default(),
+            resources: BTreeMap::from([
+                (Resource::FoodBerry, SeasonCurve::new(50, 200, 100, 0)),
+                (Resource::FoodRoot, SeasonCurve::new(100, 100, 150, 0)),
+                (Resource::Fiber, SeasonCurve::new(125, 150, 50, 0)),
+                (Resource::Water, SeasonCurve::new(150, 75, 100, 50)),
+            ]),
+            biomes: BTreeMap::from([
+                (Biome::Highland, SeasonCurve::new(75, 100, 75, 0)),
+                (Biome::Coastal, SeasonCurve::new(100, 100, 100, 200)),
+                (Biome::Wetland, SeasonCurve::new(125, 125, 100, 50)),
+                (Biome::Steppe, SeasonCurve::new(100, 50, 75, 50)),
+            ]),
+        }
+    }
+}
+
+impl RegenCurves {
+    /// Return the regeneration rate of a node of `resource` with base
+    /// rate `base` in `season` and `biome`, rounded down.
+    pub fn rate(&self, resource: Resource, base: u32, season: Season, biome: Biome) -> u32 {
+        let resource_pct = self.resources.get(&resource).unwrap_or(&self.default).pct(season);
+        let biome_pct = self.biomes.get(&biome).map_or(100, |curve| curve.pct(season));
+        let rate = u64::from(base)
+            .saturating_mul(u64::from(resource_pct))
+            .saturating_mul(u64::from(biome_pct))
+            .checked_div(10_000)
+            .unwrap_or(0);
+        u32::try_from(rate).unwrap_or(u32::MAX)
+    }
+}
+
+/// Apply one tick of regeneration to a [`ResourceNode`] in `biome`,
+/// following `curves` for `season`.
 ///
 /// Returns the number of units actually regenerated (may be zero if the
 /// node is already at capacity or the seasonal rate rounds to zero).
@@ -24,12 +125,41 @@ use crate::error::WorldError;
 /// # Errors
 ///
 /// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
-pub fn regenerate(node: &mut ResourceNode, season: Season) -> Result<u32, WorldError> {
+pub fn regenerate(
+    node: &mut ResourceNode,
+    season: Season,
+    biome: Biome,
+    curves: &RegenCurves,
+) -> Result<u32, WorldError> {
+    regenerate_penalized(node, season, biome, curves, 0)
+}
+
+/// Apply one tick of regeneration to a [`ResourceNode`], with the seasonal
+/// rate reduced by `penalty_pct` percent (rounded down).
+///
+/// Used for overcrowded locations; see [`crate::crowding`].
+///
+/// # Errors
+///
+/// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
+pub fn regenerate_penalized(
+    node: &mut ResourceNode,
+    season: Season,
+    biome: Biome,
+    curves: &RegenCurves,
+    penalty_pct: u32,
+) -> Result<u32, WorldError> {
     if node.available >= node.max_capacity {
         return Ok(0);
     }
 
-    let effective_regen = seasonal_regen(node.regen_per_tick, season)?;
+    let kept_pct = u64::from(100_u32.saturating_sub(penalty_pct));
+    let seasonal = u64::from(curves.rate(node.resource, node.regen_per_tick, season, biome));
+    let effective_regen = seasonal
+        .saturating_mul(kept_pct)
+        .checked_div(100)
+        .and_then(|regen| u32::try_from(regen).ok())
+        .ok_or(WorldError::ArithmeticOverflow)?;
 
     let headroom = node
         .max_capacity
@@ -42,33 +172,11 @@ pub fn regenerate(node: &mut ResourceNode, season: Season) -> Result<u32, WorldE
         .checked_add(added)
         .ok_or(WorldError::ArithmeticOverflow)?;
 
-    Ok(added)
-}
-
-/// Calculate the seasonal regeneration rate from a base rate.
-///
-/// The modifier is applied as integer arithmetic to avoid floating-point:
-/// - Spring: `base * 5 / 4` (+25%)
-/// - Summer: `base` (no change)
-/// - Autumn: `base * 3 / 4` (-25%)
-/// - Winter: `base / 4`     (-75%)
-///
-/// # Errors
-///
-/// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
-fn seasonal_regen(base: u32, season: Season) -> Result<u32, WorldError> {
-    match season {
-        Season::Spring => base
-            .checked_mul(5)
-            .and_then(|v| v.checked_div(4))
-            .ok_or(WorldError::ArithmeticOverflow),
-        Season::Summer => Ok(base),
-        Season::Autumn => base
-            .checked_mul(3)
-            .and_then(|v| v.checked_div(4))
-            .ok_or(WorldError::ArithmeticOverflow),
-        Season::Winter => base.checked_div(4).ok_or(WorldError::ArithmeticOverflow),
+    if added > 0 {
+        metrics::RESOURCES_REGENERATED
+            .increment_with(&format!("{:?}", node.resource), u64::from(added));
     }
+    Ok(added)
 }
 
 /// Deduct a quantity from a resource node, returning the actual amount taken.
@@ -90,10 +198,12 @@ pub fn harvest(node: &mut ResourceNode, requested: u32) -> Result<u32, WorldErro
 
 #[cfg(test)]
 mod tests {
-    use emergence_types::Resource;
-
     use super::*;
 
+    fn regen(node: &mut ResourceNode, season: Season) -> Result<u32, WorldError> {
+        regenerate(node, season, Biome::Temperate, &RegenCurves::default())
+    }
+
     fn make_node(available: u32, regen: u32, max: u32) -> ResourceNode {
         ResourceNode {
             resource: Resource::Wood,
@@ -106,7 +216,7 @@ mod tests {
     #[test]
     fn regen_summer_normal() {
         let mut node = make_node(40, 10, 100);
-        let added = regenerate(&mut node, Season::Summer);
+        let added = regen(&mut node, Season::Summer);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(10));
         assert_eq!(node.available, 50);
@@ -115,7 +225,7 @@ mod tests {
     #[test]
     fn regen_capped_at_max() {
         let mut node = make_node(95, 10, 100);
-        let added = regenerate(&mut node, Season::Summer);
+        let added = regen(&mut node, Season::Summer);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(5));
         assert_eq!(node.available, 100);
@@ -124,7 +234,7 @@ mod tests {
     #[test]
     fn regen_already_full() {
         let mut node = make_node(100, 10, 100);
-        let added = regenerate(&mut node, Season::Summer);
+        let added = regen(&mut node, Season::Summer);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(0));
         assert_eq!(node.available, 100);
@@ -134,7 +244,7 @@ mod tests {
     fn regen_spring_bonus() {
         // Spring: 10 * 5 / 4 = 12 (integer)
         let mut node = make_node(0, 10, 100);
-        let added = regenerate(&mut node, Season::Spring);
+        let added = regen(&mut node, Season::Spring);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(12));
         assert_eq!(node.available, 12);
@@ -144,7 +254,7 @@ mod tests {
     fn regen_autumn_reduction() {
         // Autumn: 10 * 3 / 4 = 7 (integer)
         let mut node = make_node(0, 10, 100);
-        let added = regenerate(&mut node, Season::Autumn);
+        let added = regen(&mut node, Season::Autumn);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(7));
         assert_eq!(node.available, 7);
@@ -154,7 +264,7 @@ mod tests {
     fn regen_winter_severe_reduction() {
         // Winter: 10 / 4 = 2 (integer)
         let mut node = make_node(0, 10, 100);
-        let added = regenerate(&mut node, Season::Winter);
+        let added = regen(&mut node, Season::Winter);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(2));
         assert_eq!(node.available, 2);
@@ -164,7 +274,7 @@ mod tests {
     fn regen_winter_rounds_to_zero() {
         // Winter: 3 / 4 = 0 (integer division)
         let mut node = make_node(0, 3, 100);
-        let added = regenerate(&mut node, Season::Winter);
+        let added = regen(&mut node, Season::Winter);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(0));
         assert_eq!(node.available, 0);
@@ -174,12 +284,46 @@ mod tests {
     fn regen_zero_base_rate() {
         // Stone nodes typically have regen_per_tick = 0
         let mut node = make_node(8, 0, 8);
-        let added = regenerate(&mut node, Season::Summer);
+        let added = regen(&mut node, Season::Summer);
         assert!(added.is_ok());
         assert_eq!(added.ok(), Some(0));
         assert_eq!(node.available, 8);
     }
 
+    #[test]
+    fn berries_crowd_into_summer_and_stop_in_winter() {
+        let curves = RegenCurves::default();
+        let mut node = make_node(0, 10, 100);
+        node.resource = Resource::FoodBerry;
+        let summer = regenerate(&mut node, Season::Summer, Biome::Temperate, &curves);
+        assert_eq!(summer.ok(), Some(20));
+        let winter = regenerate(&mut node, Season::Winter, Biome::Temperate, &curves);
+        assert_eq!(winter.ok(), Some(0));
+        assert_eq!(node.available, 20);
+    }
+
+    #[test]
+    fn biomes_scale_the_resource_curve() {
+        let curves = RegenCurves::default();
+        // Wood in winter: 10 * 25% = 2 on the coast's doubled rate is 5.
+        assert_eq!(curves.rate(Resource::Wood, 10, Season::Winter, Biome::Coastal), 5);
+        assert_eq!(curves.rate(Resource::Wood, 10, Season::Winter, Biome::Highland), 0);
+        // Berries on the steppe: 10 * 200% * 50% = 10 in summer.
+        assert_eq!(curves.rate(Resource::FoodBerry, 10, Season::Summer, Biome::Steppe), 10);
+    }
+
+    #[test]
+    fn configured_curves_replace_the_defaults() {
+        let curves: RegenCurves = serde_json::from_str(
+            r#"{"resources": {"FoodBerry": {"spring": 0, "summer": 300, "autumn": 0, "winter": 0}}}"#,
+        )
+        .unwrap_or_default();
+        assert_eq!(curves.rate(Resource::FoodBerry, 10, Season::Summer, Biome::Highland), 30);
+        // Roots fall back to the default curve: 10 * 75% * 75% = 5.
+        assert_eq!(curves.rate(Resource::FoodRoot, 10, Season::Autumn, Biome::Highland), 5);
+        assert_eq!(curves.default, SeasonCurve::default());
+    }
+
     #[test]
     fn harvest_full_amount() {
         let mut node = make_node(50, 5, 100);
@@ -18,13 +18,22 @@
 //! on the path type and weather conditions. When durability reaches 0, the
 //! route degrades one level (e.g. [`PathType::Road`] becomes
 //! [`PathType::WornPath`]).
+//!
+//! # Terrain
+//!
+//! Travel is slower over uneven ground. [`travel_cost`] adds a tick for
+//! every [`CLIMB_METERS_PER_TICK`] meters climbed and every
+//! [`DESCENT_METERS_PER_TICK`] meters descended between the two ends of a
+//! route. A route spanned by a [`StructureType::Bridge`] or cut through by
+//! a [`StructureType::Tunnel`] is flattened and pays no slope cost.
 
 use std::collections::BTreeMap;
 
-use emergence_types::{AgentId, GroupId, PathType, Resource, Route, Weather};
+use emergence_types::{AgentId, GroupId, PathType, Resource, Route, StructureType, Weather};
 use rust_decimal::Decimal;
 
 use crate::error::WorldError;
+use crate::metrics;
 
 /// Check whether a specific agent is permitted to traverse a route.
 ///
@@ -74,26 +83,83 @@ pub fn effective_travel_cost(
     route: &Route,
     weather: Weather,
 ) -> Result<Option<u32>, WorldError> {
+    let Some(delay) = weather_delay(weather) else {
+        return Ok(None); // Travel blocked
+    };
+    route
+        .cost_ticks
+        .checked_add(delay)
+        .map(Some)
+        .ok_or(WorldError::ArithmeticOverflow)
+}
+
+/// Return the ticks `weather` adds to any route, or `None` if it blocks
+/// travel (see [`effective_travel_cost`]).
+pub const fn weather_delay(weather: Weather) -> Option<u32> {
     match weather {
-        Weather::Storm => Ok(None), // Travel blocked
-        Weather::Clear | Weather::Drought => Ok(Some(route.cost_ticks)),
-        Weather::Rain => {
-            let cost = route
-                .cost_ticks
-                .checked_add(1)
-                .ok_or(WorldError::ArithmeticOverflow)?;
-            Ok(Some(cost))
-        }
-        Weather::Snow => {
-            let cost = route
-                .cost_ticks
-                .checked_add(2)
-                .ok_or(WorldError::ArithmeticOverflow)?;
-            Ok(Some(cost))
-        }
+        Weather::Storm => None,
+        Weather::Clear | Weather::Drought => Some(0),
+        Weather::Rain => Some(1),
+        Weather::Snow => Some(2),
     }
 }
 
+/// Meters of climb that add one tick of travel.
+pub const CLIMB_METERS_PER_TICK: u32 = 100;
+
+/// Meters of descent that add one tick of travel.
+pub const DESCENT_METERS_PER_TICK: u32 = 200;
+
+/// Return the extra ticks taken to travel from `from_elevation` to
+/// `to_elevation` (in meters), rounded down.
+pub fn slope_cost(from_elevation: i32, to_elevation: i32) -> u32 {
+    let rise = i64::from(to_elevation).saturating_sub(i64::from(from_elevation));
+    let per_tick = if rise >= 0 {
+        CLIMB_METERS_PER_TICK
+    } else {
+        DESCENT_METERS_PER_TICK
+    };
+    let ticks = rise.unsigned_abs().checked_div(u64::from(per_tick)).unwrap_or(0);
+    u32::try_from(ticks).unwrap_or(u32::MAX)
+}
+
+/// Calculate the travel cost for a route from a location at
+/// `from_elevation` to one at `to_elevation`, accounting for weather and,
+/// unless the route is `flattened`, the climb or descent.
+///
+/// Returns `None` when weather blocks travel (see
+/// [`effective_travel_cost`]).
+///
+/// # Errors
+///
+/// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
+pub fn travel_cost(
+    route: &Route,
+    weather: Weather,
+    from_elevation: i32,
+    to_elevation: i32,
+    flattened: bool,
+) -> Result<Option<u32>, WorldError> {
+    let Some(cost) = effective_travel_cost(route, weather)? else {
+        return Ok(None);
+    };
+    if flattened {
+        return Ok(Some(cost));
+    }
+    cost.checked_add(slope_cost(from_elevation, to_elevation))
+        .map(Some)
+        .ok_or(WorldError::ArithmeticOverflow)
+}
+
+/// Return whether a structure of `structure_type` flattens a route.
+///
+/// A [`StructureType::Bridge`] spans the steepest descent out of the
+/// location it is built at; a [`StructureType::Tunnel`] cuts through the
+/// steepest climb.
+pub const fn flattens_routes(structure_type: StructureType) -> bool {
+    matches!(structure_type, StructureType::Bridge | StructureType::Tunnel)
+}
+
 /// Return the base tick cost for a given [`PathType`].
 ///
 /// Values come from `data-schemas.md` section 3.8.
@@ -290,7 +356,9 @@ pub fn apply_route_decay(route: &mut Route, weather: Weather) -> Result<Option<P
     // but we also need checked arithmetic for the lint.
     let _ = decay_u32; // used for clarity above; actual loss comes from total_loss
     if total_loss > 0 {
+        let lost = route.durability.min(total_loss);
         route.durability = route.durability.saturating_sub(total_loss);
+        metrics::ROUTE_DECAY.increment(u64::from(lost));
     }
 
     // Check if route should degrade
@@ -305,6 +373,7 @@ pub fn apply_route_decay(route: &mut Route, weather: Weather) -> Result<Option<P
         route.decay_per_tick = Decimal::ZERO;
         // Only return degradation if we actually changed type
         if old_type != lower {
+            metrics::ROUTE_DEGRADATIONS.increment_with(&format!("{lower:?}"), 1);
             return Ok(Some(lower));
         }
     }
@@ -544,6 +613,25 @@ mod tests {
         assert_eq!(cost.ok().flatten(), Some(3));
     }
 
+    #[test]
+    fn climbing_costs_more_than_descending() {
+        assert_eq!(slope_cost(100, 100), 0);
+        assert_eq!(slope_cost(0, 350), 3);
+        assert_eq!(slope_cost(350, 0), 1);
+        assert_eq!(slope_cost(-50, 150), 2);
+    }
+
+    #[test]
+    fn travel_cost_adds_slope_unless_flattened() {
+        let route = make_route(3, PathType::WornPath);
+        let uphill = travel_cost(&route, Weather::Rain, 0, 400, false);
+        assert_eq!(uphill.ok().flatten(), Some(8));
+        let flattened = travel_cost(&route, Weather::Rain, 0, 400, true);
+        assert_eq!(flattened.ok().flatten(), Some(4));
+        let storm = travel_cost(&route, Weather::Storm, 0, 400, true);
+        assert_eq!(storm.ok().flatten(), None);
+    }
+
     #[test]
     fn base_costs_match_spec() {
         assert_eq!(base_cost_for_path_type(PathType::None), 8);
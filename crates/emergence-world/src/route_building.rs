@@ -0,0 +1,127 @@
+//! Building brand-new routes between locations that have none.
+//!
+//! Where [`ImproveRoute`](emergence_types::ActionType::ImproveRoute)
+//! upgrades an existing edge, the `BuildRoute` action lays a new one: a
+//! [`PathType::DirtTrail`] straight from the builder's location to another
+//! location it knows.
+//!
+//! # Length and cost
+//!
+//! A new trail cuts across country, so it is half as long as the quickest
+//! way round by the existing routes in clear weather, and at least one
+//! tick (see [`trail_length`]). Between places the graph does not connect
+//! at all it is [`UNCONNECTED_LENGTH`] ticks long. Every tick of length
+//! costs [`WOOD_PER_TICK`] wood and [`STONE_PER_TICK`] stone, paid by the
+//! agent who starts the work ([`material_cost`]).
+//!
+//! # Construction
+//!
+//! Building takes one `BuildRoute` action, by anyone, per tick of length.
+//! Work in progress is a [`RouteProject`] kept by the
+//! [`WorldMap`](crate::WorldMap); when it is finished the trail joins the
+//! map with the agent who started it recorded as its builder, the owner
+//! to whom any toll on it is paid.
+
+use std::collections::BTreeMap;
+
+use emergence_types::{AgentId, LocationId, PathType, Resource};
+use serde::{Deserialize, Serialize};
+
+/// Length in ticks of a trail between locations with no path between
+/// them.
+pub const UNCONNECTED_LENGTH: u32 = 8;
+
+/// Wood needed per tick of trail length.
+pub const WOOD_PER_TICK: u32 = 5;
+
+/// Stone needed per tick of trail length.
+pub const STONE_PER_TICK: u32 = 2;
+
+/// The path type of a newly built route.
+pub const NEW_ROUTE_PATH: PathType = PathType::DirtTrail;
+
+/// A route under construction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteProject {
+    /// The location the builder started from.
+    pub from: LocationId,
+    /// The location the route will reach.
+    pub to: LocationId,
+    /// The agent who started and paid for the work.
+    pub builder: AgentId,
+    /// Ticks of work done so far.
+    pub work_done: u32,
+    /// Length of the finished route in ticks, and the work it needs.
+    pub length: u32,
+}
+
+impl RouteProject {
+    /// Return whether this project joins `a` and `b`, in either direction.
+    pub fn joins(&self, a: LocationId, b: LocationId) -> bool {
+        (self.from == a && self.to == b) || (self.from == b && self.to == a)
+    }
+
+    /// Return whether the work is done.
+    pub const fn is_finished(&self) -> bool {
+        self.work_done >= self.length
+    }
+}
+
+/// What building a route between two locations would take.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePlan {
+    /// Length of the finished route in ticks.
+    pub length: u32,
+    /// Materials still to be paid: the full [`material_cost`] for new
+    /// work, or nothing if the work has already begun.
+    pub cost: BTreeMap<Resource, u32>,
+}
+
+/// Return the length of a new trail where the quickest existing way takes
+/// `detour_ticks`, or `None` if there is no existing way.
+pub fn trail_length(detour_ticks: Option<u32>) -> u32 {
+    detour_ticks.map_or(UNCONNECTED_LENGTH, |ticks| ticks.div_ceil(2).max(1))
+}
+
+/// Return the materials needed to build a trail `length` ticks long.
+pub fn material_cost(length: u32) -> BTreeMap<Resource, u32> {
+    BTreeMap::from([
+        (Resource::Wood, WOOD_PER_TICK.saturating_mul(length)),
+        (Resource::Stone, STONE_PER_TICK.saturating_mul(length)),
+    ])
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcuts_halve_the_detour() {
+        assert_eq!(trail_length(Some(10)), 5);
+        assert_eq!(trail_length(Some(7)), 4);
+        assert_eq!(trail_length(Some(0)), 1);
+        assert_eq!(trail_length(None), UNCONNECTED_LENGTH);
+
+        let cost = material_cost(4);
+        assert_eq!(cost.get(&Resource::Wood).copied(), Some(20));
+        assert_eq!(cost.get(&Resource::Stone).copied(), Some(8));
+    }
+
+    #[test]
+    fn projects_join_either_way_round() {
+        let (a, b) = (LocationId::new(), LocationId::new());
+        let mut project = RouteProject {
+            from: a,
+            to: b,
+            builder: AgentId::new(),
+            work_done: 0,
+            length: 2,
+        };
+        assert!(project.joins(b, a));
+        assert!(!project.joins(a, LocationId::new()));
+        assert!(!project.is_finished());
+        project.work_done = 2;
+        assert!(project.is_finished());
+    }
+}
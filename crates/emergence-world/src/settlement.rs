@@ -0,0 +1,202 @@
+//! Founding new settlements in the wilderness.
+//!
+//! The starting map is not the whole world. A group of at least
+//! [`MIN_FOUNDERS`] agents standing together at a natural location can
+//! found a settlement there with the `FoundSettlement` action. The
+//! settlement is a new location, added to the world graph beside the one
+//! the founders stand in and joined to it by a dirt trail of
+//! [`SETTLEMENT_ROUTE_TICKS`] ticks.
+//!
+//! A settlement takes its resources from the land around it: for each
+//! resource found at the founders' location or any of its neighbours, the
+//! settlement starts with the richest such node at
+//! [`BIOME_SHARE_PCT`] percent of its stock, regrowth, and capacity (see
+//! [`biome_resources`]).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use emergence_types::{
+    AgentId, Location, LocationId, PathType, Resource, ResourceNode, Route, RouteId,
+};
+use rust_decimal::Decimal;
+
+use crate::error::WorldError;
+use crate::world_map::WorldMap;
+
+/// Agents, the founder included, who must stand together to found a
+/// settlement.
+pub const MIN_FOUNDERS: usize = 3;
+
+/// Location type of the places a settlement can be founded from.
+pub const WILDERNESS_TYPE: &str = "natural";
+
+/// Location type of a founded settlement.
+pub const SETTLEMENT_TYPE: &str = "settlement";
+
+/// Agents a new settlement can hold.
+pub const SETTLEMENT_CAPACITY: u32 = 20;
+
+/// Travel time of the trail between a settlement and its origin.
+pub const SETTLEMENT_ROUTE_TICKS: u32 = 1;
+
+/// Percentage of the surrounding land's richest nodes a settlement starts
+/// with.
+pub const BIOME_SHARE_PCT: u32 = 50;
+
+/// Return whether a settlement can be founded from the location of type
+/// `location_type`.
+pub fn is_wilderness(location_type: &str) -> bool {
+    location_type == WILDERNESS_TYPE
+}
+
+/// Return the resource nodes a settlement founded from `origin` starts with.
+///
+/// These are the richest node of each resource at `origin` or its
+/// neighbours, scaled to [`BIOME_SHARE_PCT`]. Nodes that round away to
+/// nothing are left out.
+pub fn biome_resources(map: &WorldMap, origin: LocationId) -> BTreeMap<Resource, ResourceNode> {
+    let surroundings = core::iter::once(origin)
+        .chain(map.neighbors(origin).into_iter().map(|(neighbor, _)| neighbor));
+    let mut richest: BTreeMap<Resource, ResourceNode> = BTreeMap::new();
+    for id in surroundings {
+        let Some(state) = map.get_location(id) else {
+            continue;
+        };
+        for (resource, node) in &state.location.base_resources {
+            let best = richest.entry(*resource).or_insert_with(|| node.clone());
+            if node.max_capacity > best.max_capacity {
+                *best = node.clone();
+            }
+        }
+    }
+    richest
+        .into_iter()
+        .map(|(resource, node)| {
+            let scaled = ResourceNode {
+                resource,
+                available: share(node.available),
+                regen_per_tick: share(node.regen_per_tick),
+                max_capacity: share(node.max_capacity),
+            };
+            (resource, scaled)
+        })
+        .filter(|(_, node)| node.max_capacity > 0)
+        .collect()
+}
+
+/// Found a settlement named `name` beside `origin`, on behalf of `founder`
+/// at `tick`.
+///
+/// The settlement lies in the origin's region at the origin's elevation,
+/// so the trail between them is level. Returns the new location and the
+/// trail joining it to the origin.
+///
+/// # Errors
+///
+/// Returns [`WorldError::LocationNotFound`] if `origin` is not on the map.
+pub fn found_settlement(
+    map: &mut WorldMap,
+    origin: LocationId,
+    name: &str,
+    founder: AgentId,
+    tick: u64,
+) -> Result<(LocationId, RouteId), WorldError> {
+    let origin_state = map.get_location(origin).ok_or(WorldError::LocationNotFound(origin))?;
+    let region = origin_state.location.region.clone();
+    let elevation = origin_state.location.elevation;
+    let description = format!(
+        "A settlement founded beside {} in the {region}.",
+        origin_state.location.name
+    );
+
+    let id = LocationId::new();
+    map.add_location(Location {
+        id,
+        name: name.to_string(),
+        region,
+        location_type: SETTLEMENT_TYPE.to_string(),
+        description,
+        capacity: SETTLEMENT_CAPACITY,
+        elevation,
+        base_resources: biome_resources(map, origin),
+        discovered_by: BTreeSet::from([founder]),
+        created_at: Utc::now(),
+    })?;
+
+    let route = RouteId::new();
+    map.add_route(Route {
+        id: route,
+        from_location: origin,
+        to_location: id,
+        cost_ticks: SETTLEMENT_ROUTE_TICKS,
+        path_type: PathType::DirtTrail,
+        durability: 100,
+        max_durability: 100,
+        decay_per_tick: Decimal::ZERO,
+        acl: None,
+        bidirectional: true,
+        built_by: Some(founder),
+        built_at_tick: Some(tick),
+    })?;
+    Ok((id, route))
+}
+
+/// [`BIOME_SHARE_PCT`] percent of `quantity`, rounded down.
+const fn share(quantity: u32) -> u32 {
+    quantity.saturating_mul(BIOME_SHARE_PCT).saturating_div(100)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use emergence_types::Weather;
+
+    use super::*;
+
+    #[test]
+    fn settlements_draw_on_the_surrounding_land() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let resources = biome_resources(&map, ids.open_field);
+
+        // The riverbank next door brings water and fish to the field.
+        let river_water = map
+            .get_location(ids.riverbank)
+            .unwrap()
+            .location
+            .base_resources
+            .get(&Resource::Water)
+            .unwrap()
+            .max_capacity;
+        let water = resources.get(&Resource::Water).unwrap();
+        assert_eq!(water.max_capacity, share(river_water));
+        assert!(water.available <= water.max_capacity);
+        assert!(resources.contains_key(&Resource::FoodFish));
+    }
+
+    #[test]
+    fn founding_grows_the_world_graph() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let (locations, routes) = (map.location_count(), map.route_count());
+        let founder = AgentId::new();
+
+        let (id, route) =
+            found_settlement(&mut map, ids.open_field, "Newhome", founder, 7).unwrap();
+        assert_eq!(map.location_count(), locations.saturating_add(1));
+        assert_eq!(map.route_count(), routes.saturating_add(1));
+
+        let settlement = &map.get_location(id).unwrap().location;
+        assert_eq!(settlement.name, "Newhome");
+        assert_eq!(settlement.location_type, SETTLEMENT_TYPE);
+        assert!(settlement.discovered_by.contains(&founder));
+        assert!(!is_wilderness(&settlement.location_type));
+
+        let trail = map.get_route(route).unwrap();
+        assert_eq!(trail.built_at_tick, Some(7));
+        assert!(map.neighbors(id).contains(&(ids.open_field, route)));
+        assert!(map.shortest_path(id, ids.beach, Weather::Clear).is_some());
+
+        let missing = LocationId::new();
+        assert!(found_settlement(&mut map, missing, "Nowhere", founder, 7).is_err());
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! Creates 12 locations across 3 regions (Central Valley, Highlands,
 //! Coastal Lowlands) plus 3 undiscovered locations, connected by natural
-//! routes per `world-engine.md` section 3.4.
+//! routes per `world-engine.md` section 3.4. A few locations also hide
+//! deposits that only prospecting reveals.
 
 use std::collections::BTreeSet;
 
@@ -13,10 +14,11 @@ use emergence_types::{
 use rust_decimal::Decimal;
 
 use crate::error::WorldError;
+use crate::prospecting::bury_deposit;
 use crate::world_map::WorldMap;
 
 /// Helper to build a [`ResourceNode`].
-const fn node(resource: Resource, available: u32, regen: u32, max: u32) -> ResourceNode {
+pub(crate) const fn node(resource: Resource, available: u32, regen: u32, max: u32) -> ResourceNode {
     ResourceNode {
         resource,
         available,
@@ -26,7 +28,7 @@ const fn node(resource: Resource, available: u32, regen: u32, max: u32) -> Resou
 }
 
 /// Helper to build a [`Location`].
-fn loc(
+pub(crate) fn loc(
     id: LocationId,
     name: &str,
     region: &str,
@@ -42,6 +44,7 @@ fn loc(
         location_type: loc_type.to_string(),
         description: desc.to_string(),
         capacity,
+        elevation: 0,
         base_resources: resources.into_iter().collect(),
         discovered_by: BTreeSet::new(),
         created_at: Utc::now(),
@@ -49,7 +52,7 @@ fn loc(
 }
 
 /// Helper to build a bidirectional natural [`Route`].
-fn natural_route(
+pub(crate) fn natural_route(
     from: LocationId,
     to: LocationId,
     cost: u32,
@@ -442,6 +445,37 @@ pub fn create_starting_world() -> Result<(WorldMap, StartingLocationIds), WorldE
         PathType::None,
     ))?;
 
+    // ---------------------------------------------------------------
+    // Elevation (meters above sea level)
+    // ---------------------------------------------------------------
+
+    let elevations = [
+        (ids.riverbank, 40),
+        (ids.open_field, 60),
+        (ids.forest_edge, 90),
+        (ids.rocky_outcrop, 380),
+        (ids.mountain_cave, 520),
+        (ids.hilltop, 450),
+        (ids.beach, 0),
+        (ids.tidal_pools, 0),
+        (ids.estuary, 10),
+        (ids.deep_forest, 160),
+        (ids.underground_spring, 300),
+        (ids.volcanic_vent, 700),
+    ];
+    for (id, elevation) in elevations {
+        let state = map.get_location_mut(id).ok_or(WorldError::LocationNotFound(id))?;
+        state.location.elevation = elevation;
+    }
+
+    // ---------------------------------------------------------------
+    // Hidden Deposits
+    // ---------------------------------------------------------------
+
+    bury_deposit(&mut map, ids.hilltop, node(Resource::Ore, 12, 1, 30))?;
+    bury_deposit(&mut map, ids.riverbank, node(Resource::Clay, 20, 2, 40))?;
+    bury_deposit(&mut map, ids.forest_edge, node(Resource::Stone, 15, 1, 30))?;
+
     Ok((map, ids))
 }
 
@@ -3,11 +3,18 @@
 //! Implements `world-engine.md` sections 5.1 through 5.3:
 //!
 //! - [`blueprint`] returns the static blueprint for each [`StructureType`]
+//! - [`next_tier`], [`upgrade_cost`], and [`apply_upgrade`] raise a
+//!   structure along its blueprint chain (Campfire -> Hearth,
+//!   `BasicHut` -> House -> Longhouse)
 //! - [`apply_decay`] reduces durability by `decay_per_tick`, accounting for
 //!   weather and occupancy
+//! - [`apply_disaster_damage`] reduces durability by a percentage of the
+//!   maximum when a disaster strikes
 //! - [`compute_salvage`] calculates the 30% material recovery on collapse or
 //!   demolition
 //! - [`compute_repair_cost`] scales materials proportional to missing durability
+//! - [`deposit`] and [`withdraw`] move resources in and out of shared
+//!   storage structures, whose access [`can_access`] checks
 //! - [`structure_effects_at_location`] aggregates effects from all standing
 //!   structures into a [`LocationEffects`]
 
@@ -16,11 +23,12 @@ use std::collections::BTreeMap;
 use rust_decimal::Decimal;
 
 use emergence_types::{
-    LocationEffects, Resource, Structure, StructureBlueprint, StructureCategory, StructureProperties,
-    StructureType, Weather,
+    AgentId, GroupId, LocationEffects, Resource, Structure, StructureBlueprint, StructureCategory,
+    StructureProperties, StructureType, Weather,
 };
 
 use crate::error::WorldError;
+use crate::metrics;
 
 // ---------------------------------------------------------------------------
 // Blueprints (world-engine.md section 5.2)
@@ -107,6 +115,44 @@ pub fn blueprint(structure_type: StructureType) -> StructureBlueprint {
                 production_rate: 0,
             },
         },
+        StructureType::Granary => StructureBlueprint {
+            structure_type: StructureType::Granary,
+            category: StructureCategory::Storage,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 25),
+                (Resource::Stone, 15),
+            ]),
+            required_knowledge: String::from("food_preservation"),
+            max_durability: 100,
+            decay_per_tick: Decimal::new(3, 1), // 0.3
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: false,
+                storage_slots: 100,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+        StructureType::Storehouse => StructureBlueprint {
+            structure_type: StructureType::Storehouse,
+            category: StructureCategory::Storage,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 35),
+                (Resource::Stone, 25),
+            ]),
+            required_knowledge: String::from("masonry"),
+            max_durability: 140,
+            decay_per_tick: Decimal::new(3, 1), // 0.3
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: true,
+                storage_slots: 150,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
         StructureType::Well => StructureBlueprint {
             structure_type: StructureType::Well,
             category: StructureCategory::Production,
@@ -177,6 +223,22 @@ pub fn blueprint(structure_type: StructureType) -> StructureBlueprint {
                 production_rate: 0,
             },
         },
+        StructureType::Palisade => StructureBlueprint {
+            structure_type: StructureType::Palisade,
+            category: StructureCategory::Defense,
+            material_costs: BTreeMap::from([(Resource::Wood, 60)]),
+            required_knowledge: String::from("territorial_claim"),
+            max_durability: 100,
+            decay_per_tick: Decimal::new(5, 1), // 0.5
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: false,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
 
         // ---- Tier 2: Advanced ----
         StructureType::Forge => StructureBlueprint {
@@ -274,7 +336,208 @@ pub fn blueprint(structure_type: StructureType) -> StructureBlueprint {
                 production_rate: 0,
             },
         },
+        StructureType::Tunnel => StructureBlueprint {
+            structure_type: StructureType::Tunnel,
+            category: StructureCategory::Infrastructure,
+            material_costs: BTreeMap::from([
+                (Resource::Stone, 60),
+                (Resource::Wood, 40),
+            ]),
+            required_knowledge: String::from("mining"),
+            max_durability: 250,
+            decay_per_tick: Decimal::new(2, 1), // 0.2
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: true,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+        StructureType::Irrigation => StructureBlueprint {
+            structure_type: StructureType::Irrigation,
+            category: StructureCategory::Production,
+            material_costs: BTreeMap::from([
+                (Resource::Stone, 30),
+                (Resource::Wood, 10),
+            ]),
+            required_knowledge: String::from("irrigation"),
+            max_durability: 120,
+            decay_per_tick: Decimal::new(3, 1), // 0.3
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: false,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+        StructureType::Pasture => StructureBlueprint {
+            structure_type: StructureType::Pasture,
+            category: StructureCategory::Production,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 25),
+                (Resource::Fiber, 10),
+            ]),
+            required_knowledge: String::from("animal_husbandry"),
+            max_durability: 80,
+            decay_per_tick: Decimal::new(5, 1), // 0.5
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::ONE,
+                weather_protection: false,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+
+        // ---- Upgrades ----
+        StructureType::Hearth => StructureBlueprint {
+            structure_type: StructureType::Hearth,
+            category: StructureCategory::Utility,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 5),
+                (Resource::Stone, 10),
+            ]),
+            required_knowledge: String::from("pottery"),
+            max_durability: 120,
+            decay_per_tick: Decimal::new(4, 1), // 0.4
+            capacity: 0,
+            properties: StructureProperties {
+                rest_bonus: Decimal::new(11, 1), // 1.1
+                weather_protection: false,
+                storage_slots: 0,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+        StructureType::House => StructureBlueprint {
+            structure_type: StructureType::House,
+            category: StructureCategory::Shelter,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 40),
+                (Resource::Stone, 25),
+            ]),
+            required_knowledge: String::from("masonry"),
+            max_durability: 150,
+            decay_per_tick: Decimal::new(4, 1), // 0.4
+            capacity: 6,
+            properties: StructureProperties {
+                rest_bonus: Decimal::new(175, 2), // 1.75
+                weather_protection: true,
+                storage_slots: 40,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+        StructureType::Longhouse => StructureBlueprint {
+            structure_type: StructureType::Longhouse,
+            category: StructureCategory::Shelter,
+            material_costs: BTreeMap::from([
+                (Resource::Wood, 80),
+                (Resource::Stone, 40),
+            ]),
+            required_knowledge: String::from("basic_engineering"),
+            max_durability: 200,
+            decay_per_tick: Decimal::new(4, 1), // 0.4
+            capacity: 12,
+            properties: StructureProperties {
+                rest_bonus: Decimal::TWO,
+                weather_protection: true,
+                storage_slots: 80,
+                production_type: None,
+                production_rate: 0,
+            },
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Upgrades
+// ---------------------------------------------------------------------------
+
+/// Return the structure type `current` upgrades to, or `None` if it is
+/// not part of a blueprint chain or already at the top of one.
+///
+/// The chains are Campfire -> Hearth and `BasicHut` -> House -> Longhouse.
+pub const fn next_tier(current: StructureType) -> Option<StructureType> {
+    match current {
+        StructureType::Campfire => Some(StructureType::Hearth),
+        StructureType::BasicHut => Some(StructureType::House),
+        StructureType::House => Some(StructureType::Longhouse),
+        _ => None,
+    }
+}
+
+/// Return the structure type `current` is upgraded from, or `None` if it
+/// is built directly.
+///
+/// Types with a previous tier can only be reached by upgrading.
+pub const fn previous_tier(current: StructureType) -> Option<StructureType> {
+    match current {
+        StructureType::Hearth => Some(StructureType::Campfire),
+        StructureType::House => Some(StructureType::BasicHut),
+        StructureType::Longhouse => Some(StructureType::House),
+        _ => None,
+    }
+}
+
+/// Return the materials needed to upgrade a structure of type `current`
+/// to its [`next_tier`], or `None` if it cannot be upgraded.
+///
+/// An upgrade builds on what is already standing, so it costs the next
+/// tier's blueprint less the current one's, resource by resource.
+pub fn upgrade_cost(current: StructureType) -> Option<BTreeMap<Resource, u32>> {
+    let next = next_tier(current)?;
+    let have = blueprint(current).material_costs;
+    let cost = blueprint(next)
+        .material_costs
+        .into_iter()
+        .filter_map(|(resource, quantity)| {
+            let owed = quantity.saturating_sub(have.get(&resource).copied().unwrap_or(0));
+            (owed > 0).then_some((resource, owed))
+        })
+        .collect();
+    Some(cost)
+}
+
+/// Upgrade a structure to its [`next_tier`], returning the new type, or
+/// `None` (leaving the structure unchanged) if it cannot be upgraded.
+///
+/// The structure takes the next tier's maximum durability, decay rate,
+/// capacity, and properties. Its durability keeps the same proportion of
+/// the maximum it had before, so a worn hut becomes an equally worn
+/// house. The [`upgrade_cost`] is added to its `materials_used`, which
+/// repair and salvage are computed from.
+pub fn apply_upgrade(structure: &mut Structure) -> Option<StructureType> {
+    let cost = upgrade_cost(structure.structure_type)?;
+    let next = next_tier(structure.structure_type)?;
+    let bp = blueprint(next);
+
+    let durability = if structure.max_durability == 0 {
+        bp.max_durability
+    } else {
+        let scaled = u64::from(structure.durability)
+            .saturating_mul(u64::from(bp.max_durability))
+            .checked_div(u64::from(structure.max_durability))
+            .unwrap_or(0);
+        u32::try_from(scaled).unwrap_or(bp.max_durability)
+    };
+
+    structure.structure_type = next;
+    structure.durability = durability.min(bp.max_durability);
+    structure.max_durability = bp.max_durability;
+    structure.decay_per_tick = bp.decay_per_tick;
+    structure.capacity = bp.capacity;
+    structure.properties = bp.properties;
+    for (resource, quantity) in cost {
+        let used = structure.materials_used.entry(resource).or_insert(0);
+        *used = used.saturating_add(quantity);
     }
+    Some(next)
 }
 
 // ---------------------------------------------------------------------------
@@ -343,11 +606,35 @@ pub fn apply_decay(
     // Convert to integer decay (round down, minimum 0)
     let decay_amount = decimal_to_u32_floor(effective);
 
+    let standing = structure.durability > 0;
+    let lost = structure.durability.min(decay_amount);
     structure.durability = structure.durability.saturating_sub(decay_amount);
+    metrics::STRUCTURE_DECAY.increment(u64::from(lost));
+    if standing && structure.durability == 0 {
+        metrics::STRUCTURES_COLLAPSED.increment(1);
+    }
 
     Ok(structure.durability == 0)
 }
 
+/// Apply disaster damage to a structure.
+///
+/// Unlike [`apply_decay`], the loss does not depend on weather or
+/// occupancy: the structure loses `damage_pct` percent of its maximum
+/// durability (at least 1 point for a non-zero percentage).
+///
+/// Returns `true` if the structure collapsed (durability reached 0).
+pub fn apply_disaster_damage(structure: &mut Structure, damage_pct: u32) -> bool {
+    let damage = u64::from(structure.max_durability)
+        .saturating_mul(u64::from(damage_pct))
+        .checked_div(100)
+        .unwrap_or(0)
+        .max(u64::from(damage_pct.min(1)));
+    let damage = u32::try_from(damage).unwrap_or(u32::MAX);
+    structure.durability = structure.durability.saturating_sub(damage);
+    structure.durability == 0
+}
+
 // ---------------------------------------------------------------------------
 // Salvage (world-engine.md section 5.3)
 // ---------------------------------------------------------------------------
@@ -429,6 +716,185 @@ pub const fn apply_repair(structure: &mut Structure) {
     structure.durability = structure.max_durability;
 }
 
+// ---------------------------------------------------------------------------
+// Shared Storage
+// ---------------------------------------------------------------------------
+
+/// Return whether structures of this type hold an inventory of their own
+/// that agents deposit into and withdraw from.
+pub const fn is_shared_storage(structure_type: StructureType) -> bool {
+    matches!(
+        structure_type,
+        StructureType::Granary | StructureType::Storehouse
+    )
+}
+
+/// Return whether a structure of this type can store `resource`.
+///
+/// A [`StructureType::Granary`] stores food only; a
+/// [`StructureType::Storehouse`] stores anything. Other structures store
+/// nothing.
+pub const fn stores(structure_type: StructureType, resource: Resource) -> bool {
+    match structure_type {
+        StructureType::Granary => matches!(
+            resource,
+            Resource::FoodBerry
+                | Resource::FoodFish
+                | Resource::FoodRoot
+                | Resource::FoodMeat
+                | Resource::FoodFarmed
+                | Resource::FoodCooked
+        ),
+        StructureType::Storehouse => true,
+        _ => false,
+    }
+}
+
+/// Check whether an agent may deposit into or withdraw from a structure.
+///
+/// The owner always may. Otherwise the structure's access list is
+/// evaluated as for routes (see [`crate::route::can_traverse`]): no list
+/// or a public one admits everyone, denied agents are refused, and
+/// otherwise only allowed agents and members of allowed groups get in.
+pub fn can_access(structure: &Structure, agent: AgentId, agent_groups: &[GroupId]) -> bool {
+    if structure.owner == Some(agent) {
+        return true;
+    }
+    let Some(acl) = &structure.access_list else {
+        return true;
+    };
+    if acl.public {
+        return true;
+    }
+    if acl.denied_agents.contains(&agent) {
+        return false;
+    }
+    acl.allowed_agents.contains(&agent)
+        || agent_groups.iter().any(|g| acl.allowed_groups.contains(g))
+}
+
+/// Return the total quantity of all resources stored in a structure.
+pub fn stored_total(structure: &Structure) -> u32 {
+    structure
+        .inventory
+        .values()
+        .fold(0_u32, |total, quantity| total.saturating_add(*quantity))
+}
+
+/// Put `quantity` of `resource` into a shared storage structure.
+///
+/// # Errors
+///
+/// Returns [`WorldError::NotStorable`] if the structure does not store the
+/// resource, or [`WorldError::StorageFull`] if the deposit would exceed
+/// its `storage_slots`.
+pub fn deposit(
+    structure: &mut Structure,
+    resource: Resource,
+    quantity: u32,
+) -> Result<(), WorldError> {
+    if !stores(structure.structure_type, resource) {
+        return Err(WorldError::NotStorable {
+            structure: structure.id,
+            resource,
+        });
+    }
+    let capacity = structure.properties.storage_slots;
+    let full = stored_total(structure)
+        .checked_add(quantity)
+        .is_none_or(|total| total > capacity);
+    if full {
+        return Err(WorldError::StorageFull {
+            structure: structure.id,
+            capacity,
+        });
+    }
+    let stored = structure.inventory.entry(resource).or_insert(0);
+    *stored = stored.saturating_add(quantity);
+    Ok(())
+}
+
+/// Take `quantity` of `resource` out of a shared storage structure.
+///
+/// # Errors
+///
+/// Returns [`WorldError::NotInStorage`] if the structure holds less than
+/// `quantity` of the resource.
+pub fn withdraw(
+    structure: &mut Structure,
+    resource: Resource,
+    quantity: u32,
+) -> Result<(), WorldError> {
+    let held = structure.inventory.get(&resource).copied().unwrap_or(0);
+    let remaining = held.checked_sub(quantity).ok_or(WorldError::NotInStorage {
+        structure: structure.id,
+        resource,
+    })?;
+    if remaining == 0 {
+        structure.inventory.remove(&resource);
+    } else {
+        structure.inventory.insert(resource, remaining);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Fortifications
+// ---------------------------------------------------------------------------
+
+/// Return whether structures of this type fortify their location, barring
+/// entry to those their access list refuses.
+pub const fn is_fortification(structure_type: StructureType) -> bool {
+    matches!(structure_type, StructureType::Wall | StructureType::Palisade)
+}
+
+/// Check whether a standing fortification bars an agent from entering its
+/// location.
+///
+/// The access list is evaluated as in [`can_access`], so a fortification
+/// with no list, or a public one, bars no one. A collapsed or destroyed
+/// fortification bars no one either.
+pub fn bars_entry(structure: &Structure, agent: AgentId, agent_groups: &[GroupId]) -> bool {
+    is_fortification(structure.structure_type)
+        && structure.durability > 0
+        && structure.destroyed_at_tick.is_none()
+        && !can_access(structure, agent, agent_groups)
+}
+
+/// Check whether an agent may enter a location given the fortifications
+/// standing there: every one of them must admit the agent.
+pub fn admits(fortifications: &[Structure], agent: AgentId, agent_groups: &[GroupId]) -> bool {
+    !fortifications
+        .iter()
+        .any(|structure| bars_entry(structure, agent, agent_groups))
+}
+
+/// Return the fortification a breach by `agent` strikes: the weakest of
+/// those barring them, or `None` if nothing does.
+pub fn breach_target<'a>(
+    fortifications: &'a [Structure],
+    agent: AgentId,
+    agent_groups: &[GroupId],
+) -> Option<&'a Structure> {
+    fortifications
+        .iter()
+        .filter(|structure| bars_entry(structure, agent, agent_groups))
+        .min_by_key(|structure| (structure.durability, structure.id))
+}
+
+/// Knock `damage` points of durability off a fortification.
+///
+/// Returns `true` if the fortification fell (durability reached 0),
+/// opening its location to everyone.
+pub fn apply_breach(structure: &mut Structure, damage: u32) -> bool {
+    let standing = structure.durability > 0;
+    structure.durability = structure.durability.saturating_sub(damage);
+    if standing && structure.durability == 0 {
+        metrics::STRUCTURES_COLLAPSED.increment(1);
+    }
+    structure.durability == 0
+}
+
 // ---------------------------------------------------------------------------
 // Location Effects (Task 4.1.5)
 // ---------------------------------------------------------------------------
@@ -446,6 +912,10 @@ pub fn structure_effects_at_location(structures: &[Structure]) -> LocationEffect
         has_shelter: false,
         has_fire: false,
         production: BTreeMap::new(),
+        crowding_threshold: u32::MAX,
+        occupants: 0,
+        regen_penalty_pct: 0,
+        disease_risk_pct: 100,
     };
 
     for s in structures {
@@ -473,13 +943,19 @@ pub fn structure_effects_at_location(structures: &[Structure]) -> LocationEffect
         // Shelter detection
         if matches!(
             s.structure_type,
-            StructureType::LeanTo | StructureType::BasicHut
+            StructureType::LeanTo
+                | StructureType::BasicHut
+                | StructureType::House
+                | StructureType::Longhouse
         ) {
             effects.has_shelter = true;
         }
 
         // Fire detection
-        if s.structure_type == StructureType::Campfire {
+        if matches!(
+            s.structure_type,
+            StructureType::Campfire | StructureType::Hearth
+        ) {
             effects.has_fire = true;
         }
 
@@ -533,7 +1009,8 @@ mod tests {
     use std::collections::BTreeSet;
 
     use emergence_types::{
-        AgentId, LocationId, Resource, Structure, StructureId, StructureType, Weather,
+        AccessControlList, AgentId, GroupId, LocationId, Resource, Structure, StructureId,
+        StructureType, Weather,
     };
     use rust_decimal::Decimal;
 
@@ -558,6 +1035,7 @@ mod tests {
             occupants: BTreeSet::new(),
             access_list: None,
             properties: bp.properties,
+            inventory: BTreeMap::new(),
         }
     }
 
@@ -592,21 +1070,30 @@ mod tests {
     }
 
     #[test]
-    fn all_13_structure_types_have_blueprints() {
+    fn all_22_structure_types_have_blueprints() {
         let types = [
             StructureType::Campfire,
             StructureType::LeanTo,
             StructureType::BasicHut,
             StructureType::StoragePit,
+            StructureType::Granary,
+            StructureType::Storehouse,
             StructureType::Well,
             StructureType::FarmPlot,
             StructureType::Workshop,
             StructureType::MeetingHall,
+            StructureType::Palisade,
             StructureType::Forge,
             StructureType::Library,
             StructureType::Market,
             StructureType::Wall,
             StructureType::Bridge,
+            StructureType::Tunnel,
+            StructureType::Irrigation,
+            StructureType::Pasture,
+            StructureType::Hearth,
+            StructureType::House,
+            StructureType::Longhouse,
         ];
         for st in types {
             let bp = blueprint(st);
@@ -702,6 +1189,149 @@ mod tests {
         assert!(collapsed, "Campfire should collapse within 100 ticks");
     }
 
+    // -----------------------------------------------------------------------
+    // Upgrade tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn tier_chains_link_both_ways() {
+        assert_eq!(next_tier(StructureType::Campfire), Some(StructureType::Hearth));
+        assert_eq!(next_tier(StructureType::BasicHut), Some(StructureType::House));
+        assert_eq!(next_tier(StructureType::House), Some(StructureType::Longhouse));
+        assert_eq!(next_tier(StructureType::Longhouse), None);
+        assert_eq!(next_tier(StructureType::Well), None);
+        for st in [StructureType::Campfire, StructureType::BasicHut, StructureType::House] {
+            assert_eq!(next_tier(st).and_then(previous_tier), Some(st));
+        }
+    }
+
+    #[test]
+    fn upgrade_costs_the_difference_between_blueprints() {
+        let cost = upgrade_cost(StructureType::Campfire).unwrap();
+        assert_eq!(cost.get(&Resource::Wood).copied(), Some(2));
+        assert_eq!(cost.get(&Resource::Stone).copied(), Some(10));
+        assert!(upgrade_cost(StructureType::Longhouse).is_none());
+    }
+
+    #[test]
+    fn upgrade_keeps_durability_proportion_and_improves_the_hut() {
+        let mut s = make_structure(StructureType::BasicHut);
+        s.durability = 50; // half of 100
+
+        assert_eq!(apply_upgrade(&mut s), Some(StructureType::House));
+        assert_eq!(s.structure_type, StructureType::House);
+        assert_eq!(s.max_durability, 150);
+        assert_eq!(s.durability, 75);
+        assert_eq!(s.capacity, 6);
+        assert!(s.properties.rest_bonus > blueprint(StructureType::BasicHut).properties.rest_bonus);
+        // Repairs now price in what the upgrade added.
+        assert_eq!(s.materials_used.get(&Resource::Wood).copied(), Some(40));
+        assert_eq!(s.materials_used.get(&Resource::Stone).copied(), Some(25));
+
+        assert_eq!(apply_upgrade(&mut s), Some(StructureType::Longhouse));
+        assert_eq!(s.durability, 100);
+        assert_eq!(apply_upgrade(&mut s), None);
+        assert_eq!(s.structure_type, StructureType::Longhouse);
+    }
+
+    #[test]
+    fn upgraded_tiers_keep_their_effects() {
+        let effects = structure_effects_at_location(&[
+            make_structure(StructureType::Hearth),
+            make_structure(StructureType::Longhouse),
+        ]);
+        assert!(effects.has_fire);
+        assert!(effects.has_shelter);
+        assert_eq!(effects.best_rest_bonus_pct, 200);
+    }
+
+    // -----------------------------------------------------------------------
+    // Shared storage tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn granary_stores_only_food_up_to_capacity() {
+        let mut granary = make_structure(StructureType::Granary);
+        assert!(is_shared_storage(granary.structure_type));
+        assert!(!is_shared_storage(StructureType::StoragePit));
+
+        deposit(&mut granary, Resource::FoodBerry, 60).unwrap();
+        deposit(&mut granary, Resource::FoodFish, 40).unwrap();
+        assert_eq!(stored_total(&granary), 100);
+        assert!(matches!(
+            deposit(&mut granary, Resource::FoodFish, 1),
+            Err(WorldError::StorageFull { capacity: 100, .. })
+        ));
+        assert!(matches!(
+            deposit(&mut granary, Resource::Wood, 1),
+            Err(WorldError::NotStorable { .. })
+        ));
+        assert!(stores(StructureType::Storehouse, Resource::Wood));
+    }
+
+    #[test]
+    fn withdraw_takes_only_what_is_stored() {
+        let mut storehouse = make_structure(StructureType::Storehouse);
+        deposit(&mut storehouse, Resource::Stone, 5).unwrap();
+        assert!(matches!(
+            withdraw(&mut storehouse, Resource::Stone, 6),
+            Err(WorldError::NotInStorage { .. })
+        ));
+        withdraw(&mut storehouse, Resource::Stone, 5).unwrap();
+        assert!(storehouse.inventory.is_empty());
+    }
+
+    #[test]
+    fn access_list_governs_who_may_use_a_store() {
+        let mut granary = make_structure(StructureType::Granary);
+        let owner = granary.owner.unwrap();
+        let (member, stranger) = (AgentId::new(), AgentId::new());
+        let group = GroupId::new();
+        assert!(can_access(&granary, stranger, &[]));
+
+        granary.access_list = Some(AccessControlList {
+            allowed_agents: BTreeSet::new(),
+            allowed_groups: BTreeSet::from([group]),
+            denied_agents: BTreeSet::from([owner]),
+            public: false,
+            toll_cost: None,
+        });
+        assert!(can_access(&granary, owner, &[]));
+        assert!(can_access(&granary, member, &[group]));
+        assert!(!can_access(&granary, stranger, &[]));
+    }
+
+    #[test]
+    fn fortifications_bar_strangers_until_breached() {
+        let mut palisade = make_structure(StructureType::Palisade);
+        let mut wall = make_structure(StructureType::Wall);
+        let (member, stranger) = (AgentId::new(), AgentId::new());
+        let group = GroupId::new();
+        assert!(admits(&[palisade.clone(), wall.clone()], stranger, &[]));
+
+        let acl = AccessControlList {
+            allowed_agents: BTreeSet::new(),
+            allowed_groups: BTreeSet::from([group]),
+            denied_agents: BTreeSet::new(),
+            public: false,
+            toll_cost: None,
+        };
+        palisade.access_list = Some(acl.clone());
+        wall.access_list = Some(acl);
+        let walls = [palisade.clone(), wall.clone()];
+        assert!(admits(&walls, member, &[group]));
+        assert!(!admits(&walls, stranger, &[]));
+
+        // A breach strikes the weaker palisade first.
+        let target = breach_target(&walls, stranger, &[]).unwrap();
+        assert_eq!(target.id, palisade.id);
+        assert!(!apply_breach(&mut palisade, 40));
+        assert!(apply_breach(&mut palisade, 200));
+        assert!(!bars_entry(&palisade, stranger, &[]));
+        assert!(!admits(&[palisade, wall.clone()], stranger, &[]));
+        assert!(breach_target(&[wall], member, &[group]).is_none());
+    }
+
     // -----------------------------------------------------------------------
     // Salvage tests
     // -----------------------------------------------------------------------
@@ -0,0 +1,249 @@
+//! Trade-route network effects: how heavily each route is travelled, and
+//! the corridors heavy traffic wears into the map.
+//!
+//! Every journey begun along a route counts as a trip on it. A route that
+//! carries [`CORRIDOR_TRIPS`] trips within [`UTILIZATION_WINDOW_TICKS`]
+//! ticks becomes a trade corridor, and stays one until its traffic falls
+//! below [`CORRIDOR_FADE_TRIPS`]. Corridors are well marked and never
+//! empty of company:
+//!
+//! - **Throughput** -- rain and snow slow travel along a corridor by half
+//!   as much as elsewhere, rounded down (see [`TradeNetwork::travel_cost`]).
+//! - **Caravan safety** -- a traveler setting out in rain or snow along a
+//!   route that is not a corridor has a [`MISHAP_CHANCE_PCT`] chance of a
+//!   mishap on the way, costing [`MISHAP_DAMAGE`] health. Travelers on a
+//!   corridor never come to grief.
+//!
+//! Mishap rolls are derived from the agent and tick, so a replayed tick
+//! waylays the same travelers.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::{AgentId, LocationId, Route, RouteId, Weather};
+
+use crate::environment::deterministic_random;
+use crate::error::WorldError;
+use crate::metrics;
+use crate::route::weather_delay;
+use crate::world_map::WorldMap;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Ticks of traffic counted towards a route's utilization.
+pub const UTILIZATION_WINDOW_TICKS: u64 = 50;
+
+/// Trips within the window that make a route a trade corridor.
+pub const CORRIDOR_TRIPS: u32 = 12;
+
+/// Trips within the window below which a corridor fades.
+pub const CORRIDOR_FADE_TRIPS: u32 = 4;
+
+/// Chance in percent of a mishap setting out in rain or snow off the
+/// corridors.
+pub const MISHAP_CHANCE_PCT: u32 = 10;
+
+/// Health lost by a traveler who meets with a mishap.
+pub const MISHAP_DAMAGE: u32 = 5;
+
+/// Mixed into the mishap roll so it differs from other rolls made for the
+/// same agent and tick.
+const MISHAP_SALT: u64 = 0x7472_6164;
+
+/// A change in a route's standing reported by [`TradeNetwork::tick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorridorChange {
+    /// The route's traffic reached [`CORRIDOR_TRIPS`].
+    Emerged,
+    /// A corridor's traffic fell below [`CORRIDOR_FADE_TRIPS`].
+    Faded,
+}
+
+// ---------------------------------------------------------------------------
+// TradeNetwork
+// ---------------------------------------------------------------------------
+
+/// Recent trips along every route, and the routes they made corridors.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeNetwork {
+    /// Ticks of the trips along each route within the window, oldest first.
+    trips: BTreeMap<RouteId, VecDeque<u64>>,
+    corridors: BTreeSet<RouteId>,
+}
+
+impl TradeNetwork {
+    /// Create a network with no traffic.
+    pub const fn new() -> Self {
+        Self {
+            trips: BTreeMap::new(),
+            corridors: BTreeSet::new(),
+        }
+    }
+
+    /// Count a trip begun along `route_id` at `tick`.
+    pub fn record_trip(&mut self, route_id: RouteId, tick: u64) {
+        self.trips.entry(route_id).or_default().push_back(tick);
+    }
+
+    /// Return the trips along `route_id` within the window, as of the last
+    /// [`tick`](Self::tick).
+    pub fn utilization(&self, route_id: RouteId) -> u32 {
+        self.trips
+            .get(&route_id)
+            .map_or(0, |trips| u32::try_from(trips.len()).unwrap_or(u32::MAX))
+    }
+
+    /// Return whether `route_id` is a trade corridor.
+    pub fn is_corridor(&self, route_id: RouteId) -> bool {
+        self.corridors.contains(&route_id)
+    }
+
+    /// Return the trade corridors.
+    pub fn corridors(&self) -> impl Iterator<Item = RouteId> + '_ {
+        self.corridors.iter().copied()
+    }
+
+    /// Forget trips that have left the window as of `tick`, then promote
+    /// busy routes to corridors and let quiet corridors fade.
+    ///
+    /// Returns the routes that became or stopped being corridors.
+    pub fn tick(&mut self, tick: u64) -> Vec<(RouteId, CorridorChange)> {
+        for trips in self.trips.values_mut() {
+            while trips
+                .front()
+                .is_some_and(|t| t.saturating_add(UTILIZATION_WINDOW_TICKS) <= tick)
+            {
+                trips.pop_front();
+            }
+        }
+        self.trips.retain(|_, trips| !trips.is_empty());
+
+        let mut changes = Vec::new();
+        for (route_id, trips) in &self.trips {
+            let busy = u32::try_from(trips.len()).unwrap_or(u32::MAX) >= CORRIDOR_TRIPS;
+            if busy && self.corridors.insert(*route_id) {
+                metrics::TRADE_CORRIDORS_EMERGED.increment(1);
+                changes.push((*route_id, CorridorChange::Emerged));
+            }
+        }
+        let faded: Vec<RouteId> = self
+            .corridors
+            .iter()
+            .copied()
+            .filter(|id| self.utilization(*id) < CORRIDOR_FADE_TRIPS)
+            .collect();
+        for route_id in faded {
+            self.corridors.remove(&route_id);
+            changes.push((route_id, CorridorChange::Faded));
+        }
+        changes
+    }
+
+    /// Calculate the cost of travelling `route` from `from` to `to` (see
+    /// [`WorldMap::travel_cost`]), with half the weather delay taken off
+    /// along a corridor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
+    pub fn travel_cost(
+        &self,
+        map: &WorldMap,
+        route: &Route,
+        from: LocationId,
+        to: LocationId,
+        weather: Weather,
+    ) -> Result<Option<u32>, WorldError> {
+        let cost = map.travel_cost(route, from, to, weather)?;
+        if !self.is_corridor(route.id) {
+            return Ok(cost);
+        }
+        let relief = weather_delay(weather).unwrap_or(0).div_ceil(2);
+        Ok(cost.map(|c| c.saturating_sub(relief)))
+    }
+
+    /// Return whether a traveler setting out along `route_id` in `weather`
+    /// with mishap roll `roll` (see [`mishap_roll`]) meets with a mishap.
+    pub fn is_mishap(&self, route_id: RouteId, weather: Weather, roll: u32) -> bool {
+        let foul = weather_delay(weather).is_some_and(|delay| delay > 0);
+        foul && !self.is_corridor(route_id) && roll < MISHAP_CHANCE_PCT
+    }
+}
+
+/// Return the roll in `0..100` for `agent` setting out at `tick`.
+///
+/// The traveler meets with a mishap when the roll is below
+/// [`MISHAP_CHANCE_PCT`] (see [`TradeNetwork::is_mishap`]).
+pub fn mishap_roll(agent: AgentId, tick: u64) -> u32 {
+    let (high, low) = agent.into_inner().as_u64_pair();
+    let folded = high ^ low ^ MISHAP_SALT;
+    let value = deterministic_random(folded, tick).checked_rem(100).unwrap_or(0);
+    u32::try_from(value).unwrap_or(0)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_routes_become_corridors_until_traffic_falls_away() {
+        let mut network = TradeNetwork::new();
+        let route = RouteId::new();
+        for tick in 1..=CORRIDOR_TRIPS {
+            network.record_trip(route, u64::from(tick));
+        }
+        assert_eq!(network.utilization(route), CORRIDOR_TRIPS);
+        assert_eq!(network.tick(12), vec![(route, CorridorChange::Emerged)]);
+        assert!(network.is_corridor(route));
+        // Already a corridor: no second event
+        assert!(network.tick(13).is_empty());
+
+        // Trips from ticks 1..=8 have left the window
+        assert!(network.tick(58).is_empty());
+        assert_eq!(network.utilization(route), CORRIDOR_FADE_TRIPS);
+        assert_eq!(network.tick(59), vec![(route, CorridorChange::Faded)]);
+        assert_eq!(network.corridors().count(), 0);
+    }
+
+    #[test]
+    fn corridors_halve_weather_delays() {
+        let (map, _) = crate::create_starting_world().unwrap();
+        let (_, route) = map.routes().next().unwrap();
+        let (from, to) = (route.from_location, route.to_location);
+        let mut network = TradeNetwork::new();
+        let cost = |network: &TradeNetwork, weather| {
+            network.travel_cost(&map, route, from, to, weather).unwrap()
+        };
+        let clear = cost(&network, Weather::Clear).unwrap();
+        assert_eq!(cost(&network, Weather::Snow), Some(clear + 2));
+
+        network.corridors.insert(route.id);
+        assert_eq!(cost(&network, Weather::Clear), Some(clear));
+        assert_eq!(cost(&network, Weather::Rain), Some(clear));
+        assert_eq!(cost(&network, Weather::Snow), Some(clear + 1));
+        assert_eq!(cost(&network, Weather::Storm), None);
+    }
+
+    #[test]
+    fn mishaps_strike_only_off_corridors_in_foul_weather() {
+        let mut network = TradeNetwork::new();
+        let route = RouteId::new();
+        assert!(network.is_mishap(route, Weather::Rain, 0));
+        assert!(!network.is_mishap(route, Weather::Rain, MISHAP_CHANCE_PCT));
+        assert!(!network.is_mishap(route, Weather::Clear, 0));
+
+        network.corridors.insert(route);
+        assert!(!network.is_mishap(route, Weather::Snow, 0));
+    }
+
+    #[test]
+    fn mishap_rolls_are_reproducible() {
+        let agent = AgentId::new();
+        assert_eq!(mishap_roll(agent, 5), mishap_roll(agent, 5));
+        assert!(mishap_roll(agent, 5) < 100);
+    }
+}
@@ -0,0 +1,389 @@
+//! Rivers, lakes, and the fish that live in them.
+//!
+//! A [`WaterBody`] is a named river or lake whose `course` lists the
+//! locations it passes through. A river flows downhill: its course runs
+//! from its highest location to its lowest, and
+//! [`WaterRegistry::downstream_of`] follows the flow from one location to
+//! the next.
+//!
+//! Every location on a water body is a fishing spot with its own stock of
+//! fish. Agents at a spot, or at a location with a route to one, take fish
+//! from it with the `Fish` action. Once per tick, during World Wake,
+//! [`WaterRegistry::tick`] regrows each stock by [`FISH_GROWTH_PCT`] of its
+//! capacity, and fish a full spot cannot hold are carried downstream.
+//! During a drought the water runs low: nothing regrows, and catches fall
+//! to [`DROUGHT_YIELD_PCT`] of normal (see [`yield_pct`]).
+//!
+//! The same water feeds the fields: farm plots on or next to a water body
+//! are watered (see [`WaterRegistry::waters_farms_at`]) and yield the
+//! registry's configured irrigation multiple of the usual harvest.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use emergence_types::{LocationId, Resource, Weather};
+
+use crate::farming::DEFAULT_IRRIGATION_YIELD_PCT;
+use crate::world_map::WorldMap;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Fish a single fishing spot can hold.
+pub const DEFAULT_FISH_CAPACITY: u32 = 40;
+
+/// Percentage of a spot's capacity that regrows each tick.
+pub const FISH_GROWTH_PCT: u32 = 10;
+
+/// Percentage of the usual catch landed during a drought.
+pub const DROUGHT_YIELD_PCT: u32 = 50;
+
+/// Units of `FoodFish` yielded by each fish caught.
+pub const FOOD_PER_FISH: u32 = 1;
+
+// ---------------------------------------------------------------------------
+// Water bodies
+// ---------------------------------------------------------------------------
+
+/// The kind of a [`WaterBody`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaterKind {
+    /// Flowing water running through several locations.
+    River,
+    /// Still water at a single location.
+    Lake,
+}
+
+/// A river or lake and the locations it passes through.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaterBody {
+    /// Display name.
+    pub name: String,
+    /// Whether the water flows.
+    pub kind: WaterKind,
+    /// Locations on the water, from source to mouth for a river.
+    pub course: Vec<LocationId>,
+}
+
+/// The fish stock at one location on a water body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FishingSpot {
+    /// Fish present.
+    pub fish: u32,
+    /// The most fish the spot can hold.
+    pub capacity: u32,
+}
+
+impl FishingSpot {
+    /// Create a full spot holding `capacity` fish.
+    pub const fn new(capacity: u32) -> Self {
+        Self {
+            fish: capacity,
+            capacity,
+        }
+    }
+}
+
+/// Return the percentage of the usual catch landed in `weather`.
+pub const fn yield_pct(weather: Weather) -> u32 {
+    match weather {
+        Weather::Drought => DROUGHT_YIELD_PCT,
+        Weather::Clear | Weather::Rain | Weather::Storm | Weather::Snow => 100,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WaterRegistry
+// ---------------------------------------------------------------------------
+
+/// Every water body in the world and the fishing spots along them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaterRegistry {
+    bodies: Vec<WaterBody>,
+    spots: BTreeMap<LocationId, FishingSpot>,
+    /// Percentage of the usual harvest a watered farm plot yields.
+    #[serde(default = "default_irrigation_yield_pct")]
+    irrigation_yield_pct: u32,
+}
+
+/// The irrigation multiple a registry without one configured uses.
+const fn default_irrigation_yield_pct() -> u32 {
+    DEFAULT_IRRIGATION_YIELD_PCT
+}
+
+impl Default for WaterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaterRegistry {
+    /// Create a registry with no water.
+    pub const fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            spots: BTreeMap::new(),
+            irrigation_yield_pct: DEFAULT_IRRIGATION_YIELD_PCT,
+        }
+    }
+
+    /// Set the percentage of the usual harvest a watered farm plot yields.
+    #[must_use]
+    pub const fn with_irrigation_yield_pct(mut self, pct: u32) -> Self {
+        self.irrigation_yield_pct = pct;
+        self
+    }
+
+    /// Return the percentage of the usual harvest a watered farm plot
+    /// yields, before any drought.
+    pub const fn irrigation_yield_pct(&self) -> u32 {
+        self.irrigation_yield_pct
+    }
+
+    /// Find the water of `map`.
+    ///
+    /// Every location with fresh water or fish is on a water body. Such
+    /// locations joined by routes form one river, flowing from the highest
+    /// of them to the lowest and named after the region of its source; a
+    /// location with no such neighbour is a lake.
+    pub fn from_map(map: &WorldMap) -> Self {
+        let wet: BTreeSet<LocationId> = map
+            .locations()
+            .filter(|(_, state)| {
+                let resources = &state.location.base_resources;
+                resources.contains_key(&Resource::Water)
+                    || resources.contains_key(&Resource::FoodFish)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut registry = Self::new();
+        let mut seen = BTreeSet::new();
+        for &start in &wet {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut course = vec![start];
+            let mut frontier = vec![start];
+            while let Some(current) = frontier.pop() {
+                for (neighbor, _) in map.neighbors(current) {
+                    if wet.contains(&neighbor) && seen.insert(neighbor) {
+                        course.push(neighbor);
+                        frontier.push(neighbor);
+                    }
+                }
+            }
+            course.sort_by_key(|id| {
+                let elevation = map.get_location(*id).map_or(0, |s| s.location.elevation);
+                (core::cmp::Reverse(elevation), *id)
+            });
+
+            let Some(source) = course.first().and_then(|id| map.get_location(*id)) else {
+                continue;
+            };
+            let (name, kind) = if course.len() > 1 {
+                (format!("{} River", source.location.region), WaterKind::River)
+            } else {
+                (format!("{} Lake", source.location.name), WaterKind::Lake)
+            };
+            registry.add_body(WaterBody { name, kind, course });
+        }
+        registry
+    }
+
+    /// Add a water body, stocking each location on it that is not yet a
+    /// fishing spot with a full [`DEFAULT_FISH_CAPACITY`].
+    pub fn add_body(&mut self, body: WaterBody) {
+        for location in &body.course {
+            self.spots
+                .entry(*location)
+                .or_insert(FishingSpot::new(DEFAULT_FISH_CAPACITY));
+        }
+        self.bodies.push(body);
+    }
+
+    /// Return every water body.
+    pub fn bodies(&self) -> &[WaterBody] {
+        &self.bodies
+    }
+
+    /// Return the water body at `location`, if any.
+    pub fn body_at(&self, location: LocationId) -> Option<&WaterBody> {
+        self.bodies.iter().find(|b| b.course.contains(&location))
+    }
+
+    /// Return the next location down the river from `location`, if it is
+    /// on a river and not at its mouth.
+    pub fn downstream_of(&self, location: LocationId) -> Option<LocationId> {
+        let body = self.body_at(location)?;
+        if body.kind != WaterKind::River {
+            return None;
+        }
+        let mut course = body.course.iter().skip_while(|id| **id != location);
+        course.next()?;
+        course.next().copied()
+    }
+
+    /// Return the fishing spot at `location`, if any.
+    pub fn spot(&self, location: LocationId) -> Option<&FishingSpot> {
+        self.spots.get(&location)
+    }
+
+    /// Return the fishing spot an agent at `location` would fish: the
+    /// location itself if it is on the water, otherwise the neighbouring
+    /// spot with the most fish.
+    pub fn nearest_spot(&self, map: &WorldMap, location: LocationId) -> Option<LocationId> {
+        if self.spots.contains_key(&location) {
+            return Some(location);
+        }
+        map.neighbors(location)
+            .into_iter()
+            .filter_map(|(neighbor, _)| self.spots.get(&neighbor).map(|s| (s.fish, neighbor)))
+            .max()
+            .map(|(_, neighbor)| neighbor)
+    }
+
+    /// Return whether farm plots at `location` are watered by a river or
+    /// lake: whether it is on one or next to one.
+    pub fn waters_farms_at(&self, map: &WorldMap, location: LocationId) -> bool {
+        self.nearest_spot(map, location).is_some()
+    }
+
+    /// Return the fish an agent at `location` can reach.
+    pub fn fish_near(&self, map: &WorldMap, location: LocationId) -> u32 {
+        self.nearest_spot(map, location)
+            .and_then(|spot| self.spots.get(&spot))
+            .map_or(0, |s| s.fish)
+    }
+
+    /// Take up to `wanted` fish from the spot at `location`.
+    ///
+    /// Returns the number of fish taken, which is less than `wanted` when
+    /// the spot is fished out.
+    pub fn fish(&mut self, location: LocationId, wanted: u32) -> u32 {
+        let Some(spot) = self.spots.get_mut(&location) else {
+            return 0;
+        };
+        let taken = wanted.min(spot.fish);
+        spot.fish = spot.fish.saturating_sub(taken);
+        taken
+    }
+
+    /// Regrow every fishing spot for one tick in `weather`.
+    ///
+    /// Spots are visited from source to mouth; what a full spot cannot
+    /// hold flows on to the next one down. Nothing regrows in a drought.
+    pub fn tick(&mut self, weather: Weather) {
+        if weather == Weather::Drought {
+            return;
+        }
+        for body in &self.bodies {
+            let mut carried: u32 = 0;
+            for location in &body.course {
+                let Some(spot) = self.spots.get_mut(location) else {
+                    continue;
+                };
+                let growth = spot.capacity.saturating_mul(FISH_GROWTH_PCT).saturating_div(100);
+                let total = spot.fish.saturating_add(growth.max(1)).saturating_add(carried);
+                spot.fish = total.min(spot.capacity);
+                carried = if body.kind == WaterKind::River {
+                    total.saturating_sub(spot.capacity)
+                } else {
+                    0
+                };
+            }
+        }
+    }
+
+    /// Return the total fish across all spots.
+    pub fn total_fish(&self) -> u64 {
+        self.spots.values().map(|s| u64::from(s.fish)).sum()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn river(course: Vec<LocationId>) -> WaterRegistry {
+        let mut registry = WaterRegistry::new();
+        registry.add_body(WaterBody {
+            name: String::from("Test River"),
+            kind: WaterKind::River,
+            course,
+        });
+        registry
+    }
+
+    #[test]
+    fn starting_world_rivers_flow_downhill() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let waters = WaterRegistry::from_map(&map);
+        assert!(!waters.bodies().is_empty());
+
+        let estuary = waters.body_at(ids.estuary).unwrap();
+        assert_eq!(estuary.kind, WaterKind::River);
+        let elevations: Vec<i32> = estuary
+            .course
+            .iter()
+            .map(|id| map.get_location(*id).unwrap().location.elevation)
+            .collect();
+        assert!(elevations.windows(2).all(|w| w.first() >= w.get(1)));
+
+        // The open field has no water but lies beside the riverbank.
+        assert!(waters.spot(ids.open_field).is_none());
+        assert!(waters.fish_near(&map, ids.open_field) > 0);
+        assert_eq!(waters.fish_near(&map, ids.volcanic_vent), 0);
+    }
+
+    #[test]
+    fn water_nearby_waters_the_fields() {
+        let (map, ids) = crate::create_starting_world().unwrap();
+        let waters = WaterRegistry::from_map(&map).with_irrigation_yield_pct(180);
+        assert_eq!(waters.irrigation_yield_pct(), 180);
+        assert_eq!(WaterRegistry::new().irrigation_yield_pct(), DEFAULT_IRRIGATION_YIELD_PCT);
+
+        assert!(waters.waters_farms_at(&map, ids.riverbank));
+        assert!(waters.waters_farms_at(&map, ids.open_field));
+        assert!(!waters.waters_farms_at(&map, ids.volcanic_vent));
+    }
+
+    #[test]
+    fn downstream_follows_the_course() {
+        let (a, b, c) = (LocationId::new(), LocationId::new(), LocationId::new());
+        let waters = river(vec![a, b, c]);
+        assert_eq!(waters.downstream_of(a), Some(b));
+        assert_eq!(waters.downstream_of(b), Some(c));
+        assert_eq!(waters.downstream_of(c), None);
+        assert_eq!(waters.downstream_of(LocationId::new()), None);
+    }
+
+    #[test]
+    fn overflow_is_carried_downstream() {
+        let (a, b) = (LocationId::new(), LocationId::new());
+        let mut waters = river(vec![a, b]);
+        assert_eq!(waters.fish(b, 30), 30);
+
+        // a is full, so all of its growth reaches b along with b's own.
+        waters.tick(Weather::Clear);
+        assert_eq!(waters.spot(a).unwrap().fish, DEFAULT_FISH_CAPACITY);
+        assert_eq!(waters.spot(b).unwrap().fish, 18);
+    }
+
+    #[test]
+    fn droughts_stop_regrowth_and_shrink_catches() {
+        let a = LocationId::new();
+        let mut waters = river(vec![a]);
+        assert_eq!(waters.fish(a, 100), DEFAULT_FISH_CAPACITY);
+        waters.tick(Weather::Drought);
+        assert_eq!(waters.total_fish(), 0);
+        waters.tick(Weather::Rain);
+        assert_eq!(waters.total_fish(), 4);
+
+        assert_eq!(yield_pct(Weather::Drought), DROUGHT_YIELD_PCT);
+        assert_eq!(yield_pct(Weather::Clear), 100);
+    }
+}
@@ -0,0 +1,252 @@
+//! World files: a versioned, on-disk format for world maps.
+//!
+//! A [`WorldFile`] holds everything needed to rebuild a world without
+//! [`create_starting_world`](crate::create_starting_world) or
+//! [`generate_world`](crate::generate_world): the locations with their
+//! resource nodes, hidden deposits, and pollution; the routes, and which of
+//! them bridges and tunnels have flattened; routes still under
+//! construction; the structures in the world; and the crops on its farm
+//! plots. Hand-authored worlds are written in the same format.
+//!
+//! Files are JSON with every list sorted by ID, so saving an unchanged
+//! world writes an identical file. Only [`WORLD_FILE_VERSION`] is
+//! accepted; the version is checked before anything else is parsed.
+//! Agents are not part of a world: locations are saved without their
+//! occupants, who are placed again as agents spawn.
+//!
+//! Everything but `version`, `locations`, and `routes` may be left out of
+//! a hand-authored file.
+
+use std::collections::BTreeMap;
+
+use emergence_types::{Location, Resource, ResourceNode, Route, RouteId, Structure, StructureId};
+use serde::{Deserialize, Serialize};
+
+use crate::error::WorldError;
+use crate::farming::FarmRegistry;
+use crate::route_building::RouteProject;
+use crate::world_map::WorldMap;
+
+/// The world file format version written and read by this build.
+pub const WORLD_FILE_VERSION: u32 = 1;
+
+/// A location as saved in a world file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationEntry {
+    /// The location, with its resource nodes.
+    pub location: Location,
+    /// Deposits not yet found by prospecting.
+    #[serde(default)]
+    pub hidden_resources: BTreeMap<Resource, ResourceNode>,
+    /// How polluted the location is.
+    #[serde(default)]
+    pub pollution: u32,
+}
+
+/// The contents of a world file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldFile {
+    /// The format version, [`WORLD_FILE_VERSION`].
+    pub version: u32,
+    /// Every location, sorted by ID.
+    pub locations: Vec<LocationEntry>,
+    /// Every route, sorted by ID.
+    pub routes: Vec<Route>,
+    /// Routes spanned by a bridge or tunnel.
+    #[serde(default)]
+    pub flattened_routes: Vec<RouteId>,
+    /// Routes under construction.
+    #[serde(default)]
+    pub route_projects: Vec<RouteProject>,
+    /// Every structure, sorted by ID.
+    #[serde(default)]
+    pub structures: Vec<Structure>,
+    /// Crops growing on farm plots.
+    #[serde(default)]
+    pub farms: FarmRegistry,
+}
+
+/// A world rebuilt from a world file.
+#[derive(Debug, Clone)]
+pub struct LoadedWorld {
+    /// The world graph, with no agents in it.
+    pub map: WorldMap,
+    /// The structures in the world.
+    pub structures: BTreeMap<StructureId, Structure>,
+    /// Crops growing on farm plots.
+    pub farms: FarmRegistry,
+}
+
+/// The part of a world file read before the rest, to check its version.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl WorldFile {
+    /// Capture `map`, the structures in it, and the crops on its farms.
+    pub fn capture(
+        map: &WorldMap,
+        structures: &BTreeMap<StructureId, Structure>,
+        farms: &FarmRegistry,
+    ) -> Self {
+        let locations = map
+            .locations()
+            .map(|(_, state)| LocationEntry {
+                location: state.location.clone(),
+                hidden_resources: state.hidden_resources.clone(),
+                pollution: state.pollution,
+            })
+            .collect();
+        let routes = map.routes().map(|(_, route)| route.clone()).collect();
+        let flattened_routes = map
+            .route_ids()
+            .into_iter()
+            .filter(|id| map.is_flattened(*id))
+            .collect();
+        Self {
+            version: WORLD_FILE_VERSION,
+            locations,
+            routes,
+            flattened_routes,
+            route_projects: map.route_projects().to_vec(),
+            structures: structures.values().cloned().collect(),
+            farms: farms.clone(),
+        }
+    }
+
+    /// Parse a world file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::UnsupportedWorldFileVersion`] if the file is
+    /// not in [`WORLD_FILE_VERSION`], or [`WorldError::WorldFile`] if it is
+    /// not valid JSON for that version.
+    pub fn from_json(json: &str) -> Result<Self, WorldError> {
+        let header: Header =
+            serde_json::from_str(json).map_err(|e| WorldError::WorldFile(e.to_string()))?;
+        if header.version != WORLD_FILE_VERSION {
+            return Err(WorldError::UnsupportedWorldFileVersion {
+                found: header.version,
+                supported: WORLD_FILE_VERSION,
+            });
+        }
+        serde_json::from_str(json).map_err(|e| WorldError::WorldFile(e.to_string()))
+    }
+
+    /// Write this world file as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::WorldFile`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, WorldError> {
+        serde_json::to_string_pretty(self).map_err(|e| WorldError::WorldFile(e.to_string()))
+    }
+
+    /// Rebuild the world this file describes.
+    ///
+    /// Each structure is placed at its location unless it has been
+    /// destroyed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::DuplicateLocation`] or
+    /// [`WorldError::DuplicateRoute`] if an ID appears twice,
+    /// [`WorldError::LocationNotFound`] if a route, route project, or
+    /// structure refers to a location not in the file, or
+    /// [`WorldError::RouteNotFound`] if a flattened route is not in it.
+    pub fn into_world(self) -> Result<LoadedWorld, WorldError> {
+        let mut map = WorldMap::new();
+        for entry in self.locations {
+            let id = entry.location.id;
+            map.add_location(entry.location)?;
+            if let Some(state) = map.get_location_mut(id) {
+                state.hidden_resources = entry.hidden_resources;
+                state.pollution = entry.pollution;
+            }
+        }
+        for route in self.routes {
+            map.add_route(route)?;
+        }
+        for id in self.flattened_routes {
+            map.flatten_route(id)?;
+        }
+        for project in self.route_projects {
+            map.restore_route_project(project)?;
+        }
+
+        let mut structures = BTreeMap::new();
+        for structure in self.structures {
+            let state = map
+                .get_location_mut(structure.location_id)
+                .ok_or(WorldError::LocationNotFound(structure.location_id))?;
+            if structure.destroyed_at_tick.is_none() {
+                state.structures.insert(structure.id);
+            }
+            structures.insert(structure.id, structure);
+        }
+
+        Ok(LoadedWorld {
+            map,
+            structures,
+            farms: self.farms,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starting_world_survives_a_file_round_trip() {
+        let (mut map, ids) = crate::create_starting_world().unwrap();
+        let route = map.route_ids().into_iter().next().unwrap();
+        map.flatten_route(route).unwrap();
+        if let Some(state) = map.get_location_mut(ids.riverbank) {
+            state.pollution = 7;
+        }
+
+        let path = std::env::temp_dir().join(format!("world-{}.json", StructureId::new()));
+        map.to_file(&path, &BTreeMap::new(), &FarmRegistry::new())
+            .unwrap();
+        let loaded = WorldMap::from_file(&path);
+        let saved_again = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.map.location_count(), map.location_count());
+        assert_eq!(loaded.map.route_count(), map.route_count());
+        assert!(loaded.map.is_flattened(route));
+        assert_eq!(loaded.map.get_location(ids.riverbank).unwrap().pollution, 7);
+        assert!(loaded.map.is_connected());
+        assert!(loaded.structures.is_empty());
+
+        let empty = BTreeMap::new();
+        let json = WorldFile::capture(&loaded.map, &empty, &loaded.farms)
+            .to_json()
+            .unwrap();
+        assert_eq!(json, saved_again.unwrap());
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let result = WorldFile::from_json(r#"{"version": 99, "locations": "anything"}"#);
+        assert!(matches!(
+            result,
+            Err(WorldError::UnsupportedWorldFileVersion { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn routes_to_missing_locations_are_rejected() {
+        let (map, _) = crate::create_starting_world().unwrap();
+        let mut file = WorldFile::capture(&map, &BTreeMap::new(), &FarmRegistry::new());
+        file.locations.remove(0);
+        assert!(matches!(
+            file.into_world(),
+            Err(WorldError::LocationNotFound(_))
+        ));
+    }
+}
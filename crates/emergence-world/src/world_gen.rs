@@ -0,0 +1,689 @@
+//! Procedural world generation from a seed.
+//!
+//! [`generate_world`] builds a map of any size from a [`WorldGenParams`]
+//! instead of the fixed 12-location [`create_starting_world`] map, so that
+//! experiments can vary geography. Regions are drawn from a small set of
+//! archetypes (valley, highlands, coast, wetlands, steppe), and each region
+//! is filled with locations drawn from the archetype's templates, at an
+//! elevation within [`ELEVATION_SPREAD`] meters of the archetype's floor.
+//!
+//! Every generated map is connected: the locations of each region are
+//! joined by a random spanning tree of trails, each region is linked to an
+//! earlier one by a longer unmarked route, and `connectivity` then adds
+//! extra routes on top. About one location in three also hides a deposit
+//! that only prospecting reveals (see [`crate::prospecting`]). The same
+//! parameters always produce the same names, elevations, resources,
+//! deposits, and routes; only the location and route IDs are fresh.
+//!
+//! [`create_starting_world`]: crate::starting_world::create_starting_world
+
+use std::collections::BTreeMap;
+
+use emergence_types::{LocationId, PathType, Resource};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::error::WorldError;
+use crate::prospecting::bury_deposit;
+use crate::starting_world::{loc, natural_route, node};
+use crate::world_map::WorldMap;
+
+/// Parameters that shape a generated world.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldGenParams {
+    /// Seed for every random choice made during generation.
+    pub seed: u64,
+    /// Number of locations to generate (at least 1).
+    pub location_count: u32,
+    /// Number of regions to spread the locations across (at least 1, and
+    /// at most `location_count`).
+    pub region_count: u32,
+    /// Multiplier on the starting quantity, regeneration rate, and capacity
+    /// of every resource node; `1` matches the default starting world.
+    pub resource_richness: Decimal,
+    /// Extra routes per location beyond the minimum that keeps the map
+    /// connected; `0` generates a tree.
+    pub connectivity: Decimal,
+}
+
+impl Default for WorldGenParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            location_count: 12,
+            region_count: 3,
+            resource_richness: Decimal::ONE,
+            connectivity: Decimal::new(5, 1),
+        }
+    }
+}
+
+impl WorldGenParams {
+    /// Check that the parameters describe a world that can be generated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::InvalidGenParams`] if there are no locations or
+    /// regions, more regions than locations, or a negative richness or
+    /// connectivity.
+    pub const fn validate(&self) -> Result<(), WorldError> {
+        if self.location_count == 0 {
+            return Err(WorldError::InvalidGenParams("location_count must be at least 1"));
+        }
+        if self.region_count == 0 {
+            return Err(WorldError::InvalidGenParams("region_count must be at least 1"));
+        }
+        if self.region_count > self.location_count {
+            return Err(WorldError::InvalidGenParams(
+                "region_count must not exceed location_count",
+            ));
+        }
+        if self.resource_richness.is_sign_negative() {
+            return Err(WorldError::InvalidGenParams("resource_richness must not be negative"));
+        }
+        if self.connectivity.is_sign_negative() {
+            return Err(WorldError::InvalidGenParams("connectivity must not be negative"));
+        }
+        Ok(())
+    }
+}
+
+/// A resource node template: (resource, available, regen per tick, max).
+type NodeTemplate = (Resource, u32, u32, u32);
+
+/// A kind of location a region can contain.
+struct LocationTemplate {
+    name: &'static str,
+    loc_type: &'static str,
+    description: &'static str,
+    capacity: u32,
+    resources: &'static [NodeTemplate],
+}
+
+/// Meters above a region's lowest elevation its locations can lie.
+const ELEVATION_SPREAD: usize = 150;
+
+/// A kind of region and the locations it is filled with.
+struct RegionTemplate {
+    name: &'static str,
+    /// Lowest elevation of the region's locations, in meters.
+    elevation: i32,
+    locations: &'static [LocationTemplate],
+}
+
+const REGIONS: &[RegionTemplate] = &[
+    RegionTemplate {
+        name: "Central Valley",
+        elevation: 40,
+        locations: &[
+            LocationTemplate {
+                name: "Riverbank",
+                loc_type: "natural",
+                description: "A wide riverbank with fertile soil and fresh water.",
+                capacity: 20,
+                resources: &[
+                    (Resource::Water, 999, 50, 999),
+                    (Resource::Wood, 45, 3, 100),
+                    (Resource::FoodBerry, 12, 2, 30),
+                    (Resource::FoodFish, 20, 5, 40),
+                ],
+            },
+            LocationTemplate {
+                name: "Open Field",
+                loc_type: "natural",
+                description: "A broad, sunlit field with rich soil and wild grasses.",
+                capacity: 15,
+                resources: &[
+                    (Resource::FoodRoot, 8, 2, 20),
+                    (Resource::FoodBerry, 5, 1, 15),
+                    (Resource::Fiber, 10, 3, 30),
+                ],
+            },
+            LocationTemplate {
+                name: "Forest Edge",
+                loc_type: "natural",
+                description: "Dense woodland with towering oaks and thick berry bushes.",
+                capacity: 15,
+                resources: &[
+                    (Resource::Wood, 80, 8, 150),
+                    (Resource::FoodBerry, 15, 3, 40),
+                    (Resource::FoodRoot, 5, 1, 15),
+                ],
+            },
+            LocationTemplate {
+                name: "Deep Forest",
+                loc_type: "hidden",
+                description: "Ancient trees block out the sun. Rare plants grow here.",
+                capacity: 8,
+                resources: &[
+                    (Resource::Wood, 120, 10, 200),
+                    (Resource::Medicine, 5, 1, 10),
+                    (Resource::Hide, 3, 1, 8),
+                ],
+            },
+        ],
+    },
+    RegionTemplate {
+        name: "Highlands",
+        elevation: 350,
+        locations: &[
+            LocationTemplate {
+                name: "Rocky Outcrop",
+                loc_type: "natural",
+                description: "Jagged stone formations with veins of darker rock.",
+                capacity: 10,
+                resources: &[
+                    (Resource::Stone, 40, 2, 80),
+                    (Resource::Ore, 10, 1, 30),
+                    (Resource::FoodRoot, 3, 1, 10),
+                ],
+            },
+            LocationTemplate {
+                name: "Mountain Cave",
+                loc_type: "natural",
+                description: "A deep cave offering shelter from the elements.",
+                capacity: 8,
+                resources: &[
+                    (Resource::Stone, 25, 1, 50),
+                    (Resource::Ore, 15, 2, 40),
+                    (Resource::Water, 20, 3, 30),
+                ],
+            },
+            LocationTemplate {
+                name: "Hilltop",
+                loc_type: "natural",
+                description: "A windswept summit with a view of the lands below.",
+                capacity: 12,
+                resources: &[(Resource::Stone, 5, 0, 5), (Resource::FoodBerry, 3, 1, 10)],
+            },
+            LocationTemplate {
+                name: "Volcanic Vent",
+                loc_type: "hidden",
+                description: "Steam rises from cracks in the blackened rock.",
+                capacity: 5,
+                resources: &[(Resource::Ore, 30, 5, 60), (Resource::Stone, 20, 2, 40)],
+            },
+        ],
+    },
+    RegionTemplate {
+        name: "Coastal Lowlands",
+        elevation: 0,
+        locations: &[
+            LocationTemplate {
+                name: "Beach",
+                loc_type: "natural",
+                description: "A long stretch of sand strewn with driftwood.",
+                capacity: 15,
+                resources: &[
+                    (Resource::Wood, 10, 2, 25),
+                    (Resource::FoodFish, 15, 4, 35),
+                    (Resource::Stone, 3, 0, 3),
+                ],
+            },
+            LocationTemplate {
+                name: "Tidal Pools",
+                loc_type: "natural",
+                description: "Rock pools filled and emptied by the tide.",
+                capacity: 10,
+                resources: &[
+                    (Resource::FoodFish, 12, 3, 25),
+                    (Resource::FoodRoot, 4, 1, 12),
+                    (Resource::Water, 10, 2, 15),
+                ],
+            },
+            LocationTemplate {
+                name: "Estuary",
+                loc_type: "natural",
+                description: "Where the river meets the sea, rich with fish and reeds.",
+                capacity: 15,
+                resources: &[
+                    (Resource::FoodFish, 30, 8, 60),
+                    (Resource::Fiber, 20, 5, 40),
+                    (Resource::Water, 100, 20, 200),
+                    (Resource::Clay, 15, 2, 30),
+                ],
+            },
+        ],
+    },
+    RegionTemplate {
+        name: "Wetlands",
+        elevation: 10,
+        locations: &[
+            LocationTemplate {
+                name: "Marsh",
+                loc_type: "natural",
+                description: "Waterlogged ground thick with reeds and insects.",
+                capacity: 10,
+                resources: &[
+                    (Resource::Water, 60, 10, 120),
+                    (Resource::Fiber, 25, 6, 50),
+                    (Resource::Clay, 10, 2, 25),
+                ],
+            },
+            LocationTemplate {
+                name: "Reed Bed",
+                loc_type: "natural",
+                description: "Tall reeds sway over slow, shallow water.",
+                capacity: 8,
+                resources: &[(Resource::Fiber, 40, 8, 80), (Resource::FoodFish, 8, 2, 20)],
+            },
+            LocationTemplate {
+                name: "Peat Bog",
+                loc_type: "hidden",
+                description: "Soft, dark ground that swallows careless footsteps.",
+                capacity: 5,
+                resources: &[(Resource::Clay, 30, 3, 60), (Resource::Medicine, 4, 1, 8)],
+            },
+        ],
+    },
+    RegionTemplate {
+        name: "Steppe",
+        elevation: 200,
+        locations: &[
+            LocationTemplate {
+                name: "Grassland",
+                loc_type: "natural",
+                description: "Rolling grass as far as the eye can see, grazed by herds.",
+                capacity: 20,
+                resources: &[
+                    (Resource::Fiber, 15, 4, 40),
+                    (Resource::FoodMeat, 10, 2, 25),
+                    (Resource::Hide, 5, 1, 12),
+                ],
+            },
+            LocationTemplate {
+                name: "Dry Riverbed",
+                loc_type: "natural",
+                description: "A cracked channel where water still pools after rain.",
+                capacity: 12,
+                resources: &[
+                    (Resource::Stone, 20, 1, 40),
+                    (Resource::Water, 15, 3, 30),
+                    (Resource::Clay, 8, 1, 20),
+                ],
+            },
+            LocationTemplate {
+                name: "Salt Flat",
+                loc_type: "hidden",
+                description: "A blinding white plain baked hard by the sun.",
+                capacity: 6,
+                resources: &[(Resource::Stone, 10, 1, 20), (Resource::Ore, 8, 1, 20)],
+            },
+        ],
+    },
+];
+
+/// Deterministic xorshift64 generator, seeded the same way as the weather.
+struct SeedRng {
+    state: u64,
+}
+
+impl SeedRng {
+    const fn new(seed: u64) -> Self {
+        let state = seed.wrapping_mul(0x517c_c1b7_2722_0a95);
+        Self {
+            state: if state == 0 { 0xdead_beef_cafe_babe } else { state },
+        }
+    }
+
+    const fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A value in `0..bound`, or 0 if `bound` is 0.
+    fn below(&mut self, bound: usize) -> usize {
+        let value = self.next().checked_rem(bound as u64).unwrap_or(0);
+        usize::try_from(value).unwrap_or(0)
+    }
+}
+
+/// Scale a template quantity by `richness`, rounding to a whole unit.
+fn scale(quantity: u32, richness: Decimal) -> u32 {
+    Decimal::from(quantity).saturating_mul(richness).round().to_u32().unwrap_or(u32::MAX)
+}
+
+/// A generated location and the region it belongs to.
+struct Placed {
+    id: LocationId,
+    region: usize,
+}
+
+/// Generate a connected world map from `params`.
+///
+/// # Errors
+///
+/// Returns [`WorldError::InvalidGenParams`] if the parameters are out of
+/// range (see [`WorldGenParams::validate`]), or another [`WorldError`] if
+/// the map construction fails.
+pub fn generate_world(params: &WorldGenParams) -> Result<WorldMap, WorldError> {
+    params.validate()?;
+    let mut rng = SeedRng::new(params.seed);
+    let mut map = WorldMap::new();
+
+    let regions = params.region_count as usize;
+    let locations = params.location_count as usize;
+    let first_archetype = rng.below(REGIONS.len());
+    let mut placed: Vec<Placed> = Vec::with_capacity(locations);
+    let mut name_uses: BTreeMap<String, u32> = BTreeMap::new();
+
+    for region in 0..regions {
+        let archetype_index = first_archetype.saturating_add(region).checked_rem(REGIONS.len());
+        let Some(archetype) = archetype_index.and_then(|i| REGIONS.get(i)) else {
+            return Err(WorldError::InvalidGenParams("no region archetypes"));
+        };
+        let region_name = unique_name(&mut name_uses, archetype.name);
+
+        // Spread the locations as evenly as possible across regions.
+        let mut in_region = locations.checked_div(regions).unwrap_or(0);
+        if region < locations.checked_rem(regions).unwrap_or(0) {
+            in_region = in_region.saturating_add(1);
+        }
+
+        let region_start = placed.len();
+        for _ in 0..in_region {
+            let Some(template) = archetype.locations.get(rng.below(archetype.locations.len()))
+            else {
+                return Err(WorldError::InvalidGenParams("no location templates"));
+            };
+            let id = LocationId::new();
+            let rise = i32::try_from(rng.below(ELEVATION_SPREAD)).unwrap_or(0);
+            let resources = template
+                .resources
+                .iter()
+                .map(|&(resource, available, regen, max)| {
+                    let richness = params.resource_richness;
+                    let scaled = node(
+                        resource,
+                        scale(available, richness),
+                        scale(regen, richness),
+                        scale(max, richness),
+                    );
+                    (resource, scaled)
+                })
+                .filter(|(_, n)| n.max_capacity > 0)
+                .collect();
+            let mut location = loc(
+                id,
+                &unique_name(&mut name_uses, template.name),
+                &region_name,
+                template.loc_type,
+                template.description,
+                template.capacity,
+                resources,
+            );
+            location.elevation = archetype.elevation.saturating_add(rise);
+            map.add_location(location)?;
+
+            // Join each new location to one already in its region, giving
+            // a random spanning tree of trails.
+            let in_region_so_far = placed.len().saturating_sub(region_start);
+            if in_region_so_far > 0 {
+                let target = region_start.saturating_add(rng.below(in_region_so_far));
+                if let Some(other) = placed.get(target) {
+                    let cost = 2_u32.saturating_add(rng_cost(&mut rng, 3));
+                    map.add_route(natural_route(other.id, id, cost, PathType::DirtTrail))?;
+                }
+            }
+            placed.push(Placed { id, region });
+        }
+
+        // Link the region to an earlier one so the whole map is connected.
+        if region_start > 0 {
+            let here = placed.get(region_start.saturating_add(rng.below(in_region)));
+            let there = placed.get(rng.below(region_start));
+            if let (Some(here), Some(there)) = (here, there) {
+                let cost = 4_u32.saturating_add(rng_cost(&mut rng, 3));
+                map.add_route(natural_route(there.id, here.id, cost, PathType::None))?;
+            }
+        }
+    }
+
+    add_extra_routes(&mut map, &mut rng, &placed, params.connectivity)?;
+    bury_deposits(&mut map, &mut rng, &placed, params.resource_richness)?;
+    Ok(map)
+}
+
+/// A random extra travel cost in `0..bound`.
+fn rng_cost(rng: &mut SeedRng, bound: usize) -> u32 {
+    u32::try_from(rng.below(bound)).unwrap_or(0)
+}
+
+/// Return `base`, or `base` followed by a number if it has been used before.
+fn unique_name(uses: &mut BTreeMap<String, u32>, base: &str) -> String {
+    let count = uses.entry(base.to_owned()).or_default();
+    *count = count.saturating_add(1);
+    if *count == 1 {
+        base.to_owned()
+    } else {
+        format!("{base} {count}")
+    }
+}
+
+/// Add `connectivity` extra routes per location between pairs of
+/// locations that are not yet directly connected.
+fn add_extra_routes(
+    map: &mut WorldMap,
+    rng: &mut SeedRng,
+    placed: &[Placed],
+    connectivity: Decimal,
+) -> Result<(), WorldError> {
+    let count = placed.len();
+    let wanted = Decimal::from(count)
+        .saturating_mul(connectivity)
+        .round()
+        .to_usize()
+        .unwrap_or(usize::MAX);
+    // A tree already uses `count - 1` of the `count * (count - 1) / 2` pairs.
+    let pairs = count.saturating_mul(count.saturating_sub(1)).checked_div(2).unwrap_or(0);
+    let target = wanted.min(pairs.saturating_sub(count.saturating_sub(1)));
+
+    let mut added = 0_usize;
+    let mut attempts = target.saturating_mul(16);
+    while added < target && attempts > 0 {
+        attempts = attempts.saturating_sub(1);
+        let (Some(a), Some(b)) = (placed.get(rng.below(count)), placed.get(rng.below(count)))
+        else {
+            continue;
+        };
+        if a.id == b.id || map.find_route_from_to(a.id, b.id).is_some() {
+            continue;
+        }
+        let (cost, path_type) = if a.region == b.region {
+            (2_u32.saturating_add(rng_cost(rng, 3)), PathType::DirtTrail)
+        } else {
+            (4_u32.saturating_add(rng_cost(rng, 3)), PathType::None)
+        };
+        map.add_route(natural_route(a.id, b.id, cost, path_type))?;
+        added = added.saturating_add(1);
+    }
+    Ok(())
+}
+
+/// Deposits a location may hide, before scaling by richness.
+const DEPOSITS: &[NodeTemplate] = &[
+    (Resource::Ore, 12, 1, 30),
+    (Resource::Stone, 15, 1, 30),
+    (Resource::Clay, 15, 2, 30),
+];
+
+/// Hide a deposit at about one location in three.
+fn bury_deposits(
+    map: &mut WorldMap,
+    rng: &mut SeedRng,
+    placed: &[Placed],
+    richness: Decimal,
+) -> Result<(), WorldError> {
+    for location in placed {
+        if rng.below(3) != 0 {
+            continue;
+        }
+        let Some(&(resource, available, regen, max)) = DEPOSITS.get(rng.below(DEPOSITS.len()))
+        else {
+            continue;
+        };
+        let deposit = node(
+            resource,
+            scale(available, richness),
+            scale(regen, richness),
+            scale(max, richness),
+        );
+        if deposit.max_capacity > 0 {
+            bury_deposit(map, location.id, deposit)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Name, region, and available resources of each location.
+    type Summary = Vec<(String, String, Vec<(Resource, u32)>)>;
+
+    fn summary(map: &WorldMap) -> Summary {
+        let mut locations: Vec<_> = map
+            .locations()
+            .map(|(_, state)| {
+                let location = &state.location;
+                let resources =
+                    location.base_resources.values().map(|n| (n.resource, n.available)).collect();
+                (location.name.clone(), location.region.clone(), resources)
+            })
+            .collect();
+        locations.sort();
+        locations
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_world() {
+        let params = WorldGenParams {
+            seed: 7,
+            ..WorldGenParams::default()
+        };
+        let (first, second) = (generate_world(&params), generate_world(&params));
+        assert!(first.is_ok() && second.is_ok());
+        if let (Ok(first), Ok(second)) = (first, second) {
+            assert_eq!(summary(&first), summary(&second));
+            assert_eq!(first.route_count(), second.route_count());
+        }
+
+        let other = generate_world(&WorldGenParams {
+            seed: 8,
+            ..params
+        });
+        assert!(other.is_ok());
+    }
+
+    #[test]
+    fn generated_worlds_are_connected_at_any_size() {
+        for (locations, regions) in [(1, 1), (5, 2), (12, 3), (40, 7)] {
+            let params = WorldGenParams {
+                seed: u64::from(locations),
+                location_count: locations,
+                region_count: regions,
+                ..WorldGenParams::default()
+            };
+            let result = generate_world(&params);
+            assert!(result.is_ok());
+            if let Ok(map) = result {
+                assert_eq!(map.location_count(), locations as usize);
+                assert!(map.is_connected());
+            }
+        }
+    }
+
+    #[test]
+    fn connectivity_adds_routes_beyond_a_tree() {
+        let tree = generate_world(&WorldGenParams {
+            connectivity: Decimal::ZERO,
+            ..WorldGenParams::default()
+        });
+        let dense = generate_world(&WorldGenParams {
+            connectivity: Decimal::ONE,
+            ..WorldGenParams::default()
+        });
+        assert!(tree.is_ok() && dense.is_ok());
+        if let (Ok(tree), Ok(dense)) = (tree, dense) {
+            assert_eq!(tree.route_count(), 11);
+            assert_eq!(dense.route_count(), 23);
+        }
+    }
+
+    #[test]
+    fn some_locations_hide_deposits() {
+        let params = WorldGenParams {
+            seed: 3,
+            location_count: 30,
+            ..WorldGenParams::default()
+        };
+        let map = generate_world(&params);
+        assert!(map.is_ok());
+        if let Ok(map) = map {
+            let hiding = map.locations().filter(|(_, s)| !s.hidden_resources.is_empty()).count();
+            assert!(hiding > 0 && hiding < 30);
+        }
+    }
+
+    #[test]
+    fn elevations_lie_within_their_region_band() {
+        let params = WorldGenParams {
+            seed: 5,
+            location_count: 25,
+            region_count: 5,
+            ..WorldGenParams::default()
+        };
+        let map = generate_world(&params);
+        assert!(map.is_ok());
+        if let Ok(map) = map {
+            for (_, state) in map.locations() {
+                let location = &state.location;
+                let region = REGIONS.iter().find(|r| location.region.starts_with(r.name));
+                assert!(region.is_some());
+                if let Some(region) = region {
+                    let spread = i32::try_from(ELEVATION_SPREAD).unwrap_or(0);
+                    assert!(location.elevation >= region.elevation);
+                    assert!(location.elevation < region.elevation.saturating_add(spread));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn richness_scales_resources() {
+        let base = generate_world(&WorldGenParams::default());
+        let rich = generate_world(&WorldGenParams {
+            resource_richness: Decimal::TWO,
+            ..WorldGenParams::default()
+        });
+        assert!(base.is_ok() && rich.is_ok());
+        if let (Ok(base), Ok(rich)) = (base, rich) {
+            for ((_, _, base), (_, _, rich)) in summary(&base).iter().zip(&summary(&rich)) {
+                for ((_, b), (_, r)) in base.iter().zip(rich) {
+                    assert_eq!(b.saturating_mul(2), *r);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        let too_many_regions = WorldGenParams {
+            location_count: 2,
+            region_count: 3,
+            ..WorldGenParams::default()
+        };
+        assert!(matches!(
+            generate_world(&too_many_regions),
+            Err(WorldError::InvalidGenParams(_))
+        ));
+        let empty = WorldGenParams {
+            location_count: 0,
+            ..WorldGenParams::default()
+        };
+        assert!(matches!(generate_world(&empty), Err(WorldError::InvalidGenParams(_))));
+    }
+}
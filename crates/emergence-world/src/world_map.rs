@@ -7,14 +7,50 @@
 //! Internally, an adjacency map indexes outbound routes per location:
 //! `BTreeMap<LocationId, Vec<RouteId>>`. A reverse adjacency map indexes
 //! inbound routes for bidirectional traversal.
+//!
+//! Routes spanned by a bridge or tunnel are recorded as flattened and cost
+//! no extra ticks for the climb between their ends (see
+//! [`route::travel_cost`]).
+//!
+//! Routes being built between locations with no route yet are kept as
+//! [`RouteProject`]s until finished (see [`route_building`]).
+//!
+//! Paths are found with A* (see [`WorldMap::find_path`]). Every change to
+//! the routes gives the map a new [`generation`](WorldMap::generation), so
+//! a [`RouteCache`](crate::pathfinding::RouteCache) knows when the paths it
+//! holds are out of date.
+//!
+//! A map can be saved to and loaded from a world file with
+//! [`WorldMap::to_file`] and [`WorldMap::from_file`] (see
+//! [`world_file`](crate::world_file)).
 
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use emergence_types::{AgentId, Location, LocationId, Resource, Route, RouteId, Season, Weather};
+use emergence_types::{
+    AgentId, Location, LocationId, Resource, Route, RouteId, Season, Structure, StructureId,
+    StructureType, Weather,
+};
 
 use crate::error::WorldError;
+use crate::farming::FarmRegistry;
 use crate::location::LocationState;
+use crate::pathfinding::{self, Traveler};
+use crate::pollution;
+use crate::resource::RegenCurves;
 use crate::route;
+use crate::route_building::{self, RouteProject, RoutePlan};
+use crate::world_file::{LoadedWorld, WorldFile};
+
+/// The next map generation to hand out. Shared by every map, so two maps
+/// that have diverged never share a generation.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Return a generation no map has had before.
+fn fresh_generation() -> u64 {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
 
 /// The world graph holding all locations and routes.
 ///
@@ -30,19 +66,61 @@ pub struct WorldMap {
     outbound: BTreeMap<LocationId, Vec<RouteId>>,
     /// Inbound adjacency: location -> list of route IDs arriving at it.
     inbound: BTreeMap<LocationId, Vec<RouteId>>,
+    /// Routes spanned by a bridge or tunnel, which ignore slope.
+    #[serde(default)]
+    flattened_routes: BTreeSet<RouteId>,
+    /// Routes under construction.
+    #[serde(default)]
+    route_projects: Vec<RouteProject>,
+    /// Changes whenever a location or route is added or a route changes.
+    #[serde(skip, default = "fresh_generation")]
+    generation: u64,
+    /// The season curves resources regrow by.
+    #[serde(default)]
+    regen_curves: RegenCurves,
 }
 
 impl WorldMap {
-    /// Create an empty world map.
-    pub const fn new() -> Self {
+    /// Create an empty world map, with resources regrowing by the default
+    /// [`RegenCurves`].
+    pub fn new() -> Self {
         Self {
             locations: BTreeMap::new(),
             routes: BTreeMap::new(),
             outbound: BTreeMap::new(),
             inbound: BTreeMap::new(),
+            flattened_routes: BTreeSet::new(),
+            route_projects: Vec::new(),
+            generation: 0,
+            regen_curves: RegenCurves::default(),
         }
     }
 
+    /// Return the season curves resources regrow by.
+    pub const fn regen_curves(&self) -> &RegenCurves {
+        &self.regen_curves
+    }
+
+    /// Set the season curves resources regrow by.
+    pub fn set_regen_curves(&mut self, curves: RegenCurves) {
+        self.regen_curves = curves;
+    }
+
+    /// Return the map's generation, which changes whenever the paths
+    /// through it might have: when a location or route is added, a route
+    /// is flattened, decays to a worse path, or is borrowed mutably.
+    ///
+    /// Changes to locations do not count: they do not move, and nothing
+    /// about them but their elevation affects travel.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Give the map a new generation after a change to its paths.
+    fn touch(&mut self) {
+        self.generation = fresh_generation();
+    }
+
     // -------------------------------------------------------------------
     // Location operations
     // -------------------------------------------------------------------
@@ -61,6 +139,7 @@ impl WorldMap {
         self.locations.insert(id, LocationState::new(location));
         self.outbound.entry(id).or_default();
         self.inbound.entry(id).or_default();
+        self.touch();
         Ok(())
     }
 
@@ -131,6 +210,7 @@ impl WorldMap {
             self.outbound.entry(to).or_default().push(id);
             self.inbound.entry(from).or_default().push(id);
         }
+        self.touch();
 
         Ok(())
     }
@@ -142,6 +222,7 @@ impl WorldMap {
 
     /// Get a mutable reference to a route.
     pub fn get_route_mut(&mut self, id: RouteId) -> Option<&mut Route> {
+        self.touch();
         self.routes.get_mut(&id)
     }
 
@@ -162,6 +243,7 @@ impl WorldMap {
 
     /// Iterate over all routes mutably.
     pub fn routes_mut(&mut self) -> impl Iterator<Item = (&RouteId, &mut Route)> {
+        self.touch();
         self.routes.iter_mut()
     }
 
@@ -179,6 +261,176 @@ impl WorldMap {
         self.routes_between(from, to).into_iter().next()
     }
 
+    /// Mark a route as spanned by a bridge or tunnel, so that travel along
+    /// it ignores the slope between its ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::RouteNotFound`] if the route does not exist.
+    pub fn flatten_route(&mut self, id: RouteId) -> Result<(), WorldError> {
+        if !self.routes.contains_key(&id) {
+            return Err(WorldError::RouteNotFound(id));
+        }
+        self.flattened_routes.insert(id);
+        self.touch();
+        Ok(())
+    }
+
+    /// Return whether a route has been flattened by a bridge or tunnel.
+    pub fn is_flattened(&self, id: RouteId) -> bool {
+        self.flattened_routes.contains(&id)
+    }
+
+    /// Flatten the route a newly built structure spans, if it spans one.
+    ///
+    /// A [`StructureType::Bridge`] spans the steepest descent out of
+    /// `location` and a [`StructureType::Tunnel`] the steepest climb,
+    /// skipping routes that are already flattened. Other structures, and
+    /// locations with no such route, flatten nothing.
+    ///
+    /// Returns the route flattened, if any.
+    pub fn build_crossing(
+        &mut self,
+        location: LocationId,
+        structure_type: StructureType,
+    ) -> Option<RouteId> {
+        if !route::flattens_routes(structure_type) {
+            return None;
+        }
+        let here = self.locations.get(&location)?.location.elevation;
+        let climbing = structure_type == StructureType::Tunnel;
+        let (_, steepest) = self
+            .neighbors(location)
+            .into_iter()
+            .filter(|(_, id)| !self.flattened_routes.contains(id))
+            .filter_map(|(neighbor, id)| {
+                let there = self.locations.get(&neighbor)?.location.elevation;
+                let rise = i64::from(there).saturating_sub(i64::from(here));
+                let steepness = if climbing { rise } else { rise.saturating_neg() };
+                (steepness > 0).then_some((steepness, id))
+            })
+            .max()?;
+        self.flattened_routes.insert(steepest);
+        self.touch();
+        Some(steepest)
+    }
+
+    /// Return the routes under construction.
+    pub fn route_projects(&self) -> &[RouteProject] {
+        &self.route_projects
+    }
+
+    /// Return what building a new route from `from` to `to` would take.
+    ///
+    /// Returns `None` if the two are the same place, either is not on the
+    /// map, or a route already joins them.
+    pub fn route_plan(&self, from: LocationId, to: LocationId) -> Option<RoutePlan> {
+        if from == to
+            || !self.locations.contains_key(&from)
+            || !self.locations.contains_key(&to)
+            || !self.routes_between(from, to).is_empty()
+        {
+            return None;
+        }
+        if let Some(project) = self.route_projects.iter().find(|p| p.joins(from, to)) {
+            return Some(RoutePlan {
+                length: project.length,
+                cost: BTreeMap::new(),
+            });
+        }
+        let length = route_building::trail_length(self.travel_time(from, to, Weather::Clear));
+        Some(RoutePlan {
+            length,
+            cost: route_building::material_cost(length),
+        })
+    }
+
+    /// Put a tick of work into the route from `from` to `to`, starting it
+    /// on behalf of `builder` if no one has yet.
+    ///
+    /// When the work is done the route is added to the map, built by
+    /// whoever started it, and its ID is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::NoRouteBetween`] if no route can be built
+    /// between the two (see [`Self::route_plan`]).
+    pub fn work_on_route(
+        &mut self,
+        from: LocationId,
+        to: LocationId,
+        builder: AgentId,
+        tick: u64,
+    ) -> Result<Option<RouteId>, WorldError> {
+        let plan = self.route_plan(from, to).ok_or(WorldError::NoRouteBetween { from, to })?;
+        let index = if let Some(index) = self.route_projects.iter().position(|p| p.joins(from, to))
+        {
+            index
+        } else {
+            self.route_projects.push(RouteProject {
+                from,
+                to,
+                builder,
+                work_done: 0,
+                length: plan.length,
+            });
+            self.route_projects.len().saturating_sub(1)
+        };
+        let Some(project) = self.route_projects.get_mut(index) else {
+            return Ok(None);
+        };
+        project.work_done = project.work_done.saturating_add(1);
+        if !project.is_finished() {
+            return Ok(None);
+        }
+
+        let project = self.route_projects.remove(index);
+        let id = RouteId::new();
+        let durability = route::initial_durability(route_building::NEW_ROUTE_PATH);
+        self.add_route(Route {
+            id,
+            from_location: project.from,
+            to_location: project.to,
+            cost_ticks: project.length,
+            path_type: route_building::NEW_ROUTE_PATH,
+            durability,
+            max_durability: durability,
+            decay_per_tick: rust_decimal::Decimal::ZERO,
+            acl: None,
+            bidirectional: true,
+            built_by: Some(project.builder),
+            built_at_tick: Some(tick),
+        })?;
+        Ok(Some(id))
+    }
+
+    /// Calculate the cost of travelling a route from `from` to `to`, with
+    /// the weather and the slope between them (see [`route::travel_cost`]).
+    ///
+    /// Returns `None` when weather blocks travel. Unknown locations are
+    /// treated as lying at sea level.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::ArithmeticOverflow`] if checked arithmetic fails.
+    pub fn travel_cost(
+        &self,
+        route: &Route,
+        from: LocationId,
+        to: LocationId,
+        weather: Weather,
+    ) -> Result<Option<u32>, WorldError> {
+        let elevation =
+            |id: LocationId| self.locations.get(&id).map_or(0, |s| s.location.elevation);
+        route::travel_cost(
+            route,
+            weather,
+            elevation(from),
+            elevation(to),
+            self.flattened_routes.contains(&route.id),
+        )
+    }
+
     /// Apply one tick of decay to all routes and return any degradation events.
     ///
     /// Returns a list of `(RouteId, new PathType)` for routes that degraded.
@@ -199,6 +451,9 @@ impl WorldMap {
                 degraded.push((id, new_type));
             }
         }
+        if !degraded.is_empty() {
+            self.touch();
+        }
         Ok(degraded)
     }
 
@@ -251,94 +506,154 @@ impl WorldMap {
             .collect()
     }
 
-    /// Find the shortest path between two locations using BFS weighted by
-    /// travel cost. Returns the ordered list of location IDs from `start`
-    /// to `goal` (inclusive), or `None` if no path exists.
+    /// Find the quickest path between two locations in `weather`,
+    /// returning the ordered list of location IDs from `start` to `goal`
+    /// (inclusive), or `None` if no path exists.
     ///
-    /// Uses Dijkstra's algorithm with a simple priority queue (`BTreeSet`
-    /// as a min-heap keyed on distance).
+    /// Access lists and tolls are not considered (see [`Self::find_path`]).
     pub fn shortest_path(
         &self,
         start: LocationId,
         goal: LocationId,
         weather: Weather,
     ) -> Option<Vec<LocationId>> {
+        self.find_path(start, goal, weather, None)
+            .map(|path| path.locations)
+    }
+
+    /// Return the ticks the quickest path from `start` to `goal` takes in
+    /// `weather`, or `None` if there is no such path.
+    pub fn travel_time(
+        &self,
+        start: LocationId,
+        goal: LocationId,
+        weather: Weather,
+    ) -> Option<u32> {
+        self.find_path(start, goal, weather, None).map(|path| path.ticks)
+    }
+
+    /// Find the best path from `start` to `goal` with A*.
+    ///
+    /// Each route is weighed by its travel cost in `weather` (see
+    /// [`route::travel_cost`]), which follows from its path type and the
+    /// slope it climbs. With a `traveler`, routes its access list bars are
+    /// skipped, and routes that charge it a toll weigh
+    /// [`pathfinding::TOLL_WEIGHT_TICKS`] more, so that a free way round is
+    /// taken unless the toll road saves more time than that.
+    ///
+    /// The heuristic is the cheapest route into `goal`: every path still
+    /// has to take one, so it never overestimates.
+    pub fn find_path(
+        &self,
+        start: LocationId,
+        goal: LocationId,
+        weather: Weather,
+        traveler: Option<Traveler<'_>>,
+    ) -> Option<pathfinding::Path> {
         if start == goal {
-            return Some(vec![start]);
+            return Some(pathfinding::Path {
+                locations: vec![start],
+                routes: Vec::new(),
+                ticks: 0,
+            });
         }
         if !self.locations.contains_key(&start) || !self.locations.contains_key(&goal) {
             return None;
         }
+        let last_leg = self
+            .inbound
+            .get(&goal)?
+            .iter()
+            .filter_map(|id| self.routes.get(id))
+            .filter_map(|r| route::effective_travel_cost(r, weather).ok().flatten())
+            .min()?;
+        let estimate = |location: LocationId, weight: u32| {
+            if location == goal {
+                weight
+            } else {
+                weight.saturating_add(last_leg)
+            }
+        };
 
-        // Distance map: location -> best known distance.
-        let mut dist: BTreeMap<LocationId, u32> = BTreeMap::new();
-        // Predecessor map for path reconstruction.
-        let mut prev: BTreeMap<LocationId, LocationId> = BTreeMap::new();
-        // Unvisited set with distances. We use a BTreeMap<(distance, LocationId), ()>
-        // as a poor-man's priority queue.
-        let mut queue: BTreeSet<(u32, LocationId)> = BTreeSet::new();
-
-        dist.insert(start, 0);
-        queue.insert((0, start));
+        // Best known (weight, ticks) to each location reached so far.
+        let mut best: BTreeMap<LocationId, (u32, u32)> = BTreeMap::new();
+        // The location and route each location was best reached by.
+        let mut came_from: BTreeMap<LocationId, (LocationId, RouteId)> = BTreeMap::new();
+        // Open set ordered by estimated total weight, as a priority queue.
+        let mut open: BTreeSet<(u32, LocationId)> = BTreeSet::new();
 
-        while let Some(&(current_dist, current)) = queue.iter().next() {
-            queue.remove(&(current_dist, current));
+        best.insert(start, (0, 0));
+        open.insert((estimate(start, 0), start));
 
+        while let Some((_, current)) = open.pop_first() {
             if current == goal {
                 break;
             }
+            let Some(&(weight, ticks)) = best.get(&current) else {
+                continue;
+            };
 
             for (neighbor, route_id) in self.neighbors(current) {
                 let Some(r) = self.routes.get(&route_id) else {
                     continue;
                 };
-                let Some(cost) = route::effective_travel_cost(r, weather).ok().flatten() else {
+                if let Some(t) = traveler
+                    && !route::can_traverse(r, t.agent, t.groups)
+                {
+                    continue;
+                }
+                let Some(cost) = self.travel_cost(r, current, neighbor, weather).ok().flatten()
+                else {
                     continue; // Storm or error -- route not traversable.
                 };
-                let Some(new_dist) = current_dist.checked_add(cost) else {
+                let toll = traveler.map_or(0, |t| pathfinding::toll_weight(r, t.agent));
+                let (Some(new_weight), Some(new_ticks)) = (
+                    weight.checked_add(cost).and_then(|w| w.checked_add(toll)),
+                    ticks.checked_add(cost),
+                ) else {
                     continue;
                 };
 
-                let is_shorter = dist
-                    .get(&neighbor)
-                    .is_none_or(|&existing| new_dist < existing);
-
-                if is_shorter {
-                    // Remove old entry from queue if present.
-                    if let Some(&old_dist) = dist.get(&neighbor) {
-                        queue.remove(&(old_dist, neighbor));
-                    }
-                    dist.insert(neighbor, new_dist);
-                    prev.insert(neighbor, current);
-                    queue.insert((new_dist, neighbor));
+                let old = best.get(&neighbor).copied();
+                if old.is_some_and(|(existing, _)| existing <= new_weight) {
+                    continue;
                 }
+                if let Some((old_weight, _)) = old {
+                    open.remove(&(estimate(neighbor, old_weight), neighbor));
+                }
+                best.insert(neighbor, (new_weight, new_ticks));
+                came_from.insert(neighbor, (current, route_id));
+                open.insert((estimate(neighbor, new_weight), neighbor));
             }
         }
 
-        // Reconstruct path.
-        if !prev.contains_key(&goal) {
-            return None;
-        }
-
-        let mut path = VecDeque::new();
+        // Reconstruct the path.
+        let &(_, ticks) = best.get(&goal)?;
+        let mut locations = VecDeque::from([goal]);
+        let mut routes = VecDeque::new();
         let mut current = goal;
-        path.push_front(current);
-        while let Some(&predecessor) = prev.get(&current) {
-            path.push_front(predecessor);
+        while let Some(&(predecessor, route_id)) = came_from.get(&current) {
+            locations.push_front(predecessor);
+            routes.push_front(route_id);
             current = predecessor;
             if current == start {
                 break;
             }
         }
 
-        Some(path.into_iter().collect())
+        Some(pathfinding::Path {
+            locations: locations.into(),
+            routes: routes.into(),
+            ticks,
+        })
     }
 
     // -------------------------------------------------------------------
     // Tick operations
     // -------------------------------------------------------------------
 
-    /// Regenerate resources at all locations for one tick.
+    /// Regenerate resources at all locations for one tick, following the
+    /// map's [`RegenCurves`].
     ///
     /// Returns a map of location ID to resource regeneration amounts.
     ///
@@ -348,13 +663,30 @@ impl WorldMap {
     pub fn regenerate_all_resources(
         &mut self,
         season: Season,
+    ) -> Result<BTreeMap<LocationId, BTreeMap<Resource, u32>>, WorldError> {
+        self.regenerate_all_resources_penalized(season, 0)
+    }
+
+    /// Regenerate resources at all locations for one tick, each losing
+    /// `penalty_pct` percent of its regeneration.
+    ///
+    /// Returns a map of location ID to resource regeneration amounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::ArithmeticOverflow`] on math failure.
+    pub fn regenerate_all_resources_penalized(
+        &mut self,
+        season: Season,
+        penalty_pct: u32,
     ) -> Result<BTreeMap<LocationId, BTreeMap<Resource, u32>>, WorldError> {
         let mut results = BTreeMap::new();
         // Collect keys to avoid borrow conflict.
         let ids: Vec<LocationId> = self.locations.keys().copied().collect();
         for id in ids {
             if let Some(loc_state) = self.locations.get_mut(&id) {
-                let regen = loc_state.regenerate_all(season)?;
+                let regen =
+                    loc_state.regenerate_all_penalized(season, &self.regen_curves, penalty_pct)?;
                 if !regen.is_empty() {
                     results.insert(id, regen);
                 }
@@ -363,6 +695,14 @@ impl WorldMap {
         Ok(results)
     }
 
+    /// Advance the pollution at every location by one tick: crowding adds
+    /// to it and it slowly decays (see [`crate::pollution::tick`]).
+    pub fn tick_pollution(&mut self) {
+        for loc_state in self.locations.values_mut() {
+            pollution::tick(loc_state);
+        }
+    }
+
     /// Move an agent from one location to another.
     ///
     /// Removes the agent from the source location and adds them to the
@@ -452,6 +792,60 @@ impl WorldMap {
 
         visited.len() == self.locations.len()
     }
+
+    /// Put a route under construction back on the map, as when loading a
+    /// world file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::LocationNotFound`] if either end of the route
+    /// is not on the map.
+    pub(crate) fn restore_route_project(&mut self, project: RouteProject) -> Result<(), WorldError> {
+        for end in [project.from, project.to] {
+            if !self.locations.contains_key(&end) {
+                return Err(WorldError::LocationNotFound(end));
+            }
+        }
+        self.route_projects.push(project);
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------
+    // World files
+    // -------------------------------------------------------------------
+
+    /// Save this map, with the structures in it and the crops on its
+    /// farms, to a world file at `path` (see
+    /// [`world_file`](crate::world_file)).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::WorldFile`] if the file cannot be written.
+    pub fn to_file(
+        &self,
+        path: &Path,
+        structures: &BTreeMap<StructureId, Structure>,
+        farms: &FarmRegistry,
+    ) -> Result<(), WorldError> {
+        let json = WorldFile::capture(self, structures, farms).to_json()?;
+        std::fs::write(path, json)
+            .map_err(|e| WorldError::WorldFile(format!("{}: {e}", path.display())))
+    }
+
+    /// Load a world saved by [`Self::to_file`] or written by hand.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WorldError::WorldFile`] if the file cannot be read or
+    /// parsed, [`WorldError::UnsupportedWorldFileVersion`] if it was
+    /// written in another format version, or the error from rebuilding
+    /// the map if its contents are inconsistent (see
+    /// [`WorldFile::into_world`]).
+    pub fn from_file(path: &Path) -> Result<LoadedWorld, WorldError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| WorldError::WorldFile(format!("{}: {e}", path.display())))?;
+        WorldFile::from_json(&json)?.into_world()
+    }
 }
 
 impl Default for WorldMap {
@@ -488,6 +882,7 @@ mod tests {
             location_type: "natural".to_string(),
             description: format!("Test location: {name}"),
             capacity: 10,
+            elevation: 0,
             base_resources: resources,
             discovered_by: BTreeSet::new(),
             created_at: Utc::now(),
@@ -637,6 +1032,30 @@ mod tests {
         assert!(path.is_none());
     }
 
+    #[test]
+    fn mountains_are_avoided_until_crossings_flatten_them() {
+        let (mut map, a, b, c) = make_triangle_world();
+        if let Some(state) = map.get_location_mut(b) {
+            state.location.elevation = 800;
+        }
+        // a -> b -> c now costs (3 + 8) + (5 + 4) = 20; a -> c is 10.
+        let path = map.shortest_path(a, c, Weather::Clear).unwrap_or_default();
+        assert_eq!(path, vec![a, c]);
+
+        // Nothing at c climbs, and a campfire flattens nothing.
+        assert_eq!(map.build_crossing(c, StructureType::Bridge), None);
+        assert_eq!(map.build_crossing(a, StructureType::Campfire), None);
+
+        let tunnel = map.build_crossing(a, StructureType::Tunnel);
+        let bridge = map.build_crossing(b, StructureType::Bridge);
+        assert!(tunnel.is_some_and(|id| map.is_flattened(id)));
+        assert!(bridge.is_some_and(|id| map.is_flattened(id)));
+
+        let path = map.shortest_path(a, c, Weather::Clear).unwrap_or_default();
+        assert_eq!(path, vec![a, b, c]);
+        assert!(map.flatten_route(RouteId::new()).is_err());
+    }
+
     #[test]
     fn connectivity_check() {
         let (map, _, _, _) = make_triangle_world();
@@ -715,6 +1134,38 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn building_a_route_takes_a_tick_per_tick_of_length() {
+        let mut map = WorldMap::new();
+        let (a, b, c) = (LocationId::new(), LocationId::new(), LocationId::new());
+        let _ = map.add_location(make_location_with_id(a, "A", "R"));
+        let _ = map.add_location(make_location_with_id(b, "B", "R"));
+        let _ = map.add_location(make_location_with_id(c, "C", "R"));
+        let _ = map.add_route(make_route_between(a, b, 4, true));
+        let _ = map.add_route(make_route_between(b, c, 5, true));
+
+        // A shortcut from A to C is half the nine-tick detour through B.
+        assert_eq!(map.travel_time(a, c, Weather::Clear), Some(9));
+        let plan = map.route_plan(a, c);
+        assert_eq!(plan.as_ref().map(|p| p.length), Some(5));
+        assert_eq!(plan.and_then(|p| p.cost.get(&Resource::Wood).copied()), Some(25));
+        assert!(map.route_plan(a, b).is_none());
+
+        let (builder, helper) = (AgentId::new(), AgentId::new());
+        assert!(matches!(map.work_on_route(a, c, builder, 1), Ok(None)));
+        // Work under way costs nothing more, whoever carries it on.
+        assert_eq!(map.route_plan(c, a).map(|p| p.cost.is_empty()), Some(true));
+        for tick in 2..5 {
+            assert!(matches!(map.work_on_route(c, a, helper, tick), Ok(None)));
+        }
+        let finished = map.work_on_route(a, c, helper, 5).ok().flatten();
+        let built = finished.and_then(|id| map.get_route(id));
+        assert_eq!(built.and_then(|r| r.built_by), Some(builder));
+        assert_eq!(built.map(|r| r.cost_ticks), Some(5));
+        assert!(map.route_projects().is_empty());
+        assert!(map.work_on_route(a, c, builder, 6).is_err());
+    }
+
     #[test]
     fn empty_map_is_connected() {
         let map = WorldMap::new();
@@ -6,20 +6,21 @@
  * (rule engine vs LLM vs night cycle), cost tracking, and loop detection.
  *
  * Layout:
- * - Top: Cost dashboard summary bar
+ * - Top: Cost dashboard summary bar, runner-reported LLM spend below it
  * - Left sidebar: Agent list with decision source indicators
  * - Right panel: Decision stream (newest first) with expandable cards
  */
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 
-import { useDebounce, useDecisions } from "../hooks/useApi.ts";
+import { useDebounce, useDecisions, useRunnerMetrics } from "../hooks/useApi.ts";
 import { cn } from "../lib/utils.ts";
 import type {
   AgentListItem,
   DecisionRecord,
   DecisionSource,
+  RunnerMetricsResponse,
 } from "../types/generated/index.ts";
-import { formatNumber, formatTick } from "../utils/format.ts";
+import { formatNumber, formatPercent, formatTick } from "../utils/format.ts";
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -47,6 +48,24 @@ const DECISION_SOURCE_CONFIG: Record<
     dotClass: "bg-text-muted",
     badgeClass: "bg-text-muted/15 text-text-muted border-text-muted/30",
   },
+  heuristic: {
+    label: "HEURISTIC",
+    shortLabel: "HEUR",
+    dotClass: "bg-warning",
+    badgeClass: "bg-warning/15 text-warning border-warning/30",
+  },
+  budget: {
+    label: "BUDGET",
+    shortLabel: "BDGT",
+    dotClass: "bg-info",
+    badgeClass: "bg-info/15 text-info border-info/30",
+  },
+  policy: {
+    label: "POLICY",
+    shortLabel: "PLCY",
+    dotClass: "bg-text-accent",
+    badgeClass: "bg-text-accent/15 text-text-accent border-text-accent/30",
+  },
   timeout: {
     label: "TIMEOUT",
     shortLabel: "TIME",
@@ -347,6 +366,66 @@ function CostDashboard({ decisions }: { decisions: DecisionRecord[] }) {
   );
 }
 
+/**
+ * Runner-reported LLM spend, summed across runner partitions.
+ *
+ * Unlike the cost dashboard above, which is derived from the recent
+ * decision window, these are cumulative totals since each runner started,
+ * including failed calls and budget denials.
+ */
+function RunnerMetricsBar({ metrics }: { metrics: RunnerMetricsResponse | null }) {
+  if (!metrics || metrics.count === 0) return null;
+
+  const { totals } = metrics;
+  const attempts = totals.total_calls + totals.total_errors;
+  const backends = metrics.runners.flatMap((r) => r.backends);
+
+  return (
+    <div className="flex items-center gap-lg px-md py-xs bg-bg-tertiary border-b border-border-primary text-2xs font-mono flex-wrap">
+      <div className="flex items-center gap-xs">
+        <span className="text-text-muted">RUNNERS</span>
+        <span className="text-text-primary">{metrics.count}</span>
+      </div>
+      <div className="flex items-center gap-xs">
+        <span className="text-text-muted">TOTAL SPEND</span>
+        <span className="text-success font-semibold">
+          {formatCost(parseFloat(totals.total_estimated_cost) || 0)}
+        </span>
+      </div>
+      <div className="flex items-center gap-xs">
+        <span className="text-text-muted">CALLS</span>
+        <span className="text-text-primary">{formatNumber(totals.total_calls)}</span>
+      </div>
+      <div className="flex items-center gap-xs">
+        <span className="text-text-muted">ERRORS</span>
+        <span className={totals.total_errors > 0 ? "text-warning" : "text-text-primary"}>
+          {formatPercent(totals.total_errors, attempts)}
+        </span>
+      </div>
+      {totals.budget_denials > 0 && (
+        <div className="flex items-center gap-xs">
+          <span className="text-text-muted">BUDGET DENIED</span>
+          <span className="text-info">{formatNumber(totals.budget_denials)}</span>
+        </div>
+      )}
+      {backends.map((b, i) => (
+        <div key={`${b.backend}-${b.model}-${i}`} className="flex items-center gap-xs">
+          <span className="text-text-muted uppercase">{b.backend}</span>
+          <span className="text-text-secondary">{b.model || "unknown"}</span>
+          <span className="text-text-primary">
+            {formatNumber(b.calls)} / {formatCost(parseFloat(b.estimated_cost) || 0)}
+          </span>
+          {b.errors > 0 && (
+            <span className="text-warning">
+              ({formatPercent(b.errors, b.calls + b.errors)} err)
+            </span>
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
+
 /** LLM Prompt Inspector (Task 9.3.5). */
 function PromptInspector({ prompt }: { prompt: string }) {
   const sections = useMemo(() => parsePromptSections(prompt), [prompt]);
@@ -542,6 +621,17 @@ function DecisionCard({
                     </div>
                   </div>
                 )}
+                {decision.prompt_variant && (
+                  <div className="px-sm py-xs bg-bg-primary rounded-sm">
+                    <div className="text-2xs text-text-muted">
+                      Prompt Variant
+                    </div>
+                    <div className="text-xs text-text-primary font-mono">
+                      {decision.prompt_variant}
+                      {decision.prompt_version && ` @ ${decision.prompt_version}`}
+                    </div>
+                  </div>
+                )}
                 {decision.prompt_tokens !== null && (
                   <div className="px-sm py-xs bg-bg-primary rounded-sm">
                     <div className="text-2xs text-text-muted">
@@ -580,6 +670,16 @@ function DecisionCard({
                 )}
               </div>
 
+              {/* Model reasoning, separated from the action JSON */}
+              {decision.reasoning && (
+                <div className="mt-sm">
+                  <div className="text-2xs text-text-muted mb-xs">Reasoning</div>
+                  <p className="px-sm py-xs bg-bg-primary rounded-sm text-xs text-text-secondary whitespace-pre-wrap break-words max-h-[200px] overflow-y-auto">
+                    {decision.reasoning}
+                  </p>
+                </div>
+              )}
+
               {/* Raw LLM Response (collapsible) */}
               {decision.raw_llm_response && (
                 <div className="mt-sm">
@@ -664,6 +764,45 @@ function DecisionCard({
             </div>
           )}
 
+          {/* Heuristic fallback details */}
+          {decision.decision_source === "heuristic" && (
+            <div className="mb-sm">
+              <div className="font-mono text-2xs text-text-muted uppercase tracking-widest mb-xs">
+                Heuristic Fallback
+              </div>
+              <div className="px-sm py-xs bg-warning/10 border border-warning/30 rounded-sm text-xs text-warning font-mono">
+                All LLM backends failed -- survival rule{" "}
+                <span className="font-semibold">{decision.rule_matched ?? "unknown"}</span> applied
+              </div>
+            </div>
+          )}
+
+          {/* Budget bypass details */}
+          {decision.decision_source === "budget" && (
+            <div className="mb-sm">
+              <div className="font-mono text-2xs text-text-muted uppercase tracking-widest mb-xs">
+                Budget Bypass
+              </div>
+              <div className="px-sm py-xs bg-info/10 border border-info/30 rounded-sm text-xs text-info font-mono">
+                LLM budget cap{" "}
+                <span className="font-semibold">{decision.rule_matched ?? "unknown"}</span> reached -- bypass policy applied
+              </div>
+            </div>
+          )}
+
+          {/* Policy tier details */}
+          {decision.decision_source === "policy" && (
+            <div className="mb-sm">
+              <div className="font-mono text-2xs text-text-muted uppercase tracking-widest mb-xs">
+                Policy Tier
+              </div>
+              <div className="px-sm py-xs bg-bg-primary rounded-sm text-xs text-text-secondary font-mono">
+                No-LLM agent -- policy rule{" "}
+                <span className="font-semibold">{decision.rule_matched ?? "unknown"}</span> applied
+              </div>
+            </div>
+          )}
+
           {/* Timeout details */}
           {decision.decision_source === "timeout" && (
             <div className="mb-sm">
@@ -696,14 +835,18 @@ export default function DecisionViewer({ agents }: DecisionViewerProps) {
     agentId: selectedAgentId,
     limit: selectedAgentId ? 50 : 200,
   });
+  const { metrics: runnerMetrics, refetch: refetchRunnerMetrics } = useRunnerMetrics();
 
-  // Poll for new decisions
+  // Poll for new decisions and runner metrics
   const refetchRef = useRef(refetchDecisions);
   refetchRef.current = refetchDecisions;
+  const refetchMetricsRef = useRef(refetchRunnerMetrics);
+  refetchMetricsRef.current = refetchRunnerMetrics;
 
   useEffect(() => {
     const interval = setInterval(() => {
       refetchRef.current();
+      refetchMetricsRef.current();
     }, POLL_INTERVAL_MS);
     return () => clearInterval(interval);
   }, []);
@@ -786,6 +929,7 @@ export default function DecisionViewer({ agents }: DecisionViewerProps) {
 
       {/* Cost dashboard (Task 9.3.6) */}
       <CostDashboard decisions={decisions} />
+      <RunnerMetricsBar metrics={runnerMetrics} />
 
       {/* Main content: sidebar + decision stream */}
       <div className="flex flex-1 overflow-hidden">
@@ -47,15 +47,19 @@ const RESOURCE_ORDER: Resource[] = [
   "ToolAdvanced",
   "CurrencyToken",
   "WrittenRecord",
+  "Map",
+  "FoodWaste",
+  "Livestock",
 ];
 
 // Categorize resources for the Sankey diagram.
 const RESOURCE_CATEGORIES: Record<string, Resource[]> = {
   "Water": ["Water"],
   "Food": ["FoodBerry", "FoodFish", "FoodRoot", "FoodMeat", "FoodFarmed", "FoodCooked"],
-  "Raw Materials": ["Wood", "Stone", "Fiber", "Clay", "Hide", "Ore"],
+  "Raw Materials": ["Wood", "Stone", "Fiber", "Clay", "Hide", "Ore", "FoodWaste"],
   "Refined": ["Metal", "Medicine", "Tool", "ToolAdvanced"],
-  "Currency & Records": ["CurrencyToken", "WrittenRecord"],
+  "Currency & Records": ["CurrencyToken", "WrittenRecord", "Map"],
+  "Livestock": ["Livestock"],
 };
 
 export default function EconomyMonitor({
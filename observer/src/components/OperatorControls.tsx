@@ -34,6 +34,9 @@ const SPEED_PRESETS: { label: string; ms: number }[] = [
 
 const EVENT_TYPES: { value: InjectedEventType; label: string }[] = [
   { value: "natural_disaster", label: "Natural Disaster" },
+  { value: "flood", label: "Flood" },
+  { value: "wildfire", label: "Wildfire" },
+  { value: "earthquake", label: "Earthquake" },
   { value: "resource_boom", label: "Resource Boom" },
   { value: "plague", label: "Plague" },
   { value: "migration", label: "Migration Pressure" },
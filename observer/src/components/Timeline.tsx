@@ -43,6 +43,7 @@ const EVENT_CATEGORIES = new Map<string, EventType[]>([
       "StructureDestroyed",
       "StructureRepaired",
       "RouteImproved",
+      "TradeRouteEmerged",
       "LocationDiscovered",
     ],
   ],
@@ -275,6 +276,11 @@ function formatEventNarrative(
       return `${agent} improved a route to ${humanizeResourceName(pathType)}${atLoc}`;
     }
 
+    case "TradeRouteEmerged": {
+      const trips = details?.trips ?? "many";
+      return `A trade route emerged${atLoc} after ${trips} trips`;
+    }
+
     case "LocationDiscovered":
       return `${agent} discovered ${loc || "a new location"}`;
 
@@ -425,6 +431,9 @@ function formatActionNarrative(
     case "FarmHarvest":
       return `${agent} harvested crops${atLoc}`;
 
+    case "Fertilize":
+      return `${agent} fertilized a farm plot${atLoc}`;
+
     case "Craft": {
       const item = humanizeResourceName(String(details?.item ?? details?.resource ?? "item"));
       return `${agent} crafted ${item}`;
@@ -433,6 +442,18 @@ function formatActionNarrative(
     case "Mine":
       return `${agent} mined resources${atLoc}`;
 
+    case "Hunt":
+      return `${agent} hunted game${atLoc}`;
+
+    case "Slaughter":
+      return `${agent} slaughtered livestock${atLoc}`;
+
+    case "Prospect":
+      return `${agent} prospected for deposits${atLoc}`;
+
+    case "Fish":
+      return `${agent} went fishing${atLoc}`;
+
     case "Smelt":
       return `${agent} smelted ore${atLoc}`;
 
@@ -442,6 +463,19 @@ function formatActionNarrative(
     case "Read":
       return `${agent} read a record${atLoc}`;
 
+    case "Chart":
+      return `${agent} drew a map${atLoc}`;
+
+    case "Deposit": {
+      const structType = humanizeResourceName(String(details?.structure_type ?? "store"));
+      return `${agent} stocked a ${structType}${atLoc}`;
+    }
+
+    case "Withdraw": {
+      const structType = humanizeResourceName(String(details?.structure_type ?? "store"));
+      return `${agent} drew from a ${structType}${atLoc}`;
+    }
+
     case "Repair": {
       const structType = humanizeResourceName(String(details?.structure_type ?? "structure"));
       return `${agent} repaired ${structType}${atLoc}`;
@@ -452,12 +486,30 @@ function formatActionNarrative(
       return `${agent} demolished ${structType}${atLoc}`;
     }
 
+    case "UpgradeStructure": {
+      const structType = humanizeResourceName(String(details?.to ?? "structure"));
+      return `${agent} upgraded a structure into a ${structType}${atLoc}`;
+    }
+
     case "ImproveRoute":
       return `${agent} improved a route${atLoc}`;
 
+    case "BuildRoute":
+      return `${agent} worked on a new route${atLoc}`;
+
     case "Claim":
       return `${agent} claimed territory${atLoc}`;
 
+    case "TransferOwnership": {
+      const structType = humanizeResourceName(String(details?.structure_type ?? "structure"));
+      return `${agent} gave away a ${structType}${atLoc}`;
+    }
+
+    case "FoundSettlement": {
+      const name = String(details?.name ?? "a settlement");
+      return `${agent} founded ${name}${atLoc}`;
+    }
+
     case "Legislate": {
       const locSuffix = loc ? ` for ${loc}` : "";
       return `${agent} enacted a law${locSuffix}`;
@@ -472,6 +524,12 @@ function formatActionNarrative(
       return `${agent} rejected a trade with ${target}`;
     }
 
+    case "PostOrder": {
+      const side = String(details?.side ?? "Sell") === "Buy" ? "buy" : "sell";
+      const res = humanizeResourceName(String(details?.resource ?? "goods"));
+      return `${agent} posted an order to ${side} ${res}${atLoc}`;
+    }
+
     default: {
       const verb = actionType.toLowerCase();
       return `${agent} performed ${verb}${atLoc}`;
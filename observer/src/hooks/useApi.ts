@@ -21,6 +21,7 @@ import type {
   LocationDetailResponse,
   LocationListItem,
   Route,
+  RunnerMetricsResponse,
   WorldSnapshot,
 } from "../types/generated/index.ts";
 import {
@@ -35,6 +36,7 @@ import {
   parseLocationDetail,
   parseLocationsResponse,
   parseRoutesResponse,
+  parseRunnerMetricsResponse,
   parseSocialEconomyResponse,
   parseWorldSnapshot,
 } from "../types/schemas.ts";
@@ -396,6 +398,42 @@ export function useDecisions(params: UseDecisionsParams = {}): UseDecisionsRetur
   return { decisions, loading, error, refetch: fetchData };
 }
 
+// ---------------------------------------------------------------------------
+// Hook: useRunnerMetrics
+// ---------------------------------------------------------------------------
+
+interface UseRunnerMetricsReturn {
+  metrics: RunnerMetricsResponse | null;
+  loading: boolean;
+  error: string | null;
+  refetch: () => void;
+}
+
+export function useRunnerMetrics(): UseRunnerMetricsReturn {
+  const [metrics, setMetrics] = useState<RunnerMetricsResponse | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchData = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await apiFetch("/api/runner/metrics", parseRunnerMetricsResponse);
+      setMetrics(result);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Unknown error");
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchData();
+  }, [fetchData]);
+
+  return { metrics, loading, error, refetch: fetchData };
+}
+
 // ---------------------------------------------------------------------------
 // Hook: useSocialConstructs (Phase 9.7 — Social Constructs Wiring)
 // ---------------------------------------------------------------------------
@@ -45,7 +45,10 @@ export type Resource =
   | "Tool"
   | "ToolAdvanced"
   | "CurrencyToken"
-  | "WrittenRecord";
+  | "WrittenRecord"
+  | "Map"
+  | "FoodWaste"
+  | "Livestock";
 
 export type Era =
   | "Primitive"
@@ -70,15 +73,24 @@ export type StructureType =
   | "LeanTo"
   | "BasicHut"
   | "StoragePit"
+  | "Granary"
+  | "Storehouse"
   | "Well"
   | "FarmPlot"
   | "Workshop"
   | "MeetingHall"
+  | "Palisade"
   | "Forge"
   | "Library"
   | "Market"
   | "Wall"
-  | "Bridge";
+  | "Bridge"
+  | "Tunnel"
+  | "Irrigation"
+  | "Pasture"
+  | "Hearth"
+  | "House"
+  | "Longhouse";
 
 export type EventType =
   | "TickStart"
@@ -96,6 +108,7 @@ export type EventType =
   | "StructureDestroyed"
   | "StructureRepaired"
   | "RouteImproved"
+  | "TradeRouteEmerged"
   | "LocationDiscovered"
   | "KnowledgeDiscovered"
   | "KnowledgeTaught"
@@ -116,23 +129,38 @@ export type ActionType =
   | "Move"
   | "Build"
   | "Repair"
+  | "Extinguish"
   | "Demolish"
+  | "UpgradeStructure"
   | "ImproveRoute"
+  | "BuildRoute"
   | "Communicate"
   | "Broadcast"
   | "TradeOffer"
   | "TradeAccept"
   | "TradeReject"
+  | "PostOrder"
   | "FormGroup"
   | "Teach"
   | "FarmPlant"
   | "FarmHarvest"
+  | "Fertilize"
+  | "Slaughter"
   | "Craft"
   | "Mine"
+  | "DeepenMine"
+  | "Hunt"
+  | "Prospect"
+  | "Fish"
   | "Smelt"
   | "Write"
   | "Read"
+  | "Chart"
+  | "Deposit"
+  | "Withdraw"
   | "Claim"
+  | "TransferOwnership"
+  | "FoundSettlement"
   | "Legislate"
   | "Enforce"
   | "Reproduce"
@@ -239,6 +267,7 @@ export interface Location {
   location_type: string;
   description: string;
   capacity: number;
+  elevation: number;
   base_resources: Partial<Record<Resource, ResourceNode>>;
   discovered_by: AgentId[];
   created_at: string;
@@ -292,6 +321,7 @@ export interface Structure {
   occupants: AgentId[];
   access_list: AccessControlList | null;
   properties: StructureProperties;
+  inventory: Partial<Record<Resource, number>>;
 }
 
 export interface Group {
@@ -351,6 +381,19 @@ export interface WorldSnapshot {
   summary: string;
 }
 
+export interface WorldSnapshotDelta {
+  base_tick: number;
+  tick: number;
+  era: Era | null;
+  season: Season | null;
+  weather: Weather | null;
+  population: PopulationStats | null;
+  economy: EconomyStats | null;
+  discoveries_added: string[];
+  discoveries_removed: string[];
+  summary: string | null;
+}
+
 // ---------------------------------------------------------------------------
 // Event types
 // ---------------------------------------------------------------------------
@@ -407,6 +450,7 @@ export interface TickBroadcast {
   agents_alive: number;
   deaths_this_tick: number;
   actions_resolved: number;
+  world_delta?: WorldSnapshotDelta | null;
 }
 
 // ---------------------------------------------------------------------------
@@ -475,6 +519,9 @@ export interface EventsResponse {
 
 export type InjectedEventType =
   | "natural_disaster"
+  | "flood"
+  | "wildfire"
+  | "earthquake"
   | "resource_boom"
   | "plague"
   | "migration"
@@ -692,7 +739,7 @@ export interface CivilizationMilestone {
 // Decision record types (Phase 9.3 — LLM Decision Viewer)
 // ---------------------------------------------------------------------------
 
-export type DecisionSource = "llm" | "rule_engine" | "night_cycle" | "timeout";
+export type DecisionSource = "llm" | "rule_engine" | "night_cycle" | "heuristic" | "budget" | "policy" | "timeout";
 
 export interface DecisionRecord {
   agent_id: AgentId;
@@ -707,8 +754,13 @@ export interface DecisionRecord {
   cost_usd: number | null;
   latency_ms: number | null;
   raw_llm_response: string | null;
+  reasoning?: string | null;
   prompt_sent: string | null;
   rule_matched: string | null;
+  prompt_variant?: string | null;
+  prompt_version?: string | null;
+  perception?: Record<string, unknown> | null;
+  outcome?: string | null;
   created_at: string;
 }
 
@@ -716,3 +768,44 @@ export interface DecisionsResponse {
   count: number;
   decisions: DecisionRecord[];
 }
+
+// ---------------------------------------------------------------------------
+// Runner cost metrics
+// ---------------------------------------------------------------------------
+
+export interface BackendUsage {
+  backend: string;
+  model: string;
+  calls: number;
+  errors: number;
+  input_tokens: number;
+  output_tokens: number;
+  estimated_cost: string;
+}
+
+export interface RunnerMetrics {
+  partition_id: number;
+  total_calls: number;
+  total_errors: number;
+  total_input_tokens: number;
+  total_output_tokens: number;
+  total_estimated_cost: string;
+  budget_denials: number;
+  backends: BackendUsage[];
+  published_at: string;
+}
+
+export interface RunnerMetricsTotals {
+  total_calls: number;
+  total_errors: number;
+  total_input_tokens: number;
+  total_output_tokens: number;
+  total_estimated_cost: string;
+  budget_denials: number;
+}
+
+export interface RunnerMetricsResponse {
+  count: number;
+  totals: RunnerMetricsTotals;
+  runners: RunnerMetrics[];
+}
@@ -6,7 +6,7 @@
  */
 import { z } from "zod/v4";
 
-import type { DecisionsResponse, EventsResponse } from "./generated/index.ts";
+import type { DecisionsResponse, EventsResponse, RunnerMetricsResponse } from "./generated/index.ts";
 
 // ---------------------------------------------------------------------------
 // Enum schemas
@@ -32,6 +32,9 @@ export const ResourceSchema = z.enum([
   "ToolAdvanced",
   "CurrencyToken",
   "WrittenRecord",
+  "Map",
+  "FoodWaste",
+  "Livestock",
 ]);
 
 export const EraSchema = z.enum([
@@ -69,6 +72,7 @@ export const EventTypeSchema = z.enum([
   "StructureDestroyed",
   "StructureRepaired",
   "RouteImproved",
+  "TradeRouteEmerged",
   "LocationDiscovered",
   "KnowledgeDiscovered",
   "KnowledgeTaught",
@@ -240,6 +244,19 @@ export const WorldSnapshotSchema = z.object({
   summary: z.string(),
 });
 
+export const WorldSnapshotDeltaSchema = z.object({
+  base_tick: z.number(),
+  tick: z.number(),
+  era: EraSchema.nullable(),
+  season: SeasonSchema.nullable(),
+  weather: WeatherSchema.nullable(),
+  population: PopulationStatsSchema.nullable(),
+  economy: EconomyStatsSchema.nullable(),
+  discoveries_added: z.array(z.string()),
+  discoveries_removed: z.array(z.string()),
+  summary: z.string().nullable(),
+});
+
 // ---------------------------------------------------------------------------
 // Event schema
 // ---------------------------------------------------------------------------
@@ -280,6 +297,7 @@ export const TickBroadcastSchema = z.object({
   agents_alive: z.number().int().nonnegative(),
   deaths_this_tick: z.number().int().nonnegative(),
   actions_resolved: z.number().int().nonnegative(),
+  world_delta: WorldSnapshotDeltaSchema.nullable().optional(),
 });
 
 // ---------------------------------------------------------------------------
@@ -355,6 +373,9 @@ export const EventsResponseSchema = z.object({
 
 export const InjectedEventTypeSchema = z.enum([
   "natural_disaster",
+  "flood",
+  "wildfire",
+  "earthquake",
   "resource_boom",
   "plague",
   "migration",
@@ -598,7 +619,7 @@ export const CrimeStatsSchema = z.object({
 // Decision record schemas (Phase 9.3 — LLM Decision Viewer)
 // ---------------------------------------------------------------------------
 
-export const DecisionSourceSchema = z.enum(["llm", "rule_engine", "night_cycle", "timeout"]);
+export const DecisionSourceSchema = z.enum(["llm", "rule_engine", "night_cycle", "heuristic", "budget", "policy", "timeout"]);
 
 export const DecisionRecordSchema = z.object({
   agent_id: z.string(),
@@ -613,8 +634,13 @@ export const DecisionRecordSchema = z.object({
   cost_usd: z.number().nullable(),
   latency_ms: z.number().nullable(),
   raw_llm_response: z.string().nullable(),
+  reasoning: z.string().nullable().optional(),
   prompt_sent: z.string().nullable(),
   rule_matched: z.string().nullable(),
+  prompt_variant: z.string().nullable().optional(),
+  prompt_version: z.string().nullable().optional(),
+  perception: z.record(z.string(), JsonValueSchema).nullable().optional(),
+  outcome: z.string().nullable().optional(),
   created_at: z.string(),
 });
 
@@ -628,6 +654,49 @@ export function parseDecisionsResponse(data: unknown): DecisionsResponse {
   return raw as unknown as DecisionsResponse;
 }
 
+// ---------------------------------------------------------------------------
+// Runner cost metrics schemas
+// ---------------------------------------------------------------------------
+
+export const BackendUsageSchema = z.object({
+  backend: z.string(),
+  model: z.string(),
+  calls: z.number(),
+  errors: z.number(),
+  input_tokens: z.number(),
+  output_tokens: z.number(),
+  estimated_cost: z.string(),
+});
+
+export const RunnerMetricsSchema = z.object({
+  partition_id: z.number(),
+  total_calls: z.number(),
+  total_errors: z.number(),
+  total_input_tokens: z.number(),
+  total_output_tokens: z.number(),
+  total_estimated_cost: z.string(),
+  budget_denials: z.number(),
+  backends: z.array(BackendUsageSchema),
+  published_at: z.string(),
+});
+
+export const RunnerMetricsResponseSchema = z.object({
+  count: z.number(),
+  totals: z.object({
+    total_calls: z.number(),
+    total_errors: z.number(),
+    total_input_tokens: z.number(),
+    total_output_tokens: z.number(),
+    total_estimated_cost: z.string(),
+    budget_denials: z.number(),
+  }),
+  runners: z.array(RunnerMetricsSchema),
+});
+
+export function parseRunnerMetricsResponse(data: unknown): RunnerMetricsResponse {
+  return RunnerMetricsResponseSchema.parse(data);
+}
+
 // ---------------------------------------------------------------------------
 // Social API response schemas (Phase 9.7)
 // ---------------------------------------------------------------------------
@@ -69,6 +69,7 @@ export function getEventCategory(eventType: EventType): string {
     case "StructureDestroyed":
     case "StructureRepaired":
     case "RouteImproved":
+    case "TradeRouteEmerged":
     case "LocationDiscovered":
       return "event-world";
     case "KnowledgeDiscovered":
@@ -130,6 +131,9 @@ export function getResourceColor(resource: Resource): string {
     ToolAdvanced: "#f0d070",
     CurrencyToken: "#ffd700",
     WrittenRecord: "#e0e0e0",
+    Map: "#c8b48a",
+    FoodWaste: "#7d6b3a",
+    Livestock: "#b07a4f",
   };
   // eslint-disable-next-line security/detect-object-injection -- resource is typed as Resource enum, not user input
   return colors[resource];